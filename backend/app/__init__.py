@@ -0,0 +1,4 @@
+# Release of the backend, and the revision of its HTTP API. The desktop shell checks
+# API_VERSION at startup; bump it whenever an endpoint changes in a way older shells can't use.
+__version__ = "0.1.0"
+API_VERSION = 1
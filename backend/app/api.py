@@ -11,12 +11,19 @@ from app.models import (
 )
 from app.core.storage import storage, swap_storage, VaultLockedError
 from app.core.engine import runner
+from app import __version__, API_VERSION
 import os
 import subprocess
 
 router = APIRouter()
 
 
+# --- Version ---
+@router.get("/version")
+async def get_version():
+    return {"version": __version__, "api_version": API_VERSION}
+
+
 # --- Collections Index ---
 @router.get("/collections", response_model=List[CollectionMeta])
 async def list_collections():
@@ -117,6 +124,22 @@ async def get_history(collection_id: str):
         raise HTTPException(status_code=423, detail="workspace locked")
 
 
+@router.post("/collections/{collection_id}/history/tests")
+async def attach_history_tests(collection_id: str, payload: Dict[str, Any] = Body(...)):
+    try:
+        found = storage.attach_history_tests(
+            collection_id,
+            str(payload.get("request_id") or ""),
+            payload.get("timestamp"),
+            payload.get("tests") or {},
+        )
+    except VaultLockedError:
+        raise HTTPException(status_code=423, detail="workspace locked")
+    if not found:
+        raise HTTPException(status_code=404, detail="history entry not found")
+    return {"status": "ok"}
+
+
 # --- UI State ---
 @router.get("/collections/{collection_id}/ui-state")
 async def get_ui_state(collection_id: str) -> Dict[str, Any]:
@@ -0,0 +1,100 @@
+"""
+Append-only audit log of workspace mutations, one JSON object per line in
+`.litefetch/audit.jsonl`. Requests sent by the desktop shell name where a change came from
+(`import`, `sync`) in the X-LiteFetch-Origin header; everything else is the UI's doing.
+Variable values never reach the log, only their names.
+"""
+
+import contextvars
+import json
+import time
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+ORIGINS = {"ui", "import", "sync"}
+ORIGIN_HEADER = "x-litefetch-origin"
+
+current_origin: contextvars.ContextVar[str] = contextvars.ContextVar("audit_origin", default="ui")
+
+
+def audit_path(base_dir: Path) -> Path:
+    return base_dir / ".litefetch" / "audit.jsonl"
+
+
+def record(base_dir: Path, action: str, collection_id: Optional[str] = None, **details: Any):
+    entry = {
+        "timestamp_ms": int(time.time() * 1000),
+        "action": action,
+        "origin": current_origin.get(),
+        "collection_id": collection_id,
+    }
+    entry.update({k: v for k, v in details.items() if v is not None})
+    path = audit_path(base_dir)
+    try:
+        path.parent.mkdir(parents=True, exist_ok=True)
+        with open(path, "a", encoding="utf-8") as f:
+            f.write(json.dumps(entry, separators=(",", ":")) + "\n")
+    except OSError:
+        # Auditing is best-effort; never fail the write it describes
+        pass
+
+
+def index_requests(items: List[Any], out: Dict[str, Dict[str, Any]]) -> Dict[str, Dict[str, Any]]:
+    for item in items or []:
+        data = item.model_dump() if hasattr(item, "model_dump") else item
+        if "items" in data and "method" not in data:
+            index_requests(data.get("items") or [], out)
+        elif data.get("id"):
+            out[data["id"]] = data
+    return out
+
+
+def diff_requests(before: List[Any], after: List[Any]) -> List[Tuple[str, str, str]]:
+    """(action, request id, request name) for each request created, edited or deleted."""
+    old = index_requests(before, {})
+    new = index_requests(after, {})
+    changes = []
+    for rid, req in new.items():
+        if rid not in old:
+            changes.append(("request.created", rid, req.get("name", "")))
+        elif old[rid] != req:
+            changes.append(("request.edited", rid, req.get("name", "")))
+    for rid, req in old.items():
+        if rid not in new:
+            changes.append(("request.deleted", rid, req.get("name", "")))
+    return changes
+
+
+def diff_environments(before: Any, after: Any) -> List[Dict[str, Any]]:
+    """Per changed environment: its name, the change and the variable names involved."""
+    old = before.model_dump()["envs"] if before is not None else {}
+    new = after.model_dump()["envs"]
+    changes = []
+    for name, env in new.items():
+        variables = env.get("variables") or {}
+        if name not in old:
+            changes.append({"environment": name, "change": "created", "added": sorted(variables)})
+            continue
+        previous = old[name].get("variables") or {}
+        added = sorted(k for k in variables if k not in previous)
+        removed = sorted(k for k in previous if k not in variables)
+        changed = sorted(k for k in variables if k in previous and previous[k] != variables[k])
+        # Marking a variable secret (or not) counts as changing it
+        old_secrets, new_secrets = old[name].get("secrets") or {}, env.get("secrets") or {}
+        flipped = {
+            k for k in set(old_secrets) | set(new_secrets)
+            if bool(old_secrets.get(k)) != bool(new_secrets.get(k))
+        }
+        changed = sorted(set(changed) | (flipped - set(added) - set(removed)))
+        if added or removed or changed:
+            changes.append({
+                "environment": name,
+                "change": "edited",
+                "added": added,
+                "removed": removed,
+                "changed": changed,
+            })
+    for name in old:
+        if name not in new:
+            changes.append({"environment": name, "change": "deleted"})
+    return changes
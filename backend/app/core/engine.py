@@ -8,6 +8,8 @@ import json
 from typing import Any, Dict, Tuple
 from app.models import HttpRequest, RequestResult, EnvironmentFile
 from app.core.storage import storage
+from app.core.redaction import secret_values, redact
+from app.core import hawk, hmac_signing, integrated_auth, oauth1, sigv4
 from urllib.parse import urlencode, urlparse, urlunparse, parse_qsl
 import base64
 
@@ -151,10 +153,70 @@ class RequestRunner:
             elif auth_type == "bearer":
                 token = self._inject_variables(str(req_copy.auth_params.get("token", "")), env_vars)
                 headers["Authorization"] = f"Bearer {token}"
+            elif auth_type in ("oauth1", "aws_sigv4", "hawk", "hmac", "ntlm", "negotiate"):
+                # Applied in execute(): signed once the URL and body are final, or a handshake
+                req_copy.auth_params = {
+                    k: self._inject_variables(str(v), env_vars) for k, v in req_copy.auth_params.items()
+                }
             req_copy.headers = headers
 
         return req_copy
 
+    def _sign_oauth1(self, req: HttpRequest):
+        form_fields = []
+        if (req.body_mode or "raw").lower() == "form-urlencoded" and req.form_body:
+            # The same fields _build_payload sends; a repeated key keeps its last value
+            fields: Dict[str, str] = {}
+            for row in req.form_body:
+                key = (row.get("key") or "").strip()
+                if row.get("enabled") is False or not key:
+                    continue
+                fields[key] = str(row.get("value") or "")
+            form_fields = list(fields.items())
+        headers = {k: v for k, v in (req.headers or {}).items() if k.lower() != "authorization"}
+        headers["Authorization"] = oauth1.authorization_header(
+            req.method, req.url, req.auth_params, form_fields
+        )
+        req.headers = headers
+
+    def _encode_body(self, req: HttpRequest, data, json_body, content):
+        """Form and JSON bodies as the bytes httpx would send, so a signature can cover them."""
+        has_type = any(k.lower() == "content-type" for k in (req.headers or {}))
+        headers = dict(req.headers or {})
+        if data is not None:
+            content = urlencode(list(data.items()))
+            if not has_type:
+                headers["Content-Type"] = "application/x-www-form-urlencoded"
+        elif json_body is not None:
+            content = json.dumps(json_body)
+            if not has_type:
+                headers["Content-Type"] = "application/json"
+        req.headers = headers
+        if isinstance(content, str):
+            content = content.encode("utf-8")
+        return content
+
+    def _sign_sigv4(self, req: HttpRequest, files, content):
+        if files:
+            # Multipart bodies are framed by httpx; S3 accepts them unsigned
+            body_hash = sigv4.UNSIGNED_PAYLOAD
+        elif (req.auth_params.get("unsigned_payload") or "").lower() == "true":
+            body_hash = sigv4.UNSIGNED_PAYLOAD
+        else:
+            # File bodies are hashed in chunks and rewound, then streamed as usual
+            body_hash = sigv4.payload_hash(content)
+        req.headers = sigv4.sign(req.method, req.url, req.headers, req.auth_params, body_hash)
+
+    def _sign_hawk(self, req: HttpRequest, files, content):
+        headers = {k: v for k, v in (req.headers or {}).items() if k.lower() != "authorization"}
+        content_type = next((v for k, v in headers.items() if k.lower() == "content-type"), "")
+        # Bodiless requests, and multipart ones whose boundary only httpx picks, go unhashed
+        payload = None if files else content
+        headers["Authorization"] = hawk.authorization_header(
+            req.method, req.url, req.auth_params, content_type, payload
+        )
+        req.headers = headers
+
     def _build_payload(self, req: HttpRequest) -> Tuple[Dict[str, Any], Any, Any, Any, list, str]:
         """
         Returns (data, files, json_body, content, file_handles, error_message)
@@ -244,6 +306,23 @@ class RequestRunner:
         content = req.body if req.body else None
         return data, files, json_body, content, file_handles, None
 
+    def _snapshot(self, req: HttpRequest) -> Dict[str, Any]:
+        """The prepared request as sent, without inline file blobs."""
+        snapshot: Dict[str, Any] = {
+            "method": req.method,
+            "url": req.url,
+            "headers": dict(req.headers or {}),
+            "body_mode": req.body_mode,
+            "body": req.body,
+        }
+        if req.form_body:
+            snapshot["form_body"] = [
+                {k: v for k, v in row.items() if k != "file_inline"} for row in req.form_body
+            ]
+        if req.binary:
+            snapshot["binary"] = {k: v for k, v in req.binary.items() if k != "file_inline"}
+        return snapshot
+
     def _httpx_cookies_from_entries(self, entries: list) -> httpx.Cookies:
         jar = httpx.Cookies()
         if not entries:
@@ -301,13 +380,18 @@ class RequestRunner:
         env_file = storage.load_environment(collection_id)
         active_vars = env_file.envs.get(env_file.active_env, {}).variables
         env_id = env_file.active_env
+        try:
+            collection_vars = storage.load_collection(collection_id).variables or {}
+        except Exception:
+            collection_vars = {}
+        scope_vars = {**collection_vars, **active_vars, **(req.variables or {})}
 
         # Load persisted cookies for this environment
         cookie_entries = storage.load_env_cookies(collection_id, env_id)
         client_cookies = self._httpx_cookies_from_entries(cookie_entries)
 
         # 2. Prepare
-        final_req = self._prepare_request(req, active_vars)
+        final_req = self._prepare_request(req, scope_vars)
 
         # 3. Execute
         start_time = time.perf_counter()
@@ -316,9 +400,27 @@ class RequestRunner:
         file_handles = []
         
         try:
+            auth_type = (final_req.auth_type or "").lower()
+            if auth_type == "oauth1":
+                self._sign_oauth1(final_req)
             data, files, json_body, content, file_handles, build_err = self._build_payload(final_req)
             if build_err:
                 raise Exception(build_err)
+            if auth_type in ("aws_sigv4", "hawk", "hmac"):
+                if not files:
+                    content = self._encode_body(final_req, data, json_body, content)
+                    data, json_body = None, None
+                if auth_type == "aws_sigv4":
+                    self._sign_sigv4(final_req, files, content)
+                elif auth_type == "hawk":
+                    self._sign_hawk(final_req, files, content)
+                else:
+                    # Multipart bodies sign as empty; their framing is only known to httpx
+                    final_req.headers = hmac_signing.sign(
+                        final_req.method, final_req.url, final_req.headers, final_req.auth_params,
+                        None if files else content,
+                    )
+            auth = integrated_auth.auth_for(auth_type, final_req.url, final_req.auth_params)
 
             async with httpx.AsyncClient(verify=final_req.verify_ssl, cookies=client_cookies) as client:
                 response = await client.request(
@@ -329,6 +431,7 @@ class RequestRunner:
                     files=files,
                     json=json_body,
                     content=content,
+                    auth=auth,
                     timeout=final_req.timeout_seconds
                 )
                 await response.aread() # Load body into memory
@@ -346,7 +449,8 @@ class RequestRunner:
                 duration_ms=duration,
                 headers={},
                 body=None,
-                error=error
+                error=error,
+                sent_request=self._snapshot(final_req),
             )
         finally:
             for fh in file_handles:
@@ -373,6 +477,15 @@ class RequestRunner:
             except Exception:
                 body_content = ""
 
+        # Binary bodies don't survive as text; the desktop stores these bytes instead.
+        body_base64 = None
+        raw = resp_obj.content or b""
+        try:
+            raw.decode("utf-8")
+        except UnicodeDecodeError:
+            if "charset=" not in content_type_l:
+                body_base64 = base64.b64encode(raw).decode()
+
         # 5. Extraction Logic (Auto-Magic)
         vars_updated = False
         rule_errors = []
@@ -409,15 +522,20 @@ class RequestRunner:
             duration_ms=duration,
             headers=dict(resp_obj.headers),
             body=body_content,
+            body_base64=body_base64,
             body_is_json=body_is_json,
             content_type=content_type,
             body_bytes=body_bytes,
+            sent_request=self._snapshot(final_req),
         )
         if rule_errors:
             result.error = f"Extraction issues: {rule_errors}"
         
-        # 7. Log History
-        storage.append_history(collection_id, result.model_dump())
+        # 7. Log History (secret values masked; the live result keeps them)
+        active_env = env_file.envs.get(env_file.active_env)
+        active_secrets = active_env.secrets if active_env else {}
+        secrets = secret_values(final_req, active_vars, active_secrets)
+        storage.append_history(collection_id, redact(result.model_dump(exclude={"body_base64"}), secrets))
         
         return result
 
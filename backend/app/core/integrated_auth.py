@@ -0,0 +1,303 @@
+"""
+Windows-integrated auth for intranet APIs: NTLM (v2) with a username and password, and
+Negotiate (SPNEGO/Kerberos) with the signed-in user's tickets through GSSAPI, or SSPI on
+Windows. Both are challenge/response handshakes over one connection, so they run as httpx
+auth flows and read each 401 in full, which returns the connection to the pool for the next
+leg instead of closing it.
+"""
+
+import base64
+import hmac
+import os
+import struct
+import time
+from typing import Dict, Generator, Optional, Tuple
+from urllib.parse import urlparse
+
+import httpx
+
+NTLM_SIGNATURE = b"NTLMSSP\x00"
+NEGOTIATE_UNICODE = 0x00000001
+NEGOTIATE_OEM = 0x00000002
+REQUEST_TARGET = 0x00000004
+NEGOTIATE_NTLM = 0x00000200
+NEGOTIATE_ALWAYS_SIGN = 0x00008000
+NEGOTIATE_EXTENDED_SESSIONSECURITY = 0x00080000
+NEGOTIATE_TARGET_INFO = 0x00800000
+NEGOTIATE_128 = 0x20000000
+NEGOTIATE_56 = 0x80000000
+NEGOTIATE_FLAGS = (
+    NEGOTIATE_UNICODE | NEGOTIATE_OEM | REQUEST_TARGET | NEGOTIATE_NTLM | NEGOTIATE_ALWAYS_SIGN
+    | NEGOTIATE_EXTENDED_SESSIONSECURITY | NEGOTIATE_128 | NEGOTIATE_56
+)
+AV_EOL = 0
+AV_TIMESTAMP = 7
+# Seconds between 1601-01-01 (FILETIME epoch) and 1970-01-01
+FILETIME_OFFSET = 11644473600
+
+
+class IntegratedAuthError(Exception):
+    pass
+
+
+# --- MD4 (RFC 1320); OpenSSL 3 builds of hashlib no longer ship it ---
+
+def _rotl(x: int, n: int) -> int:
+    x &= 0xFFFFFFFF
+    return ((x << n) | (x >> (32 - n))) & 0xFFFFFFFF
+
+
+def md4(data: bytes) -> bytes:
+    length = (len(data) * 8) & 0xFFFFFFFFFFFFFFFF
+    data += b"\x80" + b"\x00" * ((55 - len(data)) % 64) + struct.pack("<Q", length)
+    a, b, c, d = 0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476
+    for offset in range(0, len(data), 64):
+        x = struct.unpack("<16I", data[offset:offset + 64])
+        aa, bb, cc, dd = a, b, c, d
+        f = lambda x_, y, z: (x_ & y) | (~x_ & z)
+        g = lambda x_, y, z: (x_ & y) | (x_ & z) | (y & z)
+        h = lambda x_, y, z: x_ ^ y ^ z
+        for i in range(16):
+            k, s = i, (3, 7, 11, 19)[i % 4]
+            if i % 4 == 0:
+                a = _rotl(a + f(b, c, d) + x[k], s)
+            elif i % 4 == 1:
+                d = _rotl(d + f(a, b, c) + x[k], s)
+            elif i % 4 == 2:
+                c = _rotl(c + f(d, a, b) + x[k], s)
+            else:
+                b = _rotl(b + f(c, d, a) + x[k], s)
+        for i in range(16):
+            k, s = (i % 4) * 4 + i // 4, (3, 5, 9, 13)[i % 4]
+            if i % 4 == 0:
+                a = _rotl(a + g(b, c, d) + x[k] + 0x5A827999, s)
+            elif i % 4 == 1:
+                d = _rotl(d + g(a, b, c) + x[k] + 0x5A827999, s)
+            elif i % 4 == 2:
+                c = _rotl(c + g(d, a, b) + x[k] + 0x5A827999, s)
+            else:
+                b = _rotl(b + g(c, d, a) + x[k] + 0x5A827999, s)
+        order = (0, 8, 4, 12, 2, 10, 6, 14, 1, 9, 5, 13, 3, 11, 7, 15)
+        for i in range(16):
+            k, s = order[i], (3, 9, 11, 15)[i % 4]
+            if i % 4 == 0:
+                a = _rotl(a + h(b, c, d) + x[k] + 0x6ED9EBA1, s)
+            elif i % 4 == 1:
+                d = _rotl(d + h(a, b, c) + x[k] + 0x6ED9EBA1, s)
+            elif i % 4 == 2:
+                c = _rotl(c + h(d, a, b) + x[k] + 0x6ED9EBA1, s)
+            else:
+                b = _rotl(b + h(c, d, a) + x[k] + 0x6ED9EBA1, s)
+        a = (a + aa) & 0xFFFFFFFF
+        b = (b + bb) & 0xFFFFFFFF
+        c = (c + cc) & 0xFFFFFFFF
+        d = (d + dd) & 0xFFFFFFFF
+    return struct.pack("<4I", a, b, c, d)
+
+
+# --- NTLM messages (MS-NLMP) ---
+
+def _hmac_md5(key: bytes, data: bytes) -> bytes:
+    return hmac.new(key, data, "md5").digest()
+
+
+def nt_hash(password: str) -> bytes:
+    return md4(password.encode("utf-16-le"))
+
+
+def ntowf_v2(user: str, password: str, domain: str) -> bytes:
+    return _hmac_md5(nt_hash(password), (user.upper() + domain).encode("utf-16-le"))
+
+
+def split_user(username: str, domain: str = "") -> Tuple[str, str]:
+    """DOMAIN\\user and user@domain forms, unless the domain is given separately."""
+    if not domain and "\\" in username:
+        domain, username = username.split("\\", 1)
+    elif not domain and "@" in username:
+        username, domain = username.split("@", 1)
+    return username, domain
+
+
+def negotiate_message() -> bytes:
+    # No domain or workstation supplied; empty security buffers point past the header
+    return NTLM_SIGNATURE + struct.pack("<II", 1, NEGOTIATE_FLAGS) + struct.pack("<HHI", 0, 0, 32) * 2
+
+
+def parse_challenge(message: bytes) -> Tuple[bytes, int, bytes]:
+    """(server challenge, flags, target info) of a CHALLENGE_MESSAGE."""
+    if len(message) < 32 or not message.startswith(NTLM_SIGNATURE) or struct.unpack("<I", message[8:12])[0] != 2:
+        raise IntegratedAuthError("server sent an invalid NTLM challenge")
+    flags = struct.unpack("<I", message[20:24])[0]
+    challenge = message[24:32]
+    target_info = b""
+    if flags & NEGOTIATE_TARGET_INFO and len(message) >= 48:
+        length, _, offset = struct.unpack("<HHI", message[40:48])
+        target_info = message[offset:offset + length]
+    return challenge, flags, target_info
+
+
+def _av_timestamp(target_info: bytes) -> Optional[bytes]:
+    offset = 0
+    while offset + 4 <= len(target_info):
+        av_id, length = struct.unpack("<HH", target_info[offset:offset + 4])
+        if av_id == AV_EOL:
+            break
+        if av_id == AV_TIMESTAMP:
+            return target_info[offset + 4:offset + 4 + length]
+        offset += 4 + length
+    return None
+
+
+def ntlmv2_responses(
+    user: str,
+    password: str,
+    domain: str,
+    server_challenge: bytes,
+    target_info: bytes,
+    client_challenge: bytes,
+    timestamp: bytes,
+) -> Tuple[bytes, bytes]:
+    """(LMv2, NTLMv2) responses to a server challenge."""
+    key = ntowf_v2(user, password, domain)
+    temp = b"\x01\x01" + b"\x00" * 6 + timestamp + client_challenge + b"\x00" * 4 + target_info + b"\x00" * 4
+    nt_proof = _hmac_md5(key, server_challenge + temp)
+    lm = _hmac_md5(key, server_challenge + client_challenge) + client_challenge
+    return lm, nt_proof + temp
+
+
+def authenticate_message(username: str, password: str, domain: str, challenge_message: bytes,
+                         workstation: str = "") -> bytes:
+    server_challenge, server_flags, target_info = parse_challenge(challenge_message)
+    user, domain = split_user(username, domain)
+    timestamp = _av_timestamp(target_info)
+    if timestamp is None:
+        timestamp = struct.pack("<Q", int((time.time() + FILETIME_OFFSET) * 10_000_000))
+    lm, nt = ntlmv2_responses(user, password, domain, server_challenge, target_info, os.urandom(8), timestamp)
+    flags = (NEGOTIATE_FLAGS | NEGOTIATE_TARGET_INFO) & (server_flags | NEGOTIATE_NTLM)
+    fields = [
+        lm,
+        nt,
+        domain.encode("utf-16-le"),
+        user.encode("utf-16-le"),
+        workstation.encode("utf-16-le"),
+        b"",  # no session key exchange
+    ]
+    header_len = 8 + 4 + 8 * len(fields) + 4
+    header = NTLM_SIGNATURE + struct.pack("<I", 3)
+    payload = b""
+    for field in fields:
+        header += struct.pack("<HHI", len(field), len(field), header_len + len(payload))
+        payload += field
+    return header + struct.pack("<I", flags) + payload
+
+
+def _server_token(response: httpx.Response, scheme: str) -> Optional[bytes]:
+    for value in response.headers.get_list("www-authenticate"):
+        for offer in value.split(","):
+            parts = offer.strip().split(None, 1)
+            if parts and parts[0].lower() == scheme.lower():
+                return base64.b64decode(parts[1]) if len(parts) > 1 else b""
+    return None
+
+
+class NTLMAuth(httpx.Auth):
+    # Reading the 401 keeps the connection alive for the authenticate leg
+    requires_response_body = True
+
+    def __init__(self, username: str, password: str, domain: str = "", workstation: str = ""):
+        self.username = username
+        self.password = password
+        self.domain = domain
+        self.workstation = workstation
+
+    def auth_flow(self, request: httpx.Request) -> Generator[httpx.Request, httpx.Response, None]:
+        request.headers["Authorization"] = "NTLM " + base64.b64encode(negotiate_message()).decode()
+        response = yield request
+        if response.status_code != 401:
+            return
+        challenge = _server_token(response, "NTLM")
+        if not challenge:
+            return
+        message = authenticate_message(self.username, self.password, self.domain, challenge, self.workstation)
+        request.headers["Authorization"] = "NTLM " + base64.b64encode(message).decode()
+        yield request
+
+
+class _GssapiContext:
+    def __init__(self, host: str):
+        import gssapi
+
+        name = gssapi.Name(f"HTTP@{host}", gssapi.NameType.hostbased_service)
+        self._ctx = gssapi.SecurityContext(name=name, usage="initiate")
+
+    def step(self, token: Optional[bytes]) -> Optional[bytes]:
+        return self._ctx.step(token)
+
+
+class _SspiContext:
+    def __init__(self, host: str):
+        import sspi
+
+        self._ctx = sspi.ClientAuth("Negotiate", targetspn=f"HTTP/{host}")
+
+    def step(self, token: Optional[bytes]) -> Optional[bytes]:
+        _, buffers = self._ctx.authorize(token)
+        return buffers[0].Buffer
+
+
+def _security_context(host: str):
+    for context in (_SspiContext, _GssapiContext) if os.name == "nt" else (_GssapiContext,):
+        try:
+            return context(host)
+        except ImportError:
+            continue
+    raise IntegratedAuthError(
+        "Negotiate auth needs GSSAPI (the gssapi package) or, on Windows, SSPI (pywin32)"
+    )
+
+
+class NegotiateAuth(httpx.Auth):
+    requires_response_body = True
+
+    def __init__(self, service_host: str = ""):
+        self.service_host = service_host
+
+    def auth_flow(self, request: httpx.Request) -> Generator[httpx.Request, httpx.Response, None]:
+        context = _security_context(self.service_host or request.url.host)
+        try:
+            token = context.step(None)
+        except Exception as ex:
+            raise IntegratedAuthError(f"Negotiate failed: {ex}")
+        request.headers["Authorization"] = "Negotiate " + base64.b64encode(token or b"").decode()
+        response = yield request
+        # Kerberos usually completes in one leg; NTLM under SPNEGO takes another
+        while response.status_code == 401:
+            server = _server_token(response, "Negotiate")
+            if not server:
+                return
+            try:
+                token = context.step(server)
+            except Exception as ex:
+                raise IntegratedAuthError(f"Negotiate failed: {ex}")
+            if not token:
+                return
+            request.headers["Authorization"] = "Negotiate " + base64.b64encode(token).decode()
+            response = yield request
+
+
+def auth_for(auth_type: str, url: str, auth_params: Dict[str, str]) -> Optional[httpx.Auth]:
+    """The httpx auth flow for an `ntlm` or `negotiate` request."""
+    if auth_type == "ntlm":
+        username = (auth_params.get("username") or "").strip()
+        if not username:
+            raise IntegratedAuthError("NTLM needs a username")
+        return NTLMAuth(
+            username,
+            auth_params.get("password") or "",
+            (auth_params.get("domain") or "").strip(),
+            (auth_params.get("workstation") or "").strip(),
+        )
+    if auth_type == "negotiate":
+        # The SPN host, for services reached through an alias or a load balancer
+        return NegotiateAuth((auth_params.get("spn_host") or "").strip() or (urlparse(url).hostname or ""))
+    return None
@@ -0,0 +1,121 @@
+"""
+OAuth 1.0a request signing (RFC 5849). The signature covers the method, the URL with its
+query and any urlencoded form fields, so it is computed on the prepared request, after
+variables and query params are applied.
+"""
+
+import base64
+import hashlib
+import hmac
+import secrets
+import time
+from typing import Dict, List, Optional, Tuple
+from urllib.parse import parse_qsl, quote, urlparse, urlunparse
+
+SIGNATURE_METHODS = ("HMAC-SHA1", "HMAC-SHA256", "RSA-SHA1", "RSA-SHA256", "PLAINTEXT")
+
+
+class OAuth1Error(ValueError):
+    pass
+
+
+def _enc(value: str) -> str:
+    # RFC 3986 unreserved characters only; everything else percent-encoded
+    return quote(str(value), safe="~-._")
+
+
+def base_url(url: str) -> str:
+    """Scheme and host lowercased, default ports dropped, no query or fragment."""
+    parsed = urlparse(url)
+    scheme = parsed.scheme.lower()
+    host = (parsed.hostname or "").lower()
+    port = parsed.port
+    netloc = host if port is None or (scheme, port) in (("http", 80), ("https", 443)) else f"{host}:{port}"
+    return urlunparse((scheme, netloc, parsed.path or "/", "", "", ""))
+
+
+def base_string(method: str, url: str, params: List[Tuple[str, str]]) -> str:
+    pairs = sorted((_enc(k), _enc(v)) for k, v in params)
+    normalized = "&".join(f"{k}={v}" for k, v in pairs)
+    return "&".join([method.upper(), _enc(base_url(url)), _enc(normalized)])
+
+
+def _rsa_sign(private_key: str, data: bytes, sha256: bool) -> bytes:
+    from cryptography.hazmat.primitives import hashes, serialization
+    from cryptography.hazmat.primitives.asymmetric import padding
+
+    try:
+        key = serialization.load_pem_private_key(private_key.encode("utf-8"), password=None)
+    except (ValueError, TypeError) as ex:
+        raise OAuth1Error(f"invalid RSA private key: {ex}")
+    algorithm = hashes.SHA256() if sha256 else hashes.SHA1()
+    return key.sign(data, padding.PKCS1v15(), algorithm)
+
+
+def signature(method_name: str, base: str, consumer_secret: str, token_secret: str,
+              private_key: str = "") -> str:
+    key = f"{_enc(consumer_secret)}&{_enc(token_secret)}"
+    if method_name == "PLAINTEXT":
+        return key
+    if method_name in ("HMAC-SHA1", "HMAC-SHA256"):
+        digest = hashlib.sha1 if method_name == "HMAC-SHA1" else hashlib.sha256
+        raw = hmac.new(key.encode("utf-8"), base.encode("utf-8"), digest).digest()
+    elif method_name in ("RSA-SHA1", "RSA-SHA256"):
+        if not private_key.strip():
+            raise OAuth1Error(f"{method_name} signing needs a private key")
+        raw = _rsa_sign(private_key, base.encode("utf-8"), method_name == "RSA-SHA256")
+    else:
+        raise OAuth1Error(f"unsupported OAuth 1.0a signature method: {method_name}")
+    return base64.b64encode(raw).decode("ascii")
+
+
+def authorization_header(
+    method: str,
+    url: str,
+    auth_params: Dict[str, str],
+    form_fields: Optional[List[Tuple[str, str]]] = None,
+    nonce: Optional[str] = None,
+    timestamp: Optional[str] = None,
+) -> str:
+    """
+    The `Authorization: OAuth …` value for one request. auth_params holds consumer_key,
+    consumer_secret, token, token_secret, signature_method, private_key (PEM, RSA methods),
+    and optionally realm, callback and verifier.
+    """
+    consumer_key = (auth_params.get("consumer_key") or "").strip()
+    if not consumer_key:
+        raise OAuth1Error("OAuth 1.0a needs a consumer key")
+    method_name = (auth_params.get("signature_method") or "HMAC-SHA1").strip().upper()
+    if method_name not in SIGNATURE_METHODS:
+        raise OAuth1Error(f"unsupported OAuth 1.0a signature method: {method_name}")
+
+    oauth: Dict[str, str] = {
+        "oauth_consumer_key": consumer_key,
+        "oauth_nonce": nonce or secrets.token_hex(16),
+        "oauth_signature_method": method_name,
+        "oauth_timestamp": timestamp or str(int(time.time())),
+        "oauth_version": "1.0",
+    }
+    for field, name in (("token", "oauth_token"), ("callback", "oauth_callback"), ("verifier", "oauth_verifier")):
+        value = (auth_params.get(field) or "").strip()
+        if value:
+            oauth[name] = value
+
+    params = parse_qsl(urlparse(url).query, keep_blank_values=True)
+    params += list(form_fields or [])
+    params += list(oauth.items())
+    base = base_string(method, url, params)
+    oauth["oauth_signature"] = signature(
+        method_name,
+        base,
+        auth_params.get("consumer_secret") or "",
+        auth_params.get("token_secret") or "",
+        auth_params.get("private_key") or "",
+    )
+
+    parts = []
+    realm = (auth_params.get("realm") or "").strip()
+    if realm:
+        parts.append(f'realm="{_enc(realm)}"')
+    parts += [f'{_enc(k)}="{_enc(v)}"' for k, v in sorted(oauth.items())]
+    return "OAuth " + ", ".join(parts)
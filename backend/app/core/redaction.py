@@ -0,0 +1,59 @@
+from typing import Any, Dict, List
+
+from app.models import HttpRequest
+
+MASK = "••••••••"
+# Shorter values would mask unrelated text wherever they happen to occur.
+MIN_SECRET_LEN = 4
+
+
+def _add(values: List[str], value: Any) -> None:
+    if not isinstance(value, str) or len(value) < MIN_SECRET_LEN:
+        return
+    if value not in values:
+        values.append(value)
+
+
+def secret_values(req: HttpRequest, env_vars: Dict[str, Any], env_secrets: Dict[str, bool]) -> List[str]:
+    """
+    Values sourced from secret-flagged environment variables or request fields marked
+    secret. `req` should be the prepared request so injected values are covered too.
+    """
+    values: List[str] = []
+    for key, is_secret in (env_secrets or {}).items():
+        if is_secret:
+            _add(values, env_vars.get(key))
+    for key, is_secret in (req.secret_headers or {}).items():
+        if is_secret:
+            _add(values, (req.headers or {}).get(key))
+    for key, is_secret in (req.secret_auth_params or {}).items():
+        if is_secret:
+            _add(values, (req.auth_params or {}).get(key))
+    secret_q = req.secret_query_params or {}
+    for row in req.query_params or []:
+        if secret_q.get(row.get("key")):
+            _add(values, row.get("value"))
+    secret_form = req.secret_form_fields or {}
+    for row in req.form_body or []:
+        if secret_form.get(row.get("key")) or row.get("secret"):
+            _add(values, row.get("value"))
+    if req.secret_body and isinstance(req.body, str):
+        _add(values, req.body)
+    # Longest first so a secret containing another is masked whole.
+    return sorted(values, key=len, reverse=True)
+
+
+def redact(value: Any, secrets: List[str]) -> Any:
+    """Masks every secret inside strings of a JSON-like value, dict keys included."""
+    if not secrets:
+        return value
+    if isinstance(value, str):
+        for secret in secrets:
+            if secret in value:
+                value = value.replace(secret, MASK)
+        return value
+    if isinstance(value, list):
+        return [redact(v, secrets) for v in value]
+    if isinstance(value, dict):
+        return {redact(k, secrets): redact(v, secrets) for k, v in value.items()}
+    return value
@@ -0,0 +1,211 @@
+"""
+AWS Signature Version 4. Requests are signed with their final URL, headers and body hash,
+so signing runs on the built payload, just before sending. Keys come from auth_params or
+from a profile in the shared credentials file (~/.aws/credentials).
+"""
+
+import configparser
+import datetime
+import hashlib
+import hmac
+import os
+import re
+from pathlib import Path
+from typing import Any, Dict, Optional, Tuple
+from urllib.parse import parse_qsl, quote, unquote, urlparse
+
+ALGORITHM = "AWS4-HMAC-SHA256"
+UNSIGNED_PAYLOAD = "UNSIGNED-PAYLOAD"
+EMPTY_SHA256 = hashlib.sha256(b"").hexdigest()
+_REGION = re.compile(r"^[a-z]{2}(-gov|-iso[a-z]*)?-[a-z]+-\d+$")
+_CHUNK = 1024 * 1024
+
+
+class SigV4Error(ValueError):
+    pass
+
+
+def _enc(value: str) -> str:
+    return quote(value, safe="-_.~")
+
+
+def _credentials_file() -> Path:
+    return Path(os.environ.get("AWS_SHARED_CREDENTIALS_FILE") or Path.home() / ".aws" / "credentials")
+
+
+def _config_file() -> Path:
+    return Path(os.environ.get("AWS_CONFIG_FILE") or Path.home() / ".aws" / "config")
+
+
+def profile_credentials(profile: str) -> Dict[str, str]:
+    """access_key, secret_key, session_token and region of a shared-credentials profile."""
+    creds = configparser.ConfigParser()
+    creds.read(_credentials_file())
+    if not creds.has_section(profile):
+        raise SigV4Error(f"AWS profile '{profile}' not found in {_credentials_file()}")
+    section = creds[profile]
+    found = {
+        "access_key": section.get("aws_access_key_id", ""),
+        "secret_key": section.get("aws_secret_access_key", ""),
+        "session_token": section.get("aws_session_token", ""),
+        "region": section.get("region", ""),
+    }
+    if not found["region"]:
+        config = configparser.ConfigParser()
+        config.read(_config_file())
+        name = profile if profile == "default" else f"profile {profile}"
+        if config.has_section(name):
+            found["region"] = config[name].get("region", "")
+    return found
+
+
+def derive_scope(host: str) -> Tuple[str, str]:
+    """(region, service) from an AWS endpoint host; blanks for hosts that aren't AWS."""
+    host = host.lower()
+    for suffix in (".amazonaws.com", ".amazonaws.com.cn"):
+        if host.endswith(suffix):
+            labels = host[: -len(suffix)].split(".")
+            break
+    else:
+        return "", ""
+    region, service = "", ""
+    for index, label in enumerate(labels):
+        if label == "s3" or label.startswith("s3-"):
+            service = "s3"
+            # The legacy s3-<region> endpoints
+            if _REGION.match(label[3:]):
+                region = label[3:]
+        elif label == "execute-api":
+            service = "execute-api"
+        elif _REGION.match(label):
+            region = label
+            if not service and index > 0:
+                service = labels[index - 1]
+    if not service:
+        service = labels[-1]
+    # Global endpoints (iam, sts, s3.amazonaws.com) sign for us-east-1
+    return region or "us-east-1", service
+
+
+def payload_hash(content: Any) -> str:
+    """Hex SHA-256 of a body given as bytes, text or a file opened in binary mode."""
+    if content is None:
+        return EMPTY_SHA256
+    if isinstance(content, str):
+        content = content.encode("utf-8")
+    if isinstance(content, (bytes, bytearray)):
+        return hashlib.sha256(content).hexdigest()
+    digest = hashlib.sha256()
+    start = content.tell()
+    for chunk in iter(lambda: content.read(_CHUNK), b""):
+        digest.update(chunk)
+    content.seek(start)
+    return digest.hexdigest()
+
+
+def _canonical_uri(path: str, service: str) -> str:
+    segments = [_enc(unquote(segment)) for segment in (path or "/").split("/")]
+    if service != "s3":
+        # Every service but S3 signs the already-encoded path encoded again
+        segments = [_enc(segment) for segment in segments]
+    return "/".join(segments) or "/"
+
+
+def _canonical_query(query: str) -> str:
+    pairs = sorted((_enc(k), _enc(v)) for k, v in parse_qsl(query, keep_blank_values=True))
+    return "&".join(f"{k}={v}" for k, v in pairs)
+
+
+def _host(url) -> str:
+    host = (url.hostname or "").lower()
+    if url.port and (url.scheme, url.port) not in (("http", 80), ("https", 443)):
+        return f"{host}:{url.port}"
+    return host
+
+
+def _key(secret: str, date: str, region: str, service: str) -> bytes:
+    key = ("AWS4" + secret).encode("utf-8")
+    for part in (date, region, service, "aws4_request"):
+        key = hmac.new(key, part.encode("utf-8"), hashlib.sha256).digest()
+    return key
+
+
+def resolve_credentials(auth_params: Dict[str, str]) -> Dict[str, str]:
+    creds = {k: (auth_params.get(k) or "").strip() for k in ("access_key", "secret_key", "session_token", "region")}
+    profile = (auth_params.get("profile") or "").strip()
+    if profile and not (creds["access_key"] and creds["secret_key"]):
+        from_profile = profile_credentials(profile)
+        creds = {k: creds[k] or from_profile.get(k, "") for k in creds}
+    if not creds["access_key"] or not creds["secret_key"]:
+        raise SigV4Error("AWS Signature V4 needs an access key and secret key, or a profile")
+    return creds
+
+
+def sign(
+    method: str,
+    url: str,
+    headers: Dict[str, str],
+    auth_params: Dict[str, str],
+    body_hash: str,
+    now: Optional[datetime.datetime] = None,
+) -> Dict[str, str]:
+    """
+    headers plus Authorization, X-Amz-Date, X-Amz-Content-Sha256 (S3) and, for temporary
+    credentials, X-Amz-Security-Token. auth_params holds access_key, secret_key,
+    session_token, profile, region and service; region and service default to what the
+    host names.
+    """
+    creds = resolve_credentials(auth_params)
+    parsed = urlparse(url)
+    host = _host(parsed)
+    derived_region, derived_service = derive_scope(parsed.hostname or "")
+    region = (auth_params.get("region") or "").strip() or creds["region"] or derived_region
+    service = (auth_params.get("service") or "").strip() or derived_service
+    if not region or not service:
+        raise SigV4Error("AWS Signature V4 needs a region and service for this host")
+
+    now = now or datetime.datetime.now(datetime.timezone.utc)
+    amz_date = now.strftime("%Y%m%dT%H%M%SZ")
+    date = amz_date[:8]
+
+    # Replace any earlier signature rather than sending two
+    managed = {"authorization", "x-amz-date", "x-amz-content-sha256", "x-amz-security-token", "host"}
+    signed_headers = {k: v for k, v in (headers or {}).items() if k.lower() not in managed}
+    signed_headers["X-Amz-Date"] = amz_date
+    if service == "s3" or body_hash == UNSIGNED_PAYLOAD:
+        # S3 wants the body hash as a header too; other services take it from the signature
+        signed_headers["X-Amz-Content-Sha256"] = body_hash
+    if creds["session_token"]:
+        signed_headers["X-Amz-Security-Token"] = creds["session_token"]
+
+    canonical = {"host": host}
+    for name, value in signed_headers.items():
+        lower = name.lower()
+        if lower.startswith("x-amz-") or lower in ("content-type", "content-md5"):
+            canonical[lower] = " ".join(str(value).split())
+    names = sorted(canonical)
+    canonical_request = "\n".join([
+        method.upper(),
+        _canonical_uri(parsed.path, service),
+        _canonical_query(parsed.query),
+        "".join(f"{name}:{canonical[name]}\n" for name in names),
+        ";".join(names),
+        body_hash,
+    ])
+    scope = f"{date}/{region}/{service}/aws4_request"
+    string_to_sign = "\n".join([
+        ALGORITHM,
+        amz_date,
+        scope,
+        hashlib.sha256(canonical_request.encode("utf-8")).hexdigest(),
+    ])
+    signature = hmac.new(
+        _key(creds["secret_key"], date, region, service),
+        string_to_sign.encode("utf-8"),
+        hashlib.sha256,
+    ).hexdigest()
+    signed_headers["Authorization"] = (
+        f"{ALGORITHM} Credential={creds['access_key']}/{scope}, "
+        f"SignedHeaders={';'.join(names)}, Signature={signature}"
+    )
+    return signed_headers
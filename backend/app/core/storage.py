@@ -27,6 +27,7 @@ from app.core.crypto import (
     KDF_NAME,
     KEY_LEN,
 )
+from app.core import audit
 from app.core.secret_transformers import (
     transform_request_for_encryption,
     transform_request_for_decryption,
@@ -41,6 +42,10 @@ class VaultLockedError(Exception):
     """Raised when sensitive data access is attempted while the vault is locked."""
 
 
+class ReadOnlyWorkspaceError(Exception):
+    """Raised on writes while another LiteFetch instance owns the workspace."""
+
+
 class StorageEngine:
     def __init__(self, workspace_dir: str | None = None):
         workspace_dir = workspace_dir or os.getenv("LITEFETCH_WORKSPACE", "./workspace")
@@ -49,6 +54,8 @@ class StorageEngine:
         self.collections_dir.mkdir(parents=True, exist_ok=True)
         self.master_key: bytes | None = None
         self.vault_initialized = self._vault_path().exists()
+        # Set by the desktop shell when another instance holds the workspace lock
+        self.read_only = os.getenv("LITEFETCH_READ_ONLY") == "1"
         self._ensure_workspace_gitignore()
         self._ensure_default_collection()
 
@@ -56,6 +63,8 @@ class StorageEngine:
         """
         Ensure dynamic runtime artifacts are ignored when users version their workspace.
         """
+        if self.read_only:
+            return
         patterns = [
             "collections/*/history.json",
             "collections/*/last_results.json",
@@ -158,7 +167,16 @@ class StorageEngine:
     def _is_runtime_artifact(self, target_path: Path) -> bool:
         return target_path.name in {"history.json", "last_results.json", "cookies.json"}
 
+    def _require_writable(self):
+        if self.read_only:
+            raise ReadOnlyWorkspaceError("workspace is read-only")
+
     def _atomic_write(self, target_path: Path, data: Any, compact: bool = False):
+        if self.read_only:
+            # History, results and cookies are dropped rather than failing the request
+            if self._is_runtime_artifact(target_path):
+                return
+            self._require_writable()
         target_path.parent.mkdir(parents=True, exist_ok=True)
         tmp = target_path.with_suffix(".tmp")
         dump_kwargs = {"separators": (",", ":")} if compact else {"indent": 2}
@@ -317,14 +335,14 @@ class StorageEngine:
             if collection_path.exists():
                 try:
                     col = self.load_collection(cid)
-                    self.save_collection(cid, col)
+                    self.save_collection(cid, col, audited=False)
                     stats["collections"] += 1
                 except Exception:
                     pass
             if env_path.exists():
                 try:
                     env = self.load_environment(cid)
-                    self.save_environment(cid, env)
+                    self.save_environment(cid, env, audited=False)
                     stats["environments"] += 1
                 except Exception:
                     pass
@@ -353,6 +371,8 @@ class StorageEngine:
 
     # --- Default bootstrap ---
     def _ensure_default_collection(self):
+        if self.read_only:
+            return
         dirs = [p for p in self.collections_dir.iterdir() if p.is_dir()]
         if dirs:
             return
@@ -389,6 +409,7 @@ class StorageEngine:
         last_results: Optional[Dict[str, Any]] = None,
         history: Optional[List[RequestResult]] = None,
     ) -> CollectionMeta:
+        self._require_writable()
         cid = collection_id or uuid4().hex
         cdir = self._collection_dir(cid)
         cdir.mkdir(parents=True, exist_ok=True)
@@ -396,12 +417,20 @@ class StorageEngine:
         meta = CollectionMeta(id=cid, name=name, created_at=now, updated_at=now)
 
         self._atomic_write(self._meta_path(cid), meta)
-        self.save_collection(cid, collection or Collection(id=cid, name=name))
-        self.save_environment(cid, environment or EnvironmentFile())
+        collection = collection or Collection(id=cid, name=name)
+        self.save_collection(cid, collection, audited=False)
+        self.save_environment(cid, environment or EnvironmentFile(), audited=False)
         self._atomic_write(self._ui_state_path(cid), ui_state or {"openFolders": []})
         self._write_sensitive(self._last_results_path(cid), last_results or {})
         self._write_sensitive(self._history_path(cid), history or [])
         self._write_sensitive(self._cookies_path(cid), {})
+        audit.record(
+            self.base_dir,
+            "collection.created",
+            cid,
+            name=name,
+            requests=len(audit.index_requests(collection.items, {})),
+        )
         return meta
 
     def _touch_meta(self, collection_id: str, name: Optional[str] = None):
@@ -430,20 +459,33 @@ class StorageEngine:
         # If we unwrapped a legacy encrypted blob, rewrite in new format
         if was_wrapped:
             try:
-                self.save_collection(collection_id, col)
+                self.save_collection(collection_id, col, audited=False)
             except Exception:
                 pass
         return col
 
-    def save_collection(self, collection_id: str, collection: Collection):
+    def save_collection(self, collection_id: str, collection: Collection, audited: bool = True):
         # Protect against plaintext writes when vault exists but locked
         if self._vault_ready() and not self.master_key:
             raise VaultLockedError("workspace locked")
+        previous = self._previous(self.load_collection, collection_id) if audited else None
         col_copy = collection.model_copy(deep=True)
         if self.master_key:
             col_copy.items = self._walk_requests(col_copy.items or [], self._encrypt_request_secrets)
         self._atomic_write(self._collection_path(collection_id), col_copy)
         self._touch_meta(collection_id, name=collection.name)
+        if previous is not None:
+            if previous.name != collection.name:
+                audit.record(self.base_dir, "collection.renamed", collection_id, name=collection.name)
+            for action, request_id, name in audit.diff_requests(previous.items, collection.items):
+                audit.record(self.base_dir, action, collection_id, request_id=request_id, name=name)
+
+    def _previous(self, load, collection_id: str):
+        """The stored version of what is about to be overwritten, to audit the difference."""
+        try:
+            return load(collection_id)
+        except (OSError, ValueError, VaultLockedError):
+            return None
 
     def load_environment(self, collection_id: str) -> EnvironmentFile:
         if self._vault_ready() and not self.master_key:
@@ -460,17 +502,21 @@ class StorageEngine:
         env_file = transform_environment_for_decryption(env_file, self.master_key)
         if was_wrapped:
             try:
-                self.save_environment(collection_id, env_file)
+                self.save_environment(collection_id, env_file, audited=False)
             except Exception:
                 pass
         return env_file
 
-    def save_environment(self, collection_id: str, env: EnvironmentFile):
+    def save_environment(self, collection_id: str, env: EnvironmentFile, audited: bool = True):
         if self._vault_ready() and not self.master_key:
             raise VaultLockedError("workspace locked")
+        previous = self._previous(self.load_environment, collection_id) if audited else None
         env_copy = transform_environment_for_encryption(env, self.master_key)
         self._atomic_write(self._env_path(collection_id), env_copy)
         self._touch_meta(collection_id)
+        if audited:
+            for change in audit.diff_environments(previous, env):
+                audit.record(self.base_dir, "environment.changed", collection_id, **change)
 
     def load_ui_state(self, collection_id: str) -> dict:
         with open(self._ui_state_path(collection_id), "r", encoding="utf-8") as f:
@@ -509,6 +555,15 @@ class StorageEngine:
         history = history[:50]
         self._write_sensitive(self._history_path(collection_id), history)
 
+    def attach_history_tests(self, collection_id: str, request_id: str, timestamp: float, tests: dict) -> bool:
+        history = self.load_history(collection_id) or []
+        for entry in history:
+            if entry.get("request_id") == request_id and entry.get("timestamp") == timestamp:
+                entry["tests"] = tests
+                self._write_sensitive(self._history_path(collection_id), history)
+                return True
+        return False
+
     # --- Cookies ---
     def _load_cookies_blob(self, collection_id: str) -> Dict[str, list]:
         data = self._read_sensitive(self._cookies_path(collection_id), default={})
@@ -566,9 +621,12 @@ class StorageEngine:
         )
 
     def delete_collection(self, collection_id: str):
+        self._require_writable()
         cdir = self._collection_dir(collection_id)
         if cdir.exists():
+            name = self.load_meta(collection_id).name if self._meta_path(collection_id).exists() else None
             shutil.rmtree(cdir)
+            audit.record(self.base_dir, "collection.deleted", collection_id, name=name)
 
     # --- Workspace crypto helpers ---
     def has_legacy_inline_encryption(self) -> bool:
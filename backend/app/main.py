@@ -1,6 +1,9 @@
-from fastapi import FastAPI
+from fastapi import FastAPI, Request
+from fastapi.responses import JSONResponse
 from fastapi.middleware.cors import CORSMiddleware
 from app.api import router
+from app.core.audit import ORIGIN_HEADER, ORIGINS, current_origin
+from app.core.storage import ReadOnlyWorkspaceError
 
 app = FastAPI(title="LiteFetch Core")
 
@@ -13,6 +16,20 @@ app.add_middleware(
     allow_headers=["*"],
 )
 
+@app.middleware("http")
+async def audit_origin(request: Request, call_next):
+    # Attribute workspace changes to the shell's imports and syncs; the rest come from the UI
+    origin = request.headers.get(ORIGIN_HEADER, "ui")
+    token = current_origin.set(origin if origin in ORIGINS else "ui")
+    try:
+        return await call_next(request)
+    finally:
+        current_origin.reset(token)
+
+@app.exception_handler(ReadOnlyWorkspaceError)
+async def read_only_workspace(_request: Request, exc: ReadOnlyWorkspaceError):
+    return JSONResponse(status_code=409, content={"detail": str(exc)})
+
 app.include_router(router, prefix="/api")
 
 @app.get("/")
@@ -11,46 +11,129 @@ class ExtractionRule(BaseModel):
     source_path: str  # JMESPath, e.g. "body.data.token"
     target_variable: str  # e.g. "access_token"
 
+class ResponseExample(BaseModel):
+    # A saved response, served by the desktop mock server; see desktop/src/mock/mod.rs
+    id: str = Field(default_factory=lambda: str(uuid.uuid4()))
+    name: str = "Example"
+    status_code: int = 200
+    headers: Dict[str, str] = {}
+    body: str = ""
+    delay_ms: int = 0
+
 class HttpRequest(BaseModel):
     id: str = Field(default_factory=lambda: str(uuid.uuid4()))
     name: str = "New Request"
     method: Literal["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"] = "GET"
     url: str = ""
+    # Markdown shown in generated docs; see desktop/core/src/docs.rs
+    description: Optional[str] = None
     headers: Dict[str, str] = {}
     body: Optional[Union[str, Dict[str, Any]]] = None # raw string or JSON
-    body_mode: str = "raw" # raw, json, form-urlencoded, form-data, binary
+    body_mode: str = "raw" # raw, json, form-urlencoded, form-data, binary, file, multipart
+    # "file" and "multipart" bodies are streamed by the desktop shell; see desktop/src/upload.rs
     # form_body rows support explicit type/text/file/binary metadata
     # {key, type: 'text' | 'file' | 'binary', value?, file_path?, file_inline?, file_name?, enabled?, secret?}
+    # multipart rows may also set content_type and headers per part
     form_body: Optional[List[Dict[str, Any]]] = None
-    # binary payload metadata for body_mode == "binary"
-    binary: Optional[Dict[str, Any]] = None  # {file_path?, file_inline?, file_name?}
-    auth_type: Literal["none", "basic", "bearer"] = "none"
+    # binary payload metadata for body_mode == "binary" or "file"
+    binary: Optional[Dict[str, Any]] = None  # {file_path?, file_inline?, file_name?, content_type?}
+    # "plugin" auth is applied by the desktop shell from a WASM plugin; see desktop/src/plugins.rs
+    # oauth1 signs with auth_params consumer_key/consumer_secret/token/token_secret/signature_method
+    # aws_sigv4 signs with access_key/secret_key/session_token or profile, plus region/service
+    # ntlm takes username/password/domain; negotiate uses the signed-in user's Kerberos tickets
+    # hawk signs with id/key/algorithm and optional ext/app/dlg
+    # hmac signs a string-to-sign template (secret/algorithm/template/header); see core/hmac_signing.py
+    # jwt_bearer tokens are signed by the desktop shell from algorithm/key/claims; see desktop/src/jwt.rs
+    auth_type: Literal[
+        "none", "basic", "bearer", "plugin", "oauth2", "oauth1", "aws_sigv4", "hawk", "ntlm",
+        "negotiate", "jwt_bearer", "hmac",
+    ] = "none"
     auth_params: Dict[str, str] = {}
     query_params: Optional[List[Dict[str, Any]]] = None  # [{key, value, enabled}]
     extract_rules: List[ExtractionRule] = []
+    # Request-scoped variables; the last layer, overriding environment and collection values
+    variables: Dict[str, Any] = {}
+    # JavaScript run by the desktop shell before sending; see desktop/src/scripting.rs
+    pre_request_script: Optional[str] = None
+    # JavaScript run after the response arrives, asserting on it with lf.test/lf.expect
+    test_script: Optional[str] = None
+    examples: List[ResponseExample] = []
+    # GraphQL persisted queries: "apq" or "static" (manifest ids); see desktop/src/graphql_persisted.rs
+    graphql_persisted: Optional[str] = None
+    # JSON-RPC 2.0 calls the body is written from: {calls: [{method, params?, id?, notification?}], batch?}; see desktop/src/jsonrpc.rs
+    jsonrpc: Optional[Dict[str, Any]] = None
+    # jq program the response view applies to the body; see desktop/src/responses.rs
+    jq_filter: Optional[str] = None
+    # JSON Schema the response body must match; checked by collection runs
+    response_schema: Optional[Dict[str, Any]] = None
     # Secret markers for UI/serialization awareness
     secret_headers: Dict[str, bool] = {}
     secret_query_params: Dict[str, bool] = {}
     secret_form_fields: Dict[str, bool] = {}
     secret_auth_params: Dict[str, bool] = {}
     secret_body: bool = False
+    # Opt out of workspace/collection default headers and interceptors; see desktop/src/defaults.rs
+    skip_defaults: bool = False
+    skip_interceptors: List[str] = []
+    # Browser/tool User-Agent and matching headers this request goes out as; see desktop/src/client_profiles.rs
+    client_profile: Optional[str] = None
+    # Generated header values (Idempotency-Key, traceparent, ...) set per send; see desktop/src/defaults.rs
+    correlation_ids: Optional[Dict[str, str]] = None
     
     # Settings
     timeout_seconds: int = 30
+    # Wait before this request in collection runs; 0 uses the run's delay
+    delay_ms: int = 0
     verify_ssl: bool = False
+    # Simulated latency/throttling/failures replacing the workspace's; see desktop/src/network.rs
+    network_profile: Optional[Dict[str, Any]] = None
+    # How the next page is found when fetching every page; see desktop/src/pagination.rs
+    pagination: Optional[Dict[str, Any]] = None
+    # Checks on the response body: {expected?: "<algorithm>:<hash>", signature?: {header?, key?, jwks_url?}}; see desktop/src/integrity.rs
+    integrity: Optional[Dict[str, Any]] = None
+    # Read the response record by record as it arrives: {format?: auto|ndjson|json_seq|lines|chunks, max_records?}; see desktop/src/streaming.rs
+    streaming: Optional[Dict[str, Any]] = None
+    # Local commands run around the send: {pre?: [...], post?: [...]} of {program, args?, variable?, timeout_ms?, required?}; see desktop/src/hooks.rs
+    hooks: Optional[Dict[str, Any]] = None
 
 class CollectionFolder(BaseModel):
     id: str = Field(default_factory=lambda: str(uuid.uuid4()))
     name: str = "New Folder"
+    description: Optional[str] = None
     items: List[Union['CollectionFolder', HttpRequest]] = [] # Recursive structure
 
 # Resolve forward reference for recursion
 CollectionFolder.model_rebuild()
 
+class WorkflowStep(BaseModel):
+    id: str = Field(default_factory=lambda: str(uuid.uuid4()))
+    request_id: str
+    name: Optional[str] = None
+    # [{variable, source: body|header|status, path?}]; body paths are JSONPath, e.g. "$.data.token"
+    extracts: List[Dict[str, Any]] = []
+    # [{source: body|header|status|duration, path?, op, value?}]; a failed assertion stops the run
+    assertions: List[Dict[str, Any]] = []
+    # [{when: <assertion>, goto: step id | "end"}]; the first match wins, otherwise the next step runs
+    branches: List[Dict[str, Any]] = []
+
+class Workflow(BaseModel):
+    id: str = Field(default_factory=lambda: str(uuid.uuid4()))
+    name: str = "New Workflow"
+    # Starting values for the request layer of every step; extracts add to them
+    variables: Dict[str, Any] = {}
+    steps: List[WorkflowStep] = []
+
 class Collection(BaseModel):
     id: str = Field(default_factory=lambda: str(uuid.uuid4()))
     name: str = "My Collection"
+    description: Optional[str] = None
     items: List[Union[CollectionFolder, HttpRequest]] = []
+    # Collection-scoped variables; the active environment's values take precedence
+    variables: Dict[str, Any] = {}
+    # Request chains run by the desktop shell; see desktop/src/workflow.rs
+    workflows: List[Workflow] = []
+    # Default headers and request/response interceptors applied by the desktop shell
+    defaults: Dict[str, Any] = {}
 
 class CollectionMeta(BaseModel):
     id: str
@@ -79,11 +162,17 @@ class RequestResult(BaseModel):
     duration_ms: float
     headers: Dict[str, str]
     body: Any
+    # The raw bytes, base64-encoded, when the body isn't text; left out of history
+    body_base64: Optional[str] = None
     body_is_json: bool = False
     content_type: Optional[str] = None
     body_bytes: int = 0
     error: Optional[str] = None
     timestamp: float = Field(default_factory=time.time)
+    # What was actually sent after variable injection: {method, url, headers, body_mode, body, form_body?}
+    sent_request: Optional[Dict[str, Any]] = None
+    # Post-response test report from the desktop shell: {passed, failed, results: [{name, passed, error?}], error?}
+    tests: Optional[Dict[str, Any]] = None
 
 # --- Cookie Models ---
 
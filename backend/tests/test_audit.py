@@ -0,0 +1,36 @@
+import sys
+from pathlib import Path
+
+sys.path.append(str(Path(__file__).resolve().parents[1]))
+
+from app.core.audit import diff_environments, diff_requests
+from app.models import EnvironmentFile
+
+
+def test_request_changes_are_detected_inside_folders():
+    before = [{"id": "f1", "name": "Auth", "items": [{"id": "r1", "name": "Login", "method": "POST"}]}]
+    after = [
+        {"id": "f1", "name": "Auth", "items": [{"id": "r1", "name": "Sign in", "method": "POST"}]},
+        {"id": "r2", "name": "Me", "method": "GET"},
+    ]
+    assert diff_requests(before, after) == [
+        ("request.edited", "r1", "Sign in"),
+        ("request.created", "r2", "Me"),
+    ]
+    assert diff_requests(after, before) == [
+        ("request.edited", "r1", "Login"),
+        ("request.deleted", "r2", "Me"),
+    ]
+
+
+def test_environment_changes_list_names_not_values():
+    before = EnvironmentFile(envs={"default": {"name": "default", "variables": {"a": "1", "b": "2"}}})
+    after = EnvironmentFile(envs={"default": {"name": "default", "variables": {"a": "9", "c": "3"}}})
+    (change,) = diff_environments(before, after)
+    assert change == {
+        "environment": "default",
+        "change": "edited",
+        "added": ["c"],
+        "removed": ["b"],
+        "changed": ["a"],
+    }
@@ -0,0 +1,49 @@
+import io
+import sys
+from pathlib import Path
+
+sys.path.append(str(Path(__file__).resolve().parents[1]))
+
+from app.core import hawk
+
+# The credentials and request of the Hawk reference documentation
+PARAMS = {
+    "id": "dh37fgj492je",
+    "key": "werxhqb98rpaxn39848xrunpaw3489ruxnpa98w4rxn",
+    "algorithm": "sha256",
+    "ext": "some-app-ext-data",
+}
+URL = "http://example.com:8000/resource/1?b=1&a=2"
+
+
+def test_header_without_payload_matches_reference():
+    header = hawk.authorization_header("GET", URL, PARAMS, ts="1353832234", nonce="j4h3g2")
+    assert header == (
+        'Hawk id="dh37fgj492je", ts="1353832234", nonce="j4h3g2", ext="some-app-ext-data", '
+        'mac="6R4rV5iE+NPoym+WwjeHzjAGXUtLNIxmo1vpMofpLAE="'
+    )
+
+
+def test_header_with_payload_hash_matches_reference():
+    header = hawk.authorization_header(
+        "POST", URL, PARAMS, "text/plain", b"Thank you for flying Hawk", ts="1353832234", nonce="j4h3g2"
+    )
+    assert 'hash="Yi9LfIIFRtBEPt74PVmbTF/xVAwPn7ub15ePICfgnuY="' in header
+    assert 'mac="aSe1DERmZuRl3pI36/9BdZmnErTw3sNzOOAUlfeKjVw="' in header
+
+
+def test_file_payload_is_hashed_and_rewound():
+    body = io.BytesIO(b"Thank you for flying Hawk")
+    assert hawk.payload_hash("sha256", "text/plain; charset=utf-8", body) == (
+        "Yi9LfIIFRtBEPt74PVmbTF/xVAwPn7ub15ePICfgnuY="
+    )
+    assert body.tell() == 0
+
+
+def test_app_and_dlg_are_signed_and_sent():
+    header = hawk.authorization_header(
+        "GET", URL, {**PARAMS, "app": "app-1", "dlg": "dlg-1"}, ts="1353832234", nonce="j4h3g2"
+    )
+    assert header.endswith('app="app-1", dlg="dlg-1"')
+    plain = hawk.authorization_header("GET", URL, PARAMS, ts="1353832234", nonce="j4h3g2")
+    assert header.split('mac="')[1][:44] != plain.split('mac="')[1][:44]
@@ -0,0 +1,70 @@
+import base64
+import hashlib
+import hmac
+import io
+import sys
+from pathlib import Path
+
+sys.path.append(str(Path(__file__).resolve().parents[1]))
+
+from app.core import hmac_signing
+
+
+def test_query_template_matches_binance_example():
+    # From Binance's SIGNED endpoint documentation
+    url = (
+        "https://api.binance.com/api/v3/order?symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC"
+        "&quantity=1&price=0.1&recvWindow=5000&timestamp=1499827319559"
+    )
+    headers = hmac_signing.sign("POST", url, {}, {
+        "secret": "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j",
+        "template": "{query}",
+        "header": "X-MBX-SIGNATURE",
+    })
+    assert headers["X-MBX-SIGNATURE"] == "c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71"
+
+
+def test_default_template_and_timestamp_header():
+    headers = hmac_signing.sign(
+        "post", "https://api.example.com/v1/pay?x=1", {"Content-Type": "application/json"},
+        {"secret": "s3cret", "timestamp_header": "X-Timestamp"}, b'{"a":1}', now=1700000000.5,
+    )
+    assert headers["X-Timestamp"] == "1700000000"
+    message = hmac_signing.string_to_sign(
+        hmac_signing.DEFAULT_TEMPLATE, "post", "https://api.example.com/v1/pay?x=1", {}, b'{"a":1}',
+        "1700000000", "1700000000500", "n",
+    )
+    assert message.split("\n")[:3] == ["POST", "/v1/pay?x=1", "1700000000"]
+    assert headers["X-Signature"] == hmac.new(b"s3cret", message.encode(), hashlib.sha256).hexdigest()
+
+
+def test_headers_prefix_and_base64_secret():
+    headers = hmac_signing.sign(
+        "GET", "https://h/p", {"X-Api-Key": "k1"},
+        {
+            "secret": "c2VjcmV0",
+            "secret_encoding": "base64",
+            "template": "{header:x-api-key}|{nonce}",
+            "encoding": "base64",
+            "header": "Authorization",
+            "prefix": "HMAC",
+            "nonce_header": "X-Nonce",
+        },
+        nonce="abc",
+    )
+    assert headers["X-Nonce"] == "abc"
+    expected = base64.b64encode(hmac.new(b"secret", b"k1|abc", hashlib.sha256).digest()).decode()
+    assert headers["Authorization"] == f"HMAC {expected}"
+
+
+def test_file_body_digest_rewinds_and_unknown_placeholder_fails():
+    body = io.BytesIO(b"payload")
+    message = hmac_signing.string_to_sign("{body_md5}", "PUT", "https://h/", {}, body, "0", "0", "n")
+    assert message == "321c3cf486ed509164edec1e1981fec8"
+    assert body.tell() == 0
+    try:
+        hmac_signing.string_to_sign("{nope}", "GET", "https://h/", {}, None, "0", "0", "n")
+    except hmac_signing.HmacSigningError:
+        pass
+    else:
+        raise AssertionError("expected HmacSigningError")
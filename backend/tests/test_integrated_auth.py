@@ -0,0 +1,55 @@
+import struct
+import sys
+from pathlib import Path
+
+sys.path.append(str(Path(__file__).resolve().parents[1]))
+
+from app.core import integrated_auth as ia
+
+# MS-NLMP §4.2.1 and §4.2.4 example values
+USER, PASSWORD, DOMAIN = "User", "Password", "Domain"
+SERVER_CHALLENGE = bytes.fromhex("0123456789abcdef")
+CLIENT_CHALLENGE = b"\xaa" * 8
+TARGET_INFO = (
+    struct.pack("<HH", 2, 12) + "Domain".encode("utf-16-le")
+    + struct.pack("<HH", 1, 12) + "Server".encode("utf-16-le")
+    + b"\x00" * 4
+)
+
+
+def test_md4_and_nt_hash():
+    assert ia.md4(b"").hex() == "31d6cfe0d16ae931b73c59d7e0c089c0"
+    assert ia.md4(b"abc").hex() == "a448017aaf21d8525fc10ae87aa6729d"
+    assert ia.nt_hash(PASSWORD).hex() == "a4f49c406510bdcab6824ee7c30fd852"
+
+
+def test_ntlmv2_responses_match_spec():
+    assert ia.ntowf_v2(USER, PASSWORD, DOMAIN).hex() == "0c868a403bfd7a93a3001ef22ef02e3f"
+    lm, nt = ia.ntlmv2_responses(
+        USER, PASSWORD, DOMAIN, SERVER_CHALLENGE, TARGET_INFO, CLIENT_CHALLENGE, b"\x00" * 8
+    )
+    assert lm.hex() == "86c35097ac9cec102554764a57cccc19" + "aa" * 8
+    assert nt[:16].hex() == "68cd0ab851e51c96aabc927bebef6a1c"
+
+
+def test_authenticate_message_carries_user_and_domain():
+    challenge = (
+        ia.NTLM_SIGNATURE + struct.pack("<I", 2) + struct.pack("<HHI", 0, 0, 48)
+        + struct.pack("<I", ia.NEGOTIATE_FLAGS | ia.NEGOTIATE_TARGET_INFO) + SERVER_CHALLENGE
+        + b"\x00" * 8 + struct.pack("<HHI", len(TARGET_INFO), len(TARGET_INFO), 48) + TARGET_INFO
+    )
+    assert ia.parse_challenge(challenge) == (
+        SERVER_CHALLENGE, ia.NEGOTIATE_FLAGS | ia.NEGOTIATE_TARGET_INFO, TARGET_INFO
+    )
+    message = ia.authenticate_message("CORP\\alice", "pw", "", challenge)
+    assert message.startswith(ia.NTLM_SIGNATURE + struct.pack("<I", 3))
+    length, _, offset = struct.unpack("<HHI", message[28:36])
+    assert message[offset:offset + length].decode("utf-16-le") == "CORP"
+    length, _, offset = struct.unpack("<HHI", message[36:44])
+    assert message[offset:offset + length].decode("utf-16-le") == "alice"
+
+
+def test_split_user_forms():
+    assert ia.split_user("CORP\\alice") == ("alice", "CORP")
+    assert ia.split_user("alice@corp.example") == ("alice", "corp.example")
+    assert ia.split_user("CORP\\alice", "OTHER") == ("CORP\\alice", "OTHER")
@@ -0,0 +1,73 @@
+import base64
+import sys
+from pathlib import Path
+
+sys.path.append(str(Path(__file__).resolve().parents[1]))
+
+from cryptography.hazmat.primitives import hashes, serialization
+from cryptography.hazmat.primitives.asymmetric import padding, rsa
+
+from app.core import oauth1
+
+
+def _header_params(header: str) -> dict:
+    assert header.startswith("OAuth ")
+    params = {}
+    for part in header[len("OAuth "):].split(", "):
+        key, value = part.split("=", 1)
+        params[key] = value.strip('"')
+    return params
+
+
+def test_hmac_sha1_matches_rfc5849_example():
+    # RFC 5849 §1.2, the photo-sharing resource request
+    header = oauth1.authorization_header(
+        "GET",
+        "http://photos.example.net/photos?file=vacation.jpg&size=original",
+        {
+            "consumer_key": "dpf43f3p2l4k3l03",
+            "consumer_secret": "kd94hf93k423kf44",
+            "token": "nnch734d00sl2jdk",
+            "token_secret": "pfkkdhi9sl3r4s00",
+            "signature_method": "HMAC-SHA1",
+            "realm": "Photos",
+        },
+        nonce="kllo9940pd9333jh",
+        timestamp="1191242096",
+    )
+    params = _header_params(header)
+    assert params["realm"] == "Photos"
+    assert params["oauth_signature"] == "tR3%2BTy81lMeYAr%2FFid0kMTYa%2FWM%3D"
+
+
+def test_base_string_includes_form_fields_and_normalizes_url():
+    base = oauth1.base_string(
+        "post",
+        "HTTPS://Example.com:443/a%20b?x=1",
+        [("x", "1"), ("z", "a&b"), ("oauth_nonce", "n")],
+    )
+    assert base == "POST&https%3A%2F%2Fexample.com%2Fa%2520b&oauth_nonce%3Dn%26x%3D1%26z%3Da%2526b"
+
+
+def test_rsa_sha256_signature_verifies_with_public_key():
+    key = rsa.generate_private_key(public_exponent=65537, key_size=2048)
+    pem = key.private_bytes(
+        serialization.Encoding.PEM,
+        serialization.PrivateFormat.PKCS8,
+        serialization.NoEncryption(),
+    ).decode()
+    base = oauth1.base_string("GET", "https://api.example.com/r", [("oauth_consumer_key", "ck")])
+    signature = oauth1.signature("RSA-SHA256", base, "", "", pem)
+    key.public_key().verify(
+        base64.b64decode(signature), base.encode(), padding.PKCS1v15(), hashes.SHA256()
+    )
+
+
+def test_plaintext_and_unknown_methods():
+    assert oauth1.signature("PLAINTEXT", "ignored", "c&s", "t") == "c%26s&t"
+    try:
+        oauth1.authorization_header("GET", "https://x", {"consumer_key": "k", "signature_method": "MD5"})
+    except oauth1.OAuth1Error:
+        pass
+    else:
+        raise AssertionError("expected OAuth1Error")
@@ -0,0 +1,35 @@
+import sys
+from pathlib import Path
+
+import pytest
+
+sys.path.append(str(Path(__file__).resolve().parents[1]))
+
+from app.core.storage import StorageEngine, ReadOnlyWorkspaceError
+
+
+def test_read_only_rejects_writes(tmp_path, monkeypatch):
+    StorageEngine(str(tmp_path))
+    monkeypatch.setenv("LITEFETCH_READ_ONLY", "1")
+    engine = StorageEngine(str(tmp_path))
+    collection = engine.load_collection("default")
+    with pytest.raises(ReadOnlyWorkspaceError):
+        engine.save_collection("default", collection)
+    with pytest.raises(ReadOnlyWorkspaceError):
+        engine.delete_collection("default")
+    assert (tmp_path / "collections" / "default").exists()
+
+
+def test_read_only_skips_runtime_artifacts(tmp_path, monkeypatch):
+    StorageEngine(str(tmp_path))
+    monkeypatch.setenv("LITEFETCH_READ_ONLY", "1")
+    engine = StorageEngine(str(tmp_path))
+    engine.save_last_results("default", {"req": {"status_code": 200}})
+    assert engine.load_last_results("default") == {}
+
+
+def test_read_only_skips_bootstrap(tmp_path, monkeypatch):
+    monkeypatch.setenv("LITEFETCH_READ_ONLY", "1")
+    StorageEngine(str(tmp_path))
+    assert not (tmp_path / ".gitignore").exists()
+    assert not any((tmp_path / "collections").iterdir())
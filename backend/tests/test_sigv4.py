@@ -0,0 +1,65 @@
+import datetime
+import io
+import sys
+from pathlib import Path
+
+sys.path.append(str(Path(__file__).resolve().parents[1]))
+
+from app.core import sigv4
+
+# Credentials and time of the AWS SigV4 test suite
+SUITE_PARAMS = {
+    "access_key": "AKIDEXAMPLE",
+    "secret_key": "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY",
+    "region": "us-east-1",
+    "service": "service",
+}
+SUITE_TIME = datetime.datetime(2015, 8, 30, 12, 36, tzinfo=datetime.timezone.utc)
+
+
+def test_get_vanilla_matches_aws_test_suite():
+    headers = sigv4.sign("GET", "https://example.amazonaws.com/", {}, SUITE_PARAMS, sigv4.EMPTY_SHA256, SUITE_TIME)
+    assert headers["X-Amz-Date"] == "20150830T123600Z"
+    assert headers["Authorization"] == (
+        "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, "
+        "SignedHeaders=host;x-amz-date, "
+        "Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+    )
+
+
+def test_s3_adds_content_hash_and_session_token():
+    params = {"access_key": "AK", "secret_key": "SK", "session_token": "tok"}
+    headers = sigv4.sign(
+        "PUT", "https://bucket.s3.eu-west-1.amazonaws.com/key", {"Authorization": "old"},
+        params, sigv4.payload_hash(b"hi"), SUITE_TIME,
+    )
+    assert headers["X-Amz-Content-Sha256"] == sigv4.payload_hash("hi")
+    assert headers["X-Amz-Security-Token"] == "tok"
+    assert "/20150830/eu-west-1/s3/aws4_request" in headers["Authorization"]
+    assert "SignedHeaders=host;x-amz-content-sha256;x-amz-date;x-amz-security-token" in headers["Authorization"]
+
+
+def test_derive_scope_from_host():
+    assert sigv4.derive_scope("dynamodb.us-west-2.amazonaws.com") == ("us-west-2", "dynamodb")
+    assert sigv4.derive_scope("abc.execute-api.eu-central-1.amazonaws.com") == ("eu-central-1", "execute-api")
+    assert sigv4.derive_scope("s3.amazonaws.com") == ("us-east-1", "s3")
+    assert sigv4.derive_scope("localhost") == ("", "")
+
+
+def test_profile_credentials_are_read_from_shared_file(tmp_path, monkeypatch):
+    creds = tmp_path / "credentials"
+    creds.write_text("[dev]\naws_access_key_id = AKDEV\naws_secret_access_key = SKDEV\n")
+    config = tmp_path / "config"
+    config.write_text("[profile dev]\nregion = ap-south-1\n")
+    monkeypatch.setenv("AWS_SHARED_CREDENTIALS_FILE", str(creds))
+    monkeypatch.setenv("AWS_CONFIG_FILE", str(config))
+
+    resolved = sigv4.resolve_credentials({"profile": "dev"})
+    assert resolved["access_key"] == "AKDEV"
+    assert resolved["region"] == "ap-south-1"
+
+
+def test_file_payload_hash_rewinds():
+    body = io.BytesIO(b"x" * 3_000_000)
+    assert sigv4.payload_hash(body) == sigv4.payload_hash(b"x" * 3_000_000)
+    assert body.tell() == 0
@@ -0,0 +1,80 @@
+//! Starting the same `litefetch-backend` sidecar the desktop app bundles, on the workspace
+//! the run should use.
+
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::process::{Child, Command};
+
+use litefetch_core::backend;
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+const STARTUP_POLL: Duration = Duration::from_millis(200);
+
+/// A backend this process started; it is stopped when dropped.
+pub struct Backend {
+    pub base_url: String,
+    _child: Child,
+}
+
+/// The sidecar next to this binary, as the desktop bundle installs it, or on `PATH`.
+fn locate() -> PathBuf {
+    let name = format!("litefetch-backend{}", std::env::consts::EXE_SUFFIX);
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&name)))
+        .filter(|candidate| candidate.is_file())
+        .unwrap_or_else(|| PathBuf::from(name))
+}
+
+impl Backend {
+    /// Starts the backend (`program`, or the located sidecar) on `workspace` and waits until
+    /// it answers. Its output goes to this process's stderr when `verbose`.
+    pub async fn start(
+        program: Option<&Path>,
+        workspace: &Path,
+        verbose: bool,
+    ) -> Result<Self, String> {
+        let program = program.map(Path::to_path_buf).unwrap_or_else(locate);
+        let port = backend::reserve_port()?;
+        let output = || match verbose {
+            true => Stdio::inherit(),
+            false => Stdio::null(),
+        };
+        let mut child = Command::new(&program)
+            .env("PORT", port.to_string())
+            .env("LITEFETCH_WORKSPACE", workspace)
+            .args(["--host", "127.0.0.1", "--port", &port.to_string(), "--dir"])
+            .arg(workspace)
+            .stdin(Stdio::null())
+            .stdout(output())
+            .stderr(output())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| format!("failed to start backend {}: {e}", program.display()))?;
+
+        let base_url = format!("http://127.0.0.1:{port}/api");
+        let started = Instant::now();
+        loop {
+            match backend::get(&base_url, "/collections").await {
+                Ok(_) => {
+                    return Ok(Self {
+                        base_url,
+                        _child: child,
+                    })
+                }
+                Err(e) if e == "workspace locked" => {
+                    return Err("workspace is locked; unlock it in the desktop app".to_string())
+                }
+                Err(_) => {}
+            }
+            if let Ok(Some(status)) = child.try_wait() {
+                return Err(format!("backend exited during startup: {status}"));
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                return Err("backend did not start in time".to_string());
+            }
+            tokio::time::sleep(STARTUP_POLL).await;
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! `litefetch-cli`: runs a collection or a workflow from the terminal against the same
+//! workspace the desktop app uses, for CI and scripted checks. The exit code is 0 when
+//! everything passed, 1 when a test or assertion failed and 2 when a request couldn't be
+//! sent or the run couldn't start.
+
+mod backend;
+mod output;
+mod send;
+
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+
+use litefetch_core::json::{array_of, str_of};
+use litefetch_core::report::ReportFormat;
+use litefetch_core::runner::{Plan, RunOptions};
+use litefetch_core::variables::Layers;
+use litefetch_core::workspace;
+use litefetch_core::{portable, profile};
+
+use crate::backend::Backend;
+use crate::output::{Format, Printer};
+use crate::send::Direct;
+
+/// The desktop app's bundle identifier; its app data directory is named after it.
+const APP_IDENTIFIER: &str = "com.litefetch.client";
+
+const USAGE: &str = "\
+Usage: litefetch-cli <command> [options]
+
+Commands:
+  list                             List the workspace's collections
+  run <collection>                 Run the requests of a collection (by id or name)
+  workflow <collection> <id>       Run a workflow of a collection (by id or name)
+
+Options:
+  -e, --env <name>                 Environment to use (default: the active one)
+  -w, --workspace <dir>            Workspace folder (default: the desktop app's)
+      --profile <name>             Desktop app profile whose workspace and keychain
+                                   entries to use (default: LITEFETCH_PROFILE)
+      --portable                   Use the app data beside the executable, as the
+                                   desktop app's portable mode does
+      --backend <path>             litefetch-backend binary to start
+      --request <id>               Run only this request; repeat for several (run)
+      --data <file>                CSV or JSON rows, one iteration each (run)
+      --concurrency <n>            Requests in flight at once (run)
+      --delay <ms>                 Wait before each request (run)
+      --bail                       Stop after the first failure or error (run)
+      --snapshots                  Check responses against saved snapshots (run)
+      --insecure                   Don't verify TLS certificates
+      --format <text|json|jsonl|tap>
+                                   Output: lines, a JSON summary, JSON lines as
+                                   results arrive, or TAP (default: text)
+      --json                       Same as --format json
+      --reporter <junit|html>      Also write a report, to --out
+      --out <path>                 Report file
+  -v, --verbose                    Show backend output
+  -h, --help                       Show this help
+
+Exit codes: 0 all passed, 1 a test or assertion failed, 2 a request errored or the
+run couldn't start.
+
+Pre-request and test scripts and plugins run only in the desktop app.";
+
+#[derive(Default)]
+struct Args {
+    command: String,
+    positional: Vec<String>,
+    env: Option<String>,
+    workspace: Option<String>,
+    profile: Option<String>,
+    portable: bool,
+    backend: Option<String>,
+    requests: Vec<String>,
+    data: Option<String>,
+    concurrency: Option<usize>,
+    delay: Option<u64>,
+    bail: bool,
+    snapshots: bool,
+    insecure: bool,
+    format: Option<Format>,
+    reporter: Option<ReportFormat>,
+    out: Option<String>,
+    verbose: bool,
+}
+
+fn parse_args(mut raw: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut args = Args::default();
+    while let Some(arg) = raw.next() {
+        let mut value = |name: &str| raw.next().ok_or_else(|| format!("{name} needs a value"));
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-e" | "--env" => args.env = Some(value(&arg)?),
+            "-w" | "--workspace" => args.workspace = Some(value(&arg)?),
+            "--profile" => args.profile = Some(value(&arg)?),
+            "--portable" => args.portable = true,
+            "--backend" => args.backend = Some(value(&arg)?),
+            "--request" => args.requests.push(value(&arg)?),
+            "--data" => args.data = Some(value(&arg)?),
+            "--concurrency" => {
+                args.concurrency = Some(
+                    value(&arg)?
+                        .parse()
+                        .map_err(|_| "--concurrency must be a number".to_string())?,
+                )
+            }
+            "--delay" => {
+                args.delay = Some(
+                    value(&arg)?
+                        .parse()
+                        .map_err(|_| "--delay must be a number of milliseconds".to_string())?,
+                )
+            }
+            "--bail" => args.bail = true,
+            "--snapshots" => args.snapshots = true,
+            "--insecure" => args.insecure = true,
+            "--format" => args.format = Some(Format::parse(&value(&arg)?)?),
+            "--reporter" => {
+                args.reporter = Some(match value(&arg)?.as_str() {
+                    "junit" => ReportFormat::Junit,
+                    "html" => ReportFormat::Html,
+                    other => return Err(format!("unknown reporter: {other}")),
+                })
+            }
+            "--out" => args.out = Some(value(&arg)?),
+            "--json" => args.format = Some(Format::Json),
+            "-v" | "--verbose" => args.verbose = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown option: {flag}")),
+            _ if args.command.is_empty() => args.command = arg,
+            _ => args.positional.push(arg),
+        }
+    }
+    if args.command.is_empty() {
+        return Ok(None);
+    }
+    if args.reporter.is_some() != args.out.is_some() {
+        return Err("--reporter and --out go together".to_string());
+    }
+    Ok(Some(args))
+}
+
+/// The desktop app's data directory, where it records the workspace and global variables.
+fn app_data_root() -> Result<PathBuf, String> {
+    let root = profile::data_root(&portable::base(|| {
+        dirs::data_dir()
+            .map(|dir| dir.join(APP_IDENTIFIER))
+            .ok_or("unable to resolve app data dir".to_string())
+    })?);
+    std::fs::create_dir_all(&root).map_err(|e| format!("workspace init failed: {e}"))?;
+    Ok(root)
+}
+
+/// A collection's id from its id or name, as listed by the backend.
+fn pick_collection(collections: &Value, wanted: &str) -> Result<String, String> {
+    let all = collections
+        .as_array()
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    if let Some(found) = all.iter().find(|c| str_of(c, "id") == wanted) {
+        return Ok(str_of(found, "id").to_string());
+    }
+    let named: Vec<&Value> = all.iter().filter(|c| str_of(c, "name") == wanted).collect();
+    match named.as_slice() {
+        [one] => Ok(str_of(one, "id").to_string()),
+        [] => Err(format!("unknown collection: {wanted}")),
+        _ => Err(format!("several collections are named {wanted}; use an id")),
+    }
+}
+
+/// Runs the command and returns the exit code.
+async fn run(args: Args) -> Result<u8, String> {
+    profile::set(profile::resolve(args.profile.as_deref())?);
+    portable::set(portable::detect(args.portable));
+    let app_root = app_data_root()?;
+    let workspace = match &args.workspace {
+        Some(dir) => workspace::normalize_path(dir),
+        None => workspace::resolve(&app_root)?,
+    };
+    let backend = Backend::start(
+        args.backend.as_deref().map(std::path::Path::new),
+        &workspace,
+        args.verbose,
+    )
+    .await?;
+    let base_url = backend.base_url.clone();
+    let collections = litefetch_core::backend::get(&base_url, "/collections").await?;
+
+    if args.command == "list" {
+        for collection in collections.as_array().into_iter().flatten() {
+            println!(
+                "{}  {}",
+                str_of(collection, "id"),
+                str_of(collection, "name")
+            );
+        }
+        return Ok(0);
+    }
+
+    let wanted = args
+        .positional
+        .first()
+        .ok_or_else(|| format!("{} needs a collection", args.command))?;
+    let collection_id = pick_collection(&collections, wanted)?;
+    let collection = litefetch_core::backend::get(
+        &base_url,
+        &format!("/collections/{collection_id}/collection"),
+    )
+    .await?;
+    let environment = litefetch_core::backend::get(
+        &base_url,
+        &format!("/collections/{collection_id}/environment"),
+    )
+    .await?;
+    let env_name = args
+        .env
+        .clone()
+        .unwrap_or_else(|| str_of(&environment, "active_env").to_string());
+    if !env_name.is_empty()
+        && environment
+            .pointer("/envs")
+            .and_then(|e| e.get(&env_name))
+            .is_none()
+    {
+        return Err(format!("unknown environment: {env_name}"));
+    }
+    let sender = Direct {
+        base_url,
+        layers: Layers {
+            global: app_root,
+            workspace,
+        },
+        collection_id: collection_id.clone(),
+        collection: collection.clone(),
+        environment,
+        env_name,
+        insecure: args.insecure,
+    };
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let mut printer = Printer::new(args.format.unwrap_or(Format::Text));
+
+    let (summary, code) = match args.command.as_str() {
+        "run" => {
+            let options = RunOptions {
+                request_ids: (!args.requests.is_empty()).then(|| args.requests.clone()),
+                environment_id: None,
+                concurrency: args.concurrency,
+                delay_ms: args.delay,
+                stop_on_failure: args.bail,
+                data_file: args
+                    .data
+                    .as_deref()
+                    .map(|data| {
+                        litefetch_core::fixtures::resolve_reference(&sender.layers.workspace, data)
+                    })
+                    .transpose()?,
+                spec: None,
+                verify_snapshots: args.snapshots,
+            };
+            let mut plan = Plan::new(&collection, &options)?;
+            if options.verify_snapshots {
+                plan = plan.with_snapshots(litefetch_core::snapshot::load_all(
+                    &sender.layers.workspace,
+                    &collection_id,
+                ));
+            }
+            let cancel = AtomicBool::new(false);
+            let summary = litefetch_core::runner::execute(
+                run_id,
+                collection_id,
+                &plan,
+                &options,
+                &sender,
+                &cancel,
+                |_, result| printer.request(result),
+            )
+            .await;
+            let text = format!(
+                "{} passed, {} failed, {} errors, {} skipped in {:.2} s",
+                summary.passed,
+                summary.failed,
+                summary.errors,
+                summary.skipped,
+                summary.duration_ms as f64 / 1000.0
+            );
+            let code = match (summary.errors, summary.failed) {
+                (0, 0) => 0,
+                (0, _) => 1,
+                _ => 2,
+            };
+            let value = serde_json::to_value(&summary)
+                .map_err(|e| format!("summary serialize failed: {e}"))?;
+            printer.finish(&value, &text, summary.skipped);
+            (value, code)
+        }
+        "workflow" => {
+            let workflow_id = args
+                .positional
+                .get(1)
+                .ok_or("workflow needs a workflow id")?;
+            let workflow_id = array_of(&collection, "workflows")
+                .iter()
+                .find(|w| str_of(w, "id") == workflow_id || str_of(w, "name") == workflow_id)
+                .map(|w| str_of(w, "id").to_string())
+                .ok_or_else(|| format!("unknown workflow: {workflow_id}"))?;
+            let run = litefetch_core::workflow::run(
+                run_id,
+                &collection,
+                &workflow_id,
+                &sender,
+                |_, step| printer.step(step),
+            )
+            .await?;
+            let mut text = format!(
+                "workflow {} in {:.2} s",
+                if run.passed { "passed" } else { "failed" },
+                run.duration_ms as f64 / 1000.0
+            );
+            if let Some(error) = &run.error {
+                text = format!("{text}: {error}");
+            }
+            let errored = run.error.is_some() || run.steps.iter().any(|s| s.status == "error");
+            let code = match (errored, run.passed) {
+                (true, _) => 2,
+                (false, true) => 0,
+                (false, false) => 1,
+            };
+            let value =
+                serde_json::to_value(&run).map_err(|e| format!("summary serialize failed: {e}"))?;
+            printer.finish(&value, &text, 0);
+            (value, code)
+        }
+        other => return Err(format!("unknown command: {other}")),
+    };
+    if let (Some(format), Some(out)) = (args.reporter, &args.out) {
+        let title = format!("LiteFetch: {}", str_of(&collection, "name"));
+        let content = litefetch_core::report::render(&summary, format, &title)?;
+        std::fs::write(workspace::normalize_path(out), content)
+            .map_err(|e| format!("report write failed: {e}"))?;
+    }
+    Ok(code)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("litefetch-cli: {e}\n\n{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(args).await {
+        Ok(code) => ExitCode::from(code),
+        Err(e) => {
+            eprintln!("litefetch-cli: {e}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! How results reach the terminal: readable lines, one JSON summary, JSON lines as results
+//! arrive, or TAP for CI systems that consume it.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use litefetch_core::runner::RequestRun;
+use litefetch_core::workflow::StepReport;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    JsonLines,
+    Tap,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "jsonl" => Ok(Self::JsonLines),
+            "tap" => Ok(Self::Tap),
+            other => Err(format!("unknown output format: {other}")),
+        }
+    }
+}
+
+/// Numbers TAP test points as results arrive; the plan goes last since stops and skips
+/// aren't known up front.
+pub struct Printer {
+    format: Format,
+    emitted: usize,
+}
+
+/// A TAP diagnostic value; JSON scalars are valid YAML.
+fn yaml(value: &impl Serialize) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+fn mark(status: &str) -> &'static str {
+    match status {
+        "passed" => "PASS",
+        "failed" => "FAIL",
+        _ => "ERR ",
+    }
+}
+
+fn code_of(code: Option<u64>) -> String {
+    code.map(|c| c.to_string())
+        .unwrap_or_else(|| "---".to_string())
+}
+
+impl Printer {
+    pub fn new(format: Format) -> Self {
+        if format == Format::Tap {
+            println!("TAP version 13");
+        }
+        Self { format, emitted: 0 }
+    }
+
+    fn line(&self, kind: &str, item: &impl Serialize) {
+        println!("{}", json!({ "type": kind, "data": item }));
+    }
+
+    /// A TAP test point, with a YAML block of `diagnostics` when it didn't pass.
+    fn point(&mut self, passed: bool, name: &str, diagnostics: &[(&str, String)]) {
+        self.emitted += 1;
+        let status = if passed { "ok" } else { "not ok" };
+        println!("{status} {} - {}", self.emitted, name.replace('#', "\\#"));
+        if !passed && !diagnostics.is_empty() {
+            println!("  ---");
+            for (key, value) in diagnostics {
+                println!("  {key}: {value}");
+            }
+            println!("  ...");
+        }
+    }
+
+    pub fn request(&mut self, result: &RequestRun) {
+        let mut path = result.folder.clone();
+        path.push(result.name.clone());
+        let name = path.join("/");
+        let iteration = result
+            .iteration
+            .map(|i| format!("[{}] ", i + 1))
+            .unwrap_or_default();
+        match self.format {
+            Format::Text => {
+                println!(
+                    "{}  {iteration}{name}  {}  {:.0} ms",
+                    mark(&result.status),
+                    code_of(result.status_code),
+                    result.duration_ms.unwrap_or(0.0)
+                );
+                if let Some(error) = &result.error {
+                    println!("      {error}");
+                }
+                for test in result.tests.iter().flat_map(|t| t.results.iter()) {
+                    if !test.passed {
+                        println!(
+                            "      test failed: {} {}",
+                            test.name,
+                            test.error.as_deref().unwrap_or_default()
+                        );
+                    }
+                }
+            }
+            Format::Json => {}
+            Format::JsonLines => self.line("request", result),
+            Format::Tap => {
+                let mut diagnostics = vec![
+                    ("status", yaml(&result.status)),
+                    ("status_code", yaml(&result.status_code)),
+                    ("duration_ms", yaml(&result.duration_ms)),
+                ];
+                if let Some(error) = &result.error {
+                    diagnostics.push(("message", yaml(error)));
+                }
+                let failed: Vec<&str> = result
+                    .tests
+                    .iter()
+                    .flat_map(|t| t.results.iter())
+                    .filter(|t| !t.passed)
+                    .map(|t| t.name.as_str())
+                    .collect();
+                if !failed.is_empty() {
+                    diagnostics.push(("failed_tests", yaml(&failed)));
+                }
+                self.point(
+                    result.status == "passed",
+                    &format!("{iteration}{name}"),
+                    &diagnostics,
+                );
+            }
+        }
+    }
+
+    pub fn step(&mut self, step: &StepReport) {
+        let name = step.name.as_deref().unwrap_or(&step.step_id);
+        let failed: Vec<&Option<String>> = step
+            .assertions
+            .iter()
+            .filter(|a| !a.passed)
+            .map(|a| &a.actual)
+            .collect();
+        match self.format {
+            Format::Text => {
+                println!(
+                    "{}  {name}  {}  {:.0} ms",
+                    mark(&step.status),
+                    code_of(step.status_code),
+                    step.duration_ms.unwrap_or(0.0)
+                );
+                if let Some(error) = &step.error {
+                    println!("      {error}");
+                }
+                for actual in failed {
+                    println!(
+                        "      assertion failed, got {}",
+                        actual.as_deref().unwrap_or("nothing")
+                    );
+                }
+            }
+            Format::Json => {}
+            Format::JsonLines => self.line("step", step),
+            Format::Tap => {
+                let mut diagnostics = vec![
+                    ("status", yaml(&step.status)),
+                    ("status_code", yaml(&step.status_code)),
+                    ("duration_ms", yaml(&step.duration_ms)),
+                ];
+                if let Some(error) = &step.error {
+                    diagnostics.push(("message", yaml(error)));
+                }
+                if !failed.is_empty() {
+                    diagnostics.push(("actual", yaml(&failed)));
+                }
+                self.point(step.status == "passed", name, &diagnostics);
+            }
+        }
+    }
+
+    /// Ends the output with `summary`; `text` is its one-line form.
+    pub fn finish(&mut self, summary: &Value, text: &str, skipped: usize) {
+        match self.format {
+            Format::Text => println!("\n{text}"),
+            Format::Json => println!(
+                "{}",
+                serde_json::to_string_pretty(summary).unwrap_or_default()
+            ),
+            Format::JsonLines => self.line("summary", summary),
+            Format::Tap => {
+                println!("1..{}", self.emitted);
+                if skipped > 0 {
+                    println!("# skipped {skipped}");
+                }
+                println!("# {text}");
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Sending from the terminal: the workspace's routing rules and variables are applied as in
+//! the desktop app, then the backend sends the request. Pre-request and test scripts and
+//! plugins run only in the desktop app.
+
+use serde_json::Value;
+
+use litefetch_core::backend;
+use litefetch_core::send::{resolve_request, Sender, Sent};
+use litefetch_core::variables::{Layers, Scope};
+
+pub struct Direct {
+    pub base_url: String,
+    pub layers: Layers,
+    pub collection_id: String,
+    pub collection: Value,
+    pub environment: Value,
+    pub env_name: String,
+    /// Sends every request with TLS verification off, whatever the request says.
+    pub insecure: bool,
+}
+
+impl Sender for Direct {
+    async fn send(&self, mut request: Value) -> Result<Sent, String> {
+        if !request.is_object() {
+            return Err("request must be an object".to_string());
+        }
+        let route = litefetch_core::routing::route_request(
+            &self.layers.workspace,
+            &self.env_name,
+            &mut request,
+        )?;
+        let scope = Scope::load(
+            &self.layers,
+            &self.collection_id,
+            &self.collection,
+            &self.environment,
+            &self.env_name,
+            Some(&request),
+        )?;
+        resolve_request(&mut request, &scope);
+        if let Some(route) = &route {
+            route.finish(&mut request);
+        }
+        litefetch_core::fixtures::resolve_request(&self.layers.workspace, &mut request)?;
+        if self.insecure {
+            request["verify_ssl"] = Value::Bool(false);
+        }
+        let result = backend::post(
+            &self.base_url,
+            &format!("/collections/{}/run", self.collection_id),
+            &request,
+        )
+        .await?;
+        Ok(Sent {
+            result,
+            tests: None,
+        })
+    }
+}
@@ -0,0 +1,77 @@
+//! Calls to the Python backend's HTTP API. Callers own the backend process and pass its API
+//! base URL (`http://127.0.0.1:<port>/api`).
+
+use serde_json::Value;
+use std::net::TcpListener;
+use std::ops::RangeInclusive;
+
+/// Request header naming where a workspace change came from, for the backend's audit log.
+pub const ORIGIN_HEADER: &str = "x-litefetch-origin";
+
+/// Revisions of the backend's HTTP API (`API_VERSION` in `backend/app/__init__.py`) this
+/// build can talk to.
+pub const SUPPORTED_API: RangeInclusive<u32> = 1..=1;
+
+/// A free local port for a backend about to start.
+pub fn reserve_port() -> Result<u16, String> {
+    let socket = TcpListener::bind("127.0.0.1:0").map_err(|e| format!("port bind failed: {e}"))?;
+    let port = socket
+        .local_addr()
+        .map_err(|e| format!("port discovery failed: {e}"))?
+        .port();
+    drop(socket);
+    Ok(port)
+}
+
+async fn read(response: reqwest::Response) -> Result<Value, String> {
+    if response.status().as_u16() == 423 {
+        return Err("workspace locked".to_string());
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "backend request failed: HTTP {}",
+            response.status()
+        ));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("backend response invalid: {e}"))
+}
+
+/// GETs a backend API path (e.g. `/collections/<id>/history`) and returns the JSON body.
+pub async fn get(base_url: &str, path: &str) -> Result<Value, String> {
+    let response = reqwest::get(format!("{base_url}{path}"))
+        .await
+        .map_err(|e| format!("backend request failed: {e}"))?;
+    read(response).await
+}
+
+/// POSTs JSON to a backend API path, with the same locked-workspace handling as `get`.
+pub async fn post(base_url: &str, path: &str, body: &Value) -> Result<Value, String> {
+    let response = reqwest::Client::new()
+        .post(format!("{base_url}{path}"))
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("backend request failed: {e}"))?;
+    read(response).await
+}
+
+/// Like `post`, recording `origin` (`import`, `sync`) as the source of the change in the
+/// workspace audit log instead of the UI.
+pub async fn post_as(
+    base_url: &str,
+    path: &str,
+    body: &Value,
+    origin: &str,
+) -> Result<Value, String> {
+    let response = reqwest::Client::new()
+        .post(format!("{base_url}{path}"))
+        .header(ORIGIN_HEADER, origin)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("backend request failed: {e}"))?;
+    read(response).await
+}
@@ -0,0 +1,244 @@
+//! Binary response bodies: hex and ASCII dumps, and naming what a body is from its first
+//! bytes when the `Content-Type` is missing or just `application/octet-stream`.
+
+use serde::Serialize;
+
+/// Bytes per dump line.
+pub const LINE_WIDTH: usize = 16;
+
+/// Leading bytes worth passing to `detect`; protobuf is only recognized in bodies no longer
+/// than this, since it has to be read to the end.
+pub const SNIFF_LEN: usize = 64 * 1024;
+
+#[derive(Serialize, Clone)]
+pub struct Detected {
+    pub mime: &'static str,
+    pub label: &'static str,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HexLine {
+    /// Offset of the line's first byte in the whole body.
+    pub offset: u64,
+    /// Space-separated byte pairs, e.g. `89 50 4e 47`.
+    pub hex: String,
+    /// Printable ASCII as is, everything else as `.`.
+    pub ascii: String,
+}
+
+/// Signatures as (offset, bytes, mime, label), checked in order.
+const MAGIC: &[(usize, &[u8], &str, &str)] = &[
+    (0, b"\x89PNG\r\n\x1a\n", "image/png", "PNG image"),
+    (0, b"\xff\xd8\xff", "image/jpeg", "JPEG image"),
+    (0, b"GIF87a", "image/gif", "GIF image"),
+    (0, b"GIF89a", "image/gif", "GIF image"),
+    (0, b"BM", "image/bmp", "BMP image"),
+    (0, b"\x00\x00\x01\x00", "image/x-icon", "ICO image"),
+    (0, b"II*\x00", "image/tiff", "TIFF image"),
+    (0, b"MM\x00*", "image/tiff", "TIFF image"),
+    (0, b"%PDF-", "application/pdf", "PDF document"),
+    (0, b"\x1f\x8b", "application/gzip", "gzip data"),
+    (0, b"BZh", "application/x-bzip2", "bzip2 data"),
+    (0, b"\xfd7zXZ\x00", "application/x-xz", "xz data"),
+    (0, b"\x28\xb5\x2f\xfd", "application/zstd", "Zstandard data"),
+    (0, b"PK\x03\x04", "application/zip", "ZIP archive"),
+    (0, b"PK\x05\x06", "application/zip", "ZIP archive (empty)"),
+    (
+        0,
+        b"7z\xbc\xaf\x27\x1c",
+        "application/x-7z-compressed",
+        "7-Zip archive",
+    ),
+    (0, b"Rar!\x1a\x07", "application/vnd.rar", "RAR archive"),
+    (257, b"ustar", "application/x-tar", "tar archive"),
+    (0, b"\x00asm", "application/wasm", "WebAssembly module"),
+    (0, b"\x7fELF", "application/x-elf", "ELF executable"),
+    (0, b"MZ", "application/x-msdownload", "Windows executable"),
+    (
+        0,
+        b"SQLite format 3\x00",
+        "application/vnd.sqlite3",
+        "SQLite database",
+    ),
+    (0, b"PAR1", "application/vnd.apache.parquet", "Parquet file"),
+    (0, b"Obj\x01", "application/avro", "Avro container"),
+    (
+        0,
+        b"ARROW1",
+        "application/vnd.apache.arrow.file",
+        "Arrow file",
+    ),
+    (0, b"wOFF", "font/woff", "WOFF font"),
+    (0, b"wOF2", "font/woff2", "WOFF2 font"),
+    (0, b"\x00\x01\x00\x00", "font/ttf", "TrueType font"),
+    (0, b"OTTO", "font/otf", "OpenType font"),
+    (0, b"OggS", "audio/ogg", "Ogg media"),
+    (0, b"fLaC", "audio/flac", "FLAC audio"),
+    (0, b"ID3", "audio/mpeg", "MP3 audio"),
+    (0, b"\x1a\x45\xdf\xa3", "video/webm", "WebM/Matroska video"),
+    (4, b"ftyp", "video/mp4", "MP4/QuickTime media"),
+    (0, b"\xef\xbb\xbf", "text/plain", "UTF-8 text (with BOM)"),
+];
+
+fn riff(bytes: &[u8]) -> Option<Detected> {
+    if bytes.len() < 12 || &bytes[..4] != b"RIFF" {
+        return None;
+    }
+    Some(match &bytes[8..12] {
+        b"WEBP" => Detected {
+            mime: "image/webp",
+            label: "WebP image",
+        },
+        b"WAVE" => Detected {
+            mime: "audio/wav",
+            label: "WAV audio",
+        },
+        b"AVI " => Detected {
+            mime: "video/x-msvideo",
+            label: "AVI video",
+        },
+        _ => Detected {
+            mime: "application/octet-stream",
+            label: "RIFF container",
+        },
+    })
+}
+
+/// Reads a protobuf varint, returning it and the bytes it took.
+pub(crate) fn varint(bytes: &[u8]) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+/// Protobuf has no signature; this accepts bytes that read as a run of well-formed fields
+/// (valid tags and wire types, lengths inside the body) ending exactly at the end, which
+/// random or text data practically never does.
+fn looks_like_protobuf(bytes: &[u8]) -> bool {
+    let mut at = 0;
+    let mut fields = 0;
+    while at < bytes.len() {
+        let Some((tag, used)) = varint(&bytes[at..]) else {
+            return false;
+        };
+        at += used;
+        if tag >> 3 == 0 {
+            return false;
+        }
+        let size = match tag & 7 {
+            0 => match varint(&bytes[at..]) {
+                Some((_, used)) => used,
+                None => return false,
+            },
+            1 => 8,
+            2 => match varint(&bytes[at..]) {
+                Some((len, used)) => used.saturating_add(len as usize),
+                None => return false,
+            },
+            5 => 4,
+            _ => return false,
+        };
+        at = match at.checked_add(size) {
+            Some(end) if end <= bytes.len() => end,
+            _ => return false,
+        };
+        fields += 1;
+    }
+    fields > 0
+}
+
+/// Text: UTF-8 (possibly cut mid-character at the end of `head`) without control bytes.
+fn is_text(head: &[u8]) -> bool {
+    let valid = match std::str::from_utf8(head) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    valid
+        && !head
+            .iter()
+            .any(|&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c))
+}
+
+/// Names the format of a body from its leading bytes, `None` for text. `whole` says `head`
+/// is the entire body, which formats without a signature need.
+pub fn detect(head: &[u8], whole: bool) -> Option<Detected> {
+    let signature = MAGIC.iter().find(|(offset, magic, _, _)| {
+        head.len() >= offset + magic.len() && &head[*offset..offset + magic.len()] == *magic
+    });
+    if let Some((_, _, mime, label)) = signature {
+        return Some(Detected { mime, label });
+    }
+    if let Some(detected) = riff(head) {
+        return Some(detected);
+    }
+    if is_text(head) {
+        return None;
+    }
+    if whole && looks_like_protobuf(head) {
+        return Some(Detected {
+            mime: "application/x-protobuf",
+            label: "protobuf message (probably)",
+        });
+    }
+    Some(Detected {
+        mime: "application/octet-stream",
+        label: "binary data",
+    })
+}
+
+/// The usual media type for a file extension (without the dot), for bodies sent from files.
+pub fn mime_for_extension(extension: &str) -> Option<&'static str> {
+    Some(match extension.to_ascii_lowercase().as_str() {
+        "json" => "application/json",
+        "ndjson" | "jsonl" => "application/x-ndjson",
+        "xml" => "application/xml",
+        "yaml" | "yml" => "application/yaml",
+        "csv" => "text/csv",
+        "txt" | "log" => "text/plain",
+        "html" | "htm" => "text/html",
+        "js" => "text/javascript",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" | "tgz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "mp4" => "video/mp4",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "wasm" => "application/wasm",
+        "pb" | "bin" => "application/octet-stream",
+        _ => return None,
+    })
+}
+
+/// One dump line per `LINE_WIDTH` bytes of `bytes`, which start at `offset` in the body.
+pub fn hex_lines(bytes: &[u8], offset: u64) -> Vec<HexLine> {
+    bytes
+        .chunks(LINE_WIDTH)
+        .enumerate()
+        .map(|(i, chunk)| HexLine {
+            offset: offset + (i * LINE_WIDTH) as u64,
+            hex: chunk
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect::<Vec<_>>()
+                .join(" "),
+            ascii: chunk
+                .iter()
+                .map(|&b| match b.is_ascii_graphic() || b == b' ' {
+                    true => b as char,
+                    false => '.',
+                })
+                .collect(),
+        })
+        .collect()
+}
@@ -0,0 +1,340 @@
+//! Semantic comparison of two versions of a collection, e.g. at two git revisions: the
+//! requests added and removed, and for each request kept (matched by id) what changed in its
+//! method, URL, headers, query, body, auth and the rest, rather than a diff of the JSON text.
+//! Values the request flags secret are masked on both sides.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+
+use crate::diff::Change;
+use crate::json::{array_of, str_of, value_text};
+use crate::redact::MASK;
+
+/// Request fields compared key by key, with the marker that flags their secret keys.
+const KEYED_FIELDS: &[(&str, &str, Option<&str>)] = &[
+    ("headers", "headers", Some("secret_headers")),
+    ("auth_params", "auth", Some("secret_auth_params")),
+    ("variables", "variables", None),
+];
+/// Row lists compared by key, enabled rows only.
+const ROW_FIELDS: &[(&str, &str, &str)] = &[
+    ("query_params", "query", "secret_query_params"),
+    ("form_body", "form", "secret_form_fields"),
+];
+/// Compared separately, or bookkeeping that isn't part of the API.
+const SKIPPED_FIELDS: &[&str] = &[
+    "id",
+    "items",
+    "headers",
+    "auth_params",
+    "variables",
+    "query_params",
+    "form_body",
+    "examples",
+    "body",
+    "secret_headers",
+    "secret_query_params",
+    "secret_form_fields",
+    "secret_auth_params",
+    "secret_body",
+];
+
+#[derive(Serialize, Clone)]
+pub struct RequestRef {
+    pub id: String,
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    /// Folder names from the collection root.
+    pub folder: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct RequestChange {
+    #[serde(flatten)]
+    pub request: RequestRef,
+    /// Paths such as `url`, `headers.Authorization`, `query.limit` or `examples.Not found`.
+    pub changes: Vec<Change>,
+}
+
+#[derive(Serialize, Default)]
+pub struct CollectionDiff {
+    /// Collection-level fields: `name`, `variables.<key>`, `workflows` and so on.
+    pub collection: Vec<Change>,
+    pub added: Vec<RequestRef>,
+    pub removed: Vec<RequestRef>,
+    pub changed: Vec<RequestChange>,
+}
+
+impl CollectionDiff {
+    pub fn is_empty(&self) -> bool {
+        self.collection.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+fn requests<'a>(items: &'a [Value], folder: &[String], out: &mut Vec<(RequestRef, &'a Value)>) {
+    for item in items {
+        match item.get("items").and_then(Value::as_array) {
+            Some(children) => {
+                let mut path = folder.to_vec();
+                path.push(str_of(item, "name").to_string());
+                requests(children, &path, out);
+            }
+            None => out.push((
+                RequestRef {
+                    id: str_of(item, "id").to_string(),
+                    name: str_of(item, "name").to_string(),
+                    method: str_of(item, "method").to_string(),
+                    url: str_of(item, "url").to_string(),
+                    folder: folder.to_vec(),
+                },
+                item,
+            )),
+        }
+    }
+}
+
+fn change(path: String, before: Option<Value>, after: Option<Value>) -> Option<Change> {
+    let kind = match (&before, &after) {
+        (None, None) => return None,
+        (Some(a), Some(b)) if a == b => return None,
+        (None, Some(_)) => "added",
+        (Some(_), None) => "removed",
+        (Some(_), Some(_)) => "changed",
+    };
+    Some(Change {
+        path,
+        kind: kind.to_string(),
+        before,
+        after,
+    })
+}
+
+/// Matched without case, as header names are; masking too much beats showing a secret.
+fn secret(request: &Value, marker: &str, key: &str) -> bool {
+    request
+        .get(marker)
+        .and_then(Value::as_object)
+        .is_some_and(|m| {
+            m.iter()
+                .any(|(k, v)| k.eq_ignore_ascii_case(key) && v == &Value::Bool(true))
+        })
+}
+
+fn masked(value: Option<Value>) -> Option<Value> {
+    value.map(|_| Value::String(MASK.to_string()))
+}
+
+/// Null and empty values count as unset, so `{}` turning into a missing field isn't a change.
+fn present(value: Option<&Value>) -> Option<&Value> {
+    value.filter(|v| match v {
+        Value::Null => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(a) => !a.is_empty(),
+        Value::Object(o) => !o.is_empty(),
+        _ => true,
+    })
+}
+
+/// Compares two objects key by key under `prefix`, masking the keys `hidden` flags;
+/// `case_insensitive` for header names.
+fn keyed(
+    out: &mut Vec<Change>,
+    prefix: &str,
+    a: Option<&Map<String, Value>>,
+    b: Option<&Map<String, Value>>,
+    hidden: impl Fn(&str) -> bool,
+    case_insensitive: bool,
+) {
+    let empty = Map::new();
+    let (a, b) = (a.unwrap_or(&empty), b.unwrap_or(&empty));
+    let find = |map: &Map<String, Value>, key: &str| -> Option<(String, Value)> {
+        map.iter()
+            .find(|(k, _)| match case_insensitive {
+                true => k.eq_ignore_ascii_case(key),
+                false => k.as_str() == key,
+            })
+            .map(|(k, v)| (k.clone(), v.clone()))
+    };
+    let mut seen = BTreeSet::new();
+    for key in a.keys().chain(b.keys()) {
+        let folded = match case_insensitive {
+            true => key.to_ascii_lowercase(),
+            false => key.clone(),
+        };
+        if !seen.insert(folded) {
+            continue;
+        }
+        let old = find(a, key);
+        let new = find(b, key);
+        let name = new
+            .as_ref()
+            .or(old.as_ref())
+            .map(|(k, _)| k.clone())
+            .unwrap_or_default();
+        let Some(mut c) = change(
+            format!("{prefix}.{name}"),
+            old.map(|(_, v)| v),
+            new.map(|(_, v)| v),
+        ) else {
+            continue;
+        };
+        if hidden(&name) {
+            c.before = masked(c.before);
+            c.after = masked(c.after);
+        }
+        out.push(c);
+    }
+}
+
+/// `query_params` or `form_body` rows as a `key -> value` object, enabled rows only.
+fn rows(request: &Value, field: &str) -> Map<String, Value> {
+    array_of(request, field)
+        .iter()
+        .filter(|row| row.get("enabled").and_then(Value::as_bool) != Some(false))
+        .map(|row| {
+            let value = match str_of(row, "type") {
+                "file" | "binary" => Value::String(format!("<{}>", str_of(row, "file_name"))),
+                _ => row.get("value").cloned().unwrap_or(Value::Null),
+            };
+            (str_of(row, "key").to_string(), value)
+        })
+        .collect()
+}
+
+fn examples(request: &Value) -> Map<String, Value> {
+    array_of(request, "examples")
+        .iter()
+        .map(|example| {
+            let mut shown = example.clone();
+            if let Some(fields) = shown.as_object_mut() {
+                fields.remove("id");
+            }
+            (str_of(example, "name").to_string(), shown)
+        })
+        .collect()
+}
+
+fn request_changes(before: (&RequestRef, &Value), after: (&RequestRef, &Value)) -> Vec<Change> {
+    let (old_ref, a) = before;
+    let (new_ref, b) = after;
+    let mut out = Vec::new();
+    if old_ref.folder != new_ref.folder {
+        out.extend(change(
+            "folder".to_string(),
+            Some(Value::String(old_ref.folder.join("/"))),
+            Some(Value::String(new_ref.folder.join("/"))),
+        ));
+    }
+    let fields: BTreeSet<&String> = a
+        .as_object()
+        .into_iter()
+        .chain(b.as_object())
+        .flat_map(|o| o.keys())
+        .filter(|k| !SKIPPED_FIELDS.contains(&k.as_str()))
+        .collect();
+    for field in fields {
+        out.extend(change(
+            field.clone(),
+            present(a.get(field)).cloned(),
+            present(b.get(field)).cloned(),
+        ));
+    }
+    let body = |r: &Value| present(r.get("body")).map(|v| Value::String(value_text(Some(v))));
+    if let Some(mut c) = change("body".to_string(), body(a), body(b)) {
+        let hide = |r: &Value| r.get("secret_body").and_then(Value::as_bool) == Some(true);
+        if hide(a) || hide(b) {
+            c.before = masked(c.before);
+            c.after = masked(c.after);
+        }
+        out.push(c);
+    }
+    let flagged = |marker: Option<&'static str>| {
+        move |name: &str| marker.is_some_and(|m| secret(a, m, name) || secret(b, m, name))
+    };
+    for (field, prefix, marker) in KEYED_FIELDS {
+        let (x, y) = (a.get(*field), b.get(*field));
+        keyed(
+            &mut out,
+            prefix,
+            x.and_then(Value::as_object),
+            y.and_then(Value::as_object),
+            flagged(*marker),
+            *field == "headers",
+        );
+    }
+    for (field, prefix, marker) in ROW_FIELDS {
+        let (x, y) = (rows(a, field), rows(b, field));
+        keyed(
+            &mut out,
+            prefix,
+            Some(&x),
+            Some(&y),
+            flagged(Some(marker)),
+            false,
+        );
+    }
+    let (x, y) = (examples(a), examples(b));
+    keyed(
+        &mut out,
+        "examples",
+        Some(&x),
+        Some(&y),
+        flagged(None),
+        false,
+    );
+    out
+}
+
+/// What changed from `before` to `after`; either may be `Value::Null` for a collection that
+/// only exists on one side.
+pub fn diff(before: &Value, after: &Value) -> CollectionDiff {
+    let mut result = CollectionDiff::default();
+    let fields: BTreeSet<&String> = before
+        .as_object()
+        .into_iter()
+        .chain(after.as_object())
+        .flat_map(|o| o.keys())
+        .filter(|k| !matches!(k.as_str(), "id" | "items" | "variables"))
+        .collect();
+    for field in fields {
+        result.collection.extend(change(
+            field.clone(),
+            present(before.get(field)).cloned(),
+            present(after.get(field)).cloned(),
+        ));
+    }
+    keyed(
+        &mut result.collection,
+        "variables",
+        before.get("variables").and_then(Value::as_object),
+        after.get("variables").and_then(Value::as_object),
+        |_| false,
+        false,
+    );
+
+    let (mut old, mut new) = (Vec::new(), Vec::new());
+    requests(array_of(before, "items"), &[], &mut old);
+    requests(array_of(after, "items"), &[], &mut new);
+    for (old_ref, a) in &old {
+        match new.iter().find(|(r, _)| r.id == old_ref.id) {
+            Some((new_ref, b)) => {
+                let changes = request_changes((old_ref, a), (new_ref, b));
+                if !changes.is_empty() {
+                    result.changed.push(RequestChange {
+                        request: new_ref.clone(),
+                        changes,
+                    });
+                }
+            }
+            None => result.removed.push(old_ref.clone()),
+        }
+    }
+    new.retain(|(r, _)| !old.iter().any(|(o, _)| o.id == r.id));
+    result.added = new.into_iter().map(|(r, _)| r).collect();
+    result
+}
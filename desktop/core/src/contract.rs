@@ -0,0 +1,245 @@
+//! Contract tests: every operation of an OpenAPI spec is sent, through the saved request of
+//! the collection imported from it, to a live environment, and the response is checked
+//! against what the spec documents: status code, content type and body schema.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use crate::json::{array_of, str_of};
+use crate::schema::{self, Violation};
+use crate::send::Sender;
+
+const METHODS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+#[derive(Serialize, Clone)]
+pub struct OperationResult {
+    pub method: String,
+    /// The spec's path template, e.g. `/users/{id}`.
+    pub path: String,
+    pub operation_id: Option<String>,
+    /// The request that exercised it; `None` when the collection has none.
+    pub request_id: Option<String>,
+    pub name: Option<String>,
+    /// `passed`, `failed` (the response broke the contract), `error` (no response) or
+    /// `skipped`.
+    pub status: String,
+    pub status_code: Option<u64>,
+    pub duration_ms: Option<f64>,
+    pub content_type: Option<String>,
+    /// Whether the status code, the content type and the body each conform; `None` when
+    /// the check didn't run because an earlier one failed.
+    pub status_documented: Option<bool>,
+    pub content_type_documented: Option<bool>,
+    pub schema_valid: Option<bool>,
+    pub violations: Vec<Violation>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ContractReport {
+    pub run_id: String,
+    pub collection_id: String,
+    /// The spec's `info.title` and `info.version`.
+    pub title: String,
+    pub version: String,
+    pub started_ms: u64,
+    pub duration_ms: u64,
+    pub cancelled: bool,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    pub skipped: usize,
+    /// Share of the operations that were sent and conformed, in percent.
+    pub conformance_pct: f64,
+    pub operations: Vec<OperationResult>,
+}
+
+/// An operation of the spec and the request that covers it.
+struct Planned<'a> {
+    method: &'static str,
+    path: &'a str,
+    operation: &'a Value,
+    request: Option<&'a Value>,
+}
+
+fn requests(items: &[Value]) -> Vec<&Value> {
+    items
+        .iter()
+        .flat_map(|item| match item.get("items").and_then(Value::as_array) {
+            Some(children) => requests(children),
+            None => vec![item],
+        })
+        .collect()
+}
+
+/// The operations of a spec paired with the requests that cover them.
+pub struct Plan<'a> {
+    doc: &'a Value,
+    planned: Vec<Planned<'a>>,
+}
+
+impl<'a> Plan<'a> {
+    /// Pairs every operation of `doc` with the first request of `collection` whose method
+    /// and URL address it; only operations with one of `methods` when given.
+    pub fn new(doc: &'a Value, collection: &'a Value, methods: Option<&[String]>) -> Self {
+        let requests = requests(array_of(collection, "items"));
+        let mut planned = Vec::new();
+        for (path, item) in doc
+            .get("paths")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            for &method in METHODS {
+                let Some(operation) = item.get(method) else {
+                    continue;
+                };
+                if methods.is_some_and(|m| !m.iter().any(|m| m.eq_ignore_ascii_case(method))) {
+                    continue;
+                }
+                let request = requests.iter().copied().find(|request| {
+                    str_of(request, "method").eq_ignore_ascii_case(method)
+                        && schema::find_operation(doc, method, str_of(request, "url"))
+                            .is_some_and(|(template, _)| template == path)
+                });
+                planned.push(Planned {
+                    method,
+                    path,
+                    operation,
+                    request,
+                });
+            }
+        }
+        Self { doc, planned }
+    }
+
+    /// Operations to check, covered or not.
+    pub fn total(&self) -> usize {
+        self.planned.len()
+    }
+}
+
+async fn run_one<S: Sender>(sender: &S, doc: &Value, planned: &Planned<'_>) -> OperationResult {
+    let mut result = OperationResult {
+        method: planned.method.to_uppercase(),
+        path: planned.path.to_string(),
+        operation_id: Some(str_of(planned.operation, "operationId"))
+            .filter(|id| !id.is_empty())
+            .map(str::to_string),
+        request_id: None,
+        name: None,
+        status: "skipped".to_string(),
+        status_code: None,
+        duration_ms: None,
+        content_type: None,
+        status_documented: None,
+        content_type_documented: None,
+        schema_valid: None,
+        violations: Vec::new(),
+        error: None,
+    };
+    let Some(request) = planned.request else {
+        result.error = Some("no request in the collection covers this operation".to_string());
+        return result;
+    };
+    result.request_id = Some(str_of(request, "id").to_string());
+    result.name = Some(str_of(request, "name").to_string());
+    result.status = "error".to_string();
+    let sent = match sender.send(request.clone()).await {
+        Ok(sent) => sent.result,
+        Err(e) => {
+            result.error = Some(e);
+            return result;
+        }
+    };
+    result.status_code = sent.get("status_code").and_then(Value::as_u64);
+    result.duration_ms = sent.get("duration_ms").and_then(Value::as_f64);
+    result.content_type = sent
+        .get("content_type")
+        .and_then(Value::as_str)
+        .filter(|ct| !ct.is_empty())
+        .map(str::to_string);
+    result.error = sent
+        .get("error")
+        .and_then(Value::as_str)
+        .filter(|e| !e.is_empty())
+        .map(str::to_string);
+    if result.error.is_some() {
+        return result;
+    }
+
+    result.violations = schema::check_operation(
+        doc,
+        planned.operation,
+        result.status_code.unwrap_or(0) as u16,
+        result.content_type.as_deref(),
+        &schema::body_of(&sent),
+    );
+    let failed = |keyword: &str| result.violations.iter().any(|v| v.keyword == keyword);
+    let status_ok = !failed("status");
+    let content_type_ok = status_ok && !failed("content-type");
+    result.status_documented = Some(status_ok);
+    result.content_type_documented = status_ok.then_some(content_type_ok);
+    result.schema_valid = content_type_ok.then_some(result.violations.is_empty());
+    result.status = match result.violations.is_empty() {
+        true => "passed",
+        false => "failed",
+    }
+    .to_string();
+    result
+}
+
+/// Exercises every operation of `plan` with its request, one at a time, calling `on_result`
+/// with the completed count as each finishes. Operations without a request are reported
+/// as skipped. Setting `cancel` stops further requests from starting.
+pub async fn execute<S: Sender>(
+    run_id: String,
+    collection_id: String,
+    plan: &Plan<'_>,
+    sender: &S,
+    cancel: &AtomicBool,
+    mut on_result: impl FnMut(usize, &OperationResult),
+) -> ContractReport {
+    let started_ms = crate::now_ms();
+    let started = Instant::now();
+    let (doc, total) = (plan.doc, plan.total());
+    let mut operations = Vec::new();
+    let mut cancelled = false;
+    for planned in &plan.planned {
+        if cancel.load(Ordering::Relaxed) {
+            cancelled = true;
+            break;
+        }
+        let result = run_one(sender, doc, planned).await;
+        on_result(operations.len() + 1, &result);
+        operations.push(result);
+    }
+    let count = |status: &str| operations.iter().filter(|r| r.status == status).count();
+    let (passed, failed, errors) = (count("passed"), count("failed"), count("error"));
+    let info = doc.get("info").unwrap_or(&Value::Null);
+    ContractReport {
+        run_id,
+        collection_id,
+        title: str_of(info, "title").to_string(),
+        version: str_of(info, "version").to_string(),
+        started_ms,
+        duration_ms: started.elapsed().as_millis() as u64,
+        cancelled,
+        total,
+        passed,
+        failed,
+        errors,
+        // Operations never reached after a cancel count as skipped too.
+        skipped: total - passed - failed - errors,
+        conformance_pct: match total {
+            0 => 0.0,
+            _ => passed as f64 / total as f64 * 100.0,
+        },
+        operations,
+    }
+}
@@ -0,0 +1,136 @@
+//! MessagePack and CBOR bodies as JSON, for display and for the JSON tools that work on
+//! response bodies. What JSON can't say directly is spelled out: binary as base64, map keys
+//! that aren't strings in their JSON form, MessagePack extensions as `{"$ext", "data"}`.
+//! A body holding several values back to back decodes to an array of them.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+use serde_json::{Map, Number, Value};
+use std::io::Cursor;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    Msgpack,
+    Cbor,
+}
+
+impl Format {
+    /// The format a `Content-Type` names, if it's one of these.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        let content_type = content_type.to_ascii_lowercase();
+        if content_type.contains("msgpack") || content_type.contains("messagepack") {
+            Some(Format::Msgpack)
+        } else if content_type.contains("cbor") {
+            Some(Format::Cbor)
+        } else {
+            None
+        }
+    }
+}
+
+fn float(value: f64) -> Value {
+    Number::from_f64(value).map_or(Value::Null, Value::Number)
+}
+
+/// A map key as an object key: strings as they are, anything else in its JSON form.
+fn key(value: Value) -> String {
+    match value {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+fn from_msgpack(value: rmpv::Value) -> Value {
+    match value {
+        rmpv::Value::Nil => Value::Null,
+        rmpv::Value::Boolean(b) => Value::Bool(b),
+        rmpv::Value::Integer(n) => match (n.as_u64(), n.as_i64()) {
+            (Some(n), _) => Value::from(n),
+            (_, Some(n)) => Value::from(n),
+            _ => Value::Null,
+        },
+        rmpv::Value::F32(n) => float(f64::from(n)),
+        rmpv::Value::F64(n) => float(n),
+        rmpv::Value::String(text) => match text.into_str() {
+            Some(text) => Value::String(text),
+            None => Value::Null,
+        },
+        rmpv::Value::Binary(bytes) => Value::String(STANDARD.encode(bytes)),
+        rmpv::Value::Array(items) => Value::Array(items.into_iter().map(from_msgpack).collect()),
+        rmpv::Value::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(k, v)| (key(from_msgpack(k)), from_msgpack(v)))
+                .collect(),
+        ),
+        rmpv::Value::Ext(kind, data) => {
+            let mut ext = Map::new();
+            ext.insert("$ext".to_string(), Value::from(kind));
+            ext.insert("data".to_string(), Value::String(STANDARD.encode(data)));
+            Value::Object(ext)
+        }
+    }
+}
+
+fn from_cbor(value: ciborium::Value) -> Value {
+    match value {
+        ciborium::Value::Null => Value::Null,
+        ciborium::Value::Bool(b) => Value::Bool(b),
+        ciborium::Value::Integer(n) => {
+            let n = i128::from(n);
+            match (u64::try_from(n), i64::try_from(n)) {
+                (Ok(n), _) => Value::from(n),
+                (_, Ok(n)) => Value::from(n),
+                _ => Value::String(n.to_string()),
+            }
+        }
+        ciborium::Value::Float(n) => float(n),
+        ciborium::Value::Text(text) => Value::String(text),
+        ciborium::Value::Bytes(bytes) => Value::String(STANDARD.encode(bytes)),
+        // Bignums (tags 2 and 3) as decimal strings when they fit in 128 bits.
+        ciborium::Value::Tag(tag @ (2 | 3), inner) => match *inner {
+            ciborium::Value::Bytes(bytes) if bytes.len() <= 16 => {
+                let n = bytes.iter().fold(0u128, |n, b| (n << 8) | u128::from(*b));
+                Value::String(match tag {
+                    2 => n.to_string(),
+                    _ => format!("-{}", n + 1),
+                })
+            }
+            other => from_cbor(other),
+        },
+        // Dates, URIs and the rest read fine as their content.
+        ciborium::Value::Tag(_, inner) => from_cbor(*inner),
+        ciborium::Value::Array(items) => Value::Array(items.into_iter().map(from_cbor).collect()),
+        ciborium::Value::Map(entries) => Value::Object(
+            entries
+                .into_iter()
+                .map(|(k, v)| (key(from_cbor(k)), from_cbor(v)))
+                .collect(),
+        ),
+        _ => Value::Null,
+    }
+}
+
+/// Decodes `bytes` as `format`.
+pub fn to_json(format: Format, bytes: &[u8]) -> Result<Value, String> {
+    let mut reader = Cursor::new(bytes);
+    let mut values = Vec::new();
+    while (reader.position() as usize) < bytes.len() {
+        let value = match format {
+            Format::Msgpack => rmpv::decode::read_value(&mut reader)
+                .map(from_msgpack)
+                .map_err(|e| format!("MessagePack decode failed: {e}"))?,
+            Format::Cbor => ciborium::from_reader::<ciborium::Value, _>(&mut reader)
+                .map(from_cbor)
+                .map_err(|e| format!("CBOR decode failed: {e}"))?,
+        };
+        values.push(value);
+    }
+    Ok(match values.len() {
+        0 => Value::Null,
+        1 => values.pop().unwrap_or(Value::Null),
+        _ => Value::Array(values),
+    })
+}
@@ -0,0 +1,294 @@
+//! Structural comparison of two responses: the JSON paths added, removed or changed between
+//! the bodies, and the headers that differ, optionally leaving out fields that change on
+//! every call (ids, timestamps, request ids) so staging and production, or a response
+//! before and after a change, can be compared on what matters.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::schema::{body_of, child_path};
+
+/// Body keys treated as volatile, compared without `_`, `-` and case.
+const VOLATILE_KEYS: &[&str] = &[
+    "id",
+    "uuid",
+    "guid",
+    "etag",
+    "nonce",
+    "timestamp",
+    "time",
+    "date",
+    "created",
+    "updated",
+    "modified",
+    "expires",
+    "requestid",
+    "traceid",
+    "spanid",
+    "correlationid",
+];
+
+/// Headers that differ between otherwise identical responses.
+const VOLATILE_HEADERS: &[&str] = &[
+    "date",
+    "age",
+    "etag",
+    "expires",
+    "last-modified",
+    "set-cookie",
+    "content-length",
+    "server-timing",
+    "traceparent",
+    "tracestate",
+    "cf-ray",
+    "x-request-id",
+    "x-correlation-id",
+    "x-trace-id",
+    "x-amzn-requestid",
+    "x-amz-request-id",
+    "x-amzn-trace-id",
+    "x-runtime",
+    "x-response-time",
+];
+
+#[derive(Deserialize, Default)]
+pub struct DiffOptions {
+    /// Body paths such as `$.meta.requestId` or `$.items[*].updatedAt`, or bare key names
+    /// matched at any depth, left out of the comparison; headers are matched by name.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Also leave out ids, timestamps and the headers that change on every response.
+    #[serde(default)]
+    pub ignore_volatile: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Change {
+    pub path: String,
+    /// `added`, `removed` or `changed`.
+    pub kind: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HeaderChange {
+    /// Lowercased.
+    pub name: String,
+    pub kind: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ResponseDiff {
+    /// Nothing differs outside the ignored fields.
+    pub identical: bool,
+    pub status_before: Option<u64>,
+    pub status_after: Option<u64>,
+    pub body: Vec<Change>,
+    pub headers: Vec<HeaderChange>,
+    /// Body fields and headers that differed but were ignored.
+    pub ignored: usize,
+}
+
+enum Rule {
+    Path(Regex),
+    Key(String),
+}
+
+fn normalized(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .collect::<String>()
+        .to_lowercase()
+}
+
+/// A volatile key, or one ending in such a word: `created_at`, `updatedAt`, `userId`.
+fn volatile_key(key: &str) -> bool {
+    VOLATILE_KEYS.contains(&normalized(key).as_str())
+        || [
+            "_at",
+            "At",
+            "_id",
+            "Id",
+            "ID",
+            "_time",
+            "Time",
+            "_date",
+            "Date",
+            "_timestamp",
+            "Timestamp",
+        ]
+        .iter()
+        .any(|suffix| key.len() > suffix.len() && key.ends_with(suffix))
+}
+
+/// Values that are different on every call whatever the key: UUIDs and ISO timestamps.
+fn volatile_value(before: &Value, after: &Value) -> bool {
+    let pattern = Regex::new(
+        r"^([0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}|\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}.*)$",
+    );
+    let (Some(before), Some(after), Ok(pattern)) = (before.as_str(), after.as_str(), pattern)
+    else {
+        return false;
+    };
+    pattern.is_match(before) && pattern.is_match(after)
+}
+
+struct Differ {
+    rules: Vec<Rule>,
+    volatile: bool,
+    changes: Vec<Change>,
+    ignored: usize,
+}
+
+impl Differ {
+    fn ignores(&self, path: &str, key: Option<&str>) -> bool {
+        self.rules.iter().any(|rule| match (rule, key) {
+            (Rule::Path(pattern), _) => pattern.is_match(path),
+            (Rule::Key(name), Some(key)) => name == key,
+            (Rule::Key(_), None) => false,
+        }) || (self.volatile && key.is_some_and(volatile_key))
+    }
+
+    fn record(&mut self, path: &str, kind: &str, before: Option<&Value>, after: Option<&Value>) {
+        self.changes.push(Change {
+            path: path.to_string(),
+            kind: kind.to_string(),
+            before: before.cloned(),
+            after: after.cloned(),
+        });
+    }
+
+    fn compare(
+        &mut self,
+        path: &str,
+        key: Option<&str>,
+        before: Option<&Value>,
+        after: Option<&Value>,
+    ) {
+        if before == after {
+            return;
+        }
+        if self.ignores(path, key) {
+            self.ignored += 1;
+            return;
+        }
+        match (before, after) {
+            (Some(Value::Object(a)), Some(Value::Object(b))) => {
+                let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+                for key in keys {
+                    self.compare(&child_path(path, key), Some(key), a.get(key), b.get(key));
+                }
+            }
+            (Some(Value::Array(a)), Some(Value::Array(b))) => {
+                for i in 0..a.len().max(b.len()) {
+                    self.compare(&format!("{path}[{i}]"), None, a.get(i), b.get(i));
+                }
+            }
+            (Some(a), Some(b)) if self.volatile && volatile_value(a, b) => self.ignored += 1,
+            (Some(a), Some(b)) => self.record(path, "changed", Some(a), Some(b)),
+            (Some(a), None) => self.record(path, "removed", Some(a), None),
+            (None, Some(b)) => self.record(path, "added", None, Some(b)),
+            (None, None) => {}
+        }
+    }
+}
+
+/// An ignore path as a pattern over the paths `compare` builds: `*` stands for one key and
+/// `[*]` for any index. A path also covers everything below it.
+fn path_rule(path: &str) -> Option<Regex> {
+    let escaped = regex::escape(path.trim())
+        .replace(r"\[\*\]", r"\[\d+\]")
+        .replace(r"\*", r"[^.\[]+");
+    Regex::new(&format!(r"^{escaped}$")).ok()
+}
+
+fn headers_of(result: &Value) -> Vec<(String, String)> {
+    let mut headers: Vec<(String, String)> = result
+        .get("headers")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| {
+            let value = match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            (name.to_lowercase(), value)
+        })
+        .collect();
+    headers.sort();
+    headers
+}
+
+/// Compares two backend `RequestResult`s.
+pub fn diff(before: &Value, after: &Value, options: &DiffOptions) -> ResponseDiff {
+    let mut differ = Differ {
+        rules: options
+            .ignore
+            .iter()
+            .filter(|rule| !rule.trim().is_empty())
+            .filter_map(|rule| match rule.trim().starts_with('$') {
+                true => path_rule(rule).map(Rule::Path),
+                false => Some(Rule::Key(rule.trim().to_string())),
+            })
+            .collect(),
+        volatile: options.ignore_volatile,
+        changes: Vec::new(),
+        ignored: 0,
+    };
+    differ.compare("$", None, Some(&body_of(before)), Some(&body_of(after)));
+
+    let ignored_headers: Vec<String> = options
+        .ignore
+        .iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+    let (a, b) = (headers_of(before), headers_of(after));
+    let names: BTreeSet<&String> = a.iter().chain(&b).map(|(name, _)| name).collect();
+    let mut headers = Vec::new();
+    for name in names {
+        let value_in = |headers: &[(String, String)]| {
+            headers
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, value)| value.clone())
+        };
+        let (before, after) = (value_in(&a), value_in(&b));
+        if before == after {
+            continue;
+        }
+        if ignored_headers.contains(name)
+            || (options.ignore_volatile && VOLATILE_HEADERS.contains(&name.as_str()))
+        {
+            differ.ignored += 1;
+            continue;
+        }
+        let kind = match (&before, &after) {
+            (Some(_), Some(_)) => "changed",
+            (Some(_), None) => "removed",
+            _ => "added",
+        };
+        headers.push(HeaderChange {
+            name: name.clone(),
+            kind: kind.to_string(),
+            before,
+            after,
+        });
+    }
+
+    let status_before = before.get("status_code").and_then(Value::as_u64);
+    let status_after = after.get("status_code").and_then(Value::as_u64);
+    ResponseDiff {
+        identical: status_before == status_after && differ.changes.is_empty() && headers.is_empty(),
+        status_before,
+        status_after,
+        body: differ.changes,
+        headers,
+        ignored: differ.ignored,
+    }
+}
@@ -0,0 +1,596 @@
+//! API documentation rendered from a collection: every request with its description, headers,
+//! query parameters and body, an example of the request as sent, and its saved example
+//! responses, plus a table of the `{{variables}}` the requests use and their value in each
+//! environment. Output is Markdown, to commit beside the code, or a standalone HTML page.
+//! Values flagged secret are masked; references to variables are kept as written.
+
+use quick_xml::escape::escape;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+use crate::json::{array_of, str_of, value_text};
+use crate::redact::MASK;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum DocsFormat {
+    Markdown,
+    Html,
+}
+
+/// A heading and what is written under it, in the order of the collection.
+enum Section<'a> {
+    Folder { depth: usize, item: &'a Value },
+    Request { depth: usize, item: &'a Value },
+}
+
+struct Anchors(HashMap<String, usize>);
+
+impl Anchors {
+    /// GitHub's heading slugs, numbered on repeats, so Markdown links work once pushed.
+    fn slug(&mut self, text: &str) -> String {
+        let base: String = text
+            .trim()
+            .to_lowercase()
+            .chars()
+            .filter(|c| c.is_alphanumeric() || *c == ' ' || *c == '-' || *c == '_')
+            .map(|c| if c == ' ' { '-' } else { c })
+            .collect();
+        let seen = self.0.entry(base.clone()).or_insert(0);
+        *seen += 1;
+        match *seen {
+            1 => base,
+            n => format!("{base}-{}", n - 1),
+        }
+    }
+}
+
+fn sections<'a>(items: &'a [Value], depth: usize, out: &mut Vec<Section<'a>>) {
+    for item in items {
+        match item.get("items").and_then(Value::as_array) {
+            Some(children) => {
+                out.push(Section::Folder { depth, item });
+                sections(children, depth + 1, out);
+            }
+            None => out.push(Section::Request { depth, item }),
+        }
+    }
+}
+
+fn title_of(section: &Section) -> String {
+    match section {
+        Section::Folder { item, .. } => str_of(item, "name").to_string(),
+        Section::Request { item, .. } => {
+            format!("{} {}", str_of(item, "method"), str_of(item, "name"))
+        }
+    }
+}
+
+fn description(item: &Value) -> &str {
+    ["description", "docs"]
+        .iter()
+        .map(|key| str_of(item, key).trim())
+        .find(|text| !text.is_empty())
+        .unwrap_or_default()
+}
+
+/// The variable names referenced in `text`, dynamic `{{$...}}` values left out.
+pub(crate) fn references(text: &str, out: &mut BTreeSet<String>) {
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = rest[start + 2..start + len].trim();
+        if !name.is_empty() && !name.starts_with('$') && !rest[..start].ends_with('\\') {
+            out.insert(name.to_string());
+        }
+        rest = &rest[start + len + 2..];
+    }
+}
+
+/// `text` is nothing but a variable reference, which is fine to show even when flagged secret.
+fn is_reference(text: &str) -> bool {
+    let text = text.trim();
+    text.starts_with("{{") && text.ends_with("}}") && text[2..].find("{{").is_none()
+}
+
+fn shown(value: &str, secret: bool) -> String {
+    match secret && !is_reference(value) {
+        true => MASK.to_string(),
+        false => value.to_string(),
+    }
+}
+
+fn flagged(request: &Value, marker: &str, key: &str) -> bool {
+    request.get(marker).and_then(|m| m.get(key)) == Some(&Value::Bool(true))
+}
+
+fn headers_of(request: &Value) -> Vec<(String, String)> {
+    request
+        .get("headers")
+        .and_then(Value::as_object)
+        .map(|headers| {
+            headers
+                .iter()
+                .map(|(name, value)| {
+                    let value = value_text(Some(value));
+                    let secret = flagged(request, "secret_headers", name);
+                    (name.clone(), shown(&value, secret))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn query_of(request: &Value) -> Vec<(String, String)> {
+    array_of(request, "query_params")
+        .iter()
+        .filter(|row| row.get("enabled").and_then(Value::as_bool) != Some(false))
+        .map(|row| {
+            let key = str_of(row, "key").to_string();
+            let secret = flagged(request, "secret_query_params", &key);
+            let value = shown(&value_text(row.get("value")), secret);
+            (key, value)
+        })
+        .collect()
+}
+
+/// The body as the request would send it, pretty-printed when it's JSON.
+fn body_of(request: &Value) -> Option<String> {
+    let mode = str_of(request, "body_mode");
+    if matches!(mode, "form-urlencoded" | "form-data") {
+        let fields: Vec<String> = array_of(request, "form_body")
+            .iter()
+            .filter(|row| row.get("enabled").and_then(Value::as_bool) != Some(false))
+            .map(|row| {
+                let key = str_of(row, "key");
+                let value = match str_of(row, "type") {
+                    "file" | "binary" => format!("<{}>", str_of(row, "file_name")),
+                    _ => shown(
+                        &value_text(row.get("value")),
+                        row.get("secret").and_then(Value::as_bool) == Some(true)
+                            || flagged(request, "secret_form_fields", key),
+                    ),
+                };
+                format!("{key}={value}")
+            })
+            .collect();
+        let separator = if mode == "form-data" { "\n" } else { "&" };
+        return (!fields.is_empty()).then(|| fields.join(separator));
+    }
+    if mode == "binary" {
+        let name = request.pointer("/binary/file_name").and_then(Value::as_str);
+        return name.map(|n| format!("<{n}>"));
+    }
+    let text = match request.get("body") {
+        None | Some(Value::Null) => return None,
+        Some(Value::String(text)) if text.trim().is_empty() => return None,
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    };
+    if request.get("secret_body").and_then(Value::as_bool) == Some(true) {
+        return Some(MASK.to_string());
+    }
+    Some(pretty(&text))
+}
+
+fn pretty(text: &str) -> String {
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .filter(|v| v.is_object() || v.is_array())
+        .and_then(|v| serde_json::to_string_pretty(&v).ok())
+        .unwrap_or_else(|| text.to_string())
+}
+
+/// The request as it goes over the wire, before variables are resolved.
+fn http_example(request: &Value) -> String {
+    let mut url = str_of(request, "url").to_string();
+    let query = query_of(request);
+    if !query.is_empty() {
+        // Enabled rows replace the URL's own query when the request is sent.
+        url.truncate(url.find('?').unwrap_or(url.len()));
+        let pairs: Vec<String> = query.iter().map(|(k, v)| format!("{k}={v}")).collect();
+        let _ = write!(url, "?{}", pairs.join("&"));
+    }
+    let mut text = format!("{} {url}", str_of(request, "method"));
+    for (name, value) in headers_of(request) {
+        let _ = write!(text, "\n{name}: {value}");
+    }
+    if let Some(body) = body_of(request) {
+        let _ = write!(text, "\n\n{body}");
+    }
+    text
+}
+
+/// A saved example response: its headers, a blank line, then the body.
+fn example_text(example: &Value) -> String {
+    let mut text = String::new();
+    if let Some(headers) = example.get("headers").and_then(Value::as_object) {
+        for (name, value) in headers {
+            let _ = writeln!(text, "{name}: {}", value_text(Some(value)));
+        }
+    }
+    if !str_of(example, "body").is_empty() {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&pretty(str_of(example, "body")));
+    }
+    text.trim_end().to_string()
+}
+
+fn auth_summary(request: &Value) -> Option<String> {
+    let auth = str_of(request, "auth_type");
+    if auth.is_empty() || auth == "none" {
+        return None;
+    }
+    let names: Vec<&str> = request
+        .get("auth_params")
+        .and_then(Value::as_object)
+        .map(|p| p.keys().map(String::as_str).collect())
+        .unwrap_or_default();
+    Some(match names.is_empty() {
+        true => auth.to_string(),
+        false => format!("{auth} ({})", names.join(", ")),
+    })
+}
+
+/// Each environment's value for the variables the requests use; secrets are masked.
+fn variable_rows(
+    collection: &Value,
+    environment: &Value,
+    used: &BTreeSet<String>,
+) -> (Vec<String>, Vec<(String, Vec<String>)>) {
+    let mut columns = vec!["Collection".to_string()];
+    let mut envs: Vec<(&String, &Value)> = environment
+        .get("envs")
+        .and_then(Value::as_object)
+        .map(|e| e.iter().collect())
+        .unwrap_or_default();
+    envs.sort_by(|a, b| a.0.cmp(b.0));
+    columns.extend(envs.iter().map(|(name, _)| name.to_string()));
+    let rows = used
+        .iter()
+        .map(|key| {
+            let mut values = vec![value_text(
+                collection.get("variables").and_then(|v| v.get(key)),
+            )];
+            for (_, env) in &envs {
+                let value = value_text(env.get("variables").and_then(|v| v.get(key)));
+                let secret = flagged(env, "secrets", key);
+                values.push(match secret {
+                    true => MASK.to_string(),
+                    false => value,
+                });
+            }
+            (key.clone(), values)
+        })
+        .collect();
+    (columns, rows)
+}
+
+fn used_variables(sections: &[Section]) -> BTreeSet<String> {
+    let mut used = BTreeSet::new();
+    for section in sections {
+        if let Section::Request { item, .. } = section {
+            references(&http_example(item), &mut used);
+            if let Some(params) = item.get("auth_params").and_then(Value::as_object) {
+                for value in params.values() {
+                    references(&value_text(Some(value)), &mut used);
+                }
+            }
+        }
+    }
+    used
+}
+
+fn md_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+/// A fence longer than any run of backticks in `text`.
+fn md_fence(text: &str) -> String {
+    let longest = text.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn md_table(out: &mut String, head: &[&str], rows: &[Vec<String>]) {
+    let _ = writeln!(out, "| {} |", head.join(" | "));
+    let _ = writeln!(out, "|{}", " --- |".repeat(head.len()));
+    for row in rows {
+        let cells: Vec<String> = row.iter().map(|c| md_cell(c)).collect();
+        let _ = writeln!(out, "| {} |", cells.join(" | "));
+    }
+    out.push('\n');
+}
+
+fn md_code(out: &mut String, language: &str, text: &str) {
+    let fence = md_fence(text);
+    let _ = writeln!(out, "{fence}{language}\n{text}\n{fence}\n");
+}
+
+fn markdown(collection: &Value, environment: &Value) -> String {
+    let mut sections_list = Vec::new();
+    sections(array_of(collection, "items"), 0, &mut sections_list);
+    let mut anchors = Anchors(HashMap::new());
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", str_of(collection, "name"));
+    anchors.slug(str_of(collection, "name"));
+    if !description(collection).is_empty() {
+        let _ = writeln!(out, "{}\n", description(collection));
+    }
+
+    if !sections_list.is_empty() {
+        anchors.slug("Contents");
+    }
+    let titles: Vec<(String, String)> = sections_list
+        .iter()
+        .map(|s| {
+            let title = title_of(s);
+            (anchors.slug(&title), title)
+        })
+        .collect();
+    let used = used_variables(&sections_list);
+    let variables = anchors.slug("Variables");
+    if !titles.is_empty() {
+        out.push_str("## Contents\n\n");
+        for ((slug, title), section) in titles.iter().zip(&sections_list) {
+            let depth = match section {
+                Section::Folder { depth, .. } | Section::Request { depth, .. } => *depth,
+            };
+            let _ = writeln!(out, "{}- [{title}](#{slug})", "  ".repeat(depth));
+        }
+        if !used.is_empty() {
+            let _ = writeln!(out, "- [Variables](#{variables})");
+        }
+        out.push('\n');
+    }
+
+    for (section, (_, title)) in sections_list.iter().zip(&titles) {
+        let (depth, item) = match section {
+            Section::Folder { depth, item } | Section::Request { depth, item } => (*depth, *item),
+        };
+        let _ = writeln!(out, "{} {title}\n", "#".repeat((depth + 2).min(6)));
+        if !description(item).is_empty() {
+            let _ = writeln!(out, "{}\n", description(item));
+        }
+        let Section::Request { .. } = section else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "`{} {}`\n",
+            str_of(item, "method"),
+            str_of(item, "url")
+        );
+        if let Some(auth) = auth_summary(item) {
+            let _ = writeln!(out, "**Auth:** {auth}\n");
+        }
+        let rows = |pairs: Vec<(String, String)>| -> Vec<Vec<String>> {
+            pairs.into_iter().map(|(k, v)| vec![k, v]).collect()
+        };
+        let query = query_of(item);
+        if !query.is_empty() {
+            out.push_str("**Query parameters**\n\n");
+            md_table(&mut out, &["Name", "Value"], &rows(query));
+        }
+        let headers = headers_of(item);
+        if !headers.is_empty() {
+            out.push_str("**Headers**\n\n");
+            md_table(&mut out, &["Name", "Value"], &rows(headers));
+        }
+        out.push_str("**Example request**\n\n");
+        md_code(&mut out, "http", &http_example(item));
+        for example in array_of(item, "examples") {
+            let _ = writeln!(
+                out,
+                "**Example response: {}** ({})\n",
+                str_of(example, "name"),
+                value_text(example.get("status_code"))
+            );
+            let text = example_text(example);
+            if !text.is_empty() {
+                md_code(&mut out, "", &text);
+            }
+        }
+    }
+
+    if !used.is_empty() {
+        out.push_str("## Variables\n\n");
+        let (columns, rows) = variable_rows(collection, environment, &used);
+        let mut head = vec!["Variable"];
+        head.extend(columns.iter().map(String::as_str));
+        let rows: Vec<Vec<String>> = rows
+            .into_iter()
+            .map(|(key, values)| {
+                let mut row = vec![format!("`{{{{{key}}}}}`")];
+                row.extend(values);
+                row
+            })
+            .collect();
+        md_table(&mut out, &head, &rows);
+    }
+    out.trim_end().to_string() + "\n"
+}
+
+const DOCS_STYLE: &str = "\
+nav ul{list-style:none;padding-left:1rem}\
+.method{font-weight:600;margin-right:.4rem}\
+.url{font-family:ui-monospace,monospace}\
+h2,h3,h4,h5,h6{margin-top:2rem}";
+
+/// Descriptions are Markdown, rendered as the docs panel shows them.
+fn html_text(text: &str) -> String {
+    crate::markdown::to_html(text)
+}
+
+fn html_table(out: &mut String, head: &[&str], rows: &[Vec<String>]) {
+    out.push_str("<table><tr>");
+    for cell in head {
+        let _ = write!(out, "<th>{}</th>", escape(cell));
+    }
+    out.push_str("</tr>\n");
+    for row in rows {
+        out.push_str("<tr>");
+        for cell in row {
+            let _ = write!(out, "<td>{}</td>", escape(cell));
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n");
+}
+
+fn html(collection: &Value, environment: &Value) -> String {
+    let mut sections_list = Vec::new();
+    sections(array_of(collection, "items"), 0, &mut sections_list);
+    let mut anchors = Anchors(HashMap::new());
+    let name = str_of(collection, "name");
+    anchors.slug(name);
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <title>{title}</title><style>{}{DOCS_STYLE}</style></head><body>\n<h1>{title}</h1>\n{}",
+        crate::report::STYLE,
+        html_text(description(collection)),
+        title = escape(name),
+    );
+
+    let titles: Vec<(String, String)> = sections_list
+        .iter()
+        .map(|s| {
+            let title = title_of(s);
+            (anchors.slug(&title), title)
+        })
+        .collect();
+    let used = used_variables(&sections_list);
+    let variables = anchors.slug("Variables");
+    if !titles.is_empty() {
+        out.push_str("<nav><ul>\n");
+        for ((slug, title), section) in titles.iter().zip(&sections_list) {
+            let depth = match section {
+                Section::Folder { depth, .. } | Section::Request { depth, .. } => *depth,
+            };
+            let _ = writeln!(
+                out,
+                "<li style=\"margin-left:{depth}rem\"><a href=\"#{}\">{}</a></li>",
+                escape(slug),
+                escape(title)
+            );
+        }
+        if !used.is_empty() {
+            let _ = writeln!(out, "<li><a href=\"#{variables}\">Variables</a></li>");
+        }
+        out.push_str("</ul></nav>\n");
+    }
+
+    for (section, (slug, title)) in sections_list.iter().zip(&titles) {
+        let (depth, item) = match section {
+            Section::Folder { depth, item } | Section::Request { depth, item } => (*depth, *item),
+        };
+        let level = (depth + 2).min(6);
+        let _ = writeln!(
+            out,
+            "<h{level} id=\"{}\">{}</h{level}>\n{}",
+            escape(slug),
+            escape(title),
+            html_text(description(item))
+        );
+        let Section::Request { .. } = section else {
+            continue;
+        };
+        let _ = writeln!(
+            out,
+            "<p><span class=\"method\">{}</span><span class=\"url\">{}</span></p>",
+            escape(str_of(item, "method")),
+            escape(str_of(item, "url"))
+        );
+        if let Some(auth) = auth_summary(item) {
+            let _ = writeln!(out, "<p><strong>Auth:</strong> {}</p>", escape(&auth));
+        }
+        let rows = |pairs: Vec<(String, String)>| -> Vec<Vec<String>> {
+            pairs.into_iter().map(|(k, v)| vec![k, v]).collect()
+        };
+        let query = query_of(item);
+        if !query.is_empty() {
+            out.push_str("<h4>Query parameters</h4>\n");
+            html_table(&mut out, &["Name", "Value"], &rows(query));
+        }
+        let headers = headers_of(item);
+        if !headers.is_empty() {
+            out.push_str("<h4>Headers</h4>\n");
+            html_table(&mut out, &["Name", "Value"], &rows(headers));
+        }
+        let _ = writeln!(
+            out,
+            "<h4>Example request</h4>\n<pre>{}</pre>",
+            escape(&http_example(item))
+        );
+        for example in array_of(item, "examples") {
+            let text = example_text(example);
+            let _ = writeln!(
+                out,
+                "<h4>Example response: {} ({})</h4>",
+                escape(str_of(example, "name")),
+                escape(&value_text(example.get("status_code")))
+            );
+            if !text.is_empty() {
+                let _ = writeln!(out, "<pre>{}</pre>", escape(&text));
+            }
+        }
+    }
+
+    if !used.is_empty() {
+        let _ = writeln!(out, "<h2 id=\"{variables}\">Variables</h2>");
+        let (columns, rows) = variable_rows(collection, environment, &used);
+        let mut head = vec!["Variable"];
+        head.extend(columns.iter().map(String::as_str));
+        let rows: Vec<Vec<String>> = rows
+            .into_iter()
+            .map(|(key, values)| {
+                let mut row = vec![format!("{{{{{key}}}}}")];
+                row.extend(values);
+                row
+            })
+            .collect();
+        html_table(&mut out, &head, &rows);
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+/// An HTML page linking to the docs of each collection (`CollectionMeta` values), for the
+/// preview server's `collections/<id>` pages.
+pub fn index(collections: &[Value]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <title>API documentation</title><style>{}{DOCS_STYLE}</style></head><body>\n\
+         <h1>API documentation</h1>\n",
+        crate::report::STYLE,
+    );
+    if collections.is_empty() {
+        out.push_str("<p class=\"meta\">This workspace has no collections.</p>\n");
+    }
+    out.push_str("<ul>\n");
+    for collection in collections {
+        let id = escape(str_of(collection, "id"));
+        let _ = writeln!(
+            out,
+            "<li><a href=\"collections/{id}\">{}</a> (<a href=\"collections/{id}.md\">Markdown</a>)</li>",
+            escape(str_of(collection, "name"))
+        );
+    }
+    out.push_str("</ul>\n</body></html>\n");
+    out
+}
+
+/// Documentation for `collection`, with variable values taken from its `environment` file.
+pub fn render(collection: &Value, environment: &Value, format: DocsFormat) -> String {
+    match format {
+        DocsFormat::Markdown => markdown(collection, environment),
+        DocsFormat::Html => html(collection, environment),
+    }
+}
@@ -0,0 +1,232 @@
+//! Dynamic variables: `{{$name}}` references that produce a fresh value every time a request
+//! is sent, such as `{{$uuid}}`, `{{$isoDate}}`, `{{$randomInt(1,6)}}` or faker-style data
+//! like `{{$randomEmail}}`. Each occurrence is evaluated on its own.
+
+use serde::Serialize;
+
+const FIRST_NAMES: &[&str] = &[
+    "Ada", "Alan", "Amara", "Ben", "Carmen", "Chen", "Diego", "Elena", "Farah", "George", "Hana",
+    "Ivan", "Jamal", "Julia", "Kai", "Lena", "Mateo", "Mei", "Nadia", "Omar", "Priya", "Quinn",
+    "Rosa", "Sam", "Sofia", "Tariq", "Uma", "Victor", "Wen", "Yusuf", "Zoe",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Adams", "Baker", "Castillo", "Dubois", "Evans", "Fischer", "Garcia", "Hughes", "Ito",
+    "Jensen", "Kowalski", "Lopez", "Martin", "Nguyen", "Okafor", "Patel", "Quispe", "Rossi",
+    "Schmidt", "Tanaka", "Usman", "Varga", "Walker", "Xu", "Yilmaz", "Zhang",
+];
+
+const STREETS: &[&str] = &[
+    "Maple Street",
+    "Oak Avenue",
+    "Cedar Lane",
+    "Elm Road",
+    "Harbor Way",
+    "Hillside Drive",
+    "Lake View Road",
+    "Mill Street",
+    "Park Avenue",
+    "River Road",
+    "Station Road",
+    "Sunset Blvd",
+];
+
+const CITIES: &[&str] = &[
+    "Springfield",
+    "Riverside",
+    "Fairview",
+    "Greenville",
+    "Bristol",
+    "Clinton",
+    "Franklin",
+    "Georgetown",
+    "Madison",
+    "Oakland",
+    "Salem",
+    "Westport",
+];
+
+const COUNTRIES: &[&str] = &[
+    "Argentina",
+    "Australia",
+    "Brazil",
+    "Canada",
+    "Egypt",
+    "France",
+    "Germany",
+    "India",
+    "Japan",
+    "Kenya",
+    "Mexico",
+    "Norway",
+    "Portugal",
+    "Spain",
+    "Sweden",
+    "United Kingdom",
+    "United States",
+];
+
+const COMPANY_SUFFIXES: &[&str] = &["Inc", "LLC", "Group", "Labs", "Systems", "Partners"];
+
+const EMAIL_DOMAINS: &[&str] = &["example.com", "example.org", "example.net"];
+
+/// The names `evaluate` understands, for autocompletion in the UI.
+pub const BUILTINS: &[&str] = &[
+    "$uuid",
+    "$guid",
+    "$ulid",
+    "$timestamp",
+    "$timestampMs",
+    "$isoDate",
+    "$randomInt",
+    "$randomInt(min,max)",
+    "$randomBoolean",
+    "$randomAlphaNumeric",
+    "$randomFirstName",
+    "$randomLastName",
+    "$randomFullName",
+    "$randomUserName",
+    "$randomEmail",
+    "$randomPhoneNumber",
+    "$randomStreetAddress",
+    "$randomCity",
+    "$randomCountry",
+    "$randomZipCode",
+    "$randomCompanyName",
+];
+
+/// One evaluated reference, kept with the sent request so the exact values can be traced.
+#[derive(Serialize, Clone)]
+pub struct DynamicValue {
+    /// The reference without braces, e.g. `$randomInt(1,6)`.
+    pub expression: String,
+    pub value: String,
+}
+
+/// A uniformly distributed number below `n`, drawn from the v4 UUID generator.
+pub fn random_below(n: u64) -> u64 {
+    (uuid::Uuid::new_v4().as_u128() % u128::from(n.max(1))) as u64
+}
+
+/// A uniformly distributed number from `min` to `max`, both included.
+pub fn random_range(min: i64, max: i64) -> i64 {
+    min.wrapping_add(random_below(max.abs_diff(min).saturating_add(1)) as i64)
+}
+
+/// A ULID: the time in milliseconds and 80 random bits, in Crockford base32, so ids sort
+/// by when they were made.
+pub fn ulid() -> String {
+    const ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+    // The 48 bits above a v4 UUID's version field and 32 below its variant are all random.
+    let bits = uuid::Uuid::new_v4().as_u128();
+    let random = ((bits >> 80) << 32) | (bits & 0xffff_ffff);
+    let value = (u128::from(crate::now_ms()) << 80) | random;
+    (0..26)
+        .rev()
+        .map(|i| char::from(ALPHABET[((value >> (i * 5)) & 0x1f) as usize]))
+        .collect()
+}
+
+pub fn pick(list: &[&str]) -> String {
+    list[random_below(list.len() as u64) as usize].to_string()
+}
+
+fn digits(count: usize) -> String {
+    (0..count)
+        .map(|_| char::from(b'0' + random_below(10) as u8))
+        .collect()
+}
+
+/// Evaluates a reference such as `$uuid` or `$randomInt(1, 10)`; `None` when the name or
+/// its arguments are unknown.
+pub fn evaluate(expression: &str) -> Option<String> {
+    let expression = expression.trim();
+    let (name, args) = match expression.split_once('(') {
+        Some((name, rest)) => (name.trim(), Some(rest.strip_suffix(')')?)),
+        None => (expression, None),
+    };
+    let args: Vec<&str> = args
+        .map(|a| a.split(',').map(str::trim).collect())
+        .unwrap_or_default();
+    let now_ms = crate::now_ms();
+    let value = match (name, args.as_slice()) {
+        ("$uuid" | "$guid", []) => uuid::Uuid::new_v4().to_string(),
+        ("$ulid", []) => ulid(),
+        ("$timestamp", []) => (now_ms / 1000).to_string(),
+        ("$timestampMs", []) => now_ms.to_string(),
+        ("$isoDate", []) => chrono::DateTime::from_timestamp_millis(now_ms as i64)?
+            .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        ("$randomInt", []) => random_range(1, 10_000).to_string(),
+        ("$randomInt", [min, max]) => {
+            let (min, max): (i64, i64) = (min.parse().ok()?, max.parse().ok()?);
+            if min > max {
+                return None;
+            }
+            random_range(min, max).to_string()
+        }
+        ("$randomBoolean", []) => (random_below(2) == 1).to_string(),
+        ("$randomAlphaNumeric", []) => {
+            const CHARS: &[u8] = b"abcdefghijklmnopqrstuvwxyz0123456789";
+            char::from(CHARS[random_below(CHARS.len() as u64) as usize]).to_string()
+        }
+        ("$randomFirstName", []) => pick(FIRST_NAMES),
+        ("$randomLastName", []) => pick(LAST_NAMES),
+        ("$randomFullName", []) => format!("{} {}", pick(FIRST_NAMES), pick(LAST_NAMES)),
+        ("$randomUserName", []) => format!(
+            "{}.{}{}",
+            pick(FIRST_NAMES).to_lowercase(),
+            pick(LAST_NAMES).to_lowercase(),
+            random_below(100)
+        ),
+        ("$randomEmail", []) => format!(
+            "{}.{}@{}",
+            pick(FIRST_NAMES).to_lowercase(),
+            pick(LAST_NAMES).to_lowercase(),
+            pick(EMAIL_DOMAINS)
+        ),
+        ("$randomPhoneNumber", []) => format!("{}-{}-{}", digits(3), digits(3), digits(4)),
+        ("$randomStreetAddress", []) => {
+            format!("{} {}", random_range(1, 9999), pick(STREETS))
+        }
+        ("$randomCity", []) => pick(CITIES),
+        ("$randomCountry", []) => pick(COUNTRIES),
+        ("$randomZipCode", []) => digits(5),
+        ("$randomCompanyName", []) => {
+            format!("{} {}", pick(LAST_NAMES), pick(COMPANY_SUFFIXES))
+        }
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// Replaces every `{{$...}}` reference in `text`, appending what was generated to `record`.
+/// Unknown references and escaped `\{{...}}` ones are kept as written.
+pub fn expand(text: &str, record: &mut Vec<DynamicValue>) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let inner = &rest[start + 2..start + len];
+        let escaped = rest[..start].ends_with('\\');
+        out.push_str(&rest[..start]);
+        let value = match escaped {
+            true => None,
+            false => evaluate(inner),
+        };
+        match value {
+            Some(value) => {
+                out.push_str(&value);
+                record.push(DynamicValue {
+                    expression: inner.trim().to_string(),
+                    value,
+                });
+            }
+            None => out.push_str(&rest[start..start + len + 2]),
+        }
+        rest = &rest[start + len + 2..];
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,320 @@
+//! Workspace fixtures: files that request bodies, multipart parts and data-driven runs refer
+//! to by name (`fixture:<name>`) instead of by a local path that doesn't exist on a
+//! teammate's machine. They live in the workspace's `fixtures` folder, so they are shared
+//! and versioned with the collections: `index.json` maps names to SHA-256 checksums, and
+//! each distinct content is stored once under `objects/<sha256>`, however many names point
+//! at it.
+//!
+//! References are resolved right before sending (`resolve_request`) and when a run reads
+//! its data file (`resolve_reference`), after variables, so a reference can come from one.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const DIR: &str = "fixtures";
+const INDEX_FILE: &str = "index.json";
+const OBJECTS_DIR: &str = "objects";
+/// How a path field refers to a fixture: `fixture:<name>`.
+pub const SCHEME: &str = "fixture:";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Fixture {
+    pub name: String,
+    pub sha256: String,
+    pub size: u64,
+    /// Guessed from the original file name; sent when the request doesn't set one.
+    #[serde(default)]
+    pub content_type: Option<String>,
+    /// The added file's name, used as the upload's file name.
+    #[serde(default)]
+    pub file_name: String,
+    pub added_at_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Index {
+    fixtures: Vec<Fixture>,
+}
+
+fn dir(workspace: &Path) -> PathBuf {
+    workspace.join(DIR)
+}
+
+fn object_path(workspace: &Path, sha256: &str) -> PathBuf {
+    dir(workspace).join(OBJECTS_DIR).join(sha256)
+}
+
+/// The index comes with the workspace, so its checksums name files under `objects` only.
+fn load_index(workspace: &Path) -> Result<Index, String> {
+    let path = dir(workspace).join(INDEX_FILE);
+    if !path.exists() {
+        return Ok(Index::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("fixtures read failed: {e}"))?;
+    let index: Index =
+        serde_json::from_str(&data).map_err(|e| format!("fixtures parse failed: {e}"))?;
+    if let Some(bad) = index.fixtures.iter().find(|f| !is_sha256(&f.sha256)) {
+        return Err(format!(
+            "fixtures index invalid: {} has checksum \"{}\"; fix or remove it in {DIR}/{INDEX_FILE}",
+            bad.name, bad.sha256
+        ));
+    }
+    Ok(index)
+}
+
+fn is_sha256(text: &str) -> bool {
+    text.len() == 64 && text.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn save_index(workspace: &Path, index: &Index) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("fixtures serialize failed: {e}"))?;
+    fs::write(dir(workspace).join(INDEX_FILE), payload)
+        .map_err(|e| format!("fixtures persist failed: {e}"))
+}
+
+/// A fixture name: letters, digits, `-`, `_`, `.` and `/` for grouping, without `..`.
+fn check_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && !name.starts_with('/')
+        && !name.split('/').any(|part| part.is_empty() || part == "..")
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '/'));
+    match valid {
+        true => Ok(()),
+        false => Err(format!(
+            "invalid fixture name \"{name}\": use letters, digits, '-', '_', '.' and '/'"
+        )),
+    }
+}
+
+fn content_type(file_name: &str) -> Option<String> {
+    let extension = file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+    let mime = match extension.as_str() {
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "csv" => "text/csv",
+        "txt" => "text/plain",
+        "html" | "htm" => "text/html",
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        _ => return None,
+    };
+    Some(mime.to_string())
+}
+
+fn sha256_file(path: &Path) -> Result<(String, u64), String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("fixture read failed: {e}"))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; 64 * 1024];
+    let mut size = 0;
+    loop {
+        let read = file
+            .read(&mut buffer)
+            .map_err(|e| format!("fixture read failed: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+    Ok((format!("{:x}", hasher.finalize()), size))
+}
+
+/// Every fixture, by name.
+pub fn list(workspace: &Path) -> Result<Vec<Fixture>, String> {
+    let mut fixtures = load_index(workspace)?.fixtures;
+    fixtures.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(fixtures)
+}
+
+/// Adds the file at `source` as `name`, replacing any fixture of that name. Content already
+/// stored under another name isn't copied again.
+pub fn add(workspace: &Path, name: &str, source: &Path) -> Result<Fixture, String> {
+    check_name(name)?;
+    let (sha256, size) = sha256_file(source)?;
+    let object = object_path(workspace, &sha256);
+    if !object.exists() {
+        fs::create_dir_all(dir(workspace).join(OBJECTS_DIR))
+            .map_err(|e| format!("fixtures init failed: {e}"))?;
+        // Copied under a temporary name first, so a failed copy never looks complete.
+        let partial = object.with_extension("partial");
+        fs::copy(source, &partial).map_err(|e| format!("fixture copy failed: {e}"))?;
+        fs::rename(&partial, &object).map_err(|e| format!("fixture copy failed: {e}"))?;
+    }
+    let file_name = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| name.rsplit('/').next().unwrap_or(name).to_string());
+    let fixture = Fixture {
+        name: name.to_string(),
+        sha256,
+        size,
+        content_type: content_type(&file_name),
+        file_name,
+        added_at_ms: crate::now_ms(),
+    };
+    let mut index = load_index(workspace)?;
+    let replaced = index
+        .fixtures
+        .iter()
+        .position(|f| f.name == name)
+        .map(|at| index.fixtures.remove(at));
+    index.fixtures.push(fixture.clone());
+    save_index(workspace, &index)?;
+    if let Some(old) = replaced {
+        prune(workspace, &index, &old.sha256);
+    }
+    Ok(fixture)
+}
+
+/// Removes a fixture, and its content once no other name uses it.
+pub fn remove(workspace: &Path, name: &str) -> Result<bool, String> {
+    let mut index = load_index(workspace)?;
+    let Some(at) = index.fixtures.iter().position(|f| f.name == name) else {
+        return Ok(false);
+    };
+    let removed = index.fixtures.remove(at);
+    save_index(workspace, &index)?;
+    prune(workspace, &index, &removed.sha256);
+    Ok(true)
+}
+
+fn prune(workspace: &Path, index: &Index, sha256: &str) {
+    if !index.fixtures.iter().any(|f| f.sha256 == sha256) {
+        let _ = fs::remove_file(object_path(workspace, sha256));
+    }
+}
+
+/// The fixture `name` and the file holding its content.
+pub fn open(workspace: &Path, name: &str) -> Result<(Fixture, PathBuf), String> {
+    let fixture = load_index(workspace)?
+        .fixtures
+        .into_iter()
+        .find(|f| f.name == name)
+        .ok_or_else(|| format!("unknown fixture: {name}"))?;
+    let path = object_path(workspace, &fixture.sha256);
+    if !path.exists() {
+        return Err(format!(
+            "fixture {name} is missing its content ({}); add it again",
+            fixture.sha256
+        ));
+    }
+    Ok((fixture, path))
+}
+
+/// `text` as a path: the fixture's stored file for a `fixture:` reference, otherwise `text`
+/// unchanged.
+pub fn resolve_reference(workspace: &Path, text: &str) -> Result<String, String> {
+    match text.trim().strip_prefix(SCHEME) {
+        Some(name) => open(workspace, name.trim()).map(|(_, path)| path.to_string_lossy().into()),
+        None => Ok(text.to_string()),
+    }
+}
+
+/// Replaces `fixture:` references in a request's file body (`binary.file_path`) and file
+/// form rows (`form_body[].file_path`) with the stored files, filling in the file name and
+/// content type the request leaves out.
+pub fn resolve_request(workspace: &Path, request: &mut Value) -> Result<(), String> {
+    if let Some(binary) = request.get_mut("binary").filter(|b| b.is_object()) {
+        resolve_file(workspace, binary)?;
+    }
+    if let Some(rows) = request.get_mut("form_body").and_then(Value::as_array_mut) {
+        for row in rows.iter_mut().filter(|row| row.is_object()) {
+            resolve_file(workspace, row)?;
+        }
+    }
+    Ok(())
+}
+
+fn resolve_file(workspace: &Path, target: &mut Value) -> Result<(), String> {
+    let Some(name) = target
+        .get("file_path")
+        .and_then(Value::as_str)
+        .and_then(|path| path.trim().strip_prefix(SCHEME))
+        .map(|name| name.trim().to_string())
+    else {
+        return Ok(());
+    };
+    let (fixture, path) = open(workspace, &name)?;
+    target["file_path"] = Value::String(path.to_string_lossy().into());
+    if crate::json::str_of(target, "file_name").is_empty() {
+        target["file_name"] = Value::String(fixture.file_name);
+    }
+    if let Some(mime) = fixture.content_type {
+        if crate::json::str_of(target, "content_type").is_empty() {
+            target["content_type"] = Value::String(mime);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace() -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("litefetch-fixtures-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(dir(&root)).unwrap();
+        root
+    }
+
+    fn source(root: &Path, name: &str, content: &str) -> PathBuf {
+        let path = root.join(name);
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn add_dedupes_content_and_remove_prunes_it() {
+        let root = workspace();
+        let a = add(&root, "a.json", &source(&root, "one.json", "{}")).unwrap();
+        let b = add(&root, "group/b", &source(&root, "two.json", "{}")).unwrap();
+        assert_eq!(a.sha256, b.sha256);
+        assert_eq!(a.content_type.as_deref(), Some("application/json"));
+        let object = object_path(&root, &a.sha256);
+        assert_eq!(open(&root, "group/b").unwrap().1, object);
+        assert_eq!(
+            resolve_reference(&root, "fixture:a.json").unwrap(),
+            object.to_string_lossy()
+        );
+
+        assert!(remove(&root, "a.json").unwrap());
+        assert!(object.exists());
+        assert!(remove(&root, "group/b").unwrap());
+        assert!(!object.exists());
+        assert!(!remove(&root, "group/b").unwrap());
+        assert!(list(&root).unwrap().is_empty());
+        assert!(add(&root, "../x", &source(&root, "x", "")).is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn traversal_checksum_is_rejected() {
+        let root = workspace();
+        let secret = source(&root, "secret", "key");
+        fs::write(
+            dir(&root).join(INDEX_FILE),
+            r#"{"fixtures":[{"name":"foo","sha256":"../../secret","size":3,"added_at_ms":0}]}"#,
+        )
+        .unwrap();
+        assert!(open(&root, "foo").is_err());
+        assert!(resolve_reference(&root, "fixture:foo").is_err());
+        assert!(remove(&root, "foo").is_err());
+        assert!(secret.exists());
+        let _ = fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,754 @@
+//! Pretty-printing and minifying bodies: JSON, XML, HTML, GraphQL and URL-encoded forms.
+//! The formatters work on the text as a token stream rather than a parsed document, so a
+//! 50 MB body is reformatted in one pass without building a tree, keys stay in their order
+//! and numbers as written. Pretty output comes with folding ranges (the lines each object,
+//! array, element or selection set spans) for the viewer.
+
+use serde::{Deserialize, Serialize};
+
+const INDENT: &str = "  ";
+
+/// HTML elements written without a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+/// HTML elements whose content is kept as is.
+const RAW_ELEMENTS: &[&str] = &["script", "style", "pre", "textarea"];
+/// HTML elements a sibling of the same kind closes, e.g. `<li>` without `</li>`.
+const OPTIONAL_END: &[&str] = &["li", "p", "td", "th", "tr", "option", "dt", "dd"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+    Json,
+    Xml,
+    Html,
+    Graphql,
+    Form,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    #[default]
+    Pretty,
+    Minify,
+}
+
+/// Lines `start_line` to `end_line` (from 0) can fold into the first.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Fold {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct Formatted {
+    pub language: Language,
+    pub text: String,
+    /// Ordered by `start_line`; empty for minified text.
+    pub folds: Vec<Fold>,
+}
+
+/// The language of a body, from its content type or, failing that, its first characters.
+pub fn detect(content_type: &str, text: &str) -> Option<Language> {
+    let content_type = content_type.to_ascii_lowercase();
+    let trimmed = text.trim_start();
+    if content_type.contains("json") {
+        Some(Language::Json)
+    } else if content_type.contains("graphql") {
+        Some(Language::Graphql)
+    } else if content_type.contains("x-www-form-urlencoded") {
+        Some(Language::Form)
+    } else if content_type.contains("html") {
+        Some(Language::Html)
+    } else if content_type.contains("xml") {
+        Some(Language::Xml)
+    } else if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        Some(Language::Json)
+    } else if trimmed.starts_with('<') {
+        let head = trimmed
+            .get(..trimmed.len().min(256))
+            .unwrap_or(trimmed)
+            .to_ascii_lowercase();
+        match head.starts_with("<!doctype html") || head.contains("<html") {
+            true => Some(Language::Html),
+            false => Some(Language::Xml),
+        }
+    } else {
+        None
+    }
+}
+
+pub fn format(text: &str, language: Language, mode: Mode) -> Result<Formatted, String> {
+    let pretty = mode == Mode::Pretty;
+    let mut out = Out::new(text.len());
+    match language {
+        Language::Json => json(text, pretty, &mut out)?,
+        Language::Xml => xml(text, pretty, &mut out)?,
+        Language::Html => html(text, pretty, &mut out),
+        Language::Graphql => graphql(text, pretty, &mut out)?,
+        Language::Form => form(text, pretty, &mut out),
+    }
+    out.folds.sort_by_key(|fold| fold.start_line);
+    Ok(Formatted {
+        language,
+        text: out.text,
+        folds: out.folds,
+    })
+}
+
+/// The formatted text, with the line it's on and the folds so far.
+struct Out {
+    text: String,
+    line: usize,
+    folds: Vec<Fold>,
+}
+
+impl Out {
+    fn new(capacity: usize) -> Self {
+        Self {
+            text: String::with_capacity(capacity + capacity / 4),
+            line: 0,
+            folds: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        self.line += text.bytes().filter(|b| *b == b'\n').count();
+        self.text.push_str(text);
+    }
+
+    fn newline(&mut self, depth: usize) {
+        self.text.push('\n');
+        self.line += 1;
+        for _ in 0..depth {
+            self.text.push_str(INDENT);
+        }
+    }
+
+    /// A new line for the next token, unless nothing has been written yet.
+    fn line_break(&mut self, depth: usize) {
+        if !self.text.is_empty() {
+            self.newline(depth);
+        }
+    }
+
+    /// Folds from `start` to the current line.
+    fn fold(&mut self, start: usize) {
+        if self.line > start {
+            self.folds.push(Fold {
+                start_line: start,
+                end_line: self.line,
+            });
+        }
+    }
+}
+
+fn is_json_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+/// The index just past the string starting at `start` (its opening quote).
+fn string_end(bytes: &[u8], start: usize, quote: u8) -> Result<usize, String> {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b if b == quote => return Ok(i + 1),
+            _ => i += 1,
+        }
+    }
+    Err(format!("unterminated string at byte {start}"))
+}
+
+/// What may come next in a JSON text, so tokens out of place are rejected as they're met.
+#[derive(Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    /// A key, or the `}` of an object just opened.
+    Key,
+    Colon,
+    /// A `,` or the closing bracket, after a value in an array or object.
+    Next,
+    /// The top-level value is complete; only whitespace may follow.
+    Done,
+}
+
+fn json(text: &str, pretty: bool, out: &mut Out) -> Result<(), String> {
+    let bytes = text.as_bytes();
+    // The open brackets, with the line each opened on.
+    let mut open: Vec<(u8, usize)> = Vec::new();
+    let mut expect = Expect::Value;
+    let after_value = |open: &Vec<(u8, usize)>| match open.is_empty() {
+        true => Expect::Done,
+        false => Expect::Next,
+    };
+    let unexpected = |i: usize| match bytes[i] {
+        b if b.is_ascii_graphic() => format!("unexpected {} at byte {i}", b as char),
+        _ => format!("unexpected character at byte {i}"),
+    };
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        if is_json_space(b) {
+            i += 1;
+            continue;
+        }
+        match (b, expect) {
+            (_, Expect::Done) => {
+                return Err(format!("unexpected text after the JSON value at byte {i}"))
+            }
+            (b'"', Expect::Value | Expect::Key) => {
+                let end = string_end(bytes, i, b'"')?;
+                out.push(&text[i..end]);
+                expect = match expect {
+                    Expect::Key => Expect::Colon,
+                    _ => after_value(&open),
+                };
+                i = end;
+                continue;
+            }
+            (b'{' | b'[', Expect::Value) => {
+                let close = if b == b'{' { b'}' } else { b']' };
+                let mut next = i + 1;
+                while next < bytes.len() && is_json_space(bytes[next]) {
+                    next += 1;
+                }
+                if bytes.get(next) == Some(&close) {
+                    out.push(&text[i..i + 1]);
+                    out.push(&text[next..next + 1]);
+                    expect = after_value(&open);
+                    i = next + 1;
+                    continue;
+                }
+                out.push(&text[i..i + 1]);
+                open.push((close, out.line));
+                expect = match close {
+                    b'}' => Expect::Key,
+                    _ => Expect::Value,
+                };
+                if pretty {
+                    out.newline(open.len());
+                }
+            }
+            (b'}' | b']', Expect::Next) => {
+                let (close, start) = open.pop().ok_or_else(|| unexpected(i))?;
+                if close != b {
+                    return Err(unexpected(i));
+                }
+                if pretty {
+                    out.newline(open.len());
+                    out.fold(start);
+                }
+                out.push(&text[i..i + 1]);
+                expect = after_value(&open);
+            }
+            (b',', Expect::Next) => {
+                out.push(",");
+                expect = match open.last() {
+                    Some((b'}', _)) => Expect::Key,
+                    _ => Expect::Value,
+                };
+                if pretty {
+                    out.newline(open.len());
+                }
+            }
+            (b':', Expect::Colon) => {
+                out.push(if pretty { ": " } else { ":" });
+                expect = Expect::Value;
+            }
+            (b'"' | b'{' | b'[' | b'}' | b']' | b',' | b':', _) => return Err(unexpected(i)),
+            (_, Expect::Value) => {
+                // Numbers, true, false and null run to the next delimiter.
+                let start = i;
+                while i < bytes.len()
+                    && !is_json_space(bytes[i])
+                    && !matches!(bytes[i], b'"' | b'{' | b'[' | b'}' | b']' | b',' | b':')
+                {
+                    i += 1;
+                }
+                let scalar = &text[start..i];
+                if !matches!(scalar, "true" | "false" | "null")
+                    && serde_json::from_str::<serde_json::Number>(scalar).is_err()
+                {
+                    return Err(format!("invalid JSON value at byte {start}: {scalar}"));
+                }
+                out.push(scalar);
+                expect = after_value(&open);
+                continue;
+            }
+            _ => return Err(unexpected(i)),
+        }
+        i += 1;
+    }
+    match expect {
+        Expect::Done => Ok(()),
+        _ => Err("unexpected end of JSON".to_string()),
+    }
+}
+
+/// Folds for markup written one tag per line: from a start tag's line to its end tag's.
+fn markup_folds(text: &str) -> Vec<Fold> {
+    let mut open = Vec::new();
+    let mut folds = Vec::new();
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim_start();
+        if line.starts_with("</") {
+            if let Some(start) = open.pop() {
+                if number > start {
+                    folds.push(Fold {
+                        start_line: start,
+                        end_line: number,
+                    });
+                }
+            }
+        } else if line.starts_with('<')
+            && !line.starts_with("<?")
+            && !line.starts_with("<!")
+            && !line.ends_with("/>")
+            && !line.contains("</")
+        {
+            open.push(number);
+        }
+    }
+    folds
+}
+
+fn xml(text: &str, pretty: bool, out: &mut Out) -> Result<(), String> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    let mut writer = match pretty {
+        true => quick_xml::Writer::new_with_indent(Vec::new(), b' ', INDENT.len()),
+        false => quick_xml::Writer::new(Vec::new()),
+    };
+    loop {
+        match reader.read_event() {
+            Ok(Event::Eof) => break,
+            Ok(event) => writer
+                .write_event(event)
+                .map_err(|e| format!("XML format failed: {e}"))?,
+            Err(e) => return Err(format!("XML format failed: {e}")),
+        }
+    }
+    let formatted =
+        String::from_utf8(writer.into_inner()).map_err(|e| format!("XML format failed: {e}"))?;
+    if pretty {
+        out.folds = markup_folds(&formatted);
+    }
+    out.push(&formatted);
+    Ok(())
+}
+
+/// The index just past the tag starting at `start`, quoted `>`s skipped.
+fn tag_end(bytes: &[u8], start: usize) -> usize {
+    let mut quote = None;
+    for (i, b) in bytes.iter().enumerate().skip(start + 1) {
+        match (quote, *b) {
+            (None, b'"' | b'\'') => quote = Some(*b),
+            (Some(q), b) if b == q => quote = None,
+            (None, b'>') => return i + 1,
+            _ => {}
+        }
+    }
+    bytes.len()
+}
+
+fn tag_name(tag: &str) -> String {
+    tag.trim_start_matches(['<', '/'])
+        .split(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Whether `<` at `i` starts a tag rather than being text (`a < b`).
+fn starts_tag(bytes: &[u8], i: usize) -> bool {
+    bytes[i] == b'<'
+        && bytes
+            .get(i + 1)
+            .is_some_and(|b| b.is_ascii_alphabetic() || matches!(b, b'/' | b'!' | b'?'))
+}
+
+fn html(text: &str, pretty: bool, out: &mut Out) {
+    let bytes = text.as_bytes();
+    // Open elements, with the line each started on.
+    let mut open: Vec<(String, usize)> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if text[i..].starts_with("<!--") {
+            let end = text[i..].find("-->").map_or(bytes.len(), |end| i + end + 3);
+            if pretty {
+                out.line_break(open.len());
+            }
+            out.push(&text[i..end]);
+            i = end;
+            continue;
+        }
+        if starts_tag(bytes, i) {
+            let end = tag_end(bytes, i);
+            let tag = &text[i..end];
+            let name = tag_name(tag);
+            if tag.starts_with("</") {
+                // Elements left open inside (`<li>` without `</li>`) end here too.
+                let index = open.iter().rposition(|(open, _)| *open == name);
+                let starts: Vec<usize> = match index {
+                    Some(index) => open.drain(index..).map(|(_, start)| start).collect(),
+                    None => Vec::new(),
+                };
+                if pretty {
+                    out.line_break(open.len());
+                    for start in starts {
+                        out.fold(start);
+                    }
+                }
+                out.push(tag);
+                i = end;
+                continue;
+            }
+            if OPTIONAL_END.contains(&name.as_str())
+                && open.last().is_some_and(|(last, _)| *last == name)
+            {
+                if let Some((_, start)) = open.pop() {
+                    out.fold(start);
+                }
+            }
+            if pretty {
+                out.line_break(open.len());
+            }
+            out.push(tag);
+            i = end;
+            let element = !tag.starts_with("<!") && !tag.starts_with("<?");
+            if !element || tag.ends_with("/>") || VOID_ELEMENTS.contains(&name.as_str()) {
+                continue;
+            }
+            if RAW_ELEMENTS.contains(&name.as_str()) {
+                let closing = format!("</{name}");
+                let content_end = text[i..]
+                    .to_ascii_lowercase()
+                    .find(&closing)
+                    .map_or(bytes.len(), |at| i + at);
+                out.push(&text[i..content_end]);
+                i = content_end;
+                if i < bytes.len() {
+                    let end = tag_end(bytes, i);
+                    out.push(&text[i..end]);
+                    i = end;
+                }
+                continue;
+            }
+            open.push((name, out.line));
+            continue;
+        }
+        let mut end = i + 1;
+        while end < bytes.len() && !starts_tag(bytes, end) {
+            end += 1;
+        }
+        let raw = &text[i..end];
+        let words = raw.split_whitespace().collect::<Vec<_>>().join(" ");
+        if pretty {
+            if !words.is_empty() {
+                out.line_break(open.len());
+                out.push(&words);
+            }
+        } else if words.is_empty() {
+            // Spacing between inline elements matters; indentation doesn't.
+            if !raw.is_empty() && !raw.contains('\n') {
+                out.push(" ");
+            }
+        } else {
+            let lead = raw.starts_with(char::is_whitespace);
+            let trail = raw.ends_with(char::is_whitespace);
+            out.push(if lead { " " } else { "" });
+            out.push(&words);
+            out.push(if trail { " " } else { "" });
+        }
+        i = end;
+    }
+    for (_, start) in open.into_iter().rev() {
+        out.fold(start);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Token<'a> {
+    /// Names, numbers, variables (`$id`) and directives (`@include`).
+    Word(&'a str),
+    Str(&'a str),
+    Comment(&'a str),
+    Spread,
+    Punct(char),
+}
+
+fn graphql_tokens(text: &str) -> Result<Vec<Token<'_>>, String> {
+    let bytes = text.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        match b {
+            b' ' | b'\t' | b'\n' | b'\r' => i += 1,
+            b'#' => {
+                let end = text[i..].find('\n').map_or(bytes.len(), |end| i + end);
+                tokens.push(Token::Comment(text[i..end].trim_end()));
+                i = end;
+            }
+            b'"' if text[i..].starts_with("\"\"\"") => {
+                let end = text[i + 3..]
+                    .find("\"\"\"")
+                    .map(|end| i + 3 + end + 3)
+                    .ok_or_else(|| format!("unterminated block string at byte {i}"))?;
+                tokens.push(Token::Str(&text[i..end]));
+                i = end;
+            }
+            b'"' => {
+                let end = string_end(bytes, i, b'"')?;
+                tokens.push(Token::Str(&text[i..end]));
+                i = end;
+            }
+            b'.' if text[i..].starts_with("...") => {
+                tokens.push(Token::Spread);
+                i += 3;
+            }
+            b'{' | b'}' | b'(' | b')' | b'[' | b']' | b':' | b'=' | b'!' | b'|' | b'&' | b',' => {
+                tokens.push(Token::Punct(b as char));
+                i += 1;
+            }
+            _ => {
+                let start = i;
+                i += 1;
+                while i < bytes.len()
+                    && (bytes[i].is_ascii_alphanumeric()
+                        || matches!(bytes[i], b'_' | b'-' | b'+' | b'.')
+                        || bytes[i] >= 0x80)
+                    && !text[i..].starts_with("...")
+                {
+                    i += 1;
+                }
+                tokens.push(Token::Word(&text[start..i]));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn graphql(text: &str, pretty: bool, out: &mut Out) -> Result<(), String> {
+    let tokens = graphql_tokens(text)?;
+    // Open selection sets, with the line each opened on.
+    let mut open: Vec<usize> = Vec::new();
+    let mut parens = 0usize;
+    let mut prev: Option<Token> = None;
+    let mut prev2: Option<Token> = None;
+    // After a top-level `}` the next definition gets a blank line; after `{` or a comment,
+    // a line of its own.
+    let mut blank_line = false;
+    let mut own_line = false;
+    for token in tokens {
+        let depth = open.len();
+        let valued = matches!(
+            prev,
+            Some(Token::Word(_) | Token::Str(_) | Token::Punct('}' | ')' | ']' | '!'))
+        );
+        match token {
+            Token::Comment(comment) => {
+                if pretty {
+                    out.line_break(depth);
+                    out.push(comment);
+                    own_line = true;
+                }
+                continue;
+            }
+            Token::Punct('{') => {
+                out.push(if pretty && valued { " {" } else { "{" });
+                open.push(out.line);
+                own_line = pretty;
+            }
+            Token::Punct('}') => {
+                let start = open.pop().ok_or("unexpected }")?;
+                if pretty {
+                    out.newline(open.len());
+                    out.fold(start);
+                }
+                out.push("}");
+                blank_line = pretty && open.is_empty() && parens == 0;
+                own_line = false;
+            }
+            Token::Punct(',') => {
+                if pretty && parens > 0 {
+                    out.push(",");
+                }
+                // Insignificant elsewhere, and between selections.
+                continue;
+            }
+            Token::Punct(c @ ('(' | ')' | '[' | ']' | '!')) => {
+                if c == '(' {
+                    parens += 1;
+                } else if c == ')' {
+                    parens = parens.saturating_sub(1);
+                }
+                if pretty && c == '[' && matches!(prev, Some(Token::Punct('=' | '|' | '&'))) {
+                    out.push(" ");
+                }
+                out.push(&c.to_string());
+            }
+            Token::Punct(':') => out.push(if pretty { ": " } else { ":" }),
+            Token::Punct(c) => match pretty {
+                true => out.push(&format!(" {c} ")),
+                false => out.push(&c.to_string()),
+            },
+            Token::Word(_) | Token::Str(_) | Token::Spread => {
+                let directive = matches!(token, Token::Word(w) if w.starts_with('@'));
+                let fragment_type = prev == Some(Token::Word("on")) && prev2 == Some(Token::Spread);
+                if pretty {
+                    if blank_line {
+                        out.text.push_str("\n\n");
+                        out.line += 2;
+                    } else if own_line
+                        || (depth > 0 && parens == 0 && valued && !directive && !fragment_type)
+                    {
+                        out.newline(depth);
+                    } else if valued || (prev == Some(Token::Spread) && token == Token::Word("on"))
+                    {
+                        out.push(" ");
+                    }
+                } else if matches!(prev, Some(Token::Word(_)))
+                    && matches!(token, Token::Word(_) | Token::Str(_))
+                {
+                    out.push(" ");
+                }
+                match token {
+                    Token::Word(word) | Token::Str(word) => out.push(word),
+                    _ => out.push("..."),
+                }
+                blank_line = false;
+                own_line = false;
+            }
+        }
+        prev2 = prev;
+        prev = Some(token);
+    }
+    match open.is_empty() {
+        true => Ok(()),
+        false => Err("unexpected end of GraphQL document".to_string()),
+    }
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = || u8::from_str_radix(text.get(i + 1..i + 3)?, 16).ok();
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' if hex().is_some() => {
+                decoded.extend(hex());
+                i += 2;
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for b in text.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(b as char)
+            }
+            b' ' => encoded.push('+'),
+            _ => encoded.push_str(&format!("%{b:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Pretty forms are one decoded `key = value` per line; minifying encodes such lines back
+/// (a form already on one line stays as it is).
+fn form(text: &str, pretty: bool, out: &mut Out) {
+    let text = text.trim();
+    if pretty {
+        let mut first = true;
+        for pair in text.split('&').filter(|p| !p.is_empty()) {
+            if !first {
+                out.newline(0);
+            }
+            first = false;
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let line = format!("{} = {}", percent_decode(key), percent_decode(value));
+            out.push(line.trim_end());
+        }
+    } else if !text.contains('\n') {
+        out.push(text);
+    } else {
+        let pairs: Vec<String> = text
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (key, value) = line
+                    .split_once(" = ")
+                    .or_else(|| line.split_once('='))
+                    .unwrap_or((line, ""));
+                format!(
+                    "{}={}",
+                    percent_encode(key.trim()),
+                    percent_encode(value.trim())
+                )
+            })
+            .collect();
+        out.push(&pairs.join("&"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pretty(text: &str) -> Result<String, String> {
+        format(text, Language::Json, Mode::Pretty).map(|f| f.text)
+    }
+
+    #[test]
+    fn formats_valid_json() {
+        assert_eq!(
+            pretty(r#"{"a":1,"b":[true,null,"x"],"c":{}}"#).unwrap(),
+            "{\n  \"a\": 1,\n  \"b\": [\n    true,\n    null,\n    \"x\"\n  ],\n  \"c\": {}\n}"
+        );
+        assert_eq!(pretty(" 1.5e3 ").unwrap(), "1.5e3");
+        assert_eq!(
+            format("{ \"a\" : [ 1 , 2 ] }", Language::Json, Mode::Minify)
+                .unwrap()
+                .text,
+            r#"{"a":[1,2]}"#
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_json() {
+        for text in [
+            r#"{"a":1} trailing"#,
+            r#"{"a":1}{"b":2}"#,
+            r#"{"a" 1}"#,
+            r#"{"a":1 "b":2}"#,
+            r#"[1 2]"#,
+            r#"[1,]"#,
+            r#"{"a":1,}"#,
+            r#"{1:2}"#,
+            r#"{"a":}"#,
+            r#"[tru]"#,
+            r#"[1,,2]"#,
+            r#"{"a":1]"#,
+            r#"{"a":1"#,
+            "",
+        ] {
+            assert!(pretty(text).is_err(), "{text:?} should not format");
+        }
+    }
+}
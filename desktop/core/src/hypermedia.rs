@@ -0,0 +1,254 @@
+//! Links out of a hypermedia response, for click-through exploration: HAL `_links`, OData
+//! annotations (`@odata.nextLink`, `@odata.deltaLink`, the `@odata.context` metadata
+//! document, `@odata.id`/`@odata.editLink` and `<property>@odata.navigationLink`, with the
+//! `odata.`-prefixed v3 forms and `__next`) and the RFC 8288 `Link` header. Relative targets
+//! are resolved against the URL the response came from.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+#[derive(Serialize, Clone)]
+pub struct Link {
+    pub rel: String,
+    /// Absolute, or as written when it couldn't be resolved; templated links still have
+    /// their `{…}` expressions (see `expand`).
+    pub href: String,
+    pub templated: bool,
+    pub title: Option<String>,
+    /// `hal`, `odata` or `header`.
+    pub source: &'static str,
+}
+
+fn resolve(base: &str, href: &str) -> String {
+    reqwest::Url::parse(base)
+        .and_then(|base| base.join(href))
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| href.to_string())
+}
+
+fn hal_links(links: &Map<String, Value>, base: &str, out: &mut Vec<Link>) {
+    for (rel, value) in links {
+        // CURIE definitions document rels; they aren't links to follow.
+        if rel == "curies" {
+            continue;
+        }
+        let entries = match value {
+            Value::Array(items) => items.iter().collect(),
+            other => vec![other],
+        };
+        for entry in entries {
+            let Some(href) = entry.get("href").and_then(Value::as_str) else {
+                continue;
+            };
+            let templated = entry.get("templated").and_then(Value::as_bool) == Some(true);
+            out.push(Link {
+                rel: rel.clone(),
+                // A template's braces don't survive URL parsing; it's resolved once expanded.
+                href: match templated {
+                    true => href.to_string(),
+                    false => resolve(base, href),
+                },
+                templated,
+                title: entry
+                    .get("title")
+                    .or_else(|| entry.get("name"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                source: "hal",
+            });
+        }
+    }
+}
+
+fn odata_links(body: &Map<String, Value>, base: &str, out: &mut Vec<Link>) {
+    let mut push = |rel: &str, href: &str, title: Option<String>| {
+        out.push(Link {
+            rel: rel.to_string(),
+            href: resolve(base, href),
+            templated: false,
+            title,
+            source: "odata",
+        });
+    };
+    for (key, value) in body {
+        let Some(href) = value.as_str() else {
+            continue;
+        };
+        let annotation = key
+            .split_once("@odata.")
+            .or_else(|| key.split_once("@odata"))
+            .map(|(property, term)| (property, term.trim_start_matches('.')))
+            .or_else(|| key.strip_prefix("odata.").map(|term| ("", term)));
+        let Some((property, term)) = annotation else {
+            if key == "__next" {
+                push("next", href, None);
+            }
+            continue;
+        };
+        match (property, term) {
+            ("", "nextLink") => push("next", href, None),
+            ("", "deltaLink") => push("delta", href, None),
+            ("", "id") => push("self", href, None),
+            ("", "editLink") => push("edit", href, None),
+            ("", "context" | "metadata") => {
+                // `$metadata#Customers` names the set; the document is `$metadata` itself.
+                let document = href.split('#').next().unwrap_or(href);
+                push("metadata", document, Some(href.to_string()));
+            }
+            (property, "navigationLink" | "associationLink" | "nextLink")
+                if !property.is_empty() =>
+            {
+                push(property, href, Some(term.to_string()));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Every `<…>; rel="…"` target of `Link` headers, one link per rel.
+pub fn header_links(value: &str, base: &str) -> Vec<Link> {
+    let mut out = Vec::new();
+    for part in value.split(',') {
+        let Some((target, params)) = part.split_once(';') else {
+            continue;
+        };
+        let href = target.trim().trim_start_matches('<').trim_end_matches('>');
+        let mut rels = Vec::new();
+        let mut title = None;
+        for param in params.split(';') {
+            let Some((name, raw)) = param.trim().split_once('=') else {
+                continue;
+            };
+            let raw = raw.trim().trim_matches('"');
+            match name.trim().to_ascii_lowercase().as_str() {
+                "rel" => rels.extend(raw.split_whitespace().map(str::to_string)),
+                "title" => title = Some(raw.to_string()),
+                _ => {}
+            }
+        }
+        for rel in rels {
+            out.push(Link {
+                rel,
+                href: resolve(base, href),
+                templated: false,
+                title: title.clone(),
+                source: "header",
+            });
+        }
+    }
+    out
+}
+
+/// The links of a response, as a backend `RequestResult` carries it: its body, headers and
+/// `sent_request.url` as the base.
+pub fn links(result: &Value) -> Vec<Link> {
+    let base = result
+        .pointer("/sent_request/url")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let mut out = Vec::new();
+    if let Value::Object(body) = crate::schema::body_of(result) {
+        if let Some(links) = body.get("_links").and_then(Value::as_object) {
+            hal_links(links, base, &mut out);
+        }
+        odata_links(&body, base, &mut out);
+        // OData v2 nests the payload under `d`.
+        if let Some(Value::Object(d)) = body.get("d") {
+            odata_links(d, base, &mut out);
+        }
+    }
+    if let Some(headers) = result.get("headers").and_then(Value::as_object) {
+        for (name, value) in headers {
+            if let (true, Some(value)) = (name.eq_ignore_ascii_case("link"), value.as_str()) {
+                out.extend(header_links(value, base));
+            }
+        }
+    }
+    out
+}
+
+fn encode(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Expands an RFC 6570 template with the common operators (`{x}`, `{/x}`, `{?x,y}`,
+/// `{&x}`, `{#x}`); variables without a value are left out.
+pub fn expand(template: &str, variables: &Map<String, Value>) -> String {
+    let value_of = |name: &str| -> Option<String> {
+        match variables.get(name)? {
+            Value::Null => None,
+            Value::String(text) => Some(text.clone()),
+            other => Some(other.to_string()),
+        }
+    };
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        let expression = &rest[start + 1..start + len];
+        rest = &rest[start + len + 1..];
+        let (operator, names) = match expression.chars().next() {
+            Some(op @ ('/' | '?' | '&' | '#' | '+' | '.')) => (Some(op), &expression[1..]),
+            _ => (None, expression),
+        };
+        let present: Vec<(&str, String)> = names
+            .split(',')
+            .map(|name| name.trim().trim_end_matches('*'))
+            .filter_map(|name| value_of(name).map(|value| (name, value)))
+            .collect();
+        if present.is_empty() {
+            continue;
+        }
+        match operator {
+            Some('?' | '&') => {
+                let pairs: Vec<String> = present
+                    .iter()
+                    .map(|(name, value)| format!("{name}={}", encode(value)))
+                    .collect();
+                out.push(operator.unwrap_or('?'));
+                out.push_str(&pairs.join("&"));
+            }
+            Some('/') => present.iter().for_each(|(_, value)| {
+                out.push('/');
+                out.push_str(&encode(value));
+            }),
+            Some('.') => present.iter().for_each(|(_, value)| {
+                out.push('.');
+                out.push_str(&encode(value));
+            }),
+            Some(op @ ('#' | '+')) => {
+                if op == '#' {
+                    out.push('#');
+                }
+                let values: Vec<&str> = present.iter().map(|(_, value)| value.as_str()).collect();
+                out.push_str(&values.join(","));
+            }
+            _ => {
+                let values: Vec<String> = present.iter().map(|(_, value)| encode(value)).collect();
+                out.push_str(&values.join(","));
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A templated link made concrete and resolved against `base`.
+pub fn expand_link(link: &Link, variables: &Map<String, Value>, base: &str) -> String {
+    match link.templated {
+        true => resolve(base, &expand(&link.href, variables)),
+        false => link.href.clone(),
+    }
+}
@@ -0,0 +1,661 @@
+//! Bruno file-based collections: a directory with `bruno.json`, one `.bru` file per request,
+//! `folder.bru`/`collection.bru` for folder and collection settings, and `environments/*.bru`.
+//! `read_collection` reads that layout into the intermediate model.
+
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+use super::{
+    str_of, FormRow, ImportReport, ImportedAuth, ImportedBody, ImportedCollection,
+    ImportedEnvironment, ImportedItem, ImportedRequest, ImportedVariable,
+};
+
+const METHODS: &[&str] = &[
+    "get", "post", "put", "delete", "patch", "head", "options", "connect", "trace",
+];
+
+enum Block {
+    /// `key: value` rows; a leading `~` disables the row.
+    Dict(Vec<(String, String, bool)>),
+    List(Vec<String>),
+    Text(String),
+}
+
+fn is_text_block(name: &str) -> bool {
+    (name.starts_with("body")
+        && !matches!(
+            name,
+            "body:form-urlencoded" | "body:multipart-form" | "body:file"
+        ))
+        || name.starts_with("script")
+        || name == "tests"
+        || name == "docs"
+}
+
+fn parse_bru(text: &str) -> Vec<(String, Block)> {
+    let mut blocks = Vec::new();
+    let mut lines = text.lines();
+    while let Some(line) = lines.next() {
+        let line = line.trim_end();
+        let (name, list) = if let Some(name) = line.strip_suffix('{') {
+            (name.trim(), false)
+        } else if let Some(name) = line.strip_suffix('[') {
+            (name.trim(), true)
+        } else {
+            continue;
+        };
+        if name.is_empty() || line.starts_with(char::is_whitespace) {
+            continue;
+        }
+        let close = if list { "]" } else { "}" };
+        let body: Vec<&str> = lines
+            .by_ref()
+            .take_while(|l| l.trim_end() != close)
+            .collect();
+        let block = if list {
+            Block::List(
+                body.iter()
+                    .map(|l| l.trim().trim_end_matches(',').to_string())
+                    .filter(|l| !l.is_empty())
+                    .collect(),
+            )
+        } else if is_text_block(name) {
+            Block::Text(
+                body.iter()
+                    .map(|l| l.strip_prefix("  ").unwrap_or(l))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            )
+        } else {
+            Block::Dict(
+                body.iter()
+                    .filter_map(|l| {
+                        let l = l.trim();
+                        let (l, enabled) = match l.strip_prefix('~') {
+                            Some(rest) => (rest, false),
+                            None => (l, true),
+                        };
+                        let (key, value) = l.split_once(':')?;
+                        Some((key.trim().to_string(), value.trim().to_string(), enabled))
+                    })
+                    .collect(),
+            )
+        };
+        blocks.push((name.to_string(), block));
+    }
+    blocks
+}
+
+struct BruFile {
+    blocks: Vec<(String, Block)>,
+}
+
+impl BruFile {
+    fn read(path: &Path) -> Result<Self, String> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| format!("Bruno read failed for {}: {e}", path.display()))?;
+        Ok(Self {
+            blocks: parse_bru(&text),
+        })
+    }
+
+    fn dict(&self, name: &str) -> &[(String, String, bool)] {
+        self.blocks
+            .iter()
+            .find_map(|(n, b)| match b {
+                Block::Dict(rows) if n == name => Some(rows.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn value(&self, block: &str, key: &str) -> Option<&str> {
+        self.dict(block)
+            .iter()
+            .find(|(k, _, enabled)| k == key && *enabled)
+            .map(|(_, v, _)| v.as_str())
+    }
+
+    fn text(&self, name: &str) -> Option<&str> {
+        self.blocks.iter().find_map(|(n, b)| match b {
+            Block::Text(text) if n == name => Some(text.as_str()),
+            _ => None,
+        })
+    }
+
+    fn list(&self, name: &str) -> &[String] {
+        self.blocks
+            .iter()
+            .find_map(|(n, b)| match b {
+                Block::List(items) if n == name => Some(items.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.blocks.iter().any(|(n, _)| n == name)
+    }
+
+    fn seq(&self) -> f64 {
+        self.value("meta", "seq")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(f64::MAX)
+    }
+}
+
+/// Resolves an `auth:<mode>` block; `inherit` (and a missing mode) takes the parent's auth.
+fn auth_of(
+    file: &BruFile,
+    mode: &str,
+    parent: &ImportedAuth,
+    request: &mut ImportedRequest,
+    report: &mut ImportReport,
+) -> ImportedAuth {
+    match mode {
+        "" | "inherit" => parent.clone(),
+        "none" => ImportedAuth::None,
+        "basic" => ImportedAuth::Basic {
+            username: file
+                .value("auth:basic", "username")
+                .unwrap_or_default()
+                .to_string(),
+            password: file
+                .value("auth:basic", "password")
+                .unwrap_or_default()
+                .to_string(),
+        },
+        "bearer" => ImportedAuth::Bearer {
+            token: file
+                .value("auth:bearer", "token")
+                .unwrap_or_default()
+                .to_string(),
+        },
+        "apikey" => {
+            let key = file
+                .value("auth:apikey", "key")
+                .unwrap_or_default()
+                .to_string();
+            let value = file
+                .value("auth:apikey", "value")
+                .unwrap_or_default()
+                .to_string();
+            if file.value("auth:apikey", "placement") == Some("queryparams") {
+                request.query_params.push(FormRow::text(key, value, true));
+            } else {
+                request.headers.push((key, value));
+            }
+            ImportedAuth::None
+        }
+        other => {
+            report.unsupported(format!(
+                "{other} auth is not supported; requests imported without auth"
+            ));
+            ImportedAuth::None
+        }
+    }
+}
+
+fn file_ref(value: &str) -> Option<String> {
+    let inner = value.strip_prefix("@file(")?;
+    let end = inner.find(')')?;
+    // Multi-file fields are `@file(a|b)`; LiteFetch sends one file per field.
+    inner[..end].split('|').next().map(str::to_string)
+}
+
+fn body_of(file: &BruFile, mode: &str, report: &mut ImportReport) -> ImportedBody {
+    let raw = |name: &str, json: bool| match file.text(name) {
+        Some(text) if !text.trim().is_empty() => ImportedBody::Raw {
+            text: text.to_string(),
+            json,
+        },
+        _ => ImportedBody::None,
+    };
+    let rows = |name: &str| {
+        file.dict(name)
+            .iter()
+            .map(|(key, value, enabled)| match file_ref(value) {
+                Some(path) => FormRow {
+                    key: key.clone(),
+                    value: path.clone(),
+                    enabled: *enabled,
+                    file_path: Some(path),
+                },
+                None => FormRow::text(key.clone(), value.clone(), *enabled),
+            })
+            .collect()
+    };
+    match mode {
+        "json" => raw("body:json", true),
+        "text" => raw("body:text", false),
+        "xml" => raw("body:xml", false),
+        "sparql" => raw("body:sparql", false),
+        "formUrlEncoded" => ImportedBody::UrlEncoded(rows("body:form-urlencoded")),
+        "multipartForm" => ImportedBody::FormData(rows("body:multipart-form")),
+        "file" => file
+            .dict("body:file")
+            .iter()
+            .filter(|(_, _, enabled)| *enabled)
+            .find_map(|(_, value, _)| file_ref(value))
+            .map(|file_path| ImportedBody::Binary { file_path })
+            .unwrap_or(ImportedBody::None),
+        "graphql" => {
+            report.unsupported("GraphQL bodies are imported as raw JSON payloads");
+            let variables = file
+                .text("body:graphql:vars")
+                .and_then(|v| serde_json::from_str::<Value>(v).ok())
+                .unwrap_or(Value::Null);
+            let payload = json!({
+                "query": file.text("body:graphql").unwrap_or_default(),
+                "variables": variables,
+            });
+            ImportedBody::Raw {
+                text: serde_json::to_string_pretty(&payload).unwrap_or_default(),
+                json: true,
+            }
+        }
+        _ => ImportedBody::None,
+    }
+}
+
+fn read_request(
+    file: &BruFile,
+    fallback_name: &str,
+    parent_auth: &ImportedAuth,
+    report: &mut ImportReport,
+) -> Option<ImportedRequest> {
+    let method = METHODS.iter().find(|m| file.has(m))?;
+    let name = file.value("meta", "name").unwrap_or(fallback_name);
+    let mut url = file.value(method, "url").unwrap_or_default().to_string();
+    for (key, value, _) in file.dict("params:path") {
+        url = url.replace(&format!("/:{key}"), &format!("/{value}"));
+    }
+    let mut request = ImportedRequest::new(name, method.to_uppercase(), url);
+    request.description = file.text("docs").map(str::to_string);
+    request.query_params = file
+        .dict("params:query")
+        .iter()
+        .map(|(k, v, enabled)| FormRow::text(k.clone(), v.clone(), *enabled))
+        .collect();
+    for (key, value, enabled) in file.dict("headers") {
+        if *enabled {
+            request.headers.push((key.clone(), value.clone()));
+        }
+    }
+    let mode = file.value(method, "body").unwrap_or("none");
+    request.body = body_of(file, mode, report);
+    let auth_mode = file.value(method, "auth").unwrap_or("inherit").to_string();
+    request.auth = auth_of(file, &auth_mode, parent_auth, &mut request, report);
+    request.extract_rules = file
+        .dict("vars:post-response")
+        .iter()
+        .filter(|(_, _, enabled)| *enabled)
+        .filter_map(|(target, expr, _)| {
+            let path = expr
+                .strip_prefix("res.body.")
+                .or_else(|| expr.strip_prefix("res.body?."))?;
+            Some((path.replace("?.", "."), target.clone()))
+        })
+        .collect();
+    if file.dict("vars:post-response").len() > request.extract_rules.len() {
+        report.unsupported("post-response vars other than res.body paths are not imported");
+    }
+    if file.has("vars:pre-request") {
+        report.unsupported("request-level vars are not imported");
+    }
+    if file.has("script:pre-request") || file.has("script:post-response") {
+        report.unsupported("scripts are not imported");
+    }
+    if file.has("tests") {
+        report.unsupported("tests are not imported");
+    }
+    if file.has("assert") {
+        report.unsupported("assertions are not imported");
+    }
+    Some(request)
+}
+
+fn folder_auth(
+    file: Option<&BruFile>,
+    parent: &ImportedAuth,
+    report: &mut ImportReport,
+) -> ImportedAuth {
+    let Some(file) = file else {
+        return parent.clone();
+    };
+    let mode = file.value("auth", "mode").unwrap_or("inherit").to_string();
+    let mut scratch = ImportedRequest::new("", "GET", "");
+    let auth = auth_of(file, &mode, parent, &mut scratch, report);
+    if !scratch.headers.is_empty() || !scratch.query_params.is_empty() {
+        report.unsupported("folder-level API key auth is not inherited by requests");
+    }
+    if !file.dict("headers").is_empty() {
+        report.unsupported("folder and collection headers are not inherited by requests");
+    }
+    auth
+}
+
+fn read_dir(
+    dir: &Path,
+    auth: &ImportedAuth,
+    report: &mut ImportReport,
+) -> Result<Vec<ImportedItem>, String> {
+    let mut entries: Vec<(f64, String, ImportedItem)> = Vec::new();
+    let listing =
+        fs::read_dir(dir).map_err(|e| format!("Bruno read failed for {}: {e}", dir.display()))?;
+    for entry in listing.flatten() {
+        let path = entry.path();
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.starts_with('.') || file_name == "node_modules" {
+            continue;
+        }
+        if path.is_dir() {
+            if file_name == "environments" && dir.join("bruno.json").exists() {
+                continue;
+            }
+            let folder_file = Some(path.join("folder.bru"))
+                .filter(|p| p.exists())
+                .map(|p| BruFile::read(&p))
+                .transpose()?;
+            let auth = folder_auth(folder_file.as_ref(), auth, report);
+            let items = read_dir(&path, &auth, report)?;
+            let seq = folder_file.as_ref().map(BruFile::seq).unwrap_or(f64::MAX);
+            let name = folder_file
+                .as_ref()
+                .and_then(|f| f.value("meta", "name"))
+                .unwrap_or(&file_name)
+                .to_string();
+            entries.push((seq, name.clone(), ImportedItem::Folder { name, items }));
+        } else if file_name.ends_with(".bru")
+            && file_name != "folder.bru"
+            && file_name != "collection.bru"
+        {
+            let file = BruFile::read(&path)?;
+            let stem = file_name.trim_end_matches(".bru");
+            if file
+                .value("meta", "type")
+                .is_some_and(|t| t != "http" && t != "graphql")
+            {
+                report.unsupported(format!(
+                    "{} requests are not imported",
+                    file.value("meta", "type").unwrap_or_default()
+                ));
+                continue;
+            }
+            if let Some(request) = read_request(&file, stem, auth, report) {
+                entries.push((
+                    file.seq(),
+                    request.name.clone(),
+                    ImportedItem::Request(Box::new(request)),
+                ));
+            }
+        }
+    }
+    entries.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+    Ok(entries.into_iter().map(|(_, _, item)| item).collect())
+}
+
+fn read_environments(root: &Path) -> Result<Vec<ImportedEnvironment>, String> {
+    let Ok(listing) = fs::read_dir(root.join("environments")) else {
+        return Ok(Vec::new());
+    };
+    let mut environments = Vec::new();
+    for entry in listing.flatten() {
+        let path = entry.path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_suffix(".bru"))
+        else {
+            continue;
+        };
+        let file = BruFile::read(&path)?;
+        let mut variables: Vec<ImportedVariable> = file
+            .dict("vars")
+            .iter()
+            .filter(|(_, _, enabled)| *enabled)
+            .map(|(key, value, _)| ImportedVariable {
+                key: key.clone(),
+                value: value.clone(),
+                secret: false,
+            })
+            .collect();
+        // Bruno never writes secret values to disk; keep the keys so they can be filled in.
+        for key in file.list("vars:secret") {
+            let key = key.trim_start_matches('~').to_string();
+            if !variables.iter().any(|v| v.key == key) {
+                variables.push(ImportedVariable {
+                    key,
+                    value: String::new(),
+                    secret: true,
+                });
+            }
+        }
+        environments.push(ImportedEnvironment {
+            name: name.to_string(),
+            variables,
+        });
+    }
+    environments.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(environments)
+}
+
+pub fn read_collection(
+    root: &Path,
+    report: &mut ImportReport,
+) -> Result<ImportedCollection, String> {
+    let manifest_path = root.join("bruno.json");
+    let manifest: Value = fs::read_to_string(&manifest_path)
+        .map_err(|e| format!("not a Bruno collection (bruno.json unreadable): {e}"))
+        .and_then(|raw| {
+            serde_json::from_str(&raw).map_err(|e| format!("bruno.json parse failed: {e}"))
+        })?;
+    let collection_file = Some(root.join("collection.bru"))
+        .filter(|p| p.exists())
+        .map(|p| BruFile::read(&p))
+        .transpose()?;
+    let auth = folder_auth(collection_file.as_ref(), &ImportedAuth::None, report);
+    let variables = collection_file
+        .as_ref()
+        .map(|f| {
+            f.dict("vars:pre-request")
+                .iter()
+                .filter(|(_, _, enabled)| *enabled)
+                .map(|(key, value, _)| ImportedVariable {
+                    key: key.clone(),
+                    value: value.clone(),
+                    secret: false,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if root.join(".env").exists() {
+        report.unsupported("process.env values from .env are not imported");
+    }
+    Ok(ImportedCollection {
+        name: match str_of(&manifest, "name") {
+            "" => "Imported Bruno Collection".to_string(),
+            n => n.to_string(),
+        },
+        items: read_dir(root, &auth, report)?,
+        variables,
+        environments: read_environments(root)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    const FILES: &[(&str, &str)] = &[
+        (
+            "bruno.json",
+            r#"{ "version": "1", "name": "Pets", "type": "collection" }"#,
+        ),
+        (
+            "collection.bru",
+            "auth {\n  mode: bearer\n}\n\nauth:bearer {\n  token: {{token}}\n}\n\n\
+             vars:pre-request {\n  baseUrl: https://pets.test\n  ~unused: x\n}\n",
+        ),
+        (
+            "Pets/folder.bru",
+            "meta {\n  name: Pets\n  seq: 1\n}\n\nauth {\n  mode: basic\n}\n\n\
+             auth:basic {\n  username: admin\n  password: {{adminPassword}}\n}\n",
+        ),
+        (
+            "Pets/Create pet.bru",
+            "meta {\n  name: Create pet\n  type: http\n  seq: 2\n}\n\n\
+             post {\n  url: {{baseUrl}}/pets/:kind\n  body: json\n  auth: inherit\n}\n\n\
+             params:path {\n  kind: dog\n}\n\n\
+             params:query {\n  dry: 1\n  ~debug: true\n}\n\n\
+             headers {\n  X-Trace: abc\n  ~X-Off: no\n}\n\n\
+             body:json {\n  {\n    \"name\": \"Rex\"\n  }\n}\n\n\
+             vars:post-response {\n  petId: res.body.id\n}\n",
+        ),
+        (
+            "Pets/Vets/List vets.bru",
+            "meta {\n  name: List vets\n  seq: 1\n}\n\n\
+             get {\n  url: {{baseUrl}}/vets\n  body: none\n  auth: inherit\n}\n",
+        ),
+        (
+            "Upload.bru",
+            "meta {\n  name: Upload\n  seq: 2\n}\n\n\
+             post {\n  url: {{baseUrl}}/upload\n  body: multipartForm\n  auth: inherit\n}\n\n\
+             body:multipart-form {\n  photo: @file(/tmp/a.png|/tmp/b.png)\n  ~caption: hello\n}\n",
+        ),
+        (
+            "Login.bru",
+            "meta {\n  name: Login\n  seq: 3\n}\n\n\
+             post {\n  url: {{baseUrl}}/login\n  body: formUrlEncoded\n  auth: none\n}\n\n\
+             body:form-urlencoded {\n  user: ann\n}\n",
+        ),
+        (
+            "environments/Staging.bru",
+            "vars {\n  host: staging.test\n}\n\nvars:secret [\n  apiKey\n]\n",
+        ),
+    ];
+
+    fn collection() -> PathBuf {
+        let root = std::env::temp_dir().join(format!("litefetch-bruno-{}", uuid::Uuid::new_v4()));
+        for (name, content) in FILES {
+            let path = root.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+        root
+    }
+
+    fn names(items: &[ImportedItem]) -> Vec<&str> {
+        items
+            .iter()
+            .map(|item| match item {
+                ImportedItem::Folder { name, .. } => name.as_str(),
+                ImportedItem::Request(request) => request.name.as_str(),
+            })
+            .collect()
+    }
+
+    fn request<'a>(items: &'a [ImportedItem], path: &[&str]) -> &'a ImportedRequest {
+        let (first, rest) = path.split_first().unwrap();
+        let index = names(items).iter().position(|n| n == first).unwrap();
+        match &items[index] {
+            ImportedItem::Folder { items, .. } => request(items, rest),
+            ImportedItem::Request(request) => request,
+        }
+    }
+
+    #[test]
+    fn reads_nested_folders_in_seq_order_with_inherited_auth() {
+        let root = collection();
+        let mut report = ImportReport::new("bruno");
+        let imported = read_collection(&root, &mut report).unwrap();
+        assert_eq!(imported.name, "Pets");
+        assert_eq!(names(&imported.items), ["Pets", "Upload", "Login"]);
+        let ImportedItem::Folder { items, .. } = &imported.items[0] else {
+            panic!("expected the Pets folder");
+        };
+        assert_eq!(names(items), ["Create pet", "Vets"]);
+
+        let vets = request(&imported.items, &["Pets", "Vets", "List vets"]);
+        let ImportedAuth::Basic { username, password } = &vets.auth else {
+            panic!("expected the Pets folder's basic auth");
+        };
+        assert_eq!(
+            (username.as_str(), password.as_str()),
+            ("admin", "{{adminPassword}}")
+        );
+        let upload = request(&imported.items, &["Upload"]);
+        assert!(matches!(&upload.auth, ImportedAuth::Bearer { token } if token == "{{token}}"));
+        assert!(matches!(
+            request(&imported.items, &["Login"]).auth,
+            ImportedAuth::None
+        ));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn reads_variables_environments_and_bodies() {
+        let root = collection();
+        let mut report = ImportReport::new("bruno");
+        let imported = read_collection(&root, &mut report).unwrap();
+        let variables: Vec<_> = imported
+            .variables
+            .iter()
+            .map(|v| (v.key.as_str(), v.value.as_str()))
+            .collect();
+        assert_eq!(variables, [("baseUrl", "https://pets.test")]);
+        assert_eq!(imported.environments.len(), 1);
+        let staging = &imported.environments[0];
+        assert_eq!(staging.name, "Staging");
+        let staging: Vec<_> = staging
+            .variables
+            .iter()
+            .map(|v| (v.key.as_str(), v.value.as_str(), v.secret))
+            .collect();
+        assert_eq!(
+            staging,
+            [("host", "staging.test", false), ("apiKey", "", true)]
+        );
+
+        let create = request(&imported.items, &["Pets", "Create pet"]);
+        assert_eq!(create.method, "POST");
+        assert_eq!(create.url, "{{baseUrl}}/pets/dog");
+        assert_eq!(create.headers, [("X-Trace".to_string(), "abc".to_string())]);
+        assert_eq!(create.query_params.len(), 2);
+        assert!(!create.query_params[1].enabled);
+        let ImportedBody::Raw { text, json } = &create.body else {
+            panic!("expected a raw body");
+        };
+        assert!(json);
+        assert_eq!(text, "{\n  \"name\": \"Rex\"\n}");
+        assert_eq!(
+            create.extract_rules,
+            [("id".to_string(), "petId".to_string())]
+        );
+
+        let ImportedBody::FormData(rows) = &request(&imported.items, &["Upload"]).body else {
+            panic!("expected form data");
+        };
+        assert_eq!(rows[0].file_path.as_deref(), Some("/tmp/a.png"));
+        assert!(!rows[1].enabled);
+        let ImportedBody::UrlEncoded(rows) = &request(&imported.items, &["Login"]).body else {
+            panic!("expected a url-encoded body");
+        };
+        assert_eq!(
+            (rows[0].key.as_str(), rows[0].value.as_str()),
+            ("user", "ann")
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn requires_a_manifest() {
+        let root = std::env::temp_dir().join(format!("litefetch-bruno-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&root).unwrap();
+        let mut report = ImportReport::new("bruno");
+        assert!(read_collection(&root, &mut report).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
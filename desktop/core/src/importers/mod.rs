@@ -0,0 +1,168 @@
+//! Other API clients' export formats, parsed into one intermediate model. The desktop shell
+//! turns that model into a LiteFetch collection; the parsing itself needs no window, so it
+//! lives here where it can be tested on plain files.
+
+pub mod bruno;
+pub mod openapi;
+pub mod postman;
+
+use serde::Serialize;
+
+pub use crate::json::{array_of, str_of, value_text};
+
+#[derive(Default)]
+pub struct ImportedCollection {
+    pub name: String,
+    pub items: Vec<ImportedItem>,
+    pub variables: Vec<ImportedVariable>,
+    pub environments: Vec<ImportedEnvironment>,
+}
+
+pub enum ImportedItem {
+    Folder {
+        name: String,
+        items: Vec<ImportedItem>,
+    },
+    Request(Box<ImportedRequest>),
+}
+
+pub struct ImportedRequest {
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub query_params: Vec<FormRow>,
+    pub body: ImportedBody,
+    pub auth: ImportedAuth,
+    /// `(source_path, target_variable)` pairs derived from post-response scripts.
+    pub extract_rules: Vec<(String, String)>,
+    pub examples: Vec<ImportedExample>,
+    /// Markdown, kept as the request's `description`.
+    pub description: Option<String>,
+}
+
+impl ImportedRequest {
+    pub fn new(name: impl Into<String>, method: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            method: method.into(),
+            url: url.into(),
+            headers: Vec::new(),
+            query_params: Vec::new(),
+            body: ImportedBody::None,
+            auth: ImportedAuth::None,
+            extract_rules: Vec::new(),
+            examples: Vec::new(),
+            description: None,
+        }
+    }
+}
+
+pub enum ImportedBody {
+    None,
+    Raw { text: String, json: bool },
+    UrlEncoded(Vec<FormRow>),
+    FormData(Vec<FormRow>),
+    Binary { file_path: String },
+}
+
+pub struct FormRow {
+    pub key: String,
+    pub value: String,
+    pub enabled: bool,
+    /// Set for multipart file fields.
+    pub file_path: Option<String>,
+}
+
+impl FormRow {
+    pub fn text(key: impl Into<String>, value: impl Into<String>, enabled: bool) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            enabled,
+            file_path: None,
+        }
+    }
+}
+
+/// Importers resolve folder/collection inheritance before building requests.
+#[derive(Clone)]
+pub enum ImportedAuth {
+    None,
+    Basic { username: String, password: String },
+    Bearer { token: String },
+}
+
+#[derive(Clone)]
+pub struct ImportedVariable {
+    pub key: String,
+    pub value: String,
+    pub secret: bool,
+}
+
+#[derive(Clone)]
+pub struct ImportedEnvironment {
+    pub name: String,
+    pub variables: Vec<ImportedVariable>,
+}
+
+/// A saved response, seeded into history so it can be inspected after import.
+pub struct ImportedExample {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    pub content_type: Option<String>,
+}
+
+#[derive(Serialize, Default)]
+pub struct ImportReport {
+    pub source: String,
+    pub folders: usize,
+    pub requests: usize,
+    pub variables: usize,
+    pub environments: usize,
+    pub examples: usize,
+    pub extract_rules: usize,
+    /// Secret values moved into the OS keychain.
+    pub secrets: usize,
+    /// Features that were dropped or approximated during conversion.
+    pub unsupported: Vec<String>,
+    /// Problems found in the source itself, such as an OpenAPI document's dangling `$ref`s.
+    pub warnings: Vec<ImportWarning>,
+}
+
+#[derive(Serialize, Clone, PartialEq)]
+pub struct ImportWarning {
+    /// What kind of problem, e.g. `missing_operation_id`.
+    pub code: String,
+    /// Where in the source: an operation (`GET /users`) or a JSON pointer.
+    pub location: String,
+    pub message: String,
+}
+
+impl ImportReport {
+    pub fn new(source: &str) -> Self {
+        Self {
+            source: source.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn unsupported(&mut self, note: impl Into<String>) {
+        let note = note.into();
+        if !self.unsupported.contains(&note) {
+            self.unsupported.push(note);
+        }
+    }
+
+    pub fn warn(&mut self, code: &str, location: &str, message: impl Into<String>) {
+        let warning = ImportWarning {
+            code: code.to_string(),
+            location: location.to_string(),
+            message: message.into(),
+        };
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+}
@@ -0,0 +1,906 @@
+//! OpenAPI 3.0/3.1 and Swagger 2.0 import. Each operation becomes a request grouped by its
+//! first tag; servers become a `baseUrl` variable (one environment per extra server) and
+//! request bodies are filled from examples or generated from their schemas.
+
+use serde_json::{json, Map, Value};
+
+use super::{
+    array_of, str_of, value_text, FormRow, ImportReport, ImportedAuth, ImportedBody,
+    ImportedCollection, ImportedEnvironment, ImportedExample, ImportedItem, ImportedRequest,
+    ImportedVariable,
+};
+
+const OPERATIONS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Schemas nest (and recurse) arbitrarily; generated examples stop at this depth.
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+struct Spec<'a> {
+    doc: &'a Value,
+    swagger: bool,
+}
+
+impl<'a> Spec<'a> {
+    /// Follows local `$ref`s (`#/components/...`, `#/definitions/...`). External references
+    /// are left unresolved.
+    fn resolve(&self, mut value: &'a Value, report: &mut ImportReport) -> &'a Value {
+        for _ in 0..16 {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return value;
+            };
+            let Some(pointer) = reference.strip_prefix('#') else {
+                report.unsupported(format!("external reference {reference} was not resolved"));
+                return value;
+            };
+            match self.doc.pointer(pointer) {
+                Some(target) => value = target,
+                None => {
+                    report.unsupported(format!("reference {reference} was not found"));
+                    return value;
+                }
+            }
+        }
+        value
+    }
+
+    fn example_of(&self, schema: &'a Value, depth: usize, report: &mut ImportReport) -> Value {
+        let schema = self.resolve(schema, report);
+        for key in ["example", "default", "const"] {
+            if let Some(value) = schema.get(key) {
+                return value.clone();
+            }
+        }
+        if let Some(first) = schema
+            .get("examples")
+            .and_then(Value::as_array)
+            .and_then(|e| e.first())
+        {
+            return first.clone();
+        }
+        if let Some(first) = schema
+            .get("enum")
+            .and_then(Value::as_array)
+            .and_then(|e| e.first())
+        {
+            return first.clone();
+        }
+        if depth >= MAX_SCHEMA_DEPTH {
+            return Value::Null;
+        }
+        if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = Map::new();
+            for part in parts {
+                if let Value::Object(fields) = self.example_of(part, depth + 1, report) {
+                    merged.extend(fields);
+                }
+            }
+            return Value::Object(merged);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(first) = schema
+                .get(key)
+                .and_then(Value::as_array)
+                .and_then(|v| v.first())
+            {
+                return self.example_of(first, depth + 1, report);
+            }
+        }
+        // 3.1 allows `type: [string, "null"]`.
+        let kind = match schema.get("type") {
+            Some(Value::Array(types)) => types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|t| *t != "null")
+                .unwrap_or_default(),
+            Some(Value::String(kind)) => kind.as_str(),
+            _ if schema.get("properties").is_some() => "object",
+            _ if schema.get("items").is_some() => "array",
+            _ => "",
+        };
+        match kind {
+            "object" => {
+                let mut fields = Map::new();
+                if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                    for (name, property) in properties {
+                        fields.insert(name.clone(), self.example_of(property, depth + 1, report));
+                    }
+                }
+                Value::Object(fields)
+            }
+            "array" => match schema.get("items") {
+                Some(items) => Value::Array(vec![self.example_of(items, depth + 1, report)]),
+                None => json!([]),
+            },
+            "integer" => json!(0),
+            "number" => json!(0.0),
+            "boolean" => json!(true),
+            "string" => json!(match str_of(schema, "format") {
+                "date-time" => "1970-01-01T00:00:00Z",
+                "date" => "1970-01-01",
+                "email" => "user@example.com",
+                "uuid" => "00000000-0000-0000-0000-000000000000",
+                "uri" | "url" => "https://example.com",
+                _ => "string",
+            }),
+            _ => Value::Null,
+        }
+    }
+
+    /// A parameter's example, falling back to its schema (2.0 keeps type info inline).
+    fn parameter_example(&self, param: &'a Value, report: &mut ImportReport) -> String {
+        if let Some(example) = param.get("example") {
+            return value_text(Some(example));
+        }
+        if let Some(first) = param
+            .get("examples")
+            .and_then(Value::as_object)
+            .and_then(|e| e.values().next())
+        {
+            let first = self.resolve(first, report);
+            return value_text(first.get("value"));
+        }
+        let schema = if self.swagger {
+            param
+        } else {
+            param.get("schema").unwrap_or(&Value::Null)
+        };
+        let schema = self.resolve(schema, report);
+        let has_hint = ["example", "default", "enum", "const"]
+            .iter()
+            .any(|k| schema.get(k).is_some());
+        if has_hint {
+            value_text(Some(&self.example_of(schema, 0, report)))
+        } else {
+            String::new()
+        }
+    }
+
+    fn form_rows(&self, schema: &'a Value, report: &mut ImportReport) -> Vec<FormRow> {
+        let schema = self.resolve(schema, report);
+        let required: Vec<&str> = array_of(schema, "required")
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .map(|properties| {
+                properties
+                    .iter()
+                    .map(|(name, property)| {
+                        let property = self.resolve(property, report);
+                        let enabled = required.is_empty() || required.contains(&name.as_str());
+                        if str_of(property, "format") == "binary" {
+                            FormRow {
+                                key: name.clone(),
+                                value: String::new(),
+                                enabled,
+                                file_path: Some(String::new()),
+                            }
+                        } else {
+                            let example = self.example_of(property, 0, report);
+                            FormRow::text(name.clone(), value_text(Some(&example)), enabled)
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Picks the request body for a 3.x `requestBody`, preferring JSON media types.
+    fn body_v3(&self, body: &'a Value, request: &mut ImportedRequest, report: &mut ImportReport) {
+        let body = self.resolve(body, report);
+        let Some(content) = body.get("content").and_then(Value::as_object) else {
+            return;
+        };
+        let media = content
+            .iter()
+            .find(|(mime, _)| mime.contains("json"))
+            .or_else(|| content.iter().next());
+        let Some((mime, media)) = media else {
+            return;
+        };
+        request
+            .headers
+            .push(("Content-Type".to_string(), mime.clone()));
+        let schema = media.get("schema").unwrap_or(&Value::Null);
+        request.body = match mime.as_str() {
+            "application/x-www-form-urlencoded" => {
+                ImportedBody::UrlEncoded(self.form_rows(schema, report))
+            }
+            "multipart/form-data" => {
+                request.headers.retain(|(k, _)| k != "Content-Type");
+                ImportedBody::FormData(self.form_rows(schema, report))
+            }
+            "application/octet-stream" => ImportedBody::None,
+            _ => {
+                let example = media.get("example").cloned().or_else(|| {
+                    media
+                        .get("examples")
+                        .and_then(Value::as_object)
+                        .and_then(|e| e.values().next())
+                        .map(|e| self.resolve(e, report))
+                        .and_then(|e| e.get("value").cloned())
+                });
+                let example = example.unwrap_or_else(|| self.example_of(schema, 0, report));
+                let json = mime.contains("json");
+                ImportedBody::Raw {
+                    text: match (&example, json) {
+                        (Value::String(text), _) => text.clone(),
+                        (_, true) => serde_json::to_string_pretty(&example).unwrap_or_default(),
+                        (other, false) => value_text(Some(other)),
+                    },
+                    json,
+                }
+            }
+        };
+    }
+
+    fn examples_v3(&self, responses: &'a Value, report: &mut ImportReport) -> Vec<ImportedExample> {
+        let mut examples = Vec::new();
+        for (status, response) in responses.as_object().into_iter().flatten() {
+            let response = self.resolve(response, report);
+            let content = response.get("content").and_then(Value::as_object);
+            for (mime, media) in content.into_iter().flatten() {
+                let example = media.get("example").cloned().or_else(|| {
+                    media
+                        .get("examples")
+                        .and_then(Value::as_object)
+                        .and_then(|e| e.values().next())
+                        .map(|e| self.resolve(e, report))
+                        .and_then(|e| e.get("value").cloned())
+                });
+                if let Some(example) = example {
+                    examples.push(example_entry(status, mime, &example));
+                }
+            }
+        }
+        examples
+    }
+
+    fn examples_v2(&self, responses: &'a Value, report: &mut ImportReport) -> Vec<ImportedExample> {
+        let mut examples = Vec::new();
+        for (status, response) in responses.as_object().into_iter().flatten() {
+            let response = self.resolve(response, report);
+            let listed = response.get("examples").and_then(Value::as_object);
+            for (mime, example) in listed.into_iter().flatten() {
+                examples.push(example_entry(status, mime, example));
+            }
+        }
+        examples
+    }
+}
+
+fn example_entry(status: &str, mime: &str, example: &Value) -> ImportedExample {
+    let body = match example {
+        Value::String(text) => text.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    };
+    ImportedExample {
+        // `default` and range keys (`2XX`) have no single status code.
+        status: status.parse().unwrap_or(200),
+        headers: vec![("Content-Type".to_string(), mime.to_string())],
+        body,
+        content_type: Some(mime.to_string()),
+    }
+}
+
+/// Maps a security requirement onto the request, using variables for the credentials.
+fn apply_security(
+    spec: &Spec,
+    requirements: &[Value],
+    request: &mut ImportedRequest,
+    variables: &mut Vec<ImportedVariable>,
+    report: &mut ImportReport,
+) {
+    let schemes = if spec.swagger {
+        spec.doc.get("securityDefinitions")
+    } else {
+        spec.doc.pointer("/components/securitySchemes")
+    };
+    let Some(name) = requirements
+        .first()
+        .and_then(Value::as_object)
+        .and_then(|r| r.keys().next())
+    else {
+        return;
+    };
+    let Some(scheme) = schemes.and_then(|s| s.get(name)) else {
+        return;
+    };
+    let scheme = spec.resolve(scheme, report);
+    let mut variable = |key: &str| {
+        if !variables.iter().any(|v| v.key == key) {
+            variables.push(ImportedVariable {
+                key: key.to_string(),
+                value: String::new(),
+                secret: true,
+            });
+        }
+        format!("{{{{{key}}}}}")
+    };
+    match (
+        str_of(scheme, "type"),
+        str_of(scheme, "scheme").to_lowercase().as_str(),
+    ) {
+        ("basic", _) | ("http", "basic") => {
+            request.auth = ImportedAuth::Basic {
+                username: variable("username"),
+                password: variable("password"),
+            }
+        }
+        ("http", "bearer") => {
+            request.auth = ImportedAuth::Bearer {
+                token: variable("bearerToken"),
+            }
+        }
+        ("apiKey", _) => {
+            let key = str_of(scheme, "name").to_string();
+            let value = variable("apiKey");
+            match str_of(scheme, "in") {
+                "query" => request.query_params.push(FormRow::text(key, value, true)),
+                "header" => request.headers.push((key, value)),
+                _ => report.unsupported("cookie API keys are not imported"),
+            }
+        }
+        ("oauth2" | "openIdConnect", _) => {
+            report.unsupported(
+                "OAuth 2 flows are imported as bearer auth with an accessToken variable",
+            );
+            request.auth = ImportedAuth::Bearer {
+                token: variable("accessToken"),
+            }
+        }
+        (kind, scheme) => report.unsupported(format!("{kind} {scheme} security is not supported")),
+    }
+}
+
+/// Server URLs may contain `{var}` placeholders with defaults; they become variables.
+fn server_url(server: &Value, variables: &mut Vec<ImportedVariable>) -> String {
+    let mut url = str_of(server, "url").trim_end_matches('/').to_string();
+    for (name, variable) in server
+        .get("variables")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        url = url.replace(&format!("{{{name}}}"), &format!("{{{{{name}}}}}"));
+        if !variables.iter().any(|v| v.key == *name) {
+            variables.push(ImportedVariable {
+                key: name.clone(),
+                value: value_text(variable.get("default")),
+                secret: false,
+            });
+        }
+    }
+    url
+}
+
+fn servers(spec: &Spec, variables: &mut Vec<ImportedVariable>) -> Vec<(String, String)> {
+    if spec.swagger {
+        let host = str_of(spec.doc, "host");
+        if host.is_empty() {
+            return Vec::new();
+        }
+        let scheme = array_of(spec.doc, "schemes")
+            .first()
+            .and_then(Value::as_str)
+            .unwrap_or("https");
+        let base_path = str_of(spec.doc, "basePath").trim_end_matches('/');
+        return vec![(
+            "default".to_string(),
+            format!("{scheme}://{host}{base_path}"),
+        )];
+    }
+    array_of(spec.doc, "servers")
+        .iter()
+        .enumerate()
+        .map(|(index, server)| {
+            let name = match str_of(server, "description") {
+                "" => format!("server {}", index + 1),
+                d => d.to_string(),
+            };
+            (name, server_url(server, variables))
+        })
+        .collect()
+}
+
+pub fn parse(doc: &Value, report: &mut ImportReport) -> Result<ImportedCollection, String> {
+    let swagger = str_of(doc, "swagger").starts_with("2.");
+    if !swagger && !str_of(doc, "openapi").starts_with('3') {
+        return Err("not an OpenAPI 3 or Swagger 2 document".to_string());
+    }
+    let spec = Spec { doc, swagger };
+    let mut variables = Vec::new();
+    let servers = servers(&spec, &mut variables);
+    let base_url = servers
+        .first()
+        .map(|(_, url)| url.clone())
+        .unwrap_or_default();
+    variables.insert(
+        0,
+        ImportedVariable {
+            key: "baseUrl".to_string(),
+            value: base_url,
+            secret: false,
+        },
+    );
+    let global_security = array_of(doc, "security");
+
+    // Tagged operations are grouped in document tag order, then first-seen order.
+    let mut folders: Vec<(String, Vec<ImportedItem>)> = array_of(doc, "tags")
+        .iter()
+        .map(|t| (str_of(t, "name").to_string(), Vec::new()))
+        .collect();
+    let mut root = Vec::new();
+    for (path, item) in doc
+        .get("paths")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let item = spec.resolve(item, report);
+        for method in OPERATIONS {
+            let Some(operation) = item.get(*method) else {
+                continue;
+            };
+            let name = [
+                str_of(operation, "summary"),
+                str_of(operation, "operationId"),
+            ]
+            .into_iter()
+            .find(|n| !n.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{} {path}", method.to_uppercase()));
+
+            // Operation parameters override path-level ones with the same name and location.
+            let mut params: Vec<&Value> = Vec::new();
+            for param in array_of(operation, "parameters")
+                .iter()
+                .chain(array_of(item, "parameters"))
+            {
+                let param = spec.resolve(param, report);
+                let key = (str_of(param, "name"), str_of(param, "in"));
+                if !params
+                    .iter()
+                    .any(|p| (str_of(p, "name"), str_of(p, "in")) == key)
+                {
+                    params.push(param);
+                }
+            }
+
+            let mut url = format!("{{{{baseUrl}}}}{path}");
+            let mut request = ImportedRequest::new(name, method.to_uppercase(), "");
+            request.description = Some(str_of(operation, "description").to_string());
+            let mut form_rows = Vec::new();
+            for param in &params {
+                let key = str_of(param, "name").to_string();
+                let required = param
+                    .get("required")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                match str_of(param, "in") {
+                    "path" => {
+                        let value = spec.parameter_example(param, report);
+                        let value = if value.is_empty() {
+                            if !variables.iter().any(|v| v.key == key) {
+                                variables.push(ImportedVariable {
+                                    key: key.clone(),
+                                    value: String::new(),
+                                    secret: false,
+                                });
+                            }
+                            format!("{{{{{key}}}}}")
+                        } else {
+                            value
+                        };
+                        url = url.replace(&format!("{{{key}}}"), &value);
+                    }
+                    "query" => {
+                        let value = spec.parameter_example(param, report);
+                        request
+                            .query_params
+                            .push(FormRow::text(key, value, required));
+                    }
+                    "header" => {
+                        let value = spec.parameter_example(param, report);
+                        request.headers.push((key, value));
+                    }
+                    "body" => {
+                        let schema = param.get("schema").unwrap_or(&Value::Null);
+                        let example = spec.example_of(schema, 0, report);
+                        request.body = ImportedBody::Raw {
+                            text: serde_json::to_string_pretty(&example).unwrap_or_default(),
+                            json: true,
+                        };
+                    }
+                    "formData" => {
+                        let value = spec.parameter_example(param, report);
+                        form_rows.push(if str_of(param, "type") == "file" {
+                            FormRow {
+                                key,
+                                value: String::new(),
+                                enabled: required,
+                                file_path: Some(String::new()),
+                            }
+                        } else {
+                            FormRow::text(key, value, required)
+                        });
+                    }
+                    "cookie" => report.unsupported("cookie parameters are not imported"),
+                    _ => {}
+                }
+            }
+            if !form_rows.is_empty() {
+                let consumes = array_of(operation, "consumes")
+                    .iter()
+                    .chain(array_of(doc, "consumes"))
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<_>>();
+                let multipart = form_rows.iter().any(|r| r.file_path.is_some())
+                    || consumes.contains(&"multipart/form-data");
+                request.body = if multipart {
+                    ImportedBody::FormData(form_rows)
+                } else {
+                    ImportedBody::UrlEncoded(form_rows)
+                };
+            }
+            if let Some(body) = operation.get("requestBody") {
+                spec.body_v3(body, &mut request, report);
+            }
+            request.url = url;
+
+            let security = match operation.get("security").and_then(Value::as_array) {
+                Some(security) => security.as_slice(),
+                None => global_security,
+            };
+            apply_security(&spec, security, &mut request, &mut variables, report);
+
+            if let Some(responses) = operation.get("responses") {
+                request.examples = if swagger {
+                    spec.examples_v2(responses, report)
+                } else {
+                    spec.examples_v3(responses, report)
+                };
+            }
+            if operation.get("callbacks").is_some() {
+                report.unsupported("callbacks are not imported");
+            }
+
+            let item = ImportedItem::Request(Box::new(request));
+            match array_of(operation, "tags").first().and_then(Value::as_str) {
+                Some(tag) => match folders.iter_mut().find(|(name, _)| name == tag) {
+                    Some((_, items)) => items.push(item),
+                    None => folders.push((tag.to_string(), vec![item])),
+                },
+                None => root.push(item),
+            }
+        }
+    }
+    if doc.get("webhooks").is_some() {
+        report.unsupported("webhooks are not imported");
+    }
+
+    let mut items: Vec<ImportedItem> = folders
+        .into_iter()
+        .filter(|(_, items)| !items.is_empty())
+        .map(|(name, items)| ImportedItem::Folder { name, items })
+        .collect();
+    items.extend(root);
+
+    // The first server is the default; each other server gets an environment overriding baseUrl.
+    let environments = servers
+        .iter()
+        .skip(1)
+        .map(|(name, url)| ImportedEnvironment {
+            name: name.clone(),
+            variables: variables
+                .iter()
+                .map(|v| ImportedVariable {
+                    value: if v.key == "baseUrl" {
+                        url.clone()
+                    } else {
+                        v.value.clone()
+                    },
+                    ..v.clone()
+                })
+                .collect(),
+        })
+        .collect();
+    let info = doc.get("info").unwrap_or(&Value::Null);
+    Ok(ImportedCollection {
+        name: match str_of(info, "title") {
+            "" => "Imported API".to_string(),
+            t => t.to_string(),
+        },
+        items,
+        variables,
+        environments,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request<'a>(items: &'a [ImportedItem], path: &[&str]) -> &'a ImportedRequest {
+        let (first, rest) = path.split_first().unwrap();
+        let item = items
+            .iter()
+            .find(|item| match item {
+                ImportedItem::Folder { name, .. } => name == first,
+                ImportedItem::Request(request) => request.name == *first,
+            })
+            .unwrap();
+        match item {
+            ImportedItem::Folder { items, .. } => request(items, rest),
+            ImportedItem::Request(request) => request,
+        }
+    }
+
+    fn variable<'a>(variables: &'a [ImportedVariable], key: &str) -> &'a ImportedVariable {
+        variables.iter().find(|v| v.key == key).unwrap()
+    }
+
+    fn header<'a>(request: &'a ImportedRequest, name: &str) -> Option<&'a str> {
+        request
+            .headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn openapi() -> Value {
+        json!({
+            "openapi": "3.0.3",
+            "info": { "title": "Pets API" },
+            "servers": [
+                {
+                    "url": "https://{region}.pets.test/v1/",
+                    "description": "Production",
+                    "variables": { "region": { "default": "eu" } }
+                },
+                { "url": "http://localhost:8080", "description": "Local" }
+            ],
+            "tags": [{ "name": "pets" }, { "name": "unused" }],
+            "security": [{ "bearer": [] }],
+            "components": {
+                "securitySchemes": {
+                    "bearer": { "type": "http", "scheme": "bearer" },
+                    "key": { "type": "apiKey", "in": "header", "name": "X-Api-Key" }
+                },
+                "schemas": {
+                    "Pet": {
+                        "type": "object",
+                        "required": ["name"],
+                        "properties": {
+                            "name": { "type": "string", "example": "Rex" },
+                            "born": { "type": "string", "format": "date" },
+                            "tags": { "type": "array", "items": { "type": "string" } }
+                        }
+                    }
+                }
+            },
+            "paths": {
+                "/pets/{petId}": {
+                    "parameters": [
+                        {
+                            "name": "petId",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "integer" }
+                        }
+                    ],
+                    "get": {
+                        "tags": ["pets"],
+                        "operationId": "getPet",
+                        "parameters": [
+                            {
+                                "name": "verbose",
+                                "in": "query",
+                                "schema": { "type": "boolean", "default": false }
+                            }
+                        ],
+                        "responses": {
+                            "200": {
+                                "description": "ok",
+                                "content": { "application/json": { "example": { "name": "Rex" } } }
+                            }
+                        }
+                    },
+                    "put": {
+                        "tags": ["pets"],
+                        "summary": "Replace pet",
+                        "security": [{ "key": [] }],
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/Pet" }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/login": {
+                    "post": {
+                        "summary": "Log in",
+                        "security": [],
+                        "requestBody": {
+                            "content": {
+                                "application/x-www-form-urlencoded": {
+                                    "schema": {
+                                        "type": "object",
+                                        "required": ["user"],
+                                        "properties": {
+                                            "user": { "type": "string" },
+                                            "remember": { "type": "boolean" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn groups_operations_by_tag_with_security_as_auth() {
+        let mut report = ImportReport::new("openapi");
+        let imported = parse(&openapi(), &mut report).unwrap();
+        assert_eq!(imported.name, "Pets API");
+        assert_eq!(imported.items.len(), 2);
+        let ImportedItem::Folder { name, items } = &imported.items[0] else {
+            panic!("expected the pets folder first");
+        };
+        assert_eq!((name.as_str(), items.len()), ("pets", 2));
+
+        let get = request(&imported.items, &["pets", "getPet"]);
+        assert_eq!(get.method, "GET");
+        assert_eq!(get.url, "{{baseUrl}}/pets/{{petId}}");
+        assert!(matches!(&get.auth, ImportedAuth::Bearer { token } if token == "{{bearerToken}}"));
+        let put = request(&imported.items, &["pets", "Replace pet"]);
+        assert!(matches!(put.auth, ImportedAuth::None));
+        assert_eq!(header(put, "X-Api-Key"), Some("{{apiKey}}"));
+        let login = request(&imported.items, &["Log in"]);
+        assert!(matches!(login.auth, ImportedAuth::None));
+    }
+
+    #[test]
+    fn turns_servers_and_credentials_into_variables() {
+        let mut report = ImportReport::new("openapi");
+        let imported = parse(&openapi(), &mut report).unwrap();
+        assert_eq!(imported.variables[0].key, "baseUrl");
+        assert_eq!(
+            imported.variables[0].value,
+            "https://{{region}}.pets.test/v1"
+        );
+        assert_eq!(variable(&imported.variables, "region").value, "eu");
+        assert_eq!(variable(&imported.variables, "petId").value, "");
+        assert!(variable(&imported.variables, "bearerToken").secret);
+        assert!(variable(&imported.variables, "apiKey").secret);
+
+        assert_eq!(imported.environments.len(), 1);
+        let local = &imported.environments[0];
+        assert_eq!(local.name, "Local");
+        assert_eq!(
+            variable(&local.variables, "baseUrl").value,
+            "http://localhost:8080"
+        );
+        assert_eq!(variable(&local.variables, "region").value, "eu");
+    }
+
+    #[test]
+    fn fills_bodies_parameters_and_examples() {
+        let mut report = ImportReport::new("openapi");
+        let imported = parse(&openapi(), &mut report).unwrap();
+
+        let get = request(&imported.items, &["pets", "getPet"]);
+        assert_eq!(get.query_params[0].key, "verbose");
+        assert_eq!(get.query_params[0].value, "false");
+        assert!(!get.query_params[0].enabled);
+        assert_eq!(get.examples[0].status, 200);
+        let example: Value = serde_json::from_str(&get.examples[0].body).unwrap();
+        assert_eq!(example, json!({ "name": "Rex" }));
+
+        let put = request(&imported.items, &["pets", "Replace pet"]);
+        assert_eq!(header(put, "Content-Type"), Some("application/json"));
+        let ImportedBody::Raw { text, json } = &put.body else {
+            panic!("expected a raw body");
+        };
+        assert!(json);
+        let body: Value = serde_json::from_str(text).unwrap();
+        assert_eq!(
+            body,
+            json!({ "name": "Rex", "born": "1970-01-01", "tags": ["string"] })
+        );
+
+        let login = request(&imported.items, &["Log in"]);
+        let ImportedBody::UrlEncoded(rows) = &login.body else {
+            panic!("expected a url-encoded body");
+        };
+        let user = rows.iter().find(|r| r.key == "user").unwrap();
+        let remember = rows.iter().find(|r| r.key == "remember").unwrap();
+        assert!(user.enabled && !remember.enabled);
+        assert_eq!(remember.value, "true");
+    }
+
+    #[test]
+    fn parses_swagger_2_documents() {
+        let doc = json!({
+            "swagger": "2.0",
+            "info": { "title": "Files" },
+            "host": "files.test",
+            "basePath": "/api/",
+            "schemes": ["http"],
+            "securityDefinitions": { "basic": { "type": "basic" } },
+            "security": [{ "basic": [] }],
+            "definitions": {
+                "Item": { "properties": { "qty": { "type": "integer" } } }
+            },
+            "paths": {
+                "/upload": {
+                    "post": {
+                        "operationId": "upload",
+                        "consumes": ["multipart/form-data"],
+                        "parameters": [
+                            { "name": "file", "in": "formData", "type": "file", "required": true },
+                            { "name": "note", "in": "formData", "type": "string", "default": "hi" }
+                        ],
+                        "responses": {
+                            "201": {
+                                "description": "created",
+                                "examples": { "application/json": { "id": 1 } }
+                            }
+                        }
+                    }
+                },
+                "/items": {
+                    "post": {
+                        "operationId": "createItem",
+                        "parameters": [
+                            {
+                                "name": "body",
+                                "in": "body",
+                                "schema": { "$ref": "#/definitions/Item" }
+                            }
+                        ]
+                    }
+                }
+            }
+        });
+        let mut report = ImportReport::new("openapi");
+        let imported = parse(&doc, &mut report).unwrap();
+        assert_eq!(imported.variables[0].value, "http://files.test/api");
+
+        let upload = request(&imported.items, &["upload"]);
+        let ImportedAuth::Basic { username, .. } = &upload.auth else {
+            panic!("expected basic auth");
+        };
+        assert_eq!(username, "{{username}}");
+        let ImportedBody::FormData(rows) = &upload.body else {
+            panic!("expected form data");
+        };
+        assert_eq!(rows[0].file_path.as_deref(), Some(""));
+        assert!(rows[0].enabled);
+        assert_eq!((rows[1].value.as_str(), rows[1].enabled), ("hi", false));
+        assert_eq!(upload.examples[0].status, 201);
+
+        let ImportedBody::Raw { text, .. } = &request(&imported.items, &["createItem"]).body else {
+            panic!("expected a raw body");
+        };
+        assert_eq!(
+            serde_json::from_str::<Value>(text).unwrap(),
+            json!({ "qty": 0 })
+        );
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        let mut report = ImportReport::new("openapi");
+        assert!(parse(&json!({ "info": { "title": "x" } }), &mut report).is_err());
+    }
+}
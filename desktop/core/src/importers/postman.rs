@@ -0,0 +1,754 @@
+//! Postman collection v2.0/v2.1 import. Mirrors the frontend converter
+//! (`lib/postmanImport.ts`) and adds auth, variables, examples and a report.
+
+use regex::Regex;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use super::{
+    array_of, str_of, value_text, FormRow, ImportReport, ImportedAuth, ImportedBody,
+    ImportedCollection, ImportedEnvironment, ImportedExample, ImportedItem, ImportedRequest,
+    ImportedVariable,
+};
+
+fn enabled(row: &Value) -> bool {
+    !row.get("disabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Postman dynamic variables that LiteFetch resolves under a different name.
+fn rewrite_dynamic(text: &str, report: &mut ImportReport) -> String {
+    static DYNAMIC: OnceLock<Regex> = OnceLock::new();
+    let text = text
+        .replace("{{$guid}}", "{{$uuid}}")
+        .replace("{{$randomUUID}}", "{{$uuid}}");
+    let pattern = DYNAMIC.get_or_init(|| Regex::new(r"\{\{\$(\w+)\}\}").expect("valid regex"));
+    for capture in pattern.captures_iter(&text) {
+        let name = &capture[1];
+        if !matches!(name, "uuid" | "timestamp" | "randomInt") {
+            report.unsupported(format!("dynamic variable {{{{${name}}}}} left unresolved"));
+        }
+    }
+    text
+}
+
+/// Auth blocks may be `[{key, value}]` lists (v2.1) or plain objects (v2.0).
+fn auth_param(auth: &Value, kind: &str, key: &str) -> String {
+    match auth.get(kind) {
+        Some(Value::Array(params)) => params
+            .iter()
+            .find(|p| str_of(p, "key") == key)
+            .map(|p| value_text(p.get("value")))
+            .unwrap_or_default(),
+        Some(obj @ Value::Object(_)) => value_text(obj.get(key)),
+        _ => String::new(),
+    }
+}
+
+enum AuthResolution {
+    Auth(ImportedAuth),
+    /// API key auth becomes a header or query parameter on each request.
+    ApiKey {
+        key: String,
+        value: String,
+        in_query: bool,
+    },
+}
+
+fn resolve_auth(
+    auth: Option<&Value>,
+    parent: &AuthResolution,
+    report: &mut ImportReport,
+) -> AuthResolution {
+    let Some(auth) = auth.filter(|a| !a.is_null()) else {
+        return match parent {
+            AuthResolution::Auth(a) => AuthResolution::Auth(a.clone()),
+            AuthResolution::ApiKey {
+                key,
+                value,
+                in_query,
+            } => AuthResolution::ApiKey {
+                key: key.clone(),
+                value: value.clone(),
+                in_query: *in_query,
+            },
+        };
+    };
+    match str_of(auth, "type") {
+        "noauth" | "" => AuthResolution::Auth(ImportedAuth::None),
+        "basic" => AuthResolution::Auth(ImportedAuth::Basic {
+            username: auth_param(auth, "basic", "username"),
+            password: auth_param(auth, "basic", "password"),
+        }),
+        "bearer" => AuthResolution::Auth(ImportedAuth::Bearer {
+            token: auth_param(auth, "bearer", "token"),
+        }),
+        "apikey" => AuthResolution::ApiKey {
+            key: auth_param(auth, "apikey", "key"),
+            value: auth_param(auth, "apikey", "value"),
+            in_query: auth_param(auth, "apikey", "in") == "query",
+        },
+        other => {
+            report.unsupported(format!(
+                "{other} auth is not supported; requests imported without auth"
+            ));
+            AuthResolution::Auth(ImportedAuth::None)
+        }
+    }
+}
+
+fn clean_expression(expr: &str) -> String {
+    static WRAPPER: OnceLock<Regex> = OnceLock::new();
+    static RESPONSE_JSON: OnceLock<Regex> = OnceLock::new();
+    static BRACKET: OnceLock<Regex> = OnceLock::new();
+    static LEADING_IDENT: OnceLock<Regex> = OnceLock::new();
+
+    let mut cleaned = expr.trim().trim_end_matches(';').trim();
+    cleaned = cleaned.strip_prefix("await ").unwrap_or(cleaned).trim();
+    let mut cleaned = cleaned.to_string();
+
+    // Prefer the first truthy branch before fallbacks.
+    if let Some(q) = cleaned.find('?') {
+        if let Some(c) = cleaned[q..].find(':').map(|c| c + q) {
+            cleaned = cleaned[q + 1..c].trim().to_string();
+        }
+    }
+    cleaned = cleaned
+        .split("||")
+        .next()
+        .unwrap_or_default()
+        .split("??")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+
+    let wrapper = WRAPPER.get_or_init(|| {
+        Regex::new(r"(?s)^(JSON\.stringify|String|Number|Boolean|parseInt|parseFloat)\((.*)\)$")
+            .expect("valid regex")
+    });
+    while let Some(inner) = wrapper.captures(&cleaned).map(|c| c[2].trim().to_string()) {
+        cleaned = inner;
+    }
+
+    let response_json = RESPONSE_JSON
+        .get_or_init(|| Regex::new(r"pm\.response\.json\(\)\.?").expect("valid regex"));
+    let bracket =
+        BRACKET.get_or_init(|| Regex::new(r#"\[['"]([^'"]+)['"]\]"#).expect("valid regex"));
+    cleaned = response_json.replace_all(&cleaned, "").to_string();
+    cleaned = cleaned.replace("?.", ".");
+    cleaned = bracket.replace_all(&cleaned, ".$1").to_string();
+
+    let leading =
+        LEADING_IDENT.get_or_init(|| Regex::new(r"(?s)^[a-zA-Z_]\w*\.(.*)$").expect("valid regex"));
+    if let Some(rest) = leading.captures(&cleaned).map(|c| c[1].to_string()) {
+        cleaned = rest;
+    }
+    cleaned.trim().to_string()
+}
+
+/// Turns `pm.environment.set("name", jsonData.path)` calls into extraction rules.
+fn extraction_rules(script: &str) -> Vec<(String, String)> {
+    static SETTERS: OnceLock<Regex> = OnceLock::new();
+    let setters = SETTERS.get_or_init(|| {
+        Regex::new(
+            r#"(?m)(?:pm\.(?:environment|collectionVariables|globals|variables)\.|^\s*|\s)set\(\s*['"]([^'"]+)['"]\s*,\s*(.*?)\)\s*;?\s*$"#,
+        )
+        .expect("valid regex")
+    });
+    let mut rules: Vec<(String, String)> = Vec::new();
+    for capture in setters.captures_iter(script) {
+        let target = capture[1].to_string();
+        let source = clean_expression(&capture[2]);
+        // Literals aren't response lookups.
+        if source.is_empty() || source.starts_with(['\'', '"', '`']) {
+            continue;
+        }
+        if !rules.iter().any(|(_, t)| *t == target) {
+            rules.push((source, target));
+        }
+    }
+    rules
+}
+
+fn script_text(event: &Value) -> String {
+    match event.get("script").and_then(|s| s.get("exec")) {
+        Some(Value::Array(lines)) => lines
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Some(Value::String(text)) => text.clone(),
+        _ => String::new(),
+    }
+}
+
+fn note_scripts(events: &[Value], report: &mut ImportReport) -> Vec<(String, String)> {
+    let mut rules = Vec::new();
+    for event in events {
+        let script = script_text(event);
+        if script.trim().is_empty() {
+            continue;
+        }
+        match str_of(event, "listen") {
+            "test" => {
+                let found = extraction_rules(&script);
+                let has_other_logic = script.contains("pm.test") || script.contains("pm.expect");
+                if has_other_logic {
+                    report.unsupported("test assertions (pm.test/pm.expect) are not imported");
+                }
+                rules.extend(found);
+            }
+            "prerequest" => report.unsupported("pre-request scripts are not imported"),
+            other => report.unsupported(format!("{other} scripts are not imported")),
+        }
+    }
+    rules
+}
+
+fn build_url(url: &Value, report: &mut ImportReport) -> (String, Vec<FormRow>) {
+    let url = match url {
+        Value::String(raw) => return (rewrite_dynamic(raw, report), Vec::new()),
+        Value::Object(_) => url,
+        _ => return (String::new(), Vec::new()),
+    };
+    let mut raw = str_of(url, "raw").to_string();
+    if raw.is_empty() {
+        let host = match url.get("host") {
+            Some(Value::Array(parts)) => parts
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join("."),
+            Some(Value::String(h)) => h.clone(),
+            _ => String::new(),
+        };
+        let path = match url.get("path") {
+            Some(Value::Array(parts)) => parts
+                .iter()
+                .map(|p| {
+                    p.as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| str_of(p, "value").to_string())
+                })
+                .collect::<Vec<_>>()
+                .join("/"),
+            Some(Value::String(p)) => p.trim_start_matches('/').to_string(),
+            _ => String::new(),
+        };
+        let protocol = str_of(url, "protocol");
+        raw = match protocol {
+            "" => format!("{host}/{path}"),
+            p => format!("{p}://{host}/{path}"),
+        };
+    }
+    // Path variables (`/users/:id`) have no LiteFetch equivalent; inline their values.
+    for variable in array_of(url, "variable") {
+        let key = str_of(variable, "key");
+        if key.is_empty() {
+            continue;
+        }
+        let value = value_text(variable.get("value"));
+        let replacement = if value.is_empty() {
+            format!("{{{{{key}}}}}")
+        } else {
+            value
+        };
+        raw = raw.replace(&format!("/:{key}"), &format!("/{replacement}"));
+    }
+    let query = array_of(url, "query")
+        .iter()
+        .filter(|q| !str_of(q, "key").is_empty())
+        .map(|q| FormRow::text(str_of(q, "key"), value_text(q.get("value")), enabled(q)))
+        .collect();
+    (rewrite_dynamic(&raw, report), query)
+}
+
+fn build_body(body: Option<&Value>, report: &mut ImportReport) -> ImportedBody {
+    let Some(body) = body.filter(|b| b.is_object()) else {
+        return ImportedBody::None;
+    };
+    if body
+        .get("disabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        return ImportedBody::None;
+    }
+    let rows = |key: &str, report: &mut ImportReport| -> Vec<FormRow> {
+        array_of(body, key)
+            .iter()
+            .map(|row| {
+                let file_path = (str_of(row, "type") == "file").then(|| match row.get("src") {
+                    Some(Value::Array(paths)) => {
+                        if paths.len() > 1 {
+                            report.unsupported("multi-file form fields keep only the first file");
+                        }
+                        paths
+                            .first()
+                            .and_then(Value::as_str)
+                            .unwrap_or_default()
+                            .to_string()
+                    }
+                    other => value_text(other),
+                });
+                FormRow {
+                    key: str_of(row, "key").to_string(),
+                    value: match &file_path {
+                        Some(path) => path.clone(),
+                        None => rewrite_dynamic(&value_text(row.get("value")), report),
+                    },
+                    enabled: enabled(row),
+                    file_path,
+                }
+            })
+            .collect()
+    };
+    match str_of(body, "mode") {
+        "urlencoded" => ImportedBody::UrlEncoded(rows("urlencoded", report)),
+        "formdata" => ImportedBody::FormData(rows("formdata", report)),
+        "file" => match body.get("file").and_then(|f| f.get("src")) {
+            Some(Value::String(path)) if !path.is_empty() => ImportedBody::Binary {
+                file_path: path.clone(),
+            },
+            _ => ImportedBody::None,
+        },
+        "graphql" => {
+            let graphql = body.get("graphql").cloned().unwrap_or(Value::Null);
+            let variables = match graphql.get("variables") {
+                Some(Value::String(text)) => serde_json::from_str(text).unwrap_or(Value::Null),
+                Some(other) => other.clone(),
+                None => Value::Null,
+            };
+            let payload = serde_json::json!({
+                "query": str_of(&graphql, "query"),
+                "variables": variables,
+            });
+            ImportedBody::Raw {
+                text: serde_json::to_string_pretty(&payload).unwrap_or_default(),
+                json: true,
+            }
+        }
+        _ => {
+            let text = str_of(body, "raw");
+            let language = body
+                .get("options")
+                .and_then(|o| o.get("raw"))
+                .map(|r| str_of(r, "language"))
+                .unwrap_or_default();
+            ImportedBody::Raw {
+                text: rewrite_dynamic(text, report),
+                json: language == "json",
+            }
+        }
+    }
+}
+
+fn build_examples(responses: &[Value]) -> Vec<ImportedExample> {
+    responses
+        .iter()
+        .map(|response| {
+            let headers: Vec<(String, String)> = array_of(response, "header")
+                .iter()
+                .map(|h| (str_of(h, "key").to_string(), value_text(h.get("value"))))
+                .collect();
+            let content_type = headers
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+                .map(|(_, v)| v.clone())
+                .or_else(|| match str_of(response, "_postman_previewlanguage") {
+                    "json" => Some("application/json".to_string()),
+                    _ => None,
+                });
+            ImportedExample {
+                status: response.get("code").and_then(Value::as_u64).unwrap_or(200) as u16,
+                headers,
+                body: value_text(response.get("body")),
+                content_type,
+            }
+        })
+        .collect()
+}
+
+fn build_request(
+    item: &Value,
+    auth: &AuthResolution,
+    report: &mut ImportReport,
+) -> ImportedRequest {
+    let name = match str_of(item, "name") {
+        "" => "Request",
+        n => n,
+    };
+    let request = item.get("request").cloned().unwrap_or(Value::Null);
+    // A request may be just its URL string.
+    let request = match request {
+        Value::String(url) => serde_json::json!({ "url": url }),
+        other => other,
+    };
+    let (url, query) = build_url(request.get("url").unwrap_or(&Value::Null), report);
+    let method = match str_of(&request, "method") {
+        "" => "GET",
+        m => m,
+    };
+    let mut imported = ImportedRequest::new(name, method, url);
+    imported.query_params = query;
+    // Either a string or `{content, type}`.
+    imported.description = match request.get("description") {
+        Some(Value::String(text)) => Some(text.clone()),
+        Some(description) => Some(str_of(description, "content").to_string()),
+        None => None,
+    };
+    for header in array_of(&request, "header") {
+        let key = str_of(header, "key");
+        if key.is_empty() {
+            continue;
+        }
+        if !enabled(header) {
+            report.unsupported("disabled headers are dropped");
+            continue;
+        }
+        imported.headers.push((
+            key.to_string(),
+            rewrite_dynamic(&value_text(header.get("value")), report),
+        ));
+    }
+    imported.body = build_body(request.get("body"), report);
+    match resolve_auth(request.get("auth"), auth, report) {
+        AuthResolution::Auth(a) => imported.auth = a,
+        AuthResolution::ApiKey {
+            key,
+            value,
+            in_query,
+        } => {
+            if in_query {
+                imported.query_params.push(FormRow::text(key, value, true));
+            } else {
+                imported.headers.push((key, value));
+            }
+        }
+    }
+    imported.extract_rules = note_scripts(array_of(item, "event"), report);
+    imported.examples = build_examples(array_of(item, "response"));
+    imported
+}
+
+fn build_items(
+    items: &[Value],
+    auth: &AuthResolution,
+    report: &mut ImportReport,
+) -> Vec<ImportedItem> {
+    items
+        .iter()
+        .map(|item| {
+            if let Some(children) = item.get("item").and_then(Value::as_array) {
+                let folder_auth = resolve_auth(item.get("auth"), auth, report);
+                if !array_of(item, "event").is_empty() {
+                    report.unsupported("folder-level scripts are not imported");
+                }
+                ImportedItem::Folder {
+                    name: match str_of(item, "name") {
+                        "" => "Folder".to_string(),
+                        n => n.to_string(),
+                    },
+                    items: build_items(children, &folder_auth, report),
+                }
+            } else {
+                ImportedItem::Request(Box::new(build_request(item, auth, report)))
+            }
+        })
+        .collect()
+}
+
+pub fn parse(data: &Value, report: &mut ImportReport) -> Result<ImportedCollection, String> {
+    let info = data
+        .get("info")
+        .ok_or("not a Postman collection (missing info)")?;
+    let schema = str_of(info, "schema");
+    if !schema.is_empty() && !schema.contains("v2.") {
+        return Err(format!("unsupported Postman schema: {schema}"));
+    }
+    if data.get("item").and_then(Value::as_array).is_none() {
+        return Err("not a Postman collection (missing item list)".to_string());
+    }
+    let root_auth = resolve_auth(
+        data.get("auth"),
+        &AuthResolution::Auth(ImportedAuth::None),
+        report,
+    );
+    if !array_of(data, "event").is_empty() {
+        report.unsupported("collection-level scripts are not imported");
+    }
+    let variables = array_of(data, "variable")
+        .iter()
+        .filter(|v| !str_of(v, "key").is_empty() && enabled(v))
+        .map(|v| ImportedVariable {
+            key: str_of(v, "key").to_string(),
+            value: value_text(v.get("value")),
+            secret: str_of(v, "type") == "secret",
+        })
+        .collect();
+    Ok(ImportedCollection {
+        name: match str_of(info, "name") {
+            "" => "Imported Postman Collection".to_string(),
+            n => n.to_string(),
+        },
+        items: build_items(array_of(data, "item"), &root_auth, report),
+        variables,
+        environments: Vec::new(),
+    })
+}
+
+/// Environment and globals exports share a `{name, values: [{key, value, type, enabled}]}`
+/// shape; very old globals dumps are a bare array of values.
+pub fn parse_environment(data: &Value) -> Result<(ImportedEnvironment, bool), String> {
+    let (values, name, globals) = match data {
+        Value::Array(values) => (values.as_slice(), "globals", true),
+        Value::Object(_) if data.get("values").is_some_and(Value::is_array) => (
+            array_of(data, "values"),
+            str_of(data, "name"),
+            str_of(data, "_postman_variable_scope") == "globals",
+        ),
+        _ => return Err("not a Postman environment or globals export".to_string()),
+    };
+    let variables = values
+        .iter()
+        .filter(|v| !str_of(v, "key").is_empty())
+        .filter(|v| v.get("enabled").and_then(Value::as_bool).unwrap_or(true))
+        .map(|v| ImportedVariable {
+            key: str_of(v, "key").to_string(),
+            value: value_text(v.get("value")),
+            secret: str_of(v, "type") == "secret",
+        })
+        .collect();
+    let name = match name {
+        "" => "imported",
+        n => n,
+    };
+    Ok((
+        ImportedEnvironment {
+            name: name.to_string(),
+            variables,
+        },
+        globals,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn export() -> Value {
+        json!({
+            "info": {
+                "name": "Shop",
+                "schema": "https://schema.getpostman.com/json/collection/v2.1.0/collection.json"
+            },
+            "auth": { "type": "bearer", "bearer": [{ "key": "token", "value": "{{token}}" }] },
+            "variable": [
+                { "key": "baseUrl", "value": "https://shop.test" },
+                { "key": "token", "value": "s3cret", "type": "secret" },
+                { "key": "old", "value": "x", "disabled": true }
+            ],
+            "item": [
+                {
+                    "name": "Orders",
+                    "item": [
+                        {
+                            "name": "Admin",
+                            "auth": {
+                                "type": "basic",
+                                "basic": [
+                                    { "key": "username", "value": "admin" },
+                                    { "key": "password", "value": "hunter2" }
+                                ]
+                            },
+                            "item": [{
+                                "name": "Delete order",
+                                "request": {
+                                    "method": "DELETE",
+                                    "url": {
+                                        "raw": "{{baseUrl}}/orders/:id",
+                                        "variable": [{ "key": "id", "value": "42" }]
+                                    }
+                                }
+                            }]
+                        },
+                        {
+                            "name": "Create order",
+                            "event": [{
+                                "listen": "test",
+                                "script": {
+                                    "exec": [
+                                        "const jsonData = pm.response.json();",
+                                        "pm.environment.set(\"orderId\", jsonData.data.id);"
+                                    ]
+                                }
+                            }],
+                            "request": {
+                                "method": "POST",
+                                "header": [{ "key": "X-Trace", "value": "{{$guid}}" }],
+                                "url": {
+                                    "raw": "{{baseUrl}}/orders?dry=1",
+                                    "query": [{ "key": "dry", "value": "1" }]
+                                },
+                                "body": {
+                                    "mode": "raw",
+                                    "raw": "{\"sku\": \"A1\"}",
+                                    "options": { "raw": { "language": "json" } }
+                                }
+                            },
+                            "response": [{
+                                "code": 201,
+                                "header": [{ "key": "Content-Type", "value": "application/json" }],
+                                "body": "{\"data\": {\"id\": 7}}"
+                            }]
+                        }
+                    ]
+                },
+                {
+                    "name": "Upload",
+                    "request": {
+                        "method": "POST",
+                        "url": "{{baseUrl}}/files",
+                        "body": {
+                            "mode": "formdata",
+                            "formdata": [
+                                { "key": "file", "type": "file", "src": "/tmp/a.png" },
+                                { "key": "note", "value": "hi", "disabled": true }
+                            ]
+                        }
+                    }
+                },
+                {
+                    "name": "Login",
+                    "request": {
+                        "method": "POST",
+                        "auth": { "type": "noauth" },
+                        "url": "{{baseUrl}}/login",
+                        "body": {
+                            "mode": "urlencoded",
+                            "urlencoded": [{ "key": "user", "value": "ann" }]
+                        }
+                    }
+                }
+            ]
+        })
+    }
+
+    fn request<'a>(items: &'a [ImportedItem], path: &[&str]) -> &'a ImportedRequest {
+        let (first, rest) = path.split_first().unwrap();
+        let item = items
+            .iter()
+            .find(|item| match item {
+                ImportedItem::Folder { name, .. } => name == first,
+                ImportedItem::Request(request) => request.name == *first,
+            })
+            .unwrap();
+        match item {
+            ImportedItem::Folder { items, .. } => request(items, rest),
+            ImportedItem::Request(request) => request,
+        }
+    }
+
+    #[test]
+    fn parses_nested_folders_with_inherited_auth() {
+        let mut report = ImportReport::new("postman");
+        let imported = parse(&export(), &mut report).unwrap();
+        assert_eq!(imported.name, "Shop");
+        assert_eq!(imported.items.len(), 3);
+
+        let delete = request(&imported.items, &["Orders", "Admin", "Delete order"]);
+        assert_eq!(delete.url, "{{baseUrl}}/orders/42");
+        let ImportedAuth::Basic { username, password } = &delete.auth else {
+            panic!("expected the Admin folder's basic auth");
+        };
+        assert_eq!((username.as_str(), password.as_str()), ("admin", "hunter2"));
+        let create = request(&imported.items, &["Orders", "Create order"]);
+        assert!(matches!(&create.auth, ImportedAuth::Bearer { token } if token == "{{token}}"));
+        let login = request(&imported.items, &["Login"]);
+        assert!(matches!(login.auth, ImportedAuth::None));
+    }
+
+    #[test]
+    fn parses_variables_bodies_scripts_and_examples() {
+        let mut report = ImportReport::new("postman");
+        let imported = parse(&export(), &mut report).unwrap();
+        let variables: Vec<_> = imported
+            .variables
+            .iter()
+            .map(|v| (v.key.as_str(), v.value.as_str(), v.secret))
+            .collect();
+        assert_eq!(
+            variables,
+            [
+                ("baseUrl", "https://shop.test", false),
+                ("token", "s3cret", true)
+            ]
+        );
+
+        let create = request(&imported.items, &["Orders", "Create order"]);
+        assert_eq!(
+            create.headers,
+            [("X-Trace".to_string(), "{{$uuid}}".to_string())]
+        );
+        assert_eq!(create.query_params[0].key, "dry");
+        let ImportedBody::Raw { text, json } = &create.body else {
+            panic!("expected a raw body");
+        };
+        assert!(json);
+        assert_eq!(text, "{\"sku\": \"A1\"}");
+        assert_eq!(
+            create.extract_rules,
+            [("data.id".to_string(), "orderId".to_string())]
+        );
+        assert_eq!(create.examples[0].status, 201);
+        assert_eq!(
+            create.examples[0].content_type.as_deref(),
+            Some("application/json")
+        );
+
+        let ImportedBody::FormData(rows) = &request(&imported.items, &["Upload"]).body else {
+            panic!("expected form data");
+        };
+        assert_eq!(rows[0].file_path.as_deref(), Some("/tmp/a.png"));
+        assert!(!rows[1].enabled);
+        let ImportedBody::UrlEncoded(rows) = &request(&imported.items, &["Login"]).body else {
+            panic!("expected a url-encoded body");
+        };
+        assert_eq!(
+            (rows[0].key.as_str(), rows[0].value.as_str()),
+            ("user", "ann")
+        );
+    }
+
+    #[test]
+    fn parses_environment_and_globals_exports() {
+        let environment = json!({
+            "name": "Staging",
+            "values": [
+                { "key": "host", "value": "staging.test", "enabled": true },
+                { "key": "key", "value": "k", "type": "secret" },
+                { "key": "off", "value": "x", "enabled": false }
+            ]
+        });
+        let (imported, globals) = parse_environment(&environment).unwrap();
+        assert!(!globals);
+        assert_eq!(imported.name, "Staging");
+        assert_eq!(imported.variables.len(), 2);
+        assert!(imported.variables[1].secret);
+
+        let (imported, globals) = parse_environment(&json!([{ "key": "a", "value": 1 }])).unwrap();
+        assert!(globals);
+        assert_eq!(imported.variables[0].value, "1");
+    }
+
+    #[test]
+    fn rejects_other_documents() {
+        let mut report = ImportReport::new("postman");
+        assert!(parse(&json!({ "item": [] }), &mut report).is_err());
+        let v1 = json!({ "info": { "schema": "collection/v1.0.0" }, "item": [] });
+        assert!(parse(&v1, &mut report).is_err());
+    }
+}
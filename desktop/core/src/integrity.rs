@@ -0,0 +1,227 @@
+//! Response body checksums: MD5 and SHA-256 of every body, checked against the digests the
+//! server sent (`Content-MD5`, RFC 3230 `Digest`, RFC 9530 `Content-Digest` and
+//! `Repr-Digest`) and against a hash the request expects.
+//!
+//! Header digests cover the body as sent on the wire; when it came content-encoded (gzip and
+//! the like) only the decoded body is known, so those checks are skipped rather than failed.
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256, Sha512};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Match,
+    Mismatch,
+    /// The digest names an algorithm this app doesn't compute.
+    Unsupported,
+    /// The digest can't be checked against the body as received (see the module docs).
+    Skipped,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DigestCheck {
+    /// The header it came from, or `expected` for the request's own hash.
+    pub source: String,
+    /// `md5`, `sha-256` or `sha-512`, or the name as given when unsupported.
+    pub algorithm: String,
+    pub expected: String,
+    /// Hex, like `expected` when that was hex, otherwise base64.
+    pub actual: Option<String>,
+    pub status: CheckStatus,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Checksums {
+    pub size: u64,
+    pub md5: String,
+    pub sha256: String,
+}
+
+/// MD5 (RFC 1321). Only for checking digests servers still send; not for anything secure.
+pub fn md5(data: &[u8]) -> [u8; 16] {
+    const S: [u32; 64] = [
+        7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 7, 12, 17, 22, 5, 9, 14, 20, 5, 9, 14, 20, 5,
+        9, 14, 20, 5, 9, 14, 20, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 4, 11, 16, 23, 6, 10,
+        15, 21, 6, 10, 15, 21, 6, 10, 15, 21, 6, 10, 15, 21,
+    ];
+    let k: Vec<u32> = (0..64)
+        .map(|i| ((i as f64 + 1.0).sin().abs() * 4_294_967_296.0) as u32)
+        .collect();
+    let mut state: [u32; 4] = [0x6745_2301, 0xefcd_ab89, 0x98ba_dcfe, 0x1032_5476];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64).wrapping_mul(8)).to_le_bytes());
+
+    for block in message.chunks_exact(64) {
+        let words: Vec<u32> = block
+            .chunks_exact(4)
+            .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+            .collect();
+        let [mut a, mut b, mut c, mut d] = state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(k[i])
+                .wrapping_add(words[g])
+                .rotate_left(S[i]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+        for (word, add) in state.iter_mut().zip([a, b, c, d]) {
+            *word = word.wrapping_add(add);
+        }
+    }
+    let mut out = [0; 16];
+    for (chunk, word) in out.chunks_exact_mut(4).zip(state) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+    out
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+pub fn checksums(body: &[u8]) -> Checksums {
+    Checksums {
+        size: body.len() as u64,
+        md5: hex(&md5(body)),
+        sha256: hex(&Sha256::digest(body)),
+    }
+}
+
+/// The digest of `body` under `algorithm`, in any of its usual spellings.
+fn digest(algorithm: &str, body: &[u8]) -> Option<(&'static str, Vec<u8>)> {
+    match algorithm
+        .trim()
+        .to_ascii_lowercase()
+        .replace('_', "-")
+        .as_str()
+    {
+        "md5" => Some(("md5", md5(body).to_vec())),
+        "sha-256" | "sha256" => Some(("sha-256", Sha256::digest(body).to_vec())),
+        "sha-512" | "sha512" => Some(("sha-512", Sha512::digest(body).to_vec())),
+        _ => None,
+    }
+}
+
+/// Compares `expected` (hex or base64) with the digest, returning the digest in the same
+/// encoding.
+fn compare(expected: &str, actual: &[u8]) -> (String, CheckStatus) {
+    let expected = expected.trim();
+    let is_hex =
+        expected.len() == actual.len() * 2 && expected.chars().all(|c| c.is_ascii_hexdigit());
+    let (shown, matches) = match is_hex {
+        true => {
+            let shown = hex(actual);
+            let matches = shown.eq_ignore_ascii_case(expected);
+            (shown, matches)
+        }
+        false => {
+            let shown = STANDARD.encode(actual);
+            // Padding is optional in practice, and some servers use the URL-safe alphabet.
+            let unpadded = expected.trim_end_matches('=');
+            let matches = STANDARD_NO_PAD
+                .decode(unpadded)
+                .or_else(|_| URL_SAFE_NO_PAD.decode(unpadded))
+                .is_ok_and(|decoded| decoded == actual);
+            (shown, matches)
+        }
+    };
+    let status = match matches {
+        true => CheckStatus::Match,
+        false => CheckStatus::Mismatch,
+    };
+    (shown, status)
+}
+
+fn check(source: &str, algorithm: &str, expected: &str, body: &[u8], skip: bool) -> DigestCheck {
+    let computed = digest(algorithm, body);
+    let (algorithm, actual, status) = match (computed, skip) {
+        (None, _) => (algorithm.trim().to_string(), None, CheckStatus::Unsupported),
+        (Some((name, _)), true) => (name.to_string(), None, CheckStatus::Skipped),
+        (Some((name, digest)), false) => {
+            let (shown, status) = compare(expected, &digest);
+            (name.to_string(), Some(shown), status)
+        }
+    };
+    DigestCheck {
+        source: source.to_string(),
+        algorithm,
+        expected: expected.trim().to_string(),
+        actual,
+        status,
+    }
+}
+
+/// Checks `body` against every digest header of a response.
+pub fn verify_headers(body: &[u8], headers: &Map<String, Value>) -> Vec<DigestCheck> {
+    let encoded = headers.iter().any(|(name, value)| {
+        name.eq_ignore_ascii_case("content-encoding")
+            && value
+                .as_str()
+                .is_some_and(|v| !v.trim().is_empty() && !v.trim().eq_ignore_ascii_case("identity"))
+    });
+    let mut checks = Vec::new();
+    for (name, value) in headers {
+        let Some(value) = value.as_str() else {
+            continue;
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "content-md5" => checks.push(check(name, "md5", value, body, encoded)),
+            // `SHA-256=<base64>, MD5=<base64>`
+            "digest" => {
+                for part in value.split(',') {
+                    if let Some((algorithm, expected)) = part.split_once('=') {
+                        checks.push(check(name, algorithm, expected, body, encoded));
+                    }
+                }
+            }
+            // Structured fields: `sha-256=:<base64>:, sha-512=:<base64>:`
+            "content-digest" | "repr-digest" => {
+                for part in value.split(',') {
+                    if let Some((algorithm, expected)) = part.split_once('=') {
+                        let expected = expected.trim().trim_matches(':');
+                        checks.push(check(name, algorithm, expected, body, encoded));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    checks
+}
+
+/// Checks `body` against a hash the request expects: `<algorithm>:<hex or base64>`, or bare
+/// hex, whose length tells MD5, SHA-256 and SHA-512 apart.
+pub fn verify_expected(body: &[u8], expected: &str) -> DigestCheck {
+    let expected = expected.trim();
+    let (algorithm, value) = match expected.split_once(':') {
+        Some((algorithm, value)) => (algorithm.to_string(), value),
+        None => {
+            let algorithm = match expected.len() {
+                32 => "md5",
+                128 => "sha-512",
+                _ => "sha-256",
+            };
+            (algorithm.to_string(), expected)
+        }
+    };
+    check("expected", &algorithm, value, body, false)
+}
@@ -0,0 +1,88 @@
+//! jq programs over response bodies, through jaq with its standard library: for display,
+//! for extracting values in workflows and for assertions.
+
+use jaq_core::load::{Arena, File, Loader};
+use jaq_core::{Compiler, Ctx, RcIter};
+use jaq_json::Val;
+use serde_json::Value;
+
+/// Outputs collected from one program; generators such as `repeat` never end on their own.
+const MAX_OUTPUTS: usize = 10_000;
+
+/// The text an error points at, kept short.
+fn near(text: &str) -> String {
+    let text: String = text.chars().take(20).collect();
+    match text.trim().is_empty() {
+        true => "the end".to_string(),
+        false => format!("`{}`", text.trim()),
+    }
+}
+
+/// Runs `program` against `input` and returns every output, or the first error.
+pub fn run(program: &str, input: Value) -> Result<Vec<Value>, String> {
+    let loader = Loader::new(jaq_std::defs().chain(jaq_json::defs()));
+    let arena = Arena::default();
+    let file = File {
+        code: program,
+        path: (),
+    };
+    let modules = loader.load(&arena, file).map_err(|errors| {
+        let messages: Vec<String> = errors
+            .into_iter()
+            .flat_map(|(_, error)| match error {
+                jaq_core::load::Error::Io(errors) => errors
+                    .into_iter()
+                    .map(|(path, e)| format!("{path}: {e}"))
+                    .collect(),
+                jaq_core::load::Error::Lex(errors) => errors
+                    .into_iter()
+                    .map(|(expected, found)| {
+                        format!("expected {} near {}", expected.as_str(), near(found))
+                    })
+                    .collect(),
+                jaq_core::load::Error::Parse(errors) => errors
+                    .into_iter()
+                    .map(|(expected, found)| {
+                        format!("expected {} near {}", expected.as_str(), near(found))
+                    })
+                    .collect::<Vec<_>>(),
+            })
+            .collect();
+        format!("jq parse failed: {}", messages.join("; "))
+    })?;
+    let filter = Compiler::default()
+        .with_funs(jaq_std::funs().chain(jaq_json::funs()))
+        .compile(modules)
+        .map_err(|errors| {
+            let messages: Vec<String> = errors
+                .into_iter()
+                .flat_map(|(_, errors)| errors)
+                .map(|(name, undefined)| format!("undefined {} {name}", undefined.as_str()))
+                .collect();
+            format!("jq compile failed: {}", messages.join("; "))
+        })?;
+
+    let inputs = RcIter::new(core::iter::empty());
+    filter
+        .run((Ctx::new([], &inputs), Val::from(input)))
+        .take(MAX_OUTPUTS)
+        .map(|output| {
+            output
+                .map(Value::from)
+                .map_err(|e| format!("jq failed: {e}"))
+        })
+        .collect()
+}
+
+/// A program's result as one value, for extractions and assertions: its only output, an
+/// array of them when there are several, or `None` when it produced nothing (or only
+/// null) or failed.
+pub fn select_value(program: &str, input: Value) -> Option<Value> {
+    let mut outputs = run(program, input).ok()?;
+    match outputs.len() {
+        // `.missing` yields null; treat it as absent, as a JSONPath miss is.
+        0 => None,
+        1 => outputs.pop().filter(|v| !v.is_null()),
+        _ => Some(Value::Array(outputs)),
+    }
+}
@@ -0,0 +1,45 @@
+//! Helpers for the loosely typed JSON of collections, environments and backend results.
+
+use serde_json::Value;
+
+pub fn str_of<'a>(value: &'a Value, key: &str) -> &'a str {
+    value.get(key).and_then(Value::as_str).unwrap_or_default()
+}
+
+pub fn array_of<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+}
+
+/// Stringifies a loosely typed export value; exports often store numbers or booleans.
+pub fn value_text(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// Finds a request by id anywhere in a collection's item tree.
+pub fn find_request<'a>(items: &'a [Value], request_id: &str) -> Option<&'a Value> {
+    items
+        .iter()
+        .find_map(|item| match item.get("items").and_then(Value::as_array) {
+            Some(children) => find_request(children, request_id),
+            None => (str_of(item, "id") == request_id).then_some(item),
+        })
+}
+
+/// Counts the requests in a collection's item tree, folders excluded.
+pub fn count_requests(items: &[Value]) -> usize {
+    items
+        .iter()
+        .map(|item| match item.get("items").and_then(Value::as_array) {
+            Some(children) => count_requests(children),
+            None => 1,
+        })
+        .sum()
+}
@@ -0,0 +1,529 @@
+//! JSONPath over response bodies, returning each match with its normalized path. Supports
+//! `$.a.b`, `$['a']`, `[0]`, `[-1]`, `[*]` and `.*`, `..key` (recursive descent), unions
+//! `[0,2]` / `['a','b']`, slices `[1:3]` / `[::2]` / `[::-1]` and filters
+//! `[?(@.price < 10)]`, `[?(@.id == 'x')]` or `[?(@.email)]`.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::schema::child_path;
+
+#[derive(Serialize, Clone)]
+pub struct Match {
+    /// Where the value sits, e.g. `$.items[2].id`.
+    pub path: String,
+    pub value: Value,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+enum Selector {
+    Key(String),
+    Index(i64),
+    Wildcard,
+    /// `..key`, or `..*` without a key.
+    Descendants(Option<String>),
+    Keys(Vec<String>),
+    Indexes(Vec<i64>),
+    /// `start:end:step`; the step is never 0.
+    Slice(Option<i64>, Option<i64>, i64),
+    /// `@` followed by a relative path, compared with a literal; existence without one.
+    Filter(Vec<Selector>, Option<(Comparison, Value)>),
+}
+
+impl Selector {
+    /// Whether the selector can pick more than one value.
+    fn plural(&self) -> bool {
+        !matches!(self, Selector::Key(_) | Selector::Index(_))
+    }
+}
+
+fn unquote(text: &str) -> Option<String> {
+    let text = text.trim();
+    ['\'', '"'].iter().find_map(|&quote| {
+        text.strip_prefix(quote)
+            .and_then(|t| t.strip_suffix(quote))
+            .map(|t| t.replace(&format!("\\{quote}"), &quote.to_string()))
+    })
+}
+
+fn literal(text: &str) -> Result<Value, String> {
+    let text = text.trim();
+    if let Some(text) = unquote(text) {
+        return Ok(Value::String(text));
+    }
+    serde_json::from_str(text).map_err(|_| format!("invalid JSONPath literal: {text}"))
+}
+
+fn filter(expression: &str) -> Result<Selector, String> {
+    let expression = expression.trim();
+    let inner = expression
+        .strip_prefix('(')
+        .and_then(|e| e.strip_suffix(')'))
+        .unwrap_or(expression)
+        .trim();
+    let operators = [
+        ("==", Comparison::Eq),
+        ("!=", Comparison::Ne),
+        ("<=", Comparison::Le),
+        (">=", Comparison::Ge),
+        ("<", Comparison::Lt),
+        (">", Comparison::Gt),
+    ];
+    let (left, comparison) = match operators
+        .iter()
+        .find_map(|(op, comparison)| inner.split_once(op).map(|split| (split, *comparison)))
+    {
+        Some(((left, right), comparison)) => (left, Some((comparison, literal(right)?))),
+        None => (inner, None),
+    };
+    let relative = left
+        .trim()
+        .strip_prefix('@')
+        .ok_or_else(|| format!("filter must start with @: {expression}"))?;
+    Ok(Selector::Filter(parse_segments(relative)?, comparison))
+}
+
+fn index(text: &str) -> Result<i64, String> {
+    text.trim()
+        .parse()
+        .map_err(|_| format!("invalid JSONPath index: {text}"))
+}
+
+/// `text` split at `separator`s that aren't inside quotes.
+fn split_unquoted(text: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), '\\') => escaped = true,
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, c) if c == separator => {
+                parts.push(&text[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&text[start..]);
+    parts
+}
+
+fn bracket(inner: &str) -> Result<Selector, String> {
+    let inner = inner.trim();
+    if inner == "*" {
+        return Ok(Selector::Wildcard);
+    }
+    if let Some(expression) = inner.strip_prefix('?') {
+        return filter(expression);
+    }
+    let parts = split_unquoted(inner, ',');
+    if let (1, Some(key)) = (parts.len(), unquote(inner)) {
+        return Ok(Selector::Key(key));
+    }
+    if parts.len() == 1 && inner.contains(':') {
+        let bound = |text: &str| match text.trim() {
+            "" => Ok(None),
+            text => index(text).map(Some),
+        };
+        let (start, end, step) = match inner.split(':').collect::<Vec<_>>()[..] {
+            [start, end] => (start, end, 1),
+            [start, end, step] => (start, end, bound(step)?.unwrap_or(1)),
+            _ => return Err(format!("invalid JSONPath slice: {inner}")),
+        };
+        if step == 0 {
+            return Err("a JSONPath slice step can't be 0".to_string());
+        }
+        return Ok(Selector::Slice(bound(start)?, bound(end)?, step));
+    }
+    if parts.len() > 1 {
+        return match parts.iter().all(|p| unquote(p).is_some()) {
+            true => Ok(Selector::Keys(
+                parts.iter().filter_map(|p| unquote(p)).collect(),
+            )),
+            false => parts
+                .iter()
+                .map(|p| index(p))
+                .collect::<Result<_, _>>()
+                .map(Selector::Indexes),
+        };
+    }
+    index(inner).map(Selector::Index)
+}
+
+/// Where the bracket that opens at the start of `text` closes, skipping quoted text and
+/// nested brackets.
+fn closing(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '[' | '(') => depth += 1,
+            (None, ']' | ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+fn name_end(text: &str) -> usize {
+    text.find(['.', '[', ' ', '=', '!', '<', '>', ')'])
+        .unwrap_or(text.len())
+}
+
+fn parse_segments(mut rest: &str) -> Result<Vec<Selector>, String> {
+    let mut selectors = Vec::new();
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return Ok(selectors);
+        }
+        if let Some(after) = rest.strip_prefix("..") {
+            if after.starts_with('[') {
+                let end = closing(after).ok_or("unclosed [ in JSONPath")?;
+                let key = match bracket(&after[1..end])? {
+                    Selector::Key(key) => Some(key),
+                    Selector::Wildcard => None,
+                    _ => return Err("only a key or * may follow ..".to_string()),
+                };
+                selectors.push(Selector::Descendants(key));
+                rest = &after[end + 1..];
+            } else {
+                let end = name_end(after);
+                let key = &after[..end];
+                selectors.push(Selector::Descendants((key != "*").then(|| key.to_string())));
+                rest = &after[end..];
+            }
+        } else if let Some(after) = rest.strip_prefix('.') {
+            let end = name_end(after);
+            if end == 0 {
+                return Err(format!("expected a key after . in JSONPath: {rest}"));
+            }
+            selectors.push(match &after[..end] {
+                "*" => Selector::Wildcard,
+                key => Selector::Key(key.to_string()),
+            });
+            rest = &after[end..];
+        } else if rest.starts_with('[') {
+            let end = closing(rest).ok_or("unclosed [ in JSONPath")?;
+            selectors.push(bracket(&rest[1..end])?);
+            rest = &rest[end + 1..];
+        } else {
+            // A bare leading key: `data.items` as `$.data.items`.
+            let end = name_end(rest);
+            if end == 0 {
+                return Err(format!("unexpected JSONPath text: {rest}"));
+            }
+            selectors.push(Selector::Key(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+    }
+}
+
+fn parse(path: &str) -> Result<Vec<Selector>, String> {
+    let path = path.trim();
+    let rest = path
+        .strip_prefix('$')
+        .ok_or_else(|| format!("JSONPath must start with $: {path}"))?;
+    parse_segments(rest)
+}
+
+fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let index = match index < 0 {
+        true => len as i64 + index,
+        false => index,
+    };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+/// The indexes a slice picks from an array of `len`, in the order it picks them (RFC 9535).
+fn slice_indexes(start: Option<i64>, end: Option<i64>, step: i64, len: usize) -> Vec<usize> {
+    let len = len as i64;
+    let normalize = |bound: i64| match bound < 0 {
+        true => len + bound,
+        false => bound,
+    };
+    let mut out = Vec::new();
+    if step > 0 {
+        let lower = start.map_or(0, normalize).clamp(0, len);
+        let upper = end.map_or(len, normalize).clamp(0, len);
+        let mut i = lower;
+        while i < upper {
+            out.push(i as usize);
+            i += step;
+        }
+    } else {
+        let upper = start.map_or(len - 1, normalize).clamp(-1, len - 1);
+        let lower = end.map_or(-1, normalize).clamp(-1, len - 1);
+        let mut i = upper;
+        while lower < i {
+            out.push(i as usize);
+            i += step;
+        }
+    }
+    out
+}
+
+fn compare(actual: &Value, comparison: Comparison, expected: &Value) -> bool {
+    let ordering = match (actual, expected) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().partial_cmp(&b.as_f64()),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match comparison {
+        Comparison::Eq => actual == expected || ordering == Some(std::cmp::Ordering::Equal),
+        Comparison::Ne => actual != expected && ordering != Some(std::cmp::Ordering::Equal),
+        Comparison::Lt => ordering == Some(std::cmp::Ordering::Less),
+        Comparison::Le => ordering.is_some_and(|o| o.is_le()),
+        Comparison::Gt => ordering == Some(std::cmp::Ordering::Greater),
+        Comparison::Ge => ordering.is_some_and(|o| o.is_ge()),
+    }
+}
+
+fn descendants<'a>(
+    path: &str,
+    value: &'a Value,
+    key: Option<&str>,
+    out: &mut Vec<(String, &'a Value)>,
+) {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => map.iter().map(|(k, v)| (child_path(path, k), v)).collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (format!("{path}[{i}]"), v))
+            .collect(),
+        _ => return,
+    };
+    if let (Some(key), Value::Object(map)) = (key, value) {
+        if let Some(found) = map.get(key) {
+            out.push((child_path(path, key), found));
+        }
+    }
+    for (child, item) in children {
+        if key.is_none() {
+            out.push((child.clone(), item));
+        }
+        descendants(&child, item, key, out);
+    }
+}
+
+fn select<'a>(
+    selectors: &[Selector],
+    path: String,
+    value: &'a Value,
+    out: &mut Vec<(String, &'a Value)>,
+) {
+    let Some((selector, rest)) = selectors.split_first() else {
+        out.push((path, value));
+        return;
+    };
+    let mut next = Vec::new();
+    match (selector, value) {
+        (Selector::Key(key), Value::Object(map)) => {
+            if let Some(found) = map.get(key) {
+                next.push((child_path(&path, key), found));
+            }
+        }
+        (Selector::Keys(keys), Value::Object(map)) => {
+            for key in keys {
+                if let Some(found) = map.get(key) {
+                    next.push((child_path(&path, key), found));
+                }
+            }
+        }
+        (Selector::Index(i), Value::Array(items)) => {
+            if let Some(i) = resolve_index(*i, items.len()) {
+                next.push((format!("{path}[{i}]"), &items[i]));
+            }
+        }
+        (Selector::Indexes(indexes), Value::Array(items)) => {
+            for i in indexes
+                .iter()
+                .filter_map(|&i| resolve_index(i, items.len()))
+            {
+                next.push((format!("{path}[{i}]"), &items[i]));
+            }
+        }
+        (Selector::Slice(start, end, step), Value::Array(items)) => {
+            for i in slice_indexes(*start, *end, *step, items.len()) {
+                next.push((format!("{path}[{i}]"), &items[i]));
+            }
+        }
+        (Selector::Wildcard, Value::Object(map)) => {
+            next.extend(map.iter().map(|(k, v)| (child_path(&path, k), v)));
+        }
+        (Selector::Wildcard, Value::Array(items)) => {
+            next.extend(
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| (format!("{path}[{i}]"), v)),
+            );
+        }
+        (Selector::Descendants(key), _) => {
+            descendants(&path, value, key.as_deref(), &mut next);
+        }
+        (Selector::Filter(relative, comparison), Value::Array(_) | Value::Object(_)) => {
+            let children: Vec<(String, &Value)> = match value {
+                Value::Array(items) => items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| (format!("{path}[{i}]"), v))
+                    .collect(),
+                Value::Object(map) => map.iter().map(|(k, v)| (child_path(&path, k), v)).collect(),
+                _ => Vec::new(),
+            };
+            for (child, item) in children {
+                let mut found = Vec::new();
+                select(relative, String::new(), item, &mut found);
+                let keep = match comparison {
+                    None => !found.is_empty(),
+                    Some((comparison, expected)) => found
+                        .iter()
+                        .any(|(_, actual)| compare(actual, *comparison, expected)),
+                };
+                if keep {
+                    next.push((child, item));
+                }
+            }
+        }
+        _ => {}
+    }
+    for (path, value) in next {
+        select(rest, path, value, out);
+    }
+}
+
+/// Every value `path` selects in `root`, in document order, with its path.
+pub fn query(root: &Value, path: &str) -> Result<Vec<Match>, String> {
+    let selectors = parse(path)?;
+    let mut found = Vec::new();
+    select(&selectors, "$".to_string(), root, &mut found);
+    Ok(found
+        .into_iter()
+        .map(|(path, value)| Match {
+            path,
+            value: value.clone(),
+        })
+        .collect())
+}
+
+/// The value `path` selects, for extractions and assertions: the match itself for a path
+/// naming one value, or an array of every match when it has wildcards, filters, slices or
+/// unions. `None` when a single-value path matches nothing or the path doesn't parse. A
+/// bare leading key is read from the root, so `data.next` is `$.data.next`.
+pub fn select_value(root: &Value, path: &str) -> Option<Value> {
+    let path = path.trim();
+    let selectors = match path.starts_with('$') {
+        true => parse(path).ok()?,
+        false => parse_segments(path).ok()?,
+    };
+    let mut found = Vec::new();
+    select(&selectors, "$".to_string(), root, &mut found);
+    match selectors.iter().any(Selector::plural) {
+        true => Some(Value::Array(
+            found.into_iter().map(|(_, v)| v.clone()).collect(),
+        )),
+        false => found.into_iter().next().map(|(_, v)| v.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(root: &Value, path: &str) -> Vec<String> {
+        query(root, path)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.path)
+            .collect()
+    }
+
+    #[test]
+    fn slices_step() {
+        let root = json!({ "arr": [0, 1, 2, 3, 4] });
+        assert_eq!(
+            paths(&root, "$.arr[::2]"),
+            ["$.arr[0]", "$.arr[2]", "$.arr[4]"]
+        );
+        assert_eq!(paths(&root, "$.arr[1:4:2]"), ["$.arr[1]", "$.arr[3]"]);
+        assert_eq!(paths(&root, "$.arr[::-1]").len(), 5);
+        assert_eq!(paths(&root, "$.arr[::-1]")[0], "$.arr[4]");
+        assert_eq!(paths(&root, "$.arr[3:0:-2]"), ["$.arr[3]", "$.arr[1]"]);
+        assert_eq!(paths(&root, "$.arr[-2:]"), ["$.arr[3]", "$.arr[4]"]);
+        assert_eq!(paths(&root, "$.arr[1:3]"), ["$.arr[1]", "$.arr[2]"]);
+        assert!(query(&root, "$.arr[::0]").is_err());
+        assert!(query(&root, "$.arr[1:2:3:4]").is_err());
+    }
+
+    #[test]
+    fn quoted_keys_may_hold_separators() {
+        let root = json!({ "k,1": "comma", "a:b": "colon", "x": 1, "y": 2 });
+        assert_eq!(query(&root, "$['k,1']").unwrap()[0].value, json!("comma"));
+        assert_eq!(query(&root, "$['a:b']").unwrap()[0].value, json!("colon"));
+        let both = query(&root, "$['k,1','x']").unwrap();
+        assert_eq!(both.len(), 2);
+        assert_eq!(both[1].value, json!(1));
+    }
+
+    #[test]
+    fn paths_start_at_the_root() {
+        let root = json!({ "a": { "b": 1 } });
+        let Err(e) = query(&root, "a.b") else {
+            panic!("a path without $ should fail");
+        };
+        assert!(e.contains("must start with $"));
+        assert_eq!(query(&root, "$.a.b").unwrap()[0].value, json!(1));
+        // Extractions still read a bare leading key from the root.
+        assert_eq!(select_value(&root, "a.b"), Some(json!(1)));
+    }
+
+    #[test]
+    fn selectors() {
+        let root = json!({ "items": [
+            { "id": "x", "price": 5, "email": "a@b" },
+            { "id": "y", "price": 15 },
+        ] });
+        assert_eq!(paths(&root, "$.items[-1].id"), ["$.items[1].id"]);
+        assert_eq!(paths(&root, "$.items[0,1].id").len(), 2);
+        assert_eq!(
+            paths(&root, "$..price"),
+            ["$.items[0].price", "$.items[1].price"]
+        );
+        assert_eq!(
+            paths(&root, "$.items[?(@.price < 10)].id"),
+            ["$.items[0].id"]
+        );
+        assert_eq!(paths(&root, "$.items[?(@.id == 'y')]"), ["$.items[1]"]);
+        assert_eq!(paths(&root, "$.items[?(@.email)]"), ["$.items[0]"]);
+        assert_eq!(
+            select_value(&root, "$.items[*].id"),
+            Some(json!(["x", "y"]))
+        );
+        assert_eq!(select_value(&root, "$.missing"), None);
+    }
+}
@@ -0,0 +1,184 @@
+//! Spotting values that look like credentials but were never flagged secret: AWS keys, JWTs,
+//! bearer tokens, private keys and the token formats of a few common services. Exports scan
+//! for them so a key pasted into a header or body doesn't leave the app unnoticed.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use crate::json::{array_of, str_of};
+use crate::redact::MASK;
+
+/// `(kind, label, pattern)`; the first capture group, when there is one, is the secret.
+const PATTERNS: &[(&str, &str, &str)] = &[
+    (
+        "private_key",
+        "Private key",
+        r"-----BEGIN (?:[A-Z0-9]+ )*PRIVATE KEY-----[\s\S]*?(?:-----END (?:[A-Z0-9]+ )*PRIVATE KEY-----|$)",
+    ),
+    (
+        "aws_access_key",
+        "AWS access key ID",
+        r"\b((?:AKIA|ASIA)[0-9A-Z]{16})\b",
+    ),
+    (
+        "aws_secret_key",
+        "AWS secret access key",
+        r#"(?i)aws_?secret_?(?:access_?)?key["']?\s*[:=]\s*["']?([A-Za-z0-9/+]{40})\b"#,
+    ),
+    (
+        "jwt",
+        "JSON Web Token",
+        r"\b(eyJ[A-Za-z0-9_-]{8,}\.eyJ[A-Za-z0-9_-]{8,}\.[A-Za-z0-9_-]*)",
+    ),
+    (
+        "bearer_token",
+        "Bearer token",
+        r"(?i)\bbearer\s+([A-Za-z0-9._~+/-]{16,}=*)",
+    ),
+    (
+        "github_token",
+        "GitHub token",
+        r"\b(gh[pousr]_[A-Za-z0-9]{36,})\b",
+    ),
+    (
+        "slack_token",
+        "Slack token",
+        r"\b(xox[abposr]-[A-Za-z0-9-]{10,})\b",
+    ),
+    (
+        "stripe_key",
+        "Stripe secret key",
+        r"\b((?:sk|rk)_live_[A-Za-z0-9]{16,})\b",
+    ),
+    (
+        "google_api_key",
+        "Google API key",
+        r"\b(AIza[0-9A-Za-z_-]{35})\b",
+    ),
+];
+
+#[derive(Serialize, Clone)]
+pub struct Leak {
+    /// Stable across scans of the same content: `<kind>@<location>#<n>`.
+    pub id: String,
+    pub kind: String,
+    pub label: String,
+    /// Where it was found, e.g. `Users/Get user › headers.X-Api-Key` or
+    /// `environment › envs.dev.token`.
+    pub location: String,
+    /// The first characters and a mask, enough to recognise the value.
+    pub preview: String,
+    #[serde(skip)]
+    pub value: String,
+}
+
+fn patterns() -> &'static [(&'static str, &'static str, Regex)] {
+    static COMPILED: OnceLock<Vec<(&str, &str, Regex)>> = OnceLock::new();
+    COMPILED.get_or_init(|| {
+        PATTERNS
+            .iter()
+            .filter_map(|(kind, label, pattern)| {
+                Regex::new(pattern).ok().map(|re| (*kind, *label, re))
+            })
+            .collect()
+    })
+}
+
+fn preview(value: &str) -> String {
+    let shown: String = value.chars().take(4).collect();
+    format!("{shown}{MASK}")
+}
+
+/// Credential-looking substrings of `text`, as `(kind, label, value)`, without overlaps:
+/// patterns run most specific first, so a JWT sent as a bearer token is reported as a JWT.
+pub fn find(text: &str) -> Vec<(&'static str, &'static str, String)> {
+    let mut spans: Vec<(usize, usize, &str, &str)> = Vec::new();
+    for (kind, label, re) in patterns() {
+        for caps in re.captures_iter(text) {
+            let Some(m) = caps.get(1).or(caps.get(0)) else {
+                continue;
+            };
+            // Template references name a variable; they aren't the value.
+            if m.as_str().contains("{{") || spans.iter().any(|s| m.start() < s.1 && s.0 < m.end()) {
+                continue;
+            }
+            spans.push((m.start(), m.end(), kind, label));
+        }
+    }
+    spans.sort_by_key(|s| s.0);
+    spans
+        .into_iter()
+        .map(|(start, end, kind, label)| (kind, label, text[start..end].to_string()))
+        .collect()
+}
+
+fn scan_value(value: &Value, location: &str, path: &str, out: &mut Vec<Leak>) {
+    match value {
+        Value::String(text) => {
+            for (kind, label, found) in find(text) {
+                let place = match path.is_empty() {
+                    true => location.to_string(),
+                    false => format!("{location} › {path}"),
+                };
+                let n = out
+                    .iter()
+                    .filter(|l| l.kind == kind && l.location == place)
+                    .count();
+                out.push(Leak {
+                    id: format!("{kind}@{place}#{n}"),
+                    kind: kind.to_string(),
+                    label: label.to_string(),
+                    location: place,
+                    preview: preview(&found),
+                    value: found,
+                });
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                scan_value(item, location, &format!("{path}[{i}]"), out);
+            }
+        }
+        Value::Object(map) => {
+            for (key, inner) in map {
+                let path = match path.is_empty() {
+                    true => key.clone(),
+                    false => format!("{path}.{key}"),
+                };
+                // Header names and other keys can hold a pasted value too.
+                scan_value(&Value::String(key.clone()), location, &path, out);
+                scan_value(inner, location, &path, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn scan_items(items: &[Value], folder: &str, out: &mut Vec<Leak>) {
+    for item in items {
+        let name = str_of(item, "name");
+        let path = match folder.is_empty() {
+            true => name.to_string(),
+            false => format!("{folder}/{name}"),
+        };
+        match item.get("items").and_then(Value::as_array) {
+            Some(children) => scan_items(children, &path, out),
+            None => scan_value(item, &path, "", out),
+        }
+    }
+}
+
+/// Everything in a collection and its environments that looks like a credential.
+pub fn scan(collection: &Value, environment: &Value) -> Vec<Leak> {
+    let mut out = Vec::new();
+    if let Some(fields) = collection.as_object() {
+        let mut rest = fields.clone();
+        rest.remove("items");
+        scan_value(&Value::Object(rest), "collection", "", &mut out);
+    }
+    scan_items(array_of(collection, "items"), "", &mut out);
+    scan_value(environment, "environment", "", &mut out);
+    out
+}
@@ -0,0 +1,50 @@
+//! The parts of LiteFetch that don't need a window: talking to the backend, resolving
+//! variables, and running collections and workflows. The desktop shell and `litefetch-cli`
+//! both build on it, each supplying its own way of sending a request (see `send::Sender`).
+
+pub mod backend;
+pub mod binary;
+pub mod collection_diff;
+pub mod contract;
+pub mod decode;
+pub mod diff;
+pub mod docs;
+pub mod dynamic;
+pub mod fixtures;
+pub mod format;
+pub mod hypermedia;
+pub mod importers;
+pub mod integrity;
+pub mod jq;
+pub mod json;
+pub mod jsonpath;
+pub mod leaks;
+pub mod lint;
+pub mod load;
+pub mod load_report;
+pub mod markdown;
+pub mod markup;
+pub mod multipart;
+pub mod portable;
+pub mod profile;
+pub mod records;
+pub mod redact;
+pub mod report;
+pub mod routing;
+pub mod runner;
+pub mod schema;
+pub mod secrets;
+pub mod security;
+pub mod send;
+pub mod snapshot;
+pub mod templates;
+pub mod variables;
+pub mod workflow;
+pub mod workspace;
+
+pub fn now_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
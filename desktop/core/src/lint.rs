@@ -0,0 +1,305 @@
+//! Workspace lint: references that would break a send or a run, found without sending
+//! anything. Variables no layer defines (in some or every environment), an active
+//! environment that was deleted, `fixture:` references and local files that don't exist, and
+//! workflow steps or branches pointing at requests and steps that are gone.
+//!
+//! Variables set only by scripts or workflow extracts are defined once those run, so
+//! unresolved references are warnings; the rest are errors.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use crate::json::{array_of, find_request, str_of};
+
+/// Request fields whose text is resolved before sending (see `send::resolve_request`).
+const RESOLVED_FIELDS: &[&str] = &[
+    "url",
+    "headers",
+    "auth_params",
+    "query_params",
+    "form_body",
+    "body",
+    "binary",
+];
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Problem {
+    pub severity: Severity,
+    /// `unresolved_variable`, `missing_environment`, `missing_fixture`, `missing_file`,
+    /// `dangling_step`, `dangling_branch` or, for monitors, `missing_collection` and
+    /// `missing_request`.
+    pub code: &'static str,
+    pub message: String,
+    pub collection_id: String,
+    /// `collection`, `request`, `workflow`, `environment` or `monitor`.
+    pub item_kind: &'static str,
+    pub item_id: Option<String>,
+    pub item_name: Option<String>,
+    /// Where in the item, e.g. `url`, `form_body[2].file_path` or `steps[1].branches[0]`.
+    pub field: Option<String>,
+}
+
+impl Problem {
+    pub fn new(
+        severity: Severity,
+        code: &'static str,
+        message: impl Into<String>,
+        collection_id: &str,
+        item_kind: &'static str,
+    ) -> Self {
+        Self {
+            severity,
+            code,
+            message: message.into(),
+            collection_id: collection_id.to_string(),
+            item_kind,
+            item_id: None,
+            item_name: None,
+            field: None,
+        }
+    }
+
+    fn on(mut self, item: &Value, field: Option<String>) -> Self {
+        self.item_id = Some(str_of(item, "id").to_string());
+        self.item_name = Some(str_of(item, "name").to_string()).filter(|n| !n.is_empty());
+        self.field = field;
+        self
+    }
+}
+
+fn keys(map: Option<&Value>) -> impl Iterator<Item = String> + '_ {
+    map.and_then(Value::as_object)
+        .into_iter()
+        .flat_map(Map::keys)
+        .cloned()
+}
+
+/// Every string of `value`, object keys included.
+fn texts<'a>(value: &'a Value, out: &mut Vec<&'a str>) {
+    match value {
+        Value::String(text) => out.push(text),
+        Value::Array(items) => items.iter().for_each(|item| texts(item, out)),
+        Value::Object(map) => {
+            for (key, value) in map {
+                out.push(key);
+                texts(value, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn requests<'a>(items: &'a [Value], out: &mut Vec<&'a Value>) {
+    for item in items {
+        match item.get("items").and_then(Value::as_array) {
+            Some(children) => requests(children, out),
+            None => out.push(item),
+        }
+    }
+}
+
+/// Lints one collection. `shared` holds the keys of the global and workspace layers, which
+/// every collection sees.
+pub fn lint_collection(
+    workspace: &Path,
+    shared: &BTreeSet<String>,
+    collection_id: &str,
+    collection: &Value,
+    environment: &Value,
+) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let envs: Vec<(&String, BTreeSet<String>)> = environment
+        .get("envs")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, env)| {
+            let defined = keys(env.get("variables"))
+                .chain(keys(env.get("secrets")))
+                .collect();
+            (name, defined)
+        })
+        .collect();
+
+    let active = str_of(environment, "active_env");
+    if !active.is_empty() && !envs.is_empty() && !envs.iter().any(|(name, _)| *name == active) {
+        let mut problem = Problem::new(
+            Severity::Error,
+            "missing_environment",
+            format!("the active environment \"{active}\" no longer exists"),
+            collection_id,
+            "environment",
+        );
+        problem.item_id = Some(active.to_string());
+        problem.field = Some("active_env".into());
+        problems.push(problem);
+    }
+
+    // Defined for every request: the shared layers, the collection's own variables and
+    // whatever workflows seed or extract.
+    let mut defined: BTreeSet<String> = shared.clone();
+    defined.extend(keys(collection.get("variables")));
+    for workflow in array_of(collection, "workflows") {
+        defined.extend(keys(workflow.get("variables")));
+        for step in array_of(workflow, "steps") {
+            defined.extend(
+                array_of(step, "extracts")
+                    .iter()
+                    .map(|extract| str_of(extract, "variable").to_string()),
+            );
+        }
+    }
+
+    let mut all = Vec::new();
+    requests(array_of(collection, "items"), &mut all);
+    for request in all {
+        lint_variables(request, &defined, &envs, collection_id, &mut problems);
+        lint_files(workspace, request, collection_id, &mut problems);
+    }
+    for workflow in array_of(collection, "workflows") {
+        lint_workflow(collection, workflow, collection_id, &mut problems);
+    }
+    problems
+}
+
+fn lint_variables(
+    request: &Value,
+    defined: &BTreeSet<String>,
+    envs: &[(&String, BTreeSet<String>)],
+    collection_id: &str,
+    problems: &mut Vec<Problem>,
+) {
+    let own: BTreeSet<String> = keys(request.get("variables")).collect();
+    let mut reported = BTreeSet::new();
+    for field in RESOLVED_FIELDS {
+        let Some(value) = request.get(*field) else {
+            continue;
+        };
+        let mut found = Vec::new();
+        texts(value, &mut found);
+        let mut names = BTreeSet::new();
+        found
+            .into_iter()
+            .for_each(|text| crate::docs::references(text, &mut names));
+        for name in names {
+            if defined.contains(&name) || own.contains(&name) || !reported.insert(name.clone()) {
+                continue;
+            }
+            let missing: Vec<&str> = envs
+                .iter()
+                .filter(|(_, keys)| !keys.contains(&name))
+                .map(|(env, _)| env.as_str())
+                .collect();
+            let message = match missing.len() {
+                n if n == envs.len() => format!("nothing defines {{{{{name}}}}}"),
+                0 => continue,
+                _ => format!("{{{{{name}}}}} isn't defined in {}", missing.join(", ")),
+            };
+            problems.push(
+                Problem::new(
+                    Severity::Warning,
+                    "unresolved_variable",
+                    message,
+                    collection_id,
+                    "request",
+                )
+                .on(request, Some(field.to_string())),
+            );
+        }
+    }
+}
+
+fn lint_files(workspace: &Path, request: &Value, collection_id: &str, problems: &mut Vec<Problem>) {
+    let mut paths = Vec::new();
+    if let Some(path) = request.pointer("/binary/file_path").and_then(Value::as_str) {
+        paths.push(("binary.file_path".to_string(), path));
+    }
+    for (index, row) in array_of(request, "form_body").iter().enumerate() {
+        if let Some(path) = row.get("file_path").and_then(Value::as_str) {
+            paths.push((format!("form_body[{index}].file_path"), path));
+        }
+    }
+    for (field, path) in paths {
+        let path = path.trim();
+        // Paths built from variables are only known once resolved.
+        if path.is_empty() || path.contains("{{") {
+            continue;
+        }
+        let problem = match path.strip_prefix(crate::fixtures::SCHEME) {
+            Some(name) => match crate::fixtures::open(workspace, name.trim()) {
+                Ok(_) => continue,
+                Err(e) => Problem::new(
+                    Severity::Error,
+                    "missing_fixture",
+                    e,
+                    collection_id,
+                    "request",
+                ),
+            },
+            None if Path::new(path).exists() => continue,
+            None => Problem::new(
+                Severity::Error,
+                "missing_file",
+                format!(
+                    "{path} doesn't exist on this machine; a fixture travels with the workspace"
+                ),
+                collection_id,
+                "request",
+            ),
+        };
+        problems.push(problem.on(request, Some(field)));
+    }
+}
+
+fn lint_workflow(
+    collection: &Value,
+    workflow: &Value,
+    collection_id: &str,
+    problems: &mut Vec<Problem>,
+) {
+    let steps = array_of(workflow, "steps");
+    for (index, step) in steps.iter().enumerate() {
+        let step_name = match str_of(step, "name") {
+            "" => format!("step {}", index + 1),
+            name => format!("step \"{name}\""),
+        };
+        let request_id = str_of(step, "request_id");
+        if find_request(array_of(collection, "items"), request_id).is_none() {
+            problems.push(
+                Problem::new(
+                    Severity::Error,
+                    "dangling_step",
+                    format!("{step_name} runs a request that no longer exists ({request_id})"),
+                    collection_id,
+                    "workflow",
+                )
+                .on(workflow, Some(format!("steps[{index}].request_id"))),
+            );
+        }
+        for (branch, target) in array_of(step, "branches").iter().enumerate() {
+            let goto = str_of(target, "goto");
+            if goto == "end" || steps.iter().any(|s| str_of(s, "id") == goto) {
+                continue;
+            }
+            problems.push(
+                Problem::new(
+                    Severity::Error,
+                    "dangling_branch",
+                    format!("{step_name} branches to a step that no longer exists ({goto})"),
+                    collection_id,
+                    "workflow",
+                )
+                .on(workflow, Some(format!("steps[{index}].branches[{branch}]"))),
+            );
+        }
+    }
+}
@@ -0,0 +1,388 @@
+//! Load tests: one request sent over and over by several workers for a fixed duration,
+//! optionally held to a target rate. Latencies are collected for percentiles and a
+//! histogram, and a snapshot of the running totals is reported every second.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::json::str_of;
+use crate::send::Sender;
+
+const MAX_CONCURRENCY: usize = 256;
+const MAX_DURATION_SECS: u64 = 3600;
+/// Most distinct error messages kept; the rest are counted under "other".
+const MAX_ERROR_KINDS: usize = 20;
+/// Upper bounds of the histogram buckets; a last, open bucket holds everything slower.
+const BUCKETS_MS: [f64; 14] = [
+    5.0, 10.0, 25.0, 50.0, 75.0, 100.0, 250.0, 500.0, 750.0, 1000.0, 2500.0, 5000.0, 10000.0,
+    30000.0,
+];
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct LoadOptions {
+    pub duration_secs: u64,
+    /// Workers sending at once; each waits for its response before sending again.
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    /// Target requests per second across all workers; as fast as the workers go when omitted.
+    #[serde(default)]
+    pub rps: Option<f64>,
+    #[serde(default)]
+    pub environment_id: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct Latency {
+    pub min: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+    pub max: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Bucket {
+    /// `None` for the open bucket above the last bound.
+    pub upper_ms: Option<f64>,
+    pub count: usize,
+}
+
+/// Running totals at one point of a load test.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LoadStats {
+    pub elapsed_ms: u64,
+    pub sent: usize,
+    /// Responses below 400 that passed their tests.
+    pub succeeded: usize,
+    /// 4xx/5xx responses and failed tests.
+    pub failed: usize,
+    /// Requests that got no response.
+    pub errors: usize,
+    /// Completed requests per second over the last interval.
+    pub throughput_rps: f64,
+    pub latency: Latency,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LoadSummary {
+    pub run_id: String,
+    pub collection_id: String,
+    pub request_id: String,
+    pub name: String,
+    pub options: LoadOptions,
+    pub started_ms: u64,
+    pub duration_ms: u64,
+    pub cancelled: bool,
+    pub sent: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub errors: usize,
+    /// Share of failed and errored requests, 0–100.
+    pub error_rate: f64,
+    /// Completed requests per second over the whole run.
+    pub throughput_rps: f64,
+    pub latency: Latency,
+    pub histogram: Vec<Bucket>,
+    /// Response counts by status code.
+    pub status_codes: BTreeMap<String, usize>,
+    /// Error counts by message.
+    pub error_kinds: BTreeMap<String, usize>,
+    /// One snapshot per second.
+    pub timeline: Vec<LoadStats>,
+}
+
+#[derive(Default)]
+struct Totals {
+    latencies: Vec<f64>,
+    succeeded: usize,
+    failed: usize,
+    errors: usize,
+    status_codes: BTreeMap<String, usize>,
+    error_kinds: BTreeMap<String, usize>,
+}
+
+impl Totals {
+    fn sent(&self) -> usize {
+        self.succeeded + self.failed + self.errors
+    }
+
+    fn error(&mut self, message: String) {
+        self.errors += 1;
+        let key = if self.error_kinds.len() < MAX_ERROR_KINDS
+            || self.error_kinds.contains_key(&message)
+        {
+            message
+        } else {
+            "other".to_string()
+        };
+        *self.error_kinds.entry(key).or_default() += 1;
+    }
+}
+
+/// Nearest-rank percentile of sorted `values`.
+fn percentile(sorted: &[f64], pct: f64) -> f64 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+    let rank = (sorted.len() as f64 * pct / 100.0).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+fn latency_of(latencies: &[f64]) -> Latency {
+    let mut sorted = latencies.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    if sorted.is_empty() {
+        return Latency::default();
+    }
+    Latency {
+        min: sorted[0],
+        mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+        p50: percentile(&sorted, 50.0),
+        p90: percentile(&sorted, 90.0),
+        p99: percentile(&sorted, 99.0),
+        max: sorted[sorted.len() - 1],
+    }
+}
+
+fn histogram_of(latencies: &[f64]) -> Vec<Bucket> {
+    let mut buckets: Vec<Bucket> = BUCKETS_MS
+        .iter()
+        .map(|&upper| Bucket {
+            upper_ms: Some(upper),
+            count: 0,
+        })
+        .chain([Bucket {
+            upper_ms: None,
+            count: 0,
+        }])
+        .collect();
+    for &latency in latencies {
+        let index = BUCKETS_MS
+            .iter()
+            .position(|&upper| latency <= upper)
+            .unwrap_or(BUCKETS_MS.len());
+        buckets[index].count += 1;
+    }
+    buckets
+}
+
+async fn send_one<S: Sender>(sender: &S, request: &Value, totals: &Mutex<Totals>) {
+    let started = Instant::now();
+    let sent = sender.send(request.clone()).await;
+    let wall_ms = started.elapsed().as_secs_f64() * 1000.0;
+    let mut totals = totals.lock().unwrap_or_else(|e| e.into_inner());
+    let sent = match sent {
+        Ok(sent) => sent,
+        Err(e) => return totals.error(e),
+    };
+    if let Some(error) = sent
+        .result
+        .get("error")
+        .and_then(Value::as_str)
+        .filter(|e| !e.is_empty())
+    {
+        return totals.error(error.to_string());
+    }
+    // The backend's own timing leaves out the hop to it; fall back to the wall clock.
+    let latency = sent
+        .result
+        .get("duration_ms")
+        .and_then(Value::as_f64)
+        .unwrap_or(wall_ms);
+    totals.latencies.push(latency);
+    let status_code = sent.result.get("status_code").and_then(Value::as_u64);
+    if let Some(code) = status_code {
+        *totals.status_codes.entry(code.to_string()).or_default() += 1;
+    }
+    let tests_failed = sent
+        .tests
+        .as_ref()
+        .is_some_and(|t| t.failed > 0 || t.error.is_some());
+    if status_code.is_some_and(|code| code < 400) && !tests_failed {
+        totals.succeeded += 1;
+    } else {
+        totals.failed += 1;
+    }
+}
+
+/// Keeps sending `request` until the duration is up or `cancel` is set. `on_stats` gets a
+/// snapshot every second.
+pub async fn execute<S: Sender>(
+    run_id: String,
+    collection_id: String,
+    request: &Value,
+    options: &LoadOptions,
+    sender: &S,
+    cancel: &AtomicBool,
+    mut on_stats: impl FnMut(&LoadStats),
+) -> LoadSummary {
+    let mut options = options.clone();
+    options.duration_secs = options.duration_secs.clamp(1, MAX_DURATION_SECS);
+    let concurrency = options.concurrency.unwrap_or(1).clamp(1, MAX_CONCURRENCY);
+    options.concurrency = Some(concurrency);
+    options.rps = options.rps.filter(|&rps| rps > 0.0);
+
+    let started_ms = crate::now_ms();
+    let started = Instant::now();
+    let deadline = started + Duration::from_secs(options.duration_secs);
+    let totals = Mutex::new(Totals::default());
+    let slots = AtomicU64::new(0);
+
+    let mut timeline = Vec::new();
+    {
+        let worker = || async {
+            loop {
+                if cancel.load(Ordering::Relaxed) {
+                    break;
+                }
+                if let Some(rps) = options.rps {
+                    // Workers take slots from one shared schedule so the total rate holds.
+                    let slot = slots.fetch_add(1, Ordering::Relaxed);
+                    let at = started + Duration::from_secs_f64(slot as f64 / rps);
+                    if at >= deadline {
+                        break;
+                    }
+                    tokio::time::sleep_until(at.into()).await;
+                    if cancel.load(Ordering::Relaxed) {
+                        break;
+                    }
+                } else if Instant::now() >= deadline {
+                    break;
+                }
+                send_one(sender, request, &totals).await;
+            }
+        };
+        let workers = futures_util::future::join_all((0..concurrency).map(|_| worker()));
+
+        let ticker = async {
+            let mut ticks = tokio::time::interval(Duration::from_secs(1));
+            ticks.tick().await;
+            let mut last_completed = 0;
+            let mut last_tick = started;
+            loop {
+                ticks.tick().await;
+                let now = Instant::now();
+                let totals = totals.lock().unwrap_or_else(|e| e.into_inner());
+                let sent = totals.sent();
+                let stats = LoadStats {
+                    elapsed_ms: started.elapsed().as_millis() as u64,
+                    sent,
+                    succeeded: totals.succeeded,
+                    failed: totals.failed,
+                    errors: totals.errors,
+                    throughput_rps: (sent - last_completed) as f64
+                        / now.duration_since(last_tick).as_secs_f64().max(0.001),
+                    latency: latency_of(&totals.latencies),
+                };
+                drop(totals);
+                last_completed = sent;
+                last_tick = now;
+                on_stats(&stats);
+                timeline.push(stats);
+            }
+        };
+        // The ticker never ends on its own; it's dropped once the workers are done.
+        futures_util::pin_mut!(ticker);
+        futures_util::future::select(workers, ticker).await;
+    }
+
+    let totals = totals.into_inner().unwrap_or_else(|e| e.into_inner());
+    let duration = started.elapsed();
+    let sent = totals.sent();
+    LoadSummary {
+        run_id,
+        collection_id,
+        request_id: str_of(request, "id").to_string(),
+        name: str_of(request, "name").to_string(),
+        options,
+        started_ms,
+        duration_ms: duration.as_millis() as u64,
+        cancelled: cancel.load(Ordering::Relaxed),
+        sent,
+        succeeded: totals.succeeded,
+        failed: totals.failed,
+        errors: totals.errors,
+        error_rate: if sent == 0 {
+            0.0
+        } else {
+            (totals.failed + totals.errors) as f64 * 100.0 / sent as f64
+        },
+        throughput_rps: sent as f64 / duration.as_secs_f64().max(0.001),
+        latency: latency_of(&totals.latencies),
+        histogram: histogram_of(&totals.latencies),
+        status_codes: totals.status_codes,
+        error_kinds: totals.error_kinds,
+        timeline,
+    }
+}
+
+/// How much worse a metric may get, in percent, before a comparison flags it.
+pub const DEFAULT_REGRESSION_PCT: f64 = 10.0;
+
+/// One metric of two runs side by side.
+#[derive(Serialize, Clone)]
+pub struct MetricChange {
+    pub metric: String,
+    pub before: f64,
+    pub after: f64,
+    /// Relative change from `before`, in percent; `None` when `before` is zero.
+    pub change_pct: Option<f64>,
+    /// Set when the change is for the worse by more than the threshold.
+    pub regression: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct LoadComparison {
+    pub before: String,
+    pub after: String,
+    pub threshold_pct: f64,
+    pub metrics: Vec<MetricChange>,
+    /// Whether any metric regressed.
+    pub regressed: bool,
+}
+
+/// Compares two runs, e.g. before and after a deploy. Latencies and the error rate regress
+/// when they grow by more than `threshold_pct`, throughput when it drops by as much.
+pub fn compare(before: &LoadSummary, after: &LoadSummary, threshold_pct: f64) -> LoadComparison {
+    let mut metrics = Vec::new();
+    let mut add = |metric: &str, before: f64, after: f64, higher_is_worse: bool| {
+        let change_pct = (before != 0.0).then(|| (after - before) * 100.0 / before);
+        let worse = if higher_is_worse {
+            after - before
+        } else {
+            before - after
+        };
+        let regression = worse > 0.0 && change_pct.is_none_or(|pct| pct.abs() > threshold_pct);
+        metrics.push(MetricChange {
+            metric: metric.to_string(),
+            before,
+            after,
+            change_pct,
+            regression,
+        });
+    };
+    let (b, a) = (&before.latency, &after.latency);
+    add("p50_ms", b.p50, a.p50, true);
+    add("p90_ms", b.p90, a.p90, true);
+    add("p99_ms", b.p99, a.p99, true);
+    add("mean_ms", b.mean, a.mean, true);
+    add("error_rate", before.error_rate, after.error_rate, true);
+    add(
+        "throughput_rps",
+        before.throughput_rps,
+        after.throughput_rps,
+        false,
+    );
+    LoadComparison {
+        before: before.run_id.clone(),
+        after: after.run_id.clone(),
+        threshold_pct,
+        regressed: metrics.iter().any(|m| m.regression),
+        metrics,
+    }
+}
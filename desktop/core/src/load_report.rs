@@ -0,0 +1,217 @@
+//! Exports of a finished load test: the per-second timeline as CSV, or a standalone HTML
+//! page with the totals, latency percentiles and inline SVG charts.
+
+use quick_xml::escape::escape;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crate::load::{LoadStats, LoadSummary};
+use crate::report::STYLE;
+
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 180.0;
+/// Room left of and below the plot for axis labels.
+const CHART_MARGIN: f64 = 40.0;
+
+const CHART_STYLE: &str = "\
+svg{display:block;margin:.5rem 0 2rem}\
+svg text{font-size:11px;fill:#59636e}\
+.bar{fill:#0969da}.axis{stroke:#d1d9e0}\
+.line{fill:none;stroke-width:2}.rps{stroke:#0969da}.p99{stroke:#cf222e}";
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum LoadReportFormat {
+    Csv,
+    Html,
+}
+
+fn csv(summary: &LoadSummary) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    let fail = |e: csv::Error| format!("csv write failed: {e}");
+    writer
+        .write_record([
+            "elapsed_s",
+            "sent",
+            "succeeded",
+            "failed",
+            "errors",
+            "throughput_rps",
+            "p50_ms",
+            "p90_ms",
+            "p99_ms",
+            "max_ms",
+        ])
+        .map_err(fail)?;
+    for stats in &summary.timeline {
+        writer
+            .write_record([
+                format!("{:.1}", stats.elapsed_ms as f64 / 1000.0),
+                stats.sent.to_string(),
+                stats.succeeded.to_string(),
+                stats.failed.to_string(),
+                stats.errors.to_string(),
+                format!("{:.2}", stats.throughput_rps),
+                format!("{:.2}", stats.latency.p50),
+                format!("{:.2}", stats.latency.p90),
+                format!("{:.2}", stats.latency.p99),
+                format!("{:.2}", stats.latency.max),
+            ])
+            .map_err(fail)?;
+    }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| format!("csv write failed: {e}"))?;
+    String::from_utf8(bytes).map_err(|e| format!("csv write failed: {e}"))
+}
+
+/// Vertical bars of the latency histogram, one per bucket.
+fn histogram_chart(summary: &LoadSummary) -> String {
+    let buckets = &summary.histogram;
+    let most = buckets.iter().map(|b| b.count).max().unwrap_or(0).max(1) as f64;
+    let plot_height = CHART_HEIGHT - CHART_MARGIN;
+    let slot = (CHART_WIDTH - CHART_MARGIN) / buckets.len().max(1) as f64;
+    let mut svg = format!(
+        "<svg width=\"{CHART_WIDTH}\" height=\"{CHART_HEIGHT}\" role=\"img\">\
+         <line class=\"axis\" x1=\"{CHART_MARGIN}\" y1=\"{plot_height}\" x2=\"{CHART_WIDTH}\" \
+         y2=\"{plot_height}\"/>"
+    );
+    for (i, bucket) in buckets.iter().enumerate() {
+        let height = bucket.count as f64 / most * (plot_height - 12.0);
+        let x = CHART_MARGIN + i as f64 * slot;
+        let label = match bucket.upper_ms {
+            Some(upper) => format!("≤{upper}"),
+            None => "more".to_string(),
+        };
+        let _ = write!(
+            svg,
+            "<rect class=\"bar\" x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{height:.1}\">\
+             <title>{label} ms: {}</title></rect>\
+             <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{label}</text>",
+            x + 2.0,
+            plot_height - height,
+            slot - 4.0,
+            bucket.count,
+            x + slot / 2.0,
+            plot_height + 14.0,
+        );
+        if bucket.count > 0 {
+            let _ = write!(
+                svg,
+                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+                x + slot / 2.0,
+                plot_height - height - 3.0,
+                bucket.count,
+            );
+        }
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// A line of `value` over the timeline, scaled to its own maximum.
+fn timeline_chart(
+    timeline: &[LoadStats],
+    class: &str,
+    value: impl Fn(&LoadStats) -> f64,
+) -> String {
+    let plot_height = CHART_HEIGHT - CHART_MARGIN;
+    let most = timeline.iter().map(&value).fold(0.0, f64::max).max(1.0);
+    let last_ms = timeline.last().map(|s| s.elapsed_ms).unwrap_or(0).max(1) as f64;
+    let points: Vec<String> = timeline
+        .iter()
+        .map(|stats| {
+            let x = CHART_MARGIN + stats.elapsed_ms as f64 / last_ms * (CHART_WIDTH - CHART_MARGIN);
+            let y = plot_height - value(stats) / most * (plot_height - 12.0);
+            format!("{x:.1},{y:.1}")
+        })
+        .collect();
+    format!(
+        "<svg width=\"{CHART_WIDTH}\" height=\"{CHART_HEIGHT}\" role=\"img\">\
+         <line class=\"axis\" x1=\"{CHART_MARGIN}\" y1=\"{plot_height}\" x2=\"{CHART_WIDTH}\" \
+         y2=\"{plot_height}\"/>\
+         <text x=\"0\" y=\"16\">{most:.0}</text><text x=\"0\" y=\"{plot_height}\">0</text>\
+         <text x=\"{CHART_WIDTH}\" y=\"{:.0}\" text-anchor=\"end\">{:.0} s</text>\
+         <polyline class=\"line {class}\" points=\"{}\"/></svg>",
+        plot_height + 14.0,
+        last_ms / 1000.0,
+        points.join(" "),
+    )
+}
+
+fn html(summary: &LoadSummary, title: &str) -> String {
+    let rps = summary
+        .options
+        .rps
+        .map(|rps| format!("{rps} rps target, "))
+        .unwrap_or_default();
+    let mut page = String::new();
+    let _ = write!(
+        page,
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <title>{title}</title><style>{STYLE}{CHART_STYLE}</style></head><body>\n\
+         <h1>{title}</h1><div class=\"meta\">{} — {rps}{} workers, {:.1} s{}</div>\n\
+         <div class=\"totals\"><span>{} sent</span><span class=\"passed\">{} succeeded</span>\
+         <span class=\"failed\">{} failed</span><span class=\"error\">{} errors</span>\
+         <span>{:.2}% error rate</span><span>{:.1} req/s</span></div>\n",
+        escape(&summary.name),
+        summary.options.concurrency.unwrap_or(1),
+        summary.duration_ms as f64 / 1000.0,
+        if summary.cancelled { ", cancelled" } else { "" },
+        summary.sent,
+        summary.succeeded,
+        summary.failed,
+        summary.errors,
+        summary.error_rate,
+        summary.throughput_rps,
+        title = escape(title),
+    );
+    let l = &summary.latency;
+    let _ = write!(
+        page,
+        "<h2>Latency</h2>\n<table><tr><th>Min</th><th>Mean</th><th>p50</th><th>p90</th>\
+         <th>p99</th><th>Max</th></tr>\n<tr><td>{:.1} ms</td><td>{:.1} ms</td>\
+         <td>{:.1} ms</td><td>{:.1} ms</td><td>{:.1} ms</td><td>{:.1} ms</td></tr></table>\n\
+         <h2>Latency distribution (ms)</h2>\n{}\n\
+         <h2>Throughput (req/s)</h2>\n{}\n<h2>p99 latency (ms)</h2>\n{}\n",
+        l.min,
+        l.mean,
+        l.p50,
+        l.p90,
+        l.p99,
+        l.max,
+        histogram_chart(summary),
+        timeline_chart(&summary.timeline, "rps", |s| s.throughput_rps),
+        timeline_chart(&summary.timeline, "p99", |s| s.latency.p99),
+    );
+    let mut breakdown = |heading: &str, column: &str, counts: &BTreeMap<String, usize>| {
+        if counts.is_empty() {
+            return;
+        }
+        let _ = write!(
+            page,
+            "<h2>{heading}</h2>\n<table><tr><th>{column}</th><th>Count</th></tr>\n"
+        );
+        for (key, count) in counts {
+            let _ = writeln!(page, "<tr><td>{}</td><td>{count}</td></tr>", escape(key));
+        }
+        page.push_str("</table>\n");
+    };
+    breakdown("Status codes", "Status", &summary.status_codes);
+    breakdown("Errors", "Message", &summary.error_kinds);
+    page.push_str("</body></html>\n");
+    page
+}
+
+/// Renders a load test summary in `format`.
+pub fn render(
+    summary: &LoadSummary,
+    format: LoadReportFormat,
+    title: &str,
+) -> Result<String, String> {
+    match format {
+        LoadReportFormat::Csv => csv(summary),
+        LoadReportFormat::Html => Ok(html(summary, title)),
+    }
+}
@@ -0,0 +1,60 @@
+//! Markdown to HTML for request and folder descriptions, shared by the docs panel, generated
+//! documentation and exports so a description renders the same everywhere. CommonMark with
+//! tables, strikethrough and task lists. Descriptions come from imported collections and
+//! shared workspaces, so the output is sanitized: raw HTML is shown as text, and links and
+//! images to anything but `http`, `https`, `mailto` or a relative path lose their target.
+
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag};
+
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// A link target is kept when it is relative or uses one of `SAFE_SCHEMES`.
+fn safe_url(url: &str) -> bool {
+    let url = url.trim();
+    match url.find(':') {
+        // A colon after the first `/`, `?` or `#` belongs to a relative path.
+        Some(colon) if !url[..colon].contains(['/', '?', '#']) => SAFE_SCHEMES
+            .iter()
+            .any(|scheme| url[..colon].eq_ignore_ascii_case(scheme)),
+        _ => true,
+    }
+}
+
+fn sanitized(event: Event) -> Event {
+    match event {
+        Event::Html(html) | Event::InlineHtml(html) => Event::Text(html),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !safe_url(&dest_url) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: CowStr::Borrowed(""),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) if !safe_url(&dest_url) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: CowStr::Borrowed(""),
+            title,
+            id,
+        }),
+        other => other,
+    }
+}
+
+/// Renders `text` as sanitized HTML, without a surrounding element.
+pub fn to_html(text: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(text, options).map(sanitized);
+    let mut out = String::with_capacity(text.len() * 3 / 2);
+    pulldown_cmark::html::push_html(&mut out, parser);
+    out
+}
@@ -0,0 +1,1483 @@
+//! XPath and CSS selector queries over XML and HTML bodies, e.g. a SOAP envelope or a page
+//! being debugged. Both kinds of document are read into one small tree: XML with roxmltree,
+//! HTML with html5ever (through scraper), which also evaluates CSS selectors.
+//!
+//! XPath covers the XPath 1.0 location paths (every axis but `namespace`, `*`, `text()`,
+//! `node()`, `comment()`), predicates, unions, comparisons, arithmetic (`+`, `-`, `*`,
+//! `div`, `mod`, unary minus), `and`/`or` and the common functions. Names match on their local part, so `//soap:Body` and `//Body` both find a
+//! SOAP body whatever prefix the response uses; HTML names match case-insensitively.
+
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Matches returned by one query; `total` still counts every match.
+const MAX_MATCHES: usize = 1000;
+
+/// HTML elements written without a closing tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SelectorKind {
+    Xpath,
+    Css,
+}
+
+#[derive(Serialize, Clone)]
+pub struct NodeMatch {
+    /// Where the node sits, e.g. `/Envelope/Body/GetUserResponse[1]/@id`; empty for a
+    /// computed value such as `count(//item)`.
+    pub path: String,
+    /// `element`, `attribute`, `text`, `comment` or `value`.
+    pub kind: String,
+    pub name: Option<String>,
+    /// The text content (or the value, for attributes and computed values).
+    pub text: String,
+    /// The element's markup, children included.
+    pub markup: Option<String>,
+    pub attributes: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+pub struct SelectResult {
+    pub matches: Vec<NodeMatch>,
+    pub total: usize,
+    pub truncated: bool,
+}
+
+// --- The document tree ---
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Root,
+    Element,
+    Text,
+    Comment,
+}
+
+struct Node {
+    kind: Kind,
+    /// The name as written, prefix included; the text of text and comment nodes.
+    name: String,
+    attributes: Vec<(String, String)>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
+
+/// Nodes in document order, the root first.
+struct Tree {
+    nodes: Vec<Node>,
+    /// Names compare case-insensitively.
+    html: bool,
+}
+
+fn local(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+impl Tree {
+    fn add(
+        &mut self,
+        parent: usize,
+        kind: Kind,
+        name: String,
+        attributes: Vec<(String, String)>,
+    ) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(Node {
+            kind,
+            name,
+            attributes,
+            parent: Some(parent),
+            children: Vec::new(),
+        });
+        self.nodes[parent].children.push(id);
+        id
+    }
+
+    fn empty(html: bool) -> Self {
+        Self {
+            nodes: vec![Node {
+                kind: Kind::Root,
+                name: String::new(),
+                attributes: Vec::new(),
+                parent: None,
+                children: Vec::new(),
+            }],
+            html,
+        }
+    }
+
+    fn from_xml(text: &str) -> Result<Self, String> {
+        let options = roxmltree::ParsingOptions {
+            allow_dtd: true,
+            ..Default::default()
+        };
+        let doc = roxmltree::Document::parse_with_options(text, options)
+            .map_err(|e| format!("XML parse failed: {e}"))?;
+        let mut tree = Self::empty(false);
+        fn walk(tree: &mut Tree, parent: usize, node: roxmltree::Node) {
+            for child in node.children() {
+                let id = match child.node_type() {
+                    roxmltree::NodeType::Element => {
+                        let qualified = |prefix: Option<&str>, name: &str| match prefix {
+                            Some(prefix) if !prefix.is_empty() => format!("{prefix}:{name}"),
+                            _ => name.to_string(),
+                        };
+                        let tag = child.tag_name();
+                        let prefix = tag.namespace().and_then(|ns| child.lookup_prefix(ns));
+                        let attributes = child
+                            .attributes()
+                            .map(|a| {
+                                let prefix = a.namespace().and_then(|ns| child.lookup_prefix(ns));
+                                (qualified(prefix, a.name()), a.value().to_string())
+                            })
+                            .collect();
+                        tree.add(
+                            parent,
+                            Kind::Element,
+                            qualified(prefix, tag.name()),
+                            attributes,
+                        )
+                    }
+                    roxmltree::NodeType::Text => tree.add(
+                        parent,
+                        Kind::Text,
+                        child.text().unwrap_or_default().to_string(),
+                        Vec::new(),
+                    ),
+                    roxmltree::NodeType::Comment => tree.add(
+                        parent,
+                        Kind::Comment,
+                        child.text().unwrap_or_default().to_string(),
+                        Vec::new(),
+                    ),
+                    _ => continue,
+                };
+                walk(tree, id, child);
+            }
+        }
+        walk(&mut tree, 0, doc.root());
+        Ok(tree)
+    }
+
+    /// Reads HTML; also returns where each scraper node ended up, for CSS matches.
+    fn from_html(html: &scraper::Html) -> (Self, HashMap<ego_tree::NodeId, usize>) {
+        let mut tree = Self::empty(true);
+        let mut ids = HashMap::new();
+        fn walk(
+            tree: &mut Tree,
+            ids: &mut HashMap<ego_tree::NodeId, usize>,
+            parent: usize,
+            node: ego_tree::NodeRef<scraper::Node>,
+        ) {
+            for child in node.children() {
+                let id = match child.value() {
+                    scraper::Node::Element(element) => {
+                        let attributes = element
+                            .attrs()
+                            .map(|(name, value)| (name.to_string(), value.to_string()))
+                            .collect();
+                        tree.add(
+                            parent,
+                            Kind::Element,
+                            element.name().to_string(),
+                            attributes,
+                        )
+                    }
+                    scraper::Node::Text(text) => {
+                        tree.add(parent, Kind::Text, text.to_string(), Vec::new())
+                    }
+                    scraper::Node::Comment(comment) => {
+                        tree.add(parent, Kind::Comment, comment.to_string(), Vec::new())
+                    }
+                    _ => continue,
+                };
+                ids.insert(child.id(), id);
+                walk(tree, ids, id, child);
+            }
+        }
+        walk(&mut tree, &mut ids, 0, html.tree.root());
+        (tree, ids)
+    }
+
+    fn named(&self, id: usize, name: &str) -> bool {
+        let own = local(&self.nodes[id].name);
+        let wanted = local(name);
+        match self.html {
+            true => own.eq_ignore_ascii_case(wanted),
+            false => own == wanted,
+        }
+    }
+
+    fn text(&self, id: usize) -> String {
+        let node = &self.nodes[id];
+        match node.kind {
+            Kind::Text | Kind::Comment => node.name.clone(),
+            Kind::Root | Kind::Element => {
+                let mut text = String::new();
+                self.collect_text(id, &mut text);
+                text
+            }
+        }
+    }
+
+    fn collect_text(&self, id: usize, out: &mut String) {
+        for &child in &self.nodes[id].children {
+            match self.nodes[child].kind {
+                Kind::Text => out.push_str(&self.nodes[child].name),
+                Kind::Element => self.collect_text(child, out),
+                _ => {}
+            }
+        }
+    }
+
+    fn markup(&self, id: usize, out: &mut String) {
+        let node = &self.nodes[id];
+        match node.kind {
+            Kind::Text => out.push_str(&escape(node.name.as_str())),
+            Kind::Comment => {
+                out.push_str("<!--");
+                out.push_str(&node.name);
+                out.push_str("-->");
+            }
+            Kind::Root => {
+                for &child in &node.children {
+                    self.markup(child, out);
+                }
+            }
+            Kind::Element => {
+                out.push('<');
+                out.push_str(&node.name);
+                for (name, value) in &node.attributes {
+                    out.push_str(&format!(" {name}=\"{}\"", escape(value.as_str())));
+                }
+                if node.children.is_empty() && !self.html {
+                    out.push_str("/>");
+                    return;
+                }
+                out.push('>');
+                if self.html && VOID_ELEMENTS.contains(&node.name.as_str()) {
+                    return;
+                }
+                for &child in &node.children {
+                    self.markup(child, out);
+                }
+                out.push_str("</");
+                out.push_str(&node.name);
+                out.push('>');
+            }
+        }
+    }
+
+    /// `/html/body/div[2]`: each step with its position among same-named siblings.
+    fn path(&self, id: usize) -> String {
+        let mut steps = Vec::new();
+        let mut current = id;
+        while let Some(parent) = self.nodes[current].parent {
+            let node = &self.nodes[current];
+            let step = match node.kind {
+                Kind::Element => {
+                    let siblings: Vec<usize> = self.nodes[parent]
+                        .children
+                        .iter()
+                        .copied()
+                        .filter(|&c| {
+                            self.nodes[c].kind == Kind::Element && self.nodes[c].name == node.name
+                        })
+                        .collect();
+                    match siblings.len() > 1 {
+                        true => {
+                            let position = siblings.iter().position(|&c| c == current).unwrap_or(0);
+                            format!("{}[{}]", node.name, position + 1)
+                        }
+                        false => node.name.clone(),
+                    }
+                }
+                Kind::Text => "text()".to_string(),
+                Kind::Comment => "comment()".to_string(),
+                Kind::Root => String::new(),
+            };
+            steps.push(step);
+            current = parent;
+        }
+        steps.reverse();
+        format!("/{}", steps.join("/"))
+    }
+
+    fn node_match(&self, item: Item) -> NodeMatch {
+        match item {
+            Item::Attribute(id, index) => {
+                let (name, value) = &self.nodes[id].attributes[index];
+                NodeMatch {
+                    path: format!("{}/@{name}", self.path(id)),
+                    kind: "attribute".to_string(),
+                    name: Some(name.clone()),
+                    text: value.clone(),
+                    markup: None,
+                    attributes: BTreeMap::new(),
+                }
+            }
+            Item::Node(id) => {
+                let node = &self.nodes[id];
+                let kind = match node.kind {
+                    Kind::Root | Kind::Element => "element",
+                    Kind::Text => "text",
+                    Kind::Comment => "comment",
+                };
+                let markup = matches!(node.kind, Kind::Element | Kind::Root).then(|| {
+                    let mut markup = String::new();
+                    self.markup(id, &mut markup);
+                    markup
+                });
+                NodeMatch {
+                    path: self.path(id),
+                    kind: kind.to_string(),
+                    name: (node.kind == Kind::Element).then(|| node.name.clone()),
+                    text: self.text(id),
+                    markup,
+                    attributes: node.attributes.iter().cloned().collect(),
+                }
+            }
+        }
+    }
+}
+
+// --- XPath ---
+
+/// A node of the tree, or one attribute of an element.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Item {
+    Node(usize),
+    Attribute(usize, usize),
+}
+
+#[derive(Clone, PartialEq, Debug)]
+enum Token {
+    Slash,
+    DoubleSlash,
+    Open,
+    Close,
+    OpenBracket,
+    CloseBracket,
+    At,
+    Comma,
+    Pipe,
+    Plus,
+    Minus,
+    Dot,
+    DotDot,
+    Axis(String),
+    Star,
+    Op(&'static str),
+    Name(String),
+    Literal(String),
+    Number(f64),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, width) = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '/' if next == Some('/') => (Token::DoubleSlash, 2),
+            '/' => (Token::Slash, 1),
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            '[' => (Token::OpenBracket, 1),
+            ']' => (Token::CloseBracket, 1),
+            '@' => (Token::At, 1),
+            ',' => (Token::Comma, 1),
+            '|' => (Token::Pipe, 1),
+            '+' => (Token::Plus, 1),
+            '-' => (Token::Minus, 1),
+            '*' => (Token::Star, 1),
+            '.' if next == Some('.') => (Token::DotDot, 2),
+            '.' if !next.is_some_and(|n| n.is_ascii_digit()) => (Token::Dot, 1),
+            '=' => (Token::Op("="), 1),
+            '!' if next == Some('=') => (Token::Op("!="), 2),
+            '<' if next == Some('=') => (Token::Op("<="), 2),
+            '>' if next == Some('=') => (Token::Op(">="), 2),
+            '<' => (Token::Op("<"), 1),
+            '>' => (Token::Op(">"), 1),
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&q| q == c)
+                    .ok_or("unclosed string in XPath")?;
+                let literal: String = chars[i + 1..i + 1 + end].iter().collect();
+                (Token::Literal(literal), end + 2)
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let end = chars[i..]
+                    .iter()
+                    .position(|d| !d.is_ascii_digit() && *d != '.')
+                    .unwrap_or(chars.len() - i);
+                let number: String = chars[i..i + end].iter().collect();
+                let number = number
+                    .parse()
+                    .map_err(|_| format!("invalid number in XPath: {number}"))?;
+                (Token::Number(number), end)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let mut end = i;
+                while end < chars.len() {
+                    let d = chars[end];
+                    let prefix_colon = d == ':'
+                        && chars.get(end + 1) != Some(&':')
+                        && (end == 0 || chars[end - 1] != ':')
+                        && chars
+                            .get(end + 1)
+                            .is_some_and(|n| n.is_alphabetic() || *n == '_' || *n == '*');
+                    if d.is_alphanumeric() || matches!(d, '_' | '-' | '.') || prefix_colon {
+                        end += 1;
+                    } else {
+                        break;
+                    }
+                }
+                let name: String = chars[i..end].iter().collect();
+                // `ns:*` is a wildcard in any namespace; prefixes are ignored anyway.
+                if chars.get(end) == Some(&'*') && name.ends_with(':') {
+                    tokens.push(Token::Star);
+                    i = end + 1;
+                    continue;
+                }
+                if chars.get(end) == Some(&':') && chars.get(end + 1) == Some(&':') {
+                    (Token::Axis(name), end + 2 - i)
+                } else {
+                    (Token::Name(name), end - i)
+                }
+            }
+            other => return Err(format!("unexpected `{other}` in XPath")),
+        };
+        tokens.push(token);
+        i += width;
+    }
+    Ok(tokens)
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Axis {
+    Child,
+    Descendant,
+    DescendantOrSelf,
+    Parent,
+    Ancestor,
+    AncestorOrSelf,
+    FollowingSibling,
+    PrecedingSibling,
+    Following,
+    Preceding,
+    Attribute,
+    /// `self::`.
+    Current,
+}
+
+enum Test {
+    Name(String),
+    Any,
+    Text,
+    Comment,
+    Node,
+}
+
+struct Step {
+    axis: Axis,
+    test: Test,
+    predicates: Vec<Expr>,
+}
+
+enum Start {
+    Root,
+    Context,
+    /// A parenthesized expression or function call, with predicates of its own.
+    Expr(Box<Expr>, Vec<Expr>),
+}
+
+#[derive(Clone, Copy)]
+enum Arith {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+enum Expr {
+    Path(Start, Vec<Step>),
+    Literal(String),
+    Number(f64),
+    Call(String, Vec<Expr>),
+    Compare(Box<Expr>, &'static str, Box<Expr>),
+    Arith(Box<Expr>, Arith, Box<Expr>),
+    Negate(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Union(Box<Expr>, Box<Expr>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.at)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.at).cloned();
+        self.at += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), String> {
+        match self.eat(&token) {
+            true => Ok(()),
+            false => Err(format!(
+                "expected {} in XPath",
+                match token {
+                    Token::Close => "`)`",
+                    Token::CloseBracket => "`]`",
+                    _ => "another token",
+                }
+            )),
+        }
+    }
+
+    fn keyword(&mut self, word: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Name(name)) if name == word);
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut left = self.and()?;
+        while self.keyword("or") {
+            left = Expr::Or(Box::new(left), Box::new(self.and()?));
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut left = self.equality()?;
+        while self.keyword("and") {
+            left = Expr::And(Box::new(left), Box::new(self.equality()?));
+        }
+        Ok(left)
+    }
+
+    fn equality(&mut self) -> Result<Expr, String> {
+        let mut left = self.relational()?;
+        while let Some(Token::Op(op @ ("=" | "!="))) = self.peek().cloned() {
+            self.at += 1;
+            left = Expr::Compare(Box::new(left), op, Box::new(self.relational()?));
+        }
+        Ok(left)
+    }
+
+    fn relational(&mut self) -> Result<Expr, String> {
+        let mut left = self.additive()?;
+        while let Some(Token::Op(op @ ("<" | "<=" | ">" | ">="))) = self.peek().cloned() {
+            self.at += 1;
+            left = Expr::Compare(Box::new(left), op, Box::new(self.additive()?));
+        }
+        Ok(left)
+    }
+
+    fn additive(&mut self) -> Result<Expr, String> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Plus) => Arith::Add,
+                Some(Token::Minus) => Arith::Sub,
+                _ => break,
+            };
+            self.at += 1;
+            left = Expr::Arith(Box::new(left), op, Box::new(self.multiplicative()?));
+        }
+        Ok(left)
+    }
+
+    /// After an operand, `*` multiplies and `div` and `mod` are operators, not names.
+    fn multiplicative(&mut self) -> Result<Expr, String> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat(&Token::Star) {
+                Arith::Mul
+            } else if self.keyword("div") {
+                Arith::Div
+            } else if self.keyword("mod") {
+                Arith::Mod
+            } else {
+                break;
+            };
+            left = Expr::Arith(Box::new(left), op, Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.eat(&Token::Minus) {
+            true => Ok(Expr::Negate(Box::new(self.unary()?))),
+            false => self.union(),
+        }
+    }
+
+    fn union(&mut self) -> Result<Expr, String> {
+        let mut left = self.path()?;
+        while self.eat(&Token::Pipe) {
+            left = Expr::Union(Box::new(left), Box::new(self.path()?));
+        }
+        Ok(left)
+    }
+
+    fn starts_step(&self) -> bool {
+        matches!(
+            self.peek(),
+            Some(
+                Token::Name(_)
+                    | Token::Star
+                    | Token::At
+                    | Token::Dot
+                    | Token::DotDot
+                    | Token::Axis(_)
+            )
+        )
+    }
+
+    fn predicates(&mut self) -> Result<Vec<Expr>, String> {
+        let mut predicates = Vec::new();
+        while self.eat(&Token::OpenBracket) {
+            predicates.push(self.or()?);
+            self.expect(Token::CloseBracket)?;
+        }
+        Ok(predicates)
+    }
+
+    fn path(&mut self) -> Result<Expr, String> {
+        let mut steps = Vec::new();
+        let start = match self.peek().cloned() {
+            Some(Token::Slash) => {
+                self.at += 1;
+                if self.starts_step() {
+                    steps.push(self.step()?);
+                }
+                Start::Root
+            }
+            Some(Token::DoubleSlash) => {
+                self.at += 1;
+                steps.push(descendant_or_self());
+                steps.push(self.step()?);
+                Start::Root
+            }
+            Some(Token::Literal(text)) => {
+                self.at += 1;
+                return Ok(Expr::Literal(text));
+            }
+            Some(Token::Number(number)) => {
+                self.at += 1;
+                return Ok(Expr::Number(number));
+            }
+            Some(Token::Open) => {
+                self.at += 1;
+                let inner = self.or()?;
+                self.expect(Token::Close)?;
+                Start::Expr(Box::new(inner), self.predicates()?)
+            }
+            Some(Token::Name(name))
+                if self.tokens.get(self.at + 1) == Some(&Token::Open)
+                    && !matches!(name.as_str(), "text" | "node" | "comment") =>
+            {
+                self.at += 2;
+                let mut args = Vec::new();
+                if !self.eat(&Token::Close) {
+                    loop {
+                        args.push(self.or()?);
+                        if self.eat(&Token::Close) {
+                            break;
+                        }
+                        self.expect(Token::Comma)?;
+                    }
+                }
+                Start::Expr(Box::new(Expr::Call(name, args)), self.predicates()?)
+            }
+            _ => {
+                steps.push(self.step()?);
+                Start::Context
+            }
+        };
+        loop {
+            match self.peek() {
+                Some(Token::Slash) => {
+                    self.at += 1;
+                    steps.push(self.step()?);
+                }
+                Some(Token::DoubleSlash) => {
+                    self.at += 1;
+                    steps.push(descendant_or_self());
+                    steps.push(self.step()?);
+                }
+                _ => break,
+            }
+        }
+        Ok(Expr::Path(start, steps))
+    }
+
+    fn step(&mut self) -> Result<Step, String> {
+        let axis = match self.next() {
+            Some(Token::Dot) => {
+                return Ok(Step {
+                    axis: Axis::Current,
+                    test: Test::Node,
+                    predicates: self.predicates()?,
+                })
+            }
+            Some(Token::DotDot) => {
+                return Ok(Step {
+                    axis: Axis::Parent,
+                    test: Test::Node,
+                    predicates: self.predicates()?,
+                })
+            }
+            Some(Token::At) => Axis::Attribute,
+            Some(Token::Axis(name)) => match name.as_str() {
+                "child" => Axis::Child,
+                "descendant" => Axis::Descendant,
+                "descendant-or-self" => Axis::DescendantOrSelf,
+                "parent" => Axis::Parent,
+                "ancestor" => Axis::Ancestor,
+                "ancestor-or-self" => Axis::AncestorOrSelf,
+                "following-sibling" => Axis::FollowingSibling,
+                "preceding-sibling" => Axis::PrecedingSibling,
+                "following" => Axis::Following,
+                "preceding" => Axis::Preceding,
+                "attribute" => Axis::Attribute,
+                "self" => Axis::Current,
+                other => return Err(format!("unsupported XPath axis: {other}")),
+            },
+            Some(_) => {
+                self.at -= 1;
+                Axis::Child
+            }
+            None => return Err("XPath ends where a step was expected".to_string()),
+        };
+        let test = match self.next() {
+            Some(Token::Star) => Test::Any,
+            Some(Token::Name(name)) if self.peek() == Some(&Token::Open) => {
+                self.at += 1;
+                self.expect(Token::Close)?;
+                match name.as_str() {
+                    "text" => Test::Text,
+                    "node" => Test::Node,
+                    "comment" => Test::Comment,
+                    other => return Err(format!("unsupported XPath node test: {other}()")),
+                }
+            }
+            Some(Token::Name(name)) => Test::Name(name),
+            _ => return Err("expected a name in XPath step".to_string()),
+        };
+        Ok(Step {
+            axis,
+            test,
+            predicates: self.predicates()?,
+        })
+    }
+}
+
+fn descendant_or_self() -> Step {
+    Step {
+        axis: Axis::DescendantOrSelf,
+        test: Test::Node,
+        predicates: Vec::new(),
+    }
+}
+
+fn parse_xpath(text: &str) -> Result<Expr, String> {
+    let mut parser = Parser {
+        tokens: tokenize(text)?,
+        at: 0,
+    };
+    if parser.tokens.is_empty() {
+        return Err("XPath is empty".to_string());
+    }
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {token:?} in XPath")),
+    }
+}
+
+enum XValue {
+    Items(Vec<Item>),
+    Text(String),
+    Number(f64),
+    Bool(bool),
+}
+
+struct Context {
+    item: Item,
+    position: usize,
+    size: usize,
+}
+
+impl Tree {
+    fn item_text(&self, item: Item) -> String {
+        match item {
+            Item::Node(id) => self.text(id),
+            Item::Attribute(id, index) => self.nodes[id].attributes[index].1.clone(),
+        }
+    }
+
+    fn string(&self, value: &XValue) -> String {
+        match value {
+            XValue::Items(items) => items
+                .first()
+                .map(|&i| self.item_text(i))
+                .unwrap_or_default(),
+            XValue::Text(text) => text.clone(),
+            XValue::Number(n) if n.fract() == 0.0 && n.is_finite() => format!("{}", *n as i64),
+            XValue::Number(n) if n.is_infinite() => match n.is_sign_positive() {
+                true => "Infinity".to_string(),
+                false => "-Infinity".to_string(),
+            },
+            XValue::Number(n) => n.to_string(),
+            XValue::Bool(b) => b.to_string(),
+        }
+    }
+
+    fn number(&self, value: &XValue) -> f64 {
+        match value {
+            XValue::Number(n) => *n,
+            XValue::Bool(b) => f64::from(u8::from(*b)),
+            other => self.string(other).trim().parse().unwrap_or(f64::NAN),
+        }
+    }
+
+    fn boolean(value: &XValue) -> bool {
+        match value {
+            XValue::Items(items) => !items.is_empty(),
+            XValue::Text(text) => !text.is_empty(),
+            XValue::Number(n) => *n != 0.0 && !n.is_nan(),
+            XValue::Bool(b) => *b,
+        }
+    }
+
+    fn matches_test(&self, item: Item, test: &Test, axis: Axis) -> bool {
+        match item {
+            Item::Attribute(id, index) => match test {
+                Test::Any | Test::Node => true,
+                Test::Name(name) => {
+                    let own = local(&self.nodes[id].attributes[index].0);
+                    match self.html {
+                        true => own.eq_ignore_ascii_case(local(name)),
+                        false => own == local(name),
+                    }
+                }
+                _ => false,
+            },
+            Item::Node(id) => {
+                let kind = self.nodes[id].kind;
+                match test {
+                    Test::Node => true,
+                    Test::Text => kind == Kind::Text,
+                    Test::Comment => kind == Kind::Comment,
+                    // The principal node type of every axis here but `attribute` is element.
+                    Test::Any => kind == Kind::Element || axis == Axis::Attribute,
+                    Test::Name(name) => kind == Kind::Element && self.named(id, name),
+                }
+            }
+        }
+    }
+
+    fn descendants(&self, id: usize, out: &mut Vec<Item>) {
+        for &child in &self.nodes[id].children {
+            out.push(Item::Node(child));
+            self.descendants(child, out);
+        }
+    }
+
+    fn axis(&self, item: Item, axis: Axis) -> Vec<Item> {
+        let Item::Node(id) = item else {
+            // Attributes only have a parent and themselves.
+            let Item::Attribute(owner, _) = item else {
+                unreachable!()
+            };
+            return match axis {
+                Axis::Parent => vec![Item::Node(owner)],
+                Axis::Current => vec![item],
+                Axis::Ancestor | Axis::AncestorOrSelf => {
+                    let mut out = match axis {
+                        Axis::AncestorOrSelf => vec![item],
+                        _ => Vec::new(),
+                    };
+                    out.push(Item::Node(owner));
+                    out.extend(self.axis(Item::Node(owner), Axis::Ancestor));
+                    out
+                }
+                _ => Vec::new(),
+            };
+        };
+        let node = &self.nodes[id];
+        let siblings = || {
+            node.parent
+                .map(|p| self.nodes[p].children.clone())
+                .unwrap_or_default()
+        };
+        match axis {
+            Axis::Child => node.children.iter().map(|&c| Item::Node(c)).collect(),
+            Axis::Descendant => {
+                let mut out = Vec::new();
+                self.descendants(id, &mut out);
+                out
+            }
+            Axis::DescendantOrSelf => {
+                let mut out = vec![item];
+                self.descendants(id, &mut out);
+                out
+            }
+            Axis::Parent => node.parent.map(Item::Node).into_iter().collect(),
+            Axis::Ancestor | Axis::AncestorOrSelf => {
+                let mut out = match axis {
+                    Axis::AncestorOrSelf => vec![item],
+                    _ => Vec::new(),
+                };
+                let mut current = node.parent;
+                while let Some(parent) = current {
+                    out.push(Item::Node(parent));
+                    current = self.nodes[parent].parent;
+                }
+                out
+            }
+            Axis::FollowingSibling => {
+                let siblings = siblings();
+                let at = siblings.iter().position(|&s| s == id).unwrap_or(0);
+                siblings[at + 1..].iter().map(|&s| Item::Node(s)).collect()
+            }
+            Axis::PrecedingSibling => {
+                let siblings = siblings();
+                let at = siblings.iter().position(|&s| s == id).unwrap_or(0);
+                // Reverse axis: nearest first, so `[1]` is the closest sibling.
+                siblings[..at]
+                    .iter()
+                    .rev()
+                    .map(|&s| Item::Node(s))
+                    .collect()
+            }
+            Axis::Following => {
+                let mut inside = Vec::new();
+                self.descendants(id, &mut inside);
+                let last = inside
+                    .iter()
+                    .filter_map(|i| match i {
+                        Item::Node(n) => Some(*n),
+                        _ => None,
+                    })
+                    .max()
+                    .unwrap_or(id);
+                (last + 1..self.nodes.len()).map(Item::Node).collect()
+            }
+            Axis::Preceding => {
+                let ancestors = self.axis(item, Axis::Ancestor);
+                (1..id)
+                    .rev()
+                    .map(Item::Node)
+                    .filter(|i| !ancestors.contains(i))
+                    .collect()
+            }
+            Axis::Attribute => (0..node.attributes.len())
+                .map(|index| Item::Attribute(id, index))
+                .collect(),
+            Axis::Current => vec![item],
+        }
+    }
+
+    fn step(&self, items: &[Item], step: &Step) -> Result<Vec<Item>, String> {
+        let mut out: Vec<Item> = Vec::new();
+        for &item in items {
+            let mut selected: Vec<Item> = self
+                .axis(item, step.axis)
+                .into_iter()
+                .filter(|&i| self.matches_test(i, &step.test, step.axis))
+                .collect();
+            for predicate in &step.predicates {
+                selected = self.filter(selected, predicate)?;
+            }
+            out.extend(selected);
+        }
+        out.sort();
+        out.dedup();
+        Ok(out)
+    }
+
+    fn filter(&self, items: Vec<Item>, predicate: &Expr) -> Result<Vec<Item>, String> {
+        let size = items.len();
+        let mut kept = Vec::new();
+        for (i, item) in items.into_iter().enumerate() {
+            let context = Context {
+                item,
+                position: i + 1,
+                size,
+            };
+            let keep = match self.eval(predicate, &context)? {
+                XValue::Number(n) => n == (i + 1) as f64,
+                other => Self::boolean(&other),
+            };
+            if keep {
+                kept.push(item);
+            }
+        }
+        Ok(kept)
+    }
+
+    fn compare(&self, left: &XValue, op: &str, right: &XValue) -> bool {
+        let atoms = |value: &XValue| -> Vec<XValue> {
+            match value {
+                XValue::Items(items) => items
+                    .iter()
+                    .map(|&i| XValue::Text(self.item_text(i)))
+                    .collect(),
+                XValue::Text(t) => vec![XValue::Text(t.clone())],
+                XValue::Number(n) => vec![XValue::Number(*n)],
+                XValue::Bool(b) => vec![XValue::Bool(*b)],
+            }
+        };
+        if matches!(left, XValue::Bool(_)) || matches!(right, XValue::Bool(_)) {
+            let (a, b) = (Self::boolean(left), Self::boolean(right));
+            return match op {
+                "=" => a == b,
+                "!=" => a != b,
+                _ => self.order(f64::from(u8::from(a)), op, f64::from(u8::from(b))),
+            };
+        }
+        let (left, right) = (atoms(left), atoms(right));
+        left.iter().any(|a| {
+            right.iter().any(|b| {
+                let numeric = matches!(a, XValue::Number(_)) || matches!(b, XValue::Number(_));
+                match op {
+                    "=" | "!=" if !numeric => (self.string(a) == self.string(b)) == (op == "="),
+                    "=" => self.number(a) == self.number(b),
+                    "!=" => self.number(a) != self.number(b),
+                    _ => self.order(self.number(a), op, self.number(b)),
+                }
+            })
+        })
+    }
+
+    fn order(&self, a: f64, op: &str, b: f64) -> bool {
+        match op {
+            "<" => a < b,
+            "<=" => a <= b,
+            ">" => a > b,
+            ">=" => a >= b,
+            _ => false,
+        }
+    }
+
+    fn eval(&self, expr: &Expr, context: &Context) -> Result<XValue, String> {
+        Ok(match expr {
+            Expr::Literal(text) => XValue::Text(text.clone()),
+            Expr::Number(n) => XValue::Number(*n),
+            Expr::Or(a, b) => XValue::Bool(
+                Self::boolean(&self.eval(a, context)?) || Self::boolean(&self.eval(b, context)?),
+            ),
+            Expr::And(a, b) => XValue::Bool(
+                Self::boolean(&self.eval(a, context)?) && Self::boolean(&self.eval(b, context)?),
+            ),
+            Expr::Compare(a, op, b) => {
+                let (a, b) = (self.eval(a, context)?, self.eval(b, context)?);
+                XValue::Bool(self.compare(&a, op, &b))
+            }
+            Expr::Arith(a, op, b) => {
+                let (a, b) = (self.eval(a, context)?, self.eval(b, context)?);
+                let (a, b) = (self.number(&a), self.number(&b));
+                XValue::Number(match op {
+                    Arith::Add => a + b,
+                    Arith::Sub => a - b,
+                    Arith::Mul => a * b,
+                    Arith::Div => a / b,
+                    // XPath's `mod` keeps the sign of the dividend, as `%` does.
+                    Arith::Mod => a % b,
+                })
+            }
+            Expr::Negate(inner) => {
+                let value = self.eval(inner, context)?;
+                XValue::Number(-self.number(&value))
+            }
+            Expr::Union(a, b) => {
+                let (XValue::Items(mut a), XValue::Items(b)) =
+                    (self.eval(a, context)?, self.eval(b, context)?)
+                else {
+                    return Err("| joins node sets only".to_string());
+                };
+                a.extend(b);
+                a.sort();
+                a.dedup();
+                XValue::Items(a)
+            }
+            Expr::Path(start, steps) => {
+                let mut items = match start {
+                    Start::Root => vec![Item::Node(0)],
+                    Start::Context => vec![context.item],
+                    Start::Expr(inner, predicates) => {
+                        let value = self.eval(inner, context)?;
+                        let XValue::Items(mut items) = value else {
+                            if steps.is_empty() && predicates.is_empty() {
+                                return Ok(value);
+                            }
+                            return Err("a path can only continue from a node set".to_string());
+                        };
+                        for predicate in predicates {
+                            items = self.filter(items, predicate)?;
+                        }
+                        items
+                    }
+                };
+                for step in steps {
+                    items = self.step(&items, step)?;
+                }
+                XValue::Items(items)
+            }
+            Expr::Call(name, args) => self.call(name, args, context)?,
+        })
+    }
+
+    fn call(&self, name: &str, args: &[Expr], context: &Context) -> Result<XValue, String> {
+        let arg = |i: usize| -> Result<XValue, String> {
+            match args.get(i) {
+                Some(expr) => self.eval(expr, context),
+                None => Ok(XValue::Items(vec![context.item])),
+            }
+        };
+        let text = |i: usize| -> Result<String, String> { Ok(self.string(&arg(i)?)) };
+        let arity = |min: usize, max: usize| match (min..=max).contains(&args.len()) {
+            true => Ok(()),
+            false => Err(format!("{name}() takes {min} to {max} arguments")),
+        };
+        Ok(match name {
+            "last" => XValue::Number(context.size as f64),
+            "position" => XValue::Number(context.position as f64),
+            "count" => {
+                arity(1, 1)?;
+                match arg(0)? {
+                    XValue::Items(items) => XValue::Number(items.len() as f64),
+                    _ => return Err("count() takes a node set".to_string()),
+                }
+            }
+            "string" => {
+                arity(0, 1)?;
+                XValue::Text(text(0)?)
+            }
+            "number" => {
+                arity(0, 1)?;
+                XValue::Number(self.number(&arg(0)?))
+            }
+            "boolean" => {
+                arity(1, 1)?;
+                XValue::Bool(Self::boolean(&arg(0)?))
+            }
+            "not" => {
+                arity(1, 1)?;
+                XValue::Bool(!Self::boolean(&arg(0)?))
+            }
+            "true" => XValue::Bool(true),
+            "false" => XValue::Bool(false),
+            "contains" => {
+                arity(2, 2)?;
+                XValue::Bool(text(0)?.contains(&text(1)?))
+            }
+            "starts-with" => {
+                arity(2, 2)?;
+                XValue::Bool(text(0)?.starts_with(&text(1)?))
+            }
+            "ends-with" => {
+                arity(2, 2)?;
+                XValue::Bool(text(0)?.ends_with(&text(1)?))
+            }
+            "concat" => XValue::Text(
+                (0..args.len())
+                    .map(text)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .concat(),
+            ),
+            "normalize-space" => {
+                arity(0, 1)?;
+                XValue::Text(text(0)?.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+            "string-length" => {
+                arity(0, 1)?;
+                XValue::Number(text(0)?.chars().count() as f64)
+            }
+            "lower-case" => {
+                arity(1, 1)?;
+                XValue::Text(text(0)?.to_lowercase())
+            }
+            "upper-case" => {
+                arity(1, 1)?;
+                XValue::Text(text(0)?.to_uppercase())
+            }
+            "sum" => {
+                arity(1, 1)?;
+                match arg(0)? {
+                    XValue::Items(items) => XValue::Number(
+                        items
+                            .iter()
+                            .map(|&i| self.item_text(i).trim().parse::<f64>().unwrap_or(f64::NAN))
+                            .sum(),
+                    ),
+                    _ => return Err("sum() takes a node set".to_string()),
+                }
+            }
+            "name" | "local-name" => {
+                arity(0, 1)?;
+                let item = match arg(0)? {
+                    XValue::Items(items) => items.first().copied(),
+                    _ => return Err(format!("{name}() takes a node set")),
+                };
+                let full = match item {
+                    Some(Item::Node(id)) if self.nodes[id].kind == Kind::Element => {
+                        self.nodes[id].name.clone()
+                    }
+                    Some(Item::Attribute(id, index)) => self.nodes[id].attributes[index].0.clone(),
+                    _ => String::new(),
+                };
+                XValue::Text(match name {
+                    "local-name" => local(&full).to_string(),
+                    _ => full,
+                })
+            }
+            other => return Err(format!("unsupported XPath function: {other}()")),
+        })
+    }
+}
+
+fn looks_like_xml(text: &str, content_type: Option<&str>) -> bool {
+    let content_type = content_type.unwrap_or_default().to_ascii_lowercase();
+    if content_type.contains("html") {
+        return false;
+    }
+    if content_type.contains("xml") {
+        return true;
+    }
+    let start = text.trim_start_matches('\u{feff}').trim_start();
+    start.starts_with("<?xml")
+        || (!start.to_ascii_lowercase().starts_with("<!doctype html")
+            && !start.to_ascii_lowercase().starts_with("<html"))
+}
+
+fn result(mut matches: Vec<NodeMatch>) -> SelectResult {
+    let total = matches.len();
+    matches.truncate(MAX_MATCHES);
+    SelectResult {
+        matches,
+        total,
+        truncated: total > MAX_MATCHES,
+    }
+}
+
+/// Evaluates an XPath expression or a CSS selector against `body`. Whether the body is XML
+/// or HTML follows `content_type`, or its first tag without one; XML that doesn't parse
+/// is read as HTML, which is forgiving.
+pub fn select(
+    body: &str,
+    content_type: Option<&str>,
+    kind: SelectorKind,
+    selector: &str,
+) -> Result<SelectResult, String> {
+    if kind == SelectorKind::Css {
+        let css =
+            scraper::Selector::parse(selector).map_err(|e| format!("invalid CSS selector: {e}"))?;
+        let html = scraper::Html::parse_document(body);
+        let (tree, ids) = Tree::from_html(&html);
+        let matches = html
+            .select(&css)
+            .filter_map(|element| ids.get(&element.id()))
+            .map(|&id| tree.node_match(Item::Node(id)))
+            .collect();
+        return Ok(result(matches));
+    }
+
+    let expr = parse_xpath(selector)?;
+    let tree = match looks_like_xml(body, content_type) {
+        true => Tree::from_xml(body).or_else(|e| match content_type {
+            Some(ct) if ct.to_ascii_lowercase().contains("xml") => Err(e),
+            _ => Ok(Tree::from_html(&scraper::Html::parse_document(body)).0),
+        })?,
+        false => Tree::from_html(&scraper::Html::parse_document(body)).0,
+    };
+    let context = Context {
+        item: Item::Node(0),
+        position: 1,
+        size: 1,
+    };
+    let matches = match tree.eval(&expr, &context)? {
+        XValue::Items(items) => items
+            .into_iter()
+            .map(|item| tree.node_match(item))
+            .collect(),
+        value => vec![NodeMatch {
+            path: String::new(),
+            kind: "value".to_string(),
+            name: None,
+            text: tree.string(&value),
+            markup: None,
+            attributes: BTreeMap::new(),
+        }],
+    };
+    Ok(result(matches))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = r#"<?xml version="1.0"?>
+<soap:Envelope xmlns:soap="http://schemas.xmlsoap.org/soap/envelope/">
+  <soap:Body>
+    <list>
+      <a id="1">one</a>
+      <a id="2">two</a>
+      <b id="3"><a id="4">four</a></b>
+      <a id="5">five</a>
+      <!-- note -->
+    </list>
+  </soap:Body>
+</soap:Envelope>"#;
+
+    fn texts(xpath: &str) -> Vec<String> {
+        select(DOC, Some("text/xml"), SelectorKind::Xpath, xpath)
+            .unwrap()
+            .matches
+            .into_iter()
+            .map(|m| m.text)
+            .collect()
+    }
+
+    fn value(xpath: &str) -> String {
+        let mut matches = texts(xpath);
+        assert_eq!(matches.len(), 1, "{xpath}");
+        matches.remove(0)
+    }
+
+    fn error(xpath: &str) -> String {
+        match select(DOC, Some("text/xml"), SelectorKind::Xpath, xpath) {
+            Ok(_) => panic!("{xpath} should fail"),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn paths_ignore_prefixes() {
+        assert_eq!(texts("/Envelope/Body/list/a"), ["one", "two", "five"]);
+        assert_eq!(texts("//soap:Body/list/b/a"), ["four"]);
+        assert_eq!(texts("//a"), ["one", "two", "four", "five"]);
+        assert_eq!(texts("//list/*[@id='3']/a"), ["four"]);
+    }
+
+    #[test]
+    fn axes() {
+        assert_eq!(texts("//a[@id='2']/following-sibling::a"), ["five"]);
+        assert_eq!(texts("//a[@id='5']/preceding-sibling::a[1]"), ["two"]);
+        assert_eq!(texts("//a[@id='4']/parent::*/@id"), ["3"]);
+        assert_eq!(texts("//a[@id='4']/ancestor::list/a[1]"), ["one"]);
+        assert_eq!(texts("//b/descendant::a"), ["four"]);
+        assert_eq!(texts("//a[@id='2']/following::a"), ["four", "five"]);
+        // A node set comes back in document order, whatever the axis.
+        assert_eq!(texts("//a[@id='4']/preceding::a"), ["one", "two"]);
+        assert_eq!(texts("//b/self::b/@id"), ["3"]);
+        assert_eq!(texts("//list/comment()"), [" note "]);
+        assert_eq!(texts("//a[@id='1']/text()"), ["one"]);
+        assert_eq!(texts("//a[@id='1']/.."), texts("//list"));
+    }
+
+    #[test]
+    fn predicates() {
+        assert_eq!(texts("//list/a[2]"), ["two"]);
+        assert_eq!(texts("//list/a[last()]"), ["five"]);
+        assert_eq!(texts("//list/a[position() > 1]"), ["two", "five"]);
+        assert_eq!(texts("//a[@id >= 4][1]"), ["four", "five"]);
+        assert_eq!(texts("(//a)[4]"), ["five"]);
+        assert_eq!(texts("//a[@id='1' or @id='5']"), ["one", "five"]);
+        assert_eq!(texts("//a[@id > 1 and @id < 5]"), ["two", "four"]);
+        assert_eq!(texts("//a[@id='1'] | //b/a"), ["one", "four"]);
+    }
+
+    #[test]
+    fn functions() {
+        assert_eq!(value("count(//a)"), "4");
+        assert_eq!(value("sum(//a/@id)"), "12");
+        assert_eq!(value("string(//b/a)"), "four");
+        assert_eq!(value("concat(//a[1], '-', //a[2])"), "one-two");
+        assert_eq!(value("string-length(//a[@id='5'])"), "4");
+        assert_eq!(value("normalize-space('  a   b ')"), "a b");
+        assert_eq!(value("local-name(//soap:Body)"), "Body");
+        assert_eq!(value("name(//soap:Body)"), "soap:Body");
+        assert_eq!(texts("//a[contains(., 'o')]"), ["one", "two", "four"]);
+        assert_eq!(texts("//a[starts-with(., 'f')]"), ["four", "five"]);
+        assert_eq!(texts("//a[not(@id > 2)]"), ["one", "two"]);
+        assert!(error("frobnicate()").contains("unsupported XPath function"));
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(texts("//list/a[last() - 1]"), ["two"]);
+        assert_eq!(texts("//a[@id + 1 = 3]"), ["two"]);
+        assert!(texts("//a[-1]").is_empty());
+        assert_eq!(value("7 div 2"), "3.5");
+        assert_eq!(value("7 mod 3"), "1");
+        assert_eq!(value("-7 mod 3"), "-1");
+        assert_eq!(value("2 + 3 * 4"), "14");
+        assert_eq!(value("(2 + 3) * 4"), "20");
+        assert_eq!(value("10 - 2 - 3"), "5");
+        assert_eq!(value("--3"), "3");
+        assert_eq!(value("1 div 0"), "Infinity");
+        assert_eq!(value("count(//a) * 2"), "8");
+        assert_eq!(texts("//a[@id mod 2 = 0]"), ["two", "four"]);
+    }
+
+    #[test]
+    fn comparisons_bind_tighter_than_equality() {
+        assert_eq!(value("1 < 2 = true()"), "true");
+        assert_eq!(value("2 > 1 = 1 > 2"), "false");
+    }
+
+    #[test]
+    fn names_can_be_operators_elsewhere() {
+        let html = "<html><body><div><div>inner</div></div><mod>m</mod></body></html>";
+        let found = select(html, Some("text/html"), SelectorKind::Xpath, "//div/div").unwrap();
+        assert_eq!(found.matches[0].text, "inner");
+        let found = select(html, Some("text/html"), SelectorKind::Xpath, "//mod").unwrap();
+        assert_eq!(found.matches[0].text, "m");
+    }
+
+    #[test]
+    fn errors() {
+        assert!(error("").contains("empty"));
+        assert!(error("//a[").contains("XPath"));
+        assert!(error("//a[1").contains("`]`"));
+        assert!(error("'open").contains("unclosed"));
+        assert!(error("//a/unknown::b").contains("unsupported XPath axis"));
+    }
+}
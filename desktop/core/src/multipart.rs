@@ -0,0 +1,168 @@
+//! `multipart/form-data` bodies framed here rather than by the backend, so each part can
+//! carry its own content type and headers and file parts a filename of their choosing. Parts
+//! go out in the order of the request's `form_body` rows. Files aren't read: they stay
+//! `Segment::File` for the sender to stream, which is also how the total length is known up
+//! front.
+
+use base64::Engine;
+use serde_json::Value;
+use std::path::PathBuf;
+
+use crate::json::str_of;
+
+/// A stretch of the body: bytes framed here, or a file to send as is.
+pub enum Segment {
+    Bytes(Vec<u8>),
+    File { path: PathBuf, size: u64 },
+}
+
+pub struct Multipart {
+    pub boundary: String,
+    pub segments: Vec<Segment>,
+    /// Bytes in the whole body.
+    pub length: u64,
+}
+
+impl Multipart {
+    pub fn content_type(&self) -> String {
+        format!("multipart/form-data; boundary={}", self.boundary)
+    }
+}
+
+/// Quotes a name or filename the way browsers do: `"`, CR and LF percent-encoded, the rest
+/// (UTF-8 included) as is.
+fn quoted(text: &str) -> String {
+    let escaped = text
+        .replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A");
+    format!("\"{escaped}\"")
+}
+
+fn text_of(value: Option<&Value>) -> String {
+    match value {
+        Some(Value::String(text)) => text.clone(),
+        None | Some(Value::Null) => String::new(),
+        Some(other) => other.to_string(),
+    }
+}
+
+fn push_bytes(segments: &mut Vec<Segment>, bytes: &[u8]) {
+    match segments.last_mut() {
+        Some(Segment::Bytes(last)) => last.extend_from_slice(bytes),
+        _ => segments.push(Segment::Bytes(bytes.to_vec())),
+    }
+}
+
+/// Frames the enabled `form_body` rows. A row is a text part (`value`) unless its `type` is
+/// `file`, which sends `file_path` (or base64 `file_inline`) under `file_name` (the file's
+/// own name by default). `content_type` and `headers` set the part's headers; a file part's
+/// content type otherwise follows its extension.
+pub fn build(rows: &[Value]) -> Result<Multipart, String> {
+    let boundary = format!("----LiteFetchBoundary{}", uuid::Uuid::new_v4().simple());
+    let mut segments = Vec::new();
+    for row in rows {
+        if row.get("enabled").and_then(Value::as_bool) == Some(false) {
+            continue;
+        }
+        let key = str_of(row, "key").trim();
+        if key.is_empty() {
+            continue;
+        }
+        let file = matches!(str_of(row, "type"), "file" | "binary");
+        let mut headers: Vec<(String, String)> = row
+            .get("headers")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| (name.trim().to_string(), text_of(Some(value))))
+            .filter(|(name, _)| !name.eq_ignore_ascii_case("content-disposition"))
+            .collect();
+        if let Some((name, value)) = headers
+            .iter()
+            .find(|(name, value)| name.contains(['\r', '\n', ':']) || value.contains(['\r', '\n']))
+        {
+            return Err(format!("part {key}: invalid header {name}: {value}"));
+        }
+        let mut content_type = str_of(row, "content_type").trim().to_string();
+        if let Some(index) = headers
+            .iter()
+            .position(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+        {
+            let (_, value) = headers.remove(index);
+            if content_type.is_empty() {
+                content_type = value;
+            }
+        }
+
+        let mut disposition = format!("form-data; name={}", quoted(key));
+        let mut content = None;
+        if file {
+            let path = str_of(row, "file_path").trim();
+            let inline = str_of(row, "file_inline");
+            let own_name = match path.is_empty() {
+                true => key.to_string(),
+                false => std::path::Path::new(path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| key.to_string()),
+            };
+            let file_name = match str_of(row, "file_name").trim() {
+                "" => own_name,
+                name => name.to_string(),
+            };
+            disposition.push_str(&format!("; filename={}", quoted(&file_name)));
+            if content_type.is_empty() {
+                content_type = std::path::Path::new(&file_name)
+                    .extension()
+                    .and_then(|ext| crate::binary::mime_for_extension(&ext.to_string_lossy()))
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+            }
+            content = Some(if !path.is_empty() {
+                let path = crate::workspace::normalize_path(path);
+                let size = std::fs::metadata(&path)
+                    .map_err(|e| format!("part {key}: file read failed: {e}"))?
+                    .len();
+                Segment::File { path, size }
+            } else if !inline.is_empty() {
+                Segment::Bytes(
+                    base64::engine::general_purpose::STANDARD
+                        .decode(inline)
+                        .map_err(|e| format!("part {key}: file decode failed: {e}"))?,
+                )
+            } else {
+                return Err(format!("part {key}: file missing"));
+            });
+        }
+
+        let mut head = format!("--{boundary}\r\nContent-Disposition: {disposition}\r\n");
+        if !content_type.is_empty() {
+            head.push_str(&format!("Content-Type: {content_type}\r\n"));
+        }
+        for (name, value) in &headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        head.push_str("\r\n");
+        push_bytes(&mut segments, head.as_bytes());
+        match content {
+            Some(Segment::Bytes(bytes)) => push_bytes(&mut segments, &bytes),
+            Some(file) => segments.push(file),
+            None => push_bytes(&mut segments, text_of(row.get("value")).as_bytes()),
+        }
+        push_bytes(&mut segments, b"\r\n");
+    }
+    push_bytes(&mut segments, format!("--{boundary}--\r\n").as_bytes());
+    let length = segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Bytes(bytes) => bytes.len() as u64,
+            Segment::File { size, .. } => *size,
+        })
+        .sum();
+    Ok(Multipart {
+        boundary,
+        segments,
+        length,
+    })
+}
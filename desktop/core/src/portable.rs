@@ -0,0 +1,49 @@
+//! Portable mode, for running from a USB stick or on a machine where the app can't write
+//! to the usual places: with a `portable.flag` file next to the executable, or with
+//! `--portable`, the app data root (workspace record, default workspace, settings, history,
+//! logs) moves to `LiteFetchData` beside the executable. Profiles still apply inside it.
+//! Keychain entries stay in the machine's keychain.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const FLAG: &str = "--portable";
+pub const MARKER: &str = "portable.flag";
+const DATA_DIR: &str = "LiteFetchData";
+
+static DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()?
+        .canonicalize()
+        .ok()?
+        .parent()
+        .map(Path::to_path_buf)
+}
+
+/// The directory beside the executable that holds the app data, when portable mode is on
+/// because of `flag` or the marker file.
+pub fn detect(flag: bool) -> Option<PathBuf> {
+    let dir = exe_dir()?;
+    (flag || dir.join(MARKER).exists()).then(|| dir.join(DATA_DIR))
+}
+
+/// Sets portable mode for this process; only the first call counts.
+pub fn set(dir: Option<PathBuf>) {
+    let _ = DIR.set(dir);
+}
+
+/// Where the app data lives in portable mode, if it's on.
+pub fn dir() -> Option<&'static Path> {
+    DIR.get().and_then(|d| d.as_deref())
+}
+
+/// The base directory for the app data root: the portable one when set, else `platform`
+/// (the OS app data directory).
+pub fn base(platform: impl FnOnce() -> Result<PathBuf, String>) -> Result<PathBuf, String> {
+    match dir() {
+        Some(dir) => Ok(dir.to_path_buf()),
+        None => platform(),
+    }
+}
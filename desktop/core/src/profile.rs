@@ -0,0 +1,87 @@
+//! Profiles: fully separate sets of app data, for keeping e.g. each client's configuration
+//! apart. `--profile <name>` (or `LITEFETCH_PROFILE`) moves the app data root to
+//! `litefetch/<name>`, so the workspace record, settings, logs and crash reports are the
+//! profile's own, and keychain entries go under a service of its own. Without one, the app
+//! uses `litefetch` and the `LiteFetch` service as before.
+
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const FLAG: &str = "--profile";
+pub const ENV: &str = "LITEFETCH_PROFILE";
+const KEYCHAIN_SERVICE: &str = "LiteFetch";
+/// Entries of the default app data root that a profile directory would collide with.
+const RESERVED: &[&str] = &["workspace", "logs", "crashes", "drafts"];
+
+static PROFILE: OnceLock<Option<String>> = OnceLock::new();
+
+fn validate(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.len() <= 64
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!(
+            "invalid profile {name:?}; use letters, digits, - and _"
+        ));
+    }
+    if RESERVED.contains(&name.to_ascii_lowercase().as_str()) {
+        return Err(format!("{name} can't be used as a profile name"));
+    }
+    Ok(name.to_string())
+}
+
+/// The profile named by `flag`, else by `LITEFETCH_PROFILE`.
+pub fn resolve(flag: Option<&str>) -> Result<Option<String>, String> {
+    match flag {
+        Some(name) => validate(name).map(Some),
+        None => match std::env::var(ENV) {
+            Ok(name) if !name.trim().is_empty() => validate(&name).map(Some),
+            _ => Ok(None),
+        },
+    }
+}
+
+/// Like `resolve`, finding the flag (`--profile <name>` or `--profile=<name>`) in `args`.
+pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<String>, String> {
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == FLAG {
+            let name = args.next().ok_or(format!("{FLAG} needs a value"))?;
+            return resolve(Some(&name));
+        }
+        if let Some(name) = arg.strip_prefix(&format!("{FLAG}=")) {
+            return resolve(Some(name));
+        }
+    }
+    resolve(None)
+}
+
+/// Sets the profile for this process; only the first call counts.
+pub fn set(profile: Option<String>) {
+    let _ = PROFILE.set(profile);
+}
+
+/// The profile in use, if any.
+pub fn current() -> Option<&'static str> {
+    PROFILE.get().and_then(|p| p.as_deref())
+}
+
+/// The app data root under a platform data directory (`<base>/litefetch[/<profile>]`).
+pub fn data_root(base: &Path) -> PathBuf {
+    let root = base.join("litefetch");
+    match current() {
+        Some(profile) => root.join(profile),
+        None => root,
+    }
+}
+
+/// The keychain service for entries of this profile.
+pub fn keychain_service() -> String {
+    match current() {
+        Some(profile) => format!("{KEYCHAIN_SERVICE}/{profile}"),
+        None => KEYCHAIN_SERVICE.to_string(),
+    }
+}
@@ -0,0 +1,116 @@
+//! Splitting a streamed response body into records as its bytes arrive: NDJSON / JSON Lines
+//! (one JSON value per line), RFC 7464 JSON text sequences (values led by a record
+//! separator), plain lines, or each chunk as it came off the wire. A record may span chunks;
+//! bytes after the last separator wait for the next chunk or `finish`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// RFC 7464's record separator.
+const RS: u8 = 0x1e;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordFormat {
+    /// Picked from the response's `Content-Type` (see `RecordFormat::detect`).
+    #[default]
+    Auto,
+    Ndjson,
+    JsonSeq,
+    Lines,
+    Chunks,
+}
+
+impl RecordFormat {
+    /// The format a `Content-Type` announces; line by line when it names none.
+    pub fn detect(content_type: &str) -> Self {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match mime.as_str() {
+            "application/x-ndjson"
+            | "application/ndjson"
+            | "application/jsonl"
+            | "application/x-jsonlines"
+            | "application/jsonlines" => Self::Ndjson,
+            "application/json-seq" => Self::JsonSeq,
+            m if m.ends_with("+ndjson") => Self::Ndjson,
+            _ => Self::Lines,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct Record {
+    pub text: String,
+    /// The parsed value, for the JSON formats.
+    pub value: Option<Value>,
+    /// Why a JSON record didn't parse; the text is kept either way.
+    pub error: Option<String>,
+}
+
+pub struct Splitter {
+    format: RecordFormat,
+    pending: Vec<u8>,
+}
+
+impl Splitter {
+    /// `format` must not be `Auto`; detect it first.
+    pub fn new(format: RecordFormat) -> Self {
+        Self {
+            format,
+            pending: Vec::new(),
+        }
+    }
+
+    fn record(&self, bytes: &[u8]) -> Option<Record> {
+        let text = String::from_utf8_lossy(bytes);
+        let text = match self.format {
+            RecordFormat::Chunks => text.to_string(),
+            _ => text.trim_end_matches(['\r', '\n']).to_string(),
+        };
+        if text.trim().is_empty() && self.format != RecordFormat::Chunks {
+            return None;
+        }
+        let (value, error) = match self.format {
+            RecordFormat::Ndjson | RecordFormat::JsonSeq => {
+                match serde_json::from_str::<Value>(text.trim()) {
+                    Ok(value) => (Some(value), None),
+                    Err(e) => (None, Some(e.to_string())),
+                }
+            }
+            _ => (None, None),
+        };
+        Some(Record { text, value, error })
+    }
+
+    /// The records completed by `chunk`.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Record> {
+        if self.format == RecordFormat::Chunks {
+            return self.record(chunk).into_iter().collect();
+        }
+        self.pending.extend_from_slice(chunk);
+        let separator = match self.format {
+            RecordFormat::JsonSeq => RS,
+            _ => b'\n',
+        };
+        let Some(last) = self.pending.iter().rposition(|b| *b == separator) else {
+            return Vec::new();
+        };
+        let rest = self.pending.split_off(last + 1);
+        let complete = std::mem::replace(&mut self.pending, rest);
+        complete
+            .split(|b| *b == separator)
+            .filter_map(|bytes| self.record(bytes))
+            .collect()
+    }
+
+    /// The record left over once the body ended without a final separator.
+    pub fn finish(&mut self) -> Option<Record> {
+        let rest = std::mem::take(&mut self.pending);
+        self.record(&rest)
+    }
+}
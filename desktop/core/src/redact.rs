@@ -0,0 +1,46 @@
+//! The process-wide record of secret values, so output that can't be tied to a collection
+//! (backend logs, for one) can still be masked.
+
+use std::sync::{OnceLock, RwLock};
+
+pub const MASK: &str = "••••••••";
+
+/// Shorter values would mask unrelated text wherever they happen to occur.
+pub const MIN_SECRET_LEN: usize = 4;
+
+/// Secret values seen by this process, used for log lines that can't be tied to a collection.
+fn known() -> &'static RwLock<Vec<String>> {
+    static KNOWN: OnceLock<RwLock<Vec<String>>> = OnceLock::new();
+    KNOWN.get_or_init(|| RwLock::new(Vec::new()))
+}
+
+/// Records a secret value so later log output masks it.
+pub fn remember(value: &str) {
+    if value.chars().count() < MIN_SECRET_LEN {
+        return;
+    }
+    if let Ok(mut values) = known().write() {
+        if !values.iter().any(|v| v == value) {
+            values.push(value.to_string());
+            values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+        }
+    }
+}
+
+/// Masks every remembered secret in a line of backend output.
+pub fn log_line(text: &str) -> String {
+    match known().read() {
+        Ok(values) => mask_all(text, &values),
+        Err(_) => text.to_string(),
+    }
+}
+
+pub fn mask_all(text: &str, values: &[String]) -> String {
+    let mut text = text.to_string();
+    for value in values {
+        if text.contains(value.as_str()) {
+            text = text.replace(value.as_str(), MASK);
+        }
+    }
+    text
+}
@@ -0,0 +1,411 @@
+//! Reports for finished runs: JUnit XML for CI dashboards and a standalone HTML page. Both
+//! take a run summary as JSON, from `runner::execute` or `workflow::run`.
+
+use quick_xml::escape::escape;
+use serde::Deserialize;
+use serde_json::Value;
+use std::fmt::Write;
+
+use crate::json::{array_of, str_of, value_text};
+
+/// Longer expected/actual texts are shown whole instead of diffed line by line.
+const DIFF_LINE_LIMIT: usize = 400;
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Junit,
+    Html,
+}
+
+/// One JUnit test case: a request, one of its tests, or a workflow step.
+struct Case {
+    class: String,
+    name: String,
+    seconds: f64,
+    /// `passed`, `failed` or `error`.
+    status: String,
+    message: Option<String>,
+    /// Expected and actual values, for a diff.
+    compare: Option<(String, String)>,
+    detail: String,
+}
+
+struct Suite {
+    name: String,
+    cases: Vec<Case>,
+}
+
+fn seconds(value: &Value, key: &str) -> f64 {
+    value.get(key).and_then(Value::as_f64).unwrap_or(0.0) / 1000.0
+}
+
+fn status_line(result: &Value) -> String {
+    match result.get("status_code").and_then(Value::as_u64) {
+        Some(code) => format!(
+            "HTTP {code} in {:.0} ms",
+            result
+                .get("duration_ms")
+                .and_then(Value::as_f64)
+                .unwrap_or(0.0)
+        ),
+        None => "not sent".to_string(),
+    }
+}
+
+fn collection_suites(run: &Value) -> Vec<Suite> {
+    let mut suites: Vec<Suite> = Vec::new();
+    for result in array_of(run, "results") {
+        let suite_name = match result.get("iteration").and_then(Value::as_u64) {
+            Some(iteration) => format!("Iteration {}", iteration + 1),
+            None => "Collection run".to_string(),
+        };
+        if suites.last().is_none_or(|s| s.name != suite_name) {
+            suites.push(Suite {
+                name: suite_name.clone(),
+                cases: Vec::new(),
+            });
+        }
+        let folder: Vec<&str> = array_of(result, "folder")
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        let name = str_of(result, "name").to_string();
+        let class = match folder.is_empty() {
+            true => name.clone(),
+            false => format!("{}/{name}", folder.join("/")),
+        };
+        let time = seconds(result, "duration_ms");
+        let detail = status_line(result);
+        let error = result
+            .get("error")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let cases = &mut suites.last_mut().expect("suite pushed above").cases;
+        let tests = result.get("tests").filter(|t| !t.is_null());
+        let test_results = tests.map(|t| array_of(t, "results")).unwrap_or_default();
+        if test_results.is_empty() || error.is_some() {
+            cases.push(Case {
+                class: class.clone(),
+                name: name.clone(),
+                seconds: time,
+                status: match &error {
+                    Some(_) => "error".to_string(),
+                    None => str_of(result, "status").to_string(),
+                },
+                message: error.clone(),
+                compare: None,
+                detail: detail.clone(),
+            });
+        }
+        for test in test_results {
+            let passed = test.get("passed").and_then(Value::as_bool).unwrap_or(false);
+            cases.push(Case {
+                class: class.clone(),
+                name: str_of(test, "name").to_string(),
+                // Tests have no timing of their own; the request's time is on its first case.
+                seconds: 0.0,
+                status: if passed { "passed" } else { "failed" }.to_string(),
+                message: test
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                compare: None,
+                detail: detail.clone(),
+            });
+        }
+        if let Some(script_error) = tests.and_then(|t| t.get("error")).and_then(Value::as_str) {
+            cases.push(Case {
+                class,
+                name: format!("{name} (test script)"),
+                seconds: 0.0,
+                status: "error".to_string(),
+                message: Some(script_error.to_string()),
+                compare: None,
+                detail,
+            });
+        }
+    }
+    suites
+}
+
+fn workflow_suites(run: &Value) -> Vec<Suite> {
+    let suite_name = format!("Workflow {}", str_of(run, "workflow_id"));
+    let mut cases = Vec::new();
+    for step in array_of(run, "steps") {
+        let name = step
+            .get("name")
+            .and_then(Value::as_str)
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| str_of(step, "step_id"))
+            .to_string();
+        let failed: Vec<&Value> = array_of(step, "assertions")
+            .iter()
+            .filter(|a| !a.get("passed").and_then(Value::as_bool).unwrap_or(false))
+            .collect();
+        let message = match step.get("error").and_then(Value::as_str) {
+            Some(error) => Some(error.to_string()),
+            None => (!failed.is_empty()).then(|| {
+                failed
+                    .iter()
+                    .map(|a| {
+                        let assertion = a.get("assertion").unwrap_or(&Value::Null);
+                        format!(
+                            "{} {} {} {}",
+                            str_of(assertion, "source"),
+                            str_of(assertion, "path"),
+                            str_of(assertion, "op"),
+                            value_text(assertion.get("value")),
+                        )
+                        .split_whitespace()
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("; ")
+            }),
+        };
+        // Only equality assertions have an expected value worth diffing.
+        let compare = failed
+            .iter()
+            .find(|a| str_of(a.get("assertion").unwrap_or(&Value::Null), "op") == "equals")
+            .map(|a| {
+                (
+                    value_text(a.pointer("/assertion/value")),
+                    value_text(a.get("actual")),
+                )
+            });
+        cases.push(Case {
+            class: suite_name.clone(),
+            name,
+            seconds: seconds(step, "duration_ms"),
+            status: str_of(step, "status").to_string(),
+            message,
+            compare,
+            detail: status_line(step),
+        });
+    }
+    vec![Suite {
+        name: suite_name,
+        cases,
+    }]
+}
+
+fn suites(run: &Value) -> Result<Vec<Suite>, String> {
+    if run.get("steps").is_some_and(Value::is_array) {
+        Ok(workflow_suites(run))
+    } else if run.get("results").is_some_and(Value::is_array) {
+        Ok(collection_suites(run))
+    } else {
+        Err("not a collection or workflow run".to_string())
+    }
+}
+
+/// Re-indents JSON so diffs line up on structure rather than on one long line.
+fn pretty(text: &str) -> String {
+    serde_json::from_str::<Value>(text)
+        .ok()
+        .filter(|v| v.is_object() || v.is_array())
+        .and_then(|v| serde_json::to_string_pretty(&v).ok())
+        .unwrap_or_else(|| text.to_string())
+}
+
+/// A line diff from `expected` to `actual`, with `-`/`+`/` ` prefixes.
+fn diff(expected: &str, actual: &str) -> Vec<(char, String)> {
+    let (expected, actual) = (pretty(expected), pretty(actual));
+    let a: Vec<&str> = expected.lines().collect();
+    let b: Vec<&str> = actual.lines().collect();
+    if a.len() > DIFF_LINE_LIMIT || b.len() > DIFF_LINE_LIMIT {
+        let mut lines: Vec<(char, String)> = a.iter().map(|l| ('-', l.to_string())).collect();
+        lines.extend(b.iter().map(|l| ('+', l.to_string())));
+        return lines;
+    }
+    // Longest common subsequence table, filled from the end.
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = match a[i] == b[j] {
+                true => lcs[i + 1][j + 1] + 1,
+                false => lcs[i + 1][j].max(lcs[i][j + 1]),
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push((' ', a[i].to_string()));
+            (i, j) = (i + 1, j + 1);
+        } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            lines.push(('+', b[j].to_string()));
+            j += 1;
+        } else {
+            lines.push(('-', a[i].to_string()));
+            i += 1;
+        }
+    }
+    lines
+}
+
+fn diff_text(expected: &str, actual: &str) -> String {
+    diff(expected, actual)
+        .into_iter()
+        .map(|(sign, line)| format!("{sign} {line}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn junit(run: &Value, title: &str) -> Result<String, String> {
+    let suites = suites(run)?;
+    let count =
+        |suite: &Suite, status: &str| suite.cases.iter().filter(|c| c.status == status).count();
+    let all: Vec<&Case> = suites.iter().flat_map(|s| s.cases.iter()).collect();
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+        escape(title),
+        all.len(),
+        all.iter().filter(|c| c.status == "failed").count(),
+        all.iter().filter(|c| c.status == "error").count(),
+        seconds(run, "duration_ms"),
+    );
+    for suite in &suites {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\" time=\"{:.3}\">",
+            escape(&suite.name),
+            suite.cases.len(),
+            count(suite, "failed"),
+            count(suite, "error"),
+            suite.cases.iter().map(|c| c.seconds).sum::<f64>(),
+        );
+        for case in &suite.cases {
+            let _ = write!(
+                xml,
+                "    <testcase classname=\"{}\" name=\"{}\" time=\"{:.3}\">",
+                escape(&case.class),
+                escape(&case.name),
+                case.seconds,
+            );
+            let message = case.message.as_deref().unwrap_or_default();
+            let mut body = message.to_string();
+            if let Some((expected, actual)) = &case.compare {
+                let _ = write!(body, "\n{}", diff_text(expected, actual));
+            }
+            match case.status.as_str() {
+                "failed" => {
+                    let _ = write!(
+                        xml,
+                        "\n      <failure message=\"{}\" type=\"assertion\">{}</failure>",
+                        escape(message),
+                        escape(&body),
+                    );
+                }
+                "error" => {
+                    let _ = write!(
+                        xml,
+                        "\n      <error message=\"{}\" type=\"error\">{}</error>",
+                        escape(message),
+                        escape(&body),
+                    );
+                }
+                _ => {}
+            }
+            let _ = writeln!(
+                xml,
+                "\n      <system-out>{}</system-out>\n    </testcase>",
+                escape(&case.detail)
+            );
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    Ok(xml)
+}
+
+pub(crate) const STYLE: &str = "\
+body{font:14px/1.45 system-ui,sans-serif;margin:2rem;color:#1f2328;background:#fff}\
+h1{font-size:1.4rem;margin:0 0 .25rem}\
+.meta{color:#59636e;margin-bottom:1.5rem}\
+.totals span{display:inline-block;margin-right:1rem;font-weight:600}\
+.passed{color:#1a7f37}.failed{color:#cf222e}.error{color:#9a6700}\
+table{border-collapse:collapse;width:100%;margin:1rem 0 2rem}\
+th,td{text-align:left;padding:.4rem .6rem;border-bottom:1px solid #d1d9e0;vertical-align:top}\
+th{background:#f6f8fa}\
+pre{background:#f6f8fa;padding:.6rem;overflow:auto;margin:.4rem 0 0}\
+.add{background:#dafbe1}.del{background:#ffebe9}";
+
+fn html(run: &Value, title: &str) -> Result<String, String> {
+    let suites = suites(run)?;
+    let all: Vec<&Case> = suites.iter().flat_map(|s| s.cases.iter()).collect();
+    let count = |status: &str| all.iter().filter(|c| c.status == status).count();
+    let mut page = String::new();
+    let _ = write!(
+        page,
+        "<!DOCTYPE html>\n<html lang=\"en\"><head><meta charset=\"utf-8\">\
+         <title>{title}</title><style>{STYLE}</style></head><body>\n\
+         <h1>{title}</h1><div class=\"meta\">{} cases in {:.2} s</div>\n\
+         <div class=\"totals\"><span class=\"passed\">{} passed</span>\
+         <span class=\"failed\">{} failed</span><span class=\"error\">{} errors</span></div>\n",
+        all.len(),
+        seconds(run, "duration_ms"),
+        count("passed"),
+        count("failed"),
+        count("error"),
+        title = escape(title),
+    );
+    for suite in &suites {
+        let _ = write!(
+            page,
+            "<h2>{}</h2>\n<table><tr><th>Status</th><th>Request</th><th>Case</th>\
+             <th>Result</th><th>Time</th></tr>\n",
+            escape(&suite.name)
+        );
+        for case in &suite.cases {
+            let mut result = escape(&case.detail).to_string();
+            if let Some(message) = &case.message {
+                let _ = write!(result, "<pre>{}</pre>", escape(message));
+            }
+            if let Some((expected, actual)) = &case.compare {
+                result.push_str("<pre>");
+                for (sign, line) in diff(expected, actual) {
+                    let class = match sign {
+                        '+' => "add",
+                        '-' => "del",
+                        _ => "",
+                    };
+                    let _ = writeln!(
+                        result,
+                        "<span class=\"{class}\">{sign} {}</span>",
+                        escape(&line)
+                    );
+                }
+                result.push_str("</pre>");
+            }
+            let status = match case.status.as_str() {
+                "passed" | "failed" | "error" => case.status.as_str(),
+                _ => "error",
+            };
+            let _ = writeln!(
+                page,
+                "<tr><td class=\"{status}\">{status}</td><td>{}</td><td>{}</td>\
+                 <td>{result}</td><td>{:.0} ms</td></tr>",
+                escape(&case.class),
+                escape(&case.name),
+                case.seconds * 1000.0,
+            );
+        }
+        page.push_str("</table>\n");
+    }
+    page.push_str("</body></html>\n");
+    Ok(page)
+}
+
+/// Renders `run` (a collection or workflow run summary) in `format`.
+pub fn render(run: &Value, format: ReportFormat, title: &str) -> Result<String, String> {
+    match format {
+        ReportFormat::Junit => junit(run, title),
+        ReportFormat::Html => html(run, title),
+    }
+}
@@ -0,0 +1,156 @@
+//! Base URL routing rules: a request URL that starts with a rule's pattern (`{{base}}`,
+//! `https://api.example.com`) has that prefix replaced by the base URL its environment maps
+//! to, with a path prefix put in front of the rest and, optionally, the port overridden. One
+//! set of requests then reaches a different host per environment without each request being
+//! edited. The rules live in the workspace's `routes.json`, next to the collections they
+//! serve; the longest enabled pattern that matches wins.
+//!
+//! A rule is applied to the URL as written, before variables resolve, so patterns can name
+//! a variable and targets can use them (`route_request`); the port is set once the URL is
+//! resolved (`Route::finish`).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const FILE: &str = "routes.json";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct RouteTarget {
+    /// Replaces the matched prefix; may hold variables.
+    pub base_url: String,
+    /// Put between the base URL and the rest of the path, e.g. `/v2`.
+    #[serde(default)]
+    pub path_prefix: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct RouteRule {
+    pub id: String,
+    pub name: String,
+    /// Matched against the start of the URL as written.
+    pub pattern: String,
+    /// By environment name. Environments without one leave matching URLs as they are.
+    pub targets: BTreeMap<String, RouteTarget>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub updated_ms: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// The rule a URL was routed by.
+#[derive(Serialize, Clone)]
+pub struct Route {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub environment: String,
+    /// The URL as written, before routing.
+    pub original_url: String,
+    /// The routed URL, before variables resolve.
+    pub url: String,
+    pub port: Option<u16>,
+}
+
+fn path(workspace: &Path) -> PathBuf {
+    workspace.join(FILE)
+}
+
+pub fn load(workspace: &Path) -> Result<Vec<RouteRule>, String> {
+    let path = path(workspace);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("routes read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("routes parse failed: {e}"))
+}
+
+pub fn save(workspace: &Path, rules: &[RouteRule]) -> Result<(), String> {
+    let payload =
+        serde_json::to_string_pretty(rules).map_err(|e| format!("routes serialize failed: {e}"))?;
+    fs::write(path(workspace), payload).map_err(|e| format!("routes persist failed: {e}"))
+}
+
+/// What follows `pattern` in `url`, when the pattern ends at a path, query or fragment
+/// boundary (so `{{base}}` doesn't match `{{base_v2}}`).
+fn rest_after<'a>(url: &'a str, pattern: &str) -> Option<&'a str> {
+    let pattern = pattern.trim().trim_end_matches('/');
+    if pattern.is_empty() {
+        return None;
+    }
+    let rest = url.trim().strip_prefix(pattern)?;
+    (rest.is_empty() || rest.starts_with(['/', '?', '#'])).then_some(rest)
+}
+
+fn join(target: &RouteTarget, rest: &str) -> String {
+    let base = target.base_url.trim().trim_end_matches('/');
+    let prefix = target.path_prefix.trim().trim_matches('/');
+    match prefix.is_empty() {
+        true => format!("{base}{rest}"),
+        false => format!("{base}/{prefix}{rest}"),
+    }
+}
+
+/// Routes `url` for `environment` by the longest enabled pattern that matches; `None` when
+/// no rule matches or the matching rule has no target for the environment.
+pub fn route(rules: &[RouteRule], url: &str, environment: &str) -> Option<Route> {
+    let (rule, rest) = rules
+        .iter()
+        .filter(|rule| rule.enabled)
+        .filter_map(|rule| rest_after(url, &rule.pattern).map(|rest| (rule, rest)))
+        .max_by_key(|(rule, _)| rule.pattern.trim().trim_end_matches('/').len())?;
+    let target = rule.targets.get(environment)?;
+    Some(Route {
+        rule_id: rule.id.clone(),
+        rule_name: rule.name.clone(),
+        environment: environment.to_string(),
+        original_url: url.to_string(),
+        url: join(target, rest),
+        port: target.port,
+    })
+}
+
+/// `url` with its port replaced; left as it is when it doesn't parse.
+pub fn with_port(url: &str, port: u16) -> String {
+    match reqwest::Url::parse(url.trim()) {
+        Ok(mut parsed) => match parsed.set_port(Some(port)) {
+            Ok(()) => parsed.to_string(),
+            Err(()) => url.to_string(),
+        },
+        Err(_) => url.to_string(),
+    }
+}
+
+impl Route {
+    /// Sets the port on a request whose URL has been resolved.
+    pub fn finish(&self, request: &mut Value) {
+        let Some(port) = self.port else {
+            return;
+        };
+        if let Some(url) = request.get("url").and_then(Value::as_str) {
+            request["url"] = Value::String(with_port(url, port));
+        }
+    }
+}
+
+/// Routes an unresolved request's URL by the workspace's rules for `environment`.
+pub fn route_request(
+    workspace: &Path,
+    environment: &str,
+    request: &mut Value,
+) -> Result<Option<Route>, String> {
+    let Some(url) = request.get("url").and_then(Value::as_str) else {
+        return Ok(None);
+    };
+    let route = route(&load(workspace)?, url, environment);
+    if let Some(route) = &route {
+        request["url"] = Value::String(route.url.clone());
+    }
+    Ok(route)
+}
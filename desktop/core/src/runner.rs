@@ -0,0 +1,557 @@
+//! The collection runner: sends every request of a collection, or a selection, through a
+//! `Sender` one at a time or several at once, reporting each result as it finishes.
+//!
+//! With a data file (a CSV with a header row, or a JSON array of objects) the requests run
+//! once per row, with the row's fields as request-layer variables. Rows run one after
+//! another; parallelism applies to the requests within a row.
+
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::json::{array_of, str_of};
+use crate::schema::Violation;
+use crate::send::{Sender, TestReport, TestResult};
+use crate::snapshot::{Snapshot, SnapshotCheck};
+
+const MAX_CONCURRENCY: usize = 16;
+
+#[derive(Deserialize, Default)]
+pub struct RunOptions {
+    /// Requests to run, in this order; every request in collection order when omitted. An id
+    /// given twice runs once, at its first place.
+    #[serde(default)]
+    pub request_ids: Option<Vec<String>>,
+    #[serde(default)]
+    pub environment_id: Option<String>,
+    /// How many requests may be in flight at once; 1 runs them in order.
+    #[serde(default)]
+    pub concurrency: Option<usize>,
+    /// Wait before each request, unless the request sets its own `delay_ms`.
+    #[serde(default)]
+    pub delay_ms: Option<u64>,
+    /// Stop starting requests once one fails or errors.
+    #[serde(default)]
+    pub stop_on_failure: bool,
+    /// A CSV or JSON file whose rows each drive one iteration.
+    #[serde(default)]
+    pub data_file: Option<String>,
+    /// An OpenAPI spec every response is checked against: an imported collection's id, a
+    /// path or a URL. The shell loads it (see `Plan::with_spec`).
+    #[serde(default)]
+    pub spec: Option<String>,
+    /// Check each response against its request's saved snapshot, where it has one. The
+    /// shell loads them (see `Plan::with_snapshots`).
+    #[serde(default)]
+    pub verify_snapshots: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RequestRun {
+    /// The data row this result belongs to, counting from 0; `None` without a data file.
+    pub iteration: Option<usize>,
+    pub index: usize,
+    pub request_id: String,
+    pub name: String,
+    /// Folder names from the collection root.
+    pub folder: Vec<String>,
+    /// `passed`, `failed` (a test failed) or `error` (the request couldn't be sent).
+    pub status: String,
+    pub status_code: Option<u64>,
+    pub duration_ms: Option<f64>,
+    pub tests: Option<TestReport>,
+    pub error: Option<String>,
+    /// Where the response broke the request's `response_schema` or the run's spec.
+    pub schema_violations: Vec<Violation>,
+    /// The check against the request's snapshot, when the run verifies snapshots and the
+    /// request has one.
+    pub snapshot: Option<SnapshotCheck>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RunSummary {
+    pub run_id: String,
+    pub collection_id: String,
+    pub total: usize,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+    /// Requests left unsent after a cancel or `stop_on_failure`.
+    pub skipped: usize,
+    pub cancelled: bool,
+    pub duration_ms: u64,
+    pub results: Vec<RequestRun>,
+    /// Per-row counts, one per data row; empty without a data file.
+    pub iterations: Vec<IterationSummary>,
+    /// Every failed or errored request of a data-driven run, keyed by its row.
+    pub failures: Vec<RowFailure>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct IterationSummary {
+    pub iteration: usize,
+    pub row: Map<String, Value>,
+    pub passed: usize,
+    pub failed: usize,
+    pub errors: usize,
+}
+
+#[derive(Serialize, Clone)]
+pub struct RowFailure {
+    pub iteration: usize,
+    pub row: Map<String, Value>,
+    pub request_id: String,
+    pub name: String,
+    pub status: String,
+    pub error: Option<String>,
+    /// Names of the failed tests.
+    pub failed_tests: Vec<String>,
+}
+
+/// A request to run, with the folders it sits in.
+#[derive(Clone)]
+struct Planned {
+    request: Value,
+    folder: Vec<String>,
+}
+
+fn flatten(items: &[Value], folder: &[String], out: &mut Vec<Planned>) {
+    for item in items {
+        match item.get("items").and_then(Value::as_array) {
+            Some(children) => {
+                let mut path = folder.to_vec();
+                path.push(str_of(item, "name").to_string());
+                flatten(children, &path, out);
+            }
+            None => out.push(Planned {
+                request: item.clone(),
+                folder: folder.to_vec(),
+            }),
+        }
+    }
+}
+
+fn plan(collection: &Value, request_ids: Option<&[String]>) -> Result<Vec<Planned>, String> {
+    let mut all = Vec::new();
+    flatten(array_of(collection, "items"), &[], &mut all);
+    let Some(ids) = request_ids else {
+        return Ok(all);
+    };
+    let mut by_id: HashMap<String, Planned> = all
+        .into_iter()
+        .map(|p| (str_of(&p.request, "id").to_string(), p))
+        .collect();
+    let mut seen = HashSet::new();
+    ids.iter()
+        .filter(|id| seen.insert(id.as_str()))
+        .map(|id| {
+            by_id
+                .remove(id)
+                .ok_or_else(|| format!("unknown request: {id}"))
+        })
+        .collect()
+}
+
+/// Reads a data file's rows. JSON must be an array of objects; CSV needs a header row.
+fn read_rows(path: &str) -> Result<Vec<Map<String, Value>>, String> {
+    let raw = std::fs::read_to_string(crate::workspace::normalize_path(path))
+        .map(|raw| raw.trim_start_matches('\u{feff}').to_string())
+        .map_err(|e| format!("data file read failed: {e}"))?;
+    let json = path.to_ascii_lowercase().ends_with(".json")
+        || (!path.to_ascii_lowercase().ends_with(".csv") && raw.trim_start().starts_with('['));
+    let rows = match json {
+        true => {
+            let data: Value =
+                serde_json::from_str(&raw).map_err(|e| format!("data file parse failed: {e}"))?;
+            let Value::Array(rows) = data else {
+                return Err("JSON data file must be an array of objects".to_string());
+            };
+            rows.into_iter()
+                .enumerate()
+                .map(|(i, row)| match row {
+                    Value::Object(row) => Ok(row),
+                    _ => Err(format!("data row {} is not an object", i + 1)),
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        false => {
+            let mut reader = csv::ReaderBuilder::new()
+                .flexible(true)
+                .trim(csv::Trim::Headers)
+                .from_reader(raw.as_bytes());
+            let headers = reader
+                .headers()
+                .map_err(|e| format!("data file parse failed: {e}"))?
+                .clone();
+            reader
+                .records()
+                .map(|record| {
+                    let record = record.map_err(|e| format!("data file parse failed: {e}"))?;
+                    Ok(headers
+                        .iter()
+                        .zip(record.iter())
+                        .filter(|(key, _)| !key.is_empty())
+                        .map(|(key, value)| (key.to_string(), Value::String(value.to_string())))
+                        .collect())
+                })
+                .collect::<Result<Vec<_>, String>>()?
+        }
+    };
+    if rows.is_empty() {
+        return Err("data file has no rows".to_string());
+    }
+    Ok(rows)
+}
+
+async fn run_one<S: Sender>(
+    sender: &S,
+    iteration: Option<usize>,
+    index: usize,
+    planned: Planned,
+    delay_ms: u64,
+    spec: Option<&Value>,
+    snapshot: Option<&Snapshot>,
+) -> RequestRun {
+    let mut run = RequestRun {
+        iteration,
+        index,
+        request_id: str_of(&planned.request, "id").to_string(),
+        name: str_of(&planned.request, "name").to_string(),
+        folder: planned.folder,
+        status: "error".to_string(),
+        status_code: None,
+        duration_ms: None,
+        tests: None,
+        error: None,
+        schema_violations: Vec::new(),
+        snapshot: None,
+    };
+    let schema = planned
+        .request
+        .get("response_schema")
+        .filter(|s| s.is_object())
+        .cloned();
+    let delay = planned
+        .request
+        .get("delay_ms")
+        .and_then(Value::as_u64)
+        .filter(|&d| d > 0)
+        .unwrap_or(delay_ms);
+    if delay > 0 {
+        tokio::time::sleep(Duration::from_millis(delay)).await;
+    }
+    let sent = sender.send(planned.request).await;
+    let sent = match sent {
+        Ok(sent) => sent,
+        Err(e) => {
+            run.error = Some(e);
+            return run;
+        }
+    };
+    run.status_code = sent.result.get("status_code").and_then(Value::as_u64);
+    run.duration_ms = sent.result.get("duration_ms").and_then(Value::as_f64);
+    run.error = sent
+        .result
+        .get("error")
+        .and_then(Value::as_str)
+        .filter(|e| !e.is_empty())
+        .map(str::to_string);
+    let mut tests = sent.tests;
+    if run.error.is_none() && (schema.is_some() || spec.is_some()) {
+        run.schema_violations = violations(&sent.result, schema.as_ref(), spec);
+        add_check(
+            &mut tests,
+            "Response matches schema",
+            run.schema_violations.first().map(Violation::summary),
+        );
+    }
+    if let Some(snapshot) = snapshot.filter(|_| run.error.is_none()) {
+        let check = snapshot.verify(&sent.result);
+        add_check(&mut tests, "Response matches snapshot", check.summary());
+        run.snapshot = Some(check);
+    }
+    let tests_failed = tests
+        .as_ref()
+        .is_some_and(|t| t.failed > 0 || t.error.is_some());
+    run.status = match (&run.error, tests_failed) {
+        (Some(_), _) => "error",
+        (None, true) => "failed",
+        (None, false) => "passed",
+    }
+    .to_string();
+    run.tests = tests;
+    run
+}
+
+/// Adds a check the runner made itself to the request's test report; `failure` is `None`
+/// when it passed.
+fn add_check(tests: &mut Option<TestReport>, name: &str, failure: Option<String>) {
+    let report = tests.get_or_insert_with(|| TestReport {
+        passed: 0,
+        failed: 0,
+        results: Vec::new(),
+        error: None,
+    });
+    match failure.is_none() {
+        true => report.passed += 1,
+        false => report.failed += 1,
+    }
+    report.results.push(TestResult {
+        name: name.to_string(),
+        passed: failure.is_none(),
+        error: failure,
+    });
+}
+
+/// Checks a response against the request's own schema and the run's spec, matching the
+/// spec's operation by what was actually sent.
+fn violations(result: &Value, schema: Option<&Value>, spec: Option<&Value>) -> Vec<Violation> {
+    let body = crate::schema::body_of(result);
+    let mut found = Vec::new();
+    if let Some(schema) = schema {
+        found.extend(crate::schema::validate(schema, &body));
+    }
+    if let Some(spec) = spec {
+        let sent = result.get("sent_request").unwrap_or(&Value::Null);
+        let status = result
+            .get("status_code")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        let content_type = result
+            .get("content_type")
+            .and_then(Value::as_str)
+            .filter(|ct| !ct.is_empty());
+        found.extend(crate::schema::check_response(
+            spec,
+            str_of(sent, "method"),
+            str_of(sent, "url"),
+            status as u16,
+            content_type,
+            &body,
+        ));
+    }
+    found
+}
+
+/// A run in progress, shared by its iterations.
+struct Execution<'a, S, F> {
+    sender: &'a S,
+    options: &'a RunOptions,
+    spec: Option<&'a Value>,
+    snapshots: &'a HashMap<String, Snapshot>,
+    cancel: &'a AtomicBool,
+    /// Set by `stop_on_failure`.
+    stopped: AtomicBool,
+    completed: usize,
+    on_result: F,
+}
+
+impl<S: Sender, F: FnMut(usize, &RequestRun)> Execution<'_, S, F> {
+    /// Sends `planned` with up to `concurrency` in flight, reporting each as it finishes,
+    /// with `row` on every request's variable layer. Stops starting new requests once the
+    /// run is cancelled or, with `stop_on_failure`, one fails.
+    async fn execute(
+        &mut self,
+        planned: &[Planned],
+        row: Option<(usize, &Map<String, Value>)>,
+    ) -> Vec<RequestRun> {
+        let concurrency = self
+            .options
+            .concurrency
+            .unwrap_or(1)
+            .clamp(1, MAX_CONCURRENCY);
+        let delay_ms = self.options.delay_ms.unwrap_or(0);
+        let (sender, spec, snapshots) = (self.sender, self.spec, self.snapshots);
+        let (cancel, stopped) = (self.cancel, &self.stopped);
+        let iteration = row.map(|(iteration, _)| iteration);
+        let jobs = planned
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(index, mut planned)| {
+                if let Some((_, row)) = row {
+                    if !planned
+                        .request
+                        .get("variables")
+                        .is_some_and(Value::is_object)
+                    {
+                        planned.request["variables"] = Value::Object(Map::new());
+                    }
+                    for (key, value) in row {
+                        planned.request["variables"][key] = value.clone();
+                    }
+                }
+                async move {
+                    // Checked when the request's turn comes, so a stop skips everything queued.
+                    if cancel.load(Ordering::Relaxed) || stopped.load(Ordering::Relaxed) {
+                        return None;
+                    }
+                    let snapshot = snapshots.get(str_of(&planned.request, "id"));
+                    Some(run_one(sender, iteration, index, planned, delay_ms, spec, snapshot).await)
+                }
+            });
+        let mut results = Vec::new();
+        let mut stream = futures_util::stream::iter(jobs).buffer_unordered(concurrency);
+        while let Some(result) = stream.next().await {
+            let Some(result) = result else {
+                continue;
+            };
+            if self.options.stop_on_failure && result.status != "passed" {
+                self.stopped.store(true, Ordering::Relaxed);
+            }
+            self.completed += 1;
+            (self.on_result)(self.completed, &result);
+            results.push(result);
+        }
+        results.sort_by_key(|r| r.index);
+        results
+    }
+}
+
+fn failure(iteration: usize, row: &Map<String, Value>, result: &RequestRun) -> RowFailure {
+    RowFailure {
+        iteration,
+        row: row.clone(),
+        request_id: result.request_id.clone(),
+        name: result.name.clone(),
+        status: result.status.clone(),
+        error: result.error.clone(),
+        failed_tests: result
+            .tests
+            .iter()
+            .flat_map(|t| t.results.iter())
+            .filter(|t| !t.passed)
+            .map(|t| t.name.clone())
+            .collect(),
+    }
+}
+
+/// A run ready to start: the requests in order and the data rows, if any.
+pub struct Plan {
+    planned: Vec<Planned>,
+    rows: Option<Vec<Map<String, Value>>>,
+    spec: Option<Value>,
+    snapshots: HashMap<String, Snapshot>,
+}
+
+impl Plan {
+    /// Picks the requests `options` selects and reads its data file.
+    pub fn new(collection: &Value, options: &RunOptions) -> Result<Self, String> {
+        let planned = plan(collection, options.request_ids.as_deref())?;
+        if planned.is_empty() {
+            return Err("collection has no requests to run".to_string());
+        }
+        let rows = match options
+            .data_file
+            .as_deref()
+            .filter(|p| !p.trim().is_empty())
+        {
+            Some(path) => Some(read_rows(path)?),
+            None => None,
+        };
+        Ok(Self {
+            planned,
+            rows,
+            spec: None,
+            snapshots: HashMap::new(),
+        })
+    }
+
+    /// Checks every response against `spec`, the document `RunOptions::spec` names.
+    pub fn with_spec(mut self, spec: Value) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    /// Checks each response against its request's snapshot, by request id, as
+    /// `RunOptions::verify_snapshots` asks.
+    pub fn with_snapshots(mut self, snapshots: HashMap<String, Snapshot>) -> Self {
+        self.snapshots = snapshots;
+        self
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.rows.as_ref().map_or(1, Vec::len)
+    }
+
+    /// Requests across every iteration.
+    pub fn total(&self) -> usize {
+        self.planned.len() * self.iterations()
+    }
+}
+
+/// Runs `plan` through `sender`, calling `on_result` with the completed count as each
+/// request finishes. Setting `cancel` stops further requests from starting.
+pub async fn execute<S: Sender>(
+    run_id: String,
+    collection_id: String,
+    plan: &Plan,
+    options: &RunOptions,
+    sender: &S,
+    cancel: &AtomicBool,
+    on_result: impl FnMut(usize, &RequestRun),
+) -> RunSummary {
+    let started = Instant::now();
+    let total = plan.total();
+    let mut execution = Execution {
+        sender,
+        options,
+        spec: plan.spec.as_ref(),
+        snapshots: &plan.snapshots,
+        cancel,
+        stopped: AtomicBool::new(false),
+        completed: 0,
+        on_result,
+    };
+    let mut results = Vec::new();
+    let mut iteration_summaries = Vec::new();
+    let mut failures = Vec::new();
+    match &plan.rows {
+        None => results = execution.execute(&plan.planned, None).await,
+        Some(rows) => {
+            for (iteration, row) in rows.iter().enumerate() {
+                if cancel.load(Ordering::Relaxed) || execution.stopped.load(Ordering::Relaxed) {
+                    break;
+                }
+                let row_results = execution
+                    .execute(&plan.planned, Some((iteration, row)))
+                    .await;
+                let count =
+                    |status: &str| row_results.iter().filter(|r| r.status == status).count();
+                iteration_summaries.push(IterationSummary {
+                    iteration,
+                    row: row.clone(),
+                    passed: count("passed"),
+                    failed: count("failed"),
+                    errors: count("error"),
+                });
+                failures.extend(
+                    row_results
+                        .iter()
+                        .filter(|r| r.status != "passed")
+                        .map(|r| failure(iteration, row, r)),
+                );
+                results.extend(row_results);
+            }
+        }
+    }
+
+    let count = |status: &str| results.iter().filter(|r| r.status == status).count();
+    RunSummary {
+        run_id,
+        collection_id,
+        total,
+        passed: count("passed"),
+        failed: count("failed"),
+        errors: count("error"),
+        skipped: total - results.len(),
+        cancelled: cancel.load(Ordering::Relaxed),
+        duration_ms: started.elapsed().as_millis() as u64,
+        results,
+        iterations: iteration_summaries,
+        failures,
+    }
+}
@@ -0,0 +1,687 @@
+//! Response validation against a JSON Schema, or against the response an OpenAPI spec
+//! documents for the operation. Violations carry the JSONPath of the offending value, what
+//! the schema expected and what the response had.
+//!
+//! The common draft 7 / 2020-12 keywords are checked, OpenAPI's `nullable` included; local
+//! `$ref`s resolve against the schema itself or the spec it came from.
+
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::json::str_of;
+
+/// Schemas nest (and recurse) arbitrarily; validation stops following them at this depth.
+const MAX_DEPTH: usize = 64;
+/// Checking stops after this many violations.
+const MAX_VIOLATIONS: usize = 100;
+
+#[derive(Serialize, Clone)]
+pub struct Violation {
+    /// Where in the body, e.g. `$.items[2].id`; empty for the status code or content type.
+    pub path: String,
+    /// The schema keyword that failed, or `operation`, `status` and `content-type` for
+    /// checks against a spec.
+    pub keyword: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+impl Violation {
+    /// One line, for test results and reports.
+    pub fn summary(&self) -> String {
+        let at = if self.path.is_empty() { "" } else { " at " };
+        format!(
+            "{}{at}{}: expected {}, got {}",
+            self.keyword, self.path, self.expected, self.actual
+        )
+    }
+}
+
+fn kind_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(n) if n.as_f64().is_some_and(|f| f.fract() == 0.0) => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// A short rendering of a value for `actual`.
+fn shown(value: &Value) -> String {
+    let text = value.to_string();
+    match text.chars().count() > 80 {
+        true => format!("{}…", text.chars().take(80).collect::<String>()),
+        false => text,
+    }
+}
+
+pub(crate) fn child_path(path: &str, key: &str) -> String {
+    let plain = !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    match plain {
+        true => format!("{path}.{key}"),
+        false => format!("{path}['{}']", key.replace('\'', "\\'")),
+    }
+}
+
+fn format_ok(format: &str, text: &str) -> bool {
+    let pattern = match format {
+        "date-time" => r"^\d{4}-\d{2}-\d{2}[Tt ]\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:?\d{2})$",
+        "date" => r"^\d{4}-\d{2}-\d{2}$",
+        "time" => r"^\d{2}:\d{2}:\d{2}(\.\d+)?([Zz]|[+-]\d{2}:?\d{2})?$",
+        "email" => r"^[^@\s]+@[^@\s]+\.[^@\s]+$",
+        "uuid" => r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
+        "uri" | "url" => r"^[a-zA-Z][a-zA-Z0-9+.-]*:\S+$",
+        "ipv4" => r"^((25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)$",
+        "ipv6" => return text.parse::<std::net::Ipv6Addr>().is_ok(),
+        // Unknown formats are annotations only.
+        _ => return true,
+    };
+    Regex::new(pattern).is_ok_and(|re| re.is_match(text))
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    violations: Vec<Violation>,
+}
+
+impl<'a> Validator<'a> {
+    fn resolve(&self, mut schema: &'a Value) -> &'a Value {
+        for _ in 0..16 {
+            let Some(pointer) = schema
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix('#'))
+            else {
+                return schema;
+            };
+            match self.root.pointer(pointer) {
+                Some(target) => schema = target,
+                None => return schema,
+            }
+        }
+        schema
+    }
+
+    fn fail(&mut self, path: &str, keyword: &str, expected: String, actual: String) {
+        if self.violations.len() < MAX_VIOLATIONS {
+            self.violations.push(Violation {
+                path: path.to_string(),
+                keyword: keyword.to_string(),
+                expected,
+                actual,
+            });
+        }
+    }
+
+    /// Whether `value` matches `schema`, without recording anything.
+    fn passes(&self, schema: &'a Value, value: &Value, depth: usize) -> bool {
+        let mut trial = Validator {
+            root: self.root,
+            violations: Vec::new(),
+        };
+        trial.check(schema, value, "$", depth);
+        trial.violations.is_empty()
+    }
+
+    fn check(&mut self, schema: &'a Value, value: &Value, path: &str, depth: usize) {
+        if depth >= MAX_DEPTH || self.violations.len() >= MAX_VIOLATIONS {
+            return;
+        }
+        let schema = self.resolve(schema);
+        let rules = match schema {
+            Value::Bool(true) => return,
+            Value::Bool(false) => {
+                return self.fail(path, "false", "nothing".into(), shown(value));
+            }
+            Value::Object(rules) => rules,
+            _ => return,
+        };
+        if value.is_null() && schema.get("nullable").and_then(Value::as_bool) == Some(true) {
+            return;
+        }
+
+        if let Some(wanted) = rules.get("type") {
+            let allowed: Vec<&str> = match wanted {
+                Value::String(kind) => vec![kind.as_str()],
+                Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            let actual = kind_of(value);
+            let ok = allowed
+                .iter()
+                .any(|&kind| kind == actual || (kind == "number" && actual == "integer"));
+            if !allowed.is_empty() && !ok {
+                // The other keywords assume the right type; stop at the first thing wrong.
+                return self.fail(path, "type", allowed.join(" or "), actual.to_string());
+            }
+        }
+        if let Some(expected) = rules.get("const") {
+            if expected != value {
+                self.fail(path, "const", shown(expected), shown(value));
+            }
+        }
+        if let Some(choices) = rules.get("enum").and_then(Value::as_array) {
+            if !choices.contains(value) {
+                let expected = choices.iter().map(shown).collect::<Vec<_>>().join(", ");
+                self.fail(path, "enum", format!("one of {expected}"), shown(value));
+            }
+        }
+
+        match value {
+            Value::String(text) => self.check_string(rules, text, path),
+            Value::Number(_) => self.check_number(rules, value, path),
+            Value::Array(items) => self.check_array(rules, items, path, depth),
+            Value::Object(fields) => self.check_object(rules, fields, path, depth),
+            _ => {}
+        }
+
+        if let Some(parts) = rules.get("allOf").and_then(Value::as_array) {
+            for part in parts {
+                self.check(part, value, path, depth + 1);
+            }
+        }
+        if let Some(options) = rules.get("anyOf").and_then(Value::as_array) {
+            if !options.iter().any(|o| self.passes(o, value, depth + 1)) {
+                self.fail(
+                    path,
+                    "anyOf",
+                    "a match for one of the schemas".into(),
+                    shown(value),
+                );
+            }
+        }
+        if let Some(options) = rules.get("oneOf").and_then(Value::as_array) {
+            let matching = options
+                .iter()
+                .filter(|o| self.passes(o, value, depth + 1))
+                .count();
+            if matching != 1 {
+                self.fail(
+                    path,
+                    "oneOf",
+                    "a match for exactly one schema".into(),
+                    format!("{matching} matches"),
+                );
+            }
+        }
+        if let Some(excluded) = rules.get("not") {
+            if self.passes(excluded, value, depth + 1) {
+                self.fail(path, "not", "no match for the schema".into(), shown(value));
+            }
+        }
+    }
+
+    fn check_string(&mut self, rules: &serde_json::Map<String, Value>, text: &str, path: &str) {
+        let length = text.chars().count() as u64;
+        if let Some(min) = rules.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                self.fail(
+                    path,
+                    "minLength",
+                    format!("at least {min} characters"),
+                    length.to_string(),
+                );
+            }
+        }
+        if let Some(max) = rules.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                self.fail(
+                    path,
+                    "maxLength",
+                    format!("at most {max} characters"),
+                    length.to_string(),
+                );
+            }
+        }
+        if let Some(pattern) = rules.get("pattern").and_then(Value::as_str) {
+            if Regex::new(pattern).is_ok_and(|re| !re.is_match(text)) {
+                self.fail(
+                    path,
+                    "pattern",
+                    pattern.to_string(),
+                    shown(&Value::from(text)),
+                );
+            }
+        }
+        if let Some(format) = rules.get("format").and_then(Value::as_str) {
+            if !format_ok(format, text) {
+                self.fail(
+                    path,
+                    "format",
+                    format.to_string(),
+                    shown(&Value::from(text)),
+                );
+            }
+        }
+    }
+
+    fn check_number(&mut self, rules: &serde_json::Map<String, Value>, value: &Value, path: &str) {
+        let Some(number) = value.as_f64() else {
+            return;
+        };
+        let bound = |key: &str| rules.get(key).and_then(Value::as_f64);
+        // 3.0 marks exclusive bounds with a boolean next to `minimum`/`maximum`.
+        let flag = |key: &str| rules.get(key).and_then(Value::as_bool) == Some(true);
+        if let Some(min) = bound("minimum") {
+            if number < min || (flag("exclusiveMinimum") && number == min) {
+                self.fail(path, "minimum", format!("at least {min}"), shown(value));
+            }
+        }
+        if let Some(max) = bound("maximum") {
+            if number > max || (flag("exclusiveMaximum") && number == max) {
+                self.fail(path, "maximum", format!("at most {max}"), shown(value));
+            }
+        }
+        if let Some(min) = bound("exclusiveMinimum") {
+            if number <= min {
+                self.fail(
+                    path,
+                    "exclusiveMinimum",
+                    format!("more than {min}"),
+                    shown(value),
+                );
+            }
+        }
+        if let Some(max) = bound("exclusiveMaximum") {
+            if number >= max {
+                self.fail(
+                    path,
+                    "exclusiveMaximum",
+                    format!("less than {max}"),
+                    shown(value),
+                );
+            }
+        }
+        if let Some(step) = bound("multipleOf").filter(|s| *s > 0.0) {
+            let ratio = number / step;
+            if (ratio - ratio.round()).abs() > 1e-9 {
+                self.fail(
+                    path,
+                    "multipleOf",
+                    format!("a multiple of {step}"),
+                    shown(value),
+                );
+            }
+        }
+    }
+
+    fn check_array(
+        &mut self,
+        rules: &'a serde_json::Map<String, Value>,
+        items: &[Value],
+        path: &str,
+        depth: usize,
+    ) {
+        let count = items.len() as u64;
+        if let Some(min) = rules.get("minItems").and_then(Value::as_u64) {
+            if count < min {
+                self.fail(
+                    path,
+                    "minItems",
+                    format!("at least {min} items"),
+                    count.to_string(),
+                );
+            }
+        }
+        if let Some(max) = rules.get("maxItems").and_then(Value::as_u64) {
+            if count > max {
+                self.fail(
+                    path,
+                    "maxItems",
+                    format!("at most {max} items"),
+                    count.to_string(),
+                );
+            }
+        }
+        if rules.get("uniqueItems").and_then(Value::as_bool) == Some(true) {
+            let duplicate = items
+                .iter()
+                .enumerate()
+                .find(|(i, item)| items[..*i].contains(item));
+            if let Some((_, item)) = duplicate {
+                self.fail(
+                    path,
+                    "uniqueItems",
+                    "unique items".into(),
+                    format!("{} twice", shown(item)),
+                );
+            }
+        }
+        // 2020-12 `prefixItems`, or draft 7 tuple-form `items`, then `items` for the rest.
+        let prefix = rules
+            .get("prefixItems")
+            .or_else(|| rules.get("items").filter(|i| i.is_array()))
+            .and_then(Value::as_array);
+        let rest = match prefix {
+            Some(_) if rules.get("prefixItems").is_some() => rules.get("items"),
+            Some(_) => rules.get("additionalItems"),
+            None => rules.get("items"),
+        };
+        let prefix = prefix.map(Vec::as_slice).unwrap_or_default();
+        for (i, item) in items.iter().enumerate() {
+            let schema = prefix.get(i).or(rest);
+            if let Some(schema) = schema {
+                self.check(schema, item, &format!("{path}[{i}]"), depth + 1);
+            }
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        rules: &'a serde_json::Map<String, Value>,
+        fields: &serde_json::Map<String, Value>,
+        path: &str,
+        depth: usize,
+    ) {
+        for name in rules
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !fields.contains_key(name) {
+                self.fail(
+                    &child_path(path, name),
+                    "required",
+                    "a value".into(),
+                    "missing".into(),
+                );
+            }
+        }
+        let count = fields.len() as u64;
+        if let Some(min) = rules.get("minProperties").and_then(Value::as_u64) {
+            if count < min {
+                self.fail(
+                    path,
+                    "minProperties",
+                    format!("at least {min} properties"),
+                    count.to_string(),
+                );
+            }
+        }
+        if let Some(max) = rules.get("maxProperties").and_then(Value::as_u64) {
+            if count > max {
+                self.fail(
+                    path,
+                    "maxProperties",
+                    format!("at most {max} properties"),
+                    count.to_string(),
+                );
+            }
+        }
+        let properties = rules.get("properties").and_then(Value::as_object);
+        let patterns: Vec<(Regex, &Value)> = rules
+            .get("patternProperties")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter_map(|(pattern, schema)| Some((Regex::new(pattern).ok()?, schema)))
+            .collect();
+        for (name, value) in fields {
+            let at = child_path(path, name);
+            let mut known = false;
+            if let Some(schema) = properties.and_then(|p| p.get(name)) {
+                known = true;
+                self.check(schema, value, &at, depth + 1);
+            }
+            for (pattern, schema) in &patterns {
+                if pattern.is_match(name) {
+                    known = true;
+                    self.check(schema, value, &at, depth + 1);
+                }
+            }
+            if known {
+                continue;
+            }
+            match rules.get("additionalProperties") {
+                Some(Value::Bool(false)) => {
+                    self.fail(
+                        &at,
+                        "additionalProperties",
+                        "no such property".into(),
+                        shown(value),
+                    );
+                }
+                Some(schema @ Value::Object(_)) => self.check(schema, value, &at, depth + 1),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Checks `body` against a standalone JSON Schema.
+pub fn validate(schema: &Value, body: &Value) -> Vec<Violation> {
+    validate_in(schema, schema, body)
+}
+
+/// Checks `body` against `schema`, resolving `$ref`s against `root` (e.g. an OpenAPI spec).
+pub fn validate_in(root: &Value, schema: &Value, body: &Value) -> Vec<Violation> {
+    let mut validator = Validator {
+        root,
+        violations: Vec::new(),
+    };
+    validator.check(schema, body, "$", 0);
+    validator.violations
+}
+
+/// The path part of a URL, without scheme, host, query or fragment. A leading variable
+/// (`{{baseUrl}}/users`) stands for the scheme and host.
+pub fn path_of(url: &str) -> &str {
+    let url = url.trim();
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    if let Some(rest) = url.strip_prefix("{{") {
+        return rest.split_once("}}").map_or(url, |(_, path)| path);
+    }
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|i| &rest[i..]).unwrap_or("/"),
+        None => url,
+    }
+}
+
+fn segments(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// How well a request path matches a spec path template, or `None`.
+fn path_score(template: &[&str], actual: &[&str]) -> Option<usize> {
+    if template.len() != actual.len() {
+        return None;
+    }
+    let mut score = 0;
+    for (segment, actual) in template.iter().zip(actual) {
+        if segment.starts_with('{') && segment.ends_with('}') {
+            continue;
+        }
+        if segment != actual {
+            return None;
+        }
+        score += 1;
+    }
+    Some(score)
+}
+
+/// Path prefixes the spec's servers add (`/v1` of `https://api.example.com/v1`), longest
+/// first, ending with no prefix.
+fn server_prefixes(doc: &Value) -> Vec<String> {
+    let mut prefixes: Vec<String> = match str_of(doc, "swagger").starts_with("2.") {
+        true => vec![str_of(doc, "basePath").to_string()],
+        false => doc
+            .get("servers")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .map(|server| path_of(str_of(server, "url")).to_string())
+            .collect(),
+    };
+    prefixes.retain(|p| !p.trim_matches('/').is_empty());
+    prefixes.sort_by_key(|p| std::cmp::Reverse(p.len()));
+    prefixes.push(String::new());
+    prefixes
+}
+
+/// The operation of `doc` that `method` and `url` address, with its path template.
+pub fn find_operation<'a>(doc: &'a Value, method: &str, url: &str) -> Option<(&'a str, &'a Value)> {
+    let path = path_of(url);
+    let paths = doc.get("paths").and_then(Value::as_object)?;
+    let method = method.to_lowercase();
+    for prefix in server_prefixes(doc) {
+        let prefix = prefix.trim_end_matches('/');
+        let Some(rest) = path.strip_prefix(prefix) else {
+            continue;
+        };
+        let actual = segments(rest);
+        let best = paths
+            .iter()
+            .filter_map(|(template, item)| {
+                let operation = resolve(doc, item).get(&method)?;
+                let score = path_score(&segments(template), &actual)?;
+                Some((score, template.as_str(), operation))
+            })
+            .max_by_key(|(score, _, _)| *score);
+        if let Some((_, template, operation)) = best {
+            return Some((template, operation));
+        }
+    }
+    None
+}
+
+/// The documented response for `status`: the exact code, its `4XX`-style range, or
+/// `default`.
+pub fn response_for(operation: &Value, status: u16) -> Option<&Value> {
+    let responses = operation.get("responses")?;
+    let code = status.to_string();
+    responses
+        .get(&code)
+        .or_else(|| responses.get(format!("{}XX", &code[..1])))
+        .or_else(|| responses.get(format!("{}xx", &code[..1])))
+        .or_else(|| responses.get("default"))
+}
+
+fn resolve<'a>(doc: &'a Value, mut value: &'a Value) -> &'a Value {
+    for _ in 0..16 {
+        match value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+            .and_then(|pointer| doc.pointer(pointer))
+        {
+            Some(target) => value = target,
+            None => return value,
+        }
+    }
+    value
+}
+
+/// Checks a response against what `doc` documents for the operation `method` and `url`
+/// address: that the status code is documented, that the content type is one of the
+/// documented media types and that the body matches that media type's schema.
+pub fn check_response(
+    doc: &Value,
+    method: &str,
+    url: &str,
+    status: u16,
+    content_type: Option<&str>,
+    body: &Value,
+) -> Vec<Violation> {
+    match find_operation(doc, method, url) {
+        Some((_, operation)) => check_operation(doc, operation, status, content_type, body),
+        None => vec![Violation {
+            path: String::new(),
+            keyword: "operation".to_string(),
+            expected: "an operation documented by the spec".to_string(),
+            actual: format!("{} {}", method.to_uppercase(), path_of(url)),
+        }],
+    }
+}
+
+/// Checks a response against what `operation` of `doc` documents, as `check_response` does.
+pub fn check_operation(
+    doc: &Value,
+    operation: &Value,
+    status: u16,
+    content_type: Option<&str>,
+    body: &Value,
+) -> Vec<Violation> {
+    let violation = |keyword: &str, expected: String, actual: String| Violation {
+        path: String::new(),
+        keyword: keyword.to_string(),
+        expected,
+        actual,
+    };
+    let operation = resolve(doc, operation);
+    let Some(response) = response_for(operation, status).map(|r| resolve(doc, r)) else {
+        let documented: Vec<&String> = operation
+            .get("responses")
+            .and_then(Value::as_object)
+            .map(|r| r.keys().collect())
+            .unwrap_or_default();
+        let documented = documented
+            .iter()
+            .map(|c| c.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return vec![violation(
+            "status",
+            format!("one of {documented}"),
+            status.to_string(),
+        )];
+    };
+    let mime = content_type
+        .and_then(|ct| ct.split(';').next())
+        .map(|ct| ct.trim().to_lowercase())
+        .unwrap_or_default();
+
+    // 2.0 has one schema for every media type the operation `produces`.
+    if str_of(doc, "swagger").starts_with("2.") {
+        return match response.get("schema") {
+            Some(schema) => validate_in(doc, schema, body),
+            None => Vec::new(),
+        };
+    }
+    let Some(content) = response.get("content").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let wildcard = |pattern: &str| {
+        pattern == "*/*"
+            || pattern
+                .strip_suffix("/*")
+                .is_some_and(|kind| mime.starts_with(&format!("{kind}/")))
+    };
+    let media = content
+        .get(&mime)
+        .or_else(|| content.iter().find(|(p, _)| wildcard(p)).map(|(_, m)| m));
+    let Some(media) = media else {
+        let documented = content.keys().cloned().collect::<Vec<_>>().join(", ");
+        let actual = if mime.is_empty() {
+            "none".to_string()
+        } else {
+            mime
+        };
+        return vec![violation(
+            "content-type",
+            format!("one of {documented}"),
+            actual,
+        )];
+    };
+    match media.get("schema") {
+        Some(schema) => validate_in(doc, schema, body),
+        None => Vec::new(),
+    }
+}
+
+/// The response body as JSON: parsed when the backend kept it as text.
+pub fn body_of(result: &Value) -> Value {
+    match result.get("body") {
+        Some(Value::String(text)) => {
+            serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.clone()))
+        }
+        Some(other) => other.clone(),
+        None => Value::Null,
+    }
+}
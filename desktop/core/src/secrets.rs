@@ -0,0 +1,82 @@
+//! Environment secrets held in the OS keychain (Keychain, Credential Manager, Secret Service)
+//! instead of the workspace files. The environment keeps the key with an empty value and its
+//! `secrets` flag set; the value is looked up here by collection, environment and key.
+
+use serde_json::Value;
+
+fn entry(collection_id: &str, env_name: &str, key: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(
+        &crate::profile::keychain_service(),
+        &format!("{collection_id}/{env_name}/{key}"),
+    )
+    .map_err(|e| format!("keychain unavailable: {e}"))
+}
+
+pub fn store(collection_id: &str, env_name: &str, key: &str, value: &str) -> Result<(), String> {
+    crate::redact::remember(value);
+    entry(collection_id, env_name, key)?
+        .set_password(value)
+        .map_err(|e| format!("keychain write failed: {e}"))
+}
+
+pub fn load(collection_id: &str, env_name: &str, key: &str) -> Result<Option<String>, String> {
+    match entry(collection_id, env_name, key)?.get_password() {
+        Ok(value) => {
+            crate::redact::remember(&value);
+            Ok(Some(value))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("keychain read failed: {e}")),
+    }
+}
+
+pub fn remove(collection_id: &str, env_name: &str, key: &str) -> Result<(), String> {
+    match entry(collection_id, env_name, key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("keychain delete failed: {e}")),
+    }
+}
+
+pub struct ResolvedVariable {
+    pub key: String,
+    pub value: String,
+    pub secret: bool,
+}
+
+/// An environment's variables from a fetched environment file, with secret values read from
+/// the keychain. A secret without a stored value falls back to its workspace value.
+pub fn resolve_environment(
+    collection_id: &str,
+    environment: &Value,
+    env_name: &str,
+) -> Result<Vec<ResolvedVariable>, String> {
+    let env = environment
+        .get("envs")
+        .and_then(|envs| envs.get(env_name))
+        .cloned()
+        .unwrap_or(Value::Null);
+    let mut resolved = Vec::new();
+    for (key, value) in env
+        .get("variables")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let plain = match value {
+            Value::String(text) => text.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        };
+        let secret = env.get("secrets").and_then(|s| s.get(key)) == Some(&Value::Bool(true));
+        let value = match secret {
+            true => load(collection_id, env_name, key)?.unwrap_or(plain),
+            false => plain,
+        };
+        resolved.push(ResolvedVariable {
+            key: key.clone(),
+            value,
+            secret,
+        });
+    }
+    Ok(resolved)
+}
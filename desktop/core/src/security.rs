@@ -0,0 +1,470 @@
+//! Security header audit: each response is graded on the headers that protect its clients
+//! (HSTS, a content security policy, `X-Content-Type-Options`, framing, no-store caching of
+//! responses to authenticated requests, no version banners), and each host on its TLS
+//! parameters too. Run over a collection, the grades are rolled up per host, where a check
+//! counts as bad as its worst response.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::json::{array_of, str_of};
+use crate::send::Sender;
+
+/// HSTS `max-age` below this (180 days) is too short to protect returning visitors.
+const MIN_HSTS_SECS: u64 = 180 * 24 * 60 * 60;
+/// Points taken off 100 per warning and per failure.
+const WARN_COST: u32 = 5;
+const FAIL_COST: u32 = 15;
+/// Request headers that mark the response as someone's own data.
+const CREDENTIAL_HEADERS: &[&str] = &["authorization", "cookie", "x-api-key", "api-key"];
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Pass,
+    Info,
+    Warn,
+    Fail,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Finding {
+    /// `https`, `hsts`, `csp`, `content_type_options`, `framing`, `cache_control`,
+    /// `disclosure`, `tls_version`, `certificate` or `reachability`.
+    pub check: String,
+    pub severity: Severity,
+    pub message: String,
+}
+
+/// What the handshake with a host negotiated, as `probe_endpoint` reports it.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct TlsParams {
+    pub version: Option<String>,
+    pub cipher_suite: Option<String>,
+    pub certificate_trusted: bool,
+    pub certificate_error: Option<String>,
+    /// Why there are no parameters, e.g. a failed handshake.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ResponseAudit {
+    pub request_id: String,
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub host: String,
+    pub status_code: Option<u64>,
+    pub score: u32,
+    pub grade: char,
+    pub findings: Vec<Finding>,
+    /// Set when the request got no response to grade.
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HostReport {
+    pub host: String,
+    pub score: u32,
+    pub grade: char,
+    pub tls: Option<TlsParams>,
+    /// One per check, the worst any response of the host got, then the TLS findings.
+    pub findings: Vec<Finding>,
+    pub responses: Vec<ResponseAudit>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SecurityReport {
+    pub run_id: String,
+    pub collection_id: String,
+    pub started_ms: u64,
+    pub cancelled: bool,
+    pub hosts: Vec<HostReport>,
+}
+
+fn finding(check: &str, severity: Severity, message: impl Into<String>) -> Finding {
+    Finding {
+        check: check.to_string(),
+        severity,
+        message: message.into(),
+    }
+}
+
+/// A header of a backend result or request, matched without case.
+fn header<'a>(headers: Option<&'a Value>, name: &str) -> Option<&'a str> {
+    headers?
+        .as_object()?
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .and_then(|(_, v)| v.as_str())
+}
+
+pub fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// 100 less a cost per warning and failure, and the letter for it.
+pub fn score(findings: &[Finding]) -> (u32, char) {
+    let lost: u32 = findings
+        .iter()
+        .map(|f| match f.severity {
+            Severity::Warn => WARN_COST,
+            Severity::Fail => FAIL_COST,
+            _ => 0,
+        })
+        .sum();
+    let score = 100u32.saturating_sub(lost);
+    let grade = match score {
+        90.. => 'A',
+        80..=89 => 'B',
+        70..=79 => 'C',
+        60..=69 => 'D',
+        _ => 'F',
+    };
+    (score, grade)
+}
+
+fn hsts(value: Option<&str>) -> Finding {
+    let Some(value) = value else {
+        return finding(
+            "hsts",
+            Severity::Fail,
+            "no Strict-Transport-Security header",
+        );
+    };
+    let directives: Vec<String> = value
+        .split(';')
+        .map(|d| d.trim().to_ascii_lowercase())
+        .collect();
+    let max_age = directives
+        .iter()
+        .find_map(|d| d.strip_prefix("max-age="))
+        .and_then(|v| v.trim_matches('"').parse::<u64>().ok());
+    match max_age {
+        None => finding(
+            "hsts",
+            Severity::Fail,
+            "Strict-Transport-Security has no max-age",
+        ),
+        Some(0) => finding(
+            "hsts",
+            Severity::Fail,
+            "Strict-Transport-Security is switched off (max-age=0)",
+        ),
+        Some(secs) if secs < MIN_HSTS_SECS => finding(
+            "hsts",
+            Severity::Warn,
+            format!(
+                "Strict-Transport-Security max-age is {secs}s; 180 days or more is recommended"
+            ),
+        ),
+        Some(_) if !directives.iter().any(|d| d == "includesubdomains") => finding(
+            "hsts",
+            Severity::Info,
+            "Strict-Transport-Security doesn't cover subdomains (includeSubDomains)",
+        ),
+        Some(_) => finding("hsts", Severity::Pass, "Strict-Transport-Security is set"),
+    }
+}
+
+fn csp(value: Option<&str>, html: bool) -> Finding {
+    let Some(policy) = value else {
+        return match html {
+            true => finding("csp", Severity::Fail, "HTML served without a Content-Security-Policy"),
+            false => finding(
+                "csp",
+                Severity::Info,
+                "no Content-Security-Policy; for an API, default-src 'none' keeps responses from running as pages",
+            ),
+        };
+    };
+    let policy = policy.to_ascii_lowercase();
+    let weak: Vec<&str> = ["'unsafe-inline'", "'unsafe-eval'"]
+        .into_iter()
+        .filter(|w| policy.contains(w))
+        .collect();
+    let wildcard = policy.split(';').any(|directive| {
+        let mut parts = directive.split_whitespace();
+        matches!(parts.next(), Some("default-src" | "script-src")) && parts.any(|s| s == "*")
+    });
+    match (weak.is_empty(), wildcard) {
+        (true, false) => finding("csp", Severity::Pass, "Content-Security-Policy is set"),
+        (true, true) => finding(
+            "csp",
+            Severity::Warn,
+            "Content-Security-Policy allows scripts from anywhere (*)",
+        ),
+        (false, _) => finding(
+            "csp",
+            Severity::Warn,
+            format!("Content-Security-Policy allows {}", weak.join(" and ")),
+        ),
+    }
+}
+
+/// Whether the request carried credentials or the response hands some out.
+fn sensitive(request: &Value, result: &Value) -> bool {
+    let sent = result.get("sent_request").unwrap_or(request);
+    let sent_headers = sent.get("headers").or(request.get("headers"));
+    CREDENTIAL_HEADERS
+        .iter()
+        .any(|h| header(sent_headers, h).is_some_and(|v| !v.is_empty()))
+        || !matches!(str_of(request, "auth_type"), "" | "none")
+        || header(result.get("headers"), "set-cookie").is_some()
+}
+
+fn cache_control(value: Option<&str>) -> Finding {
+    let directives: Vec<String> = value
+        .unwrap_or_default()
+        .split(',')
+        .map(|d| d.trim().to_ascii_lowercase())
+        .collect();
+    let has = |d: &str| directives.iter().any(|x| x == d);
+    if has("no-store") {
+        finding(
+            "cache_control",
+            Severity::Pass,
+            "authenticated response is not stored (no-store)",
+        )
+    } else if has("private") || has("no-cache") {
+        finding(
+            "cache_control",
+            Severity::Warn,
+            "authenticated response may still be stored by the client; use no-store",
+        )
+    } else {
+        finding(
+            "cache_control",
+            Severity::Fail,
+            "authenticated response can be cached by shared caches; send Cache-Control: no-store",
+        )
+    }
+}
+
+/// Grades one backend result of `request` on its headers.
+pub fn audit_response(request: &Value, result: &Value) -> Vec<Finding> {
+    let headers = result.get("headers");
+    let get = |name: &str| header(headers, name);
+    let url = result
+        .get("sent_request")
+        .map(|s| str_of(s, "url"))
+        .filter(|u| !u.is_empty())
+        .unwrap_or(str_of(request, "url"));
+    let html = get("content-type").is_some_and(|ct| ct.to_ascii_lowercase().contains("html"));
+    let mut findings = Vec::new();
+
+    match url
+        .get(..8)
+        .is_some_and(|s| s.eq_ignore_ascii_case("https://"))
+    {
+        true => findings.push(hsts(get("strict-transport-security"))),
+        false => findings.push(finding("https", Severity::Fail, "served over plain HTTP")),
+    }
+    findings.push(csp(get("content-security-policy"), html));
+    findings.push(match get("x-content-type-options") {
+        Some(v) if v.trim().eq_ignore_ascii_case("nosniff") => finding(
+            "content_type_options",
+            Severity::Pass,
+            "X-Content-Type-Options is nosniff",
+        ),
+        Some(v) => finding(
+            "content_type_options",
+            Severity::Fail,
+            format!("X-Content-Type-Options is {v:?}, not nosniff"),
+        ),
+        None => finding(
+            "content_type_options",
+            Severity::Fail,
+            "no X-Content-Type-Options: nosniff",
+        ),
+    });
+    if html {
+        let ancestors = get("content-security-policy")
+            .is_some_and(|p| p.to_ascii_lowercase().contains("frame-ancestors"));
+        findings.push(match (get("x-frame-options"), ancestors) {
+            (Some(_), _) | (_, true) => finding("framing", Severity::Pass, "framing is restricted"),
+            (None, false) => finding(
+                "framing",
+                Severity::Warn,
+                "page can be framed by any site; set X-Frame-Options or CSP frame-ancestors",
+            ),
+        });
+    }
+    if sensitive(request, result) {
+        findings.push(cache_control(get("cache-control")));
+    }
+    let banners: Vec<String> = ["server", "x-powered-by", "x-aspnet-version"]
+        .into_iter()
+        .filter_map(|name| get(name).map(|v| (name, v)))
+        // A bare product name is harmless; a version helps someone pick an exploit.
+        .filter(|(_, v)| v.chars().any(|c| c.is_ascii_digit()))
+        .map(|(name, v)| format!("{name}: {v}"))
+        .collect();
+    if !banners.is_empty() {
+        findings.push(finding(
+            "disclosure",
+            Severity::Warn,
+            format!("software versions disclosed ({})", banners.join(", ")),
+        ));
+    }
+    findings
+}
+
+/// Grades a host's TLS parameters.
+pub fn audit_tls(tls: &TlsParams) -> Vec<Finding> {
+    if let Some(error) = &tls.error {
+        return vec![finding(
+            "tls_version",
+            Severity::Fail,
+            format!("TLS handshake failed: {error}"),
+        )];
+    }
+    let mut findings = vec![match tls.version.as_deref() {
+        Some("TLS 1.3") => finding("tls_version", Severity::Pass, "TLS 1.3"),
+        Some("TLS 1.2") => finding(
+            "tls_version",
+            Severity::Info,
+            "TLS 1.2; TLS 1.3 is preferred",
+        ),
+        Some(other) => finding("tls_version", Severity::Fail, format!("outdated {other}")),
+        None => finding("tls_version", Severity::Warn, "TLS version unknown"),
+    }];
+    findings.push(match (&tls.certificate_trusted, &tls.certificate_error) {
+        (true, _) => finding("certificate", Severity::Pass, "certificate is trusted"),
+        (false, Some(e)) => finding(
+            "certificate",
+            Severity::Fail,
+            format!("certificate not trusted: {e}"),
+        ),
+        (false, None) => finding("certificate", Severity::Fail, "certificate not trusted"),
+    });
+    findings
+}
+
+fn failed(request: &Value, error: String) -> ResponseAudit {
+    let url = str_of(request, "url").to_string();
+    ResponseAudit {
+        request_id: str_of(request, "id").to_string(),
+        name: str_of(request, "name").to_string(),
+        method: str_of(request, "method").to_string(),
+        host: host_of(&url),
+        url,
+        status_code: None,
+        score: 0,
+        grade: 'F',
+        findings: Vec::new(),
+        error: Some(error),
+    }
+}
+
+/// Grades the backend `result` of sending `request`; one without a response gets an F.
+pub fn audit_one(request: &Value, result: &Value) -> ResponseAudit {
+    let mut audit = failed(request, String::new());
+    if let Some(url) = result
+        .get("sent_request")
+        .map(|s| str_of(s, "url"))
+        .filter(|u| !u.is_empty())
+    {
+        audit.host = host_of(url);
+        audit.url = url.to_string();
+    }
+    audit.status_code = result.get("status_code").and_then(Value::as_u64);
+    audit.error = result
+        .get("error")
+        .and_then(Value::as_str)
+        .filter(|e| !e.is_empty())
+        .map(str::to_string);
+    if audit.error.is_none() {
+        audit.findings = audit_response(request, result);
+        (audit.score, audit.grade) = score(&audit.findings);
+    }
+    audit
+}
+
+fn requests<'a>(items: &'a [Value], out: &mut Vec<&'a Value>) {
+    for item in items {
+        match item.get("items").and_then(Value::as_array) {
+            Some(children) => requests(children, out),
+            None => out.push(item),
+        }
+    }
+}
+
+/// Sends each request of `collection` (those in `request_ids`, when given) and grades its
+/// response, calling `on_result` as each finishes. Setting `cancel` stops further requests
+/// from starting.
+pub async fn execute<S: Sender>(
+    collection: &Value,
+    request_ids: Option<&[String]>,
+    sender: &S,
+    cancel: &AtomicBool,
+    mut on_result: impl FnMut(&ResponseAudit),
+) -> (Vec<ResponseAudit>, bool) {
+    let mut all = Vec::new();
+    requests(array_of(collection, "items"), &mut all);
+    all.retain(|r| request_ids.is_none_or(|ids| ids.iter().any(|id| id == str_of(r, "id"))));
+    let mut audits = Vec::new();
+    for request in all {
+        if cancel.load(Ordering::Relaxed) {
+            return (audits, true);
+        }
+        let audit = match sender.send(request.clone()).await {
+            Ok(sent) => audit_one(request, &sent.result),
+            Err(e) => failed(request, e),
+        };
+        on_result(&audit);
+        audits.push(audit);
+    }
+    (audits, false)
+}
+
+/// Groups graded responses by host, in the order hosts first appear, and adds each host's
+/// TLS findings from `tls` (looked up by host name).
+pub fn by_host(
+    audits: Vec<ResponseAudit>,
+    tls: impl Fn(&str) -> Option<TlsParams>,
+) -> Vec<HostReport> {
+    let mut hosts: Vec<HostReport> = Vec::new();
+    for audit in audits {
+        match hosts.iter_mut().find(|h| h.host == audit.host) {
+            Some(host) => host.responses.push(audit),
+            None => hosts.push(HostReport {
+                host: audit.host.clone(),
+                score: 0,
+                grade: 'F',
+                tls: None,
+                findings: Vec::new(),
+                responses: vec![audit],
+            }),
+        }
+    }
+    for host in &mut hosts {
+        let mut worst: Vec<Finding> = Vec::new();
+        for f in host.responses.iter().flat_map(|r| &r.findings) {
+            match worst.iter_mut().find(|w| w.check == f.check) {
+                Some(w) if f.severity > w.severity => *w = f.clone(),
+                Some(_) => {}
+                None => worst.push(f.clone()),
+            }
+        }
+        if host.responses.iter().all(|r| r.error.is_some()) {
+            worst.push(finding(
+                "reachability",
+                Severity::Fail,
+                "no response from this host to grade",
+            ));
+        }
+        host.tls = tls(&host.host);
+        if let Some(params) = &host.tls {
+            worst.extend(audit_tls(params));
+        }
+        (host.score, host.grade) = score(&worst);
+        host.findings = worst;
+    }
+    hosts
+}
@@ -0,0 +1,160 @@
+//! Preparing a request for the backend's `/run`, and the `Sender` seam the runner and
+//! workflows send through. The desktop shell's sender runs scripts and plugins around each
+//! request; the CLI's sends the resolved request as is.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::future::Future;
+
+use crate::json::str_of;
+use crate::variables::{Purpose, Scope, Substitution};
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TestResult {
+    pub name: String,
+    pub passed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// The outcome of a request's test script, stored with its history entry.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TestReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub results: Vec<TestResult>,
+    /// Set when the script itself threw or ran out of time; tests that ran before are kept.
+    pub error: Option<String>,
+}
+
+/// What sending one request produced.
+pub struct Sent {
+    /// The backend `RequestResult`.
+    pub result: Value,
+    /// The test script's report, when the sender ran one.
+    pub tests: Option<TestReport>,
+}
+
+/// Sends saved requests of one collection with one environment.
+pub trait Sender: Sync {
+    /// Sends `request` (in the collection's `HttpRequest` shape); its `variables` form the
+    /// request layer.
+    fn send(&self, request: Value) -> impl Future<Output = Result<Sent, String>> + Send;
+}
+
+struct Resolver<'a> {
+    scope: &'a Scope,
+    record: Vec<Substitution>,
+}
+
+impl Resolver<'_> {
+    /// Resolves `text` and reports whether a secret value went into it.
+    fn text(&mut self, text: &str) -> (String, bool) {
+        let seen = self.record.len();
+        let text = crate::variables::resolve(text, self.scope, Purpose::Send, &mut self.record);
+        (text, self.record[seen..].iter().any(|s| s.secret))
+    }
+
+    fn value(&mut self, value: &mut Value) -> bool {
+        match value {
+            Value::String(text) => {
+                let (resolved, secret) = self.text(text);
+                *text = resolved;
+                secret
+            }
+            Value::Array(items) => {
+                let mut secret = false;
+                for item in items {
+                    secret |= self.value(item);
+                }
+                secret
+            }
+            Value::Object(map) => {
+                let mut secret = false;
+                for (key, mut inner) in std::mem::take(map) {
+                    secret |= self.value(&mut inner);
+                    let (key, key_secret) = self.text(&key);
+                    map.insert(key, inner);
+                    secret |= key_secret;
+                }
+                secret
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Marks `key` secret in one of the request's `secret_*` maps, so the backend masks the
+/// value it received from a secret variable in history.
+pub fn mark_secret(request: &mut Value, field: &str, key: &str) {
+    if !request.get(field).is_some_and(Value::is_object) {
+        request[field] = Value::Object(Map::new());
+    }
+    request[field][key] = Value::Bool(true);
+}
+
+/// Resolves every field the backend sends, returning what was substituted.
+pub fn resolve_request(request: &mut Value, scope: &Scope) -> Vec<Substitution> {
+    let mut resolver = Resolver {
+        scope,
+        record: Vec::new(),
+    };
+    if let Some(url) = request.get("url").and_then(Value::as_str) {
+        let (url, _) = resolver.text(url);
+        request["url"] = Value::String(url);
+    }
+
+    for (field, marker) in [
+        ("headers", "secret_headers"),
+        ("auth_params", "secret_auth_params"),
+    ] {
+        let Some(map) = request.get_mut(field).and_then(Value::as_object_mut) else {
+            continue;
+        };
+        let mut secrets = Vec::new();
+        for (key, mut value) in std::mem::take(map) {
+            let (key, key_secret) = resolver.text(&key);
+            if resolver.value(&mut value) || key_secret {
+                secrets.push(key.clone());
+            }
+            map.insert(key, value);
+        }
+        for key in secrets {
+            mark_secret(request, marker, &key);
+        }
+    }
+
+    for (field, marker) in [
+        ("query_params", "secret_query_params"),
+        ("form_body", "secret_form_fields"),
+    ] {
+        let Some(rows) = request.get_mut(field).and_then(Value::as_array_mut) else {
+            continue;
+        };
+        let mut secrets = Vec::new();
+        for row in rows.iter_mut() {
+            let mut secret = false;
+            for cell in ["key", "value", "file_path"] {
+                if let Some(value) = row.get_mut(cell) {
+                    secret |= resolver.value(value);
+                }
+            }
+            if secret {
+                secrets.push(str_of(row, "key").to_string());
+            }
+        }
+        for key in secrets {
+            mark_secret(request, marker, &key);
+        }
+    }
+
+    if let Some(body) = request.get_mut("body") {
+        if resolver.value(body) {
+            request["secret_body"] = Value::Bool(true);
+        }
+    }
+    if let Some(path) = request.pointer_mut("/binary/file_path") {
+        resolver.value(path);
+    }
+    resolver.record
+}
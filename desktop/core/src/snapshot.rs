@@ -0,0 +1,178 @@
+//! Response snapshots: a canonical copy of a request's response (status, headers and body)
+//! kept with its collection as `collections/<id>/snapshots/<request_id>.json`, so it is
+//! versioned alongside the requests. Later responses are checked against it with the
+//! structural diff in `crate::diff`, leaving out the snapshot's `ignore` paths (and, with
+//! `ignore_volatile`, ids, timestamps and per-response headers).
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::diff::{DiffOptions, ResponseDiff};
+
+const SNAPSHOT_DIR: &str = "snapshots";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Snapshot {
+    pub request_id: String,
+    pub created_at_ms: u64,
+    /// The stored response, in the backend `RequestResult` shape: `status_code`, `headers`,
+    /// `content_type` and `body`.
+    pub response: Value,
+    /// Body paths (`$.meta.requestId`, `$.items[*].updatedAt`), key names or header names
+    /// left out of every check.
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    #[serde(default)]
+    pub ignore_volatile: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct SnapshotCheck {
+    pub request_id: String,
+    /// The response matches the snapshot outside the ignored fields.
+    pub matches: bool,
+    pub diff: ResponseDiff,
+}
+
+impl Snapshot {
+    /// A snapshot of `result`, keeping only what a check compares.
+    pub fn capture(
+        request_id: &str,
+        result: &Value,
+        ignore: Vec<String>,
+        ignore_volatile: bool,
+    ) -> Self {
+        let field = |key: &str| result.get(key).cloned().unwrap_or(Value::Null);
+        Self {
+            request_id: request_id.to_string(),
+            created_at_ms: crate::now_ms(),
+            response: json!({
+                "status_code": field("status_code"),
+                "headers": field("headers"),
+                "content_type": field("content_type"),
+                "body": field("body"),
+            }),
+            ignore,
+            ignore_volatile,
+        }
+    }
+
+    /// Compares a new response (a `RequestResult`) against the snapshot.
+    pub fn verify(&self, result: &Value) -> SnapshotCheck {
+        let diff = crate::diff::diff(
+            &self.response,
+            result,
+            &DiffOptions {
+                ignore: self.ignore.clone(),
+                ignore_volatile: self.ignore_volatile,
+            },
+        );
+        SnapshotCheck {
+            request_id: self.request_id.clone(),
+            matches: diff.identical,
+            diff,
+        }
+    }
+}
+
+impl SnapshotCheck {
+    /// One line on the first difference, for test results.
+    pub fn summary(&self) -> Option<String> {
+        if self.matches {
+            return None;
+        }
+        if self.diff.status_before != self.diff.status_after {
+            return Some(format!(
+                "status {} instead of {}",
+                status_text(self.diff.status_after),
+                status_text(self.diff.status_before)
+            ));
+        }
+        let changes = self.diff.body.len() + self.diff.headers.len();
+        let first = self
+            .diff
+            .body
+            .first()
+            .map(|c| format!("{} {}", c.path, c.kind))
+            .or_else(|| {
+                self.diff
+                    .headers
+                    .first()
+                    .map(|h| format!("header {} {}", h.name, h.kind))
+            })?;
+        Some(match changes {
+            1 => first,
+            n => format!("{first} (and {} more)", n - 1),
+        })
+    }
+}
+
+fn status_text(status: Option<u64>) -> String {
+    status.map_or("none".to_string(), |s| s.to_string())
+}
+
+fn dir(workspace: &Path, collection_id: &str) -> PathBuf {
+    workspace
+        .join("collections")
+        .join(collection_id)
+        .join(SNAPSHOT_DIR)
+}
+
+pub fn path(workspace: &Path, collection_id: &str, request_id: &str) -> PathBuf {
+    dir(workspace, collection_id).join(format!("{request_id}.json"))
+}
+
+pub fn load(
+    workspace: &Path,
+    collection_id: &str,
+    request_id: &str,
+) -> Result<Option<Snapshot>, String> {
+    let path = path(workspace, collection_id, request_id);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let raw = fs::read_to_string(&path).map_err(|e| format!("snapshot read failed: {e}"))?;
+    serde_json::from_str(&raw)
+        .map(Some)
+        .map_err(|e| format!("snapshot parse failed: {e}"))
+}
+
+/// Every snapshot of a collection, by request id; unreadable files are skipped.
+pub fn load_all(workspace: &Path, collection_id: &str) -> HashMap<String, Snapshot> {
+    let Ok(entries) = fs::read_dir(dir(workspace, collection_id)) else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let raw = fs::read_to_string(entry.path()).ok()?;
+            let snapshot: Snapshot = serde_json::from_str(&raw).ok()?;
+            Some((snapshot.request_id.clone(), snapshot))
+        })
+        .collect()
+}
+
+pub fn save(workspace: &Path, collection_id: &str, snapshot: &Snapshot) -> Result<(), String> {
+    fs::create_dir_all(dir(workspace, collection_id))
+        .map_err(|e| format!("snapshot write failed: {e}"))?;
+    let payload = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| format!("snapshot encode failed: {e}"))?;
+    fs::write(
+        path(workspace, collection_id, &snapshot.request_id),
+        payload,
+    )
+    .map_err(|e| format!("snapshot write failed: {e}"))
+}
+
+/// Removes a request's snapshot; true when there was one.
+pub fn delete(workspace: &Path, collection_id: &str, request_id: &str) -> Result<bool, String> {
+    let path = path(workspace, collection_id, request_id);
+    if !path.exists() {
+        return Ok(false);
+    }
+    fs::remove_file(&path).map_err(|e| format!("snapshot delete failed: {e}"))?;
+    Ok(true)
+}
@@ -0,0 +1,206 @@
+//! Request templates: request skeletons with named parameters, written `[[name]]` anywhere
+//! in a string of the request (environment variables keep their `{{name}}` form and pass
+//! through). A few built-in ones cover common shapes; workspaces add their own.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
+
+/// Ids of built-in templates start with this.
+pub const BUILTIN_PREFIX: &str = "builtin:";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct TemplateParam {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Used when no value is given; a parameter without one is required.
+    #[serde(default)]
+    pub default: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Template {
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub params: Vec<TemplateParam>,
+    /// In the collection's `HttpRequest` shape, without an id.
+    pub request: Value,
+}
+
+fn placeholder() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\[\[\s*([A-Za-z_][A-Za-z0-9_]*)\s*\]\]").expect("valid regex"))
+}
+
+fn param(name: &str, description: &str, default: Option<&str>) -> TemplateParam {
+    TemplateParam {
+        name: name.to_string(),
+        description: description.to_string(),
+        default: default.map(str::to_string),
+    }
+}
+
+/// The templates every workspace has.
+pub fn builtins() -> Vec<Template> {
+    vec![
+        Template {
+            id: format!("{BUILTIN_PREFIX}paginated-get"),
+            name: "Paginated GET".to_string(),
+            description: "A list endpoint paged with page and page size parameters.".to_string(),
+            params: vec![
+                param("base_url", "API root", Some("{{base_url}}")),
+                param("resource", "Collection path, e.g. users", None),
+                param("page_size", "Items per page", Some("50")),
+            ],
+            request: json!({
+                "name": "List [[resource]]",
+                "method": "GET",
+                "url": "[[base_url]]/[[resource]]",
+                "headers": { "Accept": "application/json" },
+                "query_params": [
+                    { "key": "page", "value": "1", "enabled": true },
+                    { "key": "page_size", "value": "[[page_size]]", "enabled": true }
+                ],
+            }),
+        },
+        Template {
+            id: format!("{BUILTIN_PREFIX}json-rpc"),
+            name: "JSON-RPC call".to_string(),
+            description: "A JSON-RPC 2.0 method call.".to_string(),
+            params: vec![
+                param("url", "Endpoint", Some("{{rpc_url}}")),
+                param("method", "RPC method name", None),
+                param("params", "Parameters as JSON", Some("[]")),
+            ],
+            request: json!({
+                "name": "[[method]]",
+                "method": "POST",
+                "url": "[[url]]",
+                "headers": { "Content-Type": "application/json" },
+                "body_mode": "json",
+                "body": "{\n  \"jsonrpc\": \"2.0\",\n  \"id\": 1,\n  \"method\": \"[[method]]\",\n  \"params\": [[params]]\n}",
+            }),
+        },
+        Template {
+            id: format!("{BUILTIN_PREFIX}json-create"),
+            name: "Create with JSON".to_string(),
+            description: "A POST of a JSON document, checking for 201 Created.".to_string(),
+            params: vec![
+                param("base_url", "API root", Some("{{base_url}}")),
+                param("resource", "Collection path, e.g. users", None),
+            ],
+            request: json!({
+                "name": "Create [[resource]]",
+                "method": "POST",
+                "url": "[[base_url]]/[[resource]]",
+                "headers": { "Content-Type": "application/json", "Accept": "application/json" },
+                "body_mode": "json",
+                "body": "{\n  \n}",
+                "test_script": "lf.test(\"created\", () => lf.expect(lf.response.status).toBe(201));",
+            }),
+        },
+        Template {
+            id: format!("{BUILTIN_PREFIX}graphql-query"),
+            name: "GraphQL query".to_string(),
+            description: "A GraphQL query sent as JSON over POST.".to_string(),
+            params: vec![
+                param("url", "GraphQL endpoint", Some("{{graphql_url}}")),
+                param("operation", "Operation name", Some("Query")),
+            ],
+            request: json!({
+                "name": "[[operation]]",
+                "method": "POST",
+                "url": "[[url]]",
+                "headers": { "Content-Type": "application/json" },
+                "body_mode": "json",
+                "body": "{\n  \"operationName\": \"[[operation]]\",\n  \"query\": \"query [[operation]] { __typename }\",\n  \"variables\": {}\n}",
+            }),
+        },
+    ]
+}
+
+fn collect(value: &Value, names: &mut BTreeSet<String>) {
+    match value {
+        Value::String(text) => {
+            for caps in placeholder().captures_iter(text) {
+                names.insert(caps[1].to_string());
+            }
+        }
+        Value::Array(items) => items.iter().for_each(|v| collect(v, names)),
+        Value::Object(map) => map.iter().for_each(|(k, v)| {
+            collect(&Value::String(k.clone()), names);
+            collect(v, names);
+        }),
+        _ => {}
+    }
+}
+
+/// Parameters the request uses but doesn't declare, which no value could ever fill.
+pub fn undeclared(template: &Template) -> Vec<String> {
+    let mut used = BTreeSet::new();
+    collect(&template.request, &mut used);
+    used.into_iter()
+        .filter(|name| !template.params.iter().any(|p| p.name == *name))
+        .collect()
+}
+
+fn fill(value: &Value, values: &BTreeMap<String, String>) -> Value {
+    let substitute = |text: &str| {
+        placeholder()
+            .replace_all(text, |caps: &regex::Captures| {
+                values.get(&caps[1]).cloned().unwrap_or_default()
+            })
+            .into_owned()
+    };
+    match value {
+        Value::String(text) => Value::String(substitute(text)),
+        Value::Array(items) => Value::Array(items.iter().map(|v| fill(v, values)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (substitute(k), fill(v, values)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// A new request from `template` with `params` filled in (defaults for the rest), under
+/// `id`. Fails when a parameter without a default has no value.
+pub fn instantiate(
+    template: &Template,
+    params: &BTreeMap<String, String>,
+    id: String,
+) -> Result<Value, String> {
+    let mut values = BTreeMap::new();
+    let mut missing = Vec::new();
+    for param in &template.params {
+        match params.get(&param.name).or(param.default.as_ref()) {
+            Some(value) => {
+                values.insert(param.name.clone(), value.clone());
+            }
+            None => missing.push(param.name.as_str()),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!(
+            "missing template parameters: {}",
+            missing.join(", ")
+        ));
+    }
+    let mut request = fill(&template.request, &values);
+    let Some(fields) = request.as_object_mut() else {
+        return Err("template request must be an object".to_string());
+    };
+    fields.insert("id".to_string(), Value::String(id));
+    fields
+        .entry("name")
+        .or_insert_with(|| Value::String(template.name.clone()));
+    Ok(request)
+}
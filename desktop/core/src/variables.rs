@@ -0,0 +1,210 @@
+//! Layered variable scopes and their resolution, as done at send time. Layers apply in a
+//! fixed order: global, workspace, collection, environment, request; a later layer wins
+//! for any key it defines. `{{$...}}` references are dynamic and evaluated first.
+//!
+//! Global variables live in the app data directory and follow the user across workspaces;
+//! workspace variables live in the workspace root beside the collections.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::redact::MASK;
+
+pub const LAYER_FILE: &str = "variables.json";
+
+/// Where a value came from. Declared in resolution order, lowest precedence first.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableSource {
+    Global,
+    Workspace,
+    Collection,
+    Environment,
+    Request,
+    Dynamic,
+}
+
+pub struct Variable {
+    pub key: String,
+    pub value: String,
+    pub source: VariableSource,
+    pub secret: bool,
+}
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// The folders holding the global and workspace layers.
+pub struct Layers {
+    /// The app data directory.
+    pub global: PathBuf,
+    /// The workspace root.
+    pub workspace: PathBuf,
+}
+
+/// Reads the layer file in `dir`; an absent file is an empty layer.
+pub fn read_layer(dir: &Path) -> Result<Map<String, Value>, String> {
+    let path = dir.join(LAYER_FILE);
+    if !path.exists() {
+        return Ok(Map::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("variables read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("variables parse failed: {e}"))
+}
+
+/// Variables visible to a request, lowest precedence first.
+pub struct Scope {
+    variables: Vec<Variable>,
+}
+
+impl Scope {
+    /// Every layer for a request of `collection` sent with the environment `env_name`.
+    pub fn load(
+        layers: &Layers,
+        collection_id: &str,
+        collection: &Value,
+        environment: &Value,
+        env_name: &str,
+        request: Option<&Value>,
+    ) -> Result<Self, String> {
+        let mut scope = Self {
+            variables: Vec::new(),
+        };
+        scope.push_map(&read_layer(&layers.global)?, VariableSource::Global);
+        scope.push_map(&read_layer(&layers.workspace)?, VariableSource::Workspace);
+        if let Some(vars) = collection.get("variables").and_then(Value::as_object) {
+            scope.push_map(vars, VariableSource::Collection);
+        }
+        for resolved in crate::secrets::resolve_environment(collection_id, environment, env_name)? {
+            scope.variables.push(Variable {
+                key: resolved.key,
+                value: resolved.value,
+                source: VariableSource::Environment,
+                secret: resolved.secret,
+            });
+        }
+        if let Some(vars) = request
+            .and_then(|r| r.get("variables"))
+            .and_then(Value::as_object)
+        {
+            scope.push_map(vars, VariableSource::Request);
+        }
+        Ok(scope)
+    }
+
+    fn push_map(&mut self, vars: &Map<String, Value>, source: VariableSource) {
+        self.variables
+            .extend(vars.iter().map(|(key, value)| Variable {
+                key: key.clone(),
+                value: text_of(value),
+                source,
+                secret: false,
+            }));
+    }
+
+    /// The winning value of every key, as scripts see them. Without `secrets`, keys whose
+    /// winning definition is secret are left out.
+    pub fn values(&self, secrets: bool) -> Map<String, Value> {
+        let mut values = Map::new();
+        for variable in &self.variables {
+            if variable.secret && !secrets {
+                values.remove(&variable.key);
+                continue;
+            }
+            values.insert(variable.key.clone(), Value::String(variable.value.clone()));
+        }
+        values
+    }
+
+    /// Every definition, lowest precedence first.
+    pub fn variables(&self) -> &[Variable] {
+        &self.variables
+    }
+
+    /// The winning definition of `key`.
+    pub fn lookup(&self, key: &str) -> Option<&Variable> {
+        self.variables.iter().rev().find(|v| v.key == key)
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct Substitution {
+    /// The reference as written, without braces.
+    pub reference: String,
+    /// `None` when nothing defines the reference; it is then sent as written.
+    pub value: Option<String>,
+    pub source: Option<VariableSource>,
+    pub secret: bool,
+}
+
+#[derive(Clone, Copy)]
+pub enum Purpose {
+    /// Shows the final text: escapes are removed and secrets masked unless revealed.
+    Preview { reveal: bool },
+    /// Produces what the backend receives: real values, escapes left for it to remove.
+    Send,
+}
+
+/// Substitutes the references in `text`, recording each occurrence.
+pub fn resolve(
+    text: &str,
+    scope: &Scope,
+    purpose: Purpose,
+    record: &mut Vec<Substitution>,
+) -> String {
+    let mut dynamic = Vec::new();
+    let text = crate::dynamic::expand(text, &mut dynamic);
+    record.extend(dynamic.into_iter().map(|d| Substitution {
+        reference: d.expression,
+        value: Some(d.value),
+        source: Some(VariableSource::Dynamic),
+        secret: false,
+    }));
+    let reveal = match purpose {
+        Purpose::Preview { reveal } => reveal,
+        Purpose::Send => true,
+    };
+
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start..].find("}}") else {
+            break;
+        };
+        let reference = &rest[start + 2..start + len];
+        let whole = &rest[start..start + len + 2];
+        let before = &rest[..start];
+        rest = &rest[start + len + 2..];
+        // `\{{name}}` is sent as a literal `{{name}}`.
+        if let Some(unescaped) = before.strip_suffix('\\') {
+            out.push_str(match purpose {
+                Purpose::Preview { .. } => unescaped,
+                Purpose::Send => before,
+            });
+            out.push_str(whole);
+            continue;
+        }
+        out.push_str(before);
+        let found = scope.lookup(reference);
+        let shown = found.map(|v| match v.secret && !reveal {
+            true => MASK.to_string(),
+            false => v.value.clone(),
+        });
+        out.push_str(shown.as_deref().unwrap_or(whole));
+        record.push(Substitution {
+            reference: reference.to_string(),
+            value: shown,
+            source: found.map(|v| v.source),
+            secret: found.is_some_and(|v| v.secret),
+        });
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,352 @@
+//! Workflows: ordered chains of saved requests stored on the collection. Each step sends a
+//! request through a `Sender`, with values extracted from earlier responses as request-layer
+//! variables, checks its assertions and picks the next step from its branches.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::time::Instant;
+
+use crate::json::{find_request, str_of};
+use crate::schema::Violation;
+use crate::send::Sender;
+
+/// Guards against branches that loop forever; polling loops stay well below it.
+const MAX_STEP_RUNS: usize = 200;
+
+#[derive(Deserialize, Clone)]
+struct Workflow {
+    id: String,
+    #[serde(default)]
+    variables: Map<String, Value>,
+    #[serde(default)]
+    steps: Vec<Step>,
+}
+
+#[derive(Deserialize, Clone)]
+struct Step {
+    id: String,
+    request_id: String,
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    extracts: Vec<Extract>,
+    #[serde(default)]
+    assertions: Vec<Assertion>,
+    #[serde(default)]
+    branches: Vec<Branch>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Source {
+    Body,
+    /// A jq program over the body, in `path`.
+    Jq,
+    Header,
+    Status,
+    Duration,
+}
+
+#[derive(Deserialize, Clone)]
+struct Extract {
+    variable: String,
+    source: Source,
+    /// JSONPath into the body, a jq program, or the header name.
+    #[serde(default)]
+    path: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Operator {
+    Equals,
+    NotEquals,
+    Contains,
+    Exists,
+    NotExists,
+    Matches,
+    GreaterThan,
+    LessThan,
+    /// `value` is a JSON Schema the value at `path` must match.
+    MatchesSchema,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+struct Assertion {
+    source: Source,
+    #[serde(default)]
+    path: String,
+    op: Operator,
+    #[serde(default)]
+    value: Value,
+}
+
+#[derive(Deserialize, Clone)]
+struct Branch {
+    when: Assertion,
+    /// A step id, or `end`.
+    goto: String,
+}
+
+#[derive(Serialize, Clone)]
+pub struct AssertionResult {
+    assertion: Assertion,
+    pub passed: bool,
+    pub actual: Option<String>,
+    /// Why a `matches_schema` assertion failed.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<Violation>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct StepReport {
+    pub step_id: String,
+    pub name: Option<String>,
+    pub request_id: String,
+    /// `passed`, `failed` (an assertion failed) or `error` (the request couldn't be sent).
+    pub status: String,
+    pub status_code: Option<u64>,
+    pub duration_ms: Option<f64>,
+    pub extracted: Map<String, Value>,
+    pub assertions: Vec<AssertionResult>,
+    pub error: Option<String>,
+    /// The step chosen to run next, or `None` when the run ends here.
+    pub next: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct WorkflowRun {
+    pub run_id: String,
+    pub workflow_id: String,
+    pub passed: bool,
+    pub steps: Vec<StepReport>,
+    /// The workflow's variables after every extract.
+    pub variables: Map<String, Value>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// The response value a source and path point at; `None` when it isn't there.
+fn read(result: &Value, source: Source, path: &str) -> Option<Value> {
+    match source {
+        Source::Status => result.get("status_code").cloned(),
+        Source::Duration => result.get("duration_ms").cloned(),
+        Source::Header => {
+            let wanted = path.to_ascii_lowercase();
+            result
+                .get("headers")
+                .and_then(Value::as_object)?
+                .iter()
+                .find(|(name, _)| name.to_ascii_lowercase() == wanted)
+                .map(|(_, value)| value.clone())
+        }
+        Source::Jq => {
+            let body = result.get("body")?;
+            let parsed = match body {
+                Value::String(text) => serde_json::from_str(text).unwrap_or_else(|_| body.clone()),
+                other => other.clone(),
+            };
+            crate::jq::select_value(path, parsed)
+        }
+        Source::Body => {
+            let body = result.get("body")?;
+            let parsed = match body {
+                Value::String(text) => match serde_json::from_str(text) {
+                    Ok(parsed) => parsed,
+                    Err(_) => return matches!(path.trim(), "" | "$").then(|| body.clone()),
+                },
+                other => other.clone(),
+            };
+            match path.trim() {
+                "" | "$" => Some(parsed),
+                path => crate::jsonpath::select_value(&parsed, path),
+            }
+        }
+    }
+}
+
+fn check(result: &Value, assertion: &Assertion) -> AssertionResult {
+    let actual = read(result, assertion.source, &assertion.path);
+    let actual_text = actual.as_ref().map(text_of);
+    let expected = text_of(&assertion.value);
+    let number = |text: &str| text.trim().parse::<f64>().ok();
+    let violations = match (assertion.op, &actual) {
+        (Operator::MatchesSchema, Some(actual)) => {
+            crate::schema::validate(&assertion.value, actual)
+        }
+        _ => Vec::new(),
+    };
+    let passed = match (assertion.op, actual_text.as_deref()) {
+        (Operator::Exists, found) => found.is_some(),
+        (Operator::NotExists, found) => found.is_none(),
+        (_, None) => false,
+        (Operator::Equals, Some(actual)) => actual == expected,
+        (Operator::NotEquals, Some(actual)) => actual != expected,
+        (Operator::Contains, Some(actual)) => actual.contains(&expected),
+        (Operator::Matches, Some(actual)) => {
+            Regex::new(&expected).is_ok_and(|pattern| pattern.is_match(actual))
+        }
+        (Operator::GreaterThan, Some(actual)) => {
+            matches!((number(actual), number(&expected)), (Some(a), Some(e)) if a > e)
+        }
+        (Operator::LessThan, Some(actual)) => {
+            matches!((number(actual), number(&expected)), (Some(a), Some(e)) if a < e)
+        }
+        (Operator::MatchesSchema, Some(_)) => violations.is_empty(),
+    };
+    AssertionResult {
+        assertion: assertion.clone(),
+        passed,
+        actual: actual_text,
+        violations,
+    }
+}
+
+/// Sends one step's request with the workflow variables on its request layer.
+async fn run_step<S: Sender>(
+    sender: &S,
+    collection: &Value,
+    step: &Step,
+    variables: &mut Map<String, Value>,
+) -> StepReport {
+    let mut report = StepReport {
+        step_id: step.id.clone(),
+        name: step.name.clone(),
+        request_id: step.request_id.clone(),
+        status: "error".to_string(),
+        status_code: None,
+        duration_ms: None,
+        extracted: Map::new(),
+        assertions: Vec::new(),
+        error: None,
+        next: None,
+    };
+    let items = collection
+        .get("items")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let Some(request) = find_request(items, &step.request_id) else {
+        report.error = Some(format!("unknown request: {}", step.request_id));
+        return report;
+    };
+    let mut request = request.clone();
+    if !request.get("variables").is_some_and(Value::is_object) {
+        request["variables"] = Value::Object(Map::new());
+    }
+    for (key, value) in variables.iter() {
+        request["variables"][key] = value.clone();
+    }
+    let sent = sender.send(request).await;
+    let result = match sent {
+        Ok(sent) => sent.result,
+        Err(e) => {
+            report.error = Some(e);
+            return report;
+        }
+    };
+    report.status_code = result.get("status_code").and_then(Value::as_u64);
+    report.duration_ms = result.get("duration_ms").and_then(Value::as_f64);
+    if let Some(error) = result
+        .get("error")
+        .and_then(Value::as_str)
+        .filter(|e| !e.is_empty())
+    {
+        report.error = Some(error.to_string());
+        return report;
+    }
+
+    for extract in &step.extracts {
+        if let Some(value) = read(&result, extract.source, &extract.path) {
+            let value = Value::String(text_of(&value));
+            report
+                .extracted
+                .insert(extract.variable.clone(), value.clone());
+            variables.insert(extract.variable.clone(), value);
+        }
+    }
+    report.assertions = step.assertions.iter().map(|a| check(&result, a)).collect();
+    report.status = match report.assertions.iter().all(|a| a.passed) {
+        true => "passed",
+        false => "failed",
+    }
+    .to_string();
+    report.next = step
+        .branches
+        .iter()
+        .find(|branch| check(&result, &branch.when).passed)
+        .map(|branch| branch.goto.clone());
+    report
+}
+
+/// Runs the workflow `workflow_id` of `collection` from its first step, calling `on_step`
+/// with each step's position in the run as it finishes. The run stops at a failed
+/// assertion, a request error, a branch to `end` or after the last step.
+pub async fn run<S: Sender>(
+    run_id: String,
+    collection: &Value,
+    workflow_id: &str,
+    sender: &S,
+    mut on_step: impl FnMut(usize, &StepReport),
+) -> Result<WorkflowRun, String> {
+    let workflow: Workflow = collection
+        .get("workflows")
+        .and_then(Value::as_array)
+        .and_then(|flows| flows.iter().find(|f| str_of(f, "id") == workflow_id))
+        .cloned()
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| format!("workflow invalid: {e}"))?
+        .ok_or_else(|| format!("unknown workflow: {workflow_id}"))?;
+
+    let started = Instant::now();
+    let mut variables = workflow.variables.clone();
+    let mut steps = Vec::new();
+    let mut error = None;
+    let mut index = 0;
+    while let Some(step) = workflow.steps.get(index) {
+        if steps.len() == MAX_STEP_RUNS {
+            error = Some(format!("workflow stopped after {MAX_STEP_RUNS} steps"));
+            break;
+        }
+        let mut report = run_step(sender, collection, step, &mut variables).await;
+        let target = match (report.status.as_str(), report.next.as_deref()) {
+            ("passed", Some("end")) => None,
+            ("passed", Some(goto)) => match workflow.steps.iter().position(|s| s.id == goto) {
+                Some(target) => Some(target),
+                None => {
+                    error = Some(format!("branch to unknown step: {goto}"));
+                    None
+                }
+            },
+            ("passed", None) => Some(index + 1).filter(|&next| next < workflow.steps.len()),
+            _ => None,
+        };
+        report.next = target.map(|t| workflow.steps[t].id.clone());
+        on_step(steps.len(), &report);
+        steps.push(report);
+        match target {
+            Some(target) => index = target,
+            None => break,
+        }
+    }
+
+    Ok(WorkflowRun {
+        run_id,
+        workflow_id: workflow.id,
+        passed: error.is_none() && steps.iter().all(|s| s.status == "passed"),
+        steps,
+        variables,
+        error,
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
@@ -0,0 +1,55 @@
+//! Where the workspace lives. The desktop shell records the chosen folder in its app data
+//! directory; the CLI reads the same record so both work on the same collections.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The file in the app data directory that records the workspace folder.
+pub const CONFIG_FILE: &str = "workspace_path.json";
+
+#[derive(Serialize, Deserialize)]
+pub struct WorkspaceConfig {
+    pub path: String,
+}
+
+pub fn normalize_path(path: &str) -> PathBuf {
+    if path == "~" {
+        if let Ok(home) = std::env::var("HOME") {
+            return PathBuf::from(home);
+        }
+    }
+    if path.starts_with("~/") {
+        if let Ok(home) = std::env::var("HOME") {
+            let rest = path.trim_start_matches("~/");
+            return PathBuf::from(home).join(rest);
+        }
+    }
+    let candidate = PathBuf::from(path);
+    if candidate.is_absolute() {
+        candidate
+    } else if let Ok(cwd) = std::env::current_dir() {
+        cwd.join(candidate)
+    } else {
+        candidate
+    }
+}
+
+/// The workspace recorded under `app_root`, or `<app_root>/workspace` when none was chosen.
+/// The folder is created if missing.
+pub fn resolve(app_root: &Path) -> Result<PathBuf, String> {
+    let cfg_path = app_root.join(CONFIG_FILE);
+    if cfg_path.exists() {
+        let data =
+            fs::read_to_string(&cfg_path).map_err(|e| format!("workspace read failed: {e}"))?;
+        if let Ok(cfg) = serde_json::from_str::<WorkspaceConfig>(&data) {
+            let path = normalize_path(&cfg.path);
+            fs::create_dir_all(&path)
+                .map_err(|e| format!("workspace init failed for stored path: {e}"))?;
+            return Ok(path);
+        }
+    }
+    let path = app_root.join("workspace");
+    fs::create_dir_all(&path).map_err(|e| format!("workspace init failed: {e}"))?;
+    Ok(path)
+}
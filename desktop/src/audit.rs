@@ -0,0 +1,151 @@
+//! The workspace audit log: an append-only `.litefetch/audit.jsonl` the backend writes for every
+//! collection, request and environment change, attributed to the UI, an import or a sync. The
+//! shell adds the entries for files a sync rewrites itself, and reads the log for the UI.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+
+const AUDIT_FILE: &str = "audit.jsonl";
+const DEFAULT_LIMIT: usize = 200;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuditEntry {
+    pub timestamp_ms: u64,
+    /// `collection.created`, `request.edited`, `environment.changed`, `file.updated`, …
+    pub action: String,
+    /// `ui`, `import` or `sync`.
+    pub origin: String,
+    pub collection_id: Option<String>,
+    /// Action-specific fields such as `request_id`, `name` or `path`.
+    #[serde(flatten)]
+    pub details: Map<String, Value>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct AuditFilter {
+    pub collection_id: Option<String>,
+    pub request_id: Option<String>,
+    /// An action (`request.deleted`) or a whole group of them (`request`).
+    pub action: Option<String>,
+    pub origin: Option<String>,
+    pub since_ms: Option<u64>,
+    pub until_ms: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        let action = self.action.as_deref().is_none_or(|action| {
+            entry.action == action || entry.action.starts_with(&format!("{action}."))
+        });
+        let request = self
+            .request_id
+            .as_deref()
+            .is_none_or(|id| entry.details.get("request_id").and_then(Value::as_str) == Some(id));
+        action
+            && request
+            && self
+                .collection_id
+                .as_ref()
+                .is_none_or(|id| entry.collection_id.as_ref() == Some(id))
+            && self.origin.as_ref().is_none_or(|o| entry.origin == *o)
+            && self
+                .since_ms
+                .is_none_or(|since| entry.timestamp_ms >= since)
+            && self
+                .until_ms
+                .is_none_or(|until| entry.timestamp_ms <= until)
+    }
+}
+
+fn log_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let mut path = crate::load_workspace_path(app)?;
+    path.push(".litefetch");
+    path.push(AUDIT_FILE);
+    Ok(path)
+}
+
+fn append(app: &tauri::AppHandle, entries: &[AuditEntry]) -> Result<(), String> {
+    let path = log_path(app)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("audit log write failed: {e}"))?;
+    }
+    let mut lines = String::new();
+    for entry in entries {
+        let line =
+            serde_json::to_string(entry).map_err(|e| format!("audit log write failed: {e}"))?;
+        lines.push_str(&line);
+        lines.push('\n');
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .map_err(|e| format!("audit log write failed: {e}"))
+}
+
+/// Records the workspace files a sync changed locally. Best-effort: a failure is only logged.
+pub fn record_sync(app: &tauri::AppHandle, report: &crate::sync::SyncReport) {
+    let changes = [
+        ("file.updated", &report.downloaded),
+        ("file.deleted", &report.deleted_local),
+        ("file.conflict", &report.conflicts),
+    ];
+    let entries: Vec<AuditEntry> = changes
+        .into_iter()
+        .flat_map(|(action, paths)| {
+            paths.iter().map(move |path| {
+                let mut parts = path.split('/');
+                let collection_id = match (parts.next(), parts.next(), parts.next()) {
+                    (Some("collections"), Some(id), Some(_)) => Some(id.to_string()),
+                    _ => None,
+                };
+                AuditEntry {
+                    timestamp_ms: report.finished_ms,
+                    action: action.to_string(),
+                    origin: "sync".to_string(),
+                    collection_id,
+                    details: Map::from_iter([("path".to_string(), Value::String(path.clone()))]),
+                }
+            })
+        })
+        .collect();
+    if entries.is_empty() {
+        return;
+    }
+    if let Err(e) = append(app, &entries) {
+        tracing::warn!("{e}");
+    }
+}
+
+/// Audit entries of the current workspace matching `filter`, newest first.
+#[tauri::command]
+pub async fn get_audit_log(
+    app: tauri::AppHandle,
+    filter: Option<AuditFilter>,
+) -> Result<Vec<AuditEntry>, String> {
+    let filter = filter.unwrap_or_default();
+    let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, 5000);
+    let path = log_path(&app)?;
+    let file = match fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(format!("audit log read failed: {e}")),
+    };
+    let mut entries: Vec<AuditEntry> = BufReader::new(file)
+        .lines()
+        .map_while(Result::ok)
+        // A line cut short by a crash mid-append is skipped.
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(&line).ok())
+        .filter(|entry| filter.matches(entry))
+        .collect();
+    // Appended as they happen, so the file is already in time order.
+    entries.reverse();
+    entries.truncate(limit);
+    Ok(entries)
+}
@@ -0,0 +1,267 @@
+//! Suggestions for the URL and header fields: hosts, paths, URLs, header names and header
+//! values seen in the workspace's requests and its history, ranked by how often each was
+//! used. The index lives in memory and is rebuilt when the collections or the history
+//! changed, checked at most every `CHECK_AFTER_MS`, so typing only costs a lookup and the
+//! history never has to reach the frontend. Secret-flagged header values stay out.
+
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+use crate::importers::{array_of, str_of};
+
+const CHECK_AFTER_MS: u64 = 5_000;
+/// Newest history entries the index covers.
+const HISTORY_SCAN: i64 = 5_000;
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+/// Offered even before they're used, below anything that was.
+const COMMON_HEADERS: &[&str] = &[
+    "Accept",
+    "Accept-Encoding",
+    "Accept-Language",
+    "Authorization",
+    "Cache-Control",
+    "Content-Type",
+    "Cookie",
+    "If-Match",
+    "If-None-Match",
+    "Origin",
+    "Referer",
+    "User-Agent",
+    "X-API-Key",
+    "X-Request-ID",
+];
+const COMMON_WEIGHT: f64 = 0.1;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    Host,
+    Path,
+    Url,
+    HeaderName,
+    HeaderValue,
+}
+
+#[derive(Serialize)]
+pub struct Suggestion {
+    pub value: String,
+    /// Times it was used: in saved requests and sends in the history.
+    pub uses: f64,
+}
+
+/// Terms per kind; header values are keyed by the lowercased header name too.
+#[derive(Default)]
+struct Index {
+    terms: HashMap<(SuggestionKind, String), HashMap<String, f64>>,
+    /// What the index was built from: the workspace, its collections, the newest history id.
+    source: (String, i64, i64),
+    checked_ms: u64,
+}
+
+impl Index {
+    fn add(&mut self, kind: SuggestionKind, scope: &str, value: &str, weight: f64) {
+        let value = value.trim();
+        if value.is_empty() {
+            return;
+        }
+        *self
+            .terms
+            .entry((kind, scope.to_string()))
+            .or_default()
+            .entry(value.to_string())
+            .or_insert(0.0) += weight;
+    }
+
+    fn add_url(&mut self, url: &str, weight: f64) {
+        let url = url.trim();
+        if url.is_empty() {
+            return;
+        }
+        self.add(SuggestionKind::Url, "", url, weight);
+        let (host, path) = split_url(url);
+        if let Some(host) = host {
+            self.add(SuggestionKind::Host, "", host, weight);
+        }
+        if path.len() > 1 {
+            self.add(SuggestionKind::Path, "", path, weight);
+        }
+    }
+}
+
+pub struct AutocompleteState {
+    index: Mutex<Index>,
+}
+
+impl AutocompleteState {
+    pub fn new() -> Self {
+        Self {
+            index: Mutex::new(Index::default()),
+        }
+    }
+}
+
+/// The host and the path (without query or fragment) of `url`, which may start with a
+/// `{{baseUrl}}`-style variable instead of a scheme and host.
+fn split_url(url: &str) -> (Option<&str>, &str) {
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest,
+        None if url.starts_with("{{") => url.split_once("}}").map_or(url, |(_, rest)| rest),
+        None => url,
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(slash) => (&rest[..slash], &rest[slash..]),
+        None => (rest, ""),
+    };
+    let path = path.split(['?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = (url.contains("://") && !host.is_empty()).then_some(host);
+    (host, path)
+}
+
+fn add_requests(index: &mut Index, items: &[Value]) {
+    for item in items {
+        if let Some(children) = item.get("items").and_then(Value::as_array) {
+            add_requests(index, children);
+            continue;
+        }
+        index.add_url(str_of(item, "url"), 1.0);
+        let headers = item.get("headers").and_then(Value::as_object);
+        for (name, value) in headers.into_iter().flatten() {
+            index.add(SuggestionKind::HeaderName, "", name, 1.0);
+            let secret = item
+                .pointer(&format!("/secret_headers/{}", name.replace('~', "~0")))
+                .and_then(Value::as_bool)
+                .unwrap_or(false);
+            if !secret {
+                let value = value.as_str().unwrap_or_default();
+                index.add(
+                    SuggestionKind::HeaderValue,
+                    &name.to_lowercase(),
+                    value,
+                    1.0,
+                );
+            }
+        }
+    }
+}
+
+/// The workspace key and the newest history id, and the history's URLs (newest
+/// `HISTORY_SCAN`) when `urls` is set.
+fn history_state(app: &tauri::AppHandle, urls: bool) -> Result<(String, i64, Vec<String>), String> {
+    let failed = |e: rusqlite::Error| format!("autocomplete failed: {e}");
+    let workspace = crate::history::workspace_key(app)?;
+    let conn = crate::history::open(app)?;
+    let newest: i64 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(id), 0) FROM entries WHERE workspace = ?1",
+            [&workspace],
+            |row| row.get(0),
+        )
+        .map_err(failed)?;
+    if !urls {
+        return Ok((workspace, newest, Vec::new()));
+    }
+    let mut statement = conn
+        .prepare("SELECT url FROM entries WHERE workspace = ?1 ORDER BY id DESC LIMIT ?2")
+        .map_err(failed)?;
+    let urls = statement
+        .query_map(params![workspace, HISTORY_SCAN], |row| row.get(0))
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<String>>>())
+        .map_err(failed)?;
+    Ok((workspace, newest, urls))
+}
+
+async fn history(app: &tauri::AppHandle, urls: bool) -> Result<(String, i64, Vec<String>), String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || history_state(&app, urls))
+        .await
+        .map_err(|e| format!("autocomplete failed: {e}"))?
+}
+
+/// Rebuilds `index` if the workspace, its collections or its history changed.
+async fn refresh(app: &tauri::AppHandle, index: &mut Index) -> Result<(), String> {
+    let now = crate::now_ms();
+    if now.saturating_sub(index.checked_ms) < CHECK_AFTER_MS {
+        return Ok(());
+    }
+    let collections = crate::backend_get(app, "/collections").await?;
+    let (workspace, newest, _) = history(app, false).await?;
+    let source = (workspace, crate::search::fingerprint(&collections), newest);
+    if index.checked_ms > 0 && index.source == source {
+        index.checked_ms = now;
+        return Ok(());
+    }
+
+    let mut fresh = Index::default();
+    for name in COMMON_HEADERS {
+        fresh.add(SuggestionKind::HeaderName, "", name, COMMON_WEIGHT);
+    }
+    for meta in collections.as_array().into_iter().flatten() {
+        let id = str_of(meta, "id");
+        let collection = crate::backend_get(app, &format!("/collections/{id}/collection")).await?;
+        add_requests(&mut fresh, array_of(&collection, "items"));
+    }
+    for url in history(app, true).await?.2 {
+        fresh.add_url(&url, 1.0);
+    }
+    fresh.source = source;
+    fresh.checked_ms = now;
+    *index = fresh;
+    Ok(())
+}
+
+/// Suggestions for `prefix` (case-insensitive), most used first; `limit` of them (10 by
+/// default, 50 at most). URLs also match past their scheme, and `header_value` needs the
+/// `header` whose values to suggest.
+#[tauri::command]
+pub async fn autocomplete(
+    app: tauri::AppHandle,
+    state: State<'_, AutocompleteState>,
+    prefix: String,
+    kind: SuggestionKind,
+    header: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<Suggestion>, String> {
+    let scope = match kind {
+        SuggestionKind::HeaderValue => header
+            .map(|h| h.trim().to_lowercase())
+            .filter(|h| !h.is_empty())
+            .ok_or("header value suggestions need a header")?,
+        _ => String::new(),
+    };
+    let mut index = state.index.lock().await;
+    refresh(&app, &mut index).await?;
+    let prefix = prefix.trim_start().to_lowercase();
+    let matches = |value: &str| {
+        let value = value.to_lowercase();
+        value.starts_with(&prefix)
+            || (kind == SuggestionKind::Url
+                && value
+                    .split_once("://")
+                    .is_some_and(|(_, rest)| rest.starts_with(&prefix)))
+    };
+    let mut suggestions: Vec<Suggestion> = index
+        .terms
+        .get(&(kind, scope))
+        .into_iter()
+        .flatten()
+        .filter(|(value, _)| matches(value) && value.to_lowercase() != prefix)
+        .map(|(value, uses)| Suggestion {
+            value: value.clone(),
+            uses: *uses,
+        })
+        .collect();
+    suggestions.sort_by(|a, b| {
+        b.uses
+            .total_cmp(&a.uses)
+            .then(a.value.len().cmp(&b.value.len()))
+            .then(a.value.cmp(&b.value))
+    });
+    suggestions.truncate(limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT));
+    Ok(suggestions)
+}
@@ -0,0 +1,352 @@
+//! Automatic workspace backups: a zip snapshot of the workspace files, taken on a schedule
+//! (skipped while nothing changed) and on demand, kept under `backups` in the app data root
+//! with the oldest rotated out. Restoring puts the workspace back exactly as snapshotted,
+//! after snapshotting the current state, so a restore can itself be undone.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::async_runtime::Mutex;
+use tauri::{Manager, State};
+
+const SETTINGS_FILE: &str = "backups.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// Left out of snapshots: git's own data, caches that are rebuilt on demand and the instance lock.
+const SKIPPED: &[&str] = &[
+    ".git",
+    ".litefetch/responses",
+    ".litefetch/search",
+    ".litefetch/instance.lock",
+];
+
+pub struct BackupState {
+    /// Held while a snapshot or restore touches the workspace.
+    busy: Mutex<()>,
+}
+
+impl BackupState {
+    pub fn new() -> Self {
+        Self {
+            busy: Mutex::new(()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_minutes: u64,
+    /// Snapshots kept per workspace.
+    pub keep: usize,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_minutes: 60,
+            keep: 24,
+        }
+    }
+}
+
+impl BackupSettings {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(crate::app_data_root(app)?.join(SETTINGS_FILE))
+    }
+
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data =
+            fs::read_to_string(&path).map_err(|e| format!("backup settings read failed: {e}"))?;
+        serde_json::from_str::<Self>(&data)
+            .map(Self::clamped)
+            .map_err(|e| format!("backup settings parse failed: {e}"))
+    }
+
+    fn clamped(self) -> Self {
+        Self {
+            enabled: self.enabled,
+            interval_minutes: self.interval_minutes.clamp(5, 7 * 24 * 60),
+            keep: self.keep.clamp(1, 500),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Backup {
+    pub id: String,
+    pub created_ms: u64,
+    /// `scheduled`, `manual` or `pre-restore`.
+    pub reason: String,
+    pub files: usize,
+    pub bytes: u64,
+    /// Digest of the snapshotted contents, to skip snapshots of an unchanged workspace.
+    pub fingerprint: String,
+}
+
+#[derive(Serialize)]
+pub struct RestoreReport {
+    pub restored: usize,
+    pub removed: usize,
+    /// The snapshot of the workspace as it was before the restore; `None` when the latest
+    /// snapshot already matched it.
+    pub previous: Option<Backup>,
+}
+
+/// Snapshots of the current workspace, in a directory of their own per workspace.
+fn backups_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let workspace = crate::history::workspace_key(app)?;
+    let digest = hex::encode(Sha256::digest(workspace.as_bytes()));
+    let dir = crate::app_data_root(app)?
+        .join("backups")
+        .join(&digest[..16]);
+    fs::create_dir_all(&dir).map_err(|e| format!("backups init failed: {e}"))?;
+    Ok(dir)
+}
+
+fn skipped(rel: &str) -> bool {
+    SKIPPED
+        .iter()
+        .any(|s| rel == *s || rel.starts_with(&format!("{s}/")))
+}
+
+/// The workspace files to snapshot, by `/`-separated relative path, in path order.
+fn scan(root: &Path) -> Result<BTreeMap<String, PathBuf>, String> {
+    let mut files = BTreeMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir).map_err(|e| format!("workspace read failed: {e}"))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let rel = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            if skipped(&rel) {
+                continue;
+            }
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() => pending.push(path),
+                Ok(kind) if kind.is_file() => {
+                    files.insert(rel, path);
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn list(dir: &Path) -> Vec<Backup> {
+    let mut backups: Vec<Backup> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|path| serde_json::from_str(&fs::read_to_string(path).ok()?).ok())
+        .collect();
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_ms));
+    backups
+}
+
+/// Snapshots the workspace unless it is unchanged since the latest snapshot, then rotates.
+fn snapshot(app: &tauri::AppHandle, reason: &str) -> Result<Option<Backup>, String> {
+    let root = crate::load_workspace_path(app)?;
+    let dir = backups_dir(app)?;
+    let files = scan(&root)?;
+    let mut contents = Vec::with_capacity(files.len());
+    let mut hasher = Sha256::new();
+    for (rel, path) in &files {
+        let Ok(data) = fs::read(path) else {
+            continue;
+        };
+        hasher.update(rel.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(&data));
+        contents.push((rel, data));
+    }
+    let fingerprint = hex::encode(hasher.finalize());
+    let existing = list(&dir);
+    if existing
+        .first()
+        .is_some_and(|latest| latest.fingerprint == fingerprint)
+    {
+        return Ok(None);
+    }
+
+    let created_ms = crate::now_ms();
+    let id = created_ms.to_string();
+    let fail = |e: &dyn std::fmt::Display| format!("backup failed: {e}");
+    let archive = dir.join(format!("{id}.zip"));
+    let file = fs::File::create(&archive).map_err(|e| fail(&e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (rel, data) in &contents {
+        zip.start_file(rel.as_str(), options)
+            .map_err(|e| fail(&e))?;
+        zip.write_all(data).map_err(|e| fail(&e))?;
+    }
+    zip.finish().map_err(|e| fail(&e))?;
+
+    let backup = Backup {
+        id: id.clone(),
+        created_ms,
+        reason: reason.to_string(),
+        files: contents.len(),
+        bytes: fs::metadata(&archive).map(|m| m.len()).unwrap_or(0),
+        fingerprint,
+    };
+    let meta = serde_json::to_string_pretty(&backup).map_err(|e| fail(&e))?;
+    fs::write(dir.join(format!("{id}.json")), meta).map_err(|e| fail(&e))?;
+
+    let keep = BackupSettings::load(app)?.keep;
+    for old in list(&dir).into_iter().skip(keep) {
+        let _ = fs::remove_file(dir.join(format!("{}.zip", old.id)));
+        let _ = fs::remove_file(dir.join(format!("{}.json", old.id)));
+    }
+    Ok(Some(backup))
+}
+
+async fn snapshot_now(
+    app: &tauri::AppHandle,
+    reason: &'static str,
+) -> Result<Option<Backup>, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || snapshot(&app, reason))
+        .await
+        .map_err(|e| format!("backup failed: {e}"))?
+}
+
+/// Takes scheduled snapshots for as long as the app runs, per the current settings.
+pub async fn backup_periodically(app: tauri::AppHandle) {
+    let mut ticks = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        ticks.tick().await;
+        let settings = match BackupSettings::load(&app) {
+            Ok(settings) => settings,
+            Err(e) => {
+                tracing::warn!("{e}");
+                continue;
+            }
+        };
+        if !settings.enabled {
+            continue;
+        }
+        let latest = backups_dir(&app)
+            .map(|dir| list(&dir).first().map_or(0, |b| b.created_ms))
+            .unwrap_or(0);
+        if crate::now_ms().saturating_sub(latest) < settings.interval_minutes * 60_000 {
+            continue;
+        }
+        let state = app.state::<BackupState>();
+        let _busy = state.busy.lock().await;
+        if let Err(e) = snapshot_now(&app, "scheduled").await {
+            tracing::warn!("{e}");
+        }
+    }
+}
+
+/// The current workspace's snapshots, newest first.
+#[tauri::command]
+pub async fn list_backups(app: tauri::AppHandle) -> Result<Vec<Backup>, String> {
+    Ok(list(&backups_dir(&app)?))
+}
+
+/// Snapshots the workspace now; `None` when it is unchanged since the latest snapshot.
+#[tauri::command]
+pub async fn create_backup(
+    app: tauri::AppHandle,
+    state: State<'_, BackupState>,
+) -> Result<Option<Backup>, String> {
+    let _busy = state.busy.lock().await;
+    snapshot_now(&app, "manual").await
+}
+
+/// Puts the workspace back as it was in snapshot `id`: its files are rewritten and files
+/// added since are removed.
+#[tauri::command]
+pub async fn restore_backup(
+    app: tauri::AppHandle,
+    state: State<'_, BackupState>,
+    id: String,
+) -> Result<RestoreReport, String> {
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("unknown backup: {id}"));
+    }
+    crate::lock::ensure_writable(&app)?;
+    let _busy = state.busy.lock().await;
+    let archive = backups_dir(&app)?.join(format!("{id}.zip"));
+    if !archive.exists() {
+        return Err(format!("unknown backup: {id}"));
+    }
+    let previous = snapshot_now(&app, "pre-restore").await?;
+    let root = crate::load_workspace_path(&app)?;
+    let (restored, removed) =
+        tauri::async_runtime::spawn_blocking(move || restore(&root, &archive))
+            .await
+            .map_err(|e| format!("restore failed: {e}"))??;
+    Ok(RestoreReport {
+        restored,
+        removed,
+        previous,
+    })
+}
+
+fn restore(root: &Path, archive: &Path) -> Result<(usize, usize), String> {
+    let fail = |e: &dyn std::fmt::Display| format!("restore failed: {e}");
+    let file = fs::File::open(archive).map_err(|e| fail(&e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| fail(&e))?;
+    let mut restored = HashSet::new();
+    for index in 0..zip.len() {
+        let mut member = zip.by_index(index).map_err(|e| fail(&e))?;
+        // Names that would land outside the workspace are ignored.
+        let Some(rel) = member.enclosed_name() else {
+            continue;
+        };
+        let mut data = Vec::new();
+        member.read_to_end(&mut data).map_err(|e| fail(&e))?;
+        let target = root.join(&rel);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|e| fail(&e))?;
+        }
+        fs::write(&target, data).map_err(|e| fail(&e))?;
+        restored.insert(rel.to_string_lossy().replace('\\', "/"));
+    }
+    let mut removed = 0;
+    for (rel, path) in scan(root)? {
+        if !restored.contains(&rel) && fs::remove_file(path).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok((restored.len(), removed))
+}
+
+#[tauri::command]
+pub async fn get_backup_settings(app: tauri::AppHandle) -> Result<BackupSettings, String> {
+    BackupSettings::load(&app)
+}
+
+#[tauri::command]
+pub async fn set_backup_settings(
+    app: tauri::AppHandle,
+    settings: BackupSettings,
+) -> Result<BackupSettings, String> {
+    let settings = settings.clamped();
+    let payload = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("backup settings serialize failed: {e}"))?;
+    fs::write(BackupSettings::path(&app)?, payload)
+        .map_err(|e| format!("backup settings persist failed: {e}"))?;
+    Ok(settings)
+}
@@ -0,0 +1,395 @@
+//! Bulk edits across the requests of one or more collections: set or remove a header, swap
+//! the base of every URL, find and replace text in URLs, headers, query values or bodies.
+//! A filter picks the requests (folder, methods, a path pattern, a header they carry). Every
+//! edit is previewed first: the result lists each affected request with its field changes,
+//! as `diff_collection` reports them, and nothing is saved unless `apply` is set.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use litefetch_core::collection_diff::{self, RequestChange};
+
+use crate::importers::str_of;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct BulkFilter {
+    /// Collections to edit; every collection in the workspace when omitted.
+    pub collection_ids: Option<Vec<String>>,
+    /// A folder path such as `Users/Admin`; requests in its subfolders match too.
+    pub folder: Option<String>,
+    /// Matched without case; any method when empty.
+    pub methods: Vec<String>,
+    /// A pattern over the URL's path, without its base: `*` stands for one segment and `**`
+    /// for any number, e.g. `/users/*` or `/admin/**`.
+    pub path_pattern: Option<String>,
+    /// Only requests that already send this header.
+    pub has_header: Option<String>,
+}
+
+/// Where a `replace` looks.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplaceTarget {
+    Url,
+    HeaderValues,
+    QueryValues,
+    Body,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BulkOperation {
+    /// Adds the header, or replaces its value where a request already has it.
+    SetHeader {
+        name: String,
+        value: String,
+    },
+    RemoveHeader {
+        name: String,
+    },
+    /// Replaces a URL prefix, e.g. `https://staging.example.com` with `{{baseUrl}}`.
+    RewriteBaseUrl {
+        from: String,
+        to: String,
+    },
+    Replace {
+        find: String,
+        replace: String,
+        /// `find` is a regular expression and `replace` may use `$1` groups.
+        #[serde(default)]
+        regex: bool,
+        /// Limits the replacement to one header's value.
+        #[serde(default)]
+        header: Option<String>,
+        targets: Vec<ReplaceTarget>,
+    },
+    SetQueryParam {
+        name: String,
+        value: String,
+    },
+    RemoveQueryParam {
+        name: String,
+    },
+}
+
+#[derive(Serialize)]
+pub struct AffectedRequest {
+    pub collection_id: String,
+    #[serde(flatten)]
+    pub change: RequestChange,
+}
+
+#[derive(Serialize)]
+pub struct BulkEditResult {
+    /// `false` for a preview.
+    pub applied: bool,
+    pub affected: Vec<AffectedRequest>,
+    /// Requests the filter matched, changed or not.
+    pub matched: usize,
+}
+
+/// A compiled `path_pattern`.
+fn path_rule(pattern: &str) -> Result<Regex, String> {
+    let mut rule = String::from("^");
+    let pattern = pattern.trim().trim_end_matches('/');
+    let mut rest = pattern;
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix("**") {
+            rule.push_str(".*");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('*') {
+            rule.push_str("[^/]*");
+            rest = after;
+        } else {
+            let next = rest.find('*').unwrap_or(rest.len());
+            rule.push_str(&regex::escape(&rest[..next]));
+            rest = &rest[next..];
+        }
+    }
+    rule.push_str("/?$");
+    Regex::new(&rule).map_err(|e| format!("invalid path pattern: {e}"))
+}
+
+struct Matcher {
+    folder: Vec<String>,
+    methods: Vec<String>,
+    path: Option<Regex>,
+    has_header: Option<String>,
+}
+
+impl Matcher {
+    fn new(filter: &BulkFilter) -> Result<Self, String> {
+        Ok(Self {
+            folder: filter
+                .folder
+                .as_deref()
+                .unwrap_or_default()
+                .split('/')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect(),
+            methods: filter.methods.iter().map(|m| m.to_uppercase()).collect(),
+            path: filter
+                .path_pattern
+                .as_deref()
+                .filter(|p| !p.trim().is_empty())
+                .map(path_rule)
+                .transpose()?,
+            has_header: filter
+                .has_header
+                .as_deref()
+                .map(str::trim)
+                .filter(|h| !h.is_empty())
+                .map(str::to_string),
+        })
+    }
+
+    fn matches(&self, folder: &[String], request: &Value) -> bool {
+        folder.starts_with(&self.folder)
+            && (self.methods.is_empty()
+                || self
+                    .methods
+                    .contains(&str_of(request, "method").to_uppercase()))
+            && self
+                .path
+                .as_ref()
+                .is_none_or(|rule| rule.is_match(crate::mock::path_of(str_of(request, "url"))))
+            && self
+                .has_header
+                .as_deref()
+                .is_none_or(|name| header_key(request, name).is_some())
+    }
+}
+
+fn header_key(request: &Value, name: &str) -> Option<String> {
+    request
+        .get("headers")
+        .and_then(Value::as_object)?
+        .keys()
+        .find(|k| k.eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+enum Finder {
+    Text(String),
+    Pattern(Regex),
+}
+
+impl Finder {
+    fn apply(&self, text: &str, replace: &str) -> String {
+        match self {
+            Finder::Text(find) => text.replace(find.as_str(), replace),
+            Finder::Pattern(rule) => rule.replace_all(text, replace).into_owned(),
+        }
+    }
+}
+
+fn replace_in(value: &mut Value, finder: &Finder, replace: &str) {
+    if let Value::String(text) = value {
+        *text = finder.apply(text, replace);
+    }
+}
+
+/// Applies `operation` to one request.
+fn edit(request: &mut Value, operation: &BulkOperation, finder: Option<&Finder>) {
+    match operation {
+        BulkOperation::SetHeader { name, value } => {
+            let name = name.trim();
+            if !request.get("headers").is_some_and(Value::is_object) {
+                request["headers"] = Value::Object(Map::new());
+            }
+            let key = header_key(request, name).unwrap_or_else(|| name.to_string());
+            request["headers"][&key] = Value::String(value.clone());
+        }
+        BulkOperation::RemoveHeader { name } => {
+            if let Some(headers) = request.get_mut("headers").and_then(Value::as_object_mut) {
+                headers.retain(|k, _| !k.eq_ignore_ascii_case(name.trim()));
+            }
+        }
+        BulkOperation::RewriteBaseUrl { from, to } => {
+            let url = str_of(request, "url");
+            if let Some(rest) = url.strip_prefix(from.as_str()) {
+                request["url"] = Value::String(format!("{to}{rest}"));
+            }
+        }
+        BulkOperation::Replace {
+            replace,
+            header,
+            targets,
+            ..
+        } => {
+            let Some(finder) = finder else {
+                return;
+            };
+            for target in targets {
+                match target {
+                    ReplaceTarget::Url => {
+                        if let Some(url) = request.get_mut("url") {
+                            replace_in(url, finder, replace);
+                        }
+                    }
+                    ReplaceTarget::HeaderValues => {
+                        let Some(headers) =
+                            request.get_mut("headers").and_then(Value::as_object_mut)
+                        else {
+                            continue;
+                        };
+                        for (name, value) in headers.iter_mut() {
+                            if header
+                                .as_deref()
+                                .is_none_or(|only| name.eq_ignore_ascii_case(only.trim()))
+                            {
+                                replace_in(value, finder, replace);
+                            }
+                        }
+                    }
+                    ReplaceTarget::QueryValues => {
+                        if let Some(rows) = request
+                            .get_mut("query_params")
+                            .and_then(Value::as_array_mut)
+                        {
+                            for row in rows {
+                                if let Some(value) = row.get_mut("value") {
+                                    replace_in(value, finder, replace);
+                                }
+                            }
+                        }
+                    }
+                    ReplaceTarget::Body => {
+                        if let Some(body) = request.get_mut("body") {
+                            replace_in(body, finder, replace);
+                        }
+                    }
+                }
+            }
+        }
+        BulkOperation::SetQueryParam { name, value } => {
+            let name = name.trim();
+            match request
+                .get_mut("query_params")
+                .and_then(Value::as_array_mut)
+            {
+                Some(rows) => match rows.iter_mut().find(|r| str_of(r, "key") == name) {
+                    Some(row) => {
+                        row["value"] = Value::String(value.clone());
+                        row["enabled"] = Value::Bool(true);
+                    }
+                    None => rows.push(json!({ "key": name, "value": value, "enabled": true })),
+                },
+                None => {
+                    request["query_params"] =
+                        json!([{ "key": name, "value": value, "enabled": true }]);
+                }
+            }
+        }
+        BulkOperation::RemoveQueryParam { name } => {
+            if let Some(rows) = request
+                .get_mut("query_params")
+                .and_then(Value::as_array_mut)
+            {
+                rows.retain(|r| str_of(r, "key") != name.trim());
+            }
+        }
+    }
+}
+
+fn edit_items(
+    items: &mut [Value],
+    folder: &mut Vec<String>,
+    matcher: &Matcher,
+    apply: &dyn Fn(&mut Value),
+) -> usize {
+    let mut matched = 0;
+    for item in items {
+        if item.get("items").is_some_and(Value::is_array) {
+            folder.push(str_of(item, "name").to_string());
+            if let Some(children) = item.get_mut("items").and_then(Value::as_array_mut) {
+                matched += edit_items(children, folder, matcher, apply);
+            }
+            folder.pop();
+            continue;
+        }
+        if matcher.matches(folder, item) {
+            apply(item);
+            matched += 1;
+        }
+    }
+    matched
+}
+
+/// Applies `operation` to every request `filter` matches. Without `apply` this is a dry run
+/// that only reports what would change.
+#[tauri::command]
+pub async fn bulk_edit(
+    app: tauri::AppHandle,
+    filter: BulkFilter,
+    operation: BulkOperation,
+    apply: Option<bool>,
+) -> Result<BulkEditResult, String> {
+    let apply = apply.unwrap_or(false);
+    if apply {
+        crate::lock::ensure_writable(&app)?;
+    }
+    let matcher = Matcher::new(&filter)?;
+    let finder = match &operation {
+        BulkOperation::Replace { find, .. } if find.is_empty() => {
+            return Err("nothing to find".to_string())
+        }
+        BulkOperation::Replace { find, regex, .. } => Some(match regex {
+            true => Finder::Pattern(Regex::new(find).map_err(|e| format!("invalid regex: {e}"))?),
+            false => Finder::Text(find.clone()),
+        }),
+        BulkOperation::SetHeader { name, .. } | BulkOperation::SetQueryParam { name, .. }
+            if name.trim().is_empty() =>
+        {
+            return Err("a name is required".to_string())
+        }
+        BulkOperation::RewriteBaseUrl { from, .. } if from.is_empty() => {
+            return Err("the base URL to replace is required".to_string())
+        }
+        _ => None,
+    };
+    let ids: Vec<String> = match filter.collection_ids {
+        Some(ids) => ids,
+        None => crate::backend_get(&app, "/collections")
+            .await?
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|meta| str_of(meta, "id").to_string())
+            .collect(),
+    };
+
+    let mut result = BulkEditResult {
+        applied: apply,
+        affected: Vec::new(),
+        matched: 0,
+    };
+    for collection_id in ids {
+        let path = format!("/collections/{collection_id}/collection");
+        let before = crate::backend_get(&app, &path).await?;
+        let mut after = before.clone();
+        if let Some(items) = after.get_mut("items").and_then(Value::as_array_mut) {
+            result.matched += edit_items(items, &mut Vec::new(), &matcher, &|request| {
+                edit(request, &operation, finder.as_ref())
+            });
+        }
+        let changes = collection_diff::diff(&before, &after).changed;
+        if changes.is_empty() {
+            continue;
+        }
+        if apply {
+            crate::backend_post(&app, &path, &after).await?;
+        }
+        result
+            .affected
+            .extend(changes.into_iter().map(|change| AffectedRequest {
+                collection_id: collection_id.clone(),
+                change,
+            }));
+    }
+    Ok(result)
+}
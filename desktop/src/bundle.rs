@@ -0,0 +1,586 @@
+//! Workspace bundles: the whole workspace — collections with their environments, cookies and
+//! the workspace settings files — in a single zip, for moving it to another machine or
+//! handing it to someone. Secret values never go in as plaintext: they are masked, or, with a
+//! passphrase, kept in `secrets.age` encrypted with it.
+//!
+//! Importing starts with `preview_workspace_bundle`; `import_workspace_bundle` then merges the
+//! bundle into the workspace (same-named collections are overwritten, the rest kept) or
+//! replaces the workspace with it.
+
+use age::secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::importers::{array_of, str_of};
+use litefetch_core::json::count_requests;
+
+const FORMAT: &str = "litefetch-bundle";
+const VERSION: u64 = 1;
+const MANIFEST: &str = "manifest.json";
+const SECRETS: &str = "secrets.age";
+
+/// The workspace settings files a bundle carries.
+pub(crate) fn settings_files() -> [&'static str; 3] {
+    [
+        crate::responses::SETTINGS_FILE,
+        crate::defaults::SETTINGS_FILE,
+        crate::network::SETTINGS_FILE,
+    ]
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct BundleOptions {
+    /// Collections to export; all of them when omitted.
+    pub collections: Option<Vec<String>>,
+    pub exclude_cookies: bool,
+    pub exclude_settings: bool,
+    /// Encrypts the secret values into the bundle instead of masking them.
+    pub secrets_passphrase: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct BundledCollection {
+    pub id: String,
+    pub name: String,
+    pub requests: usize,
+    pub environments: usize,
+    pub cookies: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Manifest {
+    format: String,
+    version: u64,
+    created_ms: u64,
+    collections: Vec<BundledCollection>,
+    settings: Vec<String>,
+    secrets_encrypted: bool,
+}
+
+#[derive(Serialize)]
+pub struct BundleSummary {
+    pub path: String,
+    pub bytes: u64,
+    pub collections: Vec<BundledCollection>,
+    pub settings: Vec<String>,
+    pub secrets: usize,
+}
+
+#[derive(Serialize)]
+pub struct PreviewCollection {
+    #[serde(flatten)]
+    pub collection: BundledCollection,
+    /// A workspace collection of the same name, which a merge would overwrite.
+    pub existing_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct BundlePreview {
+    pub created_ms: u64,
+    pub collections: Vec<PreviewCollection>,
+    pub settings: Vec<String>,
+    pub secrets_encrypted: bool,
+    /// Workspace collections a replace would delete.
+    pub workspace_collections: usize,
+}
+
+#[derive(Serialize, Default)]
+pub struct BundleImportSummary {
+    pub created: Vec<String>,
+    pub overwritten: Vec<String>,
+    pub removed: usize,
+    pub cookies: usize,
+    pub settings: Vec<String>,
+    pub secrets: usize,
+    /// Parts that could not be imported; the rest of the bundle still was.
+    pub warnings: Vec<String>,
+}
+
+/// Secret values by collection id: environment secrets by environment and key, and the
+/// collection with its secret request fields unmasked.
+#[derive(Serialize, Deserialize, Default)]
+struct Secrets(HashMap<String, CollectionSecrets>);
+
+#[derive(Serialize, Deserialize, Default)]
+struct CollectionSecrets {
+    environments: HashMap<String, HashMap<String, String>>,
+    collection: Value,
+}
+
+/// One collection as read from the backend or a bundle.
+struct Entry {
+    meta: BundledCollection,
+    collection: Value,
+    environment: Value,
+    /// Cookie lists by environment.
+    cookies: Map<String, Value>,
+}
+
+fn env_names(environment: &Value) -> Vec<String> {
+    environment
+        .get("envs")
+        .and_then(Value::as_object)
+        .map(|envs| envs.keys().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn encode_query(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+async fn read_workspace(
+    app: &tauri::AppHandle,
+    options: &BundleOptions,
+    secrets: &mut Option<Secrets>,
+) -> Result<Vec<Entry>, String> {
+    let metas = crate::backend_get(app, "/collections").await?;
+    let mut entries = Vec::new();
+    for meta in metas.as_array().into_iter().flatten() {
+        let id = str_of(meta, "id").to_string();
+        if options
+            .collections
+            .as_ref()
+            .is_some_and(|wanted| !wanted.contains(&id))
+        {
+            continue;
+        }
+        let mut collection =
+            crate::backend_get(app, &format!("/collections/{id}/collection")).await?;
+        let mut environment =
+            crate::backend_get(app, &format!("/collections/{id}/environment")).await?;
+        let names = env_names(&environment);
+
+        if let Some(secrets) = secrets.as_mut() {
+            let mut environments = HashMap::new();
+            for name in &names {
+                let values: HashMap<String, String> =
+                    crate::secrets::resolve_environment(&id, &environment, name)?
+                        .into_iter()
+                        .filter(|v| v.secret && !v.value.is_empty())
+                        .map(|v| (v.key, v.value))
+                        .collect();
+                if !values.is_empty() {
+                    environments.insert(name.clone(), values);
+                }
+            }
+            secrets.0.insert(
+                id.clone(),
+                CollectionSecrets {
+                    environments,
+                    collection: collection.clone(),
+                },
+            );
+        }
+        let redactor = crate::redact::for_export(app, &id, &collection, &environment, None).await?;
+        redactor.value(&mut collection);
+        redactor.value(&mut environment);
+
+        let mut cookies = Map::new();
+        if !options.exclude_cookies {
+            for name in &names {
+                let list = crate::backend_get(
+                    app,
+                    &format!("/collections/{id}/cookies?env={}", encode_query(name)),
+                )
+                .await?;
+                if list.as_array().is_some_and(|l| !l.is_empty()) {
+                    cookies.insert(name.clone(), list);
+                }
+            }
+        }
+        entries.push(Entry {
+            meta: BundledCollection {
+                name: str_of(meta, "name").to_string(),
+                requests: count_requests(array_of(&collection, "items")),
+                environments: names.len(),
+                cookies: cookies
+                    .values()
+                    .filter_map(Value::as_array)
+                    .map(Vec::len)
+                    .sum(),
+                id,
+            },
+            collection,
+            environment,
+            cookies,
+        });
+    }
+    Ok(entries)
+}
+
+fn secret_count(secrets: &Secrets) -> usize {
+    secrets
+        .0
+        .values()
+        .flat_map(|c| c.environments.values())
+        .map(HashMap::len)
+        .sum()
+}
+
+/// Writes the workspace bundle to `path` (a `.zip`).
+#[tauri::command]
+pub async fn export_workspace_bundle(
+    app: tauri::AppHandle,
+    path: String,
+    options: Option<BundleOptions>,
+) -> Result<BundleSummary, String> {
+    let options = options.unwrap_or_default();
+    let passphrase = options.secrets_passphrase.clone().filter(|p| !p.is_empty());
+    let mut secrets = passphrase.as_ref().map(|_| Secrets::default());
+    let entries = read_workspace(&app, &options, &mut secrets).await?;
+
+    let workspace = crate::load_workspace_path(&app)?;
+    let mut settings = Vec::new();
+    if !options.exclude_settings {
+        for name in settings_files() {
+            if let Ok(data) = fs::read(workspace.join(name)) {
+                settings.push((name.to_string(), data));
+            }
+        }
+    }
+    let sealed = match (&secrets, passphrase) {
+        (Some(secrets), Some(passphrase)) => {
+            let payload =
+                serde_json::to_vec(secrets).map_err(|e| format!("bundle serialize failed: {e}"))?;
+            let recipient = age::scrypt::Recipient::new(SecretString::from(passphrase));
+            Some(
+                age::encrypt(&recipient, &payload)
+                    .map_err(|e| format!("bundle encrypt failed: {e}"))?,
+            )
+        }
+        _ => None,
+    };
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        created_ms: crate::now_ms(),
+        collections: entries.iter().map(|e| e.meta.clone()).collect(),
+        settings: settings.iter().map(|(name, _)| name.clone()).collect(),
+        secrets_encrypted: sealed.is_some(),
+    };
+
+    let target = crate::normalize_path(path.trim());
+    let summary = BundleSummary {
+        path: target.to_string_lossy().to_string(),
+        bytes: 0,
+        collections: manifest.collections.clone(),
+        settings: manifest.settings.clone(),
+        secrets: secrets.as_ref().map_or(0, secret_count),
+    };
+    let bytes = tauri::async_runtime::spawn_blocking(move || {
+        write_zip(&target, &manifest, &entries, &settings, sealed.as_deref())
+    })
+    .await
+    .map_err(|e| format!("bundle export failed: {e}"))??;
+    Ok(BundleSummary { bytes, ..summary })
+}
+
+fn write_zip(
+    target: &Path,
+    manifest: &Manifest,
+    entries: &[Entry],
+    settings: &[(String, Vec<u8>)],
+    sealed: Option<&[u8]>,
+) -> Result<u64, String> {
+    let fail = |e: &dyn std::fmt::Display| format!("bundle export failed: {e}");
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| fail(&e))?;
+    }
+    let file = fs::File::create(target).map_err(|e| fail(&e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut add = |name: &str, data: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options).map_err(|e| fail(&e))?;
+        zip.write_all(data).map_err(|e| fail(&e))
+    };
+    add(MANIFEST, &pretty(manifest)?)?;
+    for entry in entries {
+        let dir = format!("collections/{}", entry.meta.id);
+        add(
+            &format!("{dir}/collection.json"),
+            &pretty(&entry.collection)?,
+        )?;
+        add(
+            &format!("{dir}/environment.json"),
+            &pretty(&entry.environment)?,
+        )?;
+        if !entry.cookies.is_empty() {
+            add(&format!("{dir}/cookies.json"), &pretty(&entry.cookies)?)?;
+        }
+    }
+    for (name, data) in settings {
+        add(&format!("settings/{name}"), data)?;
+    }
+    if let Some(sealed) = sealed {
+        add(SECRETS, sealed)?;
+    }
+    zip.finish().map_err(|e| fail(&e))?;
+    fs::metadata(target).map(|m| m.len()).map_err(|e| fail(&e))
+}
+
+fn pretty<T: Serialize>(value: &T) -> Result<Vec<u8>, String> {
+    serde_json::to_vec_pretty(value).map_err(|e| format!("bundle serialize failed: {e}"))
+}
+
+struct Bundle {
+    manifest: Manifest,
+    entries: Vec<Entry>,
+    settings: Vec<(String, Vec<u8>)>,
+    sealed: Option<Vec<u8>>,
+}
+
+fn read_bundle(path: &str) -> Result<Bundle, String> {
+    let fail = |e: &dyn std::fmt::Display| format!("bundle read failed: {e}");
+    let file = fs::File::open(crate::normalize_path(path.trim())).map_err(|e| fail(&e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| fail(&e))?;
+    let mut read = |name: &str| -> Result<Option<Vec<u8>>, String> {
+        let mut member = match zip.by_name(name) {
+            Ok(member) => member,
+            Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+            Err(e) => return Err(fail(&e)),
+        };
+        let mut data = Vec::new();
+        member.read_to_end(&mut data).map_err(|e| fail(&e))?;
+        Ok(Some(data))
+    };
+    let parse = |data: Vec<u8>| -> Result<Value, String> {
+        serde_json::from_slice(&data).map_err(|e| format!("bundle parse failed: {e}"))
+    };
+
+    let manifest: Manifest =
+        serde_json::from_slice(&read(MANIFEST)?.ok_or("not a LiteFetch workspace bundle")?)
+            .map_err(|e| format!("bundle parse failed: {e}"))?;
+    if manifest.format != FORMAT {
+        return Err("not a LiteFetch workspace bundle".into());
+    }
+    if manifest.version > VERSION {
+        return Err(format!(
+            "bundle version {} is newer than this app supports",
+            manifest.version
+        ));
+    }
+    let mut entries = Vec::new();
+    for meta in &manifest.collections {
+        let dir = format!("collections/{}", meta.id);
+        let member = |name: &str| format!("{dir}/{name}");
+        let collection = read(&member("collection.json"))?
+            .ok_or_else(|| format!("bundle is missing {}", member("collection.json")))?;
+        let environment = read(&member("environment.json"))?
+            .map(parse)
+            .transpose()?
+            .unwrap_or(Value::Null);
+        let cookies = read(&member("cookies.json"))?
+            .map(parse)
+            .transpose()?
+            .and_then(|v| v.as_object().cloned())
+            .unwrap_or_default();
+        entries.push(Entry {
+            meta: meta.clone(),
+            collection: parse(collection)?,
+            environment,
+            cookies,
+        });
+    }
+    let mut settings = Vec::new();
+    for name in &manifest.settings {
+        if let Some(data) = read(&format!("settings/{name}"))? {
+            settings.push((name.clone(), data));
+        }
+    }
+    let sealed = read(SECRETS)?;
+    Ok(Bundle {
+        manifest,
+        entries,
+        settings,
+        sealed,
+    })
+}
+
+/// Workspace collection ids by name.
+async fn existing_collections(app: &tauri::AppHandle) -> Result<HashMap<String, String>, String> {
+    let metas = crate::backend_get(app, "/collections").await?;
+    Ok(metas
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|meta| {
+            (
+                str_of(meta, "name").to_string(),
+                str_of(meta, "id").to_string(),
+            )
+        })
+        .collect())
+}
+
+async fn load_bundle(path: String) -> Result<Bundle, String> {
+    tauri::async_runtime::spawn_blocking(move || read_bundle(&path))
+        .await
+        .map_err(|e| format!("bundle read failed: {e}"))?
+}
+
+/// What importing the bundle at `path` would bring in, without changing anything.
+#[tauri::command]
+pub async fn preview_workspace_bundle(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<BundlePreview, String> {
+    let bundle = load_bundle(path).await?;
+    let existing = existing_collections(&app).await?;
+    Ok(BundlePreview {
+        created_ms: bundle.manifest.created_ms,
+        collections: bundle
+            .manifest
+            .collections
+            .into_iter()
+            .map(|collection| PreviewCollection {
+                existing_id: existing.get(&collection.name).cloned(),
+                collection,
+            })
+            .collect(),
+        settings: bundle.manifest.settings,
+        secrets_encrypted: bundle.manifest.secrets_encrypted,
+        workspace_collections: existing.len(),
+    })
+}
+
+/// Imports a bundle. `mode` is `merge` (the default) or `replace`, which first deletes every
+/// workspace collection. Settings files are overwritten on replace and only added on merge.
+/// Encrypted secrets are imported when `secrets_passphrase` is given.
+#[tauri::command]
+pub async fn import_workspace_bundle(
+    app: tauri::AppHandle,
+    path: String,
+    mode: Option<String>,
+    secrets_passphrase: Option<String>,
+) -> Result<BundleImportSummary, String> {
+    let replace = match mode.as_deref().unwrap_or("merge") {
+        "merge" => false,
+        "replace" => true,
+        other => return Err(format!("unknown import mode: {other}")),
+    };
+    crate::lock::ensure_writable(&app)?;
+    let bundle = load_bundle(path).await?;
+    let mut secrets = match (&bundle.sealed, secrets_passphrase.filter(|p| !p.is_empty())) {
+        (Some(sealed), Some(passphrase)) => {
+            let identity = age::scrypt::Identity::new(SecretString::from(passphrase));
+            let payload = age::decrypt(&identity, sealed)
+                .map_err(|_| "wrong passphrase for the bundle's secrets".to_string())?;
+            serde_json::from_slice(&payload).map_err(|e| format!("bundle parse failed: {e}"))?
+        }
+        _ => Secrets::default(),
+    };
+
+    let mut summary = BundleImportSummary::default();
+    let mut existing = existing_collections(&app).await?;
+    if replace {
+        let base_url = crate::backend_url(&app).await?;
+        for id in existing.values() {
+            let response = reqwest::Client::new()
+                .delete(format!("{base_url}/collections/{id}"))
+                .send()
+                .await
+                .map_err(|e| format!("collection delete failed: {e}"))?;
+            if !response.status().is_success() {
+                return Err(format!(
+                    "collection delete failed: HTTP {}",
+                    response.status()
+                ));
+            }
+            summary.removed += 1;
+        }
+        existing.clear();
+    }
+
+    for entry in bundle.entries {
+        let unsealed = secrets.0.remove(&entry.meta.id).unwrap_or_default();
+        let collection = if unsealed.collection.is_null() {
+            entry.collection
+        } else {
+            unsealed.collection
+        };
+        let mut environment = entry.environment;
+        if environment.is_null() {
+            environment = json!({ "active_env": "default", "envs": {} });
+        }
+        let name = entry.meta.name;
+        let id = match existing.get(&name) {
+            Some(id) => {
+                crate::import_post(&app, &format!("/collections/{id}/collection"), &collection)
+                    .await?;
+                crate::import_post(
+                    &app,
+                    &format!("/collections/{id}/environment"),
+                    &environment,
+                )
+                .await?;
+                summary.overwritten.push(name.clone());
+                id.clone()
+            }
+            None => {
+                let meta = crate::create_collection_with(
+                    &app,
+                    json!({ "name": name, "collection": collection, "environment": environment }),
+                )
+                .await?;
+                let id = str_of(&meta, "id").to_string();
+                existing.insert(name.clone(), id.clone());
+                summary.created.push(name.clone());
+                id
+            }
+        };
+
+        for (env, cookies) in &entry.cookies {
+            for cookie in cookies.as_array().into_iter().flatten() {
+                let path = format!("/collections/{id}/cookies?env={}", encode_query(env));
+                match crate::import_post(&app, &path, cookie).await {
+                    Ok(_) => summary.cookies += 1,
+                    Err(e) => summary
+                        .warnings
+                        .push(format!("cookie in {name}/{env} was not imported: {e}")),
+                }
+            }
+        }
+        for (env, values) in unsealed.environments {
+            for (key, value) in values {
+                match crate::secrets::store(&id, &env, &key, &value) {
+                    Ok(()) => summary.secrets += 1,
+                    Err(e) => summary.warnings.push(format!(
+                        "secret {key} in {name}/{env} was not imported: {e}"
+                    )),
+                }
+            }
+        }
+    }
+
+    let workspace = crate::load_workspace_path(&app)?;
+    for (name, data) in bundle.settings {
+        if !settings_files().contains(&name.as_str()) {
+            continue;
+        }
+        let target = workspace.join(&name);
+        if !replace && target.exists() {
+            continue;
+        }
+        match fs::write(&target, data) {
+            Ok(()) => summary.settings.push(name),
+            Err(e) => summary
+                .warnings
+                .push(format!("{name} was not imported: {e}")),
+        }
+    }
+    Ok(summary)
+}
@@ -0,0 +1,541 @@
+//! The capture proxy's traffic log, kept in SQLite (`captures.db` in the app data directory)
+//! so captures outlive the proxy and the app: one row per exchange with its method, host,
+//! path, status, response size and timing indexed for `search_captures`, and the whole
+//! exchange, bodies included, as JSON. The newest `LOG_LIMIT` exchanges are kept.
+//!
+//! Captures are promoted into a collection with `capture_to_collection` (see `proxy`),
+//! removed with `clear_captures`, and written out as HAR 1.2 with `export_captures_har`.
+
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::har::{
+    HarContent, HarCreator, HarEntry, HarFile, HarLog, HarPair, HarPostData, HarRequest,
+    HarResponse, HarTimings,
+};
+use crate::history::{like_patterns, status_bounds};
+use crate::proxy::CapturedExchange;
+
+const DB_FILE: &str = "captures.db";
+const LOG_LIMIT: u64 = 20_000;
+const DEFAULT_PAGE: u64 = 100;
+const MAX_PAGE: u64 = 1000;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS captures (
+        id TEXT PRIMARY KEY,
+        started_at_ms INTEGER NOT NULL,
+        method TEXT NOT NULL,
+        url TEXT NOT NULL,
+        host TEXT NOT NULL,
+        path TEXT NOT NULL,
+        status INTEGER,
+        size INTEGER NOT NULL,
+        duration_ms REAL NOT NULL,
+        tunneled INTEGER NOT NULL,
+        error TEXT,
+        exchange TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS captures_time ON captures (started_at_ms);
+    CREATE INDEX IF NOT EXISTS captures_host ON captures (host);
+    CREATE INDEX IF NOT EXISTS captures_status ON captures (status);
+";
+
+/// A log row without headers or bodies, for listing; `get_capture` has the rest.
+#[derive(Serialize)]
+pub struct CaptureSummary {
+    pub id: String,
+    pub started_at_ms: u64,
+    pub method: String,
+    pub url: String,
+    pub host: String,
+    pub path: String,
+    pub status: Option<u16>,
+    /// Response body bytes as received, before capture truncation.
+    pub size: u64,
+    pub duration_ms: f64,
+    pub tunneled: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct CaptureFilter {
+    pub method: Option<String>,
+    pub host: Option<String>,
+    /// Matched anywhere in the path.
+    pub path: Option<String>,
+    /// An exact code (`404`) or a class (`4xx`).
+    pub status: Option<String>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    pub min_duration_ms: Option<f64>,
+    pub max_duration_ms: Option<f64>,
+    pub since_ms: Option<u64>,
+    pub until_ms: Option<u64>,
+    /// Only exchanges that failed outright or returned an error status.
+    pub errors_only: bool,
+    /// Leave out CONNECT tunnels passed through without interception.
+    pub hide_tunnels: bool,
+}
+
+#[derive(Serialize)]
+pub struct CapturePage {
+    pub captures: Vec<CaptureSummary>,
+    /// Captures matching the search, across every page.
+    pub total: u64,
+    pub offset: u64,
+    pub limit: u64,
+}
+
+#[derive(Deserialize)]
+pub struct CaptureHarExport {
+    /// Captures to export; every one matching `filters` when unset.
+    #[serde(default)]
+    exchange_ids: Option<Vec<String>>,
+    #[serde(default)]
+    filters: Option<CaptureFilter>,
+    output_path: String,
+}
+
+#[derive(Serialize)]
+pub struct CaptureHarSummary {
+    path: String,
+    entries: usize,
+}
+
+fn db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(DB_FILE))
+}
+
+fn open(app: &tauri::AppHandle) -> Result<Connection, String> {
+    let conn =
+        Connection::open(db_path(app)?).map_err(|e| format!("capture log open failed: {e}"))?;
+    conn.execute_batch(&format!("PRAGMA journal_mode = WAL; {SCHEMA}"))
+        .map_err(|e| format!("capture log init failed: {e}"))?;
+    Ok(conn)
+}
+
+fn path_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_default()
+}
+
+fn summary_of(row: &Row) -> rusqlite::Result<CaptureSummary> {
+    Ok(CaptureSummary {
+        id: row.get("id")?,
+        started_at_ms: row.get("started_at_ms")?,
+        method: row.get("method")?,
+        url: row.get("url")?,
+        host: row.get("host")?,
+        path: row.get("path")?,
+        status: row.get("status")?,
+        size: row.get("size")?,
+        duration_ms: row.get("duration_ms")?,
+        tunneled: row.get("tunneled")?,
+        error: row.get("error")?,
+    })
+}
+
+fn exchange_of(row: &Row) -> rusqlite::Result<Option<CapturedExchange>> {
+    let exchange: String = row.get("exchange")?;
+    Ok(serde_json::from_str(&exchange).ok())
+}
+
+/// Adds an exchange to the log, dropping the oldest beyond `LOG_LIMIT`.
+pub fn record(app: &tauri::AppHandle, exchange: &CapturedExchange) -> Result<(), String> {
+    let failed = |e: rusqlite::Error| format!("capture log write failed: {e}");
+    let conn = open(app)?;
+    let json =
+        serde_json::to_string(exchange).map_err(|e| format!("capture log write failed: {e}"))?;
+    conn.execute(
+        "INSERT OR REPLACE INTO captures (id, started_at_ms, method, url, host, path, status,
+            size, duration_ms, tunneled, error, exchange)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            exchange.id,
+            exchange.started_at_ms,
+            exchange.method,
+            exchange.url,
+            exchange.host,
+            path_of(&exchange.url),
+            exchange.status,
+            exchange.response_bytes,
+            exchange.duration_ms,
+            exchange.tunneled,
+            exchange.error,
+            json,
+        ],
+    )
+    .map_err(failed)?;
+    conn.execute(
+        "DELETE FROM captures WHERE started_at_ms < (
+            SELECT started_at_ms FROM captures ORDER BY started_at_ms DESC LIMIT 1 OFFSET ?1)",
+        [LOG_LIMIT - 1],
+    )
+    .map_err(failed)?;
+    Ok(())
+}
+
+/// The WHERE clause (and its parameters) for a search; `query` words match the URL and method.
+fn conditions(query: &str, filter: &CaptureFilter) -> Result<(String, Vec<SqlValue>), String> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<SqlValue> = Vec::new();
+    for pattern in like_patterns(query) {
+        clauses.push("(url LIKE ? ESCAPE '\\' OR method LIKE ? ESCAPE '\\')".to_string());
+        values.extend(std::iter::repeat_n(SqlValue::Text(pattern), 2));
+    }
+    if let Some(method) = filter.method.as_deref().filter(|m| !m.is_empty()) {
+        clauses.push("method = ?".to_string());
+        values.push(SqlValue::Text(method.to_uppercase()));
+    }
+    if let Some(host) = filter.host.as_deref().filter(|h| !h.is_empty()) {
+        clauses.push("host = ?".to_string());
+        values.push(SqlValue::Text(host.to_lowercase()));
+    }
+    if let Some(path) = filter.path.as_deref().filter(|p| !p.is_empty()) {
+        if let Some(pattern) = like_patterns(path).into_iter().next() {
+            clauses.push("path LIKE ? ESCAPE '\\'".to_string());
+            values.push(SqlValue::Text(pattern));
+        }
+    }
+    if let Some(status) = filter
+        .status
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let (low, high) = status_bounds(status)?;
+        clauses.push("status BETWEEN ? AND ?".to_string());
+        values.push(SqlValue::Integer(low));
+        values.push(SqlValue::Integer(high));
+    }
+    let mut bound = |clause: &str, value: Option<SqlValue>| {
+        if let Some(value) = value {
+            clauses.push(clause.to_string());
+            values.push(value);
+        }
+    };
+    bound(
+        "size >= ?",
+        filter.min_size.map(|v| SqlValue::Integer(v as i64)),
+    );
+    bound(
+        "size <= ?",
+        filter.max_size.map(|v| SqlValue::Integer(v as i64)),
+    );
+    bound(
+        "duration_ms >= ?",
+        filter.min_duration_ms.map(SqlValue::Real),
+    );
+    bound(
+        "duration_ms <= ?",
+        filter.max_duration_ms.map(SqlValue::Real),
+    );
+    bound(
+        "started_at_ms >= ?",
+        filter.since_ms.map(|v| SqlValue::Integer(v as i64)),
+    );
+    bound(
+        "started_at_ms <= ?",
+        filter.until_ms.map(|v| SqlValue::Integer(v as i64)),
+    );
+    if filter.errors_only {
+        clauses.push("(status IS NULL OR status >= 400 OR error IS NOT NULL)".to_string());
+    }
+    if filter.hide_tunnels {
+        clauses.push("tunneled = 0".to_string());
+    }
+    let clause = match clauses.is_empty() {
+        true => "1 = 1".to_string(),
+        false => clauses.join(" AND "),
+    };
+    Ok((clause, values))
+}
+
+/// Full exchanges, oldest first: those with the given ids, or the newest `limit` of the log.
+pub fn load(
+    app: &tauri::AppHandle,
+    ids: Option<&[String]>,
+    limit: u64,
+) -> Result<Vec<CapturedExchange>, String> {
+    let failed = |e: rusqlite::Error| format!("capture log read failed: {e}");
+    let conn = open(app)?;
+    let (clause, mut values) = match ids {
+        Some(ids) => (
+            format!("id IN ({})", vec!["?"; ids.len()].join(", ")),
+            ids.iter().cloned().map(SqlValue::Text).collect(),
+        ),
+        None => ("1 = 1".to_string(), Vec::new()),
+    };
+    values.push(SqlValue::Integer(limit as i64));
+    let mut statement = conn
+        .prepare(&format!(
+            "SELECT exchange FROM (
+                SELECT exchange, started_at_ms FROM captures WHERE {clause}
+                ORDER BY started_at_ms DESC LIMIT ?)
+             ORDER BY started_at_ms"
+        ))
+        .map_err(failed)?;
+    let exchanges = statement
+        .query_map(params_from_iter(values.iter()), exchange_of)
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(failed)?;
+    Ok(exchanges.into_iter().flatten().collect())
+}
+
+/// Removes the given captures, or the whole log; returns how many went.
+pub fn clear(app: &tauri::AppHandle, ids: Option<&[String]>) -> Result<u64, String> {
+    let conn = open(app)?;
+    let removed = match ids {
+        Some(ids) => conn.execute(
+            &format!(
+                "DELETE FROM captures WHERE id IN ({})",
+                vec!["?"; ids.len()].join(", ")
+            ),
+            params_from_iter(ids.iter()),
+        ),
+        None => conn.execute("DELETE FROM captures", []),
+    }
+    .map_err(|e| format!("capture log clear failed: {e}"))?;
+    Ok(removed as u64)
+}
+
+/// Captures matching `query` (words matched against URL and method) and `filters`, newest
+/// first, a page at a time: `limit` rows (100 by default, 1000 at most) from `offset`.
+#[tauri::command]
+pub async fn search_captures(
+    app: tauri::AppHandle,
+    query: Option<String>,
+    filters: Option<CaptureFilter>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<CapturePage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let failed = |e: rusqlite::Error| format!("capture search failed: {e}");
+        let conn = open(&app)?;
+        let (clause, mut values) =
+            conditions(query.as_deref().unwrap_or(""), &filters.unwrap_or_default())?;
+        let total: u64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM captures WHERE {clause}"),
+                params_from_iter(values.iter()),
+                |row| row.get(0),
+            )
+            .map_err(failed)?;
+
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+        values.push(SqlValue::Integer(limit as i64));
+        values.push(SqlValue::Integer(offset as i64));
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT * FROM captures WHERE {clause}
+                 ORDER BY started_at_ms DESC LIMIT ? OFFSET ?"
+            ))
+            .map_err(failed)?;
+        let captures = statement
+            .query_map(params_from_iter(values.iter()), summary_of)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(failed)?;
+        Ok(CapturePage {
+            captures,
+            total,
+            offset,
+            limit,
+        })
+    })
+    .await
+    .map_err(|e| format!("capture search failed: {e}"))?
+}
+
+/// One capture by id, with its headers and bodies.
+#[tauri::command]
+pub async fn get_capture(app: tauri::AppHandle, id: String) -> Result<CapturedExchange, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        open(&app)?
+            .query_row(
+                "SELECT exchange FROM captures WHERE id = ?1",
+                [&id],
+                exchange_of,
+            )
+            .optional()
+            .map_err(|e| format!("capture read failed: {e}"))?
+            .flatten()
+            .ok_or_else(|| format!("unknown capture: {id}"))
+    })
+    .await
+    .map_err(|e| format!("capture read failed: {e}"))?
+}
+
+/// Removes the given captures from the log, or all of them when `exchange_ids` is unset.
+#[tauri::command]
+pub async fn clear_captures(
+    app: tauri::AppHandle,
+    exchange_ids: Option<Vec<String>>,
+) -> Result<u64, String> {
+    tauri::async_runtime::spawn_blocking(move || clear(&app, exchange_ids.as_deref()))
+        .await
+        .map_err(|e| format!("capture log clear failed: {e}"))?
+}
+
+fn pairs(headers: &[(String, String)]) -> Vec<HarPair> {
+    headers
+        .iter()
+        .map(|(name, value)| HarPair {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect()
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.as_str())
+}
+
+fn exchange_to_entry(exchange: &CapturedExchange) -> HarEntry {
+    let started = chrono::DateTime::from_timestamp_millis(exchange.started_at_ms as i64)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let query_string = reqwest::Url::parse(&exchange.url)
+        .map(|u| {
+            u.query_pairs()
+                .map(|(k, v)| HarPair {
+                    name: k.to_string(),
+                    value: v.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // HAR has no encoding field for request bodies, so binary ones are left out.
+    let post_data = match (&exchange.request_body, exchange.request_body_base64) {
+        (Some(text), false) => Some(HarPostData {
+            mime_type: header(&exchange.request_headers, "content-type")
+                .unwrap_or_default()
+                .to_string(),
+            text: Some(text.clone()),
+            params: Vec::new(),
+        }),
+        _ => None,
+    };
+    let request_size = match (&exchange.request_body, exchange.request_body_base64) {
+        (Some(text), false) => text.len() as i64,
+        (Some(_), true) => -1,
+        (None, _) => 0,
+    };
+    let mut response_headers = pairs(&exchange.response_headers);
+    if let Some(error) = &exchange.error {
+        response_headers.push(HarPair {
+            name: "x-litefetch-error".to_string(),
+            value: error.clone(),
+        });
+    }
+
+    HarEntry {
+        started_date_time: started,
+        time: exchange.duration_ms,
+        request: HarRequest {
+            method: exchange.method.clone(),
+            url: exchange.url.clone(),
+            http_version: crate::har::default_http_version(),
+            cookies: Vec::new(),
+            headers: pairs(&exchange.request_headers),
+            query_string,
+            post_data,
+            headers_size: -1,
+            body_size: request_size,
+        },
+        response: HarResponse {
+            status: exchange.status.map_or(0, i64::from),
+            status_text: String::new(),
+            http_version: crate::har::default_http_version(),
+            cookies: Vec::new(),
+            headers: response_headers,
+            content: HarContent {
+                size: exchange.response_bytes as i64,
+                mime_type: header(&exchange.response_headers, "content-type")
+                    .unwrap_or_default()
+                    .to_string(),
+                text: exchange.response_body.clone(),
+                encoding: exchange.response_body_base64.then(|| "base64".to_string()),
+            },
+            redirect_url: header(&exchange.response_headers, "location")
+                .unwrap_or_default()
+                .to_string(),
+            headers_size: -1,
+            body_size: exchange.response_bytes as i64,
+        },
+        // The proxy times whole exchanges, so the duration is attributed to `wait`.
+        timings: HarTimings {
+            wait: exchange.duration_ms,
+            ..HarTimings::default()
+        },
+    }
+}
+
+/// Writes the given captures (or those matching `filters`) as a HAR 1.2 file, oldest first.
+/// Tunnels passed through without interception have nothing to export and are skipped;
+/// remembered secret values are masked.
+#[tauri::command]
+pub async fn export_captures_har(
+    app: tauri::AppHandle,
+    export: CaptureHarExport,
+) -> Result<CaptureHarSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let exchanges = match (&export.exchange_ids, &export.filters) {
+            (Some(ids), _) => load(&app, Some(ids), LOG_LIMIT)?,
+            (None, filters) => {
+                let failed = |e: rusqlite::Error| format!("capture log read failed: {e}");
+                let conn = open(&app)?;
+                let default = CaptureFilter::default();
+                let (clause, values) = conditions("", filters.as_ref().unwrap_or(&default))?;
+                let mut statement = conn
+                    .prepare(&format!(
+                        "SELECT exchange FROM captures WHERE {clause} ORDER BY started_at_ms"
+                    ))
+                    .map_err(failed)?;
+                let rows = statement
+                    .query_map(params_from_iter(values.iter()), exchange_of)
+                    .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+                    .map_err(failed)?;
+                rows.into_iter().flatten().collect()
+            }
+        };
+        let entries: Vec<HarEntry> = exchanges
+            .iter()
+            .filter(|e| !e.tunneled)
+            .map(exchange_to_entry)
+            .collect();
+        if entries.is_empty() {
+            return Err("no captured requests to export".to_string());
+        }
+        let har = HarFile {
+            log: HarLog {
+                version: "1.2".to_string(),
+                creator: HarCreator {
+                    name: "LiteFetch".to_string(),
+                    version: env!("CARGO_PKG_VERSION").to_string(),
+                },
+                entries,
+            },
+        };
+        let path = crate::normalize_path(export.output_path.trim());
+        let payload =
+            serde_json::to_string_pretty(&har).map_err(|e| format!("HAR encode failed: {e}"))?;
+        fs::write(&path, crate::redact::log_line(&payload))
+            .map_err(|e| format!("HAR write failed: {e}"))?;
+        Ok(CaptureHarSummary {
+            path: path.to_string_lossy().to_string(),
+            entries: har.log.entries.len(),
+        })
+    })
+    .await
+    .map_err(|e| format!("HAR export failed: {e}"))?
+}
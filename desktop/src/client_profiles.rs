@@ -0,0 +1,145 @@
+//! Client profiles: a `User-Agent` with the `Accept` and `Accept-Language` headers (and, for
+//! Chromium, the client hints) the same client would send, for testing APIs that answer
+//! browsers, mobile apps or scripts differently. A profile is picked per workspace or
+//! collection (`client_profile` in their defaults) or per request (its own
+//! `client_profile`, which wins); the request's own headers still win over the profile's.
+//! Custom profiles are kept in the defaults next to the built-in ones.
+
+use serde::{Deserialize, Serialize};
+
+use crate::defaults::DefaultHeader;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ClientProfile {
+    pub id: String,
+    pub name: String,
+    pub headers: Vec<DefaultHeader>,
+    /// Set on the built-in profiles, which can't be changed.
+    #[serde(default, skip_deserializing)]
+    pub builtin: bool,
+}
+
+const CHROME_ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,image/apng,*/*;q=0.8";
+const BROWSER_ACCEPT: &str = "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8";
+const CHROME_HINTS: &str =
+    "\"Google Chrome\";v=\"131\", \"Chromium\";v=\"131\", \"Not_A Brand\";v=\"24\"";
+
+fn profile(id: &str, name: &str, headers: &[(&str, &str)]) -> ClientProfile {
+    ClientProfile {
+        id: id.to_string(),
+        name: name.to_string(),
+        headers: headers
+            .iter()
+            .map(|(name, value)| DefaultHeader {
+                name: name.to_string(),
+                value: value.to_string(),
+                enabled: true,
+            })
+            .collect(),
+        builtin: true,
+    }
+}
+
+/// The profiles every workspace has.
+pub fn builtin() -> Vec<ClientProfile> {
+    let litefetch = format!("LiteFetch/{}", env!("CARGO_PKG_VERSION"));
+    vec![
+        profile(
+            "litefetch",
+            "LiteFetch",
+            &[("User-Agent", &litefetch), ("Accept", "*/*")],
+        ),
+        profile(
+            "chrome_windows",
+            "Chrome on Windows",
+            &[
+                ("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36"),
+                ("Accept", CHROME_ACCEPT),
+                ("Accept-Language", "en-US,en;q=0.9"),
+                ("sec-ch-ua", CHROME_HINTS),
+                ("sec-ch-ua-mobile", "?0"),
+                ("sec-ch-ua-platform", "\"Windows\""),
+            ],
+        ),
+        profile(
+            "chrome_android",
+            "Chrome on Android",
+            &[
+                ("User-Agent", "Mozilla/5.0 (Linux; Android 10; K) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Mobile Safari/537.36"),
+                ("Accept", CHROME_ACCEPT),
+                ("Accept-Language", "en-US,en;q=0.9"),
+                ("sec-ch-ua", CHROME_HINTS),
+                ("sec-ch-ua-mobile", "?1"),
+                ("sec-ch-ua-platform", "\"Android\""),
+            ],
+        ),
+        profile(
+            "firefox_windows",
+            "Firefox on Windows",
+            &[
+                ("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:133.0) Gecko/20100101 Firefox/133.0"),
+                ("Accept", BROWSER_ACCEPT),
+                ("Accept-Language", "en-US,en;q=0.5"),
+            ],
+        ),
+        profile(
+            "safari_macos",
+            "Safari on macOS",
+            &[
+                ("User-Agent", "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.1 Safari/605.1.15"),
+                ("Accept", BROWSER_ACCEPT),
+                ("Accept-Language", "en-US,en;q=0.9"),
+            ],
+        ),
+        profile(
+            "safari_ios",
+            "Safari on iPhone",
+            &[
+                ("User-Agent", "Mozilla/5.0 (iPhone; CPU iPhone OS 18_1 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/18.1 Mobile/15E148 Safari/604.1"),
+                ("Accept", BROWSER_ACCEPT),
+                ("Accept-Language", "en-US,en;q=0.9"),
+            ],
+        ),
+        profile(
+            "curl",
+            "curl",
+            &[("User-Agent", "curl/8.10.1"), ("Accept", "*/*")],
+        ),
+    ]
+}
+
+/// The profile `id` among `custom` (which may redefine nothing built in) and the built-in
+/// ones.
+pub fn find(custom: &[ClientProfile], id: &str) -> Option<ClientProfile> {
+    builtin()
+        .into_iter()
+        .chain(custom.iter().cloned())
+        .find(|p| p.id == id)
+}
+
+/// Built-in profiles, then custom ones from the workspace and, with `collection_id`, the
+/// collection's defaults.
+#[tauri::command]
+pub async fn list_client_profiles(
+    app: tauri::AppHandle,
+    collection_id: Option<String>,
+) -> Result<Vec<ClientProfile>, String> {
+    let mut profiles = builtin();
+    let mut custom = crate::defaults::RequestDefaults::load(&app)?.client_profiles;
+    if let Some(collection_id) = collection_id {
+        let collection =
+            crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+        if let Some(own) = collection
+            .get("defaults")
+            .and_then(|d| d.get("client_profiles"))
+            .cloned()
+        {
+            let own: Vec<ClientProfile> = serde_json::from_value(own)
+                .map_err(|e| format!("collection defaults parse failed: {e}"))?;
+            custom.extend(own);
+        }
+    }
+    custom.retain(|p| !profiles.iter().any(|b| b.id == p.id));
+    profiles.extend(custom);
+    Ok(profiles)
+}
@@ -0,0 +1,154 @@
+//! Clipboard watcher that offers copied curl commands for import as
+//! `clipboard://curl-detected`. Off by default; the clipboard is only read while a LiteFetch
+//! window has focus, and only a hash of the last text is kept.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, Manager, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(750);
+
+/// The running watcher, and whether a LiteFetch window has focus.
+pub struct ClipboardState {
+    focused: Arc<AtomicBool>,
+    watcher: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ClipboardState {
+    pub fn new() -> Self {
+        Self {
+            focused: Arc::new(AtomicBool::new(false)),
+            watcher: Mutex::new(None),
+        }
+    }
+
+    pub fn set_focused(&self, focused: bool) {
+        self.focused.store(focused, Ordering::Relaxed);
+    }
+}
+
+#[derive(Deserialize, Serialize, Default, Clone)]
+pub struct ClipboardSettings {
+    #[serde(default)]
+    pub watch_curl: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct CurlDetectedEvent {
+    /// A request in the collection's `HttpRequest` shape, ready to add and send.
+    request: Value,
+    unsupported: Vec<String>,
+    timestamp_ms: u64,
+}
+
+fn settings_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let mut path = crate::app_data_root(app)?;
+    path.push("clipboard.json");
+    Ok(path)
+}
+
+fn load_settings(app: &tauri::AppHandle) -> ClipboardSettings {
+    settings_path(app)
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn fingerprint(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn spawn_watcher(app: tauri::AppHandle, focused: Arc<AtomicBool>) -> JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        // Whatever is on the clipboard when watching starts is not a fresh copy.
+        let mut last = app.clipboard().read_text().ok().map(|t| fingerprint(&t));
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if !focused.load(Ordering::Relaxed) {
+                continue;
+            }
+            let Ok(text) = app.clipboard().read_text() else {
+                continue;
+            };
+            let seen = fingerprint(&text);
+            if last == Some(seen) {
+                continue;
+            }
+            last = Some(seen);
+            if !crate::importers::curl::looks_like_curl(&text) {
+                continue;
+            }
+            if let Ok(import) = crate::importers::curl::convert(&text) {
+                let _ = app.emit(
+                    "clipboard://curl-detected",
+                    CurlDetectedEvent {
+                        request: import.request,
+                        unsupported: import.report.unsupported,
+                        timestamp_ms: crate::now_ms(),
+                    },
+                );
+            }
+        }
+    })
+}
+
+async fn apply(app: &tauri::AppHandle, state: &ClipboardState, enabled: bool) {
+    let mut watcher = state.watcher.lock().await;
+    match (enabled, watcher.is_some()) {
+        (true, false) => *watcher = Some(spawn_watcher(app.clone(), state.focused.clone())),
+        (false, true) => {
+            if let Some(task) = watcher.take() {
+                task.abort();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Starts the watcher at launch if the user left it enabled.
+pub fn restore(app: &tauri::AppHandle) {
+    if !load_settings(app).watch_curl {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<ClipboardState>();
+        apply(&app, &state, true).await;
+    });
+}
+
+#[tauri::command]
+pub async fn get_clipboard_settings(app: tauri::AppHandle) -> Result<ClipboardSettings, String> {
+    Ok(load_settings(&app))
+}
+
+#[tauri::command]
+pub async fn set_clipboard_watch(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardState>,
+    enabled: bool,
+) -> Result<ClipboardSettings, String> {
+    let settings = ClipboardSettings {
+        watch_curl: enabled,
+    };
+    fs::write(
+        settings_path(&app)?,
+        serde_json::to_string_pretty(&settings)
+            .map_err(|e| format!("clipboard settings serialize failed: {e}"))?,
+    )
+    .map_err(|e| format!("clipboard settings persist failed: {e}"))?;
+    apply(&app, &state, enabled).await;
+    Ok(settings)
+}
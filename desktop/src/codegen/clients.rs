@@ -0,0 +1,892 @@
+//! Client library snippets: JavaScript (fetch, axios), Python (requests, httpx), Go
+//! net/http, Rust reqwest and Java HttpClient. Each language is a template plus the slot
+//! values built from a `SnippetRequest`.
+
+use super::template::{multiline, quoted, render, rust_quoted};
+use super::{encode_component, SnippetAuth, SnippetBody, SnippetRequest};
+
+pub const LANGUAGES: &[&str] = &[
+    "javascript-fetch",
+    "javascript-axios",
+    "python-requests",
+    "python-httpx",
+    "go",
+    "rust",
+    "java",
+];
+
+pub fn generate(language: &str, request: &SnippetRequest) -> Option<String> {
+    Some(match language {
+        "javascript-fetch" => fetch(request),
+        "javascript-axios" => axios(request),
+        "python-requests" => requests(request),
+        "python-httpx" => httpx(request),
+        "go" => go(request),
+        "rust" => rust(request),
+        "java" => java(request),
+        _ => return None,
+    })
+}
+
+/// `open` + one `entry,` per line + `close`, for object/dict/argument lists.
+fn block(open: &str, entries: &[String], close: &str, indent: &str) -> String {
+    let mut out = open.to_string();
+    for entry in entries {
+        out.push_str(&format!("\n{indent}{entry},"));
+    }
+    out.push('\n');
+    out.push_str(close);
+    out
+}
+
+/// Credentials for basic auth, unless an explicit `Authorization` header wins.
+fn basic(request: &SnippetRequest) -> Option<(&str, &str)> {
+    match &request.auth {
+        SnippetAuth::Basic { username, password } if request.header("Authorization").is_none() => {
+            Some((username, password))
+        }
+        _ => None,
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+fn part_file_name<'a>(value: &'a str, path: &'a str) -> &'a str {
+    match value {
+        "" => file_name(path),
+        name => name,
+    }
+}
+
+fn proxy_parts(proxy: &str) -> (&str, &str, u16) {
+    let (scheme, rest) = proxy.split_once("://").unwrap_or(("http", proxy));
+    let authority = rest.split('/').next().unwrap_or(rest);
+    let authority = authority.rsplit('@').next().unwrap_or(authority);
+    let default = if scheme == "https" { 443 } else { 80 };
+    match authority.rsplit_once(':') {
+        Some((host, port)) => (scheme, host, port.parse().unwrap_or(default)),
+        None => (scheme, authority, default),
+    }
+}
+
+fn lines(items: Vec<String>) -> String {
+    items.join("\n")
+}
+
+/// Setup code is followed by a blank line when present.
+fn section(items: Vec<String>) -> String {
+    match items.is_empty() {
+        true => String::new(),
+        false => format!("{}\n", items.join("\n")),
+    }
+}
+
+const FETCH: &str = r#"<<imports>>
+<<setup>>
+const response = await fetch(<<url>>, {
+  method: <<method>>,
+  <<options>>
+});
+
+console.log(response.status);
+console.log(await response.text());"#;
+
+const AXIOS: &str = r#"<<imports>>
+<<setup>>
+const response = await axios({
+  method: <<method>>,
+  url: <<url>>,
+  <<options>>
+});
+
+console.log(response.status);
+console.log(response.data);"#;
+
+struct JsBody {
+    imports: Vec<String>,
+    setup: Vec<String>,
+    value: Option<String>,
+}
+
+/// A JavaScript body expression; fetch and axios accept the same ones in Node 18+.
+fn js_body(request: &SnippetRequest) -> JsBody {
+    let read_file = "import { readFile } from \"node:fs/promises\";".to_string();
+    let mut body = JsBody {
+        imports: Vec::new(),
+        setup: Vec::new(),
+        value: None,
+    };
+    match &request.body {
+        SnippetBody::None => {}
+        SnippetBody::Raw { text, .. } => {
+            let safe = !text.contains(['`', '\\']) && !text.contains("${");
+            body.value = Some(match multiline(text).filter(|_| safe) {
+                Some(raw) => format!("`{raw}`"),
+                None => quoted(text),
+            });
+        }
+        SnippetBody::UrlEncoded(rows) => {
+            let entries: Vec<String> = rows
+                .iter()
+                .map(|(k, v)| format!("[{}, {}]", quoted(k), quoted(v)))
+                .collect();
+            body.value = Some(block("new URLSearchParams([", &entries, "])", "  "));
+        }
+        SnippetBody::Multipart(parts) => {
+            body.setup.push("const form = new FormData();".to_string());
+            for part in parts {
+                body.setup.push(match &part.file_path {
+                    Some(path) => {
+                        body.imports = vec![read_file.clone()];
+                        format!(
+                            "form.append({}, new Blob([await readFile({})]), {});",
+                            quoted(&part.name),
+                            quoted(path),
+                            quoted(part_file_name(&part.value, path))
+                        )
+                    }
+                    None => format!(
+                        "form.append({}, {});",
+                        quoted(&part.name),
+                        quoted(&part.value)
+                    ),
+                });
+            }
+            body.value = Some("form".to_string());
+        }
+        SnippetBody::File(path) => {
+            body.imports.push(read_file);
+            body.value = Some(format!("await readFile({})", quoted(path)));
+        }
+    }
+    body
+}
+
+fn js_headers(request: &SnippetRequest, basic_header: bool) -> Option<String> {
+    let mut entries: Vec<String> = request
+        .header_lines()
+        .iter()
+        .map(|(k, v)| format!("{}: {}", quoted(k), quoted(v)))
+        .collect();
+    if let Some((username, password)) = basic(request).filter(|_| basic_header) {
+        entries.push(format!(
+            "\"Authorization\": \"Basic \" + btoa({})",
+            quoted(&format!("{username}:{password}"))
+        ));
+    }
+    (!entries.is_empty()).then(|| block("headers: {", &entries, "},", "  "))
+}
+
+fn fetch(request: &SnippetRequest) -> String {
+    let body = js_body(request);
+    let mut options: Vec<String> = js_headers(request, true).into_iter().collect();
+    if let Some(value) = body.value {
+        options.push(format!("body: {value},"));
+    }
+    if let Some(timeout) = request.timeout_seconds {
+        options.push(format!("signal: AbortSignal.timeout({}),", timeout * 1000));
+    }
+    let mut setup = Vec::new();
+    if let Some(proxy) = &request.proxy {
+        setup.push(format!(
+            "// fetch has no proxy option; pass an undici ProxyAgent for {proxy} as `dispatcher`."
+        ));
+    }
+    if request.insecure() {
+        setup.push(
+            "// Certificate checks are off for this request; Node's fetch needs NODE_TLS_REJECT_UNAUTHORIZED=0 to match."
+                .to_string(),
+        );
+    }
+    setup.extend(body.setup);
+    render(
+        FETCH,
+        &[
+            ("imports", section(body.imports)),
+            ("setup", section(setup)),
+            ("url", quoted(&request.url)),
+            ("method", quoted(&request.method)),
+            ("options", lines(options)),
+        ],
+    )
+}
+
+fn axios(request: &SnippetRequest) -> String {
+    let body = js_body(request);
+    let mut imports = vec!["import axios from \"axios\";".to_string()];
+    imports.extend(body.imports);
+    let mut options: Vec<String> = js_headers(request, false).into_iter().collect();
+    if let Some(value) = body.value {
+        options.push(format!("data: {value},"));
+    }
+    if let Some((username, password)) = basic(request) {
+        options.push(block(
+            "auth: {",
+            &[
+                format!("username: {}", quoted(username)),
+                format!("password: {}", quoted(password)),
+            ],
+            "},",
+            "  ",
+        ));
+    }
+    if let Some(timeout) = request.timeout_seconds {
+        options.push(format!("timeout: {},", timeout * 1000));
+    }
+    if let Some(proxy) = &request.proxy {
+        let (scheme, host, port) = proxy_parts(proxy);
+        options.push(block(
+            "proxy: {",
+            &[
+                format!("protocol: {}", quoted(scheme)),
+                format!("host: {}", quoted(host)),
+                format!("port: {port}"),
+            ],
+            "},",
+            "  ",
+        ));
+    }
+    if request.insecure() {
+        imports.push("import https from \"node:https\";".to_string());
+        options.push("httpsAgent: new https.Agent({ rejectUnauthorized: false }),".to_string());
+    }
+    render(
+        AXIOS,
+        &[
+            ("imports", section(imports)),
+            ("setup", section(body.setup)),
+            ("url", quoted(&request.url)),
+            ("method", quoted(&request.method.to_lowercase())),
+            ("options", lines(options)),
+        ],
+    )
+}
+
+const REQUESTS: &str = r#"import requests
+
+response = requests.request(
+    <<method>>,
+    <<url>>,
+    <<options>>
+)
+
+print(response.status_code)
+print(response.text)"#;
+
+const HTTPX: &str = r#"import httpx
+
+with httpx.Client(<<client>>) as client:
+    response = client.request(
+        <<method>>,
+        <<url>>,
+        <<options>>
+    )
+
+print(response.status_code)
+print(response.text)"#;
+
+fn py_literal(text: &str) -> String {
+    let safe = !text.contains('\\') && !text.contains("\"\"\"") && !text.ends_with('"');
+    match multiline(text).filter(|_| safe) {
+        Some(raw) => format!("\"\"\"{raw}\"\"\""),
+        None => quoted(text),
+    }
+}
+
+fn py_open(path: &str) -> String {
+    format!("open({}, \"rb\")", quoted(path))
+}
+
+fn py_headers(request: &SnippetRequest) -> Option<String> {
+    let entries: Vec<String> = request
+        .header_lines()
+        .iter()
+        .map(|(k, v)| format!("{}: {}", quoted(k), quoted(v)))
+        .collect();
+    (!entries.is_empty()).then(|| block("headers={", &entries, "},", "    "))
+}
+
+fn py_auth(request: &SnippetRequest) -> Option<String> {
+    basic(request).map(|(u, p)| format!("auth=({}, {}),", quoted(u), quoted(p)))
+}
+
+fn requests(request: &SnippetRequest) -> String {
+    let mut options: Vec<String> = py_headers(request).into_iter().collect();
+    match &request.body {
+        SnippetBody::None => {}
+        // A str body is sent as Latin-1, so anything wider goes out as UTF-8 bytes.
+        SnippetBody::Raw { text, .. } if !text.is_ascii() => {
+            options.push(format!("data={}.encode(),", py_literal(text)))
+        }
+        SnippetBody::Raw { text, .. } => options.push(format!("data={},", py_literal(text))),
+        SnippetBody::UrlEncoded(rows) => {
+            let entries: Vec<String> = rows
+                .iter()
+                .map(|(k, v)| format!("({}, {})", quoted(k), quoted(v)))
+                .collect();
+            options.push(block("data=[", &entries, "],", "    "));
+        }
+        SnippetBody::Multipart(parts) => {
+            let entries: Vec<String> = parts
+                .iter()
+                .map(|part| match &part.file_path {
+                    Some(path) => format!(
+                        "({}, ({}, {}))",
+                        quoted(&part.name),
+                        quoted(part_file_name(&part.value, path)),
+                        py_open(path)
+                    ),
+                    None => format!("({}, (None, {}))", quoted(&part.name), quoted(&part.value)),
+                })
+                .collect();
+            options.push(block("files=[", &entries, "],", "    "));
+        }
+        SnippetBody::File(path) => options.push(format!("data={},", py_open(path))),
+    }
+    options.extend(py_auth(request));
+    if let Some(proxy) = &request.proxy {
+        let proxy = quoted(proxy);
+        options.push(format!(
+            "proxies={{\"http\": {proxy}, \"https\": {proxy}}},"
+        ));
+    }
+    if request.insecure() {
+        options.push("verify=False,".to_string());
+    }
+    if let Some(timeout) = request.timeout_seconds {
+        options.push(format!("timeout={timeout},"));
+    }
+    render(
+        REQUESTS,
+        &[
+            ("method", quoted(&request.method)),
+            ("url", quoted(&request.url)),
+            ("options", lines(options)),
+        ],
+    )
+}
+
+/// httpx takes form fields as a dict; repeated keys become lists.
+fn py_form_dict(rows: &[(String, String)]) -> String {
+    let mut keys: Vec<&str> = Vec::new();
+    for (key, _) in rows {
+        if !keys.contains(&key.as_str()) {
+            keys.push(key);
+        }
+    }
+    let entries: Vec<String> = keys
+        .iter()
+        .map(|key| {
+            let values: Vec<String> = rows
+                .iter()
+                .filter(|(k, _)| k == key)
+                .map(|(_, v)| quoted(v))
+                .collect();
+            match values.as_slice() {
+                [single] => format!("{}: {single}", quoted(key)),
+                many => format!("{}: [{}]", quoted(key), many.join(", ")),
+            }
+        })
+        .collect();
+    block("data={", &entries, "},", "    ")
+}
+
+fn httpx(request: &SnippetRequest) -> String {
+    let mut options: Vec<String> = py_headers(request).into_iter().collect();
+    match &request.body {
+        SnippetBody::None => {}
+        SnippetBody::Raw { text, .. } => options.push(format!("content={},", py_literal(text))),
+        SnippetBody::UrlEncoded(rows) => options.push(py_form_dict(rows)),
+        SnippetBody::Multipart(parts) => {
+            let fields: Vec<(String, String)> = parts
+                .iter()
+                .filter(|p| p.file_path.is_none())
+                .map(|p| (p.name.clone(), p.value.clone()))
+                .collect();
+            if !fields.is_empty() {
+                options.push(py_form_dict(&fields));
+            }
+            let files: Vec<String> = parts
+                .iter()
+                .filter_map(|part| {
+                    let path = part.file_path.as_ref()?;
+                    Some(format!(
+                        "({}, ({}, {}))",
+                        quoted(&part.name),
+                        quoted(part_file_name(&part.value, path)),
+                        py_open(path)
+                    ))
+                })
+                .collect();
+            if !files.is_empty() {
+                options.push(block("files=[", &files, "],", "    "));
+            }
+        }
+        SnippetBody::File(path) => options.push(format!("content={}.read(),", py_open(path))),
+    }
+    options.extend(py_auth(request));
+    let mut client = Vec::new();
+    if let Some(proxy) = &request.proxy {
+        client.push(format!("proxy={}", quoted(proxy)));
+    }
+    if request.insecure() {
+        client.push("verify=False".to_string());
+    }
+    if let Some(timeout) = request.timeout_seconds {
+        client.push(format!("timeout={timeout}"));
+    }
+    render(
+        HTTPX,
+        &[
+            ("client", client.join(", ")),
+            ("method", quoted(&request.method)),
+            ("url", quoted(&request.url)),
+            ("options", lines(options)),
+        ],
+    )
+}
+
+const GO: &str = "package main
+
+import (
+<<imports>>
+)
+
+func main() {
+\t<<setup>>
+\treq, err := http.NewRequest(<<method>>, <<url>>, <<body>>)
+\tif err != nil {
+\t\tpanic(err)
+\t}
+\t<<headers>>
+
+\t<<client>>
+\tresp, err := client.Do(req)
+\tif err != nil {
+\t\tpanic(err)
+\t}
+\tdefer resp.Body.Close()
+
+\tbody, _ := io.ReadAll(resp.Body)
+\tfmt.Println(resp.Status)
+\tfmt.Println(string(body))
+}";
+
+/// Struct literal fields with gofmt's value alignment; a multi-line value is not aligned.
+fn go_fields(fields: &[(&str, String)]) -> Vec<String> {
+    let width = fields
+        .iter()
+        .filter(|(_, v)| !v.contains('\n'))
+        .map(|(k, _)| k.len())
+        .max()
+        .unwrap_or(0);
+    fields
+        .iter()
+        .map(|(key, value)| match value.contains('\n') {
+            true => format!("{key}: {value}"),
+            false => format!("{:width$} {value}", format!("{key}:"), width = width + 1),
+        })
+        .collect()
+}
+
+fn go(request: &SnippetRequest) -> String {
+    let mut imports = vec!["fmt", "io", "net/http"];
+    let mut setup = Vec::new();
+    let mut headers = Vec::new();
+    let body = match &request.body {
+        SnippetBody::None => "nil".to_string(),
+        SnippetBody::Raw { text, .. } => {
+            imports.push("strings");
+            let literal = match multiline(text).filter(|_| !text.contains('`')) {
+                Some(raw) => format!("`{raw}`"),
+                None => quoted(text),
+            };
+            format!("strings.NewReader({literal})")
+        }
+        SnippetBody::UrlEncoded(rows) => {
+            imports.extend(["net/url", "strings"]);
+            setup.push("form := url.Values{}".to_string());
+            for (key, value) in rows {
+                setup.push(format!("form.Add({}, {})", quoted(key), quoted(value)));
+            }
+            if request.header("Content-Type").is_none() {
+                headers.push(
+                    "req.Header.Set(\"Content-Type\", \"application/x-www-form-urlencoded\")"
+                        .to_string(),
+                );
+            }
+            "strings.NewReader(form.Encode())".to_string()
+        }
+        SnippetBody::Multipart(parts) => {
+            imports.extend(["bytes", "mime/multipart"]);
+            setup.push("var payload bytes.Buffer".to_string());
+            setup.push("writer := multipart.NewWriter(&payload)".to_string());
+            for part in parts {
+                match &part.file_path {
+                    Some(path) => {
+                        imports.push("os");
+                        setup.push(lines(vec![
+                            "{".to_string(),
+                            format!("\tfile, err := os.Open({})", quoted(path)),
+                            "\tif err != nil {".to_string(),
+                            "\t\tpanic(err)".to_string(),
+                            "\t}".to_string(),
+                            format!(
+                                "\tpart, _ := writer.CreateFormFile({}, {})",
+                                quoted(&part.name),
+                                quoted(part_file_name(&part.value, path))
+                            ),
+                            "\tio.Copy(part, file)".to_string(),
+                            "\tfile.Close()".to_string(),
+                            "}".to_string(),
+                        ]))
+                    }
+                    None => setup.push(format!(
+                        "writer.WriteField({}, {})",
+                        quoted(&part.name),
+                        quoted(&part.value)
+                    )),
+                }
+            }
+            setup.push("writer.Close()".to_string());
+            headers
+                .push("req.Header.Set(\"Content-Type\", writer.FormDataContentType())".to_string());
+            "&payload".to_string()
+        }
+        SnippetBody::File(path) => {
+            imports.push("os");
+            setup.push(lines(vec![
+                format!("file, err := os.Open({})", quoted(path)),
+                "if err != nil {".to_string(),
+                "\tpanic(err)".to_string(),
+                "}".to_string(),
+                "defer file.Close()".to_string(),
+            ]));
+            "file".to_string()
+        }
+    };
+    for (name, value) in request.header_lines() {
+        headers.push(format!(
+            "req.Header.Set({}, {})",
+            quoted(&name),
+            quoted(&value)
+        ));
+    }
+    if let Some((username, password)) = basic(request) {
+        headers.push(format!(
+            "req.SetBasicAuth({}, {})",
+            quoted(username),
+            quoted(password)
+        ));
+    }
+
+    let mut client_setup = Vec::new();
+    let mut transport = Vec::new();
+    if let Some(proxy) = &request.proxy {
+        imports.push("net/url");
+        client_setup.push(format!("proxyURL, _ := url.Parse({})", quoted(proxy)));
+        transport.push(("Proxy", "http.ProxyURL(proxyURL),".to_string()));
+    }
+    if request.insecure() {
+        imports.push("crypto/tls");
+        transport.push((
+            "TLSClientConfig",
+            "&tls.Config{InsecureSkipVerify: true},".to_string(),
+        ));
+    }
+    let mut fields = Vec::new();
+    if let Some(timeout) = request.timeout_seconds {
+        imports.push("time");
+        fields.push(("Timeout", format!("{timeout} * time.Second,")));
+    }
+    if !transport.is_empty() {
+        let inner: Vec<String> = go_fields(&transport)
+            .into_iter()
+            .map(|f| format!("\t{f}"))
+            .collect();
+        fields.push((
+            "Transport",
+            format!("&http.Transport{{\n{}\n}},", lines(inner)),
+        ));
+    }
+    client_setup.push(match fields.is_empty() {
+        true => "client := &http.Client{}".to_string(),
+        false => {
+            let body: Vec<String> = go_fields(&fields)
+                .into_iter()
+                .map(|f| format!("\t{}", f.replace('\n', "\n\t")))
+                .collect();
+            format!("client := &http.Client{{\n{}\n}}", lines(body))
+        }
+    });
+
+    imports.sort_unstable();
+    imports.dedup();
+    let imports: Vec<String> = imports.iter().map(|i| format!("\t\"{i}\"")).collect();
+    render(
+        GO,
+        &[
+            ("imports", lines(imports)),
+            ("setup", section(setup)),
+            ("method", quoted(&request.method)),
+            ("url", quoted(&request.url)),
+            ("body", body),
+            ("headers", lines(headers)),
+            ("client", lines(client_setup)),
+        ],
+    )
+}
+
+const RUST: &str = r#"use reqwest::Client;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let client = Client::builder()
+        <<builder>>
+        .build()?;
+    <<setup>>
+    let response = client
+        .request(reqwest::Method::<<method>>, <<url>>)
+        <<calls>>
+        .send()
+        .await?;
+
+    println!("{}", response.status());
+    println!("{}", response.text().await?);
+    Ok(())
+}"#;
+
+fn rust(request: &SnippetRequest) -> String {
+    let mut builder = Vec::new();
+    if let Some(proxy) = &request.proxy {
+        builder.push(format!(
+            ".proxy(reqwest::Proxy::all({})?)",
+            rust_quoted(proxy)
+        ));
+    }
+    if request.insecure() {
+        builder.push(".danger_accept_invalid_certs(true)".to_string());
+    }
+    if let Some(timeout) = request.timeout_seconds {
+        builder.push(format!(
+            ".timeout(std::time::Duration::from_secs({timeout}))"
+        ));
+    }
+    let mut calls: Vec<String> = request
+        .header_lines()
+        .iter()
+        .map(|(k, v)| format!(".header({}, {})", rust_quoted(k), rust_quoted(v)))
+        .collect();
+    if let Some((username, password)) = basic(request) {
+        calls.push(format!(
+            ".basic_auth({}, Some({}))",
+            rust_quoted(username),
+            rust_quoted(password)
+        ));
+    }
+    let mut setup = Vec::new();
+    match &request.body {
+        SnippetBody::None => {}
+        SnippetBody::Raw { text, .. } => {
+            let literal = match multiline(text).filter(|_| !text.contains("\"#")) {
+                Some(raw) => format!("r#\"{raw}\"#"),
+                None => rust_quoted(text),
+            };
+            calls.push(format!(".body({literal})"));
+        }
+        SnippetBody::UrlEncoded(rows) => {
+            let entries: Vec<String> = rows
+                .iter()
+                .map(|(k, v)| format!("({}, {})", rust_quoted(k), rust_quoted(v)))
+                .collect();
+            calls.push(block(".form(&[", &entries, "])", "    "));
+        }
+        SnippetBody::Multipart(parts) => {
+            let mut form = vec!["let form = reqwest::multipart::Form::new()".to_string()];
+            for part in parts {
+                form.push(match &part.file_path {
+                    Some(path) => format!(
+                        "    .part({}, reqwest::multipart::Part::bytes(std::fs::read({})?).file_name({}))",
+                        rust_quoted(&part.name),
+                        rust_quoted(path),
+                        rust_quoted(part_file_name(&part.value, path))
+                    ),
+                    None => format!(
+                        "    .text({}, {})",
+                        rust_quoted(&part.name),
+                        rust_quoted(&part.value)
+                    ),
+                });
+            }
+            setup.push(format!("{};", lines(form)));
+            calls.push(".multipart(form)".to_string());
+        }
+        SnippetBody::File(path) => {
+            calls.push(format!(".body(std::fs::read({})?)", rust_quoted(path)));
+        }
+    }
+    render(
+        RUST,
+        &[
+            ("builder", lines(builder)),
+            ("setup", section(setup)),
+            ("method", request.method.clone()),
+            ("url", rust_quoted(&request.url)),
+            ("calls", lines(calls)),
+        ],
+    )
+}
+
+const JAVA: &str = r#"<<imports>>
+public class Main {
+    public static void main(String[] args) throws Exception {
+        <<setup>>
+        HttpClient client = HttpClient.newBuilder()
+            <<builder>>
+            .build();
+
+        HttpRequest request = HttpRequest.newBuilder()
+            .uri(URI.create(<<url>>))
+            <<calls>>
+            .method(<<method>>, <<publisher>>)
+            .build();
+
+        HttpResponse<String> response = client.send(request, HttpResponse.BodyHandlers.ofString());
+        System.out.println(response.statusCode());
+        System.out.println(response.body());
+    }
+}"#;
+
+/// Headers HttpClient refuses to set from user code.
+const JAVA_RESTRICTED: &[&str] = &["connection", "content-length", "expect", "host", "upgrade"];
+
+const BOUNDARY: &str = "LiteFetchBoundary7MA4YWxkTrZu0gW";
+
+fn java(request: &SnippetRequest) -> String {
+    let mut imports = vec![
+        "java.net.URI",
+        "java.net.http.HttpClient",
+        "java.net.http.HttpRequest",
+        "java.net.http.HttpResponse",
+    ];
+    let mut setup = Vec::new();
+    let mut builder = Vec::new();
+    let mut calls: Vec<String> = request
+        .header_lines()
+        .iter()
+        .filter(|(k, _)| !JAVA_RESTRICTED.contains(&k.to_lowercase().as_str()))
+        .map(|(k, v)| format!(".header({}, {})", quoted(k), quoted(v)))
+        .collect();
+    if let Some((username, password)) = basic(request) {
+        imports.extend(["java.nio.charset.StandardCharsets", "java.util.Base64"]);
+        calls.push(format!(
+            ".header(\"Authorization\", \"Basic \" + Base64.getEncoder().encodeToString({}.getBytes(StandardCharsets.UTF_8)))",
+            quoted(&format!("{username}:{password}"))
+        ));
+    }
+    let publisher = match &request.body {
+        SnippetBody::None => "HttpRequest.BodyPublishers.noBody()".to_string(),
+        SnippetBody::Raw { text, .. } => {
+            format!("HttpRequest.BodyPublishers.ofString({})", quoted(text))
+        }
+        SnippetBody::UrlEncoded(rows) => {
+            if request.header("Content-Type").is_none() {
+                calls.push(
+                    ".header(\"Content-Type\", \"application/x-www-form-urlencoded\")".to_string(),
+                );
+            }
+            let form: Vec<String> = rows
+                .iter()
+                .map(|(k, v)| format!("{}={}", encode_component(k), encode_component(v)))
+                .collect();
+            format!(
+                "HttpRequest.BodyPublishers.ofString({})",
+                quoted(&form.join("&"))
+            )
+        }
+        SnippetBody::Multipart(parts) => {
+            imports.extend([
+                "java.io.ByteArrayOutputStream",
+                "java.nio.charset.StandardCharsets",
+            ]);
+            setup
+                .push("ByteArrayOutputStream multipart = new ByteArrayOutputStream();".to_string());
+            let write = |text: String| {
+                format!(
+                    "multipart.write({}.getBytes(StandardCharsets.UTF_8));",
+                    quoted(&text)
+                )
+            };
+            for part in parts {
+                match &part.file_path {
+                    Some(path) => {
+                        imports.extend(["java.nio.file.Files", "java.nio.file.Path"]);
+                        setup.push(write(format!(
+                            "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: application/octet-stream\r\n\r\n",
+                            part.name,
+                            part_file_name(&part.value, path)
+                        )));
+                        setup.push(format!(
+                            "multipart.write(Files.readAllBytes(Path.of({})));",
+                            quoted(path)
+                        ));
+                        setup.push(write("\r\n".to_string()));
+                    }
+                    None => setup.push(write(format!(
+                        "--{BOUNDARY}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+                        part.name, part.value
+                    ))),
+                }
+            }
+            setup.push(write(format!("--{BOUNDARY}--\r\n")));
+            calls.push(format!(
+                ".header(\"Content-Type\", {})",
+                quoted(&format!("multipart/form-data; boundary={BOUNDARY}"))
+            ));
+            "HttpRequest.BodyPublishers.ofByteArray(multipart.toByteArray())".to_string()
+        }
+        SnippetBody::File(path) => {
+            imports.push("java.nio.file.Path");
+            format!(
+                "HttpRequest.BodyPublishers.ofFile(Path.of({}))",
+                quoted(path)
+            )
+        }
+    };
+    if let Some(proxy) = &request.proxy {
+        imports.extend(["java.net.InetSocketAddress", "java.net.ProxySelector"]);
+        let (_, host, port) = proxy_parts(proxy);
+        builder.push(format!(
+            ".proxy(ProxySelector.of(new InetSocketAddress({}, {port})))",
+            quoted(host)
+        ));
+    }
+    if let Some(timeout) = request.timeout_seconds {
+        imports.push("java.time.Duration");
+        calls.push(format!(".timeout(Duration.ofSeconds({timeout}))"));
+    }
+    if request.insecure() {
+        setup.insert(
+            0,
+            "// Certificate checks are off for this request; HttpClient needs a custom SSLContext to match."
+                .to_string(),
+        );
+    }
+    imports.sort_unstable();
+    imports.dedup();
+    let imports: Vec<String> = imports.iter().map(|i| format!("import {i};")).collect();
+    render(
+        JAVA,
+        &[
+            ("imports", section(imports)),
+            ("setup", section(setup)),
+            ("builder", lines(builder)),
+            ("url", quoted(&request.url)),
+            ("calls", lines(calls)),
+            ("method", quoted(&request.method)),
+            ("publisher", publisher),
+        ],
+    )
+}
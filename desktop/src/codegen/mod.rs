@@ -0,0 +1,389 @@
+//! Turns a saved request into command lines and client code that reproduce it outside
+//! LiteFetch. Requests are first flattened into `SnippetRequest`, which mirrors what
+//! the backend would send: query rows applied to the URL, auth and cookies resolved.
+
+pub mod clients;
+pub mod shell;
+mod template;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::importers::{array_of, str_of, value_text};
+
+pub use litefetch_core::json::find_request;
+
+#[derive(Deserialize, Default)]
+pub struct SnippetOptions {
+    /// Substitute `{{var}}` references from the active environment.
+    #[serde(default)]
+    pub resolve_variables: bool,
+    /// Add a `Cookie` header from the collection's cookie jar.
+    #[serde(default)]
+    pub include_cookies: bool,
+    /// Route the command through a proxy, e.g. `http://127.0.0.1:8888`.
+    #[serde(default)]
+    pub proxy: Option<String>,
+    /// A grant from `allow_secret_export`; without one, secret values are masked.
+    #[serde(default)]
+    pub include_secrets: Option<String>,
+}
+
+pub enum SnippetAuth {
+    None,
+    Basic { username: String, password: String },
+    Bearer { token: String },
+}
+
+pub struct Part {
+    pub name: String,
+    pub value: String,
+    /// Set for file parts; `value` is then the file name.
+    pub file_path: Option<String>,
+}
+
+pub enum SnippetBody {
+    None,
+    Raw { text: String, json: bool },
+    UrlEncoded(Vec<(String, String)>),
+    Multipart(Vec<Part>),
+    File(String),
+}
+
+pub struct SnippetRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub auth: SnippetAuth,
+    pub body: SnippetBody,
+    pub cookies: Vec<(String, String)>,
+    pub proxy: Option<String>,
+    pub verify_ssl: bool,
+    pub timeout_seconds: Option<u64>,
+}
+
+impl SnippetRequest {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// All cookies as a single `Cookie` header value.
+    pub fn cookie_header(&self) -> Option<String> {
+        (!self.cookies.is_empty()).then(|| {
+            self.cookies
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>()
+                .join("; ")
+        })
+    }
+
+    /// The `Authorization` value for bearer auth, unless a header already sets one.
+    pub fn bearer_header(&self) -> Option<String> {
+        match &self.auth {
+            SnippetAuth::Bearer { token } if self.header("Authorization").is_none() => {
+                Some(format!("Bearer {token}"))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn needs_json_content_type(&self) -> bool {
+        matches!(self.body, SnippetBody::Raw { json: true, .. })
+            && self.header("Content-Type").is_none()
+    }
+
+    /// Request headers plus those implied by the body, bearer auth and cookies. Basic auth
+    /// is left to each client's own option.
+    pub fn header_lines(&self) -> Vec<(String, String)> {
+        let mut lines = self.headers.clone();
+        if self.needs_json_content_type() {
+            lines.push(("Content-Type".to_string(), "application/json".to_string()));
+        }
+        if let Some(value) = self.bearer_header() {
+            lines.push(("Authorization".to_string(), value));
+        }
+        if let Some(value) = self.cookie_header() {
+            lines.push(("Cookie".to_string(), value));
+        }
+        lines
+    }
+
+    /// The request skips certificate checks (only meaningful for https URLs).
+    pub fn insecure(&self) -> bool {
+        !self.verify_ssl && self.url.starts_with("https://")
+    }
+}
+
+fn enabled(row: &Value) -> bool {
+    row.get("enabled").and_then(Value::as_bool).unwrap_or(true)
+}
+
+/// Percent-encodes a query component, leaving `{{var}}` templates readable.
+pub fn encode_component(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'{' | b'}' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+fn substitute(text: &str, vars: &[(String, String)]) -> String {
+    let mut text = text.to_string();
+    for (key, value) in vars {
+        text = text.replace(&format!("{{{{{key}}}}}"), value);
+    }
+    text
+}
+
+/// Variables of the active environment, with secret values read from the keychain.
+fn active_variables(
+    collection_id: &str,
+    environment: &Value,
+) -> Result<Vec<(String, String)>, String> {
+    let active = str_of(environment, "active_env");
+    Ok(
+        crate::secrets::resolve_environment(collection_id, environment, active)?
+            .into_iter()
+            .map(|v| (v.key, v.value))
+            .collect(),
+    )
+}
+
+fn host_and_path(url: &str) -> Option<(String, String)> {
+    let rest = url.split_once("://")?.1;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let host = authority
+        .rsplit('@')
+        .next()?
+        .split(':')
+        .next()?
+        .to_lowercase();
+    Some((host, path.split('?').next().unwrap_or("/").to_string()))
+}
+
+/// Cookies from the collection jar that the backend would send for `url`.
+async fn matching_cookies(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    url: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let Some((host, path)) = host_and_path(url) else {
+        return Ok(Vec::new());
+    };
+    let secure = url.starts_with("https://");
+    let now = crate::now_ms() as f64 / 1000.0;
+    let jar = crate::backend_get(app, &format!("/collections/{collection_id}/cookies")).await?;
+    Ok(jar
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|c| {
+            let domain = str_of(c, "domain").trim_start_matches('.').to_lowercase();
+            let domain_ok = host == domain || host.ends_with(&format!(".{domain}"));
+            let path_ok = path.starts_with(match str_of(c, "path") {
+                "" => "/",
+                p => p,
+            });
+            let live = c
+                .get("expires")
+                .and_then(Value::as_f64)
+                .is_none_or(|e| e > now);
+            let secure_ok = secure || !c.get("secure").and_then(Value::as_bool).unwrap_or(false);
+            domain_ok && path_ok && live && secure_ok
+        })
+        .map(|c| {
+            (
+                str_of(c, "name").to_string(),
+                str_of(c, "value").to_string(),
+            )
+        })
+        .collect())
+}
+
+/// Loads a request from a collection and flattens it for snippet generation.
+pub async fn load_request(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    request_id: &str,
+    options: &SnippetOptions,
+) -> Result<SnippetRequest, String> {
+    let collection =
+        crate::backend_get(app, &format!("/collections/{collection_id}/collection")).await?;
+    let request = find_request(array_of(&collection, "items"), request_id)
+        .ok_or_else(|| format!("unknown request: {request_id}"))?;
+    let environment =
+        crate::backend_get(app, &format!("/collections/{collection_id}/environment")).await?;
+    let redactor = crate::redact::for_export(
+        app,
+        collection_id,
+        &collection,
+        &environment,
+        options.include_secrets.as_deref(),
+    )
+    .await?;
+    let vars = if options.resolve_variables {
+        active_variables(collection_id, &environment)?
+    } else {
+        Vec::new()
+    };
+    let sub = |text: &str| redactor.text(&substitute(text, &vars));
+    let mut flat = flatten(request, &sub);
+    if options.include_cookies {
+        flat.cookies = matching_cookies(app, collection_id, &flat.url).await?;
+    }
+    flat.proxy = options.proxy.clone().filter(|p| !p.trim().is_empty());
+    Ok(flat)
+}
+
+/// Flattens a saved request, passing every templated field through `sub`. Cookies and the
+/// proxy are left empty for the caller.
+pub fn flatten(request: &Value, sub: &dyn Fn(&str) -> String) -> SnippetRequest {
+    let mut url = sub(str_of(request, "url"));
+    // Explicit query rows replace the URL's query string, as the backend does.
+    if let Some(rows) = request.get("query_params").and_then(Value::as_array) {
+        url.truncate(url.find('?').unwrap_or(url.len()));
+        let query: Vec<String> = rows
+            .iter()
+            .filter(|r| enabled(r) && !str_of(r, "key").is_empty())
+            .map(|r| {
+                format!(
+                    "{}={}",
+                    encode_component(&sub(str_of(r, "key"))),
+                    encode_component(&sub(&value_text(r.get("value"))))
+                )
+            })
+            .collect();
+        if !query.is_empty() {
+            url = format!("{url}?{}", query.join("&"));
+        }
+    }
+
+    let headers: Vec<(String, String)> = request
+        .get("headers")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(k, v)| (sub(k), sub(&value_text(Some(v)))))
+        .collect();
+    let params = request.get("auth_params").cloned().unwrap_or(Value::Null);
+    let auth = match str_of(request, "auth_type") {
+        "basic" => SnippetAuth::Basic {
+            username: sub(str_of(&params, "username")),
+            password: sub(str_of(&params, "password")),
+        },
+        "bearer" => SnippetAuth::Bearer {
+            token: sub(str_of(&params, "token")),
+        },
+        _ => SnippetAuth::None,
+    };
+
+    let rows: Vec<&Value> = array_of(request, "form_body")
+        .iter()
+        .filter(|r| enabled(r) && !str_of(r, "key").is_empty())
+        .collect();
+    let body = match str_of(request, "body_mode") {
+        "form-urlencoded" => SnippetBody::UrlEncoded(
+            rows.iter()
+                .map(|r| (sub(str_of(r, "key")), sub(&value_text(r.get("value")))))
+                .collect(),
+        ),
+        "form-data" => SnippetBody::Multipart(
+            rows.iter()
+                .map(|r| {
+                    let is_file = matches!(str_of(r, "type"), "file" | "binary");
+                    Part {
+                        name: sub(str_of(r, "key")),
+                        value: if is_file {
+                            str_of(r, "file_name").to_string()
+                        } else {
+                            sub(&value_text(r.get("value")))
+                        },
+                        file_path: is_file.then(|| sub(str_of(r, "file_path"))),
+                    }
+                })
+                .collect(),
+        ),
+        "binary" => match request.get("binary").map(|b| str_of(b, "file_path")) {
+            Some(path) if !path.is_empty() => SnippetBody::File(sub(path)),
+            _ => SnippetBody::None,
+        },
+        mode => match request.get("body") {
+            None | Some(Value::Null) => SnippetBody::None,
+            Some(Value::String(text)) if text.is_empty() => SnippetBody::None,
+            Some(Value::String(text)) => SnippetBody::Raw {
+                text: sub(text),
+                json: mode == "json",
+            },
+            Some(other) => SnippetBody::Raw {
+                text: sub(&serde_json::to_string_pretty(other).unwrap_or_default()),
+                json: true,
+            },
+        },
+    };
+
+    SnippetRequest {
+        method: match str_of(request, "method") {
+            "" => "GET".to_string(),
+            m => m.to_uppercase(),
+        },
+        url,
+        headers,
+        auth,
+        body,
+        cookies: Vec::new(),
+        proxy: None,
+        verify_ssl: request
+            .get("verify_ssl")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        timeout_seconds: request.get("timeout_seconds").and_then(Value::as_u64),
+    }
+}
+
+/// Renders a saved request as a `curl` or `httpie` command line.
+#[tauri::command]
+pub async fn export_request_as(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+    format: String,
+    options: Option<SnippetOptions>,
+) -> Result<String, String> {
+    let options = options.unwrap_or_default();
+    let request = load_request(&app, &collection_id, &request_id, &options).await?;
+    match format.as_str() {
+        "curl" => Ok(shell::curl(&request)),
+        "httpie" => Ok(shell::httpie(&request)),
+        other => Err(format!("unsupported export format: {other}")),
+    }
+}
+
+/// Generates client code for a saved request in one of `clients::LANGUAGES`.
+#[tauri::command]
+pub async fn generate_code_snippet(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+    language: String,
+    options: Option<SnippetOptions>,
+) -> Result<String, String> {
+    if !clients::LANGUAGES.contains(&language.as_str()) {
+        return Err(format!("unsupported snippet language: {language}"));
+    }
+    let options = options.unwrap_or_default();
+    let request = load_request(&app, &collection_id, &request_id, &options).await?;
+    clients::generate(&language, &request)
+        .ok_or_else(|| format!("unsupported snippet language: {language}"))
+}
@@ -0,0 +1,176 @@
+//! `curl` and `httpie` command lines. Output is POSIX shell, one option per line.
+
+use super::{SnippetAuth, SnippetBody, SnippetRequest};
+
+/// Single-quotes `text` for a POSIX shell.
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+fn join(args: Vec<String>) -> String {
+    args.join(" \\\n  ")
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+pub fn curl(request: &SnippetRequest) -> String {
+    let has_body = !matches!(request.body, SnippetBody::None);
+    let mut args = vec![match (request.method.as_str(), has_body) {
+        ("HEAD", false) => "curl -I".to_string(),
+        ("GET", false) => "curl".to_string(),
+        (method, _) => format!("curl -X {method}"),
+    }];
+    args.push(quote(&request.url));
+    for (name, value) in &request.headers {
+        args.push(format!("-H {}", quote(&format!("{name}: {value}"))));
+    }
+    if request.needs_json_content_type() {
+        args.push(format!("-H {}", quote("Content-Type: application/json")));
+    }
+    match &request.auth {
+        SnippetAuth::Basic { username, password } => {
+            args.push(format!("-u {}", quote(&format!("{username}:{password}"))))
+        }
+        SnippetAuth::Bearer { .. } => {
+            if let Some(value) = request.bearer_header() {
+                args.push(format!("-H {}", quote(&format!("Authorization: {value}"))));
+            }
+        }
+        SnippetAuth::None => {}
+    }
+    if let Some(cookies) = request.cookie_header() {
+        args.push(format!("-b {}", quote(&cookies)));
+    }
+    match &request.body {
+        SnippetBody::None => {}
+        SnippetBody::Raw { text, .. } => args.push(format!("--data-raw {}", quote(text))),
+        SnippetBody::UrlEncoded(rows) => {
+            for (key, value) in rows {
+                args.push(format!(
+                    "--data-urlencode {}",
+                    quote(&format!("{key}={value}"))
+                ));
+            }
+        }
+        SnippetBody::Multipart(parts) => {
+            for part in parts {
+                match &part.file_path {
+                    Some(path) => {
+                        let mut spec = format!("{}=@\"{path}\"", part.name);
+                        if !part.value.is_empty() && part.value != file_name(path) {
+                            spec.push_str(&format!(";filename=\"{}\"", part.value));
+                        }
+                        args.push(format!("-F {}", quote(&spec)));
+                    }
+                    // --form-string keeps a leading `@` or `<` from being read as a file.
+                    None => args.push(format!(
+                        "--form-string {}",
+                        quote(&format!("{}={}", part.name, part.value))
+                    )),
+                }
+            }
+        }
+        SnippetBody::File(path) => {
+            args.push(format!("--data-binary {}", quote(&format!("@{path}"))))
+        }
+    }
+    if let Some(proxy) = &request.proxy {
+        args.push(format!("-x {}", quote(proxy)));
+    }
+    if request.insecure() {
+        args.push("-k".to_string());
+    }
+    if let Some(timeout) = request.timeout_seconds {
+        args.push(format!("--max-time {timeout}"));
+    }
+    join(args)
+}
+
+/// Escapes httpie's item separators (`:`, `=`, `@`) inside a field name.
+fn item_key(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    for c in key.chars() {
+        if matches!(c, ':' | '=' | '@' | '\\') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+pub fn httpie(request: &SnippetRequest) -> String {
+    let file_body = match &request.body {
+        SnippetBody::File(path) => Some(path),
+        _ => None,
+    };
+    // Binary bodies are piped in on stdin, so stdin can only be ignored otherwise.
+    let mut head = match file_body {
+        Some(_) => "http".to_string(),
+        None => "http --ignore-stdin".to_string(),
+    };
+    match &request.body {
+        SnippetBody::UrlEncoded(_) => head.push_str(" --form"),
+        SnippetBody::Multipart(_) => head.push_str(" --multipart"),
+        _ => {}
+    }
+    let mut args = vec![head];
+    match &request.auth {
+        SnippetAuth::Basic { username, password } => args.push(format!(
+            "--auth {}",
+            quote(&format!("{username}:{password}"))
+        )),
+        SnippetAuth::Bearer { token } if request.bearer_header().is_some() => {
+            args.push(format!("--auth-type=bearer --auth {}", quote(token)))
+        }
+        _ => {}
+    }
+    if let Some(proxy) = &request.proxy {
+        args.push(format!(
+            "--proxy={} --proxy={}",
+            quote(&format!("http:{proxy}")),
+            quote(&format!("https:{proxy}"))
+        ));
+    }
+    if request.insecure() {
+        args.push("--verify=no".to_string());
+    }
+    if let Some(timeout) = request.timeout_seconds {
+        args.push(format!("--timeout={timeout}"));
+    }
+    if let SnippetBody::Raw { text, .. } = &request.body {
+        args.push(format!("--raw {}", quote(text)));
+    }
+    args.push(format!("{} {}", request.method, quote(&request.url)));
+    for (name, value) in &request.headers {
+        args.push(quote(&format!("{}:{value}", item_key(name))));
+    }
+    if request.needs_json_content_type() {
+        args.push(quote("Content-Type:application/json"));
+    }
+    if let Some(cookies) = request.cookie_header() {
+        args.push(quote(&format!("Cookie:{cookies}")));
+    }
+    match &request.body {
+        SnippetBody::UrlEncoded(rows) => {
+            for (key, value) in rows {
+                args.push(quote(&format!("{}={value}", item_key(key))));
+            }
+        }
+        SnippetBody::Multipart(parts) => {
+            for part in parts {
+                args.push(match &part.file_path {
+                    Some(path) => quote(&format!("{}@{path}", item_key(&part.name))),
+                    None => quote(&format!("{}={}", item_key(&part.name), part.value)),
+                });
+            }
+        }
+        _ => {}
+    }
+    let mut command = join(args);
+    if let Some(path) = file_body {
+        command.push_str(&format!(" \\\n  < {}", quote(path)));
+    }
+    command
+}
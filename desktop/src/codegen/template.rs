@@ -0,0 +1,78 @@
+//! A small line-oriented template renderer for client snippets.
+//!
+//! Templates mark slots with `<<name>>`. Multi-line slot values are re-indented to the
+//! slot's line, and a line holding nothing but an empty slot is dropped, so optional
+//! blocks (imports, options, setup code) need no conditionals in the template itself.
+//! Raw string literals go through `multiline` so their contents are never re-indented.
+
+/// Stands in for newlines that must survive rendering untouched.
+const VERBATIM_NEWLINE: char = '\u{0}';
+
+pub fn render(template: &str, slots: &[(&str, String)]) -> String {
+    let mut out = Vec::new();
+    for line in template.lines() {
+        let trimmed = line.trim();
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let sole = slots
+            .iter()
+            .find(|(name, _)| trimmed == format!("<<{name}>>"));
+        if let Some((_, value)) = sole {
+            if value.is_empty() {
+                continue;
+            }
+        }
+        let mut rendered = line.to_string();
+        for (name, value) in slots {
+            let placeholder = format!("<<{name}>>");
+            if rendered.contains(&placeholder) {
+                rendered =
+                    rendered.replace(&placeholder, &value.replace('\n', &format!("\n{indent}")));
+            }
+        }
+        // Re-indenting leaves whitespace on blank lines inside multi-line values.
+        out.extend(rendered.split('\n').map(|l| match l.trim().is_empty() {
+            true => String::new(),
+            false => l.to_string(),
+        }));
+    }
+    let mut text = out.join("\n").replace(VERBATIM_NEWLINE, "\n");
+    text.push('\n');
+    text
+}
+
+/// The body of a raw multi-line literal, if `text` spans lines and has no other control
+/// characters. Callers still check for their language's own delimiter.
+pub fn multiline(text: &str) -> Option<String> {
+    let plain = !text
+        .chars()
+        .any(|c| c.is_control() && c != '\n' && c != '\t');
+    (text.contains('\n') && plain).then(|| text.replace('\n', &VERBATIM_NEWLINE.to_string()))
+}
+
+fn escape(text: &str, unicode: fn(u32) -> String) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&unicode(c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A double-quoted literal valid in JavaScript, Python, Go and Java.
+pub fn quoted(text: &str) -> String {
+    escape(text, |c| format!("\\u{c:04x}"))
+}
+
+/// A double-quoted Rust string literal.
+pub fn rust_quoted(text: &str) -> String {
+    escape(text, |c| format!("\\u{{{c:x}}}"))
+}
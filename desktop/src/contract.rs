@@ -0,0 +1,143 @@
+//! Contract tests from the UI: `litefetch_core::contract` sends every operation of an
+//! imported OpenAPI spec through `send`, against the chosen environment, and reports each
+//! result as a `contract://progress` event and the conformance report as
+//! `contract://finished`.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::async_runtime::Mutex;
+use tauri::{Emitter, State};
+
+use litefetch_core::contract::{ContractReport, OperationResult, Plan};
+
+use crate::send::Shell;
+
+pub struct ContractState {
+    /// Cancel flags of the contract runs in progress.
+    runs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl ContractState {
+    pub fn new() -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct StartedEvent {
+    run_id: String,
+    collection_id: String,
+    total: usize,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct ProgressEvent {
+    run_id: String,
+    completed: usize,
+    total: usize,
+    result: OperationResult,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct FinishedEvent {
+    report: ContractReport,
+    timestamp_ms: u64,
+}
+
+/// Runs contract tests for `spec`, the id of a collection imported from OpenAPI, against
+/// `environment_id` (the active environment when omitted), and returns the report once
+/// every operation has been checked. `methods` limits the run, e.g. to leave out `DELETE`
+/// against a shared environment. The run id arrives first in a `contract://started` event,
+/// for `cancel_contract_tests`.
+#[tauri::command]
+pub async fn run_contract_tests(
+    app: tauri::AppHandle,
+    state: State<'_, ContractState>,
+    spec: String,
+    environment_id: Option<String>,
+    methods: Option<Vec<String>>,
+) -> Result<ContractReport, String> {
+    let collection_id = spec.trim().to_string();
+    let doc = crate::importers::openapi::imported_spec(&app, &collection_id)?
+        .ok_or_else(|| format!("no OpenAPI spec was imported for collection {collection_id}"))?;
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let methods = methods.filter(|m| !m.is_empty());
+    let plan = Plan::new(&doc, &collection, methods.as_deref());
+    if plan.total() == 0 {
+        return Err("spec has no operations to test".to_string());
+    }
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    state
+        .runs
+        .lock()
+        .await
+        .insert(run_id.clone(), cancel.clone());
+    let total = plan.total();
+    let _ = app.emit(
+        "contract://started",
+        StartedEvent {
+            run_id: run_id.clone(),
+            collection_id: collection_id.clone(),
+            total,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    let sender = Shell {
+        app: app.clone(),
+        collection_id: collection_id.clone(),
+        environment_id,
+    };
+    let report = litefetch_core::contract::execute(
+        run_id.clone(),
+        collection_id,
+        &plan,
+        &sender,
+        &cancel,
+        |completed, result| {
+            let _ = app.emit(
+                "contract://progress",
+                ProgressEvent {
+                    run_id: run_id.clone(),
+                    completed,
+                    total,
+                    result: result.clone(),
+                    timestamp_ms: crate::now_ms(),
+                },
+            );
+        },
+    )
+    .await;
+    state.runs.lock().await.remove(&run_id);
+
+    let _ = app.emit(
+        "contract://finished",
+        FinishedEvent {
+            report: report.clone(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    Ok(report)
+}
+
+/// Stops a contract run from sending further requests; the one in flight still finishes.
+#[tauri::command]
+pub async fn cancel_contract_tests(
+    state: State<'_, ContractState>,
+    run_id: String,
+) -> Result<(), String> {
+    let runs = state.runs.lock().await;
+    let cancel = runs
+        .get(&run_id)
+        .ok_or_else(|| format!("unknown contract run: {run_id}"))?;
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
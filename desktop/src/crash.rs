@@ -0,0 +1,189 @@
+//! Crash reports: a panic hook writes the panic message, a backtrace, the app and backend
+//! versions and the tail of the log to `crashes/` under the app data root, and the UI
+//! lists the reports on the next launch for the user to read, export or delete. Nothing
+//! is uploaded.
+
+use serde::{Deserialize, Serialize};
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::PathBuf;
+use tauri::Manager;
+
+const CRASH_DIR: &str = "crashes";
+const LOG_TAIL_LINES: usize = 200;
+/// Reports kept; older ones are deleted as new ones arrive.
+const KEPT_REPORTS: usize = 20;
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct CrashReport {
+    /// `crash-<created_ms>`.
+    pub id: String,
+    pub created_ms: u64,
+    /// Set once the report has been opened.
+    pub reviewed: bool,
+    pub message: String,
+    /// `file:line:column` of the panic.
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+    pub app_version: String,
+    pub backend_version: Option<String>,
+    pub os: String,
+    pub arch: String,
+    /// The last lines of the shell log, redacted like the log itself.
+    pub log_tail: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct CrashSummary {
+    pub id: String,
+    pub created_ms: u64,
+    pub reviewed: bool,
+    pub message: String,
+}
+
+fn crash_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::app_data_root(app)?.join(CRASH_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("crash reports init failed: {e}"))?;
+    Ok(dir)
+}
+
+fn report_path(app: &tauri::AppHandle, id: &str) -> Result<PathBuf, String> {
+    let valid =
+        id.starts_with("crash-") && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        return Err(format!("unknown crash report: {id}"));
+    }
+    Ok(crash_dir(app)?.join(format!("{id}.json")))
+}
+
+fn load(app: &tauri::AppHandle, id: &str) -> Result<CrashReport, String> {
+    let data = fs::read_to_string(report_path(app, id)?)
+        .map_err(|_| format!("unknown crash report: {id}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("crash report parse failed: {e}"))
+}
+
+fn save(app: &tauri::AppHandle, report: &CrashReport) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("crash report serialize failed: {e}"))?;
+    fs::write(report_path(app, &report.id)?, payload)
+        .map_err(|e| format!("crash report write failed: {e}"))
+}
+
+/// Every stored report, newest first.
+fn all(app: &tauri::AppHandle) -> Result<Vec<CrashReport>, String> {
+    let entries =
+        fs::read_dir(crash_dir(app)?).map_err(|e| format!("crash reports read failed: {e}"))?;
+    let mut reports: Vec<CrashReport> = entries
+        .flatten()
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|data| serde_json::from_str(&data).ok())
+        .collect();
+    reports.sort_by_key(|r| std::cmp::Reverse(r.created_ms));
+    Ok(reports)
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panic with a non-text payload".to_string())
+}
+
+fn record(app: &tauri::AppHandle, info: &PanicHookInfo) -> Result<CrashReport, String> {
+    let created_ms = crate::now_ms();
+    let log_tail = crate::logging::tail(&app.state::<crate::logging::LogState>(), LOG_TAIL_LINES)
+        .iter()
+        .map(|line| crate::redact::log_line(line))
+        .collect();
+    let report = CrashReport {
+        id: format!("crash-{created_ms}"),
+        created_ms,
+        reviewed: false,
+        message: panic_message(info),
+        location: info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column())),
+        thread: std::thread::current().name().map(str::to_string),
+        backtrace: Backtrace::force_capture().to_string(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        backend_version: crate::handshake::backend_version(app),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        log_tail,
+    };
+    save(app, &report)?;
+    for old in all(app)?.iter().skip(KEPT_REPORTS) {
+        let _ = fs::remove_file(report_path(app, &old.id)?);
+    }
+    Ok(report)
+}
+
+/// Installs the panic hook, keeping the default one (which prints to stderr) after it.
+pub fn install(app: &tauri::AppHandle) {
+    let app = app.clone();
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        match record(&app, info) {
+            Ok(report) => tracing::error!(
+                report = %report.id,
+                "panic: {}",
+                report.message
+            ),
+            Err(e) => tracing::error!("panic: {}; {e}", panic_message(info)),
+        }
+        previous(info);
+    }));
+}
+
+/// Stored crash reports, newest first; the UI offers the unreviewed ones at startup.
+#[tauri::command]
+pub async fn list_crash_reports(app: tauri::AppHandle) -> Result<Vec<CrashSummary>, String> {
+    Ok(all(&app)?
+        .into_iter()
+        .map(|r| CrashSummary {
+            id: r.id,
+            created_ms: r.created_ms,
+            reviewed: r.reviewed,
+            message: r.message,
+        })
+        .collect())
+}
+
+/// A full crash report, which is marked reviewed.
+#[tauri::command]
+pub async fn get_crash_report(app: tauri::AppHandle, id: String) -> Result<CrashReport, String> {
+    let mut report = load(&app, &id)?;
+    if !report.reviewed {
+        report.reviewed = true;
+        save(&app, &report)?;
+    }
+    Ok(report)
+}
+
+/// Writes a crash report as JSON to `path`, e.g. to attach to an issue, and returns the path.
+#[tauri::command]
+pub async fn export_crash_report(
+    app: tauri::AppHandle,
+    id: String,
+    path: String,
+) -> Result<String, String> {
+    let report = load(&app, &id)?;
+    let payload = serde_json::to_string_pretty(&report)
+        .map_err(|e| format!("crash report serialize failed: {e}"))?;
+    let target = crate::normalize_path(&path);
+    fs::write(&target, payload).map_err(|e| format!("crash report export failed: {e}"))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+#[tauri::command]
+pub async fn delete_crash_report(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let path = report_path(&app, &id)?;
+    if !path.exists() {
+        return Err(format!("unknown crash report: {id}"));
+    }
+    fs::remove_file(path).map_err(|e| format!("crash report delete failed: {e}"))
+}
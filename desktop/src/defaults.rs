@@ -0,0 +1,406 @@
+//! Defaults applied to every request sent through the shell: headers such as `User-Agent` or
+//! a correlation ID (`{{$uuid}}` gives each send a fresh one), plus ordered request and
+//! response interceptors. They are set per workspace in `defaults.json` and per collection in
+//! its `defaults` field; the workspace's apply first, then the collection's. Request changes
+//! happen before variables are resolved, so values may use `{{vars}}` and dynamic values. A
+//! request opts out entirely with `skip_defaults`, or of single interceptors by listing their
+//! ids in `skip_interceptors`.
+//!
+//! A client profile (see `client_profiles`) adds a browser's or tool's `User-Agent` and
+//! matching headers before the default headers, again only where the request has none.
+//!
+//! Generated headers give each send fresh ids, such as an `Idempotency-Key` for POSTs or a
+//! W3C `traceparent` (or a whole trace context, see `trace`), unless the request sets the
+//! header itself. The values sent are kept in the request's `correlation_ids`, which the
+//! history records and searches, so a send can be found from the ids in a server's logs.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::client_profiles::ClientProfile;
+use crate::importers::str_of;
+use crate::trace::TraceContext;
+
+pub(crate) const SETTINGS_FILE: &str = "defaults.json";
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RequestDefaults {
+    /// Added to requests that don't set the header themselves.
+    pub headers: Vec<DefaultHeader>,
+    pub request_interceptors: Vec<RequestInterceptor>,
+    pub response_interceptors: Vec<ResponseInterceptor>,
+    pub generated_headers: Vec<GeneratedHeader>,
+    /// A collection's replaces the workspace's.
+    pub trace_context: Option<TraceContext>,
+    /// The client profile requests go out as (see `client_profiles`); a collection's
+    /// replaces the workspace's, and a request's own `client_profile` both.
+    pub client_profile: Option<String>,
+    /// Custom client profiles, next to the built-in ones.
+    pub client_profiles: Vec<ClientProfile>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct GeneratedHeader {
+    /// E.g. `Idempotency-Key`, `X-Correlation-ID` or `traceparent`.
+    pub name: String,
+    pub format: IdFormat,
+    /// Methods the header is added to, e.g. `["POST", "PATCH"]`; every method when empty.
+    #[serde(default)]
+    pub methods: Vec<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum IdFormat {
+    Uuid,
+    Ulid,
+    /// A W3C trace context header: `00-<trace id>-<span id>-01`.
+    Traceparent,
+}
+
+impl IdFormat {
+    fn generate(self) -> String {
+        match self {
+            IdFormat::Uuid => uuid::Uuid::new_v4().to_string(),
+            IdFormat::Ulid => litefetch_core::dynamic::ulid(),
+            IdFormat::Traceparent => TraceContext::traceparent(true),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct DefaultHeader {
+    pub name: String,
+    pub value: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct RequestInterceptor {
+    pub id: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub action: RequestAction,
+}
+
+/// A change made to the outgoing request.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RequestAction {
+    /// Sets the header, replacing the request's own value.
+    SetHeader {
+        name: String,
+        value: String,
+    },
+    RemoveHeader {
+        name: String,
+    },
+    /// Sets the query parameter, replacing any with the same name.
+    SetQueryParam {
+        name: String,
+        value: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct ResponseInterceptor {
+    pub id: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub action: ResponseAction,
+}
+
+/// A step run on the response before tests and the UI see it.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ResponseAction {
+    /// Saves a response header, or the status code without one, in the active environment.
+    CaptureVariable {
+        #[serde(default)]
+        header: Option<String>,
+        variable: String,
+    },
+    /// Drops a header from the result, e.g. a noisy `Set-Cookie` or `Server-Timing`.
+    RemoveHeader { name: String },
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl RequestDefaults {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(crate::load_workspace_path(app)?.join(SETTINGS_FILE))
+    }
+
+    /// The current workspace's defaults, or none if it has no `defaults.json`.
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data =
+            fs::read_to_string(&path).map_err(|e| format!("request defaults read failed: {e}"))?;
+        serde_json::from_str(&data).map_err(|e| format!("request defaults parse failed: {e}"))
+    }
+
+    /// The workspace's defaults followed by the collection's, minus what `request` opts out of.
+    pub fn for_request(
+        app: &tauri::AppHandle,
+        collection: &Value,
+        request: &Value,
+    ) -> Result<Self, String> {
+        let mut defaults = Self::load(app)?;
+        if let Some(own) = collection.get("defaults").filter(|d| d.is_object()) {
+            let own: Self = serde_json::from_value(own.clone())
+                .map_err(|e| format!("collection defaults parse failed: {e}"))?;
+            defaults.headers.extend(own.headers);
+            defaults
+                .request_interceptors
+                .extend(own.request_interceptors);
+            defaults
+                .response_interceptors
+                .extend(own.response_interceptors);
+            defaults.generated_headers.extend(own.generated_headers);
+            if own.trace_context.is_some() {
+                defaults.trace_context = own.trace_context;
+            }
+            if own.client_profile.is_some() {
+                defaults.client_profile = own.client_profile;
+            }
+            defaults.client_profiles.extend(own.client_profiles);
+        }
+        if request.get("skip_defaults").and_then(Value::as_bool) == Some(true) {
+            // Only so the request's own `client_profile` can name a custom profile.
+            return Ok(Self {
+                client_profiles: defaults.client_profiles,
+                ..Self::default()
+            });
+        }
+        let skipped: Vec<&str> = request
+            .get("skip_interceptors")
+            .and_then(Value::as_array)
+            .map(|ids| ids.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        defaults.headers.retain(|h| h.enabled);
+        defaults.generated_headers.retain(|h| h.enabled);
+        defaults
+            .request_interceptors
+            .retain(|i| i.enabled && !skipped.contains(&i.id.as_str()));
+        defaults
+            .response_interceptors
+            .retain(|i| i.enabled && !skipped.contains(&i.id.as_str()));
+        Ok(defaults)
+    }
+
+    /// Adds the client profile's headers, then the default headers, and runs the request
+    /// interceptors, in order.
+    pub fn apply_request(&self, request: &mut Value) {
+        if !request.get("headers").is_some_and(Value::is_object) {
+            request["headers"] = Value::Object(Map::new());
+        }
+        let profile = match str_of(request, "client_profile").trim() {
+            "" => self
+                .client_profile
+                .clone()
+                .filter(|id| !id.trim().is_empty()),
+            id => Some(id.to_string()),
+        };
+        if let Some(id) = profile {
+            match crate::client_profiles::find(&self.client_profiles, id.trim()) {
+                Some(profile) => {
+                    for header in profile.headers.iter().filter(|h| h.enabled) {
+                        let name = header.name.trim();
+                        if !name.is_empty() && header_key(request, name).is_none() {
+                            request["headers"][name] = Value::String(header.value.clone());
+                        }
+                    }
+                }
+                None => tracing::warn!("unknown client profile: {id}"),
+            }
+        }
+        for header in &self.headers {
+            let name = header.name.trim();
+            if !name.is_empty() && header_key(request, name).is_none() {
+                request["headers"][name] = Value::String(header.value.clone());
+            }
+        }
+        for interceptor in &self.request_interceptors {
+            match &interceptor.action {
+                RequestAction::SetHeader { name, value } => {
+                    let name = name.trim();
+                    if name.is_empty() {
+                        continue;
+                    }
+                    remove_header(request, name);
+                    request["headers"][name] = Value::String(value.clone());
+                }
+                RequestAction::RemoveHeader { name } => remove_header(request, name.trim()),
+                RequestAction::SetQueryParam { name, value } => {
+                    set_query_param(request, name.trim(), value)
+                }
+            }
+        }
+    }
+
+    /// Adds fresh values for the generated headers `request` doesn't set itself, and records
+    /// every one it goes out with in `correlation_ids`.
+    pub fn generate_ids(&self, request: &mut Value) {
+        let method = match str_of(request, "method") {
+            "" => "GET".to_string(),
+            method => method.to_uppercase(),
+        };
+        let mut ids = Map::new();
+        for header in &self.generated_headers {
+            let name = header.name.trim();
+            if name.is_empty()
+                || !(header.methods.is_empty()
+                    || header
+                        .methods
+                        .iter()
+                        .any(|m| m.eq_ignore_ascii_case(&method)))
+            {
+                continue;
+            }
+            let value = set_generated(request, name, || header.format.generate());
+            ids.insert(name.to_string(), Value::String(value));
+        }
+        if let Some(trace) = self.trace_context.as_ref().filter(|t| t.enabled) {
+            let parent = set_generated(request, "traceparent", || {
+                TraceContext::traceparent(trace.sampled)
+            });
+            ids.insert("traceparent".to_string(), Value::String(parent));
+            if let Some(state) = trace.tracestate.as_deref().filter(|s| !s.trim().is_empty()) {
+                let state = set_generated(request, "tracestate", || state.trim().to_string());
+                ids.insert("tracestate".to_string(), Value::String(state));
+            }
+        }
+        if !ids.is_empty() {
+            request["correlation_ids"] = Value::Object(ids);
+        }
+    }
+
+    /// Runs the response interceptors on `result` and returns the variables they captured.
+    pub fn apply_response(&self, result: &mut Value) -> HashMap<String, String> {
+        let mut captured = HashMap::new();
+        for interceptor in &self.response_interceptors {
+            match &interceptor.action {
+                ResponseAction::CaptureVariable { header, variable } => {
+                    let value = match header {
+                        Some(name) => result
+                            .get("headers")
+                            .and_then(Value::as_object)
+                            .and_then(|h| {
+                                h.iter()
+                                    .find(|(k, _)| k.eq_ignore_ascii_case(name.trim()))
+                                    .map(|(_, v)| v)
+                            })
+                            .and_then(Value::as_str)
+                            .map(str::to_string),
+                        None => result
+                            .get("status_code")
+                            .and_then(Value::as_u64)
+                            .map(|s| s.to_string()),
+                    };
+                    if let Some(value) = value.filter(|_| !variable.trim().is_empty()) {
+                        captured.insert(variable.trim().to_string(), value);
+                    }
+                }
+                ResponseAction::RemoveHeader { name } => {
+                    if let Some(headers) = result.get_mut("headers").and_then(Value::as_object_mut)
+                    {
+                        headers.retain(|k, _| !k.eq_ignore_ascii_case(name.trim()));
+                    }
+                }
+            }
+        }
+        captured
+    }
+}
+
+/// The request's own value for header `name`, or one from `generate` set on it.
+fn set_generated(request: &mut Value, name: &str, generate: impl FnOnce() -> String) -> String {
+    match header_key(request, name) {
+        Some(key) => str_of(&request["headers"], &key).to_string(),
+        None => {
+            let value = generate();
+            request["headers"][name] = Value::String(value.clone());
+            value
+        }
+    }
+}
+
+fn header_key(request: &Value, name: &str) -> Option<String> {
+    request
+        .get("headers")
+        .and_then(Value::as_object)?
+        .keys()
+        .find(|k| k.eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+fn remove_header(request: &mut Value, name: &str) {
+    if let Some(headers) = request.get_mut("headers").and_then(Value::as_object_mut) {
+        headers.retain(|k, _| !k.eq_ignore_ascii_case(name));
+    }
+}
+
+fn set_query_param(request: &mut Value, name: &str, value: &str) {
+    if name.is_empty() {
+        return;
+    }
+    // Enabled rows replace the URL's query in the engine, so the parameter goes where it's read.
+    if let Some(rows) = request
+        .get_mut("query_params")
+        .and_then(Value::as_array_mut)
+        .filter(|rows| {
+            rows.iter()
+                .any(|r| r.get("enabled").and_then(Value::as_bool) != Some(false))
+        })
+    {
+        rows.retain(|r| str_of(r, "key") != name);
+        rows.push(json!({ "key": name, "value": value, "enabled": true }));
+        return;
+    }
+    let Ok(mut url) = reqwest::Url::parse(str_of(request, "url").trim()) else {
+        return;
+    };
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(k, _)| k != name)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(kept)
+        .append_pair(name, value);
+    request["url"] = Value::String(url.to_string());
+}
+
+/// Reads the current workspace's default headers and interceptors.
+#[tauri::command]
+pub async fn get_workspace_defaults(app: tauri::AppHandle) -> Result<RequestDefaults, String> {
+    RequestDefaults::load(&app)
+}
+
+#[tauri::command]
+pub async fn set_workspace_defaults(
+    app: tauri::AppHandle,
+    defaults: RequestDefaults,
+) -> Result<RequestDefaults, String> {
+    crate::lock::ensure_writable(&app)?;
+    let payload = serde_json::to_string_pretty(&defaults)
+        .map_err(|e| format!("request defaults serialize failed: {e}"))?;
+    fs::write(RequestDefaults::path(&app)?, payload)
+        .map_err(|e| format!("request defaults persist failed: {e}"))?;
+    Ok(defaults)
+}
@@ -0,0 +1,20 @@
+//! Response comparison for the UI; the diff itself lives in `litefetch_core::diff`.
+
+use serde_json::Value;
+
+use litefetch_core::diff::{DiffOptions, ResponseDiff};
+
+/// Compares two responses (`RequestResult`s, e.g. from history or two environments): the
+/// status codes, a structural diff of the bodies and a diff of the headers.
+#[tauri::command]
+pub async fn diff_responses(
+    a: Value,
+    b: Value,
+    options: Option<DiffOptions>,
+) -> Result<ResponseDiff, String> {
+    Ok(litefetch_core::diff::diff(
+        &a,
+        &b,
+        &options.unwrap_or_default(),
+    ))
+}
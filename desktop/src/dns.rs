@@ -0,0 +1,350 @@
+//! DNS lookups from inside the app, to tell a name that doesn't resolve (or resolves
+//! somewhere unexpected) apart from an API that misbehaves. Queries go straight to the
+//! system's first configured nameserver (or one the caller names) over UDP, retried over TCP
+//! when the answer is truncated.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+
+use litefetch_core::dynamic::random_below;
+
+/// Used when the system's nameserver can't be read, e.g. on Windows.
+const FALLBACK_RESOLVER: IpAddr = IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1));
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Compression pointers followed per name before the answer counts as malformed.
+const MAX_JUMPS: usize = 32;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum RecordType {
+    #[default]
+    A,
+    Aaaa,
+    Cname,
+    Txt,
+    Mx,
+}
+
+impl RecordType {
+    fn code(self) -> u16 {
+        match self {
+            RecordType::A => 1,
+            RecordType::Cname => 5,
+            RecordType::Mx => 15,
+            RecordType::Txt => 16,
+            RecordType::Aaaa => 28,
+        }
+    }
+
+    fn from_code(code: u16) -> Option<Self> {
+        [
+            RecordType::A,
+            RecordType::Aaaa,
+            RecordType::Cname,
+            RecordType::Txt,
+            RecordType::Mx,
+        ]
+        .into_iter()
+        .find(|t| t.code() == code)
+    }
+}
+
+#[derive(Serialize)]
+pub struct DnsRecord {
+    pub name: String,
+    pub record_type: RecordType,
+    pub ttl: u32,
+    /// The address, target name or text.
+    pub value: String,
+    /// MX preference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub priority: Option<u16>,
+}
+
+#[derive(Serialize)]
+pub struct DnsLookup {
+    pub host: String,
+    pub record_type: RecordType,
+    /// The nameserver asked, as `address:port`.
+    pub resolver: String,
+    /// `udp`, or `tcp` after a truncated answer.
+    pub transport: &'static str,
+    /// `NOERROR`, `NXDOMAIN`, `SERVFAIL` and so on.
+    pub status: String,
+    pub duration_ms: f64,
+    /// The answer section in order, so an `A` lookup lists any `CNAME` chain first.
+    pub records: Vec<DnsRecord>,
+}
+
+/// The first `nameserver` in `/etc/resolv.conf`.
+fn system_resolver() -> Option<IpAddr> {
+    let conf = std::fs::read_to_string("/etc/resolv.conf").ok()?;
+    conf.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("nameserver")?;
+        // Scoped IPv6 addresses (`fe80::1%eth0`) can't be dialed without the scope id.
+        rest.trim().split('%').next()?.parse().ok()
+    })
+}
+
+fn resolver_addr(resolver: Option<&str>) -> Result<SocketAddr, String> {
+    let Some(text) = resolver.map(str::trim).filter(|r| !r.is_empty()) else {
+        return Ok(SocketAddr::new(
+            system_resolver().unwrap_or(FALLBACK_RESOLVER),
+            53,
+        ));
+    };
+    if let Ok(addr) = text.parse::<SocketAddr>() {
+        return Ok(addr);
+    }
+    text.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse::<IpAddr>()
+        .map(|ip| SocketAddr::new(ip, 53))
+        .map_err(|_| format!("invalid resolver address: {text}"))
+}
+
+/// The host name alone, also when given a URL.
+pub(crate) fn host_of(input: &str) -> String {
+    let input = input.trim();
+    let rest = input.split_once("://").map_or(input, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host.strip_prefix('[') {
+        Some(v6) => v6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+    host.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn query(id: u16, host: &str, record_type: RecordType) -> Result<Vec<u8>, String> {
+    if host.is_empty() || host.len() > 253 {
+        return Err(format!("invalid host name: {host}"));
+    }
+    let mut message = Vec::with_capacity(host.len() + 18);
+    message.extend_from_slice(&id.to_be_bytes());
+    // Recursion desired; one question.
+    message.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in host.split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(format!("invalid host name: {host}"));
+        }
+        message.push(label.len() as u8);
+        message.extend_from_slice(label.as_bytes());
+    }
+    message.push(0);
+    message.extend_from_slice(&record_type.code().to_be_bytes());
+    message.extend_from_slice(&1u16.to_be_bytes());
+    Ok(message)
+}
+
+struct Reader<'a> {
+    message: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn bytes(&mut self, n: usize) -> Result<&[u8], String> {
+        let end = self.pos + n;
+        let slice = self
+            .message
+            .get(self.pos..end)
+            .ok_or("DNS answer is truncated")?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    /// A possibly compressed name; leaves the reader after it.
+    fn name(&mut self) -> Result<String, String> {
+        let mut labels = Vec::new();
+        let mut pos = self.pos;
+        let mut resume = None;
+        for _ in 0..MAX_JUMPS {
+            let len = *self.message.get(pos).ok_or("DNS answer is truncated")? as usize;
+            if len & 0xC0 == 0xC0 {
+                let low = *self.message.get(pos + 1).ok_or("DNS answer is truncated")? as usize;
+                resume.get_or_insert(pos + 2);
+                pos = ((len & 0x3F) << 8) | low;
+                continue;
+            }
+            if len == 0 {
+                self.pos = resume.unwrap_or(pos + 1);
+                return Ok(labels.join("."));
+            }
+            let label = self
+                .message
+                .get(pos + 1..pos + 1 + len)
+                .ok_or("DNS answer is truncated")?;
+            labels.push(String::from_utf8_lossy(label).into_owned());
+            pos += 1 + len;
+        }
+        Err("DNS answer has a name compression loop".to_string())
+    }
+}
+
+fn status_name(rcode: u16) -> String {
+    match rcode {
+        0 => "NOERROR".to_string(),
+        1 => "FORMERR".to_string(),
+        2 => "SERVFAIL".to_string(),
+        3 => "NXDOMAIN".to_string(),
+        4 => "NOTIMP".to_string(),
+        5 => "REFUSED".to_string(),
+        other => format!("RCODE{other}"),
+    }
+}
+
+struct Answer {
+    truncated: bool,
+    status: String,
+    records: Vec<DnsRecord>,
+}
+
+fn parse(message: &[u8], id: u16) -> Result<Answer, String> {
+    let mut reader = Reader { message, pos: 0 };
+    if reader.u16()? != id {
+        return Err("DNS answer doesn't match the query".to_string());
+    }
+    let flags = reader.u16()?;
+    let questions = reader.u16()?;
+    let answers = reader.u16()?;
+    reader.bytes(4)?;
+    for _ in 0..questions {
+        reader.name()?;
+        reader.bytes(4)?;
+    }
+    let mut records = Vec::new();
+    for _ in 0..answers {
+        let name = reader.name()?;
+        let code = reader.u16()?;
+        reader.u16()?;
+        let ttl = reader.u32()?;
+        let len = reader.u16()? as usize;
+        let start = reader.pos;
+        let end = start + len;
+        let Some(record_type) = RecordType::from_code(code) else {
+            reader.bytes(len)?;
+            continue;
+        };
+        let mut priority = None;
+        let value = match record_type {
+            RecordType::A => {
+                let b = reader.bytes(4)?;
+                Ipv4Addr::new(b[0], b[1], b[2], b[3]).to_string()
+            }
+            RecordType::Aaaa => {
+                let b: [u8; 16] = reader.bytes(16)?.try_into().map_err(|_| "bad AAAA")?;
+                Ipv6Addr::from(b).to_string()
+            }
+            RecordType::Cname => reader.name()?,
+            RecordType::Mx => {
+                priority = Some(reader.u16()?);
+                reader.name()?
+            }
+            RecordType::Txt => {
+                // One or more length-prefixed strings, joined as resolvers usually show them.
+                let mut text = String::new();
+                while reader.pos < end {
+                    let n = reader.bytes(1)?[0] as usize;
+                    text.push_str(&String::from_utf8_lossy(reader.bytes(n)?));
+                }
+                text
+            }
+        };
+        reader.pos = end;
+        records.push(DnsRecord {
+            name,
+            record_type,
+            ttl,
+            value,
+            priority,
+        });
+    }
+    Ok(Answer {
+        truncated: flags & 0x0200 != 0,
+        status: status_name(flags & 0x000F),
+        records,
+    })
+}
+
+async fn over_udp(resolver: SocketAddr, message: &[u8]) -> Result<Vec<u8>, String> {
+    let local: SocketAddr = match resolver {
+        SocketAddr::V4(_) => (Ipv4Addr::UNSPECIFIED, 0).into(),
+        SocketAddr::V6(_) => (Ipv6Addr::UNSPECIFIED, 0).into(),
+    };
+    let socket = UdpSocket::bind(local)
+        .await
+        .map_err(|e| format!("DNS socket failed: {e}"))?;
+    socket
+        .send_to(message, resolver)
+        .await
+        .map_err(|e| format!("DNS query failed: {e}"))?;
+    let mut buffer = vec![0u8; 4096];
+    let (n, _) = tokio::time::timeout(TIMEOUT, socket.recv_from(&mut buffer))
+        .await
+        .map_err(|_| format!("DNS query to {resolver} timed out"))?
+        .map_err(|e| format!("DNS query failed: {e}"))?;
+    buffer.truncate(n);
+    Ok(buffer)
+}
+
+async fn over_tcp(resolver: SocketAddr, message: &[u8]) -> Result<Vec<u8>, String> {
+    let exchange = async {
+        let mut stream = TcpStream::connect(resolver).await?;
+        let mut framed = (message.len() as u16).to_be_bytes().to_vec();
+        framed.extend_from_slice(message);
+        stream.write_all(&framed).await?;
+        let len = stream.read_u16().await? as usize;
+        let mut buffer = vec![0u8; len];
+        stream.read_exact(&mut buffer).await?;
+        Ok::<_, std::io::Error>(buffer)
+    };
+    tokio::time::timeout(TIMEOUT, exchange)
+        .await
+        .map_err(|_| format!("DNS query to {resolver} timed out"))?
+        .map_err(|e| format!("DNS query failed: {e}"))
+}
+
+/// Looks up `host` (a name or a URL) for one record type, `A` when omitted. `resolver` is a
+/// nameserver address such as `8.8.8.8` or `[2606:4700::1111]:53`; the system's otherwise.
+#[tauri::command]
+pub async fn dns_lookup(
+    host: String,
+    record_type: Option<RecordType>,
+    resolver: Option<String>,
+) -> Result<DnsLookup, String> {
+    let host = host_of(&host);
+    let record_type = record_type.unwrap_or_default();
+    let resolver = resolver_addr(resolver.as_deref())?;
+    let id = random_below(1 << 16) as u16;
+    let message = query(id, &host, record_type)?;
+
+    let started = Instant::now();
+    let mut transport = "udp";
+    let mut answer = parse(&over_udp(resolver, &message).await?, id)?;
+    if answer.truncated {
+        transport = "tcp";
+        answer = parse(&over_tcp(resolver, &message).await?, id)?;
+    }
+    Ok(DnsLookup {
+        host,
+        record_type,
+        resolver: resolver.to_string(),
+        transport,
+        status: answer.status,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        records: answer.records,
+    })
+}
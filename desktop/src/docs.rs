@@ -0,0 +1,263 @@
+//! Generating API documentation from a collection (see `litefetch_core::docs`), and a local
+//! preview server that serves it for the whole workspace: `/` lists the collections,
+//! `/collections/<id>` renders one as HTML and `/collections/<id>.md` as Markdown. Pages are
+//! rendered on each request and reload themselves when the workspace changes on disk (see
+//! `watcher`), so the docs follow edits as they are made.
+
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{EventId, Listener, State};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use litefetch_core::docs::{self, DocsFormat};
+
+/// How often an open page asks whether the workspace changed.
+const RELOAD_POLL_MS: u64 = 1000;
+
+pub struct DocsState {
+    server: Mutex<Option<DocsServer>>,
+}
+
+impl DocsState {
+    pub fn new() -> Self {
+        Self {
+            server: Mutex::new(None),
+        }
+    }
+}
+
+struct DocsServer {
+    info: DocsServerInfo,
+    listener: EventId,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct DocsServerInfo {
+    port: u16,
+    url: String,
+    started_at_ms: u64,
+}
+
+struct DocsContext {
+    app: tauri::AppHandle,
+    /// Bumped on every workspace change; pages reload when it moves.
+    version: AtomicU64,
+}
+
+/// Renders Markdown or HTML documentation for a collection and returns it. With `path` the
+/// documentation is also written there, e.g. into the API's own repository.
+#[tauri::command]
+pub async fn generate_docs(
+    app: tauri::AppHandle,
+    collection_id: String,
+    format: DocsFormat,
+    path: Option<String>,
+) -> Result<String, String> {
+    let content = render(&app, &collection_id, format).await?;
+    if let Some(path) = path.filter(|p| !p.trim().is_empty()) {
+        std::fs::write(crate::normalize_path(&path), &content)
+            .map_err(|e| format!("docs write failed: {e}"))?;
+    }
+    Ok(content)
+}
+
+async fn render(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    format: DocsFormat,
+) -> Result<String, String> {
+    let collection =
+        crate::backend_get(app, &format!("/collections/{collection_id}/collection")).await?;
+    let environment =
+        crate::backend_get(app, &format!("/collections/{collection_id}/environment")).await?;
+    Ok(docs::render(&collection, &environment, format))
+}
+
+/// The script that reloads a page once the workspace version moves past `version`.
+fn reload_script(version: u64) -> String {
+    format!(
+        "<script>(function(){{var v={version};setInterval(function(){{\
+         fetch('/__version').then(function(r){{return r.text()}})\
+         .then(function(t){{if(Number(t)!==v)location.reload()}}).catch(function(){{}})}},\
+         {RELOAD_POLL_MS})}})()</script>"
+    )
+}
+
+fn reply(status: StatusCode, content_type: &str, body: String) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header("content-type", content_type)
+        .header("cache-control", "no-store")
+        .body(Full::new(Bytes::from(body)))
+        .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+}
+
+async fn page(context: &DocsContext, path: &str) -> Result<Response<Full<Bytes>>, String> {
+    let version = context.version.load(Ordering::Relaxed);
+    let live = |html: String| {
+        let html = html.replacen("</body>", &format!("{}</body>", reload_script(version)), 1);
+        reply(StatusCode::OK, "text/html; charset=utf-8", html)
+    };
+    if path == "/__version" {
+        return Ok(reply(StatusCode::OK, "text/plain", version.to_string()));
+    }
+    if path == "/" {
+        let collections = crate::backend_get(&context.app, "/collections").await?;
+        let list = collections
+            .as_array()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        return Ok(live(docs::index(list)));
+    }
+    let Some(id) = path.strip_prefix("/collections/") else {
+        return Ok(reply(
+            StatusCode::NOT_FOUND,
+            "text/plain",
+            "not found".into(),
+        ));
+    };
+    let (id, format) = match id.strip_suffix(".md") {
+        Some(id) => (id, DocsFormat::Markdown),
+        None => (id, DocsFormat::Html),
+    };
+    // Anything else could reach other backend routes through this server.
+    if crate::sealed_env::check_collection_id(id).is_err() {
+        return Ok(reply(
+            StatusCode::NOT_FOUND,
+            "text/plain",
+            "not found".into(),
+        ));
+    }
+    Ok(match format {
+        DocsFormat::Markdown => reply(
+            StatusCode::OK,
+            "text/markdown; charset=utf-8",
+            render(&context.app, id, format).await?,
+        ),
+        DocsFormat::Html => live(render(&context.app, id, format).await?),
+    })
+}
+
+async fn handle(
+    context: Arc<DocsContext>,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let path = request.uri().path().to_string();
+    Ok(match page(&context, &path).await {
+        Ok(response) => response,
+        Err(e) => reply(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "text/plain",
+            format!("docs render failed: {e}"),
+        ),
+    })
+}
+
+async fn serve(
+    context: Arc<DocsContext>,
+    listener: TcpListener,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else { continue };
+                let context = context.clone();
+                tauri::async_runtime::spawn(async move {
+                    let service = hyper::service::service_fn(move |req| handle(context.clone(), req));
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        }
+    }
+}
+
+/// Serves the workspace's docs on `port` (any free port when omitted). Only one preview
+/// server runs at a time; starting another replaces it.
+#[tauri::command]
+pub async fn start_docs_server(
+    app: tauri::AppHandle,
+    state: State<'_, DocsState>,
+    port: Option<u16>,
+    bind_all: Option<bool>,
+) -> Result<DocsServerInfo, String> {
+    stop(&app, &state).await;
+    let host = if bind_all.unwrap_or(false) {
+        "0.0.0.0"
+    } else {
+        "127.0.0.1"
+    };
+    let listener = TcpListener::bind((host, port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("docs server bind failed: {e}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("docs server bind failed: {e}"))?
+        .port();
+
+    let context = Arc::new(DocsContext {
+        app: app.clone(),
+        version: AtomicU64::new(0),
+    });
+    let watched = context.clone();
+    let events = app.listen_any("workspace://changed", move |_| {
+        watched.version.fetch_add(1, Ordering::Relaxed);
+    });
+    let info = DocsServerInfo {
+        port,
+        url: format!("http://127.0.0.1:{port}"),
+        started_at_ms: crate::now_ms(),
+    };
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let task = tauri::async_runtime::spawn(serve(context, listener, shutdown_rx));
+    *state.server.lock().await = Some(DocsServer {
+        info: info.clone(),
+        listener: events,
+        shutdown: Some(shutdown),
+        task,
+    });
+    Ok(info)
+}
+
+async fn stop(app: &tauri::AppHandle, state: &DocsState) {
+    if let Some(mut server) = state.server.lock().await.take() {
+        app.unlisten(server.listener);
+        match server.shutdown.take() {
+            Some(tx) => {
+                let _ = tx.send(());
+            }
+            None => server.task.abort(),
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn stop_docs_server(
+    app: tauri::AppHandle,
+    state: State<'_, DocsState>,
+) -> Result<(), String> {
+    stop(&app, &state).await;
+    Ok(())
+}
+
+/// The running preview server, if any.
+#[tauri::command]
+pub async fn get_docs_server(
+    state: State<'_, DocsState>,
+) -> Result<Option<DocsServerInfo>, String> {
+    Ok(state.server.lock().await.as_ref().map(|s| s.info.clone()))
+}
@@ -0,0 +1,225 @@
+//! Draft autosave: unsaved request edits are written to `drafts` in the app data root a
+//! moment after the last change (`save_draft`), and dropped once the request is saved or
+//! the edit is abandoned (`discard_draft`). A session marker is kept while the app runs and
+//! removed on a clean exit, so drafts found behind a leftover marker at startup came from a
+//! crash and are offered back through `list_recovered_drafts` / `restore_draft`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Manager, State};
+
+const SESSION_MARKER: &str = "session.lock";
+/// Quiet time after an edit before the draft is written.
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+pub struct DraftState {
+    pending: Mutex<HashMap<String, JoinHandle<()>>>,
+    /// The previous session ended without a clean exit.
+    recovering: AtomicBool,
+}
+
+impl DraftState {
+    pub fn new() -> Self {
+        Self {
+            pending: Mutex::new(HashMap::new()),
+            recovering: AtomicBool::new(false),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Draft {
+    pub draft_id: String,
+    pub workspace: String,
+    pub collection_id: String,
+    pub request_id: String,
+    /// The edited request, in the collection's `HttpRequest` shape.
+    pub request: Value,
+    pub saved_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct DraftSummary {
+    pub draft_id: String,
+    pub collection_id: String,
+    pub request_id: String,
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub saved_ms: u64,
+}
+
+fn drafts_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = crate::app_data_root(app)?.join("drafts");
+    fs::create_dir_all(&dir).map_err(|e| format!("drafts init failed: {e}"))?;
+    Ok(dir)
+}
+
+fn draft_id(workspace: &str, collection_id: &str, request_id: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    (workspace, collection_id, request_id).hash(&mut hasher);
+    format!("draft-{:016x}", hasher.finish())
+}
+
+/// Draft ids come back from the UI; keep them to the files they name.
+fn draft_path(app: &tauri::AppHandle, draft_id: &str) -> Result<PathBuf, String> {
+    if !draft_id.starts_with("draft-")
+        || !draft_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(format!("unknown draft: {draft_id}"));
+    }
+    Ok(drafts_dir(app)?.join(format!("{draft_id}.json")))
+}
+
+fn read_draft(app: &tauri::AppHandle, draft_id: &str) -> Result<Draft, String> {
+    let data = fs::read_to_string(draft_path(app, draft_id)?)
+        .map_err(|_| format!("unknown draft: {draft_id}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("draft parse failed: {e}"))
+}
+
+fn write_draft(app: &tauri::AppHandle, draft: &Draft) -> Result<(), String> {
+    let payload =
+        serde_json::to_string(draft).map_err(|e| format!("draft serialize failed: {e}"))?;
+    // Written aside and renamed, so a crash mid-write leaves the previous draft intact.
+    let path = draft_path(app, &draft.draft_id)?;
+    let partial = path.with_extension("json.partial");
+    fs::write(&partial, payload).map_err(|e| format!("draft persist failed: {e}"))?;
+    fs::rename(&partial, &path).map_err(|e| format!("draft persist failed: {e}"))
+}
+
+/// Starts a session: notes whether the last one crashed, then leaves the marker for this one.
+pub fn begin_session(app: &tauri::AppHandle) {
+    let marker = match drafts_dir(app) {
+        Ok(dir) => dir.join(SESSION_MARKER),
+        Err(e) => {
+            tracing::warn!("{e}");
+            return;
+        }
+    };
+    app.state::<DraftState>()
+        .recovering
+        .store(marker.exists(), Ordering::SeqCst);
+    if let Err(e) = fs::write(&marker, crate::now_ms().to_string()) {
+        tracing::warn!("session marker write failed: {e}");
+    }
+}
+
+/// Ends the session cleanly; drafts written so far are not offered as recovered next time.
+pub fn end_session(app: &tauri::AppHandle) {
+    if let Ok(dir) = drafts_dir(app) {
+        let _ = fs::remove_file(dir.join(SESSION_MARKER));
+    }
+}
+
+/// Records unsaved edits to a request. The draft is written once edits pause, replacing the
+/// previous one; returns its id.
+#[tauri::command]
+pub async fn save_draft(
+    app: tauri::AppHandle,
+    state: State<'_, DraftState>,
+    collection_id: String,
+    request_id: String,
+    request: Value,
+) -> Result<String, String> {
+    let workspace = crate::load_workspace_path(&app)?
+        .to_string_lossy()
+        .to_string();
+    let id = draft_id(&workspace, &collection_id, &request_id);
+    let draft = Draft {
+        draft_id: id.clone(),
+        workspace,
+        collection_id,
+        request_id,
+        request,
+        saved_ms: crate::now_ms(),
+    };
+    let mut pending = state.pending.lock().await;
+    if let Some(task) = pending.remove(&id) {
+        task.abort();
+    }
+    let task = tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(DEBOUNCE).await;
+        if let Err(e) = write_draft(&app, &draft) {
+            tracing::warn!("{e}");
+        }
+    });
+    pending.insert(id.clone(), task);
+    Ok(id)
+}
+
+/// Drops the draft of a request, pending write included: it was saved or reverted.
+#[tauri::command]
+pub async fn discard_draft(
+    app: tauri::AppHandle,
+    state: State<'_, DraftState>,
+    collection_id: String,
+    request_id: String,
+) -> Result<(), String> {
+    let workspace = crate::load_workspace_path(&app)?
+        .to_string_lossy()
+        .to_string();
+    let id = draft_id(&workspace, &collection_id, &request_id);
+    if let Some(task) = state.pending.lock().await.remove(&id) {
+        task.abort();
+    }
+    let path = draft_path(&app, &id)?;
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("draft discard failed: {e}"))?;
+    }
+    Ok(())
+}
+
+/// Drafts of the current workspace left behind by a session that didn't exit cleanly,
+/// newest first; empty after a clean exit.
+#[tauri::command]
+pub async fn list_recovered_drafts(
+    app: tauri::AppHandle,
+    state: State<'_, DraftState>,
+) -> Result<Vec<DraftSummary>, String> {
+    if !state.recovering.load(Ordering::SeqCst) {
+        return Ok(Vec::new());
+    }
+    let workspace = crate::load_workspace_path(&app)?
+        .to_string_lossy()
+        .to_string();
+    let entries =
+        fs::read_dir(drafts_dir(&app)?).map_err(|e| format!("drafts read failed: {e}"))?;
+    let mut drafts: Vec<Draft> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|path| serde_json::from_str(&fs::read_to_string(path).ok()?).ok())
+        .filter(|draft: &Draft| draft.workspace == workspace)
+        .collect();
+    drafts.sort_by_key(|draft| std::cmp::Reverse(draft.saved_ms));
+    let text = |request: &Value, key: &str| crate::importers::str_of(request, key).to_string();
+    Ok(drafts
+        .into_iter()
+        .map(|draft| DraftSummary {
+            name: text(&draft.request, "name"),
+            method: text(&draft.request, "method"),
+            url: text(&draft.request, "url"),
+            draft_id: draft.draft_id,
+            collection_id: draft.collection_id,
+            request_id: draft.request_id,
+            saved_ms: draft.saved_ms,
+        })
+        .collect())
+}
+
+/// A recovered draft in full, for the editor to reopen. The draft stays until the request
+/// is saved or the draft discarded.
+#[tauri::command]
+pub async fn restore_draft(app: tauri::AppHandle, draft_id: String) -> Result<Draft, String> {
+    read_draft(&app, &draft_id)
+}
@@ -0,0 +1,8 @@
+//! The dynamic variable listing for the UI; evaluation lives in `litefetch_core::dynamic`.
+
+pub use litefetch_core::dynamic::*;
+
+#[tauri::command]
+pub async fn list_dynamic_variables() -> Result<Vec<String>, String> {
+    Ok(BUILTINS.iter().map(|name| name.to_string()).collect())
+}
@@ -0,0 +1,296 @@
+//! Editing part of a request in an external editor. The part is written to a temp file and
+//! opened with `$VISUAL`/`$EDITOR`, or the system's default application for its extension.
+//! Saves are picked up by polling the file and sent as `editor://changed` events for the UI
+//! to apply; the session ends, with an `editor://closed` event, when the editor process exits
+//! or `stop_external_edit` is called.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, SystemTime};
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, Manager, State};
+use tokio::process::{Child, Command};
+
+use crate::importers::str_of;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub struct EditorState {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl EditorState {
+    pub fn new() -> Self {
+        Self {
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+struct Session {
+    info: EditSession,
+    dir: PathBuf,
+    task: JoinHandle<()>,
+}
+
+/// The part of a request to edit. GraphQL parts read the `query` and `variables` of a JSON
+/// body.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EditorPart {
+    Body,
+    GraphqlQuery,
+    GraphqlVariables,
+    PreRequestScript,
+    TestScript,
+}
+
+#[derive(Serialize, Clone)]
+pub struct EditSession {
+    session_id: String,
+    request_id: String,
+    part: EditorPart,
+    path: String,
+    /// The command the file was opened with.
+    editor: String,
+}
+
+#[derive(Serialize, Clone)]
+struct EditorEvent {
+    session_id: String,
+    request_id: String,
+    part: EditorPart,
+    /// The saved text; `None` for `editor://closed`.
+    content: Option<String>,
+    timestamp_ms: u64,
+}
+
+fn text_of(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+/// A JSON body as a value, whether stored as an object or as JSON text.
+fn json_body(request: &Value) -> Option<Value> {
+    match request.get("body")? {
+        Value::String(text) => serde_json::from_str(text).ok(),
+        other => Some(other.clone()),
+    }
+}
+
+/// The part's current text and the file extension editors should see.
+fn extract(request: &Value, part: EditorPart) -> Result<(String, &'static str), String> {
+    Ok(match part {
+        EditorPart::Body => {
+            let body = request.get("body").cloned().unwrap_or(Value::Null);
+            let json = str_of(request, "body_mode") == "json"
+                || body.is_object()
+                || body.is_array()
+                || body.as_str().is_some_and(|t| {
+                    serde_json::from_str::<Value>(t).is_ok_and(|v| !v.is_string())
+                });
+            (text_of(&body), if json { "json" } else { "txt" })
+        }
+        EditorPart::GraphqlQuery | EditorPart::GraphqlVariables => {
+            let body = json_body(request)
+                .filter(|b| b.get("query").is_some())
+                .ok_or("request body is not a GraphQL payload")?;
+            match part {
+                EditorPart::GraphqlQuery => (str_of(&body, "query").to_string(), "graphql"),
+                _ => (
+                    body.get("variables")
+                        .filter(|v| !v.is_null())
+                        .map(text_of)
+                        .unwrap_or_else(|| "{}".to_string()),
+                    "json",
+                ),
+            }
+        }
+        EditorPart::PreRequestScript => (str_of(request, "pre_request_script").to_string(), "js"),
+        EditorPart::TestScript => (str_of(request, "test_script").to_string(), "js"),
+    })
+}
+
+/// Starts `$VISUAL`/`$EDITOR` on `path`, or the platform opener when neither is set. Only a
+/// configured editor is returned as a child worth waiting on; openers exit immediately.
+fn launch(path: &Path) -> Result<(String, Option<Child>), String> {
+    let configured = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.trim().is_empty());
+    let (program, mut args, wait): (String, Vec<String>, bool) = match configured {
+        Some(editor) => {
+            let mut parts = editor.split_whitespace().map(str::to_string);
+            let program = parts.next().unwrap_or_default();
+            (program, parts.collect(), true)
+        }
+        None if cfg!(target_os = "macos") => ("open".to_string(), Vec::new(), false),
+        None if cfg!(target_os = "windows") => (
+            "cmd".to_string(),
+            vec!["/C".into(), "start".into(), String::new()],
+            false,
+        ),
+        None => ("xdg-open".to_string(), Vec::new(), false),
+    };
+    args.push(path.to_string_lossy().to_string());
+    let child = Command::new(&program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(false)
+        .spawn()
+        .map_err(|e| format!("failed to start {program}: {e}"))?;
+    Ok((program, wait.then_some(child)))
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+fn emit(app: &tauri::AppHandle, session: &EditSession, content: Option<String>) {
+    let event = match content {
+        Some(_) => "editor://changed",
+        None => "editor://closed",
+    };
+    let _ = app.emit(
+        event,
+        EditorEvent {
+            session_id: session.session_id.clone(),
+            request_id: session.request_id.clone(),
+            part: session.part,
+            content,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+async fn watch(app: tauri::AppHandle, session: EditSession, mut child: Option<Child>) {
+    let path = PathBuf::from(&session.path);
+    let mut last_seen = modified(&path);
+    let mut last_content = std::fs::read_to_string(&path).unwrap_or_default();
+    loop {
+        let exited = match child.as_mut() {
+            Some(child) => child.try_wait().map(|s| s.is_some()).unwrap_or(true),
+            None => false,
+        };
+        let seen = modified(&path);
+        if seen != last_seen {
+            last_seen = seen;
+            // Editors that save by replacing the file can leave it briefly missing.
+            if let Ok(content) = std::fs::read_to_string(&path) {
+                if content != last_content {
+                    last_content = content.clone();
+                    emit(&app, &session, Some(content));
+                }
+            }
+        }
+        if exited {
+            break;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+    let state = app.state::<EditorState>();
+    if let Some(ended) = state.sessions.lock().await.remove(&session.session_id) {
+        let _ = std::fs::remove_dir_all(ended.dir);
+    }
+    emit(&app, &session, None);
+}
+
+/// Opens `part` of a saved request in an external editor and streams its saves back as
+/// `editor://changed` events until the editor exits or the session is stopped.
+#[tauri::command]
+pub async fn edit_in_external_editor(
+    app: tauri::AppHandle,
+    state: State<'_, EditorState>,
+    collection_id: String,
+    request_id: String,
+    part: EditorPart,
+) -> Result<EditSession, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let items = collection
+        .get("items")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let request = crate::codegen::find_request(items, &request_id)
+        .ok_or_else(|| format!("unknown request: {request_id}"))?;
+    let (content, extension) = extract(request, part)?;
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    let dir = std::env::temp_dir()
+        .join("litefetch-edit")
+        .join(&session_id);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("editor file create failed: {e}"))?;
+    let name: String = str_of(request, "name")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let part_name = serde_json::to_value(part)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default();
+    let path = dir.join(format!(
+        "{}-{part_name}.{extension}",
+        if name.is_empty() { "request" } else { &name }
+    ));
+    std::fs::write(&path, content).map_err(|e| format!("editor file create failed: {e}"))?;
+
+    let (editor, child) = match launch(&path) {
+        Ok(launched) => launched,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(e);
+        }
+    };
+    let session = EditSession {
+        session_id: session_id.clone(),
+        request_id,
+        part,
+        path: path.to_string_lossy().to_string(),
+        editor,
+    };
+    let mut sessions = state.sessions.lock().await;
+    let task = tauri::async_runtime::spawn(watch(app.clone(), session.clone(), child));
+    sessions.insert(
+        session_id,
+        Session {
+            info: session.clone(),
+            dir,
+            task,
+        },
+    );
+    Ok(session)
+}
+
+/// Stops watching an edit session and removes its temp file. The editor itself is left open.
+#[tauri::command]
+pub async fn stop_external_edit(
+    app: tauri::AppHandle,
+    state: State<'_, EditorState>,
+    session_id: String,
+) -> Result<(), String> {
+    let session = state
+        .sessions
+        .lock()
+        .await
+        .remove(&session_id)
+        .ok_or_else(|| format!("unknown edit session: {session_id}"))?;
+    session.task.abort();
+    let _ = std::fs::remove_dir_all(&session.dir);
+    emit(&app, &session.info, None);
+    Ok(())
+}
@@ -0,0 +1,198 @@
+//! Comparing and syncing environments of a collection (e.g. dev, staging and prod). Secret
+//! values are compared through the keychain but never returned.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::redact::MASK;
+use crate::secrets::ResolvedVariable;
+
+#[derive(Serialize)]
+pub struct DiffValue {
+    key: String,
+    /// The value, or a mask for secrets.
+    value: String,
+    secret: bool,
+}
+
+#[derive(Serialize)]
+pub struct DiffChange {
+    key: String,
+    a: String,
+    b: String,
+    /// Set when either side is a secret; both values are masked then.
+    secret: bool,
+}
+
+#[derive(Serialize)]
+pub struct EnvironmentDiff {
+    a: String,
+    b: String,
+    only_in_a: Vec<DiffValue>,
+    only_in_b: Vec<DiffValue>,
+    differing: Vec<DiffChange>,
+    /// Keys with equal values on both sides.
+    same: Vec<String>,
+}
+
+fn shown(variable: &ResolvedVariable) -> String {
+    match variable.secret {
+        true => MASK.to_string(),
+        false => variable.value.clone(),
+    }
+}
+
+fn only(vars: &[ResolvedVariable], other: &[ResolvedVariable]) -> Vec<DiffValue> {
+    vars.iter()
+        .filter(|v| !other.iter().any(|o| o.key == v.key))
+        .map(|v| DiffValue {
+            key: v.key.clone(),
+            value: shown(v),
+            secret: v.secret,
+        })
+        .collect()
+}
+
+async fn environment_file(app: &tauri::AppHandle, collection_id: &str) -> Result<Value, String> {
+    crate::backend_get(app, &format!("/collections/{collection_id}/environment")).await
+}
+
+fn require_env(environment: &Value, name: &str) -> Result<(), String> {
+    match environment.get("envs").and_then(|envs| envs.get(name)) {
+        Some(_) => Ok(()),
+        None => Err(format!("unknown environment: {name}")),
+    }
+}
+
+/// Compares two environments of a collection key by key.
+#[tauri::command]
+pub async fn diff_environments(
+    app: tauri::AppHandle,
+    collection_id: String,
+    a: String,
+    b: String,
+) -> Result<EnvironmentDiff, String> {
+    let environment = environment_file(&app, &collection_id).await?;
+    require_env(&environment, &a)?;
+    require_env(&environment, &b)?;
+    let mut vars_a = crate::secrets::resolve_environment(&collection_id, &environment, &a)?;
+    let mut vars_b = crate::secrets::resolve_environment(&collection_id, &environment, &b)?;
+    vars_a.sort_by(|x, y| x.key.cmp(&y.key));
+    vars_b.sort_by(|x, y| x.key.cmp(&y.key));
+
+    let mut differing = Vec::new();
+    let mut same = Vec::new();
+    for left in &vars_a {
+        let Some(right) = vars_b.iter().find(|v| v.key == left.key) else {
+            continue;
+        };
+        if left.value == right.value {
+            same.push(left.key.clone());
+            continue;
+        }
+        let secret = left.secret || right.secret;
+        let show = |value: &str| match secret {
+            true => MASK.to_string(),
+            false => value.to_string(),
+        };
+        differing.push(DiffChange {
+            key: left.key.clone(),
+            a: show(&left.value),
+            b: show(&right.value),
+            secret,
+        });
+    }
+    Ok(EnvironmentDiff {
+        only_in_a: only(&vars_a, &vars_b),
+        only_in_b: only(&vars_b, &vars_a),
+        differing,
+        same,
+        a,
+        b,
+    })
+}
+
+#[derive(Serialize)]
+pub struct PromoteResult {
+    copied: Vec<String>,
+    /// Keys missing from the source, or already set in the target without `overwrite`.
+    skipped: Vec<String>,
+}
+
+/// Copies `keys` from one environment to another, keeping each key's secret status. Secret
+/// values move keychain to keychain and never touch the workspace files.
+#[tauri::command]
+pub async fn promote_variables(
+    app: tauri::AppHandle,
+    collection_id: String,
+    from: String,
+    to: String,
+    keys: Vec<String>,
+    overwrite: Option<bool>,
+) -> Result<PromoteResult, String> {
+    if from == to {
+        return Err("source and target environments are the same".to_string());
+    }
+    let overwrite = overwrite.unwrap_or(true);
+    let env_path = format!("/collections/{collection_id}/environment");
+    let mut environment = environment_file(&app, &collection_id).await?;
+    require_env(&environment, &from)?;
+    require_env(&environment, &to)?;
+    let source = crate::secrets::resolve_environment(&collection_id, &environment, &from)?;
+
+    let target = environment
+        .pointer_mut(&format!(
+            "/envs/{}",
+            to.replace('~', "~0").replace('/', "~1")
+        ))
+        .and_then(Value::as_object_mut)
+        .ok_or("collection environment file is malformed")?;
+    for field in ["variables", "secrets"] {
+        if !target.get(field).is_some_and(Value::is_object) {
+            target.insert(field.to_string(), Value::Object(Map::new()));
+        }
+    }
+    let mut copied = Vec::new();
+    let mut skipped = Vec::new();
+    let mut keychain: Vec<(&ResolvedVariable, bool)> = Vec::new();
+    for key in &keys {
+        let Some(variable) = source.iter().find(|v| &v.key == key) else {
+            skipped.push(key.clone());
+            continue;
+        };
+        let exists = target["variables"].get(key).is_some();
+        if exists && !overwrite {
+            skipped.push(key.clone());
+            continue;
+        }
+        let was_secret = target["secrets"].get(key) == Some(&Value::Bool(true));
+        target["variables"][key] = Value::String(match variable.secret {
+            true => String::new(),
+            false => variable.value.clone(),
+        });
+        if let Some(flags) = target["secrets"].as_object_mut() {
+            match variable.secret {
+                true => {
+                    flags.insert(key.clone(), Value::Bool(true));
+                }
+                false => {
+                    flags.remove(key);
+                }
+            }
+        }
+        if variable.secret || was_secret {
+            keychain.push((variable, was_secret));
+        }
+        copied.push(key.clone());
+    }
+    crate::backend_post(&app, &env_path, &environment).await?;
+
+    for (variable, was_secret) in keychain {
+        if variable.secret {
+            crate::secrets::store(&collection_id, &to, &variable.key, &variable.value)?;
+        } else if was_secret {
+            crate::secrets::remove(&collection_id, &to, &variable.key)?;
+        }
+    }
+    Ok(PromoteResult { copied, skipped })
+}
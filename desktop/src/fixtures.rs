@@ -0,0 +1,37 @@
+//! Workspace fixtures from the UI; storage and reference resolution live in
+//! `litefetch_core::fixtures`. Requests point at a fixture with `fixture:<name>` wherever they
+//! take a file path: a file body, a multipart part or a run's data file.
+
+use litefetch_core::fixtures::{self, Fixture};
+
+/// Copies the file at `path` into the workspace's fixtures as `name` (the file's name by
+/// default), replacing any fixture of that name.
+#[tauri::command]
+pub async fn add_fixture(
+    app: tauri::AppHandle,
+    path: String,
+    name: Option<String>,
+) -> Result<Fixture, String> {
+    let source = crate::normalize_path(path.trim());
+    let name = match name.as_deref().map(str::trim).filter(|n| !n.is_empty()) {
+        Some(name) => name.to_string(),
+        None => source
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or("a fixture needs a name")?,
+    };
+    let workspace = crate::load_workspace_path(&app)?;
+    tauri::async_runtime::spawn_blocking(move || fixtures::add(&workspace, &name, &source))
+        .await
+        .map_err(|e| format!("fixture add failed: {e}"))?
+}
+
+#[tauri::command]
+pub async fn list_fixtures(app: tauri::AppHandle) -> Result<Vec<Fixture>, String> {
+    fixtures::list(&crate::load_workspace_path(&app)?)
+}
+
+#[tauri::command]
+pub async fn remove_fixture(app: tauri::AppHandle, name: String) -> Result<bool, String> {
+    fixtures::remove(&crate::load_workspace_path(&app)?, &name)
+}
@@ -0,0 +1,175 @@
+//! Formatting for the response viewer and the body editors (see `litefetch_core::format`).
+//! Stored responses are formatted off the UI thread and kept in a small cache, so the viewer
+//! pages through a 50 MB body by lines and only ever holds what's on screen, with the
+//! folding ranges of that page.
+
+use litefetch_core::format::{Fold, Formatted, Language, Mode};
+use serde::Serialize;
+use std::sync::Arc;
+use tauri::async_runtime::Mutex;
+use tauri::State;
+
+/// Formatted responses kept for paging, most recent last.
+const CACHED: usize = 4;
+const DEFAULT_LINES: usize = 2_000;
+const MAX_LINES: usize = 20_000;
+
+struct Cached {
+    key: (String, Language, Mode),
+    text: String,
+    /// Byte offset of each line's start.
+    line_starts: Vec<usize>,
+    folds: Vec<Fold>,
+}
+
+pub struct FormatState {
+    cache: Mutex<Vec<Arc<Cached>>>,
+}
+
+impl FormatState {
+    pub fn new() -> Self {
+        Self {
+            cache: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct FormattedPage {
+    pub language: Language,
+    pub start_line: usize,
+    pub text: String,
+    /// Where the next page starts; `None` at the end of the body.
+    pub next_line: Option<usize>,
+    pub total_lines: usize,
+    /// The folds that start or end on this page, in whole-body line numbers.
+    pub folds: Vec<Fold>,
+}
+
+fn detect(content_type: &str, text: &str) -> Result<Language, String> {
+    litefetch_core::format::detect(content_type, text)
+        .ok_or_else(|| "couldn't tell how to format this body; pick a language".to_string())
+}
+
+fn format_stored(
+    app: &tauri::AppHandle,
+    response_id: &str,
+    language: Option<Language>,
+    mode: Mode,
+) -> Result<Cached, String> {
+    let body = crate::responses::read_body(app, response_id)
+        .ok_or_else(|| format!("unknown response: {response_id}"))?;
+    let text = String::from_utf8(body).map_err(|_| "response body isn't text".to_string())?;
+    let language = match language {
+        Some(language) => language,
+        None => {
+            let meta = crate::responses::read_meta(app, response_id)?;
+            detect(crate::importers::str_of(&meta, "content_type"), &text)?
+        }
+    };
+    let formatted = litefetch_core::format::format(&text, language, mode)?;
+    let line_starts = std::iter::once(0)
+        .chain(formatted.text.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    Ok(Cached {
+        key: (response_id.to_string(), language, mode),
+        text: formatted.text,
+        line_starts,
+        folds: formatted.folds,
+    })
+}
+
+/// A page of a stored response formatted as `language` (detected from its content type
+/// when unset): `max_lines` lines (2000 by default, 20000 at most) from `start_line`.
+#[tauri::command]
+pub async fn format_response(
+    app: tauri::AppHandle,
+    state: State<'_, FormatState>,
+    response_id: String,
+    language: Option<Language>,
+    mode: Option<Mode>,
+    start_line: Option<usize>,
+    max_lines: Option<usize>,
+) -> Result<FormattedPage, String> {
+    let mode = mode.unwrap_or_default();
+    let cached = {
+        let cache = state.cache.lock().await;
+        cache
+            .iter()
+            .rev()
+            .find(|c| {
+                c.key.0 == response_id && c.key.2 == mode && language.is_none_or(|l| c.key.1 == l)
+            })
+            .cloned()
+    };
+    let cached = match cached {
+        Some(cached) => cached,
+        None => {
+            let formatted = tauri::async_runtime::spawn_blocking(move || {
+                format_stored(&app, &response_id, language, mode)
+            })
+            .await
+            .map_err(|e| format!("format failed: {e}"))??;
+            let formatted = Arc::new(formatted);
+            let mut cache = state.cache.lock().await;
+            cache.retain(|c| c.key != formatted.key);
+            cache.push(formatted.clone());
+            if cache.len() > CACHED {
+                cache.remove(0);
+            }
+            formatted
+        }
+    };
+
+    let total_lines = cached.line_starts.len();
+    let start_line = start_line.unwrap_or(0).min(total_lines);
+    let end_line =
+        (start_line + max_lines.unwrap_or(DEFAULT_LINES).clamp(1, MAX_LINES)).min(total_lines);
+    let line_start = |line: usize| {
+        cached
+            .line_starts
+            .get(line)
+            .copied()
+            .unwrap_or(cached.text.len())
+    };
+    let text = cached.text[line_start(start_line)..line_start(end_line)]
+        .trim_end_matches('\n')
+        .to_string();
+    let folds = cached
+        .folds
+        .iter()
+        .filter(|fold| {
+            (fold.start_line >= start_line && fold.start_line < end_line)
+                || (fold.end_line >= start_line && fold.end_line < end_line)
+        })
+        .copied()
+        .collect();
+    Ok(FormattedPage {
+        language: cached.key.1,
+        start_line,
+        text,
+        next_line: (end_line < total_lines).then_some(end_line),
+        total_lines,
+        folds,
+    })
+}
+
+/// Formats `text` in full, for bodies being edited; `language` is detected from
+/// `content_type` and the text when unset.
+#[tauri::command]
+pub async fn format_body(
+    text: String,
+    language: Option<Language>,
+    content_type: Option<String>,
+    mode: Option<Mode>,
+) -> Result<Formatted, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let language = match language {
+            Some(language) => language,
+            None => detect(content_type.as_deref().unwrap_or_default(), &text)?,
+        };
+        litefetch_core::format::format(&text, language, mode.unwrap_or_default())
+    })
+    .await
+    .map_err(|e| format!("format failed: {e}"))?
+}
@@ -0,0 +1,717 @@
+//! Git integration for file-based workspaces: status, commit, pull, push and log, scoped to
+//! the workspace directory (which may sit anywhere inside a larger repository). Commits stage
+//! every workspace change first, leaving out the local `.litefetch` state. Merge conflicts from
+//! a pull are announced as `git://conflict`; new commits as `git://changed`.
+//! `diff_collection` compares the collections at two revisions request by request.
+
+use git2::{
+    build::CheckoutBuilder, AnnotatedCommit, BranchType, Commit, Cred, CredentialType, DiffOptions,
+    FetchOptions, IndexAddOption, MergeAnalysis, PushOptions, RemoteCallbacks, Repository,
+    RepositoryState, Signature, Status, StatusOptions, Tree,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use tauri::Emitter;
+
+use litefetch_core::collection_diff::{self, CollectionDiff};
+use litefetch_core::json::str_of;
+
+const DEFAULT_REMOTE: &str = "origin";
+const DEFAULT_LOG_LIMIT: usize = 50;
+const MAX_LOG_LIMIT: usize = 500;
+/// Authentication attempts per operation before giving up instead of looping on bad keys.
+const MAX_AUTH_ATTEMPTS: u32 = 4;
+
+#[derive(Serialize)]
+pub struct GitFile {
+    /// Relative to the workspace.
+    pub path: String,
+    /// `new`, `modified`, `deleted`, `renamed`, `typechange` or `conflicted`.
+    pub status: String,
+    pub staged: bool,
+}
+
+#[derive(Serialize)]
+pub struct GitStatus {
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub ahead: usize,
+    pub behind: usize,
+    pub files: Vec<GitFile>,
+    pub conflicts: Vec<String>,
+    /// A merge is waiting for its conflicts to be resolved and committed.
+    pub merging: bool,
+}
+
+#[derive(Serialize)]
+pub struct GitCommit {
+    pub id: String,
+    pub summary: String,
+    pub message: String,
+    pub author: String,
+    pub email: String,
+    pub time_ms: i64,
+}
+
+#[derive(Serialize)]
+pub struct PullOutcome {
+    /// `up_to_date`, `fast_forward`, `merged` or `conflicted`.
+    pub result: String,
+    pub head: Option<String>,
+    pub conflicts: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+struct ConflictEvent {
+    conflicts: Vec<String>,
+    timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct ChangedEvent {
+    head: String,
+    timestamp_ms: u64,
+}
+
+/// The repository holding the workspace, with the workspace's path inside its work tree
+/// (empty when the workspace is the repository root).
+struct Scoped {
+    repo: Repository,
+    prefix: String,
+}
+
+impl Scoped {
+    fn open(workspace: &Path) -> Result<Self, String> {
+        let repo = Repository::discover(workspace)
+            .map_err(|_| "workspace is not inside a git repository".to_string())?;
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| "bare repositories are not supported".to_string())?;
+        let canonical = |path: &Path| path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        let prefix = canonical(workspace)
+            .strip_prefix(canonical(workdir))
+            .map(|rel| rel.to_string_lossy().replace('\\', "/"))
+            .unwrap_or_default();
+        Ok(Self { repo, prefix })
+    }
+
+    /// Repository path to workspace path, or `None` outside the workspace or in `.litefetch`.
+    fn relative<'a>(&self, path: &'a str) -> Option<&'a str> {
+        let rel = if self.prefix.is_empty() {
+            path
+        } else {
+            path.strip_prefix(self.prefix.as_str())?.strip_prefix('/')?
+        };
+        (rel != ".litefetch" && !rel.starts_with(".litefetch/")).then_some(rel)
+    }
+
+    fn pathspec(&self) -> String {
+        if self.prefix.is_empty() {
+            "*".to_string()
+        } else {
+            format!("{}/*", self.prefix)
+        }
+    }
+
+    /// Conflicted paths, relative to the workspace where they are inside it.
+    fn conflicts(&self) -> Result<Vec<String>, String> {
+        let index = self
+            .repo
+            .index()
+            .map_err(|e| format!("git index failed: {e}"))?;
+        let conflicts = index
+            .conflicts()
+            .map_err(|e| format!("git index failed: {e}"))?;
+        let mut paths: Vec<String> = conflicts
+            .flatten()
+            .filter_map(|c| c.our.or(c.their).or(c.ancestor))
+            .map(|entry| {
+                let path = String::from_utf8_lossy(&entry.path).to_string();
+                self.relative(&path).map(str::to_string).unwrap_or(path)
+            })
+            .collect();
+        paths.dedup();
+        Ok(paths)
+    }
+
+    fn branch(&self) -> Result<String, String> {
+        let head = self
+            .repo
+            .head()
+            .map_err(|e| format!("git head failed: {e}"))?;
+        head.shorthand()
+            .filter(|_| head.is_branch())
+            .map(str::to_string)
+            .ok_or_else(|| "HEAD is detached; check out a branch first".to_string())
+    }
+
+    fn signature(&self) -> Result<Signature<'static>, String> {
+        self.repo
+            .signature()
+            .or_else(|_| Signature::now("LiteFetch", "litefetch@localhost"))
+            .map_err(|e| format!("git signature failed: {e}"))
+    }
+}
+
+async fn scoped<T: Send + 'static>(
+    app: &tauri::AppHandle,
+    op: impl FnOnce(Scoped) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    let workspace = crate::load_workspace_path(app)?;
+    tauri::async_runtime::spawn_blocking(move || op(Scoped::open(&workspace)?))
+        .await
+        .map_err(|e| format!("git task failed: {e}"))?
+}
+
+/// Remote callbacks that authenticate with the ssh agent or git's credential helpers.
+fn callbacks<'a>(repo: &Repository) -> RemoteCallbacks<'a> {
+    let config = repo.config().ok();
+    let attempts = RefCell::new(0);
+    let mut callbacks = RemoteCallbacks::new();
+    callbacks.credentials(move |url, username, allowed| {
+        *attempts.borrow_mut() += 1;
+        if *attempts.borrow() > MAX_AUTH_ATTEMPTS {
+            return Err(git2::Error::from_str("authentication failed"));
+        }
+        if allowed.contains(CredentialType::SSH_KEY) {
+            return Cred::ssh_key_from_agent(username.unwrap_or("git"));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+            if let Some(config) = &config {
+                return Cred::credential_helper(config, url, username);
+            }
+        }
+        Cred::default()
+    });
+    callbacks
+}
+
+fn status_label(status: Status) -> &'static str {
+    if status.is_conflicted() {
+        "conflicted"
+    } else if status.intersects(Status::INDEX_NEW | Status::WT_NEW) {
+        "new"
+    } else if status.intersects(Status::INDEX_DELETED | Status::WT_DELETED) {
+        "deleted"
+    } else if status.intersects(Status::INDEX_RENAMED | Status::WT_RENAMED) {
+        "renamed"
+    } else if status.intersects(Status::INDEX_TYPECHANGE | Status::WT_TYPECHANGE) {
+        "typechange"
+    } else {
+        "modified"
+    }
+}
+
+fn commit_info(commit: &Commit) -> GitCommit {
+    let author = commit.author();
+    GitCommit {
+        id: commit.id().to_string(),
+        summary: commit.summary().unwrap_or_default().to_string(),
+        message: commit.message().unwrap_or_default().to_string(),
+        author: author.name().unwrap_or_default().to_string(),
+        email: author.email().unwrap_or_default().to_string(),
+        time_ms: commit.time().seconds() * 1000,
+    }
+}
+
+fn emit_changed(app: &tauri::AppHandle, head: &str) {
+    let _ = app.emit(
+        "git://changed",
+        ChangedEvent {
+            head: head.to_string(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+#[tauri::command]
+pub async fn git_status(app: tauri::AppHandle) -> Result<GitStatus, String> {
+    scoped(&app, |scoped| {
+        let repo = &scoped.repo;
+        let mut options = StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(true)
+            .renames_head_to_index(true);
+        if !scoped.prefix.is_empty() {
+            options.pathspec(&scoped.prefix);
+        }
+        let statuses = repo
+            .statuses(Some(&mut options))
+            .map_err(|e| format!("git status failed: {e}"))?;
+        let staged = Status::INDEX_NEW
+            | Status::INDEX_MODIFIED
+            | Status::INDEX_DELETED
+            | Status::INDEX_RENAMED
+            | Status::INDEX_TYPECHANGE;
+        let files = statuses
+            .iter()
+            .filter_map(|entry| {
+                let path = scoped.relative(entry.path()?)?.to_string();
+                Some(GitFile {
+                    path,
+                    status: status_label(entry.status()).to_string(),
+                    staged: entry.status().intersects(staged),
+                })
+            })
+            .collect();
+        let conflicts = scoped.conflicts()?;
+
+        let branch = scoped.branch().ok();
+        let mut upstream = None;
+        let (mut ahead, mut behind) = (0, 0);
+        if let Some(name) = &branch {
+            let tracking = repo
+                .find_branch(name, BranchType::Local)
+                .and_then(|local| local.upstream());
+            if let Ok(tracking) = tracking {
+                upstream = tracking.name().ok().flatten().map(str::to_string);
+                if let (Ok(local), Some(remote)) =
+                    (repo.refname_to_id("HEAD"), tracking.get().target())
+                {
+                    (ahead, behind) = repo.graph_ahead_behind(local, remote).unwrap_or((0, 0));
+                }
+            }
+        }
+        Ok(GitStatus {
+            branch,
+            upstream,
+            ahead,
+            behind,
+            files,
+            conflicts,
+            merging: repo.state() == RepositoryState::Merge,
+        })
+    })
+    .await
+}
+
+/// Stages every change in the workspace and commits it; concludes a pending merge once its
+/// conflicts are resolved. Returns the new commit.
+#[tauri::command]
+pub async fn git_commit(app: tauri::AppHandle, message: String) -> Result<GitCommit, String> {
+    if message.trim().is_empty() {
+        return Err("commit message is required".into());
+    }
+    crate::lock::ensure_writable(&app)?;
+    let commit = scoped(&app, move |mut scoped| {
+        let merging = scoped.repo.state() == RepositoryState::Merge;
+        let mut merge_heads = Vec::new();
+        if merging {
+            scoped
+                .repo
+                .mergehead_foreach(|id| {
+                    merge_heads.push(*id);
+                    true
+                })
+                .map_err(|e| format!("git commit failed: {e}"))?;
+        }
+        let repo = &scoped.repo;
+        let mut index = repo.index().map_err(|e| format!("git index failed: {e}"))?;
+        let pathspec = [scoped.pathspec()];
+        // Non-zero skips the path: keep `.litefetch` out of the index.
+        let mut skip_state = |path: &Path, _: &[u8]| -> i32 {
+            let path = path.to_string_lossy().replace('\\', "/");
+            i32::from(scoped.relative(&path).is_none())
+        };
+        index
+            .add_all(
+                pathspec.iter(),
+                IndexAddOption::DEFAULT,
+                Some(&mut skip_state),
+            )
+            .and_then(|_| index.update_all(pathspec.iter(), Some(&mut skip_state)))
+            .and_then(|_| index.write())
+            .map_err(|e| format!("git stage failed: {e}"))?;
+        if index.has_conflicts() {
+            return Err("resolve conflicts before committing".into());
+        }
+        let tree_id = index
+            .write_tree()
+            .map_err(|e| format!("git commit failed: {e}"))?;
+        let tree = repo
+            .find_tree(tree_id)
+            .map_err(|e| format!("git commit failed: {e}"))?;
+
+        let mut parents: Vec<Commit> = repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_commit().ok())
+            .into_iter()
+            .collect();
+        for id in merge_heads {
+            parents.push(
+                repo.find_commit(id)
+                    .map_err(|e| format!("git commit failed: {e}"))?,
+            );
+        }
+        let unchanged = parents
+            .first()
+            .and_then(|head| head.tree().ok())
+            .is_some_and(|head_tree: Tree| head_tree.id() == tree_id);
+        if unchanged && !merging {
+            return Err("nothing to commit".into());
+        }
+
+        let signature = scoped.signature()?;
+        let parent_refs: Vec<&Commit> = parents.iter().collect();
+        let id = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &message,
+                &tree,
+                &parent_refs,
+            )
+            .map_err(|e| format!("git commit failed: {e}"))?;
+        if merging {
+            repo.cleanup_state()
+                .map_err(|e| format!("git commit failed: {e}"))?;
+        }
+        let commit = repo
+            .find_commit(id)
+            .map_err(|e| format!("git commit failed: {e}"))?;
+        Ok(commit_info(&commit))
+    })
+    .await?;
+    emit_changed(&app, &commit.id);
+    Ok(commit)
+}
+
+/// Fetches the current branch from `remote` (default `origin`) and fast-forwards or merges it.
+/// A merge that conflicts is left in progress for the user to resolve and `git_commit`.
+#[tauri::command]
+pub async fn git_pull(
+    app: tauri::AppHandle,
+    remote: Option<String>,
+) -> Result<PullOutcome, String> {
+    crate::lock::ensure_writable(&app)?;
+    let remote_name = remote.unwrap_or_else(|| DEFAULT_REMOTE.to_string());
+    let outcome = scoped(&app, move |scoped| {
+        let repo = &scoped.repo;
+        if repo.state() == RepositoryState::Merge {
+            return Err("a merge is in progress; resolve it and commit first".into());
+        }
+        let branch = scoped.branch()?;
+        let mut remote = repo
+            .find_remote(&remote_name)
+            .map_err(|_| format!("unknown remote: {remote_name}"))?;
+        let mut fetch = FetchOptions::new();
+        fetch.remote_callbacks(callbacks(repo));
+        remote
+            .fetch(&[branch.as_str()], Some(&mut fetch), None)
+            .map_err(|e| format!("git fetch failed: {e}"))?;
+
+        let fetched = repo
+            .find_reference("FETCH_HEAD")
+            .and_then(|r| repo.reference_to_annotated_commit(&r))
+            .map_err(|e| format!("git fetch failed: {e}"))?;
+        let (analysis, _) = repo
+            .merge_analysis(&[&fetched])
+            .map_err(|e| format!("git merge failed: {e}"))?;
+        let head_id = |id: git2::Oid| Some(id.to_string());
+
+        if analysis.contains(MergeAnalysis::ANALYSIS_UP_TO_DATE) {
+            return Ok(PullOutcome {
+                result: "up_to_date".into(),
+                head: repo.refname_to_id("HEAD").ok().and_then(head_id),
+                conflicts: Vec::new(),
+            });
+        }
+        if analysis.intersects(MergeAnalysis::ANALYSIS_FASTFORWARD | MergeAnalysis::ANALYSIS_UNBORN)
+        {
+            fast_forward(repo, &branch, &fetched)?;
+            return Ok(PullOutcome {
+                result: "fast_forward".into(),
+                head: head_id(fetched.id()),
+                conflicts: Vec::new(),
+            });
+        }
+
+        repo.merge(&[&fetched], None, Some(CheckoutBuilder::new().safe()))
+            .map_err(|e| format!("git merge failed: {e}"))?;
+        let conflicts = scoped.conflicts()?;
+        if !conflicts.is_empty() {
+            return Ok(PullOutcome {
+                result: "conflicted".into(),
+                head: repo.refname_to_id("HEAD").ok().and_then(head_id),
+                conflicts,
+            });
+        }
+        let mut index = repo.index().map_err(|e| format!("git merge failed: {e}"))?;
+        let tree = index
+            .write_tree()
+            .and_then(|id| repo.find_tree(id))
+            .map_err(|e| format!("git merge failed: {e}"))?;
+        let ours = repo
+            .head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| format!("git merge failed: {e}"))?;
+        let theirs = repo
+            .find_commit(fetched.id())
+            .map_err(|e| format!("git merge failed: {e}"))?;
+        let signature = scoped.signature()?;
+        let id = repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                &format!("Merge {remote_name}/{branch}"),
+                &tree,
+                &[&ours, &theirs],
+            )
+            .map_err(|e| format!("git merge failed: {e}"))?;
+        repo.cleanup_state()
+            .map_err(|e| format!("git merge failed: {e}"))?;
+        Ok(PullOutcome {
+            result: "merged".into(),
+            head: head_id(id),
+            conflicts: Vec::new(),
+        })
+    })
+    .await?;
+
+    if !outcome.conflicts.is_empty() {
+        let _ = app.emit(
+            "git://conflict",
+            ConflictEvent {
+                conflicts: outcome.conflicts.clone(),
+                timestamp_ms: crate::now_ms(),
+            },
+        );
+    } else if outcome.result != "up_to_date" {
+        if let Some(head) = &outcome.head {
+            emit_changed(&app, head);
+        }
+    }
+    Ok(outcome)
+}
+
+fn fast_forward(repo: &Repository, branch: &str, target: &AnnotatedCommit) -> Result<(), String> {
+    let refname = format!("refs/heads/{branch}");
+    let object = repo
+        .find_object(target.id(), None)
+        .map_err(|e| format!("git fast-forward failed: {e}"))?;
+    repo.checkout_tree(&object, Some(CheckoutBuilder::new().safe()))
+        .map_err(|e| format!("git fast-forward failed: {e}"))?;
+    match repo.find_reference(&refname) {
+        Ok(mut reference) => reference
+            .set_target(target.id(), "pull: fast-forward")
+            .map(|_| ()),
+        Err(_) => repo
+            .reference(&refname, target.id(), true, "pull: fast-forward")
+            .map(|_| ()),
+    }
+    .and_then(|_| repo.set_head(&refname))
+    .map_err(|e| format!("git fast-forward failed: {e}"))
+}
+
+/// Pushes the current branch to `remote` (default `origin`).
+#[tauri::command]
+pub async fn git_push(app: tauri::AppHandle, remote: Option<String>) -> Result<(), String> {
+    let remote_name = remote.unwrap_or_else(|| DEFAULT_REMOTE.to_string());
+    scoped(&app, move |scoped| {
+        let repo = &scoped.repo;
+        let branch = scoped.branch()?;
+        let mut remote = repo
+            .find_remote(&remote_name)
+            .map_err(|_| format!("unknown remote: {remote_name}"))?;
+        let rejected = RefCell::new(None);
+        let mut callbacks = callbacks(repo);
+        callbacks.push_update_reference(|_, status| {
+            if let Some(reason) = status {
+                *rejected.borrow_mut() = Some(reason.to_string());
+            }
+            Ok(())
+        });
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        let refspec = format!("refs/heads/{branch}:refs/heads/{branch}");
+        remote
+            .push(&[refspec.as_str()], Some(&mut options))
+            .map_err(|e| format!("git push failed: {e}"))?;
+        drop(options);
+        match rejected.into_inner() {
+            Some(reason) => Err(format!("git push rejected: {reason}; pull first")),
+            None => Ok(()),
+        }
+    })
+    .await
+}
+
+/// Commits touching the workspace (or `path` within it), newest first.
+#[tauri::command]
+pub async fn git_log(
+    app: tauri::AppHandle,
+    path: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<GitCommit>, String> {
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT).clamp(1, MAX_LOG_LIMIT);
+    scoped(&app, move |scoped| {
+        let repo = &scoped.repo;
+        let spec: PathBuf = [scoped.prefix.as_str(), path.as_deref().unwrap_or_default()]
+            .iter()
+            .filter(|part| !part.is_empty())
+            .collect();
+        let spec = spec.to_string_lossy().replace('\\', "/");
+        let mut walk = repo.revwalk().map_err(|e| format!("git log failed: {e}"))?;
+        if walk.push_head().is_err() {
+            // No commits yet.
+            return Ok(Vec::new());
+        }
+        walk.set_sorting(git2::Sort::TIME)
+            .map_err(|e| format!("git log failed: {e}"))?;
+
+        let mut commits = Vec::new();
+        for id in walk {
+            let commit = id
+                .and_then(|id| repo.find_commit(id))
+                .map_err(|e| format!("git log failed: {e}"))?;
+            if !spec.is_empty() && !touches(repo, &commit, &spec)? {
+                continue;
+            }
+            commits.push(commit_info(&commit));
+            if commits.len() == limit {
+                break;
+            }
+        }
+        Ok(commits)
+    })
+    .await
+}
+
+fn touches(repo: &Repository, commit: &Commit, spec: &str) -> Result<bool, String> {
+    let tree = commit.tree().map_err(|e| format!("git log failed: {e}"))?;
+    let parent = commit.parent(0).ok().and_then(|p| p.tree().ok());
+    let mut options = DiffOptions::new();
+    options.pathspec(spec);
+    let diff = repo
+        .diff_tree_to_tree(parent.as_ref(), Some(&tree), Some(&mut options))
+        .map_err(|e| format!("git log failed: {e}"))?;
+    Ok(diff.deltas().len() > 0)
+}
+
+#[derive(Serialize)]
+pub struct CollectionChanges {
+    pub collection_id: String,
+    pub name: String,
+    /// `added`, `removed` or `changed`.
+    pub status: String,
+    #[serde(flatten)]
+    pub diff: CollectionDiff,
+}
+
+fn parse_collection(path: &str, data: &[u8]) -> Result<Value, String> {
+    let collection: Value = serde_json::from_slice(data)
+        .map_err(|e| format!("collection parse failed ({path}): {e}"))?;
+    if collection.get("ciphertext").is_some() {
+        return Err("encrypted collections can't be compared".to_string());
+    }
+    Ok(collection)
+}
+
+/// Each collection's `collection.json` at `rev`, or in the working tree without one.
+fn collections_at(scoped: &Scoped, rev: Option<&str>) -> Result<BTreeMap<String, Value>, String> {
+    let dir: PathBuf = [scoped.prefix.as_str(), "collections"]
+        .iter()
+        .filter(|part| !part.is_empty())
+        .collect();
+    let mut collections = BTreeMap::new();
+    let Some(rev) = rev else {
+        let workdir = scoped
+            .repo
+            .workdir()
+            .ok_or_else(|| "bare repositories are not supported".to_string())?;
+        let Ok(entries) = std::fs::read_dir(workdir.join(&dir)) else {
+            return Ok(collections);
+        };
+        for entry in entries.flatten() {
+            let path = entry.path().join("collection.json");
+            let Ok(data) = std::fs::read(&path) else {
+                continue;
+            };
+            let id = entry.file_name().to_string_lossy().to_string();
+            collections.insert(id, parse_collection(&path.to_string_lossy(), &data)?);
+        }
+        return Ok(collections);
+    };
+
+    let tree = scoped
+        .repo
+        .revparse_single(rev)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|e| format!("unknown revision {rev}: {e}"))?;
+    let Ok(entry) = tree.get_path(&dir) else {
+        return Ok(collections);
+    };
+    let object = entry
+        .to_object(&scoped.repo)
+        .map_err(|e| format!("git read failed: {e}"))?;
+    let Some(folders) = object.as_tree() else {
+        return Ok(collections);
+    };
+    for folder in folders.iter() {
+        let Some(id) = folder.name().map(str::to_string) else {
+            continue;
+        };
+        let path = Path::new(&id).join("collection.json");
+        let blob = folders
+            .get_path(&path)
+            .and_then(|file| file.to_object(&scoped.repo))
+            .ok()
+            .and_then(|object| object.into_blob().ok());
+        if let Some(blob) = blob {
+            let collection =
+                parse_collection(&format!("{rev}:{}", path.display()), blob.content())?;
+            collections.insert(id, collection);
+        }
+    }
+    Ok(collections)
+}
+
+/// What changed in the workspace's collections from `rev_a` to `rev_b` (the working tree
+/// when omitted): requests added and removed, and the fields changed in the others. Only
+/// collections with changes are listed; `collection_id` narrows it to one.
+#[tauri::command]
+pub async fn diff_collection(
+    app: tauri::AppHandle,
+    rev_a: String,
+    rev_b: Option<String>,
+    collection_id: Option<String>,
+) -> Result<Vec<CollectionChanges>, String> {
+    scoped(&app, move |scoped| {
+        let before = collections_at(&scoped, Some(&rev_a))?;
+        let after = collections_at(&scoped, rev_b.as_deref())?;
+        let ids: BTreeSet<&String> = before
+            .keys()
+            .chain(after.keys())
+            .filter(|id| collection_id.as_ref().is_none_or(|only| only == *id))
+            .collect();
+        let mut changes = Vec::new();
+        for id in ids {
+            let (a, b) = (before.get(id), after.get(id));
+            let status = match (a, b) {
+                (None, _) => "added",
+                (_, None) => "removed",
+                _ => "changed",
+            };
+            let diff = collection_diff::diff(a.unwrap_or(&Value::Null), b.unwrap_or(&Value::Null));
+            if diff.is_empty() && status == "changed" {
+                continue;
+            }
+            let name = b.or(a).map(|c| str_of(c, "name")).unwrap_or_default();
+            changes.push(CollectionChanges {
+                collection_id: id.clone(),
+                name: name.to_string(),
+                status: status.to_string(),
+                diff,
+            });
+        }
+        Ok(changes)
+    })
+    .await
+}
@@ -0,0 +1,666 @@
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::async_runtime::Mutex;
+use tauri::{Emitter, Manager, State};
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::Message;
+
+const INTROSPECTION_QUERY: &str = r#"
+query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types { ...FullType }
+    directives {
+      name
+      description
+      locations
+      args { ...InputValue }
+    }
+  }
+}
+fragment FullType on __Type {
+  kind
+  name
+  description
+  fields(includeDeprecated: true) {
+    name
+    description
+    args { ...InputValue }
+    type { ...TypeRef }
+    isDeprecated
+    deprecationReason
+  }
+  inputFields { ...InputValue }
+  interfaces { ...TypeRef }
+  enumValues(includeDeprecated: true) {
+    name
+    description
+    isDeprecated
+    deprecationReason
+  }
+  possibleTypes { ...TypeRef }
+}
+fragment InputValue on __InputValue {
+  name
+  description
+  type { ...TypeRef }
+  defaultValue
+}
+fragment TypeRef on __Type {
+  kind
+  name
+  ofType {
+    kind
+    name
+    ofType {
+      kind
+      name
+      ofType {
+        kind
+        name
+        ofType {
+          kind
+          name
+          ofType { kind name ofType { kind name ofType { kind name } } }
+        }
+      }
+    }
+  }
+}
+"#;
+
+pub struct GraphqlState {
+    subscriptions: Mutex<HashMap<String, oneshot::Sender<()>>>,
+}
+
+impl GraphqlState {
+    pub fn new() -> Self {
+        Self {
+            subscriptions: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedSchema {
+    endpoint: String,
+    fetched_at: f64,
+    schema: Value,
+}
+
+#[derive(Serialize, Default)]
+pub struct TypeChange {
+    name: String,
+    added_fields: Vec<String>,
+    removed_fields: Vec<String>,
+    changed_fields: Vec<String>,
+}
+
+#[derive(Serialize, Default)]
+pub struct SchemaDiff {
+    added_types: Vec<String>,
+    removed_types: Vec<String>,
+    changed_types: Vec<TypeChange>,
+}
+
+#[derive(Serialize)]
+pub struct CompletionField {
+    name: String,
+    type_ref: String,
+    args: Vec<String>,
+    deprecated: bool,
+}
+
+#[derive(Serialize)]
+pub struct CompletionType {
+    name: String,
+    kind: String,
+    fields: Vec<CompletionField>,
+    enum_values: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct GraphqlSchemaInfo {
+    endpoint: String,
+    fetched_at: f64,
+    query_type: Option<String>,
+    mutation_type: Option<String>,
+    subscription_type: Option<String>,
+    types: Vec<CompletionType>,
+    diff: Option<SchemaDiff>,
+    schema: Value,
+}
+
+fn cache_path(app: &tauri::AppHandle, endpoint: &str) -> Result<PathBuf, String> {
+    // A SHA-256 prefix, so the file name stays the same across builds.
+    let digest = Sha256::digest(endpoint.trim_end_matches('/').as_bytes());
+    let mut dir = crate::workspace_state_dir(app, "graphql")?;
+    dir.push(format!("schema-{}.json", hex::encode(&digest[..8])));
+    Ok(dir)
+}
+
+fn load_cached(path: &Path) -> Option<CachedSchema> {
+    let data = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&data).ok()
+}
+
+fn now_secs() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+pub(crate) fn render_type_ref(type_ref: &Value) -> String {
+    match type_ref.get("kind").and_then(Value::as_str) {
+        Some("NON_NULL") => format!("{}!", render_type_ref(&type_ref["ofType"])),
+        Some("LIST") => format!("[{}]", render_type_ref(&type_ref["ofType"])),
+        _ => type_ref
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("?")
+            .to_string(),
+    }
+}
+
+fn type_signatures(schema: &Value) -> BTreeMap<String, BTreeMap<String, String>> {
+    let mut out = BTreeMap::new();
+    let types = schema["types"].as_array().cloned().unwrap_or_default();
+    for ty in types {
+        let Some(name) = ty["name"].as_str() else {
+            continue;
+        };
+        if name.starts_with("__") {
+            continue;
+        }
+        let mut members = BTreeMap::new();
+        for field in ty["fields"]
+            .as_array()
+            .into_iter()
+            .chain(ty["inputFields"].as_array())
+            .flatten()
+        {
+            if let Some(field_name) = field["name"].as_str() {
+                let args: Vec<String> = field["args"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|a| {
+                        format!(
+                            "{}: {}",
+                            a["name"].as_str().unwrap_or("?"),
+                            render_type_ref(&a["type"])
+                        )
+                    })
+                    .collect();
+                members.insert(
+                    field_name.to_string(),
+                    format!("({}) {}", args.join(", "), render_type_ref(&field["type"])),
+                );
+            }
+        }
+        for value in ty["enumValues"].as_array().into_iter().flatten() {
+            if let Some(v) = value["name"].as_str() {
+                members.insert(v.to_string(), "enum".to_string());
+            }
+        }
+        out.insert(name.to_string(), members);
+    }
+    out
+}
+
+fn diff_schemas(old: &Value, new: &Value) -> SchemaDiff {
+    let old_types = type_signatures(old);
+    let new_types = type_signatures(new);
+    let mut diff = SchemaDiff::default();
+    for (name, members) in &new_types {
+        match old_types.get(name) {
+            None => diff.added_types.push(name.clone()),
+            Some(previous) => {
+                let mut change = TypeChange {
+                    name: name.clone(),
+                    ..Default::default()
+                };
+                for (field, sig) in members {
+                    match previous.get(field) {
+                        None => change.added_fields.push(field.clone()),
+                        Some(old_sig) if old_sig != sig => {
+                            change.changed_fields.push(field.clone())
+                        }
+                        _ => {}
+                    }
+                }
+                for field in previous.keys() {
+                    if !members.contains_key(field) {
+                        change.removed_fields.push(field.clone());
+                    }
+                }
+                if !change.added_fields.is_empty()
+                    || !change.removed_fields.is_empty()
+                    || !change.changed_fields.is_empty()
+                {
+                    diff.changed_types.push(change);
+                }
+            }
+        }
+    }
+    for name in old_types.keys() {
+        if !new_types.contains_key(name) {
+            diff.removed_types.push(name.clone());
+        }
+    }
+    diff
+}
+
+fn completion_types(schema: &Value) -> Vec<CompletionType> {
+    schema["types"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|ty| {
+            let name = ty["name"].as_str()?;
+            if name.starts_with("__") {
+                return None;
+            }
+            let fields = ty["fields"]
+                .as_array()
+                .into_iter()
+                .chain(ty["inputFields"].as_array())
+                .flatten()
+                .map(|f| CompletionField {
+                    name: f["name"].as_str().unwrap_or_default().to_string(),
+                    type_ref: render_type_ref(&f["type"]),
+                    args: f["args"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|a| a["name"].as_str().map(str::to_string))
+                        .collect(),
+                    deprecated: f["isDeprecated"].as_bool().unwrap_or(false),
+                })
+                .collect();
+            let enum_values = ty["enumValues"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v["name"].as_str().map(str::to_string))
+                .collect();
+            Some(CompletionType {
+                name: name.to_string(),
+                kind: ty["kind"].as_str().unwrap_or_default().to_string(),
+                fields,
+                enum_values,
+            })
+        })
+        .collect()
+}
+
+fn schema_info(cached: CachedSchema, diff: Option<SchemaDiff>) -> GraphqlSchemaInfo {
+    let root_name = |key: &str| cached.schema[key]["name"].as_str().map(str::to_string);
+    GraphqlSchemaInfo {
+        query_type: root_name("queryType"),
+        mutation_type: root_name("mutationType"),
+        subscription_type: root_name("subscriptionType"),
+        types: completion_types(&cached.schema),
+        endpoint: cached.endpoint,
+        fetched_at: cached.fetched_at,
+        diff,
+        schema: cached.schema,
+    }
+}
+
+async fn fetch_schema(endpoint: &str, headers: &HashMap<String, String>) -> Result<Value, String> {
+    let client = reqwest::Client::new();
+    let mut request = client.post(endpoint).json(&serde_json::json!({
+        "operationName": "IntrospectionQuery",
+        "query": INTROSPECTION_QUERY,
+    }));
+    for (key, value) in headers {
+        request = request.header(key.as_str(), value.as_str());
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("introspection request failed: {e}"))?;
+    let status = response.status();
+    let payload: Value = response
+        .json()
+        .await
+        .map_err(|e| format!("introspection response invalid (HTTP {status}): {e}"))?;
+    if let Some(errors) = payload.get("errors").filter(|e| !e.is_null()) {
+        return Err(format!("introspection rejected: {errors}"));
+    }
+    payload
+        .get("data")
+        .and_then(|d| d.get("__schema"))
+        .cloned()
+        .ok_or_else(|| format!("introspection response missing __schema (HTTP {status})"))
+}
+
+/// Fetches (or reuses the cached) schema for an endpoint; refresh re-fetches and reports what changed.
+#[tauri::command]
+pub async fn graphql_introspect(
+    app: tauri::AppHandle,
+    endpoint: String,
+    headers: Option<HashMap<String, String>>,
+    refresh: Option<bool>,
+) -> Result<GraphqlSchemaInfo, String> {
+    let path = cache_path(&app, &endpoint)?;
+    let previous = match load_cached(&path) {
+        Some(cached) if !refresh.unwrap_or(false) => return Ok(schema_info(cached, None)),
+        other => other,
+    };
+
+    let schema = fetch_schema(&endpoint, &headers.unwrap_or_default()).await?;
+    let diff = previous.map(|old| diff_schemas(&old.schema, &schema));
+    let cached = CachedSchema {
+        endpoint,
+        fetched_at: now_secs(),
+        schema,
+    };
+    fs::write(
+        &path,
+        serde_json::to_string_pretty(&cached)
+            .map_err(|e| format!("schema serialize failed: {e}"))?,
+    )
+    .map_err(|e| format!("schema cache write failed: {e}"))?;
+    Ok(schema_info(cached, diff))
+}
+
+#[tauri::command]
+pub async fn graphql_get_schema(
+    app: tauri::AppHandle,
+    endpoint: String,
+) -> Result<Option<GraphqlSchemaInfo>, String> {
+    let path = cache_path(&app, &endpoint)?;
+    Ok(load_cached(&path).map(|cached| schema_info(cached, None)))
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum SubscriptionProtocol {
+    /// The `graphql-ws` library protocol (`graphql-transport-ws` subprotocol).
+    #[default]
+    GraphqlTransportWs,
+    /// Apollo's legacy `subscriptions-transport-ws` protocol (`graphql-ws` subprotocol).
+    SubscriptionsTransportWs,
+}
+
+impl SubscriptionProtocol {
+    fn subprotocol(self) -> &'static str {
+        match self {
+            SubscriptionProtocol::GraphqlTransportWs => "graphql-transport-ws",
+            SubscriptionProtocol::SubscriptionsTransportWs => "graphql-ws",
+        }
+    }
+
+    fn start_type(self) -> &'static str {
+        match self {
+            SubscriptionProtocol::GraphqlTransportWs => "subscribe",
+            SubscriptionProtocol::SubscriptionsTransportWs => "start",
+        }
+    }
+
+    fn stop_type(self) -> &'static str {
+        match self {
+            SubscriptionProtocol::GraphqlTransportWs => "complete",
+            SubscriptionProtocol::SubscriptionsTransportWs => "stop",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SubscriptionRequest {
+    endpoint: String,
+    query: String,
+    #[serde(default)]
+    variables: Option<Value>,
+    #[serde(default)]
+    operation_name: Option<String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    connection_params: Option<Value>,
+    #[serde(default)]
+    protocol: SubscriptionProtocol,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct SubscriptionEvent {
+    subscription_id: String,
+    kind: String,
+    payload: Option<Value>,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SubscriptionRecord {
+    subscription_id: String,
+    endpoint: String,
+    query: String,
+    variables: Option<Value>,
+    started_at_ms: u64,
+    ended_at_ms: u64,
+    events: Vec<SubscriptionEvent>,
+}
+
+fn subscription_history_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let mut dir = crate::workspace_state_dir(app, "graphql")?;
+    dir.push("subscriptions");
+    fs::create_dir_all(&dir).map_err(|e| format!("subscription history init failed: {e}"))?;
+    Ok(dir)
+}
+
+fn ws_endpoint(endpoint: &str) -> String {
+    if let Some(rest) = endpoint.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = endpoint.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        endpoint.to_string()
+    }
+}
+
+async fn run_subscription(
+    app: tauri::AppHandle,
+    subscription_id: String,
+    request: SubscriptionRequest,
+    mut stop: oneshot::Receiver<()>,
+) {
+    let started_at_ms = crate::now_ms();
+    let mut events: Vec<SubscriptionEvent> = Vec::new();
+    let mut record = |kind: &str, payload: Option<Value>| {
+        let event = SubscriptionEvent {
+            subscription_id: subscription_id.clone(),
+            kind: kind.to_string(),
+            payload,
+            timestamp_ms: crate::now_ms(),
+        };
+        let _ = app.emit("graphql://subscription", event.clone());
+        events.push(event);
+    };
+
+    let protocol = request.protocol;
+    let outcome: Result<(), String> = async {
+        let connection = crate::websocket::connect(
+            &ws_endpoint(&request.endpoint),
+            &request.headers,
+            &[protocol.subprotocol()],
+        )
+        .await?;
+        if connection.protocol.as_deref() != Some(protocol.subprotocol()) {
+            return Err(format!(
+                "server did not accept the {} subprotocol",
+                protocol.subprotocol()
+            ));
+        }
+        let mut socket = connection.stream;
+        let send = |value: Value| Message::Text(value.to_string());
+
+        socket
+            .send(send(serde_json::json!({
+                "type": "connection_init",
+                "payload": request.connection_params.clone().unwrap_or(Value::Object(Default::default())),
+            })))
+            .await
+            .map_err(|e| format!("connection_init failed: {e}"))?;
+
+        let operation_id = "1";
+        let mut subscribed = false;
+        loop {
+            tokio::select! {
+                _ = &mut stop => {
+                    let _ = socket
+                        .send(send(serde_json::json!({ "id": operation_id, "type": protocol.stop_type() })))
+                        .await;
+                    if protocol == SubscriptionProtocol::SubscriptionsTransportWs {
+                        let _ = socket
+                            .send(send(serde_json::json!({ "type": "connection_terminate" })))
+                            .await;
+                    }
+                    let _ = socket.close(None).await;
+                    record("stopped", None);
+                    return Ok(());
+                }
+                incoming = socket.next() => {
+                    let frame = match incoming {
+                        Some(Ok(frame)) => frame,
+                        Some(Err(e)) => return Err(format!("WebSocket error: {e}")),
+                        None => return Err("connection closed by server".to_string()),
+                    };
+                    let text = match frame {
+                        Message::Text(text) => text,
+                        Message::Close(reason) => {
+                            return Err(format!(
+                                "connection closed by server{}",
+                                reason.map(|r| format!(": {} {}", r.code, r.reason)).unwrap_or_default()
+                            ))
+                        }
+                        _ => continue,
+                    };
+                    let message: Value = serde_json::from_str(&text)
+                        .map_err(|e| format!("invalid protocol message: {e}"))?;
+                    let payload = message.get("payload").cloned();
+                    match message["type"].as_str().unwrap_or_default() {
+                        "connection_ack" if !subscribed => {
+                            record("ack", payload);
+                            subscribed = true;
+                            socket
+                                .send(send(serde_json::json!({
+                                    "id": operation_id,
+                                    "type": protocol.start_type(),
+                                    "payload": {
+                                        "query": request.query,
+                                        "variables": request.variables,
+                                        "operationName": request.operation_name,
+                                    },
+                                })))
+                                .await
+                                .map_err(|e| format!("subscribe failed: {e}"))?;
+                        }
+                        "ping" => {
+                            socket
+                                .send(send(serde_json::json!({ "type": "pong" })))
+                                .await
+                                .map_err(|e| format!("pong failed: {e}"))?;
+                        }
+                        "next" | "data" => record("next", payload),
+                        "error" | "connection_error" => record("error", payload),
+                        "complete" => {
+                            record("complete", None);
+                            let _ = socket.close(None).await;
+                            return Ok(());
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    .await;
+
+    if let Err(e) = outcome {
+        record("error", Some(Value::String(e)));
+    }
+
+    app.state::<GraphqlState>()
+        .subscriptions
+        .lock()
+        .await
+        .remove(&subscription_id);
+
+    let history = SubscriptionRecord {
+        subscription_id: subscription_id.clone(),
+        endpoint: request.endpoint,
+        query: request.query,
+        variables: request.variables,
+        started_at_ms,
+        ended_at_ms: crate::now_ms(),
+        events,
+    };
+    if let Ok(mut path) = subscription_history_dir(&app) {
+        path.push(format!("{started_at_ms}-{subscription_id}.json"));
+        if let Ok(data) = serde_json::to_string_pretty(&history) {
+            let _ = fs::write(path, data);
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn graphql_subscribe(
+    app: tauri::AppHandle,
+    state: State<'_, GraphqlState>,
+    request: SubscriptionRequest,
+) -> Result<String, String> {
+    let subscription_id = uuid::Uuid::new_v4().to_string();
+    let (stop_tx, stop_rx) = oneshot::channel();
+    state
+        .subscriptions
+        .lock()
+        .await
+        .insert(subscription_id.clone(), stop_tx);
+    tauri::async_runtime::spawn(run_subscription(
+        app,
+        subscription_id.clone(),
+        request,
+        stop_rx,
+    ));
+    Ok(subscription_id)
+}
+
+#[tauri::command]
+pub async fn graphql_unsubscribe(
+    state: State<'_, GraphqlState>,
+    subscription_id: String,
+) -> Result<(), String> {
+    let stop = state
+        .subscriptions
+        .lock()
+        .await
+        .remove(&subscription_id)
+        .ok_or_else(|| format!("unknown subscription: {subscription_id}"))?;
+    let _ = stop.send(());
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn graphql_subscription_history(
+    app: tauri::AppHandle,
+) -> Result<Vec<SubscriptionRecord>, String> {
+    let dir = subscription_history_dir(&app)?;
+    let mut records: Vec<SubscriptionRecord> = fs::read_dir(&dir)
+        .map_err(|e| format!("subscription history read failed: {e}"))?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .filter_map(|data| serde_json::from_str(&data).ok())
+        .collect();
+    records.sort_by_key(|r| std::cmp::Reverse(r.started_at_ms));
+    Ok(records)
+}
@@ -0,0 +1,247 @@
+//! GraphQL persisted queries for requests with `graphql_persisted` set:
+//! - `apq` (Automatic Persisted Queries): the request first goes out with only the query's
+//!   SHA-256 hash; when the server doesn't know it yet (`PersistedQueryNotFound`) it's sent
+//!   again with the full query, which registers it. A server without APQ support gets the
+//!   plain query.
+//! - `static`: the query is looked up in the imported persisted-query manifest and only its
+//!   id is sent, for servers that accept nothing but their static operations.
+//!
+//! Manifests (Apollo's `apollo-persisted-query-manifest`, Relay's `{id: text}` map, or a
+//! plain list of `{id, body}`) are kept per workspace in `.litefetch/graphql`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::importers::str_of;
+
+const MANIFEST_FILE: &str = "persisted-queries.json";
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PersistedOperation {
+    pub id: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub body: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct ManifestFile {
+    operations: Vec<PersistedOperation>,
+}
+
+/// The requests to send for a persisted query, in order.
+pub struct Persisted {
+    pub first: Value,
+    /// Sent when `first` misses (see `missed`); `None` for static operations.
+    pub fallback: Option<Value>,
+    /// Sent instead when the server doesn't support persisted queries at all.
+    pub unsupported: Option<Value>,
+    pub is_static: bool,
+}
+
+fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::workspace_state_dir(app, "graphql")?.join(MANIFEST_FILE))
+}
+
+fn load(app: &tauri::AppHandle) -> Result<Vec<PersistedOperation>, String> {
+    let path = path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data =
+        fs::read_to_string(&path).map_err(|e| format!("persisted queries read failed: {e}"))?;
+    serde_json::from_str::<ManifestFile>(&data)
+        .map(|file| file.operations)
+        .map_err(|e| format!("persisted queries parse failed: {e}"))
+}
+
+fn save(app: &tauri::AppHandle, operations: Vec<PersistedOperation>) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(&ManifestFile { operations })
+        .map_err(|e| format!("persisted queries serialize failed: {e}"))?;
+    fs::write(path(app)?, payload).map_err(|e| format!("persisted queries persist failed: {e}"))
+}
+
+/// The query with runs of whitespace collapsed, so formatting doesn't stop a manifest match.
+fn normalized(query: &str) -> String {
+    query.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn parse_manifest(data: &Value) -> Result<Vec<PersistedOperation>, String> {
+    let operation = |item: &Value| {
+        let body = match str_of(item, "body") {
+            "" => str_of(item, "query"),
+            body => body,
+        };
+        let name = str_of(item, "name");
+        (!str_of(item, "id").is_empty() && !body.is_empty()).then(|| PersistedOperation {
+            id: str_of(item, "id").to_string(),
+            name: (!name.is_empty()).then(|| name.to_string()),
+            body: body.to_string(),
+        })
+    };
+    let operations: Vec<PersistedOperation> = match data {
+        Value::Array(items) => items.iter().filter_map(operation).collect(),
+        Value::Object(map) => match map.get("operations").and_then(Value::as_array) {
+            Some(items) => items.iter().filter_map(operation).collect(),
+            // Relay: operation ids to query text.
+            None => map
+                .iter()
+                .filter_map(|(id, body)| {
+                    Some(PersistedOperation {
+                        id: id.clone(),
+                        name: None,
+                        body: body.as_str()?.to_string(),
+                    })
+                })
+                .collect(),
+        },
+        _ => Vec::new(),
+    };
+    match operations.is_empty() {
+        true => Err("no persisted operations found in the manifest".to_string()),
+        false => Ok(operations),
+    }
+}
+
+/// The GraphQL payload of a request's body: `query`, `variables` and `operationName`.
+fn payload_of(request: &Value) -> Result<Map<String, Value>, String> {
+    let body = match request.get("body") {
+        Some(Value::String(text)) => {
+            serde_json::from_str(text).map_err(|e| format!("GraphQL body isn't JSON: {e}"))?
+        }
+        Some(other) => other.clone(),
+        None => Value::Null,
+    };
+    match body {
+        Value::Object(map) if map.get("query").and_then(Value::as_str).is_some() => Ok(map),
+        _ => Err("a persisted query needs a JSON body with a query".to_string()),
+    }
+}
+
+fn with_body(request: &Value, body: Map<String, Value>) -> Value {
+    let mut request = request.clone();
+    request["body_mode"] = Value::String("json".to_string());
+    request["body"] = Value::String(Value::Object(body).to_string());
+    request
+}
+
+fn extension(hash: &str) -> Value {
+    json!({ "persistedQuery": { "version": 1, "sha256Hash": hash } })
+}
+
+/// The requests to send for `request` when it uses persisted queries.
+pub(crate) fn prepare(
+    app: &tauri::AppHandle,
+    request: &Value,
+) -> Result<Option<Persisted>, String> {
+    let mode = str_of(request, "graphql_persisted");
+    if mode.is_empty() || mode == "none" {
+        return Ok(None);
+    }
+    let payload = payload_of(request)?;
+    let query = payload
+        .get("query")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let mut hashed = payload.clone();
+    hashed.remove("query");
+    match mode {
+        "apq" => {
+            let hash = hex::encode(Sha256::digest(query.as_bytes()));
+            hashed.insert("extensions".to_string(), extension(&hash));
+            let mut full = payload.clone();
+            full.insert("extensions".to_string(), extension(&hash));
+            Ok(Some(Persisted {
+                first: with_body(request, hashed),
+                fallback: Some(with_body(request, full)),
+                unsupported: Some(with_body(request, payload)),
+                is_static: false,
+            }))
+        }
+        "static" => {
+            let wanted = normalized(&query);
+            let name = payload.get("operationName").and_then(Value::as_str);
+            let operations = load(app)?;
+            let found = operations
+                .iter()
+                .find(|op| normalized(&op.body) == wanted)
+                .or_else(|| {
+                    operations
+                        .iter()
+                        .find(|op| name.is_some() && op.name.as_deref() == name)
+                })
+                .ok_or("query isn't in the persisted-query manifest")?;
+            hashed.insert("extensions".to_string(), extension(&found.id));
+            Ok(Some(Persisted {
+                first: with_body(request, hashed),
+                fallback: None,
+                unsupported: None,
+                is_static: true,
+            }))
+        }
+        other => Err(format!("unknown graphql_persisted mode: {other}")),
+    }
+}
+
+/// `not_found` or `not_supported` when the server turned down a hash-only request.
+pub(crate) fn missed(result: &Value) -> Option<&'static str> {
+    let body: Value = match result.get("body") {
+        Some(Value::String(text)) => serde_json::from_str(text).ok()?,
+        Some(other) => other.clone(),
+        None => return None,
+    };
+    let errors = body.get("errors")?.as_array()?;
+    let said = |what: &str, code: &str| {
+        errors.iter().any(|error| {
+            str_of(error, "message") == what
+                || error.pointer("/extensions/code").and_then(Value::as_str) == Some(code)
+        })
+    };
+    if said("PersistedQueryNotFound", "PERSISTED_QUERY_NOT_FOUND") {
+        Some("not_found")
+    } else if said(
+        "PersistedQueryNotSupported",
+        "PERSISTED_QUERY_NOT_SUPPORTED",
+    ) {
+        Some("not_supported")
+    } else {
+        None
+    }
+}
+
+#[tauri::command]
+pub async fn graphql_list_persisted_queries(
+    app: tauri::AppHandle,
+) -> Result<Vec<PersistedOperation>, String> {
+    load(&app)
+}
+
+/// Adds the operations of a manifest file, replacing those with the same id; returns how
+/// many the manifest had.
+#[tauri::command]
+pub async fn graphql_import_persisted_manifest(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<usize, String> {
+    let data = fs::read_to_string(crate::normalize_path(&path))
+        .map_err(|e| format!("manifest read failed: {e}"))?;
+    let manifest: Value =
+        serde_json::from_str(&data).map_err(|e| format!("manifest parse failed: {e}"))?;
+    let incoming = parse_manifest(&manifest)?;
+    let count = incoming.len();
+    let mut operations = load(&app)?;
+    operations.retain(|op| !incoming.iter().any(|new| new.id == op.id));
+    operations.extend(incoming);
+    save(&app, operations)?;
+    Ok(count)
+}
+
+#[tauri::command]
+pub async fn graphql_clear_persisted_queries(app: tauri::AppHandle) -> Result<(), String> {
+    save(&app, Vec::new())
+}
@@ -0,0 +1,1194 @@
+use base64::Engine;
+use prost::Message;
+use prost_reflect::prost_types::{FileDescriptorProto, FileDescriptorSet};
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, MethodDescriptor};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, Manager, State};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::metadata::{Binary, MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic::{Code, Status};
+
+use crate::grpc_web::{self, Frame, FrameDecoder, GrpcTransport, WebTls};
+use tonic_reflection::pb::v1;
+use tonic_reflection::pb::v1alpha::server_reflection_client::ServerReflectionClient;
+use tonic_reflection::pb::v1alpha::server_reflection_request::MessageRequest;
+use tonic_reflection::pb::v1alpha::server_reflection_response::MessageResponse;
+use tonic_reflection::pb::v1alpha::{ServerReflectionRequest, ServerReflectionResponse};
+
+pub struct GrpcState {
+    pools: Mutex<HashMap<String, DescriptorPool>>,
+    streams: Mutex<HashMap<String, GrpcStreamHandle>>,
+}
+
+struct GrpcStreamHandle {
+    input: MessageDescriptor,
+    outbound: Option<mpsc::UnboundedSender<DynamicMessage>>,
+    task: JoinHandle<()>,
+}
+
+impl GrpcState {
+    pub fn new() -> Self {
+        Self {
+            pools: Mutex::new(HashMap::new()),
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) async fn pool(&self, schema_id: &str) -> Result<DescriptorPool, String> {
+        self.pools
+            .lock()
+            .await
+            .get(schema_id)
+            .cloned()
+            .ok_or_else(|| format!("unknown proto schema: {schema_id}"))
+    }
+}
+
+#[derive(Serialize)]
+pub struct GrpcMethodInfo {
+    name: String,
+    full_name: String,
+    input_type: String,
+    output_type: String,
+    client_streaming: bool,
+    server_streaming: bool,
+}
+
+#[derive(Serialize)]
+pub struct GrpcServiceInfo {
+    name: String,
+    full_name: String,
+    methods: Vec<GrpcMethodInfo>,
+}
+
+#[derive(Serialize)]
+pub struct GrpcSchema {
+    schema_id: String,
+    services: Vec<GrpcServiceInfo>,
+    /// Every message type, for decoding a protobuf HTTP response with one.
+    messages: Vec<String>,
+}
+
+const TARGET_TLS_FILE: &str = "tls.json";
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct GrpcTlsOptions {
+    #[serde(default)]
+    ca_cert_path: Option<String>,
+    #[serde(default)]
+    client_cert_path: Option<String>,
+    #[serde(default)]
+    client_key_path: Option<String>,
+    #[serde(default)]
+    domain_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GrpcCallRequest {
+    schema_id: String,
+    target: String,
+    service: String,
+    method: String,
+    #[serde(default)]
+    message: serde_json::Value,
+    #[serde(default)]
+    metadata: HashMap<String, String>,
+    #[serde(default)]
+    tls: Option<GrpcTlsOptions>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    /// Unix time (ms) the call must finish by; the sooner of this and `timeout_ms` applies.
+    #[serde(default)]
+    deadline_ms: Option<u64>,
+    #[serde(default)]
+    transport: GrpcTransport,
+}
+
+/// How a call went out, reported with its result.
+#[derive(Clone, Serialize)]
+pub struct GrpcCallInfo {
+    metadata: HashMap<String, String>,
+    /// The timeout in effect, from `timeout_ms` or `deadline_ms`.
+    timeout_ms: Option<u64>,
+    /// Where the TLS options came from: `call`, or `target` for the target's saved ones.
+    tls: Option<&'static str>,
+}
+
+impl GrpcCallRequest {
+    /// Folds `deadline_ms` into `timeout_ms` and falls back to the target's saved TLS options
+    /// when the call has none.
+    fn prepare(&mut self, app: &tauri::AppHandle) -> Result<GrpcCallInfo, String> {
+        if let Some(deadline) = self.deadline_ms {
+            let left = deadline
+                .checked_sub(crate::now_ms())
+                .filter(|ms| *ms > 0)
+                .ok_or("the call's deadline has already passed")?;
+            self.timeout_ms = Some(self.timeout_ms.map_or(left, |ms| ms.min(left)));
+        }
+        let tls = match self.tls {
+            Some(_) => Some("call"),
+            None => {
+                self.tls = target_tls(app, &self.target)?;
+                self.tls.as_ref().map(|_| "target")
+            }
+        };
+        Ok(GrpcCallInfo {
+            metadata: self.metadata.clone(),
+            timeout_ms: self.timeout_ms,
+            tls,
+        })
+    }
+}
+
+#[derive(Serialize)]
+pub struct GrpcCallResult {
+    status_code: i32,
+    status_message: String,
+    response: Option<serde_json::Value>,
+    headers: HashMap<String, String>,
+    trailers: HashMap<String, String>,
+    duration_ms: f64,
+    call: GrpcCallInfo,
+}
+
+impl GrpcTlsOptions {
+    fn web(&self) -> WebTls<'_> {
+        WebTls {
+            ca_cert_path: self.ca_cert_path.as_deref(),
+            client_cert_path: self.client_cert_path.as_deref(),
+            client_key_path: self.client_key_path.as_deref(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct GrpcStreamRequest {
+    #[serde(flatten)]
+    call: GrpcCallRequest,
+    #[serde(default)]
+    messages: Vec<serde_json::Value>,
+}
+
+#[derive(Clone, Serialize)]
+struct GrpcStreamEvent {
+    call_id: String,
+    kind: &'static str,
+    message: Option<serde_json::Value>,
+    metadata: Option<HashMap<String, String>>,
+    status_code: Option<i32>,
+    status_message: Option<String>,
+    /// On the `headers` event.
+    call: Option<GrpcCallInfo>,
+    timestamp_ms: u64,
+}
+
+impl GrpcStreamEvent {
+    fn new(call_id: &str, kind: &'static str) -> Self {
+        Self {
+            call_id: call_id.to_string(),
+            kind,
+            message: None,
+            metadata: None,
+            status_code: None,
+            status_message: None,
+            call: None,
+            timestamp_ms: crate::now_ms(),
+        }
+    }
+
+    fn status(call_id: &str, kind: &'static str, status: &Status) -> Self {
+        Self {
+            metadata: Some(metadata_to_map(status.metadata())),
+            status_code: Some(status.code() as i32),
+            status_message: Some(status.message().to_string()),
+            ..Self::new(call_id, kind)
+        }
+    }
+}
+
+/// Pass-through codec that encodes/decodes `DynamicMessage`s against runtime descriptors.
+#[derive(Clone)]
+pub(crate) struct DynamicCodec {
+    output: MessageDescriptor,
+}
+
+impl DynamicCodec {
+    pub(crate) fn new(output: MessageDescriptor) -> Self {
+        Self { output }
+    }
+}
+
+pub(crate) struct DynamicEncoder;
+
+pub(crate) struct DynamicDecoder {
+    output: MessageDescriptor,
+}
+
+impl Codec for DynamicCodec {
+    type Encode = DynamicMessage;
+    type Decode = DynamicMessage;
+    type Encoder = DynamicEncoder;
+    type Decoder = DynamicDecoder;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        DynamicEncoder
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        DynamicDecoder {
+            output: self.output.clone(),
+        }
+    }
+}
+
+impl Encoder for DynamicEncoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        item.encode(dst)
+            .map_err(|e| Status::internal(format!("message encode failed: {e}")))
+    }
+}
+
+impl Decoder for DynamicDecoder {
+    type Item = DynamicMessage;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        DynamicMessage::decode(self.output.clone(), src)
+            .map(Some)
+            .map_err(|e| Status::internal(format!("message decode failed: {e}")))
+    }
+}
+
+/// A SHA-256 prefix, so cache file names stay the same across builds.
+fn short_hash(text: &str) -> String {
+    hex::encode(&Sha256::digest(text.as_bytes())[..8])
+}
+
+fn schema_id_for(paths: &[String]) -> String {
+    let mut sorted = paths.to_vec();
+    sorted.sort();
+    format!("proto-{}", short_hash(&sorted.join("\0")))
+}
+
+fn reflection_schema_id(target: &str) -> String {
+    format!("reflect-{}", short_hash(target.trim_end_matches('/')))
+}
+
+fn reflection_cache_path(app: &tauri::AppHandle, schema_id: &str) -> Result<PathBuf, String> {
+    let mut dir = crate::workspace_state_dir(app, "grpc")?;
+    dir.push(format!("{schema_id}.bin"));
+    Ok(dir)
+}
+
+/// Saved TLS options are keyed by target without its scheme, so `host:443` and
+/// `https://host:443` share them.
+fn target_key(target: &str) -> String {
+    let target = target.trim();
+    let target = target.split_once("://").map_or(target, |(_, rest)| rest);
+    target.trim_end_matches('/').to_lowercase()
+}
+
+fn target_tls_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::workspace_state_dir(app, "grpc")?.join(TARGET_TLS_FILE))
+}
+
+fn load_target_tls(app: &tauri::AppHandle) -> Result<BTreeMap<String, GrpcTlsOptions>, String> {
+    let path = target_tls_path(app)?;
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("gRPC TLS read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("gRPC TLS parse failed: {e}"))
+}
+
+fn target_tls(app: &tauri::AppHandle, target: &str) -> Result<Option<GrpcTlsOptions>, String> {
+    Ok(load_target_tls(app)?.remove(&target_key(target)))
+}
+
+pub(crate) fn describe_pool(schema_id: &str, pool: &DescriptorPool) -> GrpcSchema {
+    let services = pool
+        .services()
+        .map(|svc| GrpcServiceInfo {
+            name: svc.name().to_string(),
+            full_name: svc.full_name().to_string(),
+            methods: svc
+                .methods()
+                .map(|m| GrpcMethodInfo {
+                    name: m.name().to_string(),
+                    full_name: m.full_name().to_string(),
+                    input_type: m.input().full_name().to_string(),
+                    output_type: m.output().full_name().to_string(),
+                    client_streaming: m.is_client_streaming(),
+                    server_streaming: m.is_server_streaming(),
+                })
+                .collect(),
+        })
+        .collect();
+    GrpcSchema {
+        schema_id: schema_id.to_string(),
+        services,
+        messages: pool
+            .all_messages()
+            .filter(|m| !m.is_map_entry())
+            .map(|m| m.full_name().to_string())
+            .collect(),
+    }
+}
+
+fn compile_protos(paths: &[String], include_dirs: &[String]) -> Result<DescriptorPool, String> {
+    let mut includes: Vec<String> = include_dirs.to_vec();
+    for path in paths {
+        if let Some(parent) = crate::normalize_path(path).parent() {
+            let parent = parent.to_string_lossy().to_string();
+            if !includes.contains(&parent) {
+                includes.push(parent);
+            }
+        }
+    }
+    let files: Vec<_> = paths.iter().map(|p| crate::normalize_path(p)).collect();
+    let mut compiler =
+        protox::Compiler::new(includes).map_err(|e| format!("proto include setup failed: {e}"))?;
+    compiler.include_imports(true);
+    compiler
+        .open_files(files)
+        .map_err(|e| format!("proto compile failed: {e}"))?;
+    Ok(compiler.descriptor_pool())
+}
+
+pub(crate) fn find_method(
+    pool: &DescriptorPool,
+    service: &str,
+    method: &str,
+) -> Result<MethodDescriptor, String> {
+    let svc = pool
+        .get_service_by_name(service)
+        .or_else(|| pool.services().find(|s| s.name() == service))
+        .ok_or_else(|| format!("unknown gRPC service: {service}"))?;
+    let found = svc.methods().find(|m| m.name() == method);
+    found.ok_or_else(|| format!("unknown method {method} on {}", svc.full_name()))
+}
+
+pub(crate) async fn connect(target: &str, tls: Option<&GrpcTlsOptions>) -> Result<Channel, String> {
+    let url = if target.contains("://") {
+        target.to_string()
+    } else if tls.is_some() {
+        format!("https://{target}")
+    } else {
+        format!("http://{target}")
+    };
+    let mut endpoint =
+        Endpoint::from_shared(url.clone()).map_err(|e| format!("invalid gRPC target: {e}"))?;
+    if url.starts_with("https://") {
+        let mut tls_config = ClientTlsConfig::new().with_native_roots();
+        if let Some(opts) = tls {
+            if let Some(ca) = &opts.ca_cert_path {
+                let pem = std::fs::read(crate::normalize_path(ca))
+                    .map_err(|e| format!("CA certificate read failed: {e}"))?;
+                tls_config = tls_config.ca_certificate(Certificate::from_pem(pem));
+            }
+            if let (Some(cert), Some(key)) = (&opts.client_cert_path, &opts.client_key_path) {
+                let cert = std::fs::read(crate::normalize_path(cert))
+                    .map_err(|e| format!("client certificate read failed: {e}"))?;
+                let key = std::fs::read(crate::normalize_path(key))
+                    .map_err(|e| format!("client key read failed: {e}"))?;
+                tls_config = tls_config.identity(Identity::from_pem(cert, key));
+            }
+            if let Some(domain) = &opts.domain_name {
+                tls_config = tls_config.domain_name(domain.clone());
+            }
+        }
+        endpoint = endpoint
+            .tls_config(tls_config)
+            .map_err(|e| format!("TLS setup failed: {e}"))?;
+    }
+    endpoint
+        .connect()
+        .await
+        .map_err(|e| format!("gRPC connect failed: {e}"))
+}
+
+pub(crate) fn apply_metadata(
+    target: &mut MetadataMap,
+    metadata: &HashMap<String, String>,
+) -> Result<(), String> {
+    for (key, value) in metadata {
+        if key.to_lowercase().ends_with("-bin") {
+            let name = MetadataKey::<Binary>::from_str(&key.to_lowercase())
+                .map_err(|e| format!("invalid metadata key {key}: {e}"))?;
+            let value = MetadataValue::from_bytes(&binary_metadata(key, value)?);
+            target.insert_bin(name, value);
+            continue;
+        }
+        let name = MetadataKey::from_str(&key.to_lowercase())
+            .map_err(|e| format!("invalid metadata key {key}: {e}"))?;
+        let value = MetadataValue::from_str(value)
+            .map_err(|e| format!("invalid metadata value for {key}: {e}"))?;
+        target.insert(name, value);
+    }
+    Ok(())
+}
+
+/// The bytes of a `-bin` metadata value, given base64-encoded (padded or not).
+pub(crate) fn binary_metadata(key: &str, value: &str) -> Result<Vec<u8>, String> {
+    base64::engine::general_purpose::STANDARD_NO_PAD
+        .decode(value.trim().trim_end_matches('='))
+        .map_err(|e| format!("metadata {key} must be base64: {e}"))
+}
+
+/// Binary (`-bin`) values stay base64, as they are on the wire.
+pub(crate) fn metadata_to_map(metadata: &MetadataMap) -> HashMap<String, String> {
+    metadata
+        .clone()
+        .into_headers()
+        .iter()
+        .map(|(k, v)| {
+            (
+                k.to_string(),
+                String::from_utf8_lossy(v.as_bytes()).to_string(),
+            )
+        })
+        .collect()
+}
+
+pub(crate) fn message_to_json(message: &DynamicMessage) -> Result<serde_json::Value, String> {
+    serde_json::to_value(message).map_err(|e| format!("message to JSON failed: {e}"))
+}
+
+/// Decodes a bare protobuf message (no gRPC framing) of type `message_type` as JSON.
+pub(crate) fn decode_message(
+    pool: &DescriptorPool,
+    message_type: &str,
+    bytes: &[u8],
+) -> Result<serde_json::Value, String> {
+    let desc = pool
+        .get_message_by_name(message_type)
+        .ok_or_else(|| format!("unknown message type: {message_type}"))?;
+    let message =
+        DynamicMessage::decode(desc, bytes).map_err(|e| format!("protobuf decode failed: {e}"))?;
+    message_to_json(&message)
+}
+
+pub(crate) fn message_from_json(
+    desc: MessageDescriptor,
+    value: serde_json::Value,
+) -> Result<DynamicMessage, String> {
+    let value = if value.is_null() {
+        serde_json::Value::Object(Default::default())
+    } else {
+        value
+    };
+    DynamicMessage::deserialize(desc, value).map_err(|e| format!("request message invalid: {e}"))
+}
+
+pub(crate) fn method_path(method: &MethodDescriptor) -> Result<http::uri::PathAndQuery, String> {
+    let path = format!("/{}/{}", method.parent_service().full_name(), method.name());
+    http::uri::PathAndQuery::from_str(&path).map_err(|e| format!("invalid method path: {e}"))
+}
+
+/// The server reflection service in the version the server offers. The two share their wire
+/// format, so requests and responses are carried between them by re-encoding.
+enum ReflectionClient {
+    V1Alpha(ServerReflectionClient<Channel>),
+    V1(v1::server_reflection_client::ServerReflectionClient<Channel>),
+}
+
+fn transcode<T: Message + Default>(message: &impl Message) -> Result<T, Status> {
+    T::decode(message.encode_to_vec().as_slice())
+        .map_err(|e| Status::internal(format!("reflection message invalid: {e}")))
+}
+
+fn reflection_error(status: Status) -> String {
+    format!("server reflection failed: {}", status.message())
+}
+
+async fn reflection_request(
+    client: &mut ReflectionClient,
+    metadata: &HashMap<String, String>,
+    message: MessageRequest,
+) -> Result<MessageResponse, Status> {
+    let outbound = ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(message),
+    };
+    let response: Option<ServerReflectionResponse> = match client {
+        ReflectionClient::V1Alpha(client) => {
+            let mut call = tonic::Request::new(tokio_stream::iter(vec![outbound]));
+            apply_metadata(call.metadata_mut(), metadata).map_err(Status::invalid_argument)?;
+            client
+                .server_reflection_info(call)
+                .await?
+                .into_inner()
+                .message()
+                .await?
+        }
+        ReflectionClient::V1(client) => {
+            let outbound: v1::ServerReflectionRequest = transcode(&outbound)?;
+            let mut call = tonic::Request::new(tokio_stream::iter(vec![outbound]));
+            apply_metadata(call.metadata_mut(), metadata).map_err(Status::invalid_argument)?;
+            match client
+                .server_reflection_info(call)
+                .await?
+                .into_inner()
+                .message()
+                .await?
+            {
+                Some(response) => Some(transcode(&response)?),
+                None => None,
+            }
+        }
+    };
+    match response.and_then(|r| r.message_response) {
+        Some(MessageResponse::ErrorResponse(err)) => Err(Status::new(
+            Code::from(err.error_code),
+            format!("error {}: {}", err.error_code, err.error_message),
+        )),
+        Some(other) => Ok(other),
+        None => Err(Status::unknown("empty response")),
+    }
+}
+
+fn collect_files(
+    response: MessageResponse,
+    files: &mut HashMap<String, FileDescriptorProto>,
+) -> Result<(), String> {
+    if let MessageResponse::FileDescriptorResponse(payload) = response {
+        for raw in payload.file_descriptor_proto {
+            let file = FileDescriptorProto::decode(raw.as_slice())
+                .map_err(|e| format!("descriptor decode failed: {e}"))?;
+            files.insert(file.name().to_string(), file);
+        }
+    }
+    Ok(())
+}
+
+async fn reflect_descriptors(
+    target: &str,
+    tls: Option<&GrpcTlsOptions>,
+    metadata: &HashMap<String, String>,
+) -> Result<FileDescriptorSet, String> {
+    let channel = connect(target, tls).await?;
+    // Most servers offer v1alpha; some newer ones only v1.
+    let mut client = ReflectionClient::V1Alpha(ServerReflectionClient::new(channel.clone()));
+    let list = MessageRequest::ListServices(String::new());
+    let listed = match reflection_request(&mut client, metadata, list.clone()).await {
+        Err(status) if status.code() == Code::Unimplemented => {
+            client = ReflectionClient::V1(
+                v1::server_reflection_client::ServerReflectionClient::new(channel),
+            );
+            reflection_request(&mut client, metadata, list).await
+        }
+        listed => listed,
+    };
+    let services = match listed.map_err(reflection_error)? {
+        MessageResponse::ListServicesResponse(list) => list.service,
+        _ => return Err("unexpected reflection response to ListServices".to_string()),
+    };
+
+    let mut files = HashMap::new();
+    for service in services {
+        if service.name.starts_with("grpc.reflection.") {
+            continue;
+        }
+        let response = reflection_request(
+            &mut client,
+            metadata,
+            MessageRequest::FileContainingSymbol(service.name),
+        )
+        .await
+        .map_err(reflection_error)?;
+        collect_files(response, &mut files)?;
+    }
+
+    // Servers may omit transitive imports; fetch any that are still missing by filename.
+    let mut requested = HashSet::new();
+    loop {
+        let missing: Vec<String> = files
+            .values()
+            .flat_map(|f| f.dependency.iter().cloned())
+            .filter(|dep| !files.contains_key(dep) && !requested.contains(dep))
+            .collect();
+        if missing.is_empty() {
+            break;
+        }
+        for dep in missing {
+            requested.insert(dep.clone());
+            let response =
+                reflection_request(&mut client, metadata, MessageRequest::FileByFilename(dep))
+                    .await
+                    .map_err(reflection_error)?;
+            collect_files(response, &mut files)?;
+        }
+    }
+
+    Ok(FileDescriptorSet {
+        file: files.into_values().collect(),
+    })
+}
+
+#[tauri::command]
+pub async fn grpc_reflect(
+    app: tauri::AppHandle,
+    state: State<'_, GrpcState>,
+    target: String,
+    tls: Option<GrpcTlsOptions>,
+    metadata: Option<HashMap<String, String>>,
+    refresh: Option<bool>,
+) -> Result<GrpcSchema, String> {
+    let schema_id = reflection_schema_id(&target);
+    let cache_path = reflection_cache_path(&app, &schema_id)?;
+    let tls = match tls {
+        Some(tls) => Some(tls),
+        None => target_tls(&app, &target)?,
+    };
+
+    let cached = if refresh.unwrap_or(false) {
+        None
+    } else {
+        fs::read(&cache_path)
+            .ok()
+            .and_then(|bytes| DescriptorPool::decode(bytes.as_slice()).ok())
+    };
+
+    let pool = match cached {
+        Some(pool) => pool,
+        None => {
+            let set =
+                reflect_descriptors(&target, tls.as_ref(), &metadata.unwrap_or_default()).await?;
+            let pool = DescriptorPool::from_file_descriptor_set(set.clone())
+                .map_err(|e| format!("reflected descriptors invalid: {e}"))?;
+            fs::write(&cache_path, set.encode_to_vec())
+                .map_err(|e| format!("descriptor cache write failed: {e}"))?;
+            pool
+        }
+    };
+
+    let schema = describe_pool(&schema_id, &pool);
+    state.pools.lock().await.insert(schema_id, pool);
+    Ok(schema)
+}
+
+#[tauri::command]
+pub async fn grpc_load_protos(
+    state: State<'_, GrpcState>,
+    paths: Vec<String>,
+    include_dirs: Option<Vec<String>>,
+) -> Result<GrpcSchema, String> {
+    if paths.is_empty() {
+        return Err("no .proto files provided".to_string());
+    }
+    let pool = compile_protos(&paths, &include_dirs.unwrap_or_default())?;
+    let schema_id = schema_id_for(&paths);
+    let schema = describe_pool(&schema_id, &pool);
+    state.pools.lock().await.insert(schema_id, pool);
+    Ok(schema)
+}
+
+#[tauri::command]
+pub async fn grpc_list_services(
+    state: State<'_, GrpcState>,
+    schema_id: String,
+) -> Result<GrpcSchema, String> {
+    let pools = state.pools.lock().await;
+    let pool = pools
+        .get(&schema_id)
+        .ok_or_else(|| format!("unknown proto schema: {schema_id}"))?;
+    Ok(describe_pool(&schema_id, pool))
+}
+
+#[tauri::command]
+pub async fn grpc_invoke_unary(
+    app: tauri::AppHandle,
+    state: State<'_, GrpcState>,
+    mut request: GrpcCallRequest,
+) -> Result<GrpcCallResult, String> {
+    let pool = state
+        .pools
+        .lock()
+        .await
+        .get(&request.schema_id)
+        .cloned()
+        .ok_or_else(|| format!("unknown proto schema: {}", request.schema_id))?;
+    let method = find_method(&pool, &request.service, &request.method)?;
+    if method.is_client_streaming() || method.is_server_streaming() {
+        return Err(format!("{} is a streaming method", method.full_name()));
+    }
+    let message = message_from_json(method.input(), std::mem::take(&mut request.message))?;
+    let info = request.prepare(&app)?;
+    if request.transport.is_web() {
+        return invoke_web_unary(&request, &method, message, info).await;
+    }
+    let channel = connect(&request.target, request.tls.as_ref()).await?;
+
+    let mut call = tonic::Request::new(message);
+    apply_metadata(call.metadata_mut(), &request.metadata)?;
+    if let Some(ms) = request.timeout_ms {
+        call.set_timeout(Duration::from_millis(ms));
+    }
+
+    let mut client = tonic::client::Grpc::new(channel);
+    client
+        .ready()
+        .await
+        .map_err(|e| format!("gRPC channel not ready: {e}"))?;
+    let started = Instant::now();
+    let outcome = client
+        .unary(
+            call,
+            method_path(&method)?,
+            DynamicCodec::new(method.output()),
+        )
+        .await;
+    let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    match outcome {
+        Ok(response) => {
+            let headers = metadata_to_map(response.metadata());
+            let body = message_to_json(response.get_ref())?;
+            Ok(GrpcCallResult {
+                status_code: 0,
+                status_message: "OK".to_string(),
+                response: Some(body),
+                headers,
+                trailers: HashMap::new(),
+                duration_ms,
+                call: info,
+            })
+        }
+        Err(status) => Ok(GrpcCallResult {
+            status_code: status.code() as i32,
+            status_message: status.message().to_string(),
+            response: None,
+            headers: HashMap::new(),
+            trailers: metadata_to_map(status.metadata()),
+            duration_ms,
+            call: info,
+        }),
+    }
+}
+
+async fn send_web(
+    call: &GrpcCallRequest,
+    method: &MethodDescriptor,
+    message: &DynamicMessage,
+) -> Result<reqwest::Response, String> {
+    let path = method_path(method)?;
+    grpc_web::send(
+        call.transport,
+        &call.target,
+        path.as_str(),
+        &message.encode_to_vec(),
+        &call.metadata,
+        call.tls.as_ref().map(GrpcTlsOptions::web),
+        call.timeout_ms,
+    )
+    .await
+}
+
+fn decode_web_message(
+    output: MessageDescriptor,
+    bytes: &[u8],
+) -> Result<serde_json::Value, String> {
+    let message =
+        DynamicMessage::decode(output, bytes).map_err(|e| format!("message decode failed: {e}"))?;
+    message_to_json(&message)
+}
+
+/// Trailers-only and gateway error responses carry the status in headers instead of a trailer frame.
+fn web_status(
+    http_status: reqwest::StatusCode,
+    headers: &HashMap<String, String>,
+    trailers: &HashMap<String, String>,
+) -> (i32, String) {
+    if trailers.contains_key("grpc-status") {
+        grpc_web::status_from(trailers)
+    } else if headers.contains_key("grpc-status") || http_status.is_success() {
+        grpc_web::status_from(headers)
+    } else {
+        (tonic::Code::Unknown as i32, format!("HTTP {http_status}"))
+    }
+}
+
+async fn invoke_web_unary(
+    request: &GrpcCallRequest,
+    method: &MethodDescriptor,
+    message: DynamicMessage,
+    info: GrpcCallInfo,
+) -> Result<GrpcCallResult, String> {
+    let started = Instant::now();
+    let response = send_web(request, method, &message).await?;
+    let http_status = response.status();
+    let headers = grpc_web::response_headers(&response);
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("grpc-web response read failed: {e}"))?;
+    let duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+    let mut reply = None;
+    let mut trailers = HashMap::new();
+    for frame in FrameDecoder::new(request.transport).push(&body)? {
+        match frame {
+            Frame::Data(bytes) if reply.is_none() => {
+                reply = Some(decode_web_message(method.output(), &bytes)?)
+            }
+            Frame::Data(_) => {}
+            Frame::Trailers(map) => trailers.extend(map),
+        }
+    }
+    let (status_code, status_message) = web_status(http_status, &headers, &trailers);
+    Ok(GrpcCallResult {
+        status_code,
+        status_message,
+        response: reply,
+        headers,
+        trailers,
+        duration_ms,
+        call: info,
+    })
+}
+
+async fn finish_stream(app: &tauri::AppHandle, call_id: &str) {
+    app.state::<GrpcState>()
+        .streams
+        .lock()
+        .await
+        .remove(call_id);
+}
+
+async fn run_web_stream(
+    app: tauri::AppHandle,
+    call_id: String,
+    call: GrpcCallRequest,
+    method: MethodDescriptor,
+    message: DynamicMessage,
+    info: GrpcCallInfo,
+) {
+    let emit = |event: GrpcStreamEvent| {
+        let _ = app.emit("grpc://stream", event);
+    };
+
+    match send_web(&call, &method, &message).await {
+        Ok(response) => {
+            let http_status = response.status();
+            let headers = grpc_web::response_headers(&response);
+            emit(GrpcStreamEvent {
+                metadata: Some(headers.clone()),
+                call: Some(info),
+                ..GrpcStreamEvent::new(&call_id, "headers")
+            });
+            let mut decoder = FrameDecoder::new(call.transport);
+            let mut trailers = HashMap::new();
+            let mut body = response.bytes_stream();
+            while let Some(chunk) = body.next().await {
+                let frames = match chunk {
+                    Ok(bytes) => decoder.push(&bytes),
+                    Err(e) => Err(format!("grpc-web stream read failed: {e}")),
+                };
+                let frames = match frames {
+                    Ok(frames) => frames,
+                    Err(e) => {
+                        emit(GrpcStreamEvent {
+                            status_message: Some(e),
+                            ..GrpcStreamEvent::new(&call_id, "error")
+                        });
+                        finish_stream(&app, &call_id).await;
+                        return;
+                    }
+                };
+                for frame in frames {
+                    match frame {
+                        Frame::Data(bytes) => match decode_web_message(method.output(), &bytes) {
+                            Ok(json) => emit(GrpcStreamEvent {
+                                message: Some(json),
+                                ..GrpcStreamEvent::new(&call_id, "message")
+                            }),
+                            Err(e) => emit(GrpcStreamEvent {
+                                status_message: Some(e),
+                                ..GrpcStreamEvent::new(&call_id, "error")
+                            }),
+                        },
+                        Frame::Trailers(map) => trailers.extend(map),
+                    }
+                }
+            }
+            let (code, message) = web_status(http_status, &headers, &trailers);
+            emit(GrpcStreamEvent {
+                metadata: Some(trailers),
+                status_code: Some(code),
+                status_message: Some(message),
+                ..GrpcStreamEvent::new(&call_id, if code == 0 { "end" } else { "error" })
+            });
+        }
+        Err(e) => emit(GrpcStreamEvent {
+            status_message: Some(e),
+            ..GrpcStreamEvent::new(&call_id, "error")
+        }),
+    }
+    finish_stream(&app, &call_id).await;
+}
+
+async fn run_stream(
+    app: tauri::AppHandle,
+    call_id: String,
+    channel: Channel,
+    call: tonic::Request<UnboundedReceiverStream<DynamicMessage>>,
+    method: MethodDescriptor,
+    info: GrpcCallInfo,
+) {
+    let emit = |event: GrpcStreamEvent| {
+        let _ = app.emit("grpc://stream", event);
+    };
+
+    let mut client = tonic::client::Grpc::new(channel);
+    let outcome = match client.ready().await {
+        Ok(()) => match method_path(&method) {
+            Ok(path) => {
+                client
+                    .streaming(call, path, DynamicCodec::new(method.output()))
+                    .await
+            }
+            Err(e) => Err(Status::internal(e)),
+        },
+        Err(e) => Err(Status::unavailable(format!("gRPC channel not ready: {e}"))),
+    };
+
+    match outcome {
+        Ok(response) => {
+            emit(GrpcStreamEvent {
+                metadata: Some(metadata_to_map(response.metadata())),
+                call: Some(info),
+                ..GrpcStreamEvent::new(&call_id, "headers")
+            });
+            let mut inbound = response.into_inner();
+            loop {
+                match inbound.message().await {
+                    Ok(Some(message)) => match message_to_json(&message) {
+                        Ok(json) => emit(GrpcStreamEvent {
+                            message: Some(json),
+                            ..GrpcStreamEvent::new(&call_id, "message")
+                        }),
+                        Err(e) => emit(GrpcStreamEvent {
+                            status_message: Some(e),
+                            ..GrpcStreamEvent::new(&call_id, "error")
+                        }),
+                    },
+                    Ok(None) => {
+                        let trailers = inbound.trailers().await.ok().flatten();
+                        emit(GrpcStreamEvent {
+                            metadata: trailers.as_ref().map(metadata_to_map),
+                            status_code: Some(0),
+                            status_message: Some("OK".to_string()),
+                            ..GrpcStreamEvent::new(&call_id, "end")
+                        });
+                        break;
+                    }
+                    Err(status) => {
+                        emit(GrpcStreamEvent::status(&call_id, "error", &status));
+                        break;
+                    }
+                }
+            }
+        }
+        Err(status) => emit(GrpcStreamEvent::status(&call_id, "error", &status)),
+    }
+
+    finish_stream(&app, &call_id).await;
+}
+
+#[tauri::command]
+pub async fn grpc_start_stream(
+    app: tauri::AppHandle,
+    state: State<'_, GrpcState>,
+    request: GrpcStreamRequest,
+) -> Result<String, String> {
+    let GrpcStreamRequest { mut call, messages } = request;
+    let pool = state
+        .pools
+        .lock()
+        .await
+        .get(&call.schema_id)
+        .cloned()
+        .ok_or_else(|| format!("unknown proto schema: {}", call.schema_id))?;
+    let method = find_method(&pool, &call.service, &call.method)?;
+    if !method.is_client_streaming() && !method.is_server_streaming() {
+        return Err(format!("{} is a unary method", method.full_name()));
+    }
+    let info = call.prepare(&app)?;
+
+    if call.transport.is_web() {
+        // Browsers cannot stream request bodies, so gRPC-Web only supports server streaming.
+        if method.is_client_streaming() {
+            return Err("gRPC-Web does not support client or bidirectional streaming".to_string());
+        }
+        let first = messages.into_iter().next().unwrap_or_default();
+        let value = if call.message.is_null() {
+            first
+        } else {
+            std::mem::take(&mut call.message)
+        };
+        let message = message_from_json(method.input(), value)?;
+        let call_id = uuid::Uuid::new_v4().to_string();
+        let mut streams = state.streams.lock().await;
+        let task = tauri::async_runtime::spawn(run_web_stream(
+            app.clone(),
+            call_id.clone(),
+            call,
+            method.clone(),
+            message,
+            info,
+        ));
+        streams.insert(
+            call_id.clone(),
+            GrpcStreamHandle {
+                input: method.input(),
+                outbound: None,
+                task,
+            },
+        );
+        return Ok(call_id);
+    }
+
+    let mut initial = Vec::new();
+    if !call.message.is_null() {
+        initial.push(std::mem::take(&mut call.message));
+    }
+    initial.extend(messages);
+    let (tx, rx) = mpsc::unbounded_channel();
+    for value in initial {
+        let message = message_from_json(method.input(), value)?;
+        tx.send(message)
+            .map_err(|_| "stream closed before start".to_string())?;
+    }
+    // Server-streaming calls carry exactly one request message, so half-close immediately.
+    let outbound = if method.is_client_streaming() {
+        Some(tx)
+    } else {
+        None
+    };
+
+    let channel = connect(&call.target, call.tls.as_ref()).await?;
+    let mut outgoing = tonic::Request::new(UnboundedReceiverStream::new(rx));
+    apply_metadata(outgoing.metadata_mut(), &call.metadata)?;
+    if let Some(ms) = call.timeout_ms {
+        outgoing.set_timeout(Duration::from_millis(ms));
+    }
+
+    let call_id = uuid::Uuid::new_v4().to_string();
+    let mut streams = state.streams.lock().await;
+    let task = tauri::async_runtime::spawn(run_stream(
+        app.clone(),
+        call_id.clone(),
+        channel,
+        outgoing,
+        method.clone(),
+        info,
+    ));
+    streams.insert(
+        call_id.clone(),
+        GrpcStreamHandle {
+            input: method.input(),
+            outbound,
+            task,
+        },
+    );
+    Ok(call_id)
+}
+
+#[tauri::command]
+pub async fn grpc_send_message(
+    state: State<'_, GrpcState>,
+    call_id: String,
+    message: serde_json::Value,
+) -> Result<(), String> {
+    let streams = state.streams.lock().await;
+    let handle = streams
+        .get(&call_id)
+        .ok_or_else(|| format!("unknown gRPC stream: {call_id}"))?;
+    let outbound = handle
+        .outbound
+        .as_ref()
+        .ok_or_else(|| "stream is not accepting client messages".to_string())?;
+    let message = message_from_json(handle.input.clone(), message)?;
+    outbound
+        .send(message)
+        .map_err(|_| "stream already closed".to_string())
+}
+
+#[tauri::command]
+pub async fn grpc_end_stream(state: State<'_, GrpcState>, call_id: String) -> Result<(), String> {
+    let mut streams = state.streams.lock().await;
+    let handle = streams
+        .get_mut(&call_id)
+        .ok_or_else(|| format!("unknown gRPC stream: {call_id}"))?;
+    handle.outbound = None;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn grpc_cancel_stream(
+    app: tauri::AppHandle,
+    state: State<'_, GrpcState>,
+    call_id: String,
+) -> Result<(), String> {
+    let handle = state
+        .streams
+        .lock()
+        .await
+        .remove(&call_id)
+        .ok_or_else(|| format!("unknown gRPC stream: {call_id}"))?;
+    handle.task.abort();
+    let _ = app.emit(
+        "grpc://stream",
+        GrpcStreamEvent {
+            status_code: Some(tonic::Code::Cancelled as i32),
+            status_message: Some("cancelled by user".to_string()),
+            ..GrpcStreamEvent::new(&call_id, "cancelled")
+        },
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn grpc_list_target_tls(
+    app: tauri::AppHandle,
+) -> Result<BTreeMap<String, GrpcTlsOptions>, String> {
+    load_target_tls(&app)
+}
+
+/// Saves the TLS options calls to `target` use when they don't bring their own; `None`
+/// forgets them.
+#[tauri::command]
+pub async fn grpc_set_target_tls(
+    app: tauri::AppHandle,
+    target: String,
+    tls: Option<GrpcTlsOptions>,
+) -> Result<BTreeMap<String, GrpcTlsOptions>, String> {
+    let key = target_key(&target);
+    if key.is_empty() {
+        return Err("gRPC target missing".to_string());
+    }
+    let mut targets = load_target_tls(&app)?;
+    match tls {
+        Some(tls) => targets.insert(key, tls),
+        None => targets.remove(&key),
+    };
+    let payload = serde_json::to_string_pretty(&targets)
+        .map_err(|e| format!("gRPC TLS serialize failed: {e}"))?;
+    fs::write(target_tls_path(&app)?, payload)
+        .map_err(|e| format!("gRPC TLS persist failed: {e}"))?;
+    Ok(targets)
+}
@@ -0,0 +1,220 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const FLAG_TRAILERS: u8 = 0x80;
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum GrpcTransport {
+    #[default]
+    Grpc,
+    GrpcWeb,
+    GrpcWebText,
+}
+
+impl GrpcTransport {
+    pub fn is_web(self) -> bool {
+        self != GrpcTransport::Grpc
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            GrpcTransport::GrpcWebText => "application/grpc-web-text+proto",
+            _ => "application/grpc-web+proto",
+        }
+    }
+}
+
+pub enum Frame {
+    Data(Vec<u8>),
+    Trailers(HashMap<String, String>),
+}
+
+/// Incremental parser for gRPC-Web response bodies (binary or base64 text framing).
+pub struct FrameDecoder {
+    text: bool,
+    pending_text: Vec<u8>,
+    buffer: Vec<u8>,
+}
+
+impl FrameDecoder {
+    pub fn new(transport: GrpcTransport) -> Self {
+        Self {
+            text: transport == GrpcTransport::GrpcWebText,
+            pending_text: Vec::new(),
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, chunk: &[u8]) -> Result<Vec<Frame>, String> {
+        if self.text {
+            self.pending_text
+                .extend(chunk.iter().copied().filter(|b| !b.is_ascii_whitespace()));
+            // Base64 decodes in independent 4-char quanta, so concatenated padded
+            // segments (one per server flush) can be decoded group by group.
+            let complete = self.pending_text.len() / 4 * 4;
+            for group in self.pending_text[..complete].chunks(4) {
+                let decoded = STANDARD
+                    .decode(group)
+                    .map_err(|e| format!("grpc-web-text decode failed: {e}"))?;
+                self.buffer.extend(decoded);
+            }
+            self.pending_text.drain(..complete);
+        } else {
+            self.buffer.extend_from_slice(chunk);
+        }
+
+        let mut frames = Vec::new();
+        while self.buffer.len() >= 5 {
+            let flag = self.buffer[0];
+            let len = u32::from_be_bytes([
+                self.buffer[1],
+                self.buffer[2],
+                self.buffer[3],
+                self.buffer[4],
+            ]) as usize;
+            if self.buffer.len() < 5 + len {
+                break;
+            }
+            let payload: Vec<u8> = self.buffer[5..5 + len].to_vec();
+            self.buffer.drain(..5 + len);
+            if flag & FLAG_TRAILERS != 0 {
+                frames.push(Frame::Trailers(parse_trailers(&payload)));
+            } else {
+                frames.push(Frame::Data(payload));
+            }
+        }
+        Ok(frames)
+    }
+}
+
+fn parse_trailers(payload: &[u8]) -> HashMap<String, String> {
+    String::from_utf8_lossy(payload)
+        .split("\r\n")
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            Some((key.trim().to_lowercase(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+pub fn encode_frame(transport: GrpcTransport, payload: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 5);
+    frame.push(0);
+    frame.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    frame.extend_from_slice(payload);
+    if transport == GrpcTransport::GrpcWebText {
+        STANDARD.encode(frame).into_bytes()
+    } else {
+        frame
+    }
+}
+
+pub struct WebTls<'a> {
+    pub ca_cert_path: Option<&'a str>,
+    pub client_cert_path: Option<&'a str>,
+    pub client_key_path: Option<&'a str>,
+}
+
+pub async fn send(
+    transport: GrpcTransport,
+    target: &str,
+    path: &str,
+    payload: &[u8],
+    metadata: &HashMap<String, String>,
+    tls: Option<WebTls<'_>>,
+    timeout_ms: Option<u64>,
+) -> Result<reqwest::Response, String> {
+    let base = if target.contains("://") {
+        target.trim_end_matches('/').to_string()
+    } else if tls.is_some() {
+        format!("https://{}", target.trim_end_matches('/'))
+    } else {
+        format!("http://{}", target.trim_end_matches('/'))
+    };
+
+    let mut builder = reqwest::Client::builder();
+    if let Some(opts) = tls {
+        if let Some(ca) = opts.ca_cert_path {
+            let pem = std::fs::read(crate::normalize_path(ca))
+                .map_err(|e| format!("CA certificate read failed: {e}"))?;
+            let cert = reqwest::Certificate::from_pem(&pem)
+                .map_err(|e| format!("CA certificate invalid: {e}"))?;
+            builder = builder.add_root_certificate(cert);
+        }
+        if let (Some(cert), Some(key)) = (opts.client_cert_path, opts.client_key_path) {
+            let mut pem = std::fs::read(crate::normalize_path(cert))
+                .map_err(|e| format!("client certificate read failed: {e}"))?;
+            pem.extend(
+                std::fs::read(crate::normalize_path(key))
+                    .map_err(|e| format!("client key read failed: {e}"))?,
+            );
+            let identity = reqwest::Identity::from_pem(&pem)
+                .map_err(|e| format!("client identity invalid: {e}"))?;
+            builder = builder.identity(identity);
+        }
+    }
+    if let Some(ms) = timeout_ms {
+        builder = builder.timeout(Duration::from_millis(ms));
+    }
+    let client = builder
+        .build()
+        .map_err(|e| format!("grpc-web client setup failed: {e}"))?;
+
+    // Mirror the headers grpc-web's JavaScript client sends from a browser.
+    let mut request = client
+        .post(format!("{base}{path}"))
+        .header("content-type", transport.content_type())
+        .header("accept", transport.content_type())
+        .header("x-grpc-web", "1")
+        .header("x-user-agent", "grpc-web-javascript/0.1")
+        .body(encode_frame(transport, payload));
+    if let Some(ms) = timeout_ms {
+        request = request.header("grpc-timeout", format!("{ms}m"));
+    }
+    for (key, value) in metadata {
+        request = match key.to_lowercase().ends_with("-bin") {
+            true => request.header(
+                key.as_str(),
+                STANDARD.encode(crate::grpc::binary_metadata(key, value)?),
+            ),
+            false => request.header(key.as_str(), value.as_str()),
+        };
+    }
+    request
+        .send()
+        .await
+        .map_err(|e| format!("grpc-web request failed: {e}"))
+}
+
+pub fn response_headers(response: &reqwest::Response) -> HashMap<String, String> {
+    response
+        .headers()
+        .iter()
+        .map(|(k, v)| {
+            (
+                k.to_string(),
+                String::from_utf8_lossy(v.as_bytes()).to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Extracts `(code, message)` from trailers, defaulting to OK when the server omitted a status.
+pub fn status_from(trailers: &HashMap<String, String>) -> (i32, String) {
+    let code = trailers
+        .get("grpc-status")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let message = trailers.get("grpc-message").cloned().unwrap_or_else(|| {
+        if code == 0 {
+            "OK".to_string()
+        } else {
+            String::new()
+        }
+    });
+    (code, message)
+}
@@ -0,0 +1,194 @@
+//! Version handshake with a freshly spawned backend: its `/version` is compared with the API
+//! revisions this shell supports (`backend::SUPPORTED_API`) and the outcome is announced as
+//! `backend://version`. An incompatible backend is refused, so callers get one clear error
+//! instead of odd API failures later; an old backend without the endpoint, or one that
+//! never answers, only warns.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager, State};
+
+use litefetch_core::backend::SUPPORTED_API;
+
+/// How long a starting backend gets to answer.
+const READY_TIMEOUT: Duration = Duration::from_secs(20);
+const RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct HandshakeState {
+    /// The outcome for the running backend.
+    last: Mutex<Option<BackendVersion>>,
+}
+
+impl HandshakeState {
+    pub fn new() -> Self {
+        Self {
+            last: Mutex::new(None),
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Compatibility {
+    Compatible,
+    /// Usable, but something may not work: the release differs from the shell's, or the
+    /// backend predates the handshake.
+    Warning,
+    /// Refused: its API revision is outside `supported_api`.
+    Incompatible,
+    /// It didn't answer within the startup timeout.
+    Unreachable,
+}
+
+#[derive(Serialize, Clone)]
+pub struct BackendVersion {
+    pub compatibility: Compatibility,
+    /// The backend's release, when it reported one.
+    pub version: Option<String>,
+    pub api_version: Option<u32>,
+    /// E.g. `1–2`.
+    pub supported_api: String,
+    pub shell_version: String,
+    pub message: Option<String>,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Deserialize)]
+struct VersionBody {
+    version: String,
+    api_version: u32,
+}
+
+enum Answer {
+    Version(VersionBody),
+    /// The endpoint doesn't exist.
+    Missing,
+}
+
+async fn ask(client: &reqwest::Client, base_url: &str) -> Result<Answer, String> {
+    let response = client
+        .get(format!("{base_url}/version"))
+        .send()
+        .await
+        .map_err(|e| format!("backend version request failed: {e}"))?;
+    if response.status().as_u16() == 404 {
+        return Ok(Answer::Missing);
+    }
+    if !response.status().is_success() {
+        return Err(format!(
+            "backend version request failed: HTTP {}",
+            response.status()
+        ));
+    }
+    response
+        .json()
+        .await
+        .map(Answer::Version)
+        .map_err(|e| format!("backend version response invalid: {e}"))
+}
+
+fn assess(answer: Result<Answer, String>) -> BackendVersion {
+    let shell_version = env!("CARGO_PKG_VERSION").to_string();
+    let supported_api = format!("{}–{}", SUPPORTED_API.start(), SUPPORTED_API.end());
+    let mut outcome = BackendVersion {
+        compatibility: Compatibility::Compatible,
+        version: None,
+        api_version: None,
+        supported_api: supported_api.clone(),
+        shell_version: shell_version.clone(),
+        message: None,
+        timestamp_ms: crate::now_ms(),
+    };
+    match answer {
+        Ok(Answer::Version(body)) => {
+            if !SUPPORTED_API.contains(&body.api_version) {
+                outcome.compatibility = Compatibility::Incompatible;
+                outcome.message = Some(format!(
+                    "backend {} speaks API {}, but this app supports API {supported_api}; \
+                     reinstall LiteFetch so the app and its backend match",
+                    body.version, body.api_version
+                ));
+            } else if body.version != shell_version {
+                outcome.compatibility = Compatibility::Warning;
+                outcome.message = Some(format!(
+                    "backend {} differs from app {shell_version}",
+                    body.version
+                ));
+            }
+            outcome.version = Some(body.version);
+            outcome.api_version = Some(body.api_version);
+        }
+        Ok(Answer::Missing) => {
+            outcome.compatibility = Compatibility::Warning;
+            outcome.message =
+                Some("backend predates version checks; some features may fail".to_string());
+        }
+        Err(e) => {
+            outcome.compatibility = Compatibility::Unreachable;
+            outcome.message = Some(e);
+        }
+    }
+    outcome
+}
+
+/// Waits for the backend at `base_url` to answer `/version`, records and announces the
+/// outcome, and fails when the backend is incompatible.
+pub async fn check(app: &tauri::AppHandle, base_url: &str) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(2))
+        .build()
+        .map_err(|e| format!("backend version request failed: {e}"))?;
+    let deadline = Instant::now() + READY_TIMEOUT;
+    let answer = loop {
+        match ask(&client, base_url).await {
+            Err(_) if Instant::now() < deadline => tokio::time::sleep(RETRY_INTERVAL).await,
+            answer => break answer,
+        }
+    };
+    let outcome = assess(answer);
+    if let Some(message) = &outcome.message {
+        tracing::warn!("{message}");
+    }
+    let _ = app.emit("backend://version", outcome.clone());
+    let state = app.state::<HandshakeState>();
+    if let Ok(mut last) = state.last.lock() {
+        *last = Some(outcome);
+    }
+    refusal(app)
+}
+
+/// Fails with the reason when the running backend was found incompatible.
+pub fn refusal(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<HandshakeState>();
+    let last = state
+        .last
+        .lock()
+        .map_err(|_| "handshake lock poisoned".to_string())?;
+    match last.as_ref() {
+        Some(outcome) if outcome.compatibility == Compatibility::Incompatible => Err(outcome
+            .message
+            .clone()
+            .unwrap_or_else(|| "backend is incompatible".to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// The running backend's release, without waiting on the state lock (for the panic hook).
+pub(crate) fn backend_version(app: &tauri::AppHandle) -> Option<String> {
+    let state = app.state::<HandshakeState>();
+    let last = state.last.try_lock().ok()?;
+    last.as_ref().and_then(|outcome| outcome.version.clone())
+}
+
+/// The handshake outcome for the running backend; `None` before it has started.
+#[tauri::command]
+pub async fn get_backend_version(
+    state: State<'_, HandshakeState>,
+) -> Result<Option<BackendVersion>, String> {
+    let last = state
+        .last
+        .lock()
+        .map_err(|_| "handshake lock poisoned".to_string())?;
+    Ok(last.clone())
+}
@@ -0,0 +1,667 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+
+const IMPORTABLE_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+const SKIPPED_HEADERS: &[&str] = &["content-length", "host", "connection", "accept-encoding"];
+
+#[derive(Deserialize, Serialize)]
+pub struct HarFile {
+    pub log: HarLog,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct HarLog {
+    #[serde(default = "default_version")]
+    pub version: String,
+    #[serde(default)]
+    pub creator: HarCreator,
+    #[serde(default)]
+    pub entries: Vec<HarEntry>,
+}
+
+fn default_version() -> String {
+    "1.2".to_string()
+}
+
+#[derive(Deserialize, Serialize, Default)]
+pub struct HarCreator {
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarEntry {
+    #[serde(default)]
+    pub started_date_time: String,
+    #[serde(default)]
+    pub time: f64,
+    pub request: HarRequest,
+    pub response: HarResponse,
+    #[serde(default)]
+    pub timings: HarTimings,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct HarPair {
+    pub name: String,
+    #[serde(default)]
+    pub value: String,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default = "default_http_version")]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<HarPair>,
+    #[serde(default)]
+    pub headers: Vec<HarPair>,
+    #[serde(default)]
+    pub query_string: Vec<HarPair>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<HarPostData>,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+pub(crate) fn default_http_version() -> String {
+    "HTTP/1.1".to_string()
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarPostData {
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<HarParam>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarParam {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarResponse {
+    #[serde(default)]
+    pub status: i64,
+    #[serde(default)]
+    pub status_text: String,
+    #[serde(default = "default_http_version")]
+    pub http_version: String,
+    #[serde(default)]
+    pub cookies: Vec<HarPair>,
+    #[serde(default)]
+    pub headers: Vec<HarPair>,
+    #[serde(default)]
+    pub content: HarContent,
+    #[serde(default, rename = "redirectURL")]
+    pub redirect_url: String,
+    #[serde(default = "unknown_size")]
+    pub headers_size: i64,
+    #[serde(default = "unknown_size")]
+    pub body_size: i64,
+}
+
+#[derive(Deserialize, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    #[serde(default)]
+    pub size: i64,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct HarTimings {
+    #[serde(default = "unknown_timing")]
+    pub blocked: f64,
+    #[serde(default = "unknown_timing")]
+    pub dns: f64,
+    #[serde(default = "unknown_timing")]
+    pub connect: f64,
+    #[serde(default)]
+    pub send: f64,
+    #[serde(default)]
+    pub wait: f64,
+    #[serde(default)]
+    pub receive: f64,
+    #[serde(default = "unknown_timing")]
+    pub ssl: f64,
+}
+
+fn unknown_timing() -> f64 {
+    -1.0
+}
+
+impl Default for HarTimings {
+    fn default() -> Self {
+        Self {
+            blocked: -1.0,
+            dns: -1.0,
+            connect: -1.0,
+            send: 0.0,
+            wait: 0.0,
+            receive: 0.0,
+            ssl: -1.0,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct HarEntryPreview {
+    index: usize,
+    method: String,
+    url: String,
+    host: String,
+    status: i64,
+    started_date_time: String,
+    time_ms: f64,
+    mime_type: String,
+    importable: bool,
+}
+
+#[derive(Serialize)]
+pub struct HarPreview {
+    creator: String,
+    entries: Vec<HarEntryPreview>,
+}
+
+fn load_har(path: &str) -> Result<HarFile, String> {
+    let raw = fs::read_to_string(crate::normalize_path(path))
+        .map_err(|e| format!("HAR read failed: {e}"))?;
+    serde_json::from_str(raw.trim_start_matches('\u{feff}'))
+        .map_err(|e| format!("HAR parse failed: {e}"))
+}
+
+fn host_of(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| "unknown host".to_string())
+}
+
+fn importable(entry: &HarEntry) -> bool {
+    IMPORTABLE_METHODS.contains(&entry.request.method.to_uppercase().as_str())
+        && (entry.request.url.starts_with("http://") || entry.request.url.starts_with("https://"))
+}
+
+/// Converts a HAR request into the backend `HttpRequest` shape.
+fn entry_to_request(entry: &HarEntry, id: &str) -> Value {
+    let request = &entry.request;
+    let mut headers: HashMap<String, String> = HashMap::new();
+    for header in &request.headers {
+        // HTTP/2 pseudo-headers (":authority" etc.) aren't sendable as regular headers.
+        if header.name.starts_with(':')
+            || SKIPPED_HEADERS.contains(&header.name.to_lowercase().as_str())
+        {
+            continue;
+        }
+        headers.insert(header.name.clone(), header.value.clone());
+    }
+    if !request.cookies.is_empty() && !headers.keys().any(|k| k.eq_ignore_ascii_case("cookie")) {
+        let cookie = request
+            .cookies
+            .iter()
+            .map(|c| format!("{}={}", c.name, c.value))
+            .collect::<Vec<_>>()
+            .join("; ");
+        headers.insert("Cookie".to_string(), cookie);
+    }
+
+    let path = reqwest::Url::parse(&request.url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|_| request.url.clone());
+    let mut value = json!({
+        "id": id,
+        "name": format!("{} {path}", request.method.to_uppercase()),
+        "method": request.method.to_uppercase(),
+        "url": request.url,
+        "headers": headers,
+        "body_mode": "raw",
+    });
+    if !request.query_string.is_empty() {
+        value["query_params"] = json!(request
+            .query_string
+            .iter()
+            .map(|q| json!({ "key": q.name, "value": q.value, "enabled": true }))
+            .collect::<Vec<_>>());
+    }
+    if let Some(post) = &request.post_data {
+        let mime = post.mime_type.to_lowercase();
+        let form_mode = if mime.starts_with("application/x-www-form-urlencoded") {
+            Some("form-urlencoded")
+        } else if mime.starts_with("multipart/form-data") {
+            Some("form-data")
+        } else {
+            None
+        };
+        match form_mode {
+            Some(mode) if !post.params.is_empty() => {
+                value["body_mode"] = json!(mode);
+                value["form_body"] = json!(post
+                    .params
+                    .iter()
+                    .map(|p| json!({
+                        "key": p.name,
+                        "value": p.value.clone().unwrap_or_default(),
+                        "type": if p.file_name.is_some() { "file" } else { "text" },
+                        "file_name": p.file_name,
+                        "enabled": true,
+                    }))
+                    .collect::<Vec<_>>());
+                // The form rows define the body; let the engine regenerate Content-Type boundaries.
+                if mode == "form-data" {
+                    if let Some(map) = value["headers"].as_object_mut() {
+                        map.retain(|k, _| !k.eq_ignore_ascii_case("content-type"));
+                    }
+                }
+            }
+            _ => {
+                if mime.contains("json") {
+                    value["body_mode"] = json!("json");
+                }
+                value["body"] = json!(post.text.clone().unwrap_or_default());
+            }
+        }
+    }
+    value
+}
+
+/// Builds a backend `RequestResult` from the recorded response so imported timings are kept.
+fn entry_to_result(entry: &HarEntry, id: &str) -> Value {
+    let response = &entry.response;
+    let text = match (&response.content.text, response.content.encoding.as_deref()) {
+        (Some(text), Some("base64")) => {
+            use base64::Engine;
+            base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .unwrap_or_else(|| text.clone())
+        }
+        (Some(text), _) => text.clone(),
+        (None, _) => String::new(),
+    };
+    let parsed = if response.content.mime_type.contains("json") {
+        serde_json::from_str::<Value>(&text).ok()
+    } else {
+        None
+    };
+    let timestamp = chrono::DateTime::parse_from_rfc3339(&entry.started_date_time)
+        .map(|t| t.timestamp_millis() as f64 / 1000.0)
+        .unwrap_or_else(|_| crate::now_ms() as f64 / 1000.0);
+    json!({
+        "request_id": id,
+        "status_code": response.status,
+        "duration_ms": entry.time,
+        "headers": response
+            .headers
+            .iter()
+            .map(|h| (h.name.to_lowercase(), h.value.clone()))
+            .collect::<HashMap<_, _>>(),
+        "body_is_json": parsed.is_some(),
+        "body": parsed.unwrap_or(Value::String(text.clone())),
+        "content_type": response.content.mime_type,
+        "body_bytes": response.content.size.max(text.len() as i64),
+        "timestamp": timestamp,
+    })
+}
+
+#[tauri::command]
+pub fn preview_har(path: String) -> Result<HarPreview, String> {
+    let har = load_har(&path)?;
+    Ok(HarPreview {
+        creator: format!("{} {}", har.log.creator.name, har.log.creator.version)
+            .trim()
+            .to_string(),
+        entries: har
+            .log
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| HarEntryPreview {
+                index,
+                method: entry.request.method.to_uppercase(),
+                url: entry.request.url.clone(),
+                host: host_of(&entry.request.url),
+                status: entry.response.status,
+                started_date_time: entry.started_date_time.clone(),
+                time_ms: entry.time,
+                mime_type: entry.response.content.mime_type.clone(),
+                importable: importable(entry),
+            })
+            .collect(),
+    })
+}
+
+/// Imports the selected HAR entries (all importable ones when `selection` is omitted)
+/// as a new collection, one folder per host, with the recorded responses seeded as history.
+#[tauri::command]
+pub async fn import_har(
+    app: tauri::AppHandle,
+    path: String,
+    selection: Option<Vec<usize>>,
+    collection_name: Option<String>,
+) -> Result<Value, String> {
+    let har = load_har(&path)?;
+    let mut requests = Vec::new();
+    let mut history = Vec::new();
+    let mut last_results = serde_json::Map::new();
+    for (index, entry) in har.log.entries.iter().enumerate() {
+        if !importable(entry) || selection.as_ref().is_some_and(|s| !s.contains(&index)) {
+            continue;
+        }
+        let id = uuid::Uuid::new_v4().to_string();
+        requests.push((host_of(&entry.request.url), entry_to_request(entry, &id)));
+        let result = entry_to_result(entry, &id);
+        last_results.insert(id, result.clone());
+        history.push(result);
+    }
+    if requests.is_empty() {
+        return Err("no importable HAR entries selected".to_string());
+    }
+
+    let name = collection_name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| {
+            std::path::Path::new(&path)
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| "HAR import".to_string())
+        });
+    let items = crate::group_into_folders(requests);
+    crate::create_collection_with(
+        &app,
+        json!({
+            "name": name,
+            "collection": { "name": name, "items": items },
+            "history": history,
+            "last_results": last_results,
+        }),
+    )
+    .await
+}
+
+#[derive(Deserialize)]
+pub struct HarExportRequest {
+    collection_id: String,
+    /// Limits the export to one request's history.
+    #[serde(default)]
+    request_id: Option<String>,
+    /// Results from a runner session (backend `RequestResult` shape); when set, stored
+    /// history is not consulted.
+    #[serde(default)]
+    results: Option<Vec<Value>>,
+    output_path: String,
+    /// A grant from `allow_secret_export`; without one, secret values are masked.
+    #[serde(default)]
+    include_secrets: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct HarExportSummary {
+    path: String,
+    entries: usize,
+}
+
+fn index_requests<'a>(items: &'a [Value], out: &mut HashMap<String, &'a Value>) {
+    for item in items {
+        match item.get("items").and_then(Value::as_array) {
+            Some(children) => index_requests(children, out),
+            None => {
+                if let Some(id) = item.get("id").and_then(Value::as_str) {
+                    out.insert(id.to_string(), item);
+                }
+            }
+        }
+    }
+}
+
+fn pairs_from(map: Option<&Value>) -> Vec<HarPair> {
+    map.and_then(Value::as_object)
+        .map(|m| {
+            m.iter()
+                .map(|(k, v)| HarPair {
+                    name: k.clone(),
+                    value: v
+                        .as_str()
+                        .map(str::to_string)
+                        .unwrap_or_else(|| v.to_string()),
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn body_text(body: Option<&Value>) -> Option<String> {
+    match body? {
+        Value::Null => None,
+        Value::String(text) => Some(text.clone()),
+        other => Some(other.to_string()),
+    }
+}
+
+fn result_to_entry(result: &Value, request: &Value) -> HarEntry {
+    let duration = result
+        .get("duration_ms")
+        .and_then(Value::as_f64)
+        .unwrap_or(0.0);
+    // RequestResult timestamps are recorded on completion, in seconds.
+    let finished_ms = result
+        .get("timestamp")
+        .and_then(Value::as_f64)
+        .map(|t| t * 1000.0)
+        .unwrap_or(crate::now_ms() as f64);
+    let started = chrono::DateTime::from_timestamp_millis((finished_ms - duration) as i64)
+        .unwrap_or_default()
+        .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+    let url = request
+        .get("url")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let headers = pairs_from(request.get("headers"));
+    let request_mime = headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+        .map(|h| h.value.clone())
+        .unwrap_or_else(|| match request.get("body_mode").and_then(Value::as_str) {
+            Some("json") => "application/json".to_string(),
+            _ => "text/plain".to_string(),
+        });
+    let post_data = body_text(request.get("body")).map(|text| HarPostData {
+        mime_type: request_mime,
+        text: Some(text),
+        params: Vec::new(),
+    });
+    let query_string = reqwest::Url::parse(&url)
+        .map(|u| {
+            u.query_pairs()
+                .map(|(k, v)| HarPair {
+                    name: k.to_string(),
+                    value: v.to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let body_size = post_data
+        .as_ref()
+        .and_then(|p| p.text.as_ref())
+        .map(|t| t.len() as i64)
+        .unwrap_or(0);
+
+    let response_text = body_text(result.get("body"));
+    let response_size = result
+        .get("body_bytes")
+        .and_then(Value::as_i64)
+        .unwrap_or_else(|| response_text.as_ref().map_or(0, |t| t.len() as i64));
+    let mut response_headers = pairs_from(result.get("headers"));
+    if let Some(error) = result.get("error").and_then(Value::as_str) {
+        response_headers.push(HarPair {
+            name: "x-litefetch-error".to_string(),
+            value: error.to_string(),
+        });
+    }
+
+    HarEntry {
+        started_date_time: started,
+        time: duration,
+        request: HarRequest {
+            method: request
+                .get("method")
+                .and_then(Value::as_str)
+                .unwrap_or("GET")
+                .to_string(),
+            url,
+            http_version: default_http_version(),
+            cookies: Vec::new(),
+            headers,
+            query_string,
+            post_data,
+            headers_size: -1,
+            body_size,
+        },
+        response: HarResponse {
+            status: result
+                .get("status_code")
+                .and_then(Value::as_i64)
+                .unwrap_or(0),
+            status_text: String::new(),
+            http_version: default_http_version(),
+            cookies: Vec::new(),
+            headers: response_headers,
+            content: HarContent {
+                size: response_size,
+                mime_type: result
+                    .get("content_type")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                text: response_text,
+                encoding: None,
+            },
+            redirect_url: String::new(),
+            headers_size: -1,
+            body_size: response_size,
+        },
+        // Only total duration is recorded per run, so it is attributed to `wait`.
+        timings: HarTimings {
+            wait: duration,
+            ..HarTimings::default()
+        },
+    }
+}
+
+/// Writes stored history (or the given runner results) as a HAR 1.2 file. Request
+/// fields come from the saved definitions, so `{{variables}}` appear unresolved; secret
+/// values are masked unless `include_secrets` carries a confirmed grant.
+#[tauri::command]
+pub async fn export_har(
+    app: tauri::AppHandle,
+    export: HarExportRequest,
+) -> Result<HarExportSummary, String> {
+    let collection = crate::backend_get(
+        &app,
+        &format!("/collections/{}/collection", export.collection_id),
+    )
+    .await?;
+    let results = match export.results {
+        Some(results) => results,
+        None => crate::backend_get(
+            &app,
+            &format!("/collections/{}/history", export.collection_id),
+        )
+        .await?
+        .as_array()
+        .cloned()
+        .unwrap_or_default(),
+    };
+    let environment = crate::backend_get(
+        &app,
+        &format!("/collections/{}/environment", export.collection_id),
+    )
+    .await?;
+    let redactor = crate::redact::for_export(
+        &app,
+        &export.collection_id,
+        &collection,
+        &environment,
+        export.include_secrets.as_deref(),
+    )
+    .await?;
+
+    let mut requests = HashMap::new();
+    if let Some(items) = collection.get("items").and_then(Value::as_array) {
+        index_requests(items, &mut requests);
+    }
+    let mut entries: Vec<HarEntry> = results
+        .iter()
+        .filter_map(|result| {
+            let id = result.get("request_id").and_then(Value::as_str)?;
+            if export
+                .request_id
+                .as_deref()
+                .is_some_and(|wanted| wanted != id)
+            {
+                return None;
+            }
+            requests
+                .get(id)
+                .map(|request| result_to_entry(result, request))
+        })
+        .collect();
+    if entries.is_empty() {
+        return Err("no executions to export".to_string());
+    }
+    entries.sort_by(|a, b| a.started_date_time.cmp(&b.started_date_time));
+
+    let har = HarFile {
+        log: HarLog {
+            version: default_version(),
+            creator: HarCreator {
+                name: "LiteFetch".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            entries,
+        },
+    };
+    let path = crate::normalize_path(export.output_path.trim());
+    let mut document = serde_json::to_value(&har).map_err(|e| format!("HAR encode failed: {e}"))?;
+    redactor.value(&mut document);
+    let payload =
+        serde_json::to_string_pretty(&document).map_err(|e| format!("HAR encode failed: {e}"))?;
+    fs::write(&path, payload).map_err(|e| format!("HAR write failed: {e}"))?;
+    Ok(HarExportSummary {
+        path: path.to_string_lossy().to_string(),
+        entries: har.log.entries.len(),
+    })
+}
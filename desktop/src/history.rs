@@ -0,0 +1,841 @@
+//! Request history kept by the shell in SQLite (`history.db` in the app data directory), next
+//! to the backend's per-collection history: one row per response sent through `send`, with
+//! its method, URL, status, timing and workspace indexed for `search_history`, which also
+//! matches the correlation ids it was sent with (see `defaults`). Bodies aren't
+//! copied; `response_id` points at the response store (see `responses`) while it has them.
+//!
+//! The history is kept within the limits in `history.json` (see `HistorySettings`): a
+//! background task prunes it hourly, and `purge_history` removes entries on request.
+//! `export_history` writes matching entries to CSV or JSON Lines for spreadsheets and log
+//! tooling. Each entry also keeps its request, as sent and as written, for `replay`.
+
+use base64::Engine;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use serde_json::Map;
+use serde_json::Value;
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::importers::str_of;
+
+const DB_FILE: &str = "history.db";
+const DEFAULT_PAGE: u64 = 50;
+const MAX_PAGE: u64 = 500;
+const SETTINGS_FILE: &str = "history.json";
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Oldest entries removed per round while the database is over its size limit.
+const PRUNE_BATCH: u64 = 1000;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS entries (
+        id INTEGER PRIMARY KEY,
+        timestamp_ms INTEGER NOT NULL,
+        workspace TEXT NOT NULL,
+        collection_id TEXT NOT NULL,
+        request_id TEXT,
+        name TEXT,
+        method TEXT NOT NULL,
+        url TEXT NOT NULL,
+        host TEXT,
+        status_code INTEGER,
+        duration_ms REAL,
+        body_bytes INTEGER,
+        content_type TEXT,
+        error TEXT,
+        response_id TEXT,
+        correlation_ids TEXT,
+        trace_ids TEXT,
+        sent_request TEXT,
+        template TEXT,
+        replay_of INTEGER
+    );
+    CREATE INDEX IF NOT EXISTS entries_time ON entries (workspace, timestamp_ms);
+    CREATE INDEX IF NOT EXISTS entries_url ON entries (url);
+    CREATE INDEX IF NOT EXISTS entries_method ON entries (method);
+    CREATE INDEX IF NOT EXISTS entries_status ON entries (status_code);
+    CREATE INDEX IF NOT EXISTS entries_request ON entries (collection_id, request_id);
+";
+/// Columns added to `entries` after its first release, with their types.
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("correlation_ids", "TEXT"),
+    ("trace_ids", "TEXT"),
+    ("sent_request", "TEXT"),
+    ("template", "TEXT"),
+    ("replay_of", "INTEGER"),
+];
+/// Request bodies larger than this aren't kept for replays.
+const MAX_KEPT_BODY: usize = 256 * 1024;
+
+/// How much history is kept, across workspaces; `None` lifts a limit.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct HistorySettings {
+    pub max_age_days: Option<u64>,
+    pub max_entries: Option<u64>,
+    /// Size of `history.db`, in MiB.
+    pub max_disk_mb: Option<u64>,
+}
+
+impl Default for HistorySettings {
+    fn default() -> Self {
+        Self {
+            max_age_days: Some(90),
+            max_entries: Some(50_000),
+            max_disk_mb: Some(256),
+        }
+    }
+}
+
+impl HistorySettings {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(crate::app_data_root(app)?.join(SETTINGS_FILE))
+    }
+
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data =
+            fs::read_to_string(&path).map_err(|e| format!("history settings read failed: {e}"))?;
+        serde_json::from_str(&data).map_err(|e| format!("history settings parse failed: {e}"))
+    }
+}
+
+#[derive(Serialize)]
+pub struct PruneReport {
+    pub removed: u64,
+    pub remaining: u64,
+}
+
+#[derive(Serialize)]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub timestamp_ms: u64,
+    pub workspace: String,
+    pub collection_id: String,
+    pub request_id: Option<String>,
+    pub name: Option<String>,
+    pub method: String,
+    /// As sent, with known secret values masked.
+    pub url: String,
+    pub host: Option<String>,
+    pub status_code: Option<u16>,
+    pub duration_ms: Option<f64>,
+    pub body_bytes: Option<u64>,
+    pub content_type: Option<String>,
+    pub error: Option<String>,
+    pub response_id: Option<String>,
+    /// Generated ids sent with the request, by header name (see `defaults`).
+    pub correlation_ids: Option<Map<String, Value>>,
+    /// Trace ids the send carried or got back (see `trace`).
+    pub trace_ids: Vec<String>,
+    /// The entry this one replayed (see `replay`).
+    pub replay_of: Option<i64>,
+    /// Whether the request was kept, so the entry can be replayed.
+    pub replayable: bool,
+}
+
+#[derive(Deserialize, Default)]
+pub struct HistoryFilter {
+    #[serde(default)]
+    pub method: Option<String>,
+    /// An exact code (`404`) or a class (`4xx`).
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub collection_id: Option<String>,
+    #[serde(default)]
+    pub request_id: Option<String>,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub since_ms: Option<u64>,
+    #[serde(default)]
+    pub until_ms: Option<u64>,
+    /// Only sends that failed outright (no response) or returned an error status.
+    #[serde(default)]
+    pub errors_only: bool,
+    /// Search every workspace instead of the current one.
+    #[serde(default)]
+    pub all_workspaces: bool,
+    /// Only replays of this entry.
+    #[serde(default)]
+    pub replay_of: Option<i64>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+}
+
+/// Columns of an export, in CSV order; `timestamp` is `timestamp_ms` as RFC 3339.
+const EXPORT_COLUMNS: &[&str] = &[
+    "id",
+    "timestamp",
+    "timestamp_ms",
+    "workspace",
+    "collection_id",
+    "request_id",
+    "name",
+    "method",
+    "url",
+    "host",
+    "status_code",
+    "duration_ms",
+    "body_bytes",
+    "content_type",
+    "error",
+    "response_id",
+    "correlation_ids",
+    "trace_ids",
+];
+/// Added with `include_bodies`: the body as text, or base64 when it isn't UTF-8.
+const BODY_COLUMNS: &[&str] = &["body", "body_base64"];
+
+#[derive(Serialize)]
+pub struct HistoryExport {
+    pub path: String,
+    pub entries: u64,
+}
+
+#[derive(Serialize)]
+pub struct HistoryPage {
+    pub entries: Vec<HistoryEntry>,
+    /// Entries matching the search, across every page.
+    pub total: u64,
+    pub offset: u64,
+    pub limit: u64,
+}
+
+fn db_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(DB_FILE))
+}
+
+pub(crate) fn open(app: &tauri::AppHandle) -> Result<Connection, String> {
+    let conn = Connection::open(db_path(app)?).map_err(|e| format!("history open failed: {e}"))?;
+    conn.execute_batch(&format!("PRAGMA journal_mode = WAL; {SCHEMA}"))
+        .map_err(|e| format!("history init failed: {e}"))?;
+    // Databases from before these columns were recorded.
+    for (column, kind) in ADDED_COLUMNS {
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) > 0 FROM pragma_table_info('entries') WHERE name = ?1",
+                [column],
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("history init failed: {e}"))?;
+        if !exists {
+            conn.execute(
+                &format!("ALTER TABLE entries ADD COLUMN {column} {kind}"),
+                [],
+            )
+            .map_err(|e| format!("history init failed: {e}"))?;
+        }
+    }
+    Ok(conn)
+}
+
+pub(crate) fn workspace_key(app: &tauri::AppHandle) -> Result<String, String> {
+    Ok(crate::load_workspace_path(app)?
+        .to_string_lossy()
+        .to_string())
+}
+
+fn entry_of(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    Ok(HistoryEntry {
+        id: row.get("id")?,
+        timestamp_ms: row.get("timestamp_ms")?,
+        workspace: row.get("workspace")?,
+        collection_id: row.get("collection_id")?,
+        request_id: row.get("request_id")?,
+        name: row.get("name")?,
+        method: row.get("method")?,
+        url: row.get("url")?,
+        host: row.get("host")?,
+        status_code: row.get("status_code")?,
+        duration_ms: row.get("duration_ms")?,
+        body_bytes: row.get("body_bytes")?,
+        content_type: row.get("content_type")?,
+        error: row.get("error")?,
+        response_id: row.get("response_id")?,
+        correlation_ids: row
+            .get::<_, Option<String>>("correlation_ids")?
+            .and_then(|ids| serde_json::from_str(&ids).ok()),
+        trace_ids: row
+            .get::<_, Option<String>>("trace_ids")?
+            .and_then(|ids| serde_json::from_str(&ids).ok())
+            .unwrap_or_default(),
+        replay_of: row.get("replay_of")?,
+        replayable: row.get::<_, Option<String>>("sent_request")?.is_some(),
+    })
+}
+
+/// A request as kept for replays: inline file contents left out, oversized bodies dropped
+/// (`None` then) and known secret values masked.
+fn kept_request(request: &Value) -> Option<String> {
+    let mut request = request.clone();
+    if let Some(binary) = request.get_mut("binary").and_then(Value::as_object_mut) {
+        binary.remove("file_inline");
+    }
+    if let Some(rows) = request.get_mut("form_body").and_then(Value::as_array_mut) {
+        for row in rows.iter_mut().filter_map(Value::as_object_mut) {
+            row.remove("file_inline");
+        }
+    }
+    let text = request.to_string();
+    let body = request.get("body").map(|b| match b {
+        Value::String(text) => text.len(),
+        other => other.to_string().len(),
+    });
+    (body.unwrap_or(0) <= MAX_KEPT_BODY).then(|| crate::redact::log_line(&text))
+}
+
+/// The requests kept with entry `id`: as sent (resolved), and as written before variables
+/// were resolved.
+pub(crate) fn kept_requests(
+    app: &tauri::AppHandle,
+    id: i64,
+) -> Result<(HistoryEntry, Option<Value>, Option<Value>), String> {
+    let conn = open(app)?;
+    conn.query_row("SELECT * FROM entries WHERE id = ?1", [id], |row| {
+        let parse = |text: Option<String>| text.and_then(|t| serde_json::from_str(&t).ok());
+        Ok((
+            entry_of(row)?,
+            parse(row.get("sent_request")?),
+            parse(row.get("template")?),
+        ))
+    })
+    .optional()
+    .map_err(|e| format!("history read failed: {e}"))?
+    .ok_or_else(|| format!("unknown history entry: {id}"))
+}
+
+/// Marks the entry for `response_id` as a replay of entry `original`.
+pub(crate) fn link_replay(
+    app: &tauri::AppHandle,
+    response_id: &str,
+    original: i64,
+) -> Result<Option<HistoryEntry>, String> {
+    let conn = open(app)?;
+    conn.execute(
+        "UPDATE entries SET replay_of = ?1 WHERE response_id = ?2",
+        params![original, response_id],
+    )
+    .map_err(|e| format!("history write failed: {e}"))?;
+    conn.query_row(
+        "SELECT * FROM entries WHERE response_id = ?1",
+        [response_id],
+        entry_of,
+    )
+    .optional()
+    .map_err(|e| format!("history read failed: {e}"))
+}
+
+/// Adds a sent request's result (a backend `RequestResult`) to the history, with the
+/// request as sent and its `template` (the request before variables were resolved).
+pub fn record(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    request: &Value,
+    template: &Value,
+    result: &Value,
+) -> Result<(), String> {
+    let sent = result.get("sent_request").unwrap_or(request);
+    let url = crate::redact::log_line(str_of(sent, "url"));
+    let host = tauri::Url::parse(&url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string));
+    let method = match str_of(sent, "method") {
+        "" => "GET".to_string(),
+        method => method.to_uppercase(),
+    };
+    let timestamp_ms = result
+        .get("timestamp")
+        .and_then(Value::as_f64)
+        .map(|secs| (secs * 1000.0) as u64)
+        .unwrap_or_else(crate::now_ms);
+    let text = |value: &Value, key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+    // Status 0 means the request never got a response.
+    let status_code = result
+        .get("status_code")
+        .and_then(Value::as_u64)
+        .filter(|code| *code > 0);
+
+    let trace_ids = crate::trace::trace_ids(sent, result);
+
+    let conn = open(app)?;
+    conn.execute(
+        "INSERT INTO entries (timestamp_ms, workspace, collection_id, request_id, name, method,
+            url, host, status_code, duration_ms, body_bytes, content_type, error, response_id,
+            correlation_ids, trace_ids, sent_request, template)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17,
+            ?18)",
+        params![
+            timestamp_ms,
+            workspace_key(app)?,
+            collection_id,
+            text(result, "request_id").or_else(|| text(request, "id")),
+            text(request, "name"),
+            method,
+            url,
+            host,
+            status_code,
+            result.get("duration_ms").and_then(Value::as_f64),
+            result.get("body_bytes").and_then(Value::as_u64),
+            text(result, "content_type"),
+            text(result, "error").map(|e| crate::redact::log_line(&e)),
+            text(result, "response_id"),
+            request
+                .get("correlation_ids")
+                .filter(|ids| ids.is_object())
+                .map(Value::to_string),
+            (!trace_ids.is_empty()).then(|| Value::from(trace_ids).to_string()),
+            kept_request(request),
+            kept_request(template),
+        ],
+    )
+    .map_err(|e| format!("history write failed: {e}"))?;
+    Ok(())
+}
+
+/// `query` words as LIKE patterns, with the pattern characters in them escaped.
+pub(crate) fn like_patterns(query: &str) -> Vec<String> {
+    query
+        .split_whitespace()
+        .map(|word| {
+            let escaped = word
+                .replace('\\', "\\\\")
+                .replace('%', "\\%")
+                .replace('_', "\\_");
+            format!("%{escaped}%")
+        })
+        .collect()
+}
+
+/// The inclusive range of codes a status filter matches: an exact code (`404`) or a class
+/// (`4xx`).
+pub(crate) fn status_bounds(status: &str) -> Result<(i64, i64), String> {
+    let class = status
+        .strip_suffix("xx")
+        .or_else(|| status.strip_suffix("XX"));
+    match (class.map(str::parse::<i64>), status.parse::<i64>()) {
+        (Some(Ok(class)), _) => Ok((class * 100, class * 100 + 99)),
+        (None, Ok(code)) => Ok((code, code)),
+        _ => Err(format!("invalid status filter: {status}")),
+    }
+}
+
+/// The WHERE clause (and its parameters) for a search.
+pub(crate) fn conditions(
+    workspace: &str,
+    query: &str,
+    filter: &HistoryFilter,
+) -> Result<(String, Vec<SqlValue>), String> {
+    let mut clauses: Vec<String> = Vec::new();
+    let mut values: Vec<SqlValue> = Vec::new();
+    if !filter.all_workspaces {
+        clauses.push("workspace = ?".to_string());
+        values.push(SqlValue::Text(workspace.to_string()));
+    }
+    for pattern in like_patterns(query) {
+        clauses.push(
+            "(url LIKE ? ESCAPE '\\' OR name LIKE ? ESCAPE '\\' OR method LIKE ? ESCAPE '\\'
+                OR correlation_ids LIKE ? ESCAPE '\\' OR trace_ids LIKE ? ESCAPE '\\')"
+                .to_string(),
+        );
+        values.extend(std::iter::repeat_n(SqlValue::Text(pattern), 5));
+    }
+    let mut exact = |column: &str, value: &Option<String>| {
+        if let Some(value) = value.as_deref().filter(|v| !v.is_empty()) {
+            clauses.push(format!("{column} = ?"));
+            values.push(SqlValue::Text(value.to_string()));
+        }
+    };
+    exact("collection_id", &filter.collection_id);
+    exact("request_id", &filter.request_id);
+    exact("host", &filter.host);
+    exact("method", &filter.method.as_ref().map(|m| m.to_uppercase()));
+    if let Some(status) = filter
+        .status
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        let (low, high) = status_bounds(status)?;
+        clauses.push("status_code BETWEEN ? AND ?".to_string());
+        values.push(SqlValue::Integer(low));
+        values.push(SqlValue::Integer(high));
+    }
+    if let Some(since) = filter.since_ms {
+        clauses.push("timestamp_ms >= ?".to_string());
+        values.push(SqlValue::Integer(since as i64));
+    }
+    if let Some(until) = filter.until_ms {
+        clauses.push("timestamp_ms <= ?".to_string());
+        values.push(SqlValue::Integer(until as i64));
+    }
+    if let Some(original) = filter.replay_of {
+        clauses.push("replay_of = ?".to_string());
+        values.push(SqlValue::Integer(original));
+    }
+    if filter.errors_only {
+        clauses
+            .push("(status_code IS NULL OR status_code >= 400 OR error IS NOT NULL)".to_string());
+    }
+    let clause = match clauses.is_empty() {
+        true => "1 = 1".to_string(),
+        false => clauses.join(" AND "),
+    };
+    Ok((clause, values))
+}
+
+/// History entries matching `query` (words matched against URL, name and method) and
+/// `filters`, newest first, a page at a time: `limit` entries (50 by default, 500 at most)
+/// from `offset`. Searches the current workspace unless `filters.all_workspaces` is set.
+#[tauri::command]
+pub async fn search_history(
+    app: tauri::AppHandle,
+    query: Option<String>,
+    filters: Option<HistoryFilter>,
+    offset: Option<u64>,
+    limit: Option<u64>,
+) -> Result<HistoryPage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = open(&app)?;
+        let filter = filters.unwrap_or_default();
+        let (clause, mut values) = conditions(
+            &workspace_key(&app)?,
+            query.as_deref().unwrap_or(""),
+            &filter,
+        )?;
+        let total: u64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM entries WHERE {clause}"),
+                params_from_iter(values.iter()),
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("history search failed: {e}"))?;
+
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(DEFAULT_PAGE).clamp(1, MAX_PAGE);
+        values.push(SqlValue::Integer(limit as i64));
+        values.push(SqlValue::Integer(offset as i64));
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT * FROM entries WHERE {clause}
+                 ORDER BY timestamp_ms DESC, id DESC LIMIT ? OFFSET ?"
+            ))
+            .map_err(|e| format!("history search failed: {e}"))?;
+        let entries = statement
+            .query_map(params_from_iter(values.iter()), entry_of)
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| format!("history search failed: {e}"))?;
+        Ok(HistoryPage {
+            entries,
+            total,
+            offset,
+            limit,
+        })
+    })
+    .await
+    .map_err(|e| format!("history search failed: {e}"))?
+}
+
+/// One history entry by id.
+#[tauri::command]
+pub async fn get_history_entry(app: tauri::AppHandle, id: i64) -> Result<HistoryEntry, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        open(&app)?
+            .query_row("SELECT * FROM entries WHERE id = ?1", [id], entry_of)
+            .optional()
+            .map_err(|e| format!("history read failed: {e}"))?
+            .ok_or_else(|| format!("unknown history entry: {id}"))
+    })
+    .await
+    .map_err(|e| format!("history read failed: {e}"))?
+}
+
+/// Entries (at most `MAX_PAGE`, newest first) with `trace_id` among their trace ids.
+pub(crate) fn by_trace_id(
+    app: &tauri::AppHandle,
+    trace_id: &str,
+    all_workspaces: bool,
+) -> Result<Vec<HistoryEntry>, String> {
+    let failed = |e: rusqlite::Error| format!("history read failed: {e}");
+    let escaped = trace_id
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    let pattern = format!("%\"{escaped}\"%");
+    let workspace = match all_workspaces {
+        true => None,
+        false => Some(workspace_key(app)?),
+    };
+    let conn = open(app)?;
+    let mut statement = conn
+        .prepare(
+            "SELECT * FROM entries WHERE trace_ids LIKE ?1 ESCAPE '\\'
+                AND (?2 IS NULL OR workspace = ?2)
+             ORDER BY timestamp_ms DESC, id DESC LIMIT ?3",
+        )
+        .map_err(failed)?;
+    let entries = statement
+        .query_map(params![pattern, workspace, MAX_PAGE], entry_of)
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(failed)?;
+    Ok(entries)
+}
+
+/// Bytes of the database in use, free pages left out.
+fn used_bytes(conn: &Connection) -> rusqlite::Result<u64> {
+    let pragma =
+        |name: &str| conn.query_row(&format!("PRAGMA {name}"), [], |row| row.get::<_, u64>(0));
+    Ok(pragma("page_count")?.saturating_sub(pragma("freelist_count")?) * pragma("page_size")?)
+}
+
+/// Applies `settings`: entries past the age limit go first, then the oldest beyond the
+/// entry limit, then the oldest in batches until the database fits the size limit.
+pub(crate) fn prune(
+    app: &tauri::AppHandle,
+    settings: &HistorySettings,
+) -> Result<PruneReport, String> {
+    let conn = open(app)?;
+    let failed = |e: rusqlite::Error| format!("history prune failed: {e}");
+    let mut removed = 0u64;
+    if let Some(days) = settings.max_age_days {
+        let cutoff = crate::now_ms().saturating_sub(days * 24 * 60 * 60 * 1000);
+        removed += conn
+            .execute("DELETE FROM entries WHERE timestamp_ms < ?1", [cutoff])
+            .map_err(failed)? as u64;
+    }
+    if let Some(max) = settings.max_entries {
+        removed += conn
+            .execute(
+                "DELETE FROM entries WHERE id IN (SELECT id FROM entries
+                 ORDER BY timestamp_ms DESC, id DESC LIMIT -1 OFFSET ?1)",
+                [max],
+            )
+            .map_err(failed)? as u64;
+    }
+    if let Some(mb) = settings.max_disk_mb {
+        let limit = mb * 1024 * 1024;
+        let mut shrunk = false;
+        while used_bytes(&conn).map_err(failed)? > limit {
+            let batch = conn
+                .execute(
+                    "DELETE FROM entries WHERE id IN (SELECT id FROM entries
+                     ORDER BY timestamp_ms ASC, id ASC LIMIT ?1)",
+                    [PRUNE_BATCH],
+                )
+                .map_err(failed)? as u64;
+            if batch == 0 {
+                break;
+            }
+            removed += batch;
+            shrunk = true;
+        }
+        // Deleting only frees pages inside the file; give them back to the disk.
+        if shrunk {
+            conn.execute_batch("VACUUM").map_err(failed)?;
+        }
+    }
+    let remaining = conn
+        .query_row("SELECT COUNT(*) FROM entries", [], |row| row.get(0))
+        .map_err(failed)?;
+    Ok(PruneReport { removed, remaining })
+}
+
+/// Prunes the history now and then every hour, for as long as the app runs.
+pub async fn prune_periodically(app: tauri::AppHandle) {
+    let mut ticks = tokio::time::interval(PRUNE_INTERVAL);
+    loop {
+        ticks.tick().await;
+        let app = app.clone();
+        let pruned = tauri::async_runtime::spawn_blocking(move || {
+            prune(&app, &HistorySettings::load(&app)?)
+        })
+        .await;
+        match pruned {
+            Ok(Err(e)) => tracing::warn!("{e}"),
+            Err(e) => tracing::warn!("prune failed: {e}"),
+            Ok(Ok(_)) => {}
+        }
+    }
+}
+
+/// Removes the history entries matching `filters` (every entry of the current workspace
+/// without any) and returns how many went.
+#[tauri::command]
+pub async fn purge_history(
+    app: tauri::AppHandle,
+    filters: Option<HistoryFilter>,
+) -> Result<u64, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let (clause, values) = conditions(&workspace_key(&app)?, "", &filters.unwrap_or_default())?;
+        let removed = open(&app)?
+            .execute(
+                &format!("DELETE FROM entries WHERE {clause}"),
+                params_from_iter(values.iter()),
+            )
+            .map_err(|e| format!("history purge failed: {e}"))?;
+        Ok(removed as u64)
+    })
+    .await
+    .map_err(|e| format!("history purge failed: {e}"))?
+}
+
+#[tauri::command]
+pub async fn get_history_settings(app: tauri::AppHandle) -> Result<HistorySettings, String> {
+    HistorySettings::load(&app)
+}
+
+/// Saves the history limits and applies them straight away.
+#[tauri::command]
+pub async fn set_history_settings(
+    app: tauri::AppHandle,
+    settings: HistorySettings,
+) -> Result<PruneReport, String> {
+    let payload = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("history settings serialize failed: {e}"))?;
+    fs::write(HistorySettings::path(&app)?, payload)
+        .map_err(|e| format!("history settings persist failed: {e}"))?;
+    tauri::async_runtime::spawn_blocking(move || prune(&app, &settings))
+        .await
+        .map_err(|e| format!("history prune failed: {e}"))?
+}
+
+fn export_row(
+    app: &tauri::AppHandle,
+    entry: HistoryEntry,
+    include_bodies: bool,
+) -> Map<String, Value> {
+    let timestamp = chrono::DateTime::from_timestamp_millis(entry.timestamp_ms as i64)
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+    let body = match (include_bodies, entry.response_id.as_deref()) {
+        (true, Some(response_id)) => crate::responses::read_body(app, response_id),
+        _ => None,
+    };
+    let Ok(Value::Object(mut row)) = serde_json::to_value(entry) else {
+        return Map::new();
+    };
+    row.insert("timestamp".to_string(), timestamp.into());
+    if include_bodies {
+        let (text, encoded) = match body.map(String::from_utf8) {
+            Some(Ok(text)) => (Some(text), None),
+            Some(Err(e)) => (
+                None,
+                Some(base64::engine::general_purpose::STANDARD.encode(e.as_bytes())),
+            ),
+            None => (None, None),
+        };
+        row.insert("body".to_string(), text.into());
+        row.insert("body_base64".to_string(), encoded.into());
+    }
+    row
+}
+
+fn csv_field(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => String::new(),
+        Some(Value::String(text)) => text.clone(),
+        Some(other) => other.to_string(),
+    }
+}
+
+/// One CSV record, with its line ending.
+fn csv_line<T: AsRef<[u8]>>(fields: impl IntoIterator<Item = T>) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(fields)
+        .map_err(|e| format!("history export failed: {e}"))?;
+    writer
+        .into_inner()
+        .map_err(|e| format!("history export failed: {e}"))
+}
+
+/// Writes the history entries matching `query` and `filters` (as in `search_history`),
+/// oldest first, to `path` as CSV or JSON Lines; entries go out as they're read, so the
+/// whole history never sits in memory. With `include_bodies`, each carries its response
+/// body while the response store still has it.
+#[tauri::command]
+pub async fn export_history(
+    app: tauri::AppHandle,
+    filters: Option<HistoryFilter>,
+    format: ExportFormat,
+    path: String,
+    query: Option<String>,
+    include_bodies: Option<bool>,
+) -> Result<HistoryExport, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let include_bodies = include_bodies.unwrap_or(false);
+        let conn = open(&app)?;
+        let (clause, values) = conditions(
+            &workspace_key(&app)?,
+            query.as_deref().unwrap_or(""),
+            &filters.unwrap_or_default(),
+        )?;
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT * FROM entries WHERE {clause} ORDER BY timestamp_ms, id"
+            ))
+            .map_err(|e| format!("history export failed: {e}"))?;
+        let mut rows = statement
+            .query(params_from_iter(values.iter()))
+            .map_err(|e| format!("history export failed: {e}"))?;
+
+        let target = crate::normalize_path(&path);
+        let file = fs::File::create(&target).map_err(|e| format!("history export failed: {e}"))?;
+        let mut out = BufWriter::new(file);
+        let columns: Vec<&str> = match include_bodies {
+            true => EXPORT_COLUMNS.iter().chain(BODY_COLUMNS).copied().collect(),
+            false => EXPORT_COLUMNS.to_vec(),
+        };
+        if format == ExportFormat::Csv {
+            out.write_all(&csv_line(&columns)?)
+                .map_err(|e| format!("history export failed: {e}"))?;
+        }
+        let mut entries = 0;
+        while let Some(row) = rows
+            .next()
+            .map_err(|e| format!("history export failed: {e}"))?
+        {
+            let entry = entry_of(row).map_err(|e| format!("history export failed: {e}"))?;
+            let row = export_row(&app, entry, include_bodies);
+            let line = match format {
+                ExportFormat::Csv => {
+                    csv_line(columns.iter().map(|column| csv_field(row.get(*column))))?
+                }
+                ExportFormat::Jsonl => {
+                    let mut line = serde_json::to_vec(&row)
+                        .map_err(|e| format!("history export failed: {e}"))?;
+                    line.push(b'\n');
+                    line
+                }
+            };
+            out.write_all(&line)
+                .map_err(|e| format!("history export failed: {e}"))?;
+            entries += 1;
+        }
+        out.flush()
+            .map_err(|e| format!("history export failed: {e}"))?;
+        Ok(HistoryExport {
+            path: target.to_string_lossy().to_string(),
+            entries,
+        })
+    })
+    .await
+    .map_err(|e| format!("history export failed: {e}"))?
+}
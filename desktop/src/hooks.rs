@@ -0,0 +1,381 @@
+//! Shell command hooks around a send: a request's `hooks.pre` commands run before its
+//! pre-request script (for `aws sso login`, or a script that prints a token) and its
+//! `hooks.post` commands once the response is in, with the response body on stdin. A hook
+//! that names a `variable` has its trimmed stdout saved to it: in the request layer for a
+//! pre hook, so the send can use it as `{{variable}}`, and in the environment for a post hook.
+//!
+//! Commands are run directly, not through a shell, from the workspace folder, with
+//! `{{variable}}` references in their arguments resolved. Each gets a timeout and is killed
+//! when it runs out. Since a workspace can come from someone else's repository, hooks run
+//! only in workspaces the user allowed, and that permission is kept in the app data root
+//! (`hooks.json`), never in the workspace itself.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::importers::str_of;
+use crate::redact::{log_line, MASK};
+use crate::variables::{resolve, Purpose, Scope};
+
+const PERMISSIONS_FILE: &str = "hooks.json";
+const DEFAULT_TIMEOUT_MS: u64 = 30_000;
+/// Interactive logins can take a while; nothing waits longer than this.
+const TIMEOUT_MS: std::ops::RangeInclusive<u64> = 100..=600_000;
+/// Kept of each hook's stdout and stderr.
+const MAX_OUTPUT_BYTES: usize = 1 << 20;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Pre,
+    Post,
+}
+
+impl Phase {
+    fn as_str(self) -> &'static str {
+        match self {
+            Phase::Pre => "pre",
+            Phase::Post => "post",
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+pub struct Hook {
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Saves the trimmed stdout under this name.
+    #[serde(default)]
+    pub variable: Option<String>,
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+    /// Fails the send when the command fails or times out; otherwise it's only reported.
+    #[serde(default = "default_true")]
+    pub required: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A request's `hooks`.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Hooks {
+    pub pre: Vec<Hook>,
+    pub post: Vec<Hook>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct HookRun {
+    pub phase: Phase,
+    pub program: String,
+    /// As run, with secret values masked.
+    pub args: Vec<String>,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub duration_ms: u64,
+    pub variable: Option<String>,
+    /// Masked when it was saved to a variable, which may hold a credential.
+    pub stdout: Option<String>,
+    pub stderr: String,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Permissions {
+    /// Workspace paths the user allowed hooks in, with when they did.
+    allowed: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize)]
+pub struct HookPermission {
+    pub workspace: String,
+    pub allowed: bool,
+    pub allowed_ms: Option<u64>,
+}
+
+fn permissions_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(PERMISSIONS_FILE))
+}
+
+fn load_permissions(app: &tauri::AppHandle) -> Result<Permissions, String> {
+    let path = permissions_path(app)?;
+    if !path.exists() {
+        return Ok(Permissions::default());
+    }
+    let data =
+        fs::read_to_string(&path).map_err(|e| format!("hook permissions read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("hook permissions parse failed: {e}"))
+}
+
+fn permission(app: &tauri::AppHandle) -> Result<HookPermission, String> {
+    let workspace = crate::load_workspace_path(app)?
+        .to_string_lossy()
+        .to_string();
+    let allowed_ms = load_permissions(app)?.allowed.get(&workspace).copied();
+    Ok(HookPermission {
+        workspace,
+        allowed: allowed_ms.is_some(),
+        allowed_ms,
+    })
+}
+
+/// The hooks `request` declares, parsed.
+pub fn of(request: &Value) -> Result<Hooks, String> {
+    match request.get("hooks").filter(|h| h.is_object()) {
+        Some(hooks) => {
+            serde_json::from_value(hooks.clone()).map_err(|e| format!("invalid hooks: {e}"))
+        }
+        None => Ok(Hooks::default()),
+    }
+}
+
+async fn read_capped(mut reader: impl tokio::io::AsyncRead + Unpin) -> Vec<u8> {
+    let mut out = Vec::new();
+    let mut chunk = [0u8; 8192];
+    while let Ok(n) = reader.read(&mut chunk).await {
+        if n == 0 {
+            break;
+        }
+        // Keeps reading past the cap so the command isn't blocked on a full pipe.
+        let room = MAX_OUTPUT_BYTES.saturating_sub(out.len());
+        out.extend_from_slice(&chunk[..n.min(room)]);
+    }
+    out
+}
+
+struct Output {
+    exit_code: Option<i32>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
+
+async fn execute(
+    workspace: &Path,
+    program: &str,
+    args: &[String],
+    env: &[(&str, String)],
+    stdin: Option<Vec<u8>>,
+) -> Result<Output, String> {
+    let mut child = tokio::process::Command::new(program)
+        .args(args)
+        .envs(env.iter().map(|(k, v)| (*k, v.as_str())))
+        .current_dir(workspace)
+        .stdin(match stdin {
+            Some(_) => Stdio::piped(),
+            None => Stdio::null(),
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("{program} failed to start: {e}"))?;
+    if let (Some(mut pipe), Some(input)) = (child.stdin.take(), stdin) {
+        tauri::async_runtime::spawn(async move {
+            // A command that doesn't read its stdin closes the pipe; that's not an error.
+            let _ = pipe.write_all(&input).await;
+        });
+    }
+    let stdout = child.stdout.take().map(read_capped);
+    let stderr = child.stderr.take().map(read_capped);
+    let (stdout, stderr) = tokio::join!(
+        async {
+            match stdout {
+                Some(read) => read.await,
+                None => Vec::new(),
+            }
+        },
+        async {
+            match stderr {
+                Some(read) => read.await,
+                None => Vec::new(),
+            }
+        }
+    );
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("{program} failed: {e}"))?;
+    Ok(Output {
+        exit_code: status.code(),
+        stdout,
+        stderr,
+    })
+}
+
+/// Runs one phase's hooks in order, returning their runs and the variables they set. Fails
+/// when hooks aren't allowed in this workspace or a required hook fails.
+pub async fn run(
+    app: &tauri::AppHandle,
+    phase: Phase,
+    hooks: &[Hook],
+    request: &Value,
+    scope: &Scope,
+    result: Option<&Value>,
+) -> Result<(Vec<HookRun>, Vec<(String, String)>), String> {
+    if hooks.is_empty() {
+        return Ok((Vec::new(), Vec::new()));
+    }
+    if !permission(app)?.allowed {
+        return Err(
+            "this request has shell hooks, which aren't allowed in this workspace; \
+             allow them in the workspace settings to run them"
+                .to_string(),
+        );
+    }
+    let workspace = crate::load_workspace_path(app)?;
+    let mut env = vec![
+        ("LITEFETCH_HOOK_PHASE", phase.as_str().to_string()),
+        ("LITEFETCH_REQUEST_ID", str_of(request, "id").to_string()),
+        (
+            "LITEFETCH_REQUEST_NAME",
+            str_of(request, "name").to_string(),
+        ),
+        (
+            "LITEFETCH_REQUEST_METHOD",
+            str_of(request, "method").to_string(),
+        ),
+    ];
+    let stdin = result.map(|result| {
+        let status = result
+            .get("status_code")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        env.push(("LITEFETCH_RESPONSE_STATUS", status.to_string()));
+        match result.get("body") {
+            Some(Value::String(text)) => text.clone().into_bytes(),
+            Some(Value::Null) | None => Vec::new(),
+            Some(other) => other.to_string().into_bytes(),
+        }
+    });
+
+    let mut runs = Vec::new();
+    let mut sets = Vec::new();
+    for hook in hooks {
+        let program = hook.program.trim().to_string();
+        if program.is_empty() {
+            return Err("a hook needs a program to run".to_string());
+        }
+        let args: Vec<String> = hook
+            .args
+            .iter()
+            .map(|arg| resolve(arg, scope, Purpose::Send, &mut Vec::new()))
+            .collect();
+        let shown_args = hook
+            .args
+            .iter()
+            .map(|arg| {
+                resolve(
+                    arg,
+                    scope,
+                    Purpose::Preview { reveal: false },
+                    &mut Vec::new(),
+                )
+            })
+            .collect();
+        let timeout = Duration::from_millis(
+            hook.timeout_ms
+                .unwrap_or(DEFAULT_TIMEOUT_MS)
+                .clamp(*TIMEOUT_MS.start(), *TIMEOUT_MS.end()),
+        );
+        let variable = hook
+            .variable
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string);
+        let started = Instant::now();
+        let outcome = tokio::time::timeout(
+            timeout,
+            execute(&workspace, &program, &args, &env, stdin.clone()),
+        )
+        .await;
+        let mut run = HookRun {
+            phase,
+            program: program.clone(),
+            args: shown_args,
+            exit_code: None,
+            timed_out: false,
+            duration_ms: started.elapsed().as_millis() as u64,
+            variable: variable.clone(),
+            stdout: None,
+            stderr: String::new(),
+            error: None,
+        };
+        match outcome {
+            Err(_) => {
+                run.timed_out = true;
+                run.error = Some(format!(
+                    "{program} timed out after {} ms",
+                    timeout.as_millis()
+                ));
+            }
+            Ok(Err(e)) => run.error = Some(e),
+            Ok(Ok(output)) => {
+                let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+                run.exit_code = output.exit_code;
+                run.stderr = log_line(String::from_utf8_lossy(&output.stderr).trim());
+                if output.exit_code != Some(0) {
+                    run.error = Some(match output.exit_code {
+                        Some(code) => format!("{program} exited with {code}"),
+                        None => format!("{program} was killed"),
+                    });
+                }
+                match &variable {
+                    Some(name) if run.error.is_none() => {
+                        crate::redact::remember(&stdout);
+                        sets.push((name.clone(), stdout));
+                        run.stdout = Some(MASK.to_string());
+                    }
+                    _ => run.stdout = Some(log_line(&stdout)),
+                }
+            }
+        }
+        let failed = run.error.clone();
+        runs.push(run);
+        if let (Some(e), true) = (failed, hook.required) {
+            return Err(format!("{} hook failed: {e}", phase.as_str()));
+        }
+    }
+    Ok((runs, sets))
+}
+
+/// Whether shell hooks may run in the current workspace.
+#[tauri::command]
+pub async fn get_hook_permission(app: tauri::AppHandle) -> Result<HookPermission, String> {
+    permission(&app)
+}
+
+/// Allows or disallows shell hooks in the current workspace.
+#[tauri::command]
+pub async fn set_hook_permission(
+    app: tauri::AppHandle,
+    allowed: bool,
+) -> Result<HookPermission, String> {
+    let workspace = crate::load_workspace_path(&app)?
+        .to_string_lossy()
+        .to_string();
+    let mut permissions = load_permissions(&app)?;
+    match allowed {
+        true => {
+            permissions.allowed.insert(workspace, crate::now_ms());
+        }
+        false => {
+            permissions.allowed.remove(&workspace);
+        }
+    }
+    let payload = serde_json::to_string_pretty(&permissions)
+        .map_err(|e| format!("hook permissions serialize failed: {e}"))?;
+    fs::write(permissions_path(&app)?, payload)
+        .map_err(|e| format!("hook permissions persist failed: {e}"))?;
+    permission(&app)
+}
@@ -0,0 +1,130 @@
+//! Following links in stored responses (see `responses`): `get_response_links` lists what
+//! `litefetch_core::hypermedia` finds in a response, and `follow_link` turns one into a
+//! request, sent right away when asked. The new request keeps the headers and auth of the
+//! request that produced the response, so an authenticated API stays browsable.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use litefetch_core::hypermedia::{self, Link};
+use litefetch_core::json::{array_of, find_request, str_of};
+
+/// Carried over from the request that produced the response.
+const KEPT_FIELDS: &[&str] = &[
+    "headers",
+    "secret_headers",
+    "auth_type",
+    "auth_params",
+    "secret_auth_params",
+    "timeout_seconds",
+    "verify_ssl",
+    "skip_defaults",
+    "skip_interceptors",
+];
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct FollowOptions {
+    /// Which link, when the response has several with the same rel.
+    pub index: usize,
+    /// Values for a templated link's variables.
+    pub variables: Map<String, Value>,
+    pub send: bool,
+    pub environment_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct FollowedLink {
+    pub link: Link,
+    /// An unsaved request for the link, to open in a tab.
+    pub request: Value,
+    /// The send's result, when `send` was set.
+    pub result: Option<Value>,
+}
+
+/// A stored response as the `RequestResult` it came from, body included.
+fn stored_result(app: &tauri::AppHandle, response_id: &str) -> Result<Value, String> {
+    let mut result = crate::responses::read_meta(app, response_id)?;
+    let body = crate::responses::read_body(app, response_id).unwrap_or_default();
+    result["body"] = Value::String(String::from_utf8_lossy(&body).into_owned());
+    Ok(result)
+}
+
+/// Every link in a stored response, in the order found: HAL, then OData, then `Link`
+/// headers.
+#[tauri::command]
+pub async fn get_response_links(
+    app: tauri::AppHandle,
+    response_id: String,
+) -> Result<Vec<Link>, String> {
+    Ok(hypermedia::links(&stored_result(&app, &response_id)?))
+}
+
+/// Builds a `GET` for the response's `rel` link (the `index`th of them) with the original
+/// request's headers and auth, and sends it when `send` is set.
+#[tauri::command]
+pub async fn follow_link(
+    app: tauri::AppHandle,
+    collection_id: String,
+    response_id: String,
+    rel: String,
+    options: Option<FollowOptions>,
+) -> Result<FollowedLink, String> {
+    let options = options.unwrap_or_default();
+    let stored = stored_result(&app, &response_id)?;
+    let link = hypermedia::links(&stored)
+        .into_iter()
+        .filter(|link| link.rel == rel)
+        .nth(options.index)
+        .ok_or_else(|| format!("the response has no \"{rel}\" link"))?;
+    let base = stored
+        .pointer("/sent_request/url")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let url = hypermedia::expand_link(&link, &options.variables, base);
+
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let original = find_request(
+        array_of(&collection, "items"),
+        str_of(&stored, "request_id"),
+    );
+    let mut request = json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "name": link.title.clone().unwrap_or_else(|| rel.clone()),
+        "method": "GET",
+        "url": url,
+        "headers": {},
+        "query_params": [],
+        "body_mode": "raw",
+        "body": null,
+    });
+    if let Some(original) = original {
+        for field in KEPT_FIELDS {
+            if let Some(value) = original.get(*field) {
+                request[*field] = value.clone();
+            }
+        }
+    }
+
+    let result = match options.send {
+        true => {
+            let mut result = crate::send::dispatch(
+                app.clone(),
+                collection_id,
+                request.clone(),
+                options.environment_id,
+            )
+            .await?
+            .result;
+            crate::responses::limit(&app, &mut result);
+            Some(result)
+        }
+        false => None,
+    };
+    Ok(FollowedLink {
+        link,
+        request,
+        result,
+    })
+}
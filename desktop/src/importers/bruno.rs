@@ -0,0 +1,358 @@
+//! Bruno file-based collections: import reads a collection directory with
+//! `litefetch_core::importers::bruno`; export writes a collection back out in that layout.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use litefetch_core::importers::bruno::read_collection;
+
+use super::{file_stem, str_of, unique, value_text, ImportReport, ImportResult};
+
+#[tauri::command]
+pub async fn import_bruno_collection(
+    app: tauri::AppHandle,
+    path: String,
+    collection_name: Option<String>,
+) -> Result<ImportResult, String> {
+    let mut root = crate::normalize_path(&path);
+    // Accept the collection directory or its bruno.json.
+    if root.file_name().is_some_and(|n| n == "bruno.json") {
+        root.pop();
+    }
+    let mut report = ImportReport::new("bruno");
+    let mut imported = read_collection(&root, &mut report)?;
+    if let Some(name) = collection_name.filter(|n| !n.trim().is_empty()) {
+        imported.name = name;
+    }
+    super::save(&app, imported, report).await
+}
+
+// --- Export ---
+
+fn dict_block(out: &mut String, name: &str, rows: &[(String, String, bool)]) {
+    if rows.is_empty() {
+        return;
+    }
+    out.push_str(&format!("\n{name} {{\n"));
+    for (key, value, enabled) in rows {
+        let prefix = if *enabled { "" } else { "~" };
+        // Dict values are single-line in the .bru grammar.
+        out.push_str(&format!("  {prefix}{key}: {}\n", value.replace('\n', " ")));
+    }
+    out.push_str("}\n");
+}
+
+fn text_block(out: &mut String, name: &str, text: &str) {
+    out.push_str(&format!("\n{name} {{\n"));
+    for line in text.lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            out.push_str(&format!("  {line}\n"));
+        }
+    }
+    out.push_str("}\n");
+}
+
+fn meta_block(out: &mut String, name: &str, kind: Option<&str>, seq: usize) {
+    out.push_str(&format!("meta {{\n  name: {name}\n"));
+    if let Some(kind) = kind {
+        out.push_str(&format!("  type: {kind}\n"));
+    }
+    out.push_str(&format!("  seq: {seq}\n}}\n"));
+}
+
+fn form_rows_of(request: &Value, key: &str) -> Vec<(String, String, bool)> {
+    request
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|rows| {
+            rows.iter()
+                .map(|row| {
+                    let value = match str_of(row, "type") {
+                        "file" | "binary" => format!("@file({})", str_of(row, "file_path")),
+                        _ => value_text(row.get("value")),
+                    };
+                    let enabled = row.get("enabled").and_then(Value::as_bool).unwrap_or(true);
+                    (str_of(row, "key").to_string(), value, enabled)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn write_request(request: &Value, seq: usize) -> String {
+    let method = str_of(request, "method").to_lowercase();
+    let method = if method.is_empty() {
+        "get".to_string()
+    } else {
+        method
+    };
+    let query = form_rows_of(request, "query_params");
+    let mut url = str_of(request, "url").to_string();
+    // LiteFetch's query rows replace the URL query; Bruno expects the URL to carry them.
+    if !query.is_empty() {
+        url.truncate(url.find('?').unwrap_or(url.len()));
+        let enabled: Vec<String> = query
+            .iter()
+            .filter(|(_, _, enabled)| *enabled)
+            .map(|(k, v, _)| {
+                if v.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{k}={v}")
+                }
+            })
+            .collect();
+        if !enabled.is_empty() {
+            url = format!("{url}?{}", enabled.join("&"));
+        }
+    }
+    let body_mode = match str_of(request, "body_mode") {
+        "json" => "json",
+        "form-urlencoded" => "formUrlEncoded",
+        "form-data" => "multipartForm",
+        "binary" => "file",
+        _ if request.get("body").is_some_and(|b| !b.is_null() && b != "") => "text",
+        _ => "none",
+    };
+    let auth_mode = match str_of(request, "auth_type") {
+        "basic" => "basic",
+        "bearer" => "bearer",
+        _ => "none",
+    };
+
+    let mut out = String::new();
+    meta_block(&mut out, str_of(request, "name"), Some("http"), seq);
+    out.push_str(&format!(
+        "\n{method} {{\n  url: {url}\n  body: {body_mode}\n  auth: {auth_mode}\n}}\n"
+    ));
+    dict_block(&mut out, "params:query", &query);
+    let headers: Vec<(String, String, bool)> = request
+        .get("headers")
+        .and_then(Value::as_object)
+        .map(|h| {
+            h.iter()
+                .map(|(k, v)| (k.clone(), value_text(Some(v)), true))
+                .collect()
+        })
+        .unwrap_or_default();
+    dict_block(&mut out, "headers", &headers);
+    let params = request.get("auth_params").cloned().unwrap_or(Value::Null);
+    match auth_mode {
+        "basic" => dict_block(
+            &mut out,
+            "auth:basic",
+            &[
+                (
+                    "username".to_string(),
+                    str_of(&params, "username").to_string(),
+                    true,
+                ),
+                (
+                    "password".to_string(),
+                    str_of(&params, "password").to_string(),
+                    true,
+                ),
+            ],
+        ),
+        "bearer" => dict_block(
+            &mut out,
+            "auth:bearer",
+            &[(
+                "token".to_string(),
+                str_of(&params, "token").to_string(),
+                true,
+            )],
+        ),
+        _ => {}
+    }
+    let body_text = match request.get("body") {
+        Some(Value::String(text)) => text.clone(),
+        Some(Value::Null) | None => String::new(),
+        Some(other) => serde_json::to_string_pretty(other).unwrap_or_default(),
+    };
+    match body_mode {
+        "json" => text_block(&mut out, "body:json", &body_text),
+        "text" => text_block(&mut out, "body:text", &body_text),
+        "formUrlEncoded" => dict_block(
+            &mut out,
+            "body:form-urlencoded",
+            &form_rows_of(request, "form_body"),
+        ),
+        "multipartForm" => dict_block(
+            &mut out,
+            "body:multipart-form",
+            &form_rows_of(request, "form_body"),
+        ),
+        "file" => {
+            let path = request
+                .get("binary")
+                .map(|b| str_of(b, "file_path").to_string())
+                .unwrap_or_default();
+            dict_block(
+                &mut out,
+                "body:file",
+                &[("file".to_string(), format!("@file({path})"), true)],
+            );
+        }
+        _ => {}
+    }
+    let rules: Vec<(String, String, bool)> = request
+        .get("extract_rules")
+        .and_then(Value::as_array)
+        .map(|rules| {
+            rules
+                .iter()
+                .map(|r| {
+                    let path = str_of(r, "source_path");
+                    let path = path
+                        .strip_prefix("body.")
+                        .unwrap_or(path)
+                        .trim_start_matches("$.");
+                    (
+                        str_of(r, "target_variable").to_string(),
+                        format!("res.body.{path}"),
+                        true,
+                    )
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    dict_block(&mut out, "vars:post-response", &rules);
+    let docs = str_of(request, "description").trim();
+    if !docs.is_empty() {
+        text_block(&mut out, "docs", docs);
+    }
+    out
+}
+
+fn write_items(dir: &Path, items: &[Value], written: &mut usize) -> Result<(), String> {
+    let mut taken = Vec::new();
+    for (index, item) in items.iter().enumerate() {
+        let name = str_of(item, "name");
+        if let Some(children) = item.get("items").and_then(Value::as_array) {
+            let folder_dir = dir.join(unique(&mut taken, file_stem(name)));
+            fs::create_dir_all(&folder_dir).map_err(|e| format!("Bruno export failed: {e}"))?;
+            let mut meta = String::new();
+            meta_block(&mut meta, name, None, index + 1);
+            fs::write(folder_dir.join("folder.bru"), meta)
+                .map_err(|e| format!("Bruno export failed: {e}"))?;
+            write_items(&folder_dir, children, written)?;
+        } else {
+            let file = format!("{}.bru", unique(&mut taken, file_stem(name)));
+            fs::write(dir.join(file), write_request(item, index + 1))
+                .map_err(|e| format!("Bruno export failed: {e}"))?;
+            *written += 1;
+        }
+    }
+    Ok(())
+}
+
+fn write_environments(root: &Path, environment: &Value) -> Result<usize, String> {
+    let Some(envs) = environment.get("envs").and_then(Value::as_object) else {
+        return Ok(0);
+    };
+    let dir = root.join("environments");
+    fs::create_dir_all(&dir).map_err(|e| format!("Bruno export failed: {e}"))?;
+    let empty = Map::new();
+    for (name, env) in envs {
+        let secrets = env
+            .get("secrets")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let is_secret = |key: &str| secrets.get(key).and_then(Value::as_bool).unwrap_or(false);
+        let variables = env
+            .get("variables")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let rows: Vec<(String, String, bool)> = variables
+            .iter()
+            .filter(|(key, _)| !is_secret(key))
+            .map(|(key, value)| (key.clone(), value_text(Some(value)), true))
+            .collect();
+        let mut out = String::new();
+        dict_block(&mut out, "vars", &rows);
+        // Secret values stay out of the files, as Bruno does; only their names are listed.
+        let secret_keys: Vec<&String> = secrets.keys().filter(|k| is_secret(k)).collect();
+        if !secret_keys.is_empty() {
+            out.push_str("\nvars:secret [\n");
+            let listed: Vec<String> = secret_keys.iter().map(|k| format!("  {k}")).collect();
+            out.push_str(&listed.join(",\n"));
+            out.push_str("\n]\n");
+        }
+        fs::write(
+            dir.join(format!("{}.bru", file_stem(name))),
+            out.trim_start(),
+        )
+        .map_err(|e| format!("Bruno export failed: {e}"))?;
+    }
+    Ok(envs.len())
+}
+
+#[derive(Serialize)]
+pub struct BrunoExportSummary {
+    pub path: String,
+    pub requests: usize,
+    pub environments: usize,
+}
+
+/// Writes a collection as a Bruno collection directory under `output_dir`. Request fields
+/// marked secret are masked unless `include_secrets` carries a confirmed grant.
+#[tauri::command]
+pub async fn export_bruno_collection(
+    app: tauri::AppHandle,
+    collection_id: String,
+    output_dir: String,
+    include_secrets: Option<String>,
+) -> Result<BrunoExportSummary, String> {
+    let mut collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    crate::redact::for_export(
+        &app,
+        &collection_id,
+        &collection,
+        &environment,
+        include_secrets.as_deref(),
+    )
+    .await?
+    .value(&mut collection);
+    let name = str_of(&collection, "name");
+    let root: PathBuf = crate::normalize_path(output_dir.trim()).join(file_stem(name));
+    if root.join("bruno.json").exists() || root.read_dir().is_ok_and(|mut d| d.next().is_some()) {
+        return Err(format!(
+            "{} already exists and is not empty",
+            root.display()
+        ));
+    }
+    fs::create_dir_all(&root).map_err(|e| format!("Bruno export failed: {e}"))?;
+    let manifest = json!({
+        "version": "1",
+        "name": name,
+        "type": "collection",
+        "ignore": ["node_modules", ".git"],
+    });
+    fs::write(
+        root.join("bruno.json"),
+        serde_json::to_string_pretty(&manifest).map_err(|e| format!("Bruno export failed: {e}"))?,
+    )
+    .map_err(|e| format!("Bruno export failed: {e}"))?;
+
+    let mut requests = 0;
+    let items = collection
+        .get("items")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    write_items(&root, &items, &mut requests)?;
+    let environments = write_environments(&root, &environment)?;
+    Ok(BrunoExportSummary {
+        path: root.to_string_lossy().to_string(),
+        requests,
+        environments,
+    })
+}
@@ -0,0 +1,587 @@
+//! Parses a pasted curl command into a request. Covers the options API docs actually use;
+//! anything else is listed in the report rather than silently dropped. Shell scripts full of
+//! curl commands import as a collection (see `import_scripts`).
+
+use base64::Engine;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::{
+    FormRow, ImportReport, ImportResult, ImportedAuth, ImportedBody, ImportedCollection,
+    ImportedItem, ImportedRequest,
+};
+
+/// Options that take a value but don't affect the request LiteFetch builds.
+const IGNORED_WITH_VALUE: &[&str] = &[
+    "-o",
+    "--output",
+    "-m",
+    "--max-time",
+    "--connect-timeout",
+    "--retry",
+    "-w",
+    "--write-out",
+    "--cacert",
+    "--cert",
+    "--key",
+    "-x",
+    "--proxy",
+    "--resolve",
+    "-c",
+    "--cookie-jar",
+    "--max-redirs",
+];
+
+/// Splits a shell command line the way a POSIX shell would for the common quoting forms:
+/// single quotes, double quotes with backslash escapes, `$'...'` strings and `\` (or a
+/// Windows `^`) before a newline as a line continuation.
+pub fn tokenize(text: &str) -> Result<Vec<String>, String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' | '^' if matches!(chars.peek(), Some('\n') | Some('\r')) => {
+                while matches!(chars.peek(), Some('\n') | Some('\r')) {
+                    chars.next();
+                }
+            }
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                    in_token = true;
+                }
+            }
+            '\'' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(ch) => current.push(ch),
+                        None => return Err("unterminated single quote".to_string()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some(ch) => current.push(ch),
+                            None => return Err("unterminated $'' string".to_string()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err("unterminated $'' string".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(ch @ ('"' | '\\' | '$' | '`')) => current.push(ch),
+                            Some('\n') => {}
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => return Err("unterminated double quote".to_string()),
+                        },
+                        Some(ch) => current.push(ch),
+                        None => return Err("unterminated double quote".to_string()),
+                    }
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+    if in_token {
+        tokens.push(current);
+    }
+    Ok(tokens)
+}
+
+fn split_pair(value: &str, separator: char) -> (String, String) {
+    match value.split_once(separator) {
+        Some((k, v)) => (k.trim().to_string(), v.trim_start().to_string()),
+        None => (value.trim().to_string(), String::new()),
+    }
+}
+
+enum Data {
+    Raw(String),
+    File(String),
+    UrlEncode(String),
+}
+
+/// A `--data-urlencode` value as curl sends it: `name=content` with only the content
+/// encoded, `=content` and `content` as the encoded content alone.
+fn url_encoded(value: &str) -> String {
+    match value.split_once('=') {
+        Some(("", content)) => crate::codegen::encode_component(content),
+        Some((name, content)) => format!("{name}={}", crate::codegen::encode_component(content)),
+        None => crate::codegen::encode_component(value),
+    }
+}
+
+/// Parses the first curl command in `text` into a request.
+pub fn parse(text: &str, report: &mut ImportReport) -> Result<ImportedRequest, String> {
+    let text = text.trim().trim_start_matches("$ ");
+    let tokens = tokenize(text)?;
+    let start = tokens
+        .iter()
+        .position(|t| t == "curl" || t.ends_with("/curl") || t.eq_ignore_ascii_case("curl.exe"))
+        .ok_or("not a curl command")?;
+
+    let mut method: Option<String> = None;
+    let mut url: Option<String> = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut data: Vec<Data> = Vec::new();
+    let mut form: Vec<FormRow> = Vec::new();
+    let mut user: Option<String> = None;
+    let mut bearer: Option<String> = None;
+    let mut get = false;
+    let mut head = false;
+    let mut json = false;
+
+    let mut args = tokens[start + 1..].iter().cloned();
+    while let Some(arg) = args.next() {
+        // `-XPOST` / `-HAccept: x` carry their value in the same token.
+        // `get` keeps a flag followed by a multi-byte character from being cut inside it.
+        let split = arg.get(..2).zip(arg.get(2..));
+        let (flag, attached) = if arg.starts_with('-') && !arg.starts_with("--") && arg.len() > 2 {
+            match split {
+                Some((
+                    short @ ("-X" | "-H" | "-d" | "-F" | "-u" | "-A" | "-e" | "-b" | "-o" | "-m"
+                    | "-x"),
+                    rest,
+                )) => (short.to_string(), Some(rest.to_string())),
+                // Bundled boolean flags such as `-sSL` or `-kG`.
+                _ => {
+                    for ch in arg[1..].chars() {
+                        match ch {
+                            'G' => get = true,
+                            'I' => head = true,
+                            'k' | 's' | 'S' | 'L' | 'v' | 'i' | 'f' => {}
+                            other => report.unsupported(format!("curl flag -{other} is ignored")),
+                        }
+                    }
+                    continue;
+                }
+            }
+        } else {
+            (arg.clone(), None)
+        };
+        let mut value = || -> Result<String, String> {
+            attached
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{flag} needs a value"))
+        };
+        match flag.as_str() {
+            "-X" | "--request" => method = Some(value()?.to_uppercase()),
+            "--url" => url = Some(value()?),
+            "-H" | "--header" => {
+                let (key, val) = split_pair(&value()?, ':');
+                if !key.is_empty() {
+                    headers.push((key, val));
+                }
+            }
+            "-d" | "--data" | "--data-ascii" | "--data-binary" => {
+                let v = value()?;
+                match v.strip_prefix('@') {
+                    Some(path) => data.push(Data::File(path.to_string())),
+                    _ => data.push(Data::Raw(v)),
+                }
+            }
+            "--data-raw" => data.push(Data::Raw(value()?)),
+            "--data-urlencode" => data.push(Data::UrlEncode(value()?)),
+            "--json" => {
+                json = true;
+                let v = value()?;
+                match v.strip_prefix('@') {
+                    Some(path) => data.push(Data::File(path.to_string())),
+                    None => data.push(Data::Raw(v)),
+                }
+            }
+            "-F" | "--form" | "--form-string" => {
+                let v = value()?;
+                let (key, val) = split_pair(&v, '=');
+                // `name=@path;type=...` uploads a file; `<path` reads a file as text.
+                // `--form-string` takes the value as it is.
+                let literal = flag == "--form-string";
+                let val = match literal {
+                    true => val,
+                    false => val.split(";type=").next().unwrap_or_default().to_string(),
+                };
+                if let Some(path) = val.strip_prefix('@').filter(|_| !literal) {
+                    form.push(FormRow {
+                        key,
+                        value: path.to_string(),
+                        enabled: true,
+                        file_path: Some(path.to_string()),
+                    });
+                } else if let Some(path) = val.strip_prefix('<').filter(|_| !literal) {
+                    // Parsing runs on anything copied or imported, so it never reads files:
+                    // the command could name any file the user can read.
+                    report.unsupported(format!(
+                        "form field {key}=<{path} is not imported; the file is not read"
+                    ));
+                } else {
+                    form.push(FormRow::text(key, val, true));
+                }
+            }
+            "-u" | "--user" => user = Some(value()?),
+            "--oauth2-bearer" => bearer = Some(value()?),
+            "-A" | "--user-agent" => headers.push(("User-Agent".to_string(), value()?)),
+            "-e" | "--referer" => headers.push(("Referer".to_string(), value()?)),
+            "-b" | "--cookie" => {
+                let v = value()?;
+                if v.contains('=') {
+                    headers.push(("Cookie".to_string(), v));
+                } else {
+                    report.unsupported("cookie files (-b <file>) are not imported");
+                }
+            }
+            "-G" | "--get" => get = true,
+            "-I" | "--head" => head = true,
+            "-k" | "--insecure" | "-s" | "--silent" | "-S" | "--show-error" | "-L"
+            | "--location" | "-v" | "--verbose" | "-i" | "--include" | "--compressed" | "-f"
+            | "--fail" => {}
+            f if IGNORED_WITH_VALUE.contains(&f) => {
+                value()?;
+                report.unsupported(format!("curl option {f} is ignored"));
+            }
+            f if f.starts_with('-') => report.unsupported(format!("curl option {f} is ignored")),
+            _ if url.is_none() => url = Some(arg),
+            _ => report.unsupported("only the first URL of a curl command is imported"),
+        }
+    }
+
+    let mut url = url.ok_or("curl command has no URL")?;
+    if !url.contains("://") {
+        url = format!("http://{url}");
+    }
+
+    // `-G` appends data to the query string instead of sending a body.
+    if get && !data.is_empty() {
+        let query: Vec<String> = data
+            .drain(..)
+            .filter_map(|d| match d {
+                Data::Raw(v) => Some(v),
+                Data::UrlEncode(v) => Some(url_encoded(&v)),
+                Data::File(_) => None,
+            })
+            .collect();
+        let separator = if url.contains('?') { '&' } else { '?' };
+        url = format!("{url}{separator}{}", query.join("&"));
+    }
+
+    let has_body = !data.is_empty() || !form.is_empty();
+    let method = method.unwrap_or_else(|| {
+        if head {
+            "HEAD"
+        } else if has_body && !get {
+            "POST"
+        } else {
+            "GET"
+        }
+        .to_string()
+    });
+    let mut request = ImportedRequest::new("Imported curl request", method, url.clone());
+    request.name = url
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split_once('/').map(|(_, path)| path))
+        .map(|path| format!("/{}", path.split('?').next().unwrap_or_default()))
+        .filter(|path| path != "/")
+        .unwrap_or(url);
+
+    if json {
+        if !headers
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        {
+            headers.push(("Content-Type".to_string(), "application/json".to_string()));
+        }
+        if !headers
+            .iter()
+            .any(|(k, _)| k.eq_ignore_ascii_case("accept"))
+        {
+            headers.push(("Accept".to_string(), "application/json".to_string()));
+        }
+    }
+    let content_type = headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("content-type"))
+        .map(|(_, v)| v.to_lowercase())
+        .unwrap_or_default();
+
+    request.body = if !form.is_empty() {
+        // The multipart boundary is generated at send time, so a copied header would be wrong.
+        headers.retain(|(k, _)| !k.eq_ignore_ascii_case("content-type"));
+        ImportedBody::FormData(form)
+    } else if let [Data::File(path)] = data.as_slice() {
+        ImportedBody::Binary {
+            file_path: path.clone(),
+        }
+    } else if data.iter().any(|d| matches!(d, Data::UrlEncode(_)))
+        || (content_type.is_empty()
+            && !json
+            && !data.is_empty()
+            && data.iter().all(|d| match d {
+                Data::Raw(v) => !v.trim_start().starts_with(['{', '[']) && v.contains('='),
+                _ => false,
+            }))
+        || content_type.starts_with("application/x-www-form-urlencoded")
+    {
+        let mut rows = Vec::new();
+        for d in &data {
+            match d {
+                Data::Raw(v) => {
+                    for pair in v.split('&').filter(|p| !p.is_empty()) {
+                        let (k, val) = split_pair(pair, '=');
+                        rows.push(FormRow::text(k, val, true));
+                    }
+                }
+                // One field, whose value may hold `&` and `=`; it's encoded at send time.
+                Data::UrlEncode(v) => {
+                    let (k, val) = v.split_once('=').unwrap_or(("", v.as_str()));
+                    rows.push(FormRow::text(k.to_string(), val.to_string(), true));
+                }
+                Data::File(path) => {
+                    report.unsupported(format!("data file @{path} is not imported"))
+                }
+            }
+        }
+        headers.retain(|(k, _)| !k.eq_ignore_ascii_case("content-type"));
+        ImportedBody::UrlEncoded(rows)
+    } else if !data.is_empty() {
+        let text = data
+            .iter()
+            .filter_map(|d| match d {
+                Data::Raw(v) => Some(v.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("&");
+        if data.iter().any(|d| matches!(d, Data::File(_))) {
+            report.unsupported("mixed inline and @file data keeps only the inline parts");
+        }
+        let looks_json = serde_json::from_str::<Value>(&text).is_ok();
+        ImportedBody::Raw {
+            json: json || content_type.contains("json") || (content_type.is_empty() && looks_json),
+            text,
+        }
+    } else {
+        ImportedBody::None
+    };
+
+    // Authorization headers map onto LiteFetch auth so the editor shows them.
+    if let Some(index) = headers
+        .iter()
+        .position(|(k, _)| k.eq_ignore_ascii_case("authorization"))
+    {
+        let value = headers[index].1.clone();
+        if let Some(token) = value
+            .strip_prefix("Bearer ")
+            .or_else(|| value.strip_prefix("bearer "))
+        {
+            request.auth = ImportedAuth::Bearer {
+                token: token.trim().to_string(),
+            };
+            headers.remove(index);
+        } else if let Some(encoded) = value
+            .strip_prefix("Basic ")
+            .or_else(|| value.strip_prefix("basic "))
+        {
+            let decoded = base64::engine::general_purpose::STANDARD
+                .decode(encoded.trim())
+                .ok()
+                .and_then(|b| String::from_utf8(b).ok());
+            if let Some((username, password)) = decoded.as_deref().and_then(|d| d.split_once(':')) {
+                request.auth = ImportedAuth::Basic {
+                    username: username.to_string(),
+                    password: password.to_string(),
+                };
+                headers.remove(index);
+            }
+        }
+    }
+    if let Some(user) = user {
+        let (username, password) = split_pair(&user, ':');
+        request.auth = ImportedAuth::Basic { username, password };
+    }
+    if let Some(token) = bearer {
+        request.auth = ImportedAuth::Bearer { token };
+    }
+    request.headers = headers;
+    Ok(request)
+}
+
+#[derive(Serialize)]
+pub struct CurlImport {
+    /// A request in the collection's `HttpRequest` shape, ready to add and send.
+    pub request: Value,
+    pub report: ImportReport,
+}
+
+/// Whether `text` is plausibly a single pasted curl command, without tokenizing it.
+pub fn looks_like_curl(text: &str) -> bool {
+    let text = text.trim_start().trim_start_matches("$ ");
+    text.len() < 256 * 1024
+        && (text.starts_with("curl ") || text.starts_with("curl.exe "))
+        && text.contains("://")
+}
+
+pub fn convert(text: &str) -> Result<CurlImport, String> {
+    let mut report = ImportReport::new("curl");
+    let parsed = parse(text, &mut report)?;
+    let request = super::request_json(&parsed, &mut report);
+    Ok(CurlImport { request, report })
+}
+
+#[tauri::command]
+pub async fn import_curl(text: String) -> Result<CurlImport, String> {
+    convert(&text)
+}
+
+/// The curl commands in a shell script, continuation lines and multi-line quoted bodies
+/// included; everything else in the script (comments, `export`s, other commands) is ignored.
+pub fn script_commands(text: &str) -> Vec<String> {
+    let mut commands = Vec::new();
+    let mut current: Option<String> = None;
+    for line in text.lines() {
+        match current.as_mut() {
+            Some(command) => {
+                command.push('\n');
+                command.push_str(line);
+            }
+            None => {
+                let start = line.trim().trim_start_matches("$ ");
+                if !(start.starts_with("curl ") || start.starts_with("curl.exe ")) {
+                    continue;
+                }
+                current = Some(start.to_string());
+            }
+        }
+        let trimmed = line.trim_end();
+        let continued = trimmed.ends_with('\\') || trimmed.ends_with('^');
+        if !continued && current.as_deref().is_some_and(|c| tokenize(c).is_ok()) {
+            commands.extend(current.take());
+        }
+    }
+    commands.extend(current);
+    commands
+}
+
+/// Imports the curl commands of shell scripts, given as `(name, path)`, as one collection
+/// called `name`: a folder per script when there are several.
+pub async fn import_scripts(
+    app: &tauri::AppHandle,
+    name: String,
+    scripts: &[(String, String)],
+) -> Result<ImportResult, String> {
+    let mut report = ImportReport::new("curl");
+    let mut folders = Vec::new();
+    for (script, path) in scripts {
+        let text = match super::read_source(path) {
+            Ok(text) => text,
+            Err(e) => {
+                report.unsupported(format!("{script}: {e}"));
+                continue;
+            }
+        };
+        let mut items = Vec::new();
+        for command in script_commands(&text) {
+            match parse(&command, &mut report) {
+                Ok(request) => items.push(ImportedItem::Request(Box::new(request))),
+                Err(e) => report.unsupported(format!("{script}: {e}")),
+            }
+        }
+        if !items.is_empty() {
+            folders.push((script.clone(), items));
+        }
+    }
+    let items = match scripts.len() {
+        1 => folders.pop().map(|(_, items)| items).unwrap_or_default(),
+        _ => folders
+            .into_iter()
+            .map(|(name, items)| ImportedItem::Folder { name, items })
+            .collect(),
+    };
+    let imported = ImportedCollection {
+        name,
+        items,
+        ..ImportedCollection::default()
+    };
+    super::save(app, imported, report).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn form(command: &str) -> (Vec<FormRow>, ImportReport) {
+        let mut report = ImportReport::new("curl");
+        let request = parse(command, &mut report).expect("parses");
+        match request.body {
+            ImportedBody::FormData(rows) => (rows, report),
+            _ => panic!("not a multipart body: {command}"),
+        }
+    }
+
+    #[test]
+    fn form_at_path_is_a_file_field() {
+        let (rows, report) = form("curl -F 'doc=@/tmp/a.pdf;type=application/pdf' https://x.test");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].key, "doc");
+        assert_eq!(rows[0].file_path.as_deref(), Some("/tmp/a.pdf"));
+        assert!(report.unsupported.is_empty());
+    }
+
+    #[test]
+    fn form_lt_path_is_reported_and_not_read() {
+        let (rows, report) = form("curl -F note=text -F 'key=<~/.ssh/id_rsa' https://x.test");
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            (rows[0].key.as_str(), rows[0].value.as_str()),
+            ("note", "text")
+        );
+        assert_eq!(report.unsupported.len(), 1);
+        assert!(report.unsupported[0].contains("key=<~/.ssh/id_rsa"));
+    }
+
+    #[test]
+    fn form_string_is_literal() {
+        let (rows, report) = form(
+            "curl --form-string 'a=@not-a-file' --form-string 'b=<x;type=text/plain' https://x.test",
+        );
+        let values: Vec<_> = rows
+            .iter()
+            .map(|r| (r.value.as_str(), r.file_path.is_none()))
+            .collect();
+        assert_eq!(
+            values,
+            [("@not-a-file", true), ("<x;type=text/plain", true)]
+        );
+        assert!(report.unsupported.is_empty());
+    }
+}
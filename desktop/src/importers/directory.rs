@@ -0,0 +1,317 @@
+//! Importing every spec under a folder in one go, e.g. when onboarding a monorepo of
+//! services. Files are recognized as for dropped files (see `dropped::sniff`): OpenAPI
+//! documents, Postman, Insomnia, Thunder and Hoppscotch exports, HAR captures and Bruno
+//! collections each become their own collection; the curl scripts (`.sh`) share one, with
+//! a folder per script; the `.proto` files are compiled together as one gRPC schema.
+//! Progress is reported as `directory-import://progress` events, and the command returns
+//! one report for the whole folder.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{Emitter, Manager};
+
+use super::dropped::{self, DroppedFormat};
+
+/// Folders that hold dependencies and build output rather than specs.
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    "venv",
+    "__pycache__",
+];
+/// Extensions worth sniffing; anything else is passed over without being read.
+const CANDIDATE_EXTENSIONS: &[&str] = &["json", "yaml", "yml", "har", "proto", "sh"];
+const MAX_DEPTH: usize = 12;
+const MAX_FILES: usize = 5_000;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryStatus {
+    Imported,
+    /// Recognized, but not imported here (environment files need a target collection).
+    Skipped,
+    Failed,
+}
+
+#[derive(Serialize)]
+pub struct DirectoryEntry {
+    /// Relative to the imported folder.
+    pub path: String,
+    pub format: Option<DroppedFormat>,
+    pub status: EntryStatus,
+    /// The collection's meta; files imported together share it.
+    pub collection: Option<Value>,
+    pub requests: usize,
+    /// The importer's report, or the loaded gRPC schema.
+    pub result: Option<Value>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DirectoryImport {
+    pub root: String,
+    pub entries: Vec<DirectoryEntry>,
+    pub collections: usize,
+    pub requests: usize,
+    pub failed: usize,
+    /// JSON and YAML files that turned out not to be specs.
+    pub unrecognized: usize,
+    /// Set when the walk stopped at `MAX_FILES`.
+    pub truncated: bool,
+    pub duration_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct ProgressEvent {
+    import_id: String,
+    path: String,
+    index: usize,
+    total: usize,
+    format: DroppedFormat,
+    timestamp_ms: u64,
+}
+
+struct Walk {
+    /// Files (and Bruno collection folders) worth sniffing.
+    found: Vec<PathBuf>,
+    truncated: bool,
+}
+
+fn walk(dir: &Path, depth: usize, walk: &mut Walk) {
+    if depth > MAX_DEPTH || walk.truncated {
+        return;
+    }
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut entries: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    entries.sort();
+    for path in entries {
+        if walk.found.len() >= MAX_FILES {
+            walk.truncated = true;
+            return;
+        }
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        if path.is_dir() {
+            if name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            // A Bruno collection imports as a whole; its `.bru` files aren't specs of their own.
+            match path.join("bruno.json").is_file() {
+                true => walk.found.push(path),
+                false => self::walk(&path, depth + 1, walk),
+            }
+            continue;
+        }
+        let extension = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        if CANDIDATE_EXTENSIONS.contains(&extension.as_str()) {
+            walk.found.push(path);
+        }
+    }
+}
+
+fn relative(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+/// The collection meta and request count in an importer's output; HAR imports return the
+/// meta itself.
+fn collection_of(result: &Value) -> (Option<Value>, usize) {
+    let requests = result
+        .pointer("/report/requests")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as usize;
+    match result.get("collection") {
+        Some(collection) => (Some(collection.clone()), requests),
+        None if result.get("id").is_some() => (Some(result.clone()), requests),
+        None => (None, requests),
+    }
+}
+
+fn entry(
+    path: String,
+    format: DroppedFormat,
+    outcome: Result<Option<Value>, String>,
+) -> DirectoryEntry {
+    let mut entry = DirectoryEntry {
+        path,
+        format: Some(format),
+        status: EntryStatus::Imported,
+        collection: None,
+        requests: 0,
+        result: None,
+        error: None,
+    };
+    match outcome {
+        Ok(Some(result)) => {
+            (entry.collection, entry.requests) = collection_of(&result);
+            entry.result = Some(result);
+        }
+        Ok(None) => {
+            entry.status = EntryStatus::Skipped;
+            entry.error = Some("environment files are imported into a chosen collection".into());
+        }
+        Err(e) => {
+            entry.status = EntryStatus::Failed;
+            entry.error = Some(e);
+        }
+    }
+    entry
+}
+
+/// Walks `path` and imports every spec found in it (see the module docs). Files that fail
+/// are reported and don't stop the rest.
+#[tauri::command]
+pub async fn import_directory(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<DirectoryImport, String> {
+    let started = Instant::now();
+    let root = crate::normalize_path(path.trim());
+    if !root.is_dir() {
+        return Err(format!("not a folder: {}", root.to_string_lossy()));
+    }
+    let mut found = Walk {
+        found: Vec::new(),
+        truncated: false,
+    };
+    walk(&root, 0, &mut found);
+
+    let mut unrecognized = 0;
+    let mut specs = Vec::new();
+    let mut scripts = Vec::new();
+    let mut protos = Vec::new();
+    for path in found.found {
+        match dropped::sniff(&path) {
+            Ok(DroppedFormat::Curl) => scripts.push(path),
+            Ok(DroppedFormat::Proto) => protos.push(path),
+            Ok(format) => specs.push((path, format)),
+            Err(_) => unrecognized += 1,
+        }
+    }
+
+    let import_id = uuid::Uuid::new_v4().to_string();
+    let total = specs.len() + scripts.len() + protos.len();
+    let mut index = 0;
+    let mut progress = |path: &Path, format| {
+        let _ = app.emit(
+            "directory-import://progress",
+            ProgressEvent {
+                import_id: import_id.clone(),
+                path: relative(&root, path),
+                index,
+                total,
+                format,
+                timestamp_ms: crate::now_ms(),
+            },
+        );
+        index += 1;
+    };
+
+    let mut entries = Vec::new();
+    for (path, format) in &specs {
+        progress(path, *format);
+        let outcome = dropped::run(&app, &path.to_string_lossy(), *format).await;
+        entries.push(entry(relative(&root, path), *format, outcome));
+    }
+
+    if !scripts.is_empty() {
+        for path in &scripts {
+            progress(path, DroppedFormat::Curl);
+        }
+        let named: Vec<(String, String)> = scripts
+            .iter()
+            .map(|path| {
+                let name = match scripts.len() {
+                    1 => dropped::script_name(path),
+                    _ => relative(&root, path),
+                };
+                (name, path.to_string_lossy().to_string())
+            })
+            .collect();
+        let folder = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Imported".to_string());
+        let name = format!("{folder} (curl)");
+        let outcome = super::curl::import_scripts(&app, name, &named)
+            .await
+            .and_then(dropped::to_value)
+            .map(Some);
+        for (index, path) in scripts.iter().enumerate() {
+            let mut entry = entry(relative(&root, path), DroppedFormat::Curl, outcome.clone());
+            // The shared collection is counted once, with the first script.
+            if index > 0 {
+                entry.requests = 0;
+            }
+            entries.push(entry);
+        }
+    }
+
+    if !protos.is_empty() {
+        let mut include_dirs: Vec<String> = vec![root.to_string_lossy().to_string()];
+        for path in &protos {
+            progress(path, DroppedFormat::Proto);
+            if let Some(dir) = path.parent() {
+                let dir = dir.to_string_lossy().to_string();
+                if !include_dirs.contains(&dir) {
+                    include_dirs.push(dir);
+                }
+            }
+        }
+        let outcome = crate::grpc::grpc_load_protos(
+            app.state::<crate::grpc::GrpcState>(),
+            protos
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+            Some(include_dirs),
+        )
+        .await
+        .and_then(dropped::to_value)
+        .map(Some);
+        for path in &protos {
+            entries.push(entry(
+                relative(&root, path),
+                DroppedFormat::Proto,
+                outcome.clone(),
+            ));
+        }
+    }
+
+    let mut collections: Vec<&str> = entries
+        .iter()
+        .filter_map(|e| e.collection.as_ref())
+        .filter_map(|c| c.get("id").and_then(Value::as_str))
+        .collect();
+    collections.sort();
+    collections.dedup();
+    Ok(DirectoryImport {
+        root: root.to_string_lossy().to_string(),
+        collections: collections.len(),
+        requests: entries.iter().map(|e| e.requests).sum(),
+        failed: entries
+            .iter()
+            .filter(|e| e.status == EntryStatus::Failed)
+            .count(),
+        unrecognized,
+        truncated: found.truncated,
+        duration_ms: started.elapsed().as_millis() as u64,
+        entries,
+    })
+}
@@ -0,0 +1,280 @@
+//! `.env` file import into a collection environment. Handles `export` prefixes, single,
+//! double and backtick quoting (all of which may span lines), inline comments and
+//! `${VAR}` references to keys defined earlier in the file.
+
+use regex::Regex;
+use serde::Serialize;
+use std::sync::OnceLock;
+
+use super::{EnvironmentImportResult, ImportReport, ImportedVariable};
+
+/// Key words that mark a variable as sensitive.
+const SECRET_WORDS: &[&str] = &[
+    "SECRET",
+    "TOKEN",
+    "PASSWORD",
+    "PASSWD",
+    "PWD",
+    "PASS",
+    "AUTH",
+    "CREDENTIAL",
+    "CREDENTIALS",
+    "PRIVATE",
+    "SESSION",
+    "COOKIE",
+    "SALT",
+    "SIGNATURE",
+    "DSN",
+];
+
+/// Key fragments that mark a variable as sensitive wherever they appear.
+const SECRET_FRAGMENTS: &[&str] = &["APIKEY", "API_KEY", "ACCESS_KEY", "SECRET", "TOKEN"];
+
+/// Whether a variable looks like a credential, from its name or its value's shape.
+pub fn looks_secret(key: &str, value: &str) -> bool {
+    if value.is_empty() {
+        return false;
+    }
+    let key = key.to_uppercase();
+    if key
+        .split(['_', '.', '-'])
+        .any(|word| SECRET_WORDS.contains(&word))
+        || SECRET_FRAGMENTS.iter().any(|f| key.contains(f))
+    {
+        return true;
+    }
+    static SHAPES: OnceLock<Regex> = OnceLock::new();
+    let shapes = SHAPES.get_or_init(|| {
+        Regex::new(concat!(
+            r"^eyJ[\w-]+\.[\w-]+\.[\w-]*$",               // JWT
+            r"|^-----BEGIN [A-Z ]*PRIVATE KEY-----",      // PEM key
+            r"|^(sk|rk|pk)_(live|test)_\w+$",             // Stripe
+            r"|^gh[pousr]_\w{30,}$",                      // GitHub
+            r"|^xox[abposr]-[\w-]+$",                     // Slack
+            r"|^AKIA[0-9A-Z]{16}$",                       // AWS access key id
+            r"|^[a-z][a-z0-9+.-]*://[^/\s:@]+:[^/\s@]+@", // URL with credentials
+        ))
+        .expect("valid regex")
+    });
+    shapes.is_match(value)
+}
+
+/// Expands `$VAR`, `${VAR}` and `${VAR:-default}` against earlier keys. Unknown references
+/// are kept as written. With `escapes`, double-quote escape sequences are decoded too.
+fn expand(raw: &str, escapes: bool, defined: &[ImportedVariable]) -> String {
+    let lookup = |name: &str| {
+        defined
+            .iter()
+            .rev()
+            .find(|v| v.key == name)
+            .map(|v| v.value.clone())
+    };
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some((_, '$')) => out.push('$'),
+                Some((_, 'n')) if escapes => out.push('\n'),
+                Some((_, 'r')) if escapes => out.push('\r'),
+                Some((_, 't')) if escapes => out.push('\t'),
+                Some((_, '"')) if escapes => out.push('"'),
+                Some((_, '\\')) if escapes => out.push('\\'),
+                Some((_, other)) => {
+                    out.push('\\');
+                    out.push(other);
+                }
+                None => out.push('\\'),
+            },
+            '$' if raw[i + 1..].starts_with('{') => {
+                let Some(end) = raw[i..].find('}') else {
+                    out.push_str(&raw[i..]);
+                    break;
+                };
+                let inner = &raw[i + 2..i + end];
+                let (name, default) = match inner.split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (inner, None),
+                };
+                match (lookup(name), default) {
+                    (Some(value), _) => out.push_str(&value),
+                    (None, Some(default)) => out.push_str(default),
+                    (None, None) => out.push_str(&raw[i..=i + end]),
+                }
+                while chars.peek().is_some_and(|(j, _)| *j <= i + end) {
+                    chars.next();
+                }
+            }
+            '$' => {
+                let name: String = raw[i + 1..]
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                    .collect();
+                match lookup(&name).filter(|_| !name.is_empty()) {
+                    Some(value) => {
+                        out.push_str(&value);
+                        for _ in 0..name.len() {
+                            chars.next();
+                        }
+                    }
+                    None => out.push('$'),
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Parses `.env` text into variables in file order; a repeated key keeps its last value.
+pub fn parse(text: &str, report: &mut ImportReport) -> Result<Vec<ImportedVariable>, String> {
+    let text = text.replace("\r\n", "\n");
+    let mut variables: Vec<ImportedVariable> = Vec::new();
+    let mut rest = text.as_str();
+    let line_of = |rest: &str| text[..text.len() - rest.len()].matches('\n').count() + 1;
+    let skip_line = |rest: &str| -> usize { rest.find('\n').map_or(rest.len(), |i| i + 1) };
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            break;
+        }
+        if rest.starts_with('#') {
+            rest = &rest[skip_line(rest)..];
+            continue;
+        }
+        if let Some(after) = rest.strip_prefix("export ") {
+            rest = after.trim_start_matches([' ', '\t']);
+        }
+        let key_len = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')))
+            .unwrap_or(rest.len());
+        let key = &rest[..key_len];
+        let after_key = rest[key_len..].trim_start_matches([' ', '\t']);
+        let Some(after_eq) = after_key.strip_prefix('=').filter(|_| !key.is_empty()) else {
+            report.unsupported(format!(
+                "line {} is not KEY=value and was skipped",
+                line_of(rest)
+            ));
+            rest = &rest[skip_line(rest)..];
+            continue;
+        };
+        let start_line = line_of(rest);
+        let value_src = after_eq.trim_start_matches([' ', '\t']);
+        let value = match value_src.chars().next() {
+            Some(quote @ ('"' | '\'' | '`')) => {
+                let body = &value_src[1..];
+                let mut end = None;
+                let mut escaped = false;
+                for (i, c) in body.char_indices() {
+                    match c {
+                        '\\' if quote == '"' && !escaped => escaped = true,
+                        c if c == quote && !escaped => {
+                            end = Some(i);
+                            break;
+                        }
+                        _ => escaped = false,
+                    }
+                }
+                let end = end.ok_or_else(|| {
+                    format!("unterminated quoted value for {key} on line {start_line}")
+                })?;
+                let raw = &body[..end];
+                rest = &body[end + 1..];
+                rest = &rest[skip_line(rest)..];
+                match quote {
+                    '"' => expand(raw, true, &variables),
+                    _ => raw.to_string(),
+                }
+            }
+            _ => {
+                let line_end = value_src.find('\n').unwrap_or(value_src.len());
+                let line = &value_src[..line_end];
+                rest = &value_src[line_end..];
+                // `#` starts a comment only after whitespace, so `a#b` stays intact.
+                let line = match line.find(" #").or_else(|| line.find("\t#")) {
+                    Some(i) => &line[..i],
+                    None if line.starts_with('#') => "",
+                    None => line,
+                };
+                expand(line.trim(), false, &variables)
+            }
+        };
+        variables.retain(|v| v.key != key);
+        variables.push(ImportedVariable {
+            key: key.to_string(),
+            secret: looks_secret(key, &value),
+            value,
+        });
+    }
+    Ok(variables)
+}
+
+#[derive(Serialize)]
+pub struct DotenvEntry {
+    key: String,
+    /// Flagged as a credential; it will be stored in the OS keychain.
+    secret: bool,
+    multiline: bool,
+}
+
+/// Lists a `.env` file's keys and which of them look like secrets, without their values, so
+/// the UI can confirm the selection before importing.
+#[tauri::command]
+pub async fn preview_dotenv(path: String) -> Result<Vec<DotenvEntry>, String> {
+    let raw = super::read_source(&path)?;
+    let mut report = ImportReport::new("dotenv");
+    Ok(parse(&raw, &mut report)?
+        .into_iter()
+        .map(|v| DotenvEntry {
+            multiline: v.value.contains('\n'),
+            secret: v.secret,
+            key: v.key,
+        })
+        .collect())
+}
+
+/// Imports a `.env` file into the environment named `environment_id`, creating it if
+/// needed. `secret_keys`, when given, replaces the automatic secret detection.
+#[tauri::command]
+pub async fn import_dotenv(
+    app: tauri::AppHandle,
+    collection_id: String,
+    path: String,
+    environment_id: String,
+    secret_keys: Option<Vec<String>>,
+    activate: Option<bool>,
+) -> Result<EnvironmentImportResult, String> {
+    let env_name = environment_id.trim().to_string();
+    if env_name.is_empty() {
+        return Err("environment name is required".to_string());
+    }
+    let raw = super::read_source(&path)?;
+    let mut report = ImportReport::new("dotenv");
+    let mut variables = parse(&raw, &mut report)?;
+    if let Some(keys) = &secret_keys {
+        for variable in &mut variables {
+            variable.secret = keys.contains(&variable.key) && !variable.value.is_empty();
+        }
+    }
+    report.variables = variables.len();
+
+    let env_path = format!("/collections/{collection_id}/environment");
+    let mut environment = crate::backend_get(&app, &env_path).await?;
+    let envs = environment
+        .get_mut("envs")
+        .and_then(serde_json::Value::as_object_mut)
+        .ok_or("collection environment file is malformed")?;
+    super::merge_environment(envs, &env_name, &variables);
+    if activate.unwrap_or(false) {
+        environment["active_env"] = serde_json::Value::String(env_name.clone());
+    }
+    crate::import_post(&app, &env_path, &environment).await?;
+
+    super::store_secrets(&collection_id, &env_name, &variables, &mut report);
+    report.environments = 1;
+    Ok(EnvironmentImportResult {
+        environments: vec![env_name],
+        report,
+    })
+}
@@ -0,0 +1,264 @@
+//! Files dropped onto a LiteFetch window. Each path is sniffed for its format and handed to
+//! the matching importer; progress and outcomes are reported as `drop-import://*` events.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use tauri::{Emitter, Manager};
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum DroppedFormat {
+    Postman,
+    PostmanEnvironment,
+    Insomnia,
+    Thunder,
+    Hoppscotch,
+    Openapi,
+    Har,
+    Bruno,
+    Proto,
+    Dotenv,
+    /// A shell script of curl commands.
+    Curl,
+}
+
+#[derive(Clone, Serialize)]
+struct DropProgressEvent {
+    drop_id: String,
+    path: String,
+    index: usize,
+    total: usize,
+    format: Option<DroppedFormat>,
+    /// `detecting` or `importing`.
+    status: String,
+    timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct DropResultEvent {
+    drop_id: String,
+    path: String,
+    format: Option<DroppedFormat>,
+    /// `imported`, `needs_target` (environment files, which need a collection and
+    /// environment picked in the UI) or `failed`.
+    status: String,
+    /// Importer output: collection import results, or the loaded gRPC schema.
+    result: Option<Value>,
+    error: Option<String>,
+    timestamp_ms: u64,
+}
+
+fn sniff_json(data: &Value) -> Option<DroppedFormat> {
+    let has = |key: &str| data.get(key).is_some();
+    let info = data.get("info");
+    if data.pointer("/log/entries").is_some_and(Value::is_array) {
+        Some(DroppedFormat::Har)
+    } else if has("openapi") || has("swagger") {
+        Some(DroppedFormat::Openapi)
+    } else if info.is_some_and(|i| {
+        i.get("_postman_id").is_some() || super::str_of(i, "schema").contains("getpostman.com")
+    }) {
+        Some(DroppedFormat::Postman)
+    } else if has("_postman_variable_scope")
+        || (data.get("values").is_some_and(Value::is_array) && has("name") && !has("requests"))
+    {
+        Some(DroppedFormat::PostmanEnvironment)
+    } else if has("__export_format") || super::str_of(data, "_type") == "export" {
+        Some(DroppedFormat::Insomnia)
+    } else if super::str_of(data, "client") == "Thunder Client" || has("collectionName") {
+        Some(DroppedFormat::Thunder)
+    } else {
+        let root = match data {
+            Value::Array(roots) => roots.first()?,
+            other => other,
+        };
+        (root.get("requests").is_some_and(Value::is_array)
+            && root.get("folders").is_some_and(Value::is_array))
+        .then_some(DroppedFormat::Hoppscotch)
+    }
+}
+
+/// Works out which importer understands `path`, from its name and then its contents.
+pub fn sniff(path: &Path) -> Result<DroppedFormat, String> {
+    if path.is_dir() {
+        return match path.join("bruno.json").is_file() {
+            true => Ok(DroppedFormat::Bruno),
+            false => Err("folder is not a Bruno collection".to_string()),
+        };
+    }
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if name == "bruno.json" {
+        return Ok(DroppedFormat::Bruno);
+    }
+    if name == ".env" || name.starts_with(".env.") || extension == "env" {
+        return Ok(DroppedFormat::Dotenv);
+    }
+    match extension.as_str() {
+        "proto" => return Ok(DroppedFormat::Proto),
+        "har" => return Ok(DroppedFormat::Har),
+        "sh" => return Ok(DroppedFormat::Curl),
+        _ => {}
+    }
+    let raw = super::read_source(&path.to_string_lossy())?;
+    let data: Value = match extension.as_str() {
+        "yaml" | "yml" => {
+            serde_yaml::from_str(&raw).map_err(|e| format!("YAML parse failed: {e}"))?
+        }
+        _ => serde_json::from_str(&raw)
+            .or_else(|_| serde_yaml::from_str(&raw))
+            .map_err(|e| format!("file is not JSON or YAML: {e}"))?,
+    };
+    sniff_json(&data).ok_or_else(|| "unrecognized import format".to_string())
+}
+
+/// A script's file name without its extension, as its collection or folder name.
+pub fn script_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "curl".to_string())
+}
+
+pub fn to_value(result: impl Serialize) -> Result<Value, String> {
+    serde_json::to_value(result).map_err(|e| format!("import result serialize failed: {e}"))
+}
+
+pub async fn run(
+    app: &tauri::AppHandle,
+    path: &str,
+    format: DroppedFormat,
+) -> Result<Option<Value>, String> {
+    let path = path.to_string();
+    let result = match format {
+        DroppedFormat::Postman => {
+            to_value(super::postman::import_postman_collection(app.clone(), path, None).await?)?
+        }
+        DroppedFormat::Insomnia => {
+            to_value(super::insomnia::import_insomnia_export(app.clone(), path).await?)?
+        }
+        DroppedFormat::Thunder => {
+            to_value(super::thunder::import_thunder_collection(app.clone(), path, None).await?)?
+        }
+        DroppedFormat::Hoppscotch => to_value(
+            super::hoppscotch::import_hoppscotch_collection(app.clone(), path, None).await?,
+        )?,
+        DroppedFormat::Openapi => {
+            to_value(super::openapi::import_openapi(app.clone(), path, None).await?)?
+        }
+        DroppedFormat::Har => crate::har::import_har(app.clone(), path, None, None).await?,
+        DroppedFormat::Bruno => {
+            to_value(super::bruno::import_bruno_collection(app.clone(), path, None).await?)?
+        }
+        DroppedFormat::Curl => {
+            let name = script_name(Path::new(&path));
+            to_value(super::curl::import_scripts(app, name.clone(), &[(name, path)]).await?)?
+        }
+        DroppedFormat::PostmanEnvironment | DroppedFormat::Dotenv | DroppedFormat::Proto => {
+            return Ok(None)
+        }
+    };
+    Ok(Some(result))
+}
+
+/// Imports every dropped path in order. `.proto` files from one drop are compiled together
+/// so they can import each other.
+pub async fn import_paths(app: tauri::AppHandle, paths: Vec<String>) -> String {
+    let drop_id = uuid::Uuid::new_v4().to_string();
+    let total = paths.len();
+    let progress = |path: &str, index: usize, format, status: &str| {
+        let _ = app.emit(
+            "drop-import://progress",
+            DropProgressEvent {
+                drop_id: drop_id.clone(),
+                path: path.to_string(),
+                index,
+                total,
+                format,
+                status: status.to_string(),
+                timestamp_ms: crate::now_ms(),
+            },
+        );
+    };
+    let finish = |path: &str, format, outcome: Result<Option<Value>, String>| {
+        let (status, result, error) = match outcome {
+            Ok(Some(result)) => ("imported", Some(result), None),
+            Ok(None) => ("needs_target", None, None),
+            Err(e) => ("failed", None, Some(e)),
+        };
+        let _ = app.emit(
+            "drop-import://result",
+            DropResultEvent {
+                drop_id: drop_id.clone(),
+                path: path.to_string(),
+                format,
+                status: status.to_string(),
+                result,
+                error,
+                timestamp_ms: crate::now_ms(),
+            },
+        );
+    };
+
+    let mut protos = Vec::new();
+    for (index, path) in paths.iter().enumerate() {
+        progress(path, index, None, "detecting");
+        let format = match sniff(&crate::normalize_path(path)) {
+            Ok(format) => format,
+            Err(e) => {
+                finish(path, None, Err(e));
+                continue;
+            }
+        };
+        if format == DroppedFormat::Proto {
+            protos.push((index, path.clone()));
+            continue;
+        }
+        progress(path, index, Some(format), "importing");
+        finish(path, Some(format), run(&app, path, format).await);
+    }
+
+    if !protos.is_empty() {
+        let mut include_dirs: Vec<String> = Vec::new();
+        for (index, path) in &protos {
+            progress(path, *index, Some(DroppedFormat::Proto), "importing");
+            if let Some(dir) = crate::normalize_path(path).parent() {
+                let dir = dir.to_string_lossy().to_string();
+                if !include_dirs.contains(&dir) {
+                    include_dirs.push(dir);
+                }
+            }
+        }
+        let outcome = crate::grpc::grpc_load_protos(
+            app.state::<crate::grpc::GrpcState>(),
+            protos.iter().map(|(_, path)| path.clone()).collect(),
+            Some(include_dirs),
+        )
+        .await
+        .and_then(to_value)
+        .map(Some);
+        for (_, path) in &protos {
+            finish(path, Some(DroppedFormat::Proto), outcome.clone());
+        }
+    }
+    drop_id
+}
+
+/// Runs the drop pipeline for paths the frontend collected itself (e.g. from a file picker);
+/// window drops are handled without a command. Returns the drop id used in events.
+#[tauri::command]
+pub async fn import_dropped_files(
+    app: tauri::AppHandle,
+    paths: Vec<String>,
+) -> Result<String, String> {
+    if paths.is_empty() {
+        return Err("no files provided".to_string());
+    }
+    Ok(import_paths(app, paths).await)
+}
@@ -0,0 +1,294 @@
+//! Hoppscotch collection export import. An export holds one or more root collections; each
+//! becomes its own LiteFetch collection. Environment exports can be imported alongside it.
+
+use regex::Regex;
+use serde_json::Value;
+use std::sync::OnceLock;
+
+use super::{
+    array_of, str_of, value_text, FormRow, ImportReport, ImportResult, ImportedAuth, ImportedBody,
+    ImportedCollection, ImportedEnvironment, ImportedItem, ImportedRequest, ImportedVariable,
+};
+
+fn active(row: &Value) -> bool {
+    row.get("active").and_then(Value::as_bool).unwrap_or(true)
+}
+
+/// Hoppscotch references variables as `<<name>>`.
+fn template(text: &str) -> String {
+    static VARIABLE: OnceLock<Regex> = OnceLock::new();
+    let pattern =
+        VARIABLE.get_or_init(|| Regex::new(r"<<\s*([\w.\-]+)\s*>>").expect("valid regex"));
+    pattern.replace_all(text, "{{$1}}").to_string()
+}
+
+fn auth_of(
+    auth: Option<&Value>,
+    parent: &ImportedAuth,
+    request: &mut ImportedRequest,
+    report: &mut ImportReport,
+) -> ImportedAuth {
+    let Some(auth) = auth.filter(|a| a.is_object()) else {
+        return parent.clone();
+    };
+    if auth.get("authActive").and_then(Value::as_bool) == Some(false) {
+        return ImportedAuth::None;
+    }
+    match str_of(auth, "authType") {
+        "" | "inherit" => parent.clone(),
+        "none" => ImportedAuth::None,
+        "basic" => ImportedAuth::Basic {
+            username: template(str_of(auth, "username")),
+            password: template(str_of(auth, "password")),
+        },
+        "bearer" => ImportedAuth::Bearer {
+            token: template(str_of(auth, "token")),
+        },
+        "api-key" => {
+            let key = template(str_of(auth, "key"));
+            let value = template(str_of(auth, "value"));
+            if str_of(auth, "addTo") == "QUERY_PARAMS" {
+                request.query_params.push(FormRow::text(key, value, true));
+            } else {
+                request.headers.push((key, value));
+            }
+            ImportedAuth::None
+        }
+        other => {
+            report.unsupported(format!(
+                "{other} auth is not supported; requests imported without auth"
+            ));
+            ImportedAuth::None
+        }
+    }
+}
+
+fn body_of(body: Option<&Value>, report: &mut ImportReport) -> ImportedBody {
+    let Some(body) = body.filter(|b| b.is_object()) else {
+        return ImportedBody::None;
+    };
+    let content_type = str_of(body, "contentType");
+    match (content_type, body.get("body")) {
+        ("", _) | (_, None | Some(Value::Null)) => ImportedBody::None,
+        ("multipart/form-data", Some(Value::Array(rows))) => ImportedBody::FormData(
+            rows.iter()
+                .filter(|row| !str_of(row, "key").is_empty())
+                .filter_map(|row| {
+                    if row.get("isFile").and_then(Value::as_bool).unwrap_or(false) {
+                        // Exports don't carry file contents or paths.
+                        report.unsupported("multipart file fields are not imported");
+                        return None;
+                    }
+                    Some(FormRow::text(
+                        template(str_of(row, "key")),
+                        template(&value_text(row.get("value"))),
+                        active(row),
+                    ))
+                })
+                .collect(),
+        ),
+        // URL-encoded bodies are stored as raw `key: value` lines; `#` comments out a row.
+        ("application/x-www-form-urlencoded", Some(Value::String(text))) => {
+            ImportedBody::UrlEncoded(
+                text.lines()
+                    .filter_map(|line| {
+                        let line = line.trim();
+                        let (line, enabled) = match line.strip_prefix('#') {
+                            Some(rest) => (rest.trim(), false),
+                            None => (line, true),
+                        };
+                        let (key, value) = line.split_once(':')?;
+                        Some(FormRow::text(
+                            template(key.trim()),
+                            template(value.trim()),
+                            enabled,
+                        ))
+                    })
+                    .collect(),
+            )
+        }
+        (_, Some(Value::String(text))) if !text.is_empty() => ImportedBody::Raw {
+            text: template(text),
+            json: content_type.contains("json"),
+        },
+        _ => ImportedBody::None,
+    }
+}
+
+/// `pw.env.set("name", pw.response.body.path)` calls become extraction rules.
+fn extract_rules(script: &str, report: &mut ImportReport) -> Vec<(String, String)> {
+    static SETTER: OnceLock<Regex> = OnceLock::new();
+    let setter = SETTER.get_or_init(|| {
+        Regex::new(r#"pw\.env\.set\(\s*['"]([^'"]+)['"]\s*,\s*pw\.response\.body((?:\.[\w$]+|\[\d+\])+)\s*\)"#)
+            .expect("valid regex")
+    });
+    let rules: Vec<(String, String)> = setter
+        .captures_iter(script)
+        .map(|c| (c[2].trim_start_matches('.').to_string(), c[1].to_string()))
+        .collect();
+    if script.contains("pw.expect") || script.contains("pw.test") {
+        report.unsupported("test scripts are not imported");
+    }
+    rules
+}
+
+fn request_of(resource: &Value, auth: &ImportedAuth, report: &mut ImportReport) -> ImportedRequest {
+    let name = match str_of(resource, "name") {
+        "" => "Request",
+        n => n,
+    };
+    let method = match str_of(resource, "method") {
+        "" => "GET",
+        m => m,
+    };
+    let mut request = ImportedRequest::new(name, method, template(str_of(resource, "endpoint")));
+    request.query_params = array_of(resource, "params")
+        .iter()
+        .filter(|p| !str_of(p, "key").is_empty())
+        .map(|p| {
+            FormRow::text(
+                template(str_of(p, "key")),
+                template(&value_text(p.get("value"))),
+                active(p),
+            )
+        })
+        .collect();
+    for header in array_of(resource, "headers") {
+        if active(header) && !str_of(header, "key").is_empty() {
+            request.headers.push((
+                template(str_of(header, "key")),
+                template(&value_text(header.get("value"))),
+            ));
+        }
+    }
+    request.body = body_of(resource.get("body"), report);
+    request.auth = auth_of(resource.get("auth"), auth, &mut request, report);
+    request.extract_rules = extract_rules(str_of(resource, "testScript"), report);
+    if !str_of(resource, "preRequestScript").trim().is_empty() {
+        report.unsupported("pre-request scripts are not imported");
+    }
+    if !array_of(resource, "requestVariables").is_empty() {
+        report.unsupported("request variables are not imported");
+    }
+    request
+}
+
+fn items_of(
+    collection: &Value,
+    auth: &ImportedAuth,
+    report: &mut ImportReport,
+) -> Vec<ImportedItem> {
+    let mut items: Vec<ImportedItem> = array_of(collection, "folders")
+        .iter()
+        .map(|folder| {
+            let mut scratch = ImportedRequest::new("", "GET", "");
+            let folder_auth = auth_of(folder.get("auth"), auth, &mut scratch, report);
+            ImportedItem::Folder {
+                name: str_of(folder, "name").to_string(),
+                items: items_of(folder, &folder_auth, report),
+            }
+        })
+        .collect();
+    items.extend(
+        array_of(collection, "requests")
+            .iter()
+            .map(|r| ImportedItem::Request(Box::new(request_of(r, auth, report)))),
+    );
+    if !array_of(collection, "headers").is_empty() {
+        report.unsupported("collection and folder headers are not inherited by requests");
+    }
+    items
+}
+
+fn variables_of(rows: &[Value]) -> Vec<ImportedVariable> {
+    rows.iter()
+        .filter(|v| !str_of(v, "key").is_empty())
+        .map(|v| {
+            // Newer exports split initial and current values; prefer the shared initial one.
+            let value = v
+                .get("value")
+                .or_else(|| v.get("initialValue"))
+                .or_else(|| v.get("currentValue"));
+            ImportedVariable {
+                key: str_of(v, "key").to_string(),
+                value: value_text(value),
+                secret: v.get("secret").and_then(Value::as_bool).unwrap_or(false),
+            }
+        })
+        .collect()
+}
+
+fn convert(collection: &Value, report: &mut ImportReport) -> ImportedCollection {
+    let mut scratch = ImportedRequest::new("", "GET", "");
+    let auth = auth_of(
+        collection.get("auth"),
+        &ImportedAuth::None,
+        &mut scratch,
+        report,
+    );
+    ImportedCollection {
+        name: match str_of(collection, "name") {
+            "" => "Imported Hoppscotch Collection".to_string(),
+            n => n.to_string(),
+        },
+        items: items_of(collection, &auth, report),
+        variables: variables_of(array_of(collection, "variables")),
+        environments: Vec::new(),
+    }
+}
+
+fn parse_environments(data: &Value) -> Vec<ImportedEnvironment> {
+    let envs = match data {
+        Value::Array(envs) => envs.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    envs.iter()
+        .filter(|env| env.get("variables").is_some_and(Value::is_array))
+        .map(|env| ImportedEnvironment {
+            name: match str_of(env, "name") {
+                "" => "hoppscotch".to_string(),
+                n => n.to_string(),
+            },
+            variables: variables_of(array_of(env, "variables")),
+        })
+        .collect()
+}
+
+/// Imports each root collection of a Hoppscotch export. Environments from
+/// `environment_path` are added to every created collection.
+#[tauri::command]
+pub async fn import_hoppscotch_collection(
+    app: tauri::AppHandle,
+    path: String,
+    environment_path: Option<String>,
+) -> Result<Vec<ImportResult>, String> {
+    let raw = super::read_source(&path)?;
+    let data: Value =
+        serde_json::from_str(&raw).map_err(|e| format!("Hoppscotch export parse failed: {e}"))?;
+    let roots = match &data {
+        Value::Array(roots) => roots.clone(),
+        Value::Object(_) if data.get("requests").is_some() => vec![data.clone()],
+        _ => return Err("not a Hoppscotch collection export".to_string()),
+    };
+    let environments = match environment_path.filter(|p| !p.trim().is_empty()) {
+        Some(env_path) => {
+            let raw = super::read_source(&env_path)?;
+            let envs: Value = serde_json::from_str(&raw)
+                .map_err(|e| format!("Hoppscotch environment parse failed: {e}"))?;
+            parse_environments(&envs)
+        }
+        None => Vec::new(),
+    };
+
+    let mut results = Vec::new();
+    for root in &roots {
+        let mut report = ImportReport::new("hoppscotch");
+        let mut imported = convert(root, &mut report);
+        imported.environments = environments.clone();
+        results.push(super::save(&app, imported, report).await?);
+    }
+    if results.is_empty() {
+        return Err("Hoppscotch export contained no collections".to_string());
+    }
+    Ok(results)
+}
@@ -0,0 +1,524 @@
+//! Insomnia v4 export import. Each workspace becomes a collection; its base environment
+//! becomes the `default` environment and sub-environments are layered over it. Response
+//! chaining tags (`{% response 'body', ... %}`) become extraction rules on the source request.
+
+use base64::Engine;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+use super::{
+    array_of, str_of, value_text, FormRow, ImportReport, ImportResult, ImportedAuth, ImportedBody,
+    ImportedCollection, ImportedEnvironment, ImportedItem, ImportedRequest, ImportedVariable,
+};
+
+fn sort_key(resource: &Value) -> f64 {
+    resource
+        .get("metaSortKey")
+        .and_then(Value::as_f64)
+        .unwrap_or(0.0)
+}
+
+fn disabled(row: &Value) -> bool {
+    row.get("disabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Flattens nested environment data into dotted keys, matching `{{ _.a.b }}` references.
+fn flatten(prefix: &str, data: &Value, out: &mut Vec<(String, String)>) {
+    match data {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = match prefix {
+                    "" => key.clone(),
+                    p => format!("{p}.{key}"),
+                };
+                flatten(&key, value, out);
+            }
+        }
+        other if !prefix.is_empty() => out.push((prefix.to_string(), value_text(Some(other)))),
+        _ => {}
+    }
+}
+
+/// Converts a JSONPath filter from a response tag into the JMESPath the backend evaluates.
+/// Recursive descent and filter expressions have no direct equivalent.
+fn jmespath_of(filter: &str) -> Option<String> {
+    static BRACKET: OnceLock<Regex> = OnceLock::new();
+    if filter.contains("..") || filter.contains("[?") || filter.contains('*') {
+        return None;
+    }
+    let bracket =
+        BRACKET.get_or_init(|| Regex::new(r#"\[['"]([^'"]+)['"]\]"#).expect("valid regex"));
+    let path = bracket.replace_all(filter.trim(), ".$1");
+    let path = path.trim_start_matches('$').trim_start_matches('.');
+    (!path.is_empty()).then(|| path.to_string())
+}
+
+fn decode_filter(raw: &str) -> String {
+    // Newer exports wrap tag arguments as `b64::<base64>::46b`.
+    raw.strip_prefix("b64::")
+        .and_then(|rest| rest.strip_suffix("::46b"))
+        .and_then(|encoded| {
+            base64::engine::general_purpose::STANDARD
+                .decode(encoded)
+                .ok()
+        })
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| raw.to_string())
+}
+
+fn tag_args(args: &str) -> Vec<String> {
+    static ARG: OnceLock<Regex> = OnceLock::new();
+    let pattern =
+        ARG.get_or_init(|| Regex::new(r#"'([^']*)'|"([^"]*)"|([^,\s]+)"#).expect("valid regex"));
+    pattern
+        .captures_iter(args)
+        .map(|c| {
+            c.get(1)
+                .or_else(|| c.get(2))
+                .or_else(|| c.get(3))
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+struct Converter<'a> {
+    resources: &'a [Value],
+    /// `(source request id, JMESPath)` → variable the chained value is extracted into.
+    chains: HashMap<(String, String), String>,
+    names: HashSet<String>,
+    report: &'a mut ImportReport,
+}
+
+impl<'a> Converter<'a> {
+    fn children(&self, parent_id: &str, types: &[&str]) -> Vec<&'a Value> {
+        let mut children: Vec<&'a Value> = self
+            .resources
+            .iter()
+            .filter(|r| str_of(r, "parentId") == parent_id && types.contains(&str_of(r, "_type")))
+            .collect();
+        children.sort_by(|a, b| sort_key(a).total_cmp(&sort_key(b)));
+        children
+    }
+
+    fn chain_variable(&mut self, args: &[String]) -> Option<String> {
+        let [attribute, request_id, filter, ..] = args else {
+            return None;
+        };
+        if attribute != "body" {
+            self.report.unsupported(format!(
+                "response {attribute} chaining is not supported; only body values are extracted"
+            ));
+            return None;
+        }
+        let filter = decode_filter(filter);
+        let Some(path) = jmespath_of(&filter) else {
+            self.report.unsupported(format!(
+                "response chain filter {filter:?} could not be converted to an extraction rule"
+            ));
+            return None;
+        };
+        let key = (request_id.clone(), path.clone());
+        if let Some(name) = self.chains.get(&key) {
+            return Some(name.clone());
+        }
+        let base: String = path
+            .rsplit('.')
+            .next()
+            .unwrap_or(&path)
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while !self.names.insert(name.clone()) {
+            name = format!("{base}_{suffix}");
+            suffix += 1;
+        }
+        self.chains.insert(key, name.clone());
+        Some(name)
+    }
+
+    /// Rewrites Insomnia's Nunjucks templating into LiteFetch `{{var}}` syntax.
+    fn template(&mut self, text: &str) -> String {
+        static TAG: OnceLock<Regex> = OnceLock::new();
+        static VARIABLE: OnceLock<Regex> = OnceLock::new();
+        let tag = TAG.get_or_init(|| Regex::new(r"\{%\s*(\w+)(.*?)%\}").expect("valid regex"));
+        let variable = VARIABLE.get_or_init(|| {
+            Regex::new(r#"\{\{\s*(?:_\.)?(?:_\[['"])?([\w.\-]+?)(?:['"]\])?\s*\}\}"#)
+                .expect("valid regex")
+        });
+
+        let mut out = String::with_capacity(text.len());
+        let mut last = 0;
+        for capture in tag.captures_iter(text) {
+            let whole = capture.get(0).expect("match");
+            out.push_str(&text[last..whole.start()]);
+            last = whole.end();
+            let replacement = match &capture[1] {
+                "response" => {
+                    let args = tag_args(&capture[2]);
+                    self.chain_variable(&args)
+                        .map(|name| format!("{{{{{name}}}}}"))
+                }
+                "uuid" => Some("{{$uuid}}".to_string()),
+                "now" | "timestamp" => Some("{{$timestamp}}".to_string()),
+                other => {
+                    self.report
+                        .unsupported(format!("{other} template tags are left unresolved"));
+                    None
+                }
+            };
+            out.push_str(&replacement.unwrap_or_else(|| whole.as_str().to_string()));
+        }
+        out.push_str(&text[last..]);
+        variable.replace_all(&out, "{{$1}}").to_string()
+    }
+
+    /// Registers every chain up front so extraction rules can be attached to source requests
+    /// that sort before the requests referencing them.
+    fn scan(&mut self, value: &Value) {
+        match value {
+            Value::String(text) if text.contains("{%") => {
+                self.template(text);
+            }
+            Value::Array(items) => items.iter().for_each(|v| self.scan(v)),
+            Value::Object(map) => map.values().for_each(|v| self.scan(v)),
+            _ => {}
+        }
+    }
+
+    fn auth(&mut self, resource: &Value, parent: &ImportedAuth, request: &mut ImportedRequest) {
+        let auth = resource
+            .get("authentication")
+            .cloned()
+            .unwrap_or(Value::Null);
+        if auth.as_object().is_none_or(|a| a.is_empty()) {
+            request.auth = parent.clone();
+            return;
+        }
+        if auth
+            .get("disabled")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            return;
+        }
+        match str_of(&auth, "type") {
+            "basic" => {
+                request.auth = ImportedAuth::Basic {
+                    username: self.template(str_of(&auth, "username")),
+                    password: self.template(str_of(&auth, "password")),
+                }
+            }
+            "bearer" => {
+                let token = self.template(str_of(&auth, "token"));
+                match str_of(&auth, "prefix") {
+                    "" | "Bearer" => request.auth = ImportedAuth::Bearer { token },
+                    prefix => request
+                        .headers
+                        .push(("Authorization".to_string(), format!("{prefix} {token}"))),
+                }
+            }
+            "apikey" => {
+                let key = self.template(str_of(&auth, "key"));
+                let value = self.template(str_of(&auth, "value"));
+                if str_of(&auth, "addTo") == "queryParams" {
+                    request.query_params.push(FormRow::text(key, value, true));
+                } else {
+                    request.headers.push((key, value));
+                }
+            }
+            "none" | "" => {}
+            other => self.report.unsupported(format!(
+                "{other} auth is not supported; requests imported without auth"
+            )),
+        }
+    }
+
+    fn rows(&mut self, rows: &[Value]) -> Vec<FormRow> {
+        rows.iter()
+            .filter(|row| !str_of(row, "name").is_empty())
+            .map(|row| {
+                let key = self.template(str_of(row, "name"));
+                if str_of(row, "type") == "file" {
+                    let path = str_of(row, "fileName").to_string();
+                    FormRow {
+                        key,
+                        value: path.clone(),
+                        enabled: !disabled(row),
+                        file_path: Some(path),
+                    }
+                } else {
+                    let value = self.template(&value_text(row.get("value")));
+                    FormRow::text(key, value, !disabled(row))
+                }
+            })
+            .collect()
+    }
+
+    fn body(&mut self, body: &Value) -> ImportedBody {
+        let mime = str_of(body, "mimeType");
+        match mime {
+            "application/x-www-form-urlencoded" => {
+                ImportedBody::UrlEncoded(self.rows(array_of(body, "params")))
+            }
+            "multipart/form-data" => ImportedBody::FormData(self.rows(array_of(body, "params"))),
+            "application/octet-stream" => match str_of(body, "fileName") {
+                "" => ImportedBody::None,
+                path => ImportedBody::Binary {
+                    file_path: path.to_string(),
+                },
+            },
+            _ => match str_of(body, "text") {
+                "" => ImportedBody::None,
+                text => {
+                    if mime == "application/graphql" {
+                        self.report
+                            .unsupported("GraphQL bodies are imported as raw JSON payloads");
+                    }
+                    ImportedBody::Raw {
+                        text: self.template(text),
+                        json: mime.contains("json") || mime == "application/graphql",
+                    }
+                }
+            },
+        }
+    }
+
+    fn request(&mut self, resource: &Value, auth: &ImportedAuth) -> ImportedRequest {
+        let name = match str_of(resource, "name") {
+            "" => "Request",
+            n => n,
+        };
+        let url = self.template(str_of(resource, "url"));
+        let method = match str_of(resource, "method") {
+            "" => "GET",
+            m => m,
+        };
+        let mut request = ImportedRequest::new(name, method, url);
+        request.description = Some(str_of(resource, "description").to_string());
+        for header in array_of(resource, "headers") {
+            if str_of(header, "name").is_empty() || disabled(header) {
+                continue;
+            }
+            let key = self.template(str_of(header, "name"));
+            let value = self.template(&value_text(header.get("value")));
+            request.headers.push((key, value));
+        }
+        let params = self.rows(array_of(resource, "parameters"));
+        request.query_params = params;
+        request.body = self.body(resource.get("body").unwrap_or(&Value::Null));
+        self.auth(resource, auth, &mut request);
+
+        let id = str_of(resource, "_id");
+        let mut rules: Vec<(String, String)> = self
+            .chains
+            .iter()
+            .filter(|((source, _), _)| source == id)
+            .map(|((_, path), variable)| (path.clone(), variable.clone()))
+            .collect();
+        rules.sort();
+        request.extract_rules = rules;
+        request
+    }
+
+    fn items(
+        &mut self,
+        parent_id: &str,
+        auth: &ImportedAuth,
+        folder_vars: &mut Vec<(String, String)>,
+    ) -> Vec<ImportedItem> {
+        let mut items = Vec::new();
+        for resource in self.children(parent_id, &["request", "request_group"]) {
+            if str_of(resource, "_type") == "request" {
+                items.push(ImportedItem::Request(Box::new(
+                    self.request(resource, auth),
+                )));
+                continue;
+            }
+            if let Some(env) = resource.get("environment").filter(|e| e.is_object()) {
+                flatten("", env, folder_vars);
+            }
+            let mut folder_auth = ImportedRequest::new("", "GET", "");
+            self.auth(resource, auth, &mut folder_auth);
+            if !folder_auth.headers.is_empty() || !folder_auth.query_params.is_empty() {
+                self.report
+                    .unsupported("folder-level API key auth is not inherited by requests");
+            }
+            items.push(ImportedItem::Folder {
+                name: match str_of(resource, "name") {
+                    "" => "Folder".to_string(),
+                    n => n.to_string(),
+                },
+                items: self.items(str_of(resource, "_id"), &folder_auth.auth, folder_vars),
+            });
+        }
+        items
+    }
+}
+
+fn environment_variables(env: &Value) -> Vec<ImportedVariable> {
+    let mut flat = Vec::new();
+    flatten("", env.get("data").unwrap_or(&Value::Null), &mut flat);
+    // Private environments are kept out of shared exports in Insomnia, so treat them as secrets.
+    let secret = env
+        .get("isPrivate")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    flat.into_iter()
+        .map(|(key, value)| ImportedVariable { key, value, secret })
+        .collect()
+}
+
+fn convert_workspace(
+    resources: &[Value],
+    workspace: &Value,
+    report: &mut ImportReport,
+) -> ImportedCollection {
+    let workspace_id = str_of(workspace, "_id");
+    let mut converter = Converter {
+        resources,
+        chains: HashMap::new(),
+        names: HashSet::new(),
+        report,
+    };
+
+    let base_env = converter
+        .children(workspace_id, &["environment"])
+        .first()
+        .copied();
+    let mut variables = base_env.map(environment_variables).unwrap_or_default();
+    converter
+        .names
+        .extend(variables.iter().map(|v| v.key.clone()));
+    let environments: Vec<ImportedEnvironment> = base_env
+        .map(|base| converter.children(str_of(base, "_id"), &["environment"]))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|env| {
+            // LiteFetch environments don't inherit, so sub-environments carry the base values.
+            let mut own = environment_variables(env);
+            for base in &variables {
+                if !own.iter().any(|v| v.key == base.key) {
+                    own.push(base.clone());
+                }
+            }
+            ImportedEnvironment {
+                name: match str_of(env, "name") {
+                    "" => "environment".to_string(),
+                    n => n.to_string(),
+                },
+                variables: own,
+            }
+        })
+        .collect();
+    for env in &environments {
+        converter
+            .names
+            .extend(env.variables.iter().map(|v| v.key.clone()));
+    }
+
+    for resource in resources
+        .iter()
+        .filter(|r| matches!(str_of(r, "_type"), "request" | "request_group"))
+    {
+        converter.scan(resource);
+    }
+    let mut folder_vars = Vec::new();
+    let items = converter.items(workspace_id, &ImportedAuth::None, &mut folder_vars);
+
+    if !folder_vars.is_empty() {
+        converter
+            .report
+            .unsupported("folder environments are merged into the default environment");
+    }
+    for (key, value) in folder_vars {
+        if !variables.iter().any(|v| v.key == key) {
+            variables.push(ImportedVariable {
+                key,
+                value,
+                secret: false,
+            });
+        }
+    }
+    ImportedCollection {
+        name: match str_of(workspace, "name") {
+            "" => "Imported Insomnia Workspace".to_string(),
+            n => n.to_string(),
+        },
+        items,
+        variables,
+        environments,
+    }
+}
+
+fn has_requests(items: &[ImportedItem]) -> bool {
+    items.iter().any(|item| match item {
+        ImportedItem::Request(_) => true,
+        ImportedItem::Folder { items, .. } => has_requests(items),
+    })
+}
+
+/// Returns one collection per workspace, each with its own report.
+pub fn parse(data: &Value) -> Result<Vec<(ImportedCollection, ImportReport)>, String> {
+    if str_of(data, "_type") != "export" {
+        return Err("not an Insomnia export".to_string());
+    }
+    let format = data
+        .get("__export_format")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    if format != 4 {
+        return Err(format!("unsupported Insomnia export format: {format}"));
+    }
+    let resources = array_of(data, "resources");
+    let mut shared = ImportReport::new("insomnia");
+    for resource in resources {
+        match str_of(resource, "_type") {
+            "grpc_request" => shared.unsupported("gRPC requests are not imported"),
+            "websocket_request" => shared.unsupported("WebSocket requests are not imported"),
+            "unit_test_suite" | "unit_test" => shared.unsupported("unit tests are not imported"),
+            "cookie_jar" if !array_of(resource, "cookies").is_empty() => {
+                shared.unsupported("cookie jars are not imported")
+            }
+            _ => {}
+        }
+    }
+    let collections: Vec<_> = resources
+        .iter()
+        .filter(|r| str_of(r, "_type") == "workspace")
+        .map(|workspace| {
+            let mut report = ImportReport::new("insomnia");
+            report.unsupported = shared.unsupported.clone();
+            let collection = convert_workspace(resources, workspace, &mut report);
+            (collection, report)
+        })
+        // Design-only or gRPC-only workspaces have nothing to create.
+        .filter(|(collection, _)| has_requests(&collection.items))
+        .collect();
+    if collections.is_empty() {
+        return Err("Insomnia export contained no requests".to_string());
+    }
+    Ok(collections)
+}
+
+/// Imports every workspace in an Insomnia export as its own collection.
+#[tauri::command]
+pub async fn import_insomnia_export(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<Vec<ImportResult>, String> {
+    let raw = super::read_source(&path)?;
+    let data: Value =
+        serde_json::from_str(&raw).map_err(|e| format!("Insomnia export parse failed: {e}"))?;
+    let mut results = Vec::new();
+    for (collection, report) in parse(&data)? {
+        results.push(super::save(&app, collection, report).await?);
+    }
+    Ok(results)
+}
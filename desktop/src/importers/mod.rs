@@ -0,0 +1,372 @@
+//! Importers for other API clients' export formats. Each format parses into the
+//! intermediate model from `litefetch_core::importers`, which `save` turns into a LiteFetch
+//! collection.
+
+pub mod bruno;
+pub mod curl;
+pub mod directory;
+pub mod dotenv;
+pub mod dropped;
+pub mod hoppscotch;
+pub mod insomnia;
+pub mod openapi;
+pub mod openapi_lint;
+pub mod postman;
+pub mod thunder;
+pub mod yaml;
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+pub use litefetch_core::importers::{
+    array_of, str_of, value_text, FormRow, ImportReport, ImportedAuth, ImportedBody,
+    ImportedCollection, ImportedEnvironment, ImportedExample, ImportedItem, ImportedRequest,
+    ImportedVariable,
+};
+
+const SUPPORTED_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+#[derive(Serialize)]
+pub struct ImportResult {
+    pub collection: Value,
+    pub report: ImportReport,
+}
+
+fn form_rows(rows: &[FormRow]) -> Value {
+    Value::Array(
+        rows.iter()
+            .map(|row| match &row.file_path {
+                Some(path) => json!({
+                    "key": row.key,
+                    "value": row.value,
+                    "type": "file",
+                    "file_path": path,
+                    "file_name": std::path::Path::new(path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                    "enabled": row.enabled,
+                }),
+                None => json!({
+                    "key": row.key,
+                    "value": row.value,
+                    "type": "text",
+                    "enabled": row.enabled,
+                }),
+            })
+            .collect(),
+    )
+}
+
+struct Builder<'a> {
+    report: &'a mut ImportReport,
+    history: Vec<Value>,
+    last_results: Map<String, Value>,
+}
+
+impl Builder<'_> {
+    fn item(&mut self, item: &ImportedItem) -> Value {
+        match item {
+            ImportedItem::Folder { name, items } => {
+                self.report.folders += 1;
+                json!({
+                    "id": uuid::Uuid::new_v4().to_string(),
+                    "name": name,
+                    "items": items.iter().map(|i| self.item(i)).collect::<Vec<_>>(),
+                })
+            }
+            ImportedItem::Request(request) => self.request(request),
+        }
+    }
+
+    fn request(&mut self, request: &ImportedRequest) -> Value {
+        self.report.requests += 1;
+        let id = uuid::Uuid::new_v4().to_string();
+        let mut method = request.method.to_uppercase();
+        if !SUPPORTED_METHODS.contains(&method.as_str()) {
+            self.report.unsupported(format!(
+                "{method} requests imported as GET ({})",
+                request.name
+            ));
+            method = "GET".to_string();
+        }
+        let headers: Map<String, Value> = request
+            .headers
+            .iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .collect();
+        let mut value = json!({
+            "id": id,
+            "name": request.name,
+            "method": method,
+            "url": request.url,
+            "headers": headers,
+            "body": Value::Null,
+            "body_mode": "raw",
+            "form_body": [],
+            "auth_type": "none",
+            "auth_params": {},
+            "extract_rules": request
+                .extract_rules
+                .iter()
+                .map(|(source, target)| json!({
+                    "id": uuid::Uuid::new_v4().to_string(),
+                    "source_path": source,
+                    "target_variable": target,
+                }))
+                .collect::<Vec<_>>(),
+        });
+        self.report.extract_rules += request.extract_rules.len();
+        if let Some(description) = request.description.as_deref().map(str::trim) {
+            if !description.is_empty() {
+                value["description"] = json!(description);
+            }
+        }
+        if !request.query_params.is_empty() {
+            value["query_params"] = form_rows(&request.query_params);
+        }
+        match &request.body {
+            ImportedBody::None => {}
+            ImportedBody::Raw { text, json } => {
+                value["body"] = json!(text);
+                if *json {
+                    value["body_mode"] = json!("json");
+                }
+            }
+            ImportedBody::UrlEncoded(rows) => {
+                value["body_mode"] = json!("form-urlencoded");
+                value["form_body"] = form_rows(rows);
+            }
+            ImportedBody::FormData(rows) => {
+                value["body_mode"] = json!("form-data");
+                value["form_body"] = form_rows(rows);
+            }
+            ImportedBody::Binary { file_path } => {
+                value["body_mode"] = json!("binary");
+                value["binary"] = json!({
+                    "file_path": file_path,
+                    "file_name": std::path::Path::new(file_path)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_default(),
+                });
+            }
+        }
+        match &request.auth {
+            ImportedAuth::Basic { username, password } => {
+                value["auth_type"] = json!("basic");
+                value["auth_params"] = json!({ "username": username, "password": password });
+            }
+            ImportedAuth::Bearer { token } => {
+                value["auth_type"] = json!("bearer");
+                value["auth_params"] = json!({ "token": token });
+            }
+            ImportedAuth::None => {}
+        }
+
+        for (index, example) in request.examples.iter().enumerate() {
+            self.report.examples += 1;
+            let parsed = example
+                .content_type
+                .as_deref()
+                .filter(|ct| ct.contains("json"))
+                .and_then(|_| serde_json::from_str::<Value>(&example.body).ok());
+            let result = json!({
+                "request_id": id,
+                "status_code": example.status,
+                "duration_ms": 0,
+                "headers": example
+                    .headers
+                    .iter()
+                    .map(|(k, v)| (k.to_lowercase(), Value::String(v.clone())))
+                    .collect::<Map<_, _>>(),
+                "body_is_json": parsed.is_some(),
+                "body": parsed.unwrap_or_else(|| Value::String(example.body.clone())),
+                "content_type": example.content_type,
+                "body_bytes": example.body.len(),
+            });
+            if index == 0 {
+                self.last_results.insert(id.clone(), result.clone());
+            }
+            self.history.push(result);
+        }
+        value
+    }
+}
+
+/// Converts a single request (e.g. from a pasted curl command) without creating a collection.
+pub fn request_json(request: &ImportedRequest, report: &mut ImportReport) -> Value {
+    let mut builder = Builder {
+        report,
+        history: Vec::new(),
+        last_results: Map::new(),
+    };
+    builder.request(request)
+}
+
+pub fn environment_entry(name: &str, variables: &[ImportedVariable]) -> Value {
+    json!({
+        "name": name,
+        "variables": variables
+            .iter()
+            .map(|v| {
+                // Secret values live in the keychain; the workspace only keeps the key.
+                let value = if v.secret { String::new() } else { v.value.clone() };
+                (v.key.clone(), Value::String(value))
+            })
+            .collect::<Map<_, _>>(),
+        "secrets": variables
+            .iter()
+            .filter(|v| v.secret)
+            .map(|v| (v.key.clone(), Value::Bool(true)))
+            .collect::<Map<_, _>>(),
+    })
+}
+
+/// Merges `variables` into the named entry of an environment file's `envs`, creating the
+/// environment if it does not exist yet.
+pub fn merge_environment(
+    envs: &mut Map<String, Value>,
+    name: &str,
+    variables: &[ImportedVariable],
+) {
+    let entry = envs
+        .entry(name.to_string())
+        .or_insert_with(|| environment_entry(name, &[]));
+    let incoming = environment_entry(name, variables);
+    for field in ["variables", "secrets"] {
+        match entry.get_mut(field).and_then(Value::as_object_mut) {
+            Some(existing) => {
+                if let Some(values) = incoming[field].as_object() {
+                    existing.extend(values.clone());
+                }
+            }
+            None => entry[field] = incoming[field].clone(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct EnvironmentImportResult {
+    /// Environments that received variables.
+    pub environments: Vec<String>,
+    pub report: ImportReport,
+}
+
+/// Creates a collection from the intermediate model. Collection-level variables become the
+/// `default` environment; imported environments are added alongside it.
+pub async fn save(
+    app: &tauri::AppHandle,
+    imported: ImportedCollection,
+    mut report: ImportReport,
+) -> Result<ImportResult, String> {
+    let mut builder = Builder {
+        report: &mut report,
+        history: Vec::new(),
+        last_results: Map::new(),
+    };
+    let items: Vec<Value> = imported
+        .items
+        .iter()
+        .map(|item| builder.item(item))
+        .collect();
+    let (history, last_results) = (builder.history, builder.last_results);
+    if report.requests == 0 {
+        return Err(format!("{} export contained no requests", report.source));
+    }
+
+    let mut envs = Map::new();
+    envs.insert(
+        "default".to_string(),
+        environment_entry("default", &imported.variables),
+    );
+    // Without collection variables, an imported environment is the more useful default.
+    let active_env = match imported.environments.first() {
+        Some(env) if imported.variables.is_empty() => env.name.clone(),
+        _ => "default".to_string(),
+    };
+    for env in &imported.environments {
+        envs.insert(
+            env.name.clone(),
+            environment_entry(&env.name, &env.variables),
+        );
+    }
+    report.variables = imported.variables.len();
+    report.environments = imported.environments.len();
+
+    let meta = crate::create_collection_with(
+        app,
+        json!({
+            "name": imported.name,
+            "collection": { "name": imported.name, "items": items },
+            "environment": { "active_env": active_env, "envs": envs },
+            "history": history,
+            "last_results": last_results,
+        }),
+    )
+    .await?;
+    let collection_id = meta.get("id").and_then(Value::as_str).unwrap_or_default();
+    store_secrets(collection_id, "default", &imported.variables, &mut report);
+    for env in &imported.environments {
+        store_secrets(collection_id, &env.name, &env.variables, &mut report);
+    }
+    Ok(ImportResult {
+        collection: meta,
+        report,
+    })
+}
+
+/// Moves secret-flagged values into the keychain. Failures are reported rather than aborting,
+/// since the collection already exists by the time secrets are stored.
+pub fn store_secrets(
+    collection_id: &str,
+    env_name: &str,
+    variables: &[ImportedVariable],
+    report: &mut ImportReport,
+) {
+    for variable in variables.iter().filter(|v| v.secret && !v.value.is_empty()) {
+        match crate::secrets::store(collection_id, env_name, &variable.key, &variable.value) {
+            Ok(()) => report.secrets += 1,
+            Err(e) => report.unsupported(format!(
+                "secret {} in {env_name} was not imported: {e}",
+                variable.key
+            )),
+        }
+    }
+}
+
+pub fn read_source(path: &str) -> Result<String, String> {
+    std::fs::read_to_string(crate::normalize_path(path))
+        .map(|raw| raw.trim_start_matches('\u{feff}').to_string())
+        .map_err(|e| format!("import read failed: {e}"))
+}
+
+/// A request or folder name made safe to use as a file name.
+pub fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| {
+            if matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    match stem.trim() {
+        "" => "untitled".to_string(),
+        s => s.to_string(),
+    }
+}
+
+/// Picks a file or directory name that isn't taken yet within one parent.
+pub fn unique(taken: &mut Vec<String>, stem: String) -> String {
+    let mut name = stem.clone();
+    let mut suffix = 2;
+    while taken.iter().any(|t| t.eq_ignore_ascii_case(&name)) {
+        name = format!("{stem} {suffix}");
+        suffix += 1;
+    }
+    taken.push(name.clone());
+    name
+}
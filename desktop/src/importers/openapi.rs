@@ -0,0 +1,464 @@
+//! OpenAPI 3.0/3.1 and Swagger 2.0 import, parsed by `litefetch_core::importers::openapi`.
+//! Export goes the other way, inferring an OpenAPI 3.1 document from saved requests and their
+//! history. Imports are linted too (see `openapi_lint`), with the findings in the report's
+//! warnings.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+use litefetch_core::importers::openapi::parse;
+
+use super::{array_of, str_of, value_text, ImportReport, ImportResult};
+
+async fn load_source(source: &str) -> Result<String, String> {
+    let source = source.trim();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = reqwest::get(source)
+            .await
+            .map_err(|e| format!("OpenAPI fetch failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("OpenAPI fetch failed: HTTP {}", response.status()));
+        }
+        return response
+            .text()
+            .await
+            .map_err(|e| format!("OpenAPI fetch failed: {e}"));
+    }
+    super::read_source(source)
+}
+
+fn parse_document(raw: &str) -> Result<Value, String> {
+    if raw.trim_start().starts_with('{') {
+        serde_json::from_str(raw).map_err(|e| format!("OpenAPI JSON parse failed: {e}"))
+    } else {
+        serde_yaml::from_str(raw).map_err(|e| format!("OpenAPI YAML parse failed: {e}"))
+    }
+}
+
+/// Imports an OpenAPI/Swagger document from a local path or an http(s) URL.
+#[tauri::command]
+pub async fn import_openapi(
+    app: tauri::AppHandle,
+    source: String,
+    collection_name: Option<String>,
+) -> Result<ImportResult, String> {
+    let raw = load_source(&source).await?;
+    let doc = parse_document(&raw)?;
+    let mut report = ImportReport::new("openapi");
+    let mut imported = parse(&doc, &mut report)?;
+    super::openapi_lint::lint(&doc, &mut report);
+    if let Some(name) = collection_name.filter(|n| !n.trim().is_empty()) {
+        imported.name = name;
+    }
+    let result = super::save(&app, imported, report).await?;
+    // Kept for mocks, validation and contract tests, which need the schemas.
+    let collection_id = str_of(&result.collection, "id");
+    if let Err(e) = save_spec(&app, collection_id, &doc) {
+        tracing::warn!("{e}");
+    }
+    Ok(result)
+}
+
+fn spec_path(app: &tauri::AppHandle, collection_id: &str) -> Result<std::path::PathBuf, String> {
+    Ok(crate::workspace_state_dir(app, "openapi")?.join(format!("{collection_id}.json")))
+}
+
+fn save_spec(app: &tauri::AppHandle, collection_id: &str, doc: &Value) -> Result<(), String> {
+    let payload = serde_json::to_string(doc).map_err(|e| format!("spec serialize failed: {e}"))?;
+    std::fs::write(spec_path(app, collection_id)?, payload)
+        .map_err(|e| format!("spec persist failed: {e}"))
+}
+
+/// The spec kept for a collection imported from OpenAPI; `None` for any other collection.
+pub fn imported_spec(app: &tauri::AppHandle, collection_id: &str) -> Result<Option<Value>, String> {
+    let is_id = !collection_id.is_empty()
+        && collection_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !is_id {
+        return Ok(None);
+    }
+    let path = spec_path(app, collection_id)?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = std::fs::read_to_string(&path).map_err(|e| format!("spec read failed: {e}"))?;
+    serde_json::from_str(&data)
+        .map(Some)
+        .map_err(|e| format!("spec parse failed: {e}"))
+}
+
+/// Loads a spec: the one kept for a collection imported from OpenAPI, when `spec` is its
+/// id, otherwise a document at a local path or URL.
+pub async fn load_spec(app: &tauri::AppHandle, spec: &str) -> Result<Value, String> {
+    let spec = spec.trim();
+    match imported_spec(app, spec)? {
+        Some(doc) => Ok(doc),
+        None => parse_document(&load_source(spec).await?),
+    }
+}
+
+// --- Export ---
+
+/// Infers a JSON Schema from an observed value.
+fn schema_of(value: &Value) -> Value {
+    match value {
+        Value::Null => json!({ "type": "null" }),
+        Value::Bool(_) => json!({ "type": "boolean" }),
+        Value::Number(n) if n.is_i64() || n.is_u64() => json!({ "type": "integer" }),
+        Value::Number(_) => json!({ "type": "number" }),
+        Value::String(_) => json!({ "type": "string" }),
+        Value::Array(items) => match items.first() {
+            Some(first) => json!({ "type": "array", "items": schema_of(first) }),
+            None => json!({ "type": "array", "items": {} }),
+        },
+        Value::Object(fields) => json!({
+            "type": "object",
+            "properties": fields
+                .iter()
+                .map(|(k, v)| (k.clone(), schema_of(v)))
+                .collect::<Map<_, _>>(),
+        }),
+    }
+}
+
+/// Splits a request URL into a server and a templated path. A leading `{{var}}` becomes a
+/// server variable; `{{name}}` path segments become path parameters.
+fn split_url(url: &str) -> (String, String, Vec<(String, String)>) {
+    let (url, query) = match url.split_once('?') {
+        Some((base, query)) => (base, query),
+        None => (url, ""),
+    };
+    let (server, path) = if let Some(rest) = url.strip_prefix("{{") {
+        match rest.split_once("}}") {
+            Some((var, path)) => (format!("{{{}}}", var.trim()), path.to_string()),
+            None => (String::new(), url.to_string()),
+        }
+    } else if let Some(scheme_end) = url.find("://") {
+        let host_end = url[scheme_end + 3..]
+            .find('/')
+            .map(|i| i + scheme_end + 3)
+            .unwrap_or(url.len());
+        (url[..host_end].to_string(), url[host_end..].to_string())
+    } else {
+        (String::new(), url.to_string())
+    };
+    let path = path
+        .split('/')
+        .map(|segment| {
+            match segment
+                .strip_prefix("{{")
+                .and_then(|s| s.strip_suffix("}}"))
+            {
+                Some(var) => format!("{{{}}}", var.trim()),
+                None => segment.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    let path = if path.starts_with('/') {
+        path
+    } else {
+        format!("/{path}")
+    };
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (k.to_string(), v.to_string())
+        })
+        .collect();
+    (server, path, query)
+}
+
+fn collect_requests<'a>(
+    items: &'a [Value],
+    tag: Option<&'a str>,
+    out: &mut Vec<(Option<&'a str>, &'a Value)>,
+) {
+    for item in items {
+        match item.get("items").and_then(Value::as_array) {
+            // Top-level folders become tags; nested folders keep their parent's tag.
+            Some(children) => collect_requests(children, tag.or(Some(str_of(item, "name"))), out),
+            None => out.push((tag, item)),
+        }
+    }
+}
+
+fn request_body_of(request: &Value) -> Option<Value> {
+    let form_schema = |binary: bool| {
+        let properties: Map<String, Value> = array_of(request, "form_body")
+            .iter()
+            .filter(|row| row.get("enabled").and_then(Value::as_bool).unwrap_or(true))
+            .map(|row| {
+                let is_file = matches!(str_of(row, "type"), "file" | "binary");
+                let schema = if is_file && binary {
+                    json!({ "type": "string", "format": "binary" })
+                } else {
+                    json!({ "type": "string", "example": value_text(row.get("value")) })
+                };
+                (str_of(row, "key").to_string(), schema)
+            })
+            .collect();
+        json!({ "type": "object", "properties": properties })
+    };
+    let (mime, media) = match str_of(request, "body_mode") {
+        "form-urlencoded" => (
+            "application/x-www-form-urlencoded",
+            json!({ "schema": form_schema(false) }),
+        ),
+        "form-data" => (
+            "multipart/form-data",
+            json!({ "schema": form_schema(true) }),
+        ),
+        "binary" => (
+            "application/octet-stream",
+            json!({ "schema": { "type": "string", "format": "binary" } }),
+        ),
+        mode => {
+            let body = match request.get("body") {
+                None | Some(Value::Null) => return None,
+                Some(Value::String(text)) if text.trim().is_empty() => return None,
+                Some(Value::String(text)) => serde_json::from_str::<Value>(text)
+                    .ok()
+                    .filter(|_| mode == "json")
+                    .unwrap_or_else(|| Value::String(text.clone())),
+                Some(other) => other.clone(),
+            };
+            if mode == "json" || !body.is_string() {
+                (
+                    "application/json",
+                    json!({ "schema": schema_of(&body), "example": body }),
+                )
+            } else {
+                (
+                    "text/plain",
+                    json!({ "schema": { "type": "string" }, "example": body }),
+                )
+            }
+        }
+    };
+    Some(json!({ "content": { mime: media } }))
+}
+
+fn responses_of(results: &[&Value]) -> Value {
+    let mut responses = Map::new();
+    // History is chronological; the latest response per status wins.
+    for result in results.iter().rev() {
+        let Some(status) = result.get("status_code").and_then(Value::as_u64) else {
+            continue;
+        };
+        let key = status.to_string();
+        if responses.contains_key(&key) {
+            continue;
+        }
+        let description = u16::try_from(status)
+            .ok()
+            .and_then(|s| http::StatusCode::from_u16(s).ok())
+            .and_then(|s| s.canonical_reason())
+            .unwrap_or("Response");
+        let mut response = json!({ "description": description });
+        let content_type = str_of(result, "content_type")
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        if let Some(body) = result.get("body").filter(|b| !b.is_null() && *b != "") {
+            let content_type = match content_type.as_str() {
+                "" if body.is_string() => "text/plain".to_string(),
+                "" => "application/json".to_string(),
+                ct => ct.to_string(),
+            };
+            response["content"] = json!({
+                content_type: { "schema": schema_of(body), "example": body }
+            });
+        }
+        responses.insert(key, response);
+    }
+    if responses.is_empty() {
+        responses.insert("default".to_string(), json!({ "description": "Response" }));
+    }
+    Value::Object(responses)
+}
+
+#[derive(Serialize)]
+pub struct OpenApiExport {
+    pub document: String,
+    pub operations: usize,
+    pub path: Option<String>,
+}
+
+/// Generates an OpenAPI 3.1 document from a collection's requests and recorded history.
+/// `format` is `json` (default) or `yaml`; the document is written when `output_path` is set.
+/// Secret values in examples are masked unless `include_secrets` carries a confirmed grant.
+#[tauri::command]
+pub async fn export_openapi(
+    app: tauri::AppHandle,
+    collection_id: String,
+    format: Option<String>,
+    output_path: Option<String>,
+    include_secrets: Option<String>,
+) -> Result<OpenApiExport, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let history =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/history")).await?;
+    let environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    let redactor = crate::redact::for_export(
+        &app,
+        &collection_id,
+        &collection,
+        &environment,
+        include_secrets.as_deref(),
+    )
+    .await?;
+    let active = str_of(&environment, "active_env");
+    let env_vars = environment
+        .pointer(&format!(
+            "/envs/{}/variables",
+            active.replace('~', "~0").replace('/', "~1")
+        ))
+        .cloned()
+        .unwrap_or(Value::Null);
+
+    let mut requests = Vec::new();
+    collect_requests(array_of(&collection, "items"), None, &mut requests);
+    let mut paths: Map<String, Value> = Map::new();
+    let mut servers: Vec<String> = Vec::new();
+    let mut tags: Vec<&str> = Vec::new();
+    let mut schemes = Map::new();
+    let mut operations = 0;
+    for (tag, request) in &requests {
+        let (server, path, url_query) = split_url(str_of(request, "url"));
+        if !server.is_empty() && !servers.contains(&server) {
+            servers.push(server);
+        }
+        let method = str_of(request, "method").to_lowercase();
+        let entry = paths.entry(path.clone()).or_insert_with(|| json!({}));
+        if entry.get(&method).is_some() {
+            continue;
+        }
+
+        let mut parameters: Vec<Value> = path
+            .split('/')
+            .filter_map(|s| s.strip_prefix('{').and_then(|s| s.strip_suffix('}')))
+            .map(|name| json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } }))
+            .collect();
+        let query_rows: Vec<(String, String)> =
+            match request.get("query_params").and_then(Value::as_array) {
+                Some(rows) => rows
+                    .iter()
+                    .map(|r| (str_of(r, "key").to_string(), value_text(r.get("value"))))
+                    .collect(),
+                None => url_query,
+            };
+        for (name, example) in query_rows.into_iter().filter(|(k, _)| !k.is_empty()) {
+            parameters.push(json!({ "name": name, "in": "query", "schema": { "type": "string" }, "example": example }));
+        }
+        for (name, value) in request
+            .get("headers")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            if matches!(
+                name.to_ascii_lowercase().as_str(),
+                "content-type" | "authorization" | "accept"
+            ) {
+                continue;
+            }
+            parameters.push(json!({ "name": name, "in": "header", "schema": { "type": "string" }, "example": value }));
+        }
+
+        let results: Vec<&Value> = history
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter(|r| str_of(r, "request_id") == str_of(request, "id"))
+            .collect();
+        let mut operation = json!({
+            "summary": str_of(request, "name"),
+            "operationId": str_of(request, "id"),
+            "responses": responses_of(&results),
+        });
+        if let Some(description) = request
+            .get("description")
+            .and_then(Value::as_str)
+            .filter(|d| !d.trim().is_empty())
+        {
+            operation["description"] = json!(description);
+        }
+        if !parameters.is_empty() {
+            operation["parameters"] = Value::Array(parameters);
+        }
+        if let Some(body) = request_body_of(request) {
+            operation["requestBody"] = body;
+        }
+        if let Some(tag) = tag {
+            operation["tags"] = json!([tag]);
+            if !tags.contains(tag) {
+                tags.push(tag);
+            }
+        }
+        let scheme = match str_of(request, "auth_type") {
+            "basic" => Some(("basicAuth", json!({ "type": "http", "scheme": "basic" }))),
+            "bearer" => Some(("bearerAuth", json!({ "type": "http", "scheme": "bearer" }))),
+            _ => None,
+        };
+        if let Some((name, definition)) = scheme {
+            schemes.insert(name.to_string(), definition);
+            operation["security"] = json!([{ name: [] }]);
+        }
+        entry[method.as_str()] = operation;
+        operations += 1;
+    }
+
+    let servers: Vec<Value> = servers
+        .iter()
+        .map(
+            |server| match server.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                // A variable server keeps the active environment's value as its default.
+                Some(var) => json!({
+                    "url": server,
+                    "variables": { var: { "default": value_text(env_vars.get(var)) } },
+                }),
+                None => json!({ "url": server }),
+            },
+        )
+        .collect();
+    let mut document = json!({
+        "openapi": "3.1.0",
+        "info": { "title": str_of(&collection, "name"), "version": "1.0.0" },
+        "servers": servers,
+        "tags": tags.iter().map(|t| json!({ "name": t })).collect::<Vec<_>>(),
+        "paths": paths,
+    });
+    if !schemes.is_empty() {
+        document["components"] = json!({ "securitySchemes": schemes });
+    }
+    redactor.value(&mut document);
+
+    let text = match format.as_deref() {
+        Some("yaml") | Some("yml") => {
+            serde_yaml::to_string(&document).map_err(|e| format!("OpenAPI export failed: {e}"))?
+        }
+        _ => serde_json::to_string_pretty(&document)
+            .map_err(|e| format!("OpenAPI export failed: {e}"))?,
+    };
+    let path = match output_path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => {
+            let path = crate::normalize_path(path.trim());
+            std::fs::write(&path, &text).map_err(|e| format!("OpenAPI export failed: {e}"))?;
+            Some(path.to_string_lossy().to_string())
+        }
+        None => None,
+    };
+    Ok(OpenApiExport {
+        document: text,
+        operations,
+        path,
+    })
+}
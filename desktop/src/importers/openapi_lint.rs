@@ -0,0 +1,178 @@
+//! A linting pass over OpenAPI/Swagger documents at import, explaining operations that came
+//! out incomplete: missing or duplicate operationIds, paths that collide once their
+//! parameter names are ignored, path parameters without a definition, and `$ref`s or
+//! security schemes that point nowhere.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+use super::{array_of, str_of, ImportReport};
+
+const OPERATIONS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// `/a/b~c` as a JSON pointer segment (`a~1b~0c`).
+fn escape(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+/// `/users/{id}` and `/users/{userId}` are the same path to a router.
+fn template(path: &str) -> String {
+    let mut out = String::new();
+    let mut in_param = false;
+    for c in path.trim_end_matches('/').chars() {
+        match c {
+            '{' => {
+                in_param = true;
+                out.push_str("{}");
+            }
+            '}' => in_param = false,
+            _ if !in_param => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn path_params(path: &str) -> Vec<&str> {
+    path.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+        .collect()
+}
+
+fn check_refs(doc: &Value, value: &Value, location: &str, report: &mut ImportReport) {
+    match value {
+        Value::Object(fields) => {
+            if let Some(reference) = fields.get("$ref").and_then(Value::as_str) {
+                match reference.strip_prefix('#') {
+                    None => report.warn(
+                        "external_ref",
+                        location,
+                        format!("{reference} is external and isn't resolved"),
+                    ),
+                    Some(pointer) if doc.pointer(pointer).is_none() => {
+                        let schema = pointer.starts_with("/components/schemas/")
+                            || pointer.starts_with("/definitions/");
+                        match schema {
+                            true => report.warn(
+                                "undefined_schema",
+                                location,
+                                format!("schema {reference} isn't defined"),
+                            ),
+                            false => report.warn(
+                                "unresolved_ref",
+                                location,
+                                format!("{reference} doesn't resolve"),
+                            ),
+                        }
+                    }
+                    Some(_) => {}
+                }
+            }
+            for (key, child) in fields {
+                check_refs(doc, child, &format!("{location}/{}", escape(key)), report);
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                check_refs(doc, child, &format!("{location}/{index}"), report);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The names (and locations) an operation's parameters declare, following local `$ref`s.
+fn declared<'a>(doc: &'a Value, params: &'a [Value]) -> Vec<(&'a str, &'a str)> {
+    params
+        .iter()
+        .map(|param| match param.get("$ref").and_then(Value::as_str) {
+            Some(reference) => reference
+                .strip_prefix('#')
+                .and_then(|p| doc.pointer(p))
+                .unwrap_or(param),
+            None => param,
+        })
+        .map(|param| (str_of(param, "name"), str_of(param, "in")))
+        .collect()
+}
+
+fn check_security(doc: &Value, security: &[Value], location: &str, report: &mut ImportReport) {
+    let schemes = doc
+        .pointer("/components/securitySchemes")
+        .or_else(|| doc.get("securityDefinitions"));
+    for requirement in security.iter().filter_map(Value::as_object) {
+        for name in requirement.keys() {
+            if schemes.and_then(|s| s.get(name)).is_none() {
+                report.warn(
+                    "undefined_security_scheme",
+                    location,
+                    format!("security scheme {name} isn't defined"),
+                );
+            }
+        }
+    }
+}
+
+/// Adds the document's lint warnings to `report`.
+pub fn lint(doc: &Value, report: &mut ImportReport) {
+    check_refs(doc, doc, "", report);
+    check_security(doc, array_of(doc, "security"), "security", report);
+
+    let mut templates: HashMap<String, &str> = HashMap::new();
+    let mut operation_ids: HashMap<&str, String> = HashMap::new();
+    let paths = doc.get("paths").and_then(Value::as_object);
+    for (path, item) in paths.into_iter().flatten() {
+        match templates.get(&template(path)) {
+            Some(first) => report.warn(
+                "duplicate_path",
+                path,
+                format!("{path} matches the same requests as {first}"),
+            ),
+            None => {
+                templates.insert(template(path), path);
+            }
+        }
+        for method in OPERATIONS {
+            let Some(operation) = item.get(*method) else {
+                continue;
+            };
+            let location = format!("{} {path}", method.to_uppercase());
+            match str_of(operation, "operationId") {
+                "" => report.warn(
+                    "missing_operation_id",
+                    &location,
+                    "operation has no operationId",
+                ),
+                id => match operation_ids.get(id) {
+                    Some(first) => report.warn(
+                        "duplicate_operation_id",
+                        &location,
+                        format!("operationId {id} is also used by {first}"),
+                    ),
+                    None => {
+                        operation_ids.insert(id, location.clone());
+                    }
+                },
+            }
+            let params: Vec<(&str, &str)> = declared(doc, array_of(operation, "parameters"))
+                .into_iter()
+                .chain(declared(doc, array_of(item, "parameters")))
+                .collect();
+            for name in path_params(path) {
+                if !params.contains(&(name, "path")) {
+                    report.warn(
+                        "missing_path_parameter",
+                        &location,
+                        format!("path parameter {name} isn't declared"),
+                    );
+                }
+            }
+            if let Some(security) = operation.get("security").and_then(Value::as_array) {
+                check_security(doc, security, &location, report);
+            }
+        }
+    }
+}
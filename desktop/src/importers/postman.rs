@@ -0,0 +1,88 @@
+//! Postman collection and environment import commands; the parsing is in
+//! `litefetch_core::importers::postman`.
+
+use serde_json::Value;
+
+use litefetch_core::importers::postman::{parse, parse_environment};
+
+use super::{EnvironmentImportResult, ImportReport, ImportResult, ImportedVariable};
+
+#[tauri::command]
+pub async fn import_postman_collection(
+    app: tauri::AppHandle,
+    path: String,
+    collection_name: Option<String>,
+) -> Result<ImportResult, String> {
+    let raw = super::read_source(&path)?;
+    let data: Value =
+        serde_json::from_str(&raw).map_err(|e| format!("Postman collection parse failed: {e}"))?;
+    let mut report = ImportReport::new("postman");
+    let mut imported = parse(&data, &mut report)?;
+    if let Some(name) = collection_name.filter(|n| !n.trim().is_empty()) {
+        imported.name = name;
+    }
+    super::save(&app, imported, report).await
+}
+
+/// Imports a Postman environment or globals export into an existing collection. Environments
+/// are merged into the same-named LiteFetch environment; globals fill every environment
+/// without overriding its own values, matching Postman's precedence.
+#[tauri::command]
+pub async fn import_postman_environment(
+    app: tauri::AppHandle,
+    collection_id: String,
+    path: String,
+    env_name: Option<String>,
+    activate: Option<bool>,
+) -> Result<EnvironmentImportResult, String> {
+    let raw = super::read_source(&path)?;
+    let data: Value =
+        serde_json::from_str(&raw).map_err(|e| format!("Postman environment parse failed: {e}"))?;
+    let (mut imported, globals) = parse_environment(&data)?;
+    if let Some(name) = env_name.filter(|n| !n.trim().is_empty()) {
+        imported.name = name;
+    }
+    let mut report = ImportReport::new("postman");
+    report.variables = imported.variables.len();
+
+    let env_path = format!("/collections/{collection_id}/environment");
+    let mut environment = crate::backend_get(&app, &env_path).await?;
+    let envs = environment
+        .get_mut("envs")
+        .and_then(Value::as_object_mut)
+        .ok_or("collection environment file is malformed")?;
+    let targets: Vec<String> = if globals {
+        envs.keys().cloned().collect()
+    } else {
+        vec![imported.name.clone()]
+    };
+    let mut applied = Vec::new();
+    for target in &targets {
+        let has_key = |key: &str| {
+            envs.get(target)
+                .and_then(|entry| entry.get("variables"))
+                .is_some_and(|vars| vars.get(key).is_some())
+        };
+        let variables: Vec<ImportedVariable> = imported
+            .variables
+            .iter()
+            .filter(|v| !globals || !has_key(&v.key))
+            .cloned()
+            .collect();
+        super::merge_environment(envs, target, &variables);
+        applied.push(variables);
+    }
+    if activate.unwrap_or(false) && !globals {
+        environment["active_env"] = Value::String(imported.name.clone());
+    }
+    crate::import_post(&app, &env_path, &environment).await?;
+
+    for (target, variables) in targets.iter().zip(&applied) {
+        super::store_secrets(&collection_id, target, variables, &mut report);
+    }
+    report.environments = targets.len();
+    Ok(EnvironmentImportResult {
+        environments: targets,
+        report,
+    })
+}
@@ -0,0 +1,307 @@
+//! Thunder Client collection export import (`thunder-collection_*.json`), with an optional
+//! environment export alongside it.
+
+use serde_json::{json, Value};
+
+use super::{
+    array_of, str_of, value_text, FormRow, ImportReport, ImportResult, ImportedAuth, ImportedBody,
+    ImportedCollection, ImportedEnvironment, ImportedItem, ImportedRequest, ImportedVariable,
+};
+
+fn sort_num(resource: &Value) -> f64 {
+    resource
+        .get("sortNum")
+        .and_then(Value::as_f64)
+        .unwrap_or(0.0)
+}
+
+fn active(row: &Value) -> bool {
+    !row.get("isDisabled")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+fn auth_of(
+    auth: Option<&Value>,
+    parent: &ImportedAuth,
+    request: &mut ImportedRequest,
+    report: &mut ImportReport,
+) -> ImportedAuth {
+    let Some(auth) = auth.filter(|a| a.is_object()) else {
+        return parent.clone();
+    };
+    match str_of(auth, "type") {
+        "" | "inherit" => parent.clone(),
+        "none" => ImportedAuth::None,
+        "basic" => {
+            let basic = auth.get("basic").cloned().unwrap_or(Value::Null);
+            ImportedAuth::Basic {
+                username: str_of(&basic, "username").to_string(),
+                password: str_of(&basic, "password").to_string(),
+            }
+        }
+        "bearer" => {
+            let token = str_of(auth, "bearer").to_string();
+            match str_of(auth, "bearerPrefix") {
+                "" | "Bearer" => ImportedAuth::Bearer { token },
+                prefix => {
+                    request
+                        .headers
+                        .push(("Authorization".to_string(), format!("{prefix} {token}")));
+                    ImportedAuth::None
+                }
+            }
+        }
+        other => {
+            report.unsupported(format!(
+                "{other} auth is not supported; requests imported without auth"
+            ));
+            ImportedAuth::None
+        }
+    }
+}
+
+fn body_of(body: Option<&Value>, report: &mut ImportReport) -> ImportedBody {
+    let Some(body) = body.filter(|b| b.is_object()) else {
+        return ImportedBody::None;
+    };
+    let raw = |json: bool| match str_of(body, "raw") {
+        "" => ImportedBody::None,
+        text => ImportedBody::Raw {
+            text: text.to_string(),
+            json,
+        },
+    };
+    let text_rows = |key: &str| -> Vec<FormRow> {
+        array_of(body, key)
+            .iter()
+            .map(|row| {
+                FormRow::text(
+                    str_of(row, "name"),
+                    value_text(row.get("value")),
+                    active(row),
+                )
+            })
+            .collect()
+    };
+    match str_of(body, "type") {
+        "json" => raw(true),
+        "text" | "xml" => raw(false),
+        "formencoded" => ImportedBody::UrlEncoded(text_rows("form")),
+        "formdata" => {
+            let mut rows = text_rows("form");
+            rows.extend(array_of(body, "files").iter().map(|file| {
+                let path = str_of(file, "value").to_string();
+                FormRow {
+                    key: str_of(file, "name").to_string(),
+                    value: path.clone(),
+                    enabled: active(file),
+                    file_path: Some(path),
+                }
+            }));
+            ImportedBody::FormData(rows)
+        }
+        "binary" => match str_of(body, "binary") {
+            "" => ImportedBody::None,
+            path => ImportedBody::Binary {
+                file_path: path.to_string(),
+            },
+        },
+        "graphql" => {
+            report.unsupported("GraphQL bodies are imported as raw JSON payloads");
+            let graphql = body.get("graphql").cloned().unwrap_or(Value::Null);
+            let variables = match graphql.get("variables") {
+                Some(Value::String(text)) => serde_json::from_str(text).unwrap_or(Value::Null),
+                Some(other) => other.clone(),
+                None => Value::Null,
+            };
+            let payload = json!({ "query": str_of(&graphql, "query"), "variables": variables });
+            ImportedBody::Raw {
+                text: serde_json::to_string_pretty(&payload).unwrap_or_default(),
+                json: true,
+            }
+        }
+        _ => ImportedBody::None,
+    }
+}
+
+/// `set-env-var` tests copy a response value into a variable, which is an extraction rule.
+fn extract_rules(tests: &[Value], report: &mut ImportReport) -> Vec<(String, String)> {
+    let mut rules = Vec::new();
+    for test in tests {
+        let target = str_of(test, "value")
+            .trim()
+            .trim_start_matches("{{")
+            .trim_end_matches("}}")
+            .trim();
+        let source = str_of(test, "custom");
+        let path = source
+            .strip_prefix("json.")
+            .or_else(|| source.strip_prefix("json"))
+            .map(|p| p.trim_start_matches('.'));
+        match (str_of(test, "type"), path) {
+            ("set-env-var", Some(path)) if !path.is_empty() && !target.is_empty() => {
+                rules.push((path.to_string(), target.to_string()))
+            }
+            ("set-env-var", _) => {
+                report.unsupported("set-env-var tests on headers or cookies are not imported")
+            }
+            _ => report.unsupported("Thunder Client assertions are not imported"),
+        }
+    }
+    rules
+}
+
+fn request_of(resource: &Value, auth: &ImportedAuth, report: &mut ImportReport) -> ImportedRequest {
+    let name = match str_of(resource, "name") {
+        "" => "Request",
+        n => n,
+    };
+    let mut url = str_of(resource, "url").to_string();
+    let params = array_of(resource, "params");
+    for param in params
+        .iter()
+        .filter(|p| p.get("isPath") == Some(&Value::Bool(true)))
+    {
+        let key = str_of(param, "name");
+        url = url.replace(
+            &format!("/:{key}"),
+            &format!("/{}", value_text(param.get("value"))),
+        );
+    }
+    let method = match str_of(resource, "method") {
+        "" => "GET",
+        m => m,
+    };
+    let mut request = ImportedRequest::new(name, method, url);
+    request.query_params = params
+        .iter()
+        .filter(|p| p.get("isPath") != Some(&Value::Bool(true)) && !str_of(p, "name").is_empty())
+        .map(|p| FormRow::text(str_of(p, "name"), value_text(p.get("value")), active(p)))
+        .collect();
+    for header in array_of(resource, "headers") {
+        if active(header) && !str_of(header, "name").is_empty() {
+            request.headers.push((
+                str_of(header, "name").to_string(),
+                value_text(header.get("value")),
+            ));
+        }
+    }
+    request.body = body_of(resource.get("body"), report);
+    request.auth = auth_of(resource.get("auth"), auth, &mut request, report);
+    request.extract_rules = extract_rules(array_of(resource, "tests"), report);
+    if resource
+        .get("preReq")
+        .is_some_and(|p| p.as_object().is_some_and(|o| !o.is_empty()))
+    {
+        report.unsupported("pre-request actions are not imported");
+    }
+    request
+}
+
+fn items_of(
+    data: &Value,
+    container_id: &str,
+    auth: &ImportedAuth,
+    report: &mut ImportReport,
+) -> Vec<ImportedItem> {
+    let mut entries: Vec<(f64, ImportedItem)> = Vec::new();
+    for folder in array_of(data, "folders")
+        .iter()
+        .filter(|f| str_of(f, "containerId") == container_id)
+    {
+        let mut scratch = ImportedRequest::new("", "GET", "");
+        let folder_auth = auth_of(
+            folder.get("settings").and_then(|s| s.get("auth")),
+            auth,
+            &mut scratch,
+            report,
+        );
+        entries.push((
+            sort_num(folder),
+            ImportedItem::Folder {
+                name: str_of(folder, "name").to_string(),
+                items: items_of(data, str_of(folder, "_id"), &folder_auth, report),
+            },
+        ));
+    }
+    for resource in array_of(data, "requests")
+        .iter()
+        .filter(|r| str_of(r, "containerId") == container_id)
+    {
+        entries.push((
+            sort_num(resource),
+            ImportedItem::Request(Box::new(request_of(resource, auth, report))),
+        ));
+    }
+    entries.sort_by(|a, b| a.0.total_cmp(&b.0));
+    entries.into_iter().map(|(_, item)| item).collect()
+}
+
+pub fn parse(data: &Value, report: &mut ImportReport) -> Result<ImportedCollection, String> {
+    if data.get("requests").and_then(Value::as_array).is_none() {
+        return Err("not a Thunder Client collection export".to_string());
+    }
+    let mut scratch = ImportedRequest::new("", "GET", "");
+    let auth = auth_of(
+        data.get("settings").and_then(|s| s.get("auth")),
+        &ImportedAuth::None,
+        &mut scratch,
+        report,
+    );
+    Ok(ImportedCollection {
+        name: match str_of(data, "collectionName") {
+            "" => "Imported Thunder Client Collection".to_string(),
+            n => n.to_string(),
+        },
+        items: items_of(data, "", &auth, report),
+        variables: Vec::new(),
+        environments: Vec::new(),
+    })
+}
+
+/// Environment exports are a single `{environmentName, data}` object or a list of them.
+fn parse_environments(data: &Value) -> Vec<ImportedEnvironment> {
+    let envs = match data {
+        Value::Array(envs) => envs.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+    envs.iter()
+        .filter(|env| env.get("data").is_some_and(Value::is_array))
+        .map(|env| ImportedEnvironment {
+            name: match (str_of(env, "environmentName"), str_of(env, "name")) {
+                ("", "") => "thunder".to_string(),
+                ("", n) | (n, _) => n.to_string(),
+            },
+            variables: array_of(env, "data")
+                .iter()
+                .filter(|v| !str_of(v, "name").is_empty())
+                .map(|v| ImportedVariable {
+                    key: str_of(v, "name").to_string(),
+                    value: value_text(v.get("value")),
+                    secret: false,
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn import_thunder_collection(
+    app: tauri::AppHandle,
+    path: String,
+    environment_path: Option<String>,
+) -> Result<ImportResult, String> {
+    let raw = super::read_source(&path)?;
+    let data: Value = serde_json::from_str(&raw)
+        .map_err(|e| format!("Thunder Client export parse failed: {e}"))?;
+    let mut report = ImportReport::new("thunder");
+    let mut imported = parse(&data, &mut report)?;
+    if let Some(env_path) = environment_path.filter(|p| !p.trim().is_empty()) {
+        let raw = super::read_source(&env_path)?;
+        let envs: Value = serde_json::from_str(&raw)
+            .map_err(|e| format!("Thunder Client environment parse failed: {e}"))?;
+        imported.environments = parse_environments(&envs);
+    }
+    super::save(&app, imported, report).await
+}
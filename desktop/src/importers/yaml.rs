@@ -0,0 +1,377 @@
+//! LiteFetch's git-friendly collection layout: a directory per collection with one YAML file
+//! per request and a directory per folder, so a change to one request is a change to one small
+//! file. Keys are written in sorted order and empty (`null`) fields are left out; history, last
+//! results, cookies and UI state stay behind, as do secret environment values. Unlike the
+//! other formats this one is lossless for requests, so export and import round-trip.
+//!
+//! ```text
+//! <collection>/collection.yaml        id, name, variables, workflows, order
+//! <collection>/<request>.yaml
+//! <collection>/<folder>/folder.yaml   id, name, order
+//! <collection>/environments/<env>.yaml
+//! ```
+//!
+//! `order` lists the directory's entries (`<request>.yaml`, `<folder>/`); entries missing from
+//! it, such as files added by hand, come after the listed ones in name order.
+
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{
+    environment_entry, file_stem, str_of, unique, value_text, ImportReport, ImportResult,
+    ImportedVariable,
+};
+
+const COLLECTION_FILE: &str = "collection.yaml";
+const FOLDER_FILE: &str = "folder.yaml";
+const ENVIRONMENTS_DIR: &str = "environments";
+
+/// `value` with keys in sorted order and `null` fields dropped, at every level.
+fn stable(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            Value::Object(
+                keys.into_iter()
+                    .filter(|key| !map[*key].is_null())
+                    .map(|key| (key.clone(), stable(&map[key])))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(stable).collect()),
+        other => other.clone(),
+    }
+}
+
+fn write_yaml(path: &Path, value: &Value) -> Result<(), String> {
+    let text =
+        serde_yaml::to_string(&stable(value)).map_err(|e| format!("YAML export failed: {e}"))?;
+    fs::write(path, text).map_err(|e| format!("YAML export failed: {e}"))
+}
+
+fn read_yaml(path: &Path) -> Result<Value, String> {
+    let raw = fs::read_to_string(path).map_err(|e| format!("YAML import failed: {e}"))?;
+    serde_yaml::from_str(&raw).map_err(|e| format!("YAML parse failed in {}: {e}", path.display()))
+}
+
+/// An item's own fields, without its children, plus the entry `order` of its directory.
+fn with_order(item: &Value, order: Vec<String>) -> Value {
+    let mut fields = item.as_object().cloned().unwrap_or_default();
+    fields.remove("items");
+    fields.insert("order".into(), json!(order));
+    Value::Object(fields)
+}
+
+// --- Export ---
+
+/// Writes `items` into `dir` and returns the entry order.
+fn write_items(
+    dir: &Path,
+    items: &[Value],
+    reserved: &[&str],
+    requests: &mut usize,
+) -> Result<Vec<String>, String> {
+    let mut taken: Vec<String> = reserved.iter().map(|name| name.to_string()).collect();
+    let mut order = Vec::with_capacity(items.len());
+    for item in items {
+        let stem = unique(&mut taken, file_stem(str_of(item, "name")));
+        if let Some(children) = item.get("items").and_then(Value::as_array) {
+            let folder_dir = dir.join(&stem);
+            fs::create_dir_all(&folder_dir).map_err(|e| format!("YAML export failed: {e}"))?;
+            let folder_order = write_items(&folder_dir, children, &["folder"], requests)?;
+            write_yaml(
+                &folder_dir.join(FOLDER_FILE),
+                &with_order(item, folder_order),
+            )?;
+            order.push(format!("{stem}/"));
+        } else {
+            write_yaml(&dir.join(format!("{stem}.yaml")), item)?;
+            *requests += 1;
+            order.push(format!("{stem}.yaml"));
+        }
+    }
+    Ok(order)
+}
+
+fn write_environments(root: &Path, environment: &Value) -> Result<usize, String> {
+    let Some(envs) = environment.get("envs").and_then(Value::as_object) else {
+        return Ok(0);
+    };
+    let dir = root.join(ENVIRONMENTS_DIR);
+    fs::create_dir_all(&dir).map_err(|e| format!("YAML export failed: {e}"))?;
+    let empty = Map::new();
+    let mut taken = Vec::new();
+    for (name, env) in envs {
+        let secrets = env
+            .get("secrets")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+        let is_secret = |key: &str| secrets.get(key).and_then(Value::as_bool).unwrap_or(false);
+        let variables: Map<String, Value> = env
+            .get("variables")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty)
+            .iter()
+            .filter(|(key, _)| !is_secret(key))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        // Secret values live in the keychain; only their names are written.
+        let secret_keys: Vec<&String> = secrets.keys().filter(|key| is_secret(key)).collect();
+        let stem = unique(&mut taken, file_stem(name));
+        write_yaml(
+            &dir.join(format!("{stem}.yaml")),
+            &json!({ "name": name, "variables": variables, "secrets": secret_keys }),
+        )?;
+    }
+    Ok(envs.len())
+}
+
+#[derive(Serialize)]
+pub struct YamlExportSummary {
+    pub path: String,
+    pub requests: usize,
+    pub environments: usize,
+}
+
+/// Writes a collection in the YAML layout under `output_dir`. Request fields marked secret are
+/// masked unless `include_secrets` carries a confirmed grant.
+#[tauri::command]
+pub async fn export_collection_yaml(
+    app: tauri::AppHandle,
+    collection_id: String,
+    output_dir: String,
+    include_secrets: Option<String>,
+) -> Result<YamlExportSummary, String> {
+    let mut collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    crate::redact::for_export(
+        &app,
+        &collection_id,
+        &collection,
+        &environment,
+        include_secrets.as_deref(),
+    )
+    .await?
+    .value(&mut collection);
+    let root: PathBuf =
+        crate::normalize_path(output_dir.trim()).join(file_stem(str_of(&collection, "name")));
+    if root.read_dir().is_ok_and(|mut d| d.next().is_some()) {
+        return Err(format!(
+            "{} already exists and is not empty",
+            root.display()
+        ));
+    }
+    fs::create_dir_all(&root).map_err(|e| format!("YAML export failed: {e}"))?;
+
+    let mut requests = 0;
+    let items = collection
+        .get("items")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    let order = write_items(
+        &root,
+        &items,
+        &["collection", ENVIRONMENTS_DIR],
+        &mut requests,
+    )?;
+    write_yaml(&root.join(COLLECTION_FILE), &with_order(&collection, order))?;
+    let environments = write_environments(&root, &environment)?;
+    Ok(YamlExportSummary {
+        path: root.to_string_lossy().to_string(),
+        requests,
+        environments,
+    })
+}
+
+// --- Import ---
+
+/// Reads the entries of `dir` in `order`, then any unlisted ones in name order.
+fn read_items(
+    dir: &Path,
+    order: &[String],
+    skipped: &[&str],
+    report: &mut ImportReport,
+) -> Result<Vec<Value>, String> {
+    let mut entries: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| format!("YAML import failed: {e}"))?
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if skipped.contains(&name.as_str()) || name.starts_with('.') {
+                return None;
+            }
+            match entry.file_type() {
+                Ok(kind) if kind.is_dir() => Some(format!("{name}/")),
+                Ok(kind) if kind.is_file() && name.ends_with(".yaml") => Some(name),
+                _ => None,
+            }
+        })
+        .collect();
+    entries.sort();
+    let mut ordered: Vec<String> = order
+        .iter()
+        .filter(|name| entries.contains(name))
+        .cloned()
+        .collect();
+    ordered.extend(entries.into_iter().filter(|name| !order.contains(name)));
+
+    let mut items = Vec::with_capacity(ordered.len());
+    for entry in ordered {
+        let mut item = match entry.strip_suffix('/') {
+            Some(folder) => {
+                let folder_dir = dir.join(folder);
+                let meta_path = folder_dir.join(FOLDER_FILE);
+                let mut meta = if meta_path.exists() {
+                    read_yaml(&meta_path)?
+                } else {
+                    json!({ "name": folder })
+                };
+                let children = read_items(&folder_dir, &order_of(&meta), &[FOLDER_FILE], report)?;
+                if let Some(fields) = meta.as_object_mut() {
+                    fields.remove("order");
+                }
+                meta["items"] = json!(children);
+                report.folders += 1;
+                meta
+            }
+            None => {
+                report.requests += 1;
+                read_yaml(&dir.join(&entry))?
+            }
+        };
+        if !item.is_object() {
+            return Err(format!("YAML import failed: {entry} is not a mapping"));
+        }
+        if str_of(&item, "id").is_empty() {
+            item["id"] = json!(uuid::Uuid::new_v4().to_string());
+        }
+        items.push(item);
+    }
+    Ok(items)
+}
+
+fn strings(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|names| names.iter().map(|n| value_text(Some(n))).collect())
+        .unwrap_or_default()
+}
+
+fn order_of(meta: &Value) -> Vec<String> {
+    strings(meta.get("order"))
+}
+
+fn read_environments(root: &Path, report: &mut ImportReport) -> Result<Map<String, Value>, String> {
+    let mut envs = Map::new();
+    let dir = root.join(ENVIRONMENTS_DIR);
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Ok(envs);
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|e| e == "yaml"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        let env = read_yaml(&path)?;
+        let name = match str_of(&env, "name") {
+            "" => path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            n => n.to_string(),
+        };
+        let mut variables: Vec<ImportedVariable> = env
+            .get("variables")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| ImportedVariable {
+                key: key.clone(),
+                value: value_text(Some(value)),
+                secret: false,
+            })
+            .collect();
+        let secrets = strings(env.get("secrets"));
+        if !secrets.is_empty() {
+            report.unsupported("secret values are not part of YAML collections; re-enter them");
+        }
+        variables.extend(secrets.into_iter().map(|key| ImportedVariable {
+            key,
+            value: String::new(),
+            secret: true,
+        }));
+        report.variables += variables.len();
+        envs.insert(name.clone(), environment_entry(&name, &variables));
+    }
+    report.environments = envs.len();
+    Ok(envs)
+}
+
+/// Creates a collection from a directory in the YAML layout (or its `collection.yaml`).
+#[tauri::command]
+pub async fn import_collection_yaml(
+    app: tauri::AppHandle,
+    path: String,
+    collection_name: Option<String>,
+) -> Result<ImportResult, String> {
+    let mut root = crate::normalize_path(&path);
+    if root.file_name().is_some_and(|n| n == COLLECTION_FILE) {
+        root.pop();
+    }
+    let manifest_path = root.join(COLLECTION_FILE);
+    if !manifest_path.exists() {
+        return Err(format!(
+            "not a YAML collection ({COLLECTION_FILE} missing in {})",
+            root.display()
+        ));
+    }
+    let mut report = ImportReport::new("yaml");
+    let mut collection = read_yaml(&manifest_path)?;
+    let items = read_items(
+        &root,
+        &order_of(&collection),
+        &[COLLECTION_FILE, ENVIRONMENTS_DIR],
+        &mut report,
+    )?;
+    if report.requests == 0 {
+        return Err("yaml export contained no requests".into());
+    }
+    let name = match collection_name.filter(|n| !n.trim().is_empty()) {
+        Some(name) => name,
+        None => match str_of(&collection, "name") {
+            "" => "Imported Collection".to_string(),
+            n => n.to_string(),
+        },
+    };
+    if let Some(fields) = collection.as_object_mut() {
+        fields.remove("order");
+        // The new collection gets an id of its own; request ids are kept for workflow steps.
+        fields.remove("id");
+    }
+    collection["name"] = json!(name);
+    collection["items"] = json!(items);
+
+    let envs = read_environments(&root, &mut report)?;
+    let mut payload = json!({ "name": name, "collection": collection });
+    if !envs.is_empty() {
+        let active_env = if envs.contains_key("default") {
+            "default".to_string()
+        } else {
+            envs.keys().next().cloned().unwrap_or_default()
+        };
+        payload["environment"] = json!({ "active_env": active_env, "envs": envs });
+    }
+    let meta = crate::create_collection_with(&app, payload).await?;
+    Ok(ImportResult {
+        collection: meta,
+        report,
+    })
+}
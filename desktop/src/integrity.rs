@@ -0,0 +1,191 @@
+//! Integrity of each response, set as `result.integrity` before it is stored: the body's MD5
+//! and SHA-256, the server's digest headers and the request's expected hash checked against
+//! them (see `litefetch_core::integrity`), and, when the request asks for it, a detached JWS
+//! signature over the body (RFC 7515 appendix F, with RFC 7797 unencoded payloads) checked
+//! with a key or a JWKS URL (see `jwt`).
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use litefetch_core::integrity::{self, CheckStatus, Checksums, DigestCheck};
+
+use crate::jwt::Algorithm;
+
+const DEFAULT_SIGNATURE_HEADER: &str = "x-jws-signature";
+
+/// A request's `integrity` settings.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct IntegrityOptions {
+    /// `<algorithm>:<hex or base64>`, or bare hex.
+    pub expected: Option<String>,
+    pub signature: Option<SignatureOptions>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct SignatureOptions {
+    /// The response header holding the detached JWS, `x-jws-signature` by default.
+    pub header: Option<String>,
+    /// A secret (HS*), a PEM public key or a JWK.
+    pub key: Option<String>,
+    pub jwks_url: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SignatureCheck {
+    pub header: String,
+    pub algorithm: Option<String>,
+    pub kid: Option<String>,
+    /// `false` as well when the signature is missing or can't be checked; see `error`.
+    pub valid: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Integrity {
+    #[serde(flatten)]
+    pub checksums: Checksums,
+    pub checks: Vec<DigestCheck>,
+    /// `false` when any check or the signature failed, `None` when there was nothing to
+    /// check.
+    pub valid: Option<bool>,
+    pub signature: Option<SignatureCheck>,
+}
+
+/// The body exactly as received: decoded from base64 for binary bodies.
+fn body_bytes(result: &Value) -> Vec<u8> {
+    if let Some(bytes) = result
+        .get("body_base64")
+        .and_then(Value::as_str)
+        .and_then(|encoded| STANDARD.decode(encoded).ok())
+    {
+        return bytes;
+    }
+    match result.get("body") {
+        Some(Value::String(text)) => text.clone().into_bytes(),
+        Some(Value::Null) | None => Vec::new(),
+        Some(other) => other.to_string().into_bytes(),
+    }
+}
+
+async fn verify_signature(
+    options: &SignatureOptions,
+    result: &Value,
+    body: &[u8],
+) -> SignatureCheck {
+    let header = options
+        .header
+        .clone()
+        .filter(|h| !h.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_SIGNATURE_HEADER.to_string());
+    let mut check = SignatureCheck {
+        header: header.clone(),
+        algorithm: None,
+        kid: None,
+        valid: false,
+        error: None,
+    };
+    let Some(jws) = result
+        .get("headers")
+        .and_then(Value::as_object)
+        .and_then(|headers| {
+            headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case(&header))
+        })
+        .and_then(|(_, value)| value.as_str())
+    else {
+        check.error = Some(format!("the response has no {header} header"));
+        return check;
+    };
+    let outcome: Result<bool, String> = async {
+        let parts: Vec<&str> = jws.trim().split('.').collect();
+        let [protected, payload, sig] = parts[..] else {
+            return Err("a JWS has three dot-separated parts".to_string());
+        };
+        if !payload.is_empty() {
+            return Err("the JWS isn't detached: it carries its own payload".to_string());
+        }
+        let protected_header = crate::jwt::json_part(protected, "header")?;
+        check.algorithm = protected_header
+            .get("alg")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        check.kid = protected_header
+            .get("kid")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        let algorithm = Algorithm::parse(check.algorithm.as_deref().unwrap_or("none"))?;
+        let sig = crate::jwt::b64_decode(sig, "signature")?;
+        // RFC 7797: `"b64": false` signs the body as is rather than its base64url form.
+        let mut message = format!("{protected}.").into_bytes();
+        match protected_header.get("b64") == Some(&Value::Bool(false)) {
+            true => message.extend_from_slice(body),
+            false => message.extend(URL_SAFE_NO_PAD.encode(body).into_bytes()),
+        }
+        let key = options.key.as_deref().filter(|k| !k.trim().is_empty());
+        let jwks_url = options.jwks_url.as_deref().filter(|u| !u.trim().is_empty());
+        match (key, jwks_url) {
+            (Some(key), _) => crate::jwt::verify(algorithm, key, &message, &sig),
+            (None, Some(url)) => {
+                let jwk = crate::jwt::jwks_key(url, check.kid.as_deref()).await?;
+                crate::jwt::verify_jwk(algorithm, &jwk, &message, &sig)
+            }
+            (None, None) => Err("a key or a JWKS URL is needed to check the signature".into()),
+        }
+    }
+    .await;
+    match outcome {
+        Ok(valid) => check.valid = valid,
+        Err(e) => check.error = Some(e),
+    }
+    check
+}
+
+/// Sets `result.integrity` for a response to `request`. Failed sends are left alone.
+pub async fn apply(request: &Value, result: &mut Value) {
+    if result
+        .get("status_code")
+        .and_then(Value::as_u64)
+        .unwrap_or(0)
+        == 0
+    {
+        return;
+    }
+    let options: IntegrityOptions = request
+        .get("integrity")
+        .filter(|o| o.is_object())
+        .and_then(|o| serde_json::from_value(o.clone()).ok())
+        .unwrap_or_default();
+    let body = body_bytes(result);
+    let mut checks = result
+        .get("headers")
+        .and_then(Value::as_object)
+        .map(|headers| integrity::verify_headers(&body, headers))
+        .unwrap_or_default();
+    if let Some(expected) = options.expected.as_deref().filter(|e| !e.trim().is_empty()) {
+        checks.push(integrity::verify_expected(&body, expected));
+    }
+    let signature = match &options.signature {
+        Some(signature) => Some(verify_signature(signature, result, &body).await),
+        None => None,
+    };
+
+    let failed = checks.iter().any(|c| c.status == CheckStatus::Mismatch)
+        || signature.as_ref().is_some_and(|s| !s.valid);
+    let checked = checks.iter().any(|c| c.status == CheckStatus::Match) || signature.is_some();
+    let report = Integrity {
+        checksums: integrity::checksums(&body),
+        checks,
+        valid: match (failed, checked) {
+            (true, _) => Some(false),
+            (false, true) => Some(true),
+            (false, false) => None,
+        },
+        signature,
+    };
+    result["integrity"] = serde_json::to_value(&report).unwrap_or(Value::Null);
+}
@@ -0,0 +1,266 @@
+//! JSON-RPC 2.0 requests, for requests with `jsonrpc` set: `{calls: [{method, params?,
+//! id?, notification?}], batch?}`. The shell writes the body right before variables are
+//! resolved, so params can use `{{variables}}` like any body: one call object, or an array
+//! for a batch (more than one call, or `batch: true`). Calls without an id get the next free
+//! number; notifications get none. The request goes out as a `POST` with a JSON body.
+//!
+//! Once the response arrives it is matched to the calls by id and set on the result as
+//! `jsonrpc`, with each error's code named by the spec's meaning, so a `200 OK` carrying
+//! error objects reads as a failure rather than a success.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use litefetch_core::schema::body_of;
+
+#[derive(Deserialize, Serialize, Clone)]
+pub struct Call {
+    pub method: String,
+    /// An array (by position) or object (by name); left out when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+    /// A number or string; assigned when absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    /// A call the server doesn't answer.
+    #[serde(default)]
+    pub notification: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct JsonRpc {
+    pub calls: Vec<Call>,
+    /// Send a one-call batch as an array too.
+    pub batch: bool,
+}
+
+#[derive(Serialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    /// What the spec says the code means: `parse_error`, `invalid_request`,
+    /// `method_not_found`, `invalid_params`, `internal_error`, `server_error` or
+    /// `application_error`; `invalid_response` when the body isn't JSON-RPC.
+    pub kind: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct CallOutcome {
+    pub method: String,
+    pub id: Option<Value>,
+    /// `result`, `error`, `notification` (nothing expected) or `missing` (no response had
+    /// its id).
+    pub outcome: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+#[derive(Serialize)]
+pub struct RpcReport {
+    pub calls: Vec<CallOutcome>,
+    pub errors: usize,
+    /// A response error that names no call: the whole body failed to parse or was
+    /// rejected, or the body isn't JSON-RPC at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<RpcError>,
+}
+
+/// The calls with ids assigned, as sent.
+pub struct Prepared {
+    pub calls: Vec<Call>,
+}
+
+fn kind(code: i64) -> &'static str {
+    match code {
+        -32700 => "parse_error",
+        -32600 => "invalid_request",
+        -32601 => "method_not_found",
+        -32602 => "invalid_params",
+        -32603 => "internal_error",
+        -32099..=-32000 => "server_error",
+        _ => "application_error",
+    }
+}
+
+fn error_of(value: &Value) -> RpcError {
+    let code = value.get("code").and_then(Value::as_i64).unwrap_or(0);
+    RpcError {
+        code,
+        message: value
+            .get("message")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string(),
+        data: value.get("data").cloned(),
+        kind: kind(code),
+    }
+}
+
+/// Numbers the calls that need an id, skipping numbers already used by others.
+fn assign_ids(calls: &mut [Call]) {
+    let taken: Vec<i64> = calls
+        .iter()
+        .filter_map(|c| c.id.as_ref().and_then(Value::as_i64))
+        .collect();
+    let mut next = 1;
+    for call in calls
+        .iter_mut()
+        .filter(|c| !c.notification && c.id.is_none())
+    {
+        while taken.contains(&next) {
+            next += 1;
+        }
+        call.id = Some(json!(next));
+        next += 1;
+    }
+    for call in calls.iter_mut().filter(|c| c.notification) {
+        call.id = None;
+    }
+}
+
+fn envelope(call: &Call) -> Value {
+    let mut message = json!({ "jsonrpc": "2.0", "method": call.method });
+    if let Some(params) = &call.params {
+        message["params"] = params.clone();
+    }
+    if let Some(id) = &call.id {
+        message["id"] = id.clone();
+    }
+    message
+}
+
+/// The request body for `config`, with ids assigned.
+pub fn body(config: &JsonRpc) -> Result<(String, Vec<Call>), String> {
+    let mut calls = config.calls.clone();
+    if calls.is_empty() {
+        return Err("a JSON-RPC request needs at least one call".into());
+    }
+    if let Some(call) = calls.iter().find(|c| c.method.trim().is_empty()) {
+        return Err(format!(
+            "every JSON-RPC call needs a method (call {} has none)",
+            call.id.as_ref().map(Value::to_string).unwrap_or_default()
+        ));
+    }
+    if let Some(params) = calls
+        .iter()
+        .filter_map(|c| c.params.as_ref())
+        .find(|p| !p.is_array() && !p.is_object())
+    {
+        return Err(format!(
+            "JSON-RPC params must be an array or object, not {params}"
+        ));
+    }
+    assign_ids(&mut calls);
+    let messages: Vec<Value> = calls.iter().map(envelope).collect();
+    let body = match (messages.len(), config.batch) {
+        (1, false) => messages[0].clone(),
+        _ => Value::Array(messages),
+    };
+    serde_json::to_string_pretty(&body)
+        .map(|text| (text, calls))
+        .map_err(|e| format!("JSON-RPC encode failed: {e}"))
+}
+
+/// Writes the JSON-RPC body onto a request with `jsonrpc` set; `None` for other requests.
+pub(crate) fn prepare(request: &mut Value) -> Result<Option<Prepared>, String> {
+    let Some(config) = request.get("jsonrpc").filter(|c| c.is_object()) else {
+        return Ok(None);
+    };
+    let config: JsonRpc = serde_json::from_value(config.clone())
+        .map_err(|e| format!("invalid JSON-RPC settings: {e}"))?;
+    let (body, calls) = body(&config)?;
+    request["method"] = json!("POST");
+    request["body_mode"] = json!("json");
+    request["body"] = json!(body);
+    if !request.get("headers").is_some_and(Value::is_object) {
+        request["headers"] = json!({});
+    }
+    if let Some(headers) = request["headers"].as_object_mut() {
+        if !headers
+            .keys()
+            .any(|k| k.eq_ignore_ascii_case("content-type"))
+        {
+            headers.insert("Content-Type".into(), json!("application/json"));
+        }
+    }
+    Ok(Some(Prepared { calls }))
+}
+
+/// Matches the response messages in a backend `RequestResult` to the calls sent.
+pub fn report(prepared: &Prepared, result: &Value) -> RpcReport {
+    let body = body_of(result);
+    let responses: Vec<&Value> = match &body {
+        Value::Array(items) => items.iter().collect(),
+        Value::Object(_) => vec![&body],
+        _ => Vec::new(),
+    };
+    // A failure the server couldn't tie to a call comes back with a null id.
+    let error = responses
+        .iter()
+        .find(|r| r.get("id").is_none_or(Value::is_null))
+        .and_then(|r| r.get("error"))
+        .map(error_of);
+
+    let calls: Vec<CallOutcome> = prepared
+        .calls
+        .iter()
+        .map(|call| {
+            let mut outcome = CallOutcome {
+                method: call.method.clone(),
+                id: call.id.clone(),
+                outcome: "notification",
+                result: None,
+                error: None,
+            };
+            let Some(id) = &call.id else {
+                return outcome;
+            };
+            match responses.iter().find(|r| r.get("id") == Some(id)) {
+                None => outcome.outcome = "missing",
+                Some(response) => match response.get("error") {
+                    Some(error) => {
+                        outcome.outcome = "error";
+                        outcome.error = Some(error_of(error));
+                    }
+                    None => {
+                        outcome.outcome = "result";
+                        outcome.result = response.get("result").cloned();
+                    }
+                },
+            }
+            outcome
+        })
+        .collect();
+    let status = result
+        .get("status_code")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    let error = error.or_else(|| {
+        let expected = calls.iter().any(|c| c.outcome != "notification");
+        (expected && responses.is_empty() && (200..300).contains(&status)).then(|| RpcError {
+            code: 0,
+            message: "the response is not a JSON-RPC message".to_string(),
+            data: None,
+            kind: "invalid_response",
+        })
+    });
+    RpcReport {
+        errors: calls
+            .iter()
+            .filter(|c| matches!(c.outcome, "error" | "missing"))
+            .count(),
+        calls,
+        error,
+    }
+}
+
+/// The body a JSON-RPC request would send, for previews in the editor.
+#[tauri::command]
+pub async fn build_jsonrpc_body(config: JsonRpc) -> Result<String, String> {
+    body(&config).map(|(body, _)| body)
+}
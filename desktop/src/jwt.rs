@@ -0,0 +1,532 @@
+//! JSON Web Tokens: `decode_jwt` shows a token's header, claims and expiry, checking the
+//! signature against a secret, a PEM/JWK public key or a JWKS URL; requests with
+//! `auth_type: "jwt_bearer"` are sent with a token signed on the fly from their `auth_params`
+//! (algorithm, key, claims template) by `authorize`.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine as _;
+use ring::rand::SystemRandom;
+use ring::signature;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Lifetime of signed tokens whose auth params name none.
+const DEFAULT_EXPIRES_IN_SECS: u64 = 300;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    HS256,
+    HS384,
+    HS512,
+    RS256,
+    RS384,
+    RS512,
+    PS256,
+    PS384,
+    PS512,
+    ES256,
+    ES384,
+    EdDSA,
+}
+
+impl Algorithm {
+    pub(crate) fn parse(name: &str) -> Result<Self, String> {
+        serde_json::from_value(Value::String(name.trim().to_string()))
+            .map_err(|_| format!("unsupported JWT algorithm: {name}"))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Algorithm::HS256 => "HS256",
+            Algorithm::HS384 => "HS384",
+            Algorithm::HS512 => "HS512",
+            Algorithm::RS256 => "RS256",
+            Algorithm::RS384 => "RS384",
+            Algorithm::RS512 => "RS512",
+            Algorithm::PS256 => "PS256",
+            Algorithm::PS384 => "PS384",
+            Algorithm::PS512 => "PS512",
+            Algorithm::ES256 => "ES256",
+            Algorithm::ES384 => "ES384",
+            Algorithm::EdDSA => "EdDSA",
+        }
+    }
+
+    fn hmac(self) -> Option<ring::hmac::Algorithm> {
+        match self {
+            Algorithm::HS256 => Some(ring::hmac::HMAC_SHA256),
+            Algorithm::HS384 => Some(ring::hmac::HMAC_SHA384),
+            Algorithm::HS512 => Some(ring::hmac::HMAC_SHA512),
+            _ => None,
+        }
+    }
+
+    fn rsa_params(self) -> Option<&'static signature::RsaParameters> {
+        match self {
+            Algorithm::RS256 => Some(&signature::RSA_PKCS1_2048_8192_SHA256),
+            Algorithm::RS384 => Some(&signature::RSA_PKCS1_2048_8192_SHA384),
+            Algorithm::RS512 => Some(&signature::RSA_PKCS1_2048_8192_SHA512),
+            Algorithm::PS256 => Some(&signature::RSA_PSS_2048_8192_SHA256),
+            Algorithm::PS384 => Some(&signature::RSA_PSS_2048_8192_SHA384),
+            Algorithm::PS512 => Some(&signature::RSA_PSS_2048_8192_SHA512),
+            _ => None,
+        }
+    }
+
+    fn verification(self) -> &'static dyn signature::VerificationAlgorithm {
+        match self {
+            Algorithm::ES256 => &signature::ECDSA_P256_SHA256_FIXED,
+            Algorithm::ES384 => &signature::ECDSA_P384_SHA384_FIXED,
+            Algorithm::EdDSA => &signature::ED25519,
+            rsa => rsa
+                .rsa_params()
+                .unwrap_or(&signature::RSA_PKCS1_2048_8192_SHA256),
+        }
+    }
+
+    fn rsa_encoding(self) -> Option<&'static dyn signature::RsaEncoding> {
+        match self {
+            Algorithm::RS256 => Some(&signature::RSA_PKCS1_SHA256),
+            Algorithm::RS384 => Some(&signature::RSA_PKCS1_SHA384),
+            Algorithm::RS512 => Some(&signature::RSA_PKCS1_SHA512),
+            Algorithm::PS256 => Some(&signature::RSA_PSS_SHA256),
+            Algorithm::PS384 => Some(&signature::RSA_PSS_SHA384),
+            Algorithm::PS512 => Some(&signature::RSA_PSS_SHA512),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DecodedJwt {
+    pub header: Value,
+    pub claims: Value,
+    pub algorithm: Option<String>,
+    /// `None` when no key was given to check it with.
+    pub signature_valid: Option<bool>,
+    pub verification_error: Option<String>,
+    pub issued_at_ms: Option<u64>,
+    pub not_before_ms: Option<u64>,
+    pub expires_at_ms: Option<u64>,
+    pub expired: Option<bool>,
+}
+
+pub(crate) fn b64_decode(part: &str, what: &str) -> Result<Vec<u8>, String> {
+    URL_SAFE_NO_PAD
+        .decode(part.trim_end_matches('='))
+        .map_err(|e| format!("invalid JWT {what}: {e}"))
+}
+
+pub(crate) fn json_part(part: &str, what: &str) -> Result<Value, String> {
+    serde_json::from_slice(&b64_decode(part, what)?).map_err(|e| format!("invalid JWT {what}: {e}"))
+}
+
+fn pem_der(text: &str) -> Result<pem::Pem, String> {
+    pem::parse(text.trim()).map_err(|e| format!("invalid PEM key: {e}"))
+}
+
+/// The key bits of a DER SubjectPublicKeyInfo: what ring verifies RSA, EC and Ed25519
+/// signatures with.
+fn spki_key(der: &[u8]) -> Option<&[u8]> {
+    // SEQUENCE { SEQUENCE algorithm, BIT STRING subjectPublicKey }
+    fn element(input: &[u8]) -> Option<(u8, &[u8], &[u8])> {
+        let (&tag, rest) = input.split_first()?;
+        let (&first, rest) = rest.split_first()?;
+        let (len, rest) = if first < 0x80 {
+            (first as usize, rest)
+        } else {
+            let count = (first & 0x7f) as usize;
+            if count == 0 || count > 4 || rest.len() < count {
+                return None;
+            }
+            let len = rest[..count]
+                .iter()
+                .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+            (len, &rest[count..])
+        };
+        (rest.len() >= len).then(|| (tag, &rest[..len], &rest[len..]))
+    }
+    let (0x30, spki, _) = element(der)? else {
+        return None;
+    };
+    let (0x30, _, rest) = element(spki)? else {
+        return None;
+    };
+    let (0x03, bits, _) = element(rest)? else {
+        return None;
+    };
+    // The first byte counts the unused bits, always 0 for keys.
+    bits.split_first().map(|(_, key)| key)
+}
+
+/// A JWK's field, base64url-decoded.
+fn jwk_field(jwk: &Value, field: &str) -> Result<Vec<u8>, String> {
+    let value = jwk
+        .get(field)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("JWK has no `{field}`"))?;
+    b64_decode(value, "key")
+}
+
+pub(crate) fn verify_jwk(
+    algorithm: Algorithm,
+    jwk: &Value,
+    message: &[u8],
+    sig: &[u8],
+) -> Result<bool, String> {
+    if let Some(alg) = jwk.get("alg").and_then(Value::as_str) {
+        if alg != algorithm.name() {
+            return Err(format!(
+                "the JWK is for {alg} but the token says {}",
+                algorithm.name()
+            ));
+        }
+    }
+    let kty = jwk.get("kty").and_then(Value::as_str);
+    // An HMAC check keyed with a public key would accept tokens anyone can sign.
+    match (algorithm.hmac(), kty) {
+        (Some(hmac), Some("oct")) => {
+            let key = ring::hmac::Key::new(hmac, &jwk_field(jwk, "k")?);
+            return Ok(ring::hmac::verify(&key, message, sig).is_ok());
+        }
+        (Some(_), _) => {
+            return Err(format!(
+                "the token says {} but the JWK is not a secret key",
+                algorithm.name()
+            ))
+        }
+        (None, Some("oct")) => {
+            return Err(format!(
+                "the token says {} but the JWK is a secret key",
+                algorithm.name()
+            ))
+        }
+        (None, _) => {}
+    }
+    match kty {
+        Some("RSA") => {
+            let components = signature::RsaPublicKeyComponents {
+                n: jwk_field(jwk, "n")?,
+                e: jwk_field(jwk, "e")?,
+            };
+            let params = algorithm
+                .rsa_params()
+                .ok_or("JWK key type does not match the algorithm")?;
+            Ok(components.verify(params, message, sig).is_ok())
+        }
+        Some("EC") if !matches!(algorithm, Algorithm::ES256 | Algorithm::ES384) => {
+            Err("JWK key type does not match the algorithm".into())
+        }
+        Some("OKP") if algorithm != Algorithm::EdDSA => {
+            Err("JWK key type does not match the algorithm".into())
+        }
+        Some("EC") => {
+            let mut point = vec![0x04];
+            point.extend(jwk_field(jwk, "x")?);
+            point.extend(jwk_field(jwk, "y")?);
+            Ok(
+                signature::UnparsedPublicKey::new(algorithm.verification(), point)
+                    .verify(message, sig)
+                    .is_ok(),
+            )
+        }
+        Some("OKP") => Ok(signature::UnparsedPublicKey::new(
+            algorithm.verification(),
+            jwk_field(jwk, "x")?,
+        )
+        .verify(message, sig)
+        .is_ok()),
+        other => Err(format!(
+            "unsupported JWK key type: {}",
+            other.unwrap_or("none")
+        )),
+    }
+}
+
+/// Checks `sig` over `message` with a secret (HS*), a PEM public key or a JWK.
+pub(crate) fn verify(
+    algorithm: Algorithm,
+    key: &str,
+    message: &[u8],
+    sig: &[u8],
+) -> Result<bool, String> {
+    let key = key.trim();
+    if key.starts_with('{') {
+        let jwk: Value = serde_json::from_str(key).map_err(|e| format!("invalid JWK: {e}"))?;
+        return verify_jwk(algorithm, &jwk, message, sig);
+    }
+    if let Some(hmac) = algorithm.hmac() {
+        // Likewise a PEM key: it is public, so as an HMAC secret anyone could sign with it.
+        if key.starts_with("-----BEGIN") {
+            return Err(format!(
+                "the token says {} but the key is a PEM key, not a secret",
+                algorithm.name()
+            ));
+        }
+        let key = ring::hmac::Key::new(hmac, key.as_bytes());
+        return Ok(ring::hmac::verify(&key, message, sig).is_ok());
+    }
+    let pem = pem_der(key)?;
+    let public = match pem.tag() {
+        "PUBLIC KEY" => {
+            spki_key(pem.contents()).ok_or("PEM public key is not a SubjectPublicKeyInfo")?
+        }
+        // PKCS#1 RSA keys are already what ring expects.
+        "RSA PUBLIC KEY" => pem.contents(),
+        tag => return Err(format!("expected a PEM public key, got {tag}")),
+    };
+    Ok(
+        signature::UnparsedPublicKey::new(algorithm.verification(), public)
+            .verify(message, sig)
+            .is_ok(),
+    )
+}
+
+/// The key of a JWKS matching the token's `kid` (or its only key).
+pub(crate) async fn jwks_key(url: &str, kid: Option<&str>) -> Result<Value, String> {
+    let jwks: Value = reqwest::Client::new()
+        .get(url.trim())
+        .header("accept", "application/json")
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("JWKS fetch failed: {e}"))?
+        .json()
+        .await
+        .map_err(|e| format!("JWKS invalid: {e}"))?;
+    let keys = jwks
+        .get("keys")
+        .and_then(Value::as_array)
+        .ok_or("JWKS has no keys")?;
+    let found = match kid {
+        Some(kid) => keys
+            .iter()
+            .find(|k| k.get("kid").and_then(Value::as_str) == Some(kid)),
+        None if keys.len() == 1 => keys.first(),
+        None => None,
+    };
+    found.cloned().ok_or_else(|| match kid {
+        Some(kid) => format!("JWKS has no key with kid {kid}"),
+        None => "token has no kid and the JWKS has several keys".to_string(),
+    })
+}
+
+fn claim_ms(claims: &Value, name: &str) -> Option<u64> {
+    claims
+        .get(name)
+        .and_then(Value::as_f64)
+        .filter(|secs| *secs >= 0.0)
+        .map(|secs| (secs * 1000.0) as u64)
+}
+
+/// A token's header and claims, with its signature checked when a key or JWKS URL is given.
+/// With `expected_algorithm`, a token whose header names another algorithm fails the check.
+#[tauri::command]
+pub async fn decode_jwt(
+    token: String,
+    key: Option<String>,
+    jwks_url: Option<String>,
+    expected_algorithm: Option<String>,
+) -> Result<DecodedJwt, String> {
+    let token = token.trim();
+    let token = token
+        .strip_prefix("Bearer ")
+        .or_else(|| token.strip_prefix("bearer "))
+        .unwrap_or(token);
+    let parts: Vec<&str> = token.split('.').collect();
+    let [header_part, claims_part, sig_part] = parts[..] else {
+        return Err("a JWT has three dot-separated parts".into());
+    };
+    let header = json_part(header_part, "header")?;
+    let claims = json_part(claims_part, "claims")?;
+    let alg = header
+        .get("alg")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+
+    let key = key.filter(|k| !k.trim().is_empty());
+    let jwks_url = jwks_url.filter(|u| !u.trim().is_empty());
+    let mut signature_valid = None;
+    let mut verification_error = None;
+    if key.is_some() || jwks_url.is_some() {
+        let outcome: Result<bool, String> = async {
+            let algorithm = Algorithm::parse(alg.as_deref().unwrap_or("none"))?;
+            if let Some(expected) = expected_algorithm
+                .as_deref()
+                .filter(|a| !a.trim().is_empty())
+            {
+                let expected = Algorithm::parse(expected)?;
+                if expected != algorithm {
+                    return Err(format!(
+                        "expected a {} token, got {}",
+                        expected.name(),
+                        algorithm.name()
+                    ));
+                }
+            }
+            let sig = b64_decode(sig_part, "signature")?;
+            let message = format!("{header_part}.{claims_part}");
+            match (&key, &jwks_url) {
+                (Some(key), _) => verify(algorithm, key, message.as_bytes(), &sig),
+                (None, Some(url)) => {
+                    let kid = header.get("kid").and_then(Value::as_str);
+                    let jwk = jwks_key(url, kid).await?;
+                    verify_jwk(algorithm, &jwk, message.as_bytes(), &sig)
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+        .await;
+        match outcome {
+            Ok(valid) => signature_valid = Some(valid),
+            Err(e) => {
+                signature_valid = Some(false);
+                verification_error = Some(e);
+            }
+        }
+    }
+
+    let expires_at_ms = claim_ms(&claims, "exp");
+    Ok(DecodedJwt {
+        algorithm: alg,
+        signature_valid,
+        verification_error,
+        issued_at_ms: claim_ms(&claims, "iat"),
+        not_before_ms: claim_ms(&claims, "nbf"),
+        expires_at_ms,
+        expired: expires_at_ms.map(|exp| exp <= crate::now_ms()),
+        header,
+        claims,
+    })
+}
+
+/// Signs `header.claims` with a secret (HS*) or a PEM private key (PKCS#8, or PKCS#1 for RSA).
+fn sign(algorithm: Algorithm, key: &str, message: &[u8]) -> Result<Vec<u8>, String> {
+    if let Some(hmac) = algorithm.hmac() {
+        let key = ring::hmac::Key::new(hmac, key.as_bytes());
+        return Ok(ring::hmac::sign(&key, message).as_ref().to_vec());
+    }
+    let pem = pem_der(key)?;
+    let der = pem.contents();
+    let rng = SystemRandom::new();
+    let rejected = |e: ring::error::KeyRejected| format!("private key rejected: {e}");
+    if let Some(encoding) = algorithm.rsa_encoding() {
+        let pair = match pem.tag() {
+            "RSA PRIVATE KEY" => signature::RsaKeyPair::from_der(der),
+            _ => signature::RsaKeyPair::from_pkcs8(der),
+        }
+        .map_err(rejected)?;
+        let mut sig = vec![0; pair.public().modulus_len()];
+        pair.sign(encoding, &rng, message, &mut sig)
+            .map_err(|_| "JWT signing failed".to_string())?;
+        return Ok(sig);
+    }
+    if pem.tag() != "PRIVATE KEY" {
+        return Err(format!(
+            "{} keys must be PKCS#8 (BEGIN PRIVATE KEY)",
+            algorithm.name()
+        ));
+    }
+    match algorithm {
+        Algorithm::ES256 | Algorithm::ES384 => {
+            let signing = match algorithm {
+                Algorithm::ES256 => &signature::ECDSA_P256_SHA256_FIXED_SIGNING,
+                _ => &signature::ECDSA_P384_SHA384_FIXED_SIGNING,
+            };
+            let pair = signature::EcdsaKeyPair::from_pkcs8(signing, der, &rng).map_err(rejected)?;
+            pair.sign(&rng, message)
+                .map(|sig| sig.as_ref().to_vec())
+                .map_err(|_| "JWT signing failed".to_string())
+        }
+        _ => {
+            let pair =
+                signature::Ed25519KeyPair::from_pkcs8_maybe_unchecked(der).map_err(rejected)?;
+            Ok(pair.sign(message).as_ref().to_vec())
+        }
+    }
+}
+
+/// A signed token from the claims template, with `iat` and `exp` filled in unless it sets them.
+pub fn encode(
+    algorithm: Algorithm,
+    key: &str,
+    kid: Option<&str>,
+    mut claims: Map<String, Value>,
+    expires_in_secs: u64,
+) -> Result<String, String> {
+    let now = crate::now_ms() / 1000;
+    claims.entry("iat").or_insert(Value::from(now));
+    claims
+        .entry("exp")
+        .or_insert(Value::from(now + expires_in_secs));
+    let mut header = Map::new();
+    header.insert("alg".into(), Value::String(algorithm.name().into()));
+    header.insert("typ".into(), Value::String("JWT".into()));
+    if let Some(kid) = kid {
+        header.insert("kid".into(), Value::String(kid.into()));
+    }
+    let encode_part = |value: &Map<String, Value>| {
+        serde_json::to_vec(value)
+            .map(|bytes| URL_SAFE_NO_PAD.encode(bytes))
+            .map_err(|e| format!("JWT encode failed: {e}"))
+    };
+    let message = format!("{}.{}", encode_part(&header)?, encode_part(&claims)?);
+    let sig = sign(algorithm, key, message.as_bytes())?;
+    Ok(format!("{message}.{}", URL_SAFE_NO_PAD.encode(sig)))
+}
+
+/// Sets the header of an `auth_type: "jwt_bearer"` request to a freshly signed token and
+/// returns its name, so it can be marked secret. The auth params hold `algorithm`, `key`,
+/// `claims` (a JSON object; variables are already resolved), and optionally `kid`,
+/// `expires_in` (seconds), `header` (default `Authorization`) and `prefix` (default `Bearer`).
+pub fn authorize(request: &mut Value) -> Result<String, String> {
+    let params = request
+        .get("auth_params")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let param = |name: &str| {
+        params
+            .get(name)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    };
+    let algorithm = Algorithm::parse(param("algorithm").unwrap_or("HS256"))?;
+    let key = params
+        .get("key")
+        .and_then(Value::as_str)
+        .filter(|k| !k.is_empty())
+        .ok_or("JWT bearer auth needs a signing key")?;
+    let claims = match param("claims") {
+        Some(text) => match serde_json::from_str(text) {
+            Ok(Value::Object(claims)) => claims,
+            Ok(_) => return Err("JWT claims must be a JSON object".into()),
+            Err(e) => return Err(format!("invalid JWT claims: {e}")),
+        },
+        None => Map::new(),
+    };
+    let expires_in = match param("expires_in") {
+        Some(secs) => secs
+            .parse()
+            .map_err(|_| format!("invalid JWT expires_in: {secs}"))?,
+        None => DEFAULT_EXPIRES_IN_SECS,
+    };
+    let token = encode(algorithm, key, param("kid"), claims, expires_in)?;
+    crate::redact::remember(&token);
+
+    let header = param("header").unwrap_or("Authorization").to_string();
+    let value = match param("prefix") {
+        Some(prefix) => format!("{prefix} {token}"),
+        None if header.eq_ignore_ascii_case("authorization") => format!("Bearer {token}"),
+        None => token,
+    };
+    if !request.get("headers").is_some_and(Value::is_object) {
+        request["headers"] = Value::Object(Map::new());
+    }
+    if let Some(headers) = request["headers"].as_object_mut() {
+        headers.retain(|name, _| !name.eq_ignore_ascii_case(&header));
+        headers.insert(header.clone(), Value::String(value));
+    }
+    Ok(header)
+}
@@ -0,0 +1,318 @@
+//! Keyboard shortcuts: a fixed set of actions with default accelerators, which the user can
+//! rebind or unbind (kept in `keymap.json` in the app data root). App actions appear in an
+//! "Actions" menu with their accelerators; global ones are registered system-wide and work
+//! while LiteFetch is in the background. Either way a press reaches the UI as
+//! `keymap://action`. Accelerators use the menu syntax, e.g. `CmdOrCtrl+Shift+K`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::menu::{IsMenuItem, Menu, MenuItem, Submenu};
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+const KEYMAP_FILE: &str = "keymap.json";
+/// Menu item ids are the action prefixed with this, apart from the predefined items.
+const MENU_PREFIX: &str = "keymap:";
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// While a LiteFetch window has focus.
+    App,
+    /// System-wide.
+    Global,
+}
+
+/// `(action, label, scope, default accelerator)`.
+const ACTIONS: &[(&str, &str, Scope, Option<&str>)] = &[
+    (
+        "send_request",
+        "Send request",
+        Scope::App,
+        Some("CmdOrCtrl+Enter"),
+    ),
+    (
+        "save_request",
+        "Save request",
+        Scope::App,
+        Some("CmdOrCtrl+S"),
+    ),
+    (
+        "new_request",
+        "New request",
+        Scope::App,
+        Some("CmdOrCtrl+N"),
+    ),
+    ("focus_url", "Focus URL", Scope::App, Some("CmdOrCtrl+L")),
+    (
+        "command_palette",
+        "Command palette",
+        Scope::App,
+        Some("CmdOrCtrl+K"),
+    ),
+    (
+        "toggle_sidebar",
+        "Toggle sidebar",
+        Scope::App,
+        Some("CmdOrCtrl+B"),
+    ),
+    (
+        "switch_environment",
+        "Switch environment",
+        Scope::App,
+        Some("CmdOrCtrl+E"),
+    ),
+    ("show_window", "Show LiteFetch", Scope::Global, None),
+    ("quick_request", "Quick request", Scope::Global, None),
+];
+
+/// Taken by the standard Edit and window menu items.
+const RESERVED: &[(&str, &str)] = &[
+    ("CmdOrCtrl+C", "Copy"),
+    ("CmdOrCtrl+X", "Cut"),
+    ("CmdOrCtrl+V", "Paste"),
+    ("CmdOrCtrl+A", "Select all"),
+    ("CmdOrCtrl+Z", "Undo"),
+    ("CmdOrCtrl+Shift+Z", "Redo"),
+    ("CmdOrCtrl+Q", "Quit"),
+    ("CmdOrCtrl+W", "Close window"),
+];
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct KeymapFile {
+    /// By action; `null` unbinds the action.
+    shortcuts: BTreeMap<String, Option<String>>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Binding {
+    pub action: String,
+    pub label: String,
+    pub scope: Scope,
+    pub accelerator: Option<String>,
+    pub default_accelerator: Option<String>,
+    pub customized: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct ActionEvent {
+    action: String,
+    /// `menu` or `global`.
+    source: String,
+    timestamp_ms: u64,
+}
+
+fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(KEYMAP_FILE))
+}
+
+fn load(app: &tauri::AppHandle) -> Result<KeymapFile, String> {
+    let path = path(app)?;
+    if !path.exists() {
+        return Ok(KeymapFile::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("keymap read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("keymap parse failed: {e}"))
+}
+
+fn save(app: &tauri::AppHandle, file: &KeymapFile) -> Result<(), String> {
+    let payload =
+        serde_json::to_string_pretty(file).map_err(|e| format!("keymap serialize failed: {e}"))?;
+    fs::write(path(app)?, payload).map_err(|e| format!("keymap persist failed: {e}"))
+}
+
+fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .parse::<Shortcut>()
+        .map_err(|e| format!("invalid shortcut {accelerator:?}: {e}"))
+}
+
+/// Every action with its accelerator in effect. A hand-edited file that binds one
+/// accelerator twice keeps the first action's binding and leaves the later one unbound.
+fn bindings(file: &KeymapFile) -> Vec<Binding> {
+    let mut taken: Vec<u32> = RESERVED
+        .iter()
+        .filter_map(|(accel, _)| parse(accel).ok())
+        .map(|s| s.id())
+        .collect();
+    ACTIONS
+        .iter()
+        .map(|(action, label, scope, default)| {
+            let custom = file.shortcuts.get(*action);
+            let mut accelerator = match custom {
+                Some(set) => set.clone(),
+                None => default.map(str::to_string),
+            };
+            if let Some(accel) = accelerator.clone() {
+                match parse(&accel) {
+                    Ok(shortcut) if !taken.contains(&shortcut.id()) => taken.push(shortcut.id()),
+                    Ok(_) => {
+                        tracing::warn!("{accel} for {action} is already taken; left unbound");
+                        accelerator = None;
+                    }
+                    Err(e) => {
+                        tracing::warn!("{e}; {action} left unbound");
+                        accelerator = None;
+                    }
+                }
+            }
+            Binding {
+                action: action.to_string(),
+                label: label.to_string(),
+                scope: *scope,
+                accelerator,
+                default_accelerator: default.map(str::to_string),
+                customized: custom.is_some(),
+            }
+        })
+        .collect()
+}
+
+fn build_menu(app: &tauri::AppHandle, bindings: &[Binding]) -> tauri::Result<()> {
+    let menu = Menu::default(app)?;
+    let items = bindings
+        .iter()
+        .filter(|b| b.scope == Scope::App)
+        .map(|b| {
+            MenuItem::with_id(
+                app,
+                format!("{MENU_PREFIX}{}", b.action),
+                &b.label,
+                true,
+                b.accelerator.as_deref(),
+            )
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let refs: Vec<&dyn IsMenuItem<tauri::Wry>> =
+        items.iter().map(|i| i as &dyn IsMenuItem<_>).collect();
+    menu.append(&Submenu::with_items(app, "Actions", true, &refs)?)?;
+    app.set_menu(menu)?;
+    Ok(())
+}
+
+/// Builds the menu and registers the global shortcuts for the current keymap; run at
+/// startup and after every change.
+pub fn apply(app: &tauri::AppHandle) {
+    let bindings = match load(app) {
+        Ok(file) => bindings(&file),
+        Err(e) => {
+            tracing::warn!("{e}; using the default shortcuts");
+            bindings(&KeymapFile::default())
+        }
+    };
+    if let Err(e) = build_menu(app, &bindings) {
+        tracing::warn!("menu build failed: {e}");
+    }
+    let globals = app.global_shortcut();
+    if let Err(e) = globals.unregister_all() {
+        tracing::warn!("global shortcuts reset failed: {e}");
+    }
+    for binding in bindings.iter().filter(|b| b.scope == Scope::Global) {
+        let Some(accel) = &binding.accelerator else {
+            continue;
+        };
+        // Another application may hold the shortcut already.
+        if let Err(e) = parse(accel).and_then(|s| globals.register(s).map_err(|e| e.to_string())) {
+            tracing::warn!("global shortcut {accel} for {} failed: {e}", binding.action);
+        }
+    }
+}
+
+fn fire(app: &tauri::AppHandle, action: &str, source: &str) {
+    if matches!(action, "show_window" | "quick_request") {
+        if let Some(window) = app.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.unminimize();
+            let _ = window.set_focus();
+        }
+    }
+    let _ = app.emit(
+        "keymap://action",
+        ActionEvent {
+            action: action.to_string(),
+            source: source.to_string(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+/// Menu events from the "Actions" menu.
+pub fn on_menu(app: &tauri::AppHandle, id: &str) {
+    if let Some(action) = id.strip_prefix(MENU_PREFIX) {
+        fire(app, action, "menu");
+    }
+}
+
+/// The global shortcut plugin's handler.
+pub fn on_global(app: &tauri::AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state != ShortcutState::Pressed {
+        return;
+    }
+    let file = load(app).unwrap_or_default();
+    let action = bindings(&file).into_iter().find(|b| {
+        b.scope == Scope::Global
+            && b.accelerator
+                .as_deref()
+                .and_then(|a| parse(a).ok())
+                .is_some_and(|s| s.id() == shortcut.id())
+    });
+    if let Some(binding) = action {
+        fire(app, &binding.action, "global");
+    }
+}
+
+/// The bindings in effect, defaults when the keymap can't be read.
+pub(crate) fn current(app: &tauri::AppHandle) -> Vec<Binding> {
+    bindings(&load(app).unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn get_keymap(app: tauri::AppHandle) -> Result<Vec<Binding>, String> {
+    Ok(bindings(&load(&app)?))
+}
+
+/// Binds `action` to `accelerator`, or unbinds it when `accelerator` is `None` or empty.
+/// Fails when the accelerator is already bound to another action or a standard menu item.
+#[tauri::command]
+pub async fn set_shortcut(
+    app: tauri::AppHandle,
+    action: String,
+    accelerator: Option<String>,
+) -> Result<Vec<Binding>, String> {
+    if !ACTIONS.iter().any(|(a, ..)| *a == action) {
+        return Err(format!("unknown action: {action}"));
+    }
+    let accelerator = accelerator
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty());
+    let mut file = load(&app)?;
+    if let Some(accel) = &accelerator {
+        let id = parse(accel)?.id();
+        let same = |other: &str| parse(other).is_ok_and(|s| s.id() == id);
+        if let Some((_, name)) = RESERVED.iter().find(|(other, _)| same(other)) {
+            return Err(format!("{accel} is reserved for {name}"));
+        }
+        let conflict = bindings(&file)
+            .into_iter()
+            .find(|b| b.action != action && b.accelerator.as_deref().is_some_and(&same));
+        if let Some(other) = conflict {
+            return Err(format!("{accel} is already used by {}", other.label));
+        }
+    }
+    file.shortcuts.insert(action, accelerator);
+    save(&app, &file)?;
+    apply(&app);
+    Ok(bindings(&file))
+}
+
+/// Puts every action back on its default shortcut.
+#[tauri::command]
+pub async fn reset_keymap(app: tauri::AppHandle) -> Result<Vec<Binding>, String> {
+    let file = KeymapFile::default();
+    save(&app, &file)?;
+    apply(&app);
+    Ok(bindings(&file))
+}
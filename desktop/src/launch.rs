@@ -0,0 +1,160 @@
+//! Advanced launch parameters for the backend sidecar: a replacement binary, extra
+//! arguments and extra environment variables, kept in the app data root (never in a
+//! workspace, which may come from someone else's repository). `spawn_backend` merges them
+//! under the parameters the shell manages itself, which can't be overridden.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const SETTINGS_FILE: &str = "launch.json";
+/// Flags `spawn_backend` passes itself.
+const RESERVED_ARGS: &[&str] = &["--host", "--port", "--dir"];
+/// Variables `spawn_backend` sets itself.
+const RESERVED_ENV: &[&str] = &["PORT", "LITEFETCH_WORKSPACE", "LITEFETCH_READ_ONLY"];
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct LaunchSettings {
+    /// A backend executable to run instead of the bundled sidecar.
+    pub binary: Option<String>,
+    /// Appended after the shell's own arguments.
+    pub args: Vec<String>,
+    pub env: BTreeMap<String, String>,
+}
+
+impl LaunchSettings {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(crate::app_data_root(app)?.join(SETTINGS_FILE))
+    }
+
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data =
+            fs::read_to_string(&path).map_err(|e| format!("launch settings read failed: {e}"))?;
+        serde_json::from_str(&data).map_err(|e| format!("launch settings parse failed: {e}"))
+    }
+
+    /// Problems that would make the sidecar fail to start or fight the shell's own
+    /// parameters.
+    fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if let Some(binary) = self.binary.as_deref().filter(|b| !b.trim().is_empty()) {
+            if !Path::new(binary.trim()).is_file() {
+                problems.push(format!("backend binary not found: {binary}"));
+            }
+        }
+        for arg in &self.args {
+            if reserved_arg(arg) {
+                problems.push(format!("argument {arg} is set by LiteFetch"));
+            } else if arg.contains('\0') {
+                problems.push("arguments can't contain NUL characters".to_string());
+            }
+        }
+        for (key, value) in &self.env {
+            if RESERVED_ENV.contains(&key.as_str()) {
+                problems.push(format!("environment variable {key} is set by LiteFetch"));
+            } else if !valid_env(key, value) {
+                problems.push(format!("invalid environment variable {key:?}"));
+            }
+        }
+        problems
+    }
+}
+
+fn valid_env(key: &str, value: &str) -> bool {
+    !key.is_empty() && !key.contains(['=', '\0']) && !value.contains('\0')
+}
+
+fn reserved_arg(arg: &str) -> bool {
+    let flag = arg.split('=').next().unwrap_or(arg);
+    RESERVED_ARGS.contains(&flag)
+}
+
+/// The program to run, when it isn't the bundled sidecar, and `base_args` and `base_env`
+/// with the user's additions merged in. Additions that clash with the shell's parameters
+/// are dropped and logged rather than failing the launch.
+pub fn merge(
+    app: &tauri::AppHandle,
+    base_args: Vec<String>,
+    mut base_env: HashMap<String, String>,
+) -> (Option<String>, Vec<String>, HashMap<String, String>) {
+    let settings = match LaunchSettings::load(app) {
+        Ok(settings) => settings,
+        Err(e) => {
+            tracing::warn!("{e}; using the defaults");
+            LaunchSettings::default()
+        }
+    };
+    for problem in settings.problems() {
+        tracing::warn!("ignoring: {problem}");
+    }
+    let binary = settings
+        .binary
+        .map(|b| b.trim().to_string())
+        .filter(|b| Path::new(b).is_file());
+    let mut args = base_args;
+    args.extend(
+        settings
+            .args
+            .into_iter()
+            .filter(|a| !reserved_arg(a) && !a.contains('\0')),
+    );
+    for (key, value) in settings.env {
+        if valid_env(&key, &value) && !RESERVED_ENV.contains(&key.as_str()) {
+            base_env.insert(key, value);
+        }
+    }
+    (binary, args, base_env)
+}
+
+/// Logs the effective launch: the command line, then the variables by name only, since their
+/// values may be credentials.
+pub fn log(program: &str, args: &[String], env: &HashMap<String, String>) {
+    let quoted: Vec<String> = std::iter::once(program.to_string())
+        .chain(args.iter().cloned())
+        .map(
+            |part| match part.contains(char::is_whitespace) || part.is_empty() {
+                true => format!("{part:?}"),
+                false => part,
+            },
+        )
+        .collect();
+    tracing::info!("{}", crate::redact::log_line(&quoted.join(" ")));
+    let mut keys: Vec<&str> = env.keys().map(String::as_str).collect();
+    keys.sort_unstable();
+    tracing::info!("environment: {}", keys.join(", "));
+}
+
+#[tauri::command]
+pub async fn get_launch_settings(app: tauri::AppHandle) -> Result<LaunchSettings, String> {
+    LaunchSettings::load(&app)
+}
+
+/// Saves the launch parameters; they apply the next time the backend starts, such as on a
+/// workspace switch or restart of the app.
+#[tauri::command]
+pub async fn set_launch_settings(
+    app: tauri::AppHandle,
+    settings: LaunchSettings,
+) -> Result<LaunchSettings, String> {
+    let settings = LaunchSettings {
+        binary: settings
+            .binary
+            .map(|b| b.trim().to_string())
+            .filter(|b| !b.is_empty()),
+        ..settings
+    };
+    if let Some(problem) = settings.problems().into_iter().next() {
+        return Err(problem);
+    }
+    let payload = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("launch settings serialize failed: {e}"))?;
+    fs::write(LaunchSettings::path(&app)?, payload)
+        .map_err(|e| format!("launch settings persist failed: {e}"))?;
+    Ok(settings)
+}
@@ -0,0 +1,120 @@
+//! `lint_workspace`: every collection checked by `litefetch_core::lint`, plus the monitors,
+//! which point at collections, requests and environments from outside them. The problems
+//! come back most severe first, for a diagnostics panel that jumps to each item.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+
+use litefetch_core::json::{array_of, find_request, str_of};
+use litefetch_core::lint::{Problem, Severity};
+
+#[derive(Serialize)]
+pub struct LintReport {
+    pub problems: Vec<Problem>,
+    pub errors: usize,
+    pub warnings: usize,
+    pub collections: usize,
+    pub duration_ms: u64,
+}
+
+fn monitor_problems(
+    app: &tauri::AppHandle,
+    collections: &HashMap<String, (Value, Value)>,
+) -> Result<Vec<Problem>, String> {
+    let mut problems = Vec::new();
+    for monitor in crate::monitor::load_monitors(app)? {
+        let problem = |code, message: String| {
+            let mut problem = Problem::new(
+                Severity::Error,
+                code,
+                message,
+                &monitor.collection_id,
+                "monitor",
+            );
+            problem.item_id = Some(monitor.id.clone());
+            problem.item_name = Some(monitor.name.clone());
+            problem
+        };
+        let Some((collection, environment)) = collections.get(&monitor.collection_id) else {
+            problems.push(problem(
+                "missing_collection",
+                format!(
+                    "checks a collection that no longer exists ({})",
+                    monitor.collection_id
+                ),
+            ));
+            continue;
+        };
+        if let Some(request_id) = &monitor.request_id {
+            if find_request(array_of(collection, "items"), request_id).is_none() {
+                let mut missing = problem(
+                    "missing_request",
+                    format!("checks a request that no longer exists ({request_id})"),
+                );
+                missing.field = Some("request_id".into());
+                problems.push(missing);
+            }
+        }
+        if let Some(env) = &monitor.environment_id {
+            if environment
+                .get("envs")
+                .and_then(|envs| envs.get(env))
+                .is_none()
+            {
+                let mut missing = problem(
+                    "missing_environment",
+                    format!("sends with an environment that no longer exists ({env})"),
+                );
+                missing.field = Some("environment_id".into());
+                problems.push(missing);
+            }
+        }
+    }
+    Ok(problems)
+}
+
+/// Checks the whole workspace for broken references without sending anything.
+#[tauri::command]
+pub async fn lint_workspace(app: tauri::AppHandle) -> Result<LintReport, String> {
+    let started = crate::now_ms();
+    let workspace = crate::load_workspace_path(&app)?;
+    let layers = crate::variables::layers(&app)?;
+    let mut shared = BTreeSet::new();
+    for dir in [&layers.global, &layers.workspace] {
+        shared.extend(crate::variables::read_layer(dir)?.keys().cloned());
+    }
+
+    let metas = crate::backend_get(&app, "/collections").await?;
+    let mut collections = HashMap::new();
+    let mut problems = Vec::new();
+    for meta in metas.as_array().into_iter().flatten() {
+        let id = str_of(meta, "id");
+        let collection = crate::backend_get(&app, &format!("/collections/{id}/collection")).await?;
+        let environment = crate::backend_get(&app, &format!("/collections/{id}/environment"))
+            .await
+            .unwrap_or(Value::Null);
+        problems.extend(litefetch_core::lint::lint_collection(
+            &workspace,
+            &shared,
+            id,
+            &collection,
+            &environment,
+        ));
+        collections.insert(id.to_string(), (collection, environment));
+    }
+    problems.extend(monitor_problems(&app, &collections)?);
+    problems.sort_by_key(|p| std::cmp::Reverse(p.severity));
+
+    let errors = problems
+        .iter()
+        .filter(|p| p.severity == Severity::Error)
+        .count();
+    Ok(LintReport {
+        warnings: problems.len() - errors,
+        errors,
+        problems,
+        collections: collections.len(),
+        duration_ms: crate::now_ms().saturating_sub(started),
+    })
+}
@@ -0,0 +1,272 @@
+//! Load tests from the UI: `litefetch_core::load` sends the request through `send`, reports
+//! running totals as `load://stats` events and the summary as `load://finished`. Summaries
+//! are kept per workspace under `.litefetch/load`, one file per run, for export and for
+//! comparing runs.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::async_runtime::Mutex;
+use tauri::{Emitter, State};
+
+use litefetch_core::json::{array_of, find_request};
+use litefetch_core::load::{
+    Latency, LoadComparison, LoadOptions, LoadStats, LoadSummary, DEFAULT_REGRESSION_PCT,
+};
+use litefetch_core::load_report::LoadReportFormat;
+
+use crate::send::Shell;
+
+pub struct LoadState {
+    /// Cancel flags of the load tests in progress.
+    runs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl LoadState {
+    pub fn new() -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct StartedEvent {
+    run_id: String,
+    collection_id: String,
+    request_id: String,
+    duration_secs: u64,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct StatsEvent {
+    run_id: String,
+    stats: LoadStats,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct FinishedEvent {
+    summary: LoadSummary,
+    timestamp_ms: u64,
+}
+
+/// A saved run without its histogram and timeline, for listing.
+#[derive(Serialize)]
+pub struct LoadRunInfo {
+    run_id: String,
+    collection_id: String,
+    request_id: String,
+    name: String,
+    started_ms: u64,
+    duration_ms: u64,
+    sent: usize,
+    error_rate: f64,
+    throughput_rps: f64,
+    latency: Latency,
+}
+
+fn runs_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    crate::workspace_state_dir(app, "load")
+}
+
+/// Run ids come back from the UI; keep them to the file they name.
+fn run_path(app: &tauri::AppHandle, run_id: &str) -> Result<PathBuf, String> {
+    if run_id.is_empty()
+        || !run_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(format!("unknown load test: {run_id}"));
+    }
+    Ok(runs_dir(app)?.join(format!("{run_id}.json")))
+}
+
+fn load_run(app: &tauri::AppHandle, run_id: &str) -> Result<LoadSummary, String> {
+    let path = run_path(app, run_id)?;
+    if !path.exists() {
+        return Err(format!("unknown load test: {run_id}"));
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("load test read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("load test parse failed: {e}"))
+}
+
+/// Load-tests one saved request and returns the summary once the duration is up. The run
+/// id arrives first in a `load://started` event, for `cancel_load_test`.
+#[tauri::command]
+pub async fn run_load_test(
+    app: tauri::AppHandle,
+    state: State<'_, LoadState>,
+    collection_id: String,
+    request_id: String,
+    options: LoadOptions,
+) -> Result<LoadSummary, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let request = find_request(array_of(&collection, "items"), &request_id)
+        .cloned()
+        .ok_or_else(|| format!("unknown request: {request_id}"))?;
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    state
+        .runs
+        .lock()
+        .await
+        .insert(run_id.clone(), cancel.clone());
+    let _ = app.emit(
+        "load://started",
+        StartedEvent {
+            run_id: run_id.clone(),
+            collection_id: collection_id.clone(),
+            request_id,
+            duration_secs: options.duration_secs,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    let sender = Shell {
+        app: app.clone(),
+        collection_id: collection_id.clone(),
+        environment_id: options.environment_id.clone(),
+    };
+    let summary = litefetch_core::load::execute(
+        run_id.clone(),
+        collection_id,
+        &request,
+        &options,
+        &sender,
+        &cancel,
+        |stats| {
+            let _ = app.emit(
+                "load://stats",
+                StatsEvent {
+                    run_id: run_id.clone(),
+                    stats: stats.clone(),
+                    timestamp_ms: crate::now_ms(),
+                },
+            );
+        },
+    )
+    .await;
+    state.runs.lock().await.remove(&run_id);
+
+    let payload =
+        serde_json::to_string(&summary).map_err(|e| format!("load test serialize failed: {e}"))?;
+    fs::write(run_path(&app, &run_id)?, payload)
+        .map_err(|e| format!("load test persist failed: {e}"))?;
+    let _ = app.emit(
+        "load://finished",
+        FinishedEvent {
+            summary: summary.clone(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    Ok(summary)
+}
+
+/// Stops a load test early; requests in flight still finish and the summary is kept.
+#[tauri::command]
+pub async fn cancel_load_test(state: State<'_, LoadState>, run_id: String) -> Result<(), String> {
+    let runs = state.runs.lock().await;
+    let cancel = runs
+        .get(&run_id)
+        .ok_or_else(|| format!("unknown load test: {run_id}"))?;
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Saved load tests, newest first; only those of one request when `request_id` is given.
+#[tauri::command]
+pub async fn list_load_tests(
+    app: tauri::AppHandle,
+    request_id: Option<String>,
+) -> Result<Vec<LoadRunInfo>, String> {
+    let entries =
+        fs::read_dir(runs_dir(&app)?).map_err(|e| format!("load tests read failed: {e}"))?;
+    let mut runs = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(summary) = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|data| serde_json::from_str::<LoadSummary>(&data).map_err(|e| e.to_string()))
+        else {
+            continue;
+        };
+        if request_id
+            .as_ref()
+            .is_some_and(|id| *id != summary.request_id)
+        {
+            continue;
+        }
+        runs.push(LoadRunInfo {
+            run_id: summary.run_id,
+            collection_id: summary.collection_id,
+            request_id: summary.request_id,
+            name: summary.name,
+            started_ms: summary.started_ms,
+            duration_ms: summary.duration_ms,
+            sent: summary.sent,
+            error_rate: summary.error_rate,
+            throughput_rps: summary.throughput_rps,
+            latency: summary.latency,
+        });
+    }
+    runs.sort_by_key(|run| std::cmp::Reverse(run.started_ms));
+    Ok(runs)
+}
+
+#[tauri::command]
+pub async fn get_load_test(app: tauri::AppHandle, run_id: String) -> Result<LoadSummary, String> {
+    load_run(&app, &run_id)
+}
+
+#[tauri::command]
+pub async fn delete_load_test(app: tauri::AppHandle, run_id: String) -> Result<(), String> {
+    let path = run_path(&app, &run_id)?;
+    fs::remove_file(path).map_err(|e| format!("load test delete failed: {e}"))
+}
+
+/// Writes a saved load test to `path` as CSV (its timeline) or an HTML report and returns
+/// the path.
+#[tauri::command]
+pub async fn export_load_report(
+    app: tauri::AppHandle,
+    run_id: String,
+    format: LoadReportFormat,
+    path: String,
+    title: Option<String>,
+) -> Result<String, String> {
+    let summary = load_run(&app, &run_id)?;
+    let title = title
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| format!("LiteFetch load test: {}", summary.name));
+    let content = litefetch_core::load_report::render(&summary, format, &title)?;
+    let target = crate::normalize_path(&path);
+    fs::write(&target, content).map_err(|e| format!("report write failed: {e}"))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Compares two saved load tests and flags metrics that got worse by more than
+/// `threshold_pct` (10% by default).
+#[tauri::command]
+pub async fn compare_load_tests(
+    app: tauri::AppHandle,
+    before: String,
+    after: String,
+    threshold_pct: Option<f64>,
+) -> Result<LoadComparison, String> {
+    let before = load_run(&app, &before)?;
+    let after = load_run(&app, &after)?;
+    Ok(litefetch_core::load::compare(
+        &before,
+        &after,
+        threshold_pct.unwrap_or(DEFAULT_REGRESSION_PCT),
+    ))
+}
@@ -0,0 +1,338 @@
+//! Keeps two LiteFetch instances from writing the same workspace. The instance that starts a
+//! backend against a workspace owns it through `.litefetch/instance.lock` (pid, start time and
+//! a heartbeat refreshed while it runs). Another instance is refused until the lock goes stale,
+//! or opens the workspace read-only: its backend then rejects writes and the shell's own
+//! writers (sync, git, restores, imports) stay off. An instance that loses its lock (another
+//! took it over while this one looked stale) turns read-only too, restarts its backend that
+//! way and emits `workspace://lock-lost`.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager, State};
+
+const LOCK_FILE: &str = "instance.lock";
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+/// A lock whose heartbeat is older than this belongs to an instance that exited uncleanly.
+const STALE_AFTER_MS: u64 = 60_000;
+
+pub struct LockState {
+    /// Tells this instance's lock apart from one written by an earlier process with the same pid.
+    instance_id: String,
+    /// The lock file this instance owns.
+    held: Mutex<Option<PathBuf>>,
+    /// The workspace the user chose to open read-only.
+    read_only: Mutex<Option<PathBuf>>,
+}
+
+impl LockState {
+    pub fn new() -> Self {
+        Self {
+            instance_id: uuid::Uuid::new_v4().to_string(),
+            held: Mutex::new(None),
+            read_only: Mutex::new(None),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LockOwner {
+    pub pid: u32,
+    pub instance_id: String,
+    pub started_ms: u64,
+    pub heartbeat_ms: u64,
+}
+
+impl LockOwner {
+    fn is_stale(&self) -> bool {
+        crate::now_ms().saturating_sub(self.heartbeat_ms) > STALE_AFTER_MS
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct LockLostEvent {
+    pub workspace: String,
+    /// The instance that holds the lock now, if any.
+    pub owner: Option<LockOwner>,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct LockStatus {
+    pub read_only: bool,
+    /// The live instance that owns the workspace, when it is not this one.
+    pub owner: Option<LockOwner>,
+}
+
+/// How this instance may use the current workspace.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Access {
+    Exclusive,
+    ReadOnly,
+}
+
+fn lock_path(root: &Path) -> PathBuf {
+    root.join(".litefetch").join(LOCK_FILE)
+}
+
+fn read_owner(path: &Path) -> Option<LockOwner> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// Creates the lock only if there is none, so of two instances claiming it at once exactly
+/// one succeeds; `Ok(false)` when a lock file already exists.
+fn create_owner(path: &Path, owner: &LockOwner) -> Result<bool, String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("workspace lock failed: {e}"))?;
+    }
+    let payload =
+        serde_json::to_string_pretty(owner).map_err(|e| format!("workspace lock failed: {e}"))?;
+    let mut file = match fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+    {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Ok(false),
+        Err(e) => return Err(format!("workspace lock failed: {e}")),
+    };
+    file.write_all(payload.as_bytes())
+        .map_err(|e| format!("workspace lock failed: {e}"))?;
+    Ok(true)
+}
+
+/// Moves the lock at `path` to a name of this instance's own; `None` when there is no lock.
+/// A rename succeeds for one instance at most, so only the instance that moved a lock acts on
+/// it. Used to remove a lock, never to refresh one: while it is aside the workspace looks
+/// free.
+fn move_aside(path: &Path, instance_id: &str) -> Result<Option<PathBuf>, String> {
+    let aside = path.with_extension(format!("lock.{instance_id}"));
+    match fs::rename(path, &aside) {
+        Ok(()) => Ok(Some(aside)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(format!("workspace lock failed: {e}")),
+    }
+}
+
+/// Moves a lock back from `aside` unless another one was created at `path` meanwhile, in
+/// which case it is dropped. Whether it landed.
+fn put_back(aside: &Path, path: &Path) -> bool {
+    let landed = match fs::hard_link(aside, path) {
+        Ok(()) => true,
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => false,
+        // Filesystems without hard links.
+        Err(_) => !path.exists() && fs::rename(aside, path).is_ok(),
+    };
+    let _ = fs::remove_file(aside);
+    landed
+}
+
+/// Refreshes the heartbeat of this instance's lock in place: written next to it and renamed
+/// over it, so a reader never sees a partial file. `Ok(false)` when the lock is gone or owned
+/// by another instance. A live lock is never taken over, so the rename only races a takeover
+/// once this instance already looked stale.
+fn renew(path: &Path, instance_id: &str) -> Result<bool, String> {
+    let Some(mut owner) = read_owner(path).filter(|owner| owner.instance_id == instance_id) else {
+        return Ok(false);
+    };
+    owner.heartbeat_ms = crate::now_ms();
+    let payload =
+        serde_json::to_string_pretty(&owner).map_err(|e| format!("workspace lock failed: {e}"))?;
+    let tmp = path.with_extension(format!("lock.{instance_id}.new"));
+    fs::write(&tmp, payload).map_err(|e| format!("workspace lock failed: {e}"))?;
+    fs::rename(&tmp, path).map_err(|e| format!("workspace lock failed: {e}"))?;
+    Ok(true)
+}
+
+fn unlocked<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// The live owner of the workspace at `root`, when it is another instance.
+fn other_owner(state: &LockState, root: &Path) -> Option<LockOwner> {
+    read_owner(&lock_path(root))
+        .filter(|owner| owner.instance_id != state.instance_id && !owner.is_stale())
+}
+
+fn owned_elsewhere(owner: &LockOwner) -> String {
+    format!(
+        "workspace is open in another LiteFetch instance (pid {}); close it or open the workspace read-only",
+        owner.pid
+    )
+}
+
+/// Takes the current workspace's lock before a backend is spawned against it. Fails when
+/// another live instance owns the workspace, unless the user chose read-only mode for it.
+pub fn acquire(app: &tauri::AppHandle) -> Result<Access, String> {
+    let state = app.state::<LockState>();
+    let root = crate::load_workspace_path(app)?;
+    if unlocked(&state.read_only).as_deref() == Some(root.as_path()) {
+        return Ok(Access::ReadOnly);
+    }
+    let path = lock_path(&root);
+    let now = crate::now_ms();
+    let owner = LockOwner {
+        pid: std::process::id(),
+        instance_id: state.instance_id.clone(),
+        started_ms: now,
+        heartbeat_ms: now,
+    };
+    // A stale lock is removed and the claim tried once more.
+    let mut claimed = false;
+    for _ in 0..2 {
+        if create_owner(&path, &owner)? {
+            claimed = true;
+            break;
+        }
+        match read_owner(&path) {
+            Some(current) if current.instance_id == state.instance_id => {
+                if renew(&path, &state.instance_id)? {
+                    claimed = true;
+                    break;
+                }
+                continue;
+            }
+            Some(current) if !current.is_stale() => return Err(owned_elsewhere(&current)),
+            Some(_) => {}
+            // Just created by another instance that hasn't written it yet.
+            None if !lock_file_stale(&path) => {
+                return Err("workspace lock failed: another instance is taking the lock".into())
+            }
+            None => {}
+        }
+        // Judged stale above, but it may have been renewed or taken over since: it is only
+        // removed if it is still stale once this instance alone holds it.
+        if let Some(aside) = move_aside(&path, &state.instance_id)? {
+            let stale = match read_owner(&aside) {
+                Some(moved) => moved.is_stale(),
+                None => lock_file_stale(&aside),
+            };
+            if stale {
+                let _ = fs::remove_file(&aside);
+            } else {
+                put_back(&aside, &path);
+            }
+        }
+    }
+    if !claimed {
+        return Err("workspace lock failed: another instance is taking the lock".into());
+    }
+    let previous = unlocked(&state.held).replace(path.clone());
+    if let Some(previous) = previous.filter(|previous| *previous != path) {
+        remove_if_ours(&state, &previous);
+    }
+    Ok(Access::Exclusive)
+}
+
+/// Whether the lock file at `path` was last written longer ago than a heartbeat allows.
+fn lock_file_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_none_or(|age| age.as_millis() as u64 > STALE_AFTER_MS)
+}
+
+fn remove_if_ours(state: &LockState, path: &Path) {
+    if !read_owner(path).is_some_and(|owner| owner.instance_id == state.instance_id) {
+        return;
+    }
+    let Ok(Some(aside)) = move_aside(path, &state.instance_id) else {
+        return;
+    };
+    if read_owner(&aside).is_some_and(|owner| owner.instance_id == state.instance_id) {
+        let _ = fs::remove_file(&aside);
+    } else {
+        put_back(&aside, path);
+    }
+}
+
+/// Gives up the workspace this instance owns, on exit or before switching workspaces.
+pub fn release(app: &tauri::AppHandle) {
+    let state = app.state::<LockState>();
+    if let Some(path) = unlocked(&state.held).take() {
+        remove_if_ours(&state, &path);
+    }
+    *unlocked(&state.read_only) = None;
+}
+
+/// Fails while the current workspace is open read-only, for shell features that write to it.
+pub fn ensure_writable(app: &tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<LockState>();
+    let root = crate::load_workspace_path(app)?;
+    if unlocked(&state.read_only).as_deref() == Some(root.as_path()) {
+        return Err("workspace is read-only: it is open in another LiteFetch instance".into());
+    }
+    Ok(())
+}
+
+/// Refreshes the heartbeat of the held lock for as long as the app runs. A lock that is gone
+/// or owned by another instance turns this one read-only and restarts its backend read-only.
+pub async fn heartbeat_periodically(app: tauri::AppHandle) {
+    let mut ticks = tokio::time::interval(HEARTBEAT_INTERVAL);
+    loop {
+        ticks.tick().await;
+        let state = app.state::<LockState>();
+        let Some(path) = unlocked(&state.held).clone() else {
+            continue;
+        };
+        match renew(&path, &state.instance_id) {
+            Ok(true) => {}
+            Err(e) => tracing::warn!("{e}"),
+            // Taken over after this instance looked stale (e.g. the machine slept), or removed.
+            Ok(false) => {
+                tracing::warn!("lost workspace lock {}", path.to_string_lossy());
+                unlocked(&state.held).take();
+                let Some(root) = path.parent().and_then(Path::parent) else {
+                    continue;
+                };
+                *unlocked(&state.read_only) = Some(root.to_path_buf());
+                // The running backend was started with write access.
+                if let Err(e) = crate::restart_backend(&app).await {
+                    tracing::warn!("backend restart after losing the workspace lock failed: {e}");
+                }
+                let current = read_owner(&path);
+                let _ = app.emit(
+                    "workspace://lock-lost",
+                    LockLostEvent {
+                        workspace: root.to_string_lossy().to_string(),
+                        owner: current,
+                        timestamp_ms: crate::now_ms(),
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_workspace_lock(
+    app: tauri::AppHandle,
+    state: State<'_, LockState>,
+) -> Result<LockStatus, String> {
+    let root = crate::load_workspace_path(&app)?;
+    Ok(LockStatus {
+        read_only: unlocked(&state.read_only).as_deref() == Some(root.as_path()),
+        owner: other_owner(&state, &root),
+    })
+}
+
+/// Opens the current workspace read-only, for when another instance owns it; a no-op when this
+/// instance already owns it. Returns the backend base URL like `start_backend`.
+#[tauri::command]
+pub async fn open_workspace_read_only(
+    app: tauri::AppHandle,
+    state: State<'_, LockState>,
+) -> Result<String, String> {
+    let root = crate::load_workspace_path(&app)?;
+    let owned = unlocked(&state.held).as_deref() == Some(lock_path(&root).as_path());
+    if !owned {
+        *unlocked(&state.read_only) = Some(root);
+    }
+    crate::backend_url(&app).await
+}
@@ -0,0 +1,212 @@
+//! The shell's logs, through `tracing`: readable lines on stderr, and JSON lines in
+//! `logs/shell.<date>.log` under the app data root, rotated daily with a week kept. The
+//! level can be raised at runtime (`set_log_level`) to capture a problem without a restart,
+//! and `get_recent_logs` reads the JSON files back for the UI.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tauri::State;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+const LOG_DIR: &str = "logs";
+const LOG_PREFIX: &str = "shell";
+const KEPT_FILES: usize = 7;
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 5000;
+/// Read from the environment at startup, e.g. `LITEFETCH_LOG=debug`.
+const LEVEL_ENV: &str = "LITEFETCH_LOG";
+
+pub struct LogState {
+    level: OnceLock<reload::Handle<LevelFilter, Registry>>,
+    dir: OnceLock<PathBuf>,
+}
+
+impl LogState {
+    pub fn new() -> Self {
+        Self {
+            level: OnceLock::new(),
+            dir: OnceLock::new(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct LogFilter {
+    /// Least severe level to include: `error`, `warn`, `info`, `debug` or `trace`.
+    pub level: Option<String>,
+    /// Part of the module the entry came from, e.g. `oauth` or `backend`.
+    pub target: Option<String>,
+    /// Text the message must contain, ignoring case.
+    pub text: Option<String>,
+    /// Newest entries returned; 200 by default.
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+    /// Structured fields other than the message.
+    pub fields: Map<String, Value>,
+}
+
+fn parse_level(text: &str) -> Result<LevelFilter, String> {
+    text.trim().parse::<LevelFilter>().map_err(|_| {
+        format!("unknown log level {text:?}; expected error, warn, info, debug or trace")
+    })
+}
+
+/// Installs the global subscriber. Called once at startup; if the log directory can't be
+/// used, logging continues on stderr alone.
+pub fn init(app: &tauri::AppHandle, state: &LogState) {
+    let initial = std::env::var(LEVEL_ENV)
+        .ok()
+        .and_then(|level| parse_level(&level).ok())
+        .unwrap_or(LevelFilter::INFO);
+    let (filter, handle) = reload::Layer::new(initial);
+    let dir = crate::app_data_root(app).map(|root| root.join(LOG_DIR));
+    let appender = dir.as_ref().map_err(Clone::clone).and_then(|dir| {
+        RollingFileAppender::builder()
+            .rotation(Rotation::DAILY)
+            .filename_prefix(LOG_PREFIX)
+            .filename_suffix("log")
+            .max_log_files(KEPT_FILES)
+            .build(dir)
+            .map_err(|e| format!("log file init failed: {e}"))
+    });
+    let (file, problem) = match appender {
+        Ok(appender) => (
+            Some(
+                fmt::layer()
+                    .json()
+                    .flatten_event(true)
+                    .with_ansi(false)
+                    .with_writer(appender),
+            ),
+            None,
+        ),
+        Err(e) => (None, Some(e)),
+    };
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stderr))
+        .with(file)
+        .try_init();
+    if installed.is_err() {
+        return;
+    }
+    let _ = state.level.set(handle);
+    if let Ok(dir) = dir {
+        let _ = state.dir.set(dir);
+    }
+    if let Some(problem) = problem {
+        tracing::warn!("{problem}");
+    }
+}
+
+/// Changes what gets logged from now on; the level isn't kept across restarts.
+#[tauri::command]
+pub async fn set_log_level(state: State<'_, LogState>, level: String) -> Result<(), String> {
+    let level = parse_level(&level)?;
+    let handle = state.level.get().ok_or("logging is not initialised")?;
+    handle
+        .modify(|current| *current = level)
+        .map_err(|e| format!("log level change failed: {e}"))?;
+    tracing::info!("log level set to {level}");
+    Ok(())
+}
+
+/// The log files, oldest first (dated names sort that way).
+fn log_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("log read failed: {e}"))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(LOG_PREFIX) && n.ends_with(".log"))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// The last `count` lines of the current log file, raw; empty when there is none.
+pub(crate) fn tail(state: &LogState, count: usize) -> Vec<String> {
+    let Some(path) = state
+        .dir
+        .get()
+        .and_then(|dir| log_files(dir).ok())
+        .and_then(|files| files.last().cloned())
+    else {
+        return Vec::new();
+    };
+    let data = fs::read_to_string(path).unwrap_or_default();
+    let lines: Vec<&str> = data.lines().collect();
+    let start = lines.len().saturating_sub(count);
+    lines[start..].iter().map(|l| l.to_string()).collect()
+}
+
+fn entry(line: &str) -> Option<LogEntry> {
+    let Value::Object(mut fields) = serde_json::from_str::<Value>(line).ok()? else {
+        return None;
+    };
+    let mut take = |key: &str| match fields.remove(key) {
+        Some(Value::String(text)) => text,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    Some(LogEntry {
+        timestamp: take("timestamp"),
+        level: take("level"),
+        target: take("target"),
+        message: take("message"),
+        fields,
+    })
+}
+
+/// The newest log entries matching `filter`, oldest first, from the current and previous
+/// log files.
+#[tauri::command]
+pub async fn get_recent_logs(
+    state: State<'_, LogState>,
+    filter: Option<LogFilter>,
+) -> Result<Vec<LogEntry>, String> {
+    let filter = filter.unwrap_or_default();
+    let least = filter
+        .level
+        .as_deref()
+        .filter(|l| !l.trim().is_empty())
+        .map(parse_level)
+        .transpose()?
+        .unwrap_or(LevelFilter::TRACE);
+    let target = filter.target.unwrap_or_default().to_lowercase();
+    let text = filter.text.unwrap_or_default().to_lowercase();
+    let limit = filter.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let dir = state.dir.get().ok_or("logging is not initialised")?;
+
+    let mut entries = Vec::new();
+    let files = log_files(dir)?;
+    for path in files.iter().rev().take(2).rev() {
+        let data = fs::read_to_string(path).map_err(|e| format!("log read failed: {e}"))?;
+        entries.extend(data.lines().filter_map(entry).filter(|e| {
+            let level = e.level.parse::<LevelFilter>().unwrap_or(LevelFilter::TRACE);
+            level <= least
+                && e.target.to_lowercase().contains(&target)
+                && e.message.to_lowercase().contains(&text)
+        }));
+    }
+    let skip = entries.len().saturating_sub(limit);
+    Ok(entries.into_iter().skip(skip).collect())
+}
@@ -1,9 +1,102 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-use serde::{Deserialize, Serialize};
+mod audit;
+mod autocomplete;
+mod backups;
+mod bulk;
+mod bundle;
+mod captures;
+mod client_profiles;
+mod clipboard;
+mod codegen;
+mod contract;
+mod crash;
+mod defaults;
+mod diff;
+mod dns;
+mod docs;
+mod drafts;
+mod dynamic;
+mod editor;
+mod environments;
+mod fixtures;
+mod format;
+mod git;
+mod graphql;
+mod graphql_persisted;
+mod grpc;
+mod grpc_web;
+mod handshake;
+mod har;
+mod history;
+mod hooks;
+mod hypermedia;
+mod importers;
+mod integrity;
+mod jsonrpc;
+mod jwt;
+mod keymap;
+mod launch;
+mod lint;
+mod load;
+mod lock;
+mod logging;
+mod metrics;
+mod mock;
+mod monitor;
+mod mqtt;
+mod network;
+mod notes;
+mod notifications;
+mod oauth;
+mod offline;
+mod pagination;
+mod pins;
+mod plugins;
+mod presign;
+mod probe;
+mod proxy;
+mod redact;
+mod rendering;
+mod replay;
+mod report;
+mod responses;
+mod routing;
+mod runner;
+mod scheduler;
+mod schema;
+mod scripting;
+mod sealed_env;
+mod search;
+mod secrets;
+mod security;
+mod self_check;
+mod send;
+mod session;
+mod sidecar;
+mod snapshots;
+mod soap;
+mod socket;
+mod starters;
+mod storage;
+mod streaming;
+mod sync;
+mod templates;
+mod trace;
+mod traceroute;
+mod tunnel;
+mod upload;
+mod variables;
+mod vault;
+mod watcher;
+mod webhook;
+mod websocket;
+mod workflow;
+
+use litefetch_core::workspace::{self, normalize_path, WorkspaceConfig};
+use litefetch_core::{backend, now_ms, portable, profile};
 use std::collections::HashMap;
 use std::fs;
-use std::net::TcpListener;
 use std::path::PathBuf;
 use tauri::async_runtime::Mutex;
 use tauri::{Manager, State, WindowEvent};
@@ -15,69 +108,33 @@ struct BackendState {
     base_url: Mutex<Option<String>>,
 }
 
-#[derive(Serialize, Deserialize)]
-struct WorkspaceConfig {
-    path: String,
-}
-
 fn app_data_root(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let mut base = app
-        .path()
-        .app_data_dir()
-        .map_err(|e| format!("unable to resolve app data dir: {e}"))?;
-    base.push("litefetch");
-    fs::create_dir_all(&base).map_err(|e| format!("workspace init failed: {e}"))?;
-    Ok(base)
-}
-
-fn default_workspace(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let mut base = app_data_root(app)?;
-    base.push("workspace");
+    let base = profile::data_root(&portable::base(|| {
+        app.path()
+            .app_data_dir()
+            .map_err(|e| format!("unable to resolve app data dir: {e}"))
+    })?);
     fs::create_dir_all(&base).map_err(|e| format!("workspace init failed: {e}"))?;
     Ok(base)
 }
 
 fn workspace_config_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
     let mut root = app_data_root(app)?;
-    root.push("workspace_path.json");
+    root.push(workspace::CONFIG_FILE);
     Ok(root)
 }
 
-fn normalize_path(path: &str) -> PathBuf {
-    if path == "~" {
-        if let Ok(home) = std::env::var("HOME") {
-            return PathBuf::from(home);
-        }
-    }
-    if path.starts_with("~/") {
-        if let Ok(home) = std::env::var("HOME") {
-            let rest = path.trim_start_matches("~/");
-            return PathBuf::from(home).join(rest);
-        }
-    }
-    let candidate = PathBuf::from(path);
-    if candidate.is_absolute() {
-        candidate
-    } else if let Ok(cwd) = std::env::current_dir() {
-        cwd.join(candidate)
-    } else {
-        candidate
-    }
+/// Per-workspace directory for shell-managed caches and state (`<workspace>/.litefetch/<name>`).
+fn workspace_state_dir(app: &tauri::AppHandle, name: &str) -> Result<PathBuf, String> {
+    let mut dir = load_workspace_path(app)?;
+    dir.push(".litefetch");
+    dir.push(name);
+    fs::create_dir_all(&dir).map_err(|e| format!("{name} state init failed: {e}"))?;
+    Ok(dir)
 }
 
 fn load_workspace_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
-    let cfg_path = workspace_config_path(app)?;
-    if cfg_path.exists() {
-        let data =
-            fs::read_to_string(&cfg_path).map_err(|e| format!("workspace read failed: {e}"))?;
-        if let Ok(cfg) = serde_json::from_str::<WorkspaceConfig>(&data) {
-            let path = normalize_path(&cfg.path);
-            fs::create_dir_all(&path)
-                .map_err(|e| format!("workspace init failed for stored path: {e}"))?;
-            return Ok(path);
-        }
-    }
-    default_workspace(app)
+    workspace::resolve(&app_data_root(app)?)
 }
 
 fn persist_workspace_path(app: &tauri::AppHandle, path: &str) -> Result<PathBuf, String> {
@@ -90,59 +147,83 @@ fn persist_workspace_path(app: &tauri::AppHandle, path: &str) -> Result<PathBuf,
     };
     fs::write(
         &cfg_path,
-        serde_json::to_string_pretty(&payload).map_err(|e| format!("workspace serialize failed: {e}"))?,
+        serde_json::to_string_pretty(&payload)
+            .map_err(|e| format!("workspace serialize failed: {e}"))?,
     )
     .map_err(|e| format!("workspace persist failed: {e}"))?;
     Ok(normalized)
 }
 
-fn reserve_port() -> Result<u16, String> {
-    let socket = TcpListener::bind("127.0.0.1:0").map_err(|e| format!("port bind failed: {e}"))?;
-    let port = socket
-        .local_addr()
-        .map_err(|e| format!("port discovery failed: {e}"))?
-        .port();
-    drop(socket);
-    Ok(port)
-}
-
 #[tauri::command]
-async fn spawn_backend(app: &tauri::AppHandle, state: &State<'_, BackendState>) -> Result<String, String> {
+async fn spawn_backend(
+    app: &tauri::AppHandle,
+    state: &State<'_, BackendState>,
+) -> Result<String, String> {
     if let Some(url) = state.base_url.lock().await.clone() {
+        handshake::refusal(app)?;
         return Ok(url);
     }
 
+    let access = lock::acquire(app)?;
     let workspace = load_workspace_path(app)?;
-    let port = reserve_port()?;
+    let port = backend::reserve_port()?;
 
     let mut envs = HashMap::new();
     envs.insert("PORT".to_string(), port.to_string());
     envs.insert(
-            "LITEFETCH_WORKSPACE".to_string(),
+        "LITEFETCH_WORKSPACE".to_string(),
+        workspace.to_string_lossy().to_string(),
+    );
+    if access == lock::Access::ReadOnly {
+        envs.insert("LITEFETCH_READ_ONLY".to_string(), "1".to_string());
+    }
+
+    let args = vec![
+        "--host".to_string(),
+        "127.0.0.1".to_string(),
+        "--port".to_string(),
+        port.to_string(),
+        "--dir".to_string(),
         workspace.to_string_lossy().to_string(),
+    ];
+    let (binary, args, envs) = launch::merge(app, args, envs);
+    launch::log(
+        binary.as_deref().unwrap_or("litefetch-backend"),
+        &args,
+        &envs,
     );
+    let command = match &binary {
+        Some(path) => app.shell().command(path),
+        None => app
+            .shell()
+            .sidecar("litefetch-backend")
+            .map_err(|e| format!("backend sidecar missing: {e}"))?,
+    }
+    .envs(envs)
+    .args(args);
 
-    let command = app
-        .shell()
-        .sidecar("litefetch-backend")
-        .map_err(|e| format!("backend sidecar missing: {e}"))?
-        .envs(envs)
-        .args([
-            "--host",
-            "127.0.0.1",
-            "--port",
-            &port.to_string(),
-            "--dir",
-            workspace.to_string_lossy().as_ref(),
-        ]);
-
-    let (mut rx, child) = command.spawn().map_err(|e| format!("failed to start backend: {e}"))?;
+    let (mut rx, child) = command
+        .spawn()
+        .map_err(|e| format!("failed to start backend: {e}"))?;
 
     tauri::async_runtime::spawn(async move {
         while let Some(event) = rx.recv().await {
             match event {
-                CommandEvent::Stdout(line) => println!("[backend] {}", String::from_utf8_lossy(&line)),
-                CommandEvent::Stderr(line) => eprintln!("[backend] {}", String::from_utf8_lossy(&line)),
+                CommandEvent::Stdout(line) => {
+                    tracing::info!(
+                        target: "backend",
+                        "{}",
+                        redact::log_line(&String::from_utf8_lossy(&line))
+                    )
+                }
+                CommandEvent::Stderr(line) => {
+                    tracing::info!(
+                        target: "backend",
+                        stream = "stderr",
+                        "{}",
+                        redact::log_line(&String::from_utf8_lossy(&line))
+                    )
+                }
                 _ => {}
             }
         }
@@ -159,11 +240,113 @@ async fn spawn_backend(app: &tauri::AppHandle, state: &State<'_, BackendState>)
         *url_guard = Some(base_url.clone());
     }
 
+    handshake::check(app, &base_url).await?;
     Ok(base_url)
 }
 
+/// Stops the backend and starts it again against the current workspace, e.g. read-only once
+/// the workspace lock was lost.
+async fn restart_backend(app: &tauri::AppHandle) -> Result<String, String> {
+    let state = app.state::<BackendState>();
+    shutdown_backend_async(&state).await;
+    *state.base_url.lock().await = None;
+    spawn_backend(app, &state).await
+}
+
+/// Resolves the backend API base URL, starting the sidecar if it is not running yet.
+async fn backend_url(app: &tauri::AppHandle) -> Result<String, String> {
+    let state = app.state::<BackendState>();
+    spawn_backend(app, &state).await
+}
+
+/// Creates a collection through the backend so vault encryption and metadata stay consistent.
+/// Returns the new collection's meta.
+async fn create_collection(
+    app: &tauri::AppHandle,
+    name: &str,
+    collection: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    create_collection_with(
+        app,
+        serde_json::json!({ "name": name, "collection": collection }),
+    )
+    .await
+}
+
+/// Like `create_collection`, but posts a full payload (e.g. with seeded history/last_results).
+async fn create_collection_with(
+    app: &tauri::AppHandle,
+    payload: serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    let base_url = backend_url(app).await?;
+    let response = reqwest::Client::new()
+        .post(format!("{base_url}/collections"))
+        .header(backend::ORIGIN_HEADER, "import")
+        .json(&payload)
+        .send()
+        .await
+        .map_err(|e| format!("collection create failed: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "collection create failed: HTTP {}",
+            response.status()
+        ));
+    }
+    response
+        .json()
+        .await
+        .map_err(|e| format!("collection create failed: {e}"))
+}
+
+/// GETs a backend API path (e.g. `/collections/<id>/history`) and returns the JSON body.
+async fn backend_get(app: &tauri::AppHandle, path: &str) -> Result<serde_json::Value, String> {
+    backend::get(&backend_url(app).await?, path).await
+}
+
+/// POSTs JSON to a backend API path, with the same locked-workspace handling as `backend_get`.
+async fn backend_post(
+    app: &tauri::AppHandle,
+    path: &str,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    backend::post(&backend_url(app).await?, path, body).await
+}
+
+/// Like `backend_post`, for writes made by an import, so the audit log attributes them to it.
+async fn import_post(
+    app: &tauri::AppHandle,
+    path: &str,
+    body: &serde_json::Value,
+) -> Result<serde_json::Value, String> {
+    backend::post_as(&backend_url(app).await?, path, body, "import").await
+}
+
+/// Groups `(folder name, request)` pairs into collection folders, keeping first-seen order.
+fn group_into_folders(entries: Vec<(String, serde_json::Value)>) -> Vec<serde_json::Value> {
+    let mut folders: Vec<(String, Vec<serde_json::Value>)> = Vec::new();
+    for (folder, request) in entries {
+        match folders.iter_mut().find(|(name, _)| *name == folder) {
+            Some((_, items)) => items.push(request),
+            None => folders.push((folder, vec![request])),
+        }
+    }
+    folders
+        .into_iter()
+        .map(|(name, items)| {
+            serde_json::json!({
+                "id": uuid::Uuid::new_v4().to_string(),
+                "name": name,
+                "items": items,
+            })
+        })
+        .collect()
+}
+
 #[tauri::command]
-async fn start_backend(app: tauri::AppHandle, state: State<'_, BackendState>) -> Result<String, String> {
+async fn start_backend(
+    app: tauri::AppHandle,
+    state: State<'_, BackendState>,
+) -> Result<String, String> {
     spawn_backend(&app, &state).await
 }
 
@@ -171,11 +354,17 @@ async fn start_backend(app: tauri::AppHandle, state: State<'_, BackendState>) ->
 async fn set_workspace_path(app: tauri::AppHandle, path: String) -> Result<String, String> {
     let persisted = persist_workspace_path(&app, path.trim())
         .map_err(|e| format!("failed to persist workspace: {e}"))?;
+    watcher::watch(&app);
     Ok(persisted.to_string_lossy().to_string())
 }
 
 #[tauri::command]
-async fn switch_workspace(app: tauri::AppHandle, state: State<'_, BackendState>, path: String) -> Result<String, String> {
+async fn switch_workspace(
+    app: tauri::AppHandle,
+    state: State<'_, BackendState>,
+    path: String,
+) -> Result<String, String> {
+    lock::release(&app);
     let persisted = persist_workspace_path(&app, path.trim())
         .map_err(|e| format!("failed to persist workspace: {e}"))?;
 
@@ -187,10 +376,29 @@ async fn switch_workspace(app: tauri::AppHandle, state: State<'_, BackendState>,
     }
     // Spawn backend with the new workspace; ignore base URL return here since the frontend will re-resolve.
     let _ = spawn_backend(&app, &state).await?;
-    println!("[workspace] switched to {}", persisted.to_string_lossy());
+    monitor::resume(app.clone()).await;
+    watcher::watch(&app);
+    tracing::info!("workspace switched to {}", persisted.to_string_lossy());
     Ok(persisted.to_string_lossy().to_string())
 }
 
+#[derive(serde::Serialize)]
+struct ProfileInfo {
+    /// `None` for the default profile.
+    name: Option<String>,
+    portable: bool,
+    data_root: String,
+}
+
+#[tauri::command]
+async fn get_profile(app: tauri::AppHandle) -> Result<ProfileInfo, String> {
+    Ok(ProfileInfo {
+        name: profile::current().map(str::to_string),
+        portable: portable::dir().is_some(),
+        data_root: app_data_root(&app)?.to_string_lossy().to_string(),
+    })
+}
+
 fn shutdown_backend(state: &State<BackendState>) {
     let mut guard = state.child.blocking_lock();
     if let Some(child) = guard.take() {
@@ -206,24 +414,406 @@ async fn shutdown_backend_async(state: &State<'_, BackendState>) {
 }
 
 fn main() {
-    // Favor software rendering to avoid EGL/DRI issues on systems without GPU/DRM setup.
-    std::env::set_var("LIBGL_ALWAYS_SOFTWARE", "1");
-    std::env::set_var("WEBKIT_DISABLE_COMPOSITING_MODE", "1");
-    std::env::set_var("WEBKIT_DISABLE_DMABUF_RENDERER", "1");
+    match profile::from_args(std::env::args().skip(1)) {
+        Ok(name) => profile::set(name),
+        Err(e) => {
+            eprintln!("litefetch: {e}");
+            std::process::exit(2);
+        }
+    }
+    portable::set(portable::detect(
+        std::env::args().any(|arg| arg == portable::FLAG),
+    ));
+    let mut context = tauri::generate_context!();
+    if let Some(dir) = portable::dir() {
+        // The webview's own storage (localStorage, caches) goes along too, where the platform
+        // lets it; macOS keeps it in its data store.
+        let webview = profile::data_root(dir).join("webview");
+        for window in &mut context.config_mut().app.windows {
+            window.data_directory = Some(webview.join(&window.label));
+        }
+    }
+    // Before the builder: WebKitGTK reads its rendering variables when the webview starts.
+    rendering::apply(rendering::settings_dir(&context.config().identifier));
 
     tauri::Builder::default()
         .manage(BackendState {
             child: Mutex::new(None),
             base_url: Mutex::new(None),
         })
-        .invoke_handler(tauri::generate_handler![start_backend, set_workspace_path, switch_workspace])
+        .manage(grpc::GrpcState::new())
+        .manage(graphql::GraphqlState::new())
+        .manage(mqtt::MqttState::new())
+        .manage(socket::SocketState::new())
+        .manage(webhook::WebhookState::new())
+        .manage(tunnel::TunnelState::new())
+        .manage(editor::EditorState::new())
+        .manage(runner::RunnerState::new())
+        .manage(proxy::ProxyState::new())
+        .manage(clipboard::ClipboardState::new())
+        .manage(redact::RedactState::new())
+        .manage(monitor::MonitorState::new())
+        .manage(load::LoadState::new())
+        .manage(mock::MockState::new())
+        .manage(docs::DocsState::new())
+        .manage(offline::OfflineState::new())
+        .manage(traceroute::TracerouteState::new())
+        .manage(security::SecurityState::new())
+        .manage(metrics::MetricsState::new())
+        .manage(sidecar::SidecarState::new())
+        .manage(handshake::HandshakeState::new())
+        .manage(logging::LogState::new())
+        .manage(notifications::NotificationState::new())
+        .manage(contract::ContractState::new())
+        .manage(drafts::DraftState::new())
+        .manage(sync::SyncState::new())
+        .manage(backups::BackupState::new())
+        .manage(watcher::WatcherState::new())
+        .manage(lock::LockState::new())
+        .manage(oauth::code::OAuthState::new())
+        .manage(oauth::tokens::TokenManager::new())
+        .manage(autocomplete::AutocompleteState::new())
+        .manage(format::FormatState::new())
+        .manage(scheduler::SchedulerState::new())
+        .manage(streaming::StreamState::new())
+        .manage(session::SessionState::new())
+        .invoke_handler(tauri::generate_handler![
+            start_backend,
+            set_workspace_path,
+            switch_workspace,
+            get_profile,
+            grpc::grpc_load_protos,
+            grpc::grpc_list_services,
+            grpc::grpc_reflect,
+            grpc::grpc_invoke_unary,
+            grpc::grpc_start_stream,
+            grpc::grpc_send_message,
+            grpc::grpc_end_stream,
+            grpc::grpc_cancel_stream,
+            grpc::grpc_list_target_tls,
+            grpc::grpc_set_target_tls,
+            graphql::graphql_introspect,
+            graphql::graphql_get_schema,
+            graphql::graphql_subscribe,
+            graphql::graphql_unsubscribe,
+            graphql::graphql_subscription_history,
+            graphql_persisted::graphql_list_persisted_queries,
+            graphql_persisted::graphql_import_persisted_manifest,
+            graphql_persisted::graphql_clear_persisted_queries,
+            mqtt::mqtt_connect,
+            mqtt::mqtt_subscribe,
+            mqtt::mqtt_unsubscribe,
+            mqtt::mqtt_publish,
+            mqtt::mqtt_disconnect,
+            socket::socket_open,
+            socket::socket_send,
+            socket::socket_close,
+            soap::soap_import_wsdl,
+            soap::soap_format_response,
+            webhook::start_webhook_listener,
+            webhook::stop_webhook_listener,
+            webhook::list_webhook_listeners,
+            webhook::set_webhook_responses,
+            webhook::get_webhook_requests,
+            tunnel::start_tunnel,
+            tunnel::stop_tunnel,
+            tunnel::list_tunnels,
+            proxy::start_capture_proxy,
+            proxy::stop_capture_proxy,
+            proxy::capture_proxy_status,
+            proxy::get_captured_traffic,
+            proxy::capture_to_collection,
+            proxy::capture_ca_certificate,
+            captures::search_captures,
+            captures::get_capture,
+            captures::clear_captures,
+            captures::export_captures_har,
+            har::preview_har,
+            har::import_har,
+            har::export_har,
+            fixtures::add_fixture,
+            fixtures::list_fixtures,
+            fixtures::remove_fixture,
+            hypermedia::get_response_links,
+            hypermedia::follow_link,
+            notes::render_markdown,
+            notes::get_item_docs,
+            notes::set_item_docs,
+            pagination::fetch_all_pages,
+            importers::postman::import_postman_collection,
+            importers::insomnia::import_insomnia_export,
+            importers::bruno::import_bruno_collection,
+            importers::bruno::export_bruno_collection,
+            importers::thunder::import_thunder_collection,
+            importers::hoppscotch::import_hoppscotch_collection,
+            importers::openapi::import_openapi,
+            importers::openapi::export_openapi,
+            importers::curl::import_curl,
+            importers::postman::import_postman_environment,
+            secrets::set_environment_secret,
+            secrets::delete_environment_secret,
+            secrets::get_environment_secrets,
+            codegen::export_request_as,
+            codegen::generate_code_snippet,
+            clipboard::get_clipboard_settings,
+            clipboard::set_clipboard_watch,
+            importers::dropped::import_dropped_files,
+            importers::directory::import_directory,
+            importers::dotenv::preview_dotenv,
+            importers::dotenv::import_dotenv,
+            environments::diff_environments,
+            environments::promote_variables,
+            sealed_env::encrypt_environment,
+            sealed_env::decrypt_environment,
+            redact::allow_secret_export,
+            redact::scan_export_leaks,
+            redact::review_export_leaks,
+            dynamic::list_dynamic_variables,
+            send::send_request,
+            variables::resolve_request_preview,
+            variables::resolve_variable_layers,
+            variables::get_layer_variables,
+            variables::set_layer_variables,
+            scripting::get_script_settings,
+            scripting::set_script_settings,
+            plugins::list_plugins,
+            plugins::set_plugin_enabled,
+            plugins::import_with_plugin,
+            plugins::render_plugin_view,
+            editor::edit_in_external_editor,
+            editor::stop_external_edit,
+            workflow::run_workflow,
+            runner::run_collection,
+            runner::cancel_collection_run,
+            runner::send_batch,
+            snapshots::save_snapshot,
+            snapshots::get_snapshot,
+            snapshots::set_snapshot_ignores,
+            snapshots::delete_snapshot,
+            snapshots::verify_snapshot,
+            report::export_run_report,
+            monitor::list_monitors,
+            monitor::save_monitor,
+            monitor::delete_monitor,
+            monitor::get_monitor_history,
+            monitor::run_monitor_now,
+            monitor::list_monitor_alerts,
+            monitor::get_monitor_command_permission,
+            monitor::set_monitor_command_permission,
+            load::run_load_test,
+            load::cancel_load_test,
+            load::list_load_tests,
+            load::get_load_test,
+            load::delete_load_test,
+            load::export_load_report,
+            load::compare_load_tests,
+            mock::start_mock_server,
+            mock::start_openapi_mock,
+            mock::stop_mock_server,
+            mock::list_mock_servers,
+            mock::reload_mock_server,
+            mock::get_mock_requests,
+            schema::validate_response,
+            contract::run_contract_tests,
+            contract::cancel_contract_tests,
+            diff::diff_responses,
+            responses::query_response,
+            responses::jq_response,
+            responses::select_response,
+            responses::get_response_hex,
+            responses::decode_response,
+            responses::save_response_body,
+            responses::get_response_text,
+            responses::get_response_settings,
+            responses::set_response_settings,
+            history::search_history,
+            history::export_history,
+            history::get_history_entry,
+            replay::replay_history_entry,
+            history::purge_history,
+            history::get_history_settings,
+            history::set_history_settings,
+            search::search_workspace,
+            pins::pin_request,
+            pins::unpin_request,
+            pins::reorder_pinned,
+            pins::list_pinned,
+            drafts::save_draft,
+            drafts::discard_draft,
+            drafts::list_recovered_drafts,
+            drafts::restore_draft,
+            git::git_status,
+            git::git_commit,
+            git::git_pull,
+            git::git_push,
+            git::git_log,
+            git::diff_collection,
+            presign::presign_s3_url,
+            presign::send_presigned,
+            sync::get_sync_config,
+            sync::set_sync_config,
+            sync::sync_workspace,
+            sync::get_sync_status,
+            sync::generate_sync_key,
+            sync::import_sync_key,
+            sync::export_sync_key,
+            bundle::export_workspace_bundle,
+            bundle::preview_workspace_bundle,
+            bundle::import_workspace_bundle,
+            backups::list_backups,
+            backups::create_backup,
+            backups::restore_backup,
+            backups::get_backup_settings,
+            backups::set_backup_settings,
+            lock::get_workspace_lock,
+            lock::open_workspace_read_only,
+            importers::yaml::export_collection_yaml,
+            importers::yaml::import_collection_yaml,
+            audit::get_audit_log,
+            oauth::code::oauth2_authorize,
+            oauth::code::cancel_oauth2_authorize,
+            oauth::device::oauth2_device_authorize,
+            oauth::get_oauth2_token,
+            oauth::clear_oauth2_token,
+            oauth::tokens::fetch_oauth2_token,
+            jsonrpc::build_jsonrpc_body,
+            jwt::decode_jwt,
+            vault::list_api_keys,
+            vault::save_api_key,
+            vault::delete_api_key,
+            client_profiles::list_client_profiles,
+            lint::lint_workspace,
+            streaming::stop_stream,
+            session::start_session_recording,
+            session::get_session_recording,
+            session::stop_session_recording,
+            session::inspect_session_bundle,
+            session::replay_session_bundle,
+            self_check::run_self_check,
+            routing::list_routes,
+            routing::save_route,
+            routing::delete_route,
+            routing::preview_route,
+            hooks::get_hook_permission,
+            hooks::set_hook_permission,
+            storage::get_storage_stats,
+            defaults::get_workspace_defaults,
+            defaults::set_workspace_defaults,
+            docs::generate_docs,
+            docs::start_docs_server,
+            docs::stop_docs_server,
+            docs::get_docs_server,
+            bulk::bulk_edit,
+            network::get_network_profile,
+            network::set_network_profile,
+            offline::get_connectivity,
+            offline::set_offline_mode,
+            offline::queue_request,
+            offline::list_queued_requests,
+            offline::remove_queued_request,
+            offline::clear_request_queue,
+            offline::replay_queued_requests,
+            dns::dns_lookup,
+            probe::probe_endpoint,
+            traceroute::traceroute,
+            traceroute::cancel_traceroute,
+            security::run_security_audit,
+            security::cancel_security_audit,
+            security::audit_response_security,
+            metrics::get_usage_metrics,
+            metrics::set_usage_metrics_enabled,
+            metrics::clear_usage_metrics,
+            sidecar::get_backend_stats,
+            sidecar::set_backend_memory_threshold,
+            handshake::get_backend_version,
+            launch::get_launch_settings,
+            launch::set_launch_settings,
+            logging::set_log_level,
+            logging::get_recent_logs,
+            crash::list_crash_reports,
+            crash::get_crash_report,
+            crash::export_crash_report,
+            crash::delete_crash_report,
+            rendering::get_rendering,
+            rendering::set_rendering_mode,
+            keymap::get_keymap,
+            keymap::set_shortcut,
+            keymap::reset_keymap,
+            notifications::get_notification_settings,
+            notifications::set_notification_settings,
+            notifications::snooze_notifications,
+            notifications::notify,
+            templates::list_request_templates,
+            templates::save_request_template,
+            templates::delete_request_template,
+            templates::create_from_template,
+            templates::export_request_templates,
+            templates::import_request_templates,
+            starters::list_workspace_templates,
+            starters::workspace_is_empty,
+            starters::init_workspace_from_template,
+            search::get_command_index,
+            autocomplete::autocomplete,
+            format::format_response,
+            format::format_body,
+            scheduler::list_host_limits,
+            scheduler::save_host_limit,
+            scheduler::delete_host_limit,
+            scheduler::list_host_queues,
+            trace::find_by_trace_id
+        ])
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
-        .on_window_event(|window, event| {
-            if let WindowEvent::CloseRequested { .. } = event {
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(keymap::on_global)
+                .build(),
+        )
+        .on_menu_event(|app, event| keymap::on_menu(app, event.id().as_ref()))
+        .setup(|app| {
+            logging::init(app.handle(), &app.state::<logging::LogState>());
+            crash::install(app.handle());
+            clipboard::restore(app.handle());
+            drafts::begin_session(app.handle());
+            watcher::watch(app.handle());
+            keymap::apply(app.handle());
+            if let (Some(name), Some(window)) = (profile::current(), app.get_webview_window("main"))
+            {
+                let title = window.title().unwrap_or_else(|_| "LiteFetch".to_string());
+                let _ = window.set_title(&format!("{title} ({name})"));
+            }
+            tauri::async_runtime::spawn(monitor::resume(app.handle().clone()));
+            tauri::async_runtime::spawn(history::prune_periodically(app.handle().clone()));
+            tauri::async_runtime::spawn(backups::backup_periodically(app.handle().clone()));
+            tauri::async_runtime::spawn(lock::heartbeat_periodically(app.handle().clone()));
+            tauri::async_runtime::spawn(offline::watch_connectivity(app.handle().clone()));
+            tauri::async_runtime::spawn(sidecar::watch_periodically(app.handle().clone()));
+            Ok(())
+        })
+        .on_window_event(|window, event| match event {
+            WindowEvent::CloseRequested { .. } => {
                 shutdown_backend(&window.state::<BackendState>());
+                lock::release(window.app_handle());
+                drafts::end_session(window.app_handle());
+            }
+            WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) => {
+                let paths = paths
+                    .iter()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .collect();
+                let app = window.app_handle().clone();
+                tauri::async_runtime::spawn(importers::dropped::import_paths(app, paths));
+            }
+            WindowEvent::Focused(focused) => {
+                window
+                    .state::<clipboard::ClipboardState>()
+                    .set_focused(*focused);
+                notifications::window_focused(window.app_handle(), *focused);
             }
+            _ => {}
         })
-        .run(tauri::generate_context!())
+        .run(context)
         .expect("error while running LiteFetch desktop");
 }
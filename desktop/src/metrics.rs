@@ -0,0 +1,296 @@
+//! Local usage metrics, off until the user turns them on: requests sent per day, error rates
+//! per host and average latency per environment, kept as daily totals in the app data
+//! directory (no URLs, bodies or values) and never sent anywhere. Days are UTC and kept for
+//! a year.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Manager;
+
+use crate::importers::str_of;
+
+const METRICS_FILE: &str = "usage_metrics.json";
+const RETENTION_DAYS: i64 = 366;
+const DEFAULT_RANGE_DAYS: i64 = 30;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+pub struct MetricsState {
+    /// Loaded on first use.
+    store: Mutex<Option<UsageStore>>,
+}
+
+impl MetricsState {
+    pub fn new() -> Self {
+        Self {
+            store: Mutex::new(None),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+struct Tally {
+    requests: u64,
+    /// No response, or a 5xx.
+    errors: u64,
+    /// 4xx responses.
+    client_errors: u64,
+    total_ms: f64,
+}
+
+impl Tally {
+    fn add(&mut self, other: &Tally) {
+        self.requests += other.requests;
+        self.errors += other.errors;
+        self.client_errors += other.client_errors;
+        self.total_ms += other.total_ms;
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Day {
+    total: Tally,
+    hosts: BTreeMap<String, Tally>,
+    environments: BTreeMap<String, Tally>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct UsageStore {
+    enabled: bool,
+    /// By `YYYY-MM-DD`.
+    days: BTreeMap<String, Day>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct UsageRange {
+    /// First day, `YYYY-MM-DD`; 30 days before `to` when omitted.
+    pub from: Option<String>,
+    /// Last day, included; today when omitted.
+    pub to: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct UsageCount {
+    /// The day, host or environment.
+    pub key: String,
+    pub requests: u64,
+    pub errors: u64,
+    pub client_errors: u64,
+    /// Errors per request, from 0 to 1.
+    pub error_rate: f64,
+    pub avg_ms: f64,
+}
+
+#[derive(Serialize)]
+pub struct UsageMetrics {
+    pub enabled: bool,
+    pub from: String,
+    pub to: String,
+    pub total: UsageCount,
+    /// Every day of the range, oldest first, days without requests included.
+    pub days: Vec<UsageCount>,
+    /// Busiest first.
+    pub hosts: Vec<UsageCount>,
+    pub environments: Vec<UsageCount>,
+}
+
+fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(METRICS_FILE))
+}
+
+fn load(app: &tauri::AppHandle) -> Result<UsageStore, String> {
+    let path = path(app)?;
+    if !path.exists() {
+        return Ok(UsageStore::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("usage metrics read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("usage metrics parse failed: {e}"))
+}
+
+fn save(app: &tauri::AppHandle, store: &UsageStore) -> Result<(), String> {
+    let payload =
+        serde_json::to_string(store).map_err(|e| format!("usage metrics serialize failed: {e}"))?;
+    fs::write(path(app)?, payload).map_err(|e| format!("usage metrics persist failed: {e}"))
+}
+
+/// Runs `change` on the store, loading it first if needed, and saves it when `change`
+/// says so.
+fn with_store<T>(
+    app: &tauri::AppHandle,
+    change: impl FnOnce(&mut UsageStore) -> (T, bool),
+) -> Result<T, String> {
+    let state = app.state::<MetricsState>();
+    let mut slot = state
+        .store
+        .lock()
+        .map_err(|_| "usage metrics lock poisoned".to_string())?;
+    if slot.is_none() {
+        *slot = Some(load(app)?);
+    }
+    let store = slot.as_mut().ok_or("usage metrics unavailable")?;
+    let (out, changed) = change(store);
+    if changed {
+        save(app, store)?;
+    }
+    Ok(out)
+}
+
+fn day_of(ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(ms)
+        .map(|t| t.date_naive().to_string())
+        .unwrap_or_default()
+}
+
+fn parse_day(text: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(text.trim(), "%Y-%m-%d")
+        .map_err(|_| format!("invalid date {text:?}; expected YYYY-MM-DD"))
+}
+
+/// Counts a finished send in today's totals, if metrics are on. Only the host and the
+/// environment name are kept.
+pub fn record(app: &tauri::AppHandle, environment: &str, result: &Value) {
+    let sent = result.get("sent_request").unwrap_or(&Value::Null);
+    let host = tauri::Url::parse(str_of(sent, "url"))
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_else(|| "unknown".to_string());
+    let status = result
+        .get("status_code")
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    let tally = Tally {
+        requests: 1,
+        errors: u64::from(status == 0 || status >= 500),
+        client_errors: u64::from((400..500).contains(&status)),
+        total_ms: result
+            .get("duration_ms")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0),
+    };
+    let now = crate::now_ms() as i64;
+    let recorded = with_store(app, |store| {
+        if !store.enabled {
+            return ((), false);
+        }
+        let day = store.days.entry(day_of(now)).or_default();
+        day.total.add(&tally);
+        day.hosts.entry(host).or_default().add(&tally);
+        let environment = match environment {
+            "" => "(none)",
+            name => name,
+        };
+        day.environments
+            .entry(environment.to_string())
+            .or_default()
+            .add(&tally);
+        let oldest = day_of(now - RETENTION_DAYS * DAY_MS);
+        store.days.retain(|date, _| *date >= oldest);
+        ((), true)
+    });
+    if let Err(e) = recorded {
+        tracing::warn!("{e}");
+    }
+}
+
+fn count(key: String, tally: &Tally) -> UsageCount {
+    let per = |n: f64| match tally.requests {
+        0 => 0.0,
+        requests => n / requests as f64,
+    };
+    UsageCount {
+        key,
+        requests: tally.requests,
+        errors: tally.errors,
+        client_errors: tally.client_errors,
+        error_rate: per(tally.errors as f64),
+        avg_ms: per(tally.total_ms),
+    }
+}
+
+fn ranked(totals: BTreeMap<String, Tally>) -> Vec<UsageCount> {
+    let mut counts: Vec<UsageCount> = totals.iter().map(|(k, t)| count(k.clone(), t)).collect();
+    counts.sort_by(|a, b| b.requests.cmp(&a.requests).then(a.key.cmp(&b.key)));
+    counts
+}
+
+/// Usage totals for a range of days, the last 30 by default.
+#[tauri::command]
+pub async fn get_usage_metrics(
+    app: tauri::AppHandle,
+    range: Option<UsageRange>,
+) -> Result<UsageMetrics, String> {
+    let range = range.unwrap_or_default();
+    let to = match range.to.as_deref().filter(|t| !t.trim().is_empty()) {
+        Some(text) => parse_day(text)?,
+        None => parse_day(&day_of(crate::now_ms() as i64))?,
+    };
+    let from = match range.from.as_deref().filter(|f| !f.trim().is_empty()) {
+        Some(text) => parse_day(text)?,
+        None => to - chrono::Duration::days(DEFAULT_RANGE_DAYS - 1),
+    };
+    if from > to {
+        return Err("the range starts after it ends".to_string());
+    }
+    if (to - from).num_days() > RETENTION_DAYS {
+        return Err(format!("the range can span at most {RETENTION_DAYS} days"));
+    }
+    with_store(&app, |store| {
+        let mut total = Tally::default();
+        let mut hosts: BTreeMap<String, Tally> = BTreeMap::new();
+        let mut environments: BTreeMap<String, Tally> = BTreeMap::new();
+        let mut days = Vec::new();
+        let mut date = from;
+        while date <= to {
+            let key = date.to_string();
+            match store.days.get(&key) {
+                Some(day) => {
+                    total.add(&day.total);
+                    for (host, tally) in &day.hosts {
+                        hosts.entry(host.clone()).or_default().add(tally);
+                    }
+                    for (name, tally) in &day.environments {
+                        environments.entry(name.clone()).or_default().add(tally);
+                    }
+                    days.push(count(key, &day.total));
+                }
+                None => days.push(count(key, &Tally::default())),
+            }
+            date += chrono::Duration::days(1);
+        }
+        let metrics = UsageMetrics {
+            enabled: store.enabled,
+            from: from.to_string(),
+            to: to.to_string(),
+            total: count("total".to_string(), &total),
+            days,
+            hosts: ranked(hosts),
+            environments: ranked(environments),
+        };
+        (metrics, false)
+    })
+}
+
+/// Turns recording on or off; what was recorded stays until `clear_usage_metrics`.
+#[tauri::command]
+pub async fn set_usage_metrics_enabled(app: tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    with_store(&app, |store| {
+        store.enabled = enabled;
+        ((), true)
+    })
+}
+
+/// Deletes every recorded day; whether recording is on doesn't change.
+#[tauri::command]
+pub async fn clear_usage_metrics(app: tauri::AppHandle) -> Result<(), String> {
+    with_store(&app, |store| {
+        store.days.clear();
+        ((), true)
+    })
+}
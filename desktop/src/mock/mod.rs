@@ -0,0 +1,613 @@
+//! Mock servers: a local HTTP server answering with the saved examples of a collection's
+//! requests, or, for requests without any, the latest recorded response of each status code
+//! (imported examples land in history too). Each request's URL becomes a route (`{{var}}`,
+//! `:param` and `{param}` segments match anything); the example is picked by the
+//! `x-mock-response-code`, `x-mock-response-name` or `x-mock-response-id` header, otherwise
+//! the first 2xx one. Every request the mock receives is logged and emitted as
+//! `mock://request`.
+//!
+//! A mock can also be started from an OpenAPI spec instead (see `openapi`), answering every
+//! operation with data generated from its response schemas.
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, State};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use litefetch_core::json::{array_of, str_of, value_text};
+
+mod openapi;
+
+const LOG_LIMIT: usize = 500;
+
+pub struct MockState {
+    servers: Mutex<HashMap<String, MockServer>>,
+}
+
+impl MockState {
+    pub fn new() -> Self {
+        Self {
+            servers: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Where a server's routes come from, for reloading them.
+#[derive(Clone)]
+enum Source {
+    Collection(String),
+    OpenApi(String),
+}
+
+struct MockServer {
+    info: MockServerInfo,
+    source: Source,
+    context: Arc<MockContext>,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+struct MockContext {
+    app: tauri::AppHandle,
+    server_id: String,
+    /// Added to every response, on top of an example's own delay.
+    delay_ms: u64,
+    routes: RwLock<Vec<Route>>,
+    log: RwLock<VecDeque<MockedRequest>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct MockServerInfo {
+    server_id: String,
+    /// `examples` or `openapi`.
+    kind: String,
+    /// The collection id, or the spec the routes were generated from.
+    source: String,
+    port: u16,
+    url: String,
+    routes: usize,
+    started_at_ms: u64,
+}
+
+#[derive(Deserialize, Clone)]
+struct Example {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default = "default_status")]
+    status_code: u16,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    delay_ms: u64,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+enum Responder {
+    Examples(Vec<Example>),
+    Schema(openapi::SchemaResponses),
+}
+
+/// A saved request with examples, or a spec operation, as a method and path pattern.
+struct Route {
+    /// The saved request's id, or the operation's `operationId`.
+    request_id: String,
+    name: String,
+    method: String,
+    /// Path segments; `None` matches any segment.
+    segments: Vec<Option<String>>,
+    responder: Responder,
+}
+
+impl Route {
+    /// How closely `path` matches, or `None`; literal segments count more than wildcards.
+    fn score(&self, method: &str, path: &[&str]) -> Option<usize> {
+        if !self.method.eq_ignore_ascii_case(method) || self.segments.len() != path.len() {
+            return None;
+        }
+        let mut score = 0;
+        for (segment, actual) in self.segments.iter().zip(path) {
+            match segment {
+                Some(literal) if literal == actual => score += 1,
+                Some(_) => return None,
+                None => {}
+            }
+        }
+        Some(score)
+    }
+
+    fn respond(&self, headers: &hyper::HeaderMap) -> Option<Example> {
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::trim)
+        };
+        let examples = match &self.responder {
+            Responder::Examples(examples) => examples,
+            Responder::Schema(responses) => {
+                return responses.respond(
+                    header("x-mock-response-code"),
+                    header("x-mock-response-name"),
+                )
+            }
+        };
+        let found = if let Some(id) = header("x-mock-response-id") {
+            examples.iter().find(|e| e.id == id)
+        } else if let Some(name) = header("x-mock-response-name") {
+            examples.iter().find(|e| e.name.eq_ignore_ascii_case(name))
+        } else if let Some(code) = header("x-mock-response-code") {
+            examples.iter().find(|e| e.status_code.to_string() == code)
+        } else {
+            examples
+                .iter()
+                .find(|e| (200..300).contains(&e.status_code))
+                .or(examples.first())
+        };
+        found.cloned()
+    }
+}
+
+fn split_path(path: &str) -> Vec<&str> {
+    path.split('/').filter(|s| !s.is_empty()).collect()
+}
+
+/// The path part of a saved URL: without a leading `{{baseUrl}}`-style variable or scheme
+/// and host, and without the query.
+pub(crate) fn path_of(url: &str) -> &str {
+    let url = url.trim();
+    let url = url.split(['?', '#']).next().unwrap_or_default();
+    if let Some(rest) = url.strip_prefix("{{") {
+        return rest
+            .split_once("}}")
+            .map(|(_, path)| path)
+            .unwrap_or_default();
+    }
+    match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map(|i| &rest[i..]).unwrap_or_default(),
+        None => url,
+    }
+}
+
+fn is_parameter(segment: &str) -> bool {
+    segment.starts_with(':') || segment.contains("{{") || segment.starts_with('{')
+}
+
+fn segments_of(path: &str) -> Vec<Option<String>> {
+    split_path(path)
+        .into_iter()
+        .map(|s| (!is_parameter(s)).then(|| s.to_string()))
+        .collect()
+}
+
+/// Headers describing the recorded transfer rather than the body the mock sends.
+const TRANSFER_HEADERS: &[&str] = &["content-length", "content-encoding", "transfer-encoding"];
+
+/// Recorded responses as examples, newest first, one per status code.
+fn recorded_examples(history: &[Value], request_id: &str) -> Vec<Example> {
+    let mut examples: Vec<Example> = Vec::new();
+    for entry in history {
+        if str_of(entry, "request_id") != request_id
+            || entry.get("error").is_some_and(|e| !e.is_null())
+        {
+            continue;
+        }
+        let Some(status_code) = entry
+            .get("status_code")
+            .and_then(Value::as_u64)
+            .and_then(|code| u16::try_from(code).ok())
+        else {
+            continue;
+        };
+        if examples.iter().any(|e| e.status_code == status_code) {
+            continue;
+        }
+        let headers = entry
+            .get("headers")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter(|(key, _)| !TRANSFER_HEADERS.contains(&key.to_lowercase().as_str()))
+            .map(|(key, value)| (key.clone(), value_text(Some(value))))
+            .collect();
+        let body = match entry.get("body") {
+            Some(Value::String(text)) => text.clone(),
+            Some(Value::Null) | None => String::new(),
+            Some(other) => serde_json::to_string_pretty(other).unwrap_or_default(),
+        };
+        examples.push(Example {
+            id: String::new(),
+            name: status_code.to_string(),
+            status_code,
+            headers,
+            body,
+            delay_ms: 0,
+        });
+    }
+    examples
+}
+
+fn collect_routes(items: &[Value], history: &[Value], out: &mut Vec<Route>) {
+    for item in items {
+        if let Some(children) = item.get("items").and_then(Value::as_array) {
+            collect_routes(children, history, out);
+            continue;
+        }
+        let mut examples: Vec<Example> = item
+            .get("examples")
+            .cloned()
+            .and_then(|e| serde_json::from_value(e).ok())
+            .unwrap_or_default();
+        if examples.is_empty() {
+            examples = recorded_examples(history, str_of(item, "id"));
+        }
+        if examples.is_empty() {
+            continue;
+        }
+        out.push(Route {
+            request_id: str_of(item, "id").to_string(),
+            name: str_of(item, "name").to_string(),
+            method: str_of(item, "method").to_string(),
+            segments: segments_of(path_of(str_of(item, "url"))),
+            responder: Responder::Examples(examples),
+        });
+    }
+}
+
+async fn collection_routes(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+) -> Result<Vec<Route>, String> {
+    let collection =
+        crate::backend_get(app, &format!("/collections/{collection_id}/collection")).await?;
+    let history = crate::backend_get(app, &format!("/collections/{collection_id}/history")).await?;
+    let mut routes = Vec::new();
+    collect_routes(
+        array_of(&collection, "items"),
+        history.as_array().map(Vec::as_slice).unwrap_or_default(),
+        &mut routes,
+    );
+    Ok(routes)
+}
+
+#[derive(Clone, Serialize)]
+pub struct MockedRequest {
+    server_id: String,
+    id: String,
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    body: String,
+    remote_addr: String,
+    received_at_ms: u64,
+    duration_ms: f64,
+    /// The saved request and example that answered; `None` when nothing matched.
+    request_id: Option<String>,
+    request_name: Option<String>,
+    example: Option<String>,
+    response_status: u16,
+}
+
+fn json_response(status: StatusCode, body: Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())))
+}
+
+async fn handle(
+    context: Arc<MockContext>,
+    remote: SocketAddr,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let started = Instant::now();
+    let received_at_ms = crate::now_ms();
+    let (parts, body) = request.into_parts();
+    let body = body
+        .collect()
+        .await
+        .map(|b| b.to_bytes())
+        .unwrap_or_default();
+    let method = parts.method.to_string();
+    let path = parts.uri.path().to_string();
+
+    let matched = context.routes.read().ok().and_then(|routes| {
+        let segments = split_path(&path);
+        let route = routes
+            .iter()
+            .filter_map(|r| r.score(&method, &segments).map(|score| (score, r)))
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, r)| r)?;
+        let example = route.respond(&parts.headers);
+        Some((route.request_id.clone(), route.name.clone(), example))
+    });
+    let (status, response, example_name) = match &matched {
+        Some((_, _, Some(example))) => {
+            let delay = context.delay_ms + example.delay_ms;
+            if delay > 0 {
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+            let status = StatusCode::from_u16(example.status_code).unwrap_or(StatusCode::OK);
+            let mut response = Response::builder().status(status);
+            for (key, value) in &example.headers {
+                response = response.header(key.as_str(), value.as_str());
+            }
+            let response = response
+                .body(Full::new(Bytes::from(example.body.clone())))
+                .unwrap_or_else(|_| Response::new(Full::new(Bytes::new())));
+            (status, response, Some(example.name.clone()))
+        }
+        Some((_, name, None)) => (
+            StatusCode::NOT_FOUND,
+            json_response(
+                StatusCode::NOT_FOUND,
+                serde_json::json!({ "error": format!("no example of {name} matches the x-mock-response header") }),
+            ),
+            None,
+        ),
+        None => (
+            StatusCode::NOT_FOUND,
+            json_response(
+                StatusCode::NOT_FOUND,
+                serde_json::json!({ "error": format!("no mocked route for {method} {path}") }),
+            ),
+            None,
+        ),
+    };
+
+    let logged = MockedRequest {
+        server_id: context.server_id.clone(),
+        id: uuid::Uuid::new_v4().to_string(),
+        method,
+        path,
+        query: parts.uri.query().unwrap_or_default().to_string(),
+        headers: parts
+            .headers
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.to_string(),
+                    String::from_utf8_lossy(v.as_bytes()).to_string(),
+                )
+            })
+            .collect(),
+        body: String::from_utf8_lossy(&body).to_string(),
+        remote_addr: remote.to_string(),
+        received_at_ms,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        request_id: matched.as_ref().map(|(id, _, _)| id.clone()),
+        request_name: matched.map(|(_, name, _)| name),
+        example: example_name,
+        response_status: status.as_u16(),
+    };
+    if let Ok(mut log) = context.log.write() {
+        if log.len() >= LOG_LIMIT {
+            log.pop_front();
+        }
+        log.push_back(logged.clone());
+    }
+    let _ = context.app.emit("mock://request", logged);
+    Ok(response)
+}
+
+async fn serve(
+    context: Arc<MockContext>,
+    listener: TcpListener,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => {
+                let Ok((stream, remote)) = accepted else { continue };
+                let context = context.clone();
+                tauri::async_runtime::spawn(async move {
+                    let service = hyper::service::service_fn(move |req| {
+                        handle(context.clone(), remote, req)
+                    });
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        }
+    }
+}
+
+async fn load_routes(app: &tauri::AppHandle, source: &Source) -> Result<Vec<Route>, String> {
+    match source {
+        Source::Collection(collection_id) => collection_routes(app, collection_id).await,
+        Source::OpenApi(spec) => {
+            openapi::routes(crate::importers::openapi::load_spec(app, spec).await?)
+        }
+    }
+}
+
+async fn launch(
+    app: tauri::AppHandle,
+    state: &MockState,
+    source: Source,
+    port: Option<u16>,
+    bind_all: Option<bool>,
+    delay_ms: Option<u64>,
+) -> Result<MockServerInfo, String> {
+    let routes = load_routes(&app, &source).await?;
+    let host = if bind_all.unwrap_or(false) {
+        "0.0.0.0"
+    } else {
+        "127.0.0.1"
+    };
+    let listener = TcpListener::bind((host, port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("mock server bind failed: {e}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("mock server bind failed: {e}"))?
+        .port();
+
+    let server_id = uuid::Uuid::new_v4().to_string();
+    let (kind, origin) = match &source {
+        Source::Collection(collection_id) => ("examples", collection_id),
+        Source::OpenApi(spec) => ("openapi", spec),
+    };
+    let info = MockServerInfo {
+        server_id: server_id.clone(),
+        kind: kind.to_string(),
+        source: origin.clone(),
+        port,
+        url: format!("http://127.0.0.1:{port}"),
+        routes: routes.len(),
+        started_at_ms: crate::now_ms(),
+    };
+    let context = Arc::new(MockContext {
+        app,
+        server_id: server_id.clone(),
+        delay_ms: delay_ms.unwrap_or(0),
+        routes: RwLock::new(routes),
+        log: RwLock::new(VecDeque::new()),
+    });
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let task = tauri::async_runtime::spawn(serve(context.clone(), listener, shutdown_rx));
+    state.servers.lock().await.insert(
+        server_id,
+        MockServer {
+            info: info.clone(),
+            source,
+            context,
+            shutdown: Some(shutdown),
+            task,
+        },
+    );
+    Ok(info)
+}
+
+/// Serves the examples of `collection_id` on `port` (any free port when omitted).
+#[tauri::command]
+pub async fn start_mock_server(
+    app: tauri::AppHandle,
+    state: State<'_, MockState>,
+    collection_id: String,
+    port: Option<u16>,
+    bind_all: Option<bool>,
+    delay_ms: Option<u64>,
+) -> Result<MockServerInfo, String> {
+    let source = Source::Collection(collection_id);
+    launch(app, &state, source, port, bind_all, delay_ms).await
+}
+
+/// Serves responses generated from an OpenAPI spec: the id of a collection imported from
+/// one, or a path or URL.
+#[tauri::command]
+pub async fn start_openapi_mock(
+    app: tauri::AppHandle,
+    state: State<'_, MockState>,
+    spec: String,
+    port: Option<u16>,
+    bind_all: Option<bool>,
+    delay_ms: Option<u64>,
+) -> Result<MockServerInfo, String> {
+    let source = Source::OpenApi(spec);
+    launch(app, &state, source, port, bind_all, delay_ms).await
+}
+
+#[tauri::command]
+pub async fn stop_mock_server(
+    state: State<'_, MockState>,
+    server_id: String,
+) -> Result<(), String> {
+    let mut server = state
+        .servers
+        .lock()
+        .await
+        .remove(&server_id)
+        .ok_or_else(|| format!("unknown mock server: {server_id}"))?;
+    if let Some(tx) = server.shutdown.take() {
+        let _ = tx.send(());
+    } else {
+        server.task.abort();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_mock_servers(state: State<'_, MockState>) -> Result<Vec<MockServerInfo>, String> {
+    let mut servers: Vec<_> = state
+        .servers
+        .lock()
+        .await
+        .values()
+        .map(|s| s.info.clone())
+        .collect();
+    servers.sort_by_key(|s| s.started_at_ms);
+    Ok(servers)
+}
+
+/// Re-reads the collection's examples or the spec, e.g. after an edit.
+#[tauri::command]
+pub async fn reload_mock_server(
+    app: tauri::AppHandle,
+    state: State<'_, MockState>,
+    server_id: String,
+) -> Result<MockServerInfo, String> {
+    let source = {
+        let servers = state.servers.lock().await;
+        let server = servers
+            .get(&server_id)
+            .ok_or_else(|| format!("unknown mock server: {server_id}"))?;
+        server.source.clone()
+    };
+    let routes = load_routes(&app, &source).await?;
+    let mut servers = state.servers.lock().await;
+    let server = servers
+        .get_mut(&server_id)
+        .ok_or_else(|| format!("unknown mock server: {server_id}"))?;
+    server.info.routes = routes.len();
+    *server
+        .context
+        .routes
+        .write()
+        .map_err(|_| "mock route table poisoned".to_string())? = routes;
+    Ok(server.info.clone())
+}
+
+#[tauri::command]
+pub async fn get_mock_requests(
+    state: State<'_, MockState>,
+    server_id: String,
+    clear: Option<bool>,
+) -> Result<Vec<MockedRequest>, String> {
+    let servers = state.servers.lock().await;
+    let server = servers
+        .get(&server_id)
+        .ok_or_else(|| format!("unknown mock server: {server_id}"))?;
+    let mut log = server
+        .context
+        .log
+        .write()
+        .map_err(|_| "mock request log poisoned".to_string())?;
+    let requests = log.iter().cloned().collect();
+    if clear.unwrap_or(false) {
+        log.clear();
+    }
+    Ok(requests)
+}
@@ -0,0 +1,370 @@
+//! OpenAPI-driven mocks: every operation of a spec becomes a route. A response uses the
+//! spec's example for the media type when there is one, otherwise data generated from the
+//! response schema, with enums, formats, bounds and common property names (`email`, `city`,
+//! ...) turned into plausible fake values.
+
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use litefetch_core::json::str_of;
+
+use super::{Example, Responder, Route};
+use crate::dynamic::{evaluate, random_below, random_range};
+
+const OPERATIONS: &[&str] = &[
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// Schemas nest (and recurse) arbitrarily; generated data stops at this depth.
+const MAX_DEPTH: usize = 8;
+
+/// The responses of one operation, generated from when a request comes in.
+pub struct SchemaResponses {
+    doc: Arc<Value>,
+    responses: Map<String, Value>,
+    swagger: bool,
+}
+
+/// Follows local `$ref`s; anything else is returned as is.
+fn resolve<'a>(doc: &'a Value, mut value: &'a Value) -> &'a Value {
+    for _ in 0..16 {
+        let Some(pointer) = value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|r| r.strip_prefix('#'))
+        else {
+            return value;
+        };
+        match doc.pointer(pointer) {
+            Some(target) => value = target,
+            None => return value,
+        }
+    }
+    value
+}
+
+/// Fake text for a string property, by format first and then by the property's name.
+fn fake_string(schema: &Value, property: &str) -> String {
+    let by_format = match str_of(schema, "format") {
+        "date-time" => {
+            let offset = random_range(0, 365 * 24 * 3600 * 1000);
+            chrono::DateTime::from_timestamp_millis(crate::now_ms() as i64 - offset)
+                .map(|d| d.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        }
+        "date" => {
+            let offset = random_range(0, 365 * 24 * 3600 * 1000);
+            chrono::DateTime::from_timestamp_millis(crate::now_ms() as i64 - offset)
+                .map(|d| d.format("%Y-%m-%d").to_string())
+        }
+        "time" => Some(format!(
+            "{:02}:{:02}:{:02}",
+            random_below(24),
+            random_below(60),
+            random_below(60)
+        )),
+        "email" => evaluate("$randomEmail"),
+        "uuid" => evaluate("$uuid"),
+        "uri" | "url" => Some(format!("https://example.com/{}", random_below(10_000))),
+        "hostname" => Some(format!("host{}.example.com", random_below(100))),
+        "ipv4" => Some(format!("192.0.2.{}", random_range(1, 254))),
+        "ipv6" => Some(format!("2001:db8::{:x}", random_below(0xffff))),
+        "byte" => Some("bGl0ZWZldGNo".to_string()),
+        "password" => Some("********".to_string()),
+        _ => None,
+    };
+    if let Some(value) = by_format {
+        return value;
+    }
+    let name = property.to_lowercase().replace(['_', '-'], "");
+    let by_name = match name.as_str() {
+        "email" | "emailaddress" => "$randomEmail",
+        "firstname" | "givenname" => "$randomFirstName",
+        "lastname" | "surname" | "familyname" => "$randomLastName",
+        "name" | "fullname" | "displayname" => "$randomFullName",
+        "username" | "login" | "handle" => "$randomUserName",
+        "phone" | "phonenumber" | "mobile" => "$randomPhoneNumber",
+        "address" | "street" | "streetaddress" => "$randomStreetAddress",
+        "city" | "town" => "$randomCity",
+        "country" => "$randomCountry",
+        "zip" | "zipcode" | "postcode" | "postalcode" => "$randomZipCode",
+        "company" | "companyname" | "organization" => "$randomCompanyName",
+        "id" | "uuid" | "guid" => "$uuid",
+        _ => "",
+    };
+    let mut text = evaluate(by_name).unwrap_or_else(|| {
+        let words = ["lorem", "ipsum", "dolor", "sit", "amet", "consectetur"];
+        words[random_below(words.len() as u64) as usize].to_string()
+    });
+    let min = schema.get("minLength").and_then(Value::as_u64).unwrap_or(0) as usize;
+    while text.chars().count() < min {
+        text.push('x');
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        text = text.chars().take(max as usize).collect();
+    }
+    text
+}
+
+fn fake_number(schema: &Value, integer: bool) -> Value {
+    let bound = |key: &str, exclusive: &str| {
+        let value = schema.get(key).and_then(Value::as_f64);
+        // 3.0 marks exclusive bounds with a boolean; 3.1 gives the bound itself.
+        match schema.get(exclusive) {
+            Some(Value::Number(n)) => n.as_f64(),
+            _ => value,
+        }
+    };
+    let min = bound("minimum", "exclusiveMinimum").unwrap_or(0.0);
+    let max = bound("maximum", "exclusiveMaximum").unwrap_or(min.max(0.0) + 1000.0);
+    if integer {
+        let (min, max) = (min.ceil() as i64, max.floor() as i64);
+        json!(random_range(min, max.max(min)))
+    } else {
+        let value = min + (max - min) * random_below(10_001) as f64 / 10_000.0;
+        json!((value * 100.0).round() / 100.0)
+    }
+}
+
+/// Generates a value for `schema`; `property` is the name it sits under, if any.
+fn fake(doc: &Value, schema: &Value, property: &str, depth: usize) -> Value {
+    let schema = resolve(doc, schema);
+    if let Some(value) = schema.get("const") {
+        return value.clone();
+    }
+    if let Some(choices) = schema.get("enum").and_then(Value::as_array) {
+        if !choices.is_empty() {
+            return choices[random_below(choices.len() as u64) as usize].clone();
+        }
+    }
+    if let Some(value) = schema.get("example") {
+        return value.clone();
+    }
+    if let Some(value) = schema
+        .get("examples")
+        .and_then(Value::as_array)
+        .and_then(|e| e.first())
+    {
+        return value.clone();
+    }
+    if depth >= MAX_DEPTH {
+        return Value::Null;
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for part in parts {
+            if let Value::Object(fields) = fake(doc, part, property, depth + 1) {
+                merged.extend(fields);
+            }
+        }
+        return Value::Object(merged);
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(options) = schema.get(key).and_then(Value::as_array) {
+            if !options.is_empty() {
+                let option = &options[random_below(options.len() as u64) as usize];
+                return fake(doc, option, property, depth + 1);
+            }
+        }
+    }
+    // 3.1 allows `type: [string, "null"]`.
+    let kind = match schema.get("type") {
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|t| *t != "null")
+            .unwrap_or_default(),
+        Some(Value::String(kind)) => kind.as_str(),
+        _ if schema.get("properties").is_some() => "object",
+        _ if schema.get("items").is_some() => "array",
+        _ => "",
+    };
+    match kind {
+        "object" => {
+            let mut fields = Map::new();
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (name, property) in properties {
+                    fields.insert(name.clone(), fake(doc, property, name, depth + 1));
+                }
+            }
+            Value::Object(fields)
+        }
+        "array" => {
+            let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(1);
+            let max = schema
+                .get("maxItems")
+                .and_then(Value::as_u64)
+                .unwrap_or(min.max(3));
+            let count = random_range(min as i64, max.max(min) as i64) as usize;
+            let items = schema.get("items").unwrap_or(&Value::Null);
+            Value::Array(
+                (0..count)
+                    .map(|_| fake(doc, items, property, depth + 1))
+                    .collect(),
+            )
+        }
+        "integer" => fake_number(schema, true),
+        "number" => fake_number(schema, false),
+        "boolean" => json!(random_below(2) == 1),
+        "string" => json!(fake_string(schema, property)),
+        _ => Value::Null,
+    }
+}
+
+fn body_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_default(),
+    }
+}
+
+impl SchemaResponses {
+    /// The response for status `code` (the lowest 2xx, else `default`, when `None`), using
+    /// the example named `example` when the media type lists several.
+    pub fn respond(&self, code: Option<&str>, example: Option<&str>) -> Option<Example> {
+        let (status, response) = match code {
+            Some(code) => self
+                .responses
+                .get_key_value(code)
+                .or_else(|| {
+                    // `4XX`-style ranges cover any code in them.
+                    let range = format!("{}XX", code.get(..1)?);
+                    self.responses.get_key_value(&range)
+                })
+                .map(|(_, response)| (code.to_string(), response))?,
+            None => {
+                let mut codes: Vec<&String> = self
+                    .responses
+                    .keys()
+                    .filter(|c| c.starts_with('2'))
+                    .collect();
+                codes.sort();
+                let key = codes
+                    .first()
+                    .map(|c| c.as_str())
+                    .or(self.responses.contains_key("default").then_some("default"))?;
+                let status = match key {
+                    "default" => "200".to_string(),
+                    range if range.ends_with("XX") => range.replace("XX", "00"),
+                    code => code.to_string(),
+                };
+                (status, &self.responses[key])
+            }
+        };
+        let doc = self.doc.as_ref();
+        let response = resolve(doc, response);
+        let status_code = status.parse().unwrap_or(200);
+        let mut headers = HashMap::new();
+
+        let (mime, body) = if self.swagger {
+            let listed = response.get("examples").and_then(Value::as_object);
+            match listed.and_then(|e| e.iter().next()) {
+                Some((mime, example)) => (Some(mime.clone()), body_text(example)),
+                None => match response.get("schema") {
+                    Some(schema) => (
+                        Some("application/json".to_string()),
+                        body_text(&fake(doc, schema, "", 0)),
+                    ),
+                    None => (None, String::new()),
+                },
+            }
+        } else {
+            let content = response.get("content").and_then(Value::as_object);
+            let media = content.and_then(|c| {
+                c.iter()
+                    .find(|(mime, _)| mime.contains("json"))
+                    .or_else(|| c.iter().next())
+            });
+            match media {
+                Some((mime, media)) => {
+                    let named = media.get("examples").and_then(Value::as_object);
+                    let chosen = named.and_then(|examples| match example {
+                        Some(name) => examples.get(name),
+                        None => examples.values().next(),
+                    });
+                    let value = media
+                        .get("example")
+                        .filter(|_| example.is_none())
+                        .cloned()
+                        .or_else(|| chosen.and_then(|e| resolve(doc, e).get("value").cloned()))
+                        .unwrap_or_else(|| {
+                            fake(doc, media.get("schema").unwrap_or(&Value::Null), "", 0)
+                        });
+                    (Some(mime.clone()), body_text(&value))
+                }
+                None => (None, String::new()),
+            }
+        };
+        if let Some(mime) = mime {
+            headers.insert("content-type".to_string(), mime);
+        }
+        Some(Example {
+            id: String::new(),
+            name: example.unwrap_or(&status).to_string(),
+            status_code,
+            headers,
+            body,
+            delay_ms: 0,
+        })
+    }
+}
+
+/// One route per operation of `doc`.
+pub fn routes(doc: Value) -> Result<Vec<Route>, String> {
+    let swagger = str_of(&doc, "swagger").starts_with("2.");
+    if !swagger && !str_of(&doc, "openapi").starts_with('3') {
+        return Err("not an OpenAPI 3 or Swagger 2 document".to_string());
+    }
+    // Servers may carry a path prefix (`https://api.example.com/v1`); 2.0 has `basePath`.
+    let prefix = if swagger {
+        str_of(&doc, "basePath").to_string()
+    } else {
+        doc.pointer("/servers/0/url")
+            .and_then(Value::as_str)
+            .map(|url| super::path_of(url).to_string())
+            .unwrap_or_default()
+    };
+    let doc = Arc::new(doc);
+    let mut routes = Vec::new();
+    for (path, item) in doc
+        .get("paths")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+    {
+        let item = resolve(&doc, item);
+        for method in OPERATIONS {
+            let Some(operation) = item.get(*method) else {
+                continue;
+            };
+            let full_path = format!(
+                "{}/{}",
+                prefix.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            );
+            routes.push(Route {
+                request_id: str_of(operation, "operationId").to_string(),
+                name: [
+                    str_of(operation, "summary"),
+                    str_of(operation, "operationId"),
+                ]
+                .into_iter()
+                .find(|n| !n.is_empty())
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("{} {path}", method.to_uppercase())),
+                method: method.to_uppercase(),
+                segments: super::segments_of(&full_path),
+                responder: Responder::Schema(SchemaResponses {
+                    doc: doc.clone(),
+                    responses: operation
+                        .get("responses")
+                        .and_then(Value::as_object)
+                        .cloned()
+                        .unwrap_or_default(),
+                    swagger,
+                }),
+            });
+        }
+    }
+    Ok(routes)
+}
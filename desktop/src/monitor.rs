@@ -0,0 +1,873 @@
+//! Monitors: a saved request, or a whole collection, checked on an interval in the
+//! background. Each check goes through `send` (or the collection runner) and is kept in the
+//! monitor's history for uptime and latency figures. A monitor that starts failing, or
+//! recovers, raises a desktop notification; the window badge counts failing monitors.
+//!
+//! Alert rules go further: each watches the p95 latency or the error rate of the monitor's
+//! latest checks against a threshold, and on firing or resolving notifies, calls a webhook
+//! or runs a command. Alert states are kept so `list_monitor_alerts` shows what's firing.
+//!
+//! Monitors, their histories and alert states are stored per workspace under
+//! `.litefetch/monitors`. Since a workspace can come from someone else's repository, command
+//! actions run only in workspaces the user allowed; that permission is kept in the app data
+//! root (`monitor-commands.json`), never in the workspace itself.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::time::Duration;
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, Manager, State};
+
+use litefetch_core::runner::{Plan, RunOptions};
+use litefetch_core::send::TestReport;
+
+use crate::send::Shell;
+
+const MONITORS_FILE: &str = "monitors.json";
+const ALERTS_FILE: &str = "alerts.json";
+const PERMISSIONS_FILE: &str = "monitor-commands.json";
+const MIN_INTERVAL_SECS: u64 = 10;
+/// Checks kept per monitor; older ones are dropped.
+const HISTORY_LIMIT: usize = 1000;
+const DEFAULT_ALERT_WINDOW: usize = 10;
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const FIRING: &str = "firing";
+const RESOLVED: &str = "resolved";
+
+pub struct MonitorState {
+    tasks: Mutex<HashMap<String, JoinHandle<()>>>,
+    /// Monitors whose last check failed.
+    failing: Mutex<HashSet<String>>,
+    /// Held while the alert states are read and written.
+    alerts: Mutex<()>,
+}
+
+impl MonitorState {
+    pub fn new() -> Self {
+        Self {
+            tasks: Mutex::new(HashMap::new()),
+            failing: Mutex::new(HashSet::new()),
+            alerts: Mutex::new(()),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_alert_window() -> usize {
+    DEFAULT_ALERT_WINDOW
+}
+
+fn default_alert_actions() -> Vec<AlertAction> {
+    vec![AlertAction::Notify]
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Monitor {
+    /// Assigned on first save.
+    #[serde(default)]
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) collection_id: String,
+    /// The request to check; every request of the collection runs when omitted.
+    #[serde(default)]
+    pub(crate) request_id: Option<String>,
+    #[serde(default)]
+    pub(crate) environment_id: Option<String>,
+    interval_secs: u64,
+    #[serde(default = "default_true")]
+    enabled: bool,
+    /// Raise notifications when the monitor fails or recovers.
+    #[serde(default = "default_true")]
+    notify: bool,
+    #[serde(default)]
+    alerts: Vec<AlertRule>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    /// The 95th percentile of the window's latencies, in ms.
+    P95Latency,
+    /// The share of failed checks in the window, 0–100.
+    ErrorRate,
+}
+
+impl AlertMetric {
+    fn describe(self, value: f64) -> String {
+        match self {
+            AlertMetric::P95Latency => format!("p95 latency {value:.0} ms"),
+            AlertMetric::ErrorRate => format!("error rate {value:.1}%"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertAction {
+    /// A desktop notification, subject to the notification settings.
+    Notify,
+    /// POSTs the alert as JSON.
+    Webhook { url: String },
+    /// Runs `program` with the alert in `LITEFETCH_ALERT_*` environment variables.
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AlertRule {
+    /// Assigned on first save.
+    #[serde(default)]
+    id: String,
+    metric: AlertMetric,
+    /// Fires above this: ms for `p95_latency`, percent for `error_rate`.
+    threshold: f64,
+    /// How many of the latest checks the metric covers; nothing fires until there are as many.
+    #[serde(default = "default_alert_window")]
+    window: usize,
+    /// Taken when the alert fires and when it resolves.
+    #[serde(default = "default_alert_actions")]
+    actions: Vec<AlertAction>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Alert {
+    monitor_id: String,
+    rule_id: String,
+    metric: AlertMetric,
+    threshold: f64,
+    /// `firing` or `resolved`.
+    state: String,
+    /// The metric at the latest check.
+    value: f64,
+    /// When it last started firing or resolved.
+    since_ms: u64,
+    updated_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct AlertEvent {
+    monitor_id: String,
+    monitor_name: String,
+    alert: Alert,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Check {
+    timestamp_ms: u64,
+    passed: bool,
+    /// The request's status; `None` for collection monitors.
+    status_code: Option<u64>,
+    duration_ms: Option<f64>,
+    /// Requests that failed, for collection monitors.
+    #[serde(default)]
+    failed_requests: Vec<String>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct MonitorStatus {
+    monitor: Monitor,
+    last: Option<Check>,
+    checks: usize,
+    /// Share of passed checks in the kept history, 0–100.
+    uptime: Option<f64>,
+    avg_latency_ms: Option<f64>,
+    p95_latency_ms: Option<f64>,
+}
+
+#[derive(Serialize, Clone)]
+struct CheckEvent {
+    monitor_id: String,
+    check: Check,
+    /// `failed` or `recovered` when the check changed the monitor's state.
+    transition: Option<String>,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct Permissions {
+    /// Workspace paths the user allowed alert commands in, with when they did.
+    allowed: BTreeMap<String, u64>,
+}
+
+#[derive(Serialize)]
+pub struct CommandPermission {
+    pub workspace: String,
+    pub allowed: bool,
+    pub allowed_ms: Option<u64>,
+}
+
+fn permissions_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(PERMISSIONS_FILE))
+}
+
+fn load_permissions(app: &tauri::AppHandle) -> Result<Permissions, String> {
+    let path = permissions_path(app)?;
+    if !path.exists() {
+        return Ok(Permissions::default());
+    }
+    let data = fs::read_to_string(&path)
+        .map_err(|e| format!("monitor command permissions read failed: {e}"))?;
+    serde_json::from_str(&data)
+        .map_err(|e| format!("monitor command permissions parse failed: {e}"))
+}
+
+fn permission(app: &tauri::AppHandle) -> Result<CommandPermission, String> {
+    let workspace = crate::load_workspace_path(app)?
+        .to_string_lossy()
+        .to_string();
+    let allowed_ms = load_permissions(app)?.allowed.get(&workspace).copied();
+    Ok(CommandPermission {
+        workspace,
+        allowed: allowed_ms.is_some(),
+        allowed_ms,
+    })
+}
+
+fn monitors_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    crate::workspace_state_dir(app, "monitors")
+}
+
+pub(crate) fn load_monitors(app: &tauri::AppHandle) -> Result<Vec<Monitor>, String> {
+    let path = monitors_dir(app)?.join(MONITORS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("monitors read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("monitors parse failed: {e}"))
+}
+
+fn save_monitors(app: &tauri::AppHandle, monitors: &[Monitor]) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(monitors)
+        .map_err(|e| format!("monitors serialize failed: {e}"))?;
+    fs::write(monitors_dir(app)?.join(MONITORS_FILE), payload)
+        .map_err(|e| format!("monitors persist failed: {e}"))
+}
+
+/// Monitor ids come from the workspace and the UI; keep them to the files they name.
+fn history_path(app: &tauri::AppHandle, monitor_id: &str) -> Result<PathBuf, String> {
+    if !valid_id(monitor_id) {
+        return Err(format!("unknown monitor: {monitor_id}"));
+    }
+    Ok(monitors_dir(app)?.join(format!("{monitor_id}.history.json")))
+}
+
+fn valid_id(monitor_id: &str) -> bool {
+    !monitor_id.is_empty()
+        && monitor_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+fn load_history(app: &tauri::AppHandle, monitor_id: &str) -> Result<Vec<Check>, String> {
+    let path = history_path(app, monitor_id)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data =
+        fs::read_to_string(&path).map_err(|e| format!("monitor history read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("monitor history parse failed: {e}"))
+}
+
+/// Adds a check to the monitor's history and returns the history.
+fn append_history(
+    app: &tauri::AppHandle,
+    monitor_id: &str,
+    check: &Check,
+) -> Result<Vec<Check>, String> {
+    let mut history = load_history(app, monitor_id)?;
+    history.push(check.clone());
+    let excess = history.len().saturating_sub(HISTORY_LIMIT);
+    history.drain(..excess);
+    let payload = serde_json::to_string(&history)
+        .map_err(|e| format!("monitor history serialize failed: {e}"))?;
+    fs::write(history_path(app, monitor_id)?, payload)
+        .map_err(|e| format!("monitor history persist failed: {e}"))?;
+    Ok(history)
+}
+
+fn load_alerts(app: &tauri::AppHandle) -> Result<Vec<Alert>, String> {
+    let path = monitors_dir(app)?.join(ALERTS_FILE);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("alerts read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("alerts parse failed: {e}"))
+}
+
+fn save_alerts(app: &tauri::AppHandle, alerts: &[Alert]) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(alerts)
+        .map_err(|e| format!("alerts serialize failed: {e}"))?;
+    fs::write(monitors_dir(app)?.join(ALERTS_FILE), payload)
+        .map_err(|e| format!("alerts persist failed: {e}"))
+}
+
+/// Drops the alert states of `monitor_id` whose rule isn't in `keep`.
+async fn prune_alerts(app: &tauri::AppHandle, monitor_id: &str, keep: &[AlertRule]) {
+    let state = app.state::<MonitorState>();
+    let _held = state.alerts.lock().await;
+    let pruned = load_alerts(app).and_then(|mut alerts| {
+        alerts.retain(|a| a.monitor_id != monitor_id || keep.iter().any(|r| r.id == a.rule_id));
+        save_alerts(app, &alerts)
+    });
+    if let Err(e) = pruned {
+        tracing::warn!("{e}");
+    }
+}
+
+fn p95(mut latencies: Vec<f64>) -> Option<f64> {
+    latencies.sort_by(f64::total_cmp);
+    (!latencies.is_empty()).then(|| {
+        let rank = (latencies.len() as f64 * 0.95).ceil() as usize;
+        latencies[rank.clamp(1, latencies.len()) - 1]
+    })
+}
+
+fn measure(metric: AlertMetric, window: &[Check]) -> Option<f64> {
+    match metric {
+        AlertMetric::P95Latency => p95(window.iter().filter_map(|c| c.duration_ms).collect()),
+        AlertMetric::ErrorRate => {
+            let failed = window.iter().filter(|c| !c.passed).count();
+            (!window.is_empty()).then(|| failed as f64 * 100.0 / window.len() as f64)
+        }
+    }
+}
+
+fn status_of(monitor: Monitor, history: &[Check]) -> MonitorStatus {
+    let latencies: Vec<f64> = history.iter().filter_map(|c| c.duration_ms).collect();
+    let passed = history.iter().filter(|c| c.passed).count();
+    MonitorStatus {
+        monitor,
+        last: history.last().cloned(),
+        checks: history.len(),
+        uptime: (!history.is_empty()).then(|| passed as f64 * 100.0 / history.len() as f64),
+        avg_latency_ms: (!latencies.is_empty())
+            .then(|| latencies.iter().sum::<f64>() / latencies.len() as f64),
+        p95_latency_ms: p95(latencies),
+    }
+}
+
+/// A response counts as healthy when it arrived, isn't a 4xx/5xx and passed its tests.
+fn healthy(status_code: Option<u64>, error: Option<&str>, tests: Option<&TestReport>) -> bool {
+    error.is_none()
+        && status_code.is_some_and(|code| code < 400)
+        && tests.is_none_or(|t| t.failed == 0 && t.error.is_none())
+}
+
+async fn check(app: &tauri::AppHandle, monitor: &Monitor) -> Check {
+    let mut check = Check {
+        timestamp_ms: crate::now_ms(),
+        passed: false,
+        status_code: None,
+        duration_ms: None,
+        failed_requests: Vec::new(),
+        error: None,
+    };
+    let collection = match crate::backend_get(
+        app,
+        &format!("/collections/{}/collection", monitor.collection_id),
+    )
+    .await
+    {
+        Ok(collection) => collection,
+        Err(e) => {
+            check.error = Some(e);
+            return check;
+        }
+    };
+    match &monitor.request_id {
+        Some(request_id) => {
+            let items = litefetch_core::json::array_of(&collection, "items");
+            let Some(request) = litefetch_core::json::find_request(items, request_id) else {
+                check.error = Some(format!("unknown request: {request_id}"));
+                return check;
+            };
+            let sent = crate::send::dispatch(
+                app.clone(),
+                monitor.collection_id.clone(),
+                request.clone(),
+                monitor.environment_id.clone(),
+            )
+            .await;
+            match sent {
+                Ok(sent) => {
+                    check.status_code = sent.result.get("status_code").and_then(Value::as_u64);
+                    check.duration_ms = sent.result.get("duration_ms").and_then(Value::as_f64);
+                    check.error = sent
+                        .result
+                        .get("error")
+                        .and_then(Value::as_str)
+                        .filter(|e| !e.is_empty())
+                        .map(str::to_string);
+                    check.passed = healthy(
+                        check.status_code,
+                        check.error.as_deref(),
+                        sent.tests.as_ref(),
+                    );
+                }
+                Err(e) => check.error = Some(e),
+            }
+        }
+        None => {
+            let options = RunOptions {
+                environment_id: monitor.environment_id.clone(),
+                ..RunOptions::default()
+            };
+            let plan = match Plan::new(&collection, &options) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    check.error = Some(e);
+                    return check;
+                }
+            };
+            let sender = Shell {
+                app: app.clone(),
+                collection_id: monitor.collection_id.clone(),
+                environment_id: monitor.environment_id.clone(),
+            };
+            let summary = litefetch_core::runner::execute(
+                uuid::Uuid::new_v4().to_string(),
+                monitor.collection_id.clone(),
+                &plan,
+                &options,
+                &sender,
+                &AtomicBool::new(false),
+                |_, _| {},
+            )
+            .await;
+            check.duration_ms = Some(summary.duration_ms as f64);
+            check.failed_requests = summary
+                .results
+                .iter()
+                .filter(|r| !healthy(r.status_code, r.error.as_deref(), r.tests.as_ref()))
+                .map(|r| r.name.clone())
+                .collect();
+            check.passed = check.failed_requests.is_empty();
+        }
+    }
+    check
+}
+
+/// Takes an alert action; failures are only logged, so one broken action doesn't hold up
+/// the others.
+async fn act(app: &tauri::AppHandle, monitor: &Monitor, alert: &Alert, action: &AlertAction) {
+    let summary = format!(
+        "{} {} (threshold {})",
+        alert.metric.describe(alert.value),
+        alert.state,
+        alert.threshold
+    );
+    match action {
+        AlertAction::Notify => {
+            crate::notifications::send(
+                app,
+                crate::notifications::Category::MonitorFailures,
+                &format!("{} alert {}", monitor.name, alert.state),
+                &summary,
+            );
+        }
+        AlertAction::Webhook { url } => {
+            let sent = reqwest::Client::new()
+                .post(url)
+                .timeout(WEBHOOK_TIMEOUT)
+                .json(&AlertEvent {
+                    monitor_id: monitor.id.clone(),
+                    monitor_name: monitor.name.clone(),
+                    alert: alert.clone(),
+                    timestamp_ms: crate::now_ms(),
+                })
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            if let Err(e) = sent {
+                tracing::warn!("alert webhook {url} failed: {e}");
+            }
+        }
+        AlertAction::Command { program, args } => {
+            match permission(app) {
+                Ok(permission) if permission.allowed => {}
+                Ok(_) => {
+                    tracing::warn!(
+                        "alert command {program} skipped: alert commands aren't allowed in this workspace"
+                    );
+                    return;
+                }
+                Err(e) => {
+                    tracing::warn!("alert command {program} skipped: {e}");
+                    return;
+                }
+            }
+            let run = tokio::process::Command::new(program)
+                .args(args)
+                .env("LITEFETCH_ALERT_MONITOR", &monitor.name)
+                .env("LITEFETCH_ALERT_MONITOR_ID", &monitor.id)
+                .env("LITEFETCH_ALERT_STATE", &alert.state)
+                .env("LITEFETCH_ALERT_VALUE", alert.value.to_string())
+                .env("LITEFETCH_ALERT_THRESHOLD", alert.threshold.to_string())
+                .env("LITEFETCH_ALERT_SUMMARY", &summary)
+                .stdin(std::process::Stdio::null())
+                .kill_on_drop(true)
+                .status();
+            match tokio::time::timeout(COMMAND_TIMEOUT, run).await {
+                Ok(Ok(status)) if status.success() => {}
+                Ok(Ok(status)) => tracing::warn!("alert command {program} exited with {status}"),
+                Ok(Err(e)) => tracing::warn!("alert command {program} failed: {e}"),
+                Err(_) => tracing::warn!("alert command {program} timed out"),
+            }
+        }
+    }
+}
+
+/// Evaluates the monitor's alert rules against its history, saving the states and taking
+/// the actions of those that started firing or resolved.
+async fn evaluate_alerts(app: &tauri::AppHandle, monitor: &Monitor, history: &[Check]) {
+    let state = app.state::<MonitorState>();
+    let held = state.alerts.lock().await;
+    let mut alerts = match load_alerts(app) {
+        Ok(alerts) => alerts,
+        Err(e) => {
+            tracing::warn!("{e}");
+            return;
+        }
+    };
+    let now = crate::now_ms();
+    let mut changed = Vec::new();
+    for rule in &monitor.alerts {
+        let window = rule.window.max(1);
+        if history.len() < window {
+            continue;
+        }
+        let Some(value) = measure(rule.metric, &history[history.len() - window..]) else {
+            continue;
+        };
+        let firing = value > rule.threshold;
+        let existing = alerts
+            .iter()
+            .position(|a| a.monitor_id == monitor.id && a.rule_id == rule.id);
+        let index = match existing {
+            Some(index) => index,
+            None if firing => {
+                alerts.push(Alert {
+                    monitor_id: monitor.id.clone(),
+                    rule_id: rule.id.clone(),
+                    metric: rule.metric,
+                    threshold: rule.threshold,
+                    state: RESOLVED.to_string(),
+                    value,
+                    since_ms: now,
+                    updated_ms: now,
+                });
+                alerts.len() - 1
+            }
+            None => continue,
+        };
+        let alert = &mut alerts[index];
+        alert.metric = rule.metric;
+        alert.threshold = rule.threshold;
+        alert.value = value;
+        alert.updated_ms = now;
+        if firing != (alert.state == FIRING) {
+            alert.state = match firing {
+                true => FIRING,
+                false => RESOLVED,
+            }
+            .to_string();
+            alert.since_ms = now;
+            changed.push((alert.clone(), rule.actions.clone()));
+        }
+    }
+    if let Err(e) = save_alerts(app, &alerts) {
+        tracing::warn!("{e}");
+    }
+    drop(held);
+
+    for (alert, actions) in changed {
+        let _ = app.emit(
+            "monitor://alert",
+            AlertEvent {
+                monitor_id: monitor.id.clone(),
+                monitor_name: monitor.name.clone(),
+                alert: alert.clone(),
+                timestamp_ms: crate::now_ms(),
+            },
+        );
+        let app = app.clone();
+        let monitor = monitor.clone();
+        tauri::async_runtime::spawn(async move {
+            for action in &actions {
+                act(&app, &monitor, &alert, action).await;
+            }
+        });
+    }
+}
+
+/// Records a check, emits it and notifies on a change between passing and failing.
+async fn record(app: &tauri::AppHandle, monitor: &Monitor, check: Check) {
+    match append_history(app, &monitor.id, &check) {
+        Ok(history) if !monitor.alerts.is_empty() => evaluate_alerts(app, monitor, &history).await,
+        Ok(_) => {}
+        Err(e) => tracing::warn!("{e}"),
+    }
+    let state = app.state::<MonitorState>();
+    let mut failing = state.failing.lock().await;
+    let transition = match (check.passed, failing.contains(&monitor.id)) {
+        (false, false) => {
+            failing.insert(monitor.id.clone());
+            Some("failed")
+        }
+        (true, true) => {
+            failing.remove(&monitor.id);
+            Some("recovered")
+        }
+        _ => None,
+    };
+    let failing_count = failing.len();
+    drop(failing);
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_badge_count((failing_count > 0).then_some(failing_count as i64));
+    }
+    if let (Some(transition), true) = (transition, monitor.notify) {
+        let body = match transition {
+            "failed" => check
+                .error
+                .clone()
+                .or_else(|| check.status_code.map(|code| format!("HTTP {code}")))
+                .unwrap_or_else(|| format!("{} requests failed", check.failed_requests.len())),
+            _ => "Back to passing".to_string(),
+        };
+        crate::notifications::send(
+            app,
+            crate::notifications::Category::MonitorFailures,
+            &format!("{} {transition}", monitor.name),
+            &body,
+        );
+    }
+    let _ = app.emit(
+        "monitor://check",
+        CheckEvent {
+            monitor_id: monitor.id.clone(),
+            check,
+            transition: transition.map(str::to_string),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+fn spawn_monitor(app: &tauri::AppHandle, monitor: Monitor) -> JoinHandle<()> {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut ticks = tokio::time::interval(Duration::from_secs(monitor.interval_secs));
+        loop {
+            ticks.tick().await;
+            let result = check(&app, &monitor).await;
+            record(&app, &monitor, result).await;
+        }
+    })
+}
+
+async fn stop_all(state: &MonitorState) {
+    for (_, task) in state.tasks.lock().await.drain() {
+        task.abort();
+    }
+    state.failing.lock().await.clear();
+}
+
+/// (Re)starts the enabled monitors of the current workspace, e.g. at launch or after the
+/// workspace changed.
+pub async fn resume(app: tauri::AppHandle) {
+    let state = app.state::<MonitorState>();
+    stop_all(&state).await;
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_badge_count(None);
+    }
+    let monitors = match load_monitors(&app) {
+        Ok(monitors) => monitors,
+        Err(e) => {
+            tracing::warn!("{e}");
+            return;
+        }
+    };
+    let mut tasks = state.tasks.lock().await;
+    for monitor in monitors.into_iter().filter(|m| m.enabled) {
+        tasks.insert(monitor.id.clone(), spawn_monitor(&app, monitor));
+    }
+}
+
+#[tauri::command]
+pub async fn list_monitors(app: tauri::AppHandle) -> Result<Vec<MonitorStatus>, String> {
+    load_monitors(&app)?
+        .into_iter()
+        .map(|monitor| {
+            let history = load_history(&app, &monitor.id)?;
+            Ok(status_of(monitor, &history))
+        })
+        .collect()
+}
+
+/// Creates a monitor (when `id` is empty) or replaces one, and restarts its schedule.
+#[tauri::command]
+pub async fn save_monitor(
+    app: tauri::AppHandle,
+    state: State<'_, MonitorState>,
+    mut monitor: Monitor,
+) -> Result<Monitor, String> {
+    if monitor.name.trim().is_empty() {
+        return Err("monitor needs a name".to_string());
+    }
+    if monitor.id.is_empty() {
+        monitor.id = uuid::Uuid::new_v4().to_string();
+    }
+    if !valid_id(&monitor.id) {
+        return Err(format!("invalid monitor id: {}", monitor.id));
+    }
+    monitor.interval_secs = monitor.interval_secs.max(MIN_INTERVAL_SECS);
+    for rule in &mut monitor.alerts {
+        if !rule.threshold.is_finite() || rule.threshold < 0.0 {
+            return Err("alert threshold must be a positive number".to_string());
+        }
+        for action in &rule.actions {
+            match action {
+                AlertAction::Webhook { url } if url.trim().is_empty() => {
+                    return Err("alert webhook needs a URL".to_string())
+                }
+                AlertAction::Command { program, .. } if program.trim().is_empty() => {
+                    return Err("alert command needs a program".to_string())
+                }
+                _ => {}
+            }
+        }
+        if rule.id.is_empty() {
+            rule.id = uuid::Uuid::new_v4().to_string();
+        }
+        rule.window = rule.window.max(1);
+    }
+    let mut monitors = load_monitors(&app)?;
+    match monitors.iter_mut().find(|m| m.id == monitor.id) {
+        Some(existing) => *existing = monitor.clone(),
+        None => monitors.push(monitor.clone()),
+    }
+    save_monitors(&app, &monitors)?;
+    prune_alerts(&app, &monitor.id, &monitor.alerts).await;
+
+    let mut tasks = state.tasks.lock().await;
+    if let Some(task) = tasks.remove(&monitor.id) {
+        task.abort();
+    }
+    if monitor.enabled {
+        tasks.insert(monitor.id.clone(), spawn_monitor(&app, monitor.clone()));
+    } else {
+        state.failing.lock().await.remove(&monitor.id);
+    }
+    Ok(monitor)
+}
+
+/// Stops a monitor and deletes it with its history.
+#[tauri::command]
+pub async fn delete_monitor(
+    app: tauri::AppHandle,
+    state: State<'_, MonitorState>,
+    monitor_id: String,
+) -> Result<(), String> {
+    let mut monitors = load_monitors(&app)?;
+    let before = monitors.len();
+    monitors.retain(|m| m.id != monitor_id);
+    if monitors.len() == before {
+        return Err(format!("unknown monitor: {monitor_id}"));
+    }
+    save_monitors(&app, &monitors)?;
+    if let Some(task) = state.tasks.lock().await.remove(&monitor_id) {
+        task.abort();
+    }
+    state.failing.lock().await.remove(&monitor_id);
+    prune_alerts(&app, &monitor_id, &[]).await;
+    let _ = fs::remove_file(history_path(&app, &monitor_id)?);
+    Ok(())
+}
+
+/// The monitor's checks, newest last; the latest `limit` when given.
+#[tauri::command]
+pub async fn get_monitor_history(
+    app: tauri::AppHandle,
+    monitor_id: String,
+    limit: Option<usize>,
+) -> Result<Vec<Check>, String> {
+    let mut history = load_history(&app, &monitor_id)?;
+    if let Some(limit) = limit {
+        let excess = history.len().saturating_sub(limit);
+        history.drain(..excess);
+    }
+    Ok(history)
+}
+
+/// Runs a check now, outside the schedule, and records it like a scheduled one.
+#[tauri::command]
+pub async fn run_monitor_now(app: tauri::AppHandle, monitor_id: String) -> Result<Check, String> {
+    let monitor = load_monitors(&app)?
+        .into_iter()
+        .find(|m| m.id == monitor_id)
+        .ok_or_else(|| format!("unknown monitor: {monitor_id}"))?;
+    let result = check(&app, &monitor).await;
+    record(&app, &monitor, result.clone()).await;
+    Ok(result)
+}
+
+/// Alert states, firing ones first; only `monitor_id`'s when given.
+#[tauri::command]
+pub async fn list_monitor_alerts(
+    app: tauri::AppHandle,
+    monitor_id: Option<String>,
+) -> Result<Vec<Alert>, String> {
+    let mut alerts = load_alerts(&app)?;
+    if let Some(monitor_id) = monitor_id {
+        alerts.retain(|a| a.monitor_id == monitor_id);
+    }
+    alerts.sort_by_key(|a| (a.state != FIRING, std::cmp::Reverse(a.since_ms)));
+    Ok(alerts)
+}
+
+/// Whether alert command actions may run in the current workspace.
+#[tauri::command]
+pub async fn get_monitor_command_permission(
+    app: tauri::AppHandle,
+) -> Result<CommandPermission, String> {
+    permission(&app)
+}
+
+/// Allows or disallows alert command actions in the current workspace.
+#[tauri::command]
+pub async fn set_monitor_command_permission(
+    app: tauri::AppHandle,
+    allowed: bool,
+) -> Result<CommandPermission, String> {
+    let workspace = crate::load_workspace_path(&app)?
+        .to_string_lossy()
+        .to_string();
+    let mut permissions = load_permissions(&app)?;
+    match allowed {
+        true => {
+            permissions.allowed.insert(workspace, crate::now_ms());
+        }
+        false => {
+            permissions.allowed.remove(&workspace);
+        }
+    }
+    let payload = serde_json::to_string_pretty(&permissions)
+        .map_err(|e| format!("monitor command permissions serialize failed: {e}"))?;
+    fs::write(permissions_path(&app)?, payload)
+        .map_err(|e| format!("monitor command permissions persist failed: {e}"))?;
+    permission(&app)
+}
@@ -0,0 +1,423 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, Manager, State};
+
+use rumqttc::v5::mqttbytes::QoS as QoS5;
+use rumqttc::{QoS, Transport};
+
+/// How long `mqtt_disconnect` waits for the DISCONNECT packet to go out.
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub struct MqttState {
+    connections: Mutex<HashMap<String, MqttConnection>>,
+}
+
+impl MqttState {
+    pub fn new() -> Self {
+        Self {
+            connections: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+enum MqttClient {
+    V4(rumqttc::AsyncClient),
+    V5(rumqttc::v5::AsyncClient),
+}
+
+struct MqttConnection {
+    client: MqttClient,
+    task: JoinHandle<()>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MqttVersion {
+    #[default]
+    #[serde(rename = "3.1.1")]
+    V311,
+    #[serde(rename = "5")]
+    V5,
+}
+
+#[derive(Deserialize)]
+pub struct MqttConnectOptions {
+    host: String,
+    #[serde(default)]
+    port: Option<u16>,
+    #[serde(default)]
+    client_id: Option<String>,
+    #[serde(default)]
+    username: Option<String>,
+    #[serde(default)]
+    password: Option<String>,
+    #[serde(default)]
+    version: MqttVersion,
+    #[serde(default)]
+    tls: bool,
+    #[serde(default)]
+    ca_cert_path: Option<String>,
+    #[serde(default)]
+    keep_alive_secs: Option<u64>,
+    #[serde(default = "default_clean_session")]
+    clean_session: bool,
+}
+
+fn default_clean_session() -> bool {
+    true
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+pub enum MqttPayloadEncoding {
+    #[default]
+    #[serde(rename = "text")]
+    Text,
+    #[serde(rename = "base64")]
+    Base64,
+}
+
+#[derive(Clone, Serialize)]
+struct MqttMessageEvent {
+    connection_id: String,
+    topic: String,
+    payload: String,
+    payload_base64: String,
+    qos: u8,
+    retain: bool,
+    timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct MqttStatusEvent {
+    connection_id: String,
+    status: String,
+    detail: Option<String>,
+    timestamp_ms: u64,
+}
+
+fn qos_level(level: u8) -> Result<QoS, String> {
+    match level {
+        0 => Ok(QoS::AtMostOnce),
+        1 => Ok(QoS::AtLeastOnce),
+        2 => Ok(QoS::ExactlyOnce),
+        _ => Err(format!("invalid QoS level: {level}")),
+    }
+}
+
+fn qos5_level(level: u8) -> Result<QoS5, String> {
+    match level {
+        0 => Ok(QoS5::AtMostOnce),
+        1 => Ok(QoS5::AtLeastOnce),
+        2 => Ok(QoS5::ExactlyOnce),
+        _ => Err(format!("invalid QoS level: {level}")),
+    }
+}
+
+fn transport(options: &MqttConnectOptions) -> Result<Transport, String> {
+    if !options.tls {
+        return Ok(Transport::Tcp);
+    }
+    match &options.ca_cert_path {
+        Some(path) => {
+            let ca = std::fs::read(crate::normalize_path(path))
+                .map_err(|e| format!("CA certificate read failed: {e}"))?;
+            Ok(Transport::tls(ca, None, None))
+        }
+        None => Ok(Transport::tls_with_default_config()),
+    }
+}
+
+fn emit_status(app: &tauri::AppHandle, connection_id: &str, status: &str, detail: Option<String>) {
+    let _ = app.emit(
+        "mqtt://status",
+        MqttStatusEvent {
+            connection_id: connection_id.to_string(),
+            status: status.to_string(),
+            detail,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+fn emit_message(
+    app: &tauri::AppHandle,
+    connection_id: &str,
+    topic: String,
+    payload: &[u8],
+    qos: u8,
+    retain: bool,
+) {
+    let _ = app.emit(
+        "mqtt://message",
+        MqttMessageEvent {
+            connection_id: connection_id.to_string(),
+            topic,
+            payload: String::from_utf8_lossy(payload).to_string(),
+            payload_base64: STANDARD.encode(payload),
+            qos,
+            retain,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+async fn forget_connection(app: &tauri::AppHandle, connection_id: &str) {
+    app.state::<MqttState>()
+        .connections
+        .lock()
+        .await
+        .remove(connection_id);
+}
+
+async fn run_v4(app: tauri::AppHandle, connection_id: String, mut eventloop: rumqttc::EventLoop) {
+    use rumqttc::{Event, Outgoing, Packet};
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(ack))) => emit_status(
+                &app,
+                &connection_id,
+                "connected",
+                Some(format!("{:?}", ack.code)),
+            ),
+            Ok(Event::Incoming(Packet::Publish(publish))) => emit_message(
+                &app,
+                &connection_id,
+                publish.topic,
+                &publish.payload,
+                publish.qos as u8,
+                publish.retain,
+            ),
+            Ok(Event::Incoming(Packet::Disconnect)) => {
+                emit_status(&app, &connection_id, "disconnected", None);
+                break;
+            }
+            // Flushed to the broker; `mqtt_disconnect` is waiting for this.
+            Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+            Ok(_) => {}
+            Err(e) => {
+                emit_status(&app, &connection_id, "error", Some(e.to_string()));
+                break;
+            }
+        }
+    }
+    forget_connection(&app, &connection_id).await;
+}
+
+async fn run_v5(
+    app: tauri::AppHandle,
+    connection_id: String,
+    mut eventloop: rumqttc::v5::EventLoop,
+) {
+    use rumqttc::v5::mqttbytes::v5::Packet;
+    use rumqttc::v5::Event;
+    use rumqttc::Outgoing;
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(ack))) => emit_status(
+                &app,
+                &connection_id,
+                "connected",
+                Some(format!("{:?}", ack.code)),
+            ),
+            Ok(Event::Incoming(Packet::Publish(publish))) => emit_message(
+                &app,
+                &connection_id,
+                String::from_utf8_lossy(&publish.topic).to_string(),
+                &publish.payload,
+                publish.qos as u8,
+                publish.retain,
+            ),
+            Ok(Event::Incoming(Packet::Disconnect(disconnect))) => {
+                emit_status(
+                    &app,
+                    &connection_id,
+                    "disconnected",
+                    Some(format!("{:?}", disconnect.reason_code)),
+                );
+                break;
+            }
+            // Flushed to the broker; `mqtt_disconnect` is waiting for this.
+            Ok(Event::Outgoing(Outgoing::Disconnect)) => break,
+            Ok(_) => {}
+            Err(e) => {
+                emit_status(&app, &connection_id, "error", Some(e.to_string()));
+                break;
+            }
+        }
+    }
+    forget_connection(&app, &connection_id).await;
+}
+
+#[tauri::command]
+pub async fn mqtt_connect(
+    app: tauri::AppHandle,
+    state: State<'_, MqttState>,
+    options: MqttConnectOptions,
+) -> Result<String, String> {
+    let connection_id = uuid::Uuid::new_v4().to_string();
+    let client_id = options
+        .client_id
+        .clone()
+        .unwrap_or_else(|| format!("litefetch-{}", &connection_id[..8]));
+    let port = options
+        .port
+        .unwrap_or(if options.tls { 8883 } else { 1883 });
+    let keep_alive = Duration::from_secs(options.keep_alive_secs.unwrap_or(30));
+    let transport = transport(&options)?;
+
+    let mut connections = state.connections.lock().await;
+    let connection = match options.version {
+        MqttVersion::V311 => {
+            let mut mqtt = rumqttc::MqttOptions::new(client_id, options.host.clone(), port);
+            mqtt.set_keep_alive(keep_alive)
+                .set_clean_session(options.clean_session)
+                .set_transport(transport);
+            if let Some(user) = &options.username {
+                mqtt.set_credentials(user.clone(), options.password.clone().unwrap_or_default());
+            }
+            let (client, eventloop) = rumqttc::AsyncClient::new(mqtt, 64);
+            MqttConnection {
+                client: MqttClient::V4(client),
+                task: tauri::async_runtime::spawn(run_v4(
+                    app.clone(),
+                    connection_id.clone(),
+                    eventloop,
+                )),
+            }
+        }
+        MqttVersion::V5 => {
+            let mut mqtt = rumqttc::v5::MqttOptions::new(client_id, options.host.clone(), port);
+            mqtt.set_keep_alive(keep_alive)
+                .set_clean_start(options.clean_session)
+                .set_transport(transport);
+            if let Some(user) = &options.username {
+                mqtt.set_credentials(user.clone(), options.password.clone().unwrap_or_default());
+            }
+            let (client, eventloop) = rumqttc::v5::AsyncClient::new(mqtt, 64);
+            MqttConnection {
+                client: MqttClient::V5(client),
+                task: tauri::async_runtime::spawn(run_v5(
+                    app.clone(),
+                    connection_id.clone(),
+                    eventloop,
+                )),
+            }
+        }
+    };
+    connections.insert(connection_id.clone(), connection);
+    emit_status(
+        &app,
+        &connection_id,
+        "connecting",
+        Some(format!("{}:{port}", options.host)),
+    );
+    Ok(connection_id)
+}
+
+/// Topic filters may use MQTT wildcards (`+` single level, `#` multi level).
+#[tauri::command]
+pub async fn mqtt_subscribe(
+    state: State<'_, MqttState>,
+    connection_id: String,
+    topic: String,
+    qos: Option<u8>,
+) -> Result<(), String> {
+    let connections = state.connections.lock().await;
+    let connection = connections
+        .get(&connection_id)
+        .ok_or_else(|| format!("unknown MQTT connection: {connection_id}"))?;
+    let level = qos.unwrap_or(0);
+    let outcome = match &connection.client {
+        MqttClient::V4(client) => client
+            .subscribe(topic, qos_level(level)?)
+            .await
+            .map_err(|e| e.to_string()),
+        MqttClient::V5(client) => client
+            .subscribe(topic, qos5_level(level)?)
+            .await
+            .map_err(|e| e.to_string()),
+    };
+    outcome.map_err(|e| format!("subscribe failed: {e}"))
+}
+
+#[tauri::command]
+pub async fn mqtt_unsubscribe(
+    state: State<'_, MqttState>,
+    connection_id: String,
+    topic: String,
+) -> Result<(), String> {
+    let connections = state.connections.lock().await;
+    let connection = connections
+        .get(&connection_id)
+        .ok_or_else(|| format!("unknown MQTT connection: {connection_id}"))?;
+    let outcome = match &connection.client {
+        MqttClient::V4(client) => client.unsubscribe(topic).await.map_err(|e| e.to_string()),
+        MqttClient::V5(client) => client.unsubscribe(topic).await.map_err(|e| e.to_string()),
+    };
+    outcome.map_err(|e| format!("unsubscribe failed: {e}"))
+}
+
+#[tauri::command]
+pub async fn mqtt_publish(
+    state: State<'_, MqttState>,
+    connection_id: String,
+    topic: String,
+    payload: String,
+    encoding: Option<MqttPayloadEncoding>,
+    qos: Option<u8>,
+    retain: Option<bool>,
+) -> Result<(), String> {
+    let bytes = match encoding.unwrap_or_default() {
+        MqttPayloadEncoding::Text => payload.into_bytes(),
+        MqttPayloadEncoding::Base64 => STANDARD
+            .decode(payload.trim())
+            .map_err(|e| format!("payload is not valid base64: {e}"))?,
+    };
+    let connections = state.connections.lock().await;
+    let connection = connections
+        .get(&connection_id)
+        .ok_or_else(|| format!("unknown MQTT connection: {connection_id}"))?;
+    let level = qos.unwrap_or(0);
+    let retain = retain.unwrap_or(false);
+    let outcome = match &connection.client {
+        MqttClient::V4(client) => client
+            .publish(topic, qos_level(level)?, retain, bytes)
+            .await
+            .map_err(|e| e.to_string()),
+        MqttClient::V5(client) => client
+            .publish(topic, qos5_level(level)?, retain, bytes)
+            .await
+            .map_err(|e| e.to_string()),
+    };
+    outcome.map_err(|e| format!("publish failed: {e}"))
+}
+
+#[tauri::command]
+pub async fn mqtt_disconnect(
+    app: tauri::AppHandle,
+    state: State<'_, MqttState>,
+    connection_id: String,
+) -> Result<(), String> {
+    let connection = state
+        .connections
+        .lock()
+        .await
+        .remove(&connection_id)
+        .ok_or_else(|| format!("unknown MQTT connection: {connection_id}"))?;
+    let queued = match &connection.client {
+        MqttClient::V4(client) => client.disconnect().await.map_err(|e| e.to_string()),
+        MqttClient::V5(client) => client.disconnect().await.map_err(|e| e.to_string()),
+    };
+    // `disconnect` only queues the packet: let the event loop send it, or the broker sees
+    // the connection drop and publishes the will.
+    let mut task = connection.task;
+    if queued.is_ok() {
+        let _ = tokio::time::timeout(DISCONNECT_TIMEOUT, &mut task).await;
+    }
+    task.abort();
+    emit_status(&app, &connection_id, "disconnected", None);
+    Ok(())
+}
@@ -0,0 +1,240 @@
+//! Simulated bad networks for requests sent through the shell: added latency (with jitter),
+//! a bandwidth cap on the request and response bodies, and random failures. The workspace
+//! profile lives in `network.json`; a request's own `network_profile` replaces it, and one
+//! with `enabled: false` turns shaping off for that request. Throttling is simulated: the
+//! transfer time a body would take at the cap is waited out and added to `duration_ms`.
+//! Every shaped result says what was applied in `network_simulation`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use litefetch_core::dynamic::random_below;
+
+pub(crate) const SETTINGS_FILE: &str = "network.json";
+/// Longest latency, jitter or throttle wait per request.
+const MAX_DELAY_MS: u64 = 5 * 60 * 1000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The connection drops before a response arrives.
+    #[default]
+    Reset,
+    /// Nothing comes back until the request's timeout runs out.
+    Timeout,
+    /// The server answers with `failure_status` and an empty body.
+    Status,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NetworkProfile {
+    pub enabled: bool,
+    /// Added before the request goes out.
+    pub latency_ms: u64,
+    /// Up to this much more latency, picked at random per request.
+    pub jitter_ms: u64,
+    /// Upload cap in kilobits per second; unlimited when unset.
+    pub upload_kbps: Option<u64>,
+    /// Download cap in kilobits per second; unlimited when unset.
+    pub download_kbps: Option<u64>,
+    /// Share of requests that fail, from 0 to 1.
+    pub failure_rate: f64,
+    pub failure: FailureKind,
+    pub failure_status: u16,
+}
+
+impl Default for NetworkProfile {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            latency_ms: 0,
+            jitter_ms: 0,
+            upload_kbps: None,
+            download_kbps: None,
+            failure_rate: 0.0,
+            failure: FailureKind::Reset,
+            failure_status: 503,
+        }
+    }
+}
+
+impl NetworkProfile {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(crate::load_workspace_path(app)?.join(SETTINGS_FILE))
+    }
+
+    /// The current workspace's profile, or a disabled one if it has none.
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data =
+            fs::read_to_string(&path).map_err(|e| format!("network profile read failed: {e}"))?;
+        let profile: Self = serde_json::from_str(&data)
+            .map_err(|e| format!("network profile parse failed: {e}"))?;
+        Ok(profile.clamped())
+    }
+
+    /// The profile `request` is sent with: its own, else the workspace's; `None` unshaped.
+    pub fn for_request(app: &tauri::AppHandle, request: &Value) -> Result<Option<Self>, String> {
+        let profile = match request.get("network_profile").filter(|p| p.is_object()) {
+            Some(own) => serde_json::from_value::<Self>(own.clone())
+                .map_err(|e| format!("request network profile parse failed: {e}"))?
+                .clamped(),
+            None => Self::load(app)?,
+        };
+        Ok(profile.enabled.then_some(profile))
+    }
+
+    fn clamped(mut self) -> Self {
+        self.latency_ms = self.latency_ms.min(MAX_DELAY_MS);
+        self.jitter_ms = self.jitter_ms.min(MAX_DELAY_MS);
+        self.upload_kbps = self.upload_kbps.filter(|k| *k > 0);
+        self.download_kbps = self.download_kbps.filter(|k| *k > 0);
+        self.failure_rate = match self.failure_rate.is_finite() {
+            true => self.failure_rate.clamp(0.0, 1.0),
+            false => 0.0,
+        };
+        if !(100..=599).contains(&self.failure_status) {
+            self.failure_status = 503;
+        }
+        self
+    }
+}
+
+/// Milliseconds `bytes` take at `kbps`.
+fn transfer_ms(bytes: u64, kbps: Option<u64>) -> u64 {
+    kbps.map(|k| (bytes.saturating_mul(8) / k).min(MAX_DELAY_MS))
+        .unwrap_or(0)
+}
+
+fn request_bytes(request: &Value) -> u64 {
+    match request.get("body") {
+        Some(Value::String(text)) => text.len() as u64,
+        None | Some(Value::Null) => 0,
+        Some(other) => other.to_string().len() as u64,
+    }
+}
+
+/// What a shaped send waits out before the backend sees the request.
+pub struct Shaping {
+    profile: NetworkProfile,
+    latency_ms: u64,
+    upload_ms: u64,
+    fails: bool,
+}
+
+impl Shaping {
+    /// Rolls the jitter and failure for one send.
+    pub fn new(profile: NetworkProfile, request: &Value) -> Self {
+        let jitter = match profile.jitter_ms {
+            0 => 0,
+            max => random_below(max + 1),
+        };
+        // Compared in millionths so a rate of 1 always fails and 0 never does.
+        let fails = (random_below(1_000_000) as f64) < profile.failure_rate * 1_000_000.0;
+        Self {
+            latency_ms: profile.latency_ms + jitter,
+            upload_ms: transfer_ms(request_bytes(request), profile.upload_kbps),
+            fails,
+            profile,
+        }
+    }
+
+    /// Waits out the latency and upload time; returns the failed result when this send fails.
+    pub async fn before(&self, request: &Value) -> Option<Value> {
+        tokio::time::sleep(Duration::from_millis(self.latency_ms + self.upload_ms)).await;
+        if !self.fails {
+            return None;
+        }
+        let mut waited = self.latency_ms + self.upload_ms;
+        let mut result = json!({
+            "request_id": request.get("id").cloned().unwrap_or(Value::Null),
+            "status_code": 0,
+            "headers": {},
+            "body": null,
+            "body_bytes": 0,
+            "timestamp": crate::now_ms() as f64 / 1000.0,
+            "sent_request": {
+                "method": request.get("method").cloned().unwrap_or(Value::Null),
+                "url": request.get("url").cloned().unwrap_or(Value::Null),
+                "headers": request.get("headers").cloned().unwrap_or(json!({})),
+            },
+        });
+        match self.profile.failure {
+            FailureKind::Reset => {
+                result["error"] = json!("simulated network failure: connection reset");
+            }
+            FailureKind::Timeout => {
+                let timeout = request
+                    .get("timeout_seconds")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(30)
+                    .saturating_mul(1000)
+                    .min(MAX_DELAY_MS);
+                tokio::time::sleep(Duration::from_millis(timeout)).await;
+                waited += timeout;
+                result["error"] = json!("simulated network failure: request timed out");
+            }
+            FailureKind::Status => {
+                result["status_code"] = json!(self.profile.failure_status);
+                result["body"] = json!("");
+            }
+        }
+        result["duration_ms"] = json!(waited);
+        result["network_simulation"] = self.summary(0, true);
+        Some(result)
+    }
+
+    /// Waits out the download time of the response and records the shaping on it.
+    pub async fn after(&self, result: &mut Value) {
+        let bytes = result
+            .get("body_bytes")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        let download_ms = transfer_ms(bytes, self.profile.download_kbps);
+        tokio::time::sleep(Duration::from_millis(download_ms)).await;
+        let added = (self.latency_ms + self.upload_ms + download_ms) as f64;
+        let duration = result
+            .get("duration_ms")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.0);
+        result["duration_ms"] = json!(duration + added);
+        result["network_simulation"] = self.summary(download_ms, false);
+    }
+
+    fn summary(&self, download_ms: u64, failed: bool) -> Value {
+        json!({
+            "latency_ms": self.latency_ms,
+            "upload_ms": self.upload_ms,
+            "download_ms": download_ms,
+            "failed": failed,
+        })
+    }
+}
+
+/// Reads the current workspace's network profile.
+#[tauri::command]
+pub async fn get_network_profile(app: tauri::AppHandle) -> Result<NetworkProfile, String> {
+    NetworkProfile::load(&app)
+}
+
+/// Saves the current workspace's network profile; out-of-range values are clamped and the
+/// saved profile is returned.
+#[tauri::command]
+pub async fn set_network_profile(
+    app: tauri::AppHandle,
+    profile: NetworkProfile,
+) -> Result<NetworkProfile, String> {
+    let profile = profile.clamped();
+    let payload = serde_json::to_string_pretty(&profile)
+        .map_err(|e| format!("network profile serialize failed: {e}"))?;
+    fs::write(NetworkProfile::path(&app)?, payload)
+        .map_err(|e| format!("network profile persist failed: {e}"))?;
+    Ok(profile)
+}
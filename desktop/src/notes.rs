@@ -0,0 +1,93 @@
+//! Request and folder descriptions: Markdown kept on the item itself (its `description` in
+//! the collection file), the one source the docs panel, generated documentation (see `docs`)
+//! and exports all read. Rendering goes through `litefetch_core::markdown`, so a description
+//! looks the same wherever it appears.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::importers::str_of;
+
+#[derive(Serialize)]
+pub struct ItemDocs {
+    pub collection_id: String,
+    pub item_id: String,
+    pub markdown: String,
+    /// `markdown` rendered and sanitized.
+    pub html: String,
+}
+
+/// Finds a request or folder by id anywhere in a collection's item tree.
+fn find_item_mut<'a>(items: &'a mut [Value], item_id: &str) -> Option<&'a mut Value> {
+    for item in items {
+        if str_of(item, "id") == item_id {
+            return Some(item);
+        }
+        if let Some(found) = item
+            .get_mut("items")
+            .and_then(Value::as_array_mut)
+            .and_then(|children| find_item_mut(children, item_id))
+        {
+            return Some(found);
+        }
+    }
+    None
+}
+
+fn docs_of(collection_id: String, item_id: String, item: &Value) -> ItemDocs {
+    let markdown = str_of(item, "description").to_string();
+    ItemDocs {
+        collection_id,
+        item_id,
+        html: litefetch_core::markdown::to_html(&markdown),
+        markdown,
+    }
+}
+
+/// Renders Markdown to sanitized HTML, for previews of text that isn't saved yet.
+#[tauri::command]
+pub async fn render_markdown(text: String) -> Result<String, String> {
+    Ok(litefetch_core::markdown::to_html(&text))
+}
+
+/// A request's or folder's description, as written and rendered.
+#[tauri::command]
+pub async fn get_item_docs(
+    app: tauri::AppHandle,
+    collection_id: String,
+    item_id: String,
+) -> Result<ItemDocs, String> {
+    let mut collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let items = collection
+        .get_mut("items")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| format!("unknown item: {item_id}"))?;
+    let item = find_item_mut(items, &item_id).ok_or_else(|| format!("unknown item: {item_id}"))?;
+    Ok(docs_of(collection_id, item_id, item))
+}
+
+/// Replaces a request's or folder's description; an empty one removes it.
+#[tauri::command]
+pub async fn set_item_docs(
+    app: tauri::AppHandle,
+    collection_id: String,
+    item_id: String,
+    markdown: String,
+) -> Result<ItemDocs, String> {
+    let path = format!("/collections/{collection_id}/collection");
+    let mut collection = crate::backend_get(&app, &path).await?;
+    let items = collection
+        .get_mut("items")
+        .and_then(Value::as_array_mut)
+        .ok_or_else(|| format!("unknown item: {item_id}"))?;
+    let item = find_item_mut(items, &item_id).ok_or_else(|| format!("unknown item: {item_id}"))?;
+    let markdown = markdown.trim_end();
+    item["description"] = match markdown.trim().is_empty() {
+        true => Value::Null,
+        false => Value::String(markdown.to_string()),
+    };
+    let docs = docs_of(collection_id, item_id, item);
+    crate::backend_post(&app, &path, &collection).await?;
+    Ok(docs)
+}
@@ -0,0 +1,308 @@
+//! Desktop notifications, by category: monitor failures and recoveries, long requests
+//! finishing while the window is in the background, and collection runs finishing. Each
+//! category can be turned off, and a do-not-disturb window in local time (plus an ad-hoc
+//! snooze) holds them all back. Preferences live in `notifications.json` in the app data
+//! root; everything in the shell that notifies goes through `send`.
+
+use chrono::{Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::importers::str_of;
+
+const SETTINGS_FILE: &str = "notifications.json";
+/// How long after a long-request notification returning to the window still counts as
+/// having clicked it.
+const CLICK_WINDOW_MS: u64 = 5 * 60 * 1000;
+
+pub struct NotificationState {
+    focused: AtomicBool,
+    /// The request of the last long-request notification, until the window is focused.
+    pending: Mutex<Option<PendingFocus>>,
+}
+
+impl NotificationState {
+    pub fn new() -> Self {
+        Self {
+            focused: AtomicBool::new(true),
+            pending: Mutex::new(None),
+        }
+    }
+}
+
+struct PendingFocus {
+    collection_id: String,
+    request_id: String,
+    notified_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct FocusEvent {
+    collection_id: String,
+    request_id: String,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    MonitorFailures,
+    LongRequests,
+    RunnerFinished,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct DoNotDisturb {
+    pub enabled: bool,
+    /// Local `HH:MM`; a window ending before it starts runs past midnight.
+    pub start: String,
+    pub end: String,
+}
+
+impl Default for DoNotDisturb {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            start: "22:00".to_string(),
+            end: "08:00".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct NotificationSettings {
+    pub monitor_failures: bool,
+    pub long_requests: bool,
+    /// How long a request has to take before its completion notifies.
+    pub long_request_secs: u64,
+    pub runner_finished: bool,
+    pub do_not_disturb: DoNotDisturb,
+    /// Set by `snooze_notifications`.
+    pub snoozed_until_ms: Option<u64>,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            monitor_failures: true,
+            long_requests: true,
+            long_request_secs: 10,
+            runner_finished: true,
+            do_not_disturb: DoNotDisturb::default(),
+            snoozed_until_ms: None,
+        }
+    }
+}
+
+impl NotificationSettings {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(crate::app_data_root(app)?.join(SETTINGS_FILE))
+    }
+
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data = fs::read_to_string(&path)
+            .map_err(|e| format!("notification settings read failed: {e}"))?;
+        serde_json::from_str(&data).map_err(|e| format!("notification settings parse failed: {e}"))
+    }
+
+    fn save(&self, app: &tauri::AppHandle) -> Result<(), String> {
+        let payload = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("notification settings serialize failed: {e}"))?;
+        fs::write(Self::path(app)?, payload)
+            .map_err(|e| format!("notification settings persist failed: {e}"))
+    }
+
+    fn allows(&self, category: Category) -> bool {
+        match category {
+            Category::MonitorFailures => self.monitor_failures,
+            Category::LongRequests => self.long_requests,
+            Category::RunnerFinished => self.runner_finished,
+        }
+    }
+
+    /// Whether notifications are held back right now.
+    fn quiet(&self, now_ms: u64) -> bool {
+        if self.snoozed_until_ms.is_some_and(|until| now_ms < until) {
+            return true;
+        }
+        let dnd = &self.do_not_disturb;
+        let (Ok(start), Ok(end)) = (parse_time(&dnd.start), parse_time(&dnd.end)) else {
+            return false;
+        };
+        let now = Local::now().time();
+        dnd.enabled
+            && match start <= end {
+                true => start <= now && now < end,
+                false => now >= start || now < end,
+            }
+    }
+}
+
+fn parse_time(text: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(text.trim(), "%H:%M")
+        .map_err(|_| format!("invalid time {text:?}; expected HH:MM"))
+}
+
+#[derive(Deserialize)]
+pub struct NotifyPayload {
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+}
+
+/// Shows a notification unless its category is off or do-not-disturb is on; returns
+/// whether it was shown.
+pub fn send(app: &tauri::AppHandle, category: Category, title: &str, body: &str) -> bool {
+    let settings = NotificationSettings::load(app).unwrap_or_else(|e| {
+        tracing::warn!("{e}");
+        NotificationSettings::default()
+    });
+    if !settings.allows(category) || settings.quiet(crate::now_ms()) {
+        return false;
+    }
+    match app.notification().builder().title(title).body(body).show() {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!("notification failed: {e}");
+            false
+        }
+    }
+}
+
+/// Notifies that a send finished, when it took longer than the threshold while the window
+/// was in the background or minimized. Desktop notifications can't report a click, so the
+/// request is remembered instead: if the window comes back to the front soon after, which
+/// is what clicking the notification does, the UI is asked to open it (`request://focus`).
+pub fn request_finished(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    request: &Value,
+    result: &Value,
+) {
+    let duration_ms = result
+        .get("duration_ms")
+        .and_then(Value::as_f64)
+        .unwrap_or(0.0);
+    let threshold_ms = NotificationSettings::load(app)
+        .map(|s| s.long_request_secs)
+        .unwrap_or(10)
+        * 1000;
+    if duration_ms < threshold_ms as f64 {
+        return;
+    }
+    let state = app.state::<NotificationState>();
+    let minimized = app
+        .get_webview_window("main")
+        .and_then(|w| w.is_minimized().ok())
+        .unwrap_or(false);
+    if state.focused.load(Ordering::Relaxed) && !minimized {
+        return;
+    }
+    let name = match str_of(request, "name") {
+        "" => str_of(request, "url"),
+        name => name,
+    };
+    let outcome = match result.get("status_code").and_then(Value::as_u64) {
+        None | Some(0) => "Failed".to_string(),
+        Some(code) => format!("HTTP {code}"),
+    };
+    let body = format!("{outcome} after {:.1} s", duration_ms / 1000.0);
+    if !send(
+        app,
+        Category::LongRequests,
+        &format!("{name} finished"),
+        &body,
+    ) {
+        return;
+    }
+    if let Ok(mut pending) = state.pending.lock() {
+        *pending = Some(PendingFocus {
+            collection_id: collection_id.to_string(),
+            request_id: str_of(request, "id").to_string(),
+            notified_ms: crate::now_ms(),
+        });
+    };
+}
+
+/// Tracks the main window's focus; coming back to the front shortly after a long-request
+/// notification opens that request.
+pub fn window_focused(app: &tauri::AppHandle, focused: bool) {
+    let state = app.state::<NotificationState>();
+    state.focused.store(focused, Ordering::Relaxed);
+    if !focused {
+        return;
+    }
+    let Some(pending) = state.pending.lock().ok().and_then(|mut p| p.take()) else {
+        return;
+    };
+    if crate::now_ms().saturating_sub(pending.notified_ms) > CLICK_WINDOW_MS {
+        return;
+    }
+    let _ = app.emit(
+        "request://focus",
+        FocusEvent {
+            collection_id: pending.collection_id,
+            request_id: pending.request_id,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+#[tauri::command]
+pub async fn get_notification_settings(
+    app: tauri::AppHandle,
+) -> Result<NotificationSettings, String> {
+    NotificationSettings::load(&app)
+}
+
+#[tauri::command]
+pub async fn set_notification_settings(
+    app: tauri::AppHandle,
+    settings: NotificationSettings,
+) -> Result<NotificationSettings, String> {
+    parse_time(&settings.do_not_disturb.start)?;
+    parse_time(&settings.do_not_disturb.end)?;
+    let settings = NotificationSettings {
+        long_request_secs: settings.long_request_secs.clamp(1, 24 * 60 * 60),
+        ..settings
+    };
+    settings.save(&app)?;
+    Ok(settings)
+}
+
+/// Holds notifications back for `minutes`; `None` or 0 ends a snooze.
+#[tauri::command]
+pub async fn snooze_notifications(
+    app: tauri::AppHandle,
+    minutes: Option<u64>,
+) -> Result<NotificationSettings, String> {
+    let mut settings = NotificationSettings::load(&app)?;
+    settings.snoozed_until_ms = minutes
+        .filter(|m| *m > 0)
+        .map(|m| crate::now_ms() + m.min(7 * 24 * 60) * 60 * 1000);
+    settings.save(&app)?;
+    Ok(settings)
+}
+
+/// Shows a notification in `category` on behalf of the UI, subject to the same preferences;
+/// returns whether it was shown.
+#[tauri::command]
+pub async fn notify(
+    app: tauri::AppHandle,
+    category: Category,
+    payload: NotifyPayload,
+) -> Result<bool, String> {
+    Ok(send(&app, category, &payload.title, &payload.body))
+}
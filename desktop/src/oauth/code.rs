@@ -0,0 +1,326 @@
+//! The authorization-code grant for desktop clients: the provider's consent page opens in the
+//! system browser, a one-shot listener on 127.0.0.1 catches the redirect, and the code is
+//! exchanged for a token. Each step is announced as `oauth2://progress`.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::time::Duration;
+use tauri::async_runtime::Mutex;
+use tauri::{Emitter, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+use super::pkce::Pkce;
+use super::tokens::TokenManager;
+use super::{ClientAuth, OAuth2Token};
+
+/// How long the user has to finish in the browser.
+const AUTHORIZE_TIMEOUT: Duration = Duration::from_secs(300);
+/// A connection that sends no request within this is dropped, so it can't stall the listener.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+const DONE_PAGE: &str = "<!doctype html><meta charset=\"utf-8\"><title>LiteFetch</title>\
+<p>Authorization complete. You can close this window and return to LiteFetch.</p>";
+const FAILED_PAGE: &str = "<!doctype html><meta charset=\"utf-8\"><title>LiteFetch</title>\
+<p>Authorization failed. Return to LiteFetch for details.</p>";
+
+pub struct OAuthState {
+    /// Cancels the authorization waiting in the browser; a new one replaces it.
+    cancel: Mutex<Option<oneshot::Sender<()>>>,
+}
+
+impl OAuthState {
+    pub fn new() -> Self {
+        Self {
+            cancel: Mutex::new(None),
+        }
+    }
+
+    /// Registers a new interactive flow, cancelling the one before it.
+    pub(super) async fn begin(&self) -> oneshot::Receiver<()> {
+        let (cancel, cancelled) = oneshot::channel();
+        // Dropping the previous sender ends that flow as cancelled.
+        *self.cancel.lock().await = Some(cancel);
+        cancelled
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AuthCodeConfig {
+    pub authorization_url: String,
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub client_auth: ClientAuth,
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// The loopback port registered with the provider; 0 picks a free one.
+    #[serde(default)]
+    pub redirect_port: u16,
+    #[serde(default = "default_redirect_path")]
+    pub redirect_path: String,
+    /// Sends an S256 PKCE challenge; on by default, as public clients need it.
+    #[serde(default = "default_true")]
+    pub pkce: bool,
+    /// Extra authorization parameters, e.g. `audience` or `prompt`.
+    #[serde(default)]
+    pub extra_params: BTreeMap<String, String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_redirect_path() -> String {
+    "/callback".to_string()
+}
+
+#[derive(Clone, Serialize, Default)]
+pub(super) struct ProgressEvent {
+    pub flow_id: String,
+    /// `waiting` (for the browser, or for the user to enter a device code), `exchanging`,
+    /// `completed` or `failed`.
+    pub stage: &'static str,
+    /// The consent page, to show when the browser did not open.
+    pub authorization_url: Option<String>,
+    /// The code to enter at `authorization_url`, for the device flow.
+    pub user_code: Option<String>,
+    pub error: Option<String>,
+    pub timestamp_ms: u64,
+}
+
+pub(super) fn emit_progress(app: &tauri::AppHandle, event: ProgressEvent) {
+    let _ = app.emit(
+        "oauth2://progress",
+        ProgressEvent {
+            timestamp_ms: crate::now_ms(),
+            ..event
+        },
+    );
+}
+
+fn emit(
+    app: &tauri::AppHandle,
+    flow_id: &str,
+    stage: &'static str,
+    authorization_url: Option<String>,
+    error: Option<String>,
+) {
+    emit_progress(
+        app,
+        ProgressEvent {
+            flow_id: flow_id.to_string(),
+            stage,
+            authorization_url,
+            error,
+            ..Default::default()
+        },
+    );
+}
+
+pub(super) fn open_browser(url: &str) -> Result<(), String> {
+    let (program, args): (&str, Vec<&str>) = if cfg!(target_os = "macos") {
+        ("open", vec![url])
+    } else if cfg!(target_os = "windows") {
+        // `start` would split the URL at `&`.
+        ("rundll32", vec!["url.dll,FileProtocolHandler", url])
+    } else {
+        ("xdg-open", vec![url])
+    };
+    std::process::Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("failed to open the browser: {e}"))
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, page: &str) {
+    let response = format!(
+        "HTTP/1.1 {status}\r\ncontent-type: text/html; charset=utf-8\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{page}",
+        page.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// The request target (`/callback?code=…`) of an HTTP request read from `stream`.
+async fn read_target(stream: &mut TcpStream) -> Option<String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 2048];
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") && buffer.len() < MAX_REQUEST_BYTES {
+        let read = stream.read(&mut chunk).await.ok()?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+    let head = String::from_utf8_lossy(&buffer);
+    let mut parts = head.lines().next()?.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(target)) => Some(target.to_string()),
+        _ => None,
+    }
+}
+
+/// Serves the redirect: returns the code once the provider sends the browser back with it.
+async fn wait_for_code(
+    listener: TcpListener,
+    redirect_path: &str,
+    expected_state: &str,
+) -> Result<String, String> {
+    loop {
+        let (mut stream, _) = listener
+            .accept()
+            .await
+            .map_err(|e| format!("redirect listener failed: {e}"))?;
+        let target = tokio::time::timeout(READ_TIMEOUT, read_target(&mut stream)).await;
+        let Ok(Some(target)) = target else {
+            respond(&mut stream, "400 Bad Request", FAILED_PAGE).await;
+            continue;
+        };
+        let Ok(url) = reqwest::Url::parse(&format!("http://127.0.0.1{target}")) else {
+            respond(&mut stream, "400 Bad Request", FAILED_PAGE).await;
+            continue;
+        };
+        // Browsers also ask for /favicon.ico and the like.
+        if url.path() != redirect_path {
+            respond(&mut stream, "404 Not Found", "").await;
+            continue;
+        }
+        let params: BTreeMap<String, String> = url.query_pairs().into_owned().collect();
+        if params.get("state").map(String::as_str) != Some(expected_state) {
+            respond(&mut stream, "400 Bad Request", FAILED_PAGE).await;
+            return Err("authorization response did not match the request (state mismatch)".into());
+        }
+        if let Some(error) = params.get("error") {
+            respond(&mut stream, "200 OK", FAILED_PAGE).await;
+            return Err(match params.get("error_description") {
+                Some(description) => format!("authorization denied: {error}: {description}"),
+                None => format!("authorization denied: {error}"),
+            });
+        }
+        match params.get("code").filter(|c| !c.is_empty()) {
+            Some(code) => {
+                respond(&mut stream, "200 OK", DONE_PAGE).await;
+                return Ok(code.clone());
+            }
+            None => {
+                respond(&mut stream, "400 Bad Request", FAILED_PAGE).await;
+                return Err("authorization response had no code".into());
+            }
+        }
+    }
+}
+
+/// Runs the authorization-code flow and stores the token in the keychain.
+#[tauri::command]
+pub async fn oauth2_authorize(
+    app: tauri::AppHandle,
+    state: State<'_, OAuthState>,
+    config: AuthCodeConfig,
+) -> Result<OAuth2Token, String> {
+    let mut authorize = reqwest::Url::parse(config.authorization_url.trim())
+        .map_err(|e| format!("invalid authorization URL: {e}"))?;
+    reqwest::Url::parse(config.token_url.trim()).map_err(|e| format!("invalid token URL: {e}"))?;
+    if config.client_id.trim().is_empty() {
+        return Err("client id is required".into());
+    }
+    let redirect_path = match config.redirect_path.trim() {
+        "" => default_redirect_path(),
+        path if path.starts_with('/') => path.to_string(),
+        path => format!("/{path}"),
+    };
+    let listener = TcpListener::bind(("127.0.0.1", config.redirect_port))
+        .await
+        .map_err(|e| format!("redirect listener failed: {e}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("redirect listener failed: {e}"))?
+        .port();
+    let redirect_uri = format!("http://127.0.0.1:{port}{redirect_path}");
+    let csrf = uuid::Uuid::new_v4().simple().to_string();
+    let pkce = config.pkce.then(Pkce::generate).transpose()?;
+    {
+        let mut query = authorize.query_pairs_mut();
+        query
+            .append_pair("response_type", "code")
+            .append_pair("client_id", config.client_id.trim())
+            .append_pair("redirect_uri", &redirect_uri)
+            .append_pair("state", &csrf);
+        if let Some(scope) = config.scope.as_deref().filter(|s| !s.trim().is_empty()) {
+            query.append_pair("scope", scope.trim());
+        }
+        if let Some(pkce) = &pkce {
+            query
+                .append_pair("code_challenge", &pkce.challenge)
+                .append_pair("code_challenge_method", Pkce::METHOD);
+        }
+        for (key, value) in &config.extra_params {
+            query.append_pair(key, value);
+        }
+    }
+
+    let cancelled = state.begin().await;
+    let flow_id = uuid::Uuid::new_v4().to_string();
+    emit(&app, &flow_id, "waiting", Some(authorize.to_string()), None);
+    if let Err(e) = open_browser(authorize.as_str()) {
+        tracing::warn!("{e}");
+    }
+
+    let result: Result<OAuth2Token, String> = async {
+        let code = tokio::select! {
+            code = wait_for_code(listener, &redirect_path, &csrf) => code?,
+            _ = cancelled => return Err("authorization cancelled".to_string()),
+            _ = tokio::time::sleep(AUTHORIZE_TIMEOUT) => {
+                return Err("authorization timed out".to_string())
+            }
+        };
+        emit(&app, &flow_id, "exchanging", None, None);
+        let mut grant = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+        ];
+        if let Some(pkce) = &pkce {
+            grant.push(("code_verifier", pkce.verifier.as_str()));
+        }
+        let token = super::request_token(
+            &config.token_url,
+            config.client_id.trim(),
+            config.client_secret.as_deref(),
+            config.client_auth,
+            &grant,
+        )
+        .await?;
+        let key = super::cache_key(
+            &config.token_url,
+            &config.client_id,
+            config.scope.as_deref(),
+        );
+        super::store_token(&key, &token)?;
+        app.state::<TokenManager>().forget(&key).await;
+        Ok(token)
+    }
+    .await;
+    match &result {
+        Ok(_) => emit(&app, &flow_id, "completed", None, None),
+        Err(e) => emit(&app, &flow_id, "failed", None, Some(e.clone())),
+    }
+    result
+}
+
+/// Abandons the authorization (browser or device code) waiting on the user, if any.
+#[tauri::command]
+pub async fn cancel_oauth2_authorize(state: State<'_, OAuthState>) -> Result<(), String> {
+    if let Some(cancel) = state.cancel.lock().await.take() {
+        let _ = cancel.send(());
+    }
+    Ok(())
+}
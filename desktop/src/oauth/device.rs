@@ -0,0 +1,207 @@
+//! The device authorization grant (RFC 8628), for identity providers aimed at CLIs and devices
+//! without a browser of their own: the provider hands out a user code, the user enters it at
+//! the verification page, and the token endpoint is polled until they have. Progress is
+//! announced as `oauth2://progress`, the `waiting` event carrying the code and the page.
+
+use serde::Deserialize;
+use std::time::Duration;
+use tauri::{Manager, State};
+
+use super::code::{emit_progress, open_browser, OAuthState, ProgressEvent};
+use super::tokens::TokenManager;
+use super::{ClientAuth, GrantError, OAuth2Token};
+
+const DEVICE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+/// The poll interval when the provider names none (RFC 8628 §3.2).
+const DEFAULT_INTERVAL_SECS: u64 = 5;
+/// Added to the interval each time the provider answers `slow_down`.
+const SLOW_DOWN_SECS: u64 = 5;
+/// Codes that claim to last longer are given up on after this.
+const MAX_WAIT_SECS: u64 = 30 * 60;
+
+#[derive(Deserialize)]
+pub struct DeviceCodeConfig {
+    pub device_authorization_url: String,
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub client_auth: ClientAuth,
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Opens the verification page in the system browser as soon as the code is issued.
+    #[serde(default)]
+    pub open_browser: bool,
+}
+
+#[derive(Deserialize)]
+struct DeviceAuthorization {
+    device_code: String,
+    user_code: String,
+    // Some providers (Google) predate the RFC's name for it.
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: Option<u64>,
+    interval: Option<u64>,
+}
+
+async fn start(config: &DeviceCodeConfig) -> Result<DeviceAuthorization, String> {
+    let client_id = config.client_id.trim();
+    let secret = config.client_secret.as_deref().filter(|s| !s.is_empty());
+    let mut form = Vec::new();
+    if let Some(scope) = config.scope.as_deref().filter(|s| !s.trim().is_empty()) {
+        form.push(("scope", scope.trim()));
+    }
+    let mut request = reqwest::Client::new().post(config.device_authorization_url.trim());
+    match config.client_auth {
+        ClientAuth::Basic => request = request.basic_auth(client_id, secret),
+        ClientAuth::Body => {
+            form.push(("client_id", client_id));
+            if let Some(secret) = secret {
+                form.push(("client_secret", secret));
+            }
+        }
+    }
+    let response = request
+        .header("accept", "application/json")
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| format!("device authorization failed: {e}"))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| format!("device authorization failed: {e}"))?;
+    if !status.is_success() {
+        return Err(match serde_json::from_str::<super::TokenError>(&body) {
+            Ok(err) => match err.error_description {
+                Some(description) => {
+                    format!("device authorization failed: {}: {description}", err.error)
+                }
+                None => format!("device authorization failed: {}", err.error),
+            },
+            Err(_) => format!("device authorization failed: HTTP {status}"),
+        });
+    }
+    serde_json::from_str(&body).map_err(|e| format!("device authorization response invalid: {e}"))
+}
+
+/// Polls the token endpoint until the user approves or denies the code, or it expires.
+async fn poll(
+    config: &DeviceCodeConfig,
+    authorization: &DeviceAuthorization,
+) -> Result<OAuth2Token, String> {
+    let mut interval = authorization
+        .interval
+        .unwrap_or(DEFAULT_INTERVAL_SECS)
+        .max(1);
+    let lifetime = authorization
+        .expires_in
+        .unwrap_or(MAX_WAIT_SECS)
+        .min(MAX_WAIT_SECS);
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(lifetime);
+    let grant = [
+        ("grant_type", DEVICE_GRANT),
+        ("device_code", authorization.device_code.as_str()),
+    ];
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if tokio::time::Instant::now() >= deadline {
+            return Err("device code expired before it was approved".into());
+        }
+        match super::try_grant(
+            &config.token_url,
+            config.client_id.trim(),
+            config.client_secret.as_deref(),
+            config.client_auth,
+            &grant,
+        )
+        .await
+        {
+            Ok(token) => return Ok(token),
+            Err(GrantError::Rejected(err)) => match err.error.as_str() {
+                "authorization_pending" => {}
+                "slow_down" => interval += SLOW_DOWN_SECS,
+                "access_denied" => return Err("authorization denied".into()),
+                "expired_token" => return Err("device code expired before it was approved".into()),
+                _ => return Err(GrantError::Rejected(err).to_string()),
+            },
+            // A dropped connection is worth another try while the code is still valid.
+            Err(GrantError::Failed(e)) => {
+                tracing::warn!("{e}");
+                interval += SLOW_DOWN_SECS;
+            }
+        }
+    }
+}
+
+/// Runs the device authorization flow and stores the token in the keychain.
+#[tauri::command]
+pub async fn oauth2_device_authorize(
+    app: tauri::AppHandle,
+    state: State<'_, OAuthState>,
+    config: DeviceCodeConfig,
+) -> Result<OAuth2Token, String> {
+    reqwest::Url::parse(config.device_authorization_url.trim())
+        .map_err(|e| format!("invalid device authorization URL: {e}"))?;
+    reqwest::Url::parse(config.token_url.trim()).map_err(|e| format!("invalid token URL: {e}"))?;
+    if config.client_id.trim().is_empty() {
+        return Err("client id is required".into());
+    }
+    let authorization = start(&config).await?;
+    let page = authorization
+        .verification_uri_complete
+        .clone()
+        .unwrap_or_else(|| authorization.verification_uri.clone());
+
+    let cancelled = state.begin().await;
+    let flow_id = uuid::Uuid::new_v4().to_string();
+    emit_progress(
+        &app,
+        ProgressEvent {
+            flow_id: flow_id.clone(),
+            stage: "waiting",
+            authorization_url: Some(authorization.verification_uri.clone()),
+            user_code: Some(authorization.user_code.clone()),
+            ..Default::default()
+        },
+    );
+    if config.open_browser {
+        if let Err(e) = open_browser(&page) {
+            tracing::warn!("{e}");
+        }
+    }
+
+    let result: Result<OAuth2Token, String> = async {
+        let token = tokio::select! {
+            token = poll(&config, &authorization) => token?,
+            _ = cancelled => return Err("authorization cancelled".to_string()),
+        };
+        let key = super::cache_key(
+            &config.token_url,
+            &config.client_id,
+            config.scope.as_deref(),
+        );
+        super::store_token(&key, &token)?;
+        app.state::<TokenManager>().forget(&key).await;
+        Ok(token)
+    }
+    .await;
+    let (stage, error) = match &result {
+        Ok(_) => ("completed", None),
+        Err(e) => ("failed", Some(e.clone())),
+    };
+    emit_progress(
+        &app,
+        ProgressEvent {
+            flow_id,
+            stage,
+            error,
+            ..Default::default()
+        },
+    );
+    result
+}
@@ -0,0 +1,213 @@
+//! OAuth 2.0 tokens for requests. Grants run in the shell and their tokens are kept in the OS
+//! keychain, keyed by token endpoint, client and scope, so they survive restarts without ever
+//! being written to the workspace.
+
+pub mod code;
+pub mod device;
+pub mod pkce;
+pub mod tokens;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// How the client authenticates to the token endpoint.
+#[derive(Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ClientAuth {
+    /// `client_id`/`client_secret` form fields.
+    #[default]
+    Body,
+    /// HTTP Basic with the client id and secret.
+    Basic,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct OAuth2Token {
+    pub access_token: String,
+    pub token_type: String,
+    pub refresh_token: Option<String>,
+    pub scope: Option<String>,
+    /// `None` when the server did not say when the token expires.
+    pub expires_at_ms: Option<u64>,
+    pub obtained_ms: u64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    token_type: Option<String>,
+    expires_in: Option<u64>,
+    refresh_token: Option<String>,
+    scope: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TokenError {
+    pub error: String,
+    pub error_description: Option<String>,
+}
+
+/// Why a token request produced no token.
+pub enum GrantError {
+    /// The endpoint answered with an OAuth error (`invalid_grant`, `authorization_pending`, …).
+    Rejected(TokenError),
+    Failed(String),
+}
+
+impl std::fmt::Display for GrantError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GrantError::Rejected(TokenError {
+                error,
+                error_description: Some(description),
+            }) => write!(f, "token request failed: {error}: {description}"),
+            GrantError::Rejected(err) => write!(f, "token request failed: {}", err.error),
+            GrantError::Failed(e) => f.write_str(e),
+        }
+    }
+}
+
+/// The keychain key of the token for one token endpoint, client and scope.
+pub fn cache_key(token_url: &str, client_id: &str, scope: Option<&str>) -> String {
+    let mut hasher = Sha256::new();
+    for part in [
+        token_url.trim(),
+        client_id.trim(),
+        scope.unwrap_or("").trim(),
+    ] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hex::encode(&hasher.finalize()[..16])
+}
+
+fn entry(key: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(
+        &litefetch_core::profile::keychain_service(),
+        &format!("oauth2/{key}"),
+    )
+    .map_err(|e| format!("keychain unavailable: {e}"))
+}
+
+fn remember(token: &OAuth2Token) {
+    crate::redact::remember(&token.access_token);
+    if let Some(refresh) = &token.refresh_token {
+        crate::redact::remember(refresh);
+    }
+}
+
+pub fn load_token(key: &str) -> Result<Option<OAuth2Token>, String> {
+    match entry(key)?.get_password() {
+        Ok(raw) => {
+            let token: OAuth2Token = serde_json::from_str(&raw)
+                .map_err(|e| format!("stored OAuth token unreadable: {e}"))?;
+            remember(&token);
+            Ok(Some(token))
+        }
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("keychain read failed: {e}")),
+    }
+}
+
+pub fn store_token(key: &str, token: &OAuth2Token) -> Result<(), String> {
+    remember(token);
+    let payload =
+        serde_json::to_string(token).map_err(|e| format!("OAuth token serialize failed: {e}"))?;
+    entry(key)?
+        .set_password(&payload)
+        .map_err(|e| format!("keychain write failed: {e}"))
+}
+
+pub fn remove_token(key: &str) -> Result<(), String> {
+    match entry(key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("keychain delete failed: {e}")),
+    }
+}
+
+/// Posts a grant to the token endpoint and reads the token out of the response.
+pub async fn request_token(
+    token_url: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+    client_auth: ClientAuth,
+    grant: &[(&str, &str)],
+) -> Result<OAuth2Token, String> {
+    try_grant(token_url, client_id, client_secret, client_auth, grant)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// [`request_token`], keeping the OAuth error apart for grants that expect some.
+pub async fn try_grant(
+    token_url: &str,
+    client_id: &str,
+    client_secret: Option<&str>,
+    client_auth: ClientAuth,
+    grant: &[(&str, &str)],
+) -> Result<OAuth2Token, GrantError> {
+    let mut form: Vec<(&str, &str)> = grant.to_vec();
+    let secret = client_secret.filter(|s| !s.is_empty());
+    let mut request = reqwest::Client::new().post(token_url.trim());
+    match client_auth {
+        ClientAuth::Basic => request = request.basic_auth(client_id, secret),
+        ClientAuth::Body => {
+            form.push(("client_id", client_id));
+            if let Some(secret) = secret {
+                form.push(("client_secret", secret));
+            }
+        }
+    }
+    let response = request
+        .header("accept", "application/json")
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| GrantError::Failed(format!("token request failed: {e}")))?;
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| GrantError::Failed(format!("token request failed: {e}")))?;
+    if !status.is_success() {
+        return Err(match serde_json::from_str::<TokenError>(&body) {
+            Ok(err) => GrantError::Rejected(err),
+            Err(_) => GrantError::Failed(format!("token request failed: HTTP {status}")),
+        });
+    }
+    let parsed: TokenResponse = serde_json::from_str(&body)
+        .map_err(|e| GrantError::Failed(format!("token response invalid: {e}")))?;
+    let obtained_ms = crate::now_ms();
+    Ok(OAuth2Token {
+        access_token: parsed.access_token,
+        token_type: parsed.token_type.unwrap_or_else(|| "Bearer".to_string()),
+        refresh_token: parsed.refresh_token,
+        scope: parsed.scope,
+        expires_at_ms: parsed
+            .expires_in
+            .map(|secs| obtained_ms + secs.saturating_mul(1000)),
+        obtained_ms,
+    })
+}
+
+/// The stored token for a token endpoint, client and scope, if any.
+#[tauri::command]
+pub async fn get_oauth2_token(
+    token_url: String,
+    client_id: String,
+    scope: Option<String>,
+) -> Result<Option<OAuth2Token>, String> {
+    load_token(&cache_key(&token_url, &client_id, scope.as_deref()))
+}
+
+#[tauri::command]
+pub async fn clear_oauth2_token(
+    manager: tauri::State<'_, tokens::TokenManager>,
+    token_url: String,
+    client_id: String,
+    scope: Option<String>,
+) -> Result<(), String> {
+    let key = cache_key(&token_url, &client_id, scope.as_deref());
+    manager.forget(&key).await;
+    remove_token(&key)
+}
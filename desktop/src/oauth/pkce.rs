@@ -0,0 +1,29 @@
+//! Proof Key for Code Exchange (RFC 7636): the authorization request carries the S256
+//! challenge of a one-time verifier, and only the client holding the verifier can redeem the
+//! code, so an intercepted redirect is useless.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+pub struct Pkce {
+    /// Sent with the token request; never leaves this process before that.
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub const METHOD: &'static str = "S256";
+
+    /// A fresh verifier from 32 random bytes (43 characters, the RFC's recommendation).
+    pub fn generate() -> Result<Self, String> {
+        let mut bytes = [0u8; 32];
+        getrandom::getrandom(&mut bytes).map_err(|e| format!("PKCE verifier failed: {e}"))?;
+        let verifier = URL_SAFE_NO_PAD.encode(bytes);
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Ok(Self {
+            verifier,
+            challenge,
+        })
+    }
+}
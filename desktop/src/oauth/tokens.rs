@@ -0,0 +1,244 @@
+//! The token manager behind requests with `auth_type: "oauth2"`. Their `auth_params` name a
+//! credential (token URL, client, scope) and its grant; the manager hands out the cached token
+//! for it, fetching a new one when it is missing or within a minute of expiring, and the
+//! request goes out with it as a Bearer header. Client-credentials tokens are fetched on
+//! demand; authorization-code tokens come from `oauth2_authorize` and are kept alive with
+//! their refresh token.
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tauri::async_runtime::Mutex;
+use tauri::{Manager, State};
+
+use super::{ClientAuth, OAuth2Token};
+
+/// Tokens this close to expiring are replaced before use.
+const REFRESH_MARGIN_MS: u64 = 60_000;
+
+pub struct TokenManager {
+    tokens: Mutex<HashMap<String, OAuth2Token>>,
+    /// One fetch per credential at a time, so concurrent sends share a single new token.
+    fetching: std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl TokenManager {
+    pub fn new() -> Self {
+        Self {
+            tokens: Mutex::new(HashMap::new()),
+            fetching: std::sync::Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn fetch_lock(&self, key: &str) -> Arc<Mutex<()>> {
+        let mut locks = self
+            .fetching
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        locks.entry(key.to_string()).or_default().clone()
+    }
+
+    /// Drops the cached copy of a token replaced or removed in the keychain.
+    pub async fn forget(&self, key: &str) {
+        self.tokens.lock().await.remove(key);
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Grant {
+    #[default]
+    ClientCredentials,
+    /// Obtained interactively with `oauth2_authorize`.
+    AuthorizationCode,
+    /// Obtained interactively with `oauth2_device_authorize`.
+    DeviceCode,
+}
+
+#[derive(Deserialize)]
+pub struct Credential {
+    #[serde(default)]
+    pub grant: Grant,
+    pub token_url: String,
+    pub client_id: String,
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    #[serde(default)]
+    pub client_auth: ClientAuth,
+    #[serde(default)]
+    pub scope: Option<String>,
+    /// Sent with client-credentials requests by providers that want it (Auth0 and the like).
+    #[serde(default)]
+    pub audience: Option<String>,
+}
+
+impl Credential {
+    fn key(&self) -> String {
+        super::cache_key(&self.token_url, &self.client_id, self.scope())
+    }
+
+    fn scope(&self) -> Option<&str> {
+        self.scope
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+}
+
+fn is_fresh(token: &OAuth2Token) -> bool {
+    token
+        .expires_at_ms
+        .is_none_or(|expires| expires.saturating_sub(crate::now_ms()) > REFRESH_MARGIN_MS)
+}
+
+async fn refresh(credential: &Credential, token: &OAuth2Token) -> Option<OAuth2Token> {
+    let refresh_token = token.refresh_token.as_deref()?;
+    let mut grant = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token),
+    ];
+    if let Some(scope) = credential.scope() {
+        grant.push(("scope", scope));
+    }
+    match super::request_token(
+        &credential.token_url,
+        credential.client_id.trim(),
+        credential.client_secret.as_deref(),
+        credential.client_auth,
+        &grant,
+    )
+    .await
+    {
+        Ok(mut fresh) => {
+            // Servers that don't rotate refresh tokens leave them out of the response.
+            if fresh.refresh_token.is_none() {
+                fresh.refresh_token = token.refresh_token.clone();
+            }
+            Some(fresh)
+        }
+        Err(e) => {
+            tracing::warn!("{e}");
+            None
+        }
+    }
+}
+
+async fn client_credentials(credential: &Credential) -> Result<OAuth2Token, String> {
+    let mut grant = vec![("grant_type", "client_credentials")];
+    if let Some(scope) = credential.scope() {
+        grant.push(("scope", scope));
+    }
+    if let Some(audience) = credential.audience.as_deref().filter(|a| !a.is_empty()) {
+        grant.push(("audience", audience));
+    }
+    super::request_token(
+        &credential.token_url,
+        credential.client_id.trim(),
+        credential.client_secret.as_deref(),
+        credential.client_auth,
+        &grant,
+    )
+    .await
+}
+
+/// A usable token for `credential`: the cached one while it is fresh, otherwise a refreshed or
+/// newly granted one, which replaces it in the cache and the keychain.
+pub async fn token_for(
+    manager: &TokenManager,
+    credential: &Credential,
+    force: bool,
+) -> Result<OAuth2Token, String> {
+    obtain(manager, credential, |token| !force && is_fresh(token)).await
+}
+
+async fn obtain(
+    manager: &TokenManager,
+    credential: &Credential,
+    usable: impl Fn(&OAuth2Token) -> bool,
+) -> Result<OAuth2Token, String> {
+    if credential.token_url.trim().is_empty() || credential.client_id.trim().is_empty() {
+        return Err("OAuth credential needs a token URL and a client id".into());
+    }
+    let key = credential.key();
+    let lock = manager.fetch_lock(&key);
+    let _fetching = lock.lock().await;
+    let cached = match manager.tokens.lock().await.get(&key).cloned() {
+        Some(token) => Some(token),
+        None => super::load_token(&key)?,
+    };
+    if let Some(token) = cached.as_ref().filter(|t| usable(t)) {
+        manager.tokens.lock().await.insert(key, token.clone());
+        return Ok(token.clone());
+    }
+    let refreshed = match &cached {
+        Some(token) => refresh(credential, token).await,
+        None => None,
+    };
+    let token = match (refreshed, credential.grant) {
+        (Some(token), _) => token,
+        (None, Grant::ClientCredentials) => client_credentials(credential).await?,
+        (None, Grant::AuthorizationCode | Grant::DeviceCode) => {
+            return Err(
+                "OAuth authorization expired or missing; authorize the credential again".into(),
+            )
+        }
+    };
+    super::store_token(&key, &token)?;
+    manager.tokens.lock().await.insert(key, token.clone());
+    Ok(token)
+}
+
+/// The access token an `auth_type: "oauth2"` request was sent with.
+pub fn sent_token(request: &Value) -> Option<&str> {
+    request
+        .get("headers")?
+        .as_object()?
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))?
+        .1
+        .as_str()?
+        .strip_prefix("Bearer ")
+}
+
+/// Sets the `Authorization` header of an `auth_type: "oauth2"` request from its credential
+/// and returns the header name, so it can be marked secret. A `rejected` token, one the server
+/// answered 401 to, is replaced even if it looks fresh, unless another send already has.
+pub async fn authorize(
+    app: &tauri::AppHandle,
+    request: &mut Value,
+    rejected: Option<&str>,
+) -> Result<String, String> {
+    let params = request
+        .get("auth_params")
+        .cloned()
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    let credential: Credential =
+        serde_json::from_value(params).map_err(|e| format!("invalid OAuth credential: {e}"))?;
+    let token = obtain(&app.state::<TokenManager>(), &credential, |token| {
+        is_fresh(token) && Some(token.access_token.as_str()) != rejected
+    })
+    .await?;
+    if !request.get("headers").is_some_and(Value::is_object) {
+        request["headers"] = Value::Object(Map::new());
+    }
+    if let Some(headers) = request["headers"].as_object_mut() {
+        headers.retain(|name, _| !name.eq_ignore_ascii_case("authorization"));
+        headers.insert(
+            "Authorization".to_string(),
+            Value::String(format!("Bearer {}", token.access_token)),
+        );
+    }
+    Ok("Authorization".to_string())
+}
+
+/// The token requests with this credential are sent with, fetching one if needed; `force`
+/// replaces a cached token that is still fresh.
+#[tauri::command]
+pub async fn fetch_oauth2_token(
+    manager: State<'_, TokenManager>,
+    credential: Credential,
+    force: Option<bool>,
+) -> Result<OAuth2Token, String> {
+    token_for(&manager, &credential, force.unwrap_or(false)).await
+}
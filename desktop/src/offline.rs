@@ -0,0 +1,309 @@
+//! Offline mode: the shell checks now and then whether the network is reachable and announces
+//! changes as `network://connectivity`. While offline (or with offline mode switched on by
+//! hand) the UI can queue a send instead of letting it fail; queued sends are kept per
+//! workspace under `.litefetch/queue` and go out through `send` once the connection returns,
+//! or when `replay_queued_requests` is called. Each replayed send is announced as
+//! `queue://replayed`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager, State};
+use tokio::net::TcpStream;
+
+const QUEUE_FILE: &str = "queue.json";
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Well-known anycast resolvers; reaching any one of them counts as online.
+const PROBES: &[&str] = &["1.1.1.1:443", "8.8.8.8:443", "9.9.9.9:443"];
+
+pub struct OfflineState {
+    online: AtomicBool,
+    /// Offline mode switched on by hand: nothing is replayed until it's switched off.
+    forced: AtomicBool,
+    checked_at_ms: AtomicU64,
+    replaying: AtomicBool,
+    /// Serializes reads and writes of the queue file.
+    queue: Mutex<()>,
+}
+
+impl OfflineState {
+    pub fn new() -> Self {
+        Self {
+            online: AtomicBool::new(true),
+            forced: AtomicBool::new(false),
+            checked_at_ms: AtomicU64::new(0),
+            replaying: AtomicBool::new(false),
+            queue: Mutex::new(()),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct Connectivity {
+    /// Whether the network was reachable at the last check.
+    pub online: bool,
+    pub offline_mode: bool,
+    pub queued: usize,
+    pub checked_at_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct QueuedRequest {
+    pub id: String,
+    pub collection_id: String,
+    #[serde(default)]
+    pub environment_id: Option<String>,
+    pub request: Value,
+    pub queued_at_ms: u64,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub last_error: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct ReplayOutcome {
+    pub id: String,
+    /// The request reached a server; it has left the queue.
+    pub sent: bool,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    pub timestamp_ms: u64,
+}
+
+fn queue_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::workspace_state_dir(app, "queue")?.join(QUEUE_FILE))
+}
+
+fn load(app: &tauri::AppHandle) -> Result<Vec<QueuedRequest>, String> {
+    let path = queue_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("request queue read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("request queue parse failed: {e}"))
+}
+
+fn save(app: &tauri::AppHandle, queue: &[QueuedRequest]) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(queue)
+        .map_err(|e| format!("request queue serialize failed: {e}"))?;
+    fs::write(queue_path(app)?, payload).map_err(|e| format!("request queue persist failed: {e}"))
+}
+
+/// Loads the queue, applies `change` and saves it, with the queue lock held.
+fn update<T>(
+    app: &tauri::AppHandle,
+    change: impl FnOnce(&mut Vec<QueuedRequest>) -> T,
+) -> Result<T, String> {
+    let state = app.state::<OfflineState>();
+    let _guard = state
+        .queue
+        .lock()
+        .map_err(|_| "request queue lock poisoned".to_string())?;
+    let mut queue = load(app)?;
+    let out = change(&mut queue);
+    save(app, &queue)?;
+    Ok(out)
+}
+
+fn connectivity(app: &tauri::AppHandle, state: &OfflineState) -> Connectivity {
+    Connectivity {
+        online: state.online.load(Ordering::Relaxed),
+        offline_mode: state.forced.load(Ordering::Relaxed),
+        queued: load(app).map(|q| q.len()).unwrap_or(0),
+        checked_at_ms: state.checked_at_ms.load(Ordering::Relaxed),
+    }
+}
+
+async fn reachable() -> bool {
+    for probe in PROBES {
+        if let Ok(Ok(_)) = tokio::time::timeout(PROBE_TIMEOUT, TcpStream::connect(probe)).await {
+            return true;
+        }
+    }
+    false
+}
+
+/// Checks connectivity every few seconds for as long as the app runs, and replays the queue
+/// when the network comes back.
+pub async fn watch_connectivity(app: tauri::AppHandle) {
+    let mut ticks = tokio::time::interval(CHECK_INTERVAL);
+    loop {
+        ticks.tick().await;
+        let online = reachable().await;
+        let state = app.state::<OfflineState>();
+        state
+            .checked_at_ms
+            .store(crate::now_ms(), Ordering::Relaxed);
+        if state.online.swap(online, Ordering::Relaxed) == online {
+            continue;
+        }
+        let _ = app.emit("network://connectivity", connectivity(&app, &state));
+        if online && !state.forced.load(Ordering::Relaxed) {
+            replay_in_background(&app);
+        }
+    }
+}
+
+fn replay_in_background(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = replay(&app, None).await {
+            tracing::warn!("{e}");
+        }
+    });
+}
+
+/// Sends the queued requests (those in `ids`, or all of them) in the order they were queued.
+/// One that can't reach a server stops the replay and stays queued with its error.
+async fn replay(
+    app: &tauri::AppHandle,
+    ids: Option<Vec<String>>,
+) -> Result<Vec<ReplayOutcome>, String> {
+    let state = app.state::<OfflineState>();
+    if state.replaying.swap(true, Ordering::AcqRel) {
+        return Err("the request queue is already being replayed".to_string());
+    }
+    let pending: Vec<QueuedRequest> = match load(app) {
+        Ok(queue) => queue
+            .into_iter()
+            .filter(|q| ids.as_ref().is_none_or(|ids| ids.contains(&q.id)))
+            .collect(),
+        Err(e) => {
+            state.replaying.store(false, Ordering::Release);
+            return Err(e);
+        }
+    };
+    let mut outcomes = Vec::new();
+    for queued in pending {
+        let sent = crate::send::dispatch(
+            app.clone(),
+            queued.collection_id.clone(),
+            queued.request.clone(),
+            queued.environment_id.clone(),
+        )
+        .await;
+        // A status of 0 means the backend never got a response: still offline.
+        let (result, error) = match sent {
+            Ok(sent) => {
+                let error = match sent.result.get("status_code").and_then(Value::as_u64) {
+                    Some(0) | None => Some(
+                        sent.result
+                            .get("error")
+                            .and_then(Value::as_str)
+                            .unwrap_or("no response")
+                            .to_string(),
+                    ),
+                    Some(_) => None,
+                };
+                (Some(sent.result), error)
+            }
+            Err(e) => (None, Some(e)),
+        };
+        let outcome = ReplayOutcome {
+            id: queued.id.clone(),
+            sent: error.is_none(),
+            result,
+            error: error.clone(),
+            timestamp_ms: crate::now_ms(),
+        };
+        let stop = error.is_some();
+        let kept = update(app, |queue| match &error {
+            None => queue.retain(|q| q.id != queued.id),
+            Some(e) => {
+                if let Some(entry) = queue.iter_mut().find(|q| q.id == queued.id) {
+                    entry.attempts += 1;
+                    entry.last_error = Some(e.clone());
+                }
+            }
+        });
+        if let Err(e) = kept {
+            tracing::warn!("{e}");
+        }
+        let _ = app.emit("queue://replayed", outcome.clone());
+        outcomes.push(outcome);
+        if stop {
+            break;
+        }
+    }
+    state.replaying.store(false, Ordering::Release);
+    Ok(outcomes)
+}
+
+#[tauri::command]
+pub async fn get_connectivity(
+    app: tauri::AppHandle,
+    state: State<'_, OfflineState>,
+) -> Result<Connectivity, String> {
+    Ok(connectivity(&app, &state))
+}
+
+/// Switches offline mode on or off. Switching it off while online replays the queue.
+#[tauri::command]
+pub async fn set_offline_mode(
+    app: tauri::AppHandle,
+    state: State<'_, OfflineState>,
+    enabled: bool,
+) -> Result<Connectivity, String> {
+    let was = state.forced.swap(enabled, Ordering::Relaxed);
+    let status = connectivity(&app, &state);
+    let _ = app.emit("network://connectivity", status.clone());
+    if was && !enabled && status.online && status.queued > 0 {
+        replay_in_background(&app);
+    }
+    Ok(status)
+}
+
+/// Queues a send (in the `send_request` shape) to go out when the connection returns.
+#[tauri::command]
+pub async fn queue_request(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request: Value,
+    environment_id: Option<String>,
+) -> Result<QueuedRequest, String> {
+    if !request.is_object() {
+        return Err("request must be an object".to_string());
+    }
+    let queued = QueuedRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        collection_id,
+        environment_id,
+        request,
+        queued_at_ms: crate::now_ms(),
+        attempts: 0,
+        last_error: None,
+    };
+    let entry = queued.clone();
+    update(&app, move |queue| queue.push(entry))?;
+    Ok(queued)
+}
+
+#[tauri::command]
+pub async fn list_queued_requests(app: tauri::AppHandle) -> Result<Vec<QueuedRequest>, String> {
+    load(&app)
+}
+
+#[tauri::command]
+pub async fn remove_queued_request(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    update(&app, |queue| queue.retain(|q| q.id != id))
+}
+
+#[tauri::command]
+pub async fn clear_request_queue(app: tauri::AppHandle) -> Result<(), String> {
+    update(&app, |queue| queue.clear())
+}
+
+/// Sends the queued requests now (`ids`, or all of them), even in offline mode.
+#[tauri::command]
+pub async fn replay_queued_requests(
+    app: tauri::AppHandle,
+    ids: Option<Vec<String>>,
+) -> Result<Vec<ReplayOutcome>, String> {
+    replay(&app, ids).await
+}
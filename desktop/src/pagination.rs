@@ -0,0 +1,297 @@
+//! Fetching every page of a list endpoint: a request's `pagination` settings say how the
+//! next page is found (the `Link` header's `rel="next"`, a cursor or next-page URL in the
+//! body, or a page number counted up), and `fetch_all_pages` sends the request through
+//! `send` page after page, collecting the items of each into one list. It stops at the last
+//! page, at a page without items, at an error status or at the page limit.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::time::Instant;
+
+use litefetch_core::json::{array_of, find_request, str_of};
+use litefetch_core::schema::body_of;
+
+const DEFAULT_PAGE_LIMIT: usize = 20;
+const MAX_PAGE_LIMIT: usize = 1000;
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum PageStrategy {
+    /// RFC 8288 `Link: <…>; rel="next"`, as GitHub and many REST APIs send.
+    LinkHeader,
+    /// A cursor in the body, sent back as a query parameter.
+    Cursor {
+        /// JSONPath to the cursor, e.g. `$.meta.next_cursor`.
+        cursor_path: String,
+        param: String,
+    },
+    /// The next page's URL in the body, e.g. `$.next` or `$.links.next`.
+    NextUrl { url_path: String },
+    /// A page number sent as a query parameter.
+    Page {
+        #[serde(default = "default_page_param")]
+        param: String,
+        #[serde(default = "default_first_page")]
+        first: u64,
+        /// A page size parameter and value to send with every page.
+        #[serde(default)]
+        size_param: Option<String>,
+        #[serde(default)]
+        size: Option<u64>,
+    },
+}
+
+fn default_page_param() -> String {
+    "page".to_string()
+}
+
+fn default_first_page() -> u64 {
+    1
+}
+
+/// How a request is paged, stored on it as `pagination`.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Pagination {
+    #[serde(flatten)]
+    pub strategy: PageStrategy,
+    /// JSONPath to the array of items on each page; the body itself, when it is an array,
+    /// by default.
+    #[serde(default)]
+    pub items_path: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct PageResult {
+    pub index: usize,
+    /// As sent, with known secret values masked.
+    pub url: String,
+    pub status_code: Option<u64>,
+    pub duration_ms: Option<f64>,
+    pub items: usize,
+    pub body_bytes: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct PagedFetch {
+    pub pages: Vec<PageResult>,
+    /// The items of every page, in order.
+    pub items: Vec<Value>,
+    /// `last_page`, `empty_page`, `limit`, `error` or `repeated` (the next page pointed back
+    /// at one already fetched).
+    pub stopped: String,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+/// Sets a query parameter on a request, replacing any row with the same key.
+fn set_query(request: &mut Value, key: &str, value: &str) {
+    if !request.get("query_params").is_some_and(Value::is_array) {
+        request["query_params"] = json!([]);
+    }
+    if let Some(rows) = request["query_params"].as_array_mut() {
+        rows.retain(|row| str_of(row, "key") != key);
+        rows.push(json!({ "key": key, "value": value, "enabled": true }));
+    }
+}
+
+/// Points a request at an absolute URL that already carries its query string.
+fn set_url(request: &mut Value, url: &str) {
+    request["url"] = json!(url);
+    request["query_params"] = json!([]);
+}
+
+fn header<'a>(result: &'a Value, name: &str) -> Option<&'a str> {
+    result
+        .get("headers")
+        .and_then(Value::as_object)?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_str())
+}
+
+/// The `rel="next"` target of a `Link` header.
+fn next_link(link: &str) -> Option<String> {
+    link.split(',').find_map(|part| {
+        let (target, params) = part.split_once(';')?;
+        let next = params.split(';').any(|param| {
+            param
+                .trim()
+                .strip_prefix("rel=")
+                .map(|rel| rel.trim_matches('"'))
+                .is_some_and(|rel| {
+                    rel.split_whitespace()
+                        .any(|r| r.eq_ignore_ascii_case("next"))
+                })
+        });
+        next.then(|| {
+            target
+                .trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+/// `target` resolved against the URL the page was fetched from.
+fn absolute(base: &str, target: &str) -> String {
+    reqwest::Url::parse(base)
+        .and_then(|base| base.join(target))
+        .map(|url| url.to_string())
+        .unwrap_or_else(|_| target.to_string())
+}
+
+fn text_at(body: &Value, path: &str) -> Option<String> {
+    match litefetch_core::jsonpath::select_value(body, path)? {
+        Value::String(text) if !text.is_empty() => Some(text),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn items_of(body: &Value, items_path: Option<&str>) -> Vec<Value> {
+    let selected = match items_path.filter(|p| !p.trim().is_empty()) {
+        Some(path) => litefetch_core::jsonpath::select_value(body, path),
+        None => Some(body.clone()),
+    };
+    match selected {
+        Some(Value::Array(items)) => items,
+        _ => Vec::new(),
+    }
+}
+
+/// Sends a request page after page, as its `pagination` settings (or `pagination`, for
+/// trying settings before saving them) describe, up to `limit` pages (20 by default, 1000 at
+/// most). Each page goes through `send`, so it gets history, scripts and plugins.
+#[tauri::command]
+pub async fn fetch_all_pages(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+    limit: Option<usize>,
+    environment_id: Option<String>,
+    pagination: Option<Pagination>,
+) -> Result<PagedFetch, String> {
+    let started = Instant::now();
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let mut request = find_request(array_of(&collection, "items"), &request_id)
+        .cloned()
+        .ok_or_else(|| format!("unknown request: {request_id}"))?;
+    let pagination = match pagination {
+        Some(pagination) => pagination,
+        None => request
+            .get("pagination")
+            .filter(|p| p.is_object())
+            .cloned()
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| format!("invalid pagination settings: {e}"))?
+            .ok_or("the request has no pagination settings")?,
+    };
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+    let mut page_number = match &pagination.strategy {
+        PageStrategy::Page {
+            param,
+            first,
+            size_param,
+            size,
+        } => {
+            set_query(&mut request, param, &first.to_string());
+            if let (Some(size_param), Some(size)) = (size_param, size) {
+                set_query(&mut request, size_param, &size.to_string());
+            }
+            *first
+        }
+        _ => 0,
+    };
+
+    let mut fetch = PagedFetch {
+        pages: Vec::new(),
+        items: Vec::new(),
+        stopped: "limit".to_string(),
+        error: None,
+        duration_ms: 0,
+    };
+    let mut seen: Vec<String> = Vec::new();
+    for index in 0..limit {
+        let sent = match crate::send::dispatch(
+            app.clone(),
+            collection_id.clone(),
+            request.clone(),
+            environment_id.clone(),
+        )
+        .await
+        {
+            Ok(sent) => sent.result,
+            Err(e) => {
+                fetch.stopped = "error".to_string();
+                fetch.error = Some(e);
+                break;
+            }
+        };
+        let sent_url = sent
+            .get("sent_request")
+            .map(|s| str_of(s, "url").to_string())
+            .unwrap_or_default();
+        seen.push(sent_url.clone());
+        let body = body_of(&sent);
+        let items = items_of(&body, pagination.items_path.as_deref());
+        let status_code = sent.get("status_code").and_then(Value::as_u64);
+        fetch.pages.push(PageResult {
+            index,
+            url: crate::redact::log_line(&sent_url),
+            status_code,
+            duration_ms: sent.get("duration_ms").and_then(Value::as_f64),
+            items: items.len(),
+            body_bytes: sent.get("body_bytes").and_then(Value::as_u64),
+        });
+        let error = str_of(&sent, "error");
+        if !error.is_empty() || status_code.is_none_or(|code| code == 0 || code >= 400) {
+            fetch.stopped = "error".to_string();
+            fetch.error = Some(match error {
+                "" => format!("page {} returned {}", index + 1, status_code.unwrap_or(0)),
+                e => e.to_string(),
+            });
+            break;
+        }
+        if items.is_empty() {
+            fetch.stopped = "empty_page".to_string();
+            break;
+        }
+        fetch.items.extend(items);
+
+        let next = match &pagination.strategy {
+            PageStrategy::LinkHeader => header(&sent, "link")
+                .and_then(next_link)
+                .map(|target| absolute(&sent_url, &target)),
+            PageStrategy::NextUrl { url_path } => {
+                text_at(&body, url_path).map(|target| absolute(&sent_url, &target))
+            }
+            PageStrategy::Cursor { cursor_path, param } => {
+                text_at(&body, cursor_path).inspect(|cursor| set_query(&mut request, param, cursor))
+            }
+            PageStrategy::Page { param, .. } => {
+                page_number += 1;
+                set_query(&mut request, param, &page_number.to_string());
+                Some(page_number.to_string())
+            }
+        };
+        let Some(next) = next else {
+            fetch.stopped = "last_page".to_string();
+            break;
+        };
+        if matches!(
+            pagination.strategy,
+            PageStrategy::LinkHeader | PageStrategy::NextUrl { .. }
+        ) {
+            if seen.contains(&next) {
+                fetch.stopped = "repeated".to_string();
+                break;
+            }
+            set_url(&mut request, &next);
+        }
+    }
+    fetch.duration_ms = started.elapsed().as_millis() as u64;
+    Ok(fetch)
+}
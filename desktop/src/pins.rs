@@ -0,0 +1,178 @@
+//! Pinned requests: a per-workspace, user-ordered list of favorite requests (stored in
+//! `.litefetch/pins`) for the quick-access surfaces — the command palette, tray menu and
+//! quick-request flow. Every change is announced as `pins://changed` so they can refresh.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tauri::Emitter;
+
+use crate::importers::str_of;
+
+const PINS_FILE: &str = "pins.json";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Pin {
+    pub collection_id: String,
+    pub request_id: String,
+    pub pinned_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct PinnedRequest {
+    pub collection_id: String,
+    pub collection_name: String,
+    pub request_id: String,
+    pub name: String,
+    pub method: String,
+    pub url: String,
+    pub pinned_ms: u64,
+    /// The request (or its collection) no longer exists; unpin it to drop it.
+    pub missing: bool,
+}
+
+#[derive(Clone, Serialize)]
+struct PinsEvent {
+    pins: Vec<Pin>,
+    timestamp_ms: u64,
+}
+
+fn pins_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::workspace_state_dir(app, "pins")?.join(PINS_FILE))
+}
+
+fn load_pins(app: &tauri::AppHandle) -> Result<Vec<Pin>, String> {
+    let path = pins_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("pins read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("pins parse failed: {e}"))
+}
+
+fn save_pins(app: &tauri::AppHandle, pins: Vec<Pin>) -> Result<Vec<Pin>, String> {
+    let payload =
+        serde_json::to_string_pretty(&pins).map_err(|e| format!("pins serialize failed: {e}"))?;
+    fs::write(pins_path(app)?, payload).map_err(|e| format!("pins persist failed: {e}"))?;
+    let _ = app.emit(
+        "pins://changed",
+        PinsEvent {
+            pins: pins.clone(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    Ok(pins)
+}
+
+/// Pins a request, at the end of the list or at `position` (0 is the top). Pinning a pinned
+/// request moves it.
+#[tauri::command]
+pub async fn pin_request(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+    position: Option<usize>,
+) -> Result<Vec<Pin>, String> {
+    let mut pins = load_pins(&app)?;
+    let existing = pins
+        .iter()
+        .position(|p| p.collection_id == collection_id && p.request_id == request_id)
+        .map(|at| pins.remove(at));
+    let pin = existing.unwrap_or(Pin {
+        collection_id,
+        request_id,
+        pinned_ms: crate::now_ms(),
+    });
+    let at = position.unwrap_or(pins.len()).min(pins.len());
+    pins.insert(at, pin);
+    save_pins(&app, pins)
+}
+
+#[tauri::command]
+pub async fn unpin_request(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+) -> Result<Vec<Pin>, String> {
+    let mut pins = load_pins(&app)?;
+    pins.retain(|p| !(p.collection_id == collection_id && p.request_id == request_id));
+    save_pins(&app, pins)
+}
+
+/// Puts the pins in the order of `request_ids`; pins left out keep their relative order
+/// after the listed ones.
+#[tauri::command]
+pub async fn reorder_pinned(
+    app: tauri::AppHandle,
+    request_ids: Vec<String>,
+) -> Result<Vec<Pin>, String> {
+    let mut pins = load_pins(&app)?;
+    let rank = |pin: &Pin| {
+        request_ids
+            .iter()
+            .position(|id| *id == pin.request_id)
+            .unwrap_or(usize::MAX)
+    };
+    pins.sort_by_key(rank);
+    save_pins(&app, pins)
+}
+
+/// The pinned requests in order, with what the quick-access surfaces show of them.
+#[tauri::command]
+pub async fn list_pinned(app: tauri::AppHandle) -> Result<Vec<PinnedRequest>, String> {
+    let pins = load_pins(&app)?;
+    if pins.is_empty() {
+        return Ok(Vec::new());
+    }
+    let metas = crate::backend_get(&app, "/collections").await?;
+    let names: HashMap<&str, &str> = metas
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|meta| (str_of(meta, "id"), str_of(meta, "name")))
+        .collect();
+    let mut collections: HashMap<String, Value> = HashMap::new();
+    let mut listed = Vec::new();
+    for pin in pins {
+        if !collections.contains_key(&pin.collection_id)
+            && names.contains_key(pin.collection_id.as_str())
+        {
+            let collection = crate::backend_get(
+                &app,
+                &format!("/collections/{}/collection", pin.collection_id),
+            )
+            .await
+            .unwrap_or(Value::Null);
+            collections.insert(pin.collection_id.clone(), collection);
+        }
+        let request = collections.get(&pin.collection_id).and_then(|collection| {
+            let items = litefetch_core::json::array_of(collection, "items");
+            litefetch_core::json::find_request(items, &pin.request_id)
+        });
+        listed.push(PinnedRequest {
+            collection_name: names
+                .get(pin.collection_id.as_str())
+                .unwrap_or(&"")
+                .to_string(),
+            name: request
+                .map(|r| str_of(r, "name"))
+                .unwrap_or_default()
+                .to_string(),
+            method: request
+                .map(|r| str_of(r, "method"))
+                .unwrap_or_default()
+                .to_string(),
+            url: request
+                .map(|r| str_of(r, "url"))
+                .unwrap_or_default()
+                .to_string(),
+            missing: request.is_none(),
+            collection_id: pin.collection_id,
+            request_id: pin.request_id,
+            pinned_ms: pin.pinned_ms,
+        });
+    }
+    Ok(listed)
+}
@@ -0,0 +1,755 @@
+//! WebAssembly plugins, loaded from the workspace's `plugins/` directory. Each plugin is a
+//! folder holding a `plugin.json` manifest and the module it names:
+//!
+//! ```json
+//! { "id": "signer", "name": "Request signer", "version": "1.0.0", "module": "plugin.wasm",
+//!   "capabilities": ["variables"], "hooks": ["before_request"],
+//!   "auth_schemes": [{ "id": "hmac", "label": "HMAC signature" }],
+//!   "importers": [{ "id": "csv", "label": "CSV requests", "extensions": ["csv"] }],
+//!   "viewers": [{ "id": "proto", "label": "Protobuf", "content_types": ["application/x-protobuf"] }] }
+//! ```
+//!
+//! Host ABI: a module exports `memory` and `lf_alloc(len: i32) -> i32`, plus one function per
+//! extension point it implements: `lf_before_request`, `lf_after_response`, `lf_auth`,
+//! `lf_import` and `lf_view`. Each takes `(ptr, len)` of a UTF-8 JSON input and returns an
+//! `i64` packing `(ptr << 32) | len` of its JSON output, or 0 for no output. Every module may
+//! import `litefetch.log(level, ptr, len)` and `litefetch.now_ms() -> i64`; the rest of the
+//! `litefetch` imports exist only for capabilities the user granted:
+//!
+//! - `network`: `http_fetch(ptr, len) -> i64`, taking and returning the same JSON as
+//!   `lf.sendRequest` in scripts.
+//! - `variables`: `get_variable(ptr, len) -> i64`, the resolved value or 0 when unset.
+//!
+//! Enabling a plugin grants the capabilities its manifest declares; if a later version
+//! declares more, or its module changes, it stays disabled until enabled again. Which plugins
+//! are enabled, with what they were granted and a hash of the module that was, is kept per
+//! workspace in the app data root's `plugins.json`, never in the workspace, so a cloned
+//! repository can't enable the plugins it ships. Modules run in an interpreter with fuel and
+//! memory limits, and have no other access to the machine.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use wasmi::{
+    AsContext, AsContextMut, Caller, Config, Engine, Extern, Linker, Memory, Module, Store,
+    StoreLimits, StoreLimitsBuilder, TypedFunc,
+};
+
+use crate::importers::str_of;
+
+const PLUGINS_DIR: &str = "plugins";
+const MANIFEST_FILE: &str = "plugin.json";
+const STATE_FILE: &str = "plugins.json";
+const CAPABILITIES: &[&str] = &["network", "variables"];
+/// Hooks run on every send; auth schemes, importers and viewers are listed separately.
+const HOOKS: &[&str] = &["before_request", "after_response"];
+/// Roughly a few seconds of interpreted work per call.
+const FUEL_LIMIT: u64 = 2_000_000_000;
+const MEMORY_LIMIT: usize = 64 << 20;
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AuthScheme {
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PluginImporter {
+    pub id: String,
+    pub label: String,
+    #[serde(default)]
+    pub extensions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PluginViewer {
+    pub id: String,
+    pub label: String,
+    /// Response content types the viewer is offered for; empty offers it for every response.
+    #[serde(default)]
+    pub content_types: Vec<String>,
+}
+
+#[derive(Deserialize, Clone)]
+struct Manifest {
+    id: String,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    description: String,
+    module: String,
+    #[serde(default)]
+    capabilities: Vec<String>,
+    #[serde(default)]
+    hooks: Vec<String>,
+    #[serde(default)]
+    auth_schemes: Vec<AuthScheme>,
+    #[serde(default)]
+    importers: Vec<PluginImporter>,
+    #[serde(default)]
+    viewers: Vec<PluginViewer>,
+}
+
+/// A discovered plugin, as listed in the UI.
+#[derive(Serialize, Clone)]
+pub struct PluginInfo {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub description: String,
+    pub capabilities: Vec<String>,
+    pub hooks: Vec<String>,
+    pub auth_schemes: Vec<AuthScheme>,
+    pub importers: Vec<PluginImporter>,
+    pub viewers: Vec<PluginViewer>,
+    pub enabled: bool,
+    /// Why the plugin can't be enabled or was disabled, e.g. an invalid manifest.
+    pub error: Option<String>,
+}
+
+/// What a plugin was enabled with.
+#[derive(Serialize, Deserialize, Clone)]
+struct Grant {
+    capabilities: Vec<String>,
+    /// SHA-256 of the module that was enabled.
+    module_sha256: String,
+    granted_ms: u64,
+}
+
+/// Per-workspace plugin state: the grant of each enabled plugin, by plugin id.
+#[derive(Serialize, Deserialize, Default)]
+struct PluginState {
+    #[serde(default)]
+    enabled: HashMap<String, Grant>,
+}
+
+/// Every workspace's plugin state, by workspace path.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct StoredState {
+    workspaces: BTreeMap<String, PluginState>,
+}
+
+#[derive(Serialize, Clone)]
+struct PluginLogEvent {
+    plugin_id: String,
+    level: String,
+    message: String,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct PluginView {
+    /// `text/html` (shown in a sandboxed frame) or `text/plain`.
+    pub content_type: String,
+    pub content: String,
+}
+
+/// A plugin that is enabled and loadable.
+struct Plugin {
+    manifest: Manifest,
+    module_path: PathBuf,
+    granted: Vec<String>,
+    module_sha256: String,
+}
+
+struct Host {
+    app: tauri::AppHandle,
+    plugin_id: String,
+    vars: Map<String, Value>,
+    limits: StoreLimits,
+}
+
+fn plugins_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::load_workspace_path(app)?.join(PLUGINS_DIR))
+}
+
+fn state_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(STATE_FILE))
+}
+
+fn workspace_key(app: &tauri::AppHandle) -> Result<String, String> {
+    Ok(crate::load_workspace_path(app)?
+        .to_string_lossy()
+        .to_string())
+}
+
+fn read_stored(app: &tauri::AppHandle) -> Result<StoredState, String> {
+    let path = state_path(app)?;
+    if !path.exists() {
+        return Ok(StoredState::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("plugin state read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("plugin state parse failed: {e}"))
+}
+
+/// The current workspace's plugin state.
+fn read_state(app: &tauri::AppHandle) -> Result<PluginState, String> {
+    let workspace = workspace_key(app)?;
+    Ok(read_stored(app)?
+        .workspaces
+        .remove(&workspace)
+        .unwrap_or_default())
+}
+
+fn write_state(app: &tauri::AppHandle, state: PluginState) -> Result<(), String> {
+    let mut stored = read_stored(app)?;
+    stored.workspaces.insert(workspace_key(app)?, state);
+    let payload = serde_json::to_string_pretty(&stored)
+        .map_err(|e| format!("plugin state serialize failed: {e}"))?;
+    fs::write(state_path(app)?, payload).map_err(|e| format!("plugin state persist failed: {e}"))
+}
+
+fn module_sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+fn read_manifest(dir: &Path) -> Result<Manifest, String> {
+    let data = fs::read_to_string(dir.join(MANIFEST_FILE))
+        .map_err(|e| format!("plugin manifest read failed: {e}"))?;
+    let manifest: Manifest =
+        serde_json::from_str(&data).map_err(|e| format!("plugin manifest invalid: {e}"))?;
+    if manifest.id.trim().is_empty() {
+        return Err("plugin manifest has no id".to_string());
+    }
+    if let Some(unknown) = manifest
+        .capabilities
+        .iter()
+        .find(|c| !CAPABILITIES.contains(&c.as_str()))
+    {
+        return Err(format!("unknown plugin capability: {unknown}"));
+    }
+    if let Some(unknown) = manifest.hooks.iter().find(|h| !HOOKS.contains(&h.as_str())) {
+        return Err(format!("unknown plugin hook: {unknown}"));
+    }
+    // The module must stay inside the plugin's folder.
+    let module = Path::new(&manifest.module);
+    if manifest.module.is_empty()
+        || module.is_absolute()
+        || module
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir))
+    {
+        return Err("plugin module must be a path inside the plugin folder".to_string());
+    }
+    Ok(manifest)
+}
+
+/// A plugin folder with its manifest, or the reason it couldn't be read.
+type Discovered = (PathBuf, Result<Manifest, String>);
+
+fn discover(app: &tauri::AppHandle) -> Result<Vec<Discovered>, String> {
+    let dir = plugins_dir(app)?;
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut found: Vec<Discovered> = fs::read_dir(&dir)
+        .map_err(|e| format!("plugins read failed: {e}"))?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.join(MANIFEST_FILE).is_file())
+        .map(|path| {
+            let manifest = read_manifest(&path);
+            (path, manifest)
+        })
+        .collect();
+    found.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(found)
+}
+
+fn info(
+    dir: &Path,
+    manifest: Result<Manifest, String>,
+    state: &PluginState,
+) -> (PluginInfo, Option<Plugin>) {
+    let manifest = match manifest {
+        Ok(manifest) => manifest,
+        Err(error) => {
+            let id = dir
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let info = PluginInfo {
+                id: id.clone(),
+                name: id,
+                version: String::new(),
+                description: String::new(),
+                capabilities: Vec::new(),
+                hooks: Vec::new(),
+                auth_schemes: Vec::new(),
+                importers: Vec::new(),
+                viewers: Vec::new(),
+                enabled: false,
+                error: Some(error),
+            };
+            return (info, None);
+        }
+    };
+    let module_path = dir.join(&manifest.module);
+    let granted = state.enabled.get(&manifest.id);
+    let error = match granted {
+        _ if !module_path.is_file() => {
+            Some(format!("plugin module not found: {}", manifest.module))
+        }
+        Some(grant)
+            if manifest
+                .capabilities
+                .iter()
+                .any(|c| !grant.capabilities.contains(c)) =>
+        {
+            Some("the plugin asks for new capabilities; enable it again to grant them".to_string())
+        }
+        Some(grant)
+            if fs::read(&module_path)
+                .map(|bytes| module_sha256(&bytes))
+                .ok()
+                != Some(grant.module_sha256.clone()) =>
+        {
+            Some("the plugin's module changed; enable it again to run it".to_string())
+        }
+        _ => None,
+    };
+    let enabled = granted.is_some() && error.is_none();
+    let info = PluginInfo {
+        id: manifest.id.clone(),
+        name: match manifest.name.as_str() {
+            "" => manifest.id.clone(),
+            name => name.to_string(),
+        },
+        version: manifest.version.clone(),
+        description: manifest.description.clone(),
+        capabilities: manifest.capabilities.clone(),
+        hooks: manifest.hooks.clone(),
+        auth_schemes: manifest.auth_schemes.clone(),
+        importers: manifest.importers.clone(),
+        viewers: manifest.viewers.clone(),
+        enabled,
+        error,
+    };
+    let plugin = granted.filter(|_| enabled).map(|grant| Plugin {
+        granted: grant.capabilities.clone(),
+        module_sha256: grant.module_sha256.clone(),
+        manifest,
+        module_path,
+    });
+    (info, plugin)
+}
+
+/// The enabled plugins of the current workspace, in folder order.
+fn enabled(app: &tauri::AppHandle) -> Result<Vec<Plugin>, String> {
+    let state = read_state(app)?;
+    Ok(discover(app)?
+        .into_iter()
+        .filter_map(|(dir, manifest)| info(&dir, manifest, &state).1)
+        .collect())
+}
+
+fn enabled_plugin(app: &tauri::AppHandle, plugin_id: &str) -> Result<Plugin, String> {
+    enabled(app)?
+        .into_iter()
+        .find(|p| p.manifest.id == plugin_id)
+        .ok_or_else(|| format!("plugin not enabled: {plugin_id}"))
+}
+
+fn trap(message: impl Into<String>) -> wasmi::Error {
+    wasmi::Error::new(message)
+}
+
+fn read_guest(
+    ctx: impl AsContext,
+    memory: Memory,
+    ptr: i32,
+    len: i32,
+) -> Result<Vec<u8>, wasmi::Error> {
+    // The length comes from the guest: bounds-checked before anything is allocated for it.
+    let start = ptr as u32 as usize;
+    let len = usize::try_from(len).map_err(|_| trap("plugin memory read out of bounds"))?;
+    if start
+        .checked_add(len)
+        .is_none_or(|end| end > memory.data_size(&ctx))
+    {
+        return Err(trap("plugin memory read out of bounds"));
+    }
+    let mut buffer = vec![0; len];
+    memory
+        .read(ctx, start, &mut buffer)
+        .map_err(|e| trap(format!("plugin memory read failed: {e}")))?;
+    Ok(buffer)
+}
+
+/// Copies `bytes` into memory allocated by the guest, returning the packed `(ptr, len)`.
+fn write_guest(
+    mut ctx: impl AsContextMut,
+    memory: Memory,
+    alloc: &TypedFunc<i32, i32>,
+    bytes: &[u8],
+) -> Result<i64, wasmi::Error> {
+    let len = i32::try_from(bytes.len()).map_err(|_| trap("plugin payload too large"))?;
+    let ptr = alloc.call(&mut ctx, len)?;
+    memory
+        .write(&mut ctx, ptr as u32 as usize, bytes)
+        .map_err(|e| trap(format!("plugin memory write failed: {e}")))?;
+    Ok(((ptr as u32 as i64) << 32) | len as u32 as i64)
+}
+
+fn caller_exports(
+    caller: &Caller<'_, Host>,
+) -> Result<(Memory, TypedFunc<i32, i32>), wasmi::Error> {
+    let memory = caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .ok_or_else(|| trap("plugin does not export memory"))?;
+    let alloc = caller
+        .get_export("lf_alloc")
+        .and_then(Extern::into_func)
+        .ok_or_else(|| trap("plugin does not export lf_alloc"))?
+        .typed::<i32, i32>(caller)?;
+    Ok((memory, alloc))
+}
+
+fn guest_text(caller: &Caller<'_, Host>, ptr: i32, len: i32) -> Result<String, wasmi::Error> {
+    let (memory, _) = caller_exports(caller)?;
+    let bytes = read_guest(caller, memory, ptr, len)?;
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+fn link(linker: &mut Linker<Host>, granted: &[String]) -> Result<(), wasmi::Error> {
+    linker.func_wrap(
+        "litefetch",
+        "log",
+        |caller: Caller<'_, Host>, level: i32, ptr: i32, len: i32| -> Result<(), wasmi::Error> {
+            let message = guest_text(&caller, ptr, len)?;
+            let level = match level {
+                0 => "debug",
+                1 => "info",
+                2 => "warn",
+                3 => "error",
+                _ => "log",
+            };
+            let host = caller.data();
+            let _ = host.app.emit(
+                "plugin://log",
+                PluginLogEvent {
+                    plugin_id: host.plugin_id.clone(),
+                    level: level.to_string(),
+                    message: crate::redact::log_line(&message),
+                    timestamp_ms: crate::now_ms(),
+                },
+            );
+            Ok(())
+        },
+    )?;
+    linker.func_wrap("litefetch", "now_ms", || crate::now_ms() as i64)?;
+    if granted.iter().any(|c| c == "network") {
+        linker.func_wrap(
+            "litefetch",
+            "http_fetch",
+            |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<i64, wasmi::Error> {
+                let spec = guest_text(&caller, ptr, len)?;
+                let response =
+                    crate::scripting::fetch(&spec, Instant::now() + NETWORK_TIMEOUT).to_string();
+                let (memory, alloc) = caller_exports(&caller)?;
+                write_guest(&mut caller, memory, &alloc, response.as_bytes())
+            },
+        )?;
+    }
+    if granted.iter().any(|c| c == "variables") {
+        linker.func_wrap(
+            "litefetch",
+            "get_variable",
+            |mut caller: Caller<'_, Host>, ptr: i32, len: i32| -> Result<i64, wasmi::Error> {
+                let key = guest_text(&caller, ptr, len)?;
+                let Some(value) = caller.data().vars.get(&key).map(|v| match v {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                }) else {
+                    return Ok(0);
+                };
+                let (memory, alloc) = caller_exports(&caller)?;
+                write_guest(&mut caller, memory, &alloc, value.as_bytes())
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// Calls `export` with `input`, returning its JSON output; `None` when the plugin returned
+/// nothing.
+fn call(
+    app: &tauri::AppHandle,
+    plugin: &Plugin,
+    export: &str,
+    input: &Value,
+    vars: Map<String, Value>,
+) -> Result<Option<Value>, String> {
+    let id = &plugin.manifest.id;
+    let fail = |e: &dyn std::fmt::Display| format!("plugin {id} failed: {e}");
+    let bytes = fs::read(&plugin.module_path).map_err(|e| fail(&e))?;
+    // The module may have been swapped since it was checked.
+    if module_sha256(&bytes) != plugin.module_sha256 {
+        return Err(fail(&"the module changed since it was enabled"));
+    }
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let module = Module::new(&engine, bytes).map_err(|e| fail(&e))?;
+    let mut store = Store::new(
+        &engine,
+        Host {
+            app: app.clone(),
+            plugin_id: id.clone(),
+            vars,
+            limits: StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build(),
+        },
+    );
+    store.limiter(|host| &mut host.limits);
+    store.set_fuel(FUEL_LIMIT).map_err(|e| fail(&e))?;
+
+    let mut linker = Linker::new(&engine);
+    link(&mut linker, &plugin.granted).map_err(|e| fail(&e))?;
+    // Imports for capabilities that weren't granted are missing, so instantiation fails.
+    let instance = linker
+        .instantiate_and_start(&mut store, &module)
+        .map_err(|e| fail(&e))?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or_else(|| fail(&"module does not export memory"))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "lf_alloc")
+        .map_err(|e| fail(&e))?;
+    let hook = instance
+        .get_typed_func::<(i32, i32), i64>(&store, export)
+        .map_err(|e| fail(&e))?;
+
+    let packed = write_guest(&mut store, memory, &alloc, input.to_string().as_bytes())
+        .map_err(|e| fail(&e))?;
+    let output = hook
+        .call(&mut store, ((packed >> 32) as i32, packed as i32))
+        .map_err(|e| match store.get_fuel() {
+            Ok(0) => fail(&"ran out of fuel"),
+            _ => fail(&e),
+        })?;
+    if output == 0 {
+        return Ok(None);
+    }
+    let bytes =
+        read_guest(&store, memory, (output >> 32) as i32, output as i32).map_err(|e| fail(&e))?;
+    serde_json::from_slice(&bytes)
+        .map(Some)
+        .map_err(|e| fail(&format!("output is not JSON: {e}")))
+}
+
+async fn call_blocking(
+    app: &tauri::AppHandle,
+    plugin: Plugin,
+    export: &'static str,
+    input: Value,
+    vars: Map<String, Value>,
+) -> Result<Option<Value>, String> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || call(&app, &plugin, export, &input, vars))
+        .await
+        .map_err(|e| format!("plugin failed: {e}"))?
+}
+
+/// Passes a resolved request through every enabled `before_request` hook, in folder order.
+/// A hook returns `{request}` to replace it.
+pub async fn before_request(
+    app: &tauri::AppHandle,
+    mut request: Value,
+    vars: &Map<String, Value>,
+) -> Result<Value, String> {
+    for plugin in enabled(app)? {
+        if !plugin.manifest.hooks.iter().any(|h| h == "before_request") {
+            continue;
+        }
+        let input = json!({ "request": request });
+        let output = call_blocking(app, plugin, "lf_before_request", input, vars.clone()).await?;
+        if let Some(replaced) = output.and_then(|o| o.get("request").cloned()) {
+            if replaced.is_object() {
+                request = replaced;
+            }
+        }
+    }
+    Ok(request)
+}
+
+/// Passes a backend `RequestResult` through every enabled `after_response` hook. A hook
+/// returns `{result}` to replace it, e.g. to decode a body.
+pub async fn after_response(
+    app: &tauri::AppHandle,
+    request: &Value,
+    mut result: Value,
+    vars: &Map<String, Value>,
+) -> Result<Value, String> {
+    for plugin in enabled(app)? {
+        if !plugin.manifest.hooks.iter().any(|h| h == "after_response") {
+            continue;
+        }
+        let input = json!({ "request": request, "result": result });
+        let output = call_blocking(app, plugin, "lf_after_response", input, vars.clone()).await?;
+        if let Some(replaced) = output.and_then(|o| o.get("result").cloned()) {
+            if replaced.is_object() {
+                result = replaced;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Runs the auth scheme of a request with `auth_type: "plugin"`, whose `auth_params` name it
+/// with `plugin` and `scheme`. The plugin gets `{scheme, params, request}` and returns
+/// `{headers}` to add; those header names are returned so they can be marked secret.
+pub async fn authorize(
+    app: &tauri::AppHandle,
+    request: &mut Value,
+    vars: &Map<String, Value>,
+) -> Result<Vec<String>, String> {
+    let params = request
+        .get("auth_params")
+        .cloned()
+        .unwrap_or_else(|| Value::Object(Map::new()));
+    let plugin = enabled_plugin(app, str_of(&params, "plugin"))?;
+    let scheme = str_of(&params, "scheme").to_string();
+    if !plugin.manifest.auth_schemes.iter().any(|s| s.id == scheme) {
+        return Err(format!(
+            "plugin {} has no auth scheme {scheme}",
+            plugin.manifest.id
+        ));
+    }
+    let input = json!({ "scheme": scheme, "params": params, "request": request });
+    let output = call_blocking(app, plugin, "lf_auth", input, vars.clone()).await?;
+    let mut added = Vec::new();
+    if let Some(headers) = output
+        .as_ref()
+        .and_then(|o| o.get("headers"))
+        .and_then(Value::as_object)
+    {
+        if !request.get("headers").is_some_and(Value::is_object) {
+            request["headers"] = Value::Object(Map::new());
+        }
+        for (name, value) in headers {
+            let value = match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            request["headers"][name] = Value::String(value);
+            added.push(name.clone());
+        }
+    }
+    Ok(added)
+}
+
+#[tauri::command]
+pub async fn list_plugins(app: tauri::AppHandle) -> Result<Vec<PluginInfo>, String> {
+    let state = read_state(&app)?;
+    Ok(discover(&app)?
+        .into_iter()
+        .map(|(dir, manifest)| info(&dir, manifest, &state).0)
+        .collect())
+}
+
+/// Enables a plugin, granting the capabilities its manifest declares, or disables it.
+#[tauri::command]
+pub async fn set_plugin_enabled(
+    app: tauri::AppHandle,
+    plugin_id: String,
+    enabled: bool,
+) -> Result<PluginInfo, String> {
+    let (dir, manifest) = discover(&app)?
+        .into_iter()
+        .find(|(_, m)| m.as_ref().is_ok_and(|m| m.id == plugin_id))
+        .ok_or_else(|| format!("unknown plugin: {plugin_id}"))?;
+    let mut state = read_state(&app)?;
+    match (enabled, &manifest) {
+        (true, Ok(manifest)) => {
+            let bytes = fs::read(dir.join(&manifest.module))
+                .map_err(|e| format!("plugin module read failed: {e}"))?;
+            let grant = Grant {
+                capabilities: manifest.capabilities.clone(),
+                module_sha256: module_sha256(&bytes),
+                granted_ms: crate::now_ms(),
+            };
+            state.enabled.insert(plugin_id, grant);
+        }
+        _ => {
+            state.enabled.remove(&plugin_id);
+        }
+    }
+    let plugin = info(&dir, manifest, &state).0;
+    write_state(&app, state)?;
+    Ok(plugin)
+}
+
+/// Imports `path` with a plugin importer, which returns `{name, items}` in the collection
+/// format; the collection is created and its metadata returned.
+#[tauri::command]
+pub async fn import_with_plugin(
+    app: tauri::AppHandle,
+    plugin_id: String,
+    importer: String,
+    path: String,
+) -> Result<Value, String> {
+    let plugin = enabled_plugin(&app, &plugin_id)?;
+    if !plugin.manifest.importers.iter().any(|i| i.id == importer) {
+        return Err(format!("plugin {plugin_id} has no importer {importer}"));
+    }
+    let content = crate::importers::read_source(&path)?;
+    let file_name = Path::new(&path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let input = json!({ "importer": importer, "file_name": file_name, "content": content });
+    let output = call_blocking(&app, plugin, "lf_import", input, Map::new())
+        .await?
+        .ok_or_else(|| format!("plugin {plugin_id} imported nothing"))?;
+    let items = output
+        .get("items")
+        .filter(|items| items.as_array().is_some_and(|a| !a.is_empty()))
+        .cloned()
+        .ok_or_else(|| format!("plugin {plugin_id} imported no requests"))?;
+    let name = match str_of(&output, "name") {
+        "" => file_name,
+        name => name.to_string(),
+    };
+    crate::create_collection_with(
+        &app,
+        json!({ "name": name, "collection": { "name": name, "items": items } }),
+    )
+    .await
+}
+
+/// Renders a response with a plugin viewer, which gets `{viewer, result}` and returns
+/// `{content_type, content}`.
+#[tauri::command]
+pub async fn render_plugin_view(
+    app: tauri::AppHandle,
+    plugin_id: String,
+    viewer: String,
+    result: Value,
+) -> Result<PluginView, String> {
+    let plugin = enabled_plugin(&app, &plugin_id)?;
+    if !plugin.manifest.viewers.iter().any(|v| v.id == viewer) {
+        return Err(format!("plugin {plugin_id} has no viewer {viewer}"));
+    }
+    let input = json!({ "viewer": viewer, "result": result });
+    let output = call_blocking(&app, plugin, "lf_view", input, Map::new())
+        .await?
+        .unwrap_or(Value::Null);
+    let content_type = match str_of(&output, "content_type") {
+        "text/html" => "text/html",
+        _ => "text/plain",
+    };
+    Ok(PluginView {
+        content_type: content_type.to_string(),
+        content: crate::importers::value_text(output.get("content")),
+    })
+}
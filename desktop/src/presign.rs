@@ -0,0 +1,244 @@
+//! Presigned URLs for S3 and S3-compatible storage (MinIO, R2, B2), for debugging
+//! object-storage integrations: a URL that grants one method on one object until it expires,
+//! signed with SigV4 query authentication (see `sync::s3`). Keys come from the workspace's
+//! S3 sync target or from a collection environment, where they can be keychain secrets.
+//! Each URL comes with a ready request, which `send_presigned` sends like any other.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use litefetch_core::json::str_of;
+
+use crate::sync::{s3::Bucket, SyncTarget};
+use crate::variables::{resolve, Purpose, Scope};
+
+const DEFAULT_EXPIRY_SECONDS: u64 = 3600;
+/// SigV4 presigned URLs are valid for a week at most.
+const MAX_EXPIRY_SECONDS: u64 = 7 * 24 * 3600;
+const METHODS: &[&str] = &["GET", "PUT", "HEAD", "DELETE"];
+
+#[derive(Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum PresignCredentials {
+    /// The workspace's S3 sync target, whose endpoint, region and bucket also fill in what
+    /// the options leave out.
+    Sync,
+    /// Values or `{{variable}}` references resolved in a collection environment (the active
+    /// one by default).
+    Environment {
+        collection_id: String,
+        #[serde(default)]
+        environment_id: Option<String>,
+        access_key_id: String,
+        secret_access_key: String,
+        #[serde(default)]
+        session_token: String,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct PresignOptions {
+    pub credentials: PresignCredentials,
+    /// For S3-compatible services; AWS when empty.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    #[serde(default)]
+    pub bucket: Option<String>,
+    pub key: String,
+    /// `GET` (the default), `PUT`, `HEAD` or `DELETE`.
+    #[serde(default)]
+    pub method: Option<String>,
+    #[serde(default)]
+    pub expires_seconds: Option<u64>,
+    #[serde(default)]
+    pub path_style: Option<bool>,
+}
+
+#[derive(Serialize)]
+pub struct PresignedUrl {
+    pub method: String,
+    pub url: String,
+    pub expires_at_ms: u64,
+    /// A request for the URL, to open in a tab or pass to `send_presigned`.
+    pub request: Value,
+}
+
+struct Keys {
+    endpoint: Option<String>,
+    region: String,
+    bucket: String,
+    path_style: bool,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: String,
+}
+
+async fn keys(app: &tauri::AppHandle, options: &PresignOptions) -> Result<Keys, String> {
+    let keys = match &options.credentials {
+        PresignCredentials::Sync => {
+            let (target, secret) = crate::sync::s3_target(app)?;
+            let SyncTarget::S3 {
+                endpoint,
+                region,
+                bucket,
+                access_key_id,
+                path_style,
+                ..
+            } = target
+            else {
+                return Err("this workspace does not sync to S3".into());
+            };
+            Keys {
+                endpoint,
+                region,
+                bucket,
+                path_style,
+                access_key_id,
+                secret_access_key: secret,
+                session_token: String::new(),
+            }
+        }
+        PresignCredentials::Environment {
+            collection_id,
+            environment_id,
+            access_key_id,
+            secret_access_key,
+            session_token,
+        } => {
+            let collection =
+                crate::backend_get(app, &format!("/collections/{collection_id}/collection"))
+                    .await?;
+            let environment =
+                crate::backend_get(app, &format!("/collections/{collection_id}/environment"))
+                    .await?;
+            let env_name = environment_id
+                .clone()
+                .unwrap_or_else(|| str_of(&environment, "active_env").into());
+            let scope = Scope::load(
+                &crate::variables::layers(app)?,
+                collection_id,
+                &collection,
+                &environment,
+                &env_name,
+                None,
+            )?;
+            let value = |text: &str| resolve(text, &scope, Purpose::Send, &mut Vec::new());
+            Keys {
+                endpoint: None,
+                region: String::new(),
+                bucket: String::new(),
+                path_style: false,
+                access_key_id: value(access_key_id),
+                secret_access_key: value(secret_access_key),
+                session_token: value(session_token),
+            }
+        }
+    };
+    if keys.access_key_id.trim().is_empty() || keys.secret_access_key.trim().is_empty() {
+        return Err("presigning needs an access key id and secret access key".into());
+    }
+    Ok(keys)
+}
+
+/// Signs a URL for one method on one object, valid for `expires_seconds` (an hour by
+/// default, a week at most).
+#[tauri::command]
+pub async fn presign_s3_url(
+    app: tauri::AppHandle,
+    options: PresignOptions,
+) -> Result<PresignedUrl, String> {
+    let method = options
+        .method
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty())
+        .unwrap_or("GET")
+        .to_ascii_uppercase();
+    if !METHODS.contains(&method.as_str()) {
+        return Err(format!("presigned URLs support {}", METHODS.join(", ")));
+    }
+    let expires = options
+        .expires_seconds
+        .unwrap_or(DEFAULT_EXPIRY_SECONDS)
+        .clamp(1, MAX_EXPIRY_SECONDS);
+    let key = options.key.trim().trim_start_matches('/');
+    if key.is_empty() {
+        return Err("an object key is required".into());
+    }
+
+    let keys = keys(&app, &options).await?;
+    let pick = |given: &Option<String>, stored: &str| {
+        given
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .unwrap_or(stored)
+            .to_string()
+    };
+    let bucket = pick(&options.bucket, &keys.bucket);
+    if bucket.is_empty() {
+        return Err("a bucket is required".into());
+    }
+    let endpoint = match options.endpoint.as_deref().map(str::trim) {
+        Some(endpoint) if !endpoint.is_empty() => Some(endpoint.to_string()),
+        _ => keys.endpoint.clone(),
+    };
+    let bucket = Bucket::new(
+        endpoint.as_deref(),
+        &pick(&options.region, &keys.region),
+        &bucket,
+        "",
+        options.path_style.unwrap_or(keys.path_style),
+        &keys.access_key_id,
+        &keys.secret_access_key,
+    )?;
+    let url = bucket.presign(&method, key, expires, Some(keys.session_token.as_str()));
+    let name = key.rsplit('/').next().unwrap_or(key);
+    Ok(PresignedUrl {
+        request: json!({
+            "name": format!("{method} {name} (presigned)"),
+            "method": method,
+            "url": url,
+            "headers": {},
+            "query_params": [],
+            "auth_type": "none",
+            "body_mode": "raw",
+            "body": null,
+        }),
+        method,
+        url,
+        expires_at_ms: crate::now_ms() + expires * 1000,
+    })
+}
+
+/// Presigns and sends in one step, through `send` so the exchange lands in history. A
+/// `PUT` uploads the file at `body_file` when given.
+#[tauri::command]
+pub async fn send_presigned(
+    app: tauri::AppHandle,
+    collection_id: String,
+    options: PresignOptions,
+    body_file: Option<String>,
+    environment_id: Option<String>,
+) -> Result<Value, String> {
+    let presigned = presign_s3_url(app.clone(), options).await?;
+    let mut request = presigned.request;
+    if let Some(path) = body_file
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        if presigned.method != "PUT" {
+            return Err("only a presigned PUT takes a body".into());
+        }
+        request["body_mode"] = json!(crate::upload::FILE_MODE);
+        request["binary"] = json!({ "file_path": path });
+    }
+    Ok(
+        crate::send::dispatch(app, collection_id, request, environment_id)
+            .await?
+            .result,
+    )
+}
@@ -0,0 +1,267 @@
+//! A quick "is it the network or the API" check: resolve a host, open a TCP connection to it
+//! and, for TLS ports, complete a handshake, timing each step. The handshake goes through even
+//! when the certificate isn't trusted, so the negotiated version, cipher suite and ALPN
+//! protocol are still reported next to the reason the certificate was rejected.
+
+use serde::Serialize;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::TcpStream;
+use tokio_rustls::rustls;
+use tokio_rustls::TlsConnector;
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_ALPN: &[&str] = &["h2", "http/1.1"];
+
+#[derive(Serialize)]
+pub struct TlsProbe {
+    /// `TLS 1.3` or `TLS 1.2`.
+    pub version: Option<String>,
+    pub cipher_suite: Option<String>,
+    /// The protocol the server picked from those offered; `None` when it ignored ALPN.
+    pub alpn: Option<String>,
+    pub handshake_ms: f64,
+    pub certificate_trusted: bool,
+    pub certificate_error: Option<String>,
+    /// Certificates the server sent, leaf first.
+    pub certificates: usize,
+}
+
+#[derive(Serialize, Default)]
+pub struct EndpointProbe {
+    pub host: String,
+    pub port: u16,
+    /// Every address the host resolved to.
+    pub addresses: Vec<String>,
+    pub resolve_ms: Option<f64>,
+    /// A TCP connection was opened.
+    pub reachable: bool,
+    /// The address connected to.
+    pub address: Option<String>,
+    pub connect_ms: Option<f64>,
+    pub tls: Option<TlsProbe>,
+    /// Why the probe stopped early, naming the step: resolve, connect or TLS handshake.
+    pub error: Option<String>,
+}
+
+/// Checks the certificate as the platform would, but only remembers a failure so the
+/// handshake can finish and be described.
+#[derive(Debug)]
+struct RecordingVerifier {
+    inner: Option<Arc<WebPkiServerVerifier>>,
+    provider: Arc<rustls::crypto::CryptoProvider>,
+    error: Mutex<Option<String>>,
+}
+
+impl RecordingVerifier {
+    fn new(provider: Arc<rustls::crypto::CryptoProvider>) -> Self {
+        let mut roots = rustls::RootCertStore::empty();
+        for cert in rustls_native_certs::load_native_certs().certs {
+            let _ = roots.add(cert);
+        }
+        let inner = WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider.clone())
+            .build()
+            .ok();
+        Self {
+            inner,
+            provider,
+            error: Mutex::new(None),
+        }
+    }
+
+    fn record(&self, error: String) {
+        if let Ok(mut slot) = self.error.lock() {
+            slot.get_or_insert(error);
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        self.error.lock().ok().and_then(|e| e.clone())
+    }
+}
+
+impl ServerCertVerifier for RecordingVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        match &self.inner {
+            Some(inner) => {
+                if let Err(e) = inner.verify_server_cert(
+                    end_entity,
+                    intermediates,
+                    server_name,
+                    ocsp_response,
+                    now,
+                ) {
+                    self.record(e.to_string());
+                }
+            }
+            None => self.record("no trusted root certificates found on this system".into()),
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+fn millis(since: Instant) -> f64 {
+    since.elapsed().as_secs_f64() * 1000.0
+}
+
+fn version_name(version: rustls::ProtocolVersion) -> String {
+    match version {
+        rustls::ProtocolVersion::TLSv1_3 => "TLS 1.3".to_string(),
+        rustls::ProtocolVersion::TLSv1_2 => "TLS 1.2".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+async fn handshake(stream: TcpStream, host: &str, alpn: &[String]) -> Result<TlsProbe, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let verifier = Arc::new(RecordingVerifier::new(provider.clone()));
+    let mut config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS setup failed: {e}"))?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier.clone())
+        .with_no_client_auth();
+    config.alpn_protocols = alpn.iter().map(|p| p.as_bytes().to_vec()).collect();
+    let name = ServerName::try_from(host.to_string())
+        .map_err(|e| format!("invalid TLS server name {host}: {e}"))?;
+
+    let started = Instant::now();
+    let tls = tokio::time::timeout(
+        TIMEOUT,
+        TlsConnector::from(Arc::new(config)).connect(name, stream),
+    )
+    .await
+    .map_err(|_| "TLS handshake timed out".to_string())?
+    .map_err(|e| format!("TLS handshake failed: {e}"))?;
+    let handshake_ms = millis(started);
+    let (_, session) = tls.get_ref();
+    let certificate_error = verifier.error();
+    Ok(TlsProbe {
+        version: session.protocol_version().map(version_name),
+        cipher_suite: session
+            .negotiated_cipher_suite()
+            .map(|s| format!("{:?}", s.suite())),
+        alpn: session
+            .alpn_protocol()
+            .map(|p| String::from_utf8_lossy(p).into_owned()),
+        handshake_ms,
+        certificate_trusted: certificate_error.is_none(),
+        certificate_error,
+        certificates: session.peer_certificates().map_or(0, <[_]>::len),
+    })
+}
+
+/// Resolves `host` (a name, address or URL), connects to `port` and, unless `tls` is
+/// `false`, shakes hands offering `alpn` (`h2` and `http/1.1` by default). Failures are
+/// reported in the probe rather than as an error, with the steps that did succeed.
+#[tauri::command]
+pub async fn probe_endpoint(
+    host: String,
+    port: Option<u16>,
+    tls: Option<bool>,
+    alpn: Option<Vec<String>>,
+) -> Result<EndpointProbe, String> {
+    let port = port.unwrap_or(443);
+    let host = crate::dns::host_of(&host);
+    if host.is_empty() {
+        return Err("a host is required".to_string());
+    }
+    let mut probe = EndpointProbe {
+        host: host.clone(),
+        port,
+        ..EndpointProbe::default()
+    };
+
+    let started = Instant::now();
+    let addresses: Vec<SocketAddr> =
+        match tokio::time::timeout(TIMEOUT, tokio::net::lookup_host((host.as_str(), port))).await {
+            Ok(Ok(addresses)) => addresses.collect(),
+            Ok(Err(e)) => {
+                probe.error = Some(format!("resolve failed: {e}"));
+                return Ok(probe);
+            }
+            Err(_) => {
+                probe.error = Some("resolve timed out".to_string());
+                return Ok(probe);
+            }
+        };
+    probe.resolve_ms = Some(millis(started));
+    probe.addresses = addresses.iter().map(|a| a.ip().to_string()).collect();
+
+    let mut last_error = "no addresses".to_string();
+    let mut connected = None;
+    for address in addresses {
+        let started = Instant::now();
+        match tokio::time::timeout(TIMEOUT, TcpStream::connect(address)).await {
+            Ok(Ok(stream)) => {
+                probe.connect_ms = Some(millis(started));
+                probe.address = Some(address.to_string());
+                connected = Some(stream);
+                break;
+            }
+            Ok(Err(e)) => last_error = format!("{address}: {e}"),
+            Err(_) => last_error = format!("{address}: timed out"),
+        }
+    }
+    let Some(stream) = connected else {
+        probe.error = Some(format!("connect failed: {last_error}"));
+        return Ok(probe);
+    };
+    probe.reachable = true;
+
+    if tls.unwrap_or(true) {
+        let alpn = alpn.unwrap_or_else(|| DEFAULT_ALPN.iter().map(|p| p.to_string()).collect());
+        match handshake(stream, &host, &alpn).await {
+            Ok(result) => probe.tls = Some(result),
+            Err(e) => probe.error = Some(e),
+        }
+    }
+    Ok(probe)
+}
@@ -0,0 +1,633 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use rcgen::{BasicConstraints, CertificateParams, DnType, IsCa, KeyPair, KeyUsagePurpose};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, State};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tokio_rustls::rustls;
+use tokio_rustls::TlsAcceptor;
+
+/// Exchanges `get_captured_traffic` returns; the persisted log keeps more (see `captures`).
+const CAPTURE_LIMIT: u64 = 2000;
+const BODY_CAPTURE_LIMIT: usize = 1024 * 1024;
+const HOP_BY_HOP: &[&str] = &[
+    "connection",
+    "proxy-connection",
+    "keep-alive",
+    "proxy-authorization",
+    "proxy-authenticate",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "host",
+    "content-length",
+];
+const COLLECTION_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS"];
+
+pub struct ProxyState {
+    running: Mutex<Option<RunningProxy>>,
+}
+
+impl ProxyState {
+    pub fn new() -> Self {
+        Self {
+            running: Mutex::new(None),
+        }
+    }
+}
+
+struct RunningProxy {
+    info: ProxyInfo,
+    shutdown: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct ProxyInfo {
+    port: u16,
+    address: String,
+    mitm: bool,
+    ca_cert_path: Option<String>,
+    started_at_ms: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CapturedExchange {
+    pub id: String,
+    pub started_at_ms: u64,
+    pub duration_ms: f64,
+    pub method: String,
+    pub url: String,
+    pub host: String,
+    pub request_headers: Vec<(String, String)>,
+    pub request_body: Option<String>,
+    pub request_body_base64: bool,
+    pub status: Option<u16>,
+    pub response_headers: Vec<(String, String)>,
+    pub response_body: Option<String>,
+    pub response_body_base64: bool,
+    /// Response body size as received; `response_body` stops at `BODY_CAPTURE_LIMIT`.
+    #[serde(default)]
+    pub response_bytes: u64,
+    /// True for CONNECT tunnels that were passed through without interception.
+    pub tunneled: bool,
+    pub error: Option<String>,
+}
+
+struct CertAuthority {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+    leaf_configs: RwLock<HashMap<String, Arc<rustls::ServerConfig>>>,
+}
+
+impl CertAuthority {
+    fn server_config(&self, host: &str) -> Result<Arc<rustls::ServerConfig>, String> {
+        if let Some(config) = self
+            .leaf_configs
+            .read()
+            .ok()
+            .and_then(|c| c.get(host).cloned())
+        {
+            return Ok(config);
+        }
+        let mut params = CertificateParams::new(vec![host.to_string()])
+            .map_err(|e| format!("leaf certificate for {host} failed: {e}"))?;
+        params.distinguished_name.push(DnType::CommonName, host);
+        let key = KeyPair::generate().map_err(|e| format!("leaf key generation failed: {e}"))?;
+        let cert = params
+            .signed_by(&key, &self.cert, &self.key)
+            .map_err(|e| format!("leaf certificate for {host} failed: {e}"))?;
+        let mut config = rustls::ServerConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("TLS setup failed: {e}"))?
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![cert.der().clone(), self.cert.der().clone()],
+            rustls::pki_types::PrivateKeyDer::Pkcs8(key.serialize_der().into()),
+        )
+        .map_err(|e| format!("TLS setup failed: {e}"))?;
+        config.alpn_protocols = vec![b"http/1.1".to_vec()];
+        let config = Arc::new(config);
+        if let Ok(mut cache) = self.leaf_configs.write() {
+            cache.insert(host.to_string(), config.clone());
+        }
+        Ok(config)
+    }
+}
+
+/// Writes the CA's private key readable by the current user only: anyone who can read it can
+/// mint certificates the system trusts.
+fn write_key(path: &Path, pem: &str) -> Result<(), String> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(pem.as_bytes()))
+        .map_err(|e| format!("proxy CA write failed: {e}"))
+}
+
+/// Loads the capture CA from app data, generating it on first use so the user only
+/// has to trust it once.
+fn load_or_create_ca(app: &tauri::AppHandle) -> Result<(CertAuthority, String), String> {
+    let mut dir = crate::app_data_root(app)?;
+    dir.push("proxy");
+    fs::create_dir_all(&dir).map_err(|e| format!("proxy CA init failed: {e}"))?;
+    let cert_path = dir.join("litefetch-ca.pem");
+    let key_path = dir.join("litefetch-ca.key");
+
+    let (params, key) = if cert_path.exists() && key_path.exists() {
+        let cert_pem =
+            fs::read_to_string(&cert_path).map_err(|e| format!("proxy CA read failed: {e}"))?;
+        let key_pem =
+            fs::read_to_string(&key_path).map_err(|e| format!("proxy CA read failed: {e}"))?;
+        // Keys written by earlier versions were readable by everyone.
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let _ = fs::set_permissions(&key_path, fs::Permissions::from_mode(0o600));
+        }
+        let key = KeyPair::from_pem(&key_pem).map_err(|e| format!("proxy CA key invalid: {e}"))?;
+        let params = CertificateParams::from_ca_cert_pem(&cert_pem)
+            .map_err(|e| format!("proxy CA certificate invalid: {e}"))?;
+        (params, key)
+    } else {
+        let mut params = CertificateParams::new(Vec::<String>::new())
+            .map_err(|e| format!("proxy CA generation failed: {e}"))?;
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        params
+            .distinguished_name
+            .push(DnType::CommonName, "LiteFetch Capture CA");
+        params
+            .distinguished_name
+            .push(DnType::OrganizationName, "LiteFetch");
+        params.key_usages = vec![
+            KeyUsagePurpose::KeyCertSign,
+            KeyUsagePurpose::CrlSign,
+            KeyUsagePurpose::DigitalSignature,
+        ];
+        let key = KeyPair::generate().map_err(|e| format!("proxy CA generation failed: {e}"))?;
+        let cert = params
+            .clone()
+            .self_signed(&key)
+            .map_err(|e| format!("proxy CA generation failed: {e}"))?;
+        fs::write(&cert_path, cert.pem()).map_err(|e| format!("proxy CA write failed: {e}"))?;
+        write_key(&key_path, &key.serialize_pem())?;
+        (params, key)
+    };
+    // Re-signing the stored parameters yields the same subject and public key, so leaves
+    // chain to the certificate the user already trusts.
+    let cert = params
+        .self_signed(&key)
+        .map_err(|e| format!("proxy CA load failed: {e}"))?;
+    Ok((
+        CertAuthority {
+            cert,
+            key,
+            leaf_configs: RwLock::new(HashMap::new()),
+        },
+        cert_path.to_string_lossy().to_string(),
+    ))
+}
+
+struct ProxyContext {
+    app: tauri::AppHandle,
+    client: reqwest::Client,
+    ca: Option<CertAuthority>,
+}
+
+impl ProxyContext {
+    fn record(&self, exchange: CapturedExchange) {
+        let app = self.app.clone();
+        let stored = exchange.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            if let Err(e) = crate::captures::record(&app, &stored) {
+                let _ = app.emit("proxy://error", e);
+            }
+        });
+        let _ = self.app.emit("proxy://exchange", exchange);
+    }
+}
+
+fn header_pairs(headers: &hyper::HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(k, v)| {
+            (
+                k.to_string(),
+                String::from_utf8_lossy(v.as_bytes()).to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Returns the captured body as text, or base64 when it is not UTF-8.
+fn capture_body(body: &[u8]) -> (Option<String>, bool) {
+    if body.is_empty() {
+        return (None, false);
+    }
+    let body = &body[..body.len().min(BODY_CAPTURE_LIMIT)];
+    match std::str::from_utf8(body) {
+        Ok(text) => (Some(text.to_string()), false),
+        Err(_) => (Some(STANDARD.encode(body)), true),
+    }
+}
+
+fn empty_response(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::new()));
+    *response.status_mut() = status;
+    response
+}
+
+/// Forwards one HTTP exchange upstream and records it. `authority` is set for requests
+/// decrypted from a CONNECT tunnel, whose URIs are origin-form.
+async fn forward(
+    context: Arc<ProxyContext>,
+    authority: Option<String>,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let started = Instant::now();
+    let started_at_ms = crate::now_ms();
+    let (parts, body) = request.into_parts();
+    let url = match &authority {
+        Some(authority) => format!(
+            "https://{authority}{}",
+            parts
+                .uri
+                .path_and_query()
+                .map(|p| p.as_str())
+                .unwrap_or("/")
+        ),
+        None => parts.uri.to_string(),
+    };
+    let host = reqwest::Url::parse(&url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    let body = body
+        .collect()
+        .await
+        .map(|b| b.to_bytes())
+        .unwrap_or_default();
+    let (request_body, request_body_base64) = capture_body(&body);
+
+    let mut exchange = CapturedExchange {
+        id: uuid::Uuid::new_v4().to_string(),
+        started_at_ms,
+        duration_ms: 0.0,
+        method: parts.method.to_string(),
+        url: url.clone(),
+        host,
+        request_headers: header_pairs(&parts.headers),
+        request_body,
+        request_body_base64,
+        status: None,
+        response_headers: Vec::new(),
+        response_body: None,
+        response_body_base64: false,
+        response_bytes: 0,
+        tunneled: false,
+        error: None,
+    };
+
+    let mut upstream = context.client.request(parts.method.clone(), &url);
+    for (key, value) in parts.headers.iter() {
+        if !HOP_BY_HOP.contains(&key.as_str()) {
+            upstream = upstream.header(key, value);
+        }
+    }
+    let result = async {
+        let response = upstream.body(body).send().await?;
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.bytes().await?;
+        Ok::<_, reqwest::Error>((status, headers, bytes))
+    }
+    .await;
+
+    let response = match result {
+        Ok((status, headers, bytes)) => {
+            exchange.status = Some(status.as_u16());
+            exchange.response_headers = header_pairs(&headers);
+            let (response_body, base64) = capture_body(&bytes);
+            exchange.response_body = response_body;
+            exchange.response_body_base64 = base64;
+            exchange.response_bytes = bytes.len() as u64;
+            let mut response = Response::new(Full::new(bytes));
+            *response.status_mut() = status;
+            for (key, value) in headers.iter() {
+                if !HOP_BY_HOP.contains(&key.as_str()) {
+                    response.headers_mut().append(key, value.clone());
+                }
+            }
+            response
+        }
+        Err(e) => {
+            exchange.error = Some(e.to_string());
+            empty_response(StatusCode::BAD_GATEWAY)
+        }
+    };
+    exchange.duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+    context.record(exchange);
+    Ok(response)
+}
+
+async fn intercept(
+    context: Arc<ProxyContext>,
+    authority: String,
+    upgraded: hyper::upgrade::Upgraded,
+) {
+    let host = authority.split(':').next().unwrap_or_default().to_string();
+    let Some(ca) = context.ca.as_ref() else {
+        return;
+    };
+    let config = match ca.server_config(&host) {
+        Ok(config) => config,
+        Err(e) => {
+            let _ = context.app.emit("proxy://error", e);
+            return;
+        }
+    };
+    let Ok(tls) = TlsAcceptor::from(config)
+        .accept(TokioIo::new(upgraded))
+        .await
+    else {
+        // Client rejected the capture CA; nothing more to record for this tunnel.
+        return;
+    };
+    let inner = context.clone();
+    let service =
+        hyper::service::service_fn(move |req| forward(inner.clone(), Some(authority.clone()), req));
+    let _ = hyper::server::conn::http1::Builder::new()
+        .serve_connection(TokioIo::new(tls), service)
+        .await;
+}
+
+async fn passthrough(
+    context: Arc<ProxyContext>,
+    authority: String,
+    upgraded: hyper::upgrade::Upgraded,
+) {
+    let started = Instant::now();
+    let started_at_ms = crate::now_ms();
+    let outcome = async {
+        let mut upstream = TcpStream::connect(&authority).await?;
+        tokio::io::copy_bidirectional(&mut TokioIo::new(upgraded), &mut upstream).await
+    }
+    .await;
+    context.record(CapturedExchange {
+        id: uuid::Uuid::new_v4().to_string(),
+        started_at_ms,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        method: "CONNECT".to_string(),
+        url: authority.clone(),
+        host: authority.split(':').next().unwrap_or_default().to_string(),
+        request_headers: Vec::new(),
+        request_body: None,
+        request_body_base64: false,
+        status: outcome.as_ref().ok().map(|_| 200),
+        response_headers: Vec::new(),
+        response_body: None,
+        response_body_base64: false,
+        response_bytes: 0,
+        tunneled: true,
+        error: outcome.err().map(|e| e.to_string()),
+    });
+}
+
+async fn handle(
+    context: Arc<ProxyContext>,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    if request.method() != Method::CONNECT {
+        if request.uri().authority().is_none() {
+            // Origin-form requests mean a client hit the proxy directly rather than through it.
+            return Ok(empty_response(StatusCode::BAD_REQUEST));
+        }
+        return forward(context, None, request).await;
+    }
+    let Some(authority) = request.uri().authority().map(|a| a.to_string()) else {
+        return Ok(empty_response(StatusCode::BAD_REQUEST));
+    };
+    tauri::async_runtime::spawn(async move {
+        let Ok(upgraded) = hyper::upgrade::on(request).await else {
+            return;
+        };
+        if context.ca.is_some() {
+            intercept(context, authority, upgraded).await;
+        } else {
+            passthrough(context, authority, upgraded).await;
+        }
+    });
+    Ok(empty_response(StatusCode::OK))
+}
+
+async fn serve(
+    context: Arc<ProxyContext>,
+    listener: TcpListener,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else { continue };
+                let context = context.clone();
+                tauri::async_runtime::spawn(async move {
+                    let service = hyper::service::service_fn(move |req| handle(context.clone(), req));
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .preserve_header_case(true)
+                        .serve_connection(TokioIo::new(stream), service)
+                        .with_upgrades()
+                        .await;
+                });
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn start_capture_proxy(
+    app: tauri::AppHandle,
+    state: State<'_, ProxyState>,
+    port: Option<u16>,
+    mitm: Option<bool>,
+) -> Result<ProxyInfo, String> {
+    let mut running = state.running.lock().await;
+    if let Some(proxy) = running.as_ref() {
+        let info = &proxy.info;
+        let other_port = port.is_some_and(|port| port != 0 && port != info.port);
+        let other_mitm = mitm.is_some_and(|mitm| mitm != info.mitm);
+        if other_port || other_mitm {
+            return Err(format!(
+                "capture proxy is already running on port {} with HTTPS interception {}; stop it first",
+                info.port,
+                if info.mitm { "on" } else { "off" }
+            ));
+        }
+        return Ok(info.clone());
+    }
+    let mitm = mitm.unwrap_or(false);
+    let (ca, ca_cert_path) = if mitm {
+        let (ca, path) = load_or_create_ca(&app)?;
+        (Some(ca), Some(path))
+    } else {
+        (None, None)
+    };
+    let listener = TcpListener::bind(("127.0.0.1", port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("capture proxy bind failed: {e}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("capture proxy bind failed: {e}"))?
+        .port();
+    let client = reqwest::Client::builder()
+        .no_proxy()
+        .redirect(reqwest::redirect::Policy::none())
+        .build()
+        .map_err(|e| format!("capture proxy client setup failed: {e}"))?;
+
+    let context = Arc::new(ProxyContext { app, client, ca });
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let task = tauri::async_runtime::spawn(serve(context, listener, shutdown_rx));
+    let info = ProxyInfo {
+        port,
+        address: format!("127.0.0.1:{port}"),
+        mitm,
+        ca_cert_path,
+        started_at_ms: crate::now_ms(),
+    };
+    *running = Some(RunningProxy {
+        info: info.clone(),
+        shutdown,
+        task,
+    });
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_capture_proxy(state: State<'_, ProxyState>) -> Result<(), String> {
+    if let Some(proxy) = state.running.lock().await.take() {
+        if proxy.shutdown.send(()).is_err() {
+            proxy.task.abort();
+        }
+    }
+    Ok(())
+}
+
+/// The newest captures, oldest first; `clear` empties the log afterwards.
+#[tauri::command]
+pub async fn get_captured_traffic(
+    app: tauri::AppHandle,
+    clear: Option<bool>,
+) -> Result<Vec<CapturedExchange>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let exchanges = crate::captures::load(&app, None, CAPTURE_LIMIT)?;
+        if clear.unwrap_or(false) {
+            crate::captures::clear(&app, None)?;
+        }
+        Ok(exchanges)
+    })
+    .await
+    .map_err(|e| format!("capture log read failed: {e}"))?
+}
+
+#[derive(Deserialize)]
+pub struct CaptureExport {
+    name: String,
+    #[serde(default)]
+    exchange_ids: Option<Vec<String>>,
+}
+
+fn exchange_to_request(exchange: &CapturedExchange) -> Value {
+    let headers: HashMap<&str, &str> = exchange
+        .request_headers
+        .iter()
+        .filter(|(k, _)| !HOP_BY_HOP.contains(&k.as_str()) && k != "accept-encoding")
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    let is_json = headers
+        .iter()
+        .any(|(k, v)| k.eq_ignore_ascii_case("content-type") && v.contains("json"));
+    let body = match (&exchange.request_body, exchange.request_body_base64) {
+        (Some(text), false) => Some(text.clone()),
+        _ => None,
+    };
+    let path = reqwest::Url::parse(&exchange.url)
+        .map(|u| u.path().to_string())
+        .unwrap_or_else(|_| exchange.url.clone());
+    json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "name": format!("{} {}", exchange.method, path),
+        "method": exchange.method,
+        "url": exchange.url,
+        "headers": headers,
+        "body": body,
+        "body_mode": if is_json { "json" } else { "raw" },
+    })
+}
+
+/// Converts captured calls (the selected ones, or the newest `CAPTURE_LIMIT`) into a new
+/// collection with one folder per host.
+#[tauri::command]
+pub async fn capture_to_collection(
+    app: tauri::AppHandle,
+    export: CaptureExport,
+) -> Result<Value, String> {
+    let reader = app.clone();
+    let ids = export.exchange_ids.clone();
+    let exchanges: Vec<CapturedExchange> = tauri::async_runtime::spawn_blocking(move || {
+        crate::captures::load(&reader, ids.as_deref(), CAPTURE_LIMIT)
+    })
+    .await
+    .map_err(|e| format!("capture log read failed: {e}"))??
+    .into_iter()
+    .filter(|e| !e.tunneled && COLLECTION_METHODS.contains(&e.method.as_str()))
+    .collect();
+    if exchanges.is_empty() {
+        return Err("no captured requests to convert".to_string());
+    }
+    let items = crate::group_into_folders(
+        exchanges
+            .iter()
+            .map(|e| (e.host.clone(), exchange_to_request(e)))
+            .collect(),
+    );
+    let collection = json!({ "name": export.name, "items": items });
+    crate::create_collection(&app, &export.name, collection).await
+}
+
+#[tauri::command]
+pub async fn capture_proxy_status(
+    state: State<'_, ProxyState>,
+) -> Result<Option<ProxyInfo>, String> {
+    Ok(state.running.lock().await.as_ref().map(|p| p.info.clone()))
+}
+
+/// Exposes the capture CA path (creating the CA if needed) so the UI can guide trust setup.
+#[tauri::command]
+pub async fn capture_ca_certificate(app: tauri::AppHandle) -> Result<String, String> {
+    load_or_create_ca(&app).map(|(_, path)| path)
+}
@@ -0,0 +1,314 @@
+//! Masking of secret values in everything that leaves the app: forwarded backend logs,
+//! HAR and collection exports, and generated snippets. A value counts as secret when it
+//! comes from a secret-flagged environment variable or a request field marked secret.
+//! Exports include secrets only with a single-use grant from `allow_secret_export`, which
+//! the UI requests after the user confirms.
+//!
+//! Values that look like credentials without being flagged (see `litefetch_core::leaks`)
+//! stop an export until each has been reviewed with `review_export_leaks`: kept as is, or
+//! redacted like a secret.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeSet, HashMap};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::Mutex;
+use tauri::{Manager, State};
+
+use crate::importers::{array_of, str_of, value_text};
+
+use litefetch_core::leaks::{self, Leak};
+pub use litefetch_core::redact::{log_line, remember, MASK};
+use litefetch_core::redact::{mask_all, MIN_SECRET_LEN};
+
+const GRANT_TTL: Duration = Duration::from_secs(60);
+/// How long a review of found credentials holds, for the exports that follow it.
+const REVIEW_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Open grants by token, each bound to one collection, and leak reviews by collection.
+pub struct RedactState {
+    grants: Mutex<HashMap<String, (String, Instant)>>,
+    reviews: Mutex<HashMap<String, (Decisions, Instant)>>,
+}
+
+/// Leak ids and what to do with each.
+type Decisions = HashMap<String, LeakDecision>;
+
+impl RedactState {
+    pub fn new() -> Self {
+        Self {
+            grants: Mutex::new(HashMap::new()),
+            reviews: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LeakDecision {
+    /// Exported as is; the user confirmed it isn't a live secret.
+    Keep,
+    Redact,
+}
+
+#[derive(Serialize)]
+pub struct ReviewedLeak {
+    #[serde(flatten)]
+    pub leak: Leak,
+    pub decision: Option<LeakDecision>,
+}
+
+#[derive(Default)]
+pub struct Redactor {
+    /// Longest first, so a secret containing another is masked whole.
+    values: Vec<String>,
+}
+
+impl Redactor {
+    /// A redactor that masks nothing, for confirmed exports.
+    pub fn disabled() -> Self {
+        Self::default()
+    }
+
+    fn add(&mut self, value: &str) {
+        // Template references such as `{{token}}` name a variable rather than hold a value.
+        if value.chars().count() < MIN_SECRET_LEN || value.contains("{{") {
+            return;
+        }
+        if !self.values.iter().any(|v| v == value) {
+            remember(value);
+            self.values.push(value.to_string());
+        }
+    }
+
+    fn add_request(&mut self, request: &Value) {
+        let flagged = |field: &str, key: &str| {
+            request.get(field).and_then(|f| f.get(key)) == Some(&Value::Bool(true))
+        };
+        for (key, value) in request
+            .get("headers")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            if flagged("secret_headers", key) {
+                self.add(&value_text(Some(value)));
+            }
+        }
+        for (field, rows) in [
+            ("secret_query_params", "query_params"),
+            ("secret_form_fields", "form_body"),
+        ] {
+            for row in array_of(request, rows) {
+                if flagged(field, str_of(row, "key"))
+                    || row.get("secret") == Some(&Value::Bool(true))
+                {
+                    self.add(&value_text(row.get("value")));
+                }
+            }
+        }
+        for (key, value) in request
+            .get("auth_params")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            if flagged("secret_auth_params", key) {
+                self.add(&value_text(Some(value)));
+            }
+        }
+        if request.get("secret_body") == Some(&Value::Bool(true)) {
+            match request.get("body") {
+                Some(Value::String(text)) => self.add(text),
+                Some(Value::Null) | None => {}
+                Some(other) => self.add_strings(other),
+            }
+        }
+    }
+
+    fn add_strings(&mut self, value: &Value) {
+        match value {
+            Value::String(text) => self.add(text),
+            Value::Array(items) => items.iter().for_each(|v| self.add_strings(v)),
+            Value::Object(map) => map.values().for_each(|v| self.add_strings(v)),
+            _ => {}
+        }
+    }
+
+    fn add_items(&mut self, items: &[Value]) {
+        for item in items {
+            match item.get("items").and_then(Value::as_array) {
+                Some(children) => self.add_items(children),
+                None => self.add_request(item),
+            }
+        }
+    }
+
+    /// Collects the secrets of every environment of a collection and of its requests.
+    pub fn for_collection(
+        collection_id: &str,
+        collection: &Value,
+        environment: &Value,
+    ) -> Result<Self, String> {
+        let mut redactor = Self::default();
+        for name in environment
+            .get("envs")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flat_map(|envs| envs.keys())
+        {
+            for variable in crate::secrets::resolve_environment(collection_id, environment, name)? {
+                if variable.secret {
+                    redactor.add(&variable.value);
+                }
+            }
+        }
+        redactor.add_items(array_of(collection, "items"));
+        redactor.values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+        Ok(redactor)
+    }
+
+    fn redacts(&self, value: &str) -> bool {
+        self.values.iter().any(|v| v == value)
+    }
+
+    pub fn text(&self, text: &str) -> String {
+        mask_all(text, &self.values)
+    }
+
+    /// Masks secrets inside every string of a JSON document, keys included.
+    pub fn value(&self, value: &mut Value) {
+        if self.values.is_empty() {
+            return;
+        }
+        match value {
+            Value::String(text) => *text = self.text(text),
+            Value::Array(items) => items.iter_mut().for_each(|v| self.value(v)),
+            Value::Object(map) => {
+                let entries = std::mem::take(map);
+                for (key, mut inner) in entries {
+                    self.value(&mut inner);
+                    map.insert(self.text(&key), inner);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Credential-looking values of a collection that `flagged` doesn't already mask.
+fn unflagged_leaks(flagged: &Redactor, collection: &Value, environment: &Value) -> Vec<Leak> {
+    leaks::scan(collection, environment)
+        .into_iter()
+        .filter(|leak| !flagged.redacts(&leak.value))
+        .collect()
+}
+
+/// The redactor for an export of `collection_id`. With a valid `grant` flagged secrets
+/// aren't masked; the grant is used up either way. Unflagged credentials must have been
+/// reviewed: those to redact are masked, and any left undecided stop the export.
+pub async fn for_export(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    collection: &Value,
+    environment: &Value,
+    grant: Option<&str>,
+) -> Result<Redactor, String> {
+    let state = app.state::<RedactState>();
+    let flagged = Redactor::for_collection(collection_id, collection, environment)?;
+    let found = unflagged_leaks(&flagged, collection, environment);
+    let mut redactor = match grant {
+        Some(token) => {
+            let mut grants = state.grants.lock().await;
+            grants.retain(|_, (_, issued)| issued.elapsed() < GRANT_TTL);
+            match grants.remove(token) {
+                Some((granted, _)) if granted == collection_id => Redactor::disabled(),
+                _ => return Err("secret export confirmation is invalid or expired".to_string()),
+            }
+        }
+        None => flagged,
+    };
+
+    if found.is_empty() {
+        return Ok(redactor);
+    }
+    let mut reviews = state.reviews.lock().await;
+    reviews.retain(|_, (_, reviewed)| reviewed.elapsed() < REVIEW_TTL);
+    let decisions = reviews.get(collection_id).map(|(d, _)| d);
+    let mut undecided = 0;
+    let mut labels = BTreeSet::new();
+    for leak in found {
+        match decisions.and_then(|d| d.get(&leak.id)) {
+            Some(LeakDecision::Redact) => redactor.add(&leak.value),
+            Some(LeakDecision::Keep) => {}
+            None => {
+                undecided += 1;
+                labels.insert(leak.label);
+            }
+        }
+    }
+    if undecided > 0 {
+        let labels: Vec<_> = labels.into_iter().collect();
+        return Err(format!(
+            "export stopped: {undecided} possible secret(s) found ({}); review them before exporting",
+            labels.join(", ")
+        ));
+    }
+    redactor.values.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    Ok(redactor)
+}
+
+/// Values in a collection and its environments that look like credentials (AWS keys, JWTs,
+/// bearer tokens, private keys and the like) but aren't flagged secret, with the decision
+/// of the current review, if any.
+#[tauri::command]
+pub async fn scan_export_leaks(
+    app: tauri::AppHandle,
+    state: State<'_, RedactState>,
+    collection_id: String,
+) -> Result<Vec<ReviewedLeak>, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    let flagged = Redactor::for_collection(&collection_id, &collection, &environment)?;
+    let found = unflagged_leaks(&flagged, &collection, &environment);
+    let mut reviews = state.reviews.lock().await;
+    reviews.retain(|_, (_, reviewed)| reviewed.elapsed() < REVIEW_TTL);
+    let decisions = reviews.get(&collection_id).map(|(d, _)| d);
+    Ok(found
+        .into_iter()
+        .map(|leak| ReviewedLeak {
+            decision: decisions.and_then(|d| d.get(&leak.id)).copied(),
+            leak,
+        })
+        .collect())
+}
+
+/// Records whether each found credential (by id from `scan_export_leaks`) is kept or
+/// redacted in exports of `collection_id` for the next ten minutes. Call it only with the
+/// user's choices.
+#[tauri::command]
+pub async fn review_export_leaks(
+    state: State<'_, RedactState>,
+    collection_id: String,
+    decisions: Decisions,
+) -> Result<(), String> {
+    let mut reviews = state.reviews.lock().await;
+    reviews.insert(collection_id, (decisions, Instant::now()));
+    Ok(())
+}
+
+/// Issues a single-use token that lets the next export of `collection_id` include secret
+/// values. Call it only after the user has confirmed; it expires after a minute.
+#[tauri::command]
+pub async fn allow_secret_export(
+    state: State<'_, RedactState>,
+    collection_id: String,
+) -> Result<String, String> {
+    let token = uuid::Uuid::new_v4().to_string();
+    let mut grants = state.grants.lock().await;
+    grants.retain(|_, (_, issued)| issued.elapsed() < GRANT_TTL);
+    grants.insert(token.clone(), (collection_id, Instant::now()));
+    Ok(token)
+}
@@ -0,0 +1,162 @@
+//! How the webview renders on Linux, where WebKitGTK's GPU paths break on some setups (no
+//! DRM device, as in VMs and containers, or the proprietary NVIDIA driver). The mode is a
+//! setting in the app data root, read in `main` before the builder runs since the
+//! environment variables only take effect at webview start; `auto` keeps the GPU unless the
+//! machine looks like one of those setups. `--force-software-rendering` overrides the
+//! setting for one launch, e.g. when the window comes up blank.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+const SETTINGS_FILE: &str = "rendering.json";
+pub const FORCE_SOFTWARE_FLAG: &str = "--force-software-rendering";
+/// Variables that make WebKitGTK render without the GPU.
+const SOFTWARE_ENV: &[&str] = &[
+    "LIBGL_ALWAYS_SOFTWARE",
+    "WEBKIT_DISABLE_COMPOSITING_MODE",
+    "WEBKIT_DISABLE_DMABUF_RENDERER",
+];
+
+/// What `main` applied at startup.
+static APPLIED: OnceLock<Rendering> = OnceLock::new();
+
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderingMode {
+    #[default]
+    Auto,
+    Gpu,
+    Software,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct RenderingSettings {
+    pub mode: RenderingMode,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Effective {
+    Gpu,
+    /// GPU compositing, without the DMA-BUF renderer that fails on NVIDIA's driver.
+    GpuWithoutDmabuf,
+    Software,
+}
+
+#[derive(Serialize, Clone)]
+pub struct Rendering {
+    /// The saved setting; a change applies at the next launch.
+    pub mode: RenderingMode,
+    /// What this launch uses.
+    pub effective: Effective,
+    /// Whether `--force-software-rendering` was passed.
+    pub forced: bool,
+    /// Why `auto` chose what it did, or that the environment already decided.
+    pub reason: String,
+}
+
+fn load(path: &Path) -> RenderingSettings {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Picks the renderer for a machine: software without a DRM render node, and no DMA-BUF
+/// with the NVIDIA driver.
+fn detect() -> (Effective, String) {
+    if !cfg!(target_os = "linux") {
+        return (Effective::Gpu, "not Linux".to_string());
+    }
+    let render_node = fs::read_dir("/dev/dri").ok().is_some_and(|entries| {
+        entries
+            .flatten()
+            .any(|e| e.file_name().to_string_lossy().starts_with("renderD"))
+    });
+    if !render_node {
+        return (
+            Effective::Software,
+            "no GPU render node in /dev/dri".to_string(),
+        );
+    }
+    if Path::new("/proc/driver/nvidia/version").exists() {
+        return (
+            Effective::GpuWithoutDmabuf,
+            "NVIDIA driver; DMA-BUF renderer disabled".to_string(),
+        );
+    }
+    (Effective::Gpu, "GPU render node found".to_string())
+}
+
+/// Reads the setting from `settings_dir` and the command line and sets the webview's
+/// environment to match. Variables already set by the user are left as they are.
+pub fn apply(settings_dir: Option<PathBuf>) {
+    let mode = settings_dir
+        .map(|dir| load(&dir.join(SETTINGS_FILE)).mode)
+        .unwrap_or_default();
+    let forced = std::env::args().any(|arg| arg == FORCE_SOFTWARE_FLAG);
+    let set = |key: &str| std::env::var_os(key).is_some();
+    let preset = SOFTWARE_ENV.iter().any(|key| set(key));
+    let (effective, reason) = if forced {
+        (Effective::Software, format!("{FORCE_SOFTWARE_FLAG} passed"))
+    } else if set("LIBGL_ALWAYS_SOFTWARE") {
+        (Effective::Software, "set by the environment".to_string())
+    } else if preset {
+        (
+            Effective::GpuWithoutDmabuf,
+            "set by the environment".to_string(),
+        )
+    } else {
+        match mode {
+            RenderingMode::Auto => detect(),
+            RenderingMode::Gpu => (Effective::Gpu, "set to GPU".to_string()),
+            RenderingMode::Software => (Effective::Software, "set to software".to_string()),
+        }
+    };
+    let keys: &[&str] = match effective {
+        Effective::Gpu => &[],
+        Effective::GpuWithoutDmabuf => &["WEBKIT_DISABLE_DMABUF_RENDERER"],
+        Effective::Software => SOFTWARE_ENV,
+    };
+    if forced || !preset {
+        for key in keys {
+            std::env::set_var(key, "1");
+        }
+    }
+    let _ = APPLIED.set(Rendering {
+        mode,
+        effective,
+        forced,
+        reason,
+    });
+}
+
+/// The app data root, before there is an app to ask; see `crate::app_data_root`.
+pub fn settings_dir(identifier: &str) -> Option<PathBuf> {
+    let base = litefetch_core::portable::base(|| {
+        dirs::data_dir()
+            .map(|dir| dir.join(identifier))
+            .ok_or("unable to resolve app data dir".to_string())
+    });
+    Some(litefetch_core::profile::data_root(&base.ok()?))
+}
+
+#[tauri::command]
+pub async fn get_rendering(app: tauri::AppHandle) -> Result<Rendering, String> {
+    let saved = load(&crate::app_data_root(&app)?.join(SETTINGS_FILE));
+    let mut rendering = APPLIED.get().cloned().ok_or("rendering not configured")?;
+    rendering.mode = saved.mode;
+    Ok(rendering)
+}
+
+/// Saves the rendering mode; it takes effect the next time the app starts.
+#[tauri::command]
+pub async fn set_rendering_mode(app: tauri::AppHandle, mode: RenderingMode) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(&RenderingSettings { mode })
+        .map_err(|e| format!("rendering settings serialize failed: {e}"))?;
+    fs::write(crate::app_data_root(&app)?.join(SETTINGS_FILE), payload)
+        .map_err(|e| format!("rendering settings persist failed: {e}"))
+}
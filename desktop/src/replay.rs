@@ -0,0 +1,105 @@
+//! Time-travel replays of history entries: `exact` sends the request the entry kept exactly
+//! as it went out, with the values it was resolved with back then; `resolve` takes the
+//! request as written at the time and sends it through `send` again, resolved against
+//! today's environment (scripts included). The replay's own entry points back at the
+//! original (`replay_of`), so the two responses can be compared side by side.
+//!
+//! History masks known secret values, so a request sent with any can't be replayed exactly;
+//! replaying it re-resolved picks the secrets up again from their variables.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::history::HistoryEntry;
+use crate::redact::MASK;
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplayMode {
+    #[default]
+    Exact,
+    Resolve,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ReplayOptions {
+    pub mode: ReplayMode,
+    /// For `resolve`: the environment to resolve in, the active one by default.
+    pub environment_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct Replay {
+    pub mode: ReplayMode,
+    pub original: HistoryEntry,
+    /// The replay's history entry.
+    pub replay: Option<HistoryEntry>,
+    /// The backend `RequestResult` of the replay.
+    pub result: Value,
+}
+
+/// Sends history entry `id` again and links the new entry to it.
+#[tauri::command]
+pub async fn replay_history_entry(
+    app: tauri::AppHandle,
+    id: i64,
+    options: Option<ReplayOptions>,
+) -> Result<Replay, String> {
+    let options = options.unwrap_or_default();
+    let kept_app = app.clone();
+    let (original, sent, template) =
+        tauri::async_runtime::spawn_blocking(move || crate::history::kept_requests(&kept_app, id))
+            .await
+            .map_err(|e| format!("history read failed: {e}"))??;
+    let request = match options.mode {
+        ReplayMode::Exact => sent,
+        ReplayMode::Resolve => template,
+    }
+    .ok_or("this entry has no request to replay: it predates replays or its body was too large")?;
+    if request.to_string().contains(MASK) {
+        return Err(match options.mode {
+            ReplayMode::Exact => {
+                "the request was sent with secret values, which history masks; replay it re-resolved instead"
+            }
+            ReplayMode::Resolve => {
+                "the request has secret values written into it, which history masks"
+            }
+        }
+        .to_string());
+    }
+
+    let mut result = match options.mode {
+        ReplayMode::Exact => {
+            crate::send::run(
+                &app,
+                &original.collection_id,
+                &request,
+                &request,
+                &Map::new(),
+            )
+            .await?
+        }
+        ReplayMode::Resolve => {
+            crate::send::dispatch(
+                app.clone(),
+                original.collection_id.clone(),
+                request,
+                options.environment_id,
+            )
+            .await?
+            .result
+        }
+    };
+    let replay = match result.get("response_id").and_then(Value::as_str) {
+        Some(response_id) => crate::history::link_replay(&app, response_id, id)?,
+        None => None,
+    };
+    crate::responses::limit(&app, &mut result);
+    Ok(Replay {
+        mode: options.mode,
+        original,
+        replay,
+        result,
+    })
+}
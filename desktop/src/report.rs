@@ -0,0 +1,22 @@
+//! Exporting a finished collection or workflow run, as the UI holds its summary, to a
+//! JUnit XML or HTML file (see `litefetch_core::report`).
+
+use litefetch_core::report::{self, ReportFormat};
+use serde_json::Value;
+
+/// Writes a report for a collection or workflow run to `path` and returns the path.
+#[tauri::command]
+pub async fn export_run_report(
+    run: Value,
+    format: ReportFormat,
+    path: String,
+    title: Option<String>,
+) -> Result<String, String> {
+    let title = title
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| "LiteFetch run report".to_string());
+    let content = report::render(&run, format, &title)?;
+    let target = crate::normalize_path(&path);
+    std::fs::write(&target, content).map_err(|e| format!("report write failed: {e}"))?;
+    Ok(target.to_string_lossy().to_string())
+}
@@ -0,0 +1,765 @@
+//! The response store: every response sent through the shell gets a `response_id` and its
+//! body (the raw bytes, for binary bodies) is written to `.litefetch/responses` in the
+//! workspace, next to a metadata file, so large bodies can be queried (JSONPath, jq, XPath or
+//! CSS selectors), paged through as hex, decoded from MessagePack, CBOR or protobuf, or
+//! saved to a file from disk instead of through the UI. The newest `MAX_RESPONSES` are kept.
+//!
+//! Bodies are stored zstd-compressed (`{id}.body.zst`) and decompressed as they are read, so
+//! a long history of large JSON payloads takes a fraction of the disk; bodies stored before
+//! that are plain `{id}.body` files and are read as they are. A compressed body is a run of
+//! independent frames of `FRAME_BYTES` each, and `{id}.body.idx` records where each starts,
+//! so a page deep into a body decompresses one frame to get there rather than everything
+//! before it.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tauri_plugin_dialog::DialogExt;
+
+use litefetch_core::binary::{Detected, HexLine};
+use litefetch_core::decode::Format;
+use litefetch_core::jsonpath::Match;
+use litefetch_core::markup::{SelectResult, SelectorKind};
+
+const MAX_RESPONSES: usize = 200;
+/// Matches returned by one query; `total` still counts every match.
+const MAX_MATCHES: usize = 1000;
+/// Largest page `get_response_hex` returns.
+const MAX_HEX_PAGE: u64 = 64 * 1024;
+/// Largest page `get_response_text` returns.
+const MAX_TEXT_PAGE: u64 = 1024 * 1024;
+/// Extension of a compressed body.
+pub(crate) const COMPRESSED_EXT: &str = "body.zst";
+/// zstd level bodies are written at: fast, and most of the gain on JSON and text.
+const ZSTD_LEVEL: i32 = 3;
+/// Largest zstd frame header, which holds the body's uncompressed size.
+const ZSTD_HEADER_MAX: u64 = 18;
+/// Uncompressed bytes per frame of a compressed body.
+const FRAME_BYTES: usize = 256 * 1024;
+/// Extension of the frame index next to a compressed body.
+const INDEX_EXT: &str = "body.idx";
+pub(crate) const SETTINGS_FILE: &str = "responses.json";
+/// Accepted range of `ResponseSettings::max_body_bytes`.
+const MAX_BODY_BYTES: std::ops::RangeInclusive<u64> = 64 * 1024..=1024 * 1024 * 1024;
+
+/// How much of a response body reaches the UI, per workspace in `responses.json`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ResponseSettings {
+    /// Bodies larger than this are sent as a preview; the rest is paged from the store.
+    pub max_body_bytes: u64,
+    /// Size of that preview, at most `max_body_bytes`.
+    pub preview_bytes: u64,
+}
+
+impl Default for ResponseSettings {
+    fn default() -> Self {
+        Self {
+            max_body_bytes: 5 * 1024 * 1024,
+            preview_bytes: 256 * 1024,
+        }
+    }
+}
+
+impl ResponseSettings {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(crate::load_workspace_path(app)?.join(SETTINGS_FILE))
+    }
+
+    /// The current workspace's settings, or the defaults if it has none.
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data =
+            fs::read_to_string(&path).map_err(|e| format!("response settings read failed: {e}"))?;
+        let settings: Self = serde_json::from_str(&data)
+            .map_err(|e| format!("response settings parse failed: {e}"))?;
+        Ok(settings.clamped())
+    }
+
+    fn clamped(mut self) -> Self {
+        self.max_body_bytes = self
+            .max_body_bytes
+            .clamp(*MAX_BODY_BYTES.start(), *MAX_BODY_BYTES.end());
+        self.preview_bytes = self.preview_bytes.min(self.max_body_bytes);
+        self
+    }
+}
+
+#[derive(Serialize)]
+pub struct TextPage {
+    pub offset: u64,
+    pub text: String,
+    /// Where the next page starts; `None` at the end of the body.
+    pub next_offset: Option<u64>,
+    /// Size of the whole body in bytes.
+    pub total: u64,
+}
+
+#[derive(Serialize)]
+pub struct QueryResult {
+    pub matches: Vec<Match>,
+    pub total: usize,
+    pub truncated: bool,
+}
+
+#[derive(Serialize)]
+pub struct HexPage {
+    pub offset: u64,
+    pub lines: Vec<HexLine>,
+    /// Size of the whole body.
+    pub total: u64,
+    /// The format the body's leading bytes point to; `None` for text.
+    pub detected: Option<Detected>,
+}
+
+#[derive(Deserialize, Default)]
+pub struct SaveOptions {
+    /// Indent JSON and XML bodies.
+    #[serde(default)]
+    pub pretty: bool,
+    /// Unpack gzip and zlib bodies.
+    #[serde(default)]
+    pub decompress: bool,
+}
+
+#[derive(Serialize)]
+pub struct SavedBody {
+    pub path: String,
+    pub bytes: u64,
+    /// SHA-256 of the file as written, checked by reading it back.
+    pub sha256: String,
+}
+
+fn store_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    crate::workspace_state_dir(app, "responses")
+}
+
+/// Response ids come back from the UI; keep them to the files they name.
+fn response_path(app: &tauri::AppHandle, response_id: &str, ext: &str) -> Result<PathBuf, String> {
+    if response_id.is_empty()
+        || !response_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(format!("unknown response: {response_id}"));
+    }
+    Ok(store_dir(app)?.join(format!("{response_id}.{ext}")))
+}
+
+/// Removes the oldest responses beyond `MAX_RESPONSES`, with their cached decodings.
+fn prune(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let files: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+    let mut metas: Vec<(std::time::SystemTime, &PathBuf)> = files
+        .iter()
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .filter_map(|path| Some((fs::metadata(path).ok()?.modified().ok()?, path)))
+        .collect();
+    if metas.len() <= MAX_RESPONSES {
+        return;
+    }
+    metas.sort();
+    for (_, meta) in &metas[..metas.len() - MAX_RESPONSES] {
+        let Some(id) = meta.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let prefix = format!("{id}.");
+        for file in &files {
+            if file
+                .file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&prefix))
+            {
+                let _ = fs::remove_file(file);
+            }
+        }
+    }
+}
+
+/// Where the frames of a compressed body start.
+#[derive(Serialize, Deserialize)]
+struct FrameIndex {
+    /// Uncompressed bytes per frame; the last one may hold fewer.
+    frame_bytes: u64,
+    /// The whole body, uncompressed.
+    size: u64,
+    /// Offset of each frame in the compressed file.
+    offsets: Vec<u64>,
+}
+
+impl FrameIndex {
+    /// The index of the compressed body at `path`; `None` for bodies stored as one frame,
+    /// before there were indexes.
+    fn load(path: &Path) -> Option<Self> {
+        let index =
+            serde_json::from_slice::<Self>(&fs::read(path.with_extension("idx")).ok()?).ok()?;
+        (index.frame_bytes > 0).then_some(index)
+    }
+}
+
+/// Writes `result` (a backend `RequestResult`) to the store and sets its `response_id`.
+pub fn store(app: &tauri::AppHandle, result: &mut Value) -> Result<(), String> {
+    let response_id = uuid::Uuid::new_v4().to_string();
+    // Binary bodies come base64-encoded; once on disk the UI reads them through
+    // `get_response_hex`, so they leave the result here.
+    let raw = result
+        .as_object_mut()
+        .and_then(|fields| fields.remove("body_base64"))
+        .and_then(|encoded| STANDARD.decode(encoded.as_str()?).ok());
+    let body = match (raw, result.get("body")) {
+        (Some(bytes), _) => bytes,
+        (None, Some(Value::String(text))) => text.clone().into_bytes(),
+        (None, Some(Value::Null) | None) => Vec::new(),
+        (None, Some(other)) => other.to_string().into_bytes(),
+    };
+    // An empty body still gets one (empty) frame.
+    let frames: Vec<&[u8]> = match body.is_empty() {
+        true => vec![&[]],
+        false => body.chunks(FRAME_BYTES).collect(),
+    };
+    let mut compressed = Vec::new();
+    let mut offsets = Vec::with_capacity(frames.len());
+    for frame in frames {
+        offsets.push(compressed.len() as u64);
+        let frame = zstd::bulk::compress(frame, ZSTD_LEVEL)
+            .map_err(|e| format!("response compress failed: {e}"))?;
+        compressed.extend_from_slice(&frame);
+    }
+    let index = FrameIndex {
+        frame_bytes: FRAME_BYTES as u64,
+        size: body.len() as u64,
+        offsets,
+    };
+    let index =
+        serde_json::to_vec(&index).map_err(|e| format!("response serialize failed: {e}"))?;
+    fs::write(response_path(app, &response_id, INDEX_EXT)?, index)
+        .map_err(|e| format!("response persist failed: {e}"))?;
+    fs::write(
+        response_path(app, &response_id, COMPRESSED_EXT)?,
+        compressed,
+    )
+    .map_err(|e| format!("response persist failed: {e}"))?;
+    let mut meta = result.clone();
+    if let Some(meta) = meta.as_object_mut() {
+        meta.remove("body");
+        meta.insert(
+            "response_id".to_string(),
+            Value::String(response_id.clone()),
+        );
+    }
+    let payload =
+        serde_json::to_string(&meta).map_err(|e| format!("response serialize failed: {e}"))?;
+    fs::write(response_path(app, &response_id, "json")?, payload)
+        .map_err(|e| format!("response persist failed: {e}"))?;
+    result["response_id"] = Value::String(response_id);
+    prune(&store_dir(app)?);
+    Ok(())
+}
+
+/// The uncompressed size of a compressed body, from its frame index or, for a body stored
+/// as one frame, its frame header; `None` when neither records it.
+pub(crate) fn compressed_body_size(path: &Path) -> Option<u64> {
+    if let Some(index) = FrameIndex::load(path) {
+        return Some(index.size);
+    }
+    let mut head = Vec::new();
+    fs::File::open(path)
+        .ok()?
+        .take(ZSTD_HEADER_MAX)
+        .read_to_end(&mut head)
+        .ok()?;
+    zstd::zstd_safe::get_frame_content_size(&head).ok()?
+}
+
+fn read_failed(e: io::Error) -> String {
+    format!("response read failed: {e}")
+}
+
+/// The size of the stored body of `response_id`, uncompressed.
+fn body_size(app: &tauri::AppHandle, response_id: &str) -> Result<u64, String> {
+    let compressed = response_path(app, response_id, COMPRESSED_EXT)?;
+    if compressed.is_file() {
+        if let Some(size) = compressed_body_size(&compressed) {
+            return Ok(size);
+        }
+        let mut body = open_body(app, response_id, 0)?;
+        return io::copy(&mut body, &mut io::sink()).map_err(read_failed);
+    }
+    fs::metadata(response_path(app, response_id, "body")?)
+        .map(|meta| meta.len())
+        .map_err(|_| format!("unknown response: {response_id}"))
+}
+
+/// The stored body of `response_id` from `offset`, decompressed as it is read.
+fn open_body(
+    app: &tauri::AppHandle,
+    response_id: &str,
+    offset: u64,
+) -> Result<Box<dyn Read>, String> {
+    let compressed = response_path(app, response_id, COMPRESSED_EXT)?;
+    if let Ok(mut file) = fs::File::open(&compressed) {
+        // Start at the frame `offset` falls in; the bytes before it in that frame (or, for
+        // a body stored as one frame, in the whole body) are decompressed and dropped.
+        let mut skip = offset;
+        if let Some(index) = FrameIndex::load(&compressed) {
+            let frame = offset / index.frame_bytes;
+            let Some(&start) = index.offsets.get(frame as usize) else {
+                return Ok(Box::new(io::empty()));
+            };
+            file.seek(SeekFrom::Start(start)).map_err(read_failed)?;
+            skip = offset - frame * index.frame_bytes;
+        }
+        let mut decoder = zstd::stream::read::Decoder::new(file).map_err(read_failed)?;
+        io::copy(&mut (&mut decoder).take(skip), &mut io::sink()).map_err(read_failed)?;
+        return Ok(Box::new(decoder));
+    }
+    let path = response_path(app, response_id, "body")?;
+    let mut file = fs::File::open(&path).map_err(|_| format!("unknown response: {response_id}"))?;
+    file.seek(SeekFrom::Start(offset)).map_err(read_failed)?;
+    Ok(Box::new(file))
+}
+
+fn body_bytes(app: &tauri::AppHandle, response_id: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    open_body(app, response_id, 0)?
+        .read_to_end(&mut bytes)
+        .map_err(read_failed)?;
+    Ok(bytes)
+}
+
+fn body_text(app: &tauri::AppHandle, response_id: &str) -> Result<String, String> {
+    String::from_utf8(body_bytes(app, response_id)?)
+        .map_err(|_| "response body is not UTF-8 text".to_string())
+}
+
+/// The stored body of `response_id`, if the store still has it.
+pub(crate) fn read_body(app: &tauri::AppHandle, response_id: &str) -> Option<Vec<u8>> {
+    body_bytes(app, response_id).ok()
+}
+
+/// The stored metadata of `response_id`: the `RequestResult` without its body.
+pub(crate) fn read_meta(app: &tauri::AppHandle, response_id: &str) -> Result<Value, String> {
+    let path = response_path(app, response_id, "json")?;
+    let text = fs::read_to_string(&path).map_err(|_| format!("unknown response: {response_id}"))?;
+    serde_json::from_str(&text).map_err(|e| format!("response metadata is corrupt: {e}"))
+}
+
+/// The stored body of `response_id` parsed as JSON, read straight from disk.
+pub fn read_json(app: &tauri::AppHandle, response_id: &str) -> Result<Value, String> {
+    serde_json::from_reader(BufReader::new(open_body(app, response_id, 0)?))
+        .map_err(|e| format!("response body is not JSON: {e}"))
+}
+
+/// Evaluates `jsonpath` against a stored response body and returns the matches with their
+/// paths, at most `MAX_MATCHES` of them.
+#[tauri::command]
+pub async fn query_response(
+    app: tauri::AppHandle,
+    response_id: String,
+    jsonpath: String,
+) -> Result<QueryResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let body = read_json(&app, &response_id)?;
+        let mut matches = litefetch_core::jsonpath::query(&body, &jsonpath)?;
+        let total = matches.len();
+        matches.truncate(MAX_MATCHES);
+        Ok(QueryResult {
+            matches,
+            total,
+            truncated: total > MAX_MATCHES,
+        })
+    })
+    .await
+    .map_err(|e| format!("response query failed: {e}"))?
+}
+
+/// Runs a jq program against a stored response body (as text when it isn't JSON) and
+/// returns its outputs, at most `MAX_MATCHES` of them.
+#[tauri::command]
+pub async fn jq_response(
+    app: tauri::AppHandle,
+    response_id: String,
+    program: String,
+) -> Result<Vec<Value>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let text = body_text(&app, &response_id)?;
+        let body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+        let mut outputs = litefetch_core::jq::run(&program, body)?;
+        outputs.truncate(MAX_MATCHES);
+        Ok(outputs)
+    })
+    .await
+    .map_err(|e| format!("jq failed: {e}"))?
+}
+
+/// Evaluates an XPath expression or CSS selector against a stored XML or HTML body and
+/// returns the matched nodes, at most 1000 of them.
+#[tauri::command]
+pub async fn select_response(
+    app: tauri::AppHandle,
+    response_id: String,
+    selector: String,
+    kind: SelectorKind,
+) -> Result<SelectResult, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let body = body_text(&app, &response_id)?;
+        let content_type = read_meta(&app, &response_id)
+            .ok()
+            .and_then(|meta| meta.get("content_type")?.as_str().map(str::to_string));
+        litefetch_core::markup::select(&body, content_type.as_deref(), kind, &selector)
+    })
+    .await
+    .map_err(|e| format!("response select failed: {e}"))?
+}
+
+/// A page of a stored body as a hex and ASCII dump: up to `length` bytes (64 KiB at most)
+/// from `offset`, read straight from disk, with the detected format of the body.
+#[tauri::command]
+pub async fn get_response_hex(
+    app: tauri::AppHandle,
+    response_id: String,
+    offset: u64,
+    length: u64,
+) -> Result<HexPage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let total = body_size(&app, &response_id)?;
+        let offset = offset.min(total);
+        let mut page = Vec::new();
+        open_body(&app, &response_id, offset)?
+            .take(length.min(MAX_HEX_PAGE))
+            .read_to_end(&mut page)
+            .map_err(read_failed)?;
+
+        // The first page holds the head the format is detected from; others read it apart.
+        let sniff = litefetch_core::binary::SNIFF_LEN;
+        let head = match offset == 0 && (page.len() >= sniff || page.len() as u64 == total) {
+            true => page[..page.len().min(sniff)].to_vec(),
+            false => {
+                let mut head = Vec::new();
+                open_body(&app, &response_id, 0)?
+                    .take(sniff as u64)
+                    .read_to_end(&mut head)
+                    .map_err(read_failed)?;
+                head
+            }
+        };
+        let detected = litefetch_core::binary::detect(&head, head.len() as u64 == total);
+        Ok(HexPage {
+            offset,
+            lines: litefetch_core::binary::hex_lines(&page, offset),
+            total,
+            detected,
+        })
+    })
+    .await
+    .map_err(|e| format!("response read failed: {e}"))?
+}
+
+/// A stored MessagePack, CBOR or protobuf body as JSON. `format` is `msgpack`, `cbor` or
+/// `protobuf`, or left out to go by the response's content type; protobuf takes a
+/// `message_type` from a `.proto` set loaded with `grpc_load_protos` (or `grpc_reflect`).
+/// Each decoding is cached next to the body.
+#[tauri::command]
+pub async fn decode_response(
+    app: tauri::AppHandle,
+    grpc: tauri::State<'_, crate::grpc::GrpcState>,
+    response_id: String,
+    format: Option<String>,
+    schema_id: Option<String>,
+    message_type: Option<String>,
+) -> Result<Value, String> {
+    let format = match format {
+        Some(format) => format,
+        None => read_meta(&app, &response_id)?
+            .get("content_type")
+            .and_then(Value::as_str)
+            .and_then(Format::from_content_type)
+            .map(|format| match format {
+                Format::Msgpack => "msgpack",
+                Format::Cbor => "cbor",
+            })
+            .ok_or("pick a format: the content type isn't MessagePack or CBOR")?
+            .to_string(),
+    };
+    let (cache_key, pool) = match format.as_str() {
+        "msgpack" | "cbor" => (format.clone(), None),
+        "protobuf" => {
+            let (Some(schema_id), Some(message_type)) = (&schema_id, &message_type) else {
+                return Err("protobuf decoding needs a schema_id and message_type".to_string());
+            };
+            let mut hasher = DefaultHasher::new();
+            (schema_id, message_type).hash(&mut hasher);
+            (
+                format!("pb-{:016x}", hasher.finish()),
+                Some(grpc.pool(schema_id).await?),
+            )
+        }
+        other => return Err(format!("unsupported body format: {other}")),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let cache = response_path(&app, &response_id, &format!("{cache_key}.decoded"))?;
+        if let Some(cached) = fs::read_to_string(&cache)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+        {
+            return Ok(cached);
+        }
+        let bytes = body_bytes(&app, &response_id)?;
+        let decoded = match (format.as_str(), pool) {
+            ("msgpack", _) => litefetch_core::decode::to_json(Format::Msgpack, &bytes)?,
+            ("cbor", _) => litefetch_core::decode::to_json(Format::Cbor, &bytes)?,
+            (_, Some(pool)) => {
+                crate::grpc::decode_message(&pool, message_type.as_deref().unwrap_or(""), &bytes)?
+            }
+            (other, None) => return Err(format!("unsupported body format: {other}")),
+        };
+        if let Err(e) = serde_json::to_string(&decoded)
+            .map_err(|e| e.to_string())
+            .and_then(|text| fs::write(&cache, text).map_err(|e| e.to_string()))
+        {
+            tracing::warn!("decoded cache write failed: {e}");
+        }
+        Ok(decoded)
+    })
+    .await
+    .map_err(|e| format!("response decode failed: {e}"))?
+}
+
+/// Extension for a saved body: from the content type, else from its leading bytes.
+fn extension_for(content_type: &str, bytes: &[u8]) -> &'static str {
+    let content_type = content_type.to_ascii_lowercase();
+    let by_type = [
+        ("json", "json"),
+        ("html", "html"),
+        ("xml", "xml"),
+        ("csv", "csv"),
+        ("yaml", "yaml"),
+        ("javascript", "js"),
+        ("css", "css"),
+        ("text/plain", "txt"),
+    ];
+    if let Some((_, ext)) = by_type
+        .iter()
+        .find(|(needle, _)| content_type.contains(needle))
+    {
+        return ext;
+    }
+    let head = &bytes[..bytes.len().min(litefetch_core::binary::SNIFF_LEN)];
+    match litefetch_core::binary::detect(head, head.len() == bytes.len()).map(|d| d.mime) {
+        None => "txt",
+        Some("image/png") => "png",
+        Some("image/jpeg") => "jpg",
+        Some("image/gif") => "gif",
+        Some("image/webp") => "webp",
+        Some("application/pdf") => "pdf",
+        Some("application/gzip") => "gz",
+        Some("application/zip") => "zip",
+        Some("application/x-protobuf") => "pb",
+        Some(_) => "bin",
+    }
+}
+
+fn decompress(bytes: Vec<u8>) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let read = match bytes.as_slice() {
+        [0x1f, 0x8b, ..] => {
+            flate2::read::MultiGzDecoder::new(bytes.as_slice()).read_to_end(&mut out)
+        }
+        // zlib: CMF says deflate and the header checksum holds.
+        [cmf, flg, ..] if cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 => {
+            flate2::read::ZlibDecoder::new(bytes.as_slice()).read_to_end(&mut out)
+        }
+        _ => return Ok(bytes),
+    };
+    read.map_err(|e| format!("decompress failed: {e}"))?;
+    Ok(out)
+}
+
+fn pretty(bytes: Vec<u8>, content_type: &str) -> Vec<u8> {
+    let Ok(text) = std::str::from_utf8(&bytes) else {
+        return bytes;
+    };
+    let trimmed = text.trim_start();
+    let formatted = if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        serde_json::from_str::<Value>(text)
+            .ok()
+            .and_then(|value| serde_json::to_string_pretty(&value).ok())
+    } else if trimmed.starts_with('<') && !content_type.to_ascii_lowercase().contains("html") {
+        crate::soap::pretty_xml(text).ok()
+    } else {
+        None
+    };
+    formatted.map_or(bytes, String::into_bytes)
+}
+
+/// Saves a stored body to a file the user picks, optionally pretty-printed or
+/// decompressed. The dialog opens in the directory of the last save in this workspace.
+/// `None` when the dialog is cancelled.
+#[tauri::command]
+pub async fn save_response_body(
+    app: tauri::AppHandle,
+    response_id: String,
+    options: Option<SaveOptions>,
+) -> Result<Option<SavedBody>, String> {
+    let options = options.unwrap_or_default();
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut bytes = body_bytes(&app, &response_id)?;
+        let content_type = read_meta(&app, &response_id)
+            .ok()
+            .and_then(|meta| meta.get("content_type")?.as_str().map(str::to_string))
+            .unwrap_or_default();
+        if options.decompress {
+            bytes = decompress(bytes)?;
+        }
+        if options.pretty {
+            bytes = pretty(bytes, &content_type);
+        }
+
+        let last_dir_file = store_dir(&app)?.join("last_export_dir");
+        let short_id: String = response_id.chars().take(8).collect();
+        let ext = extension_for(&content_type, &bytes);
+        let mut dialog = app
+            .dialog()
+            .file()
+            .set_file_name(format!("response-{short_id}.{ext}"))
+            .add_filter(ext.to_uppercase(), &[ext]);
+        if let Some(dir) = fs::read_to_string(&last_dir_file)
+            .ok()
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_dir())
+        {
+            dialog = dialog.set_directory(dir);
+        }
+        let Some(target) = dialog.blocking_save_file() else {
+            return Ok(None);
+        };
+        let target = target
+            .into_path()
+            .map_err(|e| format!("save path invalid: {e}"))?;
+
+        fs::write(&target, &bytes).map_err(|e| format!("response save failed: {e}"))?;
+        let expected = hex::encode(Sha256::digest(&bytes));
+        let written = fs::read(&target).map_err(|e| format!("response save check failed: {e}"))?;
+        let actual = hex::encode(Sha256::digest(&written));
+        if actual != expected {
+            return Err(format!(
+                "response save check failed: {} has a different checksum than the body",
+                target.display()
+            ));
+        }
+        if let Some(dir) = target.parent() {
+            if let Err(e) = fs::write(&last_dir_file, dir.to_string_lossy().as_bytes()) {
+                tracing::warn!("export dir persist failed: {e}");
+            }
+        }
+        Ok(Some(SavedBody {
+            path: target.to_string_lossy().to_string(),
+            bytes: written.len() as u64,
+            sha256: actual,
+        }))
+    })
+    .await
+    .map_err(|e| format!("response save failed: {e}"))?
+}
+
+/// The largest index at or below `at` that starts a UTF-8 character in `bytes`.
+fn char_floor(bytes: &[u8], mut at: usize) -> usize {
+    at = at.min(bytes.len());
+    while at > 0 && at < bytes.len() && bytes[at] & 0xc0 == 0x80 {
+        at -= 1;
+    }
+    at
+}
+
+/// Cuts a body larger than the workspace's `max_body_bytes` down to its preview, for the
+/// UI: `body_truncated` is set, and `body_preview_bytes` says how much of the body is
+/// there. The full body stays in the store, read with `get_response_text`.
+pub fn limit(app: &tauri::AppHandle, result: &mut Value) {
+    let settings = ResponseSettings::load(app).unwrap_or_else(|e| {
+        tracing::warn!("{e}");
+        ResponseSettings::default()
+    });
+    let Some(Value::String(body)) = result.get_mut("body") else {
+        return;
+    };
+    if body.len() as u64 <= settings.max_body_bytes {
+        return;
+    }
+    let cut = char_floor(body.as_bytes(), settings.preview_bytes as usize);
+    body.truncate(cut);
+    result["body_truncated"] = Value::Bool(true);
+    result["body_preview_bytes"] = Value::from(cut as u64);
+}
+
+/// A page of a stored body as text: up to `length` bytes (1 MiB at most) from `offset`,
+/// both moved back to a character boundary, read straight from disk.
+#[tauri::command]
+pub async fn get_response_text(
+    app: tauri::AppHandle,
+    response_id: String,
+    offset: u64,
+    length: u64,
+) -> Result<TextPage, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let total = body_size(&app, &response_id)?;
+        // Read from up to three bytes early so a start inside a character can step back.
+        let offset = offset.min(total);
+        let lead = offset.min(3);
+        let mut page = Vec::new();
+        open_body(&app, &response_id, offset - lead)?
+            .take(lead + length.min(MAX_TEXT_PAGE))
+            .read_to_end(&mut page)
+            .map_err(read_failed)?;
+        let start = char_floor(&page, lead as usize);
+        // And end before a character the page cuts off, unless the body itself ends there.
+        let at_end = offset - lead + page.len() as u64 >= total;
+        let end = match std::str::from_utf8(&page[start..]) {
+            Err(e) if !at_end && e.error_len().is_none() => start + e.valid_up_to(),
+            _ => page.len(),
+        };
+        let offset = offset - lead + start as u64;
+        let next = offset + (end - start) as u64;
+        Ok(TextPage {
+            offset,
+            text: String::from_utf8_lossy(&page[start..end]).into_owned(),
+            next_offset: (next < total).then_some(next),
+            total,
+        })
+    })
+    .await
+    .map_err(|e| format!("response read failed: {e}"))?
+}
+
+/// The current workspace's response size limits.
+#[tauri::command]
+pub async fn get_response_settings(app: tauri::AppHandle) -> Result<ResponseSettings, String> {
+    ResponseSettings::load(&app)
+}
+
+/// Saves the current workspace's response size limits. `max_body_bytes` is clamped to
+/// 64 KiB to 1 GiB and the preview to at most that; the saved settings are returned.
+#[tauri::command]
+pub async fn set_response_settings(
+    app: tauri::AppHandle,
+    settings: ResponseSettings,
+) -> Result<ResponseSettings, String> {
+    let settings = settings.clamped();
+    let payload = serde_json::to_string_pretty(&settings)
+        .map_err(|e| format!("response settings serialize failed: {e}"))?;
+    fs::write(ResponseSettings::path(&app)?, payload)
+        .map_err(|e| format!("response settings persist failed: {e}"))?;
+    Ok(settings)
+}
@@ -0,0 +1,158 @@
+//! Commands for the workspace's base URL routing rules and a preview of where a request
+//! would go; the rules themselves are `litefetch_core::routing`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use litefetch_core::routing::{self, Route, RouteRule, RouteTarget};
+
+use crate::importers::str_of;
+use crate::variables::{resolve, Purpose, Scope};
+
+#[derive(Deserialize)]
+pub struct RouteRuleInput {
+    /// Updates the rule with this id; a new one is created without it.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub name: String,
+    pub pattern: String,
+    pub targets: BTreeMap<String, RouteTarget>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize)]
+pub struct RoutePreview {
+    pub environment: String,
+    /// The URL as written.
+    pub url: String,
+    /// After routing, before variables resolve.
+    pub routed_url: String,
+    /// What would be sent; secrets are masked.
+    pub final_url: String,
+    /// `None` when no rule applies.
+    pub route: Option<Route>,
+    /// References nothing defines; they are sent as written.
+    pub unresolved: Vec<String>,
+}
+
+#[tauri::command]
+pub async fn list_routes(app: tauri::AppHandle) -> Result<Vec<RouteRule>, String> {
+    routing::load(&crate::load_workspace_path(&app)?)
+}
+
+#[tauri::command]
+pub async fn save_route(app: tauri::AppHandle, rule: RouteRuleInput) -> Result<RouteRule, String> {
+    if rule.pattern.trim().trim_end_matches('/').is_empty() {
+        return Err("a route needs a URL pattern".into());
+    }
+    if let Some((env, _)) = rule
+        .targets
+        .iter()
+        .find(|(_, target)| target.base_url.trim().is_empty())
+    {
+        return Err(format!("the route for {env} needs a base URL"));
+    }
+    crate::lock::ensure_writable(&app)?;
+    let workspace = crate::load_workspace_path(&app)?;
+    let mut rules = routing::load(&workspace)?;
+    let id = rule
+        .id
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let targets = rule
+        .targets
+        .into_iter()
+        .map(|(env, target)| {
+            let target = RouteTarget {
+                base_url: target.base_url.trim().to_string(),
+                path_prefix: target.path_prefix.trim().to_string(),
+                port: target.port.filter(|p| *p > 0),
+            };
+            (env, target)
+        })
+        .collect();
+    let saved = RouteRule {
+        id,
+        name: rule.name.trim().to_string(),
+        pattern: rule.pattern.trim().to_string(),
+        targets,
+        enabled: rule.enabled,
+        updated_ms: crate::now_ms(),
+    };
+    match rules.iter().position(|r| r.id == saved.id) {
+        Some(index) => rules[index] = saved.clone(),
+        None => rules.push(saved.clone()),
+    }
+    routing::save(&workspace, &rules)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_route(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    crate::lock::ensure_writable(&app)?;
+    let workspace = crate::load_workspace_path(&app)?;
+    let mut rules = routing::load(&workspace)?;
+    rules.retain(|r| r.id != id);
+    routing::save(&workspace, &rules)
+}
+
+/// Where `request` (saved or not) would be sent in environment `environment_id` (the active
+/// one by default): the rule that routes it and its final URL, without sending it.
+#[tauri::command]
+pub async fn preview_route(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request: Value,
+    environment_id: Option<String>,
+) -> Result<RoutePreview, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    let env_name = environment_id.unwrap_or_else(|| str_of(&environment, "active_env").into());
+    let scope = Scope::load(
+        &crate::variables::layers(&app)?,
+        &collection_id,
+        &collection,
+        &environment,
+        &env_name,
+        Some(&request),
+    )?;
+    let url = str_of(&request, "url").to_string();
+    let workspace = crate::load_workspace_path(&app)?;
+    let route = routing::route(&routing::load(&workspace)?, &url, &env_name);
+    let routed_url = route
+        .as_ref()
+        .map_or_else(|| url.clone(), |r| r.url.clone());
+    let mut record = Vec::new();
+    let resolved = resolve(
+        &routed_url,
+        &scope,
+        Purpose::Preview { reveal: false },
+        &mut record,
+    );
+    let final_url = match route.as_ref().and_then(|r| r.port) {
+        Some(port) => routing::with_port(&resolved, port),
+        None => resolved,
+    };
+    let mut unresolved: Vec<String> = Vec::new();
+    for substitution in record.into_iter().filter(|s| s.value.is_none()) {
+        if !unresolved.contains(&substitution.reference) {
+            unresolved.push(substitution.reference);
+        }
+    }
+    Ok(RoutePreview {
+        environment: env_name,
+        url,
+        routed_url,
+        final_url,
+        route,
+        unresolved,
+    })
+}
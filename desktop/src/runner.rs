@@ -0,0 +1,222 @@
+//! Collection runs from the UI: `litefetch_core::runner` sends the requests through `send`,
+//! so each gets its scripts and plugins, and every result is reported as a
+//! `runner://progress` event and the summary as `runner://finished`. `send_batch` sends a
+//! few selected requests the same way and just returns their results.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::async_runtime::Mutex;
+use tauri::{Emitter, State};
+
+use litefetch_core::runner::{Plan, RequestRun, RunOptions, RunSummary};
+
+use crate::send::Shell;
+
+pub struct RunnerState {
+    /// Cancel flags of the runs in progress.
+    runs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl RunnerState {
+    pub fn new() -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct BatchOptions {
+    pub environment_id: Option<String>,
+    /// Send the requests at once rather than one after another.
+    pub parallel: bool,
+    /// How many may be in flight when `parallel`; all of them by default.
+    pub concurrency: Option<usize>,
+    pub stop_on_failure: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct StartedEvent {
+    run_id: String,
+    collection_id: String,
+    total: usize,
+    iterations: usize,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct ProgressEvent {
+    run_id: String,
+    completed: usize,
+    total: usize,
+    result: RequestRun,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct FinishedEvent {
+    summary: RunSummary,
+    timestamp_ms: u64,
+}
+
+/// Runs a collection's requests and returns the summary once all have finished. The run id
+/// arrives first in a `runner://started` event, for `cancel_collection_run`.
+#[tauri::command]
+pub async fn run_collection(
+    app: tauri::AppHandle,
+    state: State<'_, RunnerState>,
+    collection_id: String,
+    options: Option<RunOptions>,
+) -> Result<RunSummary, String> {
+    let mut options = options.unwrap_or_default();
+    if let Some(data_file) = &options.data_file {
+        options.data_file = Some(litefetch_core::fixtures::resolve_reference(
+            &crate::load_workspace_path(&app)?,
+            data_file,
+        )?);
+    }
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let mut plan = Plan::new(&collection, &options)?;
+    if let Some(spec) = options.spec.as_deref().filter(|s| !s.trim().is_empty()) {
+        plan = plan.with_spec(crate::importers::openapi::load_spec(&app, spec).await?);
+    }
+    if options.verify_snapshots {
+        plan = plan.with_snapshots(litefetch_core::snapshot::load_all(
+            &crate::load_workspace_path(&app)?,
+            &collection_id,
+        ));
+    }
+
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    state
+        .runs
+        .lock()
+        .await
+        .insert(run_id.clone(), cancel.clone());
+    let total = plan.total();
+    let _ = app.emit(
+        "runner://started",
+        StartedEvent {
+            run_id: run_id.clone(),
+            collection_id: collection_id.clone(),
+            total,
+            iterations: plan.iterations(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    let sender = Shell {
+        app: app.clone(),
+        collection_id: collection_id.clone(),
+        environment_id: options.environment_id.clone(),
+    };
+    let summary = litefetch_core::runner::execute(
+        run_id.clone(),
+        collection_id,
+        &plan,
+        &options,
+        &sender,
+        &cancel,
+        |completed, result| {
+            let _ = app.emit(
+                "runner://progress",
+                ProgressEvent {
+                    run_id: run_id.clone(),
+                    completed,
+                    total,
+                    result: result.clone(),
+                    timestamp_ms: crate::now_ms(),
+                },
+            );
+        },
+    )
+    .await;
+    state.runs.lock().await.remove(&run_id);
+
+    let outcome = match summary.cancelled {
+        true => "Cancelled".to_string(),
+        false => format!(
+            "{} passed, {} failed, {} errors",
+            summary.passed, summary.failed, summary.errors
+        ),
+    };
+    crate::notifications::send(
+        &app,
+        crate::notifications::Category::RunnerFinished,
+        "Collection run finished",
+        &outcome,
+    );
+    let _ = app.emit(
+        "runner://finished",
+        FinishedEvent {
+            summary: summary.clone(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    Ok(summary)
+}
+
+/// Stops a run from starting further requests; those in flight still finish.
+#[tauri::command]
+pub async fn cancel_collection_run(
+    state: State<'_, RunnerState>,
+    run_id: String,
+) -> Result<(), String> {
+    let runs = state.runs.lock().await;
+    let cancel = runs
+        .get(&run_id)
+        .ok_or_else(|| format!("unknown run: {run_id}"))?;
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Sends the given requests of a collection, in that order, and returns their results
+/// together; for warming caches or re-checking a few endpoints. One after another, each sees
+/// the cookies and environment variables (captures, script sets) those before it left; in
+/// parallel they all start from the same state.
+#[tauri::command]
+pub async fn send_batch(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_ids: Vec<String>,
+    options: Option<BatchOptions>,
+) -> Result<RunSummary, String> {
+    let batch = options.unwrap_or_default();
+    if request_ids.is_empty() {
+        return Err("no requests selected".to_string());
+    }
+    let concurrency = match batch.parallel {
+        true => batch.concurrency.unwrap_or(request_ids.len()),
+        false => 1,
+    };
+    let options = RunOptions {
+        request_ids: Some(request_ids),
+        environment_id: batch.environment_id,
+        concurrency: Some(concurrency),
+        stop_on_failure: batch.stop_on_failure,
+        ..Default::default()
+    };
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let plan = Plan::new(&collection, &options)?;
+    let sender = Shell {
+        app: app.clone(),
+        collection_id: collection_id.clone(),
+        environment_id: options.environment_id.clone(),
+    };
+    let summary = litefetch_core::runner::execute(
+        uuid::Uuid::new_v4().to_string(),
+        collection_id,
+        &plan,
+        &options,
+        &sender,
+        &AtomicBool::new(false),
+        |_, _| {},
+    )
+    .await;
+    Ok(summary)
+}
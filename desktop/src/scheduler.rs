@@ -0,0 +1,266 @@
+//! Per-host limits on how hard requests hit an API: at most `max_concurrent` in flight and
+//! `requests_per_second` started, for hosts matching a pattern (`api.example.com`,
+//! `*.example.com`, `localhost:8080`, as in the key vault). Every trip in `send` waits its
+//! turn here, so single sends, collection runs, workflows and monitors share the budget.
+//! The limits live in the app data directory, since a rate limit belongs to the API rather
+//! than to a workspace; the most specific enabled pattern applies. A result that had to
+//! wait says how long in `queued_ms`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::Mutex;
+use tauri::{Manager, State};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::importers::str_of;
+
+const LIMITS_FILE: &str = "host-limits.json";
+const MAX_CONCURRENT: u32 = 1_000;
+const MAX_RATE: f64 = 10_000.0;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct HostLimit {
+    pub id: String,
+    pub host_pattern: String,
+    /// Requests in flight at once; unlimited when unset.
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    /// Requests started per second, spaced evenly; unlimited when unset.
+    #[serde(default)]
+    pub requests_per_second: Option<f64>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    pub updated_ms: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct HostLimitInput {
+    /// Updates the limit with this id; a new one is created without it.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub host_pattern: String,
+    #[serde(default)]
+    pub max_concurrent: Option<u32>,
+    #[serde(default)]
+    pub requests_per_second: Option<f64>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+/// The live side of a limit: its permits and when the next request may start.
+struct Queue {
+    limit: HostLimit,
+    permits: Option<Arc<Semaphore>>,
+    next_start: Mutex<Instant>,
+    waiting: AtomicUsize,
+}
+
+impl Queue {
+    fn new(limit: HostLimit) -> Self {
+        Self {
+            permits: limit
+                .max_concurrent
+                .map(|n| Arc::new(Semaphore::new(n as usize))),
+            limit,
+            next_start: Mutex::new(Instant::now()),
+            waiting: AtomicUsize::new(0),
+        }
+    }
+
+    fn active(&self) -> usize {
+        match (&self.permits, self.limit.max_concurrent) {
+            (Some(permits), Some(max)) => {
+                (max as usize).saturating_sub(permits.available_permits())
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Queues by limit id, replaced when their limit is edited.
+pub struct SchedulerState {
+    queues: Mutex<HashMap<String, Arc<Queue>>>,
+}
+
+impl SchedulerState {
+    pub fn new() -> Self {
+        Self {
+            queues: Mutex::new(HashMap::new()),
+        }
+    }
+
+    async fn queue(&self, limit: &HostLimit) -> Arc<Queue> {
+        let mut queues = self.queues.lock().await;
+        match queues.get(&limit.id) {
+            Some(queue) if queue.limit == *limit => queue.clone(),
+            _ => {
+                let queue = Arc::new(Queue::new(limit.clone()));
+                queues.insert(limit.id.clone(), queue.clone());
+                queue
+            }
+        }
+    }
+}
+
+/// A request's turn: holds its concurrency slot until dropped.
+pub struct Ticket {
+    _permit: Option<OwnedSemaphorePermit>,
+    pub queued_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct HostQueueStatus {
+    pub id: String,
+    pub host_pattern: String,
+    pub active: usize,
+    pub waiting: usize,
+}
+
+fn limits_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(LIMITS_FILE))
+}
+
+fn load(app: &tauri::AppHandle) -> Result<Vec<HostLimit>, String> {
+    let path = limits_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("host limits read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("host limits parse failed: {e}"))
+}
+
+fn save(app: &tauri::AppHandle, limits: &[HostLimit]) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(limits)
+        .map_err(|e| format!("host limits serialize failed: {e}"))?;
+    fs::write(limits_path(app)?, payload).map_err(|e| format!("host limits persist failed: {e}"))
+}
+
+/// The limit for a request to `url`: the most specific enabled pattern that matches.
+fn limit_for(limits: Vec<HostLimit>, url: &str) -> Option<HostLimit> {
+    let url = reqwest::Url::parse(url.trim()).ok()?;
+    let host = url.host_str()?.to_ascii_lowercase();
+    let port = url.port_or_known_default().unwrap_or(0);
+    limits
+        .into_iter()
+        .filter(|l| l.enabled && crate::vault::matches(&l.host_pattern, &host, port))
+        .max_by_key(|l| (!l.host_pattern.contains('*'), l.host_pattern.trim().len()))
+}
+
+/// Waits until `request` may go out under its host's limit; `None` when no limit applies.
+pub(crate) async fn acquire(
+    app: &tauri::AppHandle,
+    request: &Value,
+) -> Result<Option<Ticket>, String> {
+    let Some(limit) = limit_for(load(app)?, str_of(request, "url")) else {
+        return Ok(None);
+    };
+    let queue = app.state::<SchedulerState>().queue(&limit).await;
+    let started = Instant::now();
+    queue.waiting.fetch_add(1, Ordering::Relaxed);
+    let permit = match &queue.permits {
+        Some(permits) => permits.clone().acquire_owned().await.ok(),
+        None => None,
+    };
+    if let Some(rate) = limit.requests_per_second {
+        let start = {
+            let mut next_start = queue.next_start.lock().await;
+            let start = (*next_start).max(Instant::now());
+            *next_start = start + Duration::from_secs_f64(1.0 / rate);
+            start
+        };
+        tokio::time::sleep_until(start.into()).await;
+    }
+    queue.waiting.fetch_sub(1, Ordering::Relaxed);
+    Ok(Some(Ticket {
+        _permit: permit,
+        queued_ms: started.elapsed().as_millis() as u64,
+    }))
+}
+
+#[tauri::command]
+pub async fn list_host_limits(app: tauri::AppHandle) -> Result<Vec<HostLimit>, String> {
+    load(&app)
+}
+
+#[tauri::command]
+pub async fn save_host_limit(
+    app: tauri::AppHandle,
+    limit: HostLimitInput,
+) -> Result<HostLimit, String> {
+    if limit.host_pattern.trim().is_empty() {
+        return Err("a host limit needs a host pattern".into());
+    }
+    if limit.max_concurrent == Some(0) {
+        return Err("max_concurrent must be at least 1".into());
+    }
+    if limit
+        .requests_per_second
+        .is_some_and(|r| !r.is_finite() || r <= 0.0)
+    {
+        return Err("requests_per_second must be above 0".into());
+    }
+    if limit.max_concurrent.is_none() && limit.requests_per_second.is_none() {
+        return Err("a host limit needs max_concurrent or requests_per_second".into());
+    }
+    let mut limits = load(&app)?;
+    let id = limit
+        .id
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let saved = HostLimit {
+        id,
+        host_pattern: limit.host_pattern.trim().to_string(),
+        max_concurrent: limit.max_concurrent.map(|n| n.min(MAX_CONCURRENT)),
+        requests_per_second: limit.requests_per_second.map(|r| r.min(MAX_RATE)),
+        enabled: limit.enabled,
+        updated_ms: crate::now_ms(),
+    };
+    match limits.iter().position(|l| l.id == saved.id) {
+        Some(index) => limits[index] = saved.clone(),
+        None => limits.push(saved.clone()),
+    }
+    save(&app, &limits)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_host_limit(
+    app: tauri::AppHandle,
+    state: State<'_, SchedulerState>,
+    id: String,
+) -> Result<(), String> {
+    let mut limits = load(&app)?;
+    limits.retain(|l| l.id != id);
+    save(&app, &limits)?;
+    state.queues.lock().await.remove(&id);
+    Ok(())
+}
+
+/// Requests in flight and waiting under each limit that has seen traffic.
+#[tauri::command]
+pub async fn list_host_queues(
+    state: State<'_, SchedulerState>,
+) -> Result<Vec<HostQueueStatus>, String> {
+    let queues = state.queues.lock().await;
+    let mut statuses: Vec<HostQueueStatus> = queues
+        .values()
+        .map(|queue| HostQueueStatus {
+            id: queue.limit.id.clone(),
+            host_pattern: queue.limit.host_pattern.clone(),
+            active: queue.active(),
+            waiting: queue.waiting.load(Ordering::Relaxed),
+        })
+        .collect();
+    statuses.sort_by(|a, b| a.host_pattern.cmp(&b.host_pattern));
+    Ok(statuses)
+}
@@ -0,0 +1,45 @@
+//! Response validation from the UI: checks a response the UI holds against a JSON Schema or
+//! the response an OpenAPI spec documents for it (see `litefetch_core::schema`).
+
+use serde_json::Value;
+
+use litefetch_core::json::str_of;
+use litefetch_core::schema::{self, Violation};
+
+/// Checks `result` (a `RequestResult`, with its `sent_request`) against `schema`, and
+/// against `spec` (an imported collection's id, a path or a URL) when given. No violations
+/// means it matched.
+#[tauri::command]
+pub async fn validate_response(
+    app: tauri::AppHandle,
+    result: Value,
+    schema: Option<Value>,
+    spec: Option<String>,
+) -> Result<Vec<Violation>, String> {
+    let spec = spec.filter(|s| !s.trim().is_empty());
+    if schema.is_none() && spec.is_none() {
+        return Err("nothing to validate against: pass a schema or a spec".to_string());
+    }
+    let body = schema::body_of(&result);
+    let mut violations = Vec::new();
+    if let Some(schema) = &schema {
+        violations.extend(schema::validate(schema, &body));
+    }
+    if let Some(spec) = spec {
+        let doc = crate::importers::openapi::load_spec(&app, &spec).await?;
+        let sent = result.get("sent_request").unwrap_or(&Value::Null);
+        let status = result
+            .get("status_code")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        violations.extend(schema::check_response(
+            &doc,
+            str_of(sent, "method"),
+            str_of(sent, "url"),
+            status as u16,
+            result.get("content_type").and_then(Value::as_str),
+            &body,
+        ));
+    }
+    Ok(violations)
+}
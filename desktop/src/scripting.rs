@@ -0,0 +1,764 @@
+//! Request scripts, run in an embedded QuickJS runtime: pre-request scripts before a request
+//! is sent, test scripts once its response arrives. The runtime has no file, network or
+//! process access; scripts see the request as `lf.request`, read and set variables through
+//! `lf.variables` and `lf.env` (alias `lf.environment`), can wait with `lf.sleep` and call
+//! other endpoints with `lf.sendRequest`, and get hashing, encoding and JWT helpers on
+//! `lf.crypto`. Test scripts also get `lf.response`, `lf.test` and `lf.expect`. Console
+//! output is streamed as `script://console` events.
+//!
+//! What scripts may do is set per workspace (see `ScriptSettings`) and kept in the app data
+//! root's `scripts.json`, never in the workspace, so a cloned repository can't grant its own
+//! scripts network or secret access. The time and memory limits are enforced by the runtime,
+//! not the script.
+
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use rquickjs::{CatchResultExt, Context, Function, Runtime};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+
+use crate::importers::str_of;
+use crate::variables::Scope;
+
+pub use litefetch_core::send::{TestReport, TestResult};
+
+const SETTINGS_FILE: &str = "scripts.json";
+const STACK_LIMIT: usize = 1 << 20;
+const TIME_LIMIT_MS: std::ops::RangeInclusive<u64> = 100..=30_000;
+const MEMORY_LIMIT_MB: std::ops::RangeInclusive<usize> = 8..=512;
+
+/// Installs the `lf` and `console` globals around the native helpers.
+const PRELUDE: &str = r#"
+(() => {
+  const vars = JSON.parse(__lf_vars);
+  const text = (args) => args
+    .map((a) => {
+      if (typeof a === "string") return a;
+      try { return JSON.stringify(a); } catch (_) { return String(a); }
+    })
+    .join(" ");
+  const level = (name) => (...args) => __lf_console(name, text(args));
+  globalThis.console = {
+    log: level("log"), info: level("info"), warn: level("warn"),
+    error: level("error"), debug: level("debug"),
+  };
+  const permissions = JSON.parse(__lf_permissions);
+  // Reads see earlier sets; what is kept is only what went through the native setters.
+  const sets = { request: {}, environment: {} };
+  const denied = (name) => {
+    throw new Error(`${name} is disabled for scripts in this workspace`);
+  };
+  const lookup = (key) => {
+    if (key in sets.request) return sets.request[key];
+    if (key in sets.environment) return sets.environment[key];
+    return vars[key];
+  };
+  const environment = {
+    get: lookup,
+    set: (key, value) => {
+      if (!permissions.allow_environment_write) denied("lf.env.set");
+      __lf_set_environment(String(key), String(value));
+      sets.environment[String(key)] = String(value);
+    },
+  };
+  globalThis.lf = {
+    request: JSON.parse(__lf_request),
+    variables: {
+      get: lookup,
+      has: (key) => lookup(key) !== undefined,
+      set: (key, value) => {
+        __lf_set_request(String(key), String(value));
+        sets.request[String(key)] = String(value);
+      },
+    },
+    env: environment,
+    environment,
+    sleep: (ms) => __lf_sleep(Number(ms) || 0),
+    sendRequest: (spec) => {
+      if (!permissions.allow_network) denied("lf.sendRequest");
+      const request = typeof spec === "string" ? { url: spec } : Object.assign({}, spec);
+      if (request.body !== undefined && typeof request.body !== "string") {
+        request.body = JSON.stringify(request.body);
+      }
+      const response = JSON.parse(__lf_send(JSON.stringify(request)));
+      if (response.error) throw new Error(`lf.sendRequest: ${response.error}`);
+      let parsed;
+      response.json = () => {
+        if (parsed === undefined) parsed = JSON.parse(response.body);
+        return parsed;
+      };
+      return response;
+    },
+    crypto: {
+      sha1: (value) => __lf_sha1(String(value)),
+      sha256: (value) => __lf_sha256(String(value)),
+      hmacSha256: (key, value) => __lf_hmac_sha256(String(key), String(value)),
+      base64Encode: (value) => __lf_base64_encode(String(value)),
+      base64Decode: (value) => __lf_base64_decode(String(value)),
+      base64UrlEncode: (value) => __lf_base64url_encode(String(value)),
+      base64UrlDecode: (value) => __lf_base64url_decode(String(value)),
+      uuid: () => __lf_uuid(),
+      jwt: {
+        sign: (payload, secret, header) => __lf_jwt_sign(
+          JSON.stringify(Object.assign({ alg: "HS256", typ: "JWT" }, header || {})),
+          JSON.stringify(payload),
+          String(secret),
+        ),
+        decode: (token) => {
+          const [header, payload] = String(token).split(".");
+          return {
+            header: JSON.parse(__lf_base64url_decode(header || "")),
+            payload: JSON.parse(__lf_base64url_decode(payload || "")),
+          };
+        },
+      },
+    },
+  };
+})();
+"#;
+
+/// Adds `lf.response`, `lf.test` and the `lf.expect` assertion library for test scripts.
+const TEST_PRELUDE: &str = r#"
+(() => {
+  const response = JSON.parse(__lf_response);
+  let parsed;
+  response.json = () => {
+    if (parsed === undefined) parsed = JSON.parse(response.body);
+    return parsed;
+  };
+  response.path = (path) => String(path)
+    .replace(/^(\$|body|response)\.?/, "")
+    .split(/[.[\]]/)
+    .filter((part) => part !== "")
+    .reduce((node, part) => (node == null ? undefined : node[part]), response.json());
+  response.header = (name) => {
+    const wanted = String(name).toLowerCase();
+    const key = Object.keys(response.headers).find((h) => h.toLowerCase() === wanted);
+    return key === undefined ? undefined : response.headers[key];
+  };
+  lf.response = response;
+
+  const show = (value) => {
+    try { return JSON.stringify(value); } catch (_) { return String(value); }
+  };
+  const expect = (actual, negate) => {
+    const to = negate ? "not to" : "to";
+    const check = (ok, what, expected) => {
+      if (Boolean(ok) === negate) {
+        const tail = expected === undefined ? "" : ` ${show(expected)}`;
+        throw new Error(`expected ${show(actual)} ${to} ${what}${tail}`);
+      }
+    };
+    const api = {
+      toBe: (e) => check(actual === e, "be", e),
+      toEqual: (e) => check(show(actual) === show(e), "equal", e),
+      toContain: (e) => check(actual != null && actual.includes(e), "contain", e),
+      toMatch: (re) => check(new RegExp(re).test(String(actual)), "match", String(re)),
+      toBeDefined: () => check(actual !== undefined, "be defined"),
+      toBeTruthy: () => check(actual, "be truthy"),
+      toBeGreaterThan: (n) => check(actual > n, "be greater than", n),
+      toBeLessThan: (n) => check(actual < n, "be less than", n),
+      toHaveProperty: (key) => check(
+        actual != null && Object.prototype.hasOwnProperty.call(actual, key), "have property", key),
+      toHaveStatus: (code) => check(actual === code, "have status", code),
+    };
+    if (!negate) api.not = expect(actual, true);
+    return api;
+  };
+  const results = [];
+  lf.expect = (actual) => expect(actual, false);
+  lf.test = (name, fn) => {
+    try {
+      fn();
+      results.push({ name: String(name), passed: true });
+    } catch (e) {
+      results.push({ name: String(name), passed: false, error: String((e && e.message) || e) });
+    }
+  };
+  globalThis.__lf_tests = results;
+})();
+"#;
+
+#[derive(Serialize, Clone)]
+pub struct ConsoleLine {
+    /// `log`, `info`, `warn`, `error` or `debug`.
+    pub level: String,
+    pub message: String,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct ConsoleEvent {
+    request_id: String,
+    level: String,
+    message: String,
+    timestamp_ms: u64,
+}
+
+/// What scripts in a workspace may do, and how long and how much memory they may use.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ScriptSettings {
+    /// `lf.sendRequest`.
+    pub allow_network: bool,
+    /// Whether secret-flagged variables are readable; when off they are left out.
+    pub allow_secrets: bool,
+    /// `lf.env.set`.
+    pub allow_environment_write: bool,
+    pub time_limit_ms: u64,
+    pub memory_limit_mb: usize,
+}
+
+impl Default for ScriptSettings {
+    fn default() -> Self {
+        Self {
+            allow_network: false,
+            allow_secrets: false,
+            allow_environment_write: true,
+            time_limit_ms: 2_000,
+            memory_limit_mb: 32,
+        }
+    }
+}
+
+/// Every workspace's script settings, by workspace path.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct StoredSettings {
+    workspaces: BTreeMap<String, ScriptSettings>,
+}
+
+impl StoredSettings {
+    fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        Ok(crate::app_data_root(app)?.join(SETTINGS_FILE))
+    }
+
+    fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let path = Self::path(app)?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let data =
+            fs::read_to_string(&path).map_err(|e| format!("script settings read failed: {e}"))?;
+        serde_json::from_str(&data).map_err(|e| format!("script settings parse failed: {e}"))
+    }
+}
+
+fn workspace_key(app: &tauri::AppHandle) -> Result<String, String> {
+    Ok(crate::load_workspace_path(app)?
+        .to_string_lossy()
+        .to_string())
+}
+
+impl ScriptSettings {
+    /// The current workspace's settings, or the defaults if it has none.
+    pub fn load(app: &tauri::AppHandle) -> Result<Self, String> {
+        let workspace = workspace_key(app)?;
+        Ok(StoredSettings::load(app)?
+            .workspaces
+            .remove(&workspace)
+            .unwrap_or_default()
+            .clamped())
+    }
+
+    fn clamped(mut self) -> Self {
+        self.time_limit_ms = self
+            .time_limit_ms
+            .clamp(*TIME_LIMIT_MS.start(), *TIME_LIMIT_MS.end());
+        self.memory_limit_mb = self
+            .memory_limit_mb
+            .clamp(*MEMORY_LIMIT_MB.start(), *MEMORY_LIMIT_MB.end());
+        self
+    }
+}
+
+/// Variables a script set, collected by the native setters rather than read back from the
+/// script, so a script can't forge them.
+#[derive(Default, Clone)]
+pub struct VariableSets {
+    /// Variables for this send only, added to the request layer.
+    pub request: HashMap<String, String>,
+    /// Variables to persist in the environment the request is sent with. Always empty unless
+    /// the workspace allows environment writes.
+    pub environment: HashMap<String, String>,
+}
+
+pub struct ScriptOutcome {
+    /// The request after the script's changes.
+    pub request: Value,
+    pub sets: VariableSets,
+    pub console: Vec<ConsoleLine>,
+}
+
+#[derive(Deserialize)]
+struct ScriptExit {
+    request: Value,
+}
+
+pub struct TestOutcome {
+    pub report: TestReport,
+    pub sets: VariableSets,
+    pub console: Vec<ConsoleLine>,
+}
+
+#[derive(Deserialize)]
+struct TestExit {
+    results: Vec<TestResult>,
+}
+
+/// Script inputs and the expression whose JSON text is read back afterwards.
+struct Job<'a> {
+    settings: &'a ScriptSettings,
+    request: &'a Value,
+    vars: &'a Map<String, Value>,
+    response: Option<&'a Value>,
+    script: &'a str,
+    exit: &'a str,
+}
+
+struct Run {
+    /// The exit expression's value; also read after a script error unless time ran out.
+    exit: Option<String>,
+    error: Option<String>,
+    console: Vec<ConsoleLine>,
+    sets: VariableSets,
+}
+
+fn hex_digest<D: Digest>(value: &str) -> String {
+    hex::encode(D::digest(value.as_bytes()))
+}
+
+fn jwt_sign(header: &str, payload: &str, secret: &str) -> String {
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(header),
+        URL_SAFE_NO_PAD.encode(payload)
+    );
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(signing_input.as_bytes());
+    format!(
+        "{signing_input}.{}",
+        URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+    )
+}
+
+/// Sends a `{method, url, headers, body}` request directly, without the backend or history,
+/// giving up at `deadline`. Returns `{status, headers, body, time}`, or `{error}` on failure.
+/// Backs `lf.sendRequest` and the plugin `network` capability.
+pub fn fetch(spec: &str, deadline: Instant) -> Value {
+    let started = Instant::now();
+    let outcome = (|| -> Result<Value, String> {
+        let spec: Value =
+            serde_json::from_str(spec).map_err(|e| format!("invalid request: {e}"))?;
+        let method = match str_of(&spec, "method") {
+            "" => reqwest::Method::GET,
+            method => reqwest::Method::from_bytes(method.to_uppercase().as_bytes())
+                .map_err(|e| format!("invalid method: {e}"))?,
+        };
+        let url = str_of(&spec, "url").to_string();
+        if url.is_empty() {
+            return Err("url is required".to_string());
+        }
+        let remaining = deadline.saturating_duration_since(started);
+        tauri::async_runtime::block_on(async move {
+            let client = reqwest::Client::builder()
+                .timeout(remaining)
+                .build()
+                .map_err(|e| format!("request failed: {e}"))?;
+            let mut request = client.request(method, &url);
+            if let Some(headers) = spec.get("headers").and_then(Value::as_object) {
+                for (name, value) in headers {
+                    let value = match value {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    };
+                    request = request.header(name, value);
+                }
+            }
+            if let Some(body) = spec.get("body").and_then(Value::as_str) {
+                request = request.body(body.to_string());
+            }
+            let response = request
+                .send()
+                .await
+                .map_err(|e| format!("request failed: {e}"))?;
+            let status = response.status().as_u16();
+            let headers: Map<String, Value> = response
+                .headers()
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        name.to_string(),
+                        Value::String(String::from_utf8_lossy(value.as_bytes()).to_string()),
+                    )
+                })
+                .collect();
+            let body = response
+                .text()
+                .await
+                .map_err(|e| format!("request failed: {e}"))?;
+            Ok(serde_json::json!({
+                "status": status,
+                "headers": headers,
+                "body": body,
+                "time": started.elapsed().as_millis() as u64,
+            }))
+        })
+    })();
+    outcome.unwrap_or_else(|error| serde_json::json!({ "error": error }))
+}
+
+fn sandbox(app: &tauri::AppHandle, job: Job) -> Result<Run, String> {
+    let request_id = job
+        .request
+        .get("id")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let console: Rc<RefCell<Vec<ConsoleLine>>> = Rc::new(RefCell::new(Vec::new()));
+    let sets: Rc<RefCell<VariableSets>> = Rc::new(RefCell::new(VariableSets::default()));
+
+    let runtime = Runtime::new().map_err(|e| format!("script runtime failed: {e}"))?;
+    runtime.set_memory_limit(job.settings.memory_limit_mb << 20);
+    runtime.set_max_stack_size(STACK_LIMIT);
+    let deadline = Instant::now() + Duration::from_millis(job.settings.time_limit_ms);
+    let timed_out = Arc::new(AtomicBool::new(false));
+    let flag = timed_out.clone();
+    runtime.set_interrupt_handler(Some(Box::new(move || {
+        let over = Instant::now() > deadline;
+        if over {
+            flag.store(true, Ordering::Relaxed);
+        }
+        over
+    })));
+    let context = Context::full(&runtime).map_err(|e| format!("script runtime failed: {e}"))?;
+
+    let lines = console.clone();
+    let request_sets = sets.clone();
+    let environment_sets = sets.clone();
+    let emitter = app.clone();
+    let (exit, error) =
+        context.with(|ctx| -> Result<(Option<String>, Option<String>), String> {
+            let globals = ctx.globals();
+            let setup = || -> rquickjs::Result<()> {
+                globals.set("__lf_request", job.request.to_string())?;
+                globals.set("__lf_vars", Value::Object(job.vars.clone()).to_string())?;
+                globals.set(
+                    "__lf_permissions",
+                    serde_json::to_string(job.settings).unwrap_or_default(),
+                )?;
+                if let Some(response) = job.response {
+                    globals.set("__lf_response", response.to_string())?;
+                }
+                globals.set(
+                    "__lf_console",
+                    Function::new(ctx.clone(), move |level: String, message: String| {
+                        let line = ConsoleLine {
+                            level,
+                            message,
+                            timestamp_ms: crate::now_ms(),
+                        };
+                        let _ = emitter.emit(
+                            "script://console",
+                            ConsoleEvent {
+                                request_id: request_id.clone(),
+                                level: line.level.clone(),
+                                message: line.message.clone(),
+                                timestamp_ms: line.timestamp_ms,
+                            },
+                        );
+                        lines.borrow_mut().push(line);
+                    })?,
+                )?;
+                globals.set(
+                    "__lf_sha1",
+                    Function::new(ctx.clone(), |v: String| hex_digest::<Sha1>(&v))?,
+                )?;
+                globals.set(
+                    "__lf_sha256",
+                    Function::new(ctx.clone(), |v: String| hex_digest::<Sha256>(&v))?,
+                )?;
+                globals.set(
+                    "__lf_hmac_sha256",
+                    Function::new(ctx.clone(), |key: String, v: String| {
+                        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+                            .expect("HMAC accepts keys of any length");
+                        mac.update(v.as_bytes());
+                        hex::encode(mac.finalize().into_bytes())
+                    })?,
+                )?;
+                globals.set(
+                    "__lf_base64_encode",
+                    Function::new(ctx.clone(), |v: String| STANDARD.encode(v.as_bytes()))?,
+                )?;
+                globals.set(
+                    "__lf_base64_decode",
+                    Function::new(ctx.clone(), |v: String| {
+                        STANDARD
+                            .decode(v.trim())
+                            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+                            .unwrap_or_default()
+                    })?,
+                )?;
+                globals.set(
+                    "__lf_base64url_encode",
+                    Function::new(ctx.clone(), |v: String| {
+                        URL_SAFE_NO_PAD.encode(v.as_bytes())
+                    })?,
+                )?;
+                globals.set(
+                    "__lf_base64url_decode",
+                    Function::new(ctx.clone(), |v: String| {
+                        URL_SAFE_NO_PAD
+                            .decode(v.trim().trim_end_matches('='))
+                            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+                            .unwrap_or_default()
+                    })?,
+                )?;
+                globals.set(
+                    "__lf_jwt_sign",
+                    Function::new(
+                        ctx.clone(),
+                        |header: String, payload: String, secret: String| {
+                            jwt_sign(&header, &payload, &secret)
+                        },
+                    )?,
+                )?;
+                // Native waits can't be interrupted, so they never outlast the deadline.
+                globals.set(
+                    "__lf_sleep",
+                    Function::new(ctx.clone(), move |ms: f64| {
+                        let wanted = Duration::from_millis(ms.max(0.0) as u64);
+                        let left = deadline.saturating_duration_since(Instant::now());
+                        std::thread::sleep(wanted.min(left));
+                    })?,
+                )?;
+                globals.set(
+                    "__lf_set_request",
+                    Function::new(ctx.clone(), move |key: String, value: String| {
+                        request_sets.borrow_mut().request.insert(key, value);
+                    })?,
+                )?;
+                if job.settings.allow_environment_write {
+                    globals.set(
+                        "__lf_set_environment",
+                        Function::new(ctx.clone(), move |key: String, value: String| {
+                            environment_sets.borrow_mut().environment.insert(key, value);
+                        })?,
+                    )?;
+                }
+                if job.settings.allow_network {
+                    globals.set(
+                        "__lf_send",
+                        Function::new(ctx.clone(), move |spec: String| {
+                            fetch(&spec, deadline).to_string()
+                        })?,
+                    )?;
+                }
+                globals.set(
+                    "__lf_uuid",
+                    Function::new(ctx.clone(), || uuid::Uuid::new_v4().to_string())?,
+                )?;
+                Ok(())
+            };
+            setup().map_err(|e| format!("script setup failed: {e}"))?;
+            ctx.eval::<(), _>(PRELUDE)
+                .catch(&ctx)
+                .map_err(|e| format!("script setup failed: {e}"))?;
+            if job.response.is_some() {
+                ctx.eval::<(), _>(TEST_PRELUDE)
+                    .catch(&ctx)
+                    .map_err(|e| format!("script setup failed: {e}"))?;
+            }
+            // Wrapped in a function so scripts may `return` early.
+            let error = ctx
+                .eval::<(), _>(format!("(function () {{\n{}\n}})();", job.script))
+                .catch(&ctx)
+                .err()
+                .map(|e| match timed_out.load(Ordering::Relaxed) {
+                    true => format!(
+                        "script exceeded its {} ms time limit",
+                        job.settings.time_limit_ms
+                    ),
+                    false => e.to_string(),
+                });
+            if timed_out.load(Ordering::Relaxed) {
+                return Ok((None, error));
+            }
+            let exit = ctx
+                .eval::<String, _>(job.exit)
+                .catch(&ctx)
+                .map_err(|e| format!("script result is not serializable: {e}"))?;
+            Ok((Some(exit), error))
+        })?;
+    let console = console.borrow().clone();
+    let mut sets = sets.borrow().clone();
+    if !job.settings.allow_environment_write {
+        sets.environment.clear();
+    }
+    Ok(Run {
+        exit,
+        error,
+        console,
+        sets,
+    })
+}
+
+fn pre_request(
+    app: &tauri::AppHandle,
+    settings: &ScriptSettings,
+    script: &str,
+    request: &Value,
+    vars: &Map<String, Value>,
+) -> Result<ScriptOutcome, String> {
+    let run = sandbox(
+        app,
+        Job {
+            settings,
+            request,
+            vars,
+            response: None,
+            script,
+            exit: "JSON.stringify({ request: lf.request })",
+        },
+    )?;
+    if let Some(error) = run.error {
+        return Err(format!("pre-request script failed: {error}"));
+    }
+    let exit: ScriptExit = serde_json::from_str(run.exit.as_deref().unwrap_or_default())
+        .map_err(|e| format!("script result invalid: {e}"))?;
+    if !exit.request.is_object() {
+        return Err("lf.request must remain an object".to_string());
+    }
+    Ok(ScriptOutcome {
+        request: exit.request,
+        sets: run.sets,
+        console: run.console,
+    })
+}
+
+fn tests(
+    app: &tauri::AppHandle,
+    settings: &ScriptSettings,
+    script: &str,
+    request: &Value,
+    vars: &Map<String, Value>,
+    response: &Value,
+) -> Result<TestOutcome, String> {
+    let run = sandbox(
+        app,
+        Job {
+            settings,
+            request,
+            vars,
+            response: Some(response),
+            script,
+            exit: "JSON.stringify({ results: __lf_tests })",
+        },
+    )?;
+    let exit = match run.exit.as_deref() {
+        Some(text) => {
+            serde_json::from_str(text).map_err(|e| format!("test results invalid: {e}"))?
+        }
+        None => TestExit {
+            results: Vec::new(),
+        },
+    };
+    let passed = exit.results.iter().filter(|r| r.passed).count();
+    Ok(TestOutcome {
+        report: TestReport {
+            passed,
+            failed: exit.results.len() - passed,
+            results: exit.results,
+            error: run.error,
+        },
+        sets: run.sets,
+        console: run.console,
+    })
+}
+
+/// Runs a pre-request script off the async runtime, with `scope` as the variables it can
+/// read. Console output up to a failure has already been streamed.
+pub async fn run_pre_request(
+    app: &tauri::AppHandle,
+    script: String,
+    request: Value,
+    scope: &Scope,
+) -> Result<ScriptOutcome, String> {
+    let settings = ScriptSettings::load(app)?;
+    let vars = scope.values(settings.allow_secrets);
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        pre_request(&app, &settings, &script, &request, &vars)
+    })
+    .await
+    .map_err(|e| format!("pre-request script failed: {e}"))?
+}
+
+/// Runs a test script against a backend `RequestResult`. The script sees it as
+/// `lf.response` with `status`, `headers`, `body`, `time` and `json()`/`path()`/`header()`.
+pub async fn run_tests(
+    app: &tauri::AppHandle,
+    script: String,
+    request: Value,
+    scope: &Scope,
+    result: &Value,
+) -> Result<TestOutcome, String> {
+    let settings = ScriptSettings::load(app)?;
+    let vars = scope.values(settings.allow_secrets);
+    let response = serde_json::json!({
+        "status": result.get("status_code").cloned().unwrap_or(Value::from(0)),
+        "headers": result.get("headers").cloned().unwrap_or_else(|| Value::Object(Map::new())),
+        "body": result.get("body").map(|b| match b {
+            Value::String(text) => text.clone(),
+            Value::Null => String::new(),
+            other => other.to_string(),
+        }).unwrap_or_default(),
+        "time": result.get("duration_ms").cloned().unwrap_or(Value::from(0)),
+        "error": result.get("error").cloned().unwrap_or(Value::Null),
+    });
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        tests(&app, &settings, &script, &request, &vars, &response)
+    })
+    .await
+    .map_err(|e| format!("test script failed: {e}"))?
+}
+
+/// Reads the current workspace's script permissions and limits.
+#[tauri::command]
+pub async fn get_script_settings(app: tauri::AppHandle) -> Result<ScriptSettings, String> {
+    ScriptSettings::load(&app)
+}
+
+/// Saves the current workspace's script permissions and limits. Limits outside the supported
+/// range (100 ms to 30 s, 8 to 512 MB) are clamped; the saved settings are returned.
+#[tauri::command]
+pub async fn set_script_settings(
+    app: tauri::AppHandle,
+    settings: ScriptSettings,
+) -> Result<ScriptSettings, String> {
+    let settings = settings.clamped();
+    let mut stored = StoredSettings::load(&app)?;
+    stored
+        .workspaces
+        .insert(workspace_key(&app)?, settings.clone());
+    let payload = serde_json::to_string_pretty(&stored)
+        .map_err(|e| format!("script settings serialize failed: {e}"))?;
+    fs::write(StoredSettings::path(&app)?, payload)
+        .map_err(|e| format!("script settings persist failed: {e}"))?;
+    Ok(settings)
+}
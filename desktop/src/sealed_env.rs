@@ -0,0 +1,271 @@
+//! Encrypted environment files, for teams that commit environments to a shared repository:
+//! `encrypt_environment` writes `collections/<id>/environment.enc.json` beside the
+//! collection, with the same layout as its environment file but every secret value (or,
+//! with `all_values`, every value) replaced by `ENC[age,<base64>]`, sealed to the
+//! workspace key (the sync key, see `sync::crypt`). Keys and plain values stay readable, so
+//! the file diffs and merges like any other.
+//!
+//! Plaintext secrets found in the environment file along the way are moved to the keychain,
+//! so only ciphertext is left on disk. `decrypt_environment` reverses it on a teammate's
+//! machine: secret values go to the keychain and the rest into the environment file.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::sync::crypt;
+
+const SEALED_FILE: &str = "environment.enc.json";
+const PREFIX: &str = "ENC[age,";
+const FORMAT_VERSION: u64 = 1;
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct EncryptOptions {
+    /// Only these environments; all of them by default.
+    pub env_names: Option<Vec<String>>,
+    /// Encrypt every value, not only those flagged secret.
+    pub all_values: bool,
+}
+
+#[derive(Serialize)]
+pub struct SealedSummary {
+    pub path: String,
+    pub environments: usize,
+    /// Values encrypted or decrypted.
+    pub values: usize,
+    /// Secret values written to the keychain instead of the environment file.
+    pub moved_to_keychain: usize,
+}
+
+/// Collection ids come back from the UI (or a docs preview URL) and name a directory and
+/// backend routes; keep them to one path segment.
+pub(crate) fn check_collection_id(collection_id: &str) -> Result<(), String> {
+    if collection_id.is_empty()
+        || !collection_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+    {
+        return Err(format!("unknown collection: {collection_id}"));
+    }
+    Ok(())
+}
+
+fn sealed_path(app: &tauri::AppHandle, collection_id: &str) -> Result<PathBuf, String> {
+    Ok(crate::load_workspace_path(app)?
+        .join("collections")
+        .join(collection_id)
+        .join(SEALED_FILE))
+}
+
+fn workspace_identity(app: &tauri::AppHandle) -> Result<age::x25519::Identity, String> {
+    crypt::load(&crate::history::workspace_key(app)?)?
+        .ok_or_else(|| "this workspace has no key; create or import its sync key first".to_string())
+}
+
+fn seal_value(recipient: &age::x25519::Recipient, value: &str) -> Result<String, String> {
+    let sealed = age::encrypt(recipient, value.as_bytes())
+        .map_err(|e| format!("environment encrypt failed: {e}"))?;
+    Ok(format!("{PREFIX}{}]", STANDARD.encode(sealed)))
+}
+
+/// The plaintext of an `ENC[age,…]` value, or `None` for a value stored in the clear.
+fn open_value(identity: &age::x25519::Identity, value: &str) -> Result<Option<String>, String> {
+    let Some(encoded) = value
+        .strip_prefix(PREFIX)
+        .and_then(|rest| rest.strip_suffix(']'))
+    else {
+        return Ok(None);
+    };
+    let sealed = STANDARD
+        .decode(encoded)
+        .map_err(|e| format!("environment value is not valid base64: {e}"))?;
+    let plain = age::decrypt(identity, &sealed).map_err(|_| {
+        "environment decrypt failed: the file was sealed to another workspace key".to_string()
+    })?;
+    String::from_utf8(plain)
+        .map(Some)
+        .map_err(|_| "decrypted environment value is not UTF-8".to_string())
+}
+
+/// Writes the collection's encrypted environment file from its current environments, with
+/// secret values read from the keychain.
+#[tauri::command]
+pub async fn encrypt_environment(
+    app: tauri::AppHandle,
+    collection_id: String,
+    options: Option<EncryptOptions>,
+) -> Result<SealedSummary, String> {
+    crate::lock::ensure_writable(&app)?;
+    check_collection_id(&collection_id)?;
+    let options = options.unwrap_or_default();
+    let recipient = workspace_identity(&app)?.to_public();
+    let env_path = format!("/collections/{collection_id}/environment");
+    let mut environment = crate::backend_get(&app, &env_path).await?;
+    let names: Vec<String> = environment
+        .get("envs")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flat_map(|envs| envs.keys().cloned())
+        .filter(|name| {
+            options
+                .env_names
+                .as_ref()
+                .is_none_or(|wanted| wanted.contains(name))
+        })
+        .collect();
+    if names.is_empty() {
+        return Err("no environments to encrypt".to_string());
+    }
+
+    let mut sealed_envs = Map::new();
+    let mut values = 0;
+    let mut moved = 0;
+    for name in &names {
+        let resolved = crate::secrets::resolve_environment(&collection_id, &environment, name)?;
+        let mut variables = Map::new();
+        let mut flags = Map::new();
+        for variable in resolved {
+            let value = match variable.secret || options.all_values {
+                true => {
+                    values += 1;
+                    seal_value(&recipient, &variable.value)?
+                }
+                false => variable.value.clone(),
+            };
+            variables.insert(variable.key.clone(), Value::String(value));
+            if !variable.secret {
+                continue;
+            }
+            flags.insert(variable.key.clone(), Value::Bool(true));
+            let plain = &mut environment["envs"][name]["variables"][&variable.key];
+            if plain.as_str().is_some_and(|v| !v.is_empty()) {
+                crate::secrets::store(&collection_id, name, &variable.key, &variable.value)?;
+                *plain = Value::String(String::new());
+                moved += 1;
+            }
+        }
+        sealed_envs.insert(
+            name.clone(),
+            json!({ "variables": variables, "secrets": flags }),
+        );
+    }
+
+    let document = json!({
+        "litefetch_encrypted": {
+            "version": FORMAT_VERSION,
+            "recipient": recipient.to_string(),
+            "encrypted_at_ms": crate::now_ms(),
+            "all_values": options.all_values,
+        },
+        "envs": sealed_envs,
+    });
+    let path = sealed_path(&app, &collection_id)?;
+    let payload = serde_json::to_string_pretty(&document)
+        .map_err(|e| format!("environment encode failed: {e}"))?;
+    fs::write(&path, payload).map_err(|e| format!("encrypted environment write failed: {e}"))?;
+    if moved > 0 {
+        crate::backend_post(&app, &env_path, &environment).await?;
+    }
+    Ok(SealedSummary {
+        path: path.to_string_lossy().to_string(),
+        environments: names.len(),
+        values,
+        moved_to_keychain: moved,
+    })
+}
+
+/// Loads the collection's encrypted environment file (or the one at `path`) into its
+/// environments: secret values into the keychain, the rest into the environment file.
+/// Variables the file doesn't list are left as they are.
+#[tauri::command]
+pub async fn decrypt_environment(
+    app: tauri::AppHandle,
+    collection_id: String,
+    path: Option<String>,
+) -> Result<SealedSummary, String> {
+    crate::lock::ensure_writable(&app)?;
+    check_collection_id(&collection_id)?;
+    let identity = workspace_identity(&app)?;
+    let path = match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => crate::normalize_path(path),
+        None => sealed_path(&app, &collection_id)?,
+    };
+    let raw =
+        fs::read_to_string(&path).map_err(|e| format!("encrypted environment read failed: {e}"))?;
+    let document: Value = serde_json::from_str(&raw)
+        .map_err(|e| format!("encrypted environment parse failed: {e}"))?;
+    let version = document
+        .pointer("/litefetch_encrypted/version")
+        .and_then(Value::as_u64)
+        .ok_or("not a LiteFetch encrypted environment file")?;
+    if version > FORMAT_VERSION {
+        return Err(format!(
+            "encrypted environment format {version} needs a newer LiteFetch"
+        ));
+    }
+
+    let env_path = format!("/collections/{collection_id}/environment");
+    let mut environment = crate::backend_get(&app, &env_path).await?;
+    if !environment.get("envs").is_some_and(Value::is_object) {
+        environment["envs"] = Value::Object(Map::new());
+    }
+    let sealed_envs = document
+        .get("envs")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let mut values = 0;
+    let mut keychain = Vec::new();
+    for (name, sealed) in &sealed_envs {
+        let target = &mut environment["envs"][name];
+        for field in ["variables", "secrets"] {
+            if !target.get(field).is_some_and(Value::is_object) {
+                target[field] = Value::Object(Map::new());
+            }
+        }
+        for (key, value) in sealed
+            .get("variables")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+        {
+            let text = value.as_str().unwrap_or_default();
+            let plain = match open_value(&identity, text)? {
+                Some(plain) => {
+                    values += 1;
+                    plain
+                }
+                None => text.to_string(),
+            };
+            let secret = sealed.get("secrets").and_then(|s| s.get(key)) == Some(&Value::Bool(true));
+            match secret {
+                true => {
+                    target["variables"][key] = Value::String(String::new());
+                    target["secrets"][key] = Value::Bool(true);
+                    keychain.push((name.clone(), key.clone(), plain));
+                }
+                false => {
+                    target["variables"][key] = Value::String(plain);
+                    if let Some(flags) = target["secrets"].as_object_mut() {
+                        flags.remove(key);
+                    }
+                }
+            }
+        }
+    }
+    crate::backend_post(&app, &env_path, &environment).await?;
+    let moved = keychain.len();
+    for (name, key, value) in keychain {
+        crate::secrets::store(&collection_id, &name, &key, &value)?;
+    }
+    Ok(SealedSummary {
+        path: path.to_string_lossy().to_string(),
+        environments: sealed_envs.len(),
+        values,
+        moved_to_keychain: moved,
+    })
+}
@@ -0,0 +1,564 @@
+//! Workspace-wide search for the search palette: collections, folders, requests (name, URL,
+//! headers, body, docs), workflows and environments (variable names only) in a SQLite FTS5
+//! index under `.litefetch/search`. The index is rebuilt from the backend when a collection
+//! has changed since it was built, or after `STALE_AFTER_MS` for edits that don't touch the
+//! collection list. Secret-flagged headers and bodies, and every variable value, stay out.
+//! The command palette's list (`get_command_index`) is built from the same index.
+
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use crate::importers::{array_of, str_of};
+
+const INDEX_FILE: &str = "index.db";
+/// Environments change without the collection list saying so.
+const STALE_AFTER_MS: u64 = 30_000;
+const DEFAULT_LIMIT: usize = 50;
+const MAX_LIMIT: usize = 200;
+/// Marks around the matched terms in `SearchHit::snippet`.
+const HIGHLIGHT_START: &str = "\u{1}";
+const HIGHLIGHT_END: &str = "\u{2}";
+
+const SCHEMA: &str = "
+    CREATE VIRTUAL TABLE IF NOT EXISTS items USING fts5(
+        kind UNINDEXED, collection_id UNINDEXED, item_id UNINDEXED, path UNINDEXED,
+        title, url, headers, body, docs,
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value INTEGER NOT NULL);
+";
+
+#[derive(Serialize)]
+pub struct SearchHit {
+    /// `collection`, `folder`, `request`, `workflow` or `environment`.
+    pub kind: String,
+    pub collection_id: String,
+    /// The request, folder or workflow id, or the environment name.
+    pub item_id: Option<String>,
+    pub title: String,
+    /// Where the item sits, e.g. `Payments / Refunds`.
+    pub path: String,
+    /// The best-matching stretch of text, matched terms between U+0001 and U+0002.
+    pub snippet: String,
+    /// Higher is better.
+    pub score: f64,
+}
+
+struct Doc {
+    kind: &'static str,
+    collection_id: String,
+    item_id: Option<String>,
+    path: String,
+    title: String,
+    url: String,
+    headers: String,
+    body: String,
+    docs: String,
+}
+
+impl Doc {
+    fn new(kind: &'static str, collection_id: &str, title: &str, path: &str) -> Self {
+        Self {
+            kind,
+            collection_id: collection_id.to_string(),
+            item_id: None,
+            path: path.to_string(),
+            title: title.to_string(),
+            url: String::new(),
+            headers: String::new(),
+            body: String::new(),
+            docs: String::new(),
+        }
+    }
+}
+
+fn index_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::workspace_state_dir(app, "search")?.join(INDEX_FILE))
+}
+
+fn open(app: &tauri::AppHandle) -> Result<Connection, String> {
+    let conn =
+        Connection::open(index_path(app)?).map_err(|e| format!("search index open failed: {e}"))?;
+    conn.execute_batch(SCHEMA)
+        .map_err(|e| format!("search index init failed: {e}"))?;
+    Ok(conn)
+}
+
+fn keys_of(value: Option<&Value>) -> String {
+    value
+        .and_then(Value::as_object)
+        .map(|map| map.keys().cloned().collect::<Vec<_>>().join(" "))
+        .unwrap_or_default()
+}
+
+fn request_doc(collection_id: &str, request: &Value, path: &str) -> Doc {
+    let mut doc = Doc::new("request", collection_id, str_of(request, "name"), path);
+    doc.item_id = Some(str_of(request, "id").to_string());
+    doc.url = format!("{} {}", str_of(request, "method"), str_of(request, "url"));
+    let secret = |flags: &str, name: &str| {
+        request
+            .get(flags)
+            .and_then(|f| f.get(name))
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+    };
+    doc.headers = request
+        .get("headers")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(name, value)| match secret("secret_headers", name) {
+            true => name.clone(),
+            false => format!("{name}: {}", value.as_str().unwrap_or_default()),
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let secret_body = request
+        .get("secret_body")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let mut body = match (secret_body, request.get("body")) {
+        (true, _) | (_, None | Some(Value::Null)) => String::new(),
+        (false, Some(Value::String(text))) => text.clone(),
+        (false, Some(other)) => other.to_string(),
+    };
+    for param in array_of(request, "query_params") {
+        body.push('\n');
+        body.push_str(str_of(param, "key"));
+    }
+    for field in array_of(request, "form_body") {
+        body.push('\n');
+        body.push_str(str_of(field, "key"));
+    }
+    doc.body = body;
+    doc.docs = ["docs", "description"]
+        .iter()
+        .map(|key| str_of(request, key))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    doc
+}
+
+fn collect_items(collection_id: &str, items: &[Value], path: &str, out: &mut Vec<Doc>) {
+    for item in items {
+        match item.get("items").and_then(Value::as_array) {
+            Some(children) => {
+                let mut doc = Doc::new("folder", collection_id, str_of(item, "name"), path);
+                doc.item_id = Some(str_of(item, "id").to_string());
+                out.push(doc);
+                let inner = format!("{path} / {}", str_of(item, "name"));
+                collect_items(collection_id, children, &inner, out);
+            }
+            None => out.push(request_doc(collection_id, item, path)),
+        }
+    }
+}
+
+fn collection_docs(
+    collection_id: &str,
+    name: &str,
+    collection: &Value,
+    environment: &Value,
+) -> Vec<Doc> {
+    let mut docs = Vec::new();
+    let mut root = Doc::new("collection", collection_id, name, "");
+    root.body = keys_of(collection.get("variables"));
+    docs.push(root);
+    collect_items(
+        collection_id,
+        array_of(collection, "items"),
+        name,
+        &mut docs,
+    );
+    for workflow in array_of(collection, "workflows") {
+        let mut doc = Doc::new("workflow", collection_id, str_of(workflow, "name"), name);
+        doc.item_id = Some(str_of(workflow, "id").to_string());
+        doc.body = array_of(workflow, "steps")
+            .iter()
+            .map(|step| str_of(step, "name"))
+            .filter(|step| !step.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        docs.push(doc);
+    }
+    if let Some(envs) = environment.get("envs").and_then(Value::as_object) {
+        for (env_name, env) in envs {
+            let mut doc = Doc::new("environment", collection_id, env_name, name);
+            doc.item_id = Some(env_name.clone());
+            doc.body = keys_of(env.get("variables"));
+            docs.push(doc);
+        }
+    }
+    docs
+}
+
+/// A fingerprint of the collection list (ids and update times).
+pub(crate) fn fingerprint(collections: &Value) -> i64 {
+    let mut hasher = DefaultHasher::new();
+    for meta in collections.as_array().into_iter().flatten() {
+        str_of(meta, "id").hash(&mut hasher);
+        meta.get("updated_at")
+            .map(Value::to_string)
+            .hash(&mut hasher);
+    }
+    hasher.finish() as i64
+}
+
+fn meta_value(conn: &Connection, key: &str) -> Option<i64> {
+    conn.query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+        row.get(0)
+    })
+    .ok()
+}
+
+fn is_current(conn: &Connection, fingerprint: i64) -> bool {
+    let built = meta_value(conn, "built_ms").unwrap_or(0) as u64;
+    meta_value(conn, "fingerprint") == Some(fingerprint)
+        && crate::now_ms().saturating_sub(built) < STALE_AFTER_MS
+}
+
+fn rebuild(conn: &mut Connection, docs: &[Doc], fingerprint: i64) -> Result<(), String> {
+    let failed = |e: rusqlite::Error| format!("search index build failed: {e}");
+    let tx = conn.transaction().map_err(failed)?;
+    tx.execute("DELETE FROM items", []).map_err(failed)?;
+    {
+        let mut insert = tx
+            .prepare(
+                "INSERT INTO items (kind, collection_id, item_id, path, title, url, headers, body,
+                    docs) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            )
+            .map_err(failed)?;
+        for doc in docs {
+            insert
+                .execute(params![
+                    doc.kind,
+                    doc.collection_id,
+                    doc.item_id,
+                    doc.path,
+                    doc.title,
+                    doc.url,
+                    doc.headers,
+                    doc.body,
+                    doc.docs,
+                ])
+                .map_err(failed)?;
+        }
+    }
+    tx.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES ('fingerprint', ?1), ('built_ms', ?2)",
+        params![fingerprint, crate::now_ms() as i64],
+    )
+    .map_err(failed)?;
+    tx.commit().map_err(failed)
+}
+
+/// The palette's text as an FTS5 query: every word must appear, the last as a prefix so
+/// results follow typing.
+fn fts_query(query: &str) -> Option<String> {
+    let words: Vec<String> = query
+        .split_whitespace()
+        .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+        .collect();
+    let last = words.len().checked_sub(1)?;
+    Some(
+        words
+            .iter()
+            .enumerate()
+            .map(|(i, word)| match i == last {
+                true => format!("{word}*"),
+                false => word.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+async fn load_docs(app: &tauri::AppHandle, collections: &Value) -> Result<Vec<Doc>, String> {
+    let mut docs = Vec::new();
+    for meta in collections.as_array().into_iter().flatten() {
+        let id = str_of(meta, "id");
+        let collection = crate::backend_get(app, &format!("/collections/{id}/collection")).await?;
+        let environment = crate::backend_get(app, &format!("/collections/{id}/environment"))
+            .await
+            .unwrap_or(Value::Null);
+        docs.extend(collection_docs(
+            id,
+            str_of(meta, "name"),
+            &collection,
+            &environment,
+        ));
+    }
+    Ok(docs)
+}
+
+/// Rebuilds the index when the workspace changed since it was built.
+async fn refresh(app: &tauri::AppHandle) -> Result<(), String> {
+    let collections = crate::backend_get(app, "/collections").await?;
+    let fingerprint = fingerprint(&collections);
+    let current = {
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            Ok::<_, String>(is_current(&open(&app)?, fingerprint))
+        })
+        .await
+        .map_err(|e| format!("search failed: {e}"))??
+    };
+    if current {
+        return Ok(());
+    }
+    let docs = load_docs(app, &collections).await?;
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || rebuild(&mut open(&app)?, &docs, fingerprint))
+        .await
+        .map_err(|e| format!("search failed: {e}"))?
+}
+
+/// Searches the current workspace, best matches first: names weigh most, then URLs, docs,
+/// headers and bodies. `kinds` restricts the results to some of `SearchHit::kind`.
+#[tauri::command]
+pub async fn search_workspace(
+    app: tauri::AppHandle,
+    query: String,
+    kinds: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<Vec<SearchHit>, String> {
+    let Some(fts) = fts_query(&query) else {
+        return Ok(Vec::new());
+    };
+    refresh(&app).await?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = open(&app)?;
+        let kinds = kinds
+            .filter(|kinds| !kinds.is_empty())
+            .map(|kinds| Value::from(kinds).to_string());
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let mut statement = conn
+            .prepare(&format!(
+                "SELECT kind, collection_id, item_id, path, title,
+                    snippet(items, -1, '{HIGHLIGHT_START}', '{HIGHLIGHT_END}', '…', 12),
+                    bm25(items, 0, 0, 0, 0, 10.0, 5.0, 2.0, 1.0, 3.0)
+                 FROM items WHERE items MATCH ?1
+                    AND (?2 IS NULL OR kind IN (SELECT value FROM json_each(?2)))
+                 ORDER BY 7 LIMIT ?3"
+            ))
+            .map_err(|e| format!("search failed: {e}"))?;
+        statement
+            .query_map(params![fts, kinds, limit as i64], |row| {
+                Ok(SearchHit {
+                    kind: row.get(0)?,
+                    collection_id: row.get(1)?,
+                    item_id: row.get(2)?,
+                    path: row.get(3)?,
+                    title: row.get(4)?,
+                    snippet: row.get(5)?,
+                    // bm25 is lower for better matches.
+                    score: -row.get::<_, f64>(6)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| format!("search failed: {e}"))
+    })
+    .await
+    .map_err(|e| format!("search failed: {e}"))?
+}
+
+#[derive(Serialize)]
+pub struct PaletteEntry {
+    /// `action`, `request`, `environment` or `history`.
+    pub kind: String,
+    /// Unique across the index.
+    pub key: String,
+    pub title: String,
+    /// The request's method and URL, an environment's collection, an action's shortcut.
+    pub detail: String,
+    pub collection_id: Option<String>,
+    /// The request id, environment name, action or history entry id.
+    pub item_id: Option<String>,
+    /// Higher comes first.
+    pub rank: f64,
+}
+
+fn palette_entry(
+    kind: &str,
+    key: String,
+    title: String,
+    detail: String,
+    rank: f64,
+) -> PaletteEntry {
+    PaletteEntry {
+        kind: kind.to_string(),
+        key,
+        title,
+        detail,
+        collection_id: None,
+        item_id: None,
+        rank,
+    }
+}
+
+/// Decays from 1 to 0.5 over `half_life_ms`.
+fn recency(now_ms: u64, at_ms: u64, half_life_ms: u64) -> f64 {
+    0.5f64.powf(now_ms.saturating_sub(at_ms) as f64 / half_life_ms as f64)
+}
+
+/// Requests and environments from the index, ranked by how often and how lately each
+/// request was sent, then the newest `history_limit` history entries.
+fn workspace_entries(
+    app: &tauri::AppHandle,
+    history_limit: usize,
+) -> Result<Vec<PaletteEntry>, String> {
+    const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+    let failed = |e: rusqlite::Error| format!("command index failed: {e}");
+    let now = crate::now_ms();
+    let workspace = crate::history::workspace_key(app)?;
+    let history = crate::history::open(app)?;
+
+    let mut usage = std::collections::HashMap::new();
+    let mut statement = history
+        .prepare(
+            "SELECT collection_id, request_id, timestamp_ms FROM entries
+             WHERE workspace = ?1 AND request_id IS NOT NULL AND timestamp_ms > ?2",
+        )
+        .map_err(failed)?;
+    let sends = statement
+        .query_map(
+            params![workspace, now.saturating_sub(30 * DAY_MS) as i64],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            },
+        )
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(failed)?;
+    for (collection_id, request_id, at) in sends {
+        *usage.entry((collection_id, request_id)).or_insert(0.0) +=
+            recency(now, at as u64, 7 * DAY_MS);
+    }
+
+    let mut entries = Vec::new();
+    let conn = open(app)?;
+    let mut statement = conn
+        .prepare(
+            "SELECT kind, collection_id, item_id, path, title, url FROM items
+             WHERE kind IN ('request', 'environment')",
+        )
+        .map_err(failed)?;
+    let rows = statement
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+            ))
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(failed)?;
+    for (kind, collection_id, item_id, path, title, url) in rows {
+        let (detail, rank) = match kind.as_str() {
+            "request" => {
+                let used = usage
+                    .get(&(collection_id.clone(), item_id.clone()))
+                    .copied()
+                    .unwrap_or(0.0);
+                (format!("{url} · {path}"), 1.0 + used)
+            }
+            _ => (path, 0.5),
+        };
+        let mut entry = palette_entry(
+            &kind,
+            format!("{kind}:{collection_id}:{item_id}"),
+            title,
+            detail,
+            rank,
+        );
+        entry.collection_id = Some(collection_id);
+        entry.item_id = Some(item_id);
+        entries.push(entry);
+    }
+
+    let mut statement = history
+        .prepare(
+            "SELECT id, timestamp_ms, collection_id, name, method, url, status_code FROM entries
+             WHERE workspace = ?1 ORDER BY timestamp_ms DESC, id DESC LIMIT ?2",
+        )
+        .map_err(failed)?;
+    let recent = statement
+        .query_map(params![workspace, history_limit as i64], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, Option<i64>>(6)?,
+            ))
+        })
+        .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+        .map_err(failed)?;
+    for (id, at, collection_id, name, method, url, status) in recent {
+        let title = name
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| url.clone());
+        let status = status
+            .map(|s| s.to_string())
+            .unwrap_or_else(|| "failed".to_string());
+        let mut entry = palette_entry(
+            "history",
+            format!("history:{id}"),
+            title,
+            format!("{method} {url} · {status}"),
+            recency(now, at as u64, DAY_MS),
+        );
+        entry.collection_id = Some(collection_id);
+        entry.item_id = Some(id.to_string());
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Everything the command palette offers, best first: actions with their shortcuts, the
+/// workspace's requests (often and lately sent ones first) and environments, and the newest
+/// history entries (`history_limit`, 20 by default). Names and URLs only, so the palette can
+/// match on the whole list as the user types; the list comes from the search index.
+#[tauri::command]
+pub async fn get_command_index(
+    app: tauri::AppHandle,
+    history_limit: Option<usize>,
+) -> Result<Vec<PaletteEntry>, String> {
+    refresh(&app).await?;
+    let mut entries: Vec<PaletteEntry> = crate::keymap::current(&app)
+        .into_iter()
+        .map(|binding| {
+            let mut entry = palette_entry(
+                "action",
+                format!("action:{}", binding.action),
+                binding.label,
+                binding.accelerator.unwrap_or_default(),
+                1.5,
+            );
+            entry.item_id = Some(binding.action);
+            entry
+        })
+        .collect();
+    let history_limit = history_limit.unwrap_or(20).min(MAX_LIMIT);
+    let workspace =
+        tauri::async_runtime::spawn_blocking(move || workspace_entries(&app, history_limit))
+            .await
+            .map_err(|e| format!("command index failed: {e}"))??;
+    entries.extend(workspace);
+    entries.sort_by(|a, b| b.rank.total_cmp(&a.rank));
+    Ok(entries)
+}
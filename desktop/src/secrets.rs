@@ -0,0 +1,56 @@
+//! Commands for environment secrets kept in the OS keychain; the storage itself is
+//! `litefetch_core::secrets`.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub use litefetch_core::secrets::*;
+
+#[tauri::command]
+pub async fn set_environment_secret(
+    collection_id: String,
+    env_name: String,
+    key: String,
+    value: String,
+) -> Result<(), String> {
+    store(&collection_id, &env_name, &key, &value)
+}
+
+#[tauri::command]
+pub async fn delete_environment_secret(
+    collection_id: String,
+    env_name: String,
+    key: String,
+) -> Result<(), String> {
+    remove(&collection_id, &env_name, &key)
+}
+
+/// Returns the keychain values for every secret-flagged key of an environment. Keys without a
+/// stored value are omitted so plaintext values in the workspace still apply.
+#[tauri::command]
+pub async fn get_environment_secrets(
+    app: tauri::AppHandle,
+    collection_id: String,
+    env_name: String,
+) -> Result<HashMap<String, String>, String> {
+    let environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    let flags = environment
+        .pointer(&format!(
+            "/envs/{}/secrets",
+            env_name.replace('~', "~0").replace('/', "~1")
+        ))
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    let mut values = HashMap::new();
+    for (key, flagged) in flags {
+        if flagged.as_bool() != Some(true) {
+            continue;
+        }
+        if let Some(value) = load(&collection_id, &env_name, &key)? {
+            values.insert(key, value);
+        }
+    }
+    Ok(values)
+}
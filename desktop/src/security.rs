@@ -0,0 +1,188 @@
+//! Security header audits from the UI: `litefetch_core::security` sends the requests of a
+//! collection through `send` and grades each response, reporting it as a
+//! `security://progress` event; each HTTPS host is then probed once for its TLS parameters
+//! (see `probe`) and the per-host report comes back as `security://finished`.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tauri::async_runtime::Mutex;
+use tauri::{Emitter, State};
+
+use litefetch_core::security::{self, ResponseAudit, SecurityReport, TlsParams};
+
+use crate::send::Shell;
+
+pub struct SecurityState {
+    /// Cancel flags of the audits in progress.
+    runs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl SecurityState {
+    pub fn new() -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+struct StartedEvent {
+    run_id: String,
+    collection_id: String,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct ProgressEvent {
+    run_id: String,
+    completed: usize,
+    result: ResponseAudit,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct FinishedEvent {
+    report: SecurityReport,
+    timestamp_ms: u64,
+}
+
+async fn tls_params(host: &str, port: u16) -> TlsParams {
+    let probe =
+        match crate::probe::probe_endpoint(host.to_string(), Some(port), Some(true), None).await {
+            Ok(probe) => probe,
+            Err(e) => {
+                return TlsParams {
+                    error: Some(e),
+                    ..TlsParams::default()
+                }
+            }
+        };
+    match probe.tls {
+        Some(tls) => TlsParams {
+            version: tls.version,
+            cipher_suite: tls.cipher_suite,
+            certificate_trusted: tls.certificate_trusted,
+            certificate_error: tls.certificate_error,
+            error: None,
+        },
+        None => TlsParams {
+            error: probe.error.or(Some("no TLS handshake".to_string())),
+            ..TlsParams::default()
+        },
+    }
+}
+
+/// Sends the requests of a collection (only `request_ids`, when given) with
+/// `environment_id` and grades every response's security headers, and, unless `check_tls`
+/// is `false`, each HTTPS host's TLS parameters. The run id arrives first in a
+/// `security://started` event, for `cancel_security_audit`.
+#[tauri::command]
+pub async fn run_security_audit(
+    app: tauri::AppHandle,
+    state: State<'_, SecurityState>,
+    collection_id: String,
+    environment_id: Option<String>,
+    request_ids: Option<Vec<String>>,
+    check_tls: Option<bool>,
+) -> Result<SecurityReport, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    state
+        .runs
+        .lock()
+        .await
+        .insert(run_id.clone(), cancel.clone());
+    let started_ms = crate::now_ms();
+    let _ = app.emit(
+        "security://started",
+        StartedEvent {
+            run_id: run_id.clone(),
+            collection_id: collection_id.clone(),
+            timestamp_ms: started_ms,
+        },
+    );
+    let sender = Shell {
+        app: app.clone(),
+        collection_id: collection_id.clone(),
+        environment_id,
+    };
+    let mut completed = 0;
+    let (audits, cancelled) = security::execute(
+        &collection,
+        request_ids.as_deref().filter(|ids| !ids.is_empty()),
+        &sender,
+        &cancel,
+        |result| {
+            completed += 1;
+            let _ = app.emit(
+                "security://progress",
+                ProgressEvent {
+                    run_id: run_id.clone(),
+                    completed,
+                    result: result.clone(),
+                    timestamp_ms: crate::now_ms(),
+                },
+            );
+        },
+    )
+    .await;
+    state.runs.lock().await.remove(&run_id);
+
+    let mut tls = HashMap::new();
+    if check_tls.unwrap_or(true) && !cancelled {
+        for audit in &audits {
+            let Ok(url) = reqwest::Url::parse(&audit.url) else {
+                continue;
+            };
+            if url.scheme() != "https" || tls.contains_key(&audit.host) {
+                continue;
+            }
+            let port = url.port_or_known_default().unwrap_or(443);
+            tls.insert(audit.host.clone(), tls_params(&audit.host, port).await);
+        }
+    }
+    let report = SecurityReport {
+        run_id,
+        collection_id,
+        started_ms,
+        cancelled,
+        hosts: security::by_host(audits, |host| tls.get(host).cloned()),
+    };
+    let _ = app.emit(
+        "security://finished",
+        FinishedEvent {
+            report: report.clone(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    Ok(report)
+}
+
+/// Stops an audit from sending further requests; the one in flight still finishes.
+#[tauri::command]
+pub async fn cancel_security_audit(
+    state: State<'_, SecurityState>,
+    run_id: String,
+) -> Result<(), String> {
+    let runs = state.runs.lock().await;
+    let cancel = runs
+        .get(&run_id)
+        .ok_or_else(|| format!("unknown security audit: {run_id}"))?;
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Grades one response already received, such as the one in the response pane, on its
+/// headers alone.
+#[tauri::command]
+pub async fn audit_response_security(
+    request: Value,
+    result: Value,
+) -> Result<ResponseAudit, String> {
+    Ok(security::audit_one(&request, &result))
+}
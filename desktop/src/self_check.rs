@@ -0,0 +1,251 @@
+//! Startup self-check for the troubleshooting screen: whether the backend sidecar is there
+//! and answers with a compatible version, the workspace can be written, the OS keychain
+//! works, a local port can be bound and which renderer the webview got. Each check reports on
+//! its own, so one failure doesn't hide the others.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::time::Instant;
+use tauri::Manager;
+
+use crate::handshake::{Compatibility, HandshakeState};
+use crate::launch::LaunchSettings;
+use crate::rendering::Effective;
+use litefetch_core::{backend, profile};
+
+/// The keychain account written and removed by the keychain check.
+const KEYCHAIN_PROBE: &str = "self-check";
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    /// Works, but something is degraded (e.g. secrets can't be kept in the keychain).
+    Warning,
+    /// Stops the app from starting or working.
+    Failed,
+}
+
+#[derive(Serialize)]
+pub struct Check {
+    /// `sidecar`, `backend_version`, `workspace`, `keychain`, `port` or `rendering`.
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub message: String,
+    pub detail: Value,
+}
+
+impl Check {
+    fn new(name: &'static str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            message: message.into(),
+            detail: Value::Null,
+        }
+    }
+
+    fn with(mut self, detail: Value) -> Self {
+        self.detail = detail;
+        self
+    }
+}
+
+#[derive(Serialize)]
+pub struct SelfCheckReport {
+    /// `false` when any check failed; warnings don't count.
+    pub ok: bool,
+    pub checks: Vec<Check>,
+    pub timestamp_ms: u64,
+    pub duration_ms: u64,
+}
+
+/// Where the bundled sidecar lives: next to the app's executable.
+fn bundled_sidecar() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let name = format!("litefetch-backend{}", std::env::consts::EXE_SUFFIX);
+    Some(exe.parent()?.join(name))
+}
+
+fn sidecar(app: &tauri::AppHandle) -> Check {
+    let settings = match LaunchSettings::load(app) {
+        Ok(settings) => settings,
+        Err(e) => return Check::new("sidecar", CheckStatus::Warning, e),
+    };
+    let replacement = settings
+        .binary
+        .map(|b| b.trim().to_string())
+        .filter(|b| !b.is_empty());
+    let (path, source) = match replacement {
+        Some(binary) => (Some(PathBuf::from(binary)), "launch settings"),
+        None => (bundled_sidecar(), "bundled"),
+    };
+    let Some(path) = path else {
+        return Check::new(
+            "sidecar",
+            CheckStatus::Failed,
+            "unable to resolve the app's executable directory",
+        );
+    };
+    let detail = json!({ "path": path.to_string_lossy(), "source": source });
+    match path.is_file() {
+        true => Check::new("sidecar", CheckStatus::Ok, "backend binary found").with(detail),
+        // A missing replacement falls back to the bundled sidecar (see `launch::merge`).
+        false if source == "launch settings" => Check::new(
+            "sidecar",
+            CheckStatus::Warning,
+            format!(
+                "backend binary not found: {}; the bundled one is used",
+                path.to_string_lossy()
+            ),
+        )
+        .with(detail),
+        false => Check::new(
+            "sidecar",
+            CheckStatus::Failed,
+            format!("backend binary missing: {}", path.to_string_lossy()),
+        )
+        .with(detail),
+    }
+}
+
+/// Starts the backend when it isn't running yet, then reports its handshake.
+async fn backend_version(app: &tauri::AppHandle) -> Check {
+    if let Err(e) = crate::backend_url(app).await {
+        return Check::new(
+            "backend_version",
+            CheckStatus::Failed,
+            format!("backend failed to start: {e}"),
+        );
+    }
+    let state = app.state::<HandshakeState>();
+    let outcome = match crate::handshake::get_backend_version(state).await {
+        Ok(Some(outcome)) => outcome,
+        Ok(None) => {
+            return Check::new(
+                "backend_version",
+                CheckStatus::Warning,
+                "backend started but hasn't reported a version",
+            )
+        }
+        Err(e) => return Check::new("backend_version", CheckStatus::Failed, e),
+    };
+    let status = match outcome.compatibility {
+        Compatibility::Compatible => CheckStatus::Ok,
+        Compatibility::Warning => CheckStatus::Warning,
+        Compatibility::Incompatible | Compatibility::Unreachable => CheckStatus::Failed,
+    };
+    let message = outcome.message.clone().unwrap_or_else(|| {
+        format!(
+            "backend {} matches the app",
+            outcome.version.as_deref().unwrap_or("unknown")
+        )
+    });
+    Check::new("backend_version", status, message)
+        .with(serde_json::to_value(&outcome).unwrap_or(Value::Null))
+}
+
+/// Writes and removes a file at the workspace root.
+fn workspace(app: &tauri::AppHandle) -> Check {
+    let root = match crate::load_workspace_path(app) {
+        Ok(root) => root,
+        Err(e) => return Check::new("workspace", CheckStatus::Failed, e),
+    };
+    let detail = json!({ "path": root.to_string_lossy() });
+    let probe = root.join(format!(".litefetch-self-check-{}", uuid::Uuid::new_v4()));
+    let written = fs::create_dir_all(&root)
+        .and_then(|_| fs::write(&probe, b"ok"))
+        .and_then(|_| fs::remove_file(&probe));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&probe);
+        return Check::new(
+            "workspace",
+            CheckStatus::Failed,
+            format!("workspace not writable: {e}"),
+        )
+        .with(detail);
+    }
+    match crate::lock::ensure_writable(app) {
+        Ok(()) => Check::new("workspace", CheckStatus::Ok, "workspace is writable").with(detail),
+        Err(e) => Check::new("workspace", CheckStatus::Warning, e).with(detail),
+    }
+}
+
+/// Stores, reads back and removes a throwaway keychain entry.
+fn keychain() -> Check {
+    let service = profile::keychain_service();
+    let detail = json!({ "service": service });
+    let outcome = keyring::Entry::new(&service, KEYCHAIN_PROBE)
+        .map_err(|e| format!("keychain unavailable: {e}"))
+        .and_then(|entry| {
+            let value = uuid::Uuid::new_v4().to_string();
+            entry
+                .set_password(&value)
+                .map_err(|e| format!("keychain write failed: {e}"))?;
+            let read = entry
+                .get_password()
+                .map_err(|e| format!("keychain read failed: {e}"));
+            let _ = entry.delete_credential();
+            match read? == value {
+                true => Ok(()),
+                false => Err("keychain read back a different value".to_string()),
+            }
+        });
+    match outcome {
+        Ok(()) => Check::new("keychain", CheckStatus::Ok, "keychain is available").with(detail),
+        // Secrets then stay in the workspace files; everything else works.
+        Err(e) => Check::new("keychain", CheckStatus::Warning, e).with(detail),
+    }
+}
+
+fn port() -> Check {
+    match backend::reserve_port() {
+        Ok(port) => Check::new("port", CheckStatus::Ok, "a local port can be bound")
+            .with(json!({ "port": port })),
+        Err(e) => Check::new("port", CheckStatus::Failed, e),
+    }
+}
+
+async fn rendering(app: &tauri::AppHandle) -> Check {
+    let rendering = match crate::rendering::get_rendering(app.clone()).await {
+        Ok(rendering) => rendering,
+        Err(e) => return Check::new("rendering", CheckStatus::Warning, e),
+    };
+    let status = match rendering.effective {
+        Effective::Software => CheckStatus::Warning,
+        Effective::Gpu | Effective::GpuWithoutDmabuf => CheckStatus::Ok,
+    };
+    let message = format!("{}: {}", label(rendering.effective), rendering.reason);
+    Check::new("rendering", status, message)
+        .with(serde_json::to_value(&rendering).unwrap_or(Value::Null))
+}
+
+fn label(effective: Effective) -> &'static str {
+    match effective {
+        Effective::Gpu => "GPU rendering",
+        Effective::GpuWithoutDmabuf => "GPU rendering without DMA-BUF",
+        Effective::Software => "software rendering",
+    }
+}
+
+/// Runs every startup check and reports each one, for the troubleshooting screen.
+#[tauri::command]
+pub async fn run_self_check(app: tauri::AppHandle) -> Result<SelfCheckReport, String> {
+    let started = Instant::now();
+    let checks = vec![
+        sidecar(&app),
+        backend_version(&app).await,
+        workspace(&app),
+        keychain(),
+        port(),
+        rendering(&app).await,
+    ];
+    Ok(SelfCheckReport {
+        ok: checks.iter().all(|c| c.status != CheckStatus::Failed),
+        checks,
+        timestamp_ms: crate::now_ms(),
+        duration_ms: started.elapsed().as_millis() as u64,
+    })
+}
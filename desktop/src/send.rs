@@ -0,0 +1,422 @@
+//! Sending saved or edited requests through the shell. `dispatch` prepares a request
+//! (hooks, pre-request script, defaults, routing, variables, auth), `run` makes the trip and
+//! records the response, and `dispatch` finishes with the response captures, post hooks and
+//! test script. Each step lives in its own module, named where it is called.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use litefetch_core::send::{mark_secret, resolve_request, Sender, Sent, TestReport};
+
+use crate::defaults::RequestDefaults;
+use crate::dynamic::DynamicValue;
+use crate::hooks::Phase;
+use crate::importers::str_of;
+use crate::network::{NetworkProfile, Shaping};
+use crate::scripting::ConsoleLine;
+use crate::variables::{Scope, VariableSource};
+
+#[derive(Serialize)]
+pub struct SendResult {
+    /// The backend `RequestResult`, including its `sent_request` snapshot.
+    pub result: Value,
+    /// Every dynamic reference evaluated for this send, in request order.
+    pub dynamic: Vec<DynamicValue>,
+    /// Script output from both phases, also streamed as `script://console` events.
+    pub console: Vec<ConsoleLine>,
+    /// The test script's report, also set as `result.tests`; `None` without a test script.
+    pub tests: Option<TestReport>,
+}
+
+fn script_of(request: &Value, field: &str) -> Option<String> {
+    request
+        .get(field)
+        .and_then(Value::as_str)
+        .filter(|s| !s.trim().is_empty())
+        .map(str::to_string)
+}
+
+/// Saves variables set by a script with `lf.environment.set`. Keys flagged secret in the
+/// environment go to the keychain; the rest are written to the environment file.
+async fn persist_environment(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    env_name: &str,
+    values: HashMap<String, String>,
+) -> Result<(), String> {
+    let env_path = format!("/collections/{collection_id}/environment");
+    let mut environment = crate::backend_get(app, &env_path).await?;
+    let env = environment
+        .pointer_mut(&format!(
+            "/envs/{}",
+            env_name.replace('~', "~0").replace('/', "~1")
+        ))
+        .and_then(Value::as_object_mut)
+        .ok_or_else(|| format!("unknown environment: {env_name}"))?;
+    if !env.get("variables").is_some_and(Value::is_object) {
+        env.insert("variables".to_string(), Value::Object(Map::new()));
+    }
+    let mut keychain = Vec::new();
+    for (key, value) in values {
+        let secret = env.get("secrets").and_then(|s| s.get(&key)) == Some(&Value::Bool(true));
+        match secret {
+            true => keychain.push((key, value)),
+            false => {
+                env["variables"][&key] = Value::String(value);
+            }
+        }
+    }
+    crate::backend_post(app, &env_path, &environment).await?;
+    for (key, value) in keychain {
+        crate::secrets::store(collection_id, env_name, &key, &value)?;
+    }
+    Ok(())
+}
+
+/// Sends an HTTP request (in the collection's `HttpRequest` shape) through the backend after
+/// resolving its variables with the environment `environment_id` (the active one by
+/// default). The request may be unsaved; `variables` on it form the request layer, and its
+/// `pre_request_script` may change it before anything is resolved. A `test_script` sees the
+/// request as it was before resolution, so secrets stay as references. Bodies over the
+/// workspace's size limit come back as a preview (see `responses::limit`).
+#[tauri::command]
+pub async fn send_request(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request: Value,
+    environment_id: Option<String>,
+) -> Result<SendResult, String> {
+    let mut sent = dispatch(app.clone(), collection_id, request, environment_id).await?;
+    crate::responses::limit(&app, &mut sent.result);
+    Ok(sent)
+}
+
+/// `send_request` with the whole body in the result, for runs and monitors that check it.
+/// The dynamic values used are returned with the result so they can be traced afterwards.
+pub(crate) async fn dispatch(
+    app: tauri::AppHandle,
+    collection_id: String,
+    mut request: Value,
+    environment_id: Option<String>,
+) -> Result<SendResult, String> {
+    if !request.is_object() {
+        return Err("request must be an object".to_string());
+    }
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let mut environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    let env_name = environment_id.unwrap_or_else(|| str_of(&environment, "active_env").into());
+    let layers = crate::variables::layers(&app)?;
+    let mut scope = Scope::load(
+        &layers,
+        &collection_id,
+        &collection,
+        &environment,
+        &env_name,
+        Some(&request),
+    )?;
+
+    // Shell command hooks run before the pre-request script, where the workspace allows them.
+    let hooks = crate::hooks::of(&request)?;
+    let (mut hook_runs, sets) =
+        crate::hooks::run(&app, Phase::Pre, &hooks.pre, &request, &scope, None).await?;
+    if !sets.is_empty() {
+        if !request.get("variables").is_some_and(Value::is_object) {
+            request["variables"] = Value::Object(Map::new());
+        }
+        for (key, value) in sets {
+            request["variables"][&key] = Value::String(value);
+        }
+        scope = Scope::load(
+            &layers,
+            &collection_id,
+            &collection,
+            &environment,
+            &env_name,
+            Some(&request),
+        )?;
+    }
+
+    let mut console = Vec::new();
+    if let Some(script) = script_of(&request, "pre_request_script") {
+        let outcome = crate::scripting::run_pre_request(&app, script, request, &scope).await?;
+        request = outcome.request;
+        if !outcome.sets.request.is_empty() {
+            if !request.get("variables").is_some_and(Value::is_object) {
+                request["variables"] = Value::Object(Map::new());
+            }
+            for (key, value) in outcome.sets.request {
+                request["variables"][&key] = Value::String(value);
+            }
+        }
+        environment = match outcome.sets.environment.is_empty() {
+            true => environment,
+            false => {
+                persist_environment(&app, &collection_id, &env_name, outcome.sets.environment)
+                    .await?;
+                crate::backend_get(&app, &format!("/collections/{collection_id}/environment"))
+                    .await?
+            }
+        };
+        scope = Scope::load(
+            &layers,
+            &collection_id,
+            &collection,
+            &environment,
+            &env_name,
+            Some(&request),
+        )?;
+        console = outcome.console;
+    }
+    // Workspace and collection defaults add their headers and run their interceptors around
+    // the script-edited request and its response.
+    let defaults = RequestDefaults::for_request(&app, &collection, &request)?;
+    defaults.apply_request(&mut request);
+    // JSON-RPC requests get their body written from their calls.
+    let jsonrpc = crate::jsonrpc::prepare(&mut request)?;
+    let test_script = script_of(&request, "test_script");
+    let script_request = request.clone();
+    let workspace = crate::load_workspace_path(&app)?;
+    // Routing rules point the URL at the environment's base URL before variables resolve;
+    // the result names the rule used.
+    let route = litefetch_core::routing::route_request(&workspace, &env_name, &mut request)?;
+    let dynamic: Vec<DynamicValue> = resolve_request(&mut request, &scope)
+        .into_iter()
+        .filter(|s| s.source == Some(VariableSource::Dynamic))
+        .map(|s| DynamicValue {
+            expression: s.reference,
+            value: s.value.unwrap_or_default(),
+        })
+        .collect();
+    if let Some(route) = &route {
+        route.finish(&mut request);
+    }
+    defaults.generate_ids(&mut request);
+    // Files named as workspace fixtures (`fixture:<name>`) are swapped for the stored copy.
+    litefetch_core::fixtures::resolve_request(&workspace, &mut request)?;
+
+    // Auth from plugins, the OAuth token manager (see `oauth::tokens`) or a freshly signed
+    // JWT, and keys from the API key vault for matching hosts.
+    let vars = scope.values(true);
+    if str_of(&request, "auth_type") == "plugin" {
+        for name in crate::plugins::authorize(&app, &mut request, &vars).await? {
+            mark_secret(&mut request, "secret_headers", &name);
+        }
+    }
+    if str_of(&request, "auth_type") == "oauth2" {
+        let name = crate::oauth::tokens::authorize(&app, &mut request, None).await?;
+        mark_secret(&mut request, "secret_headers", &name);
+    }
+    if str_of(&request, "auth_type") == "jwt_bearer" {
+        let name = crate::jwt::authorize(&mut request)?;
+        mark_secret(&mut request, "secret_headers", &name);
+    }
+    for (marker, name) in crate::vault::inject(&app, &mut request)? {
+        mark_secret(&mut request, marker, &name);
+    }
+    // Enabled plugins see the resolved request here, and its result in `run`.
+    let request = crate::plugins::before_request(&app, request, &vars).await?;
+
+    let mut result = match crate::graphql_persisted::prepare(&app, &request)? {
+        None => run(&app, &collection_id, &request, &script_request, &vars).await?,
+        Some(persisted) => {
+            // A hash-only attempt the server refused stays in the history next to the resend.
+            let mut result = run(
+                &app,
+                &collection_id,
+                &persisted.first,
+                &script_request,
+                &vars,
+            )
+            .await?;
+            let outcome = match crate::graphql_persisted::missed(&result) {
+                Some("not_found") => persisted.fallback.map(|r| (r, "miss")),
+                Some(_) => persisted.unsupported.map(|r| (r, "unsupported")),
+                None => None,
+            };
+            let outcome = match outcome {
+                Some((retry, outcome)) => {
+                    result = run(&app, &collection_id, &retry, &script_request, &vars).await?;
+                    outcome
+                }
+                None if persisted.is_static => "static",
+                None => "hit",
+            };
+            result["persisted_query"] = Value::String(outcome.to_string());
+            result
+        }
+    };
+    if str_of(&request, "auth_type") == "oauth2"
+        && result.get("status_code").and_then(Value::as_u64) == Some(401)
+    {
+        // The token was refused before it expired (revoked, rotated): replace it and try once
+        // more. Both attempts stay in the history.
+        let rejected = crate::oauth::tokens::sent_token(&request).map(str::to_string);
+        let mut retry = request.clone();
+        match crate::oauth::tokens::authorize(&app, &mut retry, rejected.as_deref()).await {
+            Ok(_) => {
+                result = run(&app, &collection_id, &retry, &script_request, &vars).await?;
+                result["auth_retried"] = Value::Bool(true);
+            }
+            Err(e) => tracing::warn!("{e}"),
+        }
+    }
+    if let Some(prepared) = &jsonrpc {
+        let report = crate::jsonrpc::report(prepared, &result);
+        result["jsonrpc"] = serde_json::to_value(&report).unwrap_or(Value::Null);
+    }
+    if let Some(route) = &route {
+        result["route"] = serde_json::to_value(route).unwrap_or(Value::Null);
+    }
+    if let Some(ids) = request.get("correlation_ids") {
+        result["correlation_ids"] = ids.clone();
+    }
+    let sent = result.get("sent_request").unwrap_or(&request);
+    let trace_ids = crate::trace::trace_ids(sent, &result);
+    result["trace_ids"] = Value::from(trace_ids);
+    crate::metrics::record(&app, &env_name, &result);
+    crate::notifications::request_finished(&app, &collection_id, &request, &result);
+    let captured = defaults.apply_response(&mut result);
+    if !captured.is_empty() {
+        persist_environment(&app, &collection_id, &env_name, captured).await?;
+    }
+    let (post_runs, sets) = crate::hooks::run(
+        &app,
+        Phase::Post,
+        &hooks.post,
+        &script_request,
+        &scope,
+        Some(&result),
+    )
+    .await?;
+    hook_runs.extend(post_runs);
+    if !sets.is_empty() {
+        persist_environment(&app, &collection_id, &env_name, sets.into_iter().collect()).await?;
+    }
+    if !hook_runs.is_empty() {
+        result["hooks"] = serde_json::to_value(&hook_runs).unwrap_or(Value::Null);
+    }
+    if let Some(snapshot) = result
+        .get_mut("sent_request")
+        .and_then(Value::as_object_mut)
+    {
+        snapshot.insert(
+            "dynamic".to_string(),
+            serde_json::to_value(&dynamic).unwrap_or(Value::Null),
+        );
+    }
+
+    let mut tests = None;
+    if let Some(script) = test_script {
+        let outcome =
+            crate::scripting::run_tests(&app, script, script_request, &scope, &result).await?;
+        if !outcome.sets.environment.is_empty() {
+            persist_environment(&app, &collection_id, &env_name, outcome.sets.environment).await?;
+        }
+        let report = serde_json::to_value(&outcome.report).unwrap_or(Value::Null);
+        let attach = serde_json::json!({
+            "request_id": result.get("request_id").cloned().unwrap_or(Value::Null),
+            "timestamp": result.get("timestamp").cloned().unwrap_or(Value::Null),
+            "tests": report,
+        });
+        // Streamed sends don't go through the backend, so it has no entry for them.
+        if !crate::upload::is_streamed(&request) && !crate::streaming::is_streamed(&request) {
+            crate::backend_post(
+                &app,
+                &format!("/collections/{collection_id}/history/tests"),
+                &attach,
+            )
+            .await?;
+        }
+        result["tests"] = report;
+        console.extend(outcome.console);
+        tests = Some(outcome.report);
+    }
+    Ok(SendResult {
+        result,
+        dynamic,
+        console,
+        tests,
+    })
+}
+
+/// One trip, after its host's concurrency and rate limits (see `scheduler`) and shaped by the
+/// network profile (see `network`), which can slow it down or fail it. Bodies from files and
+/// rich multipart forms are streamed by the shell (see `upload`), responses read record by
+/// record are fetched by it (see `streaming`), and the rest goes through the backend.
+///
+/// The response has its checksums, digest headers and any detached signature checked (see
+/// `integrity`), is kept in the response store (see `responses`) under the `response_id` set
+/// on the result, and is logged in the searchable `history` along with `template`, the
+/// request before resolution. While a session is being recorded, it is kept for its bundle
+/// (see `session`).
+pub(crate) async fn run(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    request: &Value,
+    template: &Value,
+    vars: &Map<String, Value>,
+) -> Result<Value, String> {
+    let shaping =
+        NetworkProfile::for_request(app, request)?.map(|profile| Shaping::new(profile, request));
+    let failed = match &shaping {
+        Some(shaping) => shaping.before(request).await,
+        None => None,
+    };
+    let result = match failed {
+        Some(failed) => failed,
+        None => {
+            let ticket = crate::scheduler::acquire(app, request).await?;
+            let mut result = if crate::upload::is_streamed(request) {
+                crate::upload::send(app, request).await?
+            } else if crate::streaming::is_streamed(request) {
+                crate::streaming::send(app, request).await?
+            } else {
+                crate::backend_post(app, &format!("/collections/{collection_id}/run"), request)
+                    .await?
+            };
+            if let Some(shaping) = &shaping {
+                shaping.after(&mut result).await;
+            }
+            if let Some(ticket) = ticket.filter(|t| t.queued_ms > 0) {
+                result["queued_ms"] = Value::from(ticket.queued_ms);
+            }
+            result
+        }
+    };
+    let mut result = crate::plugins::after_response(app, request, result, vars).await?;
+    crate::integrity::apply(request, &mut result).await;
+    if let Err(e) = crate::responses::store(app, &mut result) {
+        tracing::warn!("{e}");
+    }
+    if let Err(e) = crate::history::record(app, collection_id, request, template, &result) {
+        tracing::warn!("{e}");
+    }
+    crate::session::capture(app, collection_id, request, template, &result).await;
+    Ok(result)
+}
+
+/// Sends through `send_request`, so runs and workflows get scripts and plugins too.
+pub struct Shell {
+    pub app: tauri::AppHandle,
+    pub collection_id: String,
+    pub environment_id: Option<String>,
+}
+
+impl Sender for Shell {
+    async fn send(&self, request: Value) -> Result<Sent, String> {
+        let sent = dispatch(
+            self.app.clone(),
+            self.collection_id.clone(),
+            request,
+            self.environment_id.clone(),
+        )
+        .await?;
+        Ok(Sent {
+            result: sent.result,
+            tests: sent.tests,
+        })
+    }
+}
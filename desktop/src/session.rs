@@ -0,0 +1,451 @@
+//! Session recordings: while one is running, every request sent (see `send::run`) is kept
+//! with its response, and stopping writes them to a single zip to attach to a bug report.
+//! Bundles are always redacted: flagged secrets of the collections involved, secrets this
+//! process has seen, credential headers and anything that looks like a credential (see
+//! `litefetch_core::leaks`) are masked, so a bundle can be shared as is.
+//!
+//! `inspect_session_bundle` reads a bundle back; `replay_session_bundle` sends its requests
+//! again, in order, from the requests as written (variables unresolved), so they pick up the
+//! replaying workspace's own environment and secrets.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+use tauri::async_runtime::Mutex;
+use tauri::{Manager, State};
+
+use crate::importers::str_of;
+use crate::redact::{log_line, Redactor, MASK};
+
+const FORMAT: &str = "litefetch-session";
+const VERSION: u64 = 1;
+const MANIFEST: &str = "manifest.json";
+const ENTRIES: &str = "entries.json";
+/// Sends kept per recording; later ones are counted as dropped.
+const MAX_ENTRIES: usize = 1000;
+/// Response bodies are cut to this size in the bundle.
+const MAX_BODY: usize = 1024 * 1024;
+/// Headers whose values are always masked.
+const CREDENTIAL_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+    "x-api-key",
+    "api-key",
+];
+
+pub struct SessionState {
+    recording: Mutex<Option<Recording>>,
+}
+
+impl SessionState {
+    pub fn new() -> Self {
+        Self {
+            recording: Mutex::new(None),
+        }
+    }
+}
+
+struct Recording {
+    name: String,
+    started_at_ms: u64,
+    entries: Vec<SessionEntry>,
+    dropped: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionEntry {
+    pub index: usize,
+    /// Since the recording started.
+    pub offset_ms: u64,
+    pub collection_id: String,
+    /// As sent, variables resolved.
+    pub request: Value,
+    /// As written, before variables were resolved; what replays send.
+    pub template: Value,
+    /// The backend `RequestResult`, body cut to `MAX_BODY`.
+    pub result: Value,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SessionManifest {
+    pub format: String,
+    pub version: u64,
+    pub name: String,
+    pub note: Option<String>,
+    pub started_at_ms: u64,
+    pub ended_at_ms: u64,
+    pub entries: usize,
+    pub dropped: usize,
+    pub app_version: String,
+    pub os: String,
+}
+
+#[derive(Serialize)]
+pub struct SessionStatus {
+    pub recording: bool,
+    pub name: Option<String>,
+    pub started_at_ms: Option<u64>,
+    pub entries: usize,
+}
+
+#[derive(Serialize)]
+pub struct SessionBundleSummary {
+    pub path: String,
+    pub bytes: u64,
+    #[serde(flatten)]
+    pub manifest: SessionManifest,
+}
+
+#[derive(Serialize)]
+pub struct SessionBundle {
+    pub manifest: SessionManifest,
+    pub entries: Vec<SessionEntry>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct SessionReplayOptions {
+    /// The collection to send in, instead of each entry's own.
+    pub collection_id: Option<String>,
+    pub environment_id: Option<String>,
+    /// Entries to replay, by index; every one when empty.
+    pub indices: Vec<usize>,
+}
+
+#[derive(Serialize)]
+pub struct ReplayedEntry {
+    pub index: usize,
+    pub name: String,
+    pub recorded_status: Option<u64>,
+    pub status: Option<u64>,
+    pub status_matches: bool,
+    /// Why the entry wasn't sent, or why its send failed.
+    pub error: Option<String>,
+    pub result: Option<Value>,
+}
+
+async fn status(state: &SessionState) -> SessionStatus {
+    let recording = state.recording.lock().await;
+    SessionStatus {
+        recording: recording.is_some(),
+        name: recording.as_ref().map(|r| r.name.clone()),
+        started_at_ms: recording.as_ref().map(|r| r.started_at_ms),
+        entries: recording.as_ref().map_or(0, |r| r.entries.len()),
+    }
+}
+
+/// Keeps a send in the running recording, if any.
+pub async fn capture(
+    app: &tauri::AppHandle,
+    collection_id: &str,
+    request: &Value,
+    template: &Value,
+    result: &Value,
+) {
+    let state: State<'_, SessionState> = app.state();
+    let mut recording = state.recording.lock().await;
+    let Some(recording) = recording.as_mut() else {
+        return;
+    };
+    if recording.entries.len() >= MAX_ENTRIES {
+        recording.dropped += 1;
+        return;
+    }
+    let mut result = result.clone();
+    if let Some(fields) = result.as_object_mut() {
+        let cut = match fields.get("body") {
+            Some(Value::String(body)) if body.len() > MAX_BODY => {
+                let mut end = MAX_BODY;
+                while !body.is_char_boundary(end) {
+                    end -= 1;
+                }
+                Some(body[..end].to_string())
+            }
+            _ => None,
+        };
+        if let Some(body) = cut {
+            fields.insert("body".into(), Value::String(body));
+            fields.insert("body_truncated".into(), Value::Bool(true));
+        }
+        fields.remove("body_base64");
+    }
+    recording.entries.push(SessionEntry {
+        index: recording.entries.len(),
+        offset_ms: crate::now_ms().saturating_sub(recording.started_at_ms),
+        collection_id: collection_id.to_string(),
+        request: request.clone(),
+        template: template.clone(),
+        result,
+    });
+}
+
+fn mask_text(text: &str, redactor: &Redactor) -> String {
+    let mut text = log_line(&redactor.text(text));
+    for (_, _, value) in litefetch_core::leaks::find(&text) {
+        text = text.replace(&value, MASK);
+    }
+    text
+}
+
+fn redact(value: &mut Value, redactor: &Redactor) {
+    match value {
+        Value::String(text) => *text = mask_text(text, redactor),
+        Value::Array(items) => items.iter_mut().for_each(|v| redact(v, redactor)),
+        Value::Object(map) => {
+            for (key, inner) in map.iter_mut() {
+                // A value written as a `{{reference}}` names a variable; replays need it.
+                let credential = CREDENTIAL_HEADERS.contains(&key.to_ascii_lowercase().as_str())
+                    && inner.as_str().is_some_and(|v| !v.contains("{{"));
+                if credential {
+                    *inner = Value::String(MASK.to_string());
+                    continue;
+                }
+                redact(inner, redactor);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The redactor of each collection the entries were sent in; a collection that can't be read
+/// any more still gets the process-wide masking.
+async fn redactors(app: &tauri::AppHandle, entries: &[SessionEntry]) -> HashMap<String, Redactor> {
+    let mut redactors = HashMap::new();
+    for entry in entries {
+        if redactors.contains_key(&entry.collection_id) {
+            continue;
+        }
+        let id = &entry.collection_id;
+        let collection = crate::backend_get(app, &format!("/collections/{id}/collection")).await;
+        let environment = crate::backend_get(app, &format!("/collections/{id}/environment")).await;
+        let redactor = match (collection, environment) {
+            (Ok(collection), Ok(environment)) => {
+                Redactor::for_collection(id, &collection, &environment).unwrap_or_else(|e| {
+                    tracing::warn!("{e}");
+                    Redactor::disabled()
+                })
+            }
+            _ => Redactor::disabled(),
+        };
+        redactors.insert(id.clone(), redactor);
+    }
+    redactors
+}
+
+fn write_bundle(
+    target: &Path,
+    manifest: &SessionManifest,
+    entries: &[SessionEntry],
+) -> Result<u64, String> {
+    let fail = |e: &dyn std::fmt::Display| format!("session export failed: {e}");
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent).map_err(|e| fail(&e))?;
+    }
+    let file = fs::File::create(target).map_err(|e| fail(&e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, data) in [
+        (MANIFEST, serde_json::to_vec_pretty(manifest)),
+        (ENTRIES, serde_json::to_vec_pretty(entries)),
+    ] {
+        let data = data.map_err(|e| format!("session serialize failed: {e}"))?;
+        zip.start_file(name, options).map_err(|e| fail(&e))?;
+        zip.write_all(&data).map_err(|e| fail(&e))?;
+    }
+    zip.finish().map_err(|e| fail(&e))?;
+    fs::metadata(target).map(|m| m.len()).map_err(|e| fail(&e))
+}
+
+fn read_bundle(path: &str) -> Result<SessionBundle, String> {
+    let fail = |e: &dyn std::fmt::Display| format!("session read failed: {e}");
+    let file = fs::File::open(crate::normalize_path(path.trim())).map_err(|e| fail(&e))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| fail(&e))?;
+    let mut read = |name: &str| -> Result<Vec<u8>, String> {
+        let mut member = zip
+            .by_name(name)
+            .map_err(|_| "not a LiteFetch session bundle".to_string())?;
+        let mut data = Vec::new();
+        member.read_to_end(&mut data).map_err(|e| fail(&e))?;
+        Ok(data)
+    };
+    let manifest: SessionManifest = serde_json::from_slice(&read(MANIFEST)?)
+        .map_err(|e| format!("session parse failed: {e}"))?;
+    if manifest.format != FORMAT {
+        return Err("not a LiteFetch session bundle".into());
+    }
+    if manifest.version > VERSION {
+        return Err(format!(
+            "session bundle version {} is newer than this app supports",
+            manifest.version
+        ));
+    }
+    let entries = serde_json::from_slice(&read(ENTRIES)?)
+        .map_err(|e| format!("session parse failed: {e}"))?;
+    Ok(SessionBundle { manifest, entries })
+}
+
+/// Starts recording the requests sent from now on.
+#[tauri::command]
+pub async fn start_session_recording(
+    state: State<'_, SessionState>,
+    name: Option<String>,
+) -> Result<SessionStatus, String> {
+    {
+        let mut recording = state.recording.lock().await;
+        if recording.is_some() {
+            return Err("a session is already being recorded".to_string());
+        }
+        let started_at_ms = crate::now_ms();
+        *recording = Some(Recording {
+            name: name
+                .filter(|n| !n.trim().is_empty())
+                .unwrap_or_else(|| format!("Session {started_at_ms}")),
+            started_at_ms,
+            entries: Vec::new(),
+            dropped: 0,
+        });
+    }
+    Ok(status(&state).await)
+}
+
+#[tauri::command]
+pub async fn get_session_recording(
+    state: State<'_, SessionState>,
+) -> Result<SessionStatus, String> {
+    Ok(status(&state).await)
+}
+
+/// Stops the recording and writes it, redacted, to `path`. Without a path the recording is
+/// discarded.
+#[tauri::command]
+pub async fn stop_session_recording(
+    app: tauri::AppHandle,
+    state: State<'_, SessionState>,
+    path: Option<String>,
+    note: Option<String>,
+) -> Result<Option<SessionBundleSummary>, String> {
+    let recording = state
+        .recording
+        .lock()
+        .await
+        .take()
+        .ok_or("no session is being recorded")?;
+    let Some(path) = path.filter(|p| !p.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    let redactors = redactors(&app, &recording.entries).await;
+    let disabled = Redactor::disabled();
+    let mut entries = recording.entries;
+    for entry in &mut entries {
+        let redactor = redactors.get(&entry.collection_id).unwrap_or(&disabled);
+        for value in [&mut entry.request, &mut entry.template, &mut entry.result] {
+            redact(value, redactor);
+        }
+    }
+    let manifest = SessionManifest {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        name: mask_text(&recording.name, &disabled),
+        note: note.map(|n| mask_text(&n, &disabled)),
+        started_at_ms: recording.started_at_ms,
+        ended_at_ms: crate::now_ms(),
+        entries: entries.len(),
+        dropped: recording.dropped,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+    };
+    let target = crate::normalize_path(path.trim());
+    let written = manifest.clone();
+    let bundle_path = target.clone();
+    let bytes =
+        tauri::async_runtime::spawn_blocking(move || write_bundle(&target, &written, &entries))
+            .await
+            .map_err(|e| format!("session export failed: {e}"))??;
+    Ok(Some(SessionBundleSummary {
+        path: bundle_path.to_string_lossy().to_string(),
+        bytes,
+        manifest,
+    }))
+}
+
+/// A session bundle's manifest and entries, for viewing without sending anything.
+#[tauri::command]
+pub async fn inspect_session_bundle(path: String) -> Result<SessionBundle, String> {
+    tauri::async_runtime::spawn_blocking(move || read_bundle(&path))
+        .await
+        .map_err(|e| format!("session read failed: {e}"))?
+}
+
+/// Sends a bundle's requests again, one after the other, and compares each status with the
+/// recorded one. Requests with redacted values written into them are skipped.
+#[tauri::command]
+pub async fn replay_session_bundle(
+    app: tauri::AppHandle,
+    path: String,
+    options: Option<SessionReplayOptions>,
+) -> Result<Vec<ReplayedEntry>, String> {
+    let options = options.unwrap_or_default();
+    let bundle = tauri::async_runtime::spawn_blocking(move || read_bundle(&path))
+        .await
+        .map_err(|e| format!("session read failed: {e}"))??;
+    let mut replayed = Vec::new();
+    for entry in bundle.entries {
+        if !options.indices.is_empty() && !options.indices.contains(&entry.index) {
+            continue;
+        }
+        let recorded_status = entry
+            .result
+            .get("status_code")
+            .and_then(Value::as_u64)
+            .filter(|s| *s > 0);
+        let mut outcome = ReplayedEntry {
+            index: entry.index,
+            name: str_of(&entry.template, "name").to_string(),
+            recorded_status,
+            status: None,
+            status_matches: false,
+            error: None,
+            result: None,
+        };
+        if entry.template.to_string().contains(MASK) {
+            outcome.error =
+                Some("the request has redacted values written into it; it wasn't sent".into());
+            replayed.push(outcome);
+            continue;
+        }
+        let collection_id = options.collection_id.clone().unwrap_or(entry.collection_id);
+        match crate::send::dispatch(
+            app.clone(),
+            collection_id,
+            entry.template,
+            options.environment_id.clone(),
+        )
+        .await
+        {
+            Ok(sent) => {
+                let mut result = sent.result;
+                crate::responses::limit(&app, &mut result);
+                outcome.status = result
+                    .get("status_code")
+                    .and_then(Value::as_u64)
+                    .filter(|s| *s > 0);
+                outcome.status_matches = outcome.status == recorded_status;
+                outcome.error = result
+                    .get("error")
+                    .and_then(Value::as_str)
+                    .map(str::to_string);
+                outcome.result = Some(result);
+            }
+            Err(e) => outcome.error = Some(e),
+        }
+        replayed.push(outcome);
+    }
+    Ok(replayed)
+}
@@ -0,0 +1,172 @@
+//! Resource use of the backend sidecar: CPU, memory and uptime, summed over the sidecar and
+//! the processes it started (a bundled backend runs as a launcher and an interpreter). It's
+//! sampled in the background, and crossing the memory threshold is announced as
+//! `backend://memory`, once on the way up and once on the way down.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{Emitter, Manager, State};
+
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+const DEFAULT_THRESHOLD_MB: u64 = 1024;
+
+pub struct SidecarState {
+    system: Mutex<System>,
+    threshold_bytes: AtomicU64,
+    over: AtomicBool,
+}
+
+impl SidecarState {
+    pub fn new() -> Self {
+        Self {
+            system: Mutex::new(System::new()),
+            threshold_bytes: AtomicU64::new(DEFAULT_THRESHOLD_MB * 1024 * 1024),
+            over: AtomicBool::new(false),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct BackendStats {
+    pub running: bool,
+    pub pid: Option<u32>,
+    /// The sidecar and the processes under it.
+    pub processes: usize,
+    /// Summed over the processes; 100 is one core fully busy.
+    pub cpu_percent: f32,
+    /// Resident memory.
+    pub memory_bytes: u64,
+    pub virtual_memory_bytes: u64,
+    pub uptime_secs: u64,
+    pub memory_threshold_bytes: u64,
+    pub over_threshold: bool,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct MemoryEvent {
+    /// `true` when memory went over the threshold, `false` when it came back under.
+    over_threshold: bool,
+    memory_bytes: u64,
+    memory_threshold_bytes: u64,
+    timestamp_ms: u64,
+}
+
+async fn sidecar_pid(app: &tauri::AppHandle) -> Option<u32> {
+    let state = app.state::<crate::BackendState>();
+    let child = state.child.lock().await;
+    child.as_ref().map(|c| c.pid())
+}
+
+/// Refreshes the process table and totals the sidecar's tree. CPU use is measured since the
+/// previous sample, so the first one reads 0.
+fn sample(state: &SidecarState, pid: Option<u32>) -> BackendStats {
+    let threshold = state.threshold_bytes.load(Ordering::Relaxed);
+    let mut stats = BackendStats {
+        running: false,
+        pid,
+        processes: 0,
+        cpu_percent: 0.0,
+        memory_bytes: 0,
+        virtual_memory_bytes: 0,
+        uptime_secs: 0,
+        memory_threshold_bytes: threshold,
+        over_threshold: false,
+        timestamp_ms: crate::now_ms(),
+    };
+    let (Some(pid), Ok(mut system)) = (pid, state.system.lock()) else {
+        return stats;
+    };
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing().with_cpu().with_memory(),
+    );
+    let root = Pid::from_u32(pid);
+    let processes = system.processes();
+    let under_root = |mut at: Pid| {
+        for _ in 0..64 {
+            if at == root {
+                return true;
+            }
+            match processes.get(&at).and_then(|p| p.parent()) {
+                Some(parent) => at = parent,
+                None => return false,
+            }
+        }
+        false
+    };
+    for (id, process) in processes {
+        if !under_root(*id) {
+            continue;
+        }
+        stats.processes += 1;
+        stats.cpu_percent += process.cpu_usage();
+        stats.memory_bytes += process.memory();
+        stats.virtual_memory_bytes += process.virtual_memory();
+        if *id == root {
+            stats.uptime_secs = process.run_time();
+        }
+    }
+    stats.running = stats.processes > 0;
+    stats.over_threshold = stats.memory_bytes > threshold;
+    stats
+}
+
+/// Samples the sidecar for as long as the app runs, announcing threshold crossings.
+pub async fn watch_periodically(app: tauri::AppHandle) {
+    let mut ticks = tokio::time::interval(SAMPLE_INTERVAL);
+    loop {
+        ticks.tick().await;
+        let pid = sidecar_pid(&app).await;
+        let state = app.state::<SidecarState>();
+        let stats = sample(&state, pid);
+        if state.over.swap(stats.over_threshold, Ordering::Relaxed) == stats.over_threshold {
+            continue;
+        }
+        if stats.over_threshold {
+            tracing::warn!(
+                "backend memory at {} MB, over the {} MB threshold",
+                stats.memory_bytes / (1024 * 1024),
+                stats.memory_threshold_bytes / (1024 * 1024)
+            );
+        }
+        let _ = app.emit(
+            "backend://memory",
+            MemoryEvent {
+                over_threshold: stats.over_threshold,
+                memory_bytes: stats.memory_bytes,
+                memory_threshold_bytes: stats.memory_threshold_bytes,
+                timestamp_ms: stats.timestamp_ms,
+            },
+        );
+    }
+}
+
+/// The sidecar's current CPU, memory and uptime; `running` is `false` before it starts.
+#[tauri::command]
+pub async fn get_backend_stats(
+    app: tauri::AppHandle,
+    state: State<'_, SidecarState>,
+) -> Result<BackendStats, String> {
+    let pid = sidecar_pid(&app).await;
+    Ok(sample(&state, pid))
+}
+
+/// Sets the memory level, in megabytes, above which `backend://memory` warns.
+#[tauri::command]
+pub async fn set_backend_memory_threshold(
+    state: State<'_, SidecarState>,
+    threshold_mb: u64,
+) -> Result<(), String> {
+    if threshold_mb == 0 {
+        return Err("the threshold must be at least 1 MB".to_string());
+    }
+    state
+        .threshold_bytes
+        .store(threshold_mb.saturating_mul(1024 * 1024), Ordering::Relaxed);
+    Ok(())
+}
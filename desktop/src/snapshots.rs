@@ -0,0 +1,120 @@
+//! Response snapshots from the UI; storage and the comparison live in
+//! `litefetch_core::snapshot`. `verify_snapshot` checks a response the UI already has, or
+//! sends the request afresh; collection runs check every request that has a snapshot when
+//! `verify_snapshots` is set (see `runner`).
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use litefetch_core::json::{array_of, find_request};
+use litefetch_core::snapshot::{self, Snapshot, SnapshotCheck};
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct SnapshotOptions {
+    /// Body paths, key names or header names to leave out of every check.
+    pub ignore: Vec<String>,
+    /// Also leave out ids, timestamps and per-response headers.
+    pub ignore_volatile: bool,
+}
+
+/// Stores `result` (a backend `RequestResult`) as the request's snapshot, replacing any
+/// earlier one.
+#[tauri::command]
+pub async fn save_snapshot(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+    result: Value,
+    options: Option<SnapshotOptions>,
+) -> Result<Snapshot, String> {
+    let options = options.unwrap_or_default();
+    let snapshot = Snapshot::capture(
+        &request_id,
+        &result,
+        options.ignore,
+        options.ignore_volatile,
+    );
+    snapshot::save(
+        &crate::load_workspace_path(&app)?,
+        &collection_id,
+        &snapshot,
+    )?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub async fn get_snapshot(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+) -> Result<Option<Snapshot>, String> {
+    snapshot::load(
+        &crate::load_workspace_path(&app)?,
+        &collection_id,
+        &request_id,
+    )
+}
+
+/// Changes what a snapshot's checks leave out, keeping the stored response.
+#[tauri::command]
+pub async fn set_snapshot_ignores(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+    options: SnapshotOptions,
+) -> Result<Snapshot, String> {
+    let workspace = crate::load_workspace_path(&app)?;
+    let mut snapshot = snapshot::load(&workspace, &collection_id, &request_id)?
+        .ok_or_else(|| format!("no snapshot for request: {request_id}"))?;
+    snapshot.ignore = options.ignore;
+    snapshot.ignore_volatile = options.ignore_volatile;
+    snapshot::save(&workspace, &collection_id, &snapshot)?;
+    Ok(snapshot)
+}
+
+#[tauri::command]
+pub async fn delete_snapshot(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+) -> Result<bool, String> {
+    snapshot::delete(
+        &crate::load_workspace_path(&app)?,
+        &collection_id,
+        &request_id,
+    )
+}
+
+/// Compares a response against the request's snapshot: `result` when given, otherwise a
+/// fresh send of the saved request in `environment_id` (the active environment by default).
+#[tauri::command]
+pub async fn verify_snapshot(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+    result: Option<Value>,
+    environment_id: Option<String>,
+) -> Result<SnapshotCheck, String> {
+    let snapshot = snapshot::load(
+        &crate::load_workspace_path(&app)?,
+        &collection_id,
+        &request_id,
+    )?
+    .ok_or_else(|| format!("no snapshot for request: {request_id}"))?;
+    let result = match result {
+        Some(result) => result,
+        None => {
+            let collection =
+                crate::backend_get(&app, &format!("/collections/{collection_id}/collection"))
+                    .await?;
+            let request = find_request(array_of(&collection, "items"), &request_id)
+                .cloned()
+                .ok_or_else(|| format!("unknown request: {request_id}"))?;
+            crate::send::dispatch(app.clone(), collection_id.clone(), request, environment_id)
+                .await?
+                .result
+        }
+    };
+    Ok(snapshot.verify(&result))
+}
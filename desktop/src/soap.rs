@@ -0,0 +1,491 @@
+use litefetch_core::format::{Language, Mode};
+use roxmltree::{Document, Node};
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+const WSDL_NS: &str = "http://schemas.xmlsoap.org/wsdl/";
+const SOAP11_BINDING_NS: &str = "http://schemas.xmlsoap.org/wsdl/soap/";
+const SOAP12_BINDING_NS: &str = "http://schemas.xmlsoap.org/wsdl/soap12/";
+const XSD_NS: &str = "http://www.w3.org/2001/XMLSchema";
+const SOAP11_ENVELOPE_NS: &str = "http://schemas.xmlsoap.org/soap/envelope/";
+const SOAP12_ENVELOPE_NS: &str = "http://www.w3.org/2003/05/soap-envelope";
+const MAX_SKELETON_DEPTH: usize = 8;
+
+#[derive(Serialize)]
+pub struct SoapOperation {
+    service: String,
+    port: String,
+    name: String,
+    endpoint: String,
+    soap_action: String,
+    version: &'static str,
+    style: String,
+    envelope: String,
+}
+
+#[derive(Serialize)]
+pub struct WsdlImport {
+    collection: Value,
+    operations: Vec<SoapOperation>,
+}
+
+#[derive(Serialize)]
+pub struct SoapFault {
+    code: String,
+    reason: String,
+    detail: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SoapResponseView {
+    valid: bool,
+    error: Option<String>,
+    pretty: String,
+    fault: Option<SoapFault>,
+}
+
+struct BindingOperation {
+    soap_action: String,
+    style: Option<String>,
+}
+
+struct Binding {
+    port_type: String,
+    version: &'static str,
+    style: String,
+    operations: Vec<(String, BindingOperation)>,
+}
+
+fn local(qname: &str) -> &str {
+    qname.rsplit(':').next().unwrap_or(qname)
+}
+
+fn is(node: &Node, ns: &str, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name && node.tag_name().namespace() == Some(ns)
+}
+
+fn child_elements<'a, 'i>(
+    node: Node<'a, 'i>,
+    ns: &'a str,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'i>> {
+    node.children().filter(move |n| is(n, ns, name))
+}
+
+/// Finds the SOAP extension element (1.1 or 1.2) named `name` under `node`.
+fn soap_child<'a, 'i>(node: Node<'a, 'i>, name: &str) -> Option<(Node<'a, 'i>, &'static str)> {
+    node.children().find_map(|n| {
+        if is(&n, SOAP11_BINDING_NS, name) {
+            Some((n, "1.1"))
+        } else if is(&n, SOAP12_BINDING_NS, name) {
+            Some((n, "1.2"))
+        } else {
+            None
+        }
+    })
+}
+
+fn parse_bindings(root: Node) -> HashMap<String, Binding> {
+    let mut bindings = HashMap::new();
+    for binding in child_elements(root, WSDL_NS, "binding") {
+        let Some((soap, version)) = soap_child(binding, "binding") else {
+            // HTTP/MIME bindings have no SOAP envelope to scaffold.
+            continue;
+        };
+        let operations = child_elements(binding, WSDL_NS, "operation")
+            .filter_map(|op| {
+                let name = op.attribute("name")?.to_string();
+                let soap_op = soap_child(op, "operation").map(|(n, _)| n);
+                Some((
+                    name,
+                    BindingOperation {
+                        soap_action: soap_op
+                            .and_then(|n| n.attribute("soapAction"))
+                            .unwrap_or_default()
+                            .to_string(),
+                        style: soap_op
+                            .and_then(|n| n.attribute("style"))
+                            .map(str::to_string),
+                    },
+                ))
+            })
+            .collect();
+        bindings.insert(
+            binding.attribute("name").unwrap_or_default().to_string(),
+            Binding {
+                port_type: local(binding.attribute("type").unwrap_or_default()).to_string(),
+                version,
+                style: soap.attribute("style").unwrap_or("document").to_string(),
+                operations,
+            },
+        );
+    }
+    bindings
+}
+
+/// Maps `portType name -> operation name -> input message name`.
+fn parse_port_types(root: Node) -> HashMap<String, HashMap<String, String>> {
+    child_elements(root, WSDL_NS, "portType")
+        .map(|port_type| {
+            let ops = child_elements(port_type, WSDL_NS, "operation")
+                .filter_map(|op| {
+                    let input = child_elements(op, WSDL_NS, "input").next()?;
+                    Some((
+                        op.attribute("name")?.to_string(),
+                        local(input.attribute("message")?).to_string(),
+                    ))
+                })
+                .collect();
+            (
+                port_type.attribute("name").unwrap_or_default().to_string(),
+                ops,
+            )
+        })
+        .collect()
+}
+
+/// Maps `message name -> [(part name, element qname)]`.
+fn parse_messages(root: Node) -> HashMap<String, Vec<(String, Option<String>)>> {
+    child_elements(root, WSDL_NS, "message")
+        .map(|message| {
+            let parts = child_elements(message, WSDL_NS, "part")
+                .map(|part| {
+                    (
+                        part.attribute("name").unwrap_or_default().to_string(),
+                        part.attribute("element").map(str::to_string),
+                    )
+                })
+                .collect();
+            (
+                message.attribute("name").unwrap_or_default().to_string(),
+                parts,
+            )
+        })
+        .collect()
+}
+
+struct Schemas<'a, 'i> {
+    schemas: Vec<Node<'a, 'i>>,
+}
+
+impl<'a, 'i> Schemas<'a, 'i> {
+    fn global(&self, kind: &str, name: &str) -> Option<Node<'a, 'i>> {
+        self.schemas.iter().find_map(|schema| {
+            schema
+                .children()
+                .find(|n| is(n, XSD_NS, kind) && n.attribute("name") == Some(name))
+        })
+    }
+
+    fn qualified(node: Node) -> bool {
+        node.ancestors()
+            .find(|n| is(n, XSD_NS, "schema"))
+            .and_then(|s| s.attribute("elementFormDefault"))
+            == Some("qualified")
+    }
+
+    /// Element particles of a complex type, following sequence/all/choice and complexContent extensions.
+    fn particles(&self, complex: Node<'a, 'i>, out: &mut Vec<Node<'a, 'i>>, depth: usize) {
+        if depth > MAX_SKELETON_DEPTH {
+            return;
+        }
+        for child in complex.children().filter(|n| n.is_element()) {
+            match child.tag_name().name() {
+                "element" => out.push(child),
+                "sequence" | "all" | "choice" | "complexContent" => {
+                    self.particles(child, out, depth + 1)
+                }
+                "extension" | "restriction" => {
+                    if let Some(base) = child
+                        .attribute("base")
+                        .and_then(|b| self.global("complexType", local(b)))
+                    {
+                        self.particles(base, out, depth + 1);
+                    }
+                    self.particles(child, out, depth + 1);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn render(
+        &self,
+        element: Node<'a, 'i>,
+        prefix: &str,
+        indent: usize,
+        depth: usize,
+        out: &mut Vec<String>,
+    ) {
+        let pad = "   ".repeat(indent);
+        if let Some(reference) = element.attribute("ref") {
+            if let Some(target) = self.global("element", local(reference)) {
+                if depth < MAX_SKELETON_DEPTH {
+                    self.render(target, "tns:", indent, depth + 1, out);
+                }
+            }
+            return;
+        }
+        let name = element.attribute("name").unwrap_or("value");
+        let complex = element
+            .children()
+            .find(|n| is(n, XSD_NS, "complexType"))
+            .or_else(|| {
+                element
+                    .attribute("type")
+                    .and_then(|t| self.global("complexType", local(t)))
+            });
+        let mut particles = Vec::new();
+        if let Some(complex) = complex {
+            self.particles(complex, &mut particles, 0);
+        }
+        if particles.is_empty() || depth >= MAX_SKELETON_DEPTH {
+            out.push(format!("{pad}<{prefix}{name}>?</{prefix}{name}>"));
+            return;
+        }
+        out.push(format!("{pad}<{prefix}{name}>"));
+        for particle in particles {
+            let child_prefix = if Self::qualified(particle) {
+                "tns:"
+            } else {
+                ""
+            };
+            self.render(particle, child_prefix, indent + 1, depth + 1, out);
+        }
+        out.push(format!("{pad}</{prefix}{name}>"));
+    }
+}
+
+fn envelope(version: &str, tns: &str, body: &[String]) -> String {
+    let env_ns = if version == "1.2" {
+        SOAP12_ENVELOPE_NS
+    } else {
+        SOAP11_ENVELOPE_NS
+    };
+    let mut lines = vec![
+        format!("<soapenv:Envelope xmlns:soapenv=\"{env_ns}\" xmlns:tns=\"{tns}\">"),
+        "   <soapenv:Header/>".to_string(),
+        "   <soapenv:Body>".to_string(),
+    ];
+    lines.extend(body.iter().cloned());
+    lines.push("   </soapenv:Body>".to_string());
+    lines.push("</soapenv:Envelope>".to_string());
+    lines.join("\n")
+}
+
+fn parse_wsdl(xml: &str) -> Result<Vec<SoapOperation>, String> {
+    let doc = Document::parse(xml).map_err(|e| format!("WSDL is not valid XML: {e}"))?;
+    let root = doc.root_element();
+    if !is(&root, WSDL_NS, "definitions") {
+        return Err("not a WSDL 1.1 document (expected wsdl:definitions)".to_string());
+    }
+    let tns = root.attribute("targetNamespace").unwrap_or_default();
+    let messages = parse_messages(root);
+    let port_types = parse_port_types(root);
+    let bindings = parse_bindings(root);
+    let schemas = Schemas {
+        schemas: child_elements(root, WSDL_NS, "types")
+            .flat_map(|types| child_elements(types, XSD_NS, "schema"))
+            .collect(),
+    };
+
+    let mut operations = Vec::new();
+    for service in child_elements(root, WSDL_NS, "service") {
+        let service_name = service.attribute("name").unwrap_or("Service");
+        for port in child_elements(service, WSDL_NS, "port") {
+            let Some((address, _)) = soap_child(port, "address") else {
+                continue;
+            };
+            let Some(binding) = port
+                .attribute("binding")
+                .and_then(|b| bindings.get(local(b)))
+            else {
+                continue;
+            };
+            let inputs = port_types.get(&binding.port_type);
+            for (op_name, op) in &binding.operations {
+                let style = op.style.clone().unwrap_or_else(|| binding.style.clone());
+                let parts = inputs
+                    .and_then(|ops| ops.get(op_name))
+                    .and_then(|msg| messages.get(msg))
+                    .cloned()
+                    .unwrap_or_default();
+                let mut body = Vec::new();
+                if style == "rpc" {
+                    body.push(format!("      <tns:{op_name}>"));
+                    for (part, _) in &parts {
+                        body.push(format!("         <{part}>?</{part}>"));
+                    }
+                    body.push(format!("      </tns:{op_name}>"));
+                } else {
+                    for (part, element) in &parts {
+                        match element
+                            .as_deref()
+                            .and_then(|e| schemas.global("element", local(e)))
+                        {
+                            Some(node) => schemas.render(node, "tns:", 2, 0, &mut body),
+                            None => body.push(format!("      <tns:{part}>?</tns:{part}>")),
+                        }
+                    }
+                }
+                operations.push(SoapOperation {
+                    service: service_name.to_string(),
+                    port: port.attribute("name").unwrap_or_default().to_string(),
+                    name: op_name.clone(),
+                    endpoint: address
+                        .attribute("location")
+                        .unwrap_or_default()
+                        .to_string(),
+                    soap_action: op.soap_action.clone(),
+                    version: binding.version,
+                    style,
+                    envelope: envelope(binding.version, tns, &body),
+                });
+            }
+        }
+    }
+    if operations.is_empty() {
+        return Err("WSDL declares no SOAP operations".to_string());
+    }
+    Ok(operations)
+}
+
+fn request_headers(op: &SoapOperation) -> Value {
+    if op.version == "1.2" {
+        let mut content_type = "application/soap+xml; charset=utf-8".to_string();
+        if !op.soap_action.is_empty() {
+            content_type.push_str(&format!("; action=\"{}\"", op.soap_action));
+        }
+        json!({ "Content-Type": content_type })
+    } else {
+        json!({
+            "Content-Type": "text/xml; charset=utf-8",
+            "SOAPAction": format!("\"{}\"", op.soap_action),
+        })
+    }
+}
+
+/// Groups operations into one folder per service port, shaped like the backend `Collection` model.
+fn build_collection(name: &str, operations: &[SoapOperation]) -> Value {
+    let mut folders: Vec<(String, Vec<Value>)> = Vec::new();
+    for op in operations {
+        let folder_name = format!("{} ({})", op.service, op.port);
+        let request = json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "name": op.name,
+            "method": "POST",
+            "url": op.endpoint,
+            "headers": request_headers(op),
+            "body": op.envelope,
+            "body_mode": "raw",
+        });
+        match folders.iter_mut().find(|(n, _)| *n == folder_name) {
+            Some((_, items)) => items.push(request),
+            None => folders.push((folder_name, vec![request])),
+        }
+    }
+    json!({
+        "name": name,
+        "items": folders
+            .into_iter()
+            .map(|(folder, items)| json!({
+                "id": uuid::Uuid::new_v4().to_string(),
+                "name": folder,
+                "items": items,
+            }))
+            .collect::<Vec<_>>(),
+    })
+}
+
+async fn load_source(source: &str) -> Result<String, String> {
+    if source.starts_with("http://") || source.starts_with("https://") {
+        let response = reqwest::get(source)
+            .await
+            .map_err(|e| format!("WSDL download failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("WSDL download failed: HTTP {}", response.status()));
+        }
+        response
+            .text()
+            .await
+            .map_err(|e| format!("WSDL download failed: {e}"))
+    } else {
+        std::fs::read_to_string(crate::normalize_path(source))
+            .map_err(|e| format!("WSDL read failed: {e}"))
+    }
+}
+
+pub(crate) fn pretty_xml(xml: &str) -> Result<String, String> {
+    litefetch_core::format::format(xml, Language::Xml, Mode::Pretty).map(|f| f.text)
+}
+
+fn child_text(node: Node, name: &str) -> Option<String> {
+    node.children()
+        .find(|n| n.is_element() && n.tag_name().name() == name)
+        .map(|n| {
+            n.descendants()
+                .filter(|d| d.is_text())
+                .filter_map(|d| d.text())
+                .collect::<String>()
+                .trim()
+                .to_string()
+        })
+}
+
+fn find_fault(doc: &Document) -> Option<SoapFault> {
+    let fault = doc
+        .descendants()
+        .find(|n| is(n, SOAP11_ENVELOPE_NS, "Fault") || is(n, SOAP12_ENVELOPE_NS, "Fault"))?;
+    if fault.tag_name().namespace() == Some(SOAP12_ENVELOPE_NS) {
+        Some(SoapFault {
+            code: child_text(fault, "Code").unwrap_or_default(),
+            reason: child_text(fault, "Reason").unwrap_or_default(),
+            detail: child_text(fault, "Detail"),
+        })
+    } else {
+        Some(SoapFault {
+            code: child_text(fault, "faultcode").unwrap_or_default(),
+            reason: child_text(fault, "faultstring").unwrap_or_default(),
+            detail: child_text(fault, "detail"),
+        })
+    }
+}
+
+#[tauri::command]
+pub async fn soap_import_wsdl(
+    app: tauri::AppHandle,
+    source: String,
+    collection_name: Option<String>,
+) -> Result<WsdlImport, String> {
+    let xml = load_source(source.trim()).await?;
+    let operations = parse_wsdl(&xml)?;
+    let name = collection_name
+        .filter(|n| !n.trim().is_empty())
+        .unwrap_or_else(|| format!("{} (SOAP)", operations[0].service));
+    let collection = build_collection(&name, &operations);
+
+    let meta = crate::create_collection(&app, &name, collection).await?;
+    Ok(WsdlImport {
+        collection: meta,
+        operations,
+    })
+}
+
+#[tauri::command]
+pub fn soap_format_response(xml: String) -> SoapResponseView {
+    match Document::parse(&xml) {
+        Ok(doc) => SoapResponseView {
+            valid: true,
+            error: None,
+            pretty: pretty_xml(&xml).unwrap_or_else(|_| xml.clone()),
+            fault: find_fault(&doc),
+        },
+        Err(e) => SoapResponseView {
+            valid: false,
+            error: Some(e.to_string()),
+            pretty: xml,
+            fault: None,
+        },
+    }
+}
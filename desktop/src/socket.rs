@@ -0,0 +1,291 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, Manager, State};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UdpSocket};
+use tokio::sync::mpsc;
+
+pub struct SocketState {
+    sockets: Mutex<HashMap<String, SocketHandle>>,
+}
+
+impl SocketState {
+    pub fn new() -> Self {
+        Self {
+            sockets: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+struct SocketHandle {
+    outbound: mpsc::UnboundedSender<Vec<u8>>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketProtocol {
+    Tcp,
+    Udp,
+}
+
+#[derive(Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SocketEncoding {
+    #[default]
+    Text,
+    Hex,
+    Base64,
+}
+
+#[derive(Clone, Serialize)]
+struct SocketDataEvent {
+    socket_id: String,
+    direction: &'static str,
+    size: usize,
+    text: String,
+    hex: String,
+    base64: String,
+    timestamp_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct SocketStatusEvent {
+    socket_id: String,
+    status: String,
+    detail: Option<String>,
+    timestamp_ms: u64,
+}
+
+fn decode_payload(data: &str, encoding: SocketEncoding) -> Result<Vec<u8>, String> {
+    match encoding {
+        SocketEncoding::Text => Ok(data.as_bytes().to_vec()),
+        SocketEncoding::Base64 => STANDARD
+            .decode(data.trim())
+            .map_err(|e| format!("payload is not valid base64: {e}")),
+        SocketEncoding::Hex => {
+            // Accept "de ad be ef", "deadbeef" and "0xde,0xad" styles alike.
+            let digits: String = data
+                .split(|c: char| c.is_whitespace() || c == ',' || c == ':')
+                .map(|part| part.trim_start_matches("0x").trim_start_matches("0X"))
+                .collect();
+            if let Some(bad) = digits.chars().find(|c| !c.is_ascii_hexdigit()) {
+                return Err(format!("invalid hex digit: {bad}"));
+            }
+            if !digits.len().is_multiple_of(2) {
+                return Err("hex payload has an odd number of digits".to_string());
+            }
+            (0..digits.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).map_err(|e| e.to_string()))
+                .collect()
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn emit_data(app: &tauri::AppHandle, socket_id: &str, direction: &'static str, bytes: &[u8]) {
+    let _ = app.emit(
+        "socket://data",
+        SocketDataEvent {
+            socket_id: socket_id.to_string(),
+            direction,
+            size: bytes.len(),
+            text: String::from_utf8_lossy(bytes).to_string(),
+            hex: to_hex(bytes),
+            base64: STANDARD.encode(bytes),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+fn emit_status(app: &tauri::AppHandle, socket_id: &str, status: &str, detail: Option<String>) {
+    let _ = app.emit(
+        "socket://status",
+        SocketStatusEvent {
+            socket_id: socket_id.to_string(),
+            status: status.to_string(),
+            detail,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+async fn run_tcp(
+    app: tauri::AppHandle,
+    socket_id: String,
+    stream: TcpStream,
+    mut outbound: mpsc::UnboundedReceiver<Vec<u8>>,
+) {
+    let (mut reader, mut writer) = stream.into_split();
+    let mut buf = vec![0u8; 64 * 1024];
+    let outcome = loop {
+        tokio::select! {
+            read = reader.read(&mut buf) => match read {
+                Ok(0) => break None,
+                Ok(n) => emit_data(&app, &socket_id, "in", &buf[..n]),
+                Err(e) => break Some(e.to_string()),
+            },
+            next = outbound.recv() => match next {
+                Some(bytes) => {
+                    if let Err(e) = writer.write_all(&bytes).await {
+                        break Some(e.to_string());
+                    }
+                    emit_data(&app, &socket_id, "out", &bytes);
+                }
+                None => {
+                    let _ = writer.shutdown().await;
+                    break None;
+                }
+            },
+        }
+    };
+    finish(&app, &socket_id, outcome).await;
+}
+
+async fn run_udp(
+    app: tauri::AppHandle,
+    socket_id: String,
+    socket: UdpSocket,
+    mut outbound: mpsc::UnboundedReceiver<Vec<u8>>,
+) {
+    let mut buf = vec![0u8; 64 * 1024];
+    let outcome = loop {
+        tokio::select! {
+            read = socket.recv(&mut buf) => match read {
+                Ok(n) => emit_data(&app, &socket_id, "in", &buf[..n]),
+                // ICMP port-unreachable surfaces here; report it but keep listening.
+                Err(e) => emit_status(&app, &socket_id, "error", Some(e.to_string())),
+            },
+            next = outbound.recv() => match next {
+                Some(bytes) => {
+                    if let Err(e) = socket.send(&bytes).await {
+                        break Some(e.to_string());
+                    }
+                    emit_data(&app, &socket_id, "out", &bytes);
+                }
+                None => break None,
+            },
+        }
+    };
+    finish(&app, &socket_id, outcome).await;
+}
+
+enum Connection {
+    Tcp(TcpStream),
+    Udp(UdpSocket),
+}
+
+async fn finish(app: &tauri::AppHandle, socket_id: &str, error: Option<String>) {
+    app.state::<SocketState>()
+        .sockets
+        .lock()
+        .await
+        .remove(socket_id);
+    match error {
+        Some(detail) => emit_status(app, socket_id, "error", Some(detail)),
+        None => emit_status(app, socket_id, "closed", None),
+    }
+}
+
+#[tauri::command]
+pub async fn socket_open(
+    app: tauri::AppHandle,
+    state: State<'_, SocketState>,
+    protocol: SocketProtocol,
+    host: String,
+    port: u16,
+) -> Result<String, String> {
+    let socket_id = uuid::Uuid::new_v4().to_string();
+    let address = if host.contains(':') && !host.starts_with('[') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    };
+    let connection = match protocol {
+        SocketProtocol::Tcp => {
+            let stream = TcpStream::connect(&address)
+                .await
+                .map_err(|e| format!("TCP connect to {address} failed: {e}"))?;
+            let _ = stream.set_nodelay(true);
+            Connection::Tcp(stream)
+        }
+        SocketProtocol::Udp => {
+            let bind = if address.starts_with('[') {
+                "[::]:0"
+            } else {
+                "0.0.0.0:0"
+            };
+            let socket = UdpSocket::bind(bind)
+                .await
+                .map_err(|e| format!("UDP bind failed: {e}"))?;
+            socket
+                .connect(&address)
+                .await
+                .map_err(|e| format!("UDP connect to {address} failed: {e}"))?;
+            Connection::Udp(socket)
+        }
+    };
+    // Held until the handle is in: `finish` takes the same lock, so a peer that closes at
+    // once is reported after "open" and its handle doesn't outlive it.
+    let mut sockets = state.sockets.lock().await;
+    emit_status(&app, &socket_id, "open", Some(address));
+    let (outbound, rx) = mpsc::unbounded_channel();
+    let task = match connection {
+        Connection::Tcp(stream) => {
+            tauri::async_runtime::spawn(run_tcp(app.clone(), socket_id.clone(), stream, rx))
+        }
+        Connection::Udp(socket) => {
+            tauri::async_runtime::spawn(run_udp(app.clone(), socket_id.clone(), socket, rx))
+        }
+    };
+    sockets.insert(socket_id.clone(), SocketHandle { outbound, task });
+    Ok(socket_id)
+}
+
+#[tauri::command]
+pub async fn socket_send(
+    state: State<'_, SocketState>,
+    socket_id: String,
+    data: String,
+    encoding: Option<SocketEncoding>,
+) -> Result<usize, String> {
+    let bytes = decode_payload(&data, encoding.unwrap_or_default())?;
+    let size = bytes.len();
+    let sockets = state.sockets.lock().await;
+    let handle = sockets
+        .get(&socket_id)
+        .ok_or_else(|| format!("unknown socket: {socket_id}"))?;
+    handle
+        .outbound
+        .send(bytes)
+        .map_err(|_| "socket is closed".to_string())?;
+    Ok(size)
+}
+
+#[tauri::command]
+pub async fn socket_close(
+    app: tauri::AppHandle,
+    state: State<'_, SocketState>,
+    socket_id: String,
+) -> Result<(), String> {
+    let handle = state
+        .sockets
+        .lock()
+        .await
+        .remove(&socket_id)
+        .ok_or_else(|| format!("unknown socket: {socket_id}"))?;
+    handle.task.abort();
+    emit_status(&app, &socket_id, "closed", None);
+    Ok(())
+}
@@ -0,0 +1,278 @@
+//! Workspace templates: what a new or empty workspace can start from — an example
+//! collection, its environments and starter settings. The UI checks `workspace_is_empty`
+//! when a workspace is created or switched to and offers the templates for one that is;
+//! `init_workspace_from_template` then switches to it and fills it in.
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+use tauri::State;
+
+use crate::importers::str_of;
+use crate::BackendState;
+use litefetch_core::json::count_requests;
+
+/// The collection the backend creates in a workspace without any.
+const BOOTSTRAP_COLLECTION: &str = "default";
+
+#[derive(Serialize, Clone)]
+pub struct WorkspaceTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Serialize)]
+pub struct WorkspaceInit {
+    pub path: String,
+    /// Meta of the collection the template created.
+    pub collection: Value,
+    /// Settings files written to the workspace root.
+    pub settings: Vec<String>,
+}
+
+/// `(id, name, description)`.
+const TEMPLATES: &[(&str, &str, &str)] = &[
+    (
+        "blank",
+        "Blank",
+        "An empty collection with dev, staging and prod environments.",
+    ),
+    (
+        "rest_api",
+        "REST API starter",
+        "CRUD requests against a placeholder API with tests, environments and default headers.",
+    ),
+];
+
+fn request(name: &str, method: &str, path: &str, extra: Value) -> Value {
+    let mut request = json!({
+        "id": uuid::Uuid::new_v4().to_string(),
+        "name": name,
+        "method": method,
+        "url": format!("{{{{base_url}}}}{path}"),
+        "headers": { "Accept": "application/json" },
+    });
+    if let (Some(fields), Value::Object(extra)) = (request.as_object_mut(), extra) {
+        fields.extend(extra);
+    }
+    request
+}
+
+/// One environment per stage; only `base_url` differs.
+fn environments(urls: [&str; 3]) -> Value {
+    let envs: serde_json::Map<String, Value> = ["dev", "staging", "prod"]
+        .iter()
+        .zip(urls)
+        .map(|(name, url)| {
+            (
+                name.to_string(),
+                json!({ "name": name, "variables": { "base_url": url }, "secrets": {} }),
+            )
+        })
+        .collect();
+    json!({ "active_env": "dev", "envs": envs })
+}
+
+/// What a template puts in a workspace.
+struct Starter {
+    name: &'static str,
+    collection: Value,
+    environment: Value,
+    /// Workspace settings files, by name.
+    settings: Vec<(&'static str, Value)>,
+}
+
+fn build(template: &str) -> Result<Starter, String> {
+    match template {
+        "blank" => Ok(Starter {
+            name: "My API",
+            collection: json!({ "name": "My API", "items": [] }),
+            environment: environments([
+                "http://localhost:8080",
+                "https://staging.example.com",
+                "https://api.example.com",
+            ]),
+            settings: Vec::new(),
+        }),
+        "rest_api" => {
+            let body =
+                "{\n  \"title\": \"hello\",\n  \"body\": \"from LiteFetch\",\n  \"userId\": 1\n}";
+            let status = |code: u16| {
+                format!("lf.test(\"status\", () => lf.expect(lf.response.status).toBe({code}));")
+            };
+            let posts = json!({
+                "id": uuid::Uuid::new_v4().to_string(),
+                "name": "Posts",
+                "items": [
+                    request("List posts", "GET", "/posts", json!({
+                        "query_params": [{ "key": "_limit", "value": "10", "enabled": true }],
+                        "test_script": status(200),
+                    })),
+                    request("Get post", "GET", "/posts/{{post_id}}", json!({
+                        "test_script": status(200),
+                    })),
+                    request("Create post", "POST", "/posts", json!({
+                        "headers": { "Accept": "application/json", "Content-Type": "application/json" },
+                        "body_mode": "json",
+                        "body": body,
+                        "extract_rules": [{
+                            "id": uuid::Uuid::new_v4().to_string(),
+                            "source_path": "body.id",
+                            "target_variable": "post_id",
+                        }],
+                        "test_script": status(201),
+                    })),
+                    request("Update post", "PATCH", "/posts/{{post_id}}", json!({
+                        "headers": { "Accept": "application/json", "Content-Type": "application/json" },
+                        "body_mode": "json",
+                        "body": "{\n  \"title\": \"updated\"\n}",
+                        "test_script": status(200),
+                    })),
+                    request("Delete post", "DELETE", "/posts/{{post_id}}", json!({
+                        "test_script": status(200),
+                    })),
+                ],
+            });
+            let defaults = json!({
+                "headers": [
+                    { "name": "User-Agent", "value": "LiteFetch", "enabled": true },
+                    { "name": "X-Request-Id", "value": "{{$uuid}}", "enabled": true },
+                ],
+                "request_interceptors": [],
+                "response_interceptors": [],
+            });
+            Ok(Starter {
+                name: "REST API starter",
+                collection: json!({
+                    "name": "REST API starter",
+                    "description": "Requests against JSONPlaceholder; point base_url at your own API.",
+                    "variables": { "post_id": "1" },
+                    "items": [posts],
+                }),
+                environment: environments([
+                    "https://jsonplaceholder.typicode.com",
+                    "https://jsonplaceholder.typicode.com",
+                    "https://jsonplaceholder.typicode.com",
+                ]),
+                settings: vec![(crate::defaults::SETTINGS_FILE, defaults)],
+            })
+        }
+        other => Err(format!("unknown workspace template: {other}")),
+    }
+}
+
+/// A workspace is empty when it has no settings files and its collections hold no requests
+/// (the backend creates an empty one in every new workspace).
+fn is_empty(root: &Path) -> bool {
+    if crate::bundle::settings_files()
+        .iter()
+        .any(|file| root.join(file).exists())
+    {
+        return false;
+    }
+    let Ok(entries) = fs::read_dir(root.join("collections")) else {
+        return true;
+    };
+    entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .all(|entry| {
+            fs::read_to_string(entry.path().join("collection.json"))
+                .ok()
+                .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+                .and_then(|collection| collection.get("items").and_then(Value::as_array).cloned())
+                .is_some_and(|items| count_requests(&items) == 0)
+        })
+}
+
+#[tauri::command]
+pub async fn list_workspace_templates() -> Result<Vec<WorkspaceTemplate>, String> {
+    Ok(TEMPLATES
+        .iter()
+        .map(|(id, name, description)| WorkspaceTemplate {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+        })
+        .collect())
+}
+
+/// Whether the workspace at `path` (the current one when omitted) has nothing in it yet.
+#[tauri::command]
+pub async fn workspace_is_empty(
+    app: tauri::AppHandle,
+    path: Option<String>,
+) -> Result<bool, String> {
+    let root = match path {
+        Some(path) => litefetch_core::workspace::normalize_path(path.trim()),
+        None => crate::load_workspace_path(&app)?,
+    };
+    Ok(is_empty(&root))
+}
+
+/// Switches to the workspace at `path` and initializes it from `template`. Refuses a
+/// workspace that already has requests or settings, so nothing is overwritten.
+#[tauri::command]
+pub async fn init_workspace_from_template(
+    app: tauri::AppHandle,
+    state: State<'_, BackendState>,
+    path: String,
+    template: String,
+) -> Result<WorkspaceInit, String> {
+    let starter = build(&template)?;
+    if !is_empty(&litefetch_core::workspace::normalize_path(path.trim())) {
+        return Err(
+            "workspace is not empty; templates only initialize empty workspaces".to_string(),
+        );
+    }
+    let path = crate::switch_workspace(app.clone(), state, path).await?;
+    crate::lock::ensure_writable(&app)?;
+    let root = crate::load_workspace_path(&app)?;
+
+    let metas = crate::backend_get(&app, "/collections").await?;
+    if metas
+        .as_array()
+        .into_iter()
+        .flatten()
+        .any(|meta| str_of(meta, "id") == BOOTSTRAP_COLLECTION)
+    {
+        let base_url = crate::backend_url(&app).await?;
+        let response = reqwest::Client::new()
+            .delete(format!("{base_url}/collections/{BOOTSTRAP_COLLECTION}"))
+            .send()
+            .await
+            .map_err(|e| format!("collection delete failed: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!(
+                "collection delete failed: HTTP {}",
+                response.status()
+            ));
+        }
+    }
+    let collection = crate::create_collection_with(
+        &app,
+        json!({
+            "name": starter.name,
+            "collection": starter.collection,
+            "environment": starter.environment,
+        }),
+    )
+    .await?;
+
+    let mut written = Vec::new();
+    for (file, value) in starter.settings {
+        let payload = serde_json::to_string_pretty(&value)
+            .map_err(|e| format!("workspace settings serialize failed: {e}"))?;
+        fs::write(root.join(file), payload)
+            .map_err(|e| format!("workspace settings persist failed: {e}"))?;
+        written.push(file.to_string());
+    }
+    tracing::info!("workspace {path} initialized from template {template}");
+    Ok(WorkspaceInit {
+        path,
+        collection,
+        settings: written,
+    })
+}
@@ -0,0 +1,168 @@
+//! How much disk the current workspace takes, by category, for the storage settings: the
+//! collections, their send history, the response store (with how much its compression
+//! saves), each shell cache under `.litefetch` and the workspace's settings files.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::responses::{compressed_body_size, COMPRESSED_EXT};
+
+/// The backend's per-collection files that grow with every send.
+const HISTORY_FILES: &[&str] = &["history.json", "last_results.json"];
+
+#[derive(Default)]
+struct Usage {
+    files: u64,
+    bytes: u64,
+}
+
+impl Usage {
+    fn add(&mut self, bytes: u64) {
+        self.files += 1;
+        self.bytes += bytes;
+    }
+}
+
+#[derive(Serialize)]
+pub struct Category {
+    /// `collections`, `history`, `responses`, `settings`, or the name of a cache under
+    /// `.litefetch` or another folder in the workspace.
+    pub name: String,
+    pub files: u64,
+    pub bytes: u64,
+}
+
+#[derive(Serialize, Default)]
+pub struct ResponseStore {
+    pub bodies: u64,
+    /// Of those, the ones stored compressed; the rest were stored before compression.
+    pub compressed: u64,
+    /// On disk, bodies only.
+    pub stored_bytes: u64,
+    /// What the same bodies would take uncompressed.
+    pub body_bytes: u64,
+}
+
+#[derive(Serialize)]
+pub struct StorageStats {
+    pub workspace: String,
+    pub total_bytes: u64,
+    /// Largest first.
+    pub categories: Vec<Category>,
+    pub responses: ResponseStore,
+    pub timestamp_ms: u64,
+}
+
+/// Adds up every file under `dir` into the categories of their paths.
+fn walk(dir: &Path, root: &Path, usage: &mut BTreeMap<String, Usage>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        let path = entry.path();
+        if meta.is_dir() {
+            walk(&path, root, usage);
+        } else if meta.is_file() {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            usage.entry(category(relative)).or_default().add(meta.len());
+        }
+    }
+}
+
+/// The category of a file, by its path relative to the workspace.
+fn category(relative: &Path) -> String {
+    let parts: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    match parts.as_slice() {
+        [dir, rest @ ..] if dir == "collections" => {
+            let file = rest.last().map(String::as_str).unwrap_or_default();
+            match HISTORY_FILES.contains(&file) {
+                true => "history".to_string(),
+                false => "collections".to_string(),
+            }
+        }
+        [dir, name, ..] if dir == ".litefetch" => name.clone(),
+        [dir, ..] => dir.clone(),
+        [] => "settings".to_string(),
+    }
+}
+
+fn response_store(dir: &Path) -> ResponseStore {
+    let mut store = ResponseStore::default();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return store;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let Ok(stored) = fs::metadata(&path).map(|meta| meta.len()) else {
+            continue;
+        };
+        let body = if name.ends_with(&format!(".{COMPRESSED_EXT}")) {
+            store.compressed += 1;
+            compressed_body_size(&path).unwrap_or(stored)
+        } else if name.ends_with(".body") {
+            stored
+        } else {
+            continue;
+        };
+        store.bodies += 1;
+        store.stored_bytes += stored;
+        store.body_bytes += body;
+    }
+    store
+}
+
+/// Disk usage of the current workspace by category. Version control folders (`.git`) are
+/// left out.
+#[tauri::command]
+pub async fn get_storage_stats(app: tauri::AppHandle) -> Result<StorageStats, String> {
+    let root = crate::load_workspace_path(&app)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut usage = BTreeMap::new();
+        if let Ok(entries) = fs::read_dir(&root) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+                match entry.file_name().to_string_lossy().as_ref() {
+                    ".git" | ".hg" | ".svn" => {}
+                    _ if path.is_dir() => walk(&path, &root, &mut usage),
+                    _ => {
+                        if let Ok(meta) = entry.metadata() {
+                            usage
+                                .entry("settings".to_string())
+                                .or_default()
+                                .add(meta.len());
+                        }
+                    }
+                }
+            }
+        }
+        let mut categories: Vec<Category> = usage
+            .into_iter()
+            .map(|(name, usage)| Category {
+                name,
+                files: usage.files,
+                bytes: usage.bytes,
+            })
+            .collect();
+        categories.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+        Ok(StorageStats {
+            workspace: root.to_string_lossy().to_string(),
+            total_bytes: categories.iter().map(|c| c.bytes).sum(),
+            categories,
+            responses: response_store(&root.join(".litefetch").join("responses")),
+            timestamp_ms: crate::now_ms(),
+        })
+    })
+    .await
+    .map_err(|e| format!("storage stats failed: {e}"))?
+}
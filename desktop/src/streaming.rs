@@ -0,0 +1,307 @@
+//! Streamed responses: a request with `streaming` set is sent by the shell, which reads the
+//! body as it arrives instead of waiting for the connection to close, splits it into records
+//! (NDJSON, JSON text sequences, lines or raw chunks; see `litefetch_core::records`) and emits
+//! each one as a `stream://record` event with the time it arrived. `stop_stream` ends the read
+//! early; the result then holds everything received up to that point, and is stored and
+//! logged like any other response.
+//!
+//! Like the other shell-sent modes (see `upload`), cookies and the signing auth types stay
+//! with the backend, so a request that needs them can't be streamed.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Manager, State};
+use tokio::sync::{Mutex, Notify};
+
+use crate::importers::{array_of, str_of};
+use crate::upload::{authorization, failure, header, url_of};
+use litefetch_core::records::{Record, RecordFormat, Splitter};
+
+/// Records kept in the result; later ones are still emitted and counted.
+const MAX_KEPT_RECORDS: usize = 10_000;
+
+pub struct StreamState {
+    /// Stop signals of the streams being read, by request id.
+    streams: Mutex<HashMap<String, Arc<Notify>>>,
+}
+
+impl StreamState {
+    pub fn new() -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct StreamOptions {
+    pub format: RecordFormat,
+    /// Stops reading after this many records.
+    pub max_records: Option<usize>,
+}
+
+#[derive(Serialize, Clone)]
+pub struct StreamedRecord {
+    pub index: usize,
+    pub timestamp_ms: u64,
+    /// Since the request was sent.
+    pub elapsed_ms: u64,
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+#[derive(Serialize, Clone)]
+struct RecordEvent {
+    request_id: String,
+    #[serde(flatten)]
+    record: StreamedRecord,
+}
+
+/// Whether the shell streams `request`'s response (see the module docs).
+pub fn is_streamed(request: &Value) -> bool {
+    request.get("streaming").is_some_and(Value::is_object)
+}
+
+fn body_of(request: &Value, headers: &mut Map<String, Value>) -> Result<Vec<u8>, String> {
+    let mut content_type = |mime: &str| {
+        if header(headers, "content-type").is_none() {
+            headers.insert("Content-Type".into(), Value::String(mime.into()));
+        }
+    };
+    match (str_of(request, "body_mode"), request.get("body")) {
+        ("form-urlencoded", _) => {
+            content_type("application/x-www-form-urlencoded");
+            let pairs: Vec<(String, String)> = array_of(request, "form_body")
+                .iter()
+                .filter(|row| row.get("enabled").and_then(Value::as_bool) != Some(false))
+                .map(|row| (str_of(row, "key").into(), str_of(row, "value").into()))
+                .collect();
+            Ok(reqwest::Url::parse_with_params("http://body/", pairs)
+                .ok()
+                .and_then(|url| url.query().map(|q| q.as_bytes().to_vec()))
+                .unwrap_or_default())
+        }
+        ("" | "raw" | "json", None | Some(Value::Null)) => Ok(Vec::new()),
+        ("" | "raw" | "json", Some(Value::String(text))) => {
+            if str_of(request, "body_mode") == "json" {
+                content_type("application/json");
+            }
+            Ok(text.as_bytes().to_vec())
+        }
+        ("" | "raw" | "json", Some(value)) => {
+            content_type("application/json");
+            Ok(value.to_string().into_bytes())
+        }
+        (mode, _) => Err(format!(
+            "{mode} bodies can't be sent with a streamed response"
+        )),
+    }
+}
+
+/// Sends `request` (already resolved) and reads its response record by record, returning a
+/// result shaped like the backend's with the records under `stream`.
+pub async fn send(app: &tauri::AppHandle, request: &Value) -> Result<Value, String> {
+    let started = Instant::now();
+    let request_id = str_of(request, "id").to_string();
+    let options: StreamOptions = serde_json::from_value(request["streaming"].clone())
+        .map_err(|e| format!("invalid streaming options: {e}"))?;
+    let url = url_of(request)?;
+    let method = match str_of(request, "method") {
+        "" => "GET".to_string(),
+        method => method.to_uppercase(),
+    };
+    let method = reqwest::Method::from_bytes(method.as_bytes())
+        .map_err(|e| format!("invalid method {method:?}: {e}"))?;
+    let mut headers = request
+        .get("headers")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    if let Some(auth) = authorization(request, "streamed responses")? {
+        headers.retain(|key, _| !key.eq_ignore_ascii_case("authorization"));
+        headers.insert("Authorization".to_string(), Value::String(auth));
+    }
+    let body = body_of(request, &mut headers)?;
+    let snapshot = json!({
+        "method": method.as_str(),
+        "url": url.as_str(),
+        "headers": headers,
+        "body_mode": str_of(request, "body_mode"),
+        "body": request.get("body").cloned().unwrap_or(Value::Null),
+    });
+
+    let verify = request
+        .get("verify_ssl")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let timeout = request
+        .get("timeout_seconds")
+        .and_then(Value::as_u64)
+        .filter(|t| *t > 0)
+        .unwrap_or(30);
+    // Streams stay open as long as the server keeps sending; only connecting is bounded.
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(!verify)
+        .connect_timeout(Duration::from_secs(timeout))
+        .build()
+        .map_err(|e| format!("client init failed: {e}"))?;
+    let mut builder = client.request(method, url).body(body);
+    for (key, value) in &headers {
+        builder = builder.header(key.as_str(), value.as_str().unwrap_or_default());
+    }
+
+    let stop = Arc::new(Notify::new());
+    let state: State<'_, StreamState> = app.state();
+    state
+        .streams
+        .lock()
+        .await
+        .insert(request_id.clone(), stop.clone());
+    let read = read(app, &request_id, builder, &options, &stop, started).await;
+    state.streams.lock().await.remove(&request_id);
+    let outcome = match read {
+        Ok(outcome) => outcome,
+        Err(e) => return Ok(failure(request, snapshot, started, e)),
+    };
+
+    let content_type = header(&outcome.headers, "content-type").map(str::to_string);
+    let lower = content_type.clone().unwrap_or_default().to_lowercase();
+    Ok(json!({
+        "request_id": request_id,
+        "status_code": outcome.status,
+        "duration_ms": started.elapsed().as_secs_f64() * 1000.0,
+        "headers": outcome.headers,
+        "body": String::from_utf8_lossy(&outcome.body),
+        "body_is_json": lower.contains("application/json") || lower.contains("+json"),
+        "content_type": content_type,
+        "body_bytes": outcome.body.len(),
+        "error": outcome.error,
+        "timestamp": crate::now_ms() as f64 / 1000.0,
+        "sent_request": snapshot,
+        "stream": {
+            "format": outcome.format,
+            "count": outcome.count,
+            "records": outcome.records,
+            "truncated": outcome.count > outcome.records.len(),
+            // `complete`, `stopped`, `limit` or `error`.
+            "ended": outcome.ended,
+        },
+    }))
+}
+
+struct Outcome {
+    status: u16,
+    headers: Map<String, Value>,
+    body: Vec<u8>,
+    format: RecordFormat,
+    records: Vec<StreamedRecord>,
+    count: usize,
+    ended: &'static str,
+    /// Set when the connection broke after the response started; its records are kept.
+    error: Option<String>,
+}
+
+async fn read(
+    app: &tauri::AppHandle,
+    request_id: &str,
+    builder: reqwest::RequestBuilder,
+    options: &StreamOptions,
+    stop: &Notify,
+    started: Instant,
+) -> Result<Outcome, String> {
+    let mut response = tokio::select! {
+        sent = builder.send() => sent.map_err(|e| e.to_string())?,
+        _ = stop.notified() => return Err("the stream was stopped before the response".into()),
+    };
+    let headers: Map<String, Value> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                Value::String(String::from_utf8_lossy(value.as_bytes()).to_string()),
+            )
+        })
+        .collect();
+    let format = match options.format {
+        RecordFormat::Auto => RecordFormat::detect(header(&headers, "content-type").unwrap_or("")),
+        format => format,
+    };
+    let mut outcome = Outcome {
+        status: response.status().as_u16(),
+        headers,
+        body: Vec::new(),
+        format,
+        records: Vec::new(),
+        count: 0,
+        ended: "complete",
+        error: None,
+    };
+    let mut splitter = Splitter::new(format);
+    let emit = |outcome: &mut Outcome, record: Record| {
+        let record = StreamedRecord {
+            index: outcome.count,
+            timestamp_ms: crate::now_ms(),
+            elapsed_ms: started.elapsed().as_millis() as u64,
+            record,
+        };
+        let _ = app.emit(
+            "stream://record",
+            RecordEvent {
+                request_id: request_id.to_string(),
+                record: record.clone(),
+            },
+        );
+        if outcome.records.len() < MAX_KEPT_RECORDS {
+            outcome.records.push(record);
+        }
+        outcome.count += 1;
+    };
+    let limit = options.max_records.unwrap_or(usize::MAX);
+    loop {
+        let chunk = tokio::select! {
+            chunk = response.chunk() => chunk,
+            _ = stop.notified() => {
+                outcome.ended = "stopped";
+                break;
+            }
+        };
+        let chunk = match chunk {
+            Ok(Some(chunk)) => chunk,
+            Ok(None) => break,
+            Err(e) => {
+                outcome.ended = "error";
+                outcome.error = Some(e.to_string());
+                break;
+            }
+        };
+        outcome.body.extend_from_slice(&chunk);
+        for record in splitter.push(&chunk) {
+            emit(&mut outcome, record);
+        }
+        if outcome.count >= limit {
+            outcome.ended = "limit";
+            break;
+        }
+    }
+    if let Some(record) = splitter.finish() {
+        emit(&mut outcome, record);
+    }
+    Ok(outcome)
+}
+
+/// Stops reading the streamed response of `request_id`; its send returns with the records
+/// received so far.
+#[tauri::command]
+pub async fn stop_stream(state: State<'_, StreamState>, request_id: String) -> Result<(), String> {
+    let streams = state.streams.lock().await;
+    let stop = streams
+        .get(&request_id)
+        .ok_or_else(|| format!("no stream is being read for request {request_id}"))?;
+    stop.notify_one();
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! Client-side encryption of synced files with an age X25519 workspace key. The key is kept in
+//! the OS keychain; teammates share it out of band (`export_sync_key` / `import_sync_key`), and
+//! the storage provider only ever sees age ciphertext. Encrypted environment files are sealed
+//! to the same key (see `sealed_env`).
+
+use age::secrecy::ExposeSecret;
+use age::x25519::Identity;
+use std::str::FromStr;
+
+/// First line of every age file.
+const AGE_HEADER: &[u8] = b"age-encryption.org/v1\n";
+
+fn entry(workspace: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(
+        &litefetch_core::profile::keychain_service(),
+        &format!("sync-key/{workspace}"),
+    )
+    .map_err(|e| format!("keychain unavailable: {e}"))
+}
+
+pub fn parse(key: &str) -> Result<Identity, String> {
+    Identity::from_str(key.trim()).map_err(|e| format!("invalid sync key: {e}"))
+}
+
+pub fn load(workspace: &str) -> Result<Option<Identity>, String> {
+    match entry(workspace)?.get_password() {
+        Ok(key) => parse(&key).map(Some),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("keychain read failed: {e}")),
+    }
+}
+
+pub fn store(workspace: &str, identity: &Identity) -> Result<(), String> {
+    entry(workspace)?
+        .set_password(identity.to_string().expose_secret())
+        .map_err(|e| format!("keychain write failed: {e}"))
+}
+
+/// The shareable form of a key (`AGE-SECRET-KEY-1…`).
+pub fn export(identity: &Identity) -> String {
+    identity.to_string().expose_secret().to_string()
+}
+
+pub fn is_sealed(bytes: &[u8]) -> bool {
+    bytes.starts_with(AGE_HEADER)
+}
+
+pub fn seal(identity: &Identity, bytes: &[u8]) -> Result<Vec<u8>, String> {
+    age::encrypt(&identity.to_public(), bytes).map_err(|e| format!("sync encrypt failed: {e}"))
+}
+
+pub fn open(identity: &Identity, bytes: &[u8]) -> Result<Vec<u8>, String> {
+    age::decrypt(identity, bytes).map_err(|e| format!("sync decrypt failed: {e}"))
+}
@@ -0,0 +1,708 @@
+//! Remote workspace sync: pushes and pulls the workspace files to an S3 bucket or a WebDAV
+//! collection, for teams that share a workspace without git. The configuration lives in
+//! `.litefetch/sync` (the secret key or password in the OS keychain), along with the state
+//! of every file as of the last sync, which is how changes are told apart on each side:
+//!
+//! - changed on one side only: copied to the other (deletions included);
+//! - changed on both: the most recently modified version wins, and the other one is kept
+//!   next to it as `<name>.conflict-<time><ext>`;
+//! - deleted on one side, changed on the other: the change wins.
+//!
+//! With `encrypt` on, files are encrypted client-side with the workspace's sync key (see
+//! `crypt`) before they leave the machine; files already stored in the other form are still
+//! read, and rewritten the next time they are synced. Git remotes are not covered: git works
+//! on the plaintext working tree, so encrypting there would need filters in every clone.
+//!
+//! Progress is announced as `sync://status`; `get_sync_status` reports the same state.
+
+pub(crate) mod crypt;
+pub(crate) mod s3;
+mod webdav;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+use tauri::{Emitter, State};
+
+const CONFIG_FILE: &str = "config.json";
+const STATE_FILE: &str = "state.json";
+/// Never synced: local app state, and git metadata for workspaces that also use git.
+const SKIPPED_DIRS: &[&str] = &[".litefetch", ".git"];
+
+pub struct SyncState {
+    running: AtomicBool,
+}
+
+impl SyncState {
+    pub fn new() -> Self {
+        Self {
+            running: AtomicBool::new(false),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SyncConfig {
+    #[serde(flatten)]
+    pub target: SyncTarget,
+    /// Encrypt files with the workspace's sync key before uploading them.
+    #[serde(default)]
+    pub encrypt: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum SyncTarget {
+    S3 {
+        /// For S3-compatible services; AWS when empty.
+        #[serde(default)]
+        endpoint: Option<String>,
+        #[serde(default)]
+        region: String,
+        bucket: String,
+        /// Key prefix the workspace is stored under.
+        #[serde(default)]
+        prefix: String,
+        access_key_id: String,
+        #[serde(default)]
+        path_style: bool,
+    },
+    Webdav {
+        url: String,
+        #[serde(default)]
+        username: String,
+    },
+}
+
+pub(super) struct RemoteFile {
+    pub path: String,
+    /// ETag (or modification date) identifying the stored version.
+    pub tag: String,
+    pub modified_ms: u64,
+}
+
+enum Storage {
+    S3(s3::Bucket),
+    Webdav(webdav::Dav),
+}
+
+/// The sync target, encrypting and decrypting file bodies on the way.
+struct Remote {
+    storage: Storage,
+    key: Option<age::x25519::Identity>,
+    encrypt: bool,
+}
+
+impl Remote {
+    async fn list(&self) -> Result<Vec<RemoteFile>, String> {
+        match &self.storage {
+            Storage::S3(bucket) => bucket.list().await,
+            Storage::Webdav(dav) => dav.list().await,
+        }
+    }
+
+    /// A file's plaintext, and whether it should be stored again: it is encrypted while
+    /// encryption is off, or the other way round.
+    async fn get(&self, path: &str) -> Result<(Vec<u8>, bool), String> {
+        let stored = match &self.storage {
+            Storage::S3(bucket) => bucket.get(path).await?,
+            Storage::Webdav(dav) => dav.get(path).await?,
+        };
+        if !crypt::is_sealed(&stored) {
+            return Ok((stored, self.encrypt));
+        }
+        let key = self
+            .key
+            .as_ref()
+            .ok_or_else(|| format!("{path} is encrypted; import the workspace's sync key"))?;
+        Ok((crypt::open(key, &stored)?, !self.encrypt))
+    }
+
+    async fn put(&self, path: &str, body: &[u8]) -> Result<(), String> {
+        let sealed;
+        let body = match (&self.key, self.encrypt) {
+            (Some(key), true) => {
+                sealed = crypt::seal(key, body)?;
+                &sealed
+            }
+            _ => body,
+        };
+        match &self.storage {
+            Storage::S3(bucket) => bucket.put(path, body).await,
+            Storage::Webdav(dav) => dav.put(path, body).await,
+        }
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), String> {
+        match &self.storage {
+            Storage::S3(bucket) => bucket.delete(path).await,
+            Storage::Webdav(dav) => dav.delete(path).await,
+        }
+    }
+}
+
+/// A file as of the last sync, identical on both sides at that point.
+#[derive(Serialize, Deserialize, Clone)]
+struct Synced {
+    sha256: String,
+    tag: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SyncReport {
+    pub uploaded: Vec<String>,
+    pub downloaded: Vec<String>,
+    pub deleted_local: Vec<String>,
+    pub deleted_remote: Vec<String>,
+    /// Conflict copies written next to the winning version.
+    pub conflicts: Vec<String>,
+    pub started_ms: u64,
+    pub finished_ms: u64,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct StoredState {
+    files: HashMap<String, Synced>,
+    last_sync_ms: Option<u64>,
+    last_report: Option<SyncReport>,
+    last_error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SyncStatus {
+    pub configured: bool,
+    pub running: bool,
+    pub last_sync_ms: Option<u64>,
+    pub last_report: Option<SyncReport>,
+    pub last_error: Option<String>,
+    /// Local files added, changed or deleted since the last sync.
+    pub local_changes: usize,
+}
+
+#[derive(Clone, Serialize)]
+struct StatusEvent {
+    /// `running`, `finished` or `failed`.
+    state: &'static str,
+    path: Option<String>,
+    done: usize,
+    total: usize,
+    report: Option<SyncReport>,
+    error: Option<String>,
+    timestamp_ms: u64,
+}
+
+fn sync_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    crate::workspace_state_dir(app, "sync")
+}
+
+fn load_config(app: &tauri::AppHandle) -> Result<Option<SyncConfig>, String> {
+    let path = sync_dir(app)?.join(CONFIG_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("sync config read failed: {e}"))?;
+    serde_json::from_str(&data)
+        .map(Some)
+        .map_err(|e| format!("sync config parse failed: {e}"))
+}
+
+fn load_state(app: &tauri::AppHandle) -> Result<StoredState, String> {
+    let path = sync_dir(app)?.join(STATE_FILE);
+    if !path.exists() {
+        return Ok(StoredState::default());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("sync state read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("sync state parse failed: {e}"))
+}
+
+fn save_state(app: &tauri::AppHandle, state: &StoredState) -> Result<(), String> {
+    let payload =
+        serde_json::to_string(state).map_err(|e| format!("sync state serialize failed: {e}"))?;
+    fs::write(sync_dir(app)?.join(STATE_FILE), payload)
+        .map_err(|e| format!("sync state persist failed: {e}"))
+}
+
+fn credential(app: &tauri::AppHandle) -> Result<keyring::Entry, String> {
+    let workspace = crate::history::workspace_key(app)?;
+    keyring::Entry::new(
+        &litefetch_core::profile::keychain_service(),
+        &format!("sync/{workspace}"),
+    )
+    .map_err(|e| format!("keychain unavailable: {e}"))
+}
+
+fn load_secret(app: &tauri::AppHandle) -> Result<String, String> {
+    match credential(app)?.get_password() {
+        Ok(secret) => Ok(secret),
+        Err(keyring::Error::NoEntry) => Ok(String::new()),
+        Err(e) => Err(format!("keychain read failed: {e}")),
+    }
+}
+
+/// The workspace's S3 sync target and its secret key, for tools that reuse the stored
+/// credentials (see `presign`).
+pub(crate) fn s3_target(app: &tauri::AppHandle) -> Result<(SyncTarget, String), String> {
+    match load_config(app)? {
+        Some(SyncConfig {
+            target: target @ SyncTarget::S3 { .. },
+            ..
+        }) => Ok((target, load_secret(app)?)),
+        _ => Err("this workspace does not sync to S3".into()),
+    }
+}
+
+fn connect(app: &tauri::AppHandle, config: &SyncConfig) -> Result<Remote, String> {
+    let secret = load_secret(app)?;
+    let key = crypt::load(&crate::history::workspace_key(app)?)?;
+    if config.encrypt && key.is_none() {
+        return Err("encryption is on but this workspace has no sync key".into());
+    }
+    let storage = match &config.target {
+        SyncTarget::S3 {
+            endpoint,
+            region,
+            bucket,
+            prefix,
+            access_key_id,
+            path_style,
+        } => Storage::S3(s3::Bucket::new(
+            endpoint.as_deref(),
+            region,
+            bucket,
+            prefix,
+            *path_style,
+            access_key_id,
+            &secret,
+        )?),
+        SyncTarget::Webdav { url, username } => {
+            Storage::Webdav(webdav::Dav::new(url, username, &secret)?)
+        }
+    };
+    Ok(Remote {
+        storage,
+        key,
+        encrypt: config.encrypt,
+    })
+}
+
+struct LocalFile {
+    sha256: String,
+    modified_ms: u64,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Every workspace file outside the skipped directories, by `/`-separated relative path.
+fn scan(root: &Path) -> Result<HashMap<String, LocalFile>, String> {
+    let mut files = HashMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir).map_err(|e| format!("workspace read failed: {e}"))?;
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                let name = entry.file_name();
+                if !SKIPPED_DIRS.contains(&name.to_string_lossy().as_ref()) {
+                    pending.push(path);
+                }
+                continue;
+            }
+            let Ok(bytes) = fs::read(&path) else {
+                continue;
+            };
+            let rel = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let modified_ms = meta
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            files.insert(
+                rel,
+                LocalFile {
+                    sha256: sha256_hex(&bytes),
+                    modified_ms,
+                },
+            );
+        }
+    }
+    Ok(files)
+}
+
+/// `dir/name.ext` → `dir/name.conflict-20260101-120000.ext`.
+fn conflict_name(path: &str, now_ms: u64) -> String {
+    let stamp = chrono::DateTime::from_timestamp_millis(now_ms as i64)
+        .unwrap_or_default()
+        .format("%Y%m%d-%H%M%S");
+    let (dir, file) = path.rsplit_once('/').map_or(("", path), |(d, f)| (d, f));
+    let (stem, ext) = match file.rfind('.') {
+        Some(dot) if dot > 0 => file.split_at(dot),
+        _ => (file, ""),
+    };
+    let renamed = format!("{stem}.conflict-{stamp}{ext}");
+    if dir.is_empty() {
+        renamed
+    } else {
+        format!("{dir}/{renamed}")
+    }
+}
+
+/// Whether a remote path may be written below the workspace: relative, without empty, `.` or
+/// `..` components, and outside the skipped directories. Remote listings are not trusted.
+fn is_safe_path(path: &str) -> bool {
+    let first = path.split('/').next().unwrap_or_default();
+    !SKIPPED_DIRS
+        .iter()
+        .any(|dir| dir.eq_ignore_ascii_case(first))
+        && path.split('/').all(|part| {
+            !part.is_empty() && part != "." && part != ".." && !part.contains(['\\', ':'])
+        })
+}
+
+fn write_local(root: &Path, path: &str, bytes: &[u8]) -> Result<(), String> {
+    if !is_safe_path(path) {
+        return Err(format!("sync write failed: unsafe path {path}"));
+    }
+    let target = root.join(path);
+    let Some(parent) = target.parent() else {
+        return Err(format!("sync write failed: unsafe path {path}"));
+    };
+    fs::create_dir_all(parent).map_err(|e| format!("sync write failed: {e}"))?;
+    // A directory on the way may be a symlink out of the workspace.
+    let inside = match (parent.canonicalize(), root.canonicalize()) {
+        (Ok(parent), Ok(root)) => parent.starts_with(root),
+        _ => false,
+    };
+    if !inside {
+        return Err(format!(
+            "sync write failed: {path} is outside the workspace"
+        ));
+    }
+    fs::write(&target, bytes).map_err(|e| format!("sync write failed: {e}"))
+}
+
+fn emit(app: &tauri::AppHandle, event: StatusEvent) {
+    let _ = app.emit("sync://status", event);
+}
+
+enum Action {
+    Upload,
+    Download,
+    DeleteLocal,
+    DeleteRemote,
+    /// Changed on both sides since the last sync.
+    Resolve,
+    /// Gone on both sides; only the sync state needs updating.
+    Forget,
+}
+
+async fn run(
+    app: &tauri::AppHandle,
+    root: &Path,
+    remote: &Remote,
+    state: &mut StoredState,
+) -> Result<SyncReport, String> {
+    let mut report = SyncReport {
+        started_ms: crate::now_ms(),
+        ..SyncReport::default()
+    };
+    let local = scan(root)?;
+    let listed: HashMap<String, RemoteFile> = remote
+        .list()
+        .await?
+        .into_iter()
+        .map(|file| (file.path.clone(), file))
+        .collect();
+
+    let paths: BTreeSet<&String> = local
+        .keys()
+        .chain(listed.keys())
+        .chain(state.files.keys())
+        .collect();
+    let mut plan = Vec::new();
+    for path in paths {
+        let base = state.files.get(path);
+        let here = local.get(path);
+        let there = listed.get(path);
+        let local_changed = here.map(|f| &f.sha256) != base.map(|b| &b.sha256);
+        let remote_changed = there.map(|f| &f.tag) != base.map(|b| &b.tag);
+        let action = match (local_changed, remote_changed, here, there) {
+            (false, false, _, _) => continue,
+            (_, _, None, None) => Action::Forget,
+            (true, false, Some(_), _) => Action::Upload,
+            (true, false, None, Some(_)) => Action::DeleteRemote,
+            (false, true, _, Some(_)) => Action::Download,
+            (false, true, Some(_), None) => Action::DeleteLocal,
+            // Deleted on one side, changed on the other: keep the change.
+            (true, true, Some(_), None) => Action::Upload,
+            (true, true, None, Some(_)) => Action::Download,
+            (true, true, Some(_), Some(_)) => Action::Resolve,
+        };
+        plan.push((path.clone(), action));
+    }
+
+    let total = plan.len();
+    // Paths whose remote tag must be read back once everything is uploaded.
+    let mut settled: HashMap<String, String> = HashMap::new();
+    for (done, (path, action)) in plan.into_iter().enumerate() {
+        emit(
+            app,
+            StatusEvent {
+                state: "running",
+                path: Some(path.clone()),
+                done,
+                total,
+                report: None,
+                error: None,
+                timestamp_ms: crate::now_ms(),
+            },
+        );
+        match action {
+            Action::Upload => {
+                let bytes =
+                    fs::read(root.join(&path)).map_err(|e| format!("sync read failed: {e}"))?;
+                remote.put(&path, &bytes).await?;
+                settled.insert(path.clone(), sha256_hex(&bytes));
+                report.uploaded.push(path);
+            }
+            Action::Download => {
+                let (bytes, restore) = remote.get(&path).await?;
+                write_local(root, &path, &bytes)?;
+                if restore {
+                    remote.put(&path, &bytes).await?;
+                }
+                settled.insert(path.clone(), sha256_hex(&bytes));
+                report.downloaded.push(path);
+            }
+            Action::DeleteLocal => {
+                if is_safe_path(&path) {
+                    let _ = fs::remove_file(root.join(&path));
+                }
+                state.files.remove(&path);
+                report.deleted_local.push(path);
+            }
+            Action::DeleteRemote => {
+                remote.delete(&path).await?;
+                state.files.remove(&path);
+                report.deleted_remote.push(path);
+            }
+            Action::Forget => {
+                state.files.remove(&path);
+            }
+            Action::Resolve => {
+                let (theirs, restore) = remote.get(&path).await?;
+                let ours =
+                    fs::read(root.join(&path)).map_err(|e| format!("sync read failed: {e}"))?;
+                let sha256 = sha256_hex(&ours);
+                if sha256_hex(&theirs) == sha256 {
+                    // Same edit on both sides.
+                    if restore {
+                        remote.put(&path, &ours).await?;
+                    }
+                    settled.insert(path, sha256);
+                    continue;
+                }
+                let copy = conflict_name(&path, report.started_ms);
+                let local_newer = local.get(&path).map_or(0, |f| f.modified_ms)
+                    >= listed.get(&path).map_or(0, |f| f.modified_ms);
+                if local_newer {
+                    write_local(root, &copy, &theirs)?;
+                    remote.put(&path, &ours).await?;
+                    settled.insert(path.clone(), sha256);
+                    report.uploaded.push(path);
+                } else {
+                    write_local(root, &copy, &ours)?;
+                    write_local(root, &path, &theirs)?;
+                    settled.insert(path.clone(), sha256_hex(&theirs));
+                    report.downloaded.push(path);
+                }
+                report.conflicts.push(copy);
+            }
+        }
+    }
+
+    if !settled.is_empty() {
+        // Uploads only get their tag from a fresh listing (WebDAV servers needn't return one).
+        let tags: HashMap<String, String> = remote
+            .list()
+            .await?
+            .into_iter()
+            .map(|file| (file.path, file.tag))
+            .collect();
+        for (path, sha256) in settled {
+            let tag = tags.get(&path).cloned().unwrap_or_default();
+            state.files.insert(path, Synced { sha256, tag });
+        }
+    }
+    report.finished_ms = crate::now_ms();
+    Ok(report)
+}
+
+#[tauri::command]
+pub async fn get_sync_config(app: tauri::AppHandle) -> Result<Option<SyncConfig>, String> {
+    load_config(&app)
+}
+
+/// Saves the sync target; `secret` (S3 secret key or WebDAV password) goes to the keychain and
+/// is left unchanged when omitted. `None` turns sync off. Changing the target starts over: the
+/// next sync treats every file as new on both sides.
+#[tauri::command]
+pub async fn set_sync_config(
+    app: tauri::AppHandle,
+    config: Option<SyncConfig>,
+    secret: Option<String>,
+) -> Result<(), String> {
+    let path = sync_dir(&app)?.join(CONFIG_FILE);
+    match &config {
+        Some(config) => {
+            let payload = serde_json::to_string_pretty(config)
+                .map_err(|e| format!("sync config serialize failed: {e}"))?;
+            fs::write(&path, payload).map_err(|e| format!("sync config persist failed: {e}"))?;
+            if let Some(secret) = secret {
+                credential(&app)?
+                    .set_password(&secret)
+                    .map_err(|e| format!("keychain write failed: {e}"))?;
+            }
+        }
+        None => {
+            if path.exists() {
+                fs::remove_file(&path).map_err(|e| format!("sync config persist failed: {e}"))?;
+            }
+            match credential(&app)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => {}
+                Err(e) => return Err(format!("keychain delete failed: {e}")),
+            }
+        }
+    }
+    save_state(&app, &StoredState::default())
+}
+
+/// Creates the workspace's sync key and returns it, to be shared with teammates. An existing
+/// key is only replaced with `replace`: files encrypted with it can't be read afterwards.
+#[tauri::command]
+pub async fn generate_sync_key(
+    app: tauri::AppHandle,
+    replace: Option<bool>,
+) -> Result<String, String> {
+    let workspace = crate::history::workspace_key(&app)?;
+    if crypt::load(&workspace)?.is_some() && !replace.unwrap_or(false) {
+        return Err("this workspace already has a sync key".into());
+    }
+    let identity = age::x25519::Identity::generate();
+    crypt::store(&workspace, &identity)?;
+    Ok(crypt::export(&identity))
+}
+
+/// Uses a sync key shared by a teammate for this workspace.
+#[tauri::command]
+pub async fn import_sync_key(app: tauri::AppHandle, key: String) -> Result<(), String> {
+    let identity = crypt::parse(&key)?;
+    crypt::store(&crate::history::workspace_key(&app)?, &identity)
+}
+
+#[tauri::command]
+pub async fn export_sync_key(app: tauri::AppHandle) -> Result<Option<String>, String> {
+    Ok(
+        crypt::load(&crate::history::workspace_key(&app)?)?
+            .map(|identity| crypt::export(&identity)),
+    )
+}
+
+/// Runs a sync now and returns what it did.
+#[tauri::command]
+pub async fn sync_workspace(
+    app: tauri::AppHandle,
+    state: State<'_, SyncState>,
+) -> Result<SyncReport, String> {
+    let config = load_config(&app)?.ok_or_else(|| "sync is not configured".to_string())?;
+    crate::lock::ensure_writable(&app)?;
+    if state.running.swap(true, Ordering::SeqCst) {
+        return Err("a sync is already running".into());
+    }
+    let result: Result<SyncReport, String> = async {
+        let remote = connect(&app, &config)?;
+        let root = crate::load_workspace_path(&app)?;
+        let mut stored = load_state(&app)?;
+        let outcome = run(&app, &root, &remote, &mut stored).await;
+        match &outcome {
+            Ok(report) => {
+                crate::audit::record_sync(&app, report);
+                stored.last_sync_ms = Some(report.finished_ms);
+                stored.last_report = Some(report.clone());
+                stored.last_error = None;
+            }
+            Err(e) => stored.last_error = Some(e.clone()),
+        }
+        // After a failure, files already copied are found identical on both sides next time.
+        save_state(&app, &stored)?;
+        outcome
+    }
+    .await;
+    state.running.store(false, Ordering::SeqCst);
+
+    let (event_state, report, error) = match &result {
+        Ok(report) => ("finished", Some(report.clone()), None),
+        Err(e) => ("failed", None, Some(e.clone())),
+    };
+    emit(
+        &app,
+        StatusEvent {
+            state: event_state,
+            path: None,
+            done: 0,
+            total: 0,
+            report,
+            error,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    result
+}
+
+#[tauri::command]
+pub async fn get_sync_status(
+    app: tauri::AppHandle,
+    state: State<'_, SyncState>,
+) -> Result<SyncStatus, String> {
+    let configured = load_config(&app)?.is_some();
+    let stored = load_state(&app)?;
+    let root = crate::load_workspace_path(&app)?;
+    let local_changes = if configured {
+        let local = tauri::async_runtime::spawn_blocking(move || scan(&root))
+            .await
+            .map_err(|e| format!("sync scan failed: {e}"))??;
+        let changed = local
+            .iter()
+            .filter(|(path, file)| stored.files.get(*path).map(|s| &s.sha256) != Some(&file.sha256))
+            .count();
+        let deleted = stored
+            .files
+            .keys()
+            .filter(|path| !local.contains_key(*path))
+            .count();
+        changed + deleted
+    } else {
+        0
+    };
+    Ok(SyncStatus {
+        configured,
+        running: state.running.load(Ordering::SeqCst),
+        last_sync_ms: stored.last_sync_ms,
+        last_report: stored.last_report,
+        last_error: stored.last_error,
+        local_changes,
+    })
+}
@@ -0,0 +1,322 @@
+//! S3 (and S3-compatible: MinIO, R2, B2) storage for workspace sync, signed with SigV4.
+
+use hmac::{Hmac, Mac};
+use reqwest::{Client, Method, RequestBuilder};
+use sha2::{Digest, Sha256};
+
+use super::RemoteFile;
+
+pub struct Bucket {
+    client: Client,
+    /// Scheme and authority, e.g. `https://bucket.s3.eu-west-1.amazonaws.com`.
+    origin: String,
+    /// Path before the object key: empty, or `/bucket` in path-style addressing.
+    base_path: String,
+    host: String,
+    region: String,
+    prefix: String,
+    access_key_id: String,
+    secret_access_key: String,
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// SigV4 URI encoding: everything but unreserved characters (and `/` in paths) escaped.
+fn encode(value: &str, keep_slash: bool) -> String {
+    let mut out = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char)
+            }
+            b'/' if keep_slash => out.push('/'),
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+impl Bucket {
+    pub fn new(
+        endpoint: Option<&str>,
+        region: &str,
+        bucket: &str,
+        prefix: &str,
+        path_style: bool,
+        access_key_id: &str,
+        secret_access_key: &str,
+    ) -> Result<Self, String> {
+        let region = if region.trim().is_empty() {
+            "us-east-1"
+        } else {
+            region.trim()
+        };
+        let base = match endpoint.map(str::trim).filter(|e| !e.is_empty()) {
+            Some(endpoint) => format!("{}/{bucket}", endpoint.trim_end_matches('/')),
+            None if path_style => format!("https://s3.{region}.amazonaws.com/{bucket}"),
+            None => format!("https://{bucket}.s3.{region}.amazonaws.com"),
+        };
+        let url = reqwest::Url::parse(&base).map_err(|e| format!("invalid S3 endpoint: {e}"))?;
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{host}:{port}"),
+            (Some(host), None) => host.to_string(),
+            _ => return Err("invalid S3 endpoint: missing host".into()),
+        };
+        Ok(Self {
+            client: Client::new(),
+            origin: format!("{}://{host}", url.scheme()),
+            base_path: url.path().trim_end_matches('/').to_string(),
+            host,
+            region: region.to_string(),
+            prefix: prefix.trim_matches('/').to_string(),
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+        })
+    }
+
+    fn key(&self, path: &str) -> String {
+        if self.prefix.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}/{path}", self.prefix)
+        }
+    }
+
+    /// A signed request for `key` (empty for the bucket itself) with sorted `query` pairs.
+    fn request(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, &str)],
+        body: &[u8],
+    ) -> RequestBuilder {
+        let path = format!("{}/{}", self.base_path, encode(key, true));
+        let mut query: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (encode(k, false), encode(v, false)))
+            .collect();
+        query.sort();
+        let query = query
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let now =
+            chrono::DateTime::from_timestamp_millis(crate::now_ms() as i64).unwrap_or_default();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = sha256_hex(body);
+        let canonical = format!(
+            "{}\n{path}\n{query}\nhost:{}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{amz_date}\n\nhost;x-amz-content-sha256;x-amz-date\n{payload_hash}",
+            method.as_str(),
+            self.host,
+        );
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            sha256_hex(canonical.as_bytes())
+        );
+        let key = [date.as_str(), self.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", self.secret_access_key).into_bytes(),
+                |key, part| hmac(&key, part),
+            );
+        let signature = hex::encode(hmac(&key, &to_sign));
+
+        let url = if query.is_empty() {
+            format!("{}{path}", self.origin)
+        } else {
+            format!("{}{path}?{query}", self.origin)
+        };
+        self.client
+            .request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header(
+                "authorization",
+                format!(
+                    "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={signature}",
+                    self.access_key_id
+                ),
+            )
+            .body(body.to_vec())
+    }
+
+    /// A presigned URL for `key`, valid for `expires_seconds`: SigV4 query authentication
+    /// with an unsigned payload, so any client can use it for any body.
+    pub fn presign(
+        &self,
+        method: &str,
+        key: &str,
+        expires_seconds: u64,
+        session_token: Option<&str>,
+    ) -> String {
+        let path = format!("{}/{}", self.base_path, encode(&self.key(key), true));
+        let now =
+            chrono::DateTime::from_timestamp_millis(crate::now_ms() as i64).unwrap_or_default();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let scope = format!("{date}/{}/s3/aws4_request", self.region);
+        let credential = format!("{}/{scope}", self.access_key_id);
+        let expires = expires_seconds.to_string();
+        let mut query = vec![
+            ("X-Amz-Algorithm", "AWS4-HMAC-SHA256"),
+            ("X-Amz-Credential", credential.as_str()),
+            ("X-Amz-Date", amz_date.as_str()),
+            ("X-Amz-Expires", expires.as_str()),
+            ("X-Amz-SignedHeaders", "host"),
+        ];
+        if let Some(token) = session_token.filter(|t| !t.is_empty()) {
+            query.push(("X-Amz-Security-Token", token));
+        }
+        let mut query: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (encode(k, false), encode(v, false)))
+            .collect();
+        query.sort();
+        let query = query
+            .iter()
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let canonical = format!(
+            "{}\n{path}\n{query}\nhost:{}\n\nhost\nUNSIGNED-PAYLOAD",
+            method.to_ascii_uppercase(),
+            self.host,
+        );
+        let to_sign = format!(
+            "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+            sha256_hex(canonical.as_bytes())
+        );
+        let key = [date.as_str(), self.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", self.secret_access_key).into_bytes(),
+                |key, part| hmac(&key, part),
+            );
+        let signature = hex::encode(hmac(&key, &to_sign));
+        format!("{}{path}?{query}&X-Amz-Signature={signature}", self.origin)
+    }
+
+    async fn send(&self, request: RequestBuilder, op: &str) -> Result<reqwest::Response, String> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("S3 {op} failed: {e}"))?;
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        let code = roxmltree::Document::parse(&body)
+            .ok()
+            .and_then(|doc| {
+                doc.descendants()
+                    .find(|n| n.has_tag_name("Code"))
+                    .and_then(|n| n.text().map(str::to_string))
+            })
+            .unwrap_or_default();
+        Err(format!("S3 {op} failed: {status} {code}")
+            .trim()
+            .to_string())
+    }
+
+    pub async fn list(&self) -> Result<Vec<RemoteFile>, String> {
+        let prefix = if self.prefix.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", self.prefix)
+        };
+        let mut files = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix.as_str())];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token.as_str()));
+            }
+            let response = self
+                .send(self.request(Method::GET, "", &query, b""), "list")
+                .await?;
+            let body = response
+                .text()
+                .await
+                .map_err(|e| format!("S3 list failed: {e}"))?;
+            let doc = roxmltree::Document::parse(&body)
+                .map_err(|e| format!("S3 list parse failed: {e}"))?;
+            let child = |node: roxmltree::Node, name: &str| {
+                node.children()
+                    .find(|c| c.has_tag_name(name))
+                    .and_then(|c| c.text())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            for contents in doc.descendants().filter(|n| n.has_tag_name("Contents")) {
+                let key = child(contents, "Key");
+                let Some(path) = key.strip_prefix(prefix.as_str()) else {
+                    continue;
+                };
+                if path.ends_with('/') || !super::is_safe_path(path) {
+                    continue;
+                }
+                let modified_ms =
+                    chrono::DateTime::parse_from_rfc3339(&child(contents, "LastModified"))
+                        .map(|d| d.timestamp_millis().max(0) as u64)
+                        .unwrap_or(0);
+                files.push(RemoteFile {
+                    path: path.to_string(),
+                    tag: child(contents, "ETag").trim_matches('"').to_string(),
+                    modified_ms,
+                });
+            }
+            let root = doc.root_element();
+            if child(root, "IsTruncated") != "true" {
+                break;
+            }
+            token = Some(child(root, "NextContinuationToken"));
+        }
+        Ok(files)
+    }
+
+    pub async fn get(&self, path: &str) -> Result<Vec<u8>, String> {
+        let response = self
+            .send(
+                self.request(Method::GET, &self.key(path), &[], b""),
+                "download",
+            )
+            .await?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("S3 download failed: {e}"))?;
+        Ok(bytes.to_vec())
+    }
+
+    pub async fn put(&self, path: &str, body: &[u8]) -> Result<(), String> {
+        self.send(
+            self.request(Method::PUT, &self.key(path), &[], body),
+            "upload",
+        )
+        .await
+        .map(|_| ())
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), String> {
+        self.send(
+            self.request(Method::DELETE, &self.key(path), &[], b""),
+            "delete",
+        )
+        .await
+        .map(|_| ())
+    }
+}
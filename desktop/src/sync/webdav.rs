@@ -0,0 +1,243 @@
+//! WebDAV storage for workspace sync (Nextcloud, ownCloud, Apache/nginx `mod_dav`).
+
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use std::collections::HashSet;
+use tokio::sync::Mutex;
+
+use super::RemoteFile;
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/><d:getetag/><d:getlastmodified/></d:prop></d:propfind>"#;
+
+pub struct Dav {
+    client: Client,
+    /// The collection the workspace is synced to, without a trailing slash.
+    base: String,
+    /// The decoded path of `base`, to turn listed hrefs back into workspace paths.
+    base_path: String,
+    username: String,
+    password: String,
+    /// Collections known to exist, so uploads create each parent once.
+    collections: Mutex<HashSet<String>>,
+}
+
+fn encode_path(path: &str) -> String {
+    let mut out = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+fn decode_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+impl Dav {
+    pub fn new(url: &str, username: &str, password: &str) -> Result<Self, String> {
+        let base = url.trim().trim_end_matches('/').to_string();
+        let parsed = reqwest::Url::parse(&base).map_err(|e| format!("invalid WebDAV URL: {e}"))?;
+        Ok(Self {
+            client: Client::new(),
+            base_path: decode_path(parsed.path().trim_end_matches('/')),
+            base,
+            username: username.to_string(),
+            password: password.to_string(),
+            collections: Mutex::new(HashSet::new()),
+        })
+    }
+
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = if path.is_empty() {
+            format!("{}/", self.base)
+        } else {
+            format!("{}/{}", self.base, encode_path(path))
+        };
+        let request = self.client.request(method, url);
+        if self.username.is_empty() {
+            request
+        } else {
+            request.basic_auth(&self.username, Some(&self.password))
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder, op: &str) -> Result<reqwest::Response, String> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("WebDAV {op} failed: {e}"))?;
+        if response.status().is_success() {
+            Ok(response)
+        } else {
+            Err(format!("WebDAV {op} failed: {}", response.status()))
+        }
+    }
+
+    /// Every file below the base collection, walked one level at a time (many servers refuse
+    /// `Depth: infinity`).
+    pub async fn list(&self) -> Result<Vec<RemoteFile>, String> {
+        let mut files = Vec::new();
+        let mut pending = vec![String::new()];
+        let mut collections = HashSet::new();
+        while let Some(dir) = pending.pop() {
+            let request = self
+                .request(Method::from_bytes(b"PROPFIND").expect("valid method"), &dir)
+                .header("depth", "1")
+                .header("content-type", "application/xml; charset=utf-8")
+                .body(PROPFIND_BODY);
+            let response = match request.send().await {
+                Ok(response) if response.status() == StatusCode::NOT_FOUND && dir.is_empty() => {
+                    // Nothing synced yet.
+                    return Ok(Vec::new());
+                }
+                Ok(response) if response.status().is_success() => response,
+                Ok(response) => return Err(format!("WebDAV list failed: {}", response.status())),
+                Err(e) => return Err(format!("WebDAV list failed: {e}")),
+            };
+            collections.insert(dir.clone());
+            let body = response
+                .text()
+                .await
+                .map_err(|e| format!("WebDAV list failed: {e}"))?;
+            let doc = roxmltree::Document::parse(&body)
+                .map_err(|e| format!("WebDAV list parse failed: {e}"))?;
+            for entry in doc.descendants().filter(|n| n.has_tag_name("response")) {
+                let text = |name: &str| {
+                    entry
+                        .descendants()
+                        .find(|n| n.has_tag_name(name))
+                        .and_then(|n| n.text())
+                        .unwrap_or_default()
+                        .trim()
+                        .to_string()
+                };
+                let Some(path) = self.relative(&text("href")) else {
+                    continue;
+                };
+                if path == dir || !super::is_safe_path(&path) {
+                    continue;
+                }
+                let is_collection = entry.descendants().any(|n| n.has_tag_name("collection"));
+                if is_collection {
+                    pending.push(path);
+                    continue;
+                }
+                let modified = text("getlastmodified");
+                let modified_ms = chrono::DateTime::parse_from_rfc2822(&modified)
+                    .map(|d| d.timestamp_millis().max(0) as u64)
+                    .unwrap_or(0);
+                let etag = text("getetag");
+                files.push(RemoteFile {
+                    path,
+                    tag: if etag.is_empty() {
+                        modified
+                    } else {
+                        etag.trim_start_matches("W/").trim_matches('"').to_string()
+                    },
+                    modified_ms,
+                });
+            }
+        }
+        *self.collections.lock().await = collections;
+        Ok(files)
+    }
+
+    /// A listed href (absolute URL or path) as a path below the base collection.
+    fn relative(&self, href: &str) -> Option<String> {
+        let path = match reqwest::Url::parse(href) {
+            Ok(url) => url.path().to_string(),
+            Err(_) => href.to_string(),
+        };
+        let path = decode_path(&path);
+        let rel = path.strip_prefix(self.base_path.as_str())?;
+        Some(rel.trim_matches('/').to_string())
+    }
+
+    pub async fn get(&self, path: &str) -> Result<Vec<u8>, String> {
+        let response = self
+            .send(self.request(Method::GET, path), "download")
+            .await?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("WebDAV download failed: {e}"))?;
+        Ok(bytes.to_vec())
+    }
+
+    pub async fn put(&self, path: &str, body: &[u8]) -> Result<(), String> {
+        self.create_parents(path).await?;
+        self.send(
+            self.request(Method::PUT, path).body(body.to_vec()),
+            "upload",
+        )
+        .await
+        .map(|_| ())
+    }
+
+    async fn create_parents(&self, path: &str) -> Result<(), String> {
+        let mut collections = self.collections.lock().await;
+        let mut dir = String::new();
+        let parents: Vec<&str> = path.split('/').collect();
+        // The base collection itself first, then each parent of the file.
+        for part in std::iter::once("").chain(parents[..parents.len() - 1].iter().copied()) {
+            if !part.is_empty() {
+                if !dir.is_empty() {
+                    dir.push('/');
+                }
+                dir.push_str(part);
+            }
+            if collections.contains(&dir) {
+                continue;
+            }
+            let response = self
+                .request(Method::from_bytes(b"MKCOL").expect("valid method"), &dir)
+                .send()
+                .await
+                .map_err(|e| format!("WebDAV mkcol failed: {e}"))?;
+            // 405: the collection already exists.
+            if !response.status().is_success()
+                && response.status() != StatusCode::METHOD_NOT_ALLOWED
+            {
+                return Err(format!("WebDAV mkcol failed: {}", response.status()));
+            }
+            collections.insert(dir.clone());
+        }
+        Ok(())
+    }
+
+    pub async fn delete(&self, path: &str) -> Result<(), String> {
+        match self.request(Method::DELETE, path).send().await {
+            Ok(response)
+                if response.status().is_success() || response.status() == StatusCode::NOT_FOUND =>
+            {
+                Ok(())
+            }
+            Ok(response) => Err(format!("WebDAV delete failed: {}", response.status())),
+            Err(e) => Err(format!("WebDAV delete failed: {e}")),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+//! The workspace's request templates (see `litefetch_core::templates`), kept in
+//! `templates.json` at the workspace root so they travel with it, alongside the built-in
+//! ones. Templates can be exported to a file and imported into another workspace.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use litefetch_core::templates::{self, Template, BUILTIN_PREFIX};
+
+const TEMPLATES_FILE: &str = "templates.json";
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct TemplateFile {
+    templates: Vec<Template>,
+}
+
+#[derive(Serialize)]
+pub struct TemplateImport {
+    pub added: usize,
+    /// Templates with the id of one already in the workspace, which they replaced.
+    pub replaced: usize,
+}
+
+fn path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::load_workspace_path(app)?.join(TEMPLATES_FILE))
+}
+
+fn load(app: &tauri::AppHandle) -> Result<Vec<Template>, String> {
+    let path = path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("templates read failed: {e}"))?;
+    serde_json::from_str::<TemplateFile>(&data)
+        .map(|file| file.templates)
+        .map_err(|e| format!("templates parse failed: {e}"))
+}
+
+fn save(app: &tauri::AppHandle, templates: Vec<Template>) -> Result<(), String> {
+    crate::lock::ensure_writable(app)?;
+    let payload = serde_json::to_string_pretty(&TemplateFile { templates })
+        .map_err(|e| format!("templates serialize failed: {e}"))?;
+    fs::write(path(app)?, payload).map_err(|e| format!("templates persist failed: {e}"))
+}
+
+fn validate(template: &Template) -> Result<(), String> {
+    if template.name.trim().is_empty() {
+        return Err("a template needs a name".to_string());
+    }
+    if !template.request.is_object() {
+        return Err(format!(
+            "template {}: request must be an object",
+            template.name
+        ));
+    }
+    let undeclared = templates::undeclared(template);
+    if !undeclared.is_empty() {
+        return Err(format!(
+            "template {} uses undeclared parameters: {}",
+            template.name,
+            undeclared.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// The built-in templates, then the workspace's.
+#[tauri::command]
+pub async fn list_request_templates(app: tauri::AppHandle) -> Result<Vec<Template>, String> {
+    let mut all = templates::builtins();
+    all.extend(load(&app)?);
+    Ok(all)
+}
+
+/// Adds a template, or replaces the one with its id; a new one gets an id when it has none.
+#[tauri::command]
+pub async fn save_request_template(
+    app: tauri::AppHandle,
+    template: Template,
+) -> Result<Template, String> {
+    let mut template = template;
+    if template.id.starts_with(BUILTIN_PREFIX) {
+        return Err("built-in templates can't be changed; save a copy instead".to_string());
+    }
+    if template.id.trim().is_empty() {
+        template.id = uuid::Uuid::new_v4().to_string();
+    }
+    if let Some(fields) = template.request.as_object_mut() {
+        fields.remove("id");
+    }
+    validate(&template)?;
+    let mut all = load(&app)?;
+    match all.iter_mut().find(|t| t.id == template.id) {
+        Some(existing) => *existing = template.clone(),
+        None => all.push(template.clone()),
+    }
+    save(&app, all)?;
+    Ok(template)
+}
+
+#[tauri::command]
+pub async fn delete_request_template(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let mut all = load(&app)?;
+    let before = all.len();
+    all.retain(|t| t.id != id);
+    if all.len() == before {
+        return Err(format!("unknown template: {id}"));
+    }
+    save(&app, all)
+}
+
+/// A new request from a template, ready for the UI to add to a collection.
+#[tauri::command]
+pub async fn create_from_template(
+    app: tauri::AppHandle,
+    template_id: String,
+    params: Option<BTreeMap<String, String>>,
+) -> Result<serde_json::Value, String> {
+    let template = list_request_templates(app)
+        .await?
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("unknown template: {template_id}"))?;
+    templates::instantiate(
+        &template,
+        &params.unwrap_or_default(),
+        uuid::Uuid::new_v4().to_string(),
+    )
+}
+
+/// Writes the workspace's templates (only `ids`, when given) to `path` and returns the path.
+#[tauri::command]
+pub async fn export_request_templates(
+    app: tauri::AppHandle,
+    ids: Option<Vec<String>>,
+    path: String,
+) -> Result<String, String> {
+    let templates: Vec<Template> = load(&app)?
+        .into_iter()
+        .filter(|t| ids.as_ref().is_none_or(|ids| ids.contains(&t.id)))
+        .collect();
+    let payload = serde_json::to_string_pretty(&TemplateFile { templates })
+        .map_err(|e| format!("templates serialize failed: {e}"))?;
+    let target = crate::normalize_path(&path);
+    fs::write(&target, payload).map_err(|e| format!("templates export failed: {e}"))?;
+    Ok(target.to_string_lossy().to_string())
+}
+
+/// Adds the templates of an exported file to the workspace; all of them are checked before
+/// any is added.
+#[tauri::command]
+pub async fn import_request_templates(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<TemplateImport, String> {
+    let data = fs::read_to_string(crate::normalize_path(&path))
+        .map_err(|e| format!("templates read failed: {e}"))?;
+    let incoming = serde_json::from_str::<TemplateFile>(&data)
+        .map_err(|e| format!("templates parse failed: {e}"))?
+        .templates;
+    let mut all = load(&app)?;
+    let mut report = TemplateImport {
+        added: 0,
+        replaced: 0,
+    };
+    for mut template in incoming {
+        if template.id.trim().is_empty() || template.id.starts_with(BUILTIN_PREFIX) {
+            template.id = uuid::Uuid::new_v4().to_string();
+        }
+        validate(&template)?;
+        match all.iter_mut().find(|t| t.id == template.id) {
+            Some(existing) => {
+                *existing = template;
+                report.replaced += 1;
+            }
+            None => {
+                all.push(template);
+                report.added += 1;
+            }
+        }
+    }
+    save(&app, all)?;
+    Ok(report)
+}
@@ -0,0 +1,115 @@
+//! W3C trace context for sends: with `trace_context` on in the request defaults (see
+//! `defaults`), each send gets a fresh `traceparent` and the configured `tracestate`. The
+//! trace ids a send carried or got back (`traceparent`, `traceresponse`, B3, Jaeger, AWS
+//! X-Ray, Google Cloud and Datadog headers) are recorded with its history entry, so
+//! `find_by_trace_id` goes from a trace in a tracing UI to the calls that took part in it.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::history::HistoryEntry;
+
+/// Trace context headers added to every send.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct TraceContext {
+    pub enabled: bool,
+    /// Sets the sampled flag, asking the servers to record the trace.
+    pub sampled: bool,
+    /// Sent as `tracestate` when set, e.g. `vendor=value`.
+    pub tracestate: Option<String>,
+}
+
+impl Default for TraceContext {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            sampled: true,
+            tracestate: None,
+        }
+    }
+}
+
+impl TraceContext {
+    /// A fresh `traceparent` value: version, trace id, parent span id and flags.
+    pub fn traceparent(sampled: bool) -> String {
+        let trace = uuid::Uuid::new_v4().simple().to_string();
+        let span = &uuid::Uuid::new_v4().simple().to_string()[..16];
+        format!("00-{trace}-{span}-{}", if sampled { "01" } else { "00" })
+    }
+}
+
+fn header<'a>(headers: Option<&'a Value>, name: &str) -> Option<&'a str> {
+    headers?
+        .as_object()?
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+/// The trace id in one header's value, in the form its tracing system shows it.
+fn trace_id(name: &str, value: &str) -> Option<String> {
+    let value = value.trim();
+    let id = match name {
+        // `00-<trace id>-<span id>-<flags>`; the single `b3` header leads with the trace id.
+        "traceparent" | "traceresponse" => value.split('-').nth(1)?,
+        "b3" => value.split('-').next()?,
+        "uber-trace-id" => value.split(':').next()?,
+        "x-amzn-trace-id" => value
+            .split(';')
+            .find_map(|part| part.trim().strip_prefix("Root="))?,
+        "x-cloud-trace-context" => value.split('/').next()?,
+        _ => value,
+    };
+    let id = id.trim().to_ascii_lowercase();
+    let zero = id.chars().all(|c| c == '0' || c == '-');
+    (!id.is_empty() && !zero && id.len() <= 128).then_some(id)
+}
+
+/// Headers that carry a trace id, on requests and responses.
+const TRACE_HEADERS: &[&str] = &[
+    "traceparent",
+    "traceresponse",
+    "b3",
+    "x-b3-traceid",
+    "uber-trace-id",
+    "x-amzn-trace-id",
+    "x-cloud-trace-context",
+    "x-datadog-trace-id",
+    "x-trace-id",
+];
+
+/// The trace ids in `sent`'s and `result`'s headers, without duplicates.
+pub(crate) fn trace_ids(sent: &Value, result: &Value) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for headers in [sent.get("headers"), result.get("headers")] {
+        for name in TRACE_HEADERS {
+            if let Some(id) = header(headers, name).and_then(|value| trace_id(name, value)) {
+                if !ids.contains(&id) {
+                    ids.push(id);
+                }
+            }
+        }
+    }
+    ids
+}
+
+/// History entries whose sends carried or got back `trace_id`, newest first.
+#[tauri::command]
+pub async fn find_by_trace_id(
+    app: tauri::AppHandle,
+    trace_id: String,
+    all_workspaces: Option<bool>,
+) -> Result<Vec<HistoryEntry>, String> {
+    let trace_id = trace_id.trim().to_ascii_lowercase();
+    if trace_id.is_empty() {
+        return Err("a trace id is required".to_string());
+    }
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::history::by_trace_id(&app, &trace_id, all_workspaces.unwrap_or(false))
+    })
+    .await
+    .map_err(|e| format!("trace search failed: {e}"))?
+}
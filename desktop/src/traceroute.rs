@@ -0,0 +1,384 @@
+//! Traceroute to an API host, for routes that are slow or blocked on the way to a staging
+//! environment. Probes go out with a rising TTL; ICMP echo probes need a raw socket (root,
+//! `CAP_NET_RAW` or an administrator on Windows) and name each router that answers, while the
+//! TCP fallback connects to the API port and can only tell at which hop the host answers.
+//! Hops arrive as `traceroute://hop` events while the trace runs.
+
+use serde::{Deserialize, Serialize};
+use socket2::{Domain, Protocol, SockAddr, Socket, Type};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::Mutex;
+use tauri::{Emitter, State};
+
+use litefetch_core::dynamic::random_below;
+
+const MAX_HOPS: u8 = 64;
+const MAX_PROBES: u8 = 5;
+/// Echo payload, so the probe has a body routers quote back.
+const PAYLOAD: &[u8] = b"litefetch-traceroute-probe";
+
+pub struct TracerouteState {
+    /// Cancel flags of the traces in progress.
+    runs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl TracerouteState {
+    pub fn new() -> Self {
+        Self {
+            runs: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TraceMethod {
+    /// ICMP when a raw socket can be opened, TCP otherwise.
+    #[default]
+    Auto,
+    Icmp,
+    Tcp,
+}
+
+#[derive(Deserialize)]
+#[serde(default)]
+pub struct TraceOptions {
+    /// The port TCP probes connect to.
+    pub port: u16,
+    pub max_hops: u8,
+    /// Probes per hop.
+    pub probes: u8,
+    /// How long each probe waits for an answer.
+    pub timeout_ms: u64,
+    pub method: TraceMethod,
+}
+
+impl Default for TraceOptions {
+    fn default() -> Self {
+        Self {
+            port: 443,
+            max_hops: 30,
+            probes: 3,
+            timeout_ms: 2000,
+            method: TraceMethod::Auto,
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct TraceHop {
+    pub trace_id: String,
+    pub hop: u8,
+    /// The router or host that answered; `None` when nothing did, or for TCP hops short of
+    /// the host.
+    pub address: Option<String>,
+    /// One entry per probe; `None` for a probe that got no answer.
+    pub rtts_ms: Vec<Option<f64>>,
+    /// The target host answered at this hop.
+    pub reached: bool,
+    pub timestamp_ms: u64,
+}
+
+#[derive(Serialize)]
+pub struct TraceResult {
+    pub trace_id: String,
+    pub host: String,
+    pub address: String,
+    /// `icmp` or `tcp`, as actually used.
+    pub method: TraceMethod,
+    pub hops: Vec<TraceHop>,
+    pub reached: bool,
+    pub cancelled: bool,
+}
+
+#[derive(Serialize, Clone)]
+struct StartedEvent {
+    trace_id: String,
+    host: String,
+    address: String,
+    method: TraceMethod,
+    timestamp_ms: u64,
+}
+
+/// One probe's outcome.
+struct Reply {
+    from: Option<IpAddr>,
+    rtt_ms: Option<f64>,
+    reached: bool,
+}
+
+const NO_REPLY: Reply = Reply {
+    from: None,
+    rtt_ms: None,
+    reached: false,
+};
+
+fn checksum(data: &[u8]) -> u16 {
+    let mut sum = data
+        .chunks(2)
+        .map(|pair| u32::from(u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)])))
+        .sum::<u32>();
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    !(sum as u16)
+}
+
+fn echo_request(ident: u16, seq: u16) -> Vec<u8> {
+    let mut packet = vec![8, 0, 0, 0];
+    packet.extend_from_slice(&ident.to_be_bytes());
+    packet.extend_from_slice(&seq.to_be_bytes());
+    packet.extend_from_slice(PAYLOAD);
+    let sum = checksum(&packet);
+    packet[2..4].copy_from_slice(&sum.to_be_bytes());
+    packet
+}
+
+/// The ICMP type of a packet (IP header included, as raw sockets deliver it) that answers
+/// our probe: an echo reply, time exceeded or destination unreachable.
+fn answers(packet: &[u8], ident: u16, seq: u16) -> Option<u8> {
+    let ihl = usize::from(packet.first()? & 0x0F) * 4;
+    let icmp = packet.get(ihl..)?;
+    let ours = |header: &[u8]| {
+        header.get(4..6) == Some(&ident.to_be_bytes()[..])
+            && header.get(6..8) == Some(&seq.to_be_bytes()[..])
+    };
+    match *icmp.first()? {
+        0 => ours(icmp).then_some(0),
+        // These quote the IP and ICMP headers of the probe they answer.
+        kind @ (3 | 11) => {
+            let inner = icmp.get(8..)?;
+            let inner_ihl = usize::from(inner.first()? & 0x0F) * 4;
+            ours(inner.get(inner_ihl..)?).then_some(kind)
+        }
+        _ => None,
+    }
+}
+
+/// A raw ICMP socket, driven through `UdpSocket` for its plain byte-buffer `send_to` and
+/// `recv_from`.
+fn icmp_socket() -> std::io::Result<UdpSocket> {
+    Socket::new(Domain::IPV4, Type::RAW, Some(Protocol::ICMPV4)).map(UdpSocket::from)
+}
+
+fn icmp_probe(
+    socket: &UdpSocket,
+    target: SocketAddr,
+    ttl: u8,
+    ident: u16,
+    seq: u16,
+    timeout: Duration,
+) -> Result<Reply, String> {
+    socket
+        .set_ttl(u32::from(ttl))
+        .map_err(|e| format!("traceroute TTL failed: {e}"))?;
+    let started = Instant::now();
+    socket
+        .send_to(&echo_request(ident, seq), target)
+        .map_err(|e| format!("traceroute probe failed: {e}"))?;
+    let mut buffer = [0u8; 1500];
+    loop {
+        let Some(left) = timeout
+            .checked_sub(started.elapsed())
+            .filter(|d| !d.is_zero())
+        else {
+            return Ok(NO_REPLY);
+        };
+        socket
+            .set_read_timeout(Some(left))
+            .map_err(|e| format!("traceroute socket failed: {e}"))?;
+        let (n, from) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                return Ok(NO_REPLY)
+            }
+            Err(e) => return Err(format!("traceroute receive failed: {e}")),
+        };
+        if let Some(kind) = answers(&buffer[..n], ident, seq) {
+            return Ok(Reply {
+                from: Some(from.ip()),
+                rtt_ms: Some(started.elapsed().as_secs_f64() * 1000.0),
+                // An unreachable from a router on the way doesn't count as arriving.
+                reached: kind == 0 || (kind == 3 && from.ip() == target.ip()),
+            });
+        }
+    }
+}
+
+/// A TCP connection attempt that dies after `ttl` hops. Only the host itself can answer,
+/// by accepting or refusing the connection.
+fn tcp_probe(target: SocketAddr, ttl: u8, timeout: Duration) -> Result<Reply, String> {
+    let socket = Socket::new(
+        Domain::for_address(target),
+        Type::STREAM,
+        Some(Protocol::TCP),
+    )
+    .map_err(|e| format!("traceroute socket failed: {e}"))?;
+    match target {
+        SocketAddr::V4(_) => socket.set_ttl_v4(u32::from(ttl)),
+        SocketAddr::V6(_) => socket.set_unicast_hops_v6(u32::from(ttl)),
+    }
+    .map_err(|e| format!("traceroute TTL failed: {e}"))?;
+    let started = Instant::now();
+    match socket.connect_timeout(&SockAddr::from(target), timeout) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::ConnectionRefused => {}
+        Err(_) => return Ok(NO_REPLY),
+    }
+    Ok(Reply {
+        from: Some(target.ip()),
+        rtt_ms: Some(started.elapsed().as_secs_f64() * 1000.0),
+        reached: true,
+    })
+}
+
+/// Probes one hop `probes` times, off the async runtime.
+async fn hop(
+    socket: Option<Arc<UdpSocket>>,
+    target: SocketAddr,
+    ttl: u8,
+    ident: u16,
+    options: (u8, Duration),
+) -> Result<Vec<Reply>, String> {
+    let (probes, timeout) = options;
+    tauri::async_runtime::spawn_blocking(move || {
+        (0..probes)
+            .map(|n| {
+                let seq = u16::from(ttl) << 8 | u16::from(n);
+                match &socket {
+                    Some(socket) => icmp_probe(socket, target, ttl, ident, seq, timeout),
+                    None => tcp_probe(target, ttl, timeout),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()
+    })
+    .await
+    .map_err(|e| format!("traceroute task failed: {e}"))?
+}
+
+/// Traces the route to `host` (a name, address or URL) and returns every hop once the host
+/// answers or `max_hops` is reached. The trace id arrives first in a `traceroute://started`
+/// event, for `cancel_traceroute`.
+#[tauri::command]
+pub async fn traceroute(
+    app: tauri::AppHandle,
+    state: State<'_, TracerouteState>,
+    host: String,
+    options: Option<TraceOptions>,
+) -> Result<TraceResult, String> {
+    let options = options.unwrap_or_default();
+    let host = crate::dns::host_of(&host);
+    if host.is_empty() {
+        return Err("a host is required".to_string());
+    }
+    let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), options.port))
+        .await
+        .map_err(|e| format!("resolve failed: {e}"))?
+        .collect();
+    // ICMP probes are IPv4 only, so prefer an IPv4 address unless TCP was asked for.
+    let target = addresses
+        .iter()
+        .find(|a| a.is_ipv4() || options.method == TraceMethod::Tcp)
+        .or(addresses.first())
+        .copied()
+        .ok_or_else(|| format!("{host} has no addresses"))?;
+
+    let socket = match (options.method, target) {
+        (TraceMethod::Tcp, _) => None,
+        (TraceMethod::Icmp, SocketAddr::V6(_)) => {
+            return Err("ICMP traceroute supports IPv4 only; use the tcp method".to_string())
+        }
+        (TraceMethod::Icmp, _) => Some(icmp_socket().map_err(|e| {
+            format!("ICMP traceroute needs raw socket access (root or administrator): {e}")
+        })?),
+        (TraceMethod::Auto, SocketAddr::V6(_)) => None,
+        (TraceMethod::Auto, _) => icmp_socket().ok(),
+    }
+    .map(Arc::new);
+    let method = match socket {
+        Some(_) => TraceMethod::Icmp,
+        None => TraceMethod::Tcp,
+    };
+
+    let trace_id = uuid::Uuid::new_v4().to_string();
+    let cancel = Arc::new(AtomicBool::new(false));
+    state
+        .runs
+        .lock()
+        .await
+        .insert(trace_id.clone(), cancel.clone());
+    let _ = app.emit(
+        "traceroute://started",
+        StartedEvent {
+            trace_id: trace_id.clone(),
+            host: host.clone(),
+            address: target.ip().to_string(),
+            method,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+
+    let ident = random_below(1 << 16) as u16;
+    let probes = options.probes.clamp(1, MAX_PROBES);
+    let timeout = Duration::from_millis(options.timeout_ms.clamp(100, 10_000));
+    let mut result = TraceResult {
+        trace_id: trace_id.clone(),
+        host,
+        address: target.ip().to_string(),
+        method,
+        hops: Vec::new(),
+        reached: false,
+        cancelled: false,
+    };
+    let mut failure = None;
+    for ttl in 1..=options.max_hops.clamp(1, MAX_HOPS) {
+        if cancel.load(Ordering::Relaxed) {
+            result.cancelled = true;
+            break;
+        }
+        let replies = match hop(socket.clone(), target, ttl, ident, (probes, timeout)).await {
+            Ok(replies) => replies,
+            Err(e) => {
+                failure = Some(e);
+                break;
+            }
+        };
+        let step = TraceHop {
+            trace_id: trace_id.clone(),
+            hop: ttl,
+            address: replies.iter().find_map(|r| r.from).map(|ip| ip.to_string()),
+            rtts_ms: replies.iter().map(|r| r.rtt_ms).collect(),
+            reached: replies.iter().any(|r| r.reached),
+            timestamp_ms: crate::now_ms(),
+        };
+        let _ = app.emit("traceroute://hop", step.clone());
+        result.reached = step.reached;
+        result.hops.push(step);
+        if result.reached {
+            break;
+        }
+    }
+    state.runs.lock().await.remove(&trace_id);
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(result),
+    }
+}
+
+#[tauri::command]
+pub async fn cancel_traceroute(
+    state: State<'_, TracerouteState>,
+    trace_id: String,
+) -> Result<(), String> {
+    let runs = state.runs.lock().await;
+    let cancel = runs
+        .get(&trace_id)
+        .ok_or_else(|| format!("unknown traceroute: {trace_id}"))?;
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
@@ -0,0 +1,310 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Stdio;
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, Manager, State};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+
+use crate::webhook::WebhookState;
+
+pub struct TunnelState {
+    tunnels: Mutex<HashMap<String, Tunnel>>,
+}
+
+impl TunnelState {
+    pub fn new() -> Self {
+        Self {
+            tunnels: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+struct Tunnel {
+    info: TunnelInfo,
+    task: JoinHandle<()>,
+}
+
+/// External tunnel clients LiteFetch knows how to drive. `Custom` runs any
+/// command whose arguments may reference `{port}`; the first https URL it prints
+/// is taken as the public address.
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TunnelProvider {
+    Cloudflared,
+    Ngrok,
+    Localtunnel,
+    Custom,
+}
+
+#[derive(Deserialize)]
+pub struct TunnelRequest {
+    #[serde(default)]
+    listener_id: Option<String>,
+    #[serde(default)]
+    port: Option<u16>,
+    provider: TunnelProvider,
+    #[serde(default)]
+    binary_path: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct TunnelInfo {
+    tunnel_id: String,
+    listener_id: Option<String>,
+    port: u16,
+    provider: TunnelProvider,
+    public_url: Option<String>,
+    status: String,
+    started_at_ms: u64,
+}
+
+#[derive(Clone, Serialize)]
+struct TunnelStatusEvent {
+    tunnel_id: String,
+    status: String,
+    public_url: Option<String>,
+    detail: Option<String>,
+    timestamp_ms: u64,
+}
+
+fn provider_command(request: &TunnelRequest, port: u16) -> Result<(String, Vec<String>), String> {
+    let local = format!("http://127.0.0.1:{port}");
+    let (default_bin, args): (&str, Vec<String>) = match request.provider {
+        TunnelProvider::Cloudflared => (
+            "cloudflared",
+            vec![
+                "tunnel".into(),
+                "--no-autoupdate".into(),
+                "--url".into(),
+                local,
+            ],
+        ),
+        TunnelProvider::Ngrok => (
+            "ngrok",
+            vec![
+                "http".into(),
+                port.to_string(),
+                "--log".into(),
+                "stdout".into(),
+            ],
+        ),
+        TunnelProvider::Localtunnel => ("lt", vec!["--port".into(), port.to_string()]),
+        TunnelProvider::Custom => {
+            let bin = request
+                .binary_path
+                .clone()
+                .ok_or_else(|| "custom tunnel requires binary_path".to_string())?;
+            let args = request
+                .args
+                .iter()
+                .map(|a| a.replace("{port}", &port.to_string()))
+                .collect();
+            return Ok((bin, args));
+        }
+    };
+    let bin = request
+        .binary_path
+        .clone()
+        .unwrap_or_else(|| default_bin.to_string());
+    let mut args = args;
+    args.extend(
+        request
+            .args
+            .iter()
+            .map(|a| a.replace("{port}", &port.to_string())),
+    );
+    Ok((bin, args))
+}
+
+/// Pulls the public URL out of a provider log line, skipping local and dashboard addresses.
+fn public_url_in(line: &str) -> Option<String> {
+    line.split(|c: char| c.is_whitespace() || c == '"' || c == '|' || c == '\'')
+        .map(|token| token.strip_prefix("url=").unwrap_or(token))
+        .find(|token| {
+            token.starts_with("https://")
+                && !token.contains("127.0.0.1")
+                && !token.contains("localhost")
+                && !token.contains("developers.cloudflare.com")
+                && !token.contains("ngrok.com/docs")
+        })
+        .map(|token| token.trim_end_matches(['.', ',', ';']).to_string())
+}
+
+fn forward_lines<R: AsyncRead + Unpin + Send + 'static>(
+    reader: R,
+    tx: mpsc::UnboundedSender<String>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(reader).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+fn emit_status(
+    app: &tauri::AppHandle,
+    tunnel_id: &str,
+    status: &str,
+    public_url: Option<String>,
+    detail: Option<String>,
+) {
+    let _ = app.emit(
+        "tunnel://status",
+        TunnelStatusEvent {
+            tunnel_id: tunnel_id.to_string(),
+            status: status.to_string(),
+            public_url,
+            detail,
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+}
+
+async fn supervise(app: tauri::AppHandle, tunnel_id: String, mut child: Child) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    if let Some(stdout) = child.stdout.take() {
+        forward_lines(stdout, tx.clone());
+    }
+    if let Some(stderr) = child.stderr.take() {
+        forward_lines(stderr, tx);
+    }
+
+    let mut announced = false;
+    let mut output_open = true;
+    let mut last_line = None;
+    loop {
+        tokio::select! {
+            line = rx.recv(), if output_open => {
+                let Some(line) = line else {
+                    output_open = false;
+                    continue;
+                };
+                if !announced {
+                    if let Some(url) = public_url_in(&line) {
+                        announced = true;
+                        let state = app.state::<TunnelState>();
+                        if let Some(tunnel) = state.tunnels.lock().await.get_mut(&tunnel_id) {
+                            tunnel.info.public_url = Some(url.clone());
+                            tunnel.info.status = "online".to_string();
+                        }
+                        emit_status(&app, &tunnel_id, "online", Some(url), None);
+                    }
+                }
+                last_line = Some(line);
+            }
+            exit = child.wait() => {
+                let detail = match exit {
+                    Ok(status) => Some(match last_line {
+                        Some(line) => format!("{status}: {line}"),
+                        None => status.to_string(),
+                    }),
+                    Err(e) => Some(e.to_string()),
+                };
+                emit_status(&app, &tunnel_id, "exited", None, detail);
+                break;
+            }
+        }
+    }
+    app.state::<TunnelState>()
+        .tunnels
+        .lock()
+        .await
+        .remove(&tunnel_id);
+}
+
+#[tauri::command]
+pub async fn start_tunnel(
+    app: tauri::AppHandle,
+    state: State<'_, TunnelState>,
+    webhooks: State<'_, WebhookState>,
+    request: TunnelRequest,
+) -> Result<TunnelInfo, String> {
+    let port = match (&request.listener_id, request.port) {
+        (Some(id), _) => webhooks
+            .port_of(id)
+            .await
+            .ok_or_else(|| format!("unknown webhook listener: {id}"))?,
+        (None, Some(port)) => port,
+        (None, None) => return Err("tunnel needs a listener_id or port".to_string()),
+    };
+    let (bin, args) = provider_command(&request, port)?;
+    // A bare name (the default `cloudflared`, `ngrok` or `lt`) is looked up on PATH.
+    let program = match bin.contains(['/', '\\']) {
+        true => crate::normalize_path(&bin).into_os_string(),
+        false => bin.clone().into(),
+    };
+    let child = Command::new(program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("failed to start {bin}: {e}"))?;
+
+    let tunnel_id = uuid::Uuid::new_v4().to_string();
+    let info = TunnelInfo {
+        tunnel_id: tunnel_id.clone(),
+        listener_id: request.listener_id.clone(),
+        port,
+        provider: request.provider,
+        public_url: None,
+        status: "starting".to_string(),
+        started_at_ms: crate::now_ms(),
+    };
+    let mut tunnels = state.tunnels.lock().await;
+    let task = tauri::async_runtime::spawn(supervise(app.clone(), tunnel_id.clone(), child));
+    tunnels.insert(
+        tunnel_id.clone(),
+        Tunnel {
+            info: info.clone(),
+            task,
+        },
+    );
+    emit_status(
+        &app,
+        &tunnel_id,
+        "starting",
+        None,
+        Some(format!("{bin} {}", args.join(" "))),
+    );
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_tunnel(
+    app: tauri::AppHandle,
+    state: State<'_, TunnelState>,
+    tunnel_id: String,
+) -> Result<(), String> {
+    let tunnel = state
+        .tunnels
+        .lock()
+        .await
+        .remove(&tunnel_id)
+        .ok_or_else(|| format!("unknown tunnel: {tunnel_id}"))?;
+    // Aborting drops the child handle, and kill_on_drop terminates the provider process.
+    tunnel.task.abort();
+    emit_status(&app, &tunnel_id, "stopped", None, None);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_tunnels(state: State<'_, TunnelState>) -> Result<Vec<TunnelInfo>, String> {
+    let mut tunnels: Vec<_> = state
+        .tunnels
+        .lock()
+        .await
+        .values()
+        .map(|t| t.info.clone())
+        .collect();
+    tunnels.sort_by_key(|t| t.started_at_ms);
+    Ok(tunnels)
+}
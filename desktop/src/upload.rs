@@ -0,0 +1,364 @@
+//! Bodies the shell sends itself instead of the backend, streamed so that files of any size
+//! go out without being read into memory or the editor:
+//! - `body_mode: "file"` sends the file at `binary.file_path` as the body. `Content-Type` is
+//!   the request's own header, else `binary.content_type`, else what the first bytes or the
+//!   extension say.
+//! - `body_mode: "multipart"` frames the `form_body` rows as `multipart/form-data`, with
+//!   per-part content types, headers and filenames (see `litefetch_core::multipart`).
+//!
+//! `Content-Length` is always known up front, and `upload://progress` events report the
+//! bytes sent. Cookies and the signing auth types stay with the backend, so a request that
+//! needs them can't use these modes; the send is in the shell's history but not the
+//! collection's.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures_util::stream;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::Emitter;
+use tokio::io::AsyncReadExt;
+
+use crate::importers::{array_of, str_of};
+use litefetch_core::binary;
+use litefetch_core::multipart::{self, Segment};
+
+pub const FILE_MODE: &str = "file";
+pub const MULTIPART_MODE: &str = "multipart";
+const CHUNK_SIZE: usize = 256 * 1024;
+/// Least time between two progress events of one upload.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Serialize, Clone)]
+struct ProgressEvent {
+    request_id: String,
+    sent_bytes: u64,
+    total_bytes: u64,
+    timestamp_ms: u64,
+}
+
+/// Whether the shell sends `request` itself (see the module docs).
+pub fn is_streamed(request: &Value) -> bool {
+    matches!(str_of(request, "body_mode"), FILE_MODE | MULTIPART_MODE)
+}
+
+pub(crate) fn header<'a>(headers: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .and_then(|(_, value)| value.as_str())
+}
+
+async fn content_type(path: &Path, declared: &str) -> String {
+    if !declared.trim().is_empty() {
+        return declared.trim().to_string();
+    }
+    let mut head = vec![0; binary::SNIFF_LEN];
+    let read = match tokio::fs::File::open(path).await {
+        Ok(mut file) => file.read(&mut head).await.unwrap_or(0),
+        Err(_) => 0,
+    };
+    head.truncate(read);
+    let by_extension = path
+        .extension()
+        .and_then(|ext| binary::mime_for_extension(&ext.to_string_lossy()));
+    match (
+        binary::detect(&head, read < binary::SNIFF_LEN),
+        by_extension,
+    ) {
+        // A signature beats the extension; "binary data" doesn't.
+        (Some(detected), _) if detected.mime != "application/octet-stream" => {
+            detected.mime.to_string()
+        }
+        (_, Some(mime)) => mime.to_string(),
+        (Some(detected), None) => detected.mime.to_string(),
+        (None, None) => "text/plain".to_string(),
+    }
+}
+
+/// The URL with the enabled query parameters in place of its own query, as the backend
+/// does.
+pub(crate) fn url_of(request: &Value) -> Result<reqwest::Url, String> {
+    let mut url = reqwest::Url::parse(str_of(request, "url"))
+        .map_err(|e| format!("invalid URL {:?}: {e}", str_of(request, "url")))?;
+    let params: Vec<(String, String)> = array_of(request, "query_params")
+        .iter()
+        .filter(|row| row.get("enabled").and_then(Value::as_bool) != Some(false))
+        .filter(|row| !str_of(row, "key").trim().is_empty())
+        .map(|row| {
+            let value = match row.get("value") {
+                Some(Value::String(text)) => text.clone(),
+                None | Some(Value::Null) => String::new(),
+                Some(other) => other.to_string(),
+            };
+            (str_of(row, "key").trim().to_string(), value)
+        })
+        .collect();
+    if !params.is_empty() {
+        url.query_pairs_mut().clear().extend_pairs(params);
+    }
+    Ok(url)
+}
+
+/// The `Authorization` header for the auth types the shell can apply itself; `sent_as` names
+/// the kind of send in the error for the rest.
+pub(crate) fn authorization(request: &Value, sent_as: &str) -> Result<Option<String>, String> {
+    let param = |key: &str| {
+        request
+            .get("auth_params")
+            .map(|params| str_of(params, key).to_string())
+            .unwrap_or_default()
+    };
+    match str_of(request, "auth_type") {
+        "" | "none" | "oauth2" | "jwt_bearer" | "plugin" => Ok(None),
+        "basic" => Ok(Some(format!(
+            "Basic {}",
+            STANDARD.encode(format!("{}:{}", param("username"), param("password")))
+        ))),
+        "bearer" => Ok(Some(format!("Bearer {}", param("token")))),
+        other => Err(format!("{other} auth isn't available for {sent_as}")),
+    }
+}
+
+/// A backend-shaped `RequestResult` for a send that failed before a response.
+pub(crate) fn failure(request: &Value, snapshot: Value, started: Instant, error: String) -> Value {
+    json!({
+        "request_id": str_of(request, "id"),
+        "status_code": 0,
+        "duration_ms": started.elapsed().as_secs_f64() * 1000.0,
+        "headers": {},
+        "body": null,
+        "body_bytes": 0,
+        "body_is_json": false,
+        "error": error,
+        "timestamp": crate::now_ms() as f64 / 1000.0,
+        "sent_request": snapshot,
+    })
+}
+
+/// Sends `request` (already resolved, with auth headers from the shell) with its body
+/// streamed, and returns a result shaped like the backend's.
+pub async fn send(app: &tauri::AppHandle, request: &Value) -> Result<Value, String> {
+    let started = Instant::now();
+    let request_id = str_of(request, "id").to_string();
+    let url = url_of(request)?;
+    let method = match str_of(request, "method") {
+        "" => "GET".to_string(),
+        method => method.to_uppercase(),
+    };
+    let method = reqwest::Method::from_bytes(method.as_bytes())
+        .map_err(|e| format!("invalid method {method:?}: {e}"))?;
+
+    let mut headers = request
+        .get("headers")
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+    if let Some(auth) = authorization(request, "bodies sent from a file")? {
+        headers.retain(|key, _| !key.eq_ignore_ascii_case("authorization"));
+        headers.insert("Authorization".to_string(), Value::String(auth));
+    }
+    let (segments, total, mime, body) = match str_of(request, "body_mode") {
+        MULTIPART_MODE => {
+            let rows = array_of(request, "form_body");
+            let framed = multipart::build(rows)?;
+            let form_body: Vec<Value> = rows
+                .iter()
+                .map(|row| {
+                    let mut row = row.clone();
+                    if let Some(fields) = row.as_object_mut() {
+                        fields.remove("file_inline");
+                    }
+                    row
+                })
+                .collect();
+            let content_type = framed.content_type();
+            (
+                framed.segments,
+                framed.length,
+                content_type,
+                json!({ "form_body": form_body }),
+            )
+        }
+        _ => {
+            let file_path = request
+                .pointer("/binary/file_path")
+                .and_then(Value::as_str)
+                .filter(|p| !p.trim().is_empty())
+                .ok_or("a body from a file needs binary.file_path")?;
+            let path = litefetch_core::workspace::normalize_path(file_path);
+            let declared = header(&headers, "content-type")
+                .map(str::to_string)
+                .unwrap_or_else(|| {
+                    request
+                        .pointer("/binary/content_type")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string()
+                });
+            let mime = content_type(&path, &declared).await;
+            let size = tokio::fs::metadata(&path)
+                .await
+                .map_err(|e| format!("body file read failed: {e}"))?
+                .len();
+            let body = json!({
+                "binary": { "file_path": path.to_string_lossy(), "content_type": mime, "size": size },
+            });
+            (vec![Segment::File { path, size }], size, mime, body)
+        }
+    };
+    headers.retain(|key, _| {
+        !key.eq_ignore_ascii_case("content-type") && !key.eq_ignore_ascii_case("content-length")
+    });
+    headers.insert("Content-Type".to_string(), Value::String(mime));
+    let mut snapshot = json!({
+        "method": method.as_str(),
+        "url": url.as_str(),
+        "headers": headers,
+        "body_mode": str_of(request, "body_mode"),
+        "body": null,
+    });
+    if let (Some(fields), Value::Object(body)) = (snapshot.as_object_mut(), body) {
+        fields.extend(body);
+    }
+
+    let sent = Arc::new(AtomicU64::new(0));
+    let progress = {
+        let app = app.clone();
+        let request_id = request_id.clone();
+        let sent = sent.clone();
+        move |done: bool| {
+            let _ = app.emit(
+                "upload://progress",
+                ProgressEvent {
+                    request_id: request_id.clone(),
+                    sent_bytes: if done {
+                        total
+                    } else {
+                        sent.load(Ordering::Relaxed)
+                    },
+                    total_bytes: total,
+                    timestamp_ms: crate::now_ms(),
+                },
+            );
+        }
+    };
+    let chunks = {
+        let progress = progress.clone();
+        let sent = sent.clone();
+        let state = (
+            VecDeque::from(segments),
+            None::<tokio::fs::File>,
+            Instant::now(),
+        );
+        stream::unfold(state, move |(mut segments, mut file, mut last)| {
+            let progress = progress.clone();
+            let sent = sent.clone();
+            async move {
+                let chunk = loop {
+                    if let Some(open) = file.as_mut() {
+                        let mut buffer = vec![0; CHUNK_SIZE];
+                        match open.read(&mut buffer).await {
+                            Ok(0) => file = None,
+                            Ok(read) => {
+                                buffer.truncate(read);
+                                break Ok(buffer);
+                            }
+                            Err(e) => break Err(e),
+                        }
+                        continue;
+                    }
+                    match segments.pop_front()? {
+                        Segment::Bytes(bytes) => break Ok(bytes),
+                        Segment::File { path, .. } => match tokio::fs::File::open(&path).await {
+                            Ok(opened) => file = Some(opened),
+                            Err(e) => break Err(e),
+                        },
+                    }
+                };
+                if let Ok(bytes) = &chunk {
+                    sent.fetch_add(bytes.len() as u64, Ordering::Relaxed);
+                    if last.elapsed() >= PROGRESS_INTERVAL {
+                        progress(false);
+                        last = Instant::now();
+                    }
+                }
+                Some((chunk, (segments, file, last)))
+            }
+        })
+    };
+
+    let verify = request
+        .get("verify_ssl")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let timeout = request
+        .get("timeout_seconds")
+        .and_then(Value::as_u64)
+        .filter(|t| *t > 0)
+        .unwrap_or(30);
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(!verify)
+        // The whole upload may take longer than the request's timeout; the wait for the
+        // server to connect and answer may not.
+        .connect_timeout(Duration::from_secs(timeout))
+        .read_timeout(Duration::from_secs(timeout))
+        .build()
+        .map_err(|e| format!("client init failed: {e}"))?;
+    let mut builder = client
+        .request(method, url)
+        .header(reqwest::header::CONTENT_LENGTH, total)
+        .body(reqwest::Body::wrap_stream(chunks));
+    for (key, value) in &headers {
+        builder = builder.header(key.as_str(), value.as_str().unwrap_or_default());
+    }
+
+    progress(false);
+    let response = match builder.send().await {
+        Ok(response) => response,
+        Err(e) => return Ok(failure(request, snapshot, started, e.to_string())),
+    };
+    progress(true);
+    let status = response.status().as_u16();
+    let response_headers: Map<String, Value> = response
+        .headers()
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                Value::String(String::from_utf8_lossy(value.as_bytes()).to_string()),
+            )
+        })
+        .collect();
+    let bytes = match response.bytes().await {
+        Ok(bytes) => bytes,
+        Err(e) => return Ok(failure(request, snapshot, started, e.to_string())),
+    };
+    let response_type = header(&response_headers, "content-type").map(str::to_string);
+    let lower = response_type.clone().unwrap_or_default().to_lowercase();
+    let body_is_json = lower.contains("application/json") || lower.contains("+json");
+    let text = std::str::from_utf8(&bytes).ok();
+    let body_base64 = match (text, lower.contains("charset=")) {
+        (None, false) => Some(STANDARD.encode(&bytes)),
+        _ => None,
+    };
+    Ok(json!({
+        "request_id": request_id,
+        "status_code": status,
+        "duration_ms": started.elapsed().as_secs_f64() * 1000.0,
+        "headers": response_headers,
+        "body": text.map(str::to_string).unwrap_or_else(|| String::from_utf8_lossy(&bytes).to_string()),
+        "body_base64": body_base64,
+        "body_is_json": body_is_json,
+        "content_type": response_type,
+        "body_bytes": bytes.len(),
+        "error": null,
+        "timestamp": crate::now_ms() as f64 / 1000.0,
+        "sent_request": snapshot,
+    }))
+}
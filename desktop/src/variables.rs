@@ -0,0 +1,277 @@
+//! Variable previews and the global and workspace layer files. Scopes and resolution live
+//! in `litefetch_core::variables`.
+
+use base64::Engine;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::codegen::{SnippetAuth, SnippetBody};
+use crate::importers::{array_of, str_of};
+use crate::redact::MASK;
+
+pub use litefetch_core::variables::*;
+
+fn layer_dir(app: &tauri::AppHandle, layer: VariableSource) -> Result<PathBuf, String> {
+    match layer {
+        VariableSource::Global => crate::app_data_root(app),
+        VariableSource::Workspace => crate::load_workspace_path(app),
+        _ => Err("only global and workspace variables are stored by the shell".into()),
+    }
+}
+
+/// Where this app keeps the global and workspace layers.
+pub fn layers(app: &tauri::AppHandle) -> Result<Layers, String> {
+    Ok(Layers {
+        global: crate::app_data_root(app)?,
+        workspace: crate::load_workspace_path(app)?,
+    })
+}
+
+#[derive(Serialize)]
+pub struct PreviewHeader {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize)]
+pub struct RequestPreview {
+    environment: String,
+    method: String,
+    url: String,
+    /// The routing rule that points the URL at the environment's base URL.
+    route: Option<litefetch_core::routing::Route>,
+    headers: Vec<PreviewHeader>,
+    body: Option<String>,
+    /// Every reference met while resolving, once each in request order. Dynamic values are
+    /// samples; a fresh value is generated when the request is sent.
+    substitutions: Vec<Substitution>,
+}
+
+fn body_preview(body: &SnippetBody) -> Option<String> {
+    match body {
+        SnippetBody::None => None,
+        SnippetBody::Raw { text, .. } => Some(text.clone()),
+        SnippetBody::UrlEncoded(pairs) => Some(
+            pairs
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{}={}",
+                        crate::codegen::encode_component(k),
+                        crate::codegen::encode_component(v)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("&"),
+        ),
+        SnippetBody::Multipart(parts) => Some(
+            parts
+                .iter()
+                .map(|part| match &part.file_path {
+                    Some(path) => format!("{}: @{path}", part.name),
+                    None => format!("{}: {}", part.name, part.value),
+                })
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        SnippetBody::File(path) => Some(format!("@{path}")),
+    }
+}
+
+/// Resolves a saved request against an environment (the active one by default) without
+/// sending it, reporting where every substituted value came from.
+#[tauri::command]
+pub async fn resolve_request_preview(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: String,
+    environment_id: Option<String>,
+    reveal_secrets: Option<bool>,
+) -> Result<RequestPreview, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let request = crate::codegen::find_request(array_of(&collection, "items"), &request_id)
+        .ok_or_else(|| format!("unknown request: {request_id}"))?;
+    let environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    let env_name = environment_id.unwrap_or_else(|| str_of(&environment, "active_env").into());
+    if environment
+        .pointer("/envs")
+        .and_then(|e| e.get(&env_name))
+        .is_none()
+    {
+        return Err(format!("unknown environment: {env_name}"));
+    }
+    let purpose = Purpose::Preview {
+        reveal: reveal_secrets.unwrap_or(false),
+    };
+    let scope = Scope::load(
+        &layers(&app)?,
+        &collection_id,
+        &collection,
+        &environment,
+        &env_name,
+        Some(request),
+    )?;
+
+    let mut routed = request.clone();
+    let route = litefetch_core::routing::route_request(
+        &crate::load_workspace_path(&app)?,
+        &env_name,
+        &mut routed,
+    )?;
+    let record = RefCell::new(Vec::new());
+    let flat = crate::codegen::flatten(&routed, &|text| {
+        resolve(text, &scope, purpose, &mut record.borrow_mut())
+    });
+    let mut substitutions: Vec<Substitution> = Vec::new();
+    for substitution in record.into_inner() {
+        let dynamic = substitution.source == Some(VariableSource::Dynamic);
+        if dynamic
+            || !substitutions
+                .iter()
+                .any(|s| s.reference == substitution.reference)
+        {
+            substitutions.push(substitution);
+        }
+    }
+    let mut headers: Vec<PreviewHeader> = flat
+        .header_lines()
+        .into_iter()
+        .map(|(name, value)| PreviewHeader { name, value })
+        .collect();
+    if let SnippetAuth::Basic { username, password } = &flat.auth {
+        if flat.header("Authorization").is_none() {
+            let value = match username.contains(MASK) || password.contains(MASK) {
+                true => format!("Basic {MASK}"),
+                false => format!(
+                    "Basic {}",
+                    base64::engine::general_purpose::STANDARD
+                        .encode(format!("{username}:{password}"))
+                ),
+            };
+            headers.push(PreviewHeader {
+                name: "Authorization".to_string(),
+                value,
+            });
+        }
+    }
+    let url = match route.as_ref().and_then(|r| r.port) {
+        Some(port) => litefetch_core::routing::with_port(&flat.url, port),
+        None => flat.url.clone(),
+    };
+    Ok(RequestPreview {
+        environment: env_name,
+        method: flat.method.clone(),
+        url,
+        route,
+        body: body_preview(&flat.body),
+        headers,
+        substitutions,
+    })
+}
+
+#[derive(Serialize)]
+pub struct LayerValue {
+    layer: VariableSource,
+    /// The value, or a mask for secrets.
+    value: String,
+}
+
+#[derive(Serialize)]
+pub struct ResolvedKey {
+    key: String,
+    /// The winning value, or a mask for secrets.
+    value: String,
+    layer: VariableSource,
+    secret: bool,
+    /// Lower layers that also define the key, lowest first.
+    shadowed: Vec<LayerValue>,
+}
+
+/// Lists every key visible to a request (or to the collection when `request_id` is omitted)
+/// with the layer that wins it and the layers it shadows.
+#[tauri::command]
+pub async fn resolve_variable_layers(
+    app: tauri::AppHandle,
+    collection_id: String,
+    request_id: Option<String>,
+    environment_id: Option<String>,
+) -> Result<Vec<ResolvedKey>, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let request = match &request_id {
+        Some(id) => Some(
+            crate::codegen::find_request(array_of(&collection, "items"), id)
+                .ok_or_else(|| format!("unknown request: {id}"))?,
+        ),
+        None => None,
+    };
+    let environment =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/environment")).await?;
+    let env_name = environment_id.unwrap_or_else(|| str_of(&environment, "active_env").into());
+    let scope = Scope::load(
+        &layers(&app)?,
+        &collection_id,
+        &collection,
+        &environment,
+        &env_name,
+        request,
+    )?;
+
+    let shown = |v: &Variable| match v.secret {
+        true => MASK.to_string(),
+        false => v.value.clone(),
+    };
+    let mut keys: Vec<&str> = scope.variables().iter().map(|v| v.key.as_str()).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    Ok(keys
+        .into_iter()
+        .filter_map(|key| {
+            let winner = scope.lookup(key)?;
+            let defined: Vec<&Variable> =
+                scope.variables().iter().filter(|v| v.key == key).collect();
+            Some(ResolvedKey {
+                key: key.to_string(),
+                value: shown(winner),
+                layer: winner.source,
+                secret: winner.secret,
+                shadowed: defined[..defined.len() - 1]
+                    .iter()
+                    .map(|v| LayerValue {
+                        layer: v.source,
+                        value: shown(v),
+                    })
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+/// Reads the `global` or `workspace` layer.
+#[tauri::command]
+pub async fn get_layer_variables(
+    app: tauri::AppHandle,
+    layer: VariableSource,
+) -> Result<Map<String, Value>, String> {
+    read_layer(&layer_dir(&app, layer)?)
+}
+
+/// Replaces the `global` or `workspace` layer. Collection, environment and request
+/// variables are saved with the collection and its environments.
+#[tauri::command]
+pub async fn set_layer_variables(
+    app: tauri::AppHandle,
+    layer: VariableSource,
+    variables: Map<String, Value>,
+) -> Result<Map<String, Value>, String> {
+    let path = layer_dir(&app, layer)?.join(LAYER_FILE);
+    let payload = serde_json::to_string_pretty(&variables)
+        .map_err(|e| format!("variables serialize failed: {e}"))?;
+    fs::write(&path, payload).map_err(|e| format!("variables persist failed: {e}"))?;
+    Ok(variables)
+}
@@ -0,0 +1,254 @@
+//! The API key vault: keys mapped to host patterns (`api.example.com`, `*.example.com`,
+//! `localhost:8080`) and sent as a header or query parameter on every request to a matching
+//! host. The mappings live in the app data directory and the keys in the OS keychain, so
+//! neither is part of the workspace: requests, exports and syncs never carry them. They are
+//! added to the outgoing copy of a request in `send`, after its own auth.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::importers::str_of;
+
+const VAULT_FILE: &str = "api-key-vault.json";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Placement {
+    Header,
+    Query,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct VaultEntry {
+    pub id: String,
+    pub host_pattern: String,
+    pub placement: Placement,
+    /// The header or query parameter name, e.g. `X-Api-Key` or `api_key`.
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub description: Option<String>,
+    pub updated_ms: u64,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct VaultEntryInput {
+    /// Updates the entry with this id; a new one is created without it.
+    #[serde(default)]
+    pub id: Option<String>,
+    pub host_pattern: String,
+    pub placement: Placement,
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// The key itself; left out to keep the stored one.
+    #[serde(default)]
+    pub value: Option<String>,
+}
+
+fn vault_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    Ok(crate::app_data_root(app)?.join(VAULT_FILE))
+}
+
+fn load(app: &tauri::AppHandle) -> Result<Vec<VaultEntry>, String> {
+    let path = vault_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = fs::read_to_string(&path).map_err(|e| format!("key vault read failed: {e}"))?;
+    serde_json::from_str(&data).map_err(|e| format!("key vault parse failed: {e}"))
+}
+
+fn save(app: &tauri::AppHandle, entries: &[VaultEntry]) -> Result<(), String> {
+    let payload = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("key vault serialize failed: {e}"))?;
+    fs::write(vault_path(app)?, payload).map_err(|e| format!("key vault persist failed: {e}"))
+}
+
+fn keychain(id: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(
+        &litefetch_core::profile::keychain_service(),
+        &format!("api-key/{id}"),
+    )
+    .map_err(|e| format!("keychain unavailable: {e}"))
+}
+
+fn load_key(id: &str) -> Result<Option<String>, String> {
+    match keychain(id)?.get_password() {
+        Ok(key) => Ok(Some(key)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("keychain read failed: {e}")),
+    }
+}
+
+/// Whether `pattern` covers a request to `host` (and `port`). A leading `*.` matches any
+/// subdomain, not the domain itself; a pattern with a port only matches that port.
+pub(crate) fn matches(pattern: &str, host: &str, port: u16) -> bool {
+    let pattern = pattern.trim().to_ascii_lowercase();
+    let (pattern_host, pattern_port) = match pattern.rsplit_once(':') {
+        Some((h, p)) if p.parse::<u16>().is_ok() => (h.to_string(), p.parse::<u16>().ok()),
+        _ => (pattern, None),
+    };
+    if pattern_port.is_some_and(|p| p != port) {
+        return false;
+    }
+    match pattern_host.strip_prefix("*.") {
+        Some(domain) => host.len() > domain.len() + 1 && host.ends_with(&format!(".{domain}")),
+        None => pattern_host == "*" || pattern_host == host,
+    }
+}
+
+/// Orders exact hosts before wildcards and longer patterns before shorter ones.
+fn specificity(entry: &VaultEntry) -> (bool, usize) {
+    (
+        !entry.host_pattern.contains('*'),
+        entry.host_pattern.trim().len(),
+    )
+}
+
+fn has_query_param(request: &Value, url: &reqwest::Url, name: &str) -> bool {
+    let in_rows = request
+        .get("query_params")
+        .and_then(Value::as_array)
+        .is_some_and(|rows| {
+            rows.iter().any(|row| {
+                row.get("enabled").and_then(Value::as_bool) != Some(false)
+                    && str_of(row, "key") == name
+            })
+        });
+    in_rows || url.query_pairs().any(|(key, _)| key == name)
+}
+
+/// Adds the vault's keys for the request's host that it doesn't already set itself, and
+/// returns the `(secret marker, name)` of each, so they can be marked secret.
+pub fn inject(
+    app: &tauri::AppHandle,
+    request: &mut Value,
+) -> Result<Vec<(&'static str, String)>, String> {
+    let mut entries: Vec<VaultEntry> = load(app)?.into_iter().filter(|e| e.enabled).collect();
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+    let Ok(mut url) = reqwest::Url::parse(str_of(request, "url").trim()) else {
+        return Ok(Vec::new());
+    };
+    let host = url.host_str().unwrap_or("").to_ascii_lowercase();
+    let port = url.port_or_known_default().unwrap_or(0);
+    entries.sort_by_key(|e| std::cmp::Reverse(specificity(e)));
+
+    let mut injected = Vec::new();
+    for entry in entries {
+        let name = entry.name.trim();
+        if name.is_empty() || !matches(&entry.host_pattern, &host, port) {
+            continue;
+        }
+        let already = match entry.placement {
+            Placement::Header => request
+                .get("headers")
+                .and_then(Value::as_object)
+                .is_some_and(|h| h.keys().any(|k| k.eq_ignore_ascii_case(name))),
+            Placement::Query => has_query_param(request, &url, name),
+        };
+        if already {
+            continue;
+        }
+        let Some(key) = load_key(&entry.id)? else {
+            continue;
+        };
+        crate::redact::remember(&key);
+        match entry.placement {
+            Placement::Header => {
+                if !request.get("headers").is_some_and(Value::is_object) {
+                    request["headers"] = Value::Object(Map::new());
+                }
+                request["headers"][name] = Value::String(key);
+                injected.push(("secret_headers", name.to_string()));
+            }
+            Placement::Query => {
+                // Enabled rows replace the URL's query in the engine, so they only gain one then.
+                match request
+                    .get_mut("query_params")
+                    .and_then(Value::as_array_mut)
+                {
+                    Some(rows)
+                        if rows
+                            .iter()
+                            .any(|r| r.get("enabled").and_then(Value::as_bool) != Some(false)) =>
+                    {
+                        rows.push(json!({ "key": name, "value": key, "enabled": true }))
+                    }
+                    _ => {
+                        url.query_pairs_mut().append_pair(name, &key);
+                        request["url"] = Value::String(url.to_string());
+                    }
+                }
+                injected.push(("secret_query_params", name.to_string()));
+            }
+        }
+    }
+    Ok(injected)
+}
+
+/// The vault's mappings, without their keys.
+#[tauri::command]
+pub async fn list_api_keys(app: tauri::AppHandle) -> Result<Vec<VaultEntry>, String> {
+    load(&app)
+}
+
+#[tauri::command]
+pub async fn save_api_key(
+    app: tauri::AppHandle,
+    entry: VaultEntryInput,
+) -> Result<VaultEntry, String> {
+    if entry.host_pattern.trim().is_empty() || entry.name.trim().is_empty() {
+        return Err("a vault key needs a host pattern and a name".into());
+    }
+    let mut entries = load(&app)?;
+    let id = entry
+        .id
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    let existing = entries.iter().position(|e| e.id == id);
+    match (&entry.value, existing) {
+        (Some(value), _) if !value.is_empty() => keychain(&id)?
+            .set_password(value)
+            .map_err(|e| format!("keychain write failed: {e}"))?,
+        (_, None) => return Err("a new vault key needs a value".into()),
+        _ => {}
+    }
+    let saved = VaultEntry {
+        id,
+        host_pattern: entry.host_pattern.trim().to_string(),
+        placement: entry.placement,
+        name: entry.name.trim().to_string(),
+        enabled: entry.enabled,
+        description: entry.description.filter(|d| !d.trim().is_empty()),
+        updated_ms: crate::now_ms(),
+    };
+    match existing {
+        Some(index) => entries[index] = saved.clone(),
+        None => entries.push(saved.clone()),
+    }
+    save(&app, &entries)?;
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_api_key(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    let mut entries = load(&app)?;
+    entries.retain(|e| e.id != id);
+    save(&app, &entries)?;
+    match keychain(&id)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("keychain delete failed: {e}")),
+    }
+}
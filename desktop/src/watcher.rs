@@ -0,0 +1,132 @@
+//! Watches the workspace directory so edits made outside the app — a git pull, a sync, a
+//! text editor — show up straight away: each changed file is announced as
+//! `workspace://changed` once its burst of file system events settles. Local state in
+//! `.litefetch` and git's own files are ignored.
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{Emitter, Manager};
+use tokio::sync::mpsc;
+
+/// Quiet time before a burst of events for the same file is reported.
+const SETTLE: Duration = Duration::from_millis(200);
+const IGNORED_DIRS: &[&str] = &[".litefetch", ".git"];
+
+pub struct WatcherState {
+    /// The active watcher; replacing it stops the previous one.
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl WatcherState {
+    pub fn new() -> Self {
+        Self {
+            watcher: Mutex::new(None),
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+struct ChangedEvent {
+    /// Relative to the workspace, `/`-separated.
+    path: String,
+    /// `created`, `modified`, `removed` or `renamed`.
+    kind: &'static str,
+    /// Set for files of a collection (`collections/<id>/…`).
+    collection_id: Option<String>,
+    timestamp_ms: u64,
+}
+
+fn kind_of(kind: &EventKind) -> Option<&'static str> {
+    match kind {
+        EventKind::Create(_) => Some("created"),
+        EventKind::Remove(_) => Some("removed"),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => Some("removed"),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => Some("created"),
+        EventKind::Modify(ModifyKind::Name(_)) => Some("renamed"),
+        // Access time and permission changes don't change what the app shows.
+        EventKind::Modify(ModifyKind::Metadata(_)) => None,
+        EventKind::Modify(_) => Some("modified"),
+        _ => None,
+    }
+}
+
+fn relative(root: &Path, path: &Path) -> Option<String> {
+    let rel = path.strip_prefix(root).ok()?;
+    let ignored = rel.components().any(|c| match c {
+        Component::Normal(name) => IGNORED_DIRS.contains(&name.to_string_lossy().as_ref()),
+        _ => false,
+    });
+    let rel = rel.to_string_lossy().replace('\\', "/");
+    (!ignored && !rel.is_empty()).then_some(rel)
+}
+
+fn collection_of(path: &str) -> Option<String> {
+    let mut parts = path.split('/');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some("collections"), Some(id), Some(_)) => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+/// Starts watching the current workspace, replacing any earlier watcher.
+pub fn watch(app: &tauri::AppHandle) {
+    if let Err(e) = start(app) {
+        tracing::warn!("{e}");
+    }
+}
+
+fn start(app: &tauri::AppHandle) -> Result<(), String> {
+    let root = crate::load_workspace_path(app)?;
+    let root = root.canonicalize().unwrap_or(root);
+    let (events, mut incoming) = mpsc::unbounded_channel::<(PathBuf, &'static str)>();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        if let Some(kind) = kind_of(&event.kind) {
+            for path in event.paths {
+                let _ = events.send((path, kind));
+            }
+        }
+    })
+    .map_err(|e| format!("workspace watch failed: {e}"))?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .map_err(|e| format!("workspace watch failed: {e}"))?;
+    // Dropping the previous watcher closes its channel, which ends its task below.
+    *app.state::<WatcherState>()
+        .watcher
+        .lock()
+        .map_err(|_| "workspace watcher state poisoned".to_string())? = Some(watcher);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(first) = incoming.recv().await {
+            // Latest kind per file over the burst.
+            let mut burst = BTreeMap::from([first]);
+            while let Ok(Some((path, kind))) = tokio::time::timeout(SETTLE, incoming.recv()).await {
+                burst.insert(path, kind);
+            }
+            for (path, kind) in burst {
+                let Some(rel) = relative(&root, &path) else {
+                    continue;
+                };
+                let _ = app.emit(
+                    "workspace://changed",
+                    ChangedEvent {
+                        collection_id: collection_of(&rel),
+                        path: rel,
+                        kind,
+                        timestamp_ms: crate::now_ms(),
+                    },
+                );
+            }
+        }
+    });
+    Ok(())
+}
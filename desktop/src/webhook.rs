@@ -0,0 +1,375 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use bytes::Bytes;
+use http_body_util::{BodyExt, Full};
+use hyper::body::Incoming;
+use hyper::header::{HeaderName, HeaderValue};
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use tauri::async_runtime::{JoinHandle, Mutex};
+use tauri::{Emitter, State};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+const CAPTURE_LIMIT: usize = 500;
+/// Kept of each request body; the rest is read and dropped.
+const BODY_CAPTURE_LIMIT: usize = 1024 * 1024;
+
+pub struct WebhookState {
+    listeners: Mutex<HashMap<String, WebhookListener>>,
+}
+
+impl WebhookState {
+    pub fn new() -> Self {
+        Self {
+            listeners: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Local port of a running listener, used when exposing it through a tunnel.
+    pub async fn port_of(&self, listener_id: &str) -> Option<u16> {
+        self.listeners
+            .lock()
+            .await
+            .get(listener_id)
+            .map(|l| l.info.port)
+    }
+}
+
+struct WebhookListener {
+    info: WebhookListenerInfo,
+    context: Arc<ListenerContext>,
+    shutdown: Option<oneshot::Sender<()>>,
+    task: JoinHandle<()>,
+}
+
+struct ListenerContext {
+    app: tauri::AppHandle,
+    listener_id: String,
+    responses: RwLock<Vec<CannedResponse>>,
+    captured: RwLock<VecDeque<CapturedRequest>>,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WebhookListenerInfo {
+    listener_id: String,
+    port: u16,
+    url: String,
+    started_at_ms: u64,
+}
+
+/// Response returned for requests matching `method`/`path`; the first match wins.
+/// A `path` ending in `*` matches by prefix.
+#[derive(Deserialize, Serialize, Clone)]
+pub struct CannedResponse {
+    #[serde(default)]
+    method: Option<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default = "default_status")]
+    status: u16,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default)]
+    body: String,
+    #[serde(default)]
+    delay_ms: Option<u64>,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+impl CannedResponse {
+    fn matches(&self, method: &str, path: &str) -> bool {
+        let method_ok = self
+            .method
+            .as_deref()
+            .is_none_or(|m| m == "*" || m.eq_ignore_ascii_case(method));
+        let path_ok = match self.path.as_deref() {
+            None | Some("") | Some("*") => true,
+            Some(p) => match p.strip_suffix('*') {
+                Some(prefix) => path.starts_with(prefix),
+                None => p == path,
+            },
+        };
+        method_ok && path_ok
+    }
+
+    /// Fails on a status or header the response could not be built with.
+    fn validate(&self) -> Result<(), String> {
+        StatusCode::from_u16(self.status)
+            .map_err(|_| format!("invalid canned response status {}", self.status))?;
+        for (key, value) in &self.headers {
+            HeaderName::from_bytes(key.as_bytes())
+                .map_err(|_| format!("invalid canned response header name \"{key}\""))?;
+            HeaderValue::from_str(value)
+                .map_err(|_| format!("invalid canned response header value for {key}"))?;
+        }
+        Ok(())
+    }
+
+    fn fallback() -> Self {
+        Self {
+            method: None,
+            path: None,
+            status: 200,
+            headers: HashMap::from([("content-type".to_string(), "application/json".to_string())]),
+            body: "{\"ok\":true}".to_string(),
+            delay_ms: None,
+        }
+    }
+}
+
+#[derive(Clone, Serialize)]
+pub struct CapturedRequest {
+    listener_id: String,
+    id: String,
+    method: String,
+    path: String,
+    query: String,
+    headers: Vec<(String, String)>,
+    body: String,
+    body_base64: String,
+    /// Body size as received; `body` stops at `BODY_CAPTURE_LIMIT`.
+    body_bytes: usize,
+    body_truncated: bool,
+    remote_addr: String,
+    received_at_ms: u64,
+    duration_ms: f64,
+    response_status: u16,
+}
+
+async fn handle(
+    context: Arc<ListenerContext>,
+    remote: SocketAddr,
+    request: Request<Incoming>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    let started = Instant::now();
+    let received_at_ms = crate::now_ms();
+    let (parts, mut incoming) = request.into_parts();
+    let mut body = Vec::new();
+    let mut body_bytes = 0;
+    while let Some(Ok(frame)) = incoming.frame().await {
+        let Ok(data) = frame.into_data() else {
+            continue;
+        };
+        body_bytes += data.len();
+        let room = BODY_CAPTURE_LIMIT.saturating_sub(body.len());
+        body.extend_from_slice(&data[..data.len().min(room)]);
+    }
+    let method = parts.method.to_string();
+    let path = parts.uri.path().to_string();
+
+    let canned = context
+        .responses
+        .read()
+        .ok()
+        .and_then(|r| r.iter().find(|c| c.matches(&method, &path)).cloned())
+        .unwrap_or_else(CannedResponse::fallback);
+    if let Some(ms) = canned.delay_ms {
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+    }
+
+    let captured = CapturedRequest {
+        listener_id: context.listener_id.clone(),
+        id: uuid::Uuid::new_v4().to_string(),
+        method,
+        path,
+        query: parts.uri.query().unwrap_or_default().to_string(),
+        headers: parts
+            .headers
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.to_string(),
+                    String::from_utf8_lossy(v.as_bytes()).to_string(),
+                )
+            })
+            .collect(),
+        body: String::from_utf8_lossy(&body).to_string(),
+        body_base64: STANDARD.encode(&body),
+        body_bytes,
+        body_truncated: body_bytes > body.len(),
+        remote_addr: remote.to_string(),
+        received_at_ms,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        response_status: canned.status,
+    };
+    if let Ok(mut log) = context.captured.write() {
+        if log.len() >= CAPTURE_LIMIT {
+            log.pop_front();
+        }
+        log.push_back(captured.clone());
+    }
+    let _ = context.app.emit("webhook://request", captured);
+
+    let mut response =
+        Response::builder().status(StatusCode::from_u16(canned.status).unwrap_or(StatusCode::OK));
+    for (key, value) in &canned.headers {
+        response = response.header(key.as_str(), value.as_str());
+    }
+    Ok(response
+        .body(Full::new(Bytes::from(canned.body)))
+        .unwrap_or_else(|_| Response::new(Full::new(Bytes::new()))))
+}
+
+async fn serve(
+    context: Arc<ListenerContext>,
+    listener: TcpListener,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => {
+                let Ok((stream, remote)) = accepted else { continue };
+                let context = context.clone();
+                tauri::async_runtime::spawn(async move {
+                    let service = hyper::service::service_fn(move |req| {
+                        handle(context.clone(), remote, req)
+                    });
+                    let _ = hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn start_webhook_listener(
+    app: tauri::AppHandle,
+    state: State<'_, WebhookState>,
+    port: Option<u16>,
+    bind_all: Option<bool>,
+    responses: Option<Vec<CannedResponse>>,
+) -> Result<WebhookListenerInfo, String> {
+    let responses = responses.unwrap_or_default();
+    for response in &responses {
+        response.validate()?;
+    }
+    let host = if bind_all.unwrap_or(false) {
+        "0.0.0.0"
+    } else {
+        "127.0.0.1"
+    };
+    let listener = TcpListener::bind((host, port.unwrap_or(0)))
+        .await
+        .map_err(|e| format!("webhook listener bind failed: {e}"))?;
+    let port = listener
+        .local_addr()
+        .map_err(|e| format!("webhook listener bind failed: {e}"))?
+        .port();
+
+    let listener_id = uuid::Uuid::new_v4().to_string();
+    let context = Arc::new(ListenerContext {
+        app,
+        listener_id: listener_id.clone(),
+        responses: RwLock::new(responses),
+        captured: RwLock::new(VecDeque::new()),
+    });
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let task = tauri::async_runtime::spawn(serve(context.clone(), listener, shutdown_rx));
+    let info = WebhookListenerInfo {
+        listener_id: listener_id.clone(),
+        port,
+        url: format!("http://127.0.0.1:{port}"),
+        started_at_ms: crate::now_ms(),
+    };
+    state.listeners.lock().await.insert(
+        listener_id,
+        WebhookListener {
+            info: info.clone(),
+            context,
+            shutdown: Some(shutdown),
+            task,
+        },
+    );
+    Ok(info)
+}
+
+#[tauri::command]
+pub async fn stop_webhook_listener(
+    state: State<'_, WebhookState>,
+    listener_id: String,
+) -> Result<(), String> {
+    let mut listener = state
+        .listeners
+        .lock()
+        .await
+        .remove(&listener_id)
+        .ok_or_else(|| format!("unknown webhook listener: {listener_id}"))?;
+    if let Some(tx) = listener.shutdown.take() {
+        let _ = tx.send(());
+    } else {
+        listener.task.abort();
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn list_webhook_listeners(
+    state: State<'_, WebhookState>,
+) -> Result<Vec<WebhookListenerInfo>, String> {
+    let mut listeners: Vec<_> = state
+        .listeners
+        .lock()
+        .await
+        .values()
+        .map(|l| l.info.clone())
+        .collect();
+    listeners.sort_by_key(|l| l.started_at_ms);
+    Ok(listeners)
+}
+
+#[tauri::command]
+pub async fn set_webhook_responses(
+    state: State<'_, WebhookState>,
+    listener_id: String,
+    responses: Vec<CannedResponse>,
+) -> Result<(), String> {
+    for response in &responses {
+        response.validate()?;
+    }
+    let listeners = state.listeners.lock().await;
+    let listener = listeners
+        .get(&listener_id)
+        .ok_or_else(|| format!("unknown webhook listener: {listener_id}"))?;
+    let mut current = listener
+        .context
+        .responses
+        .write()
+        .map_err(|_| "webhook response table poisoned".to_string())?;
+    *current = responses;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_webhook_requests(
+    state: State<'_, WebhookState>,
+    listener_id: String,
+    clear: Option<bool>,
+) -> Result<Vec<CapturedRequest>, String> {
+    let listeners = state.listeners.lock().await;
+    let listener = listeners
+        .get(&listener_id)
+        .ok_or_else(|| format!("unknown webhook listener: {listener_id}"))?;
+    let mut captured = listener
+        .context
+        .captured
+        .write()
+        .map_err(|_| "webhook capture log poisoned".to_string())?;
+    let requests = captured.iter().cloned().collect();
+    if clear.unwrap_or(false) {
+        captured.clear();
+    }
+    Ok(requests)
+}
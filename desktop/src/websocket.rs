@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::HeaderValue;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+pub type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+pub struct WsConnection {
+    pub stream: WsStream,
+    pub protocol: Option<String>,
+}
+
+/// Opens a client WebSocket, offering `subprotocols` in preference order.
+pub async fn connect(
+    url: &str,
+    headers: &HashMap<String, String>,
+    subprotocols: &[&str],
+) -> Result<WsConnection, String> {
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| format!("invalid WebSocket URL: {e}"))?;
+    for (key, value) in headers {
+        let name = tokio_tungstenite::tungstenite::http::HeaderName::from_bytes(key.as_bytes())
+            .map_err(|e| format!("invalid header name {key}: {e}"))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| format!("invalid header value for {key}: {e}"))?;
+        request.headers_mut().insert(name, value);
+    }
+    if !subprotocols.is_empty() {
+        let offered = HeaderValue::from_str(&subprotocols.join(", "))
+            .map_err(|e| format!("invalid subprotocol list: {e}"))?;
+        request
+            .headers_mut()
+            .insert("sec-websocket-protocol", offered);
+    }
+
+    let (stream, response) = tokio_tungstenite::connect_async(request)
+        .await
+        .map_err(|e| format!("WebSocket connect failed: {e}"))?;
+    let protocol = response
+        .headers()
+        .get("sec-websocket-protocol")
+        .map(|v| String::from_utf8_lossy(v.as_bytes()).to_string());
+    Ok(WsConnection { stream, protocol })
+}
@@ -0,0 +1,71 @@
+//! Workflow runs from the UI. `litefetch_core::workflow` runs the steps through `send`;
+//! progress is emitted as `workflow://step` events and the summary as `workflow://finished`.
+
+use serde::Serialize;
+use tauri::Emitter;
+
+use litefetch_core::workflow::{StepReport, WorkflowRun};
+
+use crate::send::Shell;
+
+#[derive(Serialize, Clone)]
+struct StepEvent {
+    run_id: String,
+    workflow_id: String,
+    index: usize,
+    step: StepReport,
+    timestamp_ms: u64,
+}
+
+#[derive(Serialize, Clone)]
+struct FinishedEvent {
+    run: WorkflowRun,
+    timestamp_ms: u64,
+}
+
+/// Runs a workflow of the collection from its first step, sending each request with the
+/// environment `environment_id` (the active one by default). The run stops at a failed
+/// assertion, a request error, a branch to `end` or after the last step.
+#[tauri::command]
+pub async fn run_workflow(
+    app: tauri::AppHandle,
+    collection_id: String,
+    workflow_id: String,
+    environment_id: Option<String>,
+) -> Result<WorkflowRun, String> {
+    let collection =
+        crate::backend_get(&app, &format!("/collections/{collection_id}/collection")).await?;
+    let run_id = uuid::Uuid::new_v4().to_string();
+    let sender = Shell {
+        app: app.clone(),
+        collection_id,
+        environment_id,
+    };
+    let run = litefetch_core::workflow::run(
+        run_id.clone(),
+        &collection,
+        &workflow_id,
+        &sender,
+        |index, step| {
+            let _ = app.emit(
+                "workflow://step",
+                StepEvent {
+                    run_id: run_id.clone(),
+                    workflow_id: workflow_id.clone(),
+                    index,
+                    step: step.clone(),
+                    timestamp_ms: crate::now_ms(),
+                },
+            );
+        },
+    )
+    .await?;
+    let _ = app.emit(
+        "workflow://finished",
+        FinishedEvent {
+            run: run.clone(),
+            timestamp_ms: crate::now_ms(),
+        },
+    );
+    Ok(run)
+}